@@ -1,8 +1,22 @@
 //! Bedrock custom block JSON parsing (blocks/*.json).
+//!
+//! Besides the base components, a block declares `states` (enumerated
+//! values), `traits` (vanilla-managed states such as placement direction)
+//! and `permutations`: component overrides applied when a Molang condition
+//! over the block's states holds. Only `q.block_state('name') == value`
+//! clauses joined with `&&` are evaluated; other conditions never match.
 
 use std::collections::HashMap;
 
 use serde::Deserialize;
+use serde_json::Value;
+
+/// Cardinal directions set by the `minecraft:placement_direction` trait.
+pub const CARDINAL_DIRECTIONS: [&str; 4] = ["north", "south", "east", "west"];
+/// Block faces, in Bedrock face-index order (down, up, north, south, west, east).
+pub const BLOCK_FACES: [&str; 6] = ["down", "up", "north", "south", "west", "east"];
+/// Halves set by the `minecraft:placement_position` trait.
+pub const VERTICAL_HALVES: [&str; 2] = ["bottom", "top"];
 
 /// Raw block file structure.
 #[derive(Debug, Clone, Deserialize)]
@@ -16,12 +30,94 @@ pub struct BlockFile {
 pub struct BlockDefinition {
     pub description: BlockDescription,
     #[serde(default)]
-    pub components: HashMap<String, serde_json::Value>,
+    pub components: HashMap<String, Value>,
+    #[serde(default)]
+    pub permutations: Vec<RawPermutation>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlockDescription {
     pub identifier: String,
+    #[serde(default)]
+    pub states: HashMap<String, Value>,
+    #[serde(default)]
+    pub traits: HashMap<String, Value>,
+    #[serde(default)]
+    pub menu_category: Option<MenuCategory>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RawPermutation {
+    pub condition: String,
+    #[serde(default)]
+    pub components: HashMap<String, Value>,
+}
+
+/// Creative inventory placement of the block's item.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct MenuCategory {
+    #[serde(default)]
+    pub category: String,
+    #[serde(default)]
+    pub group: String,
+}
+
+/// A block state value. Ints hash as TAG_Int, bools as TAG_Byte, strings as TAG_String.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockStateValue {
+    Int(i32),
+    Bool(bool),
+    Str(String),
+}
+
+impl BlockStateValue {
+    fn from_json(v: &Value) -> Option<Self> {
+        match v {
+            Value::Bool(b) => Some(Self::Bool(*b)),
+            Value::Number(n) => n.as_i64().map(|n| Self::Int(n as i32)),
+            Value::String(s) => Some(Self::Str(s.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// A state and its allowed values; the first value is the default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockStateDef {
+    pub name: String,
+    pub values: Vec<BlockStateValue>,
+}
+
+/// A vanilla block trait enabling built-in states.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockTrait {
+    /// `minecraft:placement_direction`: states derived from the placer's facing.
+    PlacementDirection {
+        cardinal_direction: bool,
+        facing_direction: bool,
+        y_rotation_offset: f32,
+    },
+    /// `minecraft:placement_position`: states derived from the clicked face.
+    PlacementPosition {
+        block_face: bool,
+        vertical_half: bool,
+    },
+}
+
+/// Component overrides applied while `condition` holds.
+#[derive(Debug, Clone)]
+pub struct BlockPermutation {
+    pub condition: String,
+    pub components: HashMap<String, Value>,
+}
+
+/// The properties of a block that can vary by permutation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockProperties {
+    pub hardness: f32,
+    pub is_solid: bool,
+    /// Loot table name (file stem), if the block has `minecraft:loot`.
+    pub loot_table: Option<String>,
 }
 
 /// Parsed block properties.
@@ -30,6 +126,13 @@ pub struct ParsedBlock {
     pub identifier: String,
     pub hardness: f32,
     pub is_solid: bool,
+    /// Custom states declared in `description.states`.
+    pub states: Vec<BlockStateDef>,
+    pub traits: Vec<BlockTrait>,
+    pub permutations: Vec<BlockPermutation>,
+    /// Raw base components (forwarded to clients in the block palette).
+    pub components: HashMap<String, Value>,
+    pub menu_category: MenuCategory,
 }
 
 impl BlockFile {
@@ -40,26 +143,272 @@ impl BlockFile {
 
     /// Extract usable properties.
     pub fn extract(&self) -> ParsedBlock {
-        let hardness = self
-            .block
-            .components
-            .get("minecraft:destructible_by_mining")
-            .and_then(|v| v.get("seconds_to_destroy").and_then(|s| s.as_f64()))
-            .unwrap_or(1.0) as f32;
-
-        // Solid unless explicitly set to false via collision box component.
-        let is_solid = self
-            .block
-            .components
-            .get("minecraft:collision_box")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(true);
+        let description = &self.block.description;
+        let base = properties_of(&self.block.components);
+
+        let mut states: Vec<BlockStateDef> = description
+            .states
+            .iter()
+            .filter_map(|(name, v)| {
+                let values = parse_state_values(v);
+                (!values.is_empty()).then(|| BlockStateDef {
+                    name: name.clone(),
+                    values,
+                })
+            })
+            .collect();
+        states.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut trait_names: Vec<&String> = description.traits.keys().collect();
+        trait_names.sort();
+        let traits = trait_names
+            .into_iter()
+            .filter_map(|name| parse_trait(name, &description.traits[name]))
+            .collect();
 
         ParsedBlock {
-            identifier: self.block.description.identifier.clone(),
-            hardness,
-            is_solid,
+            identifier: description.identifier.clone(),
+            hardness: base.hardness,
+            is_solid: base.is_solid,
+            states,
+            traits,
+            permutations: self
+                .block
+                .permutations
+                .iter()
+                .map(|p| BlockPermutation {
+                    condition: p.condition.clone(),
+                    components: p.components.clone(),
+                })
+                .collect(),
+            components: self.block.components.clone(),
+            menu_category: description.menu_category.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl ParsedBlock {
+    /// States added by traits, with their allowed values.
+    pub fn trait_states(&self) -> Vec<BlockStateDef> {
+        let strings = |values: &[&str]| {
+            values
+                .iter()
+                .map(|v| BlockStateValue::Str(v.to_string()))
+                .collect()
+        };
+        let mut out = Vec::new();
+        for t in &self.traits {
+            match *t {
+                BlockTrait::PlacementDirection {
+                    cardinal_direction,
+                    facing_direction,
+                    ..
+                } => {
+                    if cardinal_direction {
+                        out.push(BlockStateDef {
+                            name: "minecraft:cardinal_direction".into(),
+                            values: strings(&CARDINAL_DIRECTIONS),
+                        });
+                    }
+                    if facing_direction {
+                        out.push(BlockStateDef {
+                            name: "minecraft:facing_direction".into(),
+                            values: strings(&BLOCK_FACES),
+                        });
+                    }
+                }
+                BlockTrait::PlacementPosition {
+                    block_face,
+                    vertical_half,
+                } => {
+                    if block_face {
+                        out.push(BlockStateDef {
+                            name: "minecraft:block_face".into(),
+                            values: strings(&BLOCK_FACES),
+                        });
+                    }
+                    if vertical_half {
+                        out.push(BlockStateDef {
+                            name: "minecraft:vertical_half".into(),
+                            values: strings(&VERTICAL_HALVES),
+                        });
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Every combination of custom and trait state values.
+    pub fn state_permutations(&self) -> Vec<Vec<(String, BlockStateValue)>> {
+        let mut combos = vec![Vec::new()];
+        for def in self.states.iter().chain(self.trait_states().iter()) {
+            combos = combos
+                .into_iter()
+                .flat_map(|combo| {
+                    def.values.iter().map(move |v| {
+                        let mut next = combo.clone();
+                        next.push((def.name.clone(), v.clone()));
+                        next
+                    })
+                })
+                .collect();
+        }
+        combos
+    }
+
+    /// Number of state combinations, without building them.
+    pub fn permutation_count(&self) -> usize {
+        self.states
+            .iter()
+            .chain(self.trait_states().iter())
+            .map(|d| d.values.len())
+            .product()
+    }
+
+    /// The default state: the first value of every state.
+    pub fn default_state(&self) -> Vec<(String, BlockStateValue)> {
+        self.states
+            .iter()
+            .chain(self.trait_states().iter())
+            .map(|d| (d.name.clone(), d.values[0].clone()))
+            .collect()
+    }
+
+    /// Effective components for a state: base components overridden by every
+    /// matching permutation, in declaration order.
+    pub fn components_for(&self, state: &[(String, BlockStateValue)]) -> HashMap<String, Value> {
+        let mut components = self.components.clone();
+        for p in self.permutations.iter().filter(|p| p.matches(state)) {
+            components.extend(p.components.clone());
+        }
+        components
+    }
+
+    /// Hardness, solidity and loot for a state.
+    pub fn properties_for(&self, state: &[(String, BlockStateValue)]) -> BlockProperties {
+        properties_of(&self.components_for(state))
+    }
+}
+
+impl BlockPermutation {
+    /// Whether the condition holds for `state`.
+    pub fn matches(&self, state: &[(String, BlockStateValue)]) -> bool {
+        self.condition
+            .split("&&")
+            .all(|clause| eval_clause(clause, state).unwrap_or(false))
+    }
+}
+
+/// Evaluate `q.block_state('name') == value` (or `!=`).
+fn eval_clause(clause: &str, state: &[(String, BlockStateValue)]) -> Option<bool> {
+    let clause = clause.trim().trim_start_matches('(').trim_end_matches(')');
+    let (lhs, rhs, negate) = match clause.split_once("==") {
+        Some((l, r)) => (l, r, false),
+        None => {
+            let (l, r) = clause.split_once("!=")?;
+            (l, r, true)
         }
+    };
+    let lhs = lhs.trim();
+    let args = lhs
+        .strip_prefix("q.block_state(")
+        .or_else(|| lhs.strip_prefix("query.block_state("))?
+        .strip_suffix(')')?;
+    let name = unquote(args.trim())?;
+    let actual = &state.iter().find(|(n, _)| n == name)?.1;
+
+    let rhs = rhs.trim();
+    let expected = match unquote(rhs) {
+        Some(s) => BlockStateValue::Str(s.to_string()),
+        None => match rhs {
+            "true" => BlockStateValue::Bool(true),
+            "false" => BlockStateValue::Bool(false),
+            n => BlockStateValue::Int(n.parse().ok()?),
+        },
+    };
+    let equal = match (actual, &expected) {
+        // Molang has no booleans: true == 1, false == 0.
+        (BlockStateValue::Bool(b), BlockStateValue::Int(i)) => *b as i32 == *i,
+        _ => *actual == expected,
+    };
+    Some(equal != negate)
+}
+
+fn unquote(s: &str) -> Option<&str> {
+    s.strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| s.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+}
+
+/// `[v, ...]` or `{ "values": { "min": a, "max": b } }`.
+fn parse_state_values(v: &Value) -> Vec<BlockStateValue> {
+    if let Some(list) = v.as_array() {
+        return list.iter().filter_map(BlockStateValue::from_json).collect();
+    }
+    let range = v.get("values").unwrap_or(v);
+    match (
+        range.get("min").and_then(|m| m.as_i64()),
+        range.get("max").and_then(|m| m.as_i64()),
+    ) {
+        (Some(min), Some(max)) => (min..=max)
+            .map(|n| BlockStateValue::Int(n as i32))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_trait(name: &str, v: &Value) -> Option<BlockTrait> {
+    let enabled: Vec<&str> = v
+        .get("enabled_states")
+        .and_then(|s| s.as_array())
+        .map(|s| s.iter().filter_map(|e| e.as_str()).collect())
+        .unwrap_or_default();
+    let has = |state: &str| enabled.contains(&state);
+    match name {
+        "minecraft:placement_direction" => Some(BlockTrait::PlacementDirection {
+            cardinal_direction: has("minecraft:cardinal_direction"),
+            facing_direction: has("minecraft:facing_direction"),
+            y_rotation_offset: v
+                .get("y_rotation_offset")
+                .and_then(|o| o.as_f64())
+                .unwrap_or(0.0) as f32,
+        }),
+        "minecraft:placement_position" => Some(BlockTrait::PlacementPosition {
+            block_face: has("minecraft:block_face"),
+            vertical_half: has("minecraft:vertical_half"),
+        }),
+        _ => None,
+    }
+}
+
+fn properties_of(components: &HashMap<String, Value>) -> BlockProperties {
+    let hardness = components
+        .get("minecraft:destructible_by_mining")
+        .and_then(|v| match v {
+            Value::Bool(false) => Some(-1.0),
+            _ => v.get("seconds_to_destroy").and_then(|s| s.as_f64()),
+        })
+        .unwrap_or(1.0) as f32;
+
+    // Solid unless explicitly set to false via collision box component.
+    let is_solid = components
+        .get("minecraft:collision_box")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+
+    let loot_table = components
+        .get("minecraft:loot")
+        .and_then(|v| v.as_str())
+        .and_then(|path| {
+            let file = path.rsplit('/').next()?;
+            Some(file.strip_suffix(".json").unwrap_or(file).to_string())
+        });
+
+    BlockProperties {
+        hardness,
+        is_solid,
+        loot_table,
     }
 }
 
@@ -98,5 +447,98 @@ mod tests {
         let b = file.extract();
         assert_eq!(b.hardness, 1.0);
         assert!(b.is_solid);
+        assert_eq!(b.permutation_count(), 1);
+        assert!(b.default_state().is_empty());
+    }
+
+    #[test]
+    fn states_traits_and_permutations() {
+        let json = r#"{
+            "format_version": "1.20.80",
+            "minecraft:block": {
+                "description": {
+                    "identifier": "custom:lamp",
+                    "states": {
+                        "custom:lit": [false, true],
+                        "custom:level": { "values": { "min": 0, "max": 2 } }
+                    },
+                    "traits": {
+                        "minecraft:placement_direction": {
+                            "enabled_states": ["minecraft:cardinal_direction"]
+                        }
+                    }
+                },
+                "components": {
+                    "minecraft:destructible_by_mining": { "seconds_to_destroy": 1.5 },
+                    "minecraft:geometry": "geometry.lamp",
+                    "minecraft:loot": "loot_tables/blocks/lamp.json"
+                },
+                "permutations": [
+                    {
+                        "condition": "q.block_state('custom:lit') == true && q.block_state('custom:level') == 2",
+                        "components": {
+                            "minecraft:destructible_by_mining": { "seconds_to_destroy": 4.0 },
+                            "minecraft:collision_box": false
+                        }
+                    },
+                    {
+                        "condition": "query.block_state('minecraft:cardinal_direction') != 'north'",
+                        "components": { "minecraft:loot": "loot_tables/blocks/lamp_turned.json" }
+                    }
+                ]
+            }
+        }"#;
+        let b = BlockFile::parse_json(json).unwrap().extract();
+        assert_eq!(b.states.len(), 2);
+        assert_eq!(b.states[0].name, "custom:level");
+        assert_eq!(b.states[0].values.len(), 3);
+        assert_eq!(b.trait_states().len(), 1);
+        assert_eq!(b.permutation_count(), 3 * 2 * 4);
+        assert_eq!(b.state_permutations().len(), 24);
+
+        let default = b.default_state();
+        assert_eq!(
+            default,
+            vec![
+                ("custom:level".to_string(), BlockStateValue::Int(0)),
+                ("custom:lit".to_string(), BlockStateValue::Bool(false)),
+                (
+                    "minecraft:cardinal_direction".to_string(),
+                    BlockStateValue::Str("north".into())
+                ),
+            ]
+        );
+        let props = b.properties_for(&default);
+        assert_eq!(props.hardness, 1.5);
+        assert!(props.is_solid);
+        assert_eq!(props.loot_table.as_deref(), Some("lamp"));
+
+        let lit = vec![
+            ("custom:level".to_string(), BlockStateValue::Int(2)),
+            ("custom:lit".to_string(), BlockStateValue::Bool(true)),
+            (
+                "minecraft:cardinal_direction".to_string(),
+                BlockStateValue::Str("east".into()),
+            ),
+        ];
+        let props = b.properties_for(&lit);
+        assert_eq!(props.hardness, 4.0);
+        assert!(!props.is_solid);
+        assert_eq!(props.loot_table.as_deref(), Some("lamp_turned"));
+    }
+
+    #[test]
+    fn unsupported_condition_never_matches() {
+        let p = BlockPermutation {
+            condition: "q.is_raining".into(),
+            components: HashMap::new(),
+        };
+        assert!(!p.matches(&[]));
+        let p = BlockPermutation {
+            condition: "q.block_state('custom:lit') == 1".into(),
+            components: HashMap::new(),
+        };
+        assert!(p.matches(&[("custom:lit".into(), BlockStateValue::Bool(true))]));
+        assert!(!p.matches(&[("custom:other".into(), BlockStateValue::Bool(true))]));
     }
 }
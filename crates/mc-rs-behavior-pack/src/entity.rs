@@ -4,6 +4,8 @@ use std::collections::HashMap;
 
 use serde::Deserialize;
 
+use crate::entity_components::{ComponentSet, EntityBehavior, EntityEvent};
+
 /// Raw entity file structure.
 #[derive(Debug, Clone, Deserialize)]
 pub struct EntityFile {
@@ -17,6 +19,10 @@ pub struct EntityDefinition {
     pub description: EntityDescription,
     #[serde(default)]
     pub components: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub component_groups: HashMap<String, HashMap<String, serde_json::Value>>,
+    #[serde(default)]
+    pub events: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -41,6 +47,8 @@ pub struct ParsedEntity {
     pub bb_height: f32,
     /// Names of `minecraft:behavior.*` components found.
     pub behaviors: Vec<String>,
+    /// Components, component groups and events that drive the mob at runtime.
+    pub behavior: EntityBehavior,
 }
 
 impl EntityFile {
@@ -98,6 +106,21 @@ impl EntityFile {
             bb_width,
             bb_height,
             behaviors,
+            behavior: EntityBehavior {
+                components: ComponentSet::from_components(comps),
+                component_groups: self
+                    .entity
+                    .component_groups
+                    .iter()
+                    .map(|(name, group)| (name.clone(), ComponentSet::from_components(group)))
+                    .collect(),
+                events: self
+                    .entity
+                    .events
+                    .iter()
+                    .map(|(name, event)| (name.clone(), EntityEvent::from_json(event)))
+                    .collect(),
+            },
         }
     }
 }
@@ -137,6 +160,31 @@ mod tests {
         assert_eq!(e.bb_width, 0.7);
         assert_eq!(e.bb_height, 2.0);
         assert_eq!(e.behaviors.len(), 2);
+        assert_eq!(e.behavior.components.goals.len(), 2);
+        assert!(e.behavior.component_groups.is_empty());
+    }
+
+    #[test]
+    fn parse_groups_and_events() {
+        let json = r#"{
+            "format_version": "1.20.0",
+            "minecraft:entity": {
+                "description": { "identifier": "custom:guard" },
+                "component_groups": {
+                    "hostile": { "minecraft:behavior.melee_attack": { "priority": 2 } }
+                },
+                "components": {},
+                "events": {
+                    "become_hostile": { "add": { "component_groups": ["hostile"] } }
+                }
+            }
+        }"#;
+        let e = EntityFile::parse_json(json).unwrap().extract();
+        assert_eq!(
+            e.behavior.component_groups["hostile"].goals[0].name,
+            "melee_attack"
+        );
+        assert_eq!(e.behavior.events["become_hostile"].add, ["hostile"]);
     }
 
     #[test]
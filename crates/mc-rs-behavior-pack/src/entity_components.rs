@@ -0,0 +1,480 @@
+//! Runtime model of an entity's components, component groups and events.
+//!
+//! An entity file declares base `components`, named `component_groups` that
+//! events add or remove, and `events` (`add`/`remove`/`randomize`/`sequence`/
+//! `trigger`/`queue_command`). The game keeps the list of active groups per mob and calls
+//! [`EntityBehavior::resolve`] whenever it changes to get the effective
+//! goals and stats.
+
+use std::collections::HashMap;
+
+use rand::Rng;
+use serde_json::Value;
+
+/// Maximum depth of `trigger` chains, to stop events that trigger each other.
+const MAX_EVENT_DEPTH: usize = 8;
+
+/// A `minecraft:behavior.<name>` goal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalComponent {
+    /// Goal name without the `minecraft:behavior.` prefix, e.g. `"random_stroll"`.
+    pub name: String,
+    /// Lower runs first.
+    pub priority: u32,
+    pub speed_multiplier: f32,
+    /// `look_distance` / `within_radius`, if given.
+    pub range: Option<f32>,
+}
+
+/// `minecraft:transformation`: become another entity type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transformation {
+    pub into: String,
+    /// Event fired on the new entity (the `<event>` suffix of `into`).
+    pub spawn_event: Option<String>,
+    pub delay_ticks: u64,
+}
+
+/// `minecraft:timer` (or `minecraft:ageable`): fire an event after a delay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerComponent {
+    pub ticks: u64,
+    pub looping: bool,
+    pub event: String,
+}
+
+/// The runtime-relevant part of a component list (base or group).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComponentSet {
+    pub goals: Vec<GoalComponent>,
+    pub movement_speed: Option<f32>,
+    pub attack_damage: Option<f32>,
+    pub is_baby: bool,
+    pub transformation: Option<Transformation>,
+    pub timer: Option<TimerComponent>,
+}
+
+impl ComponentSet {
+    /// Extract the supported components from a raw component map.
+    pub fn from_components(comps: &HashMap<String, Value>) -> Self {
+        let mut goals: Vec<GoalComponent> = comps
+            .iter()
+            .filter_map(|(key, v)| {
+                let name = key.strip_prefix("minecraft:behavior.")?;
+                Some(GoalComponent {
+                    name: name.to_string(),
+                    priority: v.get("priority").and_then(|p| p.as_u64()).unwrap_or(0) as u32,
+                    speed_multiplier: v
+                        .get("speed_multiplier")
+                        .and_then(|s| s.as_f64())
+                        .unwrap_or(1.0) as f32,
+                    range: v
+                        .get("look_distance")
+                        .or_else(|| v.get("within_radius"))
+                        .and_then(|r| r.as_f64())
+                        .map(|r| r as f32),
+                })
+            })
+            .collect();
+        sort_goals(&mut goals);
+
+        let timer = comps
+            .get("minecraft:timer")
+            .and_then(|t| {
+                Some(TimerComponent {
+                    ticks: seconds_to_ticks(t.get("time")?),
+                    looping: t.get("looping").and_then(|l| l.as_bool()).unwrap_or(true),
+                    event: event_name(t.get("time_down_event")?)?,
+                })
+            })
+            .or_else(|| {
+                let ageable = comps.get("minecraft:ageable")?;
+                Some(TimerComponent {
+                    ticks: ageable
+                        .get("duration")
+                        .map(seconds_to_ticks)
+                        .unwrap_or(1200 * 20),
+                    looping: false,
+                    event: event_name(ageable.get("grow_up")?)?,
+                })
+            });
+
+        Self {
+            goals,
+            movement_speed: comps
+                .get("minecraft:movement")
+                .and_then(|v| v.get("value"))
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32),
+            attack_damage: comps
+                .get("minecraft:attack")
+                .and_then(|v| v.get("damage"))
+                .and_then(first_number)
+                .map(|v| v as f32),
+            is_baby: comps.contains_key("minecraft:is_baby"),
+            transformation: comps.get("minecraft:transformation").and_then(|t| {
+                let into = t.get("into")?.as_str()?;
+                let (into, spawn_event) = match into.split_once('<') {
+                    Some((id, event)) => (id, Some(event.trim_end_matches('>').to_string())),
+                    None => (into, None),
+                };
+                Some(Transformation {
+                    into: into.to_string(),
+                    spawn_event,
+                    delay_ticks: t
+                        .get("delay")
+                        .map(|d| seconds_to_ticks(d.get("value").unwrap_or(d)))
+                        .unwrap_or(0),
+                })
+            }),
+            timer,
+        }
+    }
+
+    /// Overlay `other` (a component group added later) on top of `self`.
+    fn merge(&mut self, other: &ComponentSet) {
+        for goal in &other.goals {
+            match self.goals.iter_mut().find(|g| g.name == goal.name) {
+                Some(existing) => *existing = goal.clone(),
+                None => self.goals.push(goal.clone()),
+            }
+        }
+        self.movement_speed = other.movement_speed.or(self.movement_speed);
+        self.attack_damage = other.attack_damage.or(self.attack_damage);
+        self.is_baby |= other.is_baby;
+        if other.transformation.is_some() {
+            self.transformation = other.transformation.clone();
+        }
+        if other.timer.is_some() {
+            self.timer = other.timer.clone();
+        }
+    }
+}
+
+/// An entity event: what it does to the active component groups.
+///
+/// `filters` are not evaluated; filtered `sequence` steps are skipped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityEvent {
+    pub add: Vec<String>,
+    pub remove: Vec<String>,
+    pub trigger: Option<String>,
+    /// Commands queued by `queue_command`, run by the server as the entity.
+    pub queue_command: Vec<String>,
+    /// Weighted alternatives; one is picked at random.
+    pub randomize: Vec<(u32, EntityEvent)>,
+    /// Steps run in order.
+    pub sequence: Vec<EntityEvent>,
+}
+
+impl EntityEvent {
+    pub fn from_json(v: &Value) -> Self {
+        let groups = |key: &str| -> Vec<String> {
+            v.get(key)
+                .and_then(|a| a.get("component_groups"))
+                .and_then(|g| g.as_array())
+                .map(|g| {
+                    g.iter()
+                        .filter_map(|s| s.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let list = |key: &str| -> Vec<&Value> {
+            v.get(key)
+                .and_then(|a| a.as_array())
+                .map(|a| a.iter().collect())
+                .unwrap_or_default()
+        };
+
+        Self {
+            add: groups("add"),
+            remove: groups("remove"),
+            trigger: v.get("trigger").and_then(event_name),
+            queue_command: v
+                .get("queue_command")
+                .and_then(|q| q.get("command"))
+                .map(string_or_list)
+                .unwrap_or_default(),
+            randomize: list("randomize")
+                .into_iter()
+                .map(|entry| {
+                    let weight = entry.get("weight").and_then(|w| w.as_u64()).unwrap_or(1);
+                    (weight as u32, EntityEvent::from_json(entry))
+                })
+                .collect(),
+            sequence: list("sequence")
+                .into_iter()
+                .filter(|step| step.get("filters").is_none())
+                .map(EntityEvent::from_json)
+                .collect(),
+        }
+    }
+}
+
+/// Data-driven definition of a behavior pack entity.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityBehavior {
+    pub components: ComponentSet,
+    pub component_groups: HashMap<String, ComponentSet>,
+    pub events: HashMap<String, EntityEvent>,
+}
+
+impl EntityBehavior {
+    /// Effective components with the given groups active (in activation order).
+    pub fn resolve(&self, active_groups: &[String]) -> ComponentSet {
+        let mut set = self.components.clone();
+        for group in active_groups
+            .iter()
+            .filter_map(|g| self.component_groups.get(g))
+        {
+            set.merge(group);
+        }
+        sort_goals(&mut set.goals);
+        set
+    }
+
+    /// Run `event`, updating `active_groups`. Returns the commands it queued,
+    /// or `None` if the entity has no such event.
+    pub fn apply_event(
+        &self,
+        event: &str,
+        active_groups: &mut Vec<String>,
+        rng: &mut impl Rng,
+    ) -> Option<Vec<String>> {
+        let e = self.events.get(event)?;
+        let mut commands = Vec::new();
+        self.run(e, active_groups, &mut commands, rng, 0);
+        Some(commands)
+    }
+
+    fn run(
+        &self,
+        event: &EntityEvent,
+        active: &mut Vec<String>,
+        commands: &mut Vec<String>,
+        rng: &mut impl Rng,
+        depth: usize,
+    ) {
+        if depth > MAX_EVENT_DEPTH {
+            return;
+        }
+        active.retain(|g| !event.remove.contains(g));
+        for group in &event.add {
+            if self.component_groups.contains_key(group) && !active.contains(group) {
+                active.push(group.clone());
+            }
+        }
+
+        let total: u32 = event.randomize.iter().map(|(w, _)| *w).sum();
+        if total > 0 {
+            let mut roll = rng.gen_range(0..total);
+            for (weight, choice) in &event.randomize {
+                if roll < *weight {
+                    self.run(choice, active, commands, rng, depth + 1);
+                    break;
+                }
+                roll -= weight;
+            }
+        }
+
+        for step in &event.sequence {
+            self.run(step, active, commands, rng, depth + 1);
+        }
+
+        commands.extend(event.queue_command.iter().cloned());
+
+        if let Some(next) = event.trigger.as_ref().and_then(|t| self.events.get(t)) {
+            self.run(next, active, commands, rng, depth + 1);
+        }
+    }
+}
+
+fn sort_goals(goals: &mut [GoalComponent]) {
+    goals.sort_by(|a, b| {
+        a.priority
+            .cmp(&b.priority)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+}
+
+/// `"event"` or `{"event": "..."}`.
+fn event_name(v: &Value) -> Option<String> {
+    v.as_str()
+        .or_else(|| v.get("event").and_then(|e| e.as_str()))
+        .map(String::from)
+}
+
+/// `"value"` or `["value", ...]`.
+fn string_or_list(v: &Value) -> Vec<String> {
+    match v {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(a) => a
+            .iter()
+            .filter_map(|s| s.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A number or the lower bound of a `[min, max]` range.
+fn first_number(v: &Value) -> Option<f64> {
+    v.as_f64()
+        .or_else(|| {
+            v.as_array()
+                .and_then(|a| a.first())
+                .and_then(|n| n.as_f64())
+        })
+        .or_else(|| v.get("range_min").and_then(|n| n.as_f64()))
+}
+
+fn seconds_to_ticks(v: &Value) -> u64 {
+    (first_number(v).unwrap_or(0.0).max(0.0) * 20.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn components(json: &str) -> HashMap<String, Value> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn behavior() -> EntityBehavior {
+        let base = components(
+            r#"{
+                "minecraft:movement": { "value": 0.25 },
+                "minecraft:behavior.random_stroll": { "priority": 6 },
+                "minecraft:behavior.look_at_player": { "priority": 7, "look_distance": 6 }
+            }"#,
+        );
+        let angry = components(
+            r#"{
+                "minecraft:attack": { "damage": [3, 5] },
+                "minecraft:behavior.melee_attack": { "priority": 2 },
+                "minecraft:behavior.random_stroll": { "priority": 8 }
+            }"#,
+        );
+        let baby = components(
+            r#"{
+                "minecraft:is_baby": {},
+                "minecraft:ageable": { "duration": 10, "grow_up": { "event": "grow_up", "target": "self" } }
+            }"#,
+        );
+        let convert = components(
+            r#"{ "minecraft:transformation": { "into": "custom:husk<spawned_as_husk>", "delay": { "value": 2 } } }"#,
+        );
+        let events: HashMap<String, Value> = serde_json::from_str(
+            r#"{
+                "minecraft:entity_spawned": { "randomize": [
+                    { "weight": 1, "add": { "component_groups": ["baby"] } },
+                    { "weight": 0, "add": { "component_groups": ["angry"] } }
+                ] },
+                "grow_up": { "remove": { "component_groups": ["baby"] }, "trigger": "anger" },
+                "anger": {
+                    "add": { "component_groups": ["angry", "missing"] },
+                    "queue_command": { "command": "scriptevent custom:angry" }
+                },
+                "loop": { "trigger": "loop" }
+            }"#,
+        )
+        .unwrap();
+
+        EntityBehavior {
+            components: ComponentSet::from_components(&base),
+            component_groups: HashMap::from([
+                ("angry".to_string(), ComponentSet::from_components(&angry)),
+                ("baby".to_string(), ComponentSet::from_components(&baby)),
+                (
+                    "convert".to_string(),
+                    ComponentSet::from_components(&convert),
+                ),
+            ]),
+            events: events
+                .iter()
+                .map(|(k, v)| (k.clone(), EntityEvent::from_json(v)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parse_components() {
+        let b = behavior();
+        let base = &b.components;
+        assert_eq!(base.goals.len(), 2);
+        assert_eq!(base.goals[0].name, "random_stroll");
+        assert_eq!(base.goals[1].range, Some(6.0));
+        assert_eq!(base.movement_speed, Some(0.25));
+        assert_eq!(b.component_groups["angry"].attack_damage, Some(3.0));
+
+        let baby = &b.component_groups["baby"];
+        assert!(baby.is_baby);
+        let timer = baby.timer.as_ref().unwrap();
+        assert_eq!((timer.ticks, timer.looping), (200, false));
+        assert_eq!(timer.event, "grow_up");
+
+        let t = b.component_groups["convert"]
+            .transformation
+            .as_ref()
+            .unwrap();
+        assert_eq!(t.into, "custom:husk");
+        assert_eq!(t.spawn_event.as_deref(), Some("spawned_as_husk"));
+        assert_eq!(t.delay_ticks, 40);
+    }
+
+    #[test]
+    fn resolve_overlays_groups() {
+        let b = behavior();
+        let set = b.resolve(&["angry".to_string()]);
+        let names: Vec<_> = set.goals.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["melee_attack", "look_at_player", "random_stroll"]);
+        assert_eq!(set.attack_damage, Some(3.0));
+        assert_eq!(set.movement_speed, Some(0.25));
+        assert!(!set.is_baby);
+    }
+
+    #[test]
+    fn events_add_remove_and_trigger() {
+        let b = behavior();
+        let mut rng = rand::thread_rng();
+        let mut active = Vec::new();
+
+        let spawned = b.apply_event("minecraft:entity_spawned", &mut active, &mut rng);
+        assert_eq!(spawned, Some(Vec::new()));
+        assert_eq!(active, ["baby"]);
+        assert!(b.resolve(&active).is_baby);
+
+        // grow_up removes "baby" and triggers "anger"; unknown groups are ignored
+        let commands = b.apply_event("grow_up", &mut active, &mut rng).unwrap();
+        assert_eq!(active, ["angry"]);
+        assert_eq!(commands, ["scriptevent custom:angry"]);
+
+        assert!(b.apply_event("nope", &mut active, &mut rng).is_none());
+        // Self-triggering events terminate
+        assert!(b.apply_event("loop", &mut active, &mut rng).is_some());
+    }
+
+    #[test]
+    fn sequence_skips_filtered_steps() {
+        let v: Value = serde_json::from_str(
+            r#"{ "sequence": [
+                { "add": { "component_groups": ["a"] } },
+                { "filters": { "test": "is_family" }, "add": { "component_groups": ["b"] } }
+            ] }"#,
+        )
+        .unwrap();
+        let e = EntityEvent::from_json(&v);
+        assert_eq!(e.sequence.len(), 1);
+        assert_eq!(e.sequence[0].add, ["a"]);
+    }
+
+    #[test]
+    fn queue_command_accepts_string_or_list() {
+        let v: Value = serde_json::from_str(
+            r#"{ "queue_command": { "command": ["say hi", "scriptevent a:b payload"] } }"#,
+        )
+        .unwrap();
+        let e = EntityEvent::from_json(&v);
+        assert_eq!(e.queue_command, ["say hi", "scriptevent a:b payload"]);
+    }
+}
@@ -0,0 +1,170 @@
+//! Function parsing (functions/**/*.mcfunction) and the tick/load function tags.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use tracing::warn;
+
+/// A function tag file (`functions/tick.json`, `functions/load.json`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FunctionTagFile {
+    #[serde(default)]
+    pub values: Vec<String>,
+}
+
+impl FunctionTagFile {
+    /// Parse a function tag from a JSON string.
+    pub fn parse_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// The functions of a pack, keyed by their path under `functions/` without
+/// the extension (e.g. `maps/start`).
+#[derive(Debug, Clone, Default)]
+pub struct PackFunctions {
+    pub functions: HashMap<String, Vec<String>>,
+    /// Functions run every tick (`tick.json`).
+    pub tick: Vec<String>,
+    /// Functions run once when the server starts (`load.json`).
+    pub load: Vec<String>,
+}
+
+/// Parse the commands of an .mcfunction file: one per line, skipping blank
+/// lines and `#` comments, without a leading `/`.
+pub fn parse_mcfunction(source: &str) -> Vec<String> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.strip_prefix('/').unwrap_or(line).to_string())
+        .collect()
+}
+
+/// Normalize a function name as written in `/function` or a tag: no
+/// surrounding quotes, leading slash or `.mcfunction` extension.
+pub fn normalize_function_name(name: &str) -> String {
+    let name = name.trim().trim_matches('"');
+    let name = name.strip_prefix('/').unwrap_or(name);
+    let name = name.strip_suffix(".mcfunction").unwrap_or(name);
+    name.replace('\\', "/")
+}
+
+/// Load every function and the tick/load tags under `functions/`.
+pub fn load_functions(pack_root: &Path) -> PackFunctions {
+    let dir = pack_root.join("functions");
+    let mut pack = PackFunctions::default();
+    if !dir.is_dir() {
+        return pack;
+    }
+    collect_functions(&dir, "", &mut pack.functions);
+    pack.tick = load_tag(&dir.join("tick.json"));
+    pack.load = load_tag(&dir.join("load.json"));
+    pack
+}
+
+/// Recursively read the .mcfunction files of `dir`, prefixing names with `prefix`.
+fn collect_functions(dir: &Path, prefix: &str, functions: &mut HashMap<String, Vec<String>>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let stem = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        if path.is_dir() {
+            let name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            collect_functions(&path, &format!("{prefix}{name}/"), functions);
+        } else if path.extension().map(|e| e == "mcfunction").unwrap_or(false) {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => {
+                    functions.insert(format!("{prefix}{stem}"), parse_mcfunction(&content));
+                }
+                Err(e) => warn!("Failed to read {}: {e}", path.display()),
+            }
+        }
+    }
+}
+
+/// Function names listed in a tag file, or none if it's missing.
+fn load_tag(path: &Path) -> Vec<String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+    match FunctionTagFile::parse_json(&content) {
+        Ok(tag) => tag
+            .values
+            .iter()
+            .map(|v| normalize_function_name(v))
+            .collect(),
+        Err(e) => {
+            warn!("Failed to parse {}: {e}", path.display());
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn parse_lines() {
+        let commands = parse_mcfunction(
+            "# Start the map\n\nsay hello\n  /tp @a 0 64 0  \r\n#scoreboard objectives add x dummy\nfunction maps/next\n",
+        );
+        assert_eq!(
+            commands,
+            vec!["say hello", "tp @a 0 64 0", "function maps/next"]
+        );
+    }
+
+    #[test]
+    fn normalize_names() {
+        assert_eq!(normalize_function_name("maps/start"), "maps/start");
+        assert_eq!(normalize_function_name("\"maps/start\""), "maps/start");
+        assert_eq!(
+            normalize_function_name("/maps/start.mcfunction"),
+            "maps/start"
+        );
+    }
+
+    #[test]
+    fn load_nested_functions_and_tags() {
+        let dir = std::env::temp_dir().join("mc_rs_bp_test_functions");
+        let functions = dir.join("functions");
+        let _ = fs::create_dir_all(functions.join("maps"));
+        fs::write(functions.join("setup.mcfunction"), "say setup\n").unwrap();
+        fs::write(
+            functions.join("maps").join("loop.mcfunction"),
+            "# every tick\ntime add 1\n",
+        )
+        .unwrap();
+        fs::write(
+            functions.join("tick.json"),
+            r#"{ "values": ["maps/loop"] }"#,
+        )
+        .unwrap();
+        fs::write(functions.join("load.json"), r#"{ "values": ["setup"] }"#).unwrap();
+
+        let pack = load_functions(&dir);
+        assert_eq!(pack.functions.len(), 2);
+        assert_eq!(pack.functions["setup"], vec!["say setup"]);
+        assert_eq!(pack.functions["maps/loop"], vec!["time add 1"]);
+        assert_eq!(pack.tick, vec!["maps/loop"]);
+        assert_eq!(pack.load, vec!["setup"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
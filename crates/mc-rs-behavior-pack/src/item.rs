@@ -33,6 +33,69 @@ pub struct ParsedItem {
     pub max_stack_size: u8,
     /// All behavior pack items are component-based.
     pub is_component_based: bool,
+    pub food: Option<ItemFood>,
+    pub durability: Option<ItemDurability>,
+    pub wearable: Option<ItemWearable>,
+    pub cooldown: Option<ItemCooldown>,
+    /// Ticks it takes to use (e.g. eat) the item, from `minecraft:use_modifiers`.
+    pub use_duration_ticks: Option<u64>,
+    /// Raw components, sent to the client in the item definition.
+    pub components: HashMap<String, serde_json::Value>,
+}
+
+/// `minecraft:food`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemFood {
+    pub nutrition: i32,
+    pub saturation_modifier: f32,
+    pub can_always_eat: bool,
+    /// Item left in the hand once eaten (e.g. a bowl).
+    pub using_converts_to: Option<String>,
+}
+
+impl ItemFood {
+    /// Saturation restored: `nutrition * saturation_modifier * 2`.
+    pub fn saturation(&self) -> f32 {
+        self.nutrition as f32 * self.saturation_modifier * 2.0
+    }
+}
+
+/// `minecraft:durability`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemDurability {
+    pub max_durability: u16,
+    /// Percent chance (min, max) that a use damages the item.
+    pub damage_chance: (u8, u8),
+}
+
+/// `minecraft:wearable`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemWearable {
+    /// Equipment slot, e.g. `"slot.armor.head"`.
+    pub slot: String,
+    /// Armor points granted while worn.
+    pub protection: i32,
+}
+
+impl ItemWearable {
+    /// Armor slot index (0 = head … 3 = feet), or `None` for non-armor slots.
+    pub fn armor_slot(&self) -> Option<usize> {
+        match self.slot.as_str() {
+            "slot.armor.head" => Some(0),
+            "slot.armor.chest" => Some(1),
+            "slot.armor.legs" => Some(2),
+            "slot.armor.feet" => Some(3),
+            _ => None,
+        }
+    }
+}
+
+/// `minecraft:cooldown`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemCooldown {
+    /// Items sharing a category share their cooldown.
+    pub category: String,
+    pub duration_ticks: u64,
 }
 
 impl ItemFile {
@@ -55,12 +118,105 @@ impl ItemFile {
             })
             .unwrap_or(64) as u8;
 
+        let food = self.component("minecraft:food").map(|v| ItemFood {
+            nutrition: v.get("nutrition").and_then(|n| n.as_i64()).unwrap_or(0) as i32,
+            saturation_modifier: v
+                .get("saturation_modifier")
+                .map(saturation_modifier)
+                .unwrap_or(0.6),
+            can_always_eat: v
+                .get("can_always_eat")
+                .and_then(|b| b.as_bool())
+                .unwrap_or(false),
+            using_converts_to: v
+                .get("using_converts_to")
+                .and_then(|i| i.as_str())
+                .map(String::from),
+        });
+
+        let durability = self.component("minecraft:durability").map(|v| {
+            let chance = v.get("damage_chance");
+            let bound = |key: &str| {
+                chance
+                    .and_then(|c| c.get(key))
+                    .and_then(|n| n.as_u64())
+                    .unwrap_or(100)
+                    .min(100) as u8
+            };
+            ItemDurability {
+                max_durability: v
+                    .get("max_durability")
+                    .and_then(|n| n.as_u64())
+                    .unwrap_or(0)
+                    .min(u16::MAX as u64) as u16,
+                damage_chance: (bound("min"), bound("max")),
+            }
+        });
+
+        let wearable = self.component("minecraft:wearable").and_then(|v| {
+            Some(ItemWearable {
+                slot: v.get("slot")?.as_str()?.to_string(),
+                protection: v.get("protection").and_then(|n| n.as_i64()).unwrap_or(0) as i32,
+            })
+        });
+
+        let cooldown = self.component("minecraft:cooldown").map(|v| ItemCooldown {
+            category: v
+                .get("category")
+                .and_then(|c| c.as_str())
+                .unwrap_or(&self.item.description.identifier)
+                .to_string(),
+            duration_ticks: seconds_to_ticks(v.get("duration")),
+        });
+
+        let use_duration_ticks = self
+            .component("minecraft:use_modifiers")
+            .and_then(|v| v.get("use_duration"))
+            .map(|d| seconds_to_ticks(Some(d)));
+
+        // Damageable and wearable items never stack
+        let max_stack_size = if durability.is_some() || wearable.is_some() {
+            1
+        } else {
+            max_stack
+        };
+
         ParsedItem {
             identifier: self.item.description.identifier.clone(),
-            max_stack_size: max_stack,
+            max_stack_size,
             is_component_based: true,
+            food,
+            durability,
+            wearable,
+            cooldown,
+            use_duration_ticks,
+            components: self.item.components.clone(),
         }
     }
+
+    fn component(&self, name: &str) -> Option<&serde_json::Value> {
+        self.item.components.get(name)
+    }
+}
+
+/// A number, or one of the named modifiers (`"poor"` … `"supernatural"`).
+fn saturation_modifier(v: &serde_json::Value) -> f32 {
+    if let Some(n) = v.as_f64() {
+        return n as f32;
+    }
+    match v.as_str().unwrap_or("normal") {
+        "poor" => 0.1,
+        "low" => 0.3,
+        "good" => 0.8,
+        "max" => 1.0,
+        "supernatural" => 1.2,
+        _ => 0.6,
+    }
+}
+
+fn seconds_to_ticks(v: Option<&serde_json::Value>) -> u64 {
+    let secs = v.and_then(|d| d.as_f64()).unwrap_or(0.0).max(0.0);
+    (secs * 20.0).round() as u64
 }
 
 #[cfg(test)]
@@ -97,5 +253,63 @@ mod tests {
         let file = ItemFile::parse_json(json).unwrap();
         let item = file.extract();
         assert_eq!(item.max_stack_size, 64);
+        assert!(item.food.is_none());
+        assert!(item.durability.is_none());
+    }
+
+    #[test]
+    fn parse_food_and_cooldown() {
+        let json = r#"{
+            "format_version": "1.21.0",
+            "minecraft:item": {
+                "description": { "identifier": "custom:stew" },
+                "components": {
+                    "minecraft:food": {
+                        "nutrition": 6,
+                        "saturation_modifier": "good",
+                        "can_always_eat": true,
+                        "using_converts_to": "minecraft:bowl"
+                    },
+                    "minecraft:use_modifiers": { "use_duration": 1.6 },
+                    "minecraft:cooldown": { "category": "stew", "duration": 2.5 }
+                }
+            }
+        }"#;
+        let item = ItemFile::parse_json(json).unwrap().extract();
+        let food = item.food.unwrap();
+        assert_eq!(food.nutrition, 6);
+        assert!((food.saturation() - 9.6).abs() < 1e-4);
+        assert!(food.can_always_eat);
+        assert_eq!(food.using_converts_to.as_deref(), Some("minecraft:bowl"));
+        assert_eq!(item.use_duration_ticks, Some(32));
+        let cooldown = item.cooldown.unwrap();
+        assert_eq!(cooldown.category, "stew");
+        assert_eq!(cooldown.duration_ticks, 50);
+    }
+
+    #[test]
+    fn parse_durable_wearable() {
+        let json = r#"{
+            "format_version": "1.21.0",
+            "minecraft:item": {
+                "description": { "identifier": "custom:ruby_helmet" },
+                "components": {
+                    "minecraft:max_stack_size": 64,
+                    "minecraft:durability": {
+                        "max_durability": 300,
+                        "damage_chance": { "min": 60, "max": 100 }
+                    },
+                    "minecraft:wearable": { "slot": "slot.armor.head", "protection": 3 }
+                }
+            }
+        }"#;
+        let item = ItemFile::parse_json(json).unwrap().extract();
+        assert_eq!(item.max_stack_size, 1);
+        let durability = item.durability.unwrap();
+        assert_eq!(durability.max_durability, 300);
+        assert_eq!(durability.damage_chance, (60, 100));
+        let wearable = item.wearable.unwrap();
+        assert_eq!(wearable.armor_slot(), Some(0));
+        assert_eq!(wearable.protection, 3);
     }
 }
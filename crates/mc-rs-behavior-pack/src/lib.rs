@@ -1,10 +1,14 @@
 //! Bedrock behavior pack parser.
 //!
 //! Parses behavior pack JSON files (manifest, entities, items, blocks, recipes,
-//! loot tables) and provides a loader that scans a pack directory.
+//! loot tables) and functions, and provides a loader that scans a pack
+//! directory. Entity component groups and events are parsed into a runtime
+//! model the game uses to drive mob AI.
 
 pub mod block;
 pub mod entity;
+pub mod entity_components;
+pub mod function;
 pub mod item;
 pub mod loader;
 pub mod loot_table;
@@ -7,6 +7,7 @@ use tracing::{info, warn};
 
 use crate::block::{BlockFile, ParsedBlock};
 use crate::entity::{EntityFile, ParsedEntity};
+use crate::function::{load_functions, PackFunctions};
 use crate::item::{ItemFile, ParsedItem};
 use crate::loot_table::LootTableFile;
 use crate::manifest::BehaviorPackManifest;
@@ -21,6 +22,7 @@ pub struct LoadedBehaviorPack {
     pub blocks: Vec<ParsedBlock>,
     pub recipes: Vec<RecipeFile>,
     pub loot_tables: HashMap<String, LootTableFile>,
+    pub functions: PackFunctions,
     /// Raw .mcpack bytes for client transfer (if available).
     pub pack_bytes: Option<Vec<u8>>,
     pub pack_size: u64,
@@ -53,6 +55,8 @@ pub fn load_behavior_pack(path: &Path) -> Result<LoadedBehaviorPack, String> {
 
     let loot_tables = load_loot_tables(path);
 
+    let functions = load_functions(path);
+
     // Check for a pre-zipped .mcpack file alongside the directory.
     let pack_name = path.file_name().unwrap_or_default().to_string_lossy();
     let mcpack_path = path.with_extension("mcpack");
@@ -72,7 +76,7 @@ pub fn load_behavior_pack(path: &Path) -> Result<LoadedBehaviorPack, String> {
     };
 
     info!(
-        "Loaded behavior pack '{}' v{} ({} entities, {} items, {} blocks, {} recipes, {} loot tables{})",
+        "Loaded behavior pack '{}' v{} ({} entities, {} items, {} blocks, {} recipes, {} loot tables, {} functions{})",
         manifest.header.name,
         manifest.version_string(),
         entities.len(),
@@ -80,6 +84,7 @@ pub fn load_behavior_pack(path: &Path) -> Result<LoadedBehaviorPack, String> {
         blocks.len(),
         recipes.len(),
         loot_tables.len(),
+        functions.functions.len(),
         if pack_bytes.is_some() {
             format!(", {pack_name}.mcpack for transfer")
         } else {
@@ -94,6 +99,7 @@ pub fn load_behavior_pack(path: &Path) -> Result<LoadedBehaviorPack, String> {
         blocks,
         recipes,
         loot_tables,
+        functions,
         pack_bytes,
         pack_size,
     })
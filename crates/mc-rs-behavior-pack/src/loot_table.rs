@@ -1,8 +1,25 @@
 //! Loot table parsing and evaluation (loot_tables/*.json).
 
+use std::collections::HashMap;
+
 use rand::Rng;
 use serde::Deserialize;
 
+/// Leaves with their vanilla loot: the sapling they drop one time in so
+/// many, and whether they drop apples too.
+const VANILLA_LEAVES: &[(&str, &str, u32, bool)] = &[
+    ("oak_leaves", "minecraft:oak_sapling", 20, true),
+    ("spruce_leaves", "minecraft:spruce_sapling", 20, false),
+    ("birch_leaves", "minecraft:birch_sapling", 20, false),
+    ("jungle_leaves", "minecraft:jungle_sapling", 40, false),
+    ("acacia_leaves", "minecraft:acacia_sapling", 20, false),
+    ("dark_oak_leaves", "minecraft:dark_oak_sapling", 20, true),
+    ("cherry_leaves", "minecraft:cherry_sapling", 20, false),
+];
+
+/// Leaves that drop apples drop one time in this many.
+const APPLE_CHANCE: u32 = 200;
+
 /// A loot table with one or more pools.
 #[derive(Debug, Clone, Deserialize)]
 pub struct LootTableFile {
@@ -84,6 +101,39 @@ fn default_weight() -> u32 {
     1
 }
 
+/// A pool dropping `item` one time in `one_in`.
+fn chance_pool(item: &str, one_in: u32) -> LootPool {
+    let entry = |entry_type: &str, name: Option<&str>, weight| LootEntry {
+        entry_type: entry_type.into(),
+        name: name.map(Into::into),
+        weight,
+        functions: Vec::new(),
+    };
+    LootPool {
+        rolls: RollsValue::Fixed(1),
+        entries: vec![
+            entry("item", Some(item), 1),
+            entry("empty", None, one_in - 1),
+        ],
+    }
+}
+
+/// The vanilla loot tables the server falls back on, keyed by file stem
+/// like the tables of behavior packs, which replace them by name: leaves
+/// dropping saplings and apples.
+pub fn vanilla_loot_tables() -> HashMap<String, LootTableFile> {
+    VANILLA_LEAVES
+        .iter()
+        .map(|&(leaves, sapling, sapling_chance, apples)| {
+            let mut pools = vec![chance_pool(sapling, sapling_chance)];
+            if apples {
+                pools.push(chance_pool("minecraft:apple", APPLE_CHANCE));
+            }
+            (leaves.to_string(), LootTableFile { pools })
+        })
+        .collect()
+}
+
 /// A single item drop result.
 #[derive(Debug, Clone)]
 pub struct LootDrop {
@@ -220,6 +270,23 @@ mod tests {
         assert_eq!(drops[0].count, 5);
     }
 
+    #[test]
+    fn vanilla_leaves_drop_saplings_and_apples() {
+        let tables = vanilla_loot_tables();
+        let oak = &tables["oak_leaves"];
+        assert_eq!(oak.pools.len(), 2);
+        assert_eq!(
+            oak.pools[0].entries[0].name.as_deref(),
+            Some("minecraft:oak_sapling")
+        );
+        assert_eq!(oak.pools[0].entries[1].weight, 19);
+        assert_eq!(oak.pools[1].entries[1].weight, 199);
+        assert_eq!(tables["birch_leaves"].pools.len(), 1);
+        // Over many rolls, saplings come about one time in 20
+        let saplings: usize = (0..2000).map(|_| tables["birch_leaves"].roll().len()).sum();
+        assert!((40..=170).contains(&saplings), "{saplings}");
+    }
+
     #[test]
     fn roll_empty_pool() {
         let json = r#"{ "pools": [] }"#;
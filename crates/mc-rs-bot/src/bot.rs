@@ -0,0 +1,299 @@
+//! A single synthetic client: RakNet connection, offline login, then a
+//! PlayerAuthInput every game tick until the test ends.
+
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use mc_rs_proto::batch::{decode_batch, encode_single, BatchConfig};
+use mc_rs_proto::codec::{ProtoDecode, ProtoEncode};
+use mc_rs_proto::compression::CompressionAlgorithm;
+use mc_rs_proto::packets::{
+    self, id, Disconnect, LoginPacket, NetworkSettings, PlayStatus, PlayStatusType,
+    PlayerAuthInput, RequestChunkRadius, RequestNetworkSettings, ResourcePackClientResponse,
+    ResourcePackResponseStatus, SetLocalPlayerAsInitialized,
+};
+use mc_rs_proto::protocol::ProtocolAdapter;
+use mc_rs_proto::types::{VarInt, VarLong, VarUInt32, VarUInt64, Vec2, Vec3};
+use mc_rs_raknet::{ClientStats, RakNetClient};
+
+use crate::LoadConfig;
+
+/// MTU bots propose to the server.
+const BOT_MTU: u16 = 1400;
+
+/// Time allowed for the RakNet handshake.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval between PlayerAuthInputs: one per game tick, like a real client.
+const INPUT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Radius of the circle bots walk from their spawn point.
+const WALK_RADIUS: f32 = 3.0;
+
+/// Blocks walked per tick, about walking speed.
+const WALK_SPEED: f32 = 0.2;
+
+/// What one bot saw, reported when it stops.
+#[derive(Debug, Clone, Default)]
+pub struct BotStats {
+    pub name: String,
+    /// Why the bot stopped before the end of the test, if it did.
+    pub error: Option<String>,
+    /// From starting the RakNet handshake to being allowed to spawn.
+    pub login_time: Option<Duration>,
+    pub game_packets_received: u64,
+    pub chunks_received: u64,
+    pub inputs_sent: u64,
+    pub raknet: ClientStats,
+}
+
+/// Where a bot is in the login sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    /// Waiting for NetworkSettings.
+    NetworkSettings,
+    /// Login sent; answering the resource pack exchange until StartGame.
+    Login,
+    /// StartGame received, waiting to be allowed to spawn.
+    Spawning,
+    /// In game, sending movement.
+    Playing,
+}
+
+struct Bot {
+    client: RakNetClient,
+    batch_config: BatchConfig,
+    protocol: ProtocolAdapter,
+    stage: Stage,
+    runtime_id: u64,
+    spawn: Vec3,
+    position: Vec3,
+    tick: u64,
+    stats: BotStats,
+}
+
+/// Run one bot until `deadline`, or until it fails.
+pub async fn run_bot(config: &LoadConfig, index: usize, deadline: Instant) -> BotStats {
+    let name = format!("{}{index}", config.name_prefix);
+    let started = Instant::now();
+    let guid = ((std::process::id() as i64) << 32) | index as i64;
+    let client = match RakNetClient::connect(config.server, guid, BOT_MTU, CONNECT_TIMEOUT).await {
+        Ok(c) => c,
+        Err(e) => {
+            return BotStats {
+                name,
+                error: Some(format!("connect: {e}")),
+                ..BotStats::default()
+            }
+        }
+    };
+
+    let mut bot = Bot {
+        client,
+        batch_config: BatchConfig::default(),
+        protocol: ProtocolAdapter::default(),
+        stage: Stage::NetworkSettings,
+        runtime_id: 0,
+        spawn: Vec3::ZERO,
+        position: Vec3::ZERO,
+        tick: 0,
+        stats: BotStats {
+            name,
+            ..BotStats::default()
+        },
+    };
+    if let Err(e) = bot.run(config, index, started, deadline).await {
+        bot.stats.error = Some(e);
+    }
+    let _ = bot.client.disconnect().await;
+    bot.stats.raknet = bot.client.stats.clone();
+    bot.stats
+}
+
+impl Bot {
+    async fn run(
+        &mut self,
+        config: &LoadConfig,
+        index: usize,
+        started: Instant,
+        deadline: Instant,
+    ) -> Result<(), String> {
+        self.send(
+            id::REQUEST_NETWORK_SETTINGS,
+            &RequestNetworkSettings {
+                protocol_version: packets::PROTOCOL_VERSION,
+            },
+        )
+        .await?;
+
+        let mut next_tick = Instant::now() + INPUT_INTERVAL;
+        while Instant::now() < deadline {
+            let wait = next_tick.saturating_duration_since(Instant::now());
+            if let Ok(batches) = tokio::time::timeout(wait, self.client.recv()).await {
+                for batch in batches.map_err(|e| e.to_string())? {
+                    self.handle_batch(config, index, started, batch).await?;
+                }
+            }
+            if Instant::now() >= next_tick {
+                next_tick += INPUT_INTERVAL;
+                if self.stage == Stage::Playing {
+                    self.send_input().await?;
+                }
+                self.client.tick().await.map_err(|e| e.to_string())?;
+            }
+        }
+        if self.stage != Stage::Playing {
+            return Err(format!("still in {:?} at the end of the test", self.stage));
+        }
+        Ok(())
+    }
+
+    /// Encode one packet into its own batch and send it right away.
+    async fn send(&mut self, packet_id: u32, packet: &impl ProtoEncode) -> Result<(), String> {
+        let sub_packet = self.protocol.encode(packet_id, packet);
+        let batch = encode_single(sub_packet, &self.batch_config).map_err(|e| e.to_string())?;
+        self.client.send_game(&batch);
+        self.client.tick().await.map_err(|e| e.to_string())
+    }
+
+    async fn handle_batch(
+        &mut self,
+        config: &LoadConfig,
+        index: usize,
+        started: Instant,
+        batch: Bytes,
+    ) -> Result<(), String> {
+        let sub_packets = decode_batch(batch, &self.batch_config).map_err(|e| e.to_string())?;
+        for mut body in sub_packets {
+            let packet_id = VarUInt32::proto_decode(&mut body)
+                .map_err(|e| e.to_string())?
+                .0;
+            self.stats.game_packets_received += 1;
+            match packet_id {
+                id::NETWORK_SETTINGS => {
+                    let settings =
+                        NetworkSettings::proto_decode(&mut body).map_err(|e| e.to_string())?;
+                    self.batch_config.compression_enabled = true;
+                    self.batch_config.compression =
+                        CompressionAlgorithm::from_u16(settings.compression_algorithm)
+                            .unwrap_or(CompressionAlgorithm::Zlib);
+                    self.batch_config.compression_threshold =
+                        settings.compression_threshold as usize;
+
+                    let login = LoginPacket::offline(
+                        packets::PROTOCOL_VERSION,
+                        &self.stats.name,
+                        &format!("{}", 2_535_500_000_000_000u64 + index as u64),
+                        &format!("00000000-0000-4000-9000-{index:012x}"),
+                        &format!("mc-rs-bot-{index}"),
+                    );
+                    self.send(id::LOGIN, &login).await?;
+                    self.stage = Stage::Login;
+                }
+                id::RESOURCE_PACKS_INFO => {
+                    self.respond_to_packs(ResourcePackResponseStatus::HaveAllPacks)
+                        .await?;
+                }
+                id::RESOURCE_PACK_STACK => {
+                    self.respond_to_packs(ResourcePackResponseStatus::Completed)
+                        .await?;
+                }
+                id::START_GAME => {
+                    let _entity_unique_id =
+                        VarLong::proto_decode(&mut body).map_err(|e| e.to_string())?;
+                    self.runtime_id = VarUInt64::proto_decode(&mut body)
+                        .map_err(|e| e.to_string())?
+                        .0;
+                    let _gamemode = VarInt::proto_decode(&mut body).map_err(|e| e.to_string())?;
+                    self.spawn = Vec3::proto_decode(&mut body).map_err(|e| e.to_string())?;
+                    self.position = self.spawn;
+                    self.stage = Stage::Spawning;
+                    self.send(
+                        id::REQUEST_CHUNK_RADIUS,
+                        &RequestChunkRadius {
+                            chunk_radius: config.chunk_radius,
+                            max_chunk_radius: config.chunk_radius,
+                        },
+                    )
+                    .await?;
+                }
+                id::PLAY_STATUS => {
+                    let status = PlayStatus::proto_decode(&mut body).map_err(|e| e.to_string())?;
+                    match status.status {
+                        PlayStatusType::LoginSuccess => {}
+                        PlayStatusType::PlayerSpawn if self.stage != Stage::Playing => {
+                            self.stats.login_time = Some(started.elapsed());
+                            self.stage = Stage::Playing;
+                            self.send(
+                                id::SET_LOCAL_PLAYER_AS_INITIALIZED,
+                                &SetLocalPlayerAsInitialized {
+                                    entity_runtime_id: self.runtime_id,
+                                },
+                            )
+                            .await?;
+                        }
+                        PlayStatusType::PlayerSpawn => {}
+                        other => return Err(format!("login refused: {other:?}")),
+                    }
+                }
+                id::LEVEL_CHUNK => self.stats.chunks_received += 1,
+                id::DISCONNECT => {
+                    let reason = Disconnect::proto_decode(&mut body)
+                        .ok()
+                        .and_then(|d| d.message)
+                        .unwrap_or_default();
+                    return Err(format!("disconnected: {reason}"));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn respond_to_packs(&mut self, status: ResourcePackResponseStatus) -> Result<(), String> {
+        self.send(
+            id::RESOURCE_PACK_CLIENT_RESPONSE,
+            &ResourcePackClientResponse {
+                status,
+                resource_pack_ids: Vec::new(),
+            },
+        )
+        .await
+    }
+
+    /// Walk one step around a circle that starts and ends at the spawn
+    /// point.
+    async fn send_input(&mut self) -> Result<(), String> {
+        self.tick += 1;
+        let angle = self.tick as f32 * WALK_SPEED / WALK_RADIUS;
+        let position = Vec3::new(
+            self.spawn.x - WALK_RADIUS + WALK_RADIUS * angle.cos(),
+            self.spawn.y,
+            self.spawn.z + WALK_RADIUS * angle.sin(),
+        );
+        let delta = Vec3::new(
+            position.x - self.position.x,
+            0.0,
+            position.z - self.position.z,
+        );
+        self.position = position;
+        let input = PlayerAuthInput {
+            pitch: 0.0,
+            yaw: angle.to_degrees() + 180.0,
+            position,
+            move_vector: Vec2::new(0.0, 1.0),
+            head_yaw: angle.to_degrees() + 180.0,
+            input_data: 0,
+            input_mode: 1,
+            play_mode: 0,
+            interaction_model: 0,
+            tick: self.tick,
+            position_delta: delta,
+        };
+        self.stats.inputs_sent += 1;
+        let sub_packet = self.protocol.encode(id::PLAYER_AUTH_INPUT, &input);
+        let batch = encode_single(sub_packet, &self.batch_config).map_err(|e| e.to_string())?;
+        self.client.send_game(&batch);
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+//! Load testing: synthetic Bedrock clients against a running server.
+//!
+//! Each bot opens a real RakNet connection, logs in with an unsigned
+//! identity, then sends a PlayerAuthInput every game tick while walking
+//! around its spawn point. [`run`] starts the bots at a steady rate and
+//! gathers what they measured into a [`Report`].
+//!
+//! The server must run with `online_mode = false`, and with `[throttle]`
+//! limits high enough for every bot to connect from one address. Bots do
+//! not simulate gravity, so `[anticheat]` must be disabled too.
+
+pub mod bot;
+pub mod report;
+
+pub use bot::BotStats;
+pub use report::{Report, Summary};
+
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+/// What to run against which server.
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    pub server: SocketAddr,
+    /// Number of bots to connect.
+    pub bots: usize,
+    /// How long the test lasts, from the first bot connecting.
+    pub duration: Duration,
+    /// Bots started per second.
+    pub join_rate: f64,
+    /// Bot names are this prefix followed by the bot's index.
+    pub name_prefix: String,
+    /// Chunk radius the bots ask for.
+    pub chunk_radius: i32,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            server: SocketAddr::from(([127, 0, 0, 1], 19132)),
+            bots: 10,
+            duration: Duration::from_secs(60),
+            join_rate: 5.0,
+            name_prefix: "Bot".into(),
+            chunk_radius: 4,
+        }
+    }
+}
+
+/// Run every bot until the end of the test and report on them.
+pub async fn run(config: &LoadConfig) -> Report {
+    let started = Instant::now();
+    let deadline = started + config.duration;
+    let interval = Duration::from_secs_f64(1.0 / config.join_rate.max(0.001));
+
+    let mut tasks = Vec::with_capacity(config.bots);
+    for index in 0..config.bots {
+        let start_at = started + interval.mul_f64(index as f64);
+        if start_at >= deadline {
+            break;
+        }
+        tokio::time::sleep_until(start_at.into()).await;
+        let config = config.clone();
+        tasks.push(tokio::spawn(async move {
+            bot::run_bot(&config, index, deadline).await
+        }));
+    }
+
+    let mut stats = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        match task.await {
+            Ok(s) => stats.push(s),
+            Err(e) => stats.push(BotStats {
+                error: Some(format!("bot task failed: {e}")),
+                ..BotStats::default()
+            }),
+        }
+    }
+    Report::new(&stats, config.bots)
+}
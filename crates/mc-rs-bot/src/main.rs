@@ -0,0 +1,67 @@
+//! `mc-rs-bot [options] <server address>` — connect synthetic clients to a
+//! server and report login times, latency and packet loss.
+//!
+//! Exits with status 1 if any bot failed to connect or was dropped.
+
+use std::process::ExitCode;
+use std::time::Duration;
+
+use mc_rs_bot::LoadConfig;
+
+const USAGE: &str = "usage: mc-rs-bot [--bots N] [--duration SECS] [--join-rate PER_SEC] \
+                     [--radius CHUNKS] [--prefix NAME] <server address>";
+
+fn parse_args() -> Result<LoadConfig, String> {
+    let mut config = LoadConfig::default();
+    let mut server = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            server = Some(arg);
+            continue;
+        }
+        let value = args.next().ok_or_else(|| format!("{arg} needs a value"))?;
+        let invalid = || format!("invalid value for {arg}: {value}");
+        match arg.as_str() {
+            "--bots" => config.bots = value.parse().map_err(|_| invalid())?,
+            "--duration" => {
+                config.duration = Duration::from_secs(value.parse().map_err(|_| invalid())?)
+            }
+            "--join-rate" => config.join_rate = value.parse().map_err(|_| invalid())?,
+            "--radius" => config.chunk_radius = value.parse().map_err(|_| invalid())?,
+            "--prefix" => config.name_prefix = value,
+            _ => return Err(format!("unknown option {arg}")),
+        }
+    }
+    let server = server.ok_or("missing server address")?;
+    config.server = server
+        .parse()
+        .map_err(|e| format!("invalid server address {server}: {e}"))?;
+    Ok(config)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let config = match parse_args() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    println!(
+        "connecting {} bots to {} at {}/s for {}s",
+        config.bots,
+        config.server,
+        config.join_rate,
+        config.duration.as_secs()
+    );
+    let report = mc_rs_bot::run(&config).await;
+    print!("{report}");
+    if report.is_clean() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
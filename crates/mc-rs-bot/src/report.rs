@@ -0,0 +1,243 @@
+//! Aggregated results of a load test.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::BotStats;
+
+/// Distribution of a set of durations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub count: usize,
+    pub min: Duration,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl Summary {
+    /// Summarize `samples`, or `None` if there are none.
+    pub fn of(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let total: Duration = sorted.iter().sum();
+        Some(Self {
+            count: sorted.len(),
+            min: sorted[0],
+            mean: total / sorted.len() as u32,
+            p50: percentile(&sorted, 50.0),
+            p95: percentile(&sorted, 95.0),
+            p99: percentile(&sorted, 99.0),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty samples.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        write!(
+            f,
+            "min {:.1}ms, mean {:.1}ms, p50 {:.1}ms, p95 {:.1}ms, p99 {:.1}ms, max {:.1}ms ({} samples)",
+            ms(self.min),
+            ms(self.mean),
+            ms(self.p50),
+            ms(self.p95),
+            ms(self.p99),
+            ms(self.max),
+            self.count
+        )
+    }
+}
+
+/// What a load test measured, over all bots.
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    /// Bots asked for.
+    pub requested: usize,
+    /// Bots started before the end of the test.
+    pub started: usize,
+    /// Bots that reached the game and stayed until the end.
+    pub succeeded: usize,
+    /// `(bot name, error)` of every bot that failed.
+    pub failures: Vec<(String, String)>,
+    /// Time from connecting to spawning.
+    pub login: Option<Summary>,
+    /// RakNet ping round trips.
+    pub rtt: Option<Summary>,
+    pub datagrams_sent: u64,
+    pub datagrams_received: u64,
+    /// Server datagrams that never arrived.
+    pub datagrams_missed: u64,
+    /// Bot datagrams the server reported missing.
+    pub datagrams_nacked: u64,
+    pub game_packets_received: u64,
+    pub chunks_received: u64,
+    pub inputs_sent: u64,
+}
+
+impl Report {
+    pub fn new(stats: &[BotStats], requested: usize) -> Self {
+        let mut report = Self {
+            requested,
+            started: stats.len(),
+            ..Self::default()
+        };
+        let mut logins = Vec::new();
+        let mut rtts = Vec::new();
+        for bot in stats {
+            match &bot.error {
+                Some(e) => report.failures.push((bot.name.clone(), e.clone())),
+                None => report.succeeded += 1,
+            }
+            logins.extend(bot.login_time);
+            rtts.extend_from_slice(&bot.raknet.ping_rtts);
+            report.datagrams_sent += bot.raknet.datagrams_sent;
+            report.datagrams_received += bot.raknet.datagrams_received;
+            report.datagrams_missed += bot.raknet.datagrams_missed();
+            report.datagrams_nacked += bot.raknet.datagrams_nacked;
+            report.game_packets_received += bot.game_packets_received;
+            report.chunks_received += bot.chunks_received;
+            report.inputs_sent += bot.inputs_sent;
+        }
+        report.login = Summary::of(&logins);
+        report.rtt = Summary::of(&rtts);
+        report
+    }
+
+    /// Share of the server's datagrams lost on the way to the bots.
+    pub fn inbound_loss(&self) -> f64 {
+        ratio(
+            self.datagrams_missed,
+            self.datagrams_received + self.datagrams_missed,
+        )
+    }
+
+    /// Share of the bots' datagrams lost on the way to the server.
+    pub fn outbound_loss(&self) -> f64 {
+        ratio(self.datagrams_nacked, self.datagrams_sent)
+    }
+
+    /// Whether every requested bot ran the whole test.
+    pub fn is_clean(&self) -> bool {
+        self.succeeded == self.requested
+    }
+}
+
+fn ratio(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "bots: {} requested, {} started, {} ran to the end, {} failed",
+            self.requested,
+            self.started,
+            self.succeeded,
+            self.failures.len()
+        )?;
+        match &self.login {
+            Some(s) => writeln!(f, "login: {s}")?,
+            None => writeln!(f, "login: no bot spawned")?,
+        }
+        match &self.rtt {
+            Some(s) => writeln!(f, "ping: {s}")?,
+            None => writeln!(f, "ping: no answered pings")?,
+        }
+        writeln!(
+            f,
+            "datagrams: {} sent ({} NACKed, {:.2}% loss), {} received ({} missed, {:.2}% loss)",
+            self.datagrams_sent,
+            self.datagrams_nacked,
+            self.outbound_loss() * 100.0,
+            self.datagrams_received,
+            self.datagrams_missed,
+            self.inbound_loss() * 100.0
+        )?;
+        writeln!(
+            f,
+            "game: {} inputs sent, {} packets received ({} chunks)",
+            self.inputs_sent, self.game_packets_received, self.chunks_received
+        )?;
+        for (name, error) in &self.failures {
+            writeln!(f, "  {name}: {error}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_rs_raknet::ClientStats;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn summary_uses_nearest_rank_percentiles() {
+        let samples: Vec<Duration> = (1..=100).rev().map(ms).collect();
+        let s = Summary::of(&samples).unwrap();
+        assert_eq!(s.count, 100);
+        assert_eq!(s.min, ms(1));
+        assert_eq!(s.p50, ms(50));
+        assert_eq!(s.p95, ms(95));
+        assert_eq!(s.p99, ms(99));
+        assert_eq!(s.max, ms(100));
+        assert_eq!(s.mean, Duration::from_micros(50_500));
+
+        let one = Summary::of(&[ms(7)]).unwrap();
+        assert_eq!((one.p50, one.p99), (ms(7), ms(7)));
+        assert!(Summary::of(&[]).is_none());
+    }
+
+    #[test]
+    fn report_adds_up_bots() {
+        let ok = BotStats {
+            name: "Bot0".into(),
+            login_time: Some(ms(200)),
+            inputs_sent: 40,
+            raknet: ClientStats {
+                datagrams_sent: 100,
+                datagrams_received: 90,
+                framesets_received: 90,
+                highest_sequence: Some(99),
+                datagrams_nacked: 2,
+                ping_rtts: vec![ms(10), ms(30)],
+            },
+            ..BotStats::default()
+        };
+        let failed = BotStats {
+            name: "Bot1".into(),
+            error: Some("connect: timed out".into()),
+            ..BotStats::default()
+        };
+        let report = Report::new(&[ok, failed], 3);
+        assert_eq!((report.started, report.succeeded), (2, 1));
+        assert_eq!(report.failures[0].0, "Bot1");
+        assert!(!report.is_clean());
+        assert_eq!(report.datagrams_missed, 10);
+        assert!((report.inbound_loss() - 0.1).abs() < 1e-9);
+        assert!((report.outbound_loss() - 0.02).abs() < 1e-9);
+        assert_eq!(report.rtt.unwrap().mean, ms(20));
+        assert_eq!(report.login.unwrap().count, 1);
+    }
+}
@@ -1,5 +1,6 @@
 //! Command parsing, registry, and built-in commands.
 
+pub mod score;
 pub mod selector;
 
 use std::collections::HashMap;
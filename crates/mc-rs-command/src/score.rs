@@ -0,0 +1,140 @@
+//! Score comparisons for `/execute if|unless score`.
+//!
+//! Parses `matches` ranges (`5`, `1..10`, `..3`, `7..`, `!2..4`) and the
+//! `<`, `<=`, `=`, `>=`, `>` operators comparing two scores.
+
+/// An inclusive score range, optionally negated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreRange {
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+    /// `!range`: matches scores outside the range.
+    pub negated: bool,
+}
+
+impl ScoreRange {
+    /// Parse a range. Returns `None` if it isn't one.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (negated, s) = match s.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let bound = |b: &str| -> Option<Option<i32>> {
+            if b.is_empty() {
+                Some(None)
+            } else {
+                b.parse().ok().map(Some)
+            }
+        };
+        let (min, max) = match s.split_once("..") {
+            Some((min, max)) => {
+                let (min, max) = (bound(min)?, bound(max)?);
+                if min.is_none() && max.is_none() {
+                    return None;
+                }
+                (min, max)
+            }
+            None => {
+                let value = s.parse().ok()?;
+                (Some(value), Some(value))
+            }
+        };
+        Some(Self { min, max, negated })
+    }
+
+    /// Whether `score` is in the range.
+    pub fn contains(&self, score: i32) -> bool {
+        let inside =
+            self.min.is_none_or(|min| score >= min) && self.max.is_none_or(|max| score <= max);
+        inside != self.negated
+    }
+}
+
+/// An operator comparing a target's score with a source's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreOperator {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl ScoreOperator {
+    /// Parse an operator. Returns `None` if it isn't one.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "<" => Some(Self::Less),
+            "<=" => Some(Self::LessOrEqual),
+            "=" => Some(Self::Equal),
+            ">=" => Some(Self::GreaterOrEqual),
+            ">" => Some(Self::Greater),
+            _ => None,
+        }
+    }
+
+    /// Compare `target` with `source`.
+    pub fn compare(self, target: i32, source: i32) -> bool {
+        match self {
+            Self::Less => target < source,
+            Self::LessOrEqual => target <= source,
+            Self::Equal => target == source,
+            Self::GreaterOrEqual => target >= source,
+            Self::Greater => target > source,
+        }
+    }
+}
+
+// ===========================================================================
+// Tests
+// ===========================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ranges() {
+        let exact = ScoreRange::parse("5").unwrap();
+        assert!(exact.contains(5));
+        assert!(!exact.contains(4));
+
+        let between = ScoreRange::parse("1..10").unwrap();
+        assert!(between.contains(1) && between.contains(10));
+        assert!(!between.contains(0) && !between.contains(11));
+
+        let at_most = ScoreRange::parse("..-3").unwrap();
+        assert!(at_most.contains(-3) && at_most.contains(i32::MIN));
+        assert!(!at_most.contains(-2));
+
+        let at_least = ScoreRange::parse("7..").unwrap();
+        assert!(at_least.contains(7) && at_least.contains(i32::MAX));
+        assert!(!at_least.contains(6));
+    }
+
+    #[test]
+    fn parse_negated_range() {
+        let outside = ScoreRange::parse("!2..4").unwrap();
+        assert!(outside.contains(1) && outside.contains(5));
+        assert!(!outside.contains(3));
+    }
+
+    #[test]
+    fn parse_invalid_ranges() {
+        assert_eq!(ScoreRange::parse(""), None);
+        assert_eq!(ScoreRange::parse(".."), None);
+        assert_eq!(ScoreRange::parse("a..3"), None);
+        assert_eq!(ScoreRange::parse("1...3"), None);
+    }
+
+    #[test]
+    fn compare_scores() {
+        assert!(ScoreOperator::parse("<").unwrap().compare(1, 2));
+        assert!(ScoreOperator::parse("<=").unwrap().compare(2, 2));
+        assert!(ScoreOperator::parse("=").unwrap().compare(3, 3));
+        assert!(!ScoreOperator::parse("=").unwrap().compare(3, 4));
+        assert!(ScoreOperator::parse(">=").unwrap().compare(4, 4));
+        assert!(ScoreOperator::parse(">").unwrap().compare(5, 4));
+        assert_eq!(ScoreOperator::parse("=="), None);
+    }
+}
@@ -92,6 +92,31 @@ impl PacketEncryption {
     }
 }
 
+/// One-shot AES-256-CFB8 encryption (no checksum), as used for encrypted
+/// resource pack contents.
+pub fn cfb8_encrypt(key: &[u8; 32], iv: &[u8; 16], data: &[u8]) -> Vec<u8> {
+    let mut cipher = Encryptor::<Aes256>::new(key.into(), iv.into());
+    let mut buf = data.to_vec();
+    for byte in buf.iter_mut() {
+        let mut block = GenericArray::clone_from_slice(std::slice::from_ref(byte));
+        cipher.encrypt_block_mut(&mut block);
+        *byte = block[0];
+    }
+    buf
+}
+
+/// One-shot AES-256-CFB8 decryption, the inverse of [`cfb8_encrypt`].
+pub fn cfb8_decrypt(key: &[u8; 32], iv: &[u8; 16], data: &[u8]) -> Vec<u8> {
+    let mut cipher = Decryptor::<Aes256>::new(key.into(), iv.into());
+    let mut buf = data.to_vec();
+    for byte in buf.iter_mut() {
+        let mut block = GenericArray::clone_from_slice(std::slice::from_ref(byte));
+        cipher.decrypt_block_mut(&mut block);
+        *byte = block[0];
+    }
+    buf
+}
+
 /// Compute the 8-byte SHA-256 checksum for packet integrity.
 ///
 /// ```text
@@ -180,6 +205,14 @@ mod tests {
         assert!(decrypted.is_empty());
     }
 
+    #[test]
+    fn one_shot_cfb8_roundtrip() {
+        let (key, iv) = test_key_iv();
+        let encrypted = cfb8_encrypt(&key, &iv, b"pack contents");
+        assert_ne!(encrypted, b"pack contents");
+        assert_eq!(cfb8_decrypt(&key, &iv, &encrypted), b"pack contents");
+    }
+
     #[test]
     fn too_short_data_rejected() {
         let (key, iv) = test_key_iv();
@@ -1,14 +1,19 @@
-//! Cryptography: ECDH P-384, AES-256-CFB8, key derivation, JWT signing.
+//! Cryptography: ECDH P-384, AES-256-CFB8, key derivation, JWT signing and
+//! login chain verification, HMAC.
 
 pub mod aes;
 pub mod ecdh;
+pub mod hmac;
 pub mod jwt_sign;
 pub mod key_derive;
+pub mod login_chain;
 
-pub use aes::PacketEncryption;
+pub use aes::{cfb8_decrypt, cfb8_encrypt, PacketEncryption};
 pub use ecdh::{parse_client_public_key, ServerKeyPair};
+pub use hmac::{constant_time_eq, hmac_sha256, hmac_sha256_verify};
 pub use jwt_sign::create_handshake_jwt;
 pub use key_derive::derive_key;
+pub use login_chain::{verify_jwt, verify_login_chain, VerifiedIdentity};
 
 use thiserror::Error;
 
@@ -29,4 +34,7 @@ pub enum CryptoError {
 
     #[error("base64 decode error: {0}")]
     Base64(String),
+
+    #[error("invalid login chain: {0}")]
+    InvalidChain(String),
 }
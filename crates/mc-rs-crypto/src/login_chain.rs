@@ -0,0 +1,295 @@
+//! Xbox Live login chain verification (online mode).
+//!
+//! The Login packet carries a chain of ES384 JWTs. The first is self-signed by
+//! the client; each token names, in its `identityPublicKey` claim, the key
+//! that signed the next one. One link must be signed by Mojang's root key:
+//! the token it signs is issued by Xbox Live and holds the player's XUID,
+//! name and identity UUID (`extraData`). The last `identityPublicKey` is the
+//! client's own key, which signs the client data JWT and is used for the
+//! encryption handshake.
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use p384::ecdsa::signature::Verifier;
+use p384::ecdsa::{Signature, VerifyingKey};
+
+use crate::ecdh::parse_client_public_key;
+use crate::CryptoError;
+
+/// Mojang's root public key (base64 SPKI DER) that signs Xbox Live identities.
+pub const MOJANG_ROOT_KEY: &str = "MHYwEAYHKoZIzj0CAQYFK4EEACIDYgAECRXueJeTDqNRRgJi/vlRufByu/2G0i2Ebt6YMar5QX/R0DIIyrJMcUpruK4QveTfJSTp3Shlq4Gk34cD/4GUWwkv0DVuzeuB+tXija7HBxii03NHDbPAD0AKnLr2wdAp";
+
+/// Clock skew tolerated when checking `nbf` / `exp`, in seconds.
+const CLOCK_LEEWAY_SECS: i64 = 60;
+/// Longest chain a vanilla client sends.
+const MAX_CHAIN_LEN: usize = 3;
+
+/// Identity vouched for by a verified chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedIdentity {
+    pub xuid: String,
+    pub display_name: String,
+    pub identity: String,
+    /// The client's key (base64 SPKI DER), which signed the client data JWT.
+    pub identity_public_key: String,
+}
+
+/// Verify a login chain against [`MOJANG_ROOT_KEY`]. `now` is the current Unix time.
+pub fn verify_login_chain(chain: &[String], now: i64) -> Result<VerifiedIdentity, CryptoError> {
+    verify_login_chain_with_root(chain, MOJANG_ROOT_KEY, now)
+}
+
+/// Verify a login chain against the given root key.
+pub fn verify_login_chain_with_root(
+    chain: &[String],
+    root_key: &str,
+    now: i64,
+) -> Result<VerifiedIdentity, CryptoError> {
+    if chain.is_empty() || chain.len() > MAX_CHAIN_LEN {
+        return Err(invalid(format!("chain has {} tokens", chain.len())));
+    }
+
+    let mut expected_key: Option<String> = None;
+    let mut signed_by_root = false;
+    let mut identity = None;
+
+    for (i, token) in chain.iter().enumerate() {
+        let header = decode_part(token, 0)?;
+        let signer = header
+            .get("x5u")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| invalid(format!("token {i} has no x5u")))?
+            .to_owned();
+        // The first token is self-signed; every later one must be signed by
+        // the key named in the previous token.
+        if let Some(expected) = &expected_key {
+            if &signer != expected {
+                return Err(invalid(format!("token {i} signed by an unexpected key")));
+            }
+        }
+
+        let payload = verify_jwt(token, &signer)?;
+        check_validity(&payload, now).map_err(|e| invalid(format!("token {i}: {e}")))?;
+
+        if signed_by_root {
+            // Only a token signed by Mojang may carry the player's identity.
+            if let Some(extra) = payload.get("extraData") {
+                identity = Some(extra.clone());
+            }
+        }
+        if signer == root_key {
+            signed_by_root = true;
+        }
+
+        expected_key = Some(
+            payload
+                .get("identityPublicKey")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| invalid(format!("token {i} has no identityPublicKey")))?
+                .to_owned(),
+        );
+    }
+
+    if !signed_by_root {
+        return Err(invalid("chain is not signed by the Mojang root key".into()));
+    }
+    let extra = identity.ok_or_else(|| invalid("no Xbox Live identity in chain".into()))?;
+    let field = |name: &str| {
+        extra
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(str::to_owned)
+            .ok_or_else(|| invalid(format!("identity has no {name}")))
+    };
+    let xuid = field("XUID")?;
+    if xuid.is_empty() || !xuid.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid(format!("invalid XUID \"{xuid}\"")));
+    }
+
+    Ok(VerifiedIdentity {
+        xuid,
+        display_name: field("displayName")?,
+        identity: field("identity")?,
+        identity_public_key: expected_key.unwrap_or_default(),
+    })
+}
+
+/// Verify a JWT's ES384 signature with `public_key` (base64 SPKI DER) and
+/// return its payload.
+pub fn verify_jwt(token: &str, public_key: &str) -> Result<serde_json::Value, CryptoError> {
+    let (signed, signature) = token
+        .rsplit_once('.')
+        .ok_or_else(|| invalid("malformed JWT".into()))?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature.trim_end_matches('='))
+        .map_err(|e| CryptoError::Base64(e.to_string()))?;
+    let signature =
+        Signature::from_slice(&signature).map_err(|e| invalid(format!("bad signature: {e}")))?;
+    let key = VerifyingKey::from(&parse_client_public_key(public_key)?);
+    key.verify(signed.as_bytes(), &signature)
+        .map_err(|_| invalid("signature verification failed".into()))?;
+    decode_part(token, 1)
+}
+
+/// Check the `nbf` and `exp` claims, if present.
+fn check_validity(payload: &serde_json::Value, now: i64) -> Result<(), String> {
+    if let Some(nbf) = payload.get("nbf").and_then(|v| v.as_i64()) {
+        if now + CLOCK_LEEWAY_SECS < nbf {
+            return Err("not yet valid".into());
+        }
+    }
+    if let Some(exp) = payload.get("exp").and_then(|v| v.as_i64()) {
+        if now - CLOCK_LEEWAY_SECS > exp {
+            return Err("expired".into());
+        }
+    }
+    Ok(())
+}
+
+/// Decode the JSON of a JWT part (0 = header, 1 = payload).
+fn decode_part(token: &str, index: usize) -> Result<serde_json::Value, CryptoError> {
+    let part = token
+        .split('.')
+        .nth(index)
+        .ok_or_else(|| invalid("malformed JWT".into()))?;
+    let bytes = URL_SAFE_NO_PAD
+        .decode(part.trim_end_matches('='))
+        .or_else(|_| STANDARD.decode(part))
+        .map_err(|e| CryptoError::Base64(e.to_string()))?;
+    serde_json::from_slice(&bytes).map_err(|e| invalid(format!("bad JWT JSON: {e}")))
+}
+
+fn invalid(msg: String) -> CryptoError {
+    CryptoError::InvalidChain(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecdh::ServerKeyPair;
+    use p384::ecdsa::signature::Signer;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn sign(signer: &ServerKeyPair, payload: serde_json::Value) -> String {
+        let header = serde_json::json!({"alg": "ES384", "x5u": signer.public_key_base64()});
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header).unwrap()),
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&payload).unwrap())
+        );
+        let signature: Signature = signer.signing_key().sign(message.as_bytes());
+        format!("{message}.{}", URL_SAFE_NO_PAD.encode(signature.to_bytes()))
+    }
+
+    struct Keys {
+        client: ServerKeyPair,
+        root: ServerKeyPair,
+        xbox: ServerKeyPair,
+    }
+
+    fn keys() -> Keys {
+        Keys {
+            client: ServerKeyPair::generate(),
+            root: ServerKeyPair::generate(),
+            xbox: ServerKeyPair::generate(),
+        }
+    }
+
+    /// client (self-signed) → root → xbox identity token.
+    fn chain(k: &Keys, xuid: &str, exp: i64) -> Vec<String> {
+        vec![
+            sign(
+                &k.client,
+                serde_json::json!({"identityPublicKey": k.root.public_key_base64()}),
+            ),
+            sign(
+                &k.root,
+                serde_json::json!({"identityPublicKey": k.xbox.public_key_base64(), "exp": exp}),
+            ),
+            sign(
+                &k.xbox,
+                serde_json::json!({
+                    "identityPublicKey": k.client.public_key_base64(),
+                    "exp": exp,
+                    "extraData": {"XUID": xuid, "displayName": "Steve", "identity": "uuid-1"},
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn valid_chain() {
+        let k = keys();
+        let id = verify_login_chain_with_root(
+            &chain(&k, "2535400000000000", NOW + 3600),
+            &k.root.public_key_base64(),
+            NOW,
+        )
+        .unwrap();
+        assert_eq!(id.xuid, "2535400000000000");
+        assert_eq!(id.display_name, "Steve");
+        assert_eq!(id.identity_public_key, k.client.public_key_base64());
+    }
+
+    #[test]
+    fn self_signed_chain_rejected() {
+        let k = keys();
+        let other_root = ServerKeyPair::generate();
+        let result = verify_login_chain_with_root(
+            &chain(&k, "2535400000000000", NOW + 3600),
+            &other_root.public_key_base64(),
+            NOW,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn expired_chain_rejected() {
+        let k = keys();
+        let result = verify_login_chain_with_root(
+            &chain(&k, "2535400000000000", NOW - 3600),
+            &k.root.public_key_base64(),
+            NOW,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn tampered_identity_rejected() {
+        let k = keys();
+        let mut tokens = chain(&k, "2535400000000000", NOW + 3600);
+        // Re-sign the identity token with the client's own key (spoofed XUID).
+        tokens[2] = sign(
+            &k.client,
+            serde_json::json!({
+                "identityPublicKey": k.client.public_key_base64(),
+                "extraData": {"XUID": "1", "displayName": "Notch", "identity": "uuid-2"},
+            }),
+        );
+        let result = verify_login_chain_with_root(&tokens, &k.root.public_key_base64(), NOW);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn invalid_xuid_rejected() {
+        let k = keys();
+        let result = verify_login_chain_with_root(
+            &chain(&k, "not-a-xuid", NOW + 3600),
+            &k.root.public_key_base64(),
+            NOW,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn verify_jwt_checks_signer() {
+        let k = keys();
+        let token = sign(&k.client, serde_json::json!({"a": 1}));
+        assert_eq!(
+            verify_jwt(&token, &k.client.public_key_base64()).unwrap()["a"],
+            1
+        );
+        assert!(verify_jwt(&token, &k.root.public_key_base64()).is_err());
+    }
+}
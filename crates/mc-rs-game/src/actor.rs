@@ -0,0 +1,378 @@
+//! Saved actors: non-player entities persisted with the chunk they occupy.
+//!
+//! Each actor is one LE NBT compound, stored under its own `actorprefix`
+//! key and listed in the chunk's digest (see `mc_rs_world::storage`).
+
+use mc_rs_nbt::tag::{NbtCompound, NbtRoot, NbtTag};
+use mc_rs_nbt::{read_nbt_le, write_nbt_le};
+use mc_rs_proto::item_stack::ItemStack;
+
+/// Identifier of dropped item actors.
+pub const ITEM_IDENTIFIER: &str = "minecraft:item";
+/// Identifier of experience orb actors.
+pub const XP_ORB_IDENTIFIER: &str = "minecraft:xp_orb";
+/// Identifier of arrow actors.
+pub const ARROW_IDENTIFIER: &str = "minecraft:arrow";
+/// Identifier of thrown trident actors.
+pub const TRIDENT_IDENTIFIER: &str = "minecraft:thrown_trident";
+
+/// A saved actor.
+#[derive(Debug, Clone)]
+pub struct SavedActor {
+    /// Feet-level position.
+    pub position: (f32, f32, f32),
+    /// `(yaw, pitch)` in degrees.
+    pub rotation: (f32, f32),
+    pub motion: (f32, f32, f32),
+    pub data: SavedActorData,
+}
+
+/// Per-kind state of a saved actor.
+#[derive(Debug, Clone)]
+pub enum SavedActorData {
+    Mob {
+        /// Mob type, e.g. `minecraft:zombie`.
+        identifier: String,
+        health: f32,
+        is_baby: bool,
+    },
+    Item {
+        item: ItemStack,
+        age: u32,
+    },
+    XpOrb {
+        value: i32,
+        age: u32,
+    },
+    Projectile {
+        /// Trident loyalty level, or `None` for an arrow.
+        loyalty: Option<i16>,
+        is_stuck: bool,
+        critical: bool,
+        damage: f32,
+        punch_level: i16,
+        flame: bool,
+        age: u32,
+    },
+}
+
+impl SavedActor {
+    /// The actor's entity identifier.
+    pub fn identifier(&self) -> &str {
+        match &self.data {
+            SavedActorData::Mob { identifier, .. } => identifier,
+            SavedActorData::Item { .. } => ITEM_IDENTIFIER,
+            SavedActorData::XpOrb { .. } => XP_ORB_IDENTIFIER,
+            SavedActorData::Projectile { loyalty: None, .. } => ARROW_IDENTIFIER,
+            SavedActorData::Projectile { .. } => TRIDENT_IDENTIFIER,
+        }
+    }
+
+    /// The chunk the actor occupies.
+    pub fn chunk(&self) -> (i32, i32) {
+        (
+            (self.position.0.floor() as i32) >> 4,
+            (self.position.2.floor() as i32) >> 4,
+        )
+    }
+
+    /// Serialize to LE NBT bytes (for LevelDB persistence).
+    pub fn to_le_nbt(&self, unique_id: i64) -> Vec<u8> {
+        let mut c = NbtCompound::new();
+        c.insert(
+            "identifier".to_string(),
+            NbtTag::String(self.identifier().to_string()),
+        );
+        c.insert("UniqueID".to_string(), NbtTag::Long(unique_id));
+        c.insert(
+            "Pos".to_string(),
+            float_list(&[self.position.0, self.position.1, self.position.2]),
+        );
+        c.insert(
+            "Rotation".to_string(),
+            float_list(&[self.rotation.0, self.rotation.1]),
+        );
+        c.insert(
+            "Motion".to_string(),
+            float_list(&[self.motion.0, self.motion.1, self.motion.2]),
+        );
+
+        match &self.data {
+            SavedActorData::Mob {
+                health, is_baby, ..
+            } => {
+                c.insert("Health".to_string(), NbtTag::Float(*health));
+                c.insert("IsBaby".to_string(), NbtTag::Byte(*is_baby as i8));
+            }
+            SavedActorData::Item { item, age } => {
+                let mut ic = NbtCompound::new();
+                ic.insert("id".to_string(), NbtTag::Short(item.runtime_id as i16));
+                ic.insert("Count".to_string(), NbtTag::Byte(item.count as i8));
+                ic.insert("Damage".to_string(), NbtTag::Short(item.metadata as i16));
+                ic.insert("Block".to_string(), NbtTag::Int(item.block_runtime_id));
+                if !item.nbt_data.is_empty() {
+                    ic.insert(
+                        "tag".to_string(),
+                        NbtTag::ByteArray(item.nbt_data.iter().map(|&b| b as i8).collect()),
+                    );
+                }
+                c.insert("Item".to_string(), NbtTag::Compound(ic));
+                c.insert("Age".to_string(), NbtTag::Int(*age as i32));
+            }
+            SavedActorData::XpOrb { value, age } => {
+                c.insert("experience value".to_string(), NbtTag::Int(*value));
+                c.insert("Age".to_string(), NbtTag::Int(*age as i32));
+            }
+            SavedActorData::Projectile {
+                loyalty,
+                is_stuck,
+                critical,
+                damage,
+                punch_level,
+                flame,
+                age,
+            } => {
+                if let Some(loyalty) = loyalty {
+                    c.insert("Loyalty".to_string(), NbtTag::Short(*loyalty));
+                }
+                c.insert("isStuck".to_string(), NbtTag::Byte(*is_stuck as i8));
+                c.insert("isCritical".to_string(), NbtTag::Byte(*critical as i8));
+                c.insert("Damage".to_string(), NbtTag::Float(*damage));
+                c.insert("Punch".to_string(), NbtTag::Short(*punch_level));
+                c.insert("Flame".to_string(), NbtTag::Byte(*flame as i8));
+                c.insert("Age".to_string(), NbtTag::Int(*age as i32));
+            }
+        }
+
+        let mut buf = Vec::new();
+        write_nbt_le(&mut buf, &NbtRoot::new("", c));
+        buf
+    }
+
+    /// Parse a saved actor from LE NBT bytes.
+    ///
+    /// Returns `None` if the NBT is malformed or not a supported actor.
+    pub fn from_le_nbt(data: &[u8]) -> Option<Self> {
+        let root = read_nbt_le(&mut &data[..]).ok()?;
+        let c = &root.compound;
+        let identifier = c.get("identifier")?.as_string()?;
+        let position = match read_floats(c, "Pos")?[..] {
+            [x, y, z] => (x, y, z),
+            _ => return None,
+        };
+        let rotation = match read_floats(c, "Rotation").as_deref() {
+            Some(&[yaw, pitch]) => (yaw, pitch),
+            _ => (0.0, 0.0),
+        };
+        let motion = match read_floats(c, "Motion").as_deref() {
+            Some(&[x, y, z]) => (x, y, z),
+            _ => (0.0, 0.0, 0.0),
+        };
+        let flag = |name: &str| c.get(name).and_then(|t| t.as_byte()).unwrap_or(0) != 0;
+        let age = c.get("Age").and_then(|t| t.as_int()).unwrap_or(0).max(0) as u32;
+
+        let data = match identifier {
+            ITEM_IDENTIFIER => {
+                let ic = c.get("Item")?.as_compound()?;
+                let mut item = ItemStack::new_with_meta(
+                    ic.get("id").and_then(|t| t.as_short())? as i32,
+                    ic.get("Count").and_then(|t| t.as_byte())? as u8 as u16,
+                    ic.get("Damage").and_then(|t| t.as_short()).unwrap_or(0) as u16,
+                    0,
+                );
+                item.block_runtime_id = ic.get("Block").and_then(|t| t.as_int()).unwrap_or(0);
+                if let Some(tag) = ic.get("tag").and_then(|t| t.as_byte_array()) {
+                    item.nbt_data = tag.iter().map(|&b| b as u8).collect();
+                }
+                if item.is_empty() {
+                    return None;
+                }
+                SavedActorData::Item { item, age }
+            }
+            XP_ORB_IDENTIFIER => SavedActorData::XpOrb {
+                value: c.get("experience value")?.as_int()?,
+                age,
+            },
+            ARROW_IDENTIFIER | TRIDENT_IDENTIFIER => SavedActorData::Projectile {
+                loyalty: match identifier {
+                    TRIDENT_IDENTIFIER => {
+                        Some(c.get("Loyalty").and_then(|t| t.as_short()).unwrap_or(0))
+                    }
+                    _ => None,
+                },
+                is_stuck: flag("isStuck"),
+                critical: flag("isCritical"),
+                damage: c.get("Damage").and_then(|t| t.as_float()).unwrap_or(0.0),
+                punch_level: c.get("Punch").and_then(|t| t.as_short()).unwrap_or(0),
+                flame: flag("Flame"),
+                age,
+            },
+            _ => SavedActorData::Mob {
+                identifier: identifier.to_string(),
+                health: c.get("Health")?.as_float()?,
+                is_baby: flag("IsBaby"),
+            },
+        };
+
+        Some(Self {
+            position,
+            rotation,
+            motion,
+            data,
+        })
+    }
+}
+
+fn float_list(values: &[f32]) -> NbtTag {
+    NbtTag::List(values.iter().map(|&v| NbtTag::Float(v)).collect())
+}
+
+fn read_floats(c: &NbtCompound, name: &str) -> Option<Vec<f32>> {
+    c.get(name)?
+        .as_list()?
+        .iter()
+        .map(|t| t.as_float())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(actor: &SavedActor) -> SavedActor {
+        SavedActor::from_le_nbt(&actor.to_le_nbt(7)).unwrap()
+    }
+
+    #[test]
+    fn mob_roundtrip() {
+        let actor = SavedActor {
+            position: (-20.5, 64.0, 3.25),
+            rotation: (90.0, -10.0),
+            motion: (0.0, -0.08, 0.0),
+            data: SavedActorData::Mob {
+                identifier: "minecraft:zombie".into(),
+                health: 13.5,
+                is_baby: true,
+            },
+        };
+        let parsed = roundtrip(&actor);
+        assert_eq!(parsed.identifier(), "minecraft:zombie");
+        assert_eq!(parsed.position, (-20.5, 64.0, 3.25));
+        assert_eq!(parsed.rotation, (90.0, -10.0));
+        assert_eq!(parsed.motion, (0.0, -0.08, 0.0));
+        assert_eq!(parsed.chunk(), (-2, 0));
+        match parsed.data {
+            SavedActorData::Mob {
+                health, is_baby, ..
+            } => {
+                assert_eq!(health, 13.5);
+                assert!(is_baby);
+            }
+            _ => panic!("Expected Mob"),
+        }
+    }
+
+    #[test]
+    fn item_and_xp_orb_roundtrip() {
+        let mut item = ItemStack::new_with_meta(5, 12, 3, 99);
+        item.block_runtime_id = 1234;
+        item.nbt_data = vec![0x0A, 0x00, 0x00];
+        let actor = SavedActor {
+            position: (1.0, 2.0, 3.0),
+            rotation: (0.0, 0.0),
+            motion: (0.0, 0.0, 0.0),
+            data: SavedActorData::Item { item, age: 300 },
+        };
+        match roundtrip(&actor).data {
+            SavedActorData::Item { item, age } => {
+                assert_eq!((item.runtime_id, item.count, item.metadata), (5, 12, 3));
+                assert_eq!(item.block_runtime_id, 1234);
+                assert_eq!(item.nbt_data, vec![0x0A, 0x00, 0x00]);
+                // Network IDs are per session
+                assert_eq!(item.stack_network_id, 0);
+                assert_eq!(age, 300);
+            }
+            _ => panic!("Expected Item"),
+        }
+
+        let orb = SavedActor {
+            data: SavedActorData::XpOrb { value: 17, age: 5 },
+            ..actor
+        };
+        assert_eq!(orb.identifier(), XP_ORB_IDENTIFIER);
+        assert!(matches!(
+            roundtrip(&orb).data,
+            SavedActorData::XpOrb { value: 17, age: 5 }
+        ));
+    }
+
+    #[test]
+    fn projectile_roundtrip() {
+        let trident = SavedActor {
+            position: (0.5, 70.0, 0.5),
+            rotation: (45.0, 30.0),
+            motion: (0.0, 0.0, 0.0),
+            data: SavedActorData::Projectile {
+                loyalty: Some(3),
+                is_stuck: true,
+                critical: false,
+                damage: 8.0,
+                punch_level: 0,
+                flame: false,
+                age: 40,
+            },
+        };
+        assert_eq!(trident.identifier(), TRIDENT_IDENTIFIER);
+        match roundtrip(&trident).data {
+            SavedActorData::Projectile {
+                loyalty,
+                is_stuck,
+                damage,
+                ..
+            } => {
+                assert_eq!(loyalty, Some(3));
+                assert!(is_stuck);
+                assert_eq!(damage, 8.0);
+            }
+            _ => panic!("Expected Projectile"),
+        }
+
+        let arrow = SavedActor {
+            data: SavedActorData::Projectile {
+                loyalty: None,
+                is_stuck: false,
+                critical: true,
+                damage: 2.0,
+                punch_level: 1,
+                flame: true,
+                age: 3,
+            },
+            ..trident
+        };
+        assert_eq!(arrow.identifier(), ARROW_IDENTIFIER);
+        assert!(matches!(
+            roundtrip(&arrow).data,
+            SavedActorData::Projectile {
+                loyalty: None,
+                critical: true,
+                punch_level: 1,
+                flame: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_actors() {
+        assert!(SavedActor::from_le_nbt(&[]).is_none());
+        assert!(SavedActor::from_le_nbt(&[0x0A, 0x00, 0x00, 0x00]).is_none());
+
+        // An item actor without an item
+        let mut c = NbtCompound::new();
+        c.insert("identifier".into(), NbtTag::String(ITEM_IDENTIFIER.into()));
+        c.insert("Pos".into(), float_list(&[0.0, 0.0, 0.0]));
+        let mut buf = Vec::new();
+        write_nbt_le(&mut buf, &NbtRoot::new("", c));
+        assert!(SavedActor::from_le_nbt(&buf).is_none());
+    }
+}
@@ -0,0 +1,71 @@
+//! Adventure mode: the blocks an item may be placed on or may break, from
+//! its CanPlaceOn and CanDestroy lists.
+
+use mc_rs_nbt::{NbtCompound, NbtTag};
+use mc_rs_proto::item_stack::ItemStack;
+
+/// Whether a block is in a CanPlaceOn/CanDestroy list. Entries may leave out
+/// the `minecraft:` namespace.
+fn list_contains(list: &[String], block_name: &str) -> bool {
+    fn short(name: &str) -> &str {
+        name.strip_prefix("minecraft:").unwrap_or(name)
+    }
+    let block = short(block_name);
+    list.iter().any(|entry| short(entry) == block)
+}
+
+/// Whether an adventure mode player holding `item` may break `block_name`.
+pub fn can_destroy(item: &ItemStack, block_name: &str) -> bool {
+    list_contains(&item.can_destroy, block_name)
+}
+
+/// Whether an adventure mode player holding `item` may place it against
+/// `block_name`.
+pub fn can_place_on(item: &ItemStack, block_name: &str) -> bool {
+    list_contains(&item.can_place_on, block_name)
+}
+
+/// Take the `CanPlaceOn` and `CanDestroy` string lists out of an item's NBT,
+/// since items carry them outside the NBT on the wire.
+pub fn take_block_lists(nbt: &mut NbtCompound) -> (Vec<String>, Vec<String>) {
+    let mut take = |key: &str| -> Vec<String> {
+        match nbt.remove(key) {
+            Some(NbtTag::List(entries)) => entries
+                .iter()
+                .filter_map(|e| e.as_string().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+    (take("CanPlaceOn"), take("CanDestroy"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_match_with_or_without_namespace() {
+        let mut item = ItemStack::new(1, 1);
+        item.can_destroy = vec!["minecraft:stone".into(), "dirt".into()];
+        assert!(can_destroy(&item, "minecraft:stone"));
+        assert!(can_destroy(&item, "minecraft:dirt"));
+        assert!(!can_destroy(&item, "minecraft:grass_block"));
+        assert!(!can_place_on(&item, "minecraft:stone"));
+    }
+
+    #[test]
+    fn block_lists_leave_nbt() {
+        let mut nbt = NbtCompound::new();
+        nbt.insert(
+            "CanPlaceOn".into(),
+            NbtTag::List(vec![NbtTag::String("minecraft:stone".into())]),
+        );
+        nbt.insert("display".into(), NbtTag::Compound(NbtCompound::new()));
+        let (can_place_on, can_destroy) = take_block_lists(&mut nbt);
+        assert_eq!(can_place_on, vec!["minecraft:stone".to_string()]);
+        assert!(can_destroy.is_empty());
+        assert!(!nbt.contains_key("CanPlaceOn"));
+        assert!(nbt.contains_key("display"));
+    }
+}
@@ -0,0 +1,323 @@
+//! Data-driven mobs: applies behavior pack component groups and events at runtime.
+//!
+//! A mob whose type has an [`EntityBehavior`] carries a [`ComponentGroups`]
+//! component. Whenever an event changes its active groups the effective
+//! components are re-resolved: the behavior list is rebuilt from the
+//! `minecraft:behavior.*` goals and stats, baby state, timers and
+//! transformations are updated.
+
+use std::sync::Arc;
+
+use bevy_ecs::prelude::*;
+use mc_rs_behavior_pack::entity_components::{EntityBehavior, Transformation};
+
+use crate::components::*;
+use crate::game_world::{GameEvent, OutgoingEvents, TickCounter};
+
+use super::brain::BehaviorList;
+use super::mob_behaviors;
+
+/// Event fired when a mob is spawned normally.
+pub const SPAWNED_EVENT: &str = "minecraft:entity_spawned";
+/// Event fired when a mob is born from breeding.
+pub const BORN_EVENT: &str = "minecraft:entity_born";
+
+/// Attach `definition` to a freshly spawned mob and fire `spawn_event`.
+/// Returns whether the mob is a baby afterwards.
+pub fn attach(
+    world: &mut World,
+    entity: Entity,
+    definition: Arc<EntityBehavior>,
+    spawn_event: &str,
+) -> bool {
+    world.entity_mut(entity).insert(ComponentGroups {
+        definition,
+        active: Vec::new(),
+        timer: None,
+        timer_at: None,
+        transformation: None,
+        transform_at: None,
+    });
+    if !trigger(world, entity, spawn_event) {
+        refresh(world, entity);
+    }
+    world.get::<Baby>(entity).is_some()
+}
+
+/// Fire `event` on a data-driven mob. Returns `false` if the mob has no such event.
+///
+/// Commands queued by the event are emitted as [`GameEvent::EntityCommands`].
+pub fn trigger(world: &mut World, entity: Entity, event: &str) -> bool {
+    let Some(mut groups) = world.get_mut::<ComponentGroups>(entity) else {
+        return false;
+    };
+    let definition = groups.definition.clone();
+    let mut active = std::mem::take(&mut groups.active);
+    let commands = definition.apply_event(event, &mut active, &mut rand::thread_rng());
+    groups.active = active;
+    let Some(commands) = commands else {
+        return false;
+    };
+    refresh(world, entity);
+
+    if !commands.is_empty() {
+        if let (Some(eid), Some(mob_type)) =
+            (world.get::<EntityId>(entity), world.get::<MobType>(entity))
+        {
+            let event = GameEvent::EntityCommands {
+                runtime_id: eid.runtime_id,
+                mob_type: mob_type.0.clone(),
+                commands,
+            };
+            world.resource_mut::<OutgoingEvents>().events.push(event);
+        }
+    }
+    true
+}
+
+/// Re-resolve a mob's components after its active groups changed.
+fn refresh(world: &mut World, entity: Entity) {
+    let tick = world.resource::<TickCounter>().0;
+    let resolved = {
+        let Some(mut groups) = world.get_mut::<ComponentGroups>(entity) else {
+            return;
+        };
+        let resolved = groups.definition.resolve(&groups.active);
+        if resolved.timer != groups.timer {
+            groups.timer_at = resolved.timer.as_ref().map(|t| tick + t.ticks);
+            groups.timer = resolved.timer.clone();
+        }
+        if resolved.transformation != groups.transformation {
+            groups.transform_at = resolved
+                .transformation
+                .as_ref()
+                .map(|t| tick + t.delay_ticks);
+            groups.transformation = resolved.transformation.clone();
+        }
+        resolved
+    };
+
+    let behaviors = if resolved.goals.is_empty() {
+        let mob_type = world
+            .get::<MobType>(entity)
+            .map(|m| m.0.clone())
+            .unwrap_or_default();
+        mob_behaviors::create_behaviors(&mob_type)
+    } else {
+        mob_behaviors::create_behaviors_from_goals(&resolved.goals)
+    };
+
+    let mut mob = world.entity_mut(entity);
+    mob.insert(BehaviorList::new(behaviors));
+    if let Some(speed) = resolved.movement_speed {
+        mob.insert(MovementSpeed(speed));
+    }
+    if let Some(damage) = resolved.attack_damage {
+        mob.insert(AttackDamage(damage));
+    }
+    if !resolved.is_baby {
+        mob.remove::<Baby>();
+    } else if !mob.contains::<Baby>() {
+        mob.insert(Baby(tick));
+    }
+}
+
+/// Fire due timer events and return the mobs whose transformation is due.
+pub fn system_component_timers(world: &mut World) -> Vec<(Entity, Transformation)> {
+    let tick = world.resource::<TickCounter>().0;
+
+    let mut due_events = Vec::new();
+    let mut transformations = Vec::new();
+    {
+        let mut q =
+            world.query_filtered::<(Entity, &mut ComponentGroups), (With<Mob>, Without<Dead>)>();
+        for (entity, mut groups) in q.iter_mut(world) {
+            if groups.timer_at.is_some_and(|at| tick >= at) {
+                let timer = groups.timer.clone().expect("scheduled timer");
+                groups.timer_at = timer.looping.then(|| tick + timer.ticks.max(1));
+                due_events.push((entity, timer.event));
+            }
+            if groups.transform_at.is_some_and(|at| tick >= at) {
+                groups.transform_at = None;
+                if let Some(t) = groups.transformation.clone() {
+                    transformations.push((entity, t));
+                }
+            }
+        }
+    }
+
+    for (entity, event) in due_events {
+        trigger(world, entity, &event);
+    }
+    transformations
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use mc_rs_behavior_pack::entity_components::{
+        ComponentSet, EntityEvent, GoalComponent, TimerComponent,
+    };
+
+    use super::super::behavior::BehaviorType;
+    use super::*;
+    use crate::game_world::GameWorld;
+    use crate::mob_registry::{MobCategory, MobDefinition};
+
+    fn goal(name: &str, priority: u32) -> GoalComponent {
+        GoalComponent {
+            name: name.into(),
+            priority,
+            speed_multiplier: 1.0,
+            range: None,
+        }
+    }
+
+    fn add_event(groups: &[&str]) -> EntityEvent {
+        EntityEvent {
+            add: groups.iter().map(|g| g.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// A custom mob that spawns as a baby, grows up on a timer and turns
+    /// into a zombie when it becomes angry.
+    fn world_with_custom_mob() -> GameWorld {
+        let mut gw = GameWorld::new(1);
+        gw.mob_registry.register_mob(MobDefinition {
+            type_id: "test:critter".into(),
+            display_name: "Critter".into(),
+            category: MobCategory::Passive,
+            max_health: 10.0,
+            attack_damage: 0.0,
+            movement_speed: 0.25,
+            bb_width: 0.6,
+            bb_height: 0.9,
+        });
+
+        let mut component_groups = HashMap::new();
+        component_groups.insert(
+            "baby".to_string(),
+            ComponentSet {
+                is_baby: true,
+                timer: Some(TimerComponent {
+                    ticks: 3,
+                    looping: false,
+                    event: "grow_up".into(),
+                }),
+                ..Default::default()
+            },
+        );
+        component_groups.insert(
+            "adult".to_string(),
+            ComponentSet {
+                movement_speed: Some(0.3),
+                ..Default::default()
+            },
+        );
+        component_groups.insert(
+            "angry".to_string(),
+            ComponentSet {
+                goals: vec![
+                    goal("melee_attack", 1),
+                    goal("nearest_attackable_target", 2),
+                ],
+                transformation: Some(Transformation {
+                    into: "minecraft:zombie".into(),
+                    spawn_event: None,
+                    delay_ticks: 2,
+                }),
+                ..Default::default()
+            },
+        );
+
+        let mut events = HashMap::new();
+        events.insert(SPAWNED_EVENT.to_string(), add_event(&["baby"]));
+        events.insert(
+            "grow_up".to_string(),
+            EntityEvent {
+                remove: vec!["baby".into()],
+                add: vec!["adult".into()],
+                ..Default::default()
+            },
+        );
+        events.insert(
+            "become_angry".to_string(),
+            EntityEvent {
+                add: vec!["angry".into()],
+                queue_command: vec!["scriptevent test:angry grr".into()],
+                ..Default::default()
+            },
+        );
+
+        gw.mob_registry.register_behavior(
+            "test:critter",
+            EntityBehavior {
+                components: ComponentSet {
+                    goals: vec![goal("random_stroll", 6), goal("look_at_player", 7)],
+                    ..Default::default()
+                },
+                component_groups,
+                events,
+            },
+        );
+        gw
+    }
+
+    #[test]
+    fn spawn_event_and_timer() {
+        let mut gw = world_with_custom_mob();
+        let (_, rid) = gw.spawn_mob("test:critter", 0.0, 4.0, 0.0).unwrap();
+        let entity = gw.find_mob_entity(rid).unwrap();
+        assert!(gw.world.get::<Baby>(entity).is_some());
+        assert_eq!(
+            gw.world.get::<ComponentGroups>(entity).unwrap().active,
+            vec!["baby".to_string()]
+        );
+
+        for _ in 0..4 {
+            gw.tick();
+        }
+        let entity = gw.find_mob_entity(rid).unwrap();
+        assert!(gw.world.get::<Baby>(entity).is_none());
+        assert_eq!(gw.world.get::<MovementSpeed>(entity).unwrap().0, 0.3);
+        assert_eq!(
+            gw.world.get::<ComponentGroups>(entity).unwrap().active,
+            vec!["adult".to_string()]
+        );
+    }
+
+    #[test]
+    fn event_rebuilds_goals_and_transforms() {
+        let mut gw = world_with_custom_mob();
+        let (_, rid) = gw.spawn_mob("test:critter", 0.0, 4.0, 0.0).unwrap();
+        assert!(!gw.trigger_entity_event(rid, "no_such_event"));
+
+        let entity = gw.find_mob_entity(rid).unwrap();
+        let targets = |gw: &GameWorld| {
+            gw.world
+                .get::<BehaviorList>(entity)
+                .unwrap()
+                .count_by_type(BehaviorType::TargetSelector)
+        };
+        assert_eq!(targets(&gw), 0);
+        gw.drain_events();
+        assert!(gw.trigger_entity_event(rid, "become_angry"));
+        assert_eq!(targets(&gw), 1);
+        assert!(gw.drain_events().iter().any(|e| matches!(
+            e,
+            GameEvent::EntityCommands { runtime_id, mob_type, commands }
+                if *runtime_id == rid && mob_type == "test:critter" && commands == &["scriptevent test:angry grr"]
+        )));
+
+        gw.drain_events();
+        for _ in 0..3 {
+            gw.tick();
+        }
+        assert!(gw.find_mob_entity(rid).is_none());
+        let mut q = gw.world.query_filtered::<&MobType, With<Mob>>();
+        let types: Vec<String> = q.iter(&gw.world).map(|t| t.0.clone()).collect();
+        assert_eq!(types, vec!["minecraft:zombie".to_string()]);
+    }
+}
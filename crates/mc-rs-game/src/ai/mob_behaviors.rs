@@ -1,12 +1,17 @@
 //! Per-mob-type behavior lists.
 
-use super::behavior::Behavior;
+use mc_rs_behavior_pack::entity_components::GoalComponent;
+
+use super::behavior::{Behavior, BehaviorContext, BehaviorOutput, BehaviorType};
 use super::behaviors::*;
 
 /// Create the behavior list for a given mob type.
 pub fn create_behaviors(type_id: &str) -> Vec<Box<dyn Behavior>> {
     match type_id {
-        "minecraft:zombie" | "minecraft:skeleton" => vec![
+        "minecraft:zombie"
+        | "minecraft:skeleton"
+        | "minecraft:pillager"
+        | "minecraft:vindicator" => vec![
             Box::new(Float::new()),
             Box::new(HurtByTarget::new()),
             Box::new(NearestAttackableTarget::new(16.0)),
@@ -30,10 +35,78 @@ pub fn create_behaviors(type_id: &str) -> Vec<Box<dyn Behavior>> {
     }
 }
 
+/// Create the behavior list from behavior pack goals (`minecraft:behavior.*`),
+/// keeping each goal's priority. Goals with no server-side implementation are
+/// skipped.
+pub fn create_behaviors_from_goals(goals: &[GoalComponent]) -> Vec<Box<dyn Behavior>> {
+    let mut behaviors: Vec<Box<dyn Behavior>> = goals
+        .iter()
+        .filter_map(|goal| {
+            let inner: Box<dyn Behavior> = match goal.name.as_str() {
+                "float" => Box::new(Float::new()),
+                "hurt_by_target" => Box::new(HurtByTarget::new()),
+                "nearest_attackable_target" => {
+                    Box::new(NearestAttackableTarget::new(goal.range.unwrap_or(16.0)))
+                }
+                "melee_attack" => Box::new(MeleeAttack::new(20)),
+                "panic" => Box::new(Panic::new()),
+                "tempt" => Box::new(TemptGoal::new()),
+                "breed" => Box::new(BreedGoal::new()),
+                "random_stroll" => Box::new(RandomStroll::new()),
+                "look_at_player" => Box::new(LookAtPlayer::new(goal.range.unwrap_or(8.0))),
+                _ => return None,
+            };
+            Some(Box::new(Prioritized {
+                inner,
+                priority: goal.priority,
+            }) as Box<dyn Behavior>)
+        })
+        .collect();
+    // Target selectors are evaluated in list order, so keep it sorted.
+    behaviors.sort_by_key(|b| b.priority());
+    behaviors
+}
+
+/// A behavior whose priority is set by a behavior pack goal.
+#[derive(Debug)]
+struct Prioritized {
+    inner: Box<dyn Behavior>,
+    priority: u32,
+}
+
+impl Behavior for Prioritized {
+    fn behavior_type(&self) -> BehaviorType {
+        self.inner.behavior_type()
+    }
+
+    fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    fn can_start(&self, ctx: &BehaviorContext) -> bool {
+        self.inner.can_start(ctx)
+    }
+
+    fn should_continue(&self, ctx: &BehaviorContext) -> bool {
+        self.inner.should_continue(ctx)
+    }
+
+    fn start(&mut self, ctx: &BehaviorContext) -> BehaviorOutput {
+        self.inner.start(ctx)
+    }
+
+    fn tick(&mut self, ctx: &BehaviorContext) -> BehaviorOutput {
+        self.inner.tick(ctx)
+    }
+
+    fn stop(&mut self) {
+        self.inner.stop()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ai::behavior::BehaviorType;
 
     #[test]
     fn zombie_has_target_selector() {
@@ -58,6 +131,25 @@ mod tests {
         assert_eq!(behaviors.len(), 6);
     }
 
+    #[test]
+    fn goals_keep_pack_priorities() {
+        let goal = |name: &str, priority| GoalComponent {
+            name: name.into(),
+            priority,
+            speed_multiplier: 1.0,
+            range: None,
+        };
+        let behaviors = create_behaviors_from_goals(&[
+            goal("random_stroll", 1),
+            goal("melee_attack", 3),
+            goal("celebrate", 0), // not implemented: skipped
+        ]);
+        assert_eq!(behaviors.len(), 2);
+        assert_eq!(behaviors[0].priority(), 1);
+        assert_eq!(behaviors[1].priority(), 3);
+        assert_eq!(behaviors[1].behavior_type(), BehaviorType::Movement);
+    }
+
     #[test]
     fn unknown_gets_default_behaviors() {
         let behaviors = create_behaviors("minecraft:unknown");
@@ -1,8 +1,9 @@
-//! Mob AI: behavior system, pathfinding, and natural spawning.
+//! Mob AI: behavior system, data-driven definitions, pathfinding, and natural spawning.
 
 pub mod behavior;
 pub mod behaviors;
 pub mod brain;
+pub mod definitions;
 pub mod mob_behaviors;
 pub mod pathfinding;
 pub mod spatial;
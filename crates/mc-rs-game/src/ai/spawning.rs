@@ -6,9 +6,10 @@ use rand::Rng;
 use crate::components::*;
 use crate::game_world::{OutgoingEvents, TickCounter};
 use crate::mob_registry::{MobCategory, MobRegistry};
+use crate::sunlight::{self, HELMET_CHANCE, HELMET_DURABILITY};
 
 use super::brain::BehaviorList;
-use super::mob_behaviors;
+use super::{definitions, mob_behaviors};
 
 /// Configuration for natural mob spawning.
 pub struct SpawnConfig {
@@ -26,6 +27,87 @@ pub struct SpawnConfig {
     pub despawn_interval: u64,
     /// Distance beyond which mobs are despawned.
     pub despawn_distance: f32,
+    /// Whether hostile mobs spawn (off on Peaceful).
+    pub spawn_hostiles: bool,
+    /// Which spots hostile mobs may spawn in.
+    pub hostile_rule: HostileSpawnRule,
+    /// Brightest light hostile mobs spawn in under [`HostileSpawnRule::Light`].
+    pub max_hostile_light: u8,
+    /// How far below the surface hostile mobs spawn in daylight under
+    /// [`HostileSpawnRule::Height`].
+    pub cave_depth: i32,
+}
+
+/// Which spots hostile mobs may spawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostileSpawnRule {
+    /// Spots no brighter than `max_hostile_light`. Where the terrain has no
+    /// light data, this falls back to [`HostileSpawnRule::Height`].
+    Light,
+    /// Any spot at night; in daylight, only spots at least `cave_depth`
+    /// blocks below the surface.
+    Height,
+}
+
+impl HostileSpawnRule {
+    /// Look up a rule by its config name (`light` or `height`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "light" => Some(Self::Light),
+            "height" => Some(Self::Height),
+            _ => None,
+        }
+    }
+}
+
+/// What natural spawning needs to know about the terrain around players.
+pub trait SpawnTerrain {
+    /// Feet Y of the spots of a column a mob can stand in (solid block
+    /// below, air at its feet and head), lowest first. Empty if the column
+    /// isn't loaded.
+    fn spawn_spots(&self, x: i32, z: i32) -> Vec<i32>;
+
+    /// Light level of a block, the brighter of block and sky light, or
+    /// `None` without light data.
+    fn light_level(&self, x: i32, y: i32, z: i32) -> Option<u8>;
+
+    /// Whether it is dark enough outside for hostile mobs: night or a
+    /// thunderstorm.
+    fn is_night(&self) -> bool;
+}
+
+/// Terrain for a game world on its own: every column is open ground at
+/// y = 4 in an endless night.
+pub struct FlatSpawnTerrain;
+
+impl SpawnTerrain for FlatSpawnTerrain {
+    fn spawn_spots(&self, _x: i32, _z: i32) -> Vec<i32> {
+        vec![4]
+    }
+
+    fn light_level(&self, _x: i32, _y: i32, _z: i32) -> Option<u8> {
+        None
+    }
+
+    fn is_night(&self) -> bool {
+        true
+    }
+}
+
+/// Whether a hostile mob may spawn at feet height `y` of column `(x, z)`,
+/// whose highest spot is `surface`.
+pub fn hostile_may_spawn(
+    config: &SpawnConfig,
+    terrain: &dyn SpawnTerrain,
+    (x, y, z): (i32, i32, i32),
+    surface: i32,
+) -> bool {
+    if config.hostile_rule == HostileSpawnRule::Light {
+        if let Some(light) = terrain.light_level(x, y, z) {
+            return light <= config.max_hostile_light;
+        }
+    }
+    terrain.is_night() || y <= surface - config.cave_depth
 }
 
 impl Default for SpawnConfig {
@@ -38,12 +120,22 @@ impl Default for SpawnConfig {
             spawn_interval: 100,   // every 5 seconds
             despawn_interval: 200, // every 10 seconds
             despawn_distance: 128.0,
+            spawn_hostiles: true,
+            hostile_rule: HostileSpawnRule::Light,
+            max_hostile_light: 7,
+            cave_depth: 8,
         }
     }
 }
 
-/// Periodically spawn mobs near players.
-pub fn system_natural_spawn(world: &mut World, mob_registry: &MobRegistry, config: &SpawnConfig) {
+/// Periodically spawn mobs near players: hostile mobs in a random spot of a
+/// column that passes the hostile spawn rule, passive mobs on the surface.
+pub fn system_natural_spawn(
+    world: &mut World,
+    mob_registry: &MobRegistry,
+    config: &SpawnConfig,
+    terrain: &dyn SpawnTerrain,
+) {
     let tick = world.resource::<TickCounter>().0;
     if !tick.is_multiple_of(config.spawn_interval) || tick == 0 {
         return;
@@ -65,7 +157,7 @@ pub fn system_natural_spawn(world: &mut World, mob_registry: &MobRegistry, confi
     let mut rng = rand::thread_rng();
 
     // Spawn hostiles
-    if hostile_count < config.hostile_cap {
+    if config.spawn_hostiles && hostile_count < config.hostile_cap {
         let hostile_types: Vec<&str> = mob_registry
             .all()
             .iter()
@@ -82,8 +174,21 @@ pub fn system_natural_spawn(world: &mut World, mob_registry: &MobRegistry, confi
                 config.min_distance,
                 config.max_distance,
             ) {
-                let type_id = hostile_types[rng.gen_range(0..hostile_types.len())];
-                spawn_mob_internal(world, mob_registry, type_id, x, 4.0, z);
+                let (bx, bz) = (x.floor() as i32, z.floor() as i32);
+                let spots = terrain.spawn_spots(bx, bz);
+                if let Some(&surface) = spots.last() {
+                    let y = spots[rng.gen_range(0..spots.len())];
+                    if hostile_may_spawn(config, terrain, (bx, y, bz), surface) {
+                        let type_id = hostile_types[rng.gen_range(0..hostile_types.len())];
+                        let entity =
+                            spawn_mob_internal(world, mob_registry, type_id, x, y as f32, z);
+                        if let Some(entity) = entity {
+                            if sunlight::is_undead(type_id) && rng.gen_bool(HELMET_CHANCE) {
+                                world.entity_mut(entity).insert(Helmet(HELMET_DURABILITY));
+                            }
+                        }
+                    }
+                }
             }
         }
     }
@@ -106,8 +211,14 @@ pub fn system_natural_spawn(world: &mut World, mob_registry: &MobRegistry, confi
                 config.min_distance,
                 config.max_distance,
             ) {
-                let type_id = passive_types[rng.gen_range(0..passive_types.len())];
-                spawn_mob_internal(world, mob_registry, type_id, x, 4.0, z);
+                let surface = terrain
+                    .spawn_spots(x.floor() as i32, z.floor() as i32)
+                    .last()
+                    .copied();
+                if let Some(y) = surface {
+                    let type_id = passive_types[rng.gen_range(0..passive_types.len())];
+                    spawn_mob_internal(world, mob_registry, type_id, x, y as f32, z);
+                }
             }
         }
     }
@@ -177,6 +288,7 @@ fn count_mobs_by_category(world: &mut World, mob_registry: &MobRegistry) -> (u32
             match def.category {
                 MobCategory::Hostile => hostile += 1,
                 MobCategory::Passive => passive += 1,
+                MobCategory::Raider => {}
             }
         }
     }
@@ -206,11 +318,8 @@ fn spawn_mob_internal(
     x: f32,
     y: f32,
     z: f32,
-) {
-    let def = match mob_registry.get(type_id) {
-        Some(d) => d.clone(),
-        None => return,
-    };
+) -> Option<Entity> {
+    let def = mob_registry.get(type_id)?.clone();
 
     let entity_id = world
         .resource::<crate::game_world::EntityIdAllocator>()
@@ -257,6 +366,11 @@ fn spawn_mob_internal(
         .0
         .insert(runtime_id, entity);
 
+    let is_baby = match mob_registry.behavior(type_id) {
+        Some(behavior) => definitions::attach(world, entity, behavior, definitions::SPAWNED_EVENT),
+        None => false,
+    };
+
     world
         .resource_mut::<OutgoingEvents>()
         .events
@@ -269,8 +383,9 @@ fn spawn_mob_internal(
             max_health: def.max_health,
             bb_width: def.bb_width,
             bb_height: def.bb_height,
-            is_baby: false,
+            is_baby,
         });
+    Some(entity)
 }
 
 #[cfg(test)]
@@ -278,6 +393,94 @@ mod tests {
     use super::*;
     use crate::game_world::GameWorld;
 
+    /// A column with a cave spot at y = 20 and the surface at y = 64, in
+    /// daylight, with the given light at every block.
+    struct DayTerrain(Option<u8>);
+
+    impl SpawnTerrain for DayTerrain {
+        fn spawn_spots(&self, _x: i32, _z: i32) -> Vec<i32> {
+            vec![20, 64]
+        }
+
+        fn light_level(&self, _x: i32, _y: i32, _z: i32) -> Option<u8> {
+            self.0
+        }
+
+        fn is_night(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn height_rule_keeps_hostiles_underground_by_day() {
+        let config = SpawnConfig {
+            hostile_rule: HostileSpawnRule::Height,
+            ..Default::default()
+        };
+        let day = DayTerrain(Some(0));
+        assert!(!hostile_may_spawn(&config, &day, (0, 64, 0), 64));
+        assert!(!hostile_may_spawn(&config, &day, (0, 60, 0), 64));
+        assert!(hostile_may_spawn(&config, &day, (0, 20, 0), 64));
+        assert!(hostile_may_spawn(&config, &FlatSpawnTerrain, (0, 4, 0), 4));
+    }
+
+    #[test]
+    fn light_rule_uses_light_when_known() {
+        let config = SpawnConfig::default();
+        assert!(hostile_may_spawn(
+            &config,
+            &DayTerrain(Some(7)),
+            (0, 64, 0),
+            64
+        ));
+        assert!(!hostile_may_spawn(
+            &config,
+            &DayTerrain(Some(8)),
+            (0, 20, 0),
+            64
+        ));
+        // No light data: the height rule decides
+        assert!(!hostile_may_spawn(
+            &config,
+            &DayTerrain(None),
+            (0, 64, 0),
+            64
+        ));
+        assert!(hostile_may_spawn(
+            &config,
+            &DayTerrain(None),
+            (0, 20, 0),
+            64
+        ));
+        assert_eq!(
+            HostileSpawnRule::from_name("height"),
+            Some(HostileSpawnRule::Height)
+        );
+        assert_eq!(HostileSpawnRule::from_name("dark"), None);
+    }
+
+    #[test]
+    fn mobs_spawn_on_spots() {
+        let mut gw = GameWorld::new(1);
+        let addr: std::net::SocketAddr = "127.0.0.1:19132".parse().unwrap();
+        gw.spawn_player(50, 50, (0.0, 64.0, 0.0), addr);
+        let config = SpawnConfig {
+            spawn_interval: 1,
+            hostile_rule: HostileSpawnRule::Height,
+            ..Default::default()
+        };
+        for _ in 0..40 {
+            gw.world.resource_mut::<TickCounter>().0 += 1;
+            system_natural_spawn(&mut gw.world, &gw.mob_registry, &config, &DayTerrain(None));
+        }
+        for mob in gw.all_mobs() {
+            let hostile =
+                gw.mob_registry.get(&mob.mob_type).unwrap().category == MobCategory::Hostile;
+            // Hostiles in the cave, passives on the surface
+            assert_eq!(mob.position.1, if hostile { 20.0 } else { 64.0 });
+        }
+    }
+
     #[test]
     fn spawn_respects_caps() {
         let mut gw = GameWorld::new(1);
@@ -295,7 +498,7 @@ mod tests {
         // Tick enough times for spawns
         for _ in 0..20 {
             gw.world.resource_mut::<TickCounter>().0 += 1;
-            system_natural_spawn(&mut gw.world, &gw.mob_registry, &config);
+            system_natural_spawn(&mut gw.world, &gw.mob_registry, &config, &FlatSpawnTerrain);
         }
 
         let mobs = gw.all_mobs();
@@ -303,6 +506,27 @@ mod tests {
         assert!(mobs.len() <= 4, "Too many mobs: {}", mobs.len());
     }
 
+    #[test]
+    fn no_hostiles_when_disabled() {
+        let mut gw = GameWorld::new(1);
+        let addr: std::net::SocketAddr = "127.0.0.1:19132".parse().unwrap();
+        gw.spawn_player(50, 50, (0.0, 4.0, 0.0), addr);
+
+        let config = SpawnConfig {
+            spawn_interval: 1,
+            spawn_hostiles: false,
+            ..Default::default()
+        };
+        for _ in 0..20 {
+            gw.world.resource_mut::<TickCounter>().0 += 1;
+            system_natural_spawn(&mut gw.world, &gw.mob_registry, &config, &FlatSpawnTerrain);
+        }
+
+        let (hostile, passive) = count_mobs_by_category(&mut gw.world, &gw.mob_registry);
+        assert_eq!(hostile, 0);
+        assert!(passive > 0);
+    }
+
     #[test]
     fn no_spawn_without_players() {
         let mut gw = GameWorld::new(1);
@@ -314,7 +538,7 @@ mod tests {
         };
 
         gw.world.resource_mut::<TickCounter>().0 = 10;
-        system_natural_spawn(&mut gw.world, &gw.mob_registry, &config);
+        system_natural_spawn(&mut gw.world, &gw.mob_registry, &config, &FlatSpawnTerrain);
 
         let mobs = gw.all_mobs();
         assert!(mobs.is_empty());
@@ -358,7 +582,7 @@ mod tests {
         };
 
         gw.world.resource_mut::<TickCounter>().0 = 1;
-        system_natural_spawn(&mut gw.world, &gw.mob_registry, &config);
+        system_natural_spawn(&mut gw.world, &gw.mob_registry, &config, &FlatSpawnTerrain);
 
         let events = gw.drain_events();
         let spawn_count = events
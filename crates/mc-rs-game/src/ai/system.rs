@@ -37,11 +37,13 @@ struct MobSnapshot {
 
 /// Runs AI behavior evaluation for all alive mobs with a BehaviorList.
 pub fn system_ai_tick(world: &mut World) {
-    // Step 1: Snapshot all player positions (including held item for tempt)
+    // Step 1: Snapshot all player positions (including held item for tempt).
+    // Players mobs ignore are left out, so mobs drop them as targets.
     let players: Vec<PlayerSnapshot> = {
-        let mut q = world
-            .query_filtered::<(Entity, &EntityId, &Position, Option<&HeldItemName>), With<Player>>(
-            );
+        let mut q = world.query_filtered::<
+            (Entity, &EntityId, &Position, Option<&HeldItemName>),
+            (With<Player>, Without<IgnoredByMobs>),
+        >();
         q.iter(world)
             .map(|(e, eid, pos, held)| PlayerSnapshot {
                 entity: e,
@@ -69,7 +71,12 @@ pub fn system_ai_tick(world: &mut World) {
             &MobType,
             Option<&InLove>,
             Option<&Baby>,
-        ), (With<Mob>, With<BehaviorList>, Without<Dead>)>();
+        ), (
+            With<Mob>,
+            With<BehaviorList>,
+            Without<Dead>,
+            Without<Dormant>,
+        )>();
         q.iter(world)
             .map(
                 |(entity, pos, speed, dmg, on_ground, ldt, target, mob_type, in_love, baby)| {
@@ -478,4 +485,23 @@ mod tests {
             pos
         );
     }
+
+    #[test]
+    fn zombie_ignores_hidden_player() {
+        let mut gw = GameWorld::new(1);
+        let addr: std::net::SocketAddr = "127.0.0.1:19132".parse().unwrap();
+        gw.spawn_player(50, 50, (10.0, 4.0, 10.0), addr);
+        let (_, zrid) = gw.spawn_mob("minecraft:zombie", 5.0, 4.0, 5.0).unwrap();
+        let zombie = gw.find_mob_entity(zrid).unwrap();
+
+        gw.tick();
+        assert!(gw.world.get::<AiTarget>(zombie).is_some());
+
+        // Once hidden, the zombie loses its target
+        gw.set_player_ignored_by_mobs(50, true);
+        for _ in 0..5 {
+            gw.tick();
+        }
+        assert!(gw.world.get::<AiTarget>(zombie).is_none());
+    }
 }
@@ -2,6 +2,7 @@
 //! (durability merge + enchantment fusion).
 
 use mc_rs_nbt::tag::{NbtCompound, NbtRoot, NbtTag};
+use mc_rs_nbt::{read_nbt_network_with_limits, NbtLimits};
 use mc_rs_proto::item_stack::ItemStack;
 
 use crate::combat::{build_enchantment_nbt, parse_enchantments, Enchantment};
@@ -160,6 +161,57 @@ pub fn repair_material(item_name: &str) -> Option<&'static str> {
     }
 }
 
+/// Maximum durability of a tool, weapon or piece of armor (Bedrock
+/// values), or `None` for items that don't wear out.
+pub fn max_durability(item_name: &str) -> Option<u16> {
+    let name = item_name.strip_prefix("minecraft:").unwrap_or(item_name);
+    let material = name.split('_').next()?;
+    let tool = ["_sword", "_pickaxe", "_axe", "_shovel", "_hoe"]
+        .iter()
+        .any(|t| name.ends_with(t));
+    if tool {
+        return match material {
+            "wooden" => Some(60),
+            "stone" => Some(132),
+            "iron" => Some(251),
+            "golden" => Some(33),
+            "diamond" => Some(1562),
+            "netherite" => Some(2032),
+            _ => None,
+        };
+    }
+    let armor = ["_helmet", "_chestplate", "_leggings", "_boots"]
+        .iter()
+        .position(|p| name.ends_with(p));
+    if let Some(piece) = armor {
+        let durabilities: [u16; 4] = match material {
+            "leather" => [56, 81, 76, 66],
+            "chainmail" | "iron" => [166, 241, 226, 196],
+            "golden" => [78, 113, 106, 92],
+            "diamond" => [364, 529, 496, 430],
+            "netherite" => [408, 593, 556, 482],
+            "turtle" => [276, 0, 0, 0],
+            _ => return None,
+        };
+        return Some(durabilities[piece]).filter(|&d| d > 0);
+    }
+    match name {
+        "bow" => Some(385),
+        "crossbow" => Some(465),
+        "trident" => Some(251),
+        "shield" => Some(337),
+        "fishing_rod" => Some(384),
+        "flint_and_steel" => Some(65),
+        "shears" => Some(238),
+        "elytra" => Some(433),
+        "carrot_on_a_stick" => Some(26),
+        "warped_fungus_on_a_stick" => Some(100),
+        "brush" => Some(65),
+        "mace" => Some(501),
+        _ => None,
+    }
+}
+
 /// Set or update the display name in item NBT data (network format).
 ///
 /// Adds/modifies the `display.Name` tag inside the root compound.
@@ -167,7 +219,7 @@ pub fn set_display_name(nbt_data: &[u8], name: &str) -> Vec<u8> {
     let mut root_compound = if nbt_data.is_empty() {
         NbtCompound::new()
     } else {
-        match mc_rs_nbt::read_nbt_network(&mut &nbt_data[..]) {
+        match read_nbt_network_with_limits(&mut &nbt_data[..], &NbtLimits::untrusted()) {
             Ok(root) => root.compound,
             Err(_) => NbtCompound::new(),
         }
@@ -232,7 +284,7 @@ fn build_anvil_nbt(enchantments: &[Enchantment], display_name: Option<&str>) ->
     // Start with enchantment NBT if present
     let mut root_compound = if !enchantments.is_empty() {
         let data = build_enchantment_nbt(enchantments);
-        match mc_rs_nbt::read_nbt_network(&mut &data[..]) {
+        match read_nbt_network_with_limits(&mut &data[..], &NbtLimits::untrusted()) {
             Ok(root) => root.compound,
             Err(_) => NbtCompound::new(),
         }
@@ -397,6 +449,18 @@ mod tests {
         assert_eq!(merged[0].id, SHARPNESS);
     }
 
+    #[test]
+    fn max_durability_lookup() {
+        assert_eq!(max_durability("minecraft:wooden_pickaxe"), Some(60));
+        assert_eq!(max_durability("minecraft:diamond_sword"), Some(1562));
+        assert_eq!(max_durability("minecraft:iron_chestplate"), Some(241));
+        assert_eq!(max_durability("minecraft:chainmail_boots"), Some(196));
+        assert_eq!(max_durability("minecraft:turtle_helmet"), Some(276));
+        assert_eq!(max_durability("minecraft:elytra"), Some(433));
+        assert_eq!(max_durability("minecraft:iron_ingot"), None);
+        assert_eq!(max_durability("minecraft:enchanted_book"), None);
+    }
+
     #[test]
     fn repair_material_lookup() {
         assert_eq!(
@@ -0,0 +1,117 @@
+//! Armor equipping and elytra flight rules.
+
+/// Armor slot index for the helmet.
+pub const SLOT_HEAD: usize = 0;
+/// Armor slot index for the chestplate (and elytra).
+pub const SLOT_CHEST: usize = 1;
+/// Armor slot index for the leggings.
+pub const SLOT_LEGS: usize = 2;
+/// Armor slot index for the boots.
+pub const SLOT_FEET: usize = 3;
+
+/// Damage value at which an elytra is broken (432 durability).
+pub const ELYTRA_MAX_DAMAGE: u16 = 432;
+
+/// Ticks of gliding per point of elytra durability lost.
+pub const GLIDE_DURABILITY_INTERVAL: u32 = 20;
+
+/// Speed (blocks/tick) a firework rocket propels a gliding player.
+pub const FIREWORK_BOOST_SPEED: f32 = 1.5;
+
+/// Downward speed (blocks/tick) above which a glider accumulates fall distance.
+pub const GLIDE_DIVE_THRESHOLD: f32 = -0.5;
+
+/// Armor slot an item is worn in, or `None` if it cannot be equipped.
+pub fn armor_slot(item_name: &str) -> Option<usize> {
+    let name = item_name.strip_prefix("minecraft:").unwrap_or(item_name);
+    if name == "elytra" {
+        return Some(SLOT_CHEST);
+    }
+    if name == "turtle_helmet" || name == "carved_pumpkin" {
+        return Some(SLOT_HEAD);
+    }
+    let (material, piece) = name.rsplit_once('_')?;
+    if !matches!(
+        material,
+        "leather" | "golden" | "chainmail" | "iron" | "diamond" | "netherite"
+    ) {
+        return None;
+    }
+    match piece {
+        "helmet" => Some(SLOT_HEAD),
+        "chestplate" => Some(SLOT_CHEST),
+        "leggings" => Some(SLOT_LEGS),
+        "boots" => Some(SLOT_FEET),
+        _ => None,
+    }
+}
+
+/// Returns `true` if the item is an elytra.
+pub fn is_elytra(item_name: &str) -> bool {
+    item_name.strip_prefix("minecraft:").unwrap_or(item_name) == "elytra"
+}
+
+/// Whether an elytra with this damage value can still be used to glide.
+///
+/// An elytra never fully breaks: at 1 durability left it stops working.
+pub fn elytra_usable(damage: u16) -> bool {
+    damage < ELYTRA_MAX_DAMAGE - 1
+}
+
+/// Fall distance to keep while gliding after a tick with vertical motion `delta_y`.
+///
+/// Gliding only builds up fall distance during a steep dive; otherwise it is
+/// capped at 1 block so a normal landing deals no damage.
+pub fn glide_fall_distance(fall_distance: f32, delta_y: f32) -> f32 {
+    if delta_y > GLIDE_DIVE_THRESHOLD {
+        fall_distance.min(1.0)
+    } else {
+        fall_distance
+    }
+}
+
+/// Velocity given to a gliding player by a firework rocket, along the look direction.
+pub fn firework_boost(pitch: f32, yaw: f32) -> (f32, f32, f32) {
+    crate::projectile::launch_velocity(pitch, yaw, FIREWORK_BOOST_SPEED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn armor_slots_by_piece() {
+        assert_eq!(armor_slot("minecraft:diamond_helmet"), Some(SLOT_HEAD));
+        assert_eq!(armor_slot("minecraft:iron_chestplate"), Some(SLOT_CHEST));
+        assert_eq!(armor_slot("minecraft:golden_leggings"), Some(SLOT_LEGS));
+        assert_eq!(armor_slot("minecraft:netherite_boots"), Some(SLOT_FEET));
+        assert_eq!(armor_slot("minecraft:elytra"), Some(SLOT_CHEST));
+        assert_eq!(armor_slot("minecraft:turtle_helmet"), Some(SLOT_HEAD));
+        assert_eq!(armor_slot("minecraft:diamond_sword"), None);
+        assert_eq!(armor_slot("minecraft:stone"), None);
+    }
+
+    #[test]
+    fn elytra_stops_working_at_last_point() {
+        assert!(elytra_usable(0));
+        assert!(elytra_usable(ELYTRA_MAX_DAMAGE - 2));
+        assert!(!elytra_usable(ELYTRA_MAX_DAMAGE - 1));
+    }
+
+    #[test]
+    fn gliding_caps_fall_distance_unless_diving() {
+        assert_eq!(glide_fall_distance(12.0, -0.2), 1.0);
+        assert_eq!(glide_fall_distance(0.5, 0.1), 0.5);
+        assert_eq!(glide_fall_distance(12.0, -0.8), 12.0);
+    }
+
+    #[test]
+    fn firework_boost_follows_look_direction() {
+        let (x, y, z) = firework_boost(0.0, 0.0);
+        assert!(x.abs() < 0.001);
+        assert!(y.abs() < 0.001);
+        assert!((z - FIREWORK_BOOST_SPEED).abs() < 0.001);
+        let (_, up, _) = firework_boost(-90.0, 0.0);
+        assert!((up - FIREWORK_BOOST_SPEED).abs() < 0.001);
+    }
+}
@@ -0,0 +1,181 @@
+//! Beacon logic — pyramid detection, power validation and effect range.
+
+use mc_rs_proto::packets::mob_effect::effect_id;
+
+/// Maximum number of pyramid layers a beacon counts.
+pub const MAX_PYRAMID_LEVELS: u8 = 4;
+
+/// Ticks between two beacon effect pulses (4 seconds).
+pub const PULSE_INTERVAL_TICKS: u64 = 80;
+
+/// Blocks that can form a beacon pyramid.
+pub const BASE_BLOCKS: &[&str] = &[
+    "minecraft:iron_block",
+    "minecraft:gold_block",
+    "minecraft:diamond_block",
+    "minecraft:emerald_block",
+    "minecraft:netherite_block",
+];
+
+/// Items accepted as beacon payment.
+pub const PAYMENT_ITEMS: &[&str] = &[
+    "minecraft:iron_ingot",
+    "minecraft:gold_ingot",
+    "minecraft:diamond",
+    "minecraft:emerald",
+    "minecraft:netherite_ingot",
+];
+
+/// Count the complete pyramid layers below a beacon at `(x, y, z)`.
+///
+/// Layer `n` (1-based) sits at `y - n` and is a `(2n+1)²` square that must be
+/// made entirely of base blocks. Counting stops at the first incomplete layer.
+pub fn pyramid_levels(x: i32, y: i32, z: i32, is_base: impl Fn(i32, i32, i32) -> bool) -> u8 {
+    let mut levels = 0;
+    for level in 1..=MAX_PYRAMID_LEVELS as i32 {
+        let ly = y - level;
+        for bx in x - level..=x + level {
+            for bz in z - level..=z + level {
+                if !is_base(bx, ly, bz) {
+                    return levels;
+                }
+            }
+        }
+        levels += 1;
+    }
+    levels
+}
+
+/// Minimum pyramid levels needed to select `effect` as the primary power.
+fn primary_tier(effect: i32) -> Option<u8> {
+    match effect {
+        effect_id::SPEED | effect_id::HASTE => Some(1),
+        effect_id::RESISTANCE | effect_id::JUMP_BOOST => Some(2),
+        effect_id::STRENGTH => Some(3),
+        _ => None,
+    }
+}
+
+/// Whether `primary` may be selected on a beacon with `levels` pyramid layers.
+pub fn primary_allowed(primary: i32, levels: u8) -> bool {
+    primary_tier(primary).is_some_and(|tier| levels >= tier)
+}
+
+/// Whether `secondary` may be selected alongside `primary`.
+///
+/// The secondary power needs a full 4-layer pyramid and is either
+/// Regeneration or the primary effect again (upgrading it to level II).
+pub fn secondary_allowed(primary: i32, secondary: i32, levels: u8) -> bool {
+    if secondary == 0 {
+        return true;
+    }
+    levels >= MAX_PYRAMID_LEVELS
+        && primary_allowed(primary, levels)
+        && (secondary == effect_id::REGENERATION || secondary == primary)
+}
+
+/// Horizontal radius (blocks) of a beacon's effect area.
+pub fn effect_range(levels: u8) -> f32 {
+    10.0 + 10.0 * levels as f32
+}
+
+/// Duration (ticks) of each effect applied by a beacon pulse.
+pub fn effect_duration_ticks(levels: u8) -> i32 {
+    (9 + 2 * levels as i32) * 20
+}
+
+/// Effects a beacon applies each pulse as `(effect_id, amplifier)` pairs.
+///
+/// Returns nothing for an inactive beacon or an invalid power selection.
+pub fn active_effects(primary: i32, secondary: i32, levels: u8) -> Vec<(i32, i32)> {
+    if levels == 0 || !primary_allowed(primary, levels) {
+        return Vec::new();
+    }
+    if !secondary_allowed(primary, secondary, levels) {
+        return vec![(primary, 0)];
+    }
+    if secondary == primary {
+        vec![(primary, 1)]
+    } else if secondary != 0 {
+        vec![(primary, 0), (secondary, 0)]
+    } else {
+        vec![(primary, 0)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pyramid(levels: i32) -> impl Fn(i32, i32, i32) -> bool {
+        move |x, y, z| {
+            let layer = 64 - y;
+            layer >= 1 && layer <= levels && x.abs() <= layer && z.abs() <= layer
+        }
+    }
+
+    #[test]
+    fn pyramid_levels_counts_complete_layers() {
+        assert_eq!(pyramid_levels(0, 64, 0, pyramid(0)), 0);
+        assert_eq!(pyramid_levels(0, 64, 0, pyramid(1)), 1);
+        assert_eq!(pyramid_levels(0, 64, 0, pyramid(3)), 3);
+        assert_eq!(pyramid_levels(0, 64, 0, pyramid(6)), 4);
+    }
+
+    #[test]
+    fn pyramid_levels_stops_at_hole() {
+        let holed = |x: i32, y: i32, z: i32| pyramid(4)(x, y, z) && !(y == 62 && x == 2 && z == 2);
+        assert_eq!(pyramid_levels(0, 64, 0, holed), 1);
+    }
+
+    #[test]
+    fn primary_tiers() {
+        assert!(primary_allowed(effect_id::SPEED, 1));
+        assert!(!primary_allowed(effect_id::RESISTANCE, 1));
+        assert!(primary_allowed(effect_id::JUMP_BOOST, 2));
+        assert!(!primary_allowed(effect_id::STRENGTH, 2));
+        assert!(primary_allowed(effect_id::STRENGTH, 3));
+        assert!(!primary_allowed(effect_id::POISON, 4));
+    }
+
+    #[test]
+    fn secondary_requires_full_pyramid() {
+        assert!(!secondary_allowed(
+            effect_id::SPEED,
+            effect_id::REGENERATION,
+            3
+        ));
+        assert!(secondary_allowed(
+            effect_id::SPEED,
+            effect_id::REGENERATION,
+            4
+        ));
+        assert!(secondary_allowed(effect_id::SPEED, effect_id::SPEED, 4));
+        assert!(!secondary_allowed(effect_id::SPEED, effect_id::HASTE, 4));
+    }
+
+    #[test]
+    fn range_and_duration_scale_with_levels() {
+        assert_eq!(effect_range(1), 20.0);
+        assert_eq!(effect_range(4), 50.0);
+        assert_eq!(effect_duration_ticks(1), 220);
+        assert_eq!(effect_duration_ticks(4), 340);
+    }
+
+    #[test]
+    fn active_effects_combinations() {
+        assert!(active_effects(effect_id::SPEED, 0, 0).is_empty());
+        assert_eq!(
+            active_effects(effect_id::SPEED, 0, 1),
+            vec![(effect_id::SPEED, 0)]
+        );
+        assert_eq!(
+            active_effects(effect_id::SPEED, effect_id::SPEED, 4),
+            vec![(effect_id::SPEED, 1)]
+        );
+        assert_eq!(
+            active_effects(effect_id::HASTE, effect_id::REGENERATION, 4),
+            vec![(effect_id::HASTE, 0), (effect_id::REGENERATION, 0)]
+        );
+    }
+}
@@ -5,9 +5,16 @@
 //! and NBT (de)serialization for both network and disk formats.
 
 use mc_rs_nbt::tag::{NbtCompound, NbtRoot, NbtTag};
-use mc_rs_nbt::{read_nbt_le, read_nbt_network, write_nbt_le, write_nbt_network};
+use mc_rs_nbt::{
+    read_nbt_le, read_nbt_network_with_limits, write_nbt_le, write_nbt_network, NbtLimits,
+};
 use mc_rs_proto::item_stack::ItemStack;
 
+use crate::command_block::CommandBlock;
+use crate::loom::{self, BannerPattern};
+use crate::music::NOTE_PITCHES;
+use crate::sign::SignText;
+use crate::skull::SkullOwner;
 use crate::smelting::FurnaceType;
 
 /// Block entity data stored per-block.
@@ -15,9 +22,10 @@ use crate::smelting::FurnaceType;
 #[allow(clippy::large_enum_variant)]
 pub enum BlockEntityData {
     Sign {
-        front_text: String,
-        back_text: String,
-        is_editable: bool,
+        front: SignText,
+        back: SignText,
+        /// Waxed signs can no longer be edited or dyed.
+        is_waxed: bool,
     },
     Chest {
         items: Vec<ItemStack>,
@@ -53,6 +61,47 @@ pub enum BlockEntityData {
         input: ItemStack,
         material: ItemStack,
     },
+    CartographyTable {
+        input: ItemStack,
+        additional: ItemStack,
+    },
+    Beacon {
+        /// Selected primary effect ID (0 = none).
+        primary: i32,
+        /// Selected secondary effect ID (0 = none).
+        secondary: i32,
+        /// Payment slot (transient, not persisted).
+        payment: ItemStack,
+    },
+    CommandBlock(CommandBlock),
+    Skull {
+        /// Index into `HEAD_BLOCKS` (3 = player head).
+        skull_type: i8,
+        /// Facing of a head on the floor, in degrees.
+        rotation: f32,
+        /// Whose head it is, for player heads.
+        owner: Option<SkullOwner>,
+    },
+    /// Note block.
+    Music {
+        /// Pitch, 0-24.
+        note: u8,
+        /// Whether it was powered last tick, to play on a rising edge.
+        powered: bool,
+    },
+    Jukebox {
+        record: ItemStack,
+        /// Ticks until the record ends, 0 when it isn't playing
+        /// (transient, not persisted).
+        ticks_left: u32,
+    },
+    Banner {
+        /// Base color, black (0) to white (15) like banner item metadata.
+        base: i32,
+        patterns: Vec<BannerPattern>,
+        /// 1 for an ominous banner.
+        banner_type: i32,
+    },
 }
 
 /// Number of slots in a single chest.
@@ -76,13 +125,16 @@ pub const LOOM_SLOTS: usize = 3;
 /// Number of slots in an anvil.
 pub const ANVIL_SLOTS: usize = 2;
 
+/// Number of slots in a cartography table.
+pub const CARTOGRAPHY_TABLE_SLOTS: usize = 2;
+
 impl BlockEntityData {
-    /// Create a new empty sign (editable, no text).
+    /// Create a new empty sign (unwaxed, no text).
     pub fn new_sign() -> Self {
         BlockEntityData::Sign {
-            front_text: String::new(),
-            back_text: String::new(),
-            is_editable: true,
+            front: SignText::default(),
+            back: SignText::default(),
+            is_waxed: false,
         }
     }
 
@@ -133,6 +185,62 @@ impl BlockEntityData {
         }
     }
 
+    /// Create a new empty cartography table (2 slots: map + additional item).
+    pub fn new_cartography_table() -> Self {
+        BlockEntityData::CartographyTable {
+            input: ItemStack::empty(),
+            additional: ItemStack::empty(),
+        }
+    }
+
+    /// Create a new beacon with no powers selected.
+    pub fn new_beacon() -> Self {
+        BlockEntityData::Beacon {
+            primary: 0,
+            secondary: 0,
+            payment: ItemStack::empty(),
+        }
+    }
+
+    /// Create a new command block with no command.
+    pub fn new_command_block() -> Self {
+        BlockEntityData::CommandBlock(CommandBlock::default())
+    }
+
+    /// Create a new head of the given type.
+    pub fn new_skull(skull_type: i8, rotation: f32, owner: Option<SkullOwner>) -> Self {
+        BlockEntityData::Skull {
+            skull_type,
+            rotation,
+            owner,
+        }
+    }
+
+    /// Create a new note block on the lowest pitch.
+    pub fn new_note_block() -> Self {
+        BlockEntityData::Music {
+            note: 0,
+            powered: false,
+        }
+    }
+
+    /// Create a new empty jukebox.
+    pub fn new_jukebox() -> Self {
+        BlockEntityData::Jukebox {
+            record: ItemStack::empty(),
+            ticks_left: 0,
+        }
+    }
+
+    /// Create a banner from the metadata and NBT of a banner item.
+    pub fn banner_from_item(item: &ItemStack) -> Self {
+        BlockEntityData::Banner {
+            base: item.metadata as i32,
+            patterns: loom::item_patterns(&item.nbt_data),
+            banner_type: loom::item_banner_type(&item.nbt_data),
+        }
+    }
+
     /// Create a new empty furnace of the given type.
     pub fn new_furnace(furnace_type: FurnaceType) -> Self {
         BlockEntityData::Furnace {
@@ -175,29 +283,34 @@ impl BlockEntityData {
         Self::from_nbt_compound(&root.compound)
     }
 
+    /// Apply NBT fields (e.g. the SNBT of `/setblock`) over `base`, or over
+    /// nothing when the block has no entity yet (`patch` must then set `id`).
+    ///
+    /// Returns `None` if the result is not a recognized block entity.
+    pub fn with_nbt(
+        base: Option<&Self>,
+        (x, y, z): (i32, i32, i32),
+        patch: &NbtCompound,
+    ) -> Option<Self> {
+        let mut c = base
+            .map(|b| b.build_nbt_compound(x, y, z))
+            .unwrap_or_default();
+        c.extend(patch.iter().map(|(k, v)| (k.clone(), v.clone())));
+        c.insert("x".to_string(), NbtTag::Int(x));
+        c.insert("y".to_string(), NbtTag::Int(y));
+        c.insert("z".to_string(), NbtTag::Int(z));
+        Self::from_nbt_compound(&c).map(|(_, data)| data)
+    }
+
     /// Parse sign text from network NBT bytes (from client BlockActorData).
     ///
     /// Returns `(front_text, back_text)` if valid sign data.
     pub fn sign_from_network_nbt(data: &[u8]) -> Option<(String, String)> {
-        let root = read_nbt_network(&mut &data[..]).ok()?;
+        let root = read_nbt_network_with_limits(&mut &data[..], &NbtLimits::untrusted()).ok()?;
         let c = &root.compound;
 
-        let front = c
-            .get("FrontText")
-            .and_then(|t| t.as_compound())
-            .and_then(|c| c.get("Text"))
-            .and_then(|t| t.as_string())
-            .unwrap_or_default()
-            .to_string();
-
-        let back = c
-            .get("BackText")
-            .and_then(|t| t.as_compound())
-            .and_then(|c| c.get("Text"))
-            .and_then(|t| t.as_string())
-            .unwrap_or_default()
-            .to_string();
-
+        let front = sign_text_from_nbt(c.get("FrontText")).text;
+        let back = sign_text_from_nbt(c.get("BackText")).text;
         Some((front, back))
     }
 
@@ -209,24 +322,14 @@ impl BlockEntityData {
 
         match self {
             BlockEntityData::Sign {
-                front_text,
-                back_text,
-                is_editable,
+                front,
+                back,
+                is_waxed,
             } => {
                 c.insert("id".to_string(), NbtTag::String("Sign".to_string()));
-
-                let mut front = NbtCompound::new();
-                front.insert("Text".to_string(), NbtTag::String(front_text.clone()));
-                c.insert("FrontText".to_string(), NbtTag::Compound(front));
-
-                let mut back = NbtCompound::new();
-                back.insert("Text".to_string(), NbtTag::String(back_text.clone()));
-                c.insert("BackText".to_string(), NbtTag::Compound(back));
-
-                c.insert(
-                    "IsEditable".to_string(),
-                    NbtTag::Byte(if *is_editable { 1 } else { 0 }),
-                );
+                c.insert("FrontText".to_string(), sign_text_to_nbt(front));
+                c.insert("BackText".to_string(), sign_text_to_nbt(back));
+                c.insert("IsWaxed".to_string(), NbtTag::Byte(*is_waxed as i8));
             }
             BlockEntityData::Chest { items } => {
                 c.insert("id".to_string(), NbtTag::String("Chest".to_string()));
@@ -303,11 +406,62 @@ impl BlockEntityData {
                     .collect();
                 c.insert("Items".to_string(), NbtTag::List(item_list));
             }
+            BlockEntityData::Beacon {
+                primary, secondary, ..
+            } => {
+                c.insert("id".to_string(), NbtTag::String("Beacon".to_string()));
+                c.insert("primary".to_string(), NbtTag::Int(*primary));
+                c.insert("secondary".to_string(), NbtTag::Int(*secondary));
+            }
+            BlockEntityData::CommandBlock(command_block) => {
+                c.insert("id".to_string(), NbtTag::String("CommandBlock".to_string()));
+                command_block.write_nbt(&mut c);
+            }
+            BlockEntityData::Skull {
+                skull_type,
+                rotation,
+                owner,
+            } => {
+                c.insert("id".to_string(), NbtTag::String("Skull".to_string()));
+                c.insert("SkullType".to_string(), NbtTag::Byte(*skull_type));
+                c.insert("Rotation".to_string(), NbtTag::Float(*rotation));
+                c.insert("MouthMoving".to_string(), NbtTag::Byte(0));
+                c.insert("MouthTickCount".to_string(), NbtTag::Int(0));
+                if let Some(owner) = owner {
+                    c.insert("SkullOwner".to_string(), owner.to_nbt());
+                }
+            }
+            BlockEntityData::Music { note, powered } => {
+                c.insert("id".to_string(), NbtTag::String("Music".to_string()));
+                c.insert("note".to_string(), NbtTag::Byte(*note as i8));
+                c.insert("powered".to_string(), NbtTag::Byte(*powered as i8));
+            }
+            BlockEntityData::Jukebox { record, .. } => {
+                c.insert("id".to_string(), NbtTag::String("Jukebox".to_string()));
+                if !record.is_empty() {
+                    let mut ic = NbtCompound::new();
+                    ic.insert("id".to_string(), NbtTag::Short(record.runtime_id as i16));
+                    ic.insert("Count".to_string(), NbtTag::Byte(record.count as i8));
+                    ic.insert("Damage".to_string(), NbtTag::Short(record.metadata as i16));
+                    c.insert("RecordItem".to_string(), NbtTag::Compound(ic));
+                }
+            }
+            BlockEntityData::Banner {
+                base,
+                patterns,
+                banner_type,
+            } => {
+                c.insert("id".to_string(), NbtTag::String("Banner".to_string()));
+                c.insert("Base".to_string(), NbtTag::Int(*base));
+                c.insert("Patterns".to_string(), loom::patterns_to_nbt(patterns));
+                c.insert("Type".to_string(), NbtTag::Int(*banner_type));
+            }
             // Transient containers — items are lost on close, no disk persistence.
             BlockEntityData::Stonecutter { .. }
             | BlockEntityData::Grindstone { .. }
             | BlockEntityData::Loom { .. }
-            | BlockEntityData::Anvil { .. } => {}
+            | BlockEntityData::Anvil { .. }
+            | BlockEntityData::CartographyTable { .. } => {}
         }
 
         c
@@ -321,28 +475,11 @@ impl BlockEntityData {
         let id = c.get("id").and_then(|t| t.as_string())?;
 
         let data = match id {
-            "Sign" => {
-                let front = c
-                    .get("FrontText")
-                    .and_then(|t| t.as_compound())
-                    .and_then(|c| c.get("Text"))
-                    .and_then(|t| t.as_string())
-                    .unwrap_or_default()
-                    .to_string();
-                let back = c
-                    .get("BackText")
-                    .and_then(|t| t.as_compound())
-                    .and_then(|c| c.get("Text"))
-                    .and_then(|t| t.as_string())
-                    .unwrap_or_default()
-                    .to_string();
-                let is_editable = c.get("IsEditable").and_then(|t| t.as_byte()).unwrap_or(0) != 0;
-                BlockEntityData::Sign {
-                    front_text: front,
-                    back_text: back,
-                    is_editable,
-                }
-            }
+            "Sign" => BlockEntityData::Sign {
+                front: sign_text_from_nbt(c.get("FrontText")),
+                back: sign_text_from_nbt(c.get("BackText")),
+                is_waxed: c.get("IsWaxed").and_then(|t| t.as_byte()).unwrap_or(0) != 0,
+            },
             "Chest" => {
                 let mut items: Vec<ItemStack> =
                     (0..CHEST_SLOTS).map(|_| ItemStack::empty()).collect();
@@ -405,6 +542,50 @@ impl BlockEntityData {
                 }
                 BlockEntityData::EnchantingTable { item, lapis }
             }
+            "Beacon" => BlockEntityData::Beacon {
+                primary: c.get("primary").and_then(|t| t.as_int()).unwrap_or(0),
+                secondary: c.get("secondary").and_then(|t| t.as_int()).unwrap_or(0),
+                payment: ItemStack::empty(),
+            },
+            "CommandBlock" => BlockEntityData::CommandBlock(CommandBlock::read_nbt(c)),
+            "Skull" => BlockEntityData::Skull {
+                skull_type: c.get("SkullType").and_then(|t| t.as_byte()).unwrap_or(0),
+                rotation: c.get("Rotation").and_then(|t| t.as_float()).unwrap_or(0.0),
+                owner: c.get("SkullOwner").and_then(SkullOwner::from_nbt),
+            },
+            "Music" => BlockEntityData::Music {
+                note: (c.get("note").and_then(|t| t.as_byte()).unwrap_or(0) as u8) % NOTE_PITCHES,
+                powered: c.get("powered").and_then(|t| t.as_byte()).unwrap_or(0) != 0,
+            },
+            "Jukebox" => {
+                let mut record = ItemStack::empty();
+                if let Some(ic) = c.get("RecordItem").and_then(|t| t.as_compound()) {
+                    let rid = ic.get("id").and_then(|t| t.as_short()).unwrap_or(0);
+                    let count = ic.get("Count").and_then(|t| t.as_byte()).unwrap_or(0);
+                    let damage = ic.get("Damage").and_then(|t| t.as_short()).unwrap_or(0);
+                    if rid != 0 && count > 0 {
+                        record = ItemStack {
+                            runtime_id: rid as i32,
+                            count: count as u16,
+                            metadata: damage as u16,
+                            block_runtime_id: 0,
+                            nbt_data: Vec::new(),
+                            can_place_on: Vec::new(),
+                            can_destroy: Vec::new(),
+                            stack_network_id: 0,
+                        };
+                    }
+                }
+                BlockEntityData::Jukebox {
+                    record,
+                    ticks_left: 0,
+                }
+            }
+            "Banner" => BlockEntityData::Banner {
+                base: c.get("Base").and_then(|t| t.as_int()).unwrap_or(0),
+                patterns: loom::patterns_from_nbt(c.get("Patterns")),
+                banner_type: c.get("Type").and_then(|t| t.as_int()).unwrap_or(0),
+            },
             other => {
                 if let Some(ft) = FurnaceType::from_nbt_id(other) {
                     let mut input = ItemStack::empty();
@@ -472,6 +653,41 @@ impl BlockEntityData {
     }
 }
 
+/// Build the `FrontText`/`BackText` compound of a sign face.
+fn sign_text_to_nbt(face: &SignText) -> NbtTag {
+    let mut c = NbtCompound::new();
+    c.insert("Text".to_string(), NbtTag::String(face.text.clone()));
+    c.insert("SignTextColor".to_string(), NbtTag::Int(face.color as i32));
+    c.insert(
+        "IgnoreLighting".to_string(),
+        NbtTag::Byte(face.glowing as i8),
+    );
+    // Keep the color when the client re-sends the text
+    c.insert("PersistFormatting".to_string(), NbtTag::Byte(1));
+    NbtTag::Compound(c)
+}
+
+/// Read a sign face, falling back to defaults for missing fields.
+fn sign_text_from_nbt(tag: Option<&NbtTag>) -> SignText {
+    let Some(c) = tag.and_then(|t| t.as_compound()) else {
+        return SignText::default();
+    };
+    let mut face = SignText::plain(
+        c.get("Text")
+            .and_then(|t| t.as_string())
+            .unwrap_or_default(),
+    );
+    if let Some(color) = c.get("SignTextColor").and_then(|t| t.as_int()) {
+        face.color = color as u32;
+    }
+    face.glowing = c
+        .get("IgnoreLighting")
+        .and_then(|t| t.as_byte())
+        .unwrap_or(0)
+        != 0;
+    face
+}
+
 /// Parse multiple concatenated LE NBT compounds (from LevelDB tag 0x31).
 pub fn parse_block_entities(data: &[u8]) -> Vec<((i32, i32, i32), BlockEntityData)> {
     let mut result = Vec::new();
@@ -498,13 +714,13 @@ mod tests {
         let be = BlockEntityData::new_sign();
         match &be {
             BlockEntityData::Sign {
-                front_text,
-                back_text,
-                is_editable,
+                front,
+                back,
+                is_waxed,
             } => {
-                assert!(front_text.is_empty());
-                assert!(back_text.is_empty());
-                assert!(*is_editable);
+                assert_eq!(*front, SignText::default());
+                assert_eq!(*back, SignText::default());
+                assert!(!*is_waxed);
             }
             _ => panic!("Expected Sign"),
         }
@@ -525,9 +741,9 @@ mod tests {
     #[test]
     fn sign_network_nbt_roundtrip() {
         let be = BlockEntityData::Sign {
-            front_text: "Hello\nWorld".to_string(),
-            back_text: "Back".to_string(),
-            is_editable: false,
+            front: SignText::plain("Hello\nWorld"),
+            back: SignText::plain("Back"),
+            is_waxed: false,
         };
         let nbt = be.to_network_nbt(10, 64, -5);
         assert!(!nbt.is_empty());
@@ -540,25 +756,58 @@ mod tests {
     #[test]
     fn sign_le_nbt_roundtrip() {
         let be = BlockEntityData::Sign {
-            front_text: "Line1".to_string(),
-            back_text: "Line2".to_string(),
-            is_editable: true,
+            front: SignText {
+                text: "Line1".to_string(),
+                color: crate::sign::SIGN_DYE_COLORS[14],
+                glowing: true,
+            },
+            back: SignText::plain("Line2"),
+            is_waxed: true,
         };
         let data = be.to_le_nbt(5, 100, -3);
         let ((x, y, z), parsed) = BlockEntityData::from_le_nbt(&data).unwrap();
         assert_eq!((x, y, z), (5, 100, -3));
         match parsed {
             BlockEntityData::Sign {
-                front_text,
-                back_text,
-                is_editable,
+                front,
+                back,
+                is_waxed,
+            } => {
+                assert_eq!(front.text, "Line1");
+                assert_eq!(front.color, crate::sign::SIGN_DYE_COLORS[14]);
+                assert!(front.glowing);
+                assert_eq!(back, SignText::plain("Line2"));
+                assert!(is_waxed);
+            }
+            _ => panic!("Expected Sign"),
+        }
+    }
+
+    #[test]
+    fn with_nbt_patches_fields() {
+        let sign = BlockEntityData::new_sign();
+        let patch = match mc_rs_nbt::parse_snbt(r#"{FrontText:{Text:"Hi"},x:99}"#).unwrap() {
+            NbtTag::Compound(c) => c,
+            _ => panic!("Expected compound"),
+        };
+        match BlockEntityData::with_nbt(Some(&sign), (1, 2, 3), &patch).unwrap() {
+            BlockEntityData::Sign {
+                front, is_waxed, ..
             } => {
-                assert_eq!(front_text, "Line1");
-                assert_eq!(back_text, "Line2");
-                assert!(is_editable);
+                assert_eq!(front.text, "Hi");
+                assert!(!is_waxed);
             }
             _ => panic!("Expected Sign"),
         }
+
+        // Without an existing entity, the patch must name one
+        assert!(BlockEntityData::with_nbt(None, (1, 2, 3), &patch).is_none());
+        let mut chest = NbtCompound::new();
+        chest.insert("id".into(), NbtTag::String("Chest".into()));
+        assert!(matches!(
+            BlockEntityData::with_nbt(None, (1, 2, 3), &chest),
+            Some(BlockEntityData::Chest { .. })
+        ));
     }
 
     #[test]
@@ -609,7 +858,7 @@ mod tests {
         let nbt = be.to_network_nbt(0, 64, 0);
         assert!(!nbt.is_empty());
         // Should be parseable as network NBT
-        let root = read_nbt_network(&mut &nbt[..]).unwrap();
+        let root = mc_rs_nbt::read_nbt_network(&mut &nbt[..]).unwrap();
         assert_eq!(
             root.compound.get("id").and_then(|t| t.as_string()),
             Some("Chest")
@@ -619,9 +868,9 @@ mod tests {
     #[test]
     fn parse_multiple_block_entities() {
         let sign = BlockEntityData::Sign {
-            front_text: "A".to_string(),
-            back_text: "B".to_string(),
-            is_editable: false,
+            front: SignText::plain("A"),
+            back: SignText::plain("B"),
+            is_waxed: false,
         };
         let chest = BlockEntityData::new_chest();
 
@@ -741,7 +990,7 @@ mod tests {
         let be = BlockEntityData::new_furnace(FurnaceType::Smoker);
         let nbt = be.to_network_nbt(0, 64, 0);
         assert!(!nbt.is_empty());
-        let root = read_nbt_network(&mut &nbt[..]).unwrap();
+        let root = mc_rs_nbt::read_nbt_network(&mut &nbt[..]).unwrap();
         assert_eq!(
             root.compound.get("id").and_then(|t| t.as_string()),
             Some("Smoker")
@@ -803,6 +1052,155 @@ mod tests {
         }
     }
 
+    #[test]
+    fn beacon_le_nbt_roundtrip() {
+        let mut be = BlockEntityData::new_beacon();
+        if let BlockEntityData::Beacon {
+            ref mut primary,
+            ref mut secondary,
+            ..
+        } = be
+        {
+            *primary = 1;
+            *secondary = 10;
+        }
+        let data = be.to_le_nbt(4, 80, 4);
+        let ((x, y, z), parsed) = BlockEntityData::from_le_nbt(&data).unwrap();
+        assert_eq!((x, y, z), (4, 80, 4));
+        match parsed {
+            BlockEntityData::Beacon {
+                primary,
+                secondary,
+                payment,
+            } => {
+                assert_eq!(primary, 1);
+                assert_eq!(secondary, 10);
+                assert!(payment.is_empty());
+            }
+            _ => panic!("Expected Beacon"),
+        }
+    }
+
+    #[test]
+    fn command_block_le_nbt_roundtrip() {
+        let mut be = BlockEntityData::new_command_block();
+        if let BlockEntityData::CommandBlock(ref mut cb) = be {
+            cb.command = "say hi".into();
+            cb.custom_name = "Greeter".into();
+            cb.auto = true;
+            cb.tick_delay = 20;
+            cb.success_count = 1;
+            cb.last_execution = 1234;
+        }
+        let data = be.to_le_nbt(-3, 64, 9);
+        let ((x, y, z), parsed) = BlockEntityData::from_le_nbt(&data).unwrap();
+        assert_eq!((x, y, z), (-3, 64, 9));
+        match parsed {
+            BlockEntityData::CommandBlock(cb) => {
+                assert_eq!(cb.command, "say hi");
+                assert_eq!(cb.custom_name, "Greeter");
+                assert!(cb.auto);
+                assert!(cb.was_active);
+                assert_eq!(cb.tick_delay, 20);
+                assert_eq!(cb.success_count, 1);
+                assert_eq!(cb.last_execution, 1234);
+            }
+            _ => panic!("Expected CommandBlock"),
+        }
+    }
+
+    #[test]
+    fn skull_le_nbt_roundtrip() {
+        let owner = SkullOwner {
+            name: "Steve".into(),
+            skin_id: "Standard_Custom".into(),
+        };
+        let be = BlockEntityData::new_skull(3, 202.5, Some(owner.clone()));
+        let data = be.to_le_nbt(7, 70, -7);
+        let ((x, y, z), parsed) = BlockEntityData::from_le_nbt(&data).unwrap();
+        assert_eq!((x, y, z), (7, 70, -7));
+        match parsed {
+            BlockEntityData::Skull {
+                skull_type,
+                rotation,
+                owner: parsed_owner,
+            } => {
+                assert_eq!(skull_type, 3);
+                assert_eq!(rotation, 202.5);
+                assert_eq!(parsed_owner, Some(owner));
+            }
+            _ => panic!("Expected Skull"),
+        }
+
+        // A creeper head has no owner
+        let data = BlockEntityData::new_skull(4, 0.0, None).to_le_nbt(0, 0, 0);
+        let (_, parsed) = BlockEntityData::from_le_nbt(&data).unwrap();
+        assert!(matches!(
+            parsed,
+            BlockEntityData::Skull {
+                skull_type: 4,
+                owner: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn note_block_and_jukebox_le_nbt_roundtrip() {
+        let data = BlockEntityData::Music {
+            note: 17,
+            powered: true,
+        }
+        .to_le_nbt(1, 2, 3);
+        let (_, parsed) = BlockEntityData::from_le_nbt(&data).unwrap();
+        assert!(matches!(
+            parsed,
+            BlockEntityData::Music {
+                note: 17,
+                powered: true
+            }
+        ));
+
+        let jukebox = BlockEntityData::Jukebox {
+            record: ItemStack::new(900, 1),
+            ticks_left: 1200,
+        };
+        let (_, parsed) = BlockEntityData::from_le_nbt(&jukebox.to_le_nbt(1, 2, 3)).unwrap();
+        match parsed {
+            BlockEntityData::Jukebox { record, ticks_left } => {
+                assert_eq!((record.runtime_id, record.count), (900, 1));
+                // The record doesn't resume after a reload
+                assert_eq!(ticks_left, 0);
+            }
+            _ => panic!("Expected Jukebox"),
+        }
+
+        let empty = BlockEntityData::new_jukebox().to_le_nbt(0, 0, 0);
+        let (_, parsed) = BlockEntityData::from_le_nbt(&empty).unwrap();
+        assert!(matches!(parsed, BlockEntityData::Jukebox { record, .. } if record.is_empty()));
+    }
+
+    #[test]
+    fn banner_from_item_le_nbt_roundtrip() {
+        let mut item = ItemStack::new(606, 1);
+        item.metadata = 11;
+        item.nbt_data = loom::apply_pattern(&[], "mr", 4).unwrap();
+        let banner = BlockEntityData::banner_from_item(&item);
+
+        let (_, parsed) = BlockEntityData::from_le_nbt(&banner.to_le_nbt(1, 2, 3)).unwrap();
+        match parsed {
+            BlockEntityData::Banner {
+                base,
+                patterns,
+                banner_type,
+            } => {
+                assert_eq!((base, banner_type), (11, 0));
+                assert_eq!(patterns, loom::item_patterns(&item.nbt_data));
+            }
+            _ => panic!("Expected Banner"),
+        }
+    }
+
     #[test]
     fn parse_mixed_block_entities_with_furnace() {
         let sign = BlockEntityData::new_sign();
@@ -0,0 +1,207 @@
+//! Entity collision.
+//!
+//! Entities whose bounding boxes overlap are softly pushed apart, a little
+//! every tick, and minecarts and boats moving fast enough ram the entities in
+//! their way. Only bodies sharing or neighbouring a [`CollisionGrid`] cell are
+//! compared, so the cost stays close to linear in the number of entities.
+
+use std::collections::HashMap;
+
+/// Horizontal push overlapping entities give each other per tick.
+pub const PUSH_STRENGTH: f32 = 0.05;
+/// Horizontal speed (blocks per tick) above which a vehicle rams.
+pub const RAM_SPEED: f32 = 0.2;
+/// How much of its own speed a ramming vehicle passes on.
+pub const RAM_KNOCKBACK: f32 = 2.0;
+/// Upward speed given to rammed entities.
+pub const RAM_LIFT: f32 = 0.2;
+/// Share of its speed a vehicle keeps after ramming something.
+pub const RAM_SLOWDOWN: f32 = 0.8;
+/// Width of a player's bounding box.
+pub const PLAYER_WIDTH: f32 = 0.6;
+/// Height of a player's bounding box.
+pub const PLAYER_HEIGHT: f32 = 1.8;
+/// Grid cell size in blocks; bodies up to this wide are always found.
+const CELL_SIZE: f32 = 4.0;
+
+/// Vehicles that ram the entities in their way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VehicleKind {
+    Minecart,
+    Boat,
+}
+
+impl VehicleKind {
+    /// The kind of vehicle an entity type is, if any.
+    pub fn from_type(type_id: &str) -> Option<Self> {
+        match type_id {
+            "minecraft:minecart"
+            | "minecraft:chest_minecart"
+            | "minecraft:hopper_minecart"
+            | "minecraft:tnt_minecart"
+            | "minecraft:command_block_minecart" => Some(Self::Minecart),
+            "minecraft:boat" | "minecraft:chest_boat" => Some(Self::Boat),
+            _ => None,
+        }
+    }
+}
+
+/// An entity's bounding box, standing at its feet position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Body {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Body {
+    /// Whether two bodies overlap.
+    pub fn overlaps(&self, other: &Body) -> bool {
+        let reach = (self.width + other.width) / 2.0;
+        (self.x - other.x).abs() < reach
+            && (self.z - other.z).abs() < reach
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+}
+
+/// The push `b` gets away from `a` (`a` gets the opposite one), or `None`
+/// when they stand at the same spot and there is no way to tell them apart.
+pub fn soft_push(a: &Body, b: &Body) -> Option<(f32, f32)> {
+    let (dx, dz) = (b.x - a.x, b.z - a.z);
+    let max = dx.abs().max(dz.abs());
+    if max < 0.01 {
+        return None;
+    }
+    let distance = max.sqrt();
+    let strength = (1.0 / distance).min(1.0) * PUSH_STRENGTH / distance;
+    Some((dx * strength, dz * strength))
+}
+
+/// The velocity a vehicle moving at `(vx, vz)` knocks what it runs into
+/// with, or `None` when it is too slow to ram.
+pub fn ram(vx: f32, vz: f32) -> Option<(f32, f32, f32)> {
+    if vx.hypot(vz) < RAM_SPEED {
+        return None;
+    }
+    Some((vx * RAM_KNOCKBACK, RAM_LIFT, vz * RAM_KNOCKBACK))
+}
+
+/// A spatial index of bodies by horizontal grid cell.
+#[derive(Default)]
+pub struct CollisionGrid {
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl CollisionGrid {
+    /// Index bodies by position.
+    pub fn build(bodies: &[Body]) -> Self {
+        let mut grid = Self::default();
+        for (i, body) in bodies.iter().enumerate() {
+            grid.cells
+                .entry(cell_key(body.x, body.z))
+                .or_default()
+                .push(i);
+        }
+        grid
+    }
+
+    /// Every pair `(i, j)` with `i < j` of overlapping bodies.
+    pub fn overlapping_pairs(&self, bodies: &[Body]) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (&(cx, cz), cell) in &self.cells {
+            for &i in cell {
+                for dx in -1..=1 {
+                    for dz in -1..=1 {
+                        let Some(others) = self.cells.get(&(cx + dx, cz + dz)) else {
+                            continue;
+                        };
+                        pairs.extend(
+                            others
+                                .iter()
+                                .filter(|&&j| j > i && bodies[i].overlaps(&bodies[j]))
+                                .map(|&j| (i, j)),
+                        );
+                    }
+                }
+            }
+        }
+        pairs
+    }
+}
+
+/// Compute the cell key for a world position.
+fn cell_key(x: f32, z: f32) -> (i32, i32) {
+    (
+        (x / CELL_SIZE).floor() as i32,
+        (z / CELL_SIZE).floor() as i32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(x: f32, z: f32) -> Body {
+        Body {
+            x,
+            y: 4.0,
+            z,
+            width: 0.6,
+            height: 1.8,
+        }
+    }
+
+    #[test]
+    fn vehicle_types() {
+        assert_eq!(
+            VehicleKind::from_type("minecraft:minecart"),
+            Some(VehicleKind::Minecart)
+        );
+        assert_eq!(
+            VehicleKind::from_type("minecraft:chest_boat"),
+            Some(VehicleKind::Boat)
+        );
+        assert_eq!(VehicleKind::from_type("minecraft:cow"), None);
+    }
+
+    #[test]
+    fn overlap_needs_all_three_axes() {
+        assert!(body(0.0, 0.0).overlaps(&body(0.5, 0.3)));
+        assert!(!body(0.0, 0.0).overlaps(&body(0.7, 0.0)));
+        let above = Body {
+            y: 6.0,
+            ..body(0.0, 0.0)
+        };
+        assert!(!body(0.0, 0.0).overlaps(&above));
+    }
+
+    #[test]
+    fn pushes_apart_gently() {
+        let (px, pz) = soft_push(&body(0.0, 0.0), &body(0.5, 0.0)).unwrap();
+        assert!(px > 0.0 && px <= PUSH_STRENGTH);
+        assert_eq!(pz, 0.0);
+        let (px, pz) = soft_push(&body(0.0, 0.0), &body(0.0, -0.3)).unwrap();
+        assert_eq!(px, 0.0);
+        assert!(pz < 0.0);
+        assert!(soft_push(&body(1.0, 1.0), &body(1.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn only_fast_vehicles_ram() {
+        assert!(ram(0.1, 0.0).is_none());
+        let (vx, vy, vz) = ram(0.0, -0.4).unwrap();
+        assert_eq!((vx, vy), (0.0, RAM_LIFT));
+        assert!(vz < -0.4);
+    }
+
+    #[test]
+    fn grid_finds_pairs_across_cells() {
+        // 3.9 and 4.1 fall in neighbouring cells
+        let bodies = [body(3.9, 0.0), body(4.1, 0.0), body(20.0, 0.0)];
+        let grid = CollisionGrid::build(&bodies);
+        assert_eq!(grid.overlapping_pairs(&bodies), vec![(0, 1)]);
+    }
+}
@@ -1,6 +1,7 @@
 //! Combat calculations: armor reduction, enchantments, critical hits, damage pipeline.
 
 use mc_rs_nbt::tag::{NbtCompound, NbtRoot, NbtTag};
+use mc_rs_nbt::{read_nbt_network_with_limits, NbtLimits};
 use mc_rs_proto::item_stack::ItemStack;
 use mc_rs_world::item_registry::ItemRegistry;
 
@@ -280,7 +281,7 @@ pub fn parse_enchantments(nbt_data: &[u8]) -> Vec<Enchantment> {
         return Vec::new();
     }
 
-    let root = match mc_rs_nbt::read_nbt_network(&mut &nbt_data[..]) {
+    let root = match read_nbt_network_with_limits(&mut &nbt_data[..], &NbtLimits::untrusted()) {
         Ok(r) => r,
         Err(_) => return Vec::new(),
     };
@@ -379,6 +380,31 @@ pub fn total_armor_defense(registry: &ItemRegistry, armor_slots: &[ItemStack]) -
         .sum()
 }
 
+/// Armor toughness for a single armor piece by item name.
+pub fn armor_piece_toughness(item_name: &str) -> f32 {
+    match item_name {
+        "minecraft:diamond_helmet"
+        | "minecraft:diamond_chestplate"
+        | "minecraft:diamond_leggings"
+        | "minecraft:diamond_boots" => 2.0,
+        "minecraft:netherite_helmet"
+        | "minecraft:netherite_chestplate"
+        | "minecraft:netherite_leggings"
+        | "minecraft:netherite_boots" => 3.0,
+        _ => 0.0,
+    }
+}
+
+/// Total armor toughness from armor slots.
+pub fn total_armor_toughness(registry: &ItemRegistry, armor_slots: &[ItemStack]) -> f32 {
+    armor_slots
+        .iter()
+        .filter(|item| !item.is_empty())
+        .filter_map(|item| registry.get_by_id(item.runtime_id as i16))
+        .map(|info| armor_piece_toughness(&info.name))
+        .sum()
+}
+
 /// Apply Bedrock armor reduction formula.
 /// `damage_after = damage * (1.0 - min(20, defense) / 25.0)`
 pub fn apply_armor_reduction(damage: f32, defense: f32) -> f32 {
@@ -386,6 +412,17 @@ pub fn apply_armor_reduction(damage: f32, defense: f32) -> f32 {
     damage * (1.0 - effective / 25.0)
 }
 
+/// Armor reduction accounting for toughness.
+///
+/// Heavy hits pierce armor: the effective defense drops by
+/// `damage / (2 + toughness / 4)`, but never below a fifth of the armor
+/// points. Toughness makes armor hold up better against strong attacks.
+pub fn apply_armor_reduction_with_toughness(damage: f32, defense: f32, toughness: f32) -> f32 {
+    let pierced = defense - damage / (2.0 + toughness / 4.0);
+    let effective = pierced.max(defense * 0.2);
+    apply_armor_reduction(damage, effective)
+}
+
 // ---------------------------------------------------------------------------
 // Enchantment combat bonuses
 // ---------------------------------------------------------------------------
@@ -484,6 +521,18 @@ pub fn depth_strider_level(boots_nbt: &[u8]) -> i16 {
     enchantment_level_on_item(boots_nbt, enchantment_id::DEPTH_STRIDER)
 }
 
+/// Whether the boots have Frost Walker, which keeps magma blocks from
+/// burning their wearer.
+pub fn has_frost_walker(boots_nbt: &[u8]) -> bool {
+    enchantment_level_on_item(boots_nbt, enchantment_id::FROST_WALKER) > 0
+}
+
+/// Whether the boots have Soul Speed, which keeps soul sand from slowing
+/// their wearer.
+pub fn has_soul_speed(boots_nbt: &[u8]) -> bool {
+    enchantment_level_on_item(boots_nbt, enchantment_id::SOUL_SPEED) > 0
+}
+
 /// Whether the helmet has Aqua Affinity (level >= 1).
 pub fn has_aqua_affinity(helmet_nbt: &[u8]) -> bool {
     enchantment_level_on_item(helmet_nbt, enchantment_id::AQUA_AFFINITY) > 0
@@ -502,6 +551,67 @@ pub fn is_critical_hit(on_ground: bool, delta_y: f32) -> bool {
 /// Critical hit damage multiplier.
 pub const CRITICAL_MULTIPLIER: f32 = 1.5;
 
+// ---------------------------------------------------------------------------
+// Knockback
+// ---------------------------------------------------------------------------
+
+/// Knockback tuning for melee hits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KnockbackProfile {
+    /// Horizontal push of a plain hit.
+    pub horizontal: f32,
+    /// Upward push of a hit.
+    pub vertical: f32,
+    /// Horizontal push multiplier for hits while sprinting.
+    pub sprint_multiplier: f32,
+    /// Extra horizontal push per Knockback enchantment level.
+    pub enchant_bonus: f32,
+    /// Share of the victim's current velocity removed before the push is
+    /// added: 1.0 replaces it, 0.5 halves it like vanilla Java.
+    pub friction: f32,
+}
+
+impl Default for KnockbackProfile {
+    fn default() -> Self {
+        Self {
+            horizontal: 0.4,
+            vertical: 0.4,
+            sprint_multiplier: 1.5,
+            enchant_bonus: 0.3,
+            friction: 1.0,
+        }
+    }
+}
+
+impl KnockbackProfile {
+    /// Velocity of a victim hit from the direction `(dx, dz)` (attacker to
+    /// victim) while moving at `current`. `None` if the attacker stands
+    /// right on top of the victim.
+    pub fn velocity(
+        &self,
+        direction: (f32, f32),
+        current: (f32, f32, f32),
+        sprinting: bool,
+        enchant_level: i16,
+    ) -> Option<(f32, f32, f32)> {
+        let (dx, dz) = direction;
+        let len = (dx * dx + dz * dz).sqrt();
+        if len <= 0.001 {
+            return None;
+        }
+        let mut strength = self.horizontal + enchant_level.max(0) as f32 * self.enchant_bonus;
+        if sprinting {
+            strength *= self.sprint_multiplier;
+        }
+        let keep = 1.0 - self.friction.clamp(0.0, 1.0);
+        Some((
+            current.0 * keep + dx / len * strength,
+            (current.1 * keep).max(0.0) + self.vertical,
+            current.2 * keep + dz / len * strength,
+        ))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Full damage pipeline
 // ---------------------------------------------------------------------------
@@ -511,6 +621,7 @@ pub struct DamageInput<'a> {
     pub base_damage: f32,
     pub weapon_nbt: &'a [u8],
     pub armor_defense: f32,
+    pub armor_toughness: f32,
     pub armor_nbt_slots: &'a [&'a [u8]],
     pub is_critical: bool,
     pub strength_bonus: f32,
@@ -520,12 +631,13 @@ pub struct DamageInput<'a> {
 
 /// Calculate final damage after all modifiers.
 ///
-/// Pipeline: base + sharpness + strength - weakness → ×critical → armor → protection → resistance
+/// Pipeline: base + sharpness + strength - weakness → ×critical → armor/toughness → protection → resistance
 pub fn calculate_damage(input: &DamageInput) -> f32 {
     let DamageInput {
         base_damage,
         weapon_nbt,
         armor_defense,
+        armor_toughness,
         armor_nbt_slots,
         is_critical,
         strength_bonus,
@@ -547,8 +659,8 @@ pub fn calculate_damage(input: &DamageInput) -> f32 {
         damage *= CRITICAL_MULTIPLIER;
     }
 
-    // Armor reduction
-    damage = apply_armor_reduction(damage, *armor_defense);
+    // Armor reduction (toughness limits how much big hits pierce armor)
+    damage = apply_armor_reduction_with_toughness(damage, *armor_defense, *armor_toughness);
 
     // Protection enchantment
     let prot = protection_reduction(armor_nbt_slots);
@@ -604,6 +716,27 @@ mod tests {
         assert!((with_30 - with_20).abs() < 0.01);
     }
 
+    #[test]
+    fn armor_toughness_values() {
+        assert!((armor_piece_toughness("minecraft:diamond_chestplate") - 2.0).abs() < 0.01);
+        assert!((armor_piece_toughness("minecraft:netherite_boots") - 3.0).abs() < 0.01);
+        assert!(armor_piece_toughness("minecraft:iron_helmet").abs() < 0.01);
+    }
+
+    #[test]
+    fn toughness_resists_armor_piercing() {
+        // Strong hit against full diamond: toughness keeps more of the armor effective
+        let without = apply_armor_reduction_with_toughness(20.0, 20.0, 0.0);
+        let with = apply_armor_reduction_with_toughness(20.0, 20.0, 8.0);
+        assert!(with < without);
+        // Weak hits are barely affected by piercing
+        let weak = apply_armor_reduction_with_toughness(1.0, 20.0, 8.0);
+        assert!((weak - apply_armor_reduction(1.0, 20.0 - 0.25)).abs() < 0.001);
+        // Effective defense never drops below a fifth of the armor points
+        let floor = apply_armor_reduction_with_toughness(100.0, 20.0, 0.0);
+        assert!((floor - apply_armor_reduction(100.0, 4.0)).abs() < 0.001);
+    }
+
     #[test]
     fn parse_enchantments_empty() {
         assert!(parse_enchantments(&[]).is_empty());
@@ -639,6 +772,21 @@ mod tests {
         assert_eq!(parsed.len(), 2);
     }
 
+    #[test]
+    fn parse_enchantments_rejects_oversized_lists() {
+        // Item NBT comes from clients: a list past the untrusted limits is
+        // refused as a whole
+        let enchants = vec![
+            Enchantment {
+                id: enchantment_id::SHARPNESS,
+                level: 1,
+            };
+            70_000
+        ];
+        let nbt = build_enchantment_nbt(&enchants);
+        assert!(parse_enchantments(&nbt).is_empty());
+    }
+
     #[test]
     fn sharpness_bonus_level_5() {
         let nbt = build_enchantment_nbt(&[Enchantment {
@@ -773,6 +921,20 @@ mod tests {
         }]);
         assert_eq!(respiration_level(&resp3), 3);
 
+        let strider = build_enchantment_nbt(&[
+            Enchantment {
+                id: enchantment_id::DEPTH_STRIDER,
+                level: 2,
+            },
+            Enchantment {
+                id: enchantment_id::FROST_WALKER,
+                level: 1,
+            },
+        ]);
+        assert_eq!(depth_strider_level(&strider), 2);
+        assert!(has_frost_walker(&strider));
+        assert!(!has_frost_walker(&resp3));
+
         let loot2 = build_enchantment_nbt(&[Enchantment {
             id: enchantment_id::LOOTING,
             level: 2,
@@ -789,4 +951,41 @@ mod tests {
         }]);
         assert!(has_aqua_affinity(&aa));
     }
+
+    #[test]
+    fn knockback_profiles() {
+        let default = KnockbackProfile::default();
+        let (x, y, z) = default
+            .velocity((3.0, 0.0), (0.2, -0.5, 0.1), false, 0)
+            .unwrap();
+        // Current motion replaced
+        assert!((x - 0.4).abs() < 1e-6);
+        assert!((y - 0.4).abs() < 1e-6);
+        assert!(z.abs() < 1e-6);
+
+        // Knockback II while sprinting: (0.4 + 0.6) * 1.5
+        let (x, _, _) = default
+            .velocity((0.0, -2.0), (0.0, 0.0, 0.0), true, 2)
+            .unwrap();
+        assert!(x.abs() < 1e-6);
+        let (_, _, z) = default
+            .velocity((0.0, -2.0), (0.0, 0.0, 0.0), true, 2)
+            .unwrap();
+        assert!((z + 1.5).abs() < 1e-6);
+
+        // Half friction keeps half the victim's motion
+        let vanilla = KnockbackProfile {
+            friction: 0.5,
+            ..default
+        };
+        let (x, y, _) = vanilla
+            .velocity((1.0, 0.0), (0.2, 0.2, 0.0), false, 0)
+            .unwrap();
+        assert!((x - 0.5).abs() < 1e-6);
+        assert!((y - 0.5).abs() < 1e-6);
+
+        assert!(default
+            .velocity((0.0, 0.0), (0.0, 0.0, 0.0), false, 0)
+            .is_none());
+    }
 }
@@ -0,0 +1,337 @@
+//! Command block logic — settings, redstone triggering and NBT fields,
+//! shared by command blocks and command block minecarts.
+
+use mc_rs_nbt::tag::{NbtCompound, NbtTag};
+use mc_rs_proto::packets::{CommandBlockTarget, CommandBlockUpdate};
+
+/// Name commands of an unnamed command block are sent as.
+pub const DEFAULT_NAME: &str = "!";
+
+/// Ticks between two runs of a command block minecart on a powered activator rail.
+pub const MINECART_INTERVAL_TICKS: u64 = 4;
+
+/// How a command block is triggered (stored in its block state).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandBlockMode {
+    /// Runs once each time it gets activated.
+    Impulse,
+    /// Runs every `tick_delay` ticks while active.
+    Repeat,
+    /// Runs when the command block pointing into it runs.
+    Chain,
+}
+
+impl CommandBlockMode {
+    /// Mode from its index in CommandBlockUpdate (0 = impulse, 1 = repeat, 2 = chain).
+    pub fn from_index(index: u32) -> Option<Self> {
+        match index {
+            0 => Some(Self::Impulse),
+            1 => Some(Self::Repeat),
+            2 => Some(Self::Chain),
+            _ => None,
+        }
+    }
+
+    /// Index of the mode in CommandBlockUpdate.
+    pub fn index(self) -> u32 {
+        match self {
+            Self::Impulse => 0,
+            Self::Repeat => 1,
+            Self::Chain => 2,
+        }
+    }
+}
+
+/// Settings and state of a command block or command block minecart.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandBlock {
+    pub command: String,
+    /// Custom name, used as the sender of its commands.
+    pub custom_name: String,
+    /// Output of the last run, kept only when `track_output` is on.
+    pub last_output: String,
+    pub track_output: bool,
+    /// 1 if the last run succeeded, read by conditional blocks in front of it.
+    pub success_count: i32,
+    /// "Always Active": runs without redstone power.
+    pub auto: bool,
+    /// Whether the block is receiving redstone power.
+    pub powered: bool,
+    /// Whether the condition of a conditional block passed on its last run.
+    pub condition_met: bool,
+    /// Ticks to wait between activation (or two repeats) and a run.
+    pub tick_delay: i32,
+    /// Repeating blocks run on the tick they get activated, ignoring the delay.
+    pub execute_on_first_tick: bool,
+    /// Game tick of the last run.
+    pub last_execution: u64,
+    /// Whether the block was active on the previous tick (transient).
+    pub was_active: bool,
+    /// Game tick of the next pending run (transient).
+    pub scheduled: Option<u64>,
+}
+
+impl CommandBlock {
+    /// Name the commands of this block are sent as.
+    pub fn sender_name(&self) -> &str {
+        if self.custom_name.is_empty() {
+            DEFAULT_NAME
+        } else {
+            &self.custom_name
+        }
+    }
+
+    /// Whether the block is always active or powered.
+    pub fn is_active(&self) -> bool {
+        self.auto || self.powered
+    }
+
+    /// Apply the settings saved in the command block screen.
+    pub fn apply_update(&mut self, update: &CommandBlockUpdate) {
+        self.command = update.command.clone();
+        self.custom_name = update.name.clone();
+        self.track_output = update.track_output;
+        if !self.track_output {
+            self.last_output.clear();
+        }
+        self.tick_delay = update.tick_delay.max(0);
+        self.execute_on_first_tick = update.execute_on_first_tick;
+        if let CommandBlockTarget::Block { needs_redstone, .. } = update.target {
+            self.auto = !needs_redstone;
+        }
+    }
+
+    /// Update the redstone power of an impulse or repeating block at game
+    /// tick `tick`. Returns whether it runs this tick.
+    pub fn tick_power(&mut self, mode: CommandBlockMode, powered: bool, tick: u64) -> bool {
+        self.powered = powered;
+        let active = self.is_active();
+        let activated = active && !self.was_active;
+        self.was_active = active;
+
+        let delay = self.tick_delay.max(0) as u64;
+        match mode {
+            CommandBlockMode::Impulse => {
+                if activated {
+                    self.scheduled = Some(tick + delay);
+                }
+            }
+            CommandBlockMode::Repeat => {
+                if !active {
+                    self.scheduled = None;
+                } else if activated {
+                    let first = if self.execute_on_first_tick { 0 } else { delay };
+                    self.scheduled = Some(tick + first);
+                } else if self.scheduled.is_none() {
+                    self.scheduled = Some((self.last_execution + delay).max(tick));
+                }
+            }
+            CommandBlockMode::Chain => return false,
+        }
+
+        match self.scheduled {
+            Some(at) if at <= tick => {
+                self.scheduled = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Record a run of the command at game tick `tick`.
+    pub fn record_run(&mut self, success: bool, output: String, tick: u64) {
+        self.success_count = success as i32;
+        self.condition_met = true;
+        self.last_execution = tick;
+        self.last_output = if self.track_output {
+            output
+        } else {
+            String::new()
+        };
+    }
+
+    /// Record a run skipped because its condition failed.
+    pub fn record_condition_failed(&mut self) {
+        self.success_count = 0;
+        self.condition_met = false;
+    }
+
+    /// Write the settings into a block entity compound.
+    pub fn write_nbt(&self, c: &mut NbtCompound) {
+        c.insert("Command".to_string(), NbtTag::String(self.command.clone()));
+        c.insert(
+            "CustomName".to_string(),
+            NbtTag::String(self.custom_name.clone()),
+        );
+        c.insert(
+            "LastOutput".to_string(),
+            NbtTag::String(self.last_output.clone()),
+        );
+        c.insert(
+            "TrackOutput".to_string(),
+            NbtTag::Byte(self.track_output as i8),
+        );
+        c.insert("SuccessCount".to_string(), NbtTag::Int(self.success_count));
+        c.insert("auto".to_string(), NbtTag::Byte(self.auto as i8));
+        c.insert("powered".to_string(), NbtTag::Byte(self.powered as i8));
+        c.insert(
+            "conditionMet".to_string(),
+            NbtTag::Byte(self.condition_met as i8),
+        );
+        c.insert("TickDelay".to_string(), NbtTag::Int(self.tick_delay));
+        c.insert(
+            "ExecuteOnFirstTick".to_string(),
+            NbtTag::Byte(self.execute_on_first_tick as i8),
+        );
+        c.insert(
+            "LastExecution".to_string(),
+            NbtTag::Long(self.last_execution as i64),
+        );
+    }
+
+    /// Read the settings from a block entity compound.
+    pub fn read_nbt(c: &NbtCompound) -> Self {
+        let string = |key: &str| {
+            c.get(key)
+                .and_then(|t| t.as_string())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let flag = |key: &str| c.get(key).and_then(|t| t.as_byte()).unwrap_or(0) != 0;
+        let auto = flag("auto");
+        let powered = flag("powered");
+        Self {
+            command: string("Command"),
+            custom_name: string("CustomName"),
+            last_output: string("LastOutput"),
+            track_output: flag("TrackOutput"),
+            success_count: c.get("SuccessCount").and_then(|t| t.as_int()).unwrap_or(0),
+            auto,
+            powered,
+            condition_met: flag("conditionMet"),
+            tick_delay: c.get("TickDelay").and_then(|t| t.as_int()).unwrap_or(0),
+            execute_on_first_tick: flag("ExecuteOnFirstTick"),
+            last_execution: c
+                .get("LastExecution")
+                .and_then(|t| t.as_long())
+                .unwrap_or(0)
+                .max(0) as u64,
+            // Don't re-trigger impulse blocks that were already active
+            was_active: auto || powered,
+            scheduled: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn impulse_runs_on_rising_edge() {
+        let mut cb = CommandBlock::default();
+        assert!(!cb.tick_power(CommandBlockMode::Impulse, false, 1));
+        assert!(cb.tick_power(CommandBlockMode::Impulse, true, 2));
+        assert!(!cb.tick_power(CommandBlockMode::Impulse, true, 3));
+        assert!(!cb.tick_power(CommandBlockMode::Impulse, false, 4));
+        assert!(cb.tick_power(CommandBlockMode::Impulse, true, 5));
+    }
+
+    #[test]
+    fn impulse_waits_for_delay() {
+        let mut cb = CommandBlock {
+            tick_delay: 3,
+            ..CommandBlock::default()
+        };
+        assert!(!cb.tick_power(CommandBlockMode::Impulse, true, 10));
+        assert!(!cb.tick_power(CommandBlockMode::Impulse, true, 12));
+        assert!(cb.tick_power(CommandBlockMode::Impulse, true, 13));
+        assert!(!cb.tick_power(CommandBlockMode::Impulse, true, 14));
+    }
+
+    #[test]
+    fn repeat_runs_every_delay_while_active() {
+        let mut cb = CommandBlock {
+            auto: true,
+            tick_delay: 2,
+            execute_on_first_tick: true,
+            ..CommandBlock::default()
+        };
+        let mut runs = Vec::new();
+        for tick in 0..7 {
+            if cb.tick_power(CommandBlockMode::Repeat, false, tick) {
+                cb.record_run(true, String::new(), tick);
+                runs.push(tick);
+            }
+        }
+        assert_eq!(runs, vec![0, 2, 4, 6]);
+
+        cb.auto = false;
+        assert!(!cb.tick_power(CommandBlockMode::Repeat, false, 8));
+        assert_eq!(cb.scheduled, None);
+    }
+
+    #[test]
+    fn chain_is_not_triggered_by_power() {
+        let mut cb = CommandBlock::default();
+        assert!(!cb.tick_power(CommandBlockMode::Chain, true, 1));
+        assert!(cb.is_active());
+    }
+
+    #[test]
+    fn apply_update_settings() {
+        let mut cb = CommandBlock {
+            last_output: "old".into(),
+            track_output: true,
+            ..CommandBlock::default()
+        };
+        cb.apply_update(&CommandBlockUpdate {
+            target: CommandBlockTarget::Block {
+                position: mc_rs_proto::types::BlockPos::new(0, 64, 0),
+                mode: 1,
+                needs_redstone: false,
+                conditional: false,
+            },
+            command: "say hi".into(),
+            last_output: String::new(),
+            name: String::new(),
+            filtered_name: String::new(),
+            track_output: false,
+            tick_delay: -5,
+            execute_on_first_tick: true,
+        });
+        assert_eq!(cb.command, "say hi");
+        assert_eq!(cb.sender_name(), DEFAULT_NAME);
+        assert!(cb.auto);
+        assert!(cb.last_output.is_empty());
+        assert_eq!(cb.tick_delay, 0);
+        assert!(cb.execute_on_first_tick);
+    }
+
+    #[test]
+    fn record_output_only_when_tracked() {
+        let mut cb = CommandBlock::default();
+        cb.record_run(true, "done".into(), 7);
+        assert_eq!(cb.success_count, 1);
+        assert!(cb.last_output.is_empty());
+        cb.track_output = true;
+        cb.record_run(false, "failed".into(), 8);
+        assert_eq!(cb.success_count, 0);
+        assert_eq!(cb.last_output, "failed");
+        assert_eq!(cb.last_execution, 8);
+        cb.record_condition_failed();
+        assert!(!cb.condition_met);
+    }
+
+    #[test]
+    fn mode_indices() {
+        for mode in [
+            CommandBlockMode::Impulse,
+            CommandBlockMode::Repeat,
+            CommandBlockMode::Chain,
+        ] {
+            assert_eq!(CommandBlockMode::from_index(mode.index()), Some(mode));
+        }
+        assert_eq!(CommandBlockMode::from_index(3), None);
+    }
+}
@@ -1,8 +1,14 @@
 //! ECS components for all entities (players and mobs).
 
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 use bevy_ecs::prelude::*;
+use mc_rs_behavior_pack::entity_components::{EntityBehavior, TimerComponent, Transformation};
+use mc_rs_world::physics::Surface;
+
+use crate::effects::ActiveEffect;
+use crate::move_sync::SentMove;
 
 /// Network identity for an entity.
 #[derive(Component, Debug, Clone)]
@@ -35,6 +41,10 @@ pub struct Velocity {
     pub z: f32,
 }
 
+/// The movement last broadcast for a mob.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LastSentMove(pub SentMove);
+
 /// Health points.
 #[derive(Component, Debug, Clone, Copy)]
 pub struct Health {
@@ -42,10 +52,70 @@ pub struct Health {
     pub max: f32,
 }
 
+/// Active status effects (potions, food, beacons, commands).
+#[derive(Component, Debug, Clone, Default)]
+pub struct StatusEffects(pub Vec<ActiveEffect>);
+
+/// A player's hunger: food level, saturation, and the exhaustion that
+/// drains them.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Hunger {
+    /// Food level (0-20). 20 = full.
+    pub food: i32,
+    /// Saturation level (0.0-20.0). Consumed before food.
+    pub saturation: f32,
+    /// Exhaustion accumulator (0.0-4.0+). Drains saturation/food at 4.0.
+    pub exhaustion: f32,
+}
+
+impl Default for Hunger {
+    fn default() -> Self {
+        Self {
+            food: 20,
+            saturation: 5.0,
+            exhaustion: 0.0,
+        }
+    }
+}
+
+/// A player's experience.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Experience {
+    /// XP level (0+).
+    pub level: i32,
+    /// Total accumulated XP.
+    pub total: i32,
+}
+
 /// Whether the entity is standing on the ground.
 #[derive(Component, Debug, Clone, Copy)]
 pub struct OnGround(pub bool);
 
+/// The entity is inside a bubble column, which lifts it (or drags it down)
+/// instead of gravity.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct InBubbleColumn {
+    pub drag_down: bool,
+}
+
+/// The entity is over a block that slows it down, lets it slide or bounces
+/// it: soul sand, honey, ice or slime. Plain ground has no component.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OnSurface(pub Surface);
+
+/// The entity stands under open sky while the sun is up and it isn't
+/// raining.
+#[derive(Component, Debug)]
+pub struct InSunlight;
+
+/// The mob is burning, for this many more ticks.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct OnFire(pub u32);
+
+/// The mob wears a helmet, with this much durability left.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Helmet(pub u32);
+
 /// Axis-aligned bounding box dimensions.
 #[derive(Component, Debug, Clone, Copy)]
 pub struct BoundingBox {
@@ -69,10 +139,19 @@ pub struct LastDamageTick(pub Option<u64>);
 #[derive(Component, Debug)]
 pub struct Mob;
 
+/// Marker: this mob is outside the simulation distance of every player, so
+/// its AI and movement are paused.
+#[derive(Component, Debug)]
+pub struct Dormant;
+
 /// Marker: this entity is a player.
 #[derive(Component, Debug)]
 pub struct Player;
 
+/// Marker: mobs don't see this player (spectators).
+#[derive(Component, Debug)]
+pub struct IgnoredByMobs;
+
 /// Marker: this entity is dead (pending cleanup or respawn).
 #[derive(Component, Debug)]
 pub struct Dead;
@@ -114,3 +193,19 @@ pub struct BreedCooldown(pub u64);
 /// Grows into adult after 24000 ticks (20 minutes).
 #[derive(Component, Debug, Clone, Copy)]
 pub struct Baby(pub u64);
+
+/// Data-driven state of a behavior pack mob: its definition, the component
+/// groups currently active, and scheduled timer/transformation ticks.
+#[derive(Component, Debug, Clone)]
+pub struct ComponentGroups {
+    pub definition: Arc<EntityBehavior>,
+    /// Active group names, in activation order.
+    pub active: Vec<String>,
+    /// Timer currently scheduled (to detect when the resolved timer changes).
+    pub timer: Option<TimerComponent>,
+    /// Tick at which the timer's event fires.
+    pub timer_at: Option<u64>,
+    pub transformation: Option<Transformation>,
+    /// Tick at which the mob transforms.
+    pub transform_at: Option<u64>,
+}
@@ -0,0 +1,100 @@
+//! World difficulty and the combat and hunger rules it changes.
+
+/// World difficulty, with its Bedrock protocol ID as discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Peaceful = 0,
+    Easy = 1,
+    #[default]
+    Normal = 2,
+    Hard = 3,
+}
+
+impl Difficulty {
+    /// Parse a name (`"easy"`) or protocol ID (`"1"`). Case-insensitive.
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "peaceful" | "p" | "0" => Some(Self::Peaceful),
+            "easy" | "e" | "1" => Some(Self::Easy),
+            "normal" | "n" | "2" => Some(Self::Normal),
+            "hard" | "h" | "3" => Some(Self::Hard),
+            _ => None,
+        }
+    }
+
+    /// Protocol ID (0-3).
+    pub fn id(self) -> i32 {
+        self as i32
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Peaceful => "peaceful",
+            Self::Easy => "easy",
+            Self::Normal => "normal",
+            Self::Hard => "hard",
+        }
+    }
+
+    /// Damage a mob attack with `base` damage deals: none on Peaceful,
+    /// halved plus one (never more than `base`) on Easy, ×1.5 on Hard.
+    pub fn mob_damage(self, base: f32) -> f32 {
+        match self {
+            Self::Peaceful => 0.0,
+            Self::Easy => (base / 2.0 + 1.0).min(base),
+            Self::Normal => base,
+            Self::Hard => base * 1.5,
+        }
+    }
+
+    /// Health starvation stops at, or `None` on Peaceful where players don't
+    /// starve. On Hard it can kill.
+    pub fn starvation_floor(self) -> Option<f32> {
+        match self {
+            Self::Peaceful => None,
+            Self::Easy => Some(10.0),
+            Self::Normal => Some(1.0),
+            Self::Hard => Some(0.0),
+        }
+    }
+
+    /// Whether hostile mobs spawn and stay in the world.
+    pub fn allows_hostile_mobs(self) -> bool {
+        self != Self::Peaceful
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_names_and_ids() {
+        assert_eq!(
+            Difficulty::from_name("Peaceful"),
+            Some(Difficulty::Peaceful)
+        );
+        assert_eq!(Difficulty::from_name("3"), Some(Difficulty::Hard));
+        assert_eq!(Difficulty::from_name("e"), Some(Difficulty::Easy));
+        assert_eq!(Difficulty::from_name("insane"), None);
+        assert_eq!(Difficulty::Hard.id(), 3);
+        assert_eq!(Difficulty::default(), Difficulty::Normal);
+    }
+
+    #[test]
+    fn mob_damage_scaling() {
+        assert_eq!(Difficulty::Peaceful.mob_damage(3.0), 0.0);
+        assert_eq!(Difficulty::Easy.mob_damage(3.0), 2.5);
+        assert_eq!(Difficulty::Easy.mob_damage(1.0), 1.0);
+        assert_eq!(Difficulty::Normal.mob_damage(3.0), 3.0);
+        assert_eq!(Difficulty::Hard.mob_damage(3.0), 4.5);
+    }
+
+    #[test]
+    fn starvation_floors() {
+        assert_eq!(Difficulty::Peaceful.starvation_floor(), None);
+        assert_eq!(Difficulty::Easy.starvation_floor(), Some(10.0));
+        assert_eq!(Difficulty::Normal.starvation_floor(), Some(1.0));
+        assert_eq!(Difficulty::Hard.starvation_floor(), Some(0.0));
+    }
+}
@@ -0,0 +1,190 @@
+//! Status effects and the rules shared by potions, food, beacons and
+//! commands, for players and mobs alike.
+
+use mc_rs_proto::packets::mob_effect::effect_id;
+
+use crate::components::Health;
+
+/// An active status effect on a player or mob.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveEffect {
+    /// Effect ID (see `mc_rs_proto::packets::mob_effect::effect_id`).
+    pub effect_id: i32,
+    /// Amplifier (0 = level I, 1 = level II, etc.).
+    pub amplifier: i32,
+    /// Remaining duration in ticks.
+    pub remaining_ticks: i32,
+}
+
+/// What one tick of status effects did to an entity.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectTick {
+    /// Poison or regeneration changed the entity's health.
+    pub health_changed: bool,
+    /// Health was lost (the entity flashes hurt).
+    pub hurt: bool,
+    /// Exhaustion added by Hunger.
+    pub exhaustion: f32,
+    /// Effects that ran out.
+    pub expired: Vec<i32>,
+}
+
+/// Whether a newly applied effect should replace an active one of the same type.
+///
+/// A higher amplifier always wins. At the same amplifier the effect is only
+/// refreshed when the new duration is longer than what remains. A weaker
+/// effect never overwrites a stronger one.
+pub fn should_override(
+    current_amplifier: i32,
+    current_remaining: i32,
+    new_amplifier: i32,
+    new_duration: i32,
+) -> bool {
+    if new_amplifier != current_amplifier {
+        return new_amplifier > current_amplifier;
+    }
+    new_duration > current_remaining
+}
+
+/// Add an effect, unless an active one of the same type should stay (see
+/// [`should_override`]). Returns whether it was added.
+pub fn add(
+    effects: &mut Vec<ActiveEffect>,
+    effect_id: i32,
+    amplifier: i32,
+    duration_ticks: i32,
+) -> bool {
+    if let Some(existing) = effects.iter().find(|e| e.effect_id == effect_id) {
+        if !should_override(
+            existing.amplifier,
+            existing.remaining_ticks,
+            amplifier,
+            duration_ticks,
+        ) {
+            return false;
+        }
+    }
+    effects.retain(|e| e.effect_id != effect_id);
+    effects.push(ActiveEffect {
+        effect_id,
+        amplifier,
+        remaining_ticks: duration_ticks,
+    });
+    true
+}
+
+/// Ticks between effect pulses at `amplifier` (e.g. poison base 25, regeneration base 50).
+fn pulse_interval(base: i32, amplifier: i32) -> i32 {
+    (base >> amplifier.clamp(0, 5)).max(1)
+}
+
+/// Run one tick of `effects`: poison and regeneration pulse against
+/// `health`, hunger adds exhaustion, and durations count down. Poison and
+/// hunger only affect `vulnerable` entities (e.g. not creative players).
+pub fn tick(effects: &mut Vec<ActiveEffect>, health: &mut Health, vulnerable: bool) -> EffectTick {
+    let mut result = EffectTick::default();
+    for effect in effects.iter() {
+        match effect.effect_id {
+            effect_id::POISON if vulnerable => {
+                let interval = pulse_interval(25, effect.amplifier);
+                // Poison never kills
+                if effect.remaining_ticks % interval == 0 && health.current > 1.0 {
+                    health.current = (health.current - 1.0).max(1.0);
+                    result.health_changed = true;
+                    result.hurt = true;
+                }
+            }
+            effect_id::REGENERATION => {
+                let interval = pulse_interval(50, effect.amplifier);
+                if effect.remaining_ticks % interval == 0 && health.current < health.max {
+                    health.current = (health.current + 1.0).min(health.max);
+                    result.health_changed = true;
+                }
+            }
+            effect_id::HUNGER if vulnerable => {
+                result.exhaustion += 0.005 * (effect.amplifier as f32 + 1.0);
+            }
+            _ => {}
+        }
+    }
+    for effect in effects.iter_mut() {
+        effect.remaining_ticks -= 1;
+        if effect.remaining_ticks <= 0 {
+            result.expired.push(effect.effect_id);
+        }
+    }
+    effects.retain(|e| e.remaining_ticks > 0);
+    result
+}
+
+/// Whether `effects` has an effect of this type.
+pub fn has(effects: &[ActiveEffect], effect_id: i32) -> bool {
+    effects.iter().any(|e| e.effect_id == effect_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn higher_amplifier_replaces() {
+        assert!(should_override(0, 600, 1, 100));
+    }
+
+    #[test]
+    fn lower_amplifier_ignored() {
+        assert!(!should_override(1, 100, 0, 6000));
+    }
+
+    #[test]
+    fn same_amplifier_refreshes_only_when_longer() {
+        assert!(should_override(0, 100, 0, 200));
+        assert!(!should_override(0, 300, 0, 200));
+        assert!(!should_override(0, 200, 0, 200));
+    }
+
+    #[test]
+    fn add_keeps_the_stronger_effect() {
+        let mut effects = Vec::new();
+        assert!(add(&mut effects, effect_id::POISON, 1, 100));
+        assert!(!add(&mut effects, effect_id::POISON, 0, 6000));
+        assert!(add(&mut effects, effect_id::POISON, 1, 200));
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].remaining_ticks, 200);
+    }
+
+    #[test]
+    fn poison_pulses_but_never_kills() {
+        let mut effects = Vec::new();
+        add(&mut effects, effect_id::POISON, 0, 26);
+        let mut health = Health {
+            current: 1.5,
+            max: 20.0,
+        };
+        let first = tick(&mut effects, &mut health, true);
+        assert!(!first.health_changed);
+        let second = tick(&mut effects, &mut health, true);
+        assert!(second.hurt);
+        assert_eq!(health.current, 1.0);
+        for _ in 0..23 {
+            tick(&mut effects, &mut health, true);
+        }
+        let last = tick(&mut effects, &mut health, true);
+        assert_eq!(health.current, 1.0);
+        assert_eq!(last.expired, vec![effect_id::POISON]);
+        assert!(effects.is_empty());
+    }
+
+    #[test]
+    fn regeneration_stops_at_max_health() {
+        let mut effects = Vec::new();
+        add(&mut effects, effect_id::REGENERATION, 5, 10);
+        let mut health = Health {
+            current: 18.5,
+            max: 20.0,
+        };
+        tick(&mut effects, &mut health, false);
+        tick(&mut effects, &mut health, false);
+        assert_eq!(health.current, 20.0);
+    }
+}
@@ -0,0 +1,130 @@
+//! Fall damage: landing surfaces, status effects and enchantment modifiers.
+
+/// Fall distance (blocks) a player can drop without taking damage.
+pub const SAFE_FALL_DISTANCE: f32 = 3.0;
+
+/// Fraction of fall damage taken when landing on a hay bale.
+pub const HAY_BALE_DAMAGE_FACTOR: f32 = 0.2;
+
+/// Minimum impact speed (blocks/tick) for a slime block to bounce the player.
+pub const MIN_BOUNCE_SPEED: f32 = 0.1;
+
+/// What a falling player lands on or in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LandingSurface {
+    /// Any ordinary block.
+    Solid,
+    /// Water cancels the fall.
+    Water,
+    /// Cobwebs cancel the fall.
+    Cobweb,
+    /// Hay bales absorb 80% of the damage.
+    HayBale,
+    /// Slime blocks cancel the damage and bounce the player (unless sneaking).
+    Slime,
+    /// Ladders, vines and scaffolding catch the player and cancel the fall.
+    Climbable,
+}
+
+/// Player state that changes how much a fall hurts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FallModifiers {
+    /// Jump Boost amplifier, if the effect is active.
+    pub jump_boost: Option<i32>,
+    /// Whether Slow Falling is active.
+    pub slow_falling: bool,
+    /// Feather Falling reduction from the boots (0.0-1.0).
+    pub feather_falling: f32,
+    /// Whether the player is sneaking on landing.
+    pub sneaking: bool,
+}
+
+/// Damage taken after falling `fall_distance` blocks onto `surface`.
+pub fn fall_damage(fall_distance: f32, surface: LandingSurface, modifiers: &FallModifiers) -> f32 {
+    if modifiers.slow_falling {
+        return 0.0;
+    }
+    match surface {
+        LandingSurface::Water | LandingSurface::Cobweb | LandingSurface::Climbable => return 0.0,
+        LandingSurface::Slime if !modifiers.sneaking => return 0.0,
+        _ => {}
+    }
+
+    // Jump Boost raises the safe distance by one block per level
+    let safe = SAFE_FALL_DISTANCE + modifiers.jump_boost.map_or(0.0, |amp| (amp + 1) as f32);
+    let mut damage = (fall_distance - safe).ceil().max(0.0);
+    if surface == LandingSurface::HayBale {
+        damage *= HAY_BALE_DAMAGE_FACTOR;
+    }
+    damage * (1.0 - modifiers.feather_falling.clamp(0.0, 1.0))
+}
+
+/// Upward speed given by a slime block to a player landing at `impact_speed`
+/// (blocks/tick, positive = downward). Sneaking players do not bounce.
+pub fn slime_bounce(impact_speed: f32, sneaking: bool) -> Option<f32> {
+    if sneaking || impact_speed < MIN_BOUNCE_SPEED {
+        None
+    } else {
+        Some(impact_speed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_fall_damage() {
+        let m = FallModifiers::default();
+        assert_eq!(fall_damage(3.0, LandingSurface::Solid, &m), 0.0);
+        assert_eq!(fall_damage(10.0, LandingSurface::Solid, &m), 7.0);
+        assert_eq!(fall_damage(4.2, LandingSurface::Solid, &m), 2.0);
+    }
+
+    #[test]
+    fn soft_landings() {
+        let m = FallModifiers::default();
+        assert_eq!(fall_damage(30.0, LandingSurface::Water, &m), 0.0);
+        assert_eq!(fall_damage(30.0, LandingSurface::Cobweb, &m), 0.0);
+        assert_eq!(fall_damage(30.0, LandingSurface::Climbable, &m), 0.0);
+        assert_eq!(fall_damage(30.0, LandingSurface::Slime, &m), 0.0);
+        assert!((fall_damage(13.0, LandingSurface::HayBale, &m) - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn sneaking_on_slime_hurts() {
+        let m = FallModifiers {
+            sneaking: true,
+            ..Default::default()
+        };
+        assert_eq!(fall_damage(10.0, LandingSurface::Slime, &m), 7.0);
+    }
+
+    #[test]
+    fn effects_and_enchantments() {
+        let slow = FallModifiers {
+            slow_falling: true,
+            ..Default::default()
+        };
+        assert_eq!(fall_damage(50.0, LandingSurface::Solid, &slow), 0.0);
+
+        let jump = FallModifiers {
+            jump_boost: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(fall_damage(10.0, LandingSurface::Solid, &jump), 5.0);
+
+        let feather = FallModifiers {
+            feather_falling: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(fall_damage(10.0, LandingSurface::Solid, &feather), 3.5);
+    }
+
+    #[test]
+    fn slime_bounce_rules() {
+        assert_eq!(slime_bounce(0.8, false), Some(0.8));
+        assert_eq!(slime_bounce(0.8, true), None);
+        assert_eq!(slime_bounce(0.05, false), None);
+    }
+}
@@ -1,5 +1,22 @@
 //! Food nutrition data for Minecraft Bedrock items.
 
+use mc_rs_proto::packets::mob_effect::effect_id;
+
+/// Ticks it takes to eat most food items (1.6 seconds).
+pub const EAT_DURATION_TICKS: u64 = 32;
+
+/// Players cannot sprint at or below this food level.
+pub const SPRINT_FOOD_THRESHOLD: i32 = 6;
+
+/// Hunger and saturation restored by one cake slice.
+pub const CAKE_SLICE: FoodData = FoodData {
+    hunger: 2,
+    saturation: 0.4,
+};
+
+/// Number of slices in a whole cake.
+pub const CAKE_SLICES: usize = 7;
+
 /// Nutrition data for a food item.
 #[derive(Debug, Clone, Copy)]
 pub struct FoodData {
@@ -63,6 +80,93 @@ pub fn is_food(item_name: &str) -> bool {
     food_data(item_name).is_some()
 }
 
+/// Returns `true` if the item is a milk bucket, which clears all status effects.
+pub fn is_milk(item_name: &str) -> bool {
+    item_name.strip_prefix("minecraft:").unwrap_or(item_name) == "milk_bucket"
+}
+
+/// Returns `true` if the item can be eaten or drunk.
+pub fn is_consumable(item_name: &str) -> bool {
+    is_food(item_name) || is_milk(item_name)
+}
+
+/// A status effect that may be applied after eating a food item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FoodEffect {
+    pub effect_id: i32,
+    pub amplifier: i32,
+    pub duration_ticks: i32,
+    /// Probability (0.0-1.0) that the effect is applied.
+    pub chance: f32,
+}
+
+/// Returns the status effects a food item can apply when eaten.
+pub fn food_effects(item_name: &str) -> Vec<FoodEffect> {
+    let name = item_name.strip_prefix("minecraft:").unwrap_or(item_name);
+    let effect = |effect_id, amplifier, duration_ticks, chance| FoodEffect {
+        effect_id,
+        amplifier,
+        duration_ticks,
+        chance,
+    };
+    match name {
+        "rotten_flesh" => vec![effect(effect_id::HUNGER, 0, 600, 0.8)],
+        "chicken" => vec![effect(effect_id::HUNGER, 0, 600, 0.3)],
+        "spider_eye" => vec![effect(effect_id::POISON, 0, 100, 1.0)],
+        "poisonous_potato" => vec![effect(effect_id::POISON, 0, 100, 0.6)],
+        "pufferfish" => vec![
+            effect(effect_id::HUNGER, 2, 300, 1.0),
+            effect(effect_id::NAUSEA, 0, 300, 1.0),
+            effect(effect_id::POISON, 1, 1200, 1.0),
+        ],
+        "golden_apple" => vec![
+            effect(effect_id::REGENERATION, 1, 100, 1.0),
+            effect(effect_id::ABSORPTION, 0, 2400, 1.0),
+        ],
+        "enchanted_golden_apple" => vec![
+            effect(effect_id::REGENERATION, 1, 600, 1.0),
+            effect(effect_id::ABSORPTION, 3, 2400, 1.0),
+            effect(effect_id::RESISTANCE, 0, 6000, 1.0),
+            effect(effect_id::FIRE_RESISTANCE, 0, 6000, 1.0),
+        ],
+        _ => Vec::new(),
+    }
+}
+
+/// Ticks needed to finish eating a food item.
+pub fn eat_duration_ticks(item_name: &str) -> u64 {
+    let name = item_name.strip_prefix("minecraft:").unwrap_or(item_name);
+    match name {
+        "dried_kelp" => EAT_DURATION_TICKS / 2,
+        _ => EAT_DURATION_TICKS,
+    }
+}
+
+/// Returns `true` if the item can be eaten even with a full hunger bar.
+pub fn can_always_eat(item_name: &str) -> bool {
+    let name = item_name.strip_prefix("minecraft:").unwrap_or(item_name);
+    matches!(
+        name,
+        "golden_apple" | "enchanted_golden_apple" | "milk_bucket"
+    )
+}
+
+/// Returns the container item left behind after eating (e.g. a bowl after stew).
+pub fn leftover_item(item_name: &str) -> Option<&'static str> {
+    let name = item_name.strip_prefix("minecraft:").unwrap_or(item_name);
+    match name {
+        "mushroom_stew" | "rabbit_stew" | "beetroot_soup" => Some("minecraft:bowl"),
+        "honey_bottle" => Some("minecraft:glass_bottle"),
+        "milk_bucket" => Some("minecraft:bucket"),
+        _ => None,
+    }
+}
+
+/// Returns `true` if a player with this food level is allowed to sprint.
+pub fn can_sprint(food: i32) -> bool {
+    food > SPRINT_FOOD_THRESHOLD
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,4 +200,54 @@ mod tests {
         assert!(!is_food("minecraft:iron_ingot"));
         assert!(!is_food("minecraft:stick"));
     }
+
+    #[test]
+    fn food_poisoning_effects() {
+        let flesh = food_effects("minecraft:rotten_flesh");
+        assert_eq!(flesh.len(), 1);
+        assert_eq!(flesh[0].effect_id, effect_id::HUNGER);
+        assert!((flesh[0].chance - 0.8).abs() < 0.01);
+
+        let chicken = food_effects("minecraft:chicken");
+        assert_eq!(chicken[0].effect_id, effect_id::HUNGER);
+        assert!((chicken[0].chance - 0.3).abs() < 0.01);
+
+        assert!(food_effects("minecraft:cooked_chicken").is_empty());
+        assert_eq!(food_effects("minecraft:pufferfish").len(), 3);
+    }
+
+    #[test]
+    fn eat_duration_and_leftovers() {
+        assert_eq!(eat_duration_ticks("minecraft:bread"), EAT_DURATION_TICKS);
+        assert_eq!(eat_duration_ticks("minecraft:dried_kelp"), 16);
+        assert_eq!(
+            leftover_item("minecraft:mushroom_stew"),
+            Some("minecraft:bowl")
+        );
+        assert_eq!(leftover_item("minecraft:bread"), None);
+        assert!(can_always_eat("minecraft:golden_apple"));
+        assert!(!can_always_eat("minecraft:apple"));
+    }
+
+    #[test]
+    fn milk_is_drinkable_but_not_food() {
+        assert!(is_milk("minecraft:milk_bucket"));
+        assert!(!is_food("minecraft:milk_bucket"));
+        assert!(is_consumable("minecraft:milk_bucket"));
+        assert!(is_consumable("minecraft:bread"));
+        assert!(!is_consumable("minecraft:bucket"));
+        assert!(can_always_eat("minecraft:milk_bucket"));
+        assert_eq!(
+            leftover_item("minecraft:milk_bucket"),
+            Some("minecraft:bucket")
+        );
+    }
+
+    #[test]
+    fn sprint_threshold() {
+        assert!(can_sprint(20));
+        assert!(can_sprint(7));
+        assert!(!can_sprint(6));
+        assert!(!can_sprint(0));
+    }
 }
@@ -1,16 +1,24 @@
 //! ECS game world: bevy_ecs World, entity management, tick systems, and event bus.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicI64, Ordering};
 
 use bevy_ecs::prelude::*;
+use mc_rs_world::physics::{self, Surface};
+use rand::Rng;
 
 use crate::ai::brain::BehaviorList;
-use crate::ai::mob_behaviors;
-use crate::ai::spawning::{self, SpawnConfig};
+use crate::ai::spawning::{self, FlatSpawnTerrain, SpawnConfig, SpawnTerrain};
 use crate::ai::system::system_ai_tick;
+use crate::ai::{definitions, mob_behaviors};
+use crate::collision::{self, Body, CollisionGrid, VehicleKind};
 use crate::components::*;
-use crate::mob_registry::MobRegistry;
+use crate::effects::{self, ActiveEffect, EffectTick};
+use crate::fall;
+use crate::lightning::{lightning_conversion, lightning_event, LIGHTNING_DAMAGE, STRIKE_RADIUS};
+use crate::mob_registry::{MobCategory, MobRegistry};
+use crate::move_sync::{self, MoveUpdate, SentMove};
+use crate::sunlight;
 
 // ---------------------------------------------------------------------------
 // Resources
@@ -77,6 +85,8 @@ pub struct MobSnapshot {
     pub bb_width: f32,
     pub bb_height: f32,
     pub is_baby: bool,
+    pub on_fire: bool,
+    pub has_helmet: bool,
 }
 
 /// Events produced by the game world, consumed by the network layer.
@@ -94,7 +104,7 @@ pub enum GameEvent {
         bb_height: f32,
         is_baby: bool,
     },
-    /// A mob moved — broadcast MoveActorAbsolute.
+    /// A mob moved or turned enough to be seen — broadcast MoveActorAbsolute.
     MobMoved {
         runtime_id: u64,
         position: (f32, f32, f32),
@@ -102,6 +112,8 @@ pub enum GameEvent {
         yaw: f32,
         head_yaw: f32,
         on_ground: bool,
+        /// Moved too far to interpolate.
+        teleport: bool,
     },
     /// A mob was damaged — broadcast EntityEvent(hurt) + UpdateAttributes.
     MobHurt {
@@ -127,17 +139,44 @@ pub enum GameEvent {
     },
     /// A mob shows love particles (breeding).
     MobLoveParticles { runtime_id: u64 },
+    /// A minecart or boat rammed a player — send them SetEntityMotion.
+    PlayerRammed {
+        runtime_id: u64,
+        motion: (f32, f32, f32),
+    },
+    /// A mob's helmet wore out in the sun.
+    MobHelmetBroken { runtime_id: u64 },
+    /// A behavior pack entity event queued commands (`queue_command`) to run as the mob.
+    EntityCommands {
+        runtime_id: u64,
+        mob_type: String,
+        commands: Vec<String>,
+    },
 }
 
 // ---------------------------------------------------------------------------
 // GameWorld
 // ---------------------------------------------------------------------------
 
+/// A player's maximum (and starting) health.
+pub const PLAYER_MAX_HEALTH: f32 = 20.0;
+
+/// Height of a player's eyes above their feet; player positions are taken
+/// at eye level.
+const PLAYER_EYE_HEIGHT: f32 = 1.62;
+
+/// Invulnerability frames of a mob after taking damage, in ticks.
+const MOB_DAMAGE_COOLDOWN: u64 = 10;
+
 /// The ECS game world.
 pub struct GameWorld {
     pub world: World,
     pub mob_registry: MobRegistry,
     pub spawn_config: SpawnConfig,
+    /// Chunks mobs are simulated in; `None` simulates every mob.
+    simulated_chunks: Option<HashSet<(i32, i32)>>,
+    /// Whether entities push each other apart and vehicles ram.
+    entity_collision: bool,
 }
 
 impl GameWorld {
@@ -154,21 +193,104 @@ impl GameWorld {
             world,
             mob_registry: MobRegistry::new(),
             spawn_config: SpawnConfig::default(),
+            simulated_chunks: None,
+            entity_collision: true,
         }
     }
 
-    /// Run one game tick: AI, breeding, gravity, movement collection, dead cleanup, spawning.
+    /// Restrict mob AI and movement to mobs in `chunks` (those within
+    /// simulation distance of a player); the others stay where they are.
+    pub fn set_simulated_chunks(&mut self, chunks: HashSet<(i32, i32)>) {
+        self.simulated_chunks = Some(chunks);
+    }
+
+    /// Turn entity collision (pushing and vehicle ramming) on or off.
+    pub fn set_entity_collision(&mut self, enabled: bool) {
+        self.entity_collision = enabled;
+    }
+
+    /// Run one game tick: AI, breeding, status effects, gravity, collision, movement collection, dead cleanup, spawning.
     pub fn tick(&mut self) {
+        self.tick_with_terrain(&FlatSpawnTerrain);
+    }
+
+    /// Run one game tick, spawning mobs naturally in `terrain`.
+    pub fn tick_with_terrain(&mut self, terrain: &dyn SpawnTerrain) {
         self.world.resource_mut::<TickCounter>().0 += 1;
+        self.system_mark_dormant();
+        self.system_entity_definitions();
         system_ai_tick(&mut self.world);
         self.system_breeding_tick();
+        self.system_mob_effects();
+        self.system_sunlight();
         system_mob_gravity(&mut self.world);
+        self.system_entity_collision();
         system_collect_mob_moves(&mut self.world);
         system_cleanup_dead(&mut self.world);
-        spawning::system_natural_spawn(&mut self.world, &self.mob_registry, &self.spawn_config);
+        spawning::system_natural_spawn(
+            &mut self.world,
+            &self.mob_registry,
+            &self.spawn_config,
+            terrain,
+        );
         spawning::system_despawn_far_mobs(&mut self.world, &self.spawn_config);
     }
 
+    /// Mark the mobs outside the simulated chunks [`Dormant`], and wake the
+    /// others.
+    fn system_mark_dormant(&mut self) {
+        let changes: Vec<(Entity, bool)> = {
+            let mut q = self
+                .world
+                .query_filtered::<(Entity, &Position, Has<Dormant>), With<Mob>>();
+            q.iter(&self.world)
+                .filter_map(|(entity, pos, dormant)| {
+                    let chunk = ((pos.x.floor() as i32) >> 4, (pos.z.floor() as i32) >> 4);
+                    let simulated = self
+                        .simulated_chunks
+                        .as_ref()
+                        .is_none_or(|chunks| chunks.contains(&chunk));
+                    (simulated == dormant).then_some((entity, simulated))
+                })
+                .collect()
+        };
+        for (entity, simulated) in changes {
+            if simulated {
+                self.world.entity_mut(entity).remove::<Dormant>();
+            } else {
+                self.world.entity_mut(entity).insert(Dormant);
+            }
+        }
+    }
+
+    /// Data-driven mobs: fire component timers and perform due transformations.
+    fn system_entity_definitions(&mut self) {
+        for (entity, transformation) in definitions::system_component_timers(&mut self.world) {
+            let (runtime_id, pos) = match (
+                self.world.get::<EntityId>(entity),
+                self.world.get::<Position>(entity),
+            ) {
+                (Some(eid), Some(pos)) => (eid.runtime_id, *pos),
+                _ => continue,
+            };
+            self.remove_mob(runtime_id);
+            let event = transformation
+                .spawn_event
+                .as_deref()
+                .unwrap_or(definitions::SPAWNED_EVENT);
+            self.spawn_mob_with_event(&transformation.into, pos.x, pos.y, pos.z, event);
+        }
+    }
+
+    /// Fire a behavior pack entity event (e.g. `"minecraft:become_angry"`) on a mob.
+    /// Returns `false` if the mob doesn't exist or has no such event.
+    pub fn trigger_entity_event(&mut self, runtime_id: u64, event: &str) -> bool {
+        match self.find_mob_entity(runtime_id) {
+            Some(entity) => definitions::trigger(&mut self.world, entity, event),
+            None => false,
+        }
+    }
+
     /// Breeding system: pair in-love mobs, spawn babies, expire timers.
     fn system_breeding_tick(&mut self) {
         let current_tick = self.world.resource::<TickCounter>().0;
@@ -305,6 +427,18 @@ impl GameWorld {
 
     /// Spawn a mob entity. Returns `(unique_id, runtime_id)` or `None` if type unknown.
     pub fn spawn_mob(&mut self, type_id: &str, x: f32, y: f32, z: f32) -> Option<(i64, u64)> {
+        self.spawn_mob_with_event(type_id, x, y, z, definitions::SPAWNED_EVENT)
+    }
+
+    /// Spawn a mob, firing `spawn_event` if its type is data-driven.
+    fn spawn_mob_with_event(
+        &mut self,
+        type_id: &str,
+        x: f32,
+        y: f32,
+        z: f32,
+        spawn_event: &str,
+    ) -> Option<(i64, u64)> {
         let def = self.mob_registry.get(type_id)?.clone();
         let entity_id = self.world.resource::<EntityIdAllocator>().allocate();
         let runtime_id = entity_id as u64;
@@ -343,6 +477,13 @@ impl GameWorld {
                 LastAttacker(None),
                 MovementSpeed(def.movement_speed),
                 BehaviorList::new(mob_behaviors::create_behaviors(type_id)),
+                LastSentMove(SentMove {
+                    position: (x, y, z),
+                    pitch: 0.0,
+                    yaw: 0.0,
+                    head_yaw: 0.0,
+                    on_ground: false,
+                }),
             ))
             .id();
 
@@ -351,6 +492,11 @@ impl GameWorld {
             .0
             .insert(runtime_id, entity);
 
+        let is_baby = match self.mob_registry.behavior(type_id) {
+            Some(behavior) => definitions::attach(&mut self.world, entity, behavior, spawn_event),
+            None => false,
+        };
+
         self.world
             .resource_mut::<OutgoingEvents>()
             .events
@@ -363,7 +509,7 @@ impl GameWorld {
                 max_health: def.max_health,
                 bb_width: def.bb_width,
                 bb_height: def.bb_height,
-                is_baby: false,
+                is_baby,
             });
 
         Some((entity_id, runtime_id))
@@ -419,6 +565,13 @@ impl GameWorld {
             .0
             .insert(runtime_id, entity);
 
+        let is_baby = match self.mob_registry.behavior(type_id) {
+            Some(behavior) => {
+                definitions::attach(&mut self.world, entity, behavior, definitions::BORN_EVENT)
+            }
+            None => true,
+        };
+
         self.world
             .resource_mut::<OutgoingEvents>()
             .events
@@ -431,12 +584,57 @@ impl GameWorld {
                 max_health: def.max_health,
                 bb_width: def.bb_width,
                 bb_height: def.bb_height,
-                is_baby: true,
+                is_baby,
             });
 
         Some((entity_id, runtime_id))
     }
 
+    /// Respawn a mob loaded from disk with its rotation, health and age.
+    /// Returns `(unique_id, runtime_id)` or `None` if type unknown.
+    pub fn restore_mob(
+        &mut self,
+        type_id: &str,
+        (x, y, z): (f32, f32, f32),
+        (yaw, pitch): (f32, f32),
+        health: f32,
+        is_baby: bool,
+    ) -> Option<(i64, u64)> {
+        let (unique_id, runtime_id) = if is_baby {
+            self.spawn_baby_mob(type_id, x, y, z)?
+        } else {
+            self.spawn_mob(type_id, x, y, z)?
+        };
+        let entity = self.find_mob_entity(runtime_id)?;
+        let mut entity_mut = self.world.entity_mut(entity);
+        let health = match entity_mut.get_mut::<Health>() {
+            Some(mut h) => {
+                h.current = health.clamp(1.0, h.max);
+                h.current
+            }
+            None => health,
+        };
+        if let Some(mut rot) = entity_mut.get_mut::<Rotation>() {
+            rot.yaw = yaw;
+            rot.head_yaw = yaw;
+            rot.pitch = pitch;
+        }
+
+        // The spawn event was queued with full health
+        let mut events = self.world.resource_mut::<OutgoingEvents>();
+        if let Some(GameEvent::MobSpawned {
+            runtime_id: rid,
+            health: spawned_health,
+            ..
+        }) = events.events.last_mut()
+        {
+            if *rid == runtime_id {
+                *spawned_health = health;
+            }
+        }
+        Some((unique_id, runtime_id))
+    }
+
     /// Deal damage to a mob. Returns remaining health, or `None` if invulnerable or not found.
     ///
     /// `attacker_rid` is the runtime_id of the attacking entity (for XP attribution).
@@ -448,27 +646,7 @@ impl GameWorld {
         attacker_rid: Option<u64>,
     ) -> Option<f32> {
         let target = self.find_mob_entity(runtime_id)?;
-
-        // Invulnerability check (10 ticks)
-        if let Some(ldt) = self.world.get::<LastDamageTick>(target) {
-            if let Some(last) = ldt.0 {
-                if tick.saturating_sub(last) < 10 {
-                    return None;
-                }
-            }
-        }
-
-        // Apply damage
-        let new_health = {
-            let mut health = self.world.get_mut::<Health>(target)?;
-            health.current = (health.current - damage).max(0.0);
-            health.current
-        };
-
-        // Update last damage tick
-        if let Some(mut ldt) = self.world.get_mut::<LastDamageTick>(target) {
-            ldt.0 = Some(tick);
-        }
+        let new_health = self.damage_entity(target, damage, tick, MOB_DAMAGE_COOLDOWN)?;
 
         // Track attacker for XP attribution
         if attacker_rid.is_some() {
@@ -510,6 +688,420 @@ impl GameWorld {
         Some(new_health)
     }
 
+    // -----------------------------------------------------------------------
+    // Health, damage and status effects (players and mobs)
+    // -----------------------------------------------------------------------
+
+    /// Whether `entity` is still invulnerable at `tick`: it took damage less
+    /// than `cooldown` ticks before.
+    pub fn invulnerable(&self, entity: Entity, tick: u64, cooldown: u64) -> bool {
+        self.world
+            .get::<LastDamageTick>(entity)
+            .and_then(|ldt| ldt.0)
+            .is_some_and(|last| tick.saturating_sub(last) < cooldown)
+    }
+
+    /// Deal damage to a player or mob, starting its invulnerability frames.
+    /// Returns the remaining health, or `None` if invulnerable (see
+    /// [`invulnerable`](Self::invulnerable)) or without health.
+    pub fn damage_entity(
+        &mut self,
+        entity: Entity,
+        damage: f32,
+        tick: u64,
+        cooldown: u64,
+    ) -> Option<f32> {
+        if self.invulnerable(entity, tick, cooldown) {
+            return None;
+        }
+        let new_health = {
+            let mut health = self.world.get_mut::<Health>(entity)?;
+            health.current = (health.current - damage).max(0.0);
+            health.current
+        };
+        self.world
+            .entity_mut(entity)
+            .insert(LastDamageTick(Some(tick)));
+        Some(new_health)
+    }
+
+    /// Give a player or mob a status effect (see [`effects::add`]). Returns
+    /// whether it was added.
+    pub fn add_effect(
+        &mut self,
+        entity: Entity,
+        effect_id: i32,
+        amplifier: i32,
+        duration_ticks: i32,
+    ) -> bool {
+        let mut entity_mut = self.world.entity_mut(entity);
+        if !entity_mut.contains::<StatusEffects>() {
+            entity_mut.insert(StatusEffects::default());
+        }
+        let mut status = entity_mut
+            .get_mut::<StatusEffects>()
+            .expect("inserted above");
+        effects::add(&mut status.0, effect_id, amplifier, duration_ticks)
+    }
+
+    /// Remove a status effect. Returns whether it was active.
+    pub fn remove_effect(&mut self, entity: Entity, effect_id: i32) -> bool {
+        let Some(mut status) = self.world.get_mut::<StatusEffects>(entity) else {
+            return false;
+        };
+        let before = status.0.len();
+        status.0.retain(|e| e.effect_id != effect_id);
+        status.0.len() != before
+    }
+
+    /// Remove every status effect, returning the IDs of those that were active.
+    pub fn clear_effects(&mut self, entity: Entity) -> Vec<i32> {
+        match self.world.get_mut::<StatusEffects>(entity) {
+            Some(mut status) => status.0.drain(..).map(|e| e.effect_id).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Run one tick of a player's or mob's status effects (see
+    /// [`effects::tick`]); exhaustion from Hunger goes to its [`Hunger`].
+    pub fn tick_entity_effects(&mut self, entity: Entity, vulnerable: bool) -> EffectTick {
+        let mut entity_mut = self.world.entity_mut(entity);
+        let Some(mut status) = entity_mut.take::<StatusEffects>() else {
+            return EffectTick::default();
+        };
+        let result = match entity_mut.get_mut::<Health>() {
+            Some(mut health) => effects::tick(&mut status.0, &mut health, vulnerable),
+            None => EffectTick::default(),
+        };
+        entity_mut.insert(status);
+        if result.exhaustion > 0.0 {
+            if let Some(mut hunger) = entity_mut.get_mut::<Hunger>() {
+                hunger.exhaustion += result.exhaustion;
+            }
+        }
+        result
+    }
+
+    /// Tick the status effects of every mob that has some.
+    fn system_mob_effects(&mut self) {
+        let tick = self.current_tick();
+        let mobs: Vec<(Entity, u64)> = self
+            .world
+            .query_filtered::<(Entity, &EntityId), (With<Mob>, With<StatusEffects>, Without<Dead>)>(
+            )
+            .iter(&self.world)
+            .map(|(e, eid)| (e, eid.runtime_id))
+            .collect();
+        for (entity, runtime_id) in mobs {
+            if !self.tick_entity_effects(entity, true).health_changed {
+                continue;
+            }
+            let new_health = self.world.get::<Health>(entity).map_or(0.0, |h| h.current);
+            self.world
+                .resource_mut::<OutgoingEvents>()
+                .events
+                .push(GameEvent::MobHurt {
+                    runtime_id,
+                    new_health,
+                    tick,
+                });
+        }
+    }
+
+    /// Set undead mobs in sunlight on fire, or wear their helmet down
+    /// instead, and burn the mobs on fire once a second.
+    fn system_sunlight(&mut self) {
+        let tick = self.current_tick();
+        if tick.is_multiple_of(sunlight::FIRE_DAMAGE_INTERVAL as u64) {
+            self.wear_helmets_in_sunlight();
+        }
+
+        let exposed: Vec<Entity> = {
+            let mut q = self.world.query_filtered::<(Entity, &MobType), (
+                With<Mob>,
+                With<InSunlight>,
+                Without<Helmet>,
+                Without<Dead>,
+                Without<Dormant>,
+            )>();
+            q.iter(&self.world)
+                .filter(|(_, t)| sunlight::is_undead(&t.0))
+                .map(|(e, _)| e)
+                .collect()
+        };
+        for entity in exposed {
+            let ticks = self.world.get::<OnFire>(entity).map_or(0, |f| f.0);
+            self.world
+                .entity_mut(entity)
+                .insert(OnFire(ticks.max(sunlight::DAYLIGHT_FIRE_TICKS)));
+        }
+
+        let burning: Vec<(Entity, u64, u32)> = {
+            let mut q = self
+                .world
+                .query_filtered::<(Entity, &EntityId, &OnFire), (With<Mob>, Without<Dead>)>();
+            q.iter(&self.world)
+                .map(|(e, eid, f)| (e, eid.runtime_id, f.0))
+                .collect()
+        };
+        for (entity, runtime_id, ticks) in burning {
+            if ticks <= 1 {
+                self.world.entity_mut(entity).remove::<OnFire>();
+            } else {
+                self.world.entity_mut(entity).insert(OnFire(ticks - 1));
+            }
+            if tick.is_multiple_of(sunlight::FIRE_DAMAGE_INTERVAL as u64) {
+                self.damage_mob(runtime_id, sunlight::FIRE_DAMAGE, tick, None);
+            }
+        }
+    }
+
+    /// Wear down the helmets of undead mobs in sunlight, taking off the ones
+    /// that break.
+    fn wear_helmets_in_sunlight(&mut self) {
+        let helmets: Vec<(Entity, u64, u32)> = {
+            let mut q = self.world.query_filtered::<(Entity, &EntityId, &MobType, &Helmet), (
+                With<Mob>,
+                With<InSunlight>,
+                Without<Dead>,
+                Without<Dormant>,
+            )>();
+            q.iter(&self.world)
+                .filter(|(_, _, t, _)| sunlight::is_undead(&t.0))
+                .map(|(e, eid, _, h)| (e, eid.runtime_id, h.0))
+                .collect()
+        };
+        let mut rng = rand::thread_rng();
+        for (entity, runtime_id, durability) in helmets {
+            let left = durability.saturating_sub(sunlight::helmet_wear(rng.gen()));
+            if left > 0 {
+                self.world.entity_mut(entity).insert(Helmet(left));
+                continue;
+            }
+            self.world.entity_mut(entity).remove::<Helmet>();
+            self.world
+                .resource_mut::<OutgoingEvents>()
+                .events
+                .push(GameEvent::MobHelmetBroken { runtime_id });
+        }
+    }
+
+    /// The ECS entity of a player.
+    pub fn player_entity(&self, unique_id: i64) -> Option<Entity> {
+        self.world
+            .resource::<PlayerIndex>()
+            .0
+            .get(&unique_id)
+            .copied()
+    }
+
+    /// A component of a player's entity, e.g. its [`Hunger`].
+    pub fn player<C: Component>(&self, unique_id: i64) -> Option<&C> {
+        self.world.get::<C>(self.player_entity(unique_id)?)
+    }
+
+    /// Mutable access to a component of a player's entity.
+    pub fn player_mut<C: Component>(&mut self, unique_id: i64) -> Option<Mut<'_, C>> {
+        let entity = self.player_entity(unique_id)?;
+        self.world.get_mut::<C>(entity)
+    }
+
+    /// A player's health, 0 if it has no entity.
+    pub fn player_health(&self, unique_id: i64) -> f32 {
+        self.player::<Health>(unique_id).map_or(0.0, |h| h.current)
+    }
+
+    /// Set a player's health, clamped to its maximum. Returns the new health.
+    pub fn set_player_health(&mut self, unique_id: i64, health: f32) -> f32 {
+        match self.player_mut::<Health>(unique_id) {
+            Some(mut h) => {
+                h.current = health.clamp(0.0, h.max);
+                h.current
+            }
+            None => 0.0,
+        }
+    }
+
+    /// A player's active status effects.
+    pub fn player_effects(&self, unique_id: i64) -> &[ActiveEffect] {
+        self.player::<StatusEffects>(unique_id)
+            .map_or(&[], |s| s.0.as_slice())
+    }
+
+    /// Deal damage to a player (see [`damage_entity`](Self::damage_entity)).
+    pub fn damage_player(
+        &mut self,
+        unique_id: i64,
+        damage: f32,
+        tick: u64,
+        cooldown: u64,
+    ) -> Option<f32> {
+        let entity = self.player_entity(unique_id)?;
+        self.damage_entity(entity, damage, tick, cooldown)
+    }
+
+    /// Full health and hunger, no effects and no invulnerability frames, as
+    /// after respawning.
+    pub fn reset_player_vitals(&mut self, unique_id: i64) {
+        let Some(entity) = self.player_entity(unique_id) else {
+            return;
+        };
+        let mut entity_mut = self.world.entity_mut(entity);
+        if let Some(mut health) = entity_mut.get_mut::<Health>() {
+            health.current = health.max;
+        }
+        entity_mut.insert((
+            LastDamageTick(None),
+            StatusEffects::default(),
+            Hunger::default(),
+        ));
+    }
+
+    /// Lightning strikes at a point: mobs within [`STRIKE_RADIUS`] that react
+    /// to lightning convert (through their definition's event, or into the
+    /// vanilla result if that mob type is registered); the others take
+    /// [`LIGHTNING_DAMAGE`]. Returns the runtime IDs of the mobs hit.
+    pub fn strike_lightning(&mut self, x: f32, y: f32, z: f32, tick: u64) -> Vec<u64> {
+        let hit: Vec<(u64, String, Position)> = {
+            let mut q = self
+                .world
+                .query_filtered::<(&EntityId, &MobType, &Position), (With<Mob>, Without<Dead>)>();
+            q.iter(&self.world)
+                .filter(|(_, _, p)| {
+                    let (dx, dy, dz) = (p.x - x, p.y - y, p.z - z);
+                    (dx * dx + dy * dy + dz * dz).sqrt() <= STRIKE_RADIUS
+                })
+                .map(|(eid, t, p)| (eid.runtime_id, t.0.clone(), *p))
+                .collect()
+        };
+
+        for (runtime_id, mob_type, pos) in &hit {
+            if let Some(event) = lightning_event(mob_type) {
+                if self.trigger_entity_event(*runtime_id, event) {
+                    continue;
+                }
+            }
+            if let Some(into) = lightning_conversion(mob_type) {
+                if self.mob_registry.get(into).is_some() {
+                    self.remove_mob(*runtime_id);
+                    self.spawn_mob(into, pos.x, pos.y, pos.z);
+                    continue;
+                }
+            }
+            self.damage_mob(*runtime_id, LIGHTNING_DAMAGE, tick, None);
+        }
+        hit.into_iter().map(|(rid, _, _)| rid).collect()
+    }
+
+    /// Push overlapping mobs apart and away from players, and let minecarts
+    /// and boats moving fast knock back what they run into. Players are
+    /// only rammed, never pushed: their clients keep them out of other
+    /// entities already.
+    fn system_entity_collision(&mut self) {
+        if !self.entity_collision {
+            return;
+        }
+
+        enum Owner {
+            /// A mob, with its velocity if it is a vehicle.
+            Mob(Entity, Option<(f32, f32)>),
+            Player(u64),
+        }
+        let mut owners = Vec::new();
+        let mut bodies = Vec::new();
+        let mut mobs = self.world.query_filtered::<(
+            Entity,
+            &Position,
+            &Velocity,
+            &BoundingBox,
+            &MobType,
+        ), (With<Mob>, Without<Dead>, Without<Dormant>)>();
+        for (entity, pos, vel, bb, mob_type) in mobs.iter(&self.world) {
+            let vehicle = VehicleKind::from_type(&mob_type.0).map(|_| (vel.x, vel.z));
+            owners.push(Owner::Mob(entity, vehicle));
+            bodies.push(Body {
+                x: pos.x,
+                y: pos.y,
+                z: pos.z,
+                width: bb.width,
+                height: bb.height,
+            });
+        }
+        let mut players = self.world.query_filtered::<(&EntityId, &Position), (
+            With<Player>,
+            Without<Dead>,
+            Without<IgnoredByMobs>,
+        )>();
+        for (eid, pos) in players.iter(&self.world) {
+            owners.push(Owner::Player(eid.runtime_id));
+            bodies.push(Body {
+                x: pos.x,
+                y: pos.y - PLAYER_EYE_HEIGHT,
+                z: pos.z,
+                width: collision::PLAYER_WIDTH,
+                height: collision::PLAYER_HEIGHT,
+            });
+        }
+        if bodies.len() < 2 {
+            return;
+        }
+
+        let ramming = |i: usize| match owners[i] {
+            Owner::Mob(_, Some((vx, vz))) => collision::ram(vx, vz),
+            _ => None,
+        };
+        let mut pushes = vec![(0.0, 0.0); bodies.len()];
+        let mut knocks: Vec<Option<(f32, f32, f32)>> = vec![None; bodies.len()];
+        let mut rammed = vec![false; bodies.len()];
+        for (i, j) in CollisionGrid::build(&bodies).overlapping_pairs(&bodies) {
+            if let Some(knock) = ramming(i) {
+                knocks[j] = Some(knock);
+                rammed[i] = true;
+            } else if let Some(knock) = ramming(j) {
+                knocks[i] = Some(knock);
+                rammed[j] = true;
+            } else if let Some((px, pz)) = collision::soft_push(&bodies[i], &bodies[j]) {
+                pushes[i].0 -= px;
+                pushes[i].1 -= pz;
+                pushes[j].0 += px;
+                pushes[j].1 += pz;
+            }
+        }
+
+        let mut events = Vec::new();
+        for (i, owner) in owners.iter().enumerate() {
+            match *owner {
+                Owner::Mob(entity, _) => {
+                    let (px, pz) = pushes[i];
+                    if px != 0.0 || pz != 0.0 {
+                        if let Some(mut pos) = self.world.get_mut::<Position>(entity) {
+                            pos.x += px;
+                            pos.z += pz;
+                        }
+                    }
+                    let Some(mut vel) = self.world.get_mut::<Velocity>(entity) else {
+                        continue;
+                    };
+                    if let Some((vx, vy, vz)) = knocks[i] {
+                        (vel.x, vel.y, vel.z) = (vx, vy, vz);
+                    } else if rammed[i] {
+                        vel.x *= collision::RAM_SLOWDOWN;
+                        vel.z *= collision::RAM_SLOWDOWN;
+                    }
+                }
+                Owner::Player(runtime_id) => {
+                    if let Some(motion) = knocks[i] {
+                        events.push(GameEvent::PlayerRammed { runtime_id, motion });
+                    }
+                }
+            }
+        }
+        self.world
+            .resource_mut::<OutgoingEvents>()
+            .events
+            .extend(events);
+    }
+
     /// Apply knockback velocity to a mob.
     pub fn apply_knockback(&mut self, runtime_id: u64, vx: f32, vy: f32, vz: f32) {
         if let Some(entity) = self.find_mob_entity(runtime_id) {
@@ -521,6 +1113,56 @@ impl GameWorld {
         }
     }
 
+    /// Put a mob in a bubble column (`Some(drag_down)`) or take it out
+    /// (`None`), as found in the blocks around it.
+    pub fn set_mob_bubble_column(&mut self, runtime_id: u64, column: Option<bool>) {
+        let Some(entity) = self.find_mob_entity(runtime_id) else {
+            return;
+        };
+        let mut entity = self.world.entity_mut(entity);
+        match column {
+            Some(drag_down) => {
+                entity.insert(InBubbleColumn { drag_down });
+            }
+            None => {
+                entity.remove::<InBubbleColumn>();
+            }
+        }
+    }
+
+    /// Set the surface a mob is over, as found in the blocks below it.
+    pub fn set_mob_surface(&mut self, runtime_id: u64, surface: Surface) {
+        let Some(entity) = self.find_mob_entity(runtime_id) else {
+            return;
+        };
+        let mut entity = self.world.entity_mut(entity);
+        if surface == Surface::Normal {
+            entity.remove::<OnSurface>();
+        } else {
+            entity.insert(OnSurface(surface));
+        }
+    }
+
+    /// Mark a mob as standing in sunlight or not, as found in the world
+    /// around it.
+    pub fn set_mob_in_sunlight(&mut self, runtime_id: u64, in_sunlight: bool) {
+        let Some(entity) = self.find_mob_entity(runtime_id) else {
+            return;
+        };
+        let mut entity = self.world.entity_mut(entity);
+        if in_sunlight {
+            entity.insert(InSunlight);
+        } else {
+            entity.remove::<InSunlight>();
+        }
+    }
+
+    /// Whether a mob wears a helmet.
+    pub fn mob_has_helmet(&mut self, runtime_id: u64) -> bool {
+        self.find_mob_entity(runtime_id)
+            .is_some_and(|entity| self.world.get::<Helmet>(entity).is_some())
+    }
+
     /// Check if a runtime_id belongs to a mob in the ECS.
     pub fn is_mob(&mut self, runtime_id: u64) -> bool {
         self.find_mob_entity(runtime_id).is_some()
@@ -533,6 +1175,26 @@ impl GameWorld {
         Some((pos.x, pos.y, pos.z))
     }
 
+    /// Get a mob's current health by runtime_id.
+    pub fn mob_health(&mut self, runtime_id: u64) -> Option<f32> {
+        let entity = self.find_mob_entity(runtime_id)?;
+        self.world.get::<Health>(entity).map(|h| h.current)
+    }
+
+    /// Get a mob's velocity by runtime_id.
+    pub fn mob_velocity(&mut self, runtime_id: u64) -> Option<(f32, f32, f32)> {
+        let entity = self.find_mob_entity(runtime_id)?;
+        let vel = self.world.get::<Velocity>(entity)?;
+        Some((vel.x, vel.y, vel.z))
+    }
+
+    /// Get a mob's bounding box `(width, height)` by runtime_id.
+    pub fn mob_bounding_box(&mut self, runtime_id: u64) -> Option<(f32, f32)> {
+        let entity = self.find_mob_entity(runtime_id)?;
+        let bb = self.world.get::<BoundingBox>(entity)?;
+        Some((bb.width, bb.height))
+    }
+
     /// Remove a mob by runtime_id. Returns `true` if found and removed.
     pub fn remove_mob(&mut self, runtime_id: u64) -> bool {
         if let Some(entity) = self.find_mob_entity(runtime_id) {
@@ -553,6 +1215,29 @@ impl GameWorld {
         }
     }
 
+    /// Remove every hostile mob and raider, e.g. when the difficulty goes to
+    /// Peaceful.
+    /// Returns how many were removed.
+    pub fn remove_hostile_mobs(&mut self) -> usize {
+        let hostile: Vec<u64> = {
+            let mut q = self
+                .world
+                .query_filtered::<(&EntityId, &MobType), (With<Mob>, Without<Dead>)>();
+            q.iter(&self.world)
+                .filter(|(_, t)| {
+                    self.mob_registry
+                        .get(&t.0)
+                        .is_some_and(|d| d.category != MobCategory::Passive)
+                })
+                .map(|(eid, _)| eid.runtime_id)
+                .collect()
+        };
+        for &runtime_id in &hostile {
+            self.remove_mob(runtime_id);
+        }
+        hostile.len()
+    }
+
     /// Get snapshots of all alive mobs (for sending to new players).
     pub fn all_mobs(&mut self) -> Vec<MobSnapshot> {
         let mut result = Vec::new();
@@ -564,8 +1249,12 @@ impl GameWorld {
             &MobType,
             &BoundingBox,
             Option<&Baby>,
+            Has<OnFire>,
+            Has<Helmet>,
         ), (With<Mob>, Without<Dead>)>();
-        for (eid, pos, rot, health, mob_type, bb, baby) in query.iter(&self.world) {
+        for (eid, pos, rot, health, mob_type, bb, baby, on_fire, has_helmet) in
+            query.iter(&self.world)
+        {
             result.push(MobSnapshot {
                 unique_id: eid.unique_id,
                 runtime_id: eid.runtime_id,
@@ -579,6 +1268,8 @@ impl GameWorld {
                 bb_width: bb.width,
                 bb_height: bb.height,
                 is_baby: baby.is_some(),
+                on_fire,
+                has_helmet,
             });
         }
         result
@@ -624,9 +1315,13 @@ impl GameWorld {
                     z: position.2,
                 },
                 Health {
-                    current: 20.0,
-                    max: 20.0,
+                    current: PLAYER_MAX_HEALTH,
+                    max: PLAYER_MAX_HEALTH,
                 },
+                LastDamageTick(None),
+                StatusEffects::default(),
+                Hunger::default(),
+                Experience::default(),
                 Player,
                 NetworkAddr(addr),
             ))
@@ -649,6 +1344,26 @@ impl GameWorld {
         }
     }
 
+    /// Hide a player from mobs (or show them again): hidden players are
+    /// never targeted, and mobs chasing them give up.
+    pub fn set_player_ignored_by_mobs(&mut self, unique_id: i64, ignored: bool) {
+        let entity = match self
+            .world
+            .resource::<PlayerIndex>()
+            .0
+            .get(&unique_id)
+            .copied()
+        {
+            Some(e) => e,
+            None => return,
+        };
+        if ignored {
+            self.world.entity_mut(entity).insert(IgnoredByMobs);
+        } else {
+            self.world.entity_mut(entity).remove::<IgnoredByMobs>();
+        }
+    }
+
     /// Update the held item name for a player ECS mirror entity.
     pub fn update_player_held_item(&mut self, unique_id: i64, item_name: String) {
         let entity = match self
@@ -720,7 +1435,7 @@ impl GameWorld {
     }
 
     /// Find a mob entity by runtime_id (O(1) via MobIndex).
-    fn find_mob_entity(&mut self, runtime_id: u64) -> Option<Entity> {
+    pub(crate) fn find_mob_entity(&mut self, runtime_id: u64) -> Option<Entity> {
         self.world
             .resource::<MobIndex>()
             .0
@@ -733,56 +1448,93 @@ impl GameWorld {
 // Systems (manual, called by GameWorld::tick)
 // ---------------------------------------------------------------------------
 
-/// Apply gravity to mobs not on the ground.
+/// Apply gravity to mobs not on the ground, or the push of the bubble
+/// column they are in.
 fn system_mob_gravity(world: &mut World) {
     const GRAVITY: f32 = 0.08;
     const FLAT_FLOOR_Y: f32 = 4.0; // feet position on flat world
 
-    let mut query =
-        world.query_filtered::<(&mut Position, &mut Velocity, &mut OnGround), (With<Mob>, Without<Dead>)>();
+    let mut query = world.query_filtered::<(
+        &mut Position,
+        &mut Velocity,
+        &mut OnGround,
+        Option<&InBubbleColumn>,
+        Option<&OnSurface>,
+    ), (With<Mob>, Without<Dead>, Without<Dormant>)>();
 
     // SAFETY: we need to iterate mutably; using `iter_mut` on the world.
-    for (mut pos, mut vel, mut on_ground) in query.iter_mut(world) {
-        vel.y -= GRAVITY;
-        vel.y *= 0.98; // air drag
+    for (mut pos, mut vel, mut on_ground, column, surface) in query.iter_mut(world) {
+        let surface = surface.map_or(Surface::Normal, |s| s.0);
+        // Soul sand and ice only matter to mobs standing on them
+        let ground = if on_ground.0 {
+            surface
+        } else {
+            Surface::Normal
+        };
 
-        pos.x += vel.x;
+        if let Some(column) = column {
+            vel.y = physics::bubble_column_velocity(vel.y, column.drag_down);
+        } else {
+            vel.y -= GRAVITY;
+            vel.y *= 0.98; // air drag
+        }
+
+        pos.x += vel.x * ground.speed_factor();
         pos.y += vel.y;
-        pos.z += vel.z;
+        pos.z += vel.z * ground.speed_factor();
 
-        // Horizontal drag
-        vel.x *= 0.91;
-        vel.z *= 0.91;
+        // Horizontal drag, less of it on slippery blocks
+        vel.x *= ground.horizontal_drag();
+        vel.z *= ground.horizontal_drag();
 
-        // Simple floor collision (flat world)
+        // Simple floor collision (flat world). Slime blocks bounce mobs
+        // back up as fast as they fell.
         if pos.y <= FLAT_FLOOR_Y {
             pos.y = FLAT_FLOOR_Y;
-            vel.y = 0.0;
-            on_ground.0 = true;
+            let bounce = fall::slime_bounce(-vel.y, false).filter(|_| surface == Surface::Slime);
+            vel.y = bounce.unwrap_or(0.0);
+            on_ground.0 = bounce.is_none();
         } else {
             on_ground.0 = false;
         }
     }
 }
 
-/// Collect position changes and emit MobMoved events.
+/// Emit MobMoved events for the mobs that moved or turned enough since
+/// their last one.
 fn system_collect_mob_moves(world: &mut World) {
     let mut moves = Vec::new();
 
-    let mut query = world
-        .query_filtered::<(&EntityId, &Position, &Rotation, &Velocity, &OnGround), (With<Mob>, Without<Dead>)>();
-
-    for (eid, pos, rot, vel, on_ground) in query.iter(world) {
-        if vel.x.abs() > 0.001 || vel.y.abs() > 0.001 || vel.z.abs() > 0.001 {
-            moves.push(GameEvent::MobMoved {
-                runtime_id: eid.runtime_id,
-                position: (pos.x, pos.y, pos.z),
-                pitch: rot.pitch,
-                yaw: rot.yaw,
-                head_yaw: rot.head_yaw,
-                on_ground: on_ground.0,
-            });
+    let mut query = world.query_filtered::<(
+        &EntityId,
+        &Position,
+        &Rotation,
+        &OnGround,
+        &mut LastSentMove,
+    ), (With<Mob>, Without<Dead>)>();
+
+    for (eid, pos, rot, on_ground, mut last) in query.iter_mut(world) {
+        let next = SentMove {
+            position: (pos.x, pos.y, pos.z),
+            pitch: rot.pitch,
+            yaw: rot.yaw,
+            head_yaw: rot.head_yaw,
+            on_ground: on_ground.0,
+        };
+        let update = move_sync::classify(Some(&last.0), &next);
+        if update == MoveUpdate::Skip {
+            continue;
         }
+        last.0 = next;
+        moves.push(GameEvent::MobMoved {
+            runtime_id: eid.runtime_id,
+            position: next.position,
+            pitch: next.pitch,
+            yaw: next.yaw,
+            head_yaw: next.head_yaw,
+            on_ground: next.on_ground,
+            teleport: update == MoveUpdate::Teleport,
+        });
     }
 
     world.resource_mut::<OutgoingEvents>().events.extend(moves);
@@ -828,12 +1580,131 @@ mod tests {
         assert_eq!(rid2, 101);
     }
 
+    #[test]
+    fn lightning_damages_nearby_mobs() {
+        let mut gw = GameWorld::new(1);
+        let (_, near) = gw.spawn_mob("minecraft:zombie", 1.0, 4.0, 0.0).unwrap();
+        let (_, far) = gw.spawn_mob("minecraft:cow", 10.0, 4.0, 0.0).unwrap();
+        assert_eq!(gw.strike_lightning(0.0, 4.0, 0.0, 100), vec![near]);
+        let health = |gw: &mut GameWorld, rid| {
+            gw.all_mobs()
+                .into_iter()
+                .find(|m| m.runtime_id == rid)
+                .map(|m| m.health)
+        };
+        assert_eq!(health(&mut gw, near), Some(20.0 - LIGHTNING_DAMAGE));
+        assert_eq!(health(&mut gw, far), Some(10.0));
+    }
+
+    #[test]
+    fn lightning_converts_pigs_when_target_registered() {
+        let mut gw = GameWorld::new(1);
+        // Without a zombie pigman type the pig is just hurt
+        let (_, pig) = gw.spawn_mob("minecraft:pig", 0.0, 4.0, 0.0).unwrap();
+        gw.strike_lightning(0.0, 4.0, 0.0, 100);
+        assert!(gw.is_mob(pig));
+
+        let mut def = gw.mob_registry.get("minecraft:zombie").unwrap().clone();
+        def.type_id = "minecraft:zombie_pigman".into();
+        gw.mob_registry.register_mob(def);
+        gw.strike_lightning(0.0, 4.0, 0.0, 200);
+        assert!(!gw.is_mob(pig));
+        let types: Vec<String> = gw.all_mobs().into_iter().map(|m| m.mob_type).collect();
+        assert_eq!(types, vec!["minecraft:zombie_pigman".to_string()]);
+    }
+
+    #[test]
+    fn undead_burn_in_sunlight_unless_helmeted() {
+        let mut gw = GameWorld::new(1);
+        // No natural spawns while the test ticks
+        gw.spawn_config.spawn_interval = u64::MAX;
+        let (_, zombie) = gw.spawn_mob("minecraft:zombie", 0.0, 4.0, 0.0).unwrap();
+        let (_, helmeted) = gw.spawn_mob("minecraft:skeleton", 2.0, 4.0, 0.0).unwrap();
+        let (_, cow) = gw.spawn_mob("minecraft:cow", 4.0, 4.0, 0.0).unwrap();
+        let entity = gw.find_mob_entity(helmeted).unwrap();
+        gw.world
+            .entity_mut(entity)
+            .insert(Helmet(sunlight::HELMET_DURABILITY));
+        for rid in [zombie, helmeted, cow] {
+            gw.set_mob_in_sunlight(rid, true);
+        }
+        for _ in 0..sunlight::FIRE_DAMAGE_INTERVAL {
+            gw.tick();
+        }
+        let burning: Vec<u64> = gw
+            .all_mobs()
+            .into_iter()
+            .filter(|m| m.on_fire)
+            .map(|m| m.runtime_id)
+            .collect();
+        assert_eq!(burning, vec![zombie]);
+        assert_eq!(gw.mob_health(zombie), Some(20.0 - sunlight::FIRE_DAMAGE));
+        assert_eq!(gw.mob_health(cow), Some(10.0));
+
+        // Out of the sun, the fire burns out
+        gw.set_mob_in_sunlight(zombie, false);
+        for _ in 0..sunlight::DAYLIGHT_FIRE_TICKS {
+            gw.tick();
+        }
+        assert!(gw.all_mobs().iter().all(|m| !m.on_fire));
+    }
+
+    #[test]
+    fn remove_hostile_mobs_keeps_passive() {
+        let mut gw = GameWorld::new(1);
+        gw.spawn_mob("minecraft:zombie", 0.0, 4.0, 0.0).unwrap();
+        gw.spawn_mob("minecraft:skeleton", 2.0, 4.0, 0.0).unwrap();
+        gw.spawn_mob("minecraft:cow", 4.0, 4.0, 0.0).unwrap();
+        assert_eq!(gw.remove_hostile_mobs(), 2);
+        let types: Vec<String> = gw.all_mobs().into_iter().map(|m| m.mob_type).collect();
+        assert_eq!(types, vec!["minecraft:cow".to_string()]);
+    }
+
     #[test]
     fn spawn_unknown_none() {
         let mut gw = GameWorld::new(1);
         assert!(gw.spawn_mob("minecraft:enderman", 0.0, 0.0, 0.0).is_none());
     }
 
+    #[test]
+    fn restore_mob_keeps_state() {
+        let mut gw = GameWorld::new(1);
+        let (_, rid) = gw
+            .restore_mob(
+                "minecraft:zombie",
+                (1.0, 4.0, 2.0),
+                (90.0, 10.0),
+                7.5,
+                false,
+            )
+            .unwrap();
+        let events = gw.drain_events();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            GameEvent::MobSpawned { health, is_baby: false, .. } if *health == 7.5
+        )));
+        let mob = gw
+            .all_mobs()
+            .into_iter()
+            .find(|m| m.runtime_id == rid)
+            .unwrap();
+        assert_eq!(mob.health, 7.5);
+        assert_eq!(mob.yaw, 90.0);
+        assert_eq!(mob.pitch, 10.0);
+
+        let (_, baby) = gw
+            .restore_mob("minecraft:cow", (0.0, 4.0, 0.0), (0.0, 0.0), 50.0, true)
+            .unwrap();
+        assert!(gw.is_mob_baby(baby));
+        // Health is capped to the maximum
+        let mob = gw
+            .all_mobs()
+            .into_iter()
+            .find(|m| m.runtime_id == baby)
+            .unwrap();
+        assert_eq!(mob.health, mob.max_health);
+    }
+
     #[test]
     fn damage_reduces_health() {
         let mut gw = GameWorld::new(1);
@@ -875,6 +1746,58 @@ mod tests {
             .any(|e| matches!(e, GameEvent::MobDied { .. })));
     }
 
+    #[test]
+    fn mobs_outside_simulated_chunks_stay_put() {
+        let mut gw = GameWorld::new(1);
+        let (_, near) = gw.spawn_mob("minecraft:cow", 8.0, 10.0, 8.0).unwrap();
+        let (_, far) = gw.spawn_mob("minecraft:cow", 500.0, 10.0, 8.0).unwrap();
+        gw.set_simulated_chunks(HashSet::from([(0, 0)]));
+        for _ in 0..5 {
+            gw.tick();
+        }
+        assert!(gw.mob_position(near).unwrap().1 < 10.0);
+        assert_eq!(gw.mob_position(far), Some((500.0, 10.0, 8.0)));
+
+        // Falls once a player comes near
+        gw.set_simulated_chunks(HashSet::from([(0, 0), (31, 0)]));
+        gw.tick();
+        assert!(gw.mob_position(far).unwrap().1 < 10.0);
+    }
+
+    #[test]
+    fn players_share_mob_damage_and_effects() {
+        use mc_rs_proto::packets::mob_effect::effect_id;
+
+        let mut gw = GameWorld::new(1);
+        let addr: std::net::SocketAddr = "127.0.0.1:19132".parse().unwrap();
+        gw.spawn_player(7, 7, (0.0, 5.0, 0.0), addr);
+        let (_, rid) = gw.spawn_mob("minecraft:zombie", 0.0, 4.0, 0.0).unwrap();
+        gw.drain_events();
+
+        // Same invulnerability frames, with the player's own cooldown
+        assert_eq!(gw.damage_player(7, 4.0, 0, 10), Some(16.0));
+        assert_eq!(gw.damage_player(7, 4.0, 9, 10), None);
+        assert_eq!(gw.damage_player(7, 4.0, 10, 10), Some(12.0));
+
+        // Poison goes through the same effect system for both
+        let player = gw.player_entity(7).unwrap();
+        let mob = gw.find_mob_entity(rid).unwrap();
+        assert!(gw.add_effect(player, effect_id::POISON, 0, 25));
+        assert!(gw.add_effect(mob, effect_id::POISON, 0, 25));
+        assert!(gw.tick_entity_effects(player, true).hurt);
+        assert_eq!(gw.player_health(7), 11.0);
+        gw.tick();
+        assert!(gw.drain_events().iter().any(|e| matches!(
+            e,
+            GameEvent::MobHurt { runtime_id, new_health, .. } if *runtime_id == rid && *new_health == 19.0
+        )));
+
+        gw.reset_player_vitals(7);
+        assert_eq!(gw.player_health(7), PLAYER_MAX_HEALTH);
+        assert!(gw.player_effects(7).is_empty());
+        assert_eq!(gw.damage_player(7, 1.0, 11, 10), Some(19.0));
+    }
+
     #[test]
     fn gravity_falls_to_floor() {
         let mut gw = GameWorld::new(1);
@@ -893,6 +1816,113 @@ mod tests {
         assert!((mobs[0].position.1 - 4.0).abs() < 0.01);
     }
 
+    #[test]
+    fn overlapping_mobs_are_pushed_apart() {
+        let mut gw = GameWorld::new(1);
+        let (_, a) = gw.spawn_mob("minecraft:zombie", 0.0, 4.0, 0.0).unwrap();
+        let (_, b) = gw.spawn_mob("minecraft:zombie", 0.3, 4.0, 0.0).unwrap();
+        let addr: std::net::SocketAddr = "127.0.0.1:19132".parse().unwrap();
+        gw.spawn_player(7, 7, (0.3, 4.0 + PLAYER_EYE_HEIGHT, 0.5), addr);
+
+        gw.set_entity_collision(false);
+        gw.system_entity_collision();
+        assert_eq!(gw.mob_position(a).unwrap().0, 0.0);
+
+        gw.set_entity_collision(true);
+        gw.system_entity_collision();
+        assert!(gw.mob_position(a).unwrap().0 < 0.0);
+        assert!(gw.mob_position(b).unwrap().0 > 0.3);
+        // The player pushes the zombie next to it away, but stays put
+        assert!(gw.mob_position(b).unwrap().2 < 0.0);
+        assert_eq!(gw.player::<Position>(7).unwrap().z, 0.5);
+    }
+
+    #[test]
+    fn fast_minecarts_ram_players() {
+        let mut gw = GameWorld::new(1);
+        gw.mob_registry
+            .register_mob(crate::mob_registry::MobDefinition {
+                type_id: "minecraft:minecart".into(),
+                display_name: "Minecart".into(),
+                category: MobCategory::Passive,
+                max_health: 6.0,
+                attack_damage: 0.0,
+                movement_speed: 0.0,
+                bb_width: 0.98,
+                bb_height: 0.7,
+            });
+        let (_, cart) = gw.spawn_mob("minecraft:minecart", 0.0, 4.0, 0.0).unwrap();
+        let addr: std::net::SocketAddr = "127.0.0.1:19132".parse().unwrap();
+        gw.spawn_player(7, 7, (0.5, 4.0 + PLAYER_EYE_HEIGHT, 0.0), addr);
+        gw.drain_events();
+
+        // Too slow to ram: nothing happens to the player
+        gw.apply_knockback(cart, 0.1, 0.0, 0.0);
+        gw.system_entity_collision();
+        assert!(gw.drain_events().is_empty());
+
+        gw.apply_knockback(cart, 0.5, 0.0, 0.0);
+        gw.system_entity_collision();
+        let events = gw.drain_events();
+        assert!(matches!(
+            events[..],
+            [GameEvent::PlayerRammed { runtime_id: 7, motion: (vx, vy, _) }]
+                if vx > 0.5 && vy == collision::RAM_LIFT
+        ));
+        assert_eq!(
+            gw.mob_velocity(cart).unwrap().0,
+            0.5 * collision::RAM_SLOWDOWN
+        );
+    }
+
+    #[test]
+    fn bubble_columns_lift_and_drag_mobs() {
+        let mut gw = GameWorld::new(1);
+        let (_, rid) = gw.spawn_mob("minecraft:cow", 0.0, 4.0, 0.0).unwrap();
+        gw.set_mob_bubble_column(rid, Some(false));
+        for _ in 0..10 {
+            gw.tick();
+        }
+        let lifted = gw.mob_position(rid).unwrap().1;
+        assert!(lifted > 6.0, "lifted to {lifted}");
+
+        // Dragged back down to the floor, faster than it would fall
+        gw.set_mob_bubble_column(rid, Some(true));
+        for _ in 0..100 {
+            gw.tick();
+        }
+        assert!((gw.mob_position(rid).unwrap().1 - 4.0).abs() < 0.01);
+        assert_eq!(gw.mob_velocity(rid).unwrap().1, 0.0);
+    }
+
+    #[test]
+    fn surfaces_slow_slide_and_bounce_mobs() {
+        let mut gw = GameWorld::new(1);
+        // How far a mob pushed along the ground coasts in 10 ticks
+        let mut coast = |surface| {
+            let (_, rid) = gw.spawn_mob("minecraft:cow", 0.0, 4.0, 0.0).unwrap();
+            gw.set_mob_surface(rid, surface);
+            gw.apply_knockback(rid, 0.5, 0.0, 0.0);
+            for _ in 0..10 {
+                system_mob_gravity(&mut gw.world);
+            }
+            gw.mob_position(rid).unwrap().0
+        };
+        let normal = coast(Surface::Normal);
+        assert!(coast(Surface::Ice) > normal);
+        assert!(coast(Surface::SoulSand) < normal * 0.5);
+
+        // A mob falling onto slime bounces back up
+        let (_, rid) = gw.spawn_mob("minecraft:cow", 0.0, 8.0, 0.0).unwrap();
+        gw.set_mob_surface(rid, Surface::Slime);
+        let mut bounced = false;
+        for _ in 0..20 {
+            system_mob_gravity(&mut gw.world);
+            bounced |= gw.mob_velocity(rid).unwrap().1 > 0.0;
+        }
+        assert!(bounced);
+    }
+
     #[test]
     fn cleanup_removes_dead() {
         let mut gw = GameWorld::new(1);
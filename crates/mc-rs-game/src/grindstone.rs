@@ -1,110 +1,172 @@
-//! Grindstone logic — remove enchantments and compute XP refund.
+//! Grindstone logic — remove enchantments, combine damaged items and
+//! compute the XP refund.
 
+use mc_rs_nbt::tag::{NbtCompound, NbtRoot, NbtTag};
+use mc_rs_nbt::{read_nbt_network_with_limits, NbtLimits};
 use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_world::item_registry::ItemRegistry;
 
-/// Compute the grindstone output: strip enchantments from the input item.
-///
-/// Returns `None` if there is nothing to disenchant.
-pub fn compute_grindstone_output(input1: &ItemStack, input2: &ItemStack) -> Option<ItemStack> {
-    if input1.is_empty() && input2.is_empty() {
-        return None;
-    }
+use crate::anvil::max_durability;
+use crate::combat::{enchantment_id, parse_enchantments, Enchantment};
 
-    // Primary item is whichever slot is non-empty (prefer slot 1)
-    let primary = if !input1.is_empty() { input1 } else { input2 };
+/// Result of a grindstone operation.
+pub struct GrindstoneResult {
+    /// The output item.
+    pub output: ItemStack,
+    /// XP given back for the enchantments removed.
+    pub xp: i32,
+}
 
-    // Only produce output if item has enchantments (non-empty nbt_data with ench tag)
-    if primary.nbt_data.is_empty() {
-        return None;
-    }
+/// Compute the grindstone output for the given inputs.
+///
+/// A single item loses its enchantments, except curses; an enchanted book
+/// with nothing left becomes a plain book. Two damaged items of the same
+/// type are combined like a crafting repair: their durability plus a 5%
+/// bonus, keeping the curses of both.
+///
+/// Returns `None` if the inputs don't produce a valid operation.
+pub fn compute_grindstone_output(
+    input1: &ItemStack,
+    input2: &ItemStack,
+    item_registry: &ItemRegistry,
+) -> Option<GrindstoneResult> {
+    let xp = grindstone_xp_reward(input1, input2);
+    let mut output = match (input1.is_empty(), input2.is_empty()) {
+        (true, true) => return None,
+        (false, false) => combine(input1, input2, item_registry)?,
+        (false, true) => disenchant(input1)?,
+        (true, false) => disenchant(input2)?,
+    };
+    output.stack_network_id = 0;
 
-    // Check if item has enchantments
-    if !has_enchantments(&primary.nbt_data) {
-        return None;
+    let is_book = item_registry
+        .get_by_id(output.runtime_id as i16)
+        .is_some_and(|i| i.name == "minecraft:enchanted_book");
+    if is_book && parse_enchantments(&output.nbt_data).is_empty() {
+        let book = item_registry.get_by_name("minecraft:book")?;
+        output.runtime_id = book.numeric_id as i32;
+        output.metadata = 0;
     }
-
-    // Output = same item without enchantments (clear nbt_data)
-    Some(ItemStack {
-        runtime_id: primary.runtime_id,
-        count: primary.count,
-        metadata: primary.metadata,
-        block_runtime_id: primary.block_runtime_id,
-        nbt_data: Vec::new(), // stripped
-        can_place_on: primary.can_place_on.clone(),
-        can_destroy: primary.can_destroy.clone(),
-        stack_network_id: 0,
-    })
+    Some(GrindstoneResult { output, xp })
 }
 
 /// Calculate XP reward from stripping enchantments via grindstone.
 ///
-/// Each enchantment level contributes a random amount of XP.
-/// Simplified: each enchantment level = 1-3 XP (we use the minimum: level count).
+/// Each enchantment level removed gives 1 XP; curses stay on the item and
+/// give nothing.
 pub fn grindstone_xp_reward(input1: &ItemStack, input2: &ItemStack) -> i32 {
-    let mut total = 0;
-    total += count_enchantment_levels(&input1.nbt_data);
-    total += count_enchantment_levels(&input2.nbt_data);
-    total
+    [input1, input2]
+        .iter()
+        .flat_map(|item| parse_enchantments(&item.nbt_data))
+        .filter(|e| !is_curse(e.id))
+        .map(|e| e.level as i32)
+        .sum()
+}
+
+/// Whether an enchantment is a curse, which the grindstone can't remove.
+fn is_curse(id: i16) -> bool {
+    id == enchantment_id::BINDING_CURSE || id == enchantment_id::VANISHING_CURSE
 }
 
-/// Check if NBT data contains an `ench` tag.
-fn has_enchantments(nbt_data: &[u8]) -> bool {
-    // Parse the NBT to look for the ench list
-    match mc_rs_nbt::read_nbt_le(&mut &nbt_data[..]) {
-        Ok(root) => root.compound.contains_key("ench"),
-        Err(_) => false,
+/// Strip the enchantments of a single item. `None` if it has nothing but
+/// curses.
+fn disenchant(item: &ItemStack) -> Option<ItemStack> {
+    let enchantments = parse_enchantments(&item.nbt_data);
+    if enchantments.iter().all(|e| is_curse(e.id)) {
+        return None;
     }
+    let curses: Vec<Enchantment> = enchantments
+        .into_iter()
+        .filter(|e| is_curse(e.id))
+        .collect();
+    let mut output = item.clone();
+    output.nbt_data = replace_enchantments(&item.nbt_data, &curses);
+    Some(output)
 }
 
-/// Count total enchantment levels in the item's NBT data.
-fn count_enchantment_levels(nbt_data: &[u8]) -> i32 {
-    if nbt_data.is_empty() {
-        return 0;
+/// Combine two damaged items of the same type into one.
+fn combine(
+    input1: &ItemStack,
+    input2: &ItemStack,
+    item_registry: &ItemRegistry,
+) -> Option<ItemStack> {
+    if input1.runtime_id != input2.runtime_id || input1.count != 1 || input2.count != 1 {
+        return None;
     }
-    let root = match mc_rs_nbt::read_nbt_le(&mut &nbt_data[..]) {
-        Ok(r) => r,
-        Err(_) => return 0,
-    };
-    let ench_list = match root.compound.get("ench") {
-        Some(mc_rs_nbt::tag::NbtTag::List(list)) => list,
-        _ => return 0,
-    };
-    let mut total = 0i32;
-    for tag in ench_list {
-        if let mc_rs_nbt::tag::NbtTag::Compound(c) = tag {
-            if let Some(mc_rs_nbt::tag::NbtTag::Short(lvl)) = c.get("lvl") {
-                total += *lvl as i32;
-            }
+    let name = &item_registry.get_by_id(input1.runtime_id as i16)?.name;
+    let max = max_durability(name)?;
+
+    let remaining = |item: &ItemStack| max.saturating_sub(item.metadata);
+    let combined = (remaining(input1) + remaining(input2) + max / 20).min(max);
+
+    let mut curses: Vec<Enchantment> = Vec::new();
+    for e in parse_enchantments(&input1.nbt_data)
+        .into_iter()
+        .chain(parse_enchantments(&input2.nbt_data))
+    {
+        if is_curse(e.id) && !curses.iter().any(|c| c.id == e.id) {
+            curses.push(e);
         }
     }
-    total
+
+    let mut output = input1.clone();
+    output.metadata = max - combined;
+    output.nbt_data = replace_enchantments(&input1.nbt_data, &curses);
+    Some(output)
+}
+
+/// Replace the enchantments of item NBT (network format), dropping the
+/// anvil repair cost and keeping every other tag.
+fn replace_enchantments(nbt_data: &[u8], enchantments: &[Enchantment]) -> Vec<u8> {
+    let mut compound = if nbt_data.is_empty() {
+        NbtCompound::new()
+    } else {
+        read_nbt_network_with_limits(&mut &nbt_data[..], &NbtLimits::untrusted())
+            .map(|root| root.compound)
+            .unwrap_or_default()
+    };
+    compound.remove("ench");
+    compound.remove("RepairCost");
+    if !enchantments.is_empty() {
+        let list = enchantments
+            .iter()
+            .map(|e| {
+                let mut entry = NbtCompound::new();
+                entry.insert("id".into(), NbtTag::Short(e.id));
+                entry.insert("lvl".into(), NbtTag::Short(e.level));
+                NbtTag::Compound(entry)
+            })
+            .collect();
+        compound.insert("ench".into(), NbtTag::List(list));
+    }
+    if compound.is_empty() {
+        return Vec::new();
+    }
+    let mut buf = Vec::new();
+    mc_rs_nbt::write_nbt_network(&mut buf, &NbtRoot::new("", compound));
+    buf
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mc_rs_nbt::tag::{NbtCompound, NbtRoot, NbtTag};
-
-    fn make_enchanted_item(ench_levels: &[(i16, i16)]) -> ItemStack {
-        let mut ench_list = Vec::new();
-        for &(id, lvl) in ench_levels {
-            let mut c = NbtCompound::new();
-            c.insert("id".to_string(), NbtTag::Short(id));
-            c.insert("lvl".to_string(), NbtTag::Short(lvl));
-            ench_list.push(NbtTag::Compound(c));
-        }
-        let mut root_c = NbtCompound::new();
-        root_c.insert("ench".to_string(), NbtTag::List(ench_list));
-        let root = NbtRoot::new("", root_c);
-        let mut nbt_data = Vec::new();
-        mc_rs_nbt::write_nbt_le(&mut nbt_data, &root);
+    use crate::combat::build_enchantment_nbt;
+
+    fn item_id(registry: &ItemRegistry, name: &str) -> i32 {
+        registry.get_by_name(name).unwrap().numeric_id as i32
+    }
 
+    fn make_enchanted_item(runtime_id: i32, ench_levels: &[(i16, i16)]) -> ItemStack {
+        let enchantments: Vec<Enchantment> = ench_levels
+            .iter()
+            .map(|&(id, level)| Enchantment { id, level })
+            .collect();
         ItemStack {
-            runtime_id: 42,
+            runtime_id,
             count: 1,
             metadata: 0,
             block_runtime_id: 0,
-            nbt_data,
+            nbt_data: build_enchantment_nbt(&enchantments),
             can_place_on: Vec::new(),
             can_destroy: Vec::new(),
             stack_network_id: 0,
@@ -113,43 +175,114 @@ mod tests {
 
     #[test]
     fn output_strips_enchantments() {
-        let item = make_enchanted_item(&[(9, 3)]); // Sharpness III
-        let output = compute_grindstone_output(&item, &ItemStack::empty()).unwrap();
-        assert_eq!(output.runtime_id, 42);
-        assert!(output.nbt_data.is_empty());
+        let registry = ItemRegistry::new();
+        let sword = item_id(&registry, "minecraft:iron_sword");
+        let item = make_enchanted_item(sword, &[(9, 3)]); // Sharpness III
+        let result = compute_grindstone_output(&item, &ItemStack::empty(), &registry).unwrap();
+        assert_eq!(result.output.runtime_id, sword);
+        assert!(result.output.nbt_data.is_empty());
+        assert_eq!(result.xp, 3);
     }
 
     #[test]
     fn empty_inputs_no_output() {
-        assert!(compute_grindstone_output(&ItemStack::empty(), &ItemStack::empty()).is_none());
+        let registry = ItemRegistry::new();
+        assert!(
+            compute_grindstone_output(&ItemStack::empty(), &ItemStack::empty(), &registry)
+                .is_none()
+        );
     }
 
     #[test]
     fn no_enchantments_no_output() {
-        let item = ItemStack {
-            runtime_id: 10,
-            count: 1,
-            metadata: 0,
-            block_runtime_id: 0,
-            nbt_data: Vec::new(),
-            can_place_on: Vec::new(),
-            can_destroy: Vec::new(),
-            stack_network_id: 0,
-        };
-        assert!(compute_grindstone_output(&item, &ItemStack::empty()).is_none());
+        let registry = ItemRegistry::new();
+        let item = make_enchanted_item(item_id(&registry, "minecraft:iron_sword"), &[]);
+        assert!(compute_grindstone_output(&item, &ItemStack::empty(), &registry).is_none());
+    }
+
+    #[test]
+    fn curses_are_kept() {
+        let registry = ItemRegistry::new();
+        let boots = item_id(&registry, "minecraft:iron_boots");
+        let item = make_enchanted_item(boots, &[(0, 4), (enchantment_id::BINDING_CURSE, 1)]);
+        let result = compute_grindstone_output(&ItemStack::empty(), &item, &registry).unwrap();
+        let left = parse_enchantments(&result.output.nbt_data);
+        assert_eq!(left.len(), 1);
+        assert_eq!(left[0].id, enchantment_id::BINDING_CURSE);
+        assert_eq!(result.xp, 4);
+
+        // Nothing but curses: nothing to remove
+        let cursed = make_enchanted_item(boots, &[(enchantment_id::VANISHING_CURSE, 1)]);
+        assert!(compute_grindstone_output(&cursed, &ItemStack::empty(), &registry).is_none());
+    }
+
+    #[test]
+    fn enchanted_book_becomes_book() {
+        let registry = ItemRegistry::new();
+        let book = make_enchanted_item(item_id(&registry, "minecraft:enchanted_book"), &[(9, 5)]);
+        let result = compute_grindstone_output(&book, &ItemStack::empty(), &registry).unwrap();
+        assert_eq!(
+            result.output.runtime_id,
+            item_id(&registry, "minecraft:book")
+        );
+        assert_eq!(result.xp, 5);
+    }
+
+    #[test]
+    fn combines_damaged_items() {
+        let registry = ItemRegistry::new();
+        let pickaxe = item_id(&registry, "minecraft:iron_pickaxe"); // 251 durability
+        let mut a = make_enchanted_item(pickaxe, &[(15, 2), (enchantment_id::VANISHING_CURSE, 1)]);
+        a.metadata = 200; // 51 left
+        let mut b = make_enchanted_item(pickaxe, &[(enchantment_id::BINDING_CURSE, 1)]);
+        b.metadata = 150; // 101 left
+
+        let result = compute_grindstone_output(&a, &b, &registry).unwrap();
+        // 51 + 101 + 12 (5% bonus) = 164 left
+        assert_eq!(result.output.metadata, 251 - 164);
+        let mut curses: Vec<i16> = parse_enchantments(&result.output.nbt_data)
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        curses.sort();
+        assert_eq!(
+            curses,
+            vec![
+                enchantment_id::BINDING_CURSE,
+                enchantment_id::VANISHING_CURSE
+            ]
+        );
+        assert_eq!(result.xp, 2);
+
+        // Durability is capped at the maximum
+        a.metadata = 10;
+        b.metadata = 10;
+        let result = compute_grindstone_output(&a, &b, &registry).unwrap();
+        assert_eq!(result.output.metadata, 0);
+    }
+
+    #[test]
+    fn combining_needs_matching_damageable_items() {
+        let registry = ItemRegistry::new();
+        let pickaxe = make_enchanted_item(item_id(&registry, "minecraft:iron_pickaxe"), &[]);
+        let sword = make_enchanted_item(item_id(&registry, "minecraft:iron_sword"), &[]);
+        assert!(compute_grindstone_output(&pickaxe, &sword, &registry).is_none());
+
+        let stick = make_enchanted_item(item_id(&registry, "minecraft:stick"), &[]);
+        assert!(compute_grindstone_output(&stick, &stick, &registry).is_none());
     }
 
     #[test]
     fn xp_reward_counts_levels() {
-        let item = make_enchanted_item(&[(9, 3), (17, 2)]); // Sharpness III + Smite II = 5
+        let item = make_enchanted_item(42, &[(9, 3), (17, 2)]); // Sharpness III + Smite II = 5
         let xp = grindstone_xp_reward(&item, &ItemStack::empty());
         assert_eq!(xp, 5);
     }
 
     #[test]
     fn xp_reward_both_slots() {
-        let item1 = make_enchanted_item(&[(9, 2)]); // 2
-        let item2 = make_enchanted_item(&[(0, 4)]); // 4
+        let item1 = make_enchanted_item(42, &[(9, 2)]); // 2
+        let item2 = make_enchanted_item(42, &[(0, 4)]); // 4
         assert_eq!(grindstone_xp_reward(&item1, &item2), 6);
     }
 }
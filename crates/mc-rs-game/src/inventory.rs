@@ -143,6 +143,53 @@ impl PlayerInventory {
         }
     }
 
+    /// Add an item to the main inventory, topping up matching stacks before
+    /// filling empty slots. Returns the number of items that did not fit.
+    pub fn add_item(&mut self, item: &ItemStack, max_stack: u16) -> u16 {
+        let mut remaining = item.count;
+        for slot in self.main.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+            if !slot.is_empty()
+                && slot.runtime_id == item.runtime_id
+                && slot.metadata == item.metadata
+                && slot.nbt_data == item.nbt_data
+                && slot.count < max_stack
+            {
+                let moved = remaining.min(max_stack - slot.count);
+                slot.count += moved;
+                remaining -= moved;
+            }
+        }
+        for i in 0..self.main.len() {
+            if remaining == 0 {
+                break;
+            }
+            if self.main[i].is_empty() {
+                let moved = remaining.min(max_stack.max(1));
+                let mut stack = item.clone();
+                stack.count = moved;
+                stack.stack_network_id = self.next_stack_network_id();
+                self.main[i] = stack;
+                remaining -= moved;
+            }
+        }
+        remaining
+    }
+
+    /// Remove and return every non-empty stack from the main, armor, offhand and cursor slots.
+    pub fn drain_all(&mut self) -> Vec<ItemStack> {
+        self.main
+            .iter_mut()
+            .chain(self.armor.iter_mut())
+            .chain(std::iter::once(&mut self.offhand))
+            .chain(std::iter::once(&mut self.cursor))
+            .filter(|s| !s.is_empty())
+            .map(|s| std::mem::replace(s, ItemStack::empty()))
+            .collect()
+    }
+
     /// Process a single ItemStackRequest and return the response.
     pub fn process_request(
         &mut self,
@@ -234,6 +281,16 @@ impl PlayerInventory {
                     // Loom — handled server-side
                     debug!("CraftLoom: pattern_id={}", pattern_id);
                 }
+                StackAction::BeaconPayment {
+                    primary_effect,
+                    secondary_effect,
+                } => {
+                    // Beacon — handled server-side
+                    debug!(
+                        "BeaconPayment: primary={} secondary={}",
+                        primary_effect, secondary_effect
+                    );
+                }
                 StackAction::Create { result_slot } => {
                     debug!("Create: result_slot={}", result_slot);
                 }
@@ -291,6 +348,13 @@ impl PlayerInventory {
             return false;
         }
 
+        // Shield decoration copies the banner before the grid is used up
+        let decorated_shield = if output.item_name == "minecraft:shield" {
+            self.decorated_shield(item_registry)
+        } else {
+            None
+        };
+
         // Consume ingredients from crafting grid (simplified: trust the client placement)
         match &recipe {
             RecipeRef::Shaped(shaped) => {
@@ -336,6 +400,9 @@ impl PlayerInventory {
         // Place result in crafting output
         let mut result = ItemStack::new(output_rid, output.count as u16 * times as u16);
         result.metadata = output.metadata;
+        if let Some(shield) = decorated_shield {
+            result = shield;
+        }
         result.stack_network_id = self.next_stack_network_id();
         self.crafting_output = result;
 
@@ -349,6 +416,20 @@ impl PlayerInventory {
         true
     }
 
+    /// The shield a shield and a banner in the crafting grid make, with the
+    /// banner's colors.
+    fn decorated_shield(&self, item_registry: &ItemRegistry) -> Option<ItemStack> {
+        let find = |name: &str| {
+            let rid = item_registry.get_by_name(name)?.numeric_id as i32;
+            self.crafting_grid
+                .iter()
+                .find(|i| !i.is_empty() && i.runtime_id == rid)
+        };
+        let shield = find("minecraft:shield")?;
+        let banner = find("minecraft:banner")?;
+        crate::loom::decorate_shield(shield, banner)
+    }
+
     /// Clear the crafting grid (called when the player closes the crafting UI).
     pub fn clear_crafting_grid(&mut self) {
         for slot in &mut self.crafting_grid {
@@ -888,6 +969,40 @@ mod tests {
         assert_eq!(inv.offhand.runtime_id, 422);
     }
 
+    #[test]
+    fn add_item_merges_then_fills_empty_slots() {
+        let mut inv = PlayerInventory::new();
+        inv.set_slot(CONTAINER_INVENTORY, 0, ItemStack::new(1, 60));
+        let leftover = inv.add_item(&ItemStack::new(1, 70), 64);
+        assert_eq!(leftover, 0);
+        assert_eq!(inv.main[0].count, 64);
+        assert_eq!(inv.main[1].count, 64);
+        assert_eq!(inv.main[2].count, 2);
+        assert_ne!(inv.main[1].stack_network_id, inv.main[2].stack_network_id);
+    }
+
+    #[test]
+    fn add_item_reports_leftover_when_full() {
+        let mut inv = PlayerInventory::new();
+        for slot in 0..36 {
+            inv.set_slot(CONTAINER_INVENTORY, slot, ItemStack::new(2, 1));
+        }
+        assert_eq!(inv.add_item(&ItemStack::new(1, 5), 64), 5);
+    }
+
+    #[test]
+    fn drain_all_empties_every_container() {
+        let mut inv = PlayerInventory::new();
+        inv.set_slot(CONTAINER_INVENTORY, 5, ItemStack::new(1, 10));
+        inv.set_slot(CONTAINER_ARMOR, 0, ItemStack::new(379, 1));
+        inv.set_slot(CONTAINER_OFFHAND, 0, ItemStack::new(422, 16));
+        let drained = inv.drain_all();
+        assert_eq!(drained.len(), 3);
+        assert!(inv.main.iter().all(|s| s.is_empty()));
+        assert!(inv.armor.iter().all(|s| s.is_empty()));
+        assert!(inv.offhand.is_empty());
+    }
+
     #[test]
     fn held_item_changes_with_slot() {
         let mut inv = PlayerInventory::new();
@@ -1079,4 +1194,36 @@ mod tests {
             assert_eq!(inv.crafting_output.count, 4); // planks = 4
         }
     }
+
+    #[test]
+    fn shield_decoration_copies_the_banner() {
+        let mut inv = PlayerInventory::new();
+        let registry = test_registry();
+        let recipe_reg = RecipeRegistry::new();
+        let recipe = recipe_reg
+            .shapeless_recipes()
+            .iter()
+            .find(|r| r.output[0].item_name == "minecraft:shield")
+            .unwrap();
+        let rid = |name: &str| registry.get_by_name(name).unwrap().numeric_id as i32;
+
+        let mut banner = ItemStack::new(rid("minecraft:banner"), 1);
+        banner.metadata = 1;
+        banner.nbt_data = crate::loom::apply_pattern(&[], "bo", 15).unwrap();
+        inv.set_slot(
+            CONTAINER_CRAFTING_INPUT,
+            0,
+            ItemStack::new(rid("minecraft:shield"), 1),
+        );
+        inv.set_slot(CONTAINER_CRAFTING_INPUT, 1, banner);
+
+        assert!(inv.process_craft_recipe(recipe.network_id, 1, &recipe_reg, &registry));
+        let shield = &inv.crafting_output;
+        assert_eq!(shield.runtime_id, rid("minecraft:shield"));
+        assert_eq!(
+            crate::loom::item_patterns(&shield.nbt_data)[0].pattern,
+            "bo"
+        );
+        assert!(inv.crafting_grid.iter().all(|s| s.is_empty()));
+    }
 }
@@ -1,19 +1,39 @@
 //! Game logic: ECS, player management, entities, and physics.
 
+pub mod actor;
+pub mod adventure;
 pub mod ai;
 pub mod anvil;
+pub mod armor;
+pub mod beacon;
 pub mod block_entity;
 pub mod breeding;
+pub mod collision;
 pub mod combat;
+pub mod command_block;
 pub mod components;
+pub mod difficulty;
+pub mod effects;
 pub mod enchanting;
+pub mod fall;
 pub mod food;
 pub mod game_world;
 pub mod grindstone;
 pub mod inventory;
+pub mod lightning;
 pub mod loom;
+pub mod map;
+pub mod mining;
 pub mod mob_registry;
+pub mod move_sync;
+pub mod music;
 pub mod projectile;
+pub mod raid;
 pub mod recipe;
+pub mod shop;
+pub mod sign;
+pub mod skin;
+pub mod skull;
 pub mod smelting;
+pub mod sunlight;
 pub mod xp;
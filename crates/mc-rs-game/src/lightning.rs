@@ -0,0 +1,55 @@
+//! Lightning strikes: damage to nearby entities and the mobs they convert.
+
+/// Damage dealt to entities hit by lightning.
+pub const LIGHTNING_DAMAGE: f32 = 5.0;
+
+/// Distance (blocks) from the strike within which entities are hit.
+pub const STRIKE_RADIUS: f32 = 3.0;
+
+/// Ticks entities hit by lightning stay on fire.
+pub const STRIKE_FIRE_TICKS: i32 = 160;
+
+/// Behavior pack event the vanilla entity definitions fire when the mob is
+/// struck, e.g. a creeper becoming charged or a villager turning into a witch.
+pub fn lightning_event(type_id: &str) -> Option<&'static str> {
+    match type_id {
+        "minecraft:creeper" => Some("minecraft:become_charged"),
+        "minecraft:villager" | "minecraft:villager_v2" => Some("minecraft:become_witch"),
+        "minecraft:pig" => Some("minecraft:become_zombie"),
+        "minecraft:mooshroom" => Some("minecraft:become_brown"),
+        _ => None,
+    }
+}
+
+/// Mob a struck mob turns into when it has no definition handling the strike.
+pub fn lightning_conversion(type_id: &str) -> Option<&'static str> {
+    match type_id {
+        "minecraft:villager" | "minecraft:villager_v2" => Some("minecraft:witch"),
+        "minecraft:pig" => Some("minecraft:zombie_pigman"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(
+            lightning_event("minecraft:creeper"),
+            Some("minecraft:become_charged")
+        );
+        assert_eq!(lightning_conversion("minecraft:creeper"), None);
+        assert_eq!(
+            lightning_conversion("minecraft:villager_v2"),
+            Some("minecraft:witch")
+        );
+        assert_eq!(
+            lightning_conversion("minecraft:pig"),
+            Some("minecraft:zombie_pigman")
+        );
+        assert_eq!(lightning_event("minecraft:cow"), None);
+        assert_eq!(lightning_conversion("minecraft:cow"), None);
+    }
+}
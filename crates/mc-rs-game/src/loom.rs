@@ -1,6 +1,9 @@
-//! Loom logic — banner pattern application.
+//! Loom logic — banner pattern application, banner item NBT and shield
+//! decoration.
 
 use mc_rs_nbt::tag::{NbtCompound, NbtRoot, NbtTag};
+use mc_rs_nbt::{read_nbt_network_with_limits, write_nbt_network, NbtLimits};
+use mc_rs_proto::item_stack::ItemStack;
 
 /// All known banner pattern IDs and their display names.
 pub const LOOM_PATTERNS: &[(&str, &str)] = &[
@@ -46,6 +49,114 @@ pub const MAX_LAYERS: usize = 6;
 /// Patterns that require a special banner pattern item in the pattern slot.
 const SPECIAL_PATTERNS: &[&str] = &["cre", "flo", "mc", "sc", "glb", "pig"];
 
+/// Banner pattern items and the pattern they unlock.
+const PATTERN_ITEMS: &[(&str, &str)] = &[
+    ("minecraft:creeper_banner_pattern", "cre"),
+    ("minecraft:flower_banner_pattern", "flo"),
+    ("minecraft:mojang_banner_pattern", "mc"),
+    ("minecraft:skull_banner_pattern", "sc"),
+    ("minecraft:globe_banner_pattern", "glb"),
+    ("minecraft:piglin_banner_pattern", "pig"),
+];
+
+/// One layer of a banner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BannerPattern {
+    /// Pattern ID, e.g. `"bo"`.
+    pub pattern: String,
+    /// Banner color, see [`banner_color`].
+    pub color: i32,
+}
+
+impl BannerPattern {
+    /// Build a `Patterns` list entry.
+    pub fn to_nbt(&self) -> NbtTag {
+        let mut c = NbtCompound::new();
+        c.insert("Color".to_string(), NbtTag::Int(self.color));
+        c.insert("Pattern".to_string(), NbtTag::String(self.pattern.clone()));
+        NbtTag::Compound(c)
+    }
+
+    /// Read a `Patterns` list entry.
+    pub fn from_nbt(tag: &NbtTag) -> Option<Self> {
+        let c = tag.as_compound()?;
+        Some(Self {
+            pattern: c.get("Pattern")?.as_string()?.to_string(),
+            color: c.get("Color").and_then(|t| t.as_int()).unwrap_or(0),
+        })
+    }
+}
+
+/// Build a `Patterns` list.
+pub fn patterns_to_nbt(patterns: &[BannerPattern]) -> NbtTag {
+    NbtTag::List(patterns.iter().map(BannerPattern::to_nbt).collect())
+}
+
+/// Read a `Patterns` list, skipping entries that aren't patterns.
+pub fn patterns_from_nbt(tag: Option<&NbtTag>) -> Vec<BannerPattern> {
+    match tag {
+        Some(NbtTag::List(list)) => list.iter().filter_map(BannerPattern::from_nbt).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Banner colors (pattern colors, banner item metadata and `Base`) count
+/// from black (0) to white (15), the reverse of dye color indices.
+pub fn banner_color(dye_color: i32) -> i32 {
+    15 - dye_color
+}
+
+/// The pattern a banner pattern item unlocks.
+pub fn pattern_of_item(item_name: &str) -> Option<&'static str> {
+    PATTERN_ITEMS
+        .iter()
+        .find(|(item, _)| *item == item_name)
+        .map(|(_, pattern)| *pattern)
+}
+
+/// Read the network NBT of an item, or an empty compound.
+fn item_compound(nbt: &[u8]) -> NbtCompound {
+    if nbt.is_empty() {
+        return NbtCompound::new();
+    }
+    read_nbt_network_with_limits(&mut &nbt[..], &NbtLimits::untrusted())
+        .map(|root| root.compound)
+        .unwrap_or_default()
+}
+
+fn write_item_compound(c: NbtCompound) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_nbt_network(&mut buf, &NbtRoot::new("", c));
+    buf
+}
+
+/// The patterns of a banner or shield item.
+pub fn item_patterns(nbt: &[u8]) -> Vec<BannerPattern> {
+    patterns_from_nbt(item_compound(nbt).get("Patterns"))
+}
+
+/// The `Type` of a banner item (1 = ominous banner).
+pub fn item_banner_type(nbt: &[u8]) -> i32 {
+    item_compound(nbt)
+        .get("Type")
+        .and_then(|t| t.as_int())
+        .unwrap_or(0)
+}
+
+/// Network NBT of a banner item with these patterns and `Type`; empty for
+/// a plain banner.
+pub fn banner_item_nbt(patterns: &[BannerPattern], banner_type: i32) -> Vec<u8> {
+    if patterns.is_empty() && banner_type == 0 {
+        return Vec::new();
+    }
+    let mut c = NbtCompound::new();
+    c.insert("Patterns".to_string(), patterns_to_nbt(patterns));
+    if banner_type != 0 {
+        c.insert("Type".to_string(), NbtTag::Int(banner_type));
+    }
+    write_item_compound(c)
+}
+
 /// Check if a pattern ID is valid.
 pub fn is_valid_pattern(pattern_id: &str) -> bool {
     LOOM_PATTERNS.iter().any(|(id, _)| *id == pattern_id)
@@ -56,7 +167,7 @@ pub fn pattern_needs_item(pattern_id: &str) -> bool {
     SPECIAL_PATTERNS.contains(&pattern_id)
 }
 
-/// Map a dye item name to the Bedrock banner dye color index (0-15).
+/// Map a dye item name to its dye color index (0-15, white first).
 pub fn dye_color_from_item(item_name: &str) -> Option<i32> {
     match item_name {
         "minecraft:white_dye" | "minecraft:bone_meal" => Some(0),
@@ -79,46 +190,65 @@ pub fn dye_color_from_item(item_name: &str) -> Option<i32> {
     }
 }
 
-/// Apply a banner pattern to existing banner NBT data.
+/// Apply a banner pattern to the network NBT of a banner item, in the
+/// color of a dye (see [`dye_color_from_item`]).
 ///
-/// If `banner_nbt` is empty, starts a fresh pattern list.
-/// Returns the new NBT data with the pattern appended, or `None` if
-/// the banner already has the maximum number of layers.
+/// If `banner_nbt` is empty, starts a fresh pattern list; other tags (the
+/// name, `Type`) are kept. Returns the new NBT data with the pattern
+/// appended, or `None` if the banner already has the maximum number of
+/// layers.
 pub fn apply_pattern(banner_nbt: &[u8], pattern_id: &str, dye_color: i32) -> Option<Vec<u8>> {
-    let mut patterns: Vec<NbtTag> = Vec::new();
-
-    // Parse existing patterns if present
-    if !banner_nbt.is_empty() {
-        if let Ok(root) = mc_rs_nbt::read_nbt_le(&mut &banner_nbt[..]) {
-            if let Some(NbtTag::List(list)) = root.compound.get("Patterns") {
-                if list.len() >= MAX_LAYERS {
-                    return None; // Already at max
-                }
-                patterns = list.clone();
-            }
-        }
+    let mut c = item_compound(banner_nbt);
+    let mut patterns = patterns_from_nbt(c.get("Patterns"));
+    if patterns.len() >= MAX_LAYERS {
+        return None;
     }
+    patterns.push(BannerPattern {
+        pattern: pattern_id.to_string(),
+        color: banner_color(dye_color),
+    });
+    c.insert("Patterns".to_string(), patterns_to_nbt(&patterns));
+    Some(write_item_compound(c))
+}
 
-    if patterns.len() >= MAX_LAYERS {
+/// The banner a loom makes from `banner`, a dye and the item in the
+/// pattern slot, or `None` if the pattern can't be applied.
+pub fn loom_result(
+    banner: &ItemStack,
+    dye_name: &str,
+    pattern_item: Option<&str>,
+    pattern_id: &str,
+) -> Option<ItemStack> {
+    if banner.is_empty() || !is_valid_pattern(pattern_id) {
+        return None;
+    }
+    if pattern_needs_item(pattern_id) && pattern_item.and_then(pattern_of_item) != Some(pattern_id)
+    {
         return None;
     }
+    let dye_color = dye_color_from_item(dye_name)?;
+    let mut result = banner.clone();
+    result.count = 1;
+    result.nbt_data = apply_pattern(&banner.nbt_data, pattern_id, dye_color)?;
+    Some(result)
+}
 
-    // Add new pattern
-    let mut entry = NbtCompound::new();
-    entry.insert("Color".to_string(), NbtTag::Int(dye_color));
-    entry.insert(
-        "Pattern".to_string(),
-        NbtTag::String(pattern_id.to_string()),
+/// Put the base color and patterns of `banner` on an undecorated shield.
+/// Returns `None` if the shield is already decorated.
+pub fn decorate_shield(shield: &ItemStack, banner: &ItemStack) -> Option<ItemStack> {
+    let mut c = item_compound(&shield.nbt_data);
+    if c.contains_key("Base") || !patterns_from_nbt(c.get("Patterns")).is_empty() {
+        return None;
+    }
+    c.insert("Base".to_string(), NbtTag::Int(banner.metadata as i32));
+    c.insert(
+        "Patterns".to_string(),
+        patterns_to_nbt(&item_patterns(&banner.nbt_data)),
     );
-    patterns.push(NbtTag::Compound(entry));
-
-    // Build output NBT
-    let mut root_c = NbtCompound::new();
-    root_c.insert("Patterns".to_string(), NbtTag::List(patterns));
-    let root = NbtRoot::new("", root_c);
-    let mut buf = Vec::new();
-    mc_rs_nbt::write_nbt_le(&mut buf, &root);
-    Some(buf)
+    let mut result = shield.clone();
+    result.count = 1;
+    result.nbt_data = write_item_compound(c);
+    Some(result)
 }
 
 #[cfg(test)]
@@ -154,10 +284,18 @@ mod tests {
         let result = apply_pattern(&[], "bo", 14).unwrap();
         assert!(!result.is_empty());
 
-        // Parse the result and verify
-        let root = mc_rs_nbt::read_nbt_le(&mut &result[..]).unwrap();
+        // Item NBT is in the network format
+        let root = mc_rs_nbt::read_nbt_network(&mut &result[..]).unwrap();
         let patterns = root.compound.get("Patterns").unwrap().as_list().unwrap();
         assert_eq!(patterns.len(), 1);
+        // Red dye is color 1 on a banner
+        assert_eq!(
+            item_patterns(&result),
+            vec![BannerPattern {
+                pattern: "bo".into(),
+                color: 1
+            }]
+        );
     }
 
     #[test]
@@ -165,9 +303,50 @@ mod tests {
         let nbt1 = apply_pattern(&[], "bo", 0).unwrap();
         let nbt2 = apply_pattern(&nbt1, "cr", 14).unwrap();
 
-        let root = mc_rs_nbt::read_nbt_le(&mut &nbt2[..]).unwrap();
-        let patterns = root.compound.get("Patterns").unwrap().as_list().unwrap();
+        let patterns = item_patterns(&nbt2);
         assert_eq!(patterns.len(), 2);
+        assert_eq!((patterns[0].color, patterns[1].color), (15, 1));
+    }
+
+    #[test]
+    fn apply_pattern_keeps_the_banner_type() {
+        let ominous = banner_item_nbt(&[], 1);
+        let nbt = apply_pattern(&ominous, "bo", 15).unwrap();
+        assert_eq!(item_banner_type(&nbt), 1);
+        assert_eq!(item_patterns(&nbt)[0].color, 0);
+        assert!(banner_item_nbt(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn loom_needs_the_pattern_item() {
+        let banner = ItemStack::new(606, 1);
+        let result = loom_result(&banner, "minecraft:red_dye", None, "bo").unwrap();
+        assert_eq!(item_patterns(&result.nbt_data).len(), 1);
+        assert!(loom_result(&banner, "minecraft:stone", None, "bo").is_none());
+        assert!(loom_result(&banner, "minecraft:red_dye", None, "nope").is_none());
+
+        assert!(loom_result(&banner, "minecraft:red_dye", None, "cre").is_none());
+        let skull = Some("minecraft:skull_banner_pattern");
+        assert!(loom_result(&banner, "minecraft:red_dye", skull, "cre").is_none());
+        let creeper = Some("minecraft:creeper_banner_pattern");
+        assert!(loom_result(&banner, "minecraft:red_dye", creeper, "cre").is_some());
+    }
+
+    #[test]
+    fn shield_takes_the_banner() {
+        let mut banner = ItemStack::new(606, 1);
+        banner.metadata = 4;
+        banner.nbt_data = apply_pattern(&[], "cr", 0).unwrap();
+        let shield = ItemStack::new(387, 1);
+
+        let decorated = decorate_shield(&shield, &banner).unwrap();
+        let root = mc_rs_nbt::read_nbt_network(&mut &decorated.nbt_data[..]).unwrap();
+        assert_eq!(root.compound.get("Base").and_then(|t| t.as_int()), Some(4));
+        assert_eq!(item_patterns(&decorated.nbt_data)[0].pattern, "cr");
+
+        // A plain banner still colors the shield; a decorated one is final
+        assert!(decorate_shield(&shield, &ItemStack::new(606, 1)).is_some());
+        assert!(decorate_shield(&decorated, &banner).is_none());
     }
 
     #[test]
@@ -0,0 +1,548 @@
+//! Maps — filled map data, top-down block colors and cartography.
+//!
+//! A map covers 128×128 pixels, each pixel `2^scale` blocks wide, on a
+//! grid aligned so that maps of the same scale tile the world. Pixels are
+//! filled in as players carrying the map explore the area.
+
+use mc_rs_nbt::tag::{NbtCompound, NbtRoot, NbtTag};
+use mc_rs_nbt::{
+    read_nbt_le, read_nbt_network_with_limits, write_nbt_le, write_nbt_network, NbtLimits,
+};
+
+/// Item name of an unexplored map.
+pub const EMPTY_MAP: &str = "minecraft:empty_map";
+/// Item name of a map bound to map data.
+pub const FILLED_MAP: &str = "minecraft:filled_map";
+
+/// Width and height of a map, in pixels.
+pub const MAP_SIZE: usize = 128;
+/// Largest map scale (one pixel = 16×16 blocks).
+pub const MAX_MAP_SCALE: u8 = 4;
+/// Radius around the holder that gets explored, in pixels.
+pub const EXPLORE_RADIUS: i32 = 64;
+/// Ticks between two map updates for the players holding them.
+pub const MAP_UPDATE_INTERVAL_TICKS: u64 = 20;
+
+/// Cartography table recipe cloning a filled map with an empty map.
+pub const MAP_CLONING_RECIPE_UUID: &str = "442d85ed-8272-4543-a6f1-418f90ded05d";
+/// Cartography table recipe zooming a filled map out with paper.
+pub const MAP_EXTENDING_RECIPE_UUID: &str = "8b36268c-1829-483c-a0f1-993b7156a8f2";
+
+/// Item NBT tag holding the map ID of a filled map.
+const MAP_UUID_TAG: &str = "map_uuid";
+
+/// Map data bound to filled map items.
+#[derive(Debug, Clone)]
+pub struct MapData {
+    pub id: i64,
+    /// Map this one was zoomed out from, or -1.
+    pub parent_id: i64,
+    pub dimension: i32,
+    /// World block at the center of the map.
+    pub center: (i32, i32),
+    pub scale: u8,
+    pub locked: bool,
+    /// `MAP_SIZE²` RGBA pixels (little-endian), row by row from the north.
+    /// Unexplored pixels are 0 (transparent).
+    pub pixels: Vec<u32>,
+    /// Fixed markers drawn on the map, such as the target of an explorer
+    /// map.
+    pub markers: Vec<MapMarker>,
+    /// Changed since it was last saved.
+    pub dirty: bool,
+}
+
+/// A marker fixed to a world block column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapMarker {
+    /// Map decoration type drawn for it.
+    pub decoration_type: u8,
+    pub x: i32,
+    pub z: i32,
+}
+
+/// Top block of one world column, as seen by a map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapSample {
+    /// Base map color of the block.
+    pub color: (u8, u8, u8),
+    pub height: i32,
+}
+
+impl MapData {
+    /// Create a blank map covering the grid cell that contains `(x, z)`.
+    pub fn new(id: i64, dimension: i32, (x, z): (i32, i32), scale: u8) -> Self {
+        let scale = scale.min(MAX_MAP_SCALE);
+        Self {
+            id,
+            parent_id: -1,
+            dimension,
+            center: (map_center(x, scale), map_center(z, scale)),
+            scale,
+            locked: false,
+            pixels: vec![0; MAP_SIZE * MAP_SIZE],
+            markers: Vec::new(),
+            dirty: true,
+        }
+    }
+
+    /// An explorer map: a blank map of `scale` covering `target`, with a
+    /// marker of `decoration_type` on it.
+    pub fn explorer(
+        id: i64,
+        dimension: i32,
+        target: (i32, i32),
+        scale: u8,
+        decoration_type: u8,
+    ) -> Self {
+        let mut map = Self::new(id, dimension, target, scale);
+        map.markers.push(MapMarker {
+            decoration_type,
+            x: target.0,
+            z: target.1,
+        });
+        map
+    }
+
+    /// Blocks covered by one pixel along each axis.
+    pub fn block_size(&self) -> i32 {
+        1 << self.scale
+    }
+
+    /// World block at the top-left (north-west) corner of the map.
+    pub fn origin(&self) -> (i32, i32) {
+        let half = (MAP_SIZE as i32 / 2) * self.block_size();
+        (self.center.0 - half, self.center.1 - half)
+    }
+
+    /// A blank map one scale further out, covering this map's area. `None`
+    /// if the map is already at the largest scale.
+    pub fn zoomed_out(&self, id: i64) -> Option<MapData> {
+        if self.scale >= MAX_MAP_SCALE {
+            return None;
+        }
+        let mut map = MapData::new(id, self.dimension, self.center, self.scale + 1);
+        map.parent_id = self.id;
+        map.markers = self.markers.clone();
+        Some(map)
+    }
+
+    /// Explore the pixels within [`EXPLORE_RADIUS`] of a holder standing at
+    /// block `(x, z)`. `surface` returns the top block of a world column, or
+    /// `None` if it is not loaded. Returns whether any pixel changed.
+    pub fn explore(
+        &mut self,
+        (x, z): (i32, i32),
+        mut surface: impl FnMut(i32, i32) -> Option<MapSample>,
+    ) -> bool {
+        if self.locked {
+            return false;
+        }
+        let bs = self.block_size();
+        let (ox, oz) = self.origin();
+        let size = MAP_SIZE as i32;
+        let (hx, hz) = ((x - ox).div_euclid(bs), (z - oz).div_euclid(bs));
+        let (x0, x1) = (
+            (hx - EXPLORE_RADIUS).max(0),
+            (hx + EXPLORE_RADIUS).min(size - 1),
+        );
+        let (z0, z1) = (
+            (hz - EXPLORE_RADIUS).max(0),
+            (hz + EXPLORE_RADIUS).min(size - 1),
+        );
+        if x0 > x1 || z0 > z1 {
+            return false;
+        }
+
+        // Heights of the row north of the current one, for shading
+        let mut north: Vec<Option<i32>> = (x0..=x1)
+            .map(|px| surface(ox + px * bs, oz + (z0 - 1) * bs).map(|s| s.height))
+            .collect();
+        let mut changed = false;
+        for pz in z0..=z1 {
+            for (i, px) in (x0..=x1).enumerate() {
+                let sample = surface(ox + px * bs, oz + pz * bs);
+                let in_range = (px - hx).pow(2) + (pz - hz).pow(2) <= EXPLORE_RADIUS.pow(2);
+                if let (Some(sample), true) = (sample, in_range) {
+                    let shade = match north[i] {
+                        Some(h) if sample.height > h => MapShade::Bright,
+                        Some(h) if sample.height < h => MapShade::Dark,
+                        _ => MapShade::Normal,
+                    };
+                    let pixel = shaded_pixel(sample.color, shade);
+                    let slot = &mut self.pixels[pz as usize * MAP_SIZE + px as usize];
+                    if *slot != pixel {
+                        *slot = pixel;
+                        changed = true;
+                    }
+                }
+                north[i] = sample.map(|s| s.height);
+            }
+        }
+        self.dirty |= changed;
+        changed
+    }
+
+    /// Position of a marker for a holder at world `(x, z)`, in half pixels
+    /// from the map center. `None` if the holder is off the map.
+    pub fn marker_position(&self, x: f32, z: f32) -> Option<(i8, i8)> {
+        let bs = self.block_size() as f32;
+        let mx = ((x - self.center.0 as f32) / bs * 2.0).round();
+        let mz = ((z - self.center.1 as f32) / bs * 2.0).round();
+        if (-128.0..=127.0).contains(&mx) && (-128.0..=127.0).contains(&mz) {
+            Some((mx as i8, mz as i8))
+        } else {
+            None
+        }
+    }
+
+    /// Serialize to LE NBT bytes (for LevelDB persistence).
+    pub fn to_le_nbt(&self) -> Vec<u8> {
+        let mut c = NbtCompound::new();
+        c.insert("mapId".into(), NbtTag::Long(self.id));
+        c.insert("parentMapId".into(), NbtTag::Long(self.parent_id));
+        c.insert("dimension".into(), NbtTag::Byte(self.dimension as i8));
+        c.insert("xCenter".into(), NbtTag::Int(self.center.0));
+        c.insert("zCenter".into(), NbtTag::Int(self.center.1));
+        c.insert("scale".into(), NbtTag::Byte(self.scale as i8));
+        c.insert("mapLocked".into(), NbtTag::Byte(self.locked as i8));
+        c.insert("width".into(), NbtTag::Short(MAP_SIZE as i16));
+        c.insert("height".into(), NbtTag::Short(MAP_SIZE as i16));
+        let colors = self
+            .pixels
+            .iter()
+            .flat_map(|p| p.to_le_bytes())
+            .map(|b| b as i8)
+            .collect();
+        c.insert("colors".into(), NbtTag::ByteArray(colors));
+        let markers = self
+            .markers
+            .iter()
+            .map(|m| {
+                let mut marker = NbtCompound::new();
+                marker.insert("type".into(), NbtTag::Byte(m.decoration_type as i8));
+                marker.insert("x".into(), NbtTag::Int(m.x));
+                marker.insert("z".into(), NbtTag::Int(m.z));
+                NbtTag::Compound(marker)
+            })
+            .collect();
+        c.insert("decorations".into(), NbtTag::List(markers));
+
+        let mut buf = Vec::new();
+        write_nbt_le(&mut buf, &NbtRoot::new("", c));
+        buf
+    }
+
+    /// Parse from LE NBT bytes.
+    pub fn from_le_nbt(data: &[u8]) -> Option<Self> {
+        let root = read_nbt_le(&mut &data[..]).ok()?;
+        let c = &root.compound;
+        let id = c.get("mapId").and_then(|t| t.as_long())?;
+        let byte = |key: &str| c.get(key).and_then(|t| t.as_byte()).unwrap_or(0);
+        let int = |key: &str| c.get(key).and_then(|t| t.as_int()).unwrap_or(0);
+
+        let mut pixels = vec![0; MAP_SIZE * MAP_SIZE];
+        if let Some(NbtTag::ByteArray(colors)) = c.get("colors") {
+            for (pixel, rgba) in pixels.iter_mut().zip(colors.chunks_exact(4)) {
+                *pixel = u32::from_le_bytes([
+                    rgba[0] as u8,
+                    rgba[1] as u8,
+                    rgba[2] as u8,
+                    rgba[3] as u8,
+                ]);
+            }
+        }
+        let markers = c
+            .get("decorations")
+            .and_then(|t| t.as_list())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|t| {
+                let m = t.as_compound()?;
+                Some(MapMarker {
+                    decoration_type: m.get("type")?.as_byte()? as u8,
+                    x: m.get("x")?.as_int()?,
+                    z: m.get("z")?.as_int()?,
+                })
+            })
+            .collect();
+        Some(Self {
+            id,
+            parent_id: c.get("parentMapId").and_then(|t| t.as_long()).unwrap_or(-1),
+            dimension: byte("dimension") as i32,
+            center: (int("xCenter"), int("zCenter")),
+            scale: (byte("scale").max(0) as u8).min(MAX_MAP_SCALE),
+            locked: byte("mapLocked") != 0,
+            pixels,
+            markers,
+            dirty: false,
+        })
+    }
+}
+
+/// Center of the map grid cell containing world coordinate `v`.
+pub fn map_center(v: i32, scale: u8) -> i32 {
+    let size = (MAP_SIZE as i32) << scale;
+    (v + 64).div_euclid(size) * size + size / 2 - 64
+}
+
+/// Brightness of a map pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapShade {
+    /// Lower than the block to the north.
+    Dark,
+    Normal,
+    /// Higher than the block to the north.
+    Bright,
+}
+
+/// Opaque RGBA pixel (little-endian) for a base color and shade.
+pub fn shaded_pixel((r, g, b): (u8, u8, u8), shade: MapShade) -> u32 {
+    let m = match shade {
+        MapShade::Dark => 180,
+        MapShade::Normal => 220,
+        MapShade::Bright => 255,
+    };
+    let c = |v: u8| (v as u32 * m / 255) & 0xFF;
+    c(r) | (c(g) << 8) | (c(b) << 16) | 0xFF00_0000
+}
+
+/// Base map color of a block, or `None` if maps see through it.
+pub fn block_map_color(name: &str) -> Option<(u8, u8, u8)> {
+    const GRASS: (u8, u8, u8) = (127, 178, 56);
+    const SAND: (u8, u8, u8) = (247, 233, 163);
+    const WOOL: (u8, u8, u8) = (199, 199, 199);
+    const FIRE: (u8, u8, u8) = (255, 0, 0);
+    const ICE: (u8, u8, u8) = (160, 160, 255);
+    const METAL: (u8, u8, u8) = (167, 167, 167);
+    const PLANT: (u8, u8, u8) = (0, 124, 0);
+    const SNOW: (u8, u8, u8) = (255, 255, 255);
+    const CLAY: (u8, u8, u8) = (164, 168, 184);
+    const DIRT: (u8, u8, u8) = (151, 109, 77);
+    const STONE: (u8, u8, u8) = (112, 112, 112);
+    const WATER: (u8, u8, u8) = (64, 64, 255);
+    const WOOD: (u8, u8, u8) = (143, 119, 72);
+    const QUARTZ: (u8, u8, u8) = (255, 252, 245);
+    const ORANGE: (u8, u8, u8) = (216, 127, 51);
+    const BLACK: (u8, u8, u8) = (25, 25, 25);
+    const PODZOL: (u8, u8, u8) = (129, 86, 49);
+    const NETHER: (u8, u8, u8) = (112, 2, 0);
+    const GOLD: (u8, u8, u8) = (250, 238, 77);
+    const DIAMOND: (u8, u8, u8) = (92, 219, 213);
+    const LAPIS: (u8, u8, u8) = (74, 128, 255);
+    const EMERALD: (u8, u8, u8) = (0, 217, 58);
+    const MYCELIUM: (u8, u8, u8) = (127, 63, 178);
+    const DEEPSLATE: (u8, u8, u8) = (100, 100, 100);
+
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    let color = match name {
+        "air" | "cave_air" | "void_air" | "barrier" | "light_block" | "structure_void" => {
+            return None
+        }
+        n if n.contains("glass") => return None,
+        "grass_block" | "grass" => GRASS,
+        "water" | "flowing_water" | "bubble_column" => WATER,
+        "lava" | "flowing_lava" | "fire" | "soul_fire" => FIRE,
+        "snow" | "snow_layer" | "powder_snow" => SNOW,
+        "sand" | "end_stone" | "glowstone" => SAND,
+        "red_sand" | "pumpkin" | "acacia_planks" => ORANGE,
+        "dirt" | "coarse_dirt" | "farmland" | "grass_path" | "dirt_path" | "rooted_dirt" => DIRT,
+        "podzol" | "spruce_planks" => PODZOL,
+        "mycelium" => MYCELIUM,
+        "clay" => CLAY,
+        "obsidian" | "crying_obsidian" | "coal_block" => BLACK,
+        "gold_block" => GOLD,
+        "diamond_block" => DIAMOND,
+        "lapis_block" => LAPIS,
+        "emerald_block" => EMERALD,
+        "iron_block" | "anvil" | "iron_bars" => METAL,
+        "netherrack" => NETHER,
+        n if n.contains("ice") => ICE,
+        n if n.contains("sandstone") => SAND,
+        n if n.contains("leaves")
+            || n.contains("sapling")
+            || n.contains("fern")
+            || n.contains("tall_grass")
+            || n.contains("flower")
+            || n.contains("tulip")
+            || matches!(
+                n,
+                "short_grass" | "dandelion" | "poppy" | "cactus" | "reeds" | "sugar_cane"
+            ) =>
+        {
+            PLANT
+        }
+        n if n.contains("deepslate") => DEEPSLATE,
+        n if n.contains("quartz") => QUARTZ,
+        n if n.contains("wool") || n.contains("carpet") => WOOL,
+        n if n.contains("log") || n.contains("planks") || n.contains("wood") => WOOD,
+        n if n.starts_with("nether") => NETHER,
+        _ => STONE,
+    };
+    Some(color)
+}
+
+/// Map ID stored in a filled map item's (network) NBT.
+pub fn map_id_from_item_nbt(nbt_data: &[u8]) -> Option<i64> {
+    if nbt_data.is_empty() {
+        return None;
+    }
+    let root = read_nbt_network_with_limits(&mut &nbt_data[..], &NbtLimits::untrusted()).ok()?;
+    root.compound.get(MAP_UUID_TAG).and_then(|t| t.as_long())
+}
+
+/// Network NBT of a filled map item bound to `map_id`.
+pub fn filled_map_nbt(map_id: i64) -> Vec<u8> {
+    let mut c = NbtCompound::new();
+    c.insert(MAP_UUID_TAG.into(), NbtTag::Long(map_id));
+    let mut buf = Vec::new();
+    write_nbt_network(&mut buf, &NbtRoot::new("", c));
+    buf
+}
+
+/// What a cartography table does with its two input items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CartographyCraft {
+    /// Filled map + empty map: two copies of the map.
+    Clone,
+    /// Filled map + paper: a new map one scale further out.
+    Extend,
+}
+
+/// The cartography craft for an input and additional item, if any.
+pub fn cartography_craft(input: &str, additional: &str) -> Option<CartographyCraft> {
+    match (input, additional) {
+        (FILLED_MAP, EMPTY_MAP) => Some(CartographyCraft::Clone),
+        (FILLED_MAP, "minecraft:paper") => Some(CartographyCraft::Extend),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_grid_alignment() {
+        // Scale 0 maps span [-64, 63], [64, 191], ...
+        assert_eq!(map_center(0, 0), 0);
+        assert_eq!(map_center(63, 0), 0);
+        assert_eq!(map_center(64, 0), 128);
+        assert_eq!(map_center(-65, 0), -128);
+        // Scale 1 maps are twice as wide
+        assert_eq!(map_center(100, 1), 64);
+        assert_eq!(map_center(200, 1), 320);
+
+        let map = MapData::new(1, 0, (10, -10), 0);
+        assert_eq!(map.origin(), (-64, -64));
+        let zoomed = map.zoomed_out(2).unwrap();
+        assert_eq!(zoomed.scale, 1);
+        assert_eq!(zoomed.parent_id, 1);
+        // Zoomed-out maps snap to their own, coarser grid
+        assert_eq!(zoomed.center, (64, 64));
+        assert_eq!(zoomed.origin(), (-64, -64));
+    }
+
+    #[test]
+    fn explore_fills_and_shades_pixels() {
+        let mut map = MapData::new(1, 0, (0, 0), 0);
+        // Ground rising one block per block south of z = 0
+        let surface = |_x: i32, z: i32| {
+            Some(MapSample {
+                color: (127, 178, 56),
+                height: z.max(0),
+            })
+        };
+        assert!(map.explore((0, 0), surface));
+        // Holder at pixel (64, 64): the row south of it is higher than its
+        // north neighbour, so it is bright
+        let bright = shaded_pixel((127, 178, 56), MapShade::Bright);
+        let normal = shaded_pixel((127, 178, 56), MapShade::Normal);
+        assert_eq!(map.pixels[65 * MAP_SIZE + 64], bright);
+        assert_eq!(map.pixels[64 * MAP_SIZE + 64], normal);
+        // North of z = 0 the ground is flat
+        assert_eq!(map.pixels[10 * MAP_SIZE + 64], normal);
+        // Corners are outside the explore circle
+        assert_eq!(map.pixels[0], 0);
+        // Exploring again changes nothing
+        assert!(!map.explore((0, 0), surface));
+
+        map.locked = true;
+        assert!(!map.explore((0, 0), |_, _| None));
+    }
+
+    #[test]
+    fn marker_positions() {
+        // Scale 1: centered on (64, 64), two blocks per pixel
+        let map = MapData::new(1, 0, (0, 0), 1);
+        assert_eq!(map.marker_position(64.0, 64.0), Some((0, 0)));
+        assert_eq!(map.marker_position(74.0, 44.0), Some((10, -20)));
+        assert_eq!(map.marker_position(400.0, 64.0), None);
+    }
+
+    #[test]
+    fn map_nbt_roundtrip() {
+        let mut map = MapData::new(-42, 1, (300, -300), 2);
+        map.pixels[5] = 0xFF12_3456;
+        map.locked = true;
+        map.markers.push(MapMarker {
+            decoration_type: 4,
+            x: 310,
+            z: -290,
+        });
+        let parsed = MapData::from_le_nbt(&map.to_le_nbt()).unwrap();
+        assert_eq!(parsed.id, -42);
+        assert_eq!(parsed.dimension, 1);
+        assert_eq!(parsed.center, map.center);
+        assert_eq!(parsed.scale, 2);
+        assert!(parsed.locked);
+        assert_eq!(parsed.pixels, map.pixels);
+        assert_eq!(parsed.markers, map.markers);
+        assert!(!parsed.dirty);
+    }
+
+    #[test]
+    fn explorer_maps_mark_their_target() {
+        let map = MapData::explorer(1, 0, (1000, -500), 2, 4);
+        assert_eq!(map.center, (1216, -320));
+        assert_eq!(map.marker_position(1000.0, -500.0), Some((-108, -90)));
+        assert_eq!(
+            map.markers,
+            [MapMarker {
+                decoration_type: 4,
+                x: 1000,
+                z: -500
+            }]
+        );
+        // Zooming out keeps the marker
+        assert_eq!(map.zoomed_out(2).unwrap().markers, map.markers);
+    }
+
+    #[test]
+    fn block_colors() {
+        assert_eq!(block_map_color("minecraft:air"), None);
+        assert_eq!(block_map_color("minecraft:glass"), None);
+        assert_eq!(block_map_color("minecraft:water"), Some((64, 64, 255)));
+        assert_eq!(block_map_color("minecraft:oak_leaves"), Some((0, 124, 0)));
+        assert_eq!(block_map_color("minecraft:stone"), Some((112, 112, 112)));
+        assert_eq!(
+            block_map_color("minecraft:packed_ice"),
+            Some((160, 160, 255))
+        );
+    }
+
+    #[test]
+    fn item_nbt_and_cartography() {
+        assert_eq!(map_id_from_item_nbt(&filled_map_nbt(-7)), Some(-7));
+        assert_eq!(map_id_from_item_nbt(&[]), None);
+        assert_eq!(
+            cartography_craft(FILLED_MAP, EMPTY_MAP),
+            Some(CartographyCraft::Clone)
+        );
+        assert_eq!(
+            cartography_craft(FILLED_MAP, "minecraft:paper"),
+            Some(CartographyCraft::Extend)
+        );
+        assert_eq!(cartography_craft(EMPTY_MAP, "minecraft:paper"), None);
+    }
+}
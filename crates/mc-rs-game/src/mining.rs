@@ -0,0 +1,321 @@
+//! Block breaking time — tool tiers, Efficiency, Haste/Mining Fatigue and
+//! the underwater and airborne penalties.
+
+use mc_rs_world::block_registry::ToolType;
+
+/// Material tier of a tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolTier {
+    Wood,
+    Gold,
+    Stone,
+    Iron,
+    Diamond,
+    Netherite,
+}
+
+impl ToolTier {
+    /// Mining speed multiplier on blocks of the tool's type.
+    pub fn speed(self) -> f32 {
+        match self {
+            ToolTier::Wood => 2.0,
+            ToolTier::Stone => 4.0,
+            ToolTier::Iron => 6.0,
+            ToolTier::Diamond => 8.0,
+            ToolTier::Netherite => 9.0,
+            ToolTier::Gold => 12.0,
+        }
+    }
+
+    /// Harvest level: the hardest blocks this tier can collect.
+    pub fn harvest_level(self) -> u8 {
+        match self {
+            ToolTier::Wood | ToolTier::Gold => 0,
+            ToolTier::Stone => 1,
+            ToolTier::Iron => 2,
+            ToolTier::Diamond => 3,
+            ToolTier::Netherite => 4,
+        }
+    }
+}
+
+/// A held mining tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tool {
+    pub kind: ToolType,
+    /// `None` for tools without tiers (shears).
+    pub tier: Option<ToolTier>,
+}
+
+/// The mining tool an item is, if any.
+pub fn tool_from_item(name: &str) -> Option<Tool> {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    if name == "shears" {
+        return Some(Tool {
+            kind: ToolType::Shears,
+            tier: None,
+        });
+    }
+    let (material, kind) = name.rsplit_once('_')?;
+    let kind = match kind {
+        "pickaxe" => ToolType::Pickaxe,
+        "axe" => ToolType::Axe,
+        "shovel" => ToolType::Shovel,
+        "hoe" => ToolType::Hoe,
+        "sword" => ToolType::Sword,
+        _ => return None,
+    };
+    let tier = match material {
+        "wooden" => ToolTier::Wood,
+        "stone" => ToolTier::Stone,
+        "iron" => ToolTier::Iron,
+        "golden" => ToolTier::Gold,
+        "diamond" => ToolTier::Diamond,
+        "netherite" => ToolTier::Netherite,
+        _ => return None,
+    };
+    Some(Tool {
+        kind,
+        tier: Some(tier),
+    })
+}
+
+/// Harvest level a pickaxe needs to collect a block, or `None` if the
+/// block drops without one.
+pub fn required_harvest_level(block_name: &str, tool_type: ToolType) -> Option<u8> {
+    if tool_type != ToolType::Pickaxe {
+        return None;
+    }
+    let name = block_name.strip_prefix("minecraft:").unwrap_or(block_name);
+    let level = match name {
+        "obsidian" | "crying_obsidian" | "ancient_debris" | "netherite_block"
+        | "respawn_anchor" => 3,
+        n if n.contains("diamond")
+            || n.contains("emerald")
+            || n.contains("redstone_ore")
+            || n == "gold_ore"
+            || n == "deepslate_gold_ore"
+            || n == "gold_block"
+            || n == "raw_gold_block" =>
+        {
+            2
+        }
+        n if n.contains("iron") || n.contains("lapis") || n.contains("copper") => 1,
+        _ => 0,
+    };
+    Some(level)
+}
+
+/// Everything about the player that changes how fast they break blocks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MiningContext {
+    pub tool: Option<Tool>,
+    /// Efficiency level of the held tool.
+    pub efficiency: i16,
+    /// Haste amplifier (0 = Haste I), if active.
+    pub haste: Option<i32>,
+    /// Mining Fatigue amplifier (0 = Mining Fatigue I), if active.
+    pub mining_fatigue: Option<i32>,
+    /// Head under water without Aqua Affinity.
+    pub underwater: bool,
+    pub on_ground: bool,
+}
+
+impl MiningContext {
+    /// Whether the held tool collects a block's drops.
+    pub fn can_harvest(&self, block_name: &str, tool_type: ToolType) -> bool {
+        match required_harvest_level(block_name, tool_type) {
+            None => true,
+            Some(level) => self.tool.is_some_and(|t| {
+                t.kind == ToolType::Pickaxe
+                    && t.tier.is_some_and(|tier| tier.harvest_level() >= level)
+            }),
+        }
+    }
+
+    /// Mining speed multiplier on a block.
+    fn speed(&self, block_name: &str, tool_type: ToolType) -> f32 {
+        let mut speed = match self.tool {
+            Some(Tool {
+                kind: ToolType::Shears,
+                ..
+            }) if block_name.contains("leaves") || block_name.ends_with("web") => 15.0,
+            Some(Tool {
+                kind: ToolType::Sword,
+                ..
+            }) if block_name.ends_with("web") => 15.0,
+            Some(Tool {
+                kind: ToolType::Sword,
+                ..
+            }) => 1.5,
+            Some(Tool {
+                kind: ToolType::Shears,
+                ..
+            }) if tool_type == ToolType::Shears || block_name.contains("wool") => 5.0,
+            Some(Tool {
+                kind,
+                tier: Some(tier),
+            }) if kind == tool_type => tier.speed(),
+            _ => 1.0,
+        };
+        if speed > 1.0 && self.efficiency > 0 {
+            speed += (self.efficiency as f32).powi(2) + 1.0;
+        }
+        if let Some(amp) = self.haste {
+            speed *= 1.0 + 0.2 * (amp + 1) as f32;
+        }
+        if let Some(amp) = self.mining_fatigue {
+            speed *= match amp {
+                0 => 0.3,
+                1 => 0.09,
+                2 => 0.0027,
+                _ => 0.00081,
+            };
+        }
+        if self.underwater {
+            speed /= 5.0;
+        }
+        if !self.on_ground {
+            speed /= 5.0;
+        }
+        speed
+    }
+
+    /// Ticks needed to break a block, 0 for an instant break. `None` if the
+    /// block is unbreakable.
+    pub fn break_ticks(&self, block_name: &str, hardness: f32, tool_type: ToolType) -> Option<u32> {
+        if hardness < 0.0 {
+            return None;
+        }
+        if hardness == 0.0 {
+            return Some(0);
+        }
+        let divisor = if self.can_harvest(block_name, tool_type) {
+            30.0
+        } else {
+            100.0
+        };
+        let damage = self.speed(block_name, tool_type) / hardness / divisor;
+        if damage > 1.0 {
+            return Some(0);
+        }
+        Some((1.0 / damage).ceil() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(item: &str) -> MiningContext {
+        MiningContext {
+            tool: tool_from_item(item),
+            on_ground: true,
+            ..MiningContext::default()
+        }
+    }
+
+    #[test]
+    fn tools_from_items() {
+        assert_eq!(
+            tool_from_item("minecraft:diamond_pickaxe"),
+            Some(Tool {
+                kind: ToolType::Pickaxe,
+                tier: Some(ToolTier::Diamond),
+            })
+        );
+        assert_eq!(
+            tool_from_item("minecraft:golden_axe").map(|t| t.kind),
+            Some(ToolType::Axe)
+        );
+        assert_eq!(
+            tool_from_item("minecraft:shears").map(|t| t.tier),
+            Some(None)
+        );
+        assert_eq!(tool_from_item("minecraft:stick"), None);
+        assert_eq!(tool_from_item("minecraft:iron_ingot"), None);
+    }
+
+    #[test]
+    fn harvest_levels() {
+        let hand = holding("minecraft:air");
+        assert!(!hand.can_harvest("minecraft:stone", ToolType::Pickaxe));
+        assert!(hand.can_harvest("minecraft:dirt", ToolType::Shovel));
+        assert!(
+            holding("minecraft:wooden_pickaxe").can_harvest("minecraft:stone", ToolType::Pickaxe)
+        );
+        assert!(!holding("minecraft:wooden_pickaxe")
+            .can_harvest("minecraft:iron_ore", ToolType::Pickaxe));
+        assert!(!holding("minecraft:stone_pickaxe")
+            .can_harvest("minecraft:diamond_ore", ToolType::Pickaxe));
+        assert!(holding("minecraft:iron_pickaxe")
+            .can_harvest("minecraft:diamond_ore", ToolType::Pickaxe));
+        assert!(
+            !holding("minecraft:iron_pickaxe").can_harvest("minecraft:obsidian", ToolType::Pickaxe)
+        );
+        assert!(holding("minecraft:diamond_pickaxe")
+            .can_harvest("minecraft:obsidian", ToolType::Pickaxe));
+    }
+
+    #[test]
+    fn vanilla_break_times() {
+        // Stone (1.5): 7.5 s by hand, 1.15 s with a wooden pickaxe
+        let stone =
+            |ctx: &MiningContext| ctx.break_ticks("minecraft:stone", 1.5, ToolType::Pickaxe);
+        assert_eq!(stone(&holding("minecraft:air")), Some(150));
+        assert_eq!(stone(&holding("minecraft:wooden_pickaxe")), Some(23));
+        assert_eq!(stone(&holding("minecraft:diamond_pickaxe")), Some(6));
+        // Efficiency V: 2 ticks, instant once Haste II is added
+        let mut eff = holding("minecraft:diamond_pickaxe");
+        eff.efficiency = 5;
+        assert_eq!(stone(&eff), Some(2));
+        eff.haste = Some(1);
+        assert_eq!(stone(&eff), Some(0));
+        // Dirt (0.5) by hand: 0.75 s
+        assert_eq!(
+            holding("minecraft:air").break_ticks("minecraft:dirt", 0.5, ToolType::Shovel),
+            Some(15)
+        );
+        assert_eq!(
+            holding("minecraft:air").break_ticks("minecraft:bedrock", -1.0, ToolType::None),
+            None
+        );
+        assert_eq!(
+            holding("minecraft:air").break_ticks("minecraft:torch", 0.0, ToolType::None),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn effects_and_penalties() {
+        let base = holding("minecraft:iron_pickaxe");
+        let ticks = |ctx: MiningContext| {
+            ctx.break_ticks("minecraft:stone", 1.5, ToolType::Pickaxe)
+                .unwrap()
+        };
+        let normal = ticks(base);
+
+        let hasted = MiningContext {
+            haste: Some(1),
+            ..base
+        };
+        assert!(ticks(hasted) < normal);
+
+        let fatigued = MiningContext {
+            mining_fatigue: Some(0),
+            ..base
+        };
+        assert!(ticks(fatigued) > normal * 3);
+
+        let underwater = MiningContext {
+            underwater: true,
+            ..base
+        };
+        let airborne = MiningContext {
+            on_ground: false,
+            ..base
+        };
+        assert_eq!(ticks(underwater), ticks(airborne));
+        assert!(ticks(underwater) > normal * 4);
+    }
+}
@@ -2,12 +2,22 @@
 //!
 //! Provides a registry of known mob types with their stats and hitbox dimensions.
 //! Supports both vanilla mobs (hardcoded) and custom mobs from behavior packs.
+//! Behavior pack mobs can also carry a data-driven [`EntityBehavior`]
+//! (component groups and events) that drives their AI at runtime.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use mc_rs_behavior_pack::entity_components::EntityBehavior;
 
 /// Mob category for spawn cap grouping.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MobCategory {
     Passive,
     Hostile,
+    /// Hostile, but only spawned at pillager outposts and by raids, outside
+    /// the spawn caps.
+    Raider,
 }
 
 /// Definition of a mob type.
@@ -31,6 +41,8 @@ pub struct MobDefinition {
 /// Registry of supported mob types.
 pub struct MobRegistry {
     mobs: Vec<MobDefinition>,
+    /// Data-driven entity definitions keyed by type identifier.
+    behaviors: HashMap<String, Arc<EntityBehavior>>,
 }
 
 impl Default for MobRegistry {
@@ -94,7 +106,28 @@ impl MobRegistry {
                     bb_width: 0.4,
                     bb_height: 0.7,
                 },
+                MobDefinition {
+                    type_id: "minecraft:pillager".into(),
+                    display_name: "Pillager".into(),
+                    category: MobCategory::Raider,
+                    max_health: 24.0,
+                    attack_damage: 4.0,
+                    movement_speed: 0.35,
+                    bb_width: 0.6,
+                    bb_height: 1.95,
+                },
+                MobDefinition {
+                    type_id: "minecraft:vindicator".into(),
+                    display_name: "Vindicator".into(),
+                    category: MobCategory::Raider,
+                    max_health: 24.0,
+                    attack_damage: 5.0,
+                    movement_speed: 0.35,
+                    bb_width: 0.6,
+                    bb_height: 1.95,
+                },
             ],
+            behaviors: HashMap::new(),
         }
     }
 
@@ -112,6 +145,17 @@ impl MobRegistry {
     pub fn register_mob(&mut self, def: MobDefinition) {
         self.mobs.push(def);
     }
+
+    /// Attach a data-driven definition (components, groups, events) to a mob type.
+    pub fn register_behavior(&mut self, type_id: &str, behavior: EntityBehavior) {
+        self.behaviors
+            .insert(type_id.to_string(), Arc::new(behavior));
+    }
+
+    /// The data-driven definition for a mob type, if it has one.
+    pub fn behavior(&self, type_id: &str) -> Option<Arc<EntityBehavior>> {
+        self.behaviors.get(type_id).cloned()
+    }
 }
 
 #[cfg(test)]
@@ -119,9 +163,9 @@ mod tests {
     use super::*;
 
     #[test]
-    fn registry_has_5_mobs() {
+    fn registry_has_7_mobs() {
         let reg = MobRegistry::new();
-        assert_eq!(reg.all().len(), 5);
+        assert_eq!(reg.all().len(), 7);
     }
 
     #[test]
@@ -152,6 +196,14 @@ mod tests {
         assert_eq!(c.category, MobCategory::Passive);
     }
 
+    #[test]
+    fn illagers_are_raiders() {
+        let reg = MobRegistry::new();
+        for type_id in ["minecraft:pillager", "minecraft:vindicator"] {
+            assert_eq!(reg.get(type_id).unwrap().category, MobCategory::Raider);
+        }
+    }
+
     #[test]
     fn register_custom_mob() {
         let mut reg = MobRegistry::new();
@@ -165,7 +217,7 @@ mod tests {
             bb_width: 0.6,
             bb_height: 1.95,
         });
-        assert_eq!(reg.all().len(), 6);
+        assert_eq!(reg.all().len(), 8);
         let g = reg.get("custom:guard").unwrap();
         assert_eq!(g.max_health, 40.0);
     }
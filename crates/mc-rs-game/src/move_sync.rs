@@ -0,0 +1,131 @@
+//! Which entity movements are worth broadcasting, and how.
+//!
+//! Positions are compared with the last one sent for the entity rather than
+//! the previous tick, so the position other players see is never off by
+//! more than [`POSITION_THRESHOLD`], however slowly the entity creeps.
+
+/// Smallest change of position (blocks) broadcast.
+pub const POSITION_THRESHOLD: f32 = 0.01;
+
+/// Smallest change of an angle (degrees) broadcast: one step of the byte
+/// angles of MoveActorAbsolute.
+pub const ROTATION_THRESHOLD: f32 = 360.0 / 256.0;
+
+/// Distance (blocks) from the last position sent past which clients are
+/// told to teleport the entity instead of interpolating towards it.
+pub const TELEPORT_DISTANCE: f32 = 8.0;
+
+/// A position and rotation as broadcast to other players.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SentMove {
+    pub position: (f32, f32, f32),
+    pub pitch: f32,
+    pub yaw: f32,
+    pub head_yaw: f32,
+    pub on_ground: bool,
+}
+
+/// What to broadcast for a movement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveUpdate {
+    /// Nothing changed enough to be seen.
+    Skip,
+    /// Only the rotation changed.
+    Rotation,
+    /// A move for clients to interpolate.
+    Interpolate,
+    /// Too far from the last position sent to interpolate.
+    Teleport,
+}
+
+/// How to broadcast `next`, given the last movement sent (`None` if none
+/// was yet).
+pub fn classify(last: Option<&SentMove>, next: &SentMove) -> MoveUpdate {
+    let Some(last) = last else {
+        return MoveUpdate::Teleport;
+    };
+    let (dx, dy, dz) = (
+        next.position.0 - last.position.0,
+        next.position.1 - last.position.1,
+        next.position.2 - last.position.2,
+    );
+    let distance_sq = dx * dx + dy * dy + dz * dz;
+    if distance_sq > TELEPORT_DISTANCE * TELEPORT_DISTANCE {
+        return MoveUpdate::Teleport;
+    }
+    if distance_sq >= POSITION_THRESHOLD * POSITION_THRESHOLD || next.on_ground != last.on_ground {
+        return MoveUpdate::Interpolate;
+    }
+    let turned = [
+        (last.pitch, next.pitch),
+        (last.yaw, next.yaw),
+        (last.head_yaw, next.head_yaw),
+    ]
+    .iter()
+    .any(|&(a, b)| angle_difference(a, b) >= ROTATION_THRESHOLD);
+    if turned {
+        MoveUpdate::Rotation
+    } else {
+        MoveUpdate::Skip
+    }
+}
+
+/// Difference between two angles in degrees, going the short way round.
+fn angle_difference(a: f32, b: f32) -> f32 {
+    let d = (b - a).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f32, yaw: f32) -> SentMove {
+        SentMove {
+            position: (x, 64.0, 0.0),
+            pitch: 0.0,
+            yaw,
+            head_yaw: yaw,
+            on_ground: true,
+        }
+    }
+
+    #[test]
+    fn small_changes_are_skipped() {
+        let last = at(0.0, 0.0);
+        assert_eq!(classify(Some(&last), &at(0.005, 0.5)), MoveUpdate::Skip);
+        // Creeping is measured from the last move sent
+        assert_eq!(
+            classify(Some(&last), &at(0.02, 0.0)),
+            MoveUpdate::Interpolate
+        );
+        let landed = SentMove {
+            on_ground: false,
+            ..last
+        };
+        assert_eq!(classify(Some(&landed), &last), MoveUpdate::Interpolate);
+    }
+
+    #[test]
+    fn turning_in_place_sends_the_rotation() {
+        let last = at(0.0, 359.0);
+        assert_eq!(classify(Some(&last), &at(0.0, 1.0)), MoveUpdate::Rotation);
+        assert_eq!(classify(Some(&last), &at(0.0, 359.5)), MoveUpdate::Skip);
+        // Moving as well sends the whole move
+        assert_eq!(
+            classify(Some(&last), &at(0.5, 10.0)),
+            MoveUpdate::Interpolate
+        );
+    }
+
+    #[test]
+    fn far_moves_teleport() {
+        let last = at(0.0, 0.0);
+        assert_eq!(
+            classify(Some(&last), &at(7.9, 0.0)),
+            MoveUpdate::Interpolate
+        );
+        assert_eq!(classify(Some(&last), &at(20.0, 0.0)), MoveUpdate::Teleport);
+        assert_eq!(classify(None, &last), MoveUpdate::Teleport);
+    }
+}
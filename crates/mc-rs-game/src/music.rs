@@ -0,0 +1,176 @@
+//! Note blocks and jukeboxes: instruments, pitches and music discs.
+
+/// Number of note block pitches (two octaves, F♯ to F♯).
+pub const NOTE_PITCHES: u8 = 25;
+
+/// Note block instruments, by Bedrock instrument ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Instrument {
+    Harp = 0,
+    BassDrum = 1,
+    Snare = 2,
+    Hat = 3,
+    Bass = 4,
+    Bell = 5,
+    Flute = 6,
+    Chime = 7,
+    Guitar = 8,
+    Xylophone = 9,
+    IronXylophone = 10,
+    CowBell = 11,
+    Didgeridoo = 12,
+    Bit = 13,
+    Banjo = 14,
+    Pling = 15,
+}
+
+impl Instrument {
+    /// The instrument of a note block standing on `block_below`.
+    pub fn for_block_below(block_below: &str) -> Self {
+        let name = block_below
+            .strip_prefix("minecraft:")
+            .unwrap_or(block_below);
+        match name {
+            "gold_block" => return Instrument::Bell,
+            "clay" => return Instrument::Flute,
+            "packed_ice" => return Instrument::Chime,
+            "bone_block" => return Instrument::Xylophone,
+            "iron_block" => return Instrument::IronXylophone,
+            "soul_sand" => return Instrument::CowBell,
+            "pumpkin" | "carved_pumpkin" => return Instrument::Didgeridoo,
+            "emerald_block" => return Instrument::Bit,
+            "hay_block" => return Instrument::Banjo,
+            "glowstone" => return Instrument::Pling,
+            "sea_lantern" | "beacon" => return Instrument::Hat,
+            "sand" | "red_sand" | "gravel" | "suspicious_sand" | "suspicious_gravel" => {
+                return Instrument::Snare
+            }
+            _ => {}
+        }
+        if name.ends_with("_wool") {
+            Instrument::Guitar
+        } else if name.contains("glass") {
+            Instrument::Hat
+        } else if name.ends_with("concrete_powder") {
+            Instrument::Snare
+        } else if ["planks", "_log", "_wood", "_stem", "hyphae", "bookshelf"]
+            .iter()
+            .any(|w| name.contains(w))
+        {
+            Instrument::Bass
+        } else if [
+            "stone",
+            "cobble",
+            "_ore",
+            "brick",
+            "netherrack",
+            "obsidian",
+            "concrete",
+            "terracotta",
+            "quartz",
+            "deepslate",
+            "basalt",
+            "nylium",
+            "bedrock",
+            "prismarine",
+            "purpur",
+            "tuff",
+        ]
+        .iter()
+        .any(|w| name.contains(w))
+        {
+            Instrument::BassDrum
+        } else {
+            Instrument::Harp
+        }
+    }
+}
+
+/// `LevelSoundEvent` extra data of a note.
+pub fn note_sound_data(instrument: Instrument, pitch: u8) -> i32 {
+    ((instrument as i32) << 8) | pitch as i32
+}
+
+/// A music disc a jukebox can play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MusicDisc {
+    pub item: &'static str,
+    /// Offset of its record from `SOUND_RECORD_13`.
+    pub record: u32,
+    /// Length of the record, in seconds.
+    pub seconds: u32,
+}
+
+impl MusicDisc {
+    /// Length of the record, in ticks.
+    pub fn ticks(&self) -> u32 {
+        self.seconds * 20
+    }
+}
+
+/// The original discs, in the order of their record sounds.
+pub const MUSIC_DISCS: [MusicDisc; 12] = [
+    disc("minecraft:music_disc_13", 0, 178),
+    disc("minecraft:music_disc_cat", 1, 185),
+    disc("minecraft:music_disc_blocks", 2, 345),
+    disc("minecraft:music_disc_chirp", 3, 185),
+    disc("minecraft:music_disc_far", 4, 174),
+    disc("minecraft:music_disc_mall", 5, 197),
+    disc("minecraft:music_disc_mellohi", 6, 96),
+    disc("minecraft:music_disc_stal", 7, 150),
+    disc("minecraft:music_disc_strad", 8, 188),
+    disc("minecraft:music_disc_ward", 9, 251),
+    disc("minecraft:music_disc_11", 10, 71),
+    disc("minecraft:music_disc_wait", 11, 238),
+];
+
+const fn disc(item: &'static str, record: u32, seconds: u32) -> MusicDisc {
+    MusicDisc {
+        item,
+        record,
+        seconds,
+    }
+}
+
+/// Look up a music disc by item name.
+pub fn music_disc(item_name: &str) -> Option<&'static MusicDisc> {
+    MUSIC_DISCS.iter().find(|d| d.item == item_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instruments_from_the_block_below() {
+        let of = Instrument::for_block_below;
+        assert_eq!(of("minecraft:dirt"), Instrument::Harp);
+        assert_eq!(of("minecraft:stone"), Instrument::BassDrum);
+        assert_eq!(of("minecraft:sandstone"), Instrument::BassDrum);
+        assert_eq!(of("minecraft:sand"), Instrument::Snare);
+        assert_eq!(of("minecraft:glass"), Instrument::Hat);
+        assert_eq!(of("minecraft:oak_planks"), Instrument::Bass);
+        assert_eq!(of("minecraft:white_wool"), Instrument::Guitar);
+        assert_eq!(of("minecraft:glowstone"), Instrument::Pling);
+        assert_eq!(of("minecraft:gold_block"), Instrument::Bell);
+        assert_eq!(of("minecraft:hay_block"), Instrument::Banjo);
+    }
+
+    #[test]
+    fn note_data_packs_instrument_and_pitch() {
+        assert_eq!(note_sound_data(Instrument::Harp, 12), 12);
+        assert_eq!(note_sound_data(Instrument::Bass, 24), (4 << 8) | 24);
+    }
+
+    #[test]
+    fn discs() {
+        let cat = music_disc("minecraft:music_disc_cat").unwrap();
+        assert_eq!(cat.record, 1);
+        assert_eq!(cat.ticks(), 185 * 20);
+        assert!(music_disc("minecraft:stick").is_none());
+        for (i, d) in MUSIC_DISCS.iter().enumerate() {
+            assert_eq!(d.record, i as u32);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! Raids: what a player carrying Bad Omen into a village sets off, wave
+//! after wave of raiders, and the Hero of the Village reward for beating
+//! them.
+//!
+//! Bad Omen comes from killing a raid captain. Its level (amplifier + 1)
+//! sets the raid's omen level, which adds a bonus wave above level 1 and
+//! raises the hero reward.
+
+use crate::difficulty::Difficulty;
+
+/// Mob type of pillagers.
+pub const PILLAGER: &str = "minecraft:pillager";
+/// Mob type of vindicators.
+pub const VINDICATOR: &str = "minecraft:vindicator";
+
+/// Highest Bad Omen level.
+pub const MAX_OMEN_LEVEL: i32 = 5;
+/// How long Bad Omen from a captain lasts (100 minutes).
+pub const BAD_OMEN_TICKS: i32 = 120_000;
+/// How long Hero of the Village lasts (40 minutes).
+pub const HERO_TICKS: i32 = 48_000;
+/// Distance from the village center within which players take part in a
+/// raid and see its boss bar.
+pub const RAID_RADIUS: f32 = 96.0;
+/// Ticks between the end of a wave and the next one.
+pub const WAVE_COOLDOWN_TICKS: u32 = 300;
+/// Ticks before an unfinished raid is lost.
+pub const RAID_TIMEOUT_TICKS: u64 = 48_000;
+/// Ticks the victory or defeat bar stays up once a raid ends.
+pub const RAID_END_TICKS: u32 = 600;
+/// Raiders left in a wave at which the boss bar starts counting them.
+const SHOW_REMAINING_AT: usize = 2;
+
+/// Pillagers and vindicators in each wave, from the first.
+const WAVE_RAIDERS: [(u32, u32); 7] = [(4, 0), (3, 2), (3, 0), (4, 1), (4, 4), (4, 2), (2, 5)];
+
+/// Regular waves of a raid at a difficulty.
+pub fn wave_count(difficulty: Difficulty) -> u32 {
+    match difficulty {
+        Difficulty::Peaceful => 0,
+        Difficulty::Easy => 3,
+        Difficulty::Normal => 5,
+        Difficulty::Hard => 7,
+    }
+}
+
+/// Mob types of the raiders in wave `wave` (from 1). Waves past the table,
+/// like the bonus wave on Hard, repeat its last row.
+pub fn wave_raiders(wave: u32) -> Vec<&'static str> {
+    let index = (wave.max(1) as usize - 1).min(WAVE_RAIDERS.len() - 1);
+    let (pillagers, vindicators) = WAVE_RAIDERS[index];
+    let mut raiders = vec![PILLAGER; pillagers as usize];
+    raiders.extend(std::iter::repeat_n(VINDICATOR, vindicators as usize));
+    raiders
+}
+
+/// Bad Omen amplifier after killing a captain while at `current` (none if
+/// the player had no Bad Omen): one level more, up to [`MAX_OMEN_LEVEL`].
+pub fn bad_omen_after_captain_kill(current: Option<i32>) -> i32 {
+    current.map_or(0, |amplifier| (amplifier + 1).min(MAX_OMEN_LEVEL - 1))
+}
+
+/// Hero of the Village amplifier for winning a raid of `omen_level`.
+pub fn hero_amplifier(omen_level: i32) -> i32 {
+    (omen_level - 1).clamp(0, MAX_OMEN_LEVEL - 1)
+}
+
+/// Where a raid stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaidStatus {
+    Ongoing,
+    Victory,
+    Defeat,
+}
+
+/// What a raid needs done after a tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RaidAction {
+    None,
+    /// Spawn the raiders of a new wave, then [`Raid::add_raider`] them.
+    SpawnWave(Vec<&'static str>),
+    /// The last wave is beaten: reward the heroes.
+    Won,
+    /// The raid ran out of time.
+    Lost,
+}
+
+/// A raid on a village.
+#[derive(Debug, Clone)]
+pub struct Raid {
+    /// Block at the center of the village.
+    pub center: (i32, i32, i32),
+    pub omen_level: i32,
+    pub total_waves: u32,
+    /// Waves spawned so far.
+    pub waves: u32,
+    pub status: RaidStatus,
+    /// Runtime IDs of the living raiders of the current wave.
+    raiders: Vec<u64>,
+    /// Total health of the current wave when it spawned.
+    wave_health: f32,
+    /// Health of the living raiders at the last tick.
+    health: f32,
+    /// Ticks until the next wave while none is alive.
+    cooldown: u32,
+    ticks: u64,
+    /// Ticks left with the end bar up, once the raid is over.
+    end_ticks: u32,
+}
+
+impl Raid {
+    /// Start a raid of `omen_level` on the village at `center`: the regular
+    /// waves of the difficulty, plus a bonus wave above omen level 1.
+    pub fn new(center: (i32, i32, i32), omen_level: i32, difficulty: Difficulty) -> Self {
+        let omen_level = omen_level.clamp(1, MAX_OMEN_LEVEL);
+        Self {
+            center,
+            omen_level,
+            total_waves: wave_count(difficulty) + (omen_level > 1) as u32,
+            waves: 0,
+            status: RaidStatus::Ongoing,
+            raiders: Vec::new(),
+            wave_health: 0.0,
+            health: 0.0,
+            cooldown: WAVE_COOLDOWN_TICKS,
+            ticks: 0,
+            end_ticks: RAID_END_TICKS,
+        }
+    }
+
+    /// Count a raider of the current wave, spawned with `max_health`.
+    pub fn add_raider(&mut self, runtime_id: u64, max_health: f32) {
+        self.raiders.push(runtime_id);
+        self.wave_health += max_health;
+        self.health += max_health;
+    }
+
+    /// Whether a mob is one of the raid's living raiders.
+    pub fn has_raider(&self, runtime_id: u64) -> bool {
+        self.raiders.contains(&runtime_id)
+    }
+
+    /// Living raiders of the current wave.
+    pub fn raiders_left(&self) -> usize {
+        self.raiders.len()
+    }
+
+    /// Run one tick. `raider_health` gives the health of a raider, `None`
+    /// once it is gone.
+    pub fn tick(&mut self, mut raider_health: impl FnMut(u64) -> Option<f32>) -> RaidAction {
+        if self.status != RaidStatus::Ongoing {
+            self.end_ticks = self.end_ticks.saturating_sub(1);
+            return RaidAction::None;
+        }
+        self.ticks += 1;
+        if self.ticks >= RAID_TIMEOUT_TICKS {
+            self.status = RaidStatus::Defeat;
+            return RaidAction::Lost;
+        }
+
+        let mut health = 0.0;
+        self.raiders.retain(|&rid| match raider_health(rid) {
+            Some(h) if h > 0.0 => {
+                health += h;
+                true
+            }
+            _ => false,
+        });
+        self.health = health;
+        if !self.raiders.is_empty() {
+            return RaidAction::None;
+        }
+        if self.waves >= self.total_waves {
+            self.status = RaidStatus::Victory;
+            return RaidAction::Won;
+        }
+        if self.cooldown > 0 {
+            self.cooldown -= 1;
+            return RaidAction::None;
+        }
+        self.waves += 1;
+        self.cooldown = WAVE_COOLDOWN_TICKS;
+        self.wave_health = 0.0;
+        RaidAction::SpawnWave(wave_raiders(self.waves))
+    }
+
+    /// Whether the raid is over and its end bar has been shown long enough.
+    pub fn finished(&self) -> bool {
+        self.status != RaidStatus::Ongoing && self.end_ticks == 0
+    }
+
+    /// Boss bar fill: the health left in the current wave, or the bar
+    /// filling up while the next wave gathers.
+    pub fn progress(&self) -> f32 {
+        match self.status {
+            RaidStatus::Victory => 1.0,
+            RaidStatus::Defeat => 0.0,
+            RaidStatus::Ongoing if self.raiders.is_empty() => {
+                1.0 - self.cooldown as f32 / WAVE_COOLDOWN_TICKS as f32
+            }
+            RaidStatus::Ongoing if self.wave_health > 0.0 => {
+                (self.health / self.wave_health).clamp(0.0, 1.0)
+            }
+            RaidStatus::Ongoing => 1.0,
+        }
+    }
+
+    /// Boss bar title.
+    pub fn title(&self) -> String {
+        match self.status {
+            RaidStatus::Victory => "Raid - Victory".into(),
+            RaidStatus::Defeat => "Raid - Defeat".into(),
+            RaidStatus::Ongoing
+                if !self.raiders.is_empty() && self.raiders.len() <= SHOW_REMAINING_AT =>
+            {
+                format!("Raid - Raiders Remaining: {}", self.raiders.len())
+            }
+            RaidStatus::Ongoing => "Raid".into(),
+        }
+    }
+
+    /// Whether a position is close enough to the village to take part.
+    pub fn in_range(&self, x: f32, z: f32) -> bool {
+        let dx = x - (self.center.0 as f32 + 0.5);
+        let dz = z - (self.center.2 as f32 + 0.5);
+        dx * dx + dz * dz <= RAID_RADIUS * RAID_RADIUS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tick until the raid asks for something.
+    fn next_action(raid: &mut Raid, health: impl Fn(u64) -> Option<f32>) -> RaidAction {
+        loop {
+            let action = raid.tick(&health);
+            if action != RaidAction::None {
+                return action;
+            }
+        }
+    }
+
+    #[test]
+    fn waves_by_difficulty_and_omen() {
+        assert_eq!(Raid::new((0, 64, 0), 1, Difficulty::Easy).total_waves, 3);
+        assert_eq!(Raid::new((0, 64, 0), 1, Difficulty::Normal).total_waves, 5);
+        // A bonus wave above omen level 1
+        assert_eq!(Raid::new((0, 64, 0), 2, Difficulty::Hard).total_waves, 8);
+        assert_eq!(Raid::new((0, 64, 0), 9, Difficulty::Easy).omen_level, 5);
+
+        assert_eq!(wave_raiders(1), [PILLAGER; 4]);
+        assert_eq!(
+            wave_raiders(2),
+            [PILLAGER, PILLAGER, PILLAGER, VINDICATOR, VINDICATOR]
+        );
+        assert_eq!(wave_raiders(8), wave_raiders(7));
+    }
+
+    #[test]
+    fn omen_levels_and_rewards() {
+        assert_eq!(bad_omen_after_captain_kill(None), 0);
+        assert_eq!(bad_omen_after_captain_kill(Some(0)), 1);
+        assert_eq!(bad_omen_after_captain_kill(Some(4)), 4);
+        assert_eq!(hero_amplifier(1), 0);
+        assert_eq!(hero_amplifier(3), 2);
+    }
+
+    #[test]
+    fn raid_runs_wave_by_wave_to_victory() {
+        let mut raid = Raid::new((0, 64, 0), 1, Difficulty::Easy);
+        // The bar fills while the first wave gathers
+        assert_eq!(raid.progress(), 0.0);
+        for _ in 0..150 {
+            raid.tick(|_| None);
+        }
+        assert!((raid.progress() - 0.5).abs() < 0.01);
+
+        for wave in 1..=3 {
+            let action = next_action(&mut raid, |_| None);
+            assert_eq!(action, RaidAction::SpawnWave(wave_raiders(wave)));
+            raid.add_raider(1, 24.0);
+            raid.add_raider(2, 24.0);
+            assert_eq!(
+                raid.tick(|rid| Some(if rid == 1 { 12.0 } else { 24.0 })),
+                RaidAction::None
+            );
+            assert!((raid.progress() - 0.75).abs() < 0.01);
+            assert_eq!(raid.title(), "Raid - Raiders Remaining: 2");
+            // Raider 2 dies
+            raid.tick(|rid| (rid == 1).then_some(12.0));
+            assert_eq!(raid.raiders_left(), 1);
+            assert!(raid.has_raider(1) && !raid.has_raider(2));
+        }
+        assert_eq!(next_action(&mut raid, |_| None), RaidAction::Won);
+        assert_eq!(raid.status, RaidStatus::Victory);
+        assert_eq!(raid.title(), "Raid - Victory");
+        assert!(!raid.finished());
+        for _ in 0..RAID_END_TICKS {
+            raid.tick(|_| None);
+        }
+        assert!(raid.finished());
+    }
+
+    #[test]
+    fn raids_time_out() {
+        let mut raid = Raid::new((0, 64, 0), 1, Difficulty::Normal);
+        next_action(&mut raid, |_| None);
+        raid.add_raider(1, 24.0);
+        assert_eq!(next_action(&mut raid, |_| Some(24.0)), RaidAction::Lost);
+        assert_eq!(raid.title(), "Raid - Defeat");
+    }
+
+    #[test]
+    fn raid_range() {
+        let raid = Raid::new((100, 64, 100), 1, Difficulty::Normal);
+        assert!(raid.in_range(100.0, 190.0));
+        assert!(!raid.in_range(100.0, 200.0));
+    }
+}
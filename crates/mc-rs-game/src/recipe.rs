@@ -58,6 +58,16 @@ pub struct ShapelessRecipe {
     pub tag: String,
 }
 
+/// A recipe whose logic is built into the client, identified by a fixed
+/// UUID (e.g. cloning and extending maps in the cartography table).
+#[derive(Debug, Clone)]
+pub struct MultiRecipe {
+    /// UUID the client knows the recipe by.
+    pub uuid: String,
+    /// Network ID for protocol.
+    pub network_id: u32,
+}
+
 /// Reference to either a shaped or shapeless recipe.
 #[derive(Debug, Clone)]
 pub enum RecipeRef<'a> {
@@ -78,6 +88,7 @@ impl RecipeRef<'_> {
 pub struct RecipeRegistry {
     shaped: Vec<ShapedRecipe>,
     shapeless: Vec<ShapelessRecipe>,
+    multi: Vec<MultiRecipe>,
 }
 
 impl Default for RecipeRegistry {
@@ -801,6 +812,14 @@ impl RecipeRegistry {
             1
         );
 
+        // Shield decoration (the banner's colors are copied when crafting)
+        shapeless!(
+            "shield_decoration",
+            vec![inp("minecraft:shield", -1), inp("minecraft:banner", -1)],
+            "minecraft:shield",
+            1
+        );
+
         // ---- Stonecutter recipes ----
         macro_rules! stonecutter {
             ($input:expr, $output:expr, $count:expr) => {{
@@ -874,8 +893,25 @@ impl RecipeRegistry {
         stonecutter!("minecraft:sandstone", "minecraft:cut_sandstone_slab", 2);
         stonecutter!("minecraft:sandstone", "minecraft:chiseled_sandstone", 1);
 
+        // ---- Multi recipes (cartography table) ----
+        let mut multi = Vec::new();
+        for uuid in [
+            crate::map::MAP_CLONING_RECIPE_UUID,
+            crate::map::MAP_EXTENDING_RECIPE_UUID,
+        ] {
+            multi.push(MultiRecipe {
+                uuid: uuid.to_string(),
+                network_id: next_id,
+            });
+            next_id += 1;
+        }
+
         let _ = next_id;
-        Self { shaped, shapeless }
+        Self {
+            shaped,
+            shapeless,
+            multi,
+        }
     }
 
     /// Look up any recipe by network ID.
@@ -903,6 +939,16 @@ impl RecipeRegistry {
         &self.shapeless
     }
 
+    /// Get all multi recipes.
+    pub fn multi_recipes(&self) -> &[MultiRecipe] {
+        &self.multi
+    }
+
+    /// Look up a multi recipe by network ID.
+    pub fn get_multi_by_network_id(&self, id: u32) -> Option<&MultiRecipe> {
+        self.multi.iter().find(|r| r.network_id == id)
+    }
+
     /// Total number of recipes.
     pub fn len(&self) -> usize {
         self.shaped.len() + self.shapeless.len()
@@ -922,7 +968,8 @@ impl RecipeRegistry {
             .map(|r| r.network_id)
             .max()
             .unwrap_or(0);
-        max_shaped.max(max_shapeless) + 1
+        let max_multi = self.multi.iter().map(|r| r.network_id).max().unwrap_or(0);
+        max_shaped.max(max_shapeless).max(max_multi) + 1
     }
 
     /// Register a custom shaped recipe. Returns the assigned network_id.
@@ -1009,12 +1056,24 @@ mod tests {
         let reg = RecipeRegistry::new();
         let mut ids: Vec<u32> = reg.shaped_recipes().iter().map(|r| r.network_id).collect();
         ids.extend(reg.shapeless_recipes().iter().map(|r| r.network_id));
+        ids.extend(reg.multi_recipes().iter().map(|r| r.network_id));
         let len = ids.len();
         ids.sort();
         ids.dedup();
         assert_eq!(ids.len(), len, "Duplicate network IDs found");
     }
 
+    #[test]
+    fn map_multi_recipes() {
+        let reg = RecipeRegistry::new();
+        assert_eq!(reg.multi_recipes().len(), 2);
+        let cloning = &reg.multi_recipes()[0];
+        assert_eq!(cloning.uuid, crate::map::MAP_CLONING_RECIPE_UUID);
+        let found = reg.get_multi_by_network_id(cloning.network_id).unwrap();
+        assert_eq!(found.uuid, cloning.uuid);
+        assert!(reg.get_by_network_id(cloning.network_id).is_none());
+    }
+
     #[test]
     fn register_custom_recipe() {
         let mut reg = RecipeRegistry::new();
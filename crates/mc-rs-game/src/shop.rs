@@ -0,0 +1,306 @@
+//! Chest shops: the sign format and moving stock in and out of slots.
+//!
+//! A shop sign reads, line by line:
+//!
+//! ```text
+//! [Shop]
+//! 16 diamond
+//! B 10 : S 5
+//! ```
+//!
+//! Buyers pay 10 for 16 diamonds out of the chest, sellers get 5 for
+//! putting 16 in. Either price can be left out. The server writes the
+//! owner's name on the fourth line.
+
+use std::fmt;
+
+use mc_rs_proto::item_stack::ItemStack;
+
+/// First line of a shop sign (compared ignoring case).
+pub const SHOP_TAG: &str = "[Shop]";
+/// Most items one trade can move: a full single chest.
+pub const MAX_SHOP_AMOUNT: u16 = 27 * 64;
+
+/// A parsed shop sign.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShopSign {
+    /// Items per trade.
+    pub amount: u16,
+    /// Item name, with its namespace.
+    pub item: String,
+    /// What a buyer pays, if the shop sells.
+    pub buy: Option<u64>,
+    /// What a seller gets, if the shop buys.
+    pub sell: Option<u64>,
+}
+
+/// Why a `[Shop]` sign is not a valid shop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShopSignError {
+    BadAmount,
+    MissingItem,
+    BadPrices,
+}
+
+impl fmt::Display for ShopSignError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ShopSignError::BadAmount => "the second line must start with an amount (1-1728)",
+            ShopSignError::MissingItem => "the second line must name an item after the amount",
+            ShopSignError::BadPrices => "the third line must hold prices like \"B 10 : S 5\"",
+        })
+    }
+}
+
+/// Whether sign text asks for a shop.
+pub fn is_shop_sign(text: &str) -> bool {
+    text.lines()
+        .next()
+        .is_some_and(|l| l.trim().eq_ignore_ascii_case(SHOP_TAG))
+}
+
+/// Parse the text of a `[Shop]` sign. Returns `None` for any other sign.
+pub fn parse_shop_sign(text: &str) -> Option<Result<ShopSign, ShopSignError>> {
+    if !is_shop_sign(text) {
+        return None;
+    }
+    let mut lines = text.lines().skip(1);
+    Some(parse_lines(
+        lines.next().unwrap_or(""),
+        lines.next().unwrap_or(""),
+    ))
+}
+
+fn parse_lines(item_line: &str, price_line: &str) -> Result<ShopSign, ShopSignError> {
+    let mut words = item_line.split_whitespace();
+    let amount = words
+        .next()
+        .and_then(|w| w.parse::<u16>().ok())
+        .filter(|a| (1..=MAX_SHOP_AMOUNT).contains(a))
+        .ok_or(ShopSignError::BadAmount)?;
+    let item = words.next().ok_or(ShopSignError::MissingItem)?;
+    if words.next().is_some() {
+        return Err(ShopSignError::MissingItem);
+    }
+    let item = if item.contains(':') {
+        item.to_ascii_lowercase()
+    } else {
+        format!("minecraft:{}", item.to_ascii_lowercase())
+    };
+
+    let (mut buy, mut sell) = (None, None);
+    let normalized = price_line.replace(':', " ");
+    let mut words = normalized.split_whitespace();
+    while let Some(side) = words.next() {
+        let price = words
+            .next()
+            .and_then(|p| p.parse::<u64>().ok())
+            .ok_or(ShopSignError::BadPrices)?;
+        let slot = match side.to_ascii_uppercase().as_str() {
+            "B" => &mut buy,
+            "S" => &mut sell,
+            _ => return Err(ShopSignError::BadPrices),
+        };
+        if slot.replace(price).is_some() {
+            return Err(ShopSignError::BadPrices);
+        }
+    }
+    if buy.is_none() && sell.is_none() {
+        return Err(ShopSignError::BadPrices);
+    }
+    Ok(ShopSign {
+        amount,
+        item,
+        buy,
+        sell,
+    })
+}
+
+impl ShopSign {
+    /// The sign text the server shows for this shop.
+    pub fn sign_text(&self, owner: &str) -> String {
+        let item = self.item.strip_prefix("minecraft:").unwrap_or(&self.item);
+        let prices = match (self.buy, self.sell) {
+            (Some(b), Some(s)) => format!("B {b} : S {s}"),
+            (Some(b), None) => format!("B {b}"),
+            (None, Some(s)) => format!("S {s}"),
+            (None, None) => String::new(),
+        };
+        format!("{SHOP_TAG}\n{} {item}\n{prices}\n{owner}", self.amount)
+    }
+}
+
+/// A stack a shop trades: the item itself, without data or custom NBT.
+fn is_stock(slot: &ItemStack, runtime_id: i32) -> bool {
+    !slot.is_empty()
+        && slot.runtime_id == runtime_id
+        && slot.metadata == 0
+        && slot.nbt_data.is_empty()
+}
+
+/// Number of plain `runtime_id` items in some slots.
+pub fn count_stock(slots: &[ItemStack], runtime_id: i32) -> u32 {
+    slots
+        .iter()
+        .filter(|s| is_stock(s, runtime_id))
+        .map(|s| s.count as u32)
+        .sum()
+}
+
+/// Remove `amount` plain `runtime_id` items, emptying the last stacks
+/// first. Nothing is removed if there aren't enough.
+pub fn take_stock(slots: &mut [ItemStack], runtime_id: i32, amount: u16) -> bool {
+    if count_stock(slots, runtime_id) < amount as u32 {
+        return false;
+    }
+    let mut left = amount;
+    for slot in slots.iter_mut().rev() {
+        if left == 0 {
+            break;
+        }
+        if is_stock(slot, runtime_id) {
+            let taken = left.min(slot.count);
+            slot.count -= taken;
+            left -= taken;
+            if slot.count == 0 {
+                *slot = ItemStack::empty();
+            }
+        }
+    }
+    true
+}
+
+/// How many plain `runtime_id` items still fit in some slots.
+pub fn stock_room(slots: &[ItemStack], runtime_id: i32, max_stack: u16) -> u32 {
+    slots
+        .iter()
+        .map(|s| {
+            if s.is_empty() {
+                max_stack as u32
+            } else if is_stock(s, runtime_id) {
+                max_stack.saturating_sub(s.count) as u32
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+/// Put `amount` plain `runtime_id` items in some slots, topping up stacks
+/// before filling empty slots. `next_id` numbers the new stacks. Nothing
+/// is added if they don't all fit.
+pub fn add_stock(
+    slots: &mut [ItemStack],
+    runtime_id: i32,
+    amount: u16,
+    max_stack: u16,
+    mut next_id: impl FnMut() -> i32,
+) -> bool {
+    if stock_room(slots, runtime_id, max_stack) < amount as u32 {
+        return false;
+    }
+    let mut left = amount;
+    for slot in slots.iter_mut().filter(|s| is_stock(s, runtime_id)) {
+        let moved = left.min(max_stack.saturating_sub(slot.count));
+        slot.count += moved;
+        left -= moved;
+    }
+    for slot in slots.iter_mut().filter(|s| s.is_empty()) {
+        if left == 0 {
+            break;
+        }
+        let moved = left.min(max_stack);
+        let mut stack = ItemStack::new(runtime_id, moved);
+        stack.stack_network_id = next_id();
+        *slot = stack;
+        left -= moved;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slots(stacks: &[(i32, u16)]) -> Vec<ItemStack> {
+        stacks
+            .iter()
+            .map(|&(id, count)| {
+                if count == 0 {
+                    ItemStack::empty()
+                } else {
+                    ItemStack::new(id, count)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_shop_signs() {
+        let shop = parse_shop_sign("[shop]\n16 Diamond\nB 10 : S 5")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            shop,
+            ShopSign {
+                amount: 16,
+                item: "minecraft:diamond".into(),
+                buy: Some(10),
+                sell: Some(5),
+            }
+        );
+        let sell_only = parse_shop_sign("[Shop]\n1 foo:gem\ns 3").unwrap().unwrap();
+        assert_eq!(
+            (sell_only.item.as_str(), sell_only.buy, sell_only.sell),
+            ("foo:gem", None, Some(3))
+        );
+        assert!(parse_shop_sign("Hello\n16 diamond\nB 10").is_none());
+    }
+
+    #[test]
+    fn rejects_bad_shop_signs() {
+        let err = |text| parse_shop_sign(text).unwrap().unwrap_err();
+        assert_eq!(err("[Shop]\nlots diamond\nB 1"), ShopSignError::BadAmount);
+        assert_eq!(err("[Shop]\n0 diamond\nB 1"), ShopSignError::BadAmount);
+        assert_eq!(err("[Shop]\n4\nB 1"), ShopSignError::MissingItem);
+        assert_eq!(err("[Shop]\n4 diamond\n"), ShopSignError::BadPrices);
+        assert_eq!(err("[Shop]\n4 diamond\nB 1 B 2"), ShopSignError::BadPrices);
+        assert_eq!(err("[Shop]\n4 diamond\nX 1"), ShopSignError::BadPrices);
+    }
+
+    #[test]
+    fn sign_text_round_trips() {
+        let shop = parse_shop_sign("[Shop]\n16 diamond\nS 5").unwrap().unwrap();
+        let text = shop.sign_text("Alice");
+        assert_eq!(text, "[Shop]\n16 diamond\nS 5\nAlice");
+        assert_eq!(parse_shop_sign(&text).unwrap().unwrap(), shop);
+    }
+
+    #[test]
+    fn takes_stock_only_when_there_is_enough() {
+        let mut chest = slots(&[(5, 10), (6, 64), (5, 10)]);
+        chest[2].nbt_data = vec![10, 0, 0];
+        assert_eq!(count_stock(&chest, 5), 10);
+        assert!(!take_stock(&mut chest, 5, 11));
+        assert_eq!(chest[0].count, 10);
+        assert!(take_stock(&mut chest, 5, 10));
+        assert!(chest[0].is_empty());
+        assert_eq!(chest[2].count, 10, "named items are not stock");
+    }
+
+    #[test]
+    fn adds_stock_only_when_it_fits() {
+        let mut chest = slots(&[(5, 60), (6, 1), (0, 0)]);
+        assert_eq!(stock_room(&chest, 5, 64), 68);
+        assert!(!add_stock(&mut chest, 5, 69, 64, || 1));
+        assert_eq!(chest[0].count, 60);
+        let mut ids = 100;
+        assert!(add_stock(&mut chest, 5, 10, 64, || {
+            ids += 1;
+            ids
+        }));
+        assert_eq!(chest[0].count, 64);
+        assert_eq!((chest[2].runtime_id, chest[2].count), (5, 6));
+        assert_eq!(chest[2].stack_network_id, 101);
+    }
+}
@@ -0,0 +1,141 @@
+//! Sign text — per-face text, dye colors and glow ink.
+
+use crate::loom::dye_color_from_item;
+
+/// Default sign text color (opaque black, ARGB).
+pub const DEFAULT_SIGN_COLOR: u32 = 0xFF00_0000;
+
+/// Sign text colors (ARGB) by dye color index (0 = white … 15 = black).
+pub const SIGN_DYE_COLORS: [u32; 16] = [
+    0xFFF0_F0F0, // white
+    0xFFF9_801D, // orange
+    0xFFC7_4EBD, // magenta
+    0xFF3A_B3DA, // light blue
+    0xFFFE_D83D, // yellow
+    0xFF80_C71F, // lime
+    0xFFF3_8BAA, // pink
+    0xFF47_4F52, // gray
+    0xFF9D_9D97, // light gray
+    0xFF16_9C9C, // cyan
+    0xFF89_32B8, // purple
+    0xFF3C_44AA, // blue
+    0xFF83_5432, // brown
+    0xFF5E_7C16, // green
+    0xFFB0_2E26, // red
+    0xFF1D_1D21, // black
+];
+
+/// Text and styling of one sign face.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignText {
+    pub text: String,
+    /// Text color (ARGB).
+    pub color: u32,
+    /// Glow ink applied: the text ignores lighting.
+    pub glowing: bool,
+}
+
+impl Default for SignText {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            color: DEFAULT_SIGN_COLOR,
+            glowing: false,
+        }
+    }
+}
+
+impl SignText {
+    /// Text with the default style.
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Apply an ink item to this face. Returns false if nothing changed,
+    /// in which case the item should not be consumed.
+    pub fn apply_ink(&mut self, ink: SignInk) -> bool {
+        match ink {
+            SignInk::Dye(color) if self.color != color => self.color = color,
+            SignInk::Glow if !self.glowing => self.glowing = true,
+            SignInk::Plain if self.glowing => self.glowing = false,
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// An item that restyles sign text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignInk {
+    /// A dye: sets the text color (ARGB).
+    Dye(u32),
+    /// Glow ink sac: makes the text glow.
+    Glow,
+    /// Ink sac: removes the glow.
+    Plain,
+}
+
+/// Map a held item to the ink it applies to a sign, if any.
+pub fn sign_ink(item_name: &str) -> Option<SignInk> {
+    match item_name {
+        "minecraft:glow_ink_sac" => Some(SignInk::Glow),
+        "minecraft:ink_sac" => Some(SignInk::Plain),
+        name if name.ends_with("_dye") => {
+            dye_color_from_item(name).map(|i| SignInk::Dye(SIGN_DYE_COLORS[i as usize]))
+        }
+        _ => None,
+    }
+}
+
+/// Whether a player at `(px, pz)` sees the front face of a sign at block
+/// `(x, z)` whose front faces along `normal` (horizontal unit vector).
+pub fn is_front_side(normal: (f32, f32), (x, z): (i32, i32), (px, pz): (f32, f32)) -> bool {
+    let dx = px - (x as f32 + 0.5);
+    let dz = pz - (z as f32 + 0.5);
+    dx * normal.0 + dz * normal.1 >= 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_ink_from_items() {
+        assert_eq!(
+            sign_ink("minecraft:red_dye"),
+            Some(SignInk::Dye(SIGN_DYE_COLORS[14]))
+        );
+        assert_eq!(sign_ink("minecraft:glow_ink_sac"), Some(SignInk::Glow));
+        assert_eq!(sign_ink("minecraft:ink_sac"), Some(SignInk::Plain));
+        // Dye substitutes only work in the loom
+        assert_eq!(sign_ink("minecraft:bone_meal"), None);
+        assert_eq!(sign_ink("minecraft:stick"), None);
+    }
+
+    #[test]
+    fn apply_ink_reports_changes() {
+        let mut face = SignText::plain("Hi");
+        assert!(!face.apply_ink(SignInk::Plain));
+        assert!(face.apply_ink(SignInk::Glow));
+        assert!(!face.apply_ink(SignInk::Glow));
+        assert!(face.apply_ink(SignInk::Dye(SIGN_DYE_COLORS[11])));
+        assert!(!face.apply_ink(SignInk::Dye(SIGN_DYE_COLORS[11])));
+        assert!(face.apply_ink(SignInk::Plain));
+        assert_eq!(face.color, SIGN_DYE_COLORS[11]);
+        assert!(!face.glowing);
+        assert_eq!(face.text, "Hi");
+    }
+
+    #[test]
+    fn front_side_from_player_position() {
+        // Front facing south (+z)
+        assert!(is_front_side((0.0, 1.0), (0, 0), (0.5, 3.0)));
+        assert!(!is_front_side((0.0, 1.0), (0, 0), (0.5, -2.0)));
+        // Front facing west (-x)
+        assert!(is_front_side((-1.0, 0.0), (10, 4), (8.0, 4.5)));
+        assert!(!is_front_side((-1.0, 0.0), (10, 4), (12.0, 4.5)));
+    }
+}
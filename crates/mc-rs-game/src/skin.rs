@@ -0,0 +1,134 @@
+//! Skin validation — rejects skins that would break other players' clients.
+
+use mc_rs_proto::jwt::{ClientData, SkinImage};
+
+/// Skin image sizes the client can render, as `(width, height)`.
+pub const SKIN_SIZES: [(u32, u32); 7] = [
+    (64, 32),
+    (64, 64),
+    (128, 64),
+    (128, 128),
+    (256, 128),
+    (256, 256),
+    (512, 512),
+];
+
+/// Cape image size (an empty image means no cape).
+pub const CAPE_SIZE: (u32, u32) = (64, 32);
+
+/// Longest accepted skin geometry JSON, in bytes.
+pub const MAX_SKIN_GEOMETRY_LEN: usize = 512 * 1024;
+
+/// Longest accepted skin resource patch JSON, in bytes.
+pub const MAX_SKIN_RESOURCE_PATCH_LEN: usize = 16 * 1024;
+
+/// Check that a skin is safe to relay to other players: image sizes the
+/// client supports with matching RGBA data, and bounded geometry.
+pub fn validate_skin(skin: &ClientData) -> Result<(), String> {
+    let image = &skin.skin_image;
+    if !SKIN_SIZES.contains(&(image.width, image.height)) {
+        return Err(format!(
+            "unsupported skin size {}x{}",
+            image.width, image.height
+        ));
+    }
+    check_image_data("skin", image)?;
+
+    let cape = &skin.cape_image;
+    if cape.width != 0 || cape.height != 0 || !cape.data.is_empty() {
+        if (cape.width, cape.height) != CAPE_SIZE {
+            return Err(format!(
+                "unsupported cape size {}x{}",
+                cape.width, cape.height
+            ));
+        }
+        check_image_data("cape", cape)?;
+    }
+
+    if skin.skin_geometry_data.len() > MAX_SKIN_GEOMETRY_LEN {
+        return Err(format!(
+            "skin geometry too large ({} bytes)",
+            skin.skin_geometry_data.len()
+        ));
+    }
+    if skin.skin_resource_patch.len() > MAX_SKIN_RESOURCE_PATCH_LEN {
+        return Err(format!(
+            "skin resource patch too large ({} bytes)",
+            skin.skin_resource_patch.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Check that an image holds exactly one RGBA pixel per texel.
+fn check_image_data(what: &str, image: &SkinImage) -> Result<(), String> {
+    let expected = image.width as usize * image.height as usize * 4;
+    if image.data.len() != expected {
+        return Err(format!(
+            "{what} data is {} bytes, expected {expected} for {}x{}",
+            image.data.len(),
+            image.width,
+            image.height
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skin(width: u32, height: u32) -> ClientData {
+        ClientData {
+            skin_image: SkinImage {
+                width,
+                height,
+                data: vec![0; (width * height * 4) as usize],
+            },
+            ..ClientData::default()
+        }
+    }
+
+    #[test]
+    fn accepts_standard_skins() {
+        assert!(validate_skin(&skin(64, 64)).is_ok());
+        assert!(validate_skin(&skin(128, 128)).is_ok());
+
+        let mut caped = skin(64, 32);
+        caped.cape_image = SkinImage {
+            width: 64,
+            height: 32,
+            data: vec![0; 64 * 32 * 4],
+        };
+        assert!(validate_skin(&caped).is_ok());
+    }
+
+    #[test]
+    fn rejects_bad_images() {
+        assert!(validate_skin(&skin(0, 0)).is_err());
+        assert!(validate_skin(&skin(1024, 1024)).is_err());
+
+        let mut truncated = skin(64, 64);
+        truncated.skin_image.data.pop();
+        assert!(validate_skin(&truncated).is_err());
+
+        let mut bad_cape = skin(64, 64);
+        bad_cape.cape_image = SkinImage {
+            width: 64,
+            height: 64,
+            data: vec![0; 64 * 64 * 4],
+        };
+        assert!(validate_skin(&bad_cape).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_geometry() {
+        let mut crash = skin(64, 64);
+        crash.skin_geometry_data = "x".repeat(MAX_SKIN_GEOMETRY_LEN + 1);
+        assert!(validate_skin(&crash).is_err());
+
+        let mut patch = skin(64, 64);
+        patch.skin_resource_patch = "x".repeat(MAX_SKIN_RESOURCE_PATCH_LEN + 1);
+        assert!(validate_skin(&patch).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Mob and player heads: the owner a player head carries in its item and
+//! block entity NBT, and the rotation of heads placed on the floor.
+
+use mc_rs_nbt::tag::{NbtCompound, NbtRoot, NbtTag};
+use mc_rs_nbt::{read_nbt_network_with_limits, write_nbt_network, NbtLimits};
+use mc_rs_world::block_hash::HEAD_BLOCKS;
+
+/// `SkullType` of a player head.
+pub const PLAYER_HEAD: i8 = 3;
+
+/// Whose head a player head is: the `SkullOwner` compound of its NBT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkullOwner {
+    pub name: String,
+    /// Skin the owner wore when the head was made.
+    pub skin_id: String,
+}
+
+impl SkullOwner {
+    /// Build the `SkullOwner` compound.
+    pub fn to_nbt(&self) -> NbtTag {
+        let mut c = NbtCompound::new();
+        c.insert("Name".to_string(), NbtTag::String(self.name.clone()));
+        c.insert("SkinId".to_string(), NbtTag::String(self.skin_id.clone()));
+        NbtTag::Compound(c)
+    }
+
+    /// Read a `SkullOwner` compound. A bare string is taken as the name.
+    pub fn from_nbt(tag: &NbtTag) -> Option<Self> {
+        if let Some(name) = tag.as_string() {
+            return Some(Self {
+                name: name.to_string(),
+                skin_id: String::new(),
+            });
+        }
+        let c = tag.as_compound()?;
+        let name = c.get("Name").and_then(|t| t.as_string())?;
+        Some(Self {
+            name: name.to_string(),
+            skin_id: c
+                .get("SkinId")
+                .and_then(|t| t.as_string())
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+
+    /// Read the owner from the network NBT of a player head item.
+    pub fn from_item_nbt(data: &[u8]) -> Option<Self> {
+        if data.is_empty() {
+            return None;
+        }
+        let root = read_nbt_network_with_limits(&mut &data[..], &NbtLimits::untrusted()).ok()?;
+        Self::from_nbt(root.compound.get("SkullOwner")?)
+    }
+
+    /// Network NBT of a player head item with this owner, named after them.
+    pub fn item_nbt(&self) -> Vec<u8> {
+        let mut c = NbtCompound::new();
+        self.write_item_nbt(&mut c);
+        let mut buf = Vec::new();
+        write_nbt_network(&mut buf, &NbtRoot::new("", c));
+        buf
+    }
+
+    /// Add the owner and a "<name>'s Head" display name to item NBT,
+    /// keeping a display name already there.
+    pub fn write_item_nbt(&self, c: &mut NbtCompound) {
+        c.insert("SkullOwner".to_string(), self.to_nbt());
+        let display = c
+            .entry("display".to_string())
+            .or_insert_with(|| NbtTag::Compound(NbtCompound::new()));
+        if let NbtTag::Compound(display) = display {
+            display
+                .entry("Name".to_string())
+                .or_insert_with(|| NbtTag::String(format!("{}'s Head", self.name)));
+        }
+    }
+}
+
+/// Item name of the head with a `SkullType`.
+pub fn head_item(skull_type: i8) -> Option<&'static str> {
+    HEAD_BLOCKS.get(usize::try_from(skull_type).ok()?).copied()
+}
+
+/// `Rotation` of a head placed on the floor, facing a player looking
+/// along `yaw`, in 16 steps of 22.5° like standing signs.
+pub fn rotation_from_yaw(yaw: f32) -> f32 {
+    let step = ((yaw + 180.0) / 22.5).floor() as i32;
+    step.rem_euclid(16) as f32 * 22.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steve() -> SkullOwner {
+        SkullOwner {
+            name: "Steve".into(),
+            skin_id: "Standard_Custom".into(),
+        }
+    }
+
+    #[test]
+    fn owner_item_nbt_roundtrip() {
+        let nbt = steve().item_nbt();
+        assert_eq!(SkullOwner::from_item_nbt(&nbt), Some(steve()));
+        let root = mc_rs_nbt::read_nbt_network(&mut &nbt[..]).unwrap();
+        let name = root
+            .compound
+            .get("display")
+            .and_then(|d| d.as_compound())
+            .and_then(|d| d.get("Name"))
+            .and_then(|n| n.as_string());
+        assert_eq!(name, Some("Steve's Head"));
+        assert_eq!(SkullOwner::from_item_nbt(&[]), None);
+    }
+
+    #[test]
+    fn display_name_is_kept() {
+        let mut display = NbtCompound::new();
+        display.insert("Name".into(), NbtTag::String("Trophy".into()));
+        let mut c = NbtCompound::new();
+        c.insert("display".into(), NbtTag::Compound(display));
+        steve().write_item_nbt(&mut c);
+        let name = c["display"].as_compound().unwrap()["Name"].as_string();
+        assert_eq!(name, Some("Trophy"));
+    }
+
+    #[test]
+    fn owner_from_bare_name() {
+        let owner = SkullOwner::from_nbt(&NbtTag::String("Alex".into())).unwrap();
+        assert_eq!(owner.name, "Alex");
+        assert!(owner.skin_id.is_empty());
+        assert_eq!(SkullOwner::from_nbt(&NbtTag::Int(1)), None);
+    }
+
+    #[test]
+    fn head_items() {
+        assert_eq!(head_item(PLAYER_HEAD), Some("minecraft:player_head"));
+        assert_eq!(head_item(0), Some("minecraft:skeleton_skull"));
+        assert_eq!(head_item(7), None);
+        assert_eq!(head_item(-1), None);
+    }
+
+    #[test]
+    fn floor_rotation() {
+        assert_eq!(rotation_from_yaw(-180.0), 0.0);
+        assert_eq!(rotation_from_yaw(0.0), 180.0);
+        assert_eq!(rotation_from_yaw(12.0), 180.0);
+        assert_eq!(rotation_from_yaw(25.0), 202.5);
+        assert_eq!(rotation_from_yaw(179.0), 337.5);
+        assert_eq!(rotation_from_yaw(180.0), 0.0);
+    }
+}
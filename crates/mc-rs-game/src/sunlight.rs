@@ -0,0 +1,78 @@
+//! Undead burning in daylight.
+//!
+//! The network layer tells the game world which mobs stand under open sky
+//! while the sun is up and it isn't raining. Undead among them catch fire,
+//! unless they wear a helmet, which wears out in their place.
+
+/// Time of day from which the sun has set.
+pub const SUNSET: i64 = 12_542;
+/// Time of day at which the sun rises again.
+pub const SUNRISE: i64 = 23_460;
+/// Fire ticks daylight sets an undead mob on fire for (8 seconds).
+pub const DAYLIGHT_FIRE_TICKS: u32 = 160;
+/// Ticks between fire damage.
+pub const FIRE_DAMAGE_INTERVAL: u32 = 20;
+/// Damage fire deals each [`FIRE_DAMAGE_INTERVAL`].
+pub const FIRE_DAMAGE: f32 = 1.0;
+/// Durability of the helmets naturally spawned undead may wear.
+pub const HELMET_DURABILITY: u32 = 165;
+/// Chance of a naturally spawned undead mob wearing a helmet.
+pub const HELMET_CHANCE: f64 = 0.1;
+/// Item shown on undead wearing a helmet.
+pub const HELMET_ITEM: &str = "minecraft:iron_helmet";
+
+/// Whether a mob type burns in daylight.
+pub fn is_undead(mob_type: &str) -> bool {
+    matches!(
+        mob_type,
+        "minecraft:zombie"
+            | "minecraft:zombie_villager"
+            | "minecraft:zombie_villager_v2"
+            | "minecraft:skeleton"
+            | "minecraft:stray"
+            | "minecraft:drowned"
+            | "minecraft:phantom"
+    )
+}
+
+/// Whether the sun is up at a time of day.
+pub fn is_daytime(time: i64) -> bool {
+    let time = time.rem_euclid(24_000);
+    !(SUNSET..SUNRISE).contains(&time)
+}
+
+/// Helmet durability lost in a second of daylight: one point, like a hit,
+/// on a random half of the seconds.
+pub fn helmet_wear(roll: f64) -> u32 {
+    (roll < 0.5) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undead_types() {
+        assert!(is_undead("minecraft:zombie"));
+        assert!(is_undead("minecraft:skeleton"));
+        assert!(!is_undead("minecraft:husk"));
+        assert!(!is_undead("minecraft:pillager"));
+        assert!(!is_undead("minecraft:cow"));
+    }
+
+    #[test]
+    fn daytime_follows_the_sun() {
+        assert!(is_daytime(0));
+        assert!(is_daytime(6000));
+        assert!(!is_daytime(SUNSET));
+        assert!(!is_daytime(18000));
+        assert!(is_daytime(SUNRISE));
+        assert!(is_daytime(24_000 + 1000));
+    }
+
+    #[test]
+    fn helmets_wear_half_the_time() {
+        assert_eq!(helmet_wear(0.2), 1);
+        assert_eq!(helmet_wear(0.7), 0);
+    }
+}
@@ -73,6 +73,11 @@ pub fn xp_lost_on_death(level: i32, total_xp: i32) -> i32 {
     loss.min(total_xp).max(0)
 }
 
+/// XP dropped as orbs on death: `level * 7`, capped at 100 and at the player's total.
+pub fn xp_dropped_on_death(level: i32, total_xp: i32) -> i32 {
+    xp_lost_on_death(level, total_xp).min(100)
+}
+
 /// Compute new `(level, total)` after dying.
 pub fn after_death(level: i32, total_xp: i32) -> (i32, i32) {
     let loss = xp_lost_on_death(level, total_xp);
@@ -88,7 +93,10 @@ pub fn after_death(level: i32, total_xp: i32) -> (i32, i32) {
 /// XP dropped by a mob on death.
 pub fn mob_xp(mob_type: &str) -> i32 {
     match mob_type {
-        "minecraft:zombie" | "minecraft:skeleton" => 5,
+        "minecraft:zombie"
+        | "minecraft:skeleton"
+        | "minecraft:pillager"
+        | "minecraft:vindicator" => 5,
         "minecraft:cow" | "minecraft:pig" | "minecraft:chicken" => 2,
         _ => 0,
     }
@@ -235,6 +243,13 @@ mod tests {
         assert_eq!(xp_lost_on_death(10, 30), 30);
     }
 
+    #[test]
+    fn xp_dropped_on_death_capped() {
+        assert_eq!(xp_dropped_on_death(10, total_xp_for_level(10)), 70);
+        assert_eq!(xp_dropped_on_death(30, total_xp_for_level(30)), 100);
+        assert_eq!(xp_dropped_on_death(0, 0), 0);
+    }
+
     #[test]
     fn after_death_values() {
         let total_10 = total_xp_for_level(10);
@@ -248,6 +263,7 @@ mod tests {
     fn mob_xp_values() {
         assert_eq!(mob_xp("minecraft:zombie"), 5);
         assert_eq!(mob_xp("minecraft:skeleton"), 5);
+        assert_eq!(mob_xp("minecraft:vindicator"), 5);
         assert_eq!(mob_xp("minecraft:cow"), 2);
         assert_eq!(mob_xp("minecraft:pig"), 2);
         assert_eq!(mob_xp("minecraft:chicken"), 2);
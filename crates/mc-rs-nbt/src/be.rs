@@ -0,0 +1,150 @@
+//! Big-endian NBT variant (Java Edition: Anvil region files, .nbt structures).
+//!
+//! Strings use Java's modified UTF-8: NUL is `C0 80` and characters outside
+//! the BMP are written as two 3-byte surrogates.
+
+use std::borrow::Cow;
+
+use bytes::{Buf, BufMut};
+
+use crate::error::NbtError;
+use crate::io::{ensure_remaining, NbtVariant};
+
+pub(crate) struct BeVariant;
+
+impl NbtVariant for BeVariant {
+    fn write_int(buf: &mut impl BufMut, value: i32) {
+        buf.put_i32(value);
+    }
+
+    fn read_int(buf: &mut impl Buf) -> Result<i32, NbtError> {
+        ensure_remaining(buf, 4)?;
+        Ok(buf.get_i32())
+    }
+
+    fn write_short(buf: &mut impl BufMut, value: i16) {
+        buf.put_i16(value);
+    }
+
+    fn read_short(buf: &mut impl Buf) -> Result<i16, NbtError> {
+        ensure_remaining(buf, 2)?;
+        Ok(buf.get_i16())
+    }
+
+    fn write_long(buf: &mut impl BufMut, value: i64) {
+        buf.put_i64(value);
+    }
+
+    fn read_long(buf: &mut impl Buf) -> Result<i64, NbtError> {
+        ensure_remaining(buf, 8)?;
+        Ok(buf.get_i64())
+    }
+
+    fn write_float(buf: &mut impl BufMut, value: f32) {
+        buf.put_f32(value);
+    }
+
+    fn read_float(buf: &mut impl Buf) -> Result<f32, NbtError> {
+        ensure_remaining(buf, 4)?;
+        Ok(buf.get_f32())
+    }
+
+    fn write_double(buf: &mut impl BufMut, value: f64) {
+        buf.put_f64(value);
+    }
+
+    fn read_double(buf: &mut impl Buf) -> Result<f64, NbtError> {
+        ensure_remaining(buf, 8)?;
+        Ok(buf.get_f64())
+    }
+
+    fn write_array_len(buf: &mut impl BufMut, len: i32) {
+        buf.put_i32(len);
+    }
+
+    fn read_array_len(buf: &mut impl Buf) -> Result<i32, NbtError> {
+        Self::read_int(buf)
+    }
+
+    fn write_string_len(buf: &mut impl BufMut, len: usize) {
+        buf.put_u16(len as u16);
+    }
+
+    fn read_string_len(buf: &mut impl Buf) -> Result<usize, NbtError> {
+        ensure_remaining(buf, 2)?;
+        Ok(buf.get_u16() as usize)
+    }
+
+    fn encode_string(s: &str) -> Cow<'_, [u8]> {
+        if !s.chars().any(|c| c == '\0' || c as u32 > 0xFFFF) {
+            return Cow::Borrowed(s.as_bytes());
+        }
+        let mut out = Vec::with_capacity(s.len() + 4);
+        let mut units = [0u16; 2];
+        for c in s.chars() {
+            if c == '\0' {
+                out.extend_from_slice(&[0xC0, 0x80]);
+            } else if (c as u32) <= 0xFFFF {
+                out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            } else {
+                for &unit in c.encode_utf16(&mut units).iter() {
+                    out.extend_from_slice(&[
+                        0xE0 | (unit >> 12) as u8,
+                        0x80 | ((unit >> 6) & 0x3F) as u8,
+                        0x80 | (unit & 0x3F) as u8,
+                    ]);
+                }
+            }
+        }
+        Cow::Owned(out)
+    }
+
+    fn decode_string(data: &[u8]) -> Result<String, NbtError> {
+        // Modified UTF-8 without NULs or surrogates is plain UTF-8
+        if let Ok(s) = std::str::from_utf8(data) {
+            return Ok(s.to_string());
+        }
+        let mut units = Vec::with_capacity(data.len());
+        let cont = |i: usize| match data.get(i) {
+            Some(&b) if b & 0xC0 == 0x80 => Ok((b & 0x3F) as u16),
+            _ => Err(NbtError::InvalidUtf8),
+        };
+        let mut i = 0;
+        while i < data.len() {
+            let b = data[i];
+            let (unit, len) = match b {
+                0x00..=0x7F => (b as u16, 1),
+                0xC0..=0xDF => (((b & 0x1F) as u16) << 6 | cont(i + 1)?, 2),
+                0xE0..=0xEF => (
+                    ((b & 0x0F) as u16) << 12 | cont(i + 1)? << 6 | cont(i + 2)?,
+                    3,
+                ),
+                _ => return Err(NbtError::InvalidUtf8),
+            };
+            units.push(unit);
+            i += len;
+        }
+        String::from_utf16(&units).map_err(|_| NbtError::InvalidUtf8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modified_utf8_strings() {
+        for s in ["plain", "é€", "nul\0byte", "emoji 😀"] {
+            let data = BeVariant::encode_string(s);
+            assert_eq!(BeVariant::decode_string(&data).unwrap(), s);
+        }
+        assert_eq!(&*BeVariant::encode_string("a\0"), &[b'a', 0xC0, 0x80]);
+        // U+1F600 as a surrogate pair, 3 bytes each
+        assert_eq!(
+            &*BeVariant::encode_string("😀"),
+            &[0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]
+        );
+        assert!(BeVariant::decode_string(&[0xED, 0xA0, 0xBD]).is_err());
+        assert!(BeVariant::decode_string(&[0xC0]).is_err());
+    }
+}
@@ -0,0 +1,419 @@
+//! Serde deserializer reading [`NbtTag`] values.
+//!
+//! Accepts the layout produced by [`crate::ser`]. Unsigned integers are read
+//! back from the signed tag of the same width, `bool` from a `Byte`, and the
+//! array tags (`ByteArray`, `IntArray`, `LongArray`) can be read as sequences.
+//! Missing compound entries deserialize to `None` for `Option` fields.
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+
+use crate::error::NbtError;
+use crate::tag::{NbtCompound, NbtTag};
+
+/// Deserialize a value from an NBT tag.
+pub fn from_nbt<T: DeserializeOwned>(tag: NbtTag) -> Result<T, NbtError> {
+    T::deserialize(TagDeserializer(tag))
+}
+
+/// Deserialize a value from an NBT compound.
+pub fn from_nbt_compound<T: DeserializeOwned>(compound: NbtCompound) -> Result<T, NbtError> {
+    from_nbt(NbtTag::Compound(compound))
+}
+
+impl de::Error for NbtError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        NbtError::Custom(msg.to_string())
+    }
+}
+
+/// Deserializer over a single tag.
+struct TagDeserializer(NbtTag);
+
+impl TagDeserializer {
+    fn invalid(&self, expected: &str) -> NbtError {
+        NbtError::Custom(format!(
+            "expected {expected}, got tag type {}",
+            self.0.tag_type_id()
+        ))
+    }
+}
+
+/// Read an unsigned integer from the signed tag of the same width.
+macro_rules! deserialize_unsigned {
+    ($method:ident, $visit:ident, $variant:ident, $ty:ty, $name:literal) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, NbtError> {
+            match self.0 {
+                NbtTag::$variant(v) => visitor.$visit(v as $ty),
+                _ => Err(self.invalid($name)),
+            }
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for TagDeserializer {
+    type Error = NbtError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, NbtError> {
+        match self.0 {
+            NbtTag::Byte(v) => visitor.visit_i8(v),
+            NbtTag::Short(v) => visitor.visit_i16(v),
+            NbtTag::Int(v) => visitor.visit_i32(v),
+            NbtTag::Long(v) => visitor.visit_i64(v),
+            NbtTag::Float(v) => visitor.visit_f32(v),
+            NbtTag::Double(v) => visitor.visit_f64(v),
+            NbtTag::String(v) => visitor.visit_string(v),
+            NbtTag::ByteArray(v) => {
+                visitor.visit_seq(ListAccess::new(v.into_iter().map(NbtTag::Byte).collect()))
+            }
+            NbtTag::IntArray(v) => {
+                visitor.visit_seq(ListAccess::new(v.into_iter().map(NbtTag::Int).collect()))
+            }
+            NbtTag::LongArray(v) => {
+                visitor.visit_seq(ListAccess::new(v.into_iter().map(NbtTag::Long).collect()))
+            }
+            NbtTag::List(v) => visitor.visit_seq(ListAccess::new(v)),
+            NbtTag::Compound(c) => visitor.visit_map(CompoundAccess::new(c)),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, NbtError> {
+        match self.0 {
+            NbtTag::Byte(v) => visitor.visit_bool(v != 0),
+            _ => Err(self.invalid("a byte")),
+        }
+    }
+
+    deserialize_unsigned!(deserialize_u8, visit_u8, Byte, u8, "a byte");
+    deserialize_unsigned!(deserialize_u16, visit_u16, Short, u16, "a short");
+    deserialize_unsigned!(deserialize_u32, visit_u32, Int, u32, "an int");
+    deserialize_unsigned!(deserialize_u64, visit_u64, Long, u64, "a long");
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, NbtError> {
+        match self.0 {
+            NbtTag::ByteArray(v) => {
+                visitor.visit_byte_buf(v.into_iter().map(|b| b as u8).collect())
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, NbtError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, NbtError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, NbtError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NbtError> {
+        match self.0 {
+            NbtTag::Compound(_) => visitor.visit_unit(),
+            _ => Err(self.invalid("a compound")),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, NbtError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NbtError> {
+        match self.0 {
+            NbtTag::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            NbtTag::Compound(c) if c.len() == 1 => {
+                let (variant, value) = c.into_iter().next().expect("one entry");
+                visitor.visit_enum(VariantAccess { variant, value })
+            }
+            _ => Err(self.invalid("a string or a compound with one entry")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u128 f32 f64 char str string
+        seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Sequence access over the elements of a list or array tag.
+struct ListAccess {
+    items: std::vec::IntoIter<NbtTag>,
+}
+
+impl ListAccess {
+    fn new(items: Vec<NbtTag>) -> Self {
+        Self {
+            items: items.into_iter(),
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for ListAccess {
+    type Error = NbtError;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, NbtError> {
+        self.items
+            .next()
+            .map(|tag| seed.deserialize(TagDeserializer(tag)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
+
+/// Map access over the entries of a compound.
+struct CompoundAccess {
+    entries: std::collections::hash_map::IntoIter<String, NbtTag>,
+    value: Option<NbtTag>,
+}
+
+impl CompoundAccess {
+    fn new(compound: NbtCompound) -> Self {
+        Self {
+            entries: compound.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for CompoundAccess {
+    type Error = NbtError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, NbtError> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, NbtError> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| NbtError::Custom("compound value without a key".into()))?;
+        seed.deserialize(TagDeserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+/// Enum access for a variant stored as a single-entry compound.
+struct VariantAccess {
+    variant: String,
+    value: NbtTag,
+}
+
+impl<'de> de::EnumAccess<'de> for VariantAccess {
+    type Error = NbtError;
+    type Variant = TagDeserializer;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), NbtError> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, TagDeserializer(self.value)))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for TagDeserializer {
+    type Error = NbtError;
+
+    fn unit_variant(self) -> Result<(), NbtError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, NbtError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, NbtError> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, NbtError> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ser::to_nbt;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Mode {
+        Survival,
+        Creative,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Circle(f32),
+        Line(i32, i32),
+        Box { width: u16, height: u16 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Player {
+        name: String,
+        xp: u32,
+        health: f32,
+        position: (f64, f64, f64),
+        flying: bool,
+        mode: Mode,
+        shapes: Vec<Shape>,
+        tags: HashMap<String, i64>,
+        title: Option<String>,
+        #[serde(with = "serde_bytes_compat")]
+        data: Vec<u8>,
+    }
+
+    /// Stand-in for `serde_bytes`, forcing the byte buffer code paths.
+    mod serde_bytes_compat {
+        use serde::{Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+            s.serialize_bytes(v)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+            struct BytesVisitor;
+            impl<'de> serde::de::Visitor<'de> for BytesVisitor {
+                type Value = Vec<u8>;
+                fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str("bytes")
+                }
+                fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                    Ok(v)
+                }
+            }
+            d.deserialize_byte_buf(BytesVisitor)
+        }
+    }
+
+    fn sample() -> Player {
+        Player {
+            name: "Steve".into(),
+            xp: u32::MAX,
+            health: 17.5,
+            position: (1.5, 64.0, -3.25),
+            flying: true,
+            mode: Mode::Creative,
+            shapes: vec![
+                Shape::Circle(2.0),
+                Shape::Line(1, 2),
+                Shape::Box {
+                    width: 3,
+                    height: 60000,
+                },
+            ],
+            tags: HashMap::from([("kills".to_string(), 12)]),
+            title: None,
+            data: vec![0, 200, 255],
+        }
+    }
+
+    #[test]
+    fn roundtrip_struct() {
+        let player = sample();
+        let tag = to_nbt(&player).unwrap();
+        let c = tag.as_compound().unwrap();
+        assert_eq!(c.get("mode"), Some(&NbtTag::String("Creative".into())));
+        assert_eq!(c.get("data"), Some(&NbtTag::ByteArray(vec![0, -56, -1])));
+        assert_eq!(from_nbt::<Player>(tag).unwrap(), player);
+
+        let titled = Player {
+            title: Some("Builder".into()),
+            ..sample()
+        };
+        assert_eq!(
+            from_nbt::<Player>(to_nbt(&titled).unwrap()).unwrap(),
+            titled
+        );
+    }
+
+    #[test]
+    fn roundtrip_through_binary_nbt() {
+        let player = sample();
+        let NbtTag::Compound(compound) = to_nbt(&player).unwrap() else {
+            panic!("expected a compound");
+        };
+        let mut buf = bytes::BytesMut::new();
+        crate::write_nbt_le(&mut buf, &crate::NbtRoot::new("", compound));
+        let root = crate::read_nbt_le(&mut buf.freeze()).unwrap();
+        assert_eq!(from_nbt_compound::<Player>(root.compound).unwrap(), player);
+    }
+
+    #[test]
+    fn reads_array_tags_as_sequences() {
+        assert_eq!(
+            from_nbt::<Vec<i32>>(NbtTag::IntArray(vec![1, 2, 3])).unwrap(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            from_nbt::<Vec<i64>>(NbtTag::LongArray(vec![-1])).unwrap(),
+            vec![-1]
+        );
+        assert_eq!(
+            from_nbt::<Vec<i8>>(NbtTag::ByteArray(vec![4, -4])).unwrap(),
+            vec![4, -4]
+        );
+    }
+
+    #[test]
+    fn missing_and_mismatched_fields() {
+        #[derive(Debug, Deserialize)]
+        struct Sign {
+            #[allow(dead_code)]
+            text: String,
+        }
+
+        let mut c = NbtCompound::new();
+        assert!(from_nbt_compound::<Sign>(c.clone()).is_err());
+        c.insert("text".into(), NbtTag::Int(3));
+        assert!(from_nbt_compound::<Sign>(c).is_err());
+        assert!(from_nbt::<bool>(NbtTag::Int(1)).is_err());
+        assert!(from_nbt::<Mode>(NbtTag::String("Spectator".into())).is_err());
+    }
+}
@@ -22,6 +22,19 @@ pub enum NbtError {
     #[error("negative array length: {0}")]
     NegativeLength(i32),
 
+    #[error("list or array of {len} elements (limit: {limit})")]
+    TooLong { len: usize, limit: usize },
+
+    #[error("NBT data larger than {limit} bytes")]
+    TooLarge { limit: usize },
+
     #[error("VarInt error: {0}")]
     VarInt(String),
+
+    #[error("invalid SNBT at {pos}: {message}")]
+    Snbt { pos: usize, message: String },
+
+    /// Error raised while mapping a Rust type to or from NBT with serde.
+    #[error("{0}")]
+    Custom(String),
 }
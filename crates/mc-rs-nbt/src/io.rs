@@ -1,157 +1,224 @@
 //! Generic NBT read/write engine, parameterized by variant.
 
+use std::borrow::Cow;
+
 use bytes::{Buf, BufMut};
 
 use crate::error::NbtError;
+use crate::limits::NbtLimits;
 use crate::tag::{NbtCompound, NbtRoot, NbtTag};
 
-/// Maximum nesting depth to prevent stack overflow.
-const MAX_DEPTH: usize = 512;
-
-/// Abstraction over the two NBT wire formats.
+/// Abstraction over the NBT wire formats.
+///
+/// Shorts, longs, floats and doubles are little-endian unless the variant
+/// says otherwise.
 pub(crate) trait NbtVariant {
     fn write_int(buf: &mut impl BufMut, value: i32);
     fn read_int(buf: &mut impl Buf) -> Result<i32, NbtError>;
 
+    fn write_short(buf: &mut impl BufMut, value: i16) {
+        buf.put_i16_le(value);
+    }
+    fn read_short(buf: &mut impl Buf) -> Result<i16, NbtError> {
+        ensure_remaining(buf, 2)?;
+        Ok(buf.get_i16_le())
+    }
+
+    fn write_long(buf: &mut impl BufMut, value: i64) {
+        buf.put_i64_le(value);
+    }
+    fn read_long(buf: &mut impl Buf) -> Result<i64, NbtError> {
+        ensure_remaining(buf, 8)?;
+        Ok(buf.get_i64_le())
+    }
+
+    fn write_float(buf: &mut impl BufMut, value: f32) {
+        buf.put_f32_le(value);
+    }
+    fn read_float(buf: &mut impl Buf) -> Result<f32, NbtError> {
+        ensure_remaining(buf, 4)?;
+        Ok(buf.get_f32_le())
+    }
+
+    fn write_double(buf: &mut impl BufMut, value: f64) {
+        buf.put_f64_le(value);
+    }
+    fn read_double(buf: &mut impl Buf) -> Result<f64, NbtError> {
+        ensure_remaining(buf, 8)?;
+        Ok(buf.get_f64_le())
+    }
+
     fn write_array_len(buf: &mut impl BufMut, len: i32);
     fn read_array_len(buf: &mut impl Buf) -> Result<i32, NbtError>;
 
     fn write_string_len(buf: &mut impl BufMut, len: usize);
     fn read_string_len(buf: &mut impl Buf) -> Result<usize, NbtError>;
+
+    /// Encoding of string contents, UTF-8 unless the variant says otherwise.
+    fn encode_string(s: &str) -> Cow<'_, [u8]> {
+        Cow::Borrowed(s.as_bytes())
+    }
+    fn decode_string(data: &[u8]) -> Result<String, NbtError> {
+        std::str::from_utf8(data)
+            .map(str::to_string)
+            .map_err(|_| NbtError::InvalidUtf8)
+    }
 }
 
 // -----------------------------------------------------------------------
 // Reading
 // -----------------------------------------------------------------------
 
-pub(crate) fn read_nbt<V: NbtVariant>(buf: &mut impl Buf) -> Result<NbtRoot, NbtError> {
+pub(crate) fn read_nbt<V: NbtVariant>(
+    buf: &mut impl Buf,
+    limits: &NbtLimits,
+) -> Result<NbtRoot, NbtError> {
     if !buf.has_remaining() {
         return Err(NbtError::UnexpectedEof);
     }
+    let reader = Reader {
+        limits,
+        start: buf.remaining(),
+    };
     let tag_type = buf.get_u8();
     if tag_type != 10 {
         return Err(NbtError::ExpectedCompound { got: tag_type });
     }
-    let name = read_string::<V>(buf)?;
-    let compound = read_compound::<V>(buf, 0)?;
+    let name = reader.read_string::<V>(buf)?;
+    let compound = reader.read_compound::<V>(buf, 0)?;
     Ok(NbtRoot { name, compound })
 }
 
-fn read_tag<V: NbtVariant>(
-    buf: &mut impl Buf,
-    tag_type: u8,
-    depth: usize,
-) -> Result<NbtTag, NbtError> {
-    if depth > MAX_DEPTH {
-        return Err(NbtError::NestingTooDeep { limit: MAX_DEPTH });
-    }
+/// Tree reader state: the limits and the buffer size when reading started.
+struct Reader<'l> {
+    limits: &'l NbtLimits,
+    start: usize,
+}
 
-    match tag_type {
-        1 => {
-            ensure_remaining(buf, 1)?;
-            Ok(NbtTag::Byte(buf.get_i8()))
+impl Reader<'_> {
+    fn read_tag<V: NbtVariant>(
+        &self,
+        buf: &mut impl Buf,
+        tag_type: u8,
+        depth: usize,
+    ) -> Result<NbtTag, NbtError> {
+        if depth > self.limits.max_depth {
+            return Err(NbtError::NestingTooDeep {
+                limit: self.limits.max_depth,
+            });
         }
-        2 => {
-            ensure_remaining(buf, 2)?;
-            Ok(NbtTag::Short(buf.get_i16_le()))
-        }
-        3 => Ok(NbtTag::Int(V::read_int(buf)?)),
-        4 => {
-            ensure_remaining(buf, 8)?;
-            Ok(NbtTag::Long(buf.get_i64_le()))
-        }
-        5 => {
-            ensure_remaining(buf, 4)?;
-            Ok(NbtTag::Float(buf.get_f32_le()))
-        }
-        6 => {
-            ensure_remaining(buf, 8)?;
-            Ok(NbtTag::Double(buf.get_f64_le()))
-        }
-        7 => {
-            let len = V::read_array_len(buf)?;
-            if len < 0 {
-                return Err(NbtError::NegativeLength(len));
-            }
-            let len = len as usize;
-            ensure_remaining(buf, len)?;
-            let mut arr = Vec::with_capacity(len);
-            for _ in 0..len {
-                arr.push(buf.get_i8());
+
+        match tag_type {
+            1 => {
+                ensure_remaining(buf, 1)?;
+                Ok(NbtTag::Byte(buf.get_i8()))
             }
-            Ok(NbtTag::ByteArray(arr))
-        }
-        8 => Ok(NbtTag::String(read_string::<V>(buf)?)),
-        9 => {
-            ensure_remaining(buf, 1)?;
-            let element_type = buf.get_u8();
-            let len = V::read_array_len(buf)?;
-            if len < 0 {
-                return Err(NbtError::NegativeLength(len));
+            2 => Ok(NbtTag::Short(V::read_short(buf)?)),
+            3 => Ok(NbtTag::Int(V::read_int(buf)?)),
+            4 => Ok(NbtTag::Long(V::read_long(buf)?)),
+            5 => Ok(NbtTag::Float(V::read_float(buf)?)),
+            6 => Ok(NbtTag::Double(V::read_double(buf)?)),
+            7 => {
+                let len = self.read_len::<V>(buf, 1)?;
+                ensure_remaining(buf, len)?;
+                let mut arr = Vec::with_capacity(len);
+                for _ in 0..len {
+                    arr.push(buf.get_i8());
+                }
+                Ok(NbtTag::ByteArray(arr))
             }
-            let len = len as usize;
-            let mut list = Vec::with_capacity(len);
-            for _ in 0..len {
-                list.push(read_tag::<V>(buf, element_type, depth + 1)?);
+            8 => Ok(NbtTag::String(self.read_string::<V>(buf)?)),
+            9 => {
+                ensure_remaining(buf, 1)?;
+                let element_type = buf.get_u8();
+                let len = self.read_len::<V>(buf, 0)?;
+                // Don't trust the length for the allocation: each element
+                // takes at least a byte
+                let mut list = Vec::with_capacity(len.min(buf.remaining()));
+                for _ in 0..len {
+                    list.push(self.read_tag::<V>(buf, element_type, depth + 1)?);
+                }
+                Ok(NbtTag::List(list))
             }
-            Ok(NbtTag::List(list))
-        }
-        10 => Ok(NbtTag::Compound(read_compound::<V>(buf, depth + 1)?)),
-        11 => {
-            let len = V::read_array_len(buf)?;
-            if len < 0 {
-                return Err(NbtError::NegativeLength(len));
+            10 => Ok(NbtTag::Compound(self.read_compound::<V>(buf, depth + 1)?)),
+            11 => {
+                let len = self.read_len::<V>(buf, 1)?;
+                let mut arr = Vec::with_capacity(len.min(buf.remaining()));
+                for _ in 0..len {
+                    arr.push(V::read_int(buf)?);
+                }
+                Ok(NbtTag::IntArray(arr))
             }
-            let len = len as usize;
-            let mut arr = Vec::with_capacity(len);
-            for _ in 0..len {
-                arr.push(V::read_int(buf)?);
+            12 => {
+                let len = self.read_len::<V>(buf, 8)?;
+                let mut arr = Vec::with_capacity(len.min(buf.remaining() / 8));
+                for _ in 0..len {
+                    arr.push(V::read_long(buf)?);
+                }
+                Ok(NbtTag::LongArray(arr))
             }
-            Ok(NbtTag::IntArray(arr))
+            _ => Err(NbtError::UnknownTagType(tag_type)),
         }
-        12 => {
-            let len = V::read_array_len(buf)?;
-            if len < 0 {
-                return Err(NbtError::NegativeLength(len));
-            }
-            let len = len as usize;
-            let mut arr = Vec::with_capacity(len);
-            for _ in 0..len {
-                ensure_remaining(buf, 8)?;
-                arr.push(buf.get_i64_le());
+    }
+
+    fn read_compound<V: NbtVariant>(
+        &self,
+        buf: &mut impl Buf,
+        depth: usize,
+    ) -> Result<NbtCompound, NbtError> {
+        if depth > self.limits.max_depth {
+            return Err(NbtError::NestingTooDeep {
+                limit: self.limits.max_depth,
+            });
+        }
+        let mut map = NbtCompound::new();
+        loop {
+            self.check_size(buf, 1)?;
+            ensure_remaining(buf, 1)?;
+            let tag_type = buf.get_u8();
+            if tag_type == 0 {
+                break; // TAG_End
             }
-            Ok(NbtTag::LongArray(arr))
+            let name = self.read_string::<V>(buf)?;
+            let tag = self.read_tag::<V>(buf, tag_type, depth)?;
+            map.insert(name, tag);
         }
-        _ => Err(NbtError::UnknownTagType(tag_type)),
+        Ok(map)
     }
-}
 
-fn read_compound<V: NbtVariant>(buf: &mut impl Buf, depth: usize) -> Result<NbtCompound, NbtError> {
-    if depth > MAX_DEPTH {
-        return Err(NbtError::NestingTooDeep { limit: MAX_DEPTH });
+    fn read_string<V: NbtVariant>(&self, buf: &mut impl Buf) -> Result<String, NbtError> {
+        let len = V::read_string_len(buf)?;
+        self.check_size(buf, len)?;
+        ensure_remaining(buf, len)?;
+        let data = buf.copy_to_bytes(len);
+        V::decode_string(&data)
     }
-    let mut map = NbtCompound::new();
-    loop {
-        ensure_remaining(buf, 1)?;
-        let tag_type = buf.get_u8();
-        if tag_type == 0 {
-            break; // TAG_End
+
+    /// Read a list or array length, checked against the limits.
+    /// `element_size` is the minimum encoded size of an element.
+    fn read_len<V: NbtVariant>(
+        &self,
+        buf: &mut impl Buf,
+        element_size: usize,
+    ) -> Result<usize, NbtError> {
+        let len = V::read_array_len(buf)?;
+        if len < 0 {
+            return Err(NbtError::NegativeLength(len));
         }
-        let name = read_string::<V>(buf)?;
-        let tag = read_tag::<V>(buf, tag_type, depth)?;
-        map.insert(name, tag);
+        let len = len as usize;
+        self.limits.check_len(len)?;
+        self.check_size(buf, len.saturating_mul(element_size))?;
+        Ok(len)
     }
-    Ok(map)
-}
 
-fn read_string<V: NbtVariant>(buf: &mut impl Buf) -> Result<String, NbtError> {
-    let len = V::read_string_len(buf)?;
-    ensure_remaining(buf, len)?;
-    let data = buf.copy_to_bytes(len);
-    String::from_utf8(data.to_vec()).map_err(|_| NbtError::InvalidUtf8)
+    /// Fail if reading `upcoming` more bytes would go over `max_bytes`.
+    fn check_size(&self, buf: &impl Buf, upcoming: usize) -> Result<(), NbtError> {
+        let read = self.start - buf.remaining();
+        self.limits.check_size(read.saturating_add(upcoming))
+    }
 }
 
-fn ensure_remaining(buf: &impl Buf, needed: usize) -> Result<(), NbtError> {
+pub(crate) fn ensure_remaining(buf: &impl Buf, needed: usize) -> Result<(), NbtError> {
     if buf.remaining() < needed {
         Err(NbtError::UnexpectedEof)
     } else {
@@ -172,11 +239,11 @@ pub(crate) fn write_nbt<V: NbtVariant>(buf: &mut impl BufMut, root: &NbtRoot) {
 fn write_tag<V: NbtVariant>(buf: &mut impl BufMut, tag: &NbtTag) {
     match tag {
         NbtTag::Byte(v) => buf.put_i8(*v),
-        NbtTag::Short(v) => buf.put_i16_le(*v),
+        NbtTag::Short(v) => V::write_short(buf, *v),
         NbtTag::Int(v) => V::write_int(buf, *v),
-        NbtTag::Long(v) => buf.put_i64_le(*v),
-        NbtTag::Float(v) => buf.put_f32_le(*v),
-        NbtTag::Double(v) => buf.put_f64_le(*v),
+        NbtTag::Long(v) => V::write_long(buf, *v),
+        NbtTag::Float(v) => V::write_float(buf, *v),
+        NbtTag::Double(v) => V::write_double(buf, *v),
         NbtTag::ByteArray(arr) => {
             V::write_array_len(buf, arr.len() as i32);
             for &b in arr {
@@ -206,7 +273,7 @@ fn write_tag<V: NbtVariant>(buf: &mut impl BufMut, tag: &NbtTag) {
         NbtTag::LongArray(arr) => {
             V::write_array_len(buf, arr.len() as i32);
             for &v in arr {
-                buf.put_i64_le(v);
+                V::write_long(buf, v);
             }
         }
     }
@@ -222,6 +289,7 @@ fn write_compound<V: NbtVariant>(buf: &mut impl BufMut, map: &NbtCompound) {
 }
 
 fn write_string<V: NbtVariant>(buf: &mut impl BufMut, s: &str) {
-    V::write_string_len(buf, s.len());
-    buf.put_slice(s.as_bytes());
+    let data = V::encode_string(s);
+    V::write_string_len(buf, data.len());
+    buf.put_slice(&data);
 }
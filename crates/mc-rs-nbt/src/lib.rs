@@ -1,23 +1,46 @@
 //! NBT (Named Binary Tag) implementation for Minecraft Bedrock Edition.
 //!
-//! Supports two variants:
+//! Supports three variants:
 //! - **Standard LE**: Used for disk storage and chunk data. Ints are i32_le, string lengths are u16_le.
 //! - **Network**: Used in most game packets. Ints are VarInt (ZigZag), string lengths are VarUInt32.
+//! - **Big-endian**: Java Edition format (Anvil worlds, structure files). Strings are modified UTF-8.
+//!
+//! Besides the tree readers, [`NbtStreamReader`] reads NBT as a stream of
+//! events, and [`NbtLimits`] bounds the depth and size of untrusted input.
 
+mod be;
+pub mod de;
 pub mod error;
 mod io;
 mod le;
+pub mod limits;
 mod network;
+pub mod ser;
+pub mod snbt;
+pub mod stream;
 pub mod tag;
 
+pub use de::{from_nbt, from_nbt_compound};
 pub use error::NbtError;
+pub use limits::NbtLimits;
+pub use ser::{to_nbt, to_nbt_compound};
+pub use snbt::{parse_snbt, to_snbt, to_snbt_pretty};
+pub use stream::{NbtEvent, NbtStreamReader, NbtValue};
 pub use tag::{NbtCompound, NbtRoot, NbtTag};
 
 use bytes::{Buf, BufMut};
 
 /// Read standard little-endian NBT from a buffer.
 pub fn read_nbt_le(buf: &mut impl Buf) -> Result<NbtRoot, NbtError> {
-    io::read_nbt::<le::LeVariant>(buf)
+    io::read_nbt::<le::LeVariant>(buf, &NbtLimits::default())
+}
+
+/// Read standard little-endian NBT, failing if it goes over `limits`.
+pub fn read_nbt_le_with_limits(
+    buf: &mut impl Buf,
+    limits: &NbtLimits,
+) -> Result<NbtRoot, NbtError> {
+    io::read_nbt::<le::LeVariant>(buf, limits)
 }
 
 /// Write standard little-endian NBT to a buffer.
@@ -27,7 +50,16 @@ pub fn write_nbt_le(buf: &mut impl BufMut, root: &NbtRoot) {
 
 /// Read network NBT (VarInt variant) from a buffer.
 pub fn read_nbt_network(buf: &mut impl Buf) -> Result<NbtRoot, NbtError> {
-    io::read_nbt::<network::NetworkVariant>(buf)
+    io::read_nbt::<network::NetworkVariant>(buf, &NbtLimits::default())
+}
+
+/// Read network NBT, failing if it goes over `limits` (use
+/// [`NbtLimits::untrusted`] for NBT sent by clients).
+pub fn read_nbt_network_with_limits(
+    buf: &mut impl Buf,
+    limits: &NbtLimits,
+) -> Result<NbtRoot, NbtError> {
+    io::read_nbt::<network::NetworkVariant>(buf, limits)
 }
 
 /// Write network NBT (VarInt variant) to a buffer.
@@ -35,6 +67,24 @@ pub fn write_nbt_network(buf: &mut impl BufMut, root: &NbtRoot) {
     io::write_nbt::<network::NetworkVariant>(buf, root)
 }
 
+/// Read big-endian (Java Edition) NBT from a buffer.
+pub fn read_nbt_be(buf: &mut impl Buf) -> Result<NbtRoot, NbtError> {
+    io::read_nbt::<be::BeVariant>(buf, &NbtLimits::default())
+}
+
+/// Read big-endian NBT, failing if it goes over `limits`.
+pub fn read_nbt_be_with_limits(
+    buf: &mut impl Buf,
+    limits: &NbtLimits,
+) -> Result<NbtRoot, NbtError> {
+    io::read_nbt::<be::BeVariant>(buf, limits)
+}
+
+/// Write big-endian (Java Edition) NBT to a buffer.
+pub fn write_nbt_be(buf: &mut impl BufMut, root: &NbtRoot) {
+    io::write_nbt::<be::BeVariant>(buf, root)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,6 +104,13 @@ mod tests {
         assert_eq!(decoded, *root);
     }
 
+    fn roundtrip_be(root: &NbtRoot) {
+        let mut buf = BytesMut::new();
+        write_nbt_be(&mut buf, root);
+        let decoded = read_nbt_be(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded, *root);
+    }
+
     // -- Standard LE tests --
 
     #[test]
@@ -273,6 +330,66 @@ mod tests {
         assert!(net_buf.len() < le_buf.len());
     }
 
+    // -- Big-endian tests --
+
+    #[test]
+    fn be_all_tag_types() {
+        let mut inner = NbtCompound::new();
+        inner.insert("name".into(), NbtTag::String("Steve\0 😀".into()));
+        inner.insert("health".into(), NbtTag::Float(20.0));
+
+        let mut c = NbtCompound::new();
+        c.insert("byte".into(), NbtTag::Byte(-3));
+        c.insert("short".into(), NbtTag::Short(-300));
+        c.insert("int".into(), NbtTag::Int(19133));
+        c.insert("long".into(), NbtTag::Long(-9_000_000_000));
+        c.insert("double".into(), NbtTag::Double(0.5));
+        c.insert("bytes".into(), NbtTag::ByteArray(vec![1, -1]));
+        c.insert("ints".into(), NbtTag::IntArray(vec![1, -70000]));
+        c.insert("longs".into(), NbtTag::LongArray(vec![i64::MIN]));
+        c.insert(
+            "list".into(),
+            NbtTag::List(vec![NbtTag::Compound(inner.clone())]),
+        );
+        c.insert("empty".into(), NbtTag::List(vec![]));
+        c.insert("player".into(), NbtTag::Compound(inner));
+        roundtrip_be(&NbtRoot::new("Level", c));
+    }
+
+    #[test]
+    fn be_reads_java_file() {
+        // hello_world.nbt from the NBT specification
+        let mut data = vec![0x0A, 0x00, 0x0B];
+        data.extend_from_slice(b"hello world");
+        data.extend_from_slice(&[0x08, 0x00, 0x04]);
+        data.extend_from_slice(b"name");
+        data.extend_from_slice(&[0x00, 0x09]);
+        data.extend_from_slice(b"Bananrama");
+        data.push(0x00);
+
+        let root = read_nbt_be(&mut &data[..]).unwrap();
+        assert_eq!(root.name, "hello world");
+        assert_eq!(
+            root.compound.get("name"),
+            Some(&NbtTag::String("Bananrama".into()))
+        );
+
+        let mut written = BytesMut::new();
+        write_nbt_be(&mut written, &root);
+        assert_eq!(&written[..], &data[..]);
+    }
+
+    #[test]
+    fn be_is_big_endian() {
+        let mut c = NbtCompound::new();
+        c.insert("v".into(), NbtTag::Int(1));
+        let root = NbtRoot::new("", c);
+        let mut buf = BytesMut::new();
+        write_nbt_be(&mut buf, &root);
+        // Header (type + name length), tag type, name, then the int
+        assert_eq!(&buf[..], &[10, 0, 0, 3, 0, 1, b'v', 0, 0, 0, 1, 0]);
+    }
+
     // -- Error cases --
 
     #[test]
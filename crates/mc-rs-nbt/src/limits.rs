@@ -0,0 +1,61 @@
+//! Limits applied while reading NBT.
+
+use crate::error::NbtError;
+
+/// Depth and size limits for reading NBT.
+///
+/// The default limits only guard against stack overflows and are meant for
+/// trusted data (world saves, resource files). Use [`NbtLimits::untrusted`]
+/// for NBT sent by clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NbtLimits {
+    /// Maximum nesting of compounds and lists below the root.
+    pub max_depth: usize,
+    /// Maximum number of elements of a single list or array.
+    pub max_len: usize,
+    /// Maximum number of bytes read, including the root header.
+    pub max_bytes: usize,
+}
+
+impl Default for NbtLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 512,
+            max_len: usize::MAX,
+            max_bytes: usize::MAX,
+        }
+    }
+}
+
+impl NbtLimits {
+    /// Limits for NBT received from the network: 64 levels, 65536 elements
+    /// per list or array, 2 MiB in total.
+    pub fn untrusted() -> Self {
+        Self {
+            max_depth: 64,
+            max_len: 65_536,
+            max_bytes: 2 * 1024 * 1024,
+        }
+    }
+
+    pub(crate) fn check_len(&self, len: usize) -> Result<(), NbtError> {
+        if len > self.max_len {
+            Err(NbtError::TooLong {
+                len,
+                limit: self.max_len,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn check_size(&self, bytes: usize) -> Result<(), NbtError> {
+        if bytes > self.max_bytes {
+            Err(NbtError::TooLarge {
+                limit: self.max_bytes,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
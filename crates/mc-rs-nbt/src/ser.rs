@@ -0,0 +1,480 @@
+//! Serde serializer producing [`NbtTag`] values.
+//!
+//! Mapping:
+//! - `bool` → `Byte` (0/1); `i8`/`u8` → `Byte`, `i16`/`u16` → `Short`,
+//!   `i32`/`u32` → `Int`, `i64`/`u64` → `Long` (unsigned values are
+//!   reinterpreted, not range-checked); `f32` → `Float`, `f64` → `Double`
+//! - strings and chars → `String`; byte buffers → `ByteArray`
+//! - sequences and tuples → `List` (elements must all have the same type)
+//! - structs and maps (string keys) → `Compound`; `None` fields are omitted
+//! - unit enum variants → `String` with the variant name; other variants →
+//!   a `Compound` with a single entry named after the variant, so a list
+//!   can't mix unit and data variants of the same enum
+
+use serde::ser::{self, Serialize};
+
+use crate::error::NbtError;
+use crate::tag::{NbtCompound, NbtTag};
+
+/// Serialize `value` to an NBT tag.
+pub fn to_nbt<T: Serialize + ?Sized>(value: &T) -> Result<NbtTag, NbtError> {
+    value
+        .serialize(TagSerializer)?
+        .ok_or_else(|| NbtError::Custom("cannot serialize a unit or None value to NBT".into()))
+}
+
+/// Serialize `value` to an NBT compound (it must be a struct or a map).
+pub fn to_nbt_compound<T: Serialize + ?Sized>(value: &T) -> Result<NbtCompound, NbtError> {
+    match to_nbt(value)? {
+        NbtTag::Compound(c) => Ok(c),
+        other => Err(NbtError::Custom(format!(
+            "expected a compound, got tag type {}",
+            other.tag_type_id()
+        ))),
+    }
+}
+
+impl ser::Error for NbtError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        NbtError::Custom(msg.to_string())
+    }
+}
+
+/// Serializer for a single value. `None` stands for values NBT can't hold
+/// (`()`, `None`), which compounds leave out.
+struct TagSerializer;
+
+impl ser::Serializer for TagSerializer {
+    type Ok = Option<NbtTag>;
+    type Error = NbtError;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = VariantSerializer<ListSerializer>;
+    type SerializeMap = CompoundSerializer;
+    type SerializeStruct = CompoundSerializer;
+    type SerializeStructVariant = VariantSerializer<CompoundSerializer>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Byte(v as i8)))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Byte(v)))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Short(v)))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Int(v)))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Long(v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Byte(v as i8)))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Short(v as i16)))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Int(v as i32)))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Long(v as i64)))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Float(v)))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Double(v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::String(v.to_string())))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::String(v.to_string())))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::ByteArray(
+            v.iter().map(|&b| b as i8).collect(),
+        )))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, NbtError> {
+        Ok(None)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Self::Ok, NbtError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, NbtError> {
+        Ok(None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Compound(NbtCompound::new())))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::String(variant.to_string())))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, NbtError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, NbtError> {
+        let mut c = NbtCompound::new();
+        if let Some(tag) = value.serialize(TagSerializer)? {
+            c.insert(variant.to_string(), tag);
+        }
+        Ok(Some(NbtTag::Compound(c)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, NbtError> {
+        Ok(ListSerializer {
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, NbtError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, NbtError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, NbtError> {
+        Ok(VariantSerializer {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, NbtError> {
+        Ok(CompoundSerializer::default())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, NbtError> {
+        Ok(CompoundSerializer::default())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, NbtError> {
+        Ok(VariantSerializer {
+            variant,
+            inner: CompoundSerializer::default(),
+        })
+    }
+}
+
+/// Builds a `List` tag.
+struct ListSerializer {
+    items: Vec<NbtTag>,
+}
+
+impl ListSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        let tag = value
+            .serialize(TagSerializer)?
+            .ok_or_else(|| NbtError::Custom("NBT lists can't hold unit or None values".into()))?;
+        if let Some(first) = self.items.first() {
+            if first.tag_type_id() != tag.tag_type_id() {
+                return Err(NbtError::Custom(format!(
+                    "NBT list elements must share a type: got {} after {}",
+                    tag.tag_type_id(),
+                    first.tag_type_id()
+                )));
+            }
+        }
+        self.items.push(tag);
+        Ok(())
+    }
+
+    fn finish(self) -> NbtTag {
+        NbtTag::List(self.items)
+    }
+}
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = Option<NbtTag>;
+    type Error = NbtError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Some(self.finish()))
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = Option<NbtTag>;
+    type Error = NbtError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Some(self.finish()))
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = Option<NbtTag>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Some(self.finish()))
+    }
+}
+
+/// Builds a `Compound` tag.
+#[derive(Default)]
+struct CompoundSerializer {
+    compound: NbtCompound,
+    next_key: Option<String>,
+}
+
+impl CompoundSerializer {
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), NbtError> {
+        if let Some(tag) = value.serialize(TagSerializer)? {
+            self.compound.insert(key, tag);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for CompoundSerializer {
+    type Ok = Option<NbtTag>;
+    type Error = NbtError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), NbtError> {
+        match key.serialize(TagSerializer)? {
+            Some(NbtTag::String(key)) => {
+                self.next_key = Some(key);
+                Ok(())
+            }
+            _ => Err(NbtError::Custom("NBT compound keys must be strings".into())),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| NbtError::Custom("map value without a key".into()))?;
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Compound(self.compound)))
+    }
+}
+
+impl ser::SerializeStruct for CompoundSerializer {
+    type Ok = Option<NbtTag>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), NbtError> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Some(NbtTag::Compound(self.compound)))
+    }
+}
+
+/// Wraps a tuple or struct variant in a compound named after the variant.
+struct VariantSerializer<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl<S> VariantSerializer<S> {
+    fn wrap(variant: &'static str, tag: NbtTag) -> Option<NbtTag> {
+        let mut c = NbtCompound::new();
+        c.insert(variant.to_string(), tag);
+        Some(NbtTag::Compound(c))
+    }
+}
+
+impl ser::SerializeTupleVariant for VariantSerializer<ListSerializer> {
+    type Ok = Option<NbtTag>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), NbtError> {
+        self.inner.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Self::wrap(self.variant, self.inner.finish()))
+    }
+}
+
+impl ser::SerializeStructVariant for VariantSerializer<CompoundSerializer> {
+    type Ok = Option<NbtTag>;
+    type Error = NbtError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), NbtError> {
+        self.inner.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, NbtError> {
+        Ok(Self::wrap(
+            self.variant,
+            NbtTag::Compound(self.inner.compound),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize)]
+    struct Item {
+        name: String,
+        count: u8,
+        damage: i16,
+        enchanted: bool,
+        lore: Option<String>,
+    }
+
+    #[test]
+    fn struct_to_compound() {
+        let item = Item {
+            name: "minecraft:diamond_sword".into(),
+            count: 1,
+            damage: 12,
+            enchanted: true,
+            lore: None,
+        };
+        let c = to_nbt_compound(&item).unwrap();
+        assert_eq!(
+            c.get("name"),
+            Some(&NbtTag::String("minecraft:diamond_sword".into()))
+        );
+        assert_eq!(c.get("count"), Some(&NbtTag::Byte(1)));
+        assert_eq!(c.get("damage"), Some(&NbtTag::Short(12)));
+        assert_eq!(c.get("enchanted"), Some(&NbtTag::Byte(1)));
+        assert!(!c.contains_key("lore"));
+    }
+
+    #[test]
+    fn numbers_and_lists() {
+        assert_eq!(to_nbt(&7i32).unwrap(), NbtTag::Int(7));
+        assert_eq!(to_nbt(&u32::MAX).unwrap(), NbtTag::Int(-1));
+        assert_eq!(to_nbt(&1.5f64).unwrap(), NbtTag::Double(1.5));
+        assert_eq!(
+            to_nbt(&vec![1i64, 2]).unwrap(),
+            NbtTag::List(vec![NbtTag::Long(1), NbtTag::Long(2)])
+        );
+        assert_eq!(
+            to_nbt(&(1.0f32, 2.0f32)).unwrap(),
+            NbtTag::List(vec![NbtTag::Float(1.0), NbtTag::Float(2.0)])
+        );
+    }
+
+    #[test]
+    fn maps_and_enums() {
+        #[derive(Serialize)]
+        enum Slot {
+            Empty,
+            Held(i32),
+        }
+
+        let mut map = BTreeMap::new();
+        map.insert("a", Slot::Empty);
+        map.insert("b", Slot::Held(3));
+        let c = to_nbt_compound(&map).unwrap();
+        assert_eq!(c.get("a"), Some(&NbtTag::String("Empty".into())));
+        let held = c.get("b").and_then(|t| t.as_compound()).unwrap();
+        assert_eq!(held.get("Held"), Some(&NbtTag::Int(3)));
+    }
+
+    #[test]
+    fn rejects_unrepresentable_values() {
+        assert!(to_nbt(&()).is_err());
+        assert!(to_nbt(&None::<i32>).is_err());
+        assert!(to_nbt(&vec![Some(1), None]).is_err());
+        assert!(to_nbt_compound(&5i32).is_err());
+
+        let mut map = BTreeMap::new();
+        map.insert(1, 2);
+        assert!(to_nbt(&map).is_err());
+
+        #[derive(Serialize)]
+        #[serde(untagged)]
+        enum Mixed {
+            Int(i32),
+            Text(String),
+        }
+        assert!(to_nbt(&vec![Mixed::Int(1), Mixed::Text("x".into())]).is_err());
+    }
+}
@@ -0,0 +1,507 @@
+//! SNBT (stringified NBT), the text form of NBT used in commands.
+//!
+//! ```text
+//! {display:{Name:"Excalibur",Lore:["Sharp"]},Count:1b,Damage:0s,Pos:[0.5d,64.0d,0.5d],Data:[I;1,2]}
+//! ```
+//!
+//! Numbers take a type suffix (`b`, `s`, `L`, `f`, `d`); unsuffixed numbers
+//! are `Int`, or `Double` when they contain a `.`. `true`/`false` are bytes.
+//! Any other unquoted word is a string.
+
+use crate::error::NbtError;
+use crate::tag::{NbtCompound, NbtTag};
+
+/// Maximum nesting depth accepted by the parser.
+const MAX_DEPTH: usize = 512;
+
+/// Parse a whole SNBT string.
+pub fn parse_snbt(input: &str) -> Result<NbtTag, NbtError> {
+    let (tag, rest) = parse_snbt_prefix(input)?;
+    if !rest.trim_start().is_empty() {
+        let pos = input.len() - rest.trim_start().len();
+        return Err(error(pos, "unexpected trailing data"));
+    }
+    Ok(tag)
+}
+
+/// Parse one SNBT value at the start of `input` and return it along with
+/// the unparsed rest of the input.
+pub fn parse_snbt_prefix(input: &str) -> Result<(NbtTag, &str), NbtError> {
+    let mut parser = Parser { input, pos: 0 };
+    let tag = parser.value(0)?;
+    Ok((tag, &input[parser.pos..]))
+}
+
+/// Format a tag as compact SNBT. Compound keys are sorted.
+pub fn to_snbt(tag: &NbtTag) -> String {
+    let mut out = String::new();
+    write_tag(&mut out, tag, None, 0);
+    out
+}
+
+/// Format a tag as SNBT indented with two spaces per level.
+pub fn to_snbt_pretty(tag: &NbtTag) -> String {
+    let mut out = String::new();
+    write_tag(&mut out, tag, Some(2), 0);
+    out
+}
+
+fn error(pos: usize, message: impl Into<String>) -> NbtError {
+    NbtError::Snbt {
+        pos,
+        message: message.into(),
+    }
+}
+
+/// Characters allowed in unquoted keys and strings.
+fn is_unquoted_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+')
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), NbtError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(error(self.pos, format!("expected '{c}'")))
+        }
+    }
+
+    /// Consume `c` if it is the next non-whitespace character.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<NbtTag, NbtError> {
+        if depth > MAX_DEPTH {
+            return Err(NbtError::NestingTooDeep { limit: MAX_DEPTH });
+        }
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.compound(depth).map(NbtTag::Compound),
+            Some('[') => self.list(depth),
+            Some('"' | '\'') => self.quoted().map(NbtTag::String),
+            Some(_) => {
+                let start = self.pos;
+                let word = self.unquoted()?;
+                parse_word(word).map_err(|message| error(start, message))
+            }
+            None => Err(error(self.pos, "expected a value")),
+        }
+    }
+
+    fn compound(&mut self, depth: usize) -> Result<NbtCompound, NbtError> {
+        self.expect('{')?;
+        let mut compound = NbtCompound::new();
+        if self.eat('}') {
+            return Ok(compound);
+        }
+        loop {
+            self.skip_whitespace();
+            let key = match self.peek() {
+                Some('"' | '\'') => self.quoted()?,
+                _ => self.unquoted()?.to_string(),
+            };
+            self.expect(':')?;
+            let value = self.value(depth + 1)?;
+            compound.insert(key, value);
+            if self.eat('}') {
+                return Ok(compound);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn list(&mut self, depth: usize) -> Result<NbtTag, NbtError> {
+        self.expect('[')?;
+        let rest = &self.input[self.pos..];
+        let array = [('B', 7u8), ('I', 11), ('L', 12)]
+            .into_iter()
+            .find(|(prefix, _)| {
+                rest.starts_with(*prefix) && rest[1..].trim_start().starts_with(';')
+            });
+        if let Some((_, type_id)) = array {
+            self.pos += 1;
+            self.expect(';')?;
+            return self.array(type_id);
+        }
+
+        let mut items: Vec<NbtTag> = Vec::new();
+        if self.eat(']') {
+            return Ok(NbtTag::List(items));
+        }
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let item = self.value(depth + 1)?;
+            if let Some(first) = items.first() {
+                if first.tag_type_id() != item.tag_type_id() {
+                    return Err(error(start, "list elements must all have the same type"));
+                }
+            }
+            items.push(item);
+            if self.eat(']') {
+                return Ok(NbtTag::List(items));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    /// Elements of a `[B;`, `[I;` or `[L;` array, after the `;`.
+    fn array(&mut self, type_id: u8) -> Result<NbtTag, NbtError> {
+        let mut values = Vec::new();
+        if !self.eat(']') {
+            loop {
+                self.skip_whitespace();
+                let start = self.pos;
+                let word = self.unquoted()?;
+                let value = parse_word(word).map_err(|message| error(start, message))?;
+                let value = match (type_id, value) {
+                    (7, NbtTag::Byte(v)) => v as i64,
+                    (11, NbtTag::Int(v)) => v as i64,
+                    (12, NbtTag::Long(v)) => v,
+                    _ => return Err(error(start, "wrong element type for the array")),
+                };
+                values.push(value);
+                if self.eat(']') {
+                    break;
+                }
+                self.expect(',')?;
+            }
+        }
+        Ok(match type_id {
+            7 => NbtTag::ByteArray(values.into_iter().map(|v| v as i8).collect()),
+            11 => NbtTag::IntArray(values.into_iter().map(|v| v as i32).collect()),
+            _ => NbtTag::LongArray(values),
+        })
+    }
+
+    fn quoted(&mut self) -> Result<String, NbtError> {
+        let start = self.pos;
+        let quote = self
+            .peek()
+            .ok_or_else(|| error(start, "expected a string"))?;
+        self.pos += 1;
+        let mut out = String::new();
+        let mut chars = self.input[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some((_, 'n')) => '\n',
+                        Some((_, 't')) => '\t',
+                        Some((_, c @ ('\\' | '"' | '\''))) => c,
+                        _ => return Err(error(self.pos + i, "invalid escape sequence")),
+                    };
+                    out.push(escaped);
+                }
+                c if c == quote => {
+                    self.pos += i + 1;
+                    return Ok(out);
+                }
+                c => out.push(c),
+            }
+        }
+        Err(error(start, "unterminated string"))
+    }
+
+    fn unquoted(&mut self) -> Result<&str, NbtError> {
+        let rest = &self.input[self.pos..];
+        let len = rest
+            .find(|c: char| !is_unquoted_char(c))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(error(self.pos, "expected a value"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+}
+
+/// Interpret an unquoted word as a number, a boolean or a string.
+fn parse_word(word: &str) -> Result<NbtTag, String> {
+    match word {
+        "true" => return Ok(NbtTag::Byte(1)),
+        "false" => return Ok(NbtTag::Byte(0)),
+        _ => {}
+    }
+
+    let is_integer = |s: &str| {
+        let digits = s.strip_prefix(['-', '+']).unwrap_or(s);
+        !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+    };
+    let is_decimal = |s: &str| {
+        s.bytes().any(|b| b.is_ascii_digit())
+            && s.bytes()
+                .all(|b| b.is_ascii_digit() || matches!(b, b'-' | b'+' | b'.' | b'e' | b'E'))
+    };
+    let out_of_range = |_| format!("number out of range: {word}");
+
+    if is_integer(word) {
+        return word.parse().map(NbtTag::Int).map_err(out_of_range);
+    }
+    let (body, suffix) = word.split_at(word.len() - 1);
+    match suffix {
+        "b" | "B" if is_integer(body) => body.parse().map(NbtTag::Byte).map_err(out_of_range),
+        "s" | "S" if is_integer(body) => body.parse().map(NbtTag::Short).map_err(out_of_range),
+        "l" | "L" if is_integer(body) => body.parse().map(NbtTag::Long).map_err(out_of_range),
+        // Malformed decimals such as `1.2.3` are strings
+        "f" | "F" if is_decimal(body) => Ok(body
+            .parse()
+            .map_or_else(|_| NbtTag::String(word.to_string()), NbtTag::Float)),
+        "d" | "D" if is_decimal(body) => Ok(body
+            .parse()
+            .map_or_else(|_| NbtTag::String(word.to_string()), NbtTag::Double)),
+        _ if word.contains('.') && is_decimal(word) => Ok(word
+            .parse()
+            .map_or_else(|_| NbtTag::String(word.to_string()), NbtTag::Double)),
+        _ => Ok(NbtTag::String(word.to_string())),
+    }
+}
+
+fn write_tag(out: &mut String, tag: &NbtTag, indent: Option<usize>, level: usize) {
+    match tag {
+        NbtTag::Byte(v) => out.push_str(&format!("{v}b")),
+        NbtTag::Short(v) => out.push_str(&format!("{v}s")),
+        NbtTag::Int(v) => out.push_str(&v.to_string()),
+        NbtTag::Long(v) => out.push_str(&format!("{v}L")),
+        NbtTag::Float(v) => out.push_str(&format!("{v:?}f")),
+        NbtTag::Double(v) => out.push_str(&format!("{v:?}d")),
+        NbtTag::String(v) => write_quoted(out, v),
+        NbtTag::ByteArray(v) => write_array(out, 'B', v.iter().map(|v| format!("{v}b"))),
+        NbtTag::IntArray(v) => write_array(out, 'I', v.iter().map(|v| v.to_string())),
+        NbtTag::LongArray(v) => write_array(out, 'L', v.iter().map(|v| format!("{v}L"))),
+        NbtTag::List(items) => {
+            // Only lists of lists and compounds are spread over several lines.
+            let nested = matches!(items.first(), Some(NbtTag::List(_) | NbtTag::Compound(_)));
+            let indent = indent.filter(|_| nested);
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                new_line(out, indent, level + 1);
+                write_tag(out, item, indent, level + 1);
+            }
+            if !items.is_empty() {
+                new_line(out, indent, level);
+            }
+            out.push(']');
+        }
+        NbtTag::Compound(c) => {
+            let mut keys: Vec<&String> = c.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                new_line(out, indent, level + 1);
+                if !key.is_empty() && key.chars().all(is_unquoted_char) {
+                    out.push_str(key);
+                } else {
+                    write_quoted(out, key);
+                }
+                out.push(':');
+                if indent.is_some() {
+                    out.push(' ');
+                }
+                write_tag(out, &c[*key], indent, level + 1);
+            }
+            if !keys.is_empty() {
+                new_line(out, indent, level);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn new_line(out: &mut String, indent: Option<usize>, level: usize) {
+    if let Some(width) = indent {
+        out.push('\n');
+        out.push_str(&" ".repeat(width * level));
+    }
+}
+
+fn write_quoted(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_array(out: &mut String, prefix: char, values: impl Iterator<Item = String>) {
+    out.push('[');
+    out.push(prefix);
+    out.push(';');
+    for (i, v) in values.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&v);
+    }
+    out.push(']');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compound(entries: &[(&str, NbtTag)]) -> NbtTag {
+        NbtTag::Compound(
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn parse_item_literal() {
+        let tag =
+            parse_snbt(r#"{display:{Name:"Excalibur", Lore:['Sharp', "Old"]}, Count:1b}"#).unwrap();
+        assert_eq!(
+            tag,
+            compound(&[
+                (
+                    "display",
+                    compound(&[
+                        ("Name", NbtTag::String("Excalibur".into())),
+                        (
+                            "Lore",
+                            NbtTag::List(vec![
+                                NbtTag::String("Sharp".into()),
+                                NbtTag::String("Old".into()),
+                            ])
+                        ),
+                    ])
+                ),
+                ("Count", NbtTag::Byte(1)),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_numbers_and_words() {
+        let cases = [
+            ("3b", NbtTag::Byte(3)),
+            ("-7S", NbtTag::Short(-7)),
+            ("42", NbtTag::Int(42)),
+            ("9000000000L", NbtTag::Long(9_000_000_000)),
+            ("1.5f", NbtTag::Float(1.5)),
+            ("2d", NbtTag::Double(2.0)),
+            ("0.25", NbtTag::Double(0.25)),
+            ("true", NbtTag::Byte(1)),
+            ("false", NbtTag::Byte(0)),
+            ("stone", NbtTag::String("stone".into())),
+            ("1.2.3", NbtTag::String("1.2.3".into())),
+            ("[B;1b,-2b]", NbtTag::ByteArray(vec![1, -2])),
+            ("[I; 1, 2]", NbtTag::IntArray(vec![1, 2])),
+            ("[L;5L]", NbtTag::LongArray(vec![5])),
+            ("[]", NbtTag::List(vec![])),
+            ("{}", compound(&[])),
+        ];
+        for (input, expected) in cases {
+            let (tag, _) = parse_snbt_prefix(input).unwrap();
+            assert_eq!(tag, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_escapes_and_quoted_keys() {
+        let tag = parse_snbt(r#"{"my key":"say \"hi\"\\", 'a':'it\'s'}"#).unwrap();
+        let c = tag.as_compound().unwrap();
+        assert_eq!(c["my key"], NbtTag::String("say \"hi\"\\".into()));
+        assert_eq!(c["a"], NbtTag::String("it's".into()));
+    }
+
+    #[test]
+    fn parse_errors() {
+        for input in [
+            "", "{", "{a:1", "{a 1}", "[1, 2b]", "[I;1b]", "\"open", "200b", "{a:1} x", r#""\q""#,
+        ] {
+            assert!(parse_snbt(input).is_err(), "{input}");
+        }
+        let deep = "[".repeat(MAX_DEPTH + 2);
+        assert!(matches!(
+            parse_snbt(&deep),
+            Err(NbtError::NestingTooDeep { .. })
+        ));
+    }
+
+    #[test]
+    fn prefix_leaves_rest() {
+        let (tag, rest) = parse_snbt_prefix("{a:1b} destroy").unwrap();
+        assert_eq!(tag, compound(&[("a", NbtTag::Byte(1))]));
+        assert_eq!(rest, " destroy");
+    }
+
+    #[test]
+    fn format_compact_and_pretty() {
+        let tag = compound(&[
+            ("b", NbtTag::List(vec![compound(&[("x", NbtTag::Int(1))])])),
+            ("a", NbtTag::String("q\"uote".into())),
+            ("odd key", NbtTag::Double(1.0)),
+        ]);
+        assert_eq!(to_snbt(&tag), r#"{a:"q\"uote",b:[{x:1}],"odd key":1.0d}"#);
+        assert_eq!(
+            to_snbt_pretty(&tag),
+            "{\n  a: \"q\\\"uote\",\n  b: [\n    {\n      x: 1\n    }\n  ],\n  \"odd key\": 1.0d\n}"
+        );
+    }
+
+    #[test]
+    fn roundtrip_through_binary_codecs() {
+        let input = r#"{Name:"Chest",Items:[{Slot:0b,Count:64b,Damage:3s,id:"minecraft:stone"}],Pos:[1.5d,-2.0d],Time:123456789012L,Speed:0.1f,Data:[I;1,-1],Flags:[B;1b],Seeds:[L;7L],Empty:[],Nested:{}}"#;
+        let tag = parse_snbt(input).unwrap();
+        let NbtTag::Compound(c) = tag.clone() else {
+            panic!("expected a compound");
+        };
+        let root = crate::NbtRoot::new("", c);
+
+        let mut le = Vec::new();
+        crate::write_nbt_le(&mut le, &root);
+        let from_le = crate::read_nbt_le(&mut &le[..]).unwrap();
+        let mut network = Vec::new();
+        crate::write_nbt_network(&mut network, &root);
+        let from_network = crate::read_nbt_network(&mut &network[..]).unwrap();
+
+        for decoded in [from_le, from_network] {
+            let printed = to_snbt(&NbtTag::Compound(decoded.compound));
+            assert_eq!(parse_snbt(&printed).unwrap(), tag);
+            assert_eq!(
+                parse_snbt(&to_snbt_pretty(&parse_snbt(&printed).unwrap())).unwrap(),
+                tag
+            );
+        }
+    }
+}
@@ -0,0 +1,531 @@
+//! Event-based NBT reader.
+//!
+//! [`NbtStreamReader`] walks binary NBT one event at a time without building
+//! a tree: strings and byte arrays borrow from the input, and int/long
+//! arrays are decoded lazily. Containers the caller doesn't care about can
+//! be skipped with [`NbtStreamReader::skip`].
+
+use crate::error::NbtError;
+use crate::io::NbtVariant;
+use crate::le::LeVariant;
+use crate::limits::NbtLimits;
+use crate::network::NetworkVariant;
+use crate::tag::NbtTag;
+
+/// One step of an NBT document. `name` is `None` for list elements.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtEvent<'a> {
+    CompoundStart {
+        name: Option<&'a str>,
+    },
+    CompoundEnd,
+    ListStart {
+        name: Option<&'a str>,
+        element_type: u8,
+        len: usize,
+    },
+    ListEnd,
+    Value {
+        name: Option<&'a str>,
+        value: NbtValue<'a>,
+    },
+}
+
+/// A tag that isn't a compound or a list.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtValue<'a> {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(&'a str),
+    ByteArray(&'a [u8]),
+    IntArray(IntArray<'a>),
+    LongArray(LongArray<'a>),
+}
+
+impl NbtValue<'_> {
+    /// Copy the value into an owned tag.
+    pub fn to_tag(&self) -> NbtTag {
+        match self {
+            NbtValue::Byte(v) => NbtTag::Byte(*v),
+            NbtValue::Short(v) => NbtTag::Short(*v),
+            NbtValue::Int(v) => NbtTag::Int(*v),
+            NbtValue::Long(v) => NbtTag::Long(*v),
+            NbtValue::Float(v) => NbtTag::Float(*v),
+            NbtValue::Double(v) => NbtTag::Double(*v),
+            NbtValue::String(v) => NbtTag::String(v.to_string()),
+            NbtValue::ByteArray(v) => NbtTag::ByteArray(v.iter().map(|&b| b as i8).collect()),
+            NbtValue::IntArray(v) => NbtTag::IntArray(v.iter().collect()),
+            NbtValue::LongArray(v) => NbtTag::LongArray(v.iter().collect()),
+        }
+    }
+}
+
+/// An int array borrowed from the input, decoded on iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntArray<'a> {
+    data: &'a [u8],
+    len: usize,
+    varint: bool,
+}
+
+impl<'a> IntArray<'a> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = i32> + 'a {
+        let mut data = self.data;
+        let varint = self.varint;
+        // The elements were validated when the array was read
+        (0..self.len).map(move |_| {
+            let v = if varint {
+                NetworkVariant::read_int(&mut data)
+            } else {
+                LeVariant::read_int(&mut data)
+            };
+            v.unwrap_or_default()
+        })
+    }
+}
+
+/// A long array borrowed from the input, decoded on iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LongArray<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> LongArray<'a> {
+    pub fn len(&self) -> usize {
+        self.data.len() / 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = i64> + 'a {
+        self.data
+            .chunks_exact(8)
+            .map(|c| i64::from_le_bytes(c.try_into().expect("8 bytes")))
+    }
+}
+
+enum Frame {
+    Compound,
+    List { element_type: u8, remaining: usize },
+}
+
+/// Pull reader over binary NBT.
+pub struct NbtStreamReader<'a> {
+    data: &'a [u8],
+    total: usize,
+    varint: bool,
+    limits: NbtLimits,
+    stack: Vec<Frame>,
+    started: bool,
+}
+
+impl<'a> NbtStreamReader<'a> {
+    /// Reader for standard little-endian NBT.
+    pub fn le(data: &'a [u8]) -> Self {
+        Self::new(data, false)
+    }
+
+    /// Reader for network NBT.
+    pub fn network(data: &'a [u8]) -> Self {
+        Self::new(data, true)
+    }
+
+    fn new(data: &'a [u8], varint: bool) -> Self {
+        Self {
+            data,
+            total: data.len(),
+            varint,
+            limits: NbtLimits::default(),
+            stack: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Apply depth and size limits.
+    pub fn with_limits(mut self, limits: NbtLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Input left after the root compound (or not read yet).
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Nesting depth of the current position (0 outside the root).
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Read the next event. Returns `None` once the root compound has ended.
+    pub fn next_event(&mut self) -> Result<Option<NbtEvent<'a>>, NbtError> {
+        if !self.started {
+            self.started = true;
+            let tag_type = self.take(1)?[0];
+            if tag_type != 10 {
+                return Err(NbtError::ExpectedCompound { got: tag_type });
+            }
+            let name = self.read_string()?;
+            self.push(Frame::Compound)?;
+            return Ok(Some(NbtEvent::CompoundStart { name: Some(name) }));
+        }
+
+        match self.stack.last_mut() {
+            None => Ok(None),
+            Some(Frame::Compound) => {
+                let tag_type = self.take(1)?[0];
+                if tag_type == 0 {
+                    self.stack.pop();
+                    return Ok(Some(NbtEvent::CompoundEnd));
+                }
+                let name = self.read_string()?;
+                self.read_payload(tag_type, Some(name)).map(Some)
+            }
+            Some(Frame::List {
+                element_type,
+                remaining,
+            }) => {
+                if *remaining == 0 {
+                    self.stack.pop();
+                    return Ok(Some(NbtEvent::ListEnd));
+                }
+                *remaining -= 1;
+                let element_type = *element_type;
+                self.read_payload(element_type, None).map(Some)
+            }
+        }
+    }
+
+    /// Skip the rest of the compound or list opened by the last event.
+    pub fn skip(&mut self) -> Result<(), NbtError> {
+        let depth = self.stack.len();
+        while self.stack.len() >= depth && depth > 0 {
+            if self.next_event()?.is_none() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_payload(
+        &mut self,
+        tag_type: u8,
+        name: Option<&'a str>,
+    ) -> Result<NbtEvent<'a>, NbtError> {
+        let value = match tag_type {
+            1 => NbtValue::Byte(self.take(1)?[0] as i8),
+            2 => NbtValue::Short(i16::from_le_bytes(self.take_array()?)),
+            3 => NbtValue::Int(self.read_int()?),
+            4 => NbtValue::Long(i64::from_le_bytes(self.take_array()?)),
+            5 => NbtValue::Float(f32::from_le_bytes(self.take_array()?)),
+            6 => NbtValue::Double(f64::from_le_bytes(self.take_array()?)),
+            7 => {
+                let len = self.read_len(1)?;
+                NbtValue::ByteArray(self.take(len)?)
+            }
+            8 => NbtValue::String(self.read_string()?),
+            9 => {
+                let element_type = self.take(1)?[0];
+                let len = self.read_len(0)?;
+                self.push(Frame::List {
+                    element_type,
+                    remaining: len,
+                })?;
+                return Ok(NbtEvent::ListStart {
+                    name,
+                    element_type,
+                    len,
+                });
+            }
+            10 => {
+                self.push(Frame::Compound)?;
+                return Ok(NbtEvent::CompoundStart { name });
+            }
+            11 => {
+                let len = self.read_len(1)?;
+                let start = self.data;
+                for _ in 0..len {
+                    self.read_int()?;
+                }
+                let data = &start[..start.len() - self.data.len()];
+                NbtValue::IntArray(IntArray {
+                    data,
+                    len,
+                    varint: self.varint,
+                })
+            }
+            12 => {
+                let len = self.read_len(8)?;
+                NbtValue::LongArray(LongArray {
+                    data: self.take(len * 8)?,
+                })
+            }
+            _ => return Err(NbtError::UnknownTagType(tag_type)),
+        };
+        Ok(NbtEvent::Value { name, value })
+    }
+
+    fn push(&mut self, frame: Frame) -> Result<(), NbtError> {
+        if self.stack.len() > self.limits.max_depth {
+            return Err(NbtError::NestingTooDeep {
+                limit: self.limits.max_depth,
+            });
+        }
+        self.stack.push(frame);
+        Ok(())
+    }
+
+    /// Fail if reading `upcoming` more bytes would go over `max_bytes`.
+    fn check_size(&self, upcoming: usize) -> Result<(), NbtError> {
+        let read = self.total - self.data.len();
+        self.limits.check_size(read.saturating_add(upcoming))
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], NbtError> {
+        self.check_size(n)?;
+        if self.data.len() < n {
+            return Err(NbtError::UnexpectedEof);
+        }
+        let (head, tail) = self.data.split_at(n);
+        self.data = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], NbtError> {
+        Ok(self.take(N)?.try_into().expect("N bytes"))
+    }
+
+    fn read_int(&mut self) -> Result<i32, NbtError> {
+        let v = if self.varint {
+            NetworkVariant::read_int(&mut self.data)
+        } else {
+            LeVariant::read_int(&mut self.data)
+        }?;
+        self.check_size(0)?;
+        Ok(v)
+    }
+
+    /// Read a list or array length, checked against the limits.
+    /// `element_size` is the minimum encoded size of an element.
+    fn read_len(&mut self, element_size: usize) -> Result<usize, NbtError> {
+        let len = if self.varint {
+            NetworkVariant::read_array_len(&mut self.data)
+        } else {
+            LeVariant::read_array_len(&mut self.data)
+        }?;
+        if len < 0 {
+            return Err(NbtError::NegativeLength(len));
+        }
+        let len = len as usize;
+        self.limits.check_len(len)?;
+        self.check_size(len.saturating_mul(element_size))?;
+        Ok(len)
+    }
+
+    fn read_string(&mut self) -> Result<&'a str, NbtError> {
+        let len = if self.varint {
+            NetworkVariant::read_string_len(&mut self.data)
+        } else {
+            LeVariant::read_string_len(&mut self.data)
+        }?;
+        std::str::from_utf8(self.take(len)?).map_err(|_| NbtError::InvalidUtf8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tag::{NbtCompound, NbtRoot};
+
+    fn sample() -> NbtRoot {
+        let mut block = NbtCompound::new();
+        block.insert("name".into(), NbtTag::String("minecraft:stone".into()));
+        let mut c = NbtCompound::new();
+        c.insert("size".into(), NbtTag::IntArray(vec![3, -1, 70000]));
+        c.insert(
+            "palette".into(),
+            NbtTag::List(vec![NbtTag::Compound(block)]),
+        );
+        c.insert("seeds".into(), NbtTag::LongArray(vec![i64::MIN, 5]));
+        c.insert("data".into(), NbtTag::ByteArray(vec![1, -1]));
+        NbtRoot::new("structure", c)
+    }
+
+    /// Rebuild a tree from the events.
+    fn collect(reader: &mut NbtStreamReader) -> NbtRoot {
+        fn container(reader: &mut NbtStreamReader, list: bool) -> NbtTag {
+            let mut items = Vec::new();
+            let mut map = NbtCompound::new();
+            loop {
+                let (name, tag) = match reader.next_event().unwrap().unwrap() {
+                    NbtEvent::CompoundEnd | NbtEvent::ListEnd => break,
+                    NbtEvent::CompoundStart { name } => (name, container(reader, false)),
+                    NbtEvent::ListStart { name, .. } => (name, container(reader, true)),
+                    NbtEvent::Value { name, value } => (name, value.to_tag()),
+                };
+                match name {
+                    Some(name) => {
+                        map.insert(name.to_string(), tag);
+                    }
+                    None => items.push(tag),
+                }
+            }
+            if list {
+                NbtTag::List(items)
+            } else {
+                NbtTag::Compound(map)
+            }
+        }
+
+        let Some(NbtEvent::CompoundStart { name: Some(name) }) = reader.next_event().unwrap()
+        else {
+            panic!("expected the root compound");
+        };
+        let NbtTag::Compound(compound) = container(reader, false) else {
+            unreachable!();
+        };
+        assert_eq!(reader.next_event().unwrap(), None);
+        NbtRoot::new(name, compound)
+    }
+
+    #[test]
+    fn events_match_tree_reader() {
+        let root = sample();
+        let mut le = Vec::new();
+        crate::write_nbt_le(&mut le, &root);
+        assert_eq!(collect(&mut NbtStreamReader::le(&le)), root);
+
+        let mut network = Vec::new();
+        crate::write_nbt_network(&mut network, &root);
+        assert_eq!(collect(&mut NbtStreamReader::network(&network)), root);
+    }
+
+    #[test]
+    fn strings_borrow_from_input() {
+        let mut le = Vec::new();
+        crate::write_nbt_le(&mut le, &sample());
+        let mut reader = NbtStreamReader::le(&le);
+        while let Some(event) = reader.next_event().unwrap() {
+            if let NbtEvent::Value {
+                value: NbtValue::String(s),
+                ..
+            } = event
+            {
+                let range = le.as_ptr_range();
+                assert!(range.contains(&s.as_ptr()));
+                return;
+            }
+        }
+        panic!("no string found");
+    }
+
+    #[test]
+    fn skip_container() {
+        let mut le = Vec::new();
+        crate::write_nbt_le(&mut le, &sample());
+        let mut reader = NbtStreamReader::le(&le);
+        let mut names = Vec::new();
+        while let Some(event) = reader.next_event().unwrap() {
+            match event {
+                NbtEvent::ListStart { name, len, .. } => {
+                    assert_eq!(len, 1);
+                    names.push(name.unwrap().to_string());
+                    reader.skip().unwrap();
+                }
+                NbtEvent::Value {
+                    name: Some(name), ..
+                } => names.push(name.to_string()),
+                NbtEvent::Value { name: None, .. } | NbtEvent::CompoundStart { .. } => {
+                    assert_eq!(reader.depth(), 1, "only the root compound is opened");
+                }
+                _ => {}
+            }
+        }
+        names.sort();
+        assert_eq!(names, ["data", "palette", "seeds", "size"]);
+        assert!(reader.remaining().is_empty());
+    }
+
+    #[test]
+    fn limits_reject_bombs() {
+        // A list claiming 2^31 - 1 compounds
+        let mut bomb = vec![10, 0, 0, 9, 1, 0, b'l', 10];
+        bomb.extend_from_slice(&i32::MAX.to_le_bytes());
+        let mut reader = NbtStreamReader::le(&bomb).with_limits(NbtLimits::untrusted());
+        reader.next_event().unwrap();
+        assert!(matches!(reader.next_event(), Err(NbtError::TooLong { .. })));
+        assert!(matches!(
+            crate::read_nbt_le_with_limits(&mut &bomb[..], &NbtLimits::untrusted()),
+            Err(NbtError::TooLong { .. })
+        ));
+        // Without limits the tree reader fails on the missing data instead of
+        // allocating for the claimed length
+        assert!(matches!(
+            crate::read_nbt_le(&mut &bomb[..]),
+            Err(NbtError::UnexpectedEof)
+        ));
+
+        let limits = NbtLimits {
+            max_bytes: 16,
+            ..NbtLimits::default()
+        };
+        let mut le = Vec::new();
+        crate::write_nbt_le(&mut le, &sample());
+        let mut reader = NbtStreamReader::le(&le).with_limits(limits);
+        let err = loop {
+            match reader.next_event() {
+                Ok(Some(_)) => {}
+                Ok(None) => panic!("read past the size limit"),
+                Err(e) => break e,
+            }
+        };
+        assert!(matches!(err, NbtError::TooLarge { limit: 16 }));
+        assert!(matches!(
+            crate::read_nbt_le_with_limits(&mut &le[..], &limits),
+            Err(NbtError::TooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn depth_limit() {
+        let mut tag = NbtTag::Compound(NbtCompound::new());
+        for _ in 0..10 {
+            let mut c = NbtCompound::new();
+            c.insert("c".into(), tag);
+            tag = NbtTag::Compound(c);
+        }
+        let NbtTag::Compound(c) = tag else {
+            unreachable!();
+        };
+        let mut network = Vec::new();
+        crate::write_nbt_network(&mut network, &NbtRoot::new("", c));
+        let limits = NbtLimits {
+            max_depth: 5,
+            ..NbtLimits::default()
+        };
+
+        let mut reader = NbtStreamReader::network(&network).with_limits(limits);
+        let err = loop {
+            if let Err(e) = reader.next_event() {
+                break e;
+            }
+        };
+        assert!(matches!(err, NbtError::NestingTooDeep { limit: 5 }));
+        assert!(crate::read_nbt_network_with_limits(&mut &network[..], &limits).is_err());
+        assert!(crate::read_nbt_network(&mut &network[..]).is_ok());
+    }
+}
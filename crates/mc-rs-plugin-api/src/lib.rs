@@ -14,6 +14,10 @@ pub struct PluginPlayer {
     pub position: (f32, f32, f32),
     pub gamemode: i32,
     pub health: f32,
+    /// ISO code of the country the player connects from (`"FR"`), if
+    /// `[geoip]` is enabled and knows it.
+    #[serde(default)]
+    pub country: Option<String>,
 }
 
 /// Block position for plugin events (decoupled from mc-rs-proto).
@@ -38,6 +42,143 @@ pub enum DamageCause {
     Other,
 }
 
+/// An item entry in a plugin-registered kit.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PluginKitItem {
+    /// Item identifier; the `minecraft:` prefix is optional.
+    pub item: String,
+    pub count: u16,
+    pub metadata: u16,
+}
+
+/// A team of a plugin-defined arena.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PluginArenaTeam {
+    pub name: String,
+    /// Where the team's players start a match.
+    pub spawn: (f32, f32, f32),
+}
+
+/// A minigame arena, as passed to `ServerApi::create_arena`.
+///
+/// Players queue in the lobby; once `min_players` have joined a countdown
+/// starts, then the players are split over the teams and sent to their
+/// spawns. The blocks inside `region` are put back when the match ends.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PluginArena {
+    pub name: String,
+    /// Two opposite corners of the arena, inclusive.
+    pub region: (PluginBlockPos, PluginBlockPos),
+    /// Where queued players wait, and go back to after a match.
+    pub lobby: (f32, f32, f32),
+    pub teams: Vec<PluginArenaTeam>,
+    pub min_players: u32,
+    pub max_players: u32,
+    pub countdown_secs: u32,
+    /// Match length; 0 runs until the plugin calls `end_arena`.
+    pub duration_secs: u32,
+}
+
+/// A step of a cutscene.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PluginCutsceneStep {
+    /// Move the camera to a position, looking at `rotation` (pitch, yaw),
+    /// and wait until it gets there. `ease` is a Bedrock easing function such
+    /// as `linear` or `in_out_sine`; the camera jumps when `duration_secs`
+    /// is 0.
+    Camera {
+        position: (f32, f32, f32),
+        rotation: (f32, f32),
+        ease: String,
+        duration_secs: f32,
+    },
+    /// Fade the screen to a color (red, green and blue, from 0 to 1) and
+    /// back. The next steps run while it fades.
+    Fade {
+        color: (f32, f32, f32),
+        fade_in_secs: f32,
+        hold_secs: f32,
+        fade_out_secs: f32,
+    },
+    /// Do nothing for a while.
+    Wait { secs: f32 },
+}
+
+/// A camera sequence, as passed to `ServerApi::play_cutscene`. The player
+/// gets their camera back once the last step is done.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PluginCutscene {
+    pub name: String,
+    pub steps: Vec<PluginCutsceneStep>,
+}
+
+/// Changes to how an entity looks, as passed to
+/// `ServerApi::set_entity_appearance`. Fields left unset keep their value.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PluginEntityAppearance {
+    #[serde(default)]
+    pub nametag: Option<String>,
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Mob variant, such as a cat's breed.
+    #[serde(default)]
+    pub variant: Option<i32>,
+    /// Mob color, such as a sheep's wool.
+    #[serde(default)]
+    pub color: Option<u8>,
+    /// Flags to turn on or off by name, such as `on_fire`, `invisible`,
+    /// `baby` or `always_show_nametag`.
+    #[serde(default)]
+    pub flags: Vec<(String, bool)>,
+}
+
+/// Cumulative statistics of a player, as returned by `ServerApi::get_player_stats`.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PluginPlayerStats {
+    /// Blocks broken, keyed by block name.
+    pub blocks_mined: std::collections::HashMap<String, u64>,
+    /// Mobs killed, keyed by entity type.
+    pub mobs_killed: std::collections::HashMap<String, u64>,
+    pub distance_walked_cm: u64,
+    pub deaths: u64,
+    pub playtime_ticks: u64,
+}
+
+/// What fired a script event.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum ScriptEventSource {
+    /// The console, RCON, or another plugin.
+    Server,
+    /// A player running `/scriptevent`.
+    Player(PluginPlayer),
+    /// A behavior pack entity (via a `queue_command` in one of its events).
+    Entity {
+        runtime_id: u64,
+        entity_type: String,
+    },
+}
+
+/// Whether `id` is a valid script event ID: `namespace:name`, where the
+/// namespace isn't `minecraft` and neither part is empty or has whitespace.
+pub fn is_valid_script_event_id(id: &str) -> bool {
+    match id.split_once(':') {
+        Some((namespace, name)) => {
+            !namespace.is_empty()
+                && !name.is_empty()
+                && namespace != "minecraft"
+                && !id.chars().any(char::is_whitespace)
+        }
+        None => false,
+    }
+}
+
+/// Dimensions with their own world flags.
+pub const WORLD_FLAG_DIMENSIONS: [&str; 3] = ["overworld", "nether", "end"];
+
+/// World flags plugins can read and toggle per dimension.
+pub const WORLD_FLAGS: [&str; 4] = ["pvp", "fall_damage", "drowning", "hunger"];
+
 /// Log level for plugin logging.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum LogLevel {
@@ -67,8 +208,145 @@ pub enum FormResponse {
     Simple { button_index: u32 },
     /// The player responded to a ModalForm (true = button1, false = button2).
     Modal { accepted: bool },
-    /// The player submitted a CustomForm with an array of values.
-    Custom { values: Vec<serde_json::Value> },
+    /// The player submitted a CustomForm, with a value for each element.
+    Custom { values: Vec<FormValue> },
+}
+
+/// The value a player gave an element of a CustomForm.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum FormValue {
+    /// Labels carry no value.
+    Label,
+    Input(String),
+    Toggle(bool),
+    /// Index of the chosen option.
+    Dropdown(usize),
+    Slider(f64),
+    /// Index of the chosen step.
+    StepSlider(usize),
+}
+
+impl FormValue {
+    /// The value of a `element_type` element (`"input"`, `"toggle"`...) as
+    /// sent by the client, or guessed from the JSON alone if the element
+    /// type is unknown.
+    pub fn from_json(element_type: Option<&str>, value: &serde_json::Value) -> Self {
+        let index = || value.as_u64().unwrap_or(0) as usize;
+        match (element_type, value) {
+            (Some("label"), _) | (None, serde_json::Value::Null) => Self::Label,
+            (Some("toggle"), _) | (None, serde_json::Value::Bool(_)) => {
+                Self::Toggle(value.as_bool().unwrap_or(false))
+            }
+            (Some("dropdown"), _) => Self::Dropdown(index()),
+            (Some("step_slider"), _) => Self::StepSlider(index()),
+            (Some("slider"), _) | (None, serde_json::Value::Number(_)) => {
+                Self::Slider(value.as_f64().unwrap_or(0.0))
+            }
+            _ => Self::Input(value.as_str().unwrap_or_default().to_string()),
+        }
+    }
+
+    /// The text of an input.
+    pub fn as_text(&self) -> Option<&str> {
+        match self {
+            Self::Input(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Whether a toggle is on.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Toggle(on) => Some(*on),
+            _ => None,
+        }
+    }
+
+    /// The chosen index of a dropdown or step slider.
+    pub fn as_index(&self) -> Option<usize> {
+        match self {
+            Self::Dropdown(i) | Self::StepSlider(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// The value of a slider.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Slider(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The value as the client sends it, e.g. to fill a form in again.
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Self::Label => serde_json::Value::Null,
+            Self::Input(text) => serde_json::json!(text),
+            Self::Toggle(on) => serde_json::json!(on),
+            Self::Dropdown(i) | Self::StepSlider(i) => serde_json::json!(i),
+            Self::Slider(n) => serde_json::json!(n),
+        }
+    }
+}
+
+/// A rule the text of a CustomForm input must follow. An answer breaking
+/// one never reaches the plugin: the form opens again, saying what is wrong.
+/// Rules other than `Required` let an input be left empty.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum InputRule {
+    /// The input must not be empty.
+    Required,
+    MinLength {
+        min: usize,
+    },
+    MaxLength {
+        max: usize,
+    },
+    /// A whole number from `min` to `max`.
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// A number from `min` to `max`.
+    Number {
+        min: f64,
+        max: f64,
+    },
+    /// Only ASCII letters, digits and underscores.
+    Alphanumeric,
+}
+
+impl InputRule {
+    /// Check the text of an input, returning what is wrong with it.
+    pub fn check(&self, text: &str) -> Result<(), String> {
+        let ok = match self {
+            Self::Required => !text.trim().is_empty(),
+            Self::MinLength { min } => text.chars().count() >= *min,
+            Self::MaxLength { max } => text.chars().count() <= *max,
+            Self::Integer { min, max } => text
+                .trim()
+                .parse::<i64>()
+                .is_ok_and(|n| (*min..=*max).contains(&n)),
+            Self::Number { min, max } => text
+                .trim()
+                .parse::<f64>()
+                .is_ok_and(|n| (*min..=*max).contains(&n)),
+            Self::Alphanumeric => text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        };
+        if ok {
+            return Ok(());
+        }
+        Err(match self {
+            Self::Required => "This field is required".to_string(),
+            Self::MinLength { min } => format!("Use at least {min} characters"),
+            Self::MaxLength { max } => format!("Use at most {max} characters"),
+            Self::Integer { min, max } => format!("Enter a whole number from {min} to {max}"),
+            Self::Number { min, max } => format!("Enter a number from {min} to {max}"),
+            Self::Alphanumeric => "Use only letters, digits and underscores".to_string(),
+        })
+    }
 }
 
 /// An image for a form button.
@@ -97,6 +375,7 @@ pub enum FormElement {
         text: String,
         placeholder: String,
         default: String,
+        rules: Vec<InputRule>,
     },
     Toggle {
         text: String,
@@ -232,11 +511,23 @@ impl CustomFormBuilder {
         self
     }
 
-    pub fn input(mut self, text: &str, placeholder: &str, default: &str) -> Self {
+    pub fn input(self, text: &str, placeholder: &str, default: &str) -> Self {
+        self.validated_input(text, placeholder, default, Vec::new())
+    }
+
+    /// An input whose text must follow `rules`.
+    pub fn validated_input(
+        mut self,
+        text: &str,
+        placeholder: &str,
+        default: &str,
+        rules: Vec<InputRule>,
+    ) -> Self {
         self.elements.push(FormElement::Input {
             text: text.to_string(),
             placeholder: placeholder.to_string(),
             default: default.to_string(),
+            rules,
         });
         self
     }
@@ -291,12 +582,19 @@ impl CustomFormBuilder {
                     text,
                     placeholder,
                     default,
-                } => serde_json::json!({
-                    "type": "input",
-                    "text": text,
-                    "placeholder": placeholder,
-                    "default": default,
-                }),
+                    rules,
+                } => {
+                    let mut input = serde_json::json!({
+                        "type": "input",
+                        "text": text,
+                        "placeholder": placeholder,
+                        "default": default,
+                    });
+                    if !rules.is_empty() {
+                        input[VALIDATION_KEY] = serde_json::json!(rules);
+                    }
+                    input
+                }
                 FormElement::Toggle { text, default } => serde_json::json!({
                     "type": "toggle",
                     "text": text,
@@ -347,8 +645,28 @@ impl CustomFormBuilder {
     }
 }
 
-/// Parse a form response string based on form type.
-pub fn parse_form_response(form_type: &str, response_data: Option<&str>) -> FormResponse {
+/// Key of the [`InputRule`]s of an input in form JSON. The server keeps them
+/// to itself, the client never sees them.
+pub const VALIDATION_KEY: &str = "validation";
+
+/// The form type (`"simple"`, `"modal"` or `"custom"`) of form JSON.
+pub fn form_type_of(form_data: &str) -> Option<&'static str> {
+    let form: serde_json::Value = serde_json::from_str(form_data).ok()?;
+    match form["type"].as_str()? {
+        "form" => Some("simple"),
+        "modal" => Some("modal"),
+        "custom_form" => Some("custom"),
+        _ => None,
+    }
+}
+
+/// Parse a form response string based on form type. The values of a
+/// CustomForm are typed after the elements of its JSON, `form_data`.
+pub fn parse_form_response(
+    form_type: &str,
+    form_data: &str,
+    response_data: Option<&str>,
+) -> FormResponse {
     let data = match response_data {
         Some(d) => d,
         None => return FormResponse::Cancelled,
@@ -367,22 +685,100 @@ pub fn parse_form_response(form_type: &str, response_data: Option<&str>) -> Form
             FormResponse::Modal { accepted }
         }
         "custom" => {
-            if let Ok(values) = serde_json::from_str::<Vec<serde_json::Value>>(data) {
-                FormResponse::Custom { values }
-            } else {
-                FormResponse::Cancelled
-            }
+            let Ok(values) = serde_json::from_str::<Vec<serde_json::Value>>(data) else {
+                return FormResponse::Cancelled;
+            };
+            let form = serde_json::from_str::<serde_json::Value>(form_data).unwrap_or_default();
+            let values = values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| FormValue::from_json(form["content"][i]["type"].as_str(), value))
+                .collect();
+            FormResponse::Custom { values }
         }
         _ => FormResponse::Cancelled,
     }
 }
 
+/// What is wrong with the answer to a CustomForm: the index of each input
+/// breaking one of its [`InputRule`]s, with the first rule it breaks.
+pub fn validate_form_response(form_data: &str, response: &FormResponse) -> Vec<(usize, String)> {
+    let FormResponse::Custom { values } = response else {
+        return Vec::new();
+    };
+    let Ok(form) = serde_json::from_str::<serde_json::Value>(form_data) else {
+        return Vec::new();
+    };
+    let Some(content) = form["content"].as_array() else {
+        return Vec::new();
+    };
+    content
+        .iter()
+        .zip(values)
+        .enumerate()
+        .filter_map(|(i, (element, value))| {
+            let rules: Vec<InputRule> =
+                serde_json::from_value(element.get(VALIDATION_KEY)?.clone()).ok()?;
+            let text = value.as_text()?;
+            if text.is_empty() && !rules.contains(&InputRule::Required) {
+                return None;
+            }
+            let error = rules.iter().find_map(|rule| rule.check(text).err())?;
+            Some((i, error))
+        })
+        .collect()
+}
+
+/// A CustomForm filled in with the answer the player gave, and the errors
+/// found in it shown under the inputs they belong to.
+pub fn form_with_errors(
+    form_data: &str,
+    response: &FormResponse,
+    errors: &[(usize, String)],
+) -> String {
+    let Ok(mut form) = serde_json::from_str::<serde_json::Value>(form_data) else {
+        return form_data.to_string();
+    };
+    if let (FormResponse::Custom { values }, Some(content)) =
+        (response, form["content"].as_array_mut())
+    {
+        for (element, value) in content.iter_mut().zip(values) {
+            if !matches!(value, FormValue::Label) {
+                element["default"] = value.to_json();
+            }
+        }
+        for (i, error) in errors {
+            if let Some(element) = content.get_mut(*i) {
+                let text = element["text"].as_str().unwrap_or_default();
+                element["text"] = serde_json::json!(format!("{text}\n§c{error}"));
+            }
+        }
+    }
+    form.to_string()
+}
+
+/// Form JSON as sent to the client, without the [`InputRule`]s.
+pub fn client_form_json(form_data: &str) -> String {
+    let Ok(mut form) = serde_json::from_str::<serde_json::Value>(form_data) else {
+        return form_data.to_string();
+    };
+    let Some(content) = form["content"].as_array_mut() else {
+        return form_data.to_string();
+    };
+    for element in content {
+        if let Some(element) = element.as_object_mut() {
+            element.remove(VALIDATION_KEY);
+        }
+    }
+    form.to_string()
+}
+
 // ─── Events ──────────────────────────────────────────────────────────────────
 
 /// All events that plugins can listen to.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub enum PluginEvent {
-    // --- Player events (8) ---
+    // --- Player events (12) ---
     PlayerJoin {
         player: PluginPlayer,
     },
@@ -415,8 +811,33 @@ pub enum PluginEvent {
     PlayerRespawn {
         player: PluginPlayer,
     },
+    /// A player played an emote, before it is shown to nearby players.
+    PlayerEmote {
+        player: PluginPlayer,
+        emote_id: String,
+    },
+    /// A player failed an anti-cheat check, before the violation is scored
+    /// and acted on. `score` is what the check's violation score becomes.
+    PlayerViolation {
+        player: PluginPlayer,
+        check: String,
+        score: u32,
+        detail: String,
+    },
+    /// A player was marked AFK after idling, or came back from AFK.
+    PlayerAfkChange {
+        player: PluginPlayer,
+        afk: bool,
+    },
+    /// A player sent another a private message with `/msg` or `/reply`,
+    /// before it is delivered.
+    PlayerPrivateMessage {
+        player: PluginPlayer,
+        recipient: PluginPlayer,
+        message: String,
+    },
 
-    // --- Block events (2) ---
+    // --- Block events (3) ---
     BlockBreak {
         player: PluginPlayer,
         position: PluginBlockPos,
@@ -427,6 +848,14 @@ pub enum PluginEvent {
         position: PluginBlockPos,
         block_id: u32,
     },
+    /// A player finished editing a sign. Both faces are sent, with the
+    /// new text on the face that was edited.
+    SignChange {
+        player: PluginPlayer,
+        position: PluginBlockPos,
+        front_text: String,
+        back_text: String,
+    },
 
     // --- Entity events (3) ---
     MobSpawn {
@@ -445,7 +874,7 @@ pub enum PluginEvent {
         attacker_runtime_id: Option<u64>,
     },
 
-    // --- World events (2) ---
+    // --- World events (5) ---
     WeatherChange {
         raining: bool,
         thundering: bool,
@@ -453,6 +882,23 @@ pub enum PluginEvent {
     TimeChange {
         new_time: i64,
     },
+    /// A chunk came into view of a player while no other player had it in
+    /// view. `dimension` is 0-2 for the main world's overworld, nether and
+    /// end, and an extra world's own ID from 3 on.
+    ChunkLoad {
+        dimension: i32,
+        cx: i32,
+        cz: i32,
+    },
+    /// The last player having a chunk in view lost it.
+    ChunkUnload {
+        dimension: i32,
+        cx: i32,
+        cz: i32,
+    },
+    /// The world is being saved, by auto-save, `save-all` or on shutdown.
+    /// Plugins can flush their own data alongside it.
+    WorldSave,
 
     // --- Form events (1) ---
     FormResponse {
@@ -464,6 +910,36 @@ pub enum PluginEvent {
     // --- Server events (2) ---
     ServerStarted,
     ServerStopping,
+
+    // --- Arena events (2) ---
+    /// An arena's countdown ran out and its match began. `teams` lists the
+    /// players of each team.
+    ArenaStart {
+        arena: String,
+        teams: Vec<(String, Vec<String>)>,
+    },
+    /// An arena's match ended, after its blocks were rolled back.
+    ArenaEnd {
+        arena: String,
+        winner: Option<String>,
+    },
+
+    // --- NPC events (1) ---
+    /// A player clicked an NPC with an `event` action; `id` is the action's.
+    NpcInteract {
+        player: PluginPlayer,
+        npc: String,
+        id: String,
+    },
+
+    // --- Script events (1) ---
+    /// A `@minecraft/server`-style script event, from `/scriptevent`, a
+    /// behavior pack entity or a plugin.
+    ScriptEvent {
+        id: String,
+        payload: String,
+        source: ScriptEventSource,
+    },
 }
 
 impl PluginEvent {
@@ -475,8 +951,12 @@ impl PluginEvent {
                 | PluginEvent::PlayerCommand { .. }
                 | PluginEvent::PlayerMove { .. }
                 | PluginEvent::PlayerDamage { .. }
+                | PluginEvent::PlayerEmote { .. }
+                | PluginEvent::PlayerViolation { .. }
+                | PluginEvent::PlayerPrivateMessage { .. }
                 | PluginEvent::BlockBreak { .. }
                 | PluginEvent::BlockPlace { .. }
+                | PluginEvent::SignChange { .. }
                 | PluginEvent::MobSpawn { .. }
                 | PluginEvent::EntityDamage { .. }
                 | PluginEvent::WeatherChange { .. }
@@ -544,29 +1024,51 @@ pub trait Plugin: Send {
 /// Safe read/write access to server state, passed to plugins during callbacks.
 ///
 /// Read methods return data immediately. Write methods are deferred (applied
-/// after the plugin callback returns).
+/// after the plugin callback returns), except the economy ones.
 pub trait ServerApi {
     // --- Players ---
     fn online_players(&self) -> Vec<PluginPlayer>;
     fn get_player(&self, name: &str) -> Option<PluginPlayer>;
+    /// Statistics of an online player.
+    fn get_player_stats(&self, name: &str) -> Option<PluginPlayerStats>;
     fn send_message(&mut self, player_name: &str, message: &str);
     fn broadcast_message(&mut self, message: &str);
     fn kick_player(&mut self, player_name: &str, reason: &str);
     fn set_player_health(&mut self, player_name: &str, health: f32);
     fn set_player_food(&mut self, player_name: &str, food: i32);
     fn teleport_player(&mut self, player_name: &str, x: f32, y: f32, z: f32);
+    /// Send a player to another server (with identity forwarding if configured).
+    fn transfer_player(&mut self, player_name: &str, host: &str, port: u16);
 
     // --- World ---
     fn get_time(&self) -> i64;
     fn set_time(&mut self, time: i64);
     fn is_raining(&self) -> bool;
+    /// Whether a world flag (see [`WORLD_FLAGS`]) is on in a dimension (see
+    /// [`WORLD_FLAG_DIMENSIONS`]). `None` for an unknown flag or dimension.
+    fn get_world_flag(&self, dimension: &str, flag: &str) -> Option<bool>;
+    fn set_world_flag(&mut self, dimension: &str, flag: &str, value: bool);
 
     // --- Entities ---
     fn spawn_mob(&mut self, mob_type: &str, x: f32, y: f32, z: f32);
     fn remove_mob(&mut self, runtime_id: u64);
+    /// Fire a behavior pack entity event (e.g. `"minecraft:become_angry"`) on a mob.
+    fn trigger_entity_event(&mut self, runtime_id: u64, event: &str);
+    /// Change how a mob or player looks to everyone, including players who
+    /// see it later.
+    fn set_entity_appearance(&mut self, runtime_id: u64, appearance: PluginEntityAppearance);
+
+    // --- Script events ---
+    /// Send a script event to every plugin (as [`PluginEvent::ScriptEvent`]
+    /// with a [`ScriptEventSource::Server`] source).
+    fn send_script_event(&mut self, id: &str, payload: &str);
 
     // --- Server ---
     fn get_tick(&self) -> u64;
+    /// Ticks per second over the last few seconds (at most 20).
+    fn get_tps(&self) -> f64;
+    /// Mean milliseconds a tick took to run over the last few seconds.
+    fn get_mspt(&self) -> f64;
     fn log(&self, level: LogLevel, message: &str);
 
     // --- Scheduler ---
@@ -587,6 +1089,77 @@ pub trait ServerApi {
     fn show_simple_form(&mut self, player_name: &str, form_id: u32, json: &str);
     fn show_modal_form(&mut self, player_name: &str, form_id: u32, json: &str);
     fn show_custom_form(&mut self, player_name: &str, form_id: u32, json: &str);
+    /// Show a form from the response to another form of the plugin: closing
+    /// it goes back to that form. The form type is read from the JSON.
+    fn show_next_form(&mut self, player_name: &str, form_id: u32, json: &str);
+
+    // --- Kits ---
+    /// Register a kit claimable via `/kit <name>`. `cooldown_secs` = 0 disables the cooldown.
+    fn register_kit(&mut self, name: &str, items: Vec<PluginKitItem>, cooldown_secs: u64);
+
+    // --- Economy ---
+    // Money moves in the server's ledger straight away, so a shop knows
+    // whether a payment went through before handing anything over.
+    /// Name of the server currency (`[economy] currency_name`).
+    fn currency_name(&self) -> String;
+    /// Balance of a player, online or not. `None` if they never joined.
+    fn get_balance(&self, player_name: &str) -> Option<u64>;
+    /// Add money to a player's account. False if they have none.
+    fn deposit(&mut self, player_name: &str, amount: u64, reason: &str) -> bool;
+    /// Take money from a player's account. False if they have none or
+    /// haven't enough.
+    fn withdraw(&mut self, player_name: &str, amount: u64, reason: &str) -> bool;
+    /// Move money between two players' accounts. False, and nothing moves,
+    /// if either has none or the payer hasn't enough.
+    fn transfer_money(&mut self, from: &str, to: &str, amount: u64, reason: &str) -> bool;
+
+    // --- Arenas ---
+    /// Define an arena, or redefine one that isn't running a match.
+    fn create_arena(&mut self, arena: PluginArena);
+    /// Queue a player in an arena's lobby.
+    fn join_arena(&mut self, player_name: &str, arena_name: &str);
+    /// Take a player out of their arena and send them back to its lobby.
+    fn leave_arena(&mut self, player_name: &str);
+    /// Set a line of an arena's sidebar scoreboard.
+    fn set_arena_score(&mut self, arena_name: &str, entry: &str, score: i32);
+    /// End an arena's match, rolling its blocks back.
+    fn end_arena(&mut self, arena_name: &str, winner: Option<&str>);
+    /// Name of the arena a player is queued or playing in.
+    fn player_arena(&self, player_name: &str) -> Option<String>;
+
+    // --- Holograms ---
+    /// Show floating text in the overworld, its top line at the given
+    /// position, replacing a hologram with the same name. Lines may hold
+    /// placeholders such as `{online}` or `{balance}`.
+    fn create_hologram(&mut self, name: &str, x: f32, y: f32, z: f32, lines: Vec<String>);
+    /// Replace the lines of a hologram.
+    fn set_hologram_lines(&mut self, name: &str, lines: Vec<String>);
+    /// Delete a hologram.
+    fn remove_hologram(&mut self, name: &str);
+
+    // --- Camera ---
+    /// Play a cutscene to a player, replacing the one they are watching.
+    fn play_cutscene(&mut self, player_name: &str, cutscene: PluginCutscene);
+    /// Stop a player's cutscene and give them their camera back.
+    fn stop_cutscene(&mut self, player_name: &str);
+
+    // --- Client settings ---
+    /// Push a fog definition, such as `minecraft:fog_hell`, onto a player's
+    /// fog stack under a user ID.
+    fn push_fog(&mut self, player_name: &str, fog_id: &str, user_id: &str);
+    /// Remove every fog pushed under a user ID from a player's fog stack.
+    fn remove_fog(&mut self, player_name: &str, user_id: &str);
+    /// Set a client-side game rule (`showcoordinates`, `showdaysplayed`,
+    /// `showtags` or `showbordereffect`) for one player, until they rejoin.
+    fn set_client_game_rule(&mut self, player_name: &str, rule: &str, value: bool);
+    /// Allow or forbid one of a player's inputs, by its `/inputpermission`
+    /// name such as `camera`, `movement` or `jump`.
+    fn set_input_permission(&mut self, player_name: &str, permission: &str, enabled: bool);
+
+    // --- Resource packs ---
+    /// UUIDs of the packs an online player accepted when joining; empty if
+    /// they declined the packs or none were offered.
+    fn accepted_packs(&self, player_name: &str) -> Option<Vec<String>>;
 }
 
 // ─── Tests ───────────────────────────────────────────────────────────────────
@@ -594,6 +1167,7 @@ pub trait ServerApi {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     fn test_player() -> PluginPlayer {
         PluginPlayer {
@@ -603,6 +1177,7 @@ mod tests {
             position: (0.5, 65.62, 0.5),
             gamemode: 0,
             health: 20.0,
+            country: None,
         }
     }
 
@@ -611,6 +1186,15 @@ mod tests {
         messages: Vec<(String, String)>,
         broadcasts: Vec<String>,
         commands: Vec<(String, String)>,
+        kits: Vec<(String, Vec<PluginKitItem>, u64)>,
+        script_events: Vec<(String, String)>,
+        balances: HashMap<String, u64>,
+        arenas: Vec<PluginArena>,
+        arena_players: HashMap<String, String>,
+        holograms: HashMap<String, Vec<String>>,
+        cutscenes: HashMap<String, PluginCutscene>,
+        fog: Vec<(String, String, String)>,
+        locked_inputs: Vec<(String, String)>,
     }
 
     impl MockApi {
@@ -619,6 +1203,15 @@ mod tests {
                 messages: Vec::new(),
                 broadcasts: Vec::new(),
                 commands: Vec::new(),
+                kits: Vec::new(),
+                script_events: Vec::new(),
+                balances: HashMap::from([("TestPlayer".into(), 20), ("Alice".into(), 0)]),
+                arenas: Vec::new(),
+                arena_players: HashMap::new(),
+                holograms: HashMap::new(),
+                cutscenes: HashMap::new(),
+                fog: Vec::new(),
+                locked_inputs: Vec::new(),
             }
         }
     }
@@ -634,6 +1227,16 @@ mod tests {
                 None
             }
         }
+        fn get_player_stats(&self, name: &str) -> Option<PluginPlayerStats> {
+            if name == "TestPlayer" {
+                Some(PluginPlayerStats {
+                    deaths: 2,
+                    ..Default::default()
+                })
+            } else {
+                None
+            }
+        }
         fn send_message(&mut self, player_name: &str, message: &str) {
             self.messages
                 .push((player_name.to_string(), message.to_string()));
@@ -645,6 +1248,7 @@ mod tests {
         fn set_player_health(&mut self, _player_name: &str, _health: f32) {}
         fn set_player_food(&mut self, _player_name: &str, _food: i32) {}
         fn teleport_player(&mut self, _player_name: &str, _x: f32, _y: f32, _z: f32) {}
+        fn transfer_player(&mut self, _player_name: &str, _host: &str, _port: u16) {}
         fn get_time(&self) -> i64 {
             6000
         }
@@ -652,11 +1256,28 @@ mod tests {
         fn is_raining(&self) -> bool {
             false
         }
+        fn get_world_flag(&self, _dimension: &str, _flag: &str) -> Option<bool> {
+            None
+        }
+        fn set_world_flag(&mut self, _dimension: &str, _flag: &str, _value: bool) {}
         fn spawn_mob(&mut self, _mob_type: &str, _x: f32, _y: f32, _z: f32) {}
         fn remove_mob(&mut self, _runtime_id: u64) {}
+        fn trigger_entity_event(&mut self, _runtime_id: u64, _event: &str) {}
+        fn set_entity_appearance(&mut self, _runtime_id: u64, _appearance: PluginEntityAppearance) {
+        }
+        fn send_script_event(&mut self, id: &str, payload: &str) {
+            self.script_events
+                .push((id.to_string(), payload.to_string()));
+        }
         fn get_tick(&self) -> u64 {
             100
         }
+        fn get_tps(&self) -> f64 {
+            20.0
+        }
+        fn get_mspt(&self) -> f64 {
+            5.0
+        }
         fn log(&self, _level: LogLevel, _message: &str) {}
         fn schedule_delayed(&mut self, _plugin_name: &str, _delay_ticks: u64, _task_id: u32) {}
         fn schedule_repeating(
@@ -675,6 +1296,90 @@ mod tests {
         fn show_simple_form(&mut self, _player_name: &str, _form_id: u32, _json: &str) {}
         fn show_modal_form(&mut self, _player_name: &str, _form_id: u32, _json: &str) {}
         fn show_custom_form(&mut self, _player_name: &str, _form_id: u32, _json: &str) {}
+        fn show_next_form(&mut self, _player_name: &str, _form_id: u32, _json: &str) {}
+        fn register_kit(&mut self, name: &str, items: Vec<PluginKitItem>, cooldown_secs: u64) {
+            self.kits.push((name.to_string(), items, cooldown_secs));
+        }
+        fn currency_name(&self) -> String {
+            "coins".into()
+        }
+        fn get_balance(&self, player_name: &str) -> Option<u64> {
+            self.balances.get(player_name).copied()
+        }
+        fn deposit(&mut self, player_name: &str, amount: u64, _reason: &str) -> bool {
+            match self.balances.get_mut(player_name) {
+                Some(balance) => {
+                    *balance += amount;
+                    true
+                }
+                None => false,
+            }
+        }
+        fn withdraw(&mut self, player_name: &str, amount: u64, _reason: &str) -> bool {
+            match self.balances.get_mut(player_name) {
+                Some(balance) if *balance >= amount => {
+                    *balance -= amount;
+                    true
+                }
+                _ => false,
+            }
+        }
+        fn transfer_money(&mut self, from: &str, to: &str, amount: u64, reason: &str) -> bool {
+            self.balances.contains_key(to)
+                && self.withdraw(from, amount, reason)
+                && self.deposit(to, amount, reason)
+        }
+        fn create_arena(&mut self, arena: PluginArena) {
+            self.arenas.push(arena);
+        }
+        fn join_arena(&mut self, player_name: &str, arena_name: &str) {
+            self.arena_players
+                .insert(player_name.to_string(), arena_name.to_string());
+        }
+        fn leave_arena(&mut self, player_name: &str) {
+            self.arena_players.remove(player_name);
+        }
+        fn set_arena_score(&mut self, _arena_name: &str, _entry: &str, _score: i32) {}
+        fn end_arena(&mut self, _arena_name: &str, _winner: Option<&str>) {}
+        fn player_arena(&self, player_name: &str) -> Option<String> {
+            self.arena_players.get(player_name).cloned()
+        }
+        fn create_hologram(&mut self, name: &str, _x: f32, _y: f32, _z: f32, lines: Vec<String>) {
+            self.holograms.insert(name.into(), lines);
+        }
+        fn set_hologram_lines(&mut self, name: &str, lines: Vec<String>) {
+            if let Some(l) = self.holograms.get_mut(name) {
+                *l = lines;
+            }
+        }
+        fn remove_hologram(&mut self, name: &str) {
+            self.holograms.remove(name);
+        }
+        fn play_cutscene(&mut self, player_name: &str, cutscene: PluginCutscene) {
+            self.cutscenes.insert(player_name.into(), cutscene);
+        }
+        fn stop_cutscene(&mut self, player_name: &str) {
+            self.cutscenes.remove(player_name);
+        }
+        fn push_fog(&mut self, player_name: &str, fog_id: &str, user_id: &str) {
+            self.fog
+                .push((player_name.into(), fog_id.into(), user_id.into()));
+        }
+        fn remove_fog(&mut self, player_name: &str, user_id: &str) {
+            self.fog
+                .retain(|(p, _, u)| p != player_name || u != user_id);
+        }
+        fn set_client_game_rule(&mut self, _player_name: &str, _rule: &str, _value: bool) {}
+        fn set_input_permission(&mut self, player_name: &str, permission: &str, enabled: bool) {
+            let entry = (player_name.to_string(), permission.to_string());
+            self.locked_inputs.retain(|e| *e != entry);
+            if !enabled {
+                self.locked_inputs.push(entry);
+            }
+        }
+        fn accepted_packs(&self, _player_name: &str) -> Option<Vec<String>> {
+            None
+        }
     }
 
     // A simple test plugin.
@@ -682,6 +1387,28 @@ mod tests {
         greet_on_join: bool,
     }
 
+    /// A fly-over of the duel arena, played when a match starts.
+    fn intro_cutscene() -> PluginCutscene {
+        PluginCutscene {
+            name: "duel_intro".into(),
+            steps: vec![
+                PluginCutsceneStep::Fade {
+                    color: (0.0, 0.0, 0.0),
+                    fade_in_secs: 0.5,
+                    hold_secs: 0.5,
+                    fade_out_secs: 0.5,
+                },
+                PluginCutsceneStep::Camera {
+                    position: (8.0, 80.0, -4.0),
+                    rotation: (30.0, 0.0),
+                    ease: "in_out_sine".into(),
+                    duration_secs: 3.0,
+                },
+                PluginCutsceneStep::Wait { secs: 1.0 },
+            ],
+        }
+    }
+
     impl HelloPlugin {
         fn new() -> Self {
             Self {
@@ -702,6 +1429,48 @@ mod tests {
 
         fn on_enable(&mut self, api: &mut dyn ServerApi) {
             api.register_command("greet", "Greet a player", "HelloPlugin");
+            api.register_kit(
+                "welcome",
+                vec![PluginKitItem {
+                    item: "minecraft:bread".into(),
+                    count: 4,
+                    metadata: 0,
+                }],
+                600,
+            );
+            api.create_arena(PluginArena {
+                name: "duel".into(),
+                region: (
+                    PluginBlockPos { x: 0, y: 60, z: 0 },
+                    PluginBlockPos {
+                        x: 15,
+                        y: 70,
+                        z: 15,
+                    },
+                ),
+                lobby: (8.5, 72.0, 8.5),
+                teams: vec![
+                    PluginArenaTeam {
+                        name: "red".into(),
+                        spawn: (1.5, 61.0, 1.5),
+                    },
+                    PluginArenaTeam {
+                        name: "blue".into(),
+                        spawn: (14.5, 61.0, 14.5),
+                    },
+                ],
+                min_players: 2,
+                max_players: 2,
+                countdown_secs: 10,
+                duration_secs: 300,
+            });
+            api.create_hologram(
+                "welcome",
+                0.5,
+                67.0,
+                0.5,
+                vec!["Welcome!".into(), "{online} online".into()],
+            );
             api.log(LogLevel::Info, "HelloPlugin enabled!");
         }
 
@@ -714,6 +1483,25 @@ mod tests {
                 PluginEvent::PlayerChat { message, .. } if message.contains("bad") => {
                     EventResult::Cancelled
                 }
+                PluginEvent::ArenaEnd {
+                    winner: Some(winner),
+                    ..
+                } => {
+                    api.deposit(winner, 10, "arena win");
+                    EventResult::Continue
+                }
+                PluginEvent::ArenaStart { arena, teams } if arena == "duel" => {
+                    for player in teams.iter().flat_map(|(_, players)| players) {
+                        api.play_cutscene(player, intro_cutscene());
+                        api.push_fog(player, "minecraft:fog_hell", "duel");
+                        api.set_input_permission(player, "movement", false);
+                    }
+                    EventResult::Continue
+                }
+                PluginEvent::ScriptEvent { id, payload, .. } if id == "hello:ping" => {
+                    api.send_script_event("hello:pong", payload);
+                    EventResult::Continue
+                }
                 _ => EventResult::Continue,
             }
         }
@@ -729,6 +1517,13 @@ mod tests {
                 let target = args.first().map(|s| s.as_str()).unwrap_or(sender);
                 api.send_message(target, "Hello from plugin!");
                 Some(format!("Greeted {target}"))
+            } else if command == "tip" {
+                let target = args.first()?;
+                if api.transfer_money(sender, target, 5, "tip") {
+                    Some(format!("Tipped {target} 5 {}", api.currency_name()))
+                } else {
+                    Some("You can't afford a tip".into())
+                }
             } else {
                 None
             }
@@ -762,6 +1557,89 @@ mod tests {
         assert_eq!(api.commands[0].0, "greet");
     }
 
+    #[test]
+    fn plugin_on_enable_registers_kit() {
+        let mut plugin = HelloPlugin::new();
+        let mut api = MockApi::new();
+        plugin.on_enable(&mut api);
+        assert_eq!(api.kits.len(), 1);
+        let (name, items, cooldown) = &api.kits[0];
+        assert_eq!(name, "welcome");
+        assert_eq!(items[0].item, "minecraft:bread");
+        assert_eq!(items[0].count, 4);
+        assert_eq!(*cooldown, 600);
+    }
+
+    #[test]
+    fn plugin_on_enable_creates_arena() {
+        let mut plugin = HelloPlugin::new();
+        let mut api = MockApi::new();
+        plugin.on_enable(&mut api);
+        assert_eq!(api.arenas.len(), 1);
+        assert_eq!(api.arenas[0].name, "duel");
+        assert_eq!(api.arenas[0].teams.len(), 2);
+
+        api.join_arena("TestPlayer", "duel");
+        assert_eq!(api.player_arena("TestPlayer"), Some("duel".into()));
+        api.leave_arena("TestPlayer");
+        assert_eq!(api.player_arena("TestPlayer"), None);
+    }
+
+    #[test]
+    fn plugin_plays_a_cutscene_when_the_duel_starts() {
+        let mut plugin = HelloPlugin::new();
+        let mut api = MockApi::new();
+        let event = PluginEvent::ArenaStart {
+            arena: "duel".into(),
+            teams: vec![
+                ("red".into(), vec!["Alice".into()]),
+                ("blue".into(), vec!["Bob".into()]),
+            ],
+        };
+        plugin.on_event(&event, &mut api);
+        assert_eq!(api.cutscenes.len(), 2);
+        assert_eq!(api.cutscenes["Bob"].name, "duel_intro");
+        api.stop_cutscene("Bob");
+        assert!(!api.cutscenes.contains_key("Bob"));
+
+        let json = serde_json::to_value(&api.cutscenes["Alice"].steps[2]).unwrap();
+        assert_eq!(json, serde_json::json!({"type": "wait", "secs": 1.0}));
+        assert_eq!(api.fog.len(), 2);
+        assert_eq!(
+            api.locked_inputs,
+            [
+                ("Alice".to_string(), "movement".to_string()),
+                ("Bob".to_string(), "movement".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn plugin_on_enable_creates_hologram() {
+        let mut plugin = HelloPlugin::new();
+        let mut api = MockApi::new();
+        plugin.on_enable(&mut api);
+        assert_eq!(api.holograms["welcome"], ["Welcome!", "{online} online"]);
+
+        api.set_hologram_lines("welcome", vec!["Bye".into()]);
+        assert_eq!(api.holograms["welcome"], ["Bye"]);
+        api.remove_hologram("welcome");
+        assert!(api.holograms.is_empty());
+    }
+
+    #[test]
+    fn plugin_rewards_arena_winner() {
+        let mut plugin = HelloPlugin::new();
+        let mut api = MockApi::new();
+        let event = PluginEvent::ArenaEnd {
+            arena: "duel".into(),
+            winner: Some("Alice".into()),
+        };
+        assert_eq!(plugin.on_event(&event, &mut api), EventResult::Continue);
+        assert!(!event.is_cancellable());
+        assert_eq!(api.get_balance("Alice"), Some(10));
+    }
+
     #[test]
     fn plugin_greets_on_join() {
         let mut plugin = HelloPlugin::new();
@@ -800,6 +1678,22 @@ mod tests {
         assert_eq!(result, EventResult::Continue);
     }
 
+    #[test]
+    fn plugin_command_moves_money() {
+        let mut plugin = HelloPlugin::new();
+        let mut api = MockApi::new();
+        let response = plugin.on_command("tip", &["Alice".into()], "TestPlayer", &mut api);
+        assert_eq!(response, Some("Tipped Alice 5 coins".into()));
+        assert_eq!(api.get_balance("TestPlayer"), Some(15));
+        assert_eq!(api.get_balance("Alice"), Some(5));
+
+        let response = plugin.on_command("tip", &["TestPlayer".into()], "Alice", &mut api);
+        assert_eq!(response, Some("Tipped TestPlayer 5 coins".into()));
+        let response = plugin.on_command("tip", &["TestPlayer".into()], "Alice", &mut api);
+        assert_eq!(response, Some("You can't afford a tip".into()));
+        assert_eq!(api.get_balance("Alice"), Some(0));
+    }
+
     #[test]
     fn plugin_command_greet() {
         let mut plugin = HelloPlugin::new();
@@ -836,6 +1730,31 @@ mod tests {
             block_id: 0,
         }
         .is_cancellable());
+        assert!(PluginEvent::PlayerEmote {
+            player: test_player(),
+            emote_id: "4c8ae710-df2e-47cd-814d-cc7bf21a3d67".into(),
+        }
+        .is_cancellable());
+        assert!(PluginEvent::PlayerViolation {
+            player: test_player(),
+            check: "speed".into(),
+            score: 1,
+            detail: String::new(),
+        }
+        .is_cancellable());
+        assert!(PluginEvent::PlayerPrivateMessage {
+            player: test_player(),
+            recipient: test_player(),
+            message: "hi".into(),
+        }
+        .is_cancellable());
+        assert!(PluginEvent::SignChange {
+            player: test_player(),
+            position: PluginBlockPos { x: 0, y: 0, z: 0 },
+            front_text: "Hello".into(),
+            back_text: String::new(),
+        }
+        .is_cancellable());
         assert!(PluginEvent::PlayerDamage {
             player: test_player(),
             damage: 0.0,
@@ -846,12 +1765,30 @@ mod tests {
             player: test_player()
         }
         .is_cancellable());
+        assert!(!PluginEvent::PlayerAfkChange {
+            player: test_player(),
+            afk: true,
+        }
+        .is_cancellable());
+        assert!(!PluginEvent::NpcInteract {
+            player: test_player(),
+            npc: "guide".into(),
+            id: "quest:start".into(),
+        }
+        .is_cancellable());
         assert!(!PluginEvent::PlayerQuit {
             player: test_player()
         }
         .is_cancellable());
         assert!(!PluginEvent::ServerStarted.is_cancellable());
         assert!(!PluginEvent::ServerStopping.is_cancellable());
+        assert!(!PluginEvent::WorldSave.is_cancellable());
+        assert!(!PluginEvent::ChunkLoad {
+            dimension: 0,
+            cx: 0,
+            cz: 0
+        }
+        .is_cancellable());
         assert!(!PluginEvent::MobDeath {
             mob_type: String::new(),
             runtime_id: 0,
@@ -864,6 +1801,41 @@ mod tests {
             response: FormResponse::Cancelled,
         }
         .is_cancellable());
+        assert!(!PluginEvent::ScriptEvent {
+            id: "a:b".into(),
+            payload: String::new(),
+            source: ScriptEventSource::Server,
+        }
+        .is_cancellable());
+    }
+
+    #[test]
+    fn script_event_roundtrip() {
+        let mut plugin = HelloPlugin::new();
+        let mut api = MockApi::new();
+        let event = PluginEvent::ScriptEvent {
+            id: "hello:ping".into(),
+            payload: "42".into(),
+            source: ScriptEventSource::Entity {
+                runtime_id: 7,
+                entity_type: "custom:golem".into(),
+            },
+        };
+        plugin.on_event(&event, &mut api);
+        assert_eq!(
+            api.script_events,
+            vec![("hello:pong".to_string(), "42".to_string())]
+        );
+    }
+
+    #[test]
+    fn script_event_ids() {
+        assert!(is_valid_script_event_id("my_pack:open_door"));
+        assert!(!is_valid_script_event_id("minecraft:open_door"));
+        assert!(!is_valid_script_event_id("no_namespace"));
+        assert!(!is_valid_script_event_id(":empty"));
+        assert!(!is_valid_script_event_id("ns:"));
+        assert!(!is_valid_script_event_id("ns:with space"));
     }
 
     #[test]
@@ -937,7 +1909,7 @@ mod tests {
 
     #[test]
     fn parse_simple_form_response() {
-        let resp = parse_form_response("simple", Some("2"));
+        let resp = parse_form_response("simple", "", Some("2"));
         match resp {
             FormResponse::Simple { button_index } => assert_eq!(button_index, 2),
             other => panic!("Expected Simple, got {other:?}"),
@@ -946,12 +1918,12 @@ mod tests {
 
     #[test]
     fn parse_modal_form_response() {
-        let resp = parse_form_response("modal", Some("true"));
+        let resp = parse_form_response("modal", "", Some("true"));
         match resp {
             FormResponse::Modal { accepted } => assert!(accepted),
             other => panic!("Expected Modal, got {other:?}"),
         }
-        let resp2 = parse_form_response("modal", Some("false"));
+        let resp2 = parse_form_response("modal", "", Some("false"));
         match resp2 {
             FormResponse::Modal { accepted } => assert!(!accepted),
             other => panic!("Expected Modal, got {other:?}"),
@@ -960,20 +1932,101 @@ mod tests {
 
     #[test]
     fn parse_custom_form_response() {
-        let resp = parse_form_response("custom", Some(r#"["Steve", true, 1, 50.0]"#));
+        let form = CustomFormBuilder::new("Settings")
+            .label("Hello")
+            .input("Name", "", "")
+            .toggle("PvP", false)
+            .dropdown("Difficulty", vec!["Easy".into(), "Hard".into()], 0)
+            .slider("Volume", 0.0, 100.0, 1.0, 50.0)
+            .to_json();
+        let resp = parse_form_response("custom", &form, Some(r#"[null, "Steve", true, 1, 50.0]"#));
         match resp {
-            FormResponse::Custom { values } => {
-                assert_eq!(values.len(), 4);
-                assert_eq!(values[0], "Steve");
-                assert_eq!(values[1], true);
-            }
+            FormResponse::Custom { values } => assert_eq!(
+                values,
+                vec![
+                    FormValue::Label,
+                    FormValue::Input("Steve".into()),
+                    FormValue::Toggle(true),
+                    FormValue::Dropdown(1),
+                    FormValue::Slider(50.0),
+                ]
+            ),
             other => panic!("Expected Custom, got {other:?}"),
         }
+
+        // Without the form, values are typed after their JSON
+        let resp = parse_form_response("custom", "", Some(r#"["Steve", 2]"#));
+        let FormResponse::Custom { values } = resp else {
+            panic!("Expected Custom");
+        };
+        assert_eq!(values[0].as_text(), Some("Steve"));
+        assert_eq!(values[1].as_number(), Some(2.0));
+    }
+
+    #[test]
+    fn form_type_from_json() {
+        let simple = SimpleFormBuilder::new("T", "C").to_json();
+        assert_eq!(form_type_of(&simple), Some("simple"));
+        let custom = CustomFormBuilder::new("T").to_json();
+        assert_eq!(form_type_of(&custom), Some("custom"));
+        assert_eq!(form_type_of("not json"), None);
+    }
+
+    #[test]
+    fn input_rules() {
+        assert!(InputRule::Required.check(" ").is_err());
+        assert!(InputRule::MaxLength { max: 3 }.check("abcd").is_err());
+        assert!(InputRule::MinLength { min: 2 }.check("ab").is_ok());
+        let age = InputRule::Integer { min: 1, max: 99 };
+        assert!(age.check("42").is_ok());
+        assert!(age.check("100").is_err());
+        assert!(age.check("4.2").is_err());
+        assert!(InputRule::Number { min: 0.0, max: 1.0 }
+            .check("0.5")
+            .is_ok());
+        assert!(InputRule::Alphanumeric.check("Steve_01").is_ok());
+        assert!(InputRule::Alphanumeric.check("Steve 01").is_err());
+    }
+
+    #[test]
+    fn validated_inputs() {
+        let form = CustomFormBuilder::new("Sign up")
+            .validated_input("Name", "", "", vec![InputRule::Required])
+            .validated_input("Age", "", "", vec![InputRule::Integer { min: 1, max: 99 }])
+            .toggle("News", false)
+            .to_json();
+        let answer = |data| parse_form_response("custom", &form, Some(data));
+
+        assert!(validate_form_response(&form, &answer(r#"["Steve", "", true]"#)).is_empty());
+        let bad = answer(r#"["", "abc", true]"#);
+        let errors = validate_form_response(&form, &bad);
+        assert_eq!(
+            errors,
+            vec![
+                (0, "This field is required".to_string()),
+                (1, "Enter a whole number from 1 to 99".to_string()),
+            ]
+        );
+
+        // Shown again as answered, with the errors
+        let again: serde_json::Value =
+            serde_json::from_str(&form_with_errors(&form, &bad, &errors)).unwrap();
+        assert_eq!(again["content"][1]["default"], "abc");
+        assert_eq!(again["content"][2]["default"], true);
+        assert!(again["content"][1]["text"]
+            .as_str()
+            .unwrap()
+            .ends_with("§cEnter a whole number from 1 to 99"));
+
+        // The client never sees the rules
+        let client: serde_json::Value = serde_json::from_str(&client_form_json(&form)).unwrap();
+        assert!(client["content"][0].get(VALIDATION_KEY).is_none());
+        assert_eq!(client["content"][0]["text"], "Name");
     }
 
     #[test]
     fn parse_cancelled_form_response() {
-        let resp = parse_form_response("simple", None);
+        let resp = parse_form_response("simple", "", None);
         assert!(matches!(resp, FormResponse::Cancelled));
     }
 }
@@ -41,6 +41,11 @@ enum LuaAction {
     SetTime {
         time: i64,
     },
+    SetWorldFlag {
+        dimension: String,
+        flag: String,
+        value: bool,
+    },
     SpawnMob {
         mob_type: String,
         x: f32,
@@ -50,6 +55,21 @@ enum LuaAction {
     RemoveMob {
         runtime_id: u64,
     },
+    TriggerEntityEvent {
+        runtime_id: u64,
+        event: String,
+    },
+    SendScriptEvent {
+        id: String,
+        payload: String,
+    },
+    ShowForm {
+        player_name: String,
+        form_id: u32,
+        json: String,
+        /// Opened from the response to another form.
+        next: bool,
+    },
     Log {
         level: LogLevel,
         message: String,
@@ -78,10 +98,20 @@ struct LuaHostData {
     actions: Vec<LuaAction>,
     cached_time: i64,
     cached_tick: u64,
+    cached_tps: f64,
+    cached_mspt: f64,
     cached_is_raining: bool,
+    /// `(dimension, flag, value)` for every world flag.
+    cached_world_flags: Vec<(&'static str, &'static str, bool)>,
     cached_players: Vec<PluginPlayer>,
     plugin_name: String,
     next_task_id: u32,
+    /// Instructions run by the callback in progress, counted by the hook of
+    /// [`setup_instruction_limit`].
+    instructions: u32,
+    /// Set once the callback in progress hits the instruction limit, so the
+    /// error cannot be swallowed by `pcall`.
+    aborted: bool,
 }
 
 impl LuaHostData {
@@ -90,10 +120,15 @@ impl LuaHostData {
             actions: Vec::new(),
             cached_time: 0,
             cached_tick: 0,
+            cached_tps: 0.0,
+            cached_mspt: 0.0,
             cached_is_raining: false,
+            cached_world_flags: Vec::new(),
             cached_players: Vec::new(),
             plugin_name,
             next_task_id: 1,
+            instructions: 0,
+            aborted: false,
         }
     }
 
@@ -111,18 +146,68 @@ pub struct LuaPlugin {
     manifest: LuaPluginManifest,
     lua: Lua,
     script_path: PathBuf,
+    /// Callbacks that hit the instruction limit.
+    overruns: u32,
+    /// Set after `max_overruns` overruns: callbacks are skipped.
+    disabled: bool,
 }
 
 // Safety: LuaPlugin is only accessed from the single game-tick thread.
 unsafe impl Send for LuaPlugin {}
 
 impl LuaPlugin {
+    /// Reset the instruction count before a callback. False if the plugin
+    /// was disabled for hitting the instruction limit.
+    fn begin_callback(&self) -> bool {
+        if self.disabled {
+            return false;
+        }
+        if let Some(mut data) = self.lua.app_data_mut::<LuaHostData>() {
+            data.instructions = 0;
+            data.aborted = false;
+        }
+        true
+    }
+
+    /// Count an overrun if a callback hit the instruction limit: warn about
+    /// it, or disable the plugin once it reaches `max_overruns`.
+    fn end_callback(&mut self, callback: &str) {
+        let limit = self.manifest.instruction_limit;
+        let used = self
+            .lua
+            .app_data_ref::<LuaHostData>()
+            .map_or(0, |d| d.instructions);
+        if limit == 0 || used < limit {
+            return;
+        }
+        self.overruns += 1;
+        if self.overruns >= self.manifest.max_overruns {
+            self.disabled = true;
+            error!(
+                "Lua plugin '{}' disabled: {} callbacks hit the instruction limit ({limit}), the last in {callback}",
+                self.manifest.name, self.overruns
+            );
+        } else {
+            warn!(
+                "Lua plugin '{}' hit the instruction limit ({limit}) in {callback} ({}/{} before it is disabled)",
+                self.manifest.name, self.overruns, self.manifest.max_overruns
+            );
+        }
+    }
+
     /// Sync cached fields from the ServerApi into LuaHostData.
     fn sync_snapshot(&self, api: &dyn ServerApi) {
         if let Some(mut data) = self.lua.app_data_mut::<LuaHostData>() {
             data.cached_time = api.get_time();
             data.cached_tick = api.get_tick();
+            data.cached_tps = api.get_tps();
+            data.cached_mspt = api.get_mspt();
             data.cached_is_raining = api.is_raining();
+            data.cached_world_flags = WORLD_FLAG_DIMENSIONS
+                .iter()
+                .flat_map(|d| WORLD_FLAGS.iter().map(move |f| (*d, *f)))
+                .filter_map(|(d, f)| api.get_world_flag(d, f).map(|value| (d, f, value)))
+                .collect();
             data.cached_players = api.online_players();
         }
     }
@@ -161,8 +246,29 @@ impl LuaPlugin {
                     z,
                 } => api.teleport_player(&player_name, x, y, z),
                 LuaAction::SetTime { time } => api.set_time(time),
+                LuaAction::SetWorldFlag {
+                    dimension,
+                    flag,
+                    value,
+                } => api.set_world_flag(&dimension, &flag, value),
                 LuaAction::SpawnMob { mob_type, x, y, z } => api.spawn_mob(&mob_type, x, y, z),
                 LuaAction::RemoveMob { runtime_id } => api.remove_mob(runtime_id),
+                LuaAction::TriggerEntityEvent { runtime_id, event } => {
+                    api.trigger_entity_event(runtime_id, &event)
+                }
+                LuaAction::SendScriptEvent { id, payload } => api.send_script_event(&id, &payload),
+                LuaAction::ShowForm {
+                    player_name,
+                    form_id,
+                    json,
+                    next,
+                } => match (next, form_type_of(&json)) {
+                    (true, _) => api.show_next_form(&player_name, form_id, &json),
+                    (false, Some("simple")) => api.show_simple_form(&player_name, form_id, &json),
+                    (false, Some("modal")) => api.show_modal_form(&player_name, form_id, &json),
+                    (false, Some("custom")) => api.show_custom_form(&player_name, form_id, &json),
+                    (false, _) => warn!("[{plugin_name}] mc.show_form: unknown form type"),
+                },
                 LuaAction::Log { level, message } => {
                     api.log(level, &message);
                 }
@@ -245,6 +351,38 @@ impl LuaPlugin {
                 Self::set_player_fields(lua, &t, player)?;
                 "player_respawn"
             }
+            PluginEvent::PlayerEmote { player, emote_id } => {
+                Self::set_player_fields(lua, &t, player)?;
+                t.set("emote_id", emote_id.as_str())?;
+                "player_emote"
+            }
+            PluginEvent::PlayerViolation {
+                player,
+                check,
+                score,
+                detail,
+            } => {
+                Self::set_player_fields(lua, &t, player)?;
+                t.set("check", check.as_str())?;
+                t.set("score", *score)?;
+                t.set("detail", detail.as_str())?;
+                "player_violation"
+            }
+            PluginEvent::PlayerAfkChange { player, afk } => {
+                Self::set_player_fields(lua, &t, player)?;
+                t.set("afk", *afk)?;
+                "player_afk_change"
+            }
+            PluginEvent::PlayerPrivateMessage {
+                player,
+                recipient,
+                message,
+            } => {
+                Self::set_player_fields(lua, &t, player)?;
+                t.set("recipient", Self::player_table(lua, recipient)?)?;
+                t.set("message", message.as_str())?;
+                "player_private_message"
+            }
             PluginEvent::BlockBreak {
                 player,
                 position,
@@ -265,6 +403,18 @@ impl LuaPlugin {
                 t.set("block_id", *block_id)?;
                 "block_place"
             }
+            PluginEvent::SignChange {
+                player,
+                position,
+                front_text,
+                back_text,
+            } => {
+                Self::set_player_fields(lua, &t, player)?;
+                Self::set_block_pos(lua, &t, position)?;
+                t.set("front_text", front_text.as_str())?;
+                t.set("back_text", back_text.as_str())?;
+                "sign_change"
+            }
             PluginEvent::MobSpawn {
                 mob_type,
                 runtime_id,
@@ -313,6 +463,19 @@ impl LuaPlugin {
                 t.set("new_time", *new_time)?;
                 "time_change"
             }
+            PluginEvent::ChunkLoad { dimension, cx, cz } => {
+                t.set("dimension", *dimension)?;
+                t.set("cx", *cx)?;
+                t.set("cz", *cz)?;
+                "chunk_load"
+            }
+            PluginEvent::ChunkUnload { dimension, cx, cz } => {
+                t.set("dimension", *dimension)?;
+                t.set("cx", *cx)?;
+                t.set("cz", *cz)?;
+                "chunk_unload"
+            }
+            PluginEvent::WorldSave => "world_save",
             PluginEvent::FormResponse {
                 player,
                 form_id,
@@ -320,11 +483,55 @@ impl LuaPlugin {
             } => {
                 Self::set_player_fields(lua, &t, player)?;
                 t.set("form_id", *form_id)?;
-                t.set("response", format!("{response:?}"))?;
+                t.set("response", Self::form_response_table(lua, response)?)?;
                 "form_response"
             }
             PluginEvent::ServerStarted => "server_started",
             PluginEvent::ServerStopping => "server_stopping",
+            PluginEvent::ArenaStart { arena, teams } => {
+                t.set("arena", arena.as_str())?;
+                let teams_t = lua.create_table()?;
+                for (team, players) in teams {
+                    teams_t.set(team.as_str(), players.clone())?;
+                }
+                t.set("teams", teams_t)?;
+                "arena_start"
+            }
+            PluginEvent::ArenaEnd { arena, winner } => {
+                t.set("arena", arena.as_str())?;
+                t.set("winner", winner.as_deref())?;
+                "arena_end"
+            }
+            PluginEvent::NpcInteract { player, npc, id } => {
+                Self::set_player_fields(lua, &t, player)?;
+                t.set("npc", npc.as_str())?;
+                t.set("id", id.as_str())?;
+                "npc_interact"
+            }
+            PluginEvent::ScriptEvent {
+                id,
+                payload,
+                source,
+            } => {
+                t.set("id", id.as_str())?;
+                t.set("payload", payload.as_str())?;
+                match source {
+                    ScriptEventSource::Server => t.set("source_type", "server")?,
+                    ScriptEventSource::Player(player) => {
+                        t.set("source_type", "player")?;
+                        Self::set_player_fields(lua, &t, player)?;
+                    }
+                    ScriptEventSource::Entity {
+                        runtime_id,
+                        entity_type,
+                    } => {
+                        t.set("source_type", "entity")?;
+                        t.set("runtime_id", *runtime_id)?;
+                        t.set("entity_type", entity_type.as_str())?;
+                    }
+                }
+                "script_event"
+            }
         };
 
         t.set("cancelled", false)?;
@@ -332,6 +539,10 @@ impl LuaPlugin {
     }
 
     fn set_player_fields(lua: &Lua, t: &LuaTable, player: &PluginPlayer) -> LuaResult<()> {
+        t.set("player", Self::player_table(lua, player)?)
+    }
+
+    fn player_table(lua: &Lua, player: &PluginPlayer) -> LuaResult<LuaTable> {
         let pt = lua.create_table()?;
         pt.set("name", player.name.as_str())?;
         pt.set("uuid", player.uuid.as_str())?;
@@ -341,8 +552,44 @@ impl LuaPlugin {
         pt.set("z", player.position.2)?;
         pt.set("gamemode", player.gamemode)?;
         pt.set("health", player.health)?;
-        t.set("player", pt)?;
-        Ok(())
+        pt.set("country", player.country.as_deref())?;
+        Ok(pt)
+    }
+
+    /// A form response as `{ kind = ..., ... }`: `button_index` for simple
+    /// forms, `accepted` for modal ones, and for custom forms `values`, one
+    /// per element (nil for labels, indices of dropdowns from 0).
+    fn form_response_table(lua: &Lua, response: &FormResponse) -> LuaResult<LuaTable> {
+        let rt = lua.create_table()?;
+        match response {
+            FormResponse::Cancelled => rt.set("kind", "cancelled")?,
+            FormResponse::Simple { button_index } => {
+                rt.set("kind", "simple")?;
+                rt.set("button_index", *button_index)?;
+            }
+            FormResponse::Modal { accepted } => {
+                rt.set("kind", "modal")?;
+                rt.set("accepted", *accepted)?;
+            }
+            FormResponse::Custom { values } => {
+                rt.set("kind", "custom")?;
+                let vt = lua.create_table()?;
+                for (i, value) in values.iter().enumerate() {
+                    let value = match value {
+                        FormValue::Label => LuaValue::Nil,
+                        FormValue::Input(text) => LuaValue::String(lua.create_string(text)?),
+                        FormValue::Toggle(on) => LuaValue::Boolean(*on),
+                        FormValue::Dropdown(index) | FormValue::StepSlider(index) => {
+                            LuaValue::Integer(*index as i64)
+                        }
+                        FormValue::Slider(n) => LuaValue::Number(*n),
+                    };
+                    vt.set(i + 1, value)?;
+                }
+                rt.set("values", vt)?;
+            }
+        }
+        Ok(rt)
     }
 
     fn set_block_pos(lua: &Lua, t: &LuaTable, pos: &PluginBlockPos) -> LuaResult<()> {
@@ -368,6 +615,9 @@ impl Plugin for LuaPlugin {
     }
 
     fn on_enable(&mut self, api: &mut dyn ServerApi) {
+        if !self.begin_callback() {
+            return;
+        }
         self.sync_snapshot(api);
 
         let script = match std::fs::read_to_string(&self.script_path) {
@@ -381,7 +631,9 @@ impl Plugin for LuaPlugin {
             }
         };
 
-        if let Err(e) = self.lua.load(&script).set_name(&self.manifest.name).exec() {
+        let result = self.lua.load(&script).set_name(&self.manifest.name).exec();
+        self.end_callback("on_enable");
+        if let Err(e) = result {
             error!("Failed to execute Lua plugin '{}': {e}", self.manifest.name);
             return;
         }
@@ -398,6 +650,9 @@ impl Plugin for LuaPlugin {
     }
 
     fn on_event(&mut self, event: &PluginEvent, api: &mut dyn ServerApi) -> EventResult {
+        if !self.begin_callback() {
+            return EventResult::Continue;
+        }
         self.sync_snapshot(api);
 
         let result = (|| -> LuaResult<EventResult> {
@@ -435,12 +690,16 @@ impl Plugin for LuaPlugin {
                 EventResult::Continue
             }
         };
+        self.end_callback("on_event");
 
         self.flush_actions(api);
         event_result
     }
 
     fn on_task(&mut self, task_id: u32, api: &mut dyn ServerApi) {
+        if !self.begin_callback() {
+            return;
+        }
         self.sync_snapshot(api);
 
         let result = (|| -> LuaResult<()> {
@@ -456,6 +715,7 @@ impl Plugin for LuaPlugin {
                 self.manifest.name, task_id
             );
         }
+        self.end_callback("on_task");
 
         self.flush_actions(api);
     }
@@ -467,6 +727,9 @@ impl Plugin for LuaPlugin {
         sender: &str,
         api: &mut dyn ServerApi,
     ) -> Option<String> {
+        if !self.begin_callback() {
+            return None;
+        }
         self.sync_snapshot(api);
 
         let result = (|| -> LuaResult<Option<String>> {
@@ -495,6 +758,7 @@ impl Plugin for LuaPlugin {
                 None
             }
         };
+        self.end_callback("on_command");
 
         self.flush_actions(api);
         response
@@ -516,6 +780,56 @@ fn setup_sandbox(lua: &Lua, memory_mb: usize) -> LuaResult<()> {
     Ok(())
 }
 
+/// Instructions between two checks of the instruction limit.
+const INSTRUCTION_CHECK_INTERVAL: u32 = 1000;
+
+/// Rewraps `pcall` and `xpcall` so they re-raise once the callback has
+/// been aborted; `...` is the check that raises.
+const RETHROW_ABORTS: &str = r#"
+local check = ...
+local pcall, xpcall = pcall, xpcall
+local function rethrow(...)
+    check()
+    return ...
+end
+_G.pcall = function(...) return rethrow(pcall(...)) end
+_G.xpcall = function(...) return rethrow(xpcall(...)) end
+"#;
+
+/// Abort callbacks that run `limit` instructions (0 for no limit), so a
+/// runaway handler cannot stall the tick thread. Like WASM fuel, the count
+/// is reset by [`LuaPlugin::begin_callback`]. Once aborted, `pcall` and
+/// `xpcall` can no longer catch the error. The hook only runs on the main
+/// thread, so `coroutine` is removed as well.
+fn setup_instruction_limit(lua: &Lua, limit: u32) -> LuaResult<()> {
+    if limit == 0 {
+        return Ok(());
+    }
+    lua.globals().set("coroutine", LuaValue::Nil)?;
+    let exceeded = move || LuaError::runtime(format!("instruction limit ({limit}) exceeded"));
+
+    let check = lua.create_function(move |lua, ()| match lua.app_data_ref::<LuaHostData>() {
+        Some(data) if data.aborted => Err(exceeded()),
+        _ => Ok(()),
+    })?;
+    lua.load(RETHROW_ABORTS).call::<()>(check)?;
+
+    let step = limit.min(INSTRUCTION_CHECK_INTERVAL);
+    let triggers = LuaHookTriggers::new().every_nth_instruction(step);
+    lua.set_hook(triggers, move |lua, _| {
+        let Some(mut data) = lua.app_data_mut::<LuaHostData>() else {
+            return Ok(LuaVmState::Continue);
+        };
+        data.instructions = data.instructions.saturating_add(step);
+        if data.instructions >= limit {
+            data.aborted = true;
+            return Err(exceeded());
+        }
+        Ok(LuaVmState::Continue)
+    });
+    Ok(())
+}
+
 /// Set up the global `mc` table with all API functions.
 fn setup_mc_api(lua: &Lua) -> LuaResult<()> {
     // Create registry tables for handlers
@@ -664,6 +978,35 @@ fn setup_mc_api(lua: &Lua) -> LuaResult<()> {
         })?,
     )?;
 
+    // mc.get_world_flag(dimension, flag) -> bool or nil
+    mc.set(
+        "get_world_flag",
+        lua.create_function(|lua, (dimension, flag): (String, String)| {
+            let value = lua.app_data_ref::<LuaHostData>().and_then(|d| {
+                d.cached_world_flags
+                    .iter()
+                    .find(|(dim, f, _)| *dim == dimension && *f == flag)
+                    .map(|(_, _, value)| *value)
+            });
+            Ok(value)
+        })?,
+    )?;
+
+    // mc.set_world_flag(dimension, flag, value)
+    mc.set(
+        "set_world_flag",
+        lua.create_function(|lua, (dimension, flag, value): (String, String, bool)| {
+            if let Some(mut data) = lua.app_data_mut::<LuaHostData>() {
+                data.actions.push(LuaAction::SetWorldFlag {
+                    dimension,
+                    flag,
+                    value,
+                });
+            }
+            Ok(())
+        })?,
+    )?;
+
     // mc.get_tick()
     mc.set(
         "get_tick",
@@ -676,6 +1019,26 @@ fn setup_mc_api(lua: &Lua) -> LuaResult<()> {
         })?,
     )?;
 
+    // mc.get_tps() / mc.get_mspt()
+    mc.set(
+        "get_tps",
+        lua.create_function(|lua, ()| {
+            Ok(lua
+                .app_data_ref::<LuaHostData>()
+                .map(|d| d.cached_tps)
+                .unwrap_or(0.0))
+        })?,
+    )?;
+    mc.set(
+        "get_mspt",
+        lua.create_function(|lua, ()| {
+            Ok(lua
+                .app_data_ref::<LuaHostData>()
+                .map(|d| d.cached_mspt)
+                .unwrap_or(0.0))
+        })?,
+    )?;
+
     // mc.online_players() -> table of player tables
     mc.set(
         "online_players",
@@ -695,6 +1058,7 @@ fn setup_mc_api(lua: &Lua) -> LuaResult<()> {
                 pt.set("z", p.position.2)?;
                 pt.set("gamemode", p.gamemode)?;
                 pt.set("health", p.health)?;
+                pt.set("country", p.country.as_deref())?;
                 result.set(i + 1, pt)?;
             }
             Ok(result)
@@ -719,6 +1083,7 @@ fn setup_mc_api(lua: &Lua) -> LuaResult<()> {
                     pt.set("z", p.position.2)?;
                     pt.set("gamemode", p.gamemode)?;
                     pt.set("health", p.health)?;
+                    pt.set("country", p.country.as_deref())?;
                     Ok(LuaValue::Table(pt))
                 }
                 None => Ok(LuaValue::Nil),
@@ -748,6 +1113,52 @@ fn setup_mc_api(lua: &Lua) -> LuaResult<()> {
         })?,
     )?;
 
+    // mc.trigger_entity_event(runtime_id, event)
+    mc.set(
+        "trigger_entity_event",
+        lua.create_function(|lua, (runtime_id, event): (u64, String)| {
+            if let Some(mut data) = lua.app_data_mut::<LuaHostData>() {
+                data.actions
+                    .push(LuaAction::TriggerEntityEvent { runtime_id, event });
+            }
+            Ok(())
+        })?,
+    )?;
+
+    // mc.send_script_event(id, payload)
+    mc.set(
+        "send_script_event",
+        lua.create_function(|lua, (id, payload): (String, Option<String>)| {
+            if let Some(mut data) = lua.app_data_mut::<LuaHostData>() {
+                data.actions.push(LuaAction::SendScriptEvent {
+                    id,
+                    payload: payload.unwrap_or_default(),
+                });
+            }
+            Ok(())
+        })?,
+    )?;
+
+    // mc.show_form(player_name, form_id, json), mc.show_next_form(player_name, form_id, json)
+    for (name, next) in [("show_form", false), ("show_next_form", true)] {
+        mc.set(
+            name,
+            lua.create_function(
+                move |lua, (player_name, form_id, json): (String, u32, String)| {
+                    if let Some(mut data) = lua.app_data_mut::<LuaHostData>() {
+                        data.actions.push(LuaAction::ShowForm {
+                            player_name,
+                            form_id,
+                            json,
+                            next,
+                        });
+                    }
+                    Ok(())
+                },
+            )?,
+        )?;
+    }
+
     // mc.log(msg), mc.log_warn(msg), mc.log_error(msg), mc.log_debug(msg)
     mc.set(
         "log",
@@ -901,6 +1312,8 @@ fn load_single_plugin(dir: &Path) -> Result<LuaPlugin, String> {
 
     let lua = Lua::new();
     setup_sandbox(&lua, manifest.memory_mb).map_err(|e| format!("sandbox: {e}"))?;
+    setup_instruction_limit(&lua, manifest.instruction_limit)
+        .map_err(|e| format!("instruction limit: {e}"))?;
 
     lua.set_app_data(LuaHostData::new(manifest.name.clone()));
 
@@ -910,6 +1323,8 @@ fn load_single_plugin(dir: &Path) -> Result<LuaPlugin, String> {
         manifest,
         lua,
         script_path,
+        overruns: 0,
+        disabled: false,
     })
 }
 
@@ -931,12 +1346,13 @@ pub fn load_lua_plugins(plugins_dir: &Path) -> Vec<Box<dyn Plugin>> {
         if !path.join("plugin.toml").exists() {
             continue;
         }
-        // Skip WASM plugins (they have wasm_file in their manifest)
+        // Skip WASM and native plugins (they have wasm_file or library_file
+        // in their manifest)
         let toml_content = match std::fs::read_to_string(path.join("plugin.toml")) {
             Ok(c) => c,
             Err(_) => continue,
         };
-        if toml_content.contains("wasm_file") {
+        if toml_content.contains("wasm_file") || toml_content.contains("library_file") {
             continue;
         }
 
@@ -1047,6 +1463,7 @@ mod tests {
                 position: (0.0, 65.0, 0.0),
                 gamemode: 0,
                 health: 20.0,
+                country: None,
             },
         };
         let (event_name, event_table) = LuaPlugin::event_to_lua_table(&lua, &event).unwrap();
@@ -1085,6 +1502,7 @@ mod tests {
                 position: (0.0, 65.0, 0.0),
                 gamemode: 0,
                 health: 20.0,
+                country: None,
             },
             damage: 5.0,
             cause: DamageCause::Attack,
@@ -1175,6 +1593,49 @@ mod tests {
         assert_eq!(tick, 42);
     }
 
+    #[test]
+    fn mc_get_tps_and_mspt() {
+        let lua = test_lua();
+        if let Some(mut data) = lua.app_data_mut::<LuaHostData>() {
+            data.cached_tps = 19.5;
+            data.cached_mspt = 12.25;
+        }
+
+        let tps: f64 = lua.load("return mc.get_tps()").eval().unwrap();
+        assert_eq!(tps, 19.5);
+        let mspt: f64 = lua.load("return mc.get_mspt()").eval().unwrap();
+        assert_eq!(mspt, 12.25);
+    }
+
+    #[test]
+    fn mc_world_flags() {
+        let lua = test_lua();
+        if let Some(mut data) = lua.app_data_mut::<LuaHostData>() {
+            data.cached_world_flags = vec![("overworld", "pvp", false), ("end", "hunger", true)];
+        }
+
+        let pvp: Option<bool> = lua
+            .load(r#"return mc.get_world_flag("overworld", "pvp")"#)
+            .eval()
+            .unwrap();
+        assert_eq!(pvp, Some(false));
+        let unknown: Option<bool> = lua
+            .load(r#"return mc.get_world_flag("nether", "keep_inventory")"#)
+            .eval()
+            .unwrap();
+        assert_eq!(unknown, None);
+
+        lua.load(r#"mc.set_world_flag("nether", "fall_damage", false)"#)
+            .exec()
+            .unwrap();
+        let actions = take_actions(&lua);
+        assert!(matches!(
+            &actions[0],
+            LuaAction::SetWorldFlag { dimension, flag, value: false }
+                if dimension == "nether" && flag == "fall_damage"
+        ));
+    }
+
     #[test]
     fn mc_online_players_and_get_player() {
         let lua = test_lua();
@@ -1186,6 +1647,7 @@ mod tests {
                 position: (10.0, 65.0, 20.0),
                 gamemode: 0,
                 health: 20.0,
+                country: Some("FR".into()),
             }];
         }
 
@@ -1204,6 +1666,12 @@ mod tests {
             .unwrap();
         assert_eq!(name, "Alice");
 
+        let country: String = lua
+            .load("return mc.get_player('Alice').country")
+            .eval()
+            .unwrap();
+        assert_eq!(country, "FR");
+
         let result: LuaValue = lua.load("return mc.get_player('Bob')").eval().unwrap();
         assert!(result.is_nil());
     }
@@ -1227,6 +1695,119 @@ mod tests {
         assert!(matches!(&actions[1], LuaAction::RemoveMob { runtime_id } if *runtime_id == 42));
     }
 
+    #[test]
+    fn mc_script_events() {
+        let lua = test_lua();
+        lua.load(
+            r#"
+            mc.send_script_event("my_pack:ping", "hello")
+            mc.send_script_event("my_pack:empty")
+            mc.trigger_entity_event(7, "minecraft:become_angry")
+        "#,
+        )
+        .exec()
+        .unwrap();
+        let actions = take_actions(&lua);
+        assert_eq!(actions.len(), 3);
+        assert!(matches!(
+            &actions[0],
+            LuaAction::SendScriptEvent { id, payload } if id == "my_pack:ping" && payload == "hello"
+        ));
+        assert!(matches!(
+            &actions[1],
+            LuaAction::SendScriptEvent { payload, .. } if payload.is_empty()
+        ));
+        assert!(matches!(
+            &actions[2],
+            LuaAction::TriggerEntityEvent { runtime_id: 7, event } if event == "minecraft:become_angry"
+        ));
+    }
+
+    #[test]
+    fn mc_show_forms() {
+        let lua = test_lua();
+        lua.load(
+            r#"
+            mc.show_form("Alice", 1, '{"type":"form","title":"Menu","content":"","buttons":[]}')
+            mc.show_next_form("Alice", 2, '{"type":"modal"}')
+        "#,
+        )
+        .exec()
+        .unwrap();
+        let actions = take_actions(&lua);
+        assert!(matches!(
+            &actions[..],
+            [
+                LuaAction::ShowForm {
+                    form_id: 1,
+                    next: false,
+                    ..
+                },
+                LuaAction::ShowForm {
+                    form_id: 2,
+                    next: true,
+                    ..
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    fn form_responses_are_typed() {
+        let lua = test_lua();
+        let event = PluginEvent::FormResponse {
+            player: PluginPlayer {
+                name: "Alice".into(),
+                uuid: "u1".into(),
+                runtime_id: 1,
+                position: (0.0, 65.0, 0.0),
+                gamemode: 0,
+                health: 20.0,
+                country: None,
+            },
+            form_id: 3,
+            response: FormResponse::Custom {
+                values: vec![
+                    FormValue::Label,
+                    FormValue::Input("Steve".into()),
+                    FormValue::Toggle(true),
+                    FormValue::Dropdown(2),
+                ],
+            },
+        };
+        let (_, event_table) = LuaPlugin::event_to_lua_table(&lua, &event).unwrap();
+        lua.globals().set("event", event_table).unwrap();
+        let ok: bool = lua
+            .load(
+                r#"
+                local r = event.response
+                return r.kind == "custom" and r.values[1] == nil and r.values[2] == "Steve"
+                    and r.values[3] == true and r.values[4] == 2
+            "#,
+            )
+            .eval()
+            .unwrap();
+        assert!(ok);
+    }
+
+    #[test]
+    fn chunk_events_carry_coordinates() {
+        let lua = test_lua();
+        let event = PluginEvent::ChunkUnload {
+            dimension: 1,
+            cx: -3,
+            cz: 7,
+        };
+        let (name, t) = LuaPlugin::event_to_lua_table(&lua, &event).unwrap();
+        assert_eq!(name, "chunk_unload");
+        assert_eq!(t.get::<i32>("dimension").unwrap(), 1);
+        assert_eq!(t.get::<i32>("cx").unwrap(), -3);
+        assert_eq!(t.get::<i32>("cz").unwrap(), 7);
+
+        let (name, _) = LuaPlugin::event_to_lua_table(&lua, &PluginEvent::WorldSave).unwrap();
+        assert_eq!(name, "world_save");
+    }
+
     #[test]
     fn mc_log_variants() {
         let lua = test_lua();
@@ -1362,6 +1943,70 @@ mod tests {
         assert!(matches!(&actions[0], LuaAction::CancelTask { task_id } if *task_id == 5));
     }
 
+    // ── Instruction limit ────────────────────────────────────────────────
+
+    #[test]
+    fn instruction_limit_aborts_runaway_code() {
+        let lua = test_lua();
+        setup_instruction_limit(&lua, 10_000).unwrap();
+        let err = lua.load("while true do end").exec().unwrap_err();
+        assert!(err.to_string().contains("instruction limit"));
+
+        let mut data = lua.app_data_mut::<LuaHostData>().unwrap();
+        (data.instructions, data.aborted) = (0, false);
+        drop(data);
+        lua.load("for i = 1, 100 do end").exec().unwrap();
+        assert!(lua.load("return pcall(error, 'x')").eval::<bool>().is_ok());
+    }
+
+    #[test]
+    fn instruction_limit_cannot_be_caught() {
+        let lua = test_lua();
+        setup_instruction_limit(&lua, 10_000).unwrap();
+        for script in [
+            "local n = 0 while n < 200000 do n = n + 1 pcall(function() while true do end end) end",
+            "while true do xpcall(function() while true do end end, function(e) return e end) end",
+        ] {
+            let mut data = lua.app_data_mut::<LuaHostData>().unwrap();
+            (data.instructions, data.aborted) = (0, false);
+            drop(data);
+            let err = lua.load(script).exec().unwrap_err();
+            assert!(err.to_string().contains("instruction limit"), "{script}");
+            assert!(lua.app_data_ref::<LuaHostData>().unwrap().instructions < 20_000);
+        }
+        assert!(lua.globals().get::<LuaValue>("coroutine").unwrap().is_nil());
+    }
+
+    #[test]
+    fn instruction_overruns_disable_plugin() {
+        let dir = std::env::temp_dir().join("mc_rs_lua_test_overruns");
+        std::fs::create_dir_all(&dir).ok();
+        std::fs::write(
+            dir.join("plugin.toml"),
+            r#"
+[plugin]
+name = "Spinner"
+
+[limits]
+instruction_limit = 10000
+max_overruns = 2
+"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("main.lua"), "").unwrap();
+        let mut plugin = load_single_plugin(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        for overrun in 1..=2 {
+            assert!(plugin.begin_callback());
+            assert!(plugin.lua.load("while true do end").exec().is_err());
+            plugin.end_callback("test");
+            assert_eq!(plugin.overruns, overrun);
+        }
+        assert!(plugin.disabled);
+        assert!(!plugin.begin_callback());
+    }
+
     // ── Plugin loading ───────────────────────────────────────────────────
 
     #[test]
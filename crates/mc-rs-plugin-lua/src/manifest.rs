@@ -34,8 +34,13 @@ fn default_main() -> String {
 pub struct LimitsSection {
     #[serde(default = "default_memory_mb")]
     pub memory_mb: usize,
+    /// Instructions a single callback may run, 0 for no limit.
     #[serde(default = "default_instruction_limit")]
     pub instruction_limit: u32,
+    /// Callbacks that may hit the instruction limit before the plugin is
+    /// disabled.
+    #[serde(default = "default_max_overruns")]
+    pub max_overruns: u32,
 }
 
 impl Default for LimitsSection {
@@ -43,6 +48,7 @@ impl Default for LimitsSection {
         Self {
             memory_mb: default_memory_mb(),
             instruction_limit: default_instruction_limit(),
+            max_overruns: default_max_overruns(),
         }
     }
 }
@@ -55,6 +61,10 @@ fn default_instruction_limit() -> u32 {
     1_000_000
 }
 
+fn default_max_overruns() -> u32 {
+    3
+}
+
 /// Parsed Lua plugin manifest.
 pub struct LuaPluginManifest {
     pub name: String,
@@ -64,6 +74,7 @@ pub struct LuaPluginManifest {
     pub main: String,
     pub memory_mb: usize,
     pub instruction_limit: u32,
+    pub max_overruns: u32,
 }
 
 impl LuaPluginManifest {
@@ -78,6 +89,7 @@ impl LuaPluginManifest {
             main: raw.plugin.main,
             memory_mb: raw.limits.memory_mb,
             instruction_limit: raw.limits.instruction_limit,
+            max_overruns: raw.limits.max_overruns,
         })
     }
 }
@@ -99,6 +111,7 @@ main = "init.lua"
 [limits]
 memory_mb = 32
 instruction_limit = 500000
+max_overruns = 5
 "#;
         let m = LuaPluginManifest::parse(toml).unwrap();
         assert_eq!(m.name, "TestPlugin");
@@ -107,6 +120,7 @@ instruction_limit = 500000
         assert_eq!(m.main, "init.lua");
         assert_eq!(m.memory_mb, 32);
         assert_eq!(m.instruction_limit, 500_000);
+        assert_eq!(m.max_overruns, 5);
     }
 
     #[test]
@@ -121,6 +135,7 @@ name = "Minimal"
         assert_eq!(m.main, "main.lua");
         assert_eq!(m.memory_mb, 16);
         assert_eq!(m.instruction_limit, 1_000_000);
+        assert_eq!(m.max_overruns, 3);
     }
 
     #[test]
@@ -0,0 +1,157 @@
+//! The C ABI between the server and native plugins.
+//!
+//! A plugin is a `cdylib` exporting [`ENTRY_SYMBOL`], an [`EntryFn`] that
+//! returns a static [`PluginVTable`]. The server refuses the library unless
+//! the vtable's `abi_version` is [`ABI_VERSION`], which is bumped on every
+//! layout change of the types in this module.
+//!
+//! Strings cross the boundary as [`Str`]s, borrowed for the duration of the
+//! call: the side that receives one copies it if it needs to keep it.
+
+use std::ffi::c_void;
+
+/// Version of the layout of [`PluginVTable`] and [`HostApi`].
+pub const ABI_VERSION: u32 = 1;
+
+/// Symbol of the [`EntryFn`] a plugin exports.
+pub const ENTRY_SYMBOL: &[u8] = b"mcrs_plugin_entry";
+
+/// `extern "C" fn mcrs_plugin_entry() -> *const PluginVTable`.
+pub type EntryFn = unsafe extern "C" fn() -> *const PluginVTable;
+
+/// Log levels of [`HostApi::log`].
+pub const LOG_INFO: u32 = 0;
+pub const LOG_WARN: u32 = 1;
+pub const LOG_ERROR: u32 = 2;
+pub const LOG_DEBUG: u32 = 3;
+
+/// A borrowed UTF-8 string. A null `ptr` means no string.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct Str {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl Str {
+    /// No string, e.g. for an offline player.
+    pub const NONE: Str = Str {
+        ptr: std::ptr::null(),
+        len: 0,
+    };
+
+    /// Borrow a Rust string.
+    pub fn new(s: &str) -> Self {
+        Self {
+            ptr: s.as_ptr(),
+            len: s.len(),
+        }
+    }
+
+    /// The string, or `None` if it is [`Str::NONE`] or not UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// A non-null `ptr` must point to `len` bytes that stay valid for `'a`.
+    pub unsafe fn as_str<'a>(self) -> Option<&'a str> {
+        if self.ptr.is_null() {
+            return None;
+        }
+        std::str::from_utf8(std::slice::from_raw_parts(self.ptr, self.len)).ok()
+    }
+}
+
+/// The callbacks of a plugin, mirroring the `Plugin` trait.
+///
+/// `state` is the pointer returned by `create`, passed back to every other
+/// callback. The server calls them from the tick thread only.
+#[repr(C)]
+pub struct PluginVTable {
+    /// Must be [`ABI_VERSION`].
+    pub abi_version: u32,
+    /// Allocate the plugin's state.
+    pub create: unsafe extern "C" fn() -> *mut c_void,
+    /// Free the state. No callback is called after this one.
+    pub destroy: unsafe extern "C" fn(state: *mut c_void),
+    pub on_enable: unsafe extern "C" fn(state: *mut c_void, host: *const HostApi),
+    pub on_disable: unsafe extern "C" fn(state: *mut c_void),
+    /// `event` is the JSON of a `PluginEvent`. Return true to cancel it.
+    pub on_event:
+        unsafe extern "C" fn(state: *mut c_void, host: *const HostApi, event: Str) -> bool,
+    pub on_task: unsafe extern "C" fn(state: *mut c_void, host: *const HostApi, task_id: u32),
+    /// `args` is a JSON array of strings. Reply with [`HostApi::reply`].
+    pub on_command: unsafe extern "C" fn(
+        state: *mut c_void,
+        host: *const HostApi,
+        command: Str,
+        args: Str,
+        sender: Str,
+    ),
+    /// `config` is the JSON of the plugin's config.
+    pub load_config: unsafe extern "C" fn(state: *mut c_void, config: Str),
+}
+
+/// Server functions a plugin can call during a callback, passing `ctx` as
+/// the first argument. The table is only valid until the callback returns.
+///
+/// Unlike WASM and Lua plugins, calls go straight to the server: reads see
+/// the writes made earlier in the same callback.
+#[repr(C)]
+pub struct HostApi {
+    pub ctx: *mut c_void,
+
+    // --- Players ---
+    pub send_message: unsafe extern "C" fn(ctx: *mut c_void, player: Str, message: Str),
+    pub broadcast_message: unsafe extern "C" fn(ctx: *mut c_void, message: Str),
+    pub kick_player: unsafe extern "C" fn(ctx: *mut c_void, player: Str, reason: Str),
+    pub set_player_health: unsafe extern "C" fn(ctx: *mut c_void, player: Str, health: f32),
+    pub set_player_food: unsafe extern "C" fn(ctx: *mut c_void, player: Str, food: i32),
+    pub teleport_player:
+        unsafe extern "C" fn(ctx: *mut c_void, player: Str, x: f32, y: f32, z: f32),
+    /// JSON array of the online players.
+    pub online_players: unsafe extern "C" fn(ctx: *mut c_void) -> Str,
+    /// JSON of an online player, [`Str::NONE`] if they are offline.
+    pub get_player: unsafe extern "C" fn(ctx: *mut c_void, name: Str) -> Str,
+
+    // --- World ---
+    pub get_time: unsafe extern "C" fn(ctx: *mut c_void) -> i64,
+    pub set_time: unsafe extern "C" fn(ctx: *mut c_void, time: i64),
+    pub is_raining: unsafe extern "C" fn(ctx: *mut c_void) -> bool,
+    /// 1 if the flag is on, 0 if off, -1 for an unknown flag or dimension.
+    pub get_world_flag: unsafe extern "C" fn(ctx: *mut c_void, dimension: Str, flag: Str) -> i32,
+    pub set_world_flag:
+        unsafe extern "C" fn(ctx: *mut c_void, dimension: Str, flag: Str, value: bool),
+
+    // --- Entities ---
+    pub spawn_mob: unsafe extern "C" fn(ctx: *mut c_void, mob_type: Str, x: f32, y: f32, z: f32),
+    pub remove_mob: unsafe extern "C" fn(ctx: *mut c_void, runtime_id: u64),
+    pub trigger_entity_event: unsafe extern "C" fn(ctx: *mut c_void, runtime_id: u64, event: Str),
+
+    // --- Script events ---
+    pub send_script_event: unsafe extern "C" fn(ctx: *mut c_void, id: Str, payload: Str),
+
+    // --- Forms ---
+    /// The form type is read from the JSON.
+    pub show_form: unsafe extern "C" fn(ctx: *mut c_void, player: Str, form_id: u32, json: Str),
+    /// Show a form from the response to another one: closing it goes back.
+    pub show_next_form:
+        unsafe extern "C" fn(ctx: *mut c_void, player: Str, form_id: u32, json: Str),
+
+    // --- Server ---
+    pub get_tick: unsafe extern "C" fn(ctx: *mut c_void) -> u64,
+    pub get_tps: unsafe extern "C" fn(ctx: *mut c_void) -> f64,
+    pub get_mspt: unsafe extern "C" fn(ctx: *mut c_void) -> f64,
+    /// `level` is one of the `LOG_*` constants.
+    pub log: unsafe extern "C" fn(ctx: *mut c_void, level: u32, message: Str),
+
+    // --- Scheduler ---
+    pub schedule_delayed: unsafe extern "C" fn(ctx: *mut c_void, delay_ticks: u64, task_id: u32),
+    pub schedule_repeating:
+        unsafe extern "C" fn(ctx: *mut c_void, delay_ticks: u64, interval_ticks: u64, task_id: u32),
+    pub cancel_task: unsafe extern "C" fn(ctx: *mut c_void, task_id: u32),
+
+    // --- Commands ---
+    pub register_command: unsafe extern "C" fn(ctx: *mut c_void, name: Str, description: Str),
+    /// Set the reply shown to the sender of the command being handled.
+    pub reply: unsafe extern "C" fn(ctx: *mut c_void, message: Str),
+}
@@ -0,0 +1,213 @@
+//! The server side of [`HostApi`]: each function forwards to the
+//! `ServerApi` of the callback in progress.
+
+use std::ffi::c_void;
+
+use mc_rs_plugin_api::*;
+use tracing::warn;
+
+use crate::abi::{HostApi, Str, LOG_DEBUG, LOG_ERROR, LOG_WARN};
+
+/// State behind [`HostApi::ctx`] for one callback.
+pub(crate) struct HostContext<'a> {
+    api: &'a mut dyn ServerApi,
+    plugin_name: &'a str,
+    /// Strings handed to the plugin, kept alive until the callback returns.
+    returned: Vec<String>,
+    /// Set by [`HostApi::reply`].
+    pub(crate) reply: Option<String>,
+}
+
+impl<'a> HostContext<'a> {
+    pub(crate) fn new(api: &'a mut dyn ServerApi, plugin_name: &'a str) -> Self {
+        Self {
+            api,
+            plugin_name,
+            returned: Vec::new(),
+            reply: None,
+        }
+    }
+
+    /// The function table for this context. It must not outlive `self`.
+    pub(crate) fn host_api(&mut self) -> HostApi {
+        HostApi {
+            ctx: self as *mut Self as *mut c_void,
+            send_message,
+            broadcast_message,
+            kick_player,
+            set_player_health,
+            set_player_food,
+            teleport_player,
+            online_players,
+            get_player,
+            get_time,
+            set_time,
+            is_raining,
+            get_world_flag,
+            set_world_flag,
+            spawn_mob,
+            remove_mob,
+            trigger_entity_event,
+            send_script_event,
+            show_form,
+            show_next_form,
+            get_tick,
+            get_tps,
+            get_mspt,
+            log,
+            schedule_delayed,
+            schedule_repeating,
+            cancel_task,
+            register_command,
+            reply,
+        }
+    }
+
+    /// Keep a string alive for the rest of the callback and borrow it.
+    fn keep(&mut self, s: String) -> Str {
+        self.returned.push(s);
+        Str::new(self.returned.last().map(String::as_str).unwrap_or_default())
+    }
+}
+
+/// # Safety
+///
+/// `ctx` must be the `ctx` of a live [`HostContext::host_api`] table.
+unsafe fn cx<'a>(ctx: *mut c_void) -> &'a mut HostContext<'a> {
+    &mut *(ctx as *mut HostContext<'a>)
+}
+
+/// A string argument; invalid UTF-8 reads as empty.
+unsafe fn text<'a>(s: Str) -> &'a str {
+    s.as_str().unwrap_or_default()
+}
+
+unsafe extern "C" fn send_message(ctx: *mut c_void, player: Str, message: Str) {
+    cx(ctx).api.send_message(text(player), text(message));
+}
+unsafe extern "C" fn broadcast_message(ctx: *mut c_void, message: Str) {
+    cx(ctx).api.broadcast_message(text(message));
+}
+unsafe extern "C" fn kick_player(ctx: *mut c_void, player: Str, reason: Str) {
+    cx(ctx).api.kick_player(text(player), text(reason));
+}
+unsafe extern "C" fn set_player_health(ctx: *mut c_void, player: Str, health: f32) {
+    cx(ctx).api.set_player_health(text(player), health);
+}
+unsafe extern "C" fn set_player_food(ctx: *mut c_void, player: Str, food: i32) {
+    cx(ctx).api.set_player_food(text(player), food);
+}
+unsafe extern "C" fn teleport_player(ctx: *mut c_void, player: Str, x: f32, y: f32, z: f32) {
+    cx(ctx).api.teleport_player(text(player), x, y, z);
+}
+unsafe extern "C" fn online_players(ctx: *mut c_void) -> Str {
+    let host = cx(ctx);
+    let json = serde_json::to_string(&host.api.online_players()).unwrap_or_else(|_| "[]".into());
+    host.keep(json)
+}
+unsafe extern "C" fn get_player(ctx: *mut c_void, name: Str) -> Str {
+    let host = cx(ctx);
+    match host
+        .api
+        .get_player(text(name))
+        .and_then(|p| serde_json::to_string(&p).ok())
+    {
+        Some(json) => host.keep(json),
+        None => Str::NONE,
+    }
+}
+unsafe extern "C" fn get_time(ctx: *mut c_void) -> i64 {
+    cx(ctx).api.get_time()
+}
+unsafe extern "C" fn set_time(ctx: *mut c_void, time: i64) {
+    cx(ctx).api.set_time(time);
+}
+unsafe extern "C" fn is_raining(ctx: *mut c_void) -> bool {
+    cx(ctx).api.is_raining()
+}
+unsafe extern "C" fn get_world_flag(ctx: *mut c_void, dimension: Str, flag: Str) -> i32 {
+    match cx(ctx).api.get_world_flag(text(dimension), text(flag)) {
+        Some(true) => 1,
+        Some(false) => 0,
+        None => -1,
+    }
+}
+unsafe extern "C" fn set_world_flag(ctx: *mut c_void, dimension: Str, flag: Str, value: bool) {
+    cx(ctx)
+        .api
+        .set_world_flag(text(dimension), text(flag), value);
+}
+unsafe extern "C" fn spawn_mob(ctx: *mut c_void, mob_type: Str, x: f32, y: f32, z: f32) {
+    cx(ctx).api.spawn_mob(text(mob_type), x, y, z);
+}
+unsafe extern "C" fn remove_mob(ctx: *mut c_void, runtime_id: u64) {
+    cx(ctx).api.remove_mob(runtime_id);
+}
+unsafe extern "C" fn trigger_entity_event(ctx: *mut c_void, runtime_id: u64, event: Str) {
+    cx(ctx).api.trigger_entity_event(runtime_id, text(event));
+}
+unsafe extern "C" fn send_script_event(ctx: *mut c_void, id: Str, payload: Str) {
+    cx(ctx).api.send_script_event(text(id), text(payload));
+}
+unsafe extern "C" fn show_form(ctx: *mut c_void, player: Str, form_id: u32, json: Str) {
+    let host = cx(ctx);
+    let (player, json) = (text(player), text(json));
+    match form_type_of(json) {
+        Some("simple") => host.api.show_simple_form(player, form_id, json),
+        Some("modal") => host.api.show_modal_form(player, form_id, json),
+        Some("custom") => host.api.show_custom_form(player, form_id, json),
+        _ => warn!("[{}] show_form: unknown form type", host.plugin_name),
+    }
+}
+unsafe extern "C" fn show_next_form(ctx: *mut c_void, player: Str, form_id: u32, json: Str) {
+    cx(ctx)
+        .api
+        .show_next_form(text(player), form_id, text(json));
+}
+unsafe extern "C" fn get_tick(ctx: *mut c_void) -> u64 {
+    cx(ctx).api.get_tick()
+}
+unsafe extern "C" fn get_tps(ctx: *mut c_void) -> f64 {
+    cx(ctx).api.get_tps()
+}
+unsafe extern "C" fn get_mspt(ctx: *mut c_void) -> f64 {
+    cx(ctx).api.get_mspt()
+}
+unsafe extern "C" fn log(ctx: *mut c_void, level: u32, message: Str) {
+    let host = cx(ctx);
+    let level = match level {
+        LOG_WARN => LogLevel::Warn,
+        LOG_ERROR => LogLevel::Error,
+        LOG_DEBUG => LogLevel::Debug,
+        _ => LogLevel::Info,
+    };
+    let message = format!("[{}] {}", host.plugin_name, text(message));
+    host.api.log(level, &message);
+}
+unsafe extern "C" fn schedule_delayed(ctx: *mut c_void, delay_ticks: u64, task_id: u32) {
+    let host = cx(ctx);
+    host.api
+        .schedule_delayed(host.plugin_name, delay_ticks, task_id);
+}
+unsafe extern "C" fn schedule_repeating(
+    ctx: *mut c_void,
+    delay_ticks: u64,
+    interval_ticks: u64,
+    task_id: u32,
+) {
+    let host = cx(ctx);
+    host.api
+        .schedule_repeating(host.plugin_name, delay_ticks, interval_ticks, task_id);
+}
+unsafe extern "C" fn cancel_task(ctx: *mut c_void, task_id: u32) {
+    let host = cx(ctx);
+    host.api.cancel_task(host.plugin_name, task_id);
+}
+unsafe extern "C" fn register_command(ctx: *mut c_void, name: Str, description: Str) {
+    let host = cx(ctx);
+    host.api
+        .register_command(text(name), text(description), host.plugin_name);
+}
+unsafe extern "C" fn reply(ctx: *mut c_void, message: Str) {
+    cx(ctx).reply = Some(text(message).to_string());
+}
@@ -0,0 +1,474 @@
+//! Native plugin loader: shared libraries (`cdylib`s) implementing the C ABI
+//! of [`abi`].
+//!
+//! Native plugins run in the server process with no fuel or memory limits,
+//! for plugins that need raw performance or host libraries. A crash in one
+//! brings the server down, so the loader is off unless `[plugins] native`
+//! is enabled in the server config.
+
+pub mod abi;
+mod host;
+mod manifest;
+
+pub use manifest::NativePluginManifest;
+
+use std::ffi::c_void;
+use std::path::Path;
+
+use abi::{EntryFn, PluginVTable, Str, ABI_VERSION, ENTRY_SYMBOL};
+use host::HostContext;
+use libloading::Library;
+use mc_rs_plugin_api::*;
+use tracing::{error, info};
+
+/// A plugin loaded from a shared library.
+pub struct NativePlugin {
+    manifest: NativePluginManifest,
+    vtable: *const PluginVTable,
+    state: *mut c_void,
+    /// Declared last so the library is unloaded after `destroy` runs.
+    _library: Option<Library>,
+}
+
+impl NativePlugin {
+    /// Check the ABI version of a vtable and create the plugin's state.
+    ///
+    /// # Safety
+    ///
+    /// A non-null `vtable` must point to a [`PluginVTable`] that stays valid
+    /// while `library` is loaded, or forever if there is none.
+    unsafe fn new(
+        manifest: NativePluginManifest,
+        vtable: *const PluginVTable,
+        library: Option<Library>,
+    ) -> Result<Self, String> {
+        if vtable.is_null() {
+            return Err("plugin entry returned no vtable".into());
+        }
+        let version = (*vtable).abi_version;
+        if version != ABI_VERSION {
+            return Err(format!(
+                "plugin ABI version {version}, the server supports {ABI_VERSION}"
+            ));
+        }
+        let state = ((*vtable).create)();
+        Ok(Self {
+            manifest,
+            vtable,
+            state,
+            _library: library,
+        })
+    }
+
+    fn vtable(&self) -> &PluginVTable {
+        // SAFETY: checked non-null in `new`, valid while the library is loaded.
+        unsafe { &*self.vtable }
+    }
+
+    /// Run a callback with a host table for `api`, returning its result and
+    /// the command reply it set, if any.
+    fn with_host<R>(
+        &mut self,
+        api: &mut dyn ServerApi,
+        f: impl FnOnce(&PluginVTable, *mut c_void, *const abi::HostApi) -> R,
+    ) -> (R, Option<String>) {
+        let mut ctx = HostContext::new(api, &self.manifest.name);
+        let host = ctx.host_api();
+        let result = f(self.vtable(), self.state, &host);
+        (result, ctx.reply)
+    }
+}
+
+impl Plugin for NativePlugin {
+    fn info(&self) -> PluginInfo {
+        PluginInfo {
+            name: self.manifest.name.clone(),
+            version: self.manifest.version.clone(),
+            description: self.manifest.description.clone(),
+            author: self.manifest.author.clone(),
+        }
+    }
+
+    fn on_enable(&mut self, api: &mut dyn ServerApi) {
+        self.with_host(api, |vt, state, host| unsafe {
+            (vt.on_enable)(state, host)
+        });
+    }
+
+    fn on_disable(&mut self) {
+        unsafe { (self.vtable().on_disable)(self.state) }
+    }
+
+    fn on_event(&mut self, event: &PluginEvent, api: &mut dyn ServerApi) -> EventResult {
+        let json = match serde_json::to_string(event) {
+            Ok(j) => j,
+            Err(e) => {
+                error!(
+                    "[native:{}] failed to serialize event: {e}",
+                    self.manifest.name
+                );
+                return EventResult::Continue;
+            }
+        };
+        let (cancelled, _) = self.with_host(api, |vt, state, host| unsafe {
+            (vt.on_event)(state, host, Str::new(&json))
+        });
+        if cancelled {
+            EventResult::Cancelled
+        } else {
+            EventResult::Continue
+        }
+    }
+
+    fn on_task(&mut self, task_id: u32, api: &mut dyn ServerApi) {
+        self.with_host(api, |vt, state, host| unsafe {
+            (vt.on_task)(state, host, task_id)
+        });
+    }
+
+    fn on_command(
+        &mut self,
+        command: &str,
+        args: &[String],
+        sender: &str,
+        api: &mut dyn ServerApi,
+    ) -> Option<String> {
+        let args = serde_json::to_string(args).unwrap_or_else(|_| "[]".into());
+        let (_, reply) = self.with_host(api, |vt, state, host| unsafe {
+            (vt.on_command)(
+                state,
+                host,
+                Str::new(command),
+                Str::new(&args),
+                Str::new(sender),
+            )
+        });
+        reply
+    }
+
+    fn load_config(&mut self, config: serde_json::Value) {
+        let Ok(json) = serde_json::to_string(&config) else {
+            return;
+        };
+        unsafe { (self.vtable().load_config)(self.state, Str::new(&json)) }
+    }
+}
+
+impl Drop for NativePlugin {
+    fn drop(&mut self) {
+        unsafe { (self.vtable().destroy)(self.state) }
+    }
+}
+
+// The state is only touched from the thread running the callbacks, which
+// the ABI requires plugins to accept.
+unsafe impl Send for NativePlugin {}
+
+/// Load a single native plugin from a directory containing plugin.toml.
+fn load_single_plugin(dir: &Path) -> Result<NativePlugin, String> {
+    let manifest_path = dir.join("plugin.toml");
+    let toml_content =
+        std::fs::read_to_string(&manifest_path).map_err(|e| format!("read plugin.toml: {e}"))?;
+    let manifest = NativePluginManifest::parse(&toml_content)
+        .map_err(|e| format!("parse plugin.toml: {e}"))?;
+
+    let library_path = dir.join(&manifest.library_file);
+    // SAFETY: loading a library runs its initializers; native plugins are
+    // trusted code, which is why they must be enabled in the config.
+    let library = unsafe { Library::new(&library_path) }
+        .map_err(|e| format!("load {}: {e}", manifest.library_file))?;
+    let vtable = unsafe {
+        let entry = library
+            .get::<EntryFn>(ENTRY_SYMBOL)
+            .map_err(|e| format!("find entry point: {e}"))?;
+        entry()
+    };
+    unsafe { NativePlugin::new(manifest, vtable, Some(library)) }
+}
+
+/// Load all native plugins from the plugins directory.
+///
+/// Scans each subdirectory for a plugin.toml with a `library_file` field.
+pub fn load_native_plugins(plugins_dir: &Path) -> Vec<Box<dyn Plugin>> {
+    let mut plugins: Vec<Box<dyn Plugin>> = Vec::new();
+
+    let entries = match std::fs::read_dir(plugins_dir) {
+        Ok(e) => e,
+        Err(_) => return plugins,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        // Skip WASM and Lua plugins
+        let toml_content = match std::fs::read_to_string(path.join("plugin.toml")) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        if !toml_content.contains("library_file") {
+            continue;
+        }
+        match load_single_plugin(&path) {
+            Ok(plugin) => {
+                let info = plugin.info();
+                info!("Loaded native plugin: {} v{}", info.name, info.version);
+                plugins.push(Box::new(plugin));
+            }
+            Err(e) => {
+                error!("Failed to load native plugin from {}: {e}", path.display());
+            }
+        }
+    }
+
+    plugins
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::abi::HostApi;
+    use super::*;
+
+    // An in-process plugin built on the ABI, counting its events.
+
+    unsafe extern "C" fn test_create() -> *mut c_void {
+        Box::into_raw(Box::new(0u32)) as *mut c_void
+    }
+    unsafe extern "C" fn test_destroy(state: *mut c_void) {
+        drop(Box::from_raw(state as *mut u32));
+    }
+    unsafe extern "C" fn test_on_enable(_: *mut c_void, host: *const HostApi) {
+        let host = &*host;
+        (host.register_command)(host.ctx, Str::new("count"), Str::new("Count events"));
+    }
+    unsafe extern "C" fn test_on_disable(_: *mut c_void) {}
+    unsafe extern "C" fn test_on_event(
+        state: *mut c_void,
+        host: *const HostApi,
+        event: Str,
+    ) -> bool {
+        let host = &*host;
+        *(state as *mut u32) += 1;
+        (host.broadcast_message)(host.ctx, event);
+        (host.get_player)(host.ctx, Str::new("Nobody"))
+            .ptr
+            .is_null()
+    }
+    unsafe extern "C" fn test_on_task(_: *mut c_void, host: *const HostApi, task_id: u32) {
+        let host = &*host;
+        (host.set_time)(host.ctx, task_id as i64);
+    }
+    unsafe extern "C" fn test_on_command(
+        state: *mut c_void,
+        host: *const HostApi,
+        _: Str,
+        args: Str,
+        sender: Str,
+    ) {
+        let host = &*host;
+        let reply = format!(
+            "{} {} {}",
+            sender.as_str().unwrap(),
+            args.as_str().unwrap(),
+            *(state as *mut u32)
+        );
+        (host.reply)(host.ctx, Str::new(&reply));
+    }
+    unsafe extern "C" fn test_load_config(_: *mut c_void, _: Str) {}
+
+    fn test_vtable(abi_version: u32) -> PluginVTable {
+        PluginVTable {
+            abi_version,
+            create: test_create,
+            destroy: test_destroy,
+            on_enable: test_on_enable,
+            on_disable: test_on_disable,
+            on_event: test_on_event,
+            on_task: test_on_task,
+            on_command: test_on_command,
+            load_config: test_load_config,
+        }
+    }
+
+    fn test_manifest() -> NativePluginManifest {
+        NativePluginManifest {
+            name: "counter".into(),
+            version: "1.0.0".into(),
+            description: String::new(),
+            author: String::new(),
+            library_file: "libcounter.so".into(),
+        }
+    }
+
+    fn test_plugin(vtable: &'static PluginVTable) -> NativePlugin {
+        unsafe { NativePlugin::new(test_manifest(), vtable, None) }.unwrap()
+    }
+
+    #[test]
+    fn callbacks_reach_the_server() {
+        let vtable = Box::leak(Box::new(test_vtable(ABI_VERSION)));
+        let mut plugin = test_plugin(vtable);
+        let mut api = MockApi::default();
+
+        plugin.on_enable(&mut api);
+        assert_eq!(api.commands, vec![("count".into(), "counter".into())]);
+
+        let result = plugin.on_event(&PluginEvent::ServerStarted, &mut api);
+        assert_eq!(result, EventResult::Cancelled);
+        assert_eq!(api.broadcasts.len(), 1);
+        assert!(api.broadcasts[0].contains("ServerStarted"));
+
+        plugin.on_task(6000, &mut api);
+        assert_eq!(api.time_set, Some(6000));
+
+        let reply = plugin.on_command("count", &["a".into()], "Alice", &mut api);
+        assert_eq!(reply.as_deref(), Some("Alice [\"a\"] 1"));
+    }
+
+    #[test]
+    fn abi_version_mismatch_rejected() {
+        let vtable = Box::leak(Box::new(test_vtable(ABI_VERSION + 1)));
+        let result = unsafe { NativePlugin::new(test_manifest(), vtable, None) };
+        assert!(result.err().unwrap().contains("ABI version"));
+    }
+
+    #[test]
+    fn null_vtable_rejected() {
+        let result = unsafe { NativePlugin::new(test_manifest(), std::ptr::null(), None) };
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn missing_library_fails_to_load() {
+        let dir = std::env::temp_dir().join(format!("mcrs-native-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("plugin.toml"),
+            "[plugin]\nname = \"x\"\nversion = \"1\"\nlibrary_file = \"missing.so\"\n",
+        )
+        .unwrap();
+        let err = load_single_plugin(&dir).err().unwrap();
+        assert!(err.contains("missing.so"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn non_native_plugins_skipped() {
+        let root = std::env::temp_dir().join(format!("mcrs-native-skip-{}", std::process::id()));
+        let dir = root.join("lua-plugin");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("plugin.toml"),
+            "[plugin]\nname = \"x\"\nversion = \"1\"\nmain = \"main.lua\"\n",
+        )
+        .unwrap();
+        assert!(load_native_plugins(&root).is_empty());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    // Minimal ServerApi implementation for testing.
+    #[derive(Default)]
+    struct MockApi {
+        broadcasts: Vec<String>,
+        commands: Vec<(String, String)>,
+        time_set: Option<i64>,
+    }
+
+    impl ServerApi for MockApi {
+        fn online_players(&self) -> Vec<PluginPlayer> {
+            vec![]
+        }
+        fn get_player(&self, _: &str) -> Option<PluginPlayer> {
+            None
+        }
+        fn get_player_stats(&self, _: &str) -> Option<PluginPlayerStats> {
+            None
+        }
+        fn send_message(&mut self, _: &str, _: &str) {}
+        fn broadcast_message(&mut self, m: &str) {
+            self.broadcasts.push(m.into());
+        }
+        fn kick_player(&mut self, _: &str, _: &str) {}
+        fn set_player_health(&mut self, _: &str, _: f32) {}
+        fn set_player_food(&mut self, _: &str, _: i32) {}
+        fn teleport_player(&mut self, _: &str, _: f32, _: f32, _: f32) {}
+        fn transfer_player(&mut self, _: &str, _: &str, _: u16) {}
+        fn get_time(&self) -> i64 {
+            0
+        }
+        fn set_time(&mut self, t: i64) {
+            self.time_set = Some(t);
+        }
+        fn is_raining(&self) -> bool {
+            false
+        }
+        fn get_world_flag(&self, _: &str, _: &str) -> Option<bool> {
+            None
+        }
+        fn set_world_flag(&mut self, _: &str, _: &str, _: bool) {}
+        fn spawn_mob(&mut self, _: &str, _: f32, _: f32, _: f32) {}
+        fn remove_mob(&mut self, _: u64) {}
+        fn trigger_entity_event(&mut self, _: u64, _: &str) {}
+        fn set_entity_appearance(&mut self, _: u64, _: PluginEntityAppearance) {}
+        fn send_script_event(&mut self, _: &str, _: &str) {}
+        fn get_tick(&self) -> u64 {
+            0
+        }
+        fn get_tps(&self) -> f64 {
+            20.0
+        }
+        fn get_mspt(&self) -> f64 {
+            0.0
+        }
+        fn log(&self, _: LogLevel, _: &str) {}
+        fn schedule_delayed(&mut self, _: &str, _: u64, _: u32) {}
+        fn schedule_repeating(&mut self, _: &str, _: u64, _: u64, _: u32) {}
+        fn cancel_task(&mut self, _: &str, _: u32) {}
+        fn register_command(&mut self, name: &str, _: &str, plugin: &str) {
+            self.commands.push((name.into(), plugin.into()));
+        }
+        fn show_simple_form(&mut self, _: &str, _: u32, _: &str) {}
+        fn show_modal_form(&mut self, _: &str, _: u32, _: &str) {}
+        fn show_custom_form(&mut self, _: &str, _: u32, _: &str) {}
+        fn show_next_form(&mut self, _: &str, _: u32, _: &str) {}
+        fn register_kit(&mut self, _: &str, _: Vec<PluginKitItem>, _: u64) {}
+        fn currency_name(&self) -> String {
+            "coins".into()
+        }
+        fn get_balance(&self, _: &str) -> Option<u64> {
+            None
+        }
+        fn deposit(&mut self, _: &str, _: u64, _: &str) -> bool {
+            false
+        }
+        fn withdraw(&mut self, _: &str, _: u64, _: &str) -> bool {
+            false
+        }
+        fn transfer_money(&mut self, _: &str, _: &str, _: u64, _: &str) -> bool {
+            false
+        }
+        fn create_arena(&mut self, _: PluginArena) {}
+        fn join_arena(&mut self, _: &str, _: &str) {}
+        fn leave_arena(&mut self, _: &str) {}
+        fn set_arena_score(&mut self, _: &str, _: &str, _: i32) {}
+        fn end_arena(&mut self, _: &str, _: Option<&str>) {}
+        fn player_arena(&self, _: &str) -> Option<String> {
+            None
+        }
+        fn create_hologram(&mut self, _: &str, _: f32, _: f32, _: f32, _: Vec<String>) {}
+        fn set_hologram_lines(&mut self, _: &str, _: Vec<String>) {}
+        fn remove_hologram(&mut self, _: &str) {}
+        fn play_cutscene(&mut self, _: &str, _: PluginCutscene) {}
+        fn stop_cutscene(&mut self, _: &str) {}
+        fn push_fog(&mut self, _: &str, _: &str, _: &str) {}
+        fn remove_fog(&mut self, _: &str, _: &str) {}
+        fn set_client_game_rule(&mut self, _: &str, _: &str, _: bool) {}
+        fn set_input_permission(&mut self, _: &str, _: &str, _: bool) {}
+        fn accepted_packs(&self, _: &str) -> Option<Vec<String>> {
+            None
+        }
+    }
+}
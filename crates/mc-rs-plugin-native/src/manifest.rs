@@ -0,0 +1,77 @@
+//! Native plugin manifest (plugin.toml) parsing.
+
+use serde::Deserialize;
+
+/// Parsed native plugin manifest.
+#[derive(Debug, Clone)]
+pub struct NativePluginManifest {
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub author: String,
+    /// Path of the shared library, relative to the plugin directory.
+    pub library_file: String,
+}
+
+#[derive(Deserialize)]
+struct PluginToml {
+    plugin: PluginSection,
+}
+
+#[derive(Deserialize)]
+struct PluginSection {
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    author: String,
+    library_file: String,
+}
+
+impl NativePluginManifest {
+    /// Parse a plugin.toml file content into a NativePluginManifest.
+    pub fn parse(toml_content: &str) -> Result<Self, toml::de::Error> {
+        let parsed: PluginToml = toml::from_str(toml_content)?;
+        Ok(Self {
+            name: parsed.plugin.name,
+            version: parsed.plugin.version,
+            description: parsed.plugin.description,
+            author: parsed.plugin.author,
+            library_file: parsed.plugin.library_file,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_manifest() {
+        let toml = r#"
+[plugin]
+name = "fast-plugin"
+version = "1.0.0"
+author = "TestDev"
+library_file = "libfast_plugin.so"
+"#;
+        let m = NativePluginManifest::parse(toml).unwrap();
+        assert_eq!(m.name, "fast-plugin");
+        assert_eq!(m.version, "1.0.0");
+        assert_eq!(m.description, "");
+        assert_eq!(m.author, "TestDev");
+        assert_eq!(m.library_file, "libfast_plugin.so");
+    }
+
+    #[test]
+    fn parse_missing_library_file_fails() {
+        let toml = r#"
+[plugin]
+name = "test"
+version = "1.0.0"
+wasm_file = "plugin.wasm"
+"#;
+        assert!(NativePluginManifest::parse(toml).is_err());
+    }
+}
@@ -0,0 +1,256 @@
+//! Component-model WASM plugins, built against the `plugin` world of
+//! `wit/plugin.wit`.
+//!
+//! Guests get typed bindings (e.g. from `wit-bindgen`) instead of the raw
+//! ABI's `__malloc` and length-prefixed strings. Host functions, fuel and
+//! memory limits are the same as for raw plugins.
+
+use mc_rs_plugin_api::*;
+use tracing::{error, warn};
+use wasmtime::component::{Component, Linker};
+use wasmtime::{Engine, Store};
+
+use crate::{WasmHostData, WasmPluginManifest};
+
+mod bindings {
+    wasmtime::component::bindgen!({
+        path: "wit",
+        world: "plugin",
+    });
+}
+
+use bindings::mcrs::plugin::host;
+
+/// Whether a WASM binary is a component rather than a core module: both
+/// start with `\0asm`, followed by a version and a layer, 1 for components.
+pub(crate) fn is_component(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"\0asm") && bytes.get(6..8) == Some(&[1, 0])
+}
+
+impl host::Host for WasmHostData {
+    fn send_message(&mut self, player: String, message: String) {
+        WasmHostData::send_message(self, player, message);
+    }
+    fn broadcast_message(&mut self, message: String) {
+        WasmHostData::broadcast_message(self, message);
+    }
+    fn kick_player(&mut self, player: String, reason: String) {
+        WasmHostData::kick_player(self, player, reason);
+    }
+    fn set_player_health(&mut self, player: String, health: f32) {
+        WasmHostData::set_player_health(self, player, health);
+    }
+    fn set_player_food(&mut self, player: String, food: i32) {
+        WasmHostData::set_player_food(self, player, food);
+    }
+    fn teleport_player(&mut self, player: String, x: f32, y: f32, z: f32) {
+        WasmHostData::teleport_player(self, player, x, y, z);
+    }
+    fn online_players(&mut self) -> String {
+        self.cached_players_json.clone()
+    }
+    fn get_player(&mut self, name: String) -> Option<String> {
+        self.get_player_json(&name)
+    }
+    fn get_time(&mut self) -> i64 {
+        self.cached_time
+    }
+    fn set_time(&mut self, time: i64) {
+        WasmHostData::set_time(self, time);
+    }
+    fn is_raining(&mut self) -> bool {
+        self.cached_is_raining
+    }
+    fn get_world_flag(&mut self, dimension: String, flag: String) -> Option<bool> {
+        WasmHostData::get_world_flag(self, &dimension, &flag)
+    }
+    fn set_world_flag(&mut self, dimension: String, flag: String, value: bool) {
+        WasmHostData::set_world_flag(self, dimension, flag, value);
+    }
+    fn spawn_mob(&mut self, mob_type: String, x: f32, y: f32, z: f32) {
+        WasmHostData::spawn_mob(self, mob_type, x, y, z);
+    }
+    fn remove_mob(&mut self, runtime_id: u64) {
+        WasmHostData::remove_mob(self, runtime_id);
+    }
+    fn trigger_entity_event(&mut self, runtime_id: u64, event: String) {
+        WasmHostData::trigger_entity_event(self, runtime_id, event);
+    }
+    fn send_script_event(&mut self, id: String, payload: String) {
+        WasmHostData::send_script_event(self, id, payload);
+    }
+    fn show_form(&mut self, player: String, form_id: u32, json: String) {
+        WasmHostData::show_form(self, player, form_id, json, false);
+    }
+    fn show_next_form(&mut self, player: String, form_id: u32, json: String) {
+        WasmHostData::show_form(self, player, form_id, json, true);
+    }
+    fn get_tick(&mut self) -> u64 {
+        self.cached_tick
+    }
+    fn get_tps(&mut self) -> f64 {
+        self.cached_tps
+    }
+    fn get_mspt(&mut self) -> f64 {
+        self.cached_mspt
+    }
+    fn log(&mut self, level: host::LogLevel, message: String) {
+        let level = match level {
+            host::LogLevel::Info => LogLevel::Info,
+            host::LogLevel::Warn => LogLevel::Warn,
+            host::LogLevel::Error => LogLevel::Error,
+            host::LogLevel::Debug => LogLevel::Debug,
+        };
+        WasmHostData::log(self, level, message);
+    }
+    fn schedule_delayed(&mut self, delay_ticks: u64, task_id: u32) {
+        WasmHostData::schedule_delayed(self, delay_ticks, task_id);
+    }
+    fn schedule_repeating(&mut self, delay_ticks: u64, interval_ticks: u64, task_id: u32) {
+        WasmHostData::schedule_repeating(self, delay_ticks, interval_ticks, task_id);
+    }
+    fn cancel_task(&mut self, task_id: u32) {
+        WasmHostData::cancel_task(self, task_id);
+    }
+    fn register_command(&mut self, name: String, description: String) {
+        WasmHostData::register_command(self, name, description);
+    }
+}
+
+/// A plugin loaded from a WASM component.
+pub struct ComponentPlugin {
+    manifest: WasmPluginManifest,
+    store: Store<WasmHostData>,
+    bindings: bindings::Plugin,
+}
+
+impl ComponentPlugin {
+    /// Instantiate a component against the host functions.
+    pub(crate) fn new(
+        engine: &Engine,
+        component: &Component,
+        manifest: WasmPluginManifest,
+    ) -> Result<Self, String> {
+        let host_data = WasmHostData::new(manifest.name.clone(), manifest.max_memory_pages);
+        let mut store = Store::new(engine, host_data);
+        store.limiter(|data| &mut data.limits);
+        store
+            .set_fuel(manifest.fuel_on_enable)
+            .map_err(|e| format!("set fuel: {e}"))?;
+
+        let mut linker = Linker::new(engine);
+        bindings::Plugin::add_to_linker(&mut linker, |data: &mut WasmHostData| data)
+            .map_err(|e| format!("build linker: {e}"))?;
+        let bindings = bindings::Plugin::instantiate(&mut store, component, &linker)
+            .map_err(|e| format!("instantiate: {e}"))?;
+
+        Ok(Self {
+            manifest,
+            store,
+            bindings,
+        })
+    }
+
+    /// Reset fuel for a new guest call.
+    fn refuel(&mut self, fuel: u64) {
+        let _ = self.store.set_fuel(fuel);
+    }
+}
+
+impl Plugin for ComponentPlugin {
+    fn info(&self) -> PluginInfo {
+        PluginInfo {
+            name: self.manifest.name.clone(),
+            version: self.manifest.version.clone(),
+            description: self.manifest.description.clone(),
+            author: self.manifest.author.clone(),
+        }
+    }
+
+    fn on_enable(&mut self, api: &mut dyn ServerApi) {
+        self.store.data_mut().sync_snapshot(api);
+        self.refuel(self.manifest.fuel_on_enable);
+        if let Err(e) = self.bindings.call_on_enable(&mut self.store) {
+            error!("[wasm:{}] on_enable trapped: {e}", self.manifest.name);
+            return;
+        }
+        self.store.data_mut().flush_actions(api);
+    }
+
+    fn on_disable(&mut self) {
+        self.refuel(self.manifest.fuel_on_enable);
+        if let Err(e) = self.bindings.call_on_disable(&mut self.store) {
+            error!("[wasm:{}] on_disable trapped: {e}", self.manifest.name);
+        }
+    }
+
+    fn on_event(&mut self, event: &PluginEvent, api: &mut dyn ServerApi) -> EventResult {
+        self.store.data_mut().sync_snapshot(api);
+        self.refuel(self.manifest.fuel_per_event);
+        let json = match serde_json::to_string(event) {
+            Ok(j) => j,
+            Err(e) => {
+                error!(
+                    "[wasm:{}] failed to serialize event: {e}",
+                    self.manifest.name
+                );
+                return EventResult::Continue;
+            }
+        };
+        let result = match self.bindings.call_on_event(&mut self.store, &json) {
+            Ok(true) => EventResult::Cancelled,
+            Ok(false) => EventResult::Continue,
+            Err(e) => {
+                warn!("[wasm:{}] on_event trapped: {e}", self.manifest.name);
+                EventResult::Continue
+            }
+        };
+        self.store.data_mut().flush_actions(api);
+        result
+    }
+
+    fn on_task(&mut self, task_id: u32, api: &mut dyn ServerApi) {
+        self.store.data_mut().sync_snapshot(api);
+        self.refuel(self.manifest.fuel_per_task);
+        if let Err(e) = self.bindings.call_on_task(&mut self.store, task_id) {
+            warn!(
+                "[wasm:{}] on_task({task_id}) trapped: {e}",
+                self.manifest.name
+            );
+        }
+        self.store.data_mut().flush_actions(api);
+    }
+
+    fn on_command(
+        &mut self,
+        command: &str,
+        args: &[String],
+        sender: &str,
+        api: &mut dyn ServerApi,
+    ) -> Option<String> {
+        self.store.data_mut().sync_snapshot(api);
+        self.refuel(self.manifest.fuel_per_command);
+        let reply = self
+            .bindings
+            .call_on_command(&mut self.store, command, args, sender)
+            .unwrap_or_else(|e| {
+                warn!("[wasm:{}] on_command trapped: {e}", self.manifest.name);
+                None
+            });
+        self.store.data_mut().flush_actions(api);
+        reply
+    }
+
+    fn load_config(&mut self, config: serde_json::Value) {
+        self.refuel(self.manifest.fuel_on_enable);
+        let Ok(json) = serde_json::to_string(&config) else {
+            return;
+        };
+        if let Err(e) = self.bindings.call_load_config(&mut self.store, &json) {
+            warn!("[wasm:{}] load_config trapped: {e}", self.manifest.name);
+        }
+    }
+}
+
+// Send for the same reasons as `WasmPlugin`.
+unsafe impl Send for ComponentPlugin {}
@@ -156,18 +156,8 @@ pub fn build_linker(engine: &Engine) -> Result<Linker<WasmHostData>, wasmtime::E
         "get_player",
         |mut caller: Caller<'_, WasmHostData>, name_ptr: i32, name_len: i32| -> i32 {
             let name = read_guest_string(&mut caller, name_ptr, name_len).unwrap_or_default();
-            // Look up player in cached data.
-            let players_json = caller.data().cached_players_json.clone();
-            let players: Vec<serde_json::Value> =
-                serde_json::from_str(&players_json).unwrap_or_default();
-            let found = players
-                .iter()
-                .find(|p| p.get("name").and_then(|n| n.as_str()) == Some(&name));
-            match found {
-                Some(player) => {
-                    let json = serde_json::to_string(player).unwrap_or_default();
-                    write_guest_string(&mut caller, &json)
-                }
+            match caller.data().get_player_json(&name) {
+                Some(json) => write_guest_string(&mut caller, &json),
                 None => 0,
             }
         },
@@ -201,6 +191,43 @@ pub fn build_linker(engine: &Engine) -> Result<Linker<WasmHostData>, wasmtime::E
         },
     )?;
 
+    // Returns 1 if the flag is on, 0 if off, -1 for an unknown flag or dimension.
+    linker.func_wrap(
+        "mcrs",
+        "get_world_flag",
+        |mut caller: Caller<'_, WasmHostData>,
+         dim_ptr: i32,
+         dim_len: i32,
+         flag_ptr: i32,
+         flag_len: i32|
+         -> i32 {
+            let dimension = read_guest_string(&mut caller, dim_ptr, dim_len).unwrap_or_default();
+            let flag = read_guest_string(&mut caller, flag_ptr, flag_len).unwrap_or_default();
+            match caller.data().get_world_flag(&dimension, &flag) {
+                Some(true) => 1,
+                Some(false) => 0,
+                None => -1,
+            }
+        },
+    )?;
+
+    linker.func_wrap(
+        "mcrs",
+        "set_world_flag",
+        |mut caller: Caller<'_, WasmHostData>,
+         dim_ptr: i32,
+         dim_len: i32,
+         flag_ptr: i32,
+         flag_len: i32,
+         value: i32| {
+            let dimension = read_guest_string(&mut caller, dim_ptr, dim_len).unwrap_or_default();
+            let flag = read_guest_string(&mut caller, flag_ptr, flag_len).unwrap_or_default();
+            caller
+                .data_mut()
+                .set_world_flag(dimension, flag, value != 0);
+        },
+    )?;
+
     // ── Entity API ──────────────────────────────────────────────
 
     linker.func_wrap(
@@ -225,6 +252,55 @@ pub fn build_linker(engine: &Engine) -> Result<Linker<WasmHostData>, wasmtime::E
         },
     )?;
 
+    linker.func_wrap(
+        "mcrs",
+        "trigger_entity_event",
+        |mut caller: Caller<'_, WasmHostData>, runtime_id: i64, event_ptr: i32, event_len: i32| {
+            let event = read_guest_string(&mut caller, event_ptr, event_len).unwrap_or_default();
+            caller
+                .data_mut()
+                .trigger_entity_event(runtime_id as u64, event);
+        },
+    )?;
+
+    // ── Script events ───────────────────────────────────────────
+
+    linker.func_wrap(
+        "mcrs",
+        "send_script_event",
+        |mut caller: Caller<'_, WasmHostData>,
+         id_ptr: i32,
+         id_len: i32,
+         payload_ptr: i32,
+         payload_len: i32| {
+            let id = read_guest_string(&mut caller, id_ptr, id_len).unwrap_or_default();
+            let payload =
+                read_guest_string(&mut caller, payload_ptr, payload_len).unwrap_or_default();
+            caller.data_mut().send_script_event(id, payload);
+        },
+    )?;
+
+    // ── Forms ───────────────────────────────────────────────────
+
+    for (name, next) in [("show_form", false), ("show_next_form", true)] {
+        linker.func_wrap(
+            "mcrs",
+            name,
+            move |mut caller: Caller<'_, WasmHostData>,
+                  name_ptr: i32,
+                  name_len: i32,
+                  form_id: i32,
+                  json_ptr: i32,
+                  json_len: i32| {
+                let player = read_guest_string(&mut caller, name_ptr, name_len).unwrap_or_default();
+                let json = read_guest_string(&mut caller, json_ptr, json_len).unwrap_or_default();
+                caller
+                    .data_mut()
+                    .show_form(player, form_id as u32, json, next);
+            },
+        )?;
+    }
+
     // ── Server API ──────────────────────────────────────────────
 
     linker.func_wrap(
@@ -233,6 +309,18 @@ pub fn build_linker(engine: &Engine) -> Result<Linker<WasmHostData>, wasmtime::E
         |caller: Caller<'_, WasmHostData>| -> i64 { caller.data().cached_tick as i64 },
     )?;
 
+    linker.func_wrap(
+        "mcrs",
+        "get_tps",
+        |caller: Caller<'_, WasmHostData>| -> f64 { caller.data().cached_tps },
+    )?;
+
+    linker.func_wrap(
+        "mcrs",
+        "get_mspt",
+        |caller: Caller<'_, WasmHostData>| -> f64 { caller.data().cached_mspt },
+    )?;
+
     linker.func_wrap(
         "mcrs",
         "log",
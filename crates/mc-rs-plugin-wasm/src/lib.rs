@@ -1,8 +1,13 @@
 //! WASM plugin runtime using wasmtime.
+//!
+//! Plugins are either core modules using the raw `"mcrs"` pointer ABI, or
+//! components built against the WIT world in `wit/plugin.wit`.
 
+mod component;
 mod host_functions;
 mod manifest;
 
+pub use component::ComponentPlugin;
 pub use manifest::WasmPluginManifest;
 
 use mc_rs_plugin_api::*;
@@ -45,6 +50,11 @@ pub(crate) enum WasmAction {
     SetTime {
         time: i64,
     },
+    SetWorldFlag {
+        dimension: String,
+        flag: String,
+        value: bool,
+    },
     SpawnMob {
         mob_type: String,
         x: f32,
@@ -54,6 +64,21 @@ pub(crate) enum WasmAction {
     RemoveMob {
         runtime_id: u64,
     },
+    TriggerEntityEvent {
+        runtime_id: u64,
+        event: String,
+    },
+    SendScriptEvent {
+        id: String,
+        payload: String,
+    },
+    ShowForm {
+        player_name: String,
+        form_id: u32,
+        json: String,
+        /// Opened from the response to another form.
+        next: bool,
+    },
     Log {
         level: LogLevel,
         message: String,
@@ -82,7 +107,11 @@ pub(crate) struct WasmHostData {
     pub actions: Vec<WasmAction>,
     pub cached_time: i64,
     pub cached_tick: u64,
+    pub cached_tps: f64,
+    pub cached_mspt: f64,
     pub cached_is_raining: bool,
+    /// `(dimension, flag, value)` for every world flag.
+    pub cached_world_flags: Vec<(&'static str, &'static str, bool)>,
     pub cached_players_json: String,
     pub plugin_name: String,
     limits: StoreLimits,
@@ -94,7 +123,10 @@ impl WasmHostData {
             actions: Vec::new(),
             cached_time: 0,
             cached_tick: 0,
+            cached_tps: 0.0,
+            cached_mspt: 0.0,
             cached_is_raining: false,
+            cached_world_flags: Vec::new(),
             cached_players_json: "[]".to_string(),
             plugin_name,
             limits: StoreLimitsBuilder::new()
@@ -104,6 +136,15 @@ impl WasmHostData {
     }
 
     // Methods called by host functions:
+    /// JSON of an online player, looked up in the cached player list.
+    pub fn get_player_json(&self, name: &str) -> Option<String> {
+        let players: Vec<serde_json::Value> =
+            serde_json::from_str(&self.cached_players_json).unwrap_or_default();
+        let player = players
+            .iter()
+            .find(|p| p.get("name").and_then(|n| n.as_str()) == Some(name))?;
+        serde_json::to_string(player).ok()
+    }
     pub fn send_message(&mut self, player_name: String, message: String) {
         self.actions.push(WasmAction::SendMessage {
             player_name,
@@ -140,6 +181,19 @@ impl WasmHostData {
     pub fn set_time(&mut self, time: i64) {
         self.actions.push(WasmAction::SetTime { time });
     }
+    pub fn get_world_flag(&self, dimension: &str, flag: &str) -> Option<bool> {
+        self.cached_world_flags
+            .iter()
+            .find(|(d, f, _)| *d == dimension && *f == flag)
+            .map(|(_, _, value)| *value)
+    }
+    pub fn set_world_flag(&mut self, dimension: String, flag: String, value: bool) {
+        self.actions.push(WasmAction::SetWorldFlag {
+            dimension,
+            flag,
+            value,
+        });
+    }
     pub fn spawn_mob(&mut self, mob_type: String, x: f32, y: f32, z: f32) {
         self.actions
             .push(WasmAction::SpawnMob { mob_type, x, y, z });
@@ -147,6 +201,22 @@ impl WasmHostData {
     pub fn remove_mob(&mut self, runtime_id: u64) {
         self.actions.push(WasmAction::RemoveMob { runtime_id });
     }
+    pub fn trigger_entity_event(&mut self, runtime_id: u64, event: String) {
+        self.actions
+            .push(WasmAction::TriggerEntityEvent { runtime_id, event });
+    }
+    pub fn send_script_event(&mut self, id: String, payload: String) {
+        self.actions
+            .push(WasmAction::SendScriptEvent { id, payload });
+    }
+    pub fn show_form(&mut self, player_name: String, form_id: u32, json: String, next: bool) {
+        self.actions.push(WasmAction::ShowForm {
+            player_name,
+            form_id,
+            json,
+            next,
+        });
+    }
     pub fn log(&mut self, level: LogLevel, message: String) {
         let prefixed = format!("[{}] {}", self.plugin_name, message);
         self.actions.push(WasmAction::Log {
@@ -174,6 +244,94 @@ impl WasmHostData {
     pub fn cancel_task(&mut self, task_id: u32) {
         self.actions.push(WasmAction::CancelTask { task_id });
     }
+
+    /// Cache snapshot data from the ServerApi before a guest call.
+    pub(crate) fn sync_snapshot(&mut self, api: &dyn ServerApi) {
+        self.cached_time = api.get_time();
+        self.cached_tick = api.get_tick();
+        self.cached_tps = api.get_tps();
+        self.cached_mspt = api.get_mspt();
+        self.cached_is_raining = api.is_raining();
+        self.cached_world_flags = WORLD_FLAG_DIMENSIONS
+            .iter()
+            .flat_map(|d| WORLD_FLAGS.iter().map(move |f| (*d, *f)))
+            .filter_map(|(d, f)| api.get_world_flag(d, f).map(|value| (d, f, value)))
+            .collect();
+        let players = api.online_players();
+        self.cached_players_json =
+            serde_json::to_string(&players).unwrap_or_else(|_| "[]".to_string());
+        self.actions.clear();
+    }
+
+    /// Drain accumulated WasmActions and forward them through the ServerApi.
+    pub(crate) fn flush_actions(&mut self, api: &mut dyn ServerApi) {
+        let actions: Vec<WasmAction> = std::mem::take(&mut self.actions);
+        let plugin_name = self.plugin_name.clone();
+        for action in actions {
+            match action {
+                WasmAction::SendMessage {
+                    player_name,
+                    message,
+                } => api.send_message(&player_name, &message),
+                WasmAction::BroadcastMessage { message } => api.broadcast_message(&message),
+                WasmAction::KickPlayer {
+                    player_name,
+                    reason,
+                } => api.kick_player(&player_name, &reason),
+                WasmAction::SetPlayerHealth {
+                    player_name,
+                    health,
+                } => api.set_player_health(&player_name, health),
+                WasmAction::SetPlayerFood { player_name, food } => {
+                    api.set_player_food(&player_name, food)
+                }
+                WasmAction::TeleportPlayer {
+                    player_name,
+                    x,
+                    y,
+                    z,
+                } => api.teleport_player(&player_name, x, y, z),
+                WasmAction::SetTime { time } => api.set_time(time),
+                WasmAction::SetWorldFlag {
+                    dimension,
+                    flag,
+                    value,
+                } => api.set_world_flag(&dimension, &flag, value),
+                WasmAction::SpawnMob { mob_type, x, y, z } => api.spawn_mob(&mob_type, x, y, z),
+                WasmAction::RemoveMob { runtime_id } => api.remove_mob(runtime_id),
+                WasmAction::TriggerEntityEvent { runtime_id, event } => {
+                    api.trigger_entity_event(runtime_id, &event)
+                }
+                WasmAction::SendScriptEvent { id, payload } => api.send_script_event(&id, &payload),
+                WasmAction::ShowForm {
+                    player_name,
+                    form_id,
+                    json,
+                    next,
+                } => match (next, form_type_of(&json)) {
+                    (true, _) => api.show_next_form(&player_name, form_id, &json),
+                    (false, Some("simple")) => api.show_simple_form(&player_name, form_id, &json),
+                    (false, Some("modal")) => api.show_modal_form(&player_name, form_id, &json),
+                    (false, Some("custom")) => api.show_custom_form(&player_name, form_id, &json),
+                    (false, _) => warn!("[{plugin_name}] show_form: unknown form type"),
+                },
+                WasmAction::Log { level, message } => api.log(level, &message),
+                WasmAction::RegisterCommand { name, description } => {
+                    api.register_command(&name, &description, &plugin_name)
+                }
+                WasmAction::ScheduleDelayed {
+                    delay_ticks,
+                    task_id,
+                } => api.schedule_delayed(&plugin_name, delay_ticks, task_id),
+                WasmAction::ScheduleRepeating {
+                    delay_ticks,
+                    interval_ticks,
+                    task_id,
+                } => api.schedule_repeating(&plugin_name, delay_ticks, interval_ticks, task_id),
+                WasmAction::CancelTask { task_id } => api.cancel_task(&plugin_name, task_id),
+            }
+        }
+    }
 }
 
 // ─── WasmPlugin ─────────────────────────────────────────────────────────────
@@ -231,71 +389,10 @@ impl WasmPlugin {
         String::from_utf8(mem[p + 4..p + 4 + len].to_vec()).ok()
     }
 
-    /// Cache snapshot data from the ServerApi into WasmHostData before a guest call.
-    fn sync_snapshot(&mut self, api: &dyn ServerApi) {
-        let data = self.store.data_mut();
-        data.cached_time = api.get_time();
-        data.cached_tick = api.get_tick();
-        data.cached_is_raining = api.is_raining();
-        let players = api.online_players();
-        data.cached_players_json =
-            serde_json::to_string(&players).unwrap_or_else(|_| "[]".to_string());
-        data.actions.clear();
-    }
-
     /// Reset fuel for a new guest call.
     fn refuel(&mut self, fuel: u64) {
         let _ = self.store.set_fuel(fuel);
     }
-
-    /// Drain accumulated WasmActions and forward them through the ServerApi.
-    fn flush_actions(&mut self, api: &mut dyn ServerApi) {
-        let actions: Vec<WasmAction> = std::mem::take(&mut self.store.data_mut().actions);
-        let plugin_name = self.manifest.name.clone();
-        for action in actions {
-            match action {
-                WasmAction::SendMessage {
-                    player_name,
-                    message,
-                } => api.send_message(&player_name, &message),
-                WasmAction::BroadcastMessage { message } => api.broadcast_message(&message),
-                WasmAction::KickPlayer {
-                    player_name,
-                    reason,
-                } => api.kick_player(&player_name, &reason),
-                WasmAction::SetPlayerHealth {
-                    player_name,
-                    health,
-                } => api.set_player_health(&player_name, health),
-                WasmAction::SetPlayerFood { player_name, food } => {
-                    api.set_player_food(&player_name, food)
-                }
-                WasmAction::TeleportPlayer {
-                    player_name,
-                    x,
-                    y,
-                    z,
-                } => api.teleport_player(&player_name, x, y, z),
-                WasmAction::SetTime { time } => api.set_time(time),
-                WasmAction::SpawnMob { mob_type, x, y, z } => api.spawn_mob(&mob_type, x, y, z),
-                WasmAction::RemoveMob { runtime_id } => api.remove_mob(runtime_id),
-                WasmAction::Log { level, message } => api.log(level, &message),
-                WasmAction::RegisterCommand { name, description } => {
-                    api.register_command(&name, &description, &plugin_name)
-                }
-                WasmAction::ScheduleDelayed {
-                    delay_ticks,
-                    task_id,
-                } => api.schedule_delayed(&plugin_name, delay_ticks, task_id),
-                WasmAction::ScheduleRepeating {
-                    delay_ticks,
-                    interval_ticks,
-                    task_id,
-                } => api.schedule_repeating(&plugin_name, delay_ticks, interval_ticks, task_id),
-                WasmAction::CancelTask { task_id } => api.cancel_task(&plugin_name, task_id),
-            }
-        }
-    }
 }
 
 // ─── Plugin trait implementation ────────────────────────────────────────────
@@ -311,13 +408,13 @@ impl Plugin for WasmPlugin {
     }
 
     fn on_enable(&mut self, api: &mut dyn ServerApi) {
-        self.sync_snapshot(api);
+        self.store.data_mut().sync_snapshot(api);
         self.refuel(self.manifest.fuel_on_enable);
         if let Err(e) = self.fn_on_enable.call(&mut self.store, ()) {
             error!("[wasm:{}] on_enable trapped: {e}", self.manifest.name);
             return;
         }
-        self.flush_actions(api);
+        self.store.data_mut().flush_actions(api);
     }
 
     fn on_disable(&mut self) {
@@ -328,7 +425,7 @@ impl Plugin for WasmPlugin {
     }
 
     fn on_event(&mut self, event: &PluginEvent, api: &mut dyn ServerApi) -> EventResult {
-        self.sync_snapshot(api);
+        self.store.data_mut().sync_snapshot(api);
         self.refuel(self.manifest.fuel_per_event);
         let json = match serde_json::to_string(event) {
             Ok(j) => j,
@@ -363,12 +460,12 @@ impl Plugin for WasmPlugin {
                 EventResult::Continue
             }
         };
-        self.flush_actions(api);
+        self.store.data_mut().flush_actions(api);
         result
     }
 
     fn on_task(&mut self, task_id: u32, api: &mut dyn ServerApi) {
-        self.sync_snapshot(api);
+        self.store.data_mut().sync_snapshot(api);
         self.refuel(self.manifest.fuel_per_task);
         if let Err(e) = self.fn_on_task.call(&mut self.store, task_id as i32) {
             warn!(
@@ -376,7 +473,7 @@ impl Plugin for WasmPlugin {
                 self.manifest.name
             );
         }
-        self.flush_actions(api);
+        self.store.data_mut().flush_actions(api);
     }
 
     fn on_command(
@@ -386,7 +483,7 @@ impl Plugin for WasmPlugin {
         sender: &str,
         api: &mut dyn ServerApi,
     ) -> Option<String> {
-        self.sync_snapshot(api);
+        self.store.data_mut().sync_snapshot(api);
         self.refuel(self.manifest.fuel_per_command);
         let input = serde_json::json!({
             "command": command,
@@ -402,11 +499,11 @@ impl Plugin for WasmPlugin {
             Ok(p) => p,
             Err(e) => {
                 warn!("[wasm:{}] on_command trapped: {e}", self.manifest.name);
-                self.flush_actions(api);
+                self.store.data_mut().flush_actions(api);
                 return None;
             }
         };
-        self.flush_actions(api);
+        self.store.data_mut().flush_actions(api);
         self.read_length_prefixed(result_ptr)
     }
 
@@ -445,8 +542,9 @@ pub fn create_engine() -> Engine {
     Engine::new(&config).expect("failed to create wasmtime engine")
 }
 
-/// Load a single WASM plugin from a directory containing plugin.toml.
-fn load_single_plugin(dir: &Path, engine: &Engine) -> Result<WasmPlugin, String> {
+/// Load a single WASM plugin from a directory containing plugin.toml, as a
+/// component or a raw module depending on its binary.
+fn load_single_plugin(dir: &Path, engine: &Engine) -> Result<Box<dyn Plugin>, String> {
     let manifest_path = dir.join("plugin.toml");
     let toml_content =
         std::fs::read_to_string(&manifest_path).map_err(|e| format!("read plugin.toml: {e}"))?;
@@ -454,8 +552,17 @@ fn load_single_plugin(dir: &Path, engine: &Engine) -> Result<WasmPlugin, String>
         WasmPluginManifest::parse(&toml_content).map_err(|e| format!("parse plugin.toml: {e}"))?;
 
     let wasm_path = dir.join(&manifest.wasm_file);
-    let module = Module::from_file(engine, &wasm_path)
-        .map_err(|e| format!("load {}: {e}", manifest.wasm_file))?;
+    let bytes =
+        std::fs::read(&wasm_path).map_err(|e| format!("read {}: {e}", manifest.wasm_file))?;
+    if component::is_component(&bytes) {
+        let component = wasmtime::component::Component::new(engine, &bytes)
+            .map_err(|e| format!("load {}: {e}", manifest.wasm_file))?;
+        return Ok(Box::new(ComponentPlugin::new(
+            engine, &component, manifest,
+        )?));
+    }
+    let module =
+        Module::new(engine, &bytes).map_err(|e| format!("load {}: {e}", manifest.wasm_file))?;
 
     let host_data = WasmHostData::new(manifest.name.clone(), manifest.max_memory_pages);
     let mut store = Store::new(engine, host_data);
@@ -503,7 +610,7 @@ fn load_single_plugin(dir: &Path, engine: &Engine) -> Result<WasmPlugin, String>
         .get_typed_func::<(i32, i32), ()>(&mut store, "__load_config")
         .map_err(|e| format!("missing __load_config: {e}"))?;
 
-    Ok(WasmPlugin {
+    Ok(Box::new(WasmPlugin {
         manifest,
         store,
         instance,
@@ -518,7 +625,7 @@ fn load_single_plugin(dir: &Path, engine: &Engine) -> Result<WasmPlugin, String>
         fn_plugin_info,
         fn_default_config,
         fn_load_config,
-    })
+    }))
 }
 
 /// Scan a directory for WASM plugin subdirectories and load all valid plugins.
@@ -540,11 +647,9 @@ pub fn load_wasm_plugins(plugins_dir: &Path, engine: &Engine) -> Vec<Box<dyn Plu
         }
         match load_single_plugin(&path, engine) {
             Ok(plugin) => {
-                info!(
-                    "Loaded WASM plugin: {} v{}",
-                    plugin.manifest.name, plugin.manifest.version
-                );
-                plugins.push(Box::new(plugin));
+                let info = plugin.info();
+                info!("Loaded WASM plugin: {} v{}", info.name, info.version);
+                plugins.push(plugin);
             }
             Err(e) => {
                 error!("Failed to load WASM plugin from {}: {e}", path.display());
@@ -576,6 +681,21 @@ mod tests {
         assert_eq!(hd.actions.len(), 3);
     }
 
+    #[test]
+    fn host_data_world_flags() {
+        let mut hd = WasmHostData::new("test".to_string(), 256);
+        hd.cached_world_flags = vec![("overworld", "pvp", false), ("nether", "pvp", true)];
+        assert_eq!(hd.get_world_flag("overworld", "pvp"), Some(false));
+        assert_eq!(hd.get_world_flag("nether", "pvp"), Some(true));
+        assert_eq!(hd.get_world_flag("end", "fly"), None);
+        hd.set_world_flag("end".into(), "hunger".into(), false);
+        assert!(matches!(
+            &hd.actions[0],
+            WasmAction::SetWorldFlag { dimension, flag, value: false }
+                if dimension == "end" && flag == "hunger"
+        ));
+    }
+
     #[test]
     fn load_empty_dir() {
         let dir = std::env::temp_dir().join("mc-rs-wasm-test-empty");
@@ -744,6 +864,104 @@ mod tests {
         // Should not panic.
     }
 
+    /// Component broadcasting on enable, cancelling every event and
+    /// answering every command with "pong".
+    const COMPONENT_WAT: &str = r#"
+        (component
+            (import "mcrs:plugin/host@0.1.0" (instance $host
+                (export "broadcast-message" (func (param "message" string)))
+            ))
+
+            (core module $libc
+                (memory (export "memory") 1)
+                (global $heap (mut i32) (i32.const 1024))
+                ;; Bump allocator: (old_ptr, old_size, align, new_size)
+                (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                    (local $ptr i32)
+                    (local.set $ptr (i32.and
+                        (i32.add (global.get $heap) (i32.sub (local.get 2) (i32.const 1)))
+                        (i32.sub (i32.const 0) (local.get 2))))
+                    (global.set $heap (i32.add (local.get $ptr) (local.get 3)))
+                    (local.get $ptr)
+                )
+            )
+            (core instance $libc (instantiate $libc))
+            (core func $broadcast
+                (canon lower (func $host "broadcast-message") (memory $libc "memory")))
+
+            (core module $plugin
+                (import "libc" "memory" (memory 1))
+                (import "host" "broadcast-message" (func $broadcast (param i32 i32)))
+                (data (i32.const 16) "enabled")
+                ;; Some("pong"): discriminant, pointer, length
+                (data (i32.const 64) "\01\00\00\00\50\00\00\00\04\00\00\00")
+                (data (i32.const 80) "pong")
+                (func (export "on-enable") (call $broadcast (i32.const 16) (i32.const 7)))
+                (func (export "on-disable"))
+                (func (export "on-event") (param i32 i32) (result i32) (i32.const 1))
+                (func (export "on-task") (param i32))
+                (func (export "on-command") (param i32 i32 i32 i32 i32 i32) (result i32)
+                    (i32.const 64))
+                (func (export "load-config") (param i32 i32))
+            )
+            (core instance $plugin (instantiate $plugin
+                (with "libc" (instance $libc))
+                (with "host" (instance (export "broadcast-message" (func $broadcast))))
+            ))
+
+            (func (export "on-enable") (canon lift (core func $plugin "on-enable")))
+            (func (export "on-disable") (canon lift (core func $plugin "on-disable")))
+            (func (export "on-event") (param "event" string) (result bool)
+                (canon lift (core func $plugin "on-event")
+                    (memory $libc "memory") (realloc (func $libc "realloc"))))
+            (func (export "on-task") (param "task-id" u32)
+                (canon lift (core func $plugin "on-task")))
+            (func (export "on-command")
+                (param "command" string) (param "args" (list string)) (param "sender" string)
+                (result (option string))
+                (canon lift (core func $plugin "on-command")
+                    (memory $libc "memory") (realloc (func $libc "realloc"))))
+            (func (export "load-config") (param "config" string)
+                (canon lift (core func $plugin "load-config")
+                    (memory $libc "memory") (realloc (func $libc "realloc"))))
+        )
+    "#;
+
+    #[test]
+    fn component_plugin_uses_typed_bindings() {
+        let engine = create_engine();
+        let component = wasmtime::component::Component::new(&engine, COMPONENT_WAT)
+            .expect("component WAT compilation failed");
+        let manifest = WasmPluginManifest::parse(
+            "[plugin]\nname = \"comp\"\nversion = \"1.0.0\"\nwasm_file = \"comp.wasm\"\n",
+        )
+        .unwrap();
+        let mut plugin = ComponentPlugin::new(&engine, &component, manifest).unwrap();
+        assert_eq!(plugin.info().name, "comp");
+
+        let mut api = MockApi::new();
+        plugin.on_enable(&mut api);
+        assert_eq!(api.broadcasts, vec!["enabled".to_string()]);
+        assert_eq!(
+            plugin.on_event(&PluginEvent::ServerStarted, &mut api),
+            EventResult::Cancelled
+        );
+        assert_eq!(
+            plugin.on_command("ping", &["a".into()], "Alice", &mut api),
+            Some("pong".to_string())
+        );
+        plugin.on_task(1, &mut api);
+        plugin.load_config(serde_json::json!({ "key": 1 }));
+        plugin.on_disable();
+    }
+
+    #[test]
+    fn components_are_told_from_modules() {
+        assert!(!component::is_component(b"\0asm\x01\0\0\0"));
+        assert!(component::is_component(b"\0asm\x0d\0\x01\0"));
+        assert!(!component::is_component(b"(component)"));
+    }
+
     #[test]
     fn flush_actions_forwards_to_api() {
         let mut hd = WasmHostData::new("test".into(), 256);
@@ -799,6 +1017,9 @@ mod tests {
         fn get_player(&self, _: &str) -> Option<PluginPlayer> {
             None
         }
+        fn get_player_stats(&self, _: &str) -> Option<PluginPlayerStats> {
+            None
+        }
         fn send_message(&mut self, p: &str, m: &str) {
             self.messages.push((p.into(), m.into()));
         }
@@ -809,6 +1030,7 @@ mod tests {
         fn set_player_health(&mut self, _: &str, _: f32) {}
         fn set_player_food(&mut self, _: &str, _: i32) {}
         fn teleport_player(&mut self, _: &str, _: f32, _: f32, _: f32) {}
+        fn transfer_player(&mut self, _: &str, _: &str, _: u16) {}
         fn get_time(&self) -> i64 {
             0
         }
@@ -818,11 +1040,24 @@ mod tests {
         fn is_raining(&self) -> bool {
             false
         }
+        fn get_world_flag(&self, _: &str, _: &str) -> Option<bool> {
+            None
+        }
+        fn set_world_flag(&mut self, _: &str, _: &str, _: bool) {}
         fn spawn_mob(&mut self, _: &str, _: f32, _: f32, _: f32) {}
         fn remove_mob(&mut self, _: u64) {}
+        fn trigger_entity_event(&mut self, _: u64, _: &str) {}
+        fn set_entity_appearance(&mut self, _: u64, _: PluginEntityAppearance) {}
+        fn send_script_event(&mut self, _: &str, _: &str) {}
         fn get_tick(&self) -> u64 {
             0
         }
+        fn get_tps(&self) -> f64 {
+            20.0
+        }
+        fn get_mspt(&self) -> f64 {
+            0.0
+        }
         fn log(&self, _: LogLevel, _: &str) {}
         fn schedule_delayed(&mut self, _: &str, _: u64, _: u32) {}
         fn schedule_repeating(&mut self, _: &str, _: u64, _: u64, _: u32) {}
@@ -831,5 +1066,42 @@ mod tests {
         fn show_simple_form(&mut self, _: &str, _: u32, _: &str) {}
         fn show_modal_form(&mut self, _: &str, _: u32, _: &str) {}
         fn show_custom_form(&mut self, _: &str, _: u32, _: &str) {}
+        fn show_next_form(&mut self, _: &str, _: u32, _: &str) {}
+        fn register_kit(&mut self, _: &str, _: Vec<PluginKitItem>, _: u64) {}
+        fn currency_name(&self) -> String {
+            "coins".into()
+        }
+        fn get_balance(&self, _: &str) -> Option<u64> {
+            None
+        }
+        fn deposit(&mut self, _: &str, _: u64, _: &str) -> bool {
+            false
+        }
+        fn withdraw(&mut self, _: &str, _: u64, _: &str) -> bool {
+            false
+        }
+        fn transfer_money(&mut self, _: &str, _: &str, _: u64, _: &str) -> bool {
+            false
+        }
+        fn create_arena(&mut self, _: PluginArena) {}
+        fn join_arena(&mut self, _: &str, _: &str) {}
+        fn leave_arena(&mut self, _: &str) {}
+        fn set_arena_score(&mut self, _: &str, _: &str, _: i32) {}
+        fn end_arena(&mut self, _: &str, _: Option<&str>) {}
+        fn player_arena(&self, _: &str) -> Option<String> {
+            None
+        }
+        fn create_hologram(&mut self, _: &str, _: f32, _: f32, _: f32, _: Vec<String>) {}
+        fn set_hologram_lines(&mut self, _: &str, _: Vec<String>) {}
+        fn remove_hologram(&mut self, _: &str) {}
+        fn play_cutscene(&mut self, _: &str, _: PluginCutscene) {}
+        fn stop_cutscene(&mut self, _: &str) {}
+        fn push_fog(&mut self, _: &str, _: &str, _: &str) {}
+        fn remove_fog(&mut self, _: &str, _: &str) {}
+        fn set_client_game_rule(&mut self, _: &str, _: &str, _: bool) {}
+        fn set_input_permission(&mut self, _: &str, _: &str, _: bool) {}
+        fn accepted_packs(&self, _: &str) -> Option<Vec<String>> {
+            None
+        }
     }
 }
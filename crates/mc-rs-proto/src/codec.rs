@@ -8,6 +8,15 @@ use crate::types::VarUInt32;
 /// Encode a value onto a buffer.
 pub trait ProtoEncode {
     fn proto_encode(&self, buf: &mut impl BufMut);
+
+    /// Encode for a client speaking `protocol`. Packets whose layout differs
+    /// between [`SUPPORTED_VERSIONS`](crate::packets::SUPPORTED_VERSIONS)
+    /// override this; `proto_encode` always writes the current
+    /// [`PROTOCOL_VERSION`](crate::packets::PROTOCOL_VERSION) layout.
+    fn proto_encode_versioned(&self, buf: &mut impl BufMut, protocol: i32) {
+        let _ = protocol;
+        self.proto_encode(buf);
+    }
 }
 
 /// Decode a value from a buffer.
@@ -0,0 +1,417 @@
+//! Entity metadata: the properties sent with AddActor, AddPlayer and
+//! SetActorData, such as flags, name tag and scale.
+//!
+//! Each entry is a key, the type of its value and the value. Build them
+//! with [`MetadataBuilder`] rather than by hand.
+
+use bytes::{Buf, BufMut};
+
+use crate::codec::{read_string, write_string, ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
+use crate::types::{VarInt, VarLong, VarUInt32};
+
+/// Metadata keys.
+pub mod key {
+    pub const FLAGS: u32 = 0;
+    pub const VARIANT: u32 = 2;
+    pub const COLOR: u32 = 3;
+    pub const NAMETAG: u32 = 4;
+    pub const AIR: u32 = 7;
+    pub const SCALE: u32 = 23;
+    pub const BOUNDING_BOX_WIDTH: u32 = 38;
+    pub const BOUNDING_BOX_HEIGHT: u32 = 39;
+}
+
+/// Entity metadata value types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataValue {
+    Byte(u8),
+    Short(i16),
+    Int(i32),
+    Float(f32),
+    String(String),
+    Long(i64),
+}
+
+impl MetadataValue {
+    /// Data type ID sent before the value.
+    pub fn type_id(&self) -> u32 {
+        match self {
+            MetadataValue::Byte(_) => 0,
+            MetadataValue::Short(_) => 1,
+            MetadataValue::Int(_) => 2,
+            MetadataValue::Float(_) => 3,
+            MetadataValue::String(_) => 4,
+            MetadataValue::Long(_) => 7,
+        }
+    }
+}
+
+/// A single entity metadata entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityMetadataEntry {
+    /// Metadata key, one of [`key`].
+    pub key: u32,
+    /// Data type ID (0=byte, 1=short, 2=int, 3=float, 4=string, 7=long).
+    pub data_type: u32,
+    /// The value.
+    pub value: MetadataValue,
+}
+
+impl EntityMetadataEntry {
+    pub fn new(key: u32, value: MetadataValue) -> Self {
+        Self {
+            key,
+            data_type: value.type_id(),
+            value,
+        }
+    }
+}
+
+/// Encode entity metadata entries.
+pub fn encode_entity_metadata(buf: &mut impl BufMut, entries: &[EntityMetadataEntry]) {
+    VarUInt32(entries.len() as u32).proto_encode(buf);
+    for entry in entries {
+        VarUInt32(entry.key).proto_encode(buf);
+        VarUInt32(entry.data_type).proto_encode(buf);
+        match &entry.value {
+            MetadataValue::Byte(v) => buf.put_u8(*v),
+            MetadataValue::Short(v) => buf.put_i16_le(*v),
+            MetadataValue::Int(v) => VarInt(*v).proto_encode(buf),
+            MetadataValue::Float(v) => buf.put_f32_le(*v),
+            MetadataValue::String(v) => write_string(buf, v),
+            MetadataValue::Long(v) => VarLong(*v).proto_encode(buf),
+        }
+    }
+}
+
+/// Decode entity metadata entries, as written by [`encode_entity_metadata`].
+pub fn decode_entity_metadata(buf: &mut impl Buf) -> Result<Vec<EntityMetadataEntry>, ProtoError> {
+    let count = VarUInt32::proto_decode(buf)?.0;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let key = VarUInt32::proto_decode(buf)?.0;
+        let data_type = VarUInt32::proto_decode(buf)?.0;
+        let value = match data_type {
+            0 => {
+                ensure_remaining(buf, 1)?;
+                MetadataValue::Byte(buf.get_u8())
+            }
+            1 => {
+                ensure_remaining(buf, 2)?;
+                MetadataValue::Short(buf.get_i16_le())
+            }
+            2 => MetadataValue::Int(VarInt::proto_decode(buf)?.0),
+            3 => {
+                ensure_remaining(buf, 4)?;
+                MetadataValue::Float(buf.get_f32_le())
+            }
+            4 => MetadataValue::String(read_string(buf)?),
+            7 => MetadataValue::Long(VarLong::proto_decode(buf)?.0),
+            other => {
+                return Err(ProtoError::InvalidData(format!(
+                    "unsupported metadata type {other}"
+                )))
+            }
+        };
+        entries.push(EntityMetadataEntry {
+            key,
+            data_type,
+            value,
+        });
+    }
+    Ok(entries)
+}
+
+fn ensure_remaining(buf: &impl Buf, needed: usize) -> Result<(), ProtoError> {
+    if buf.remaining() < needed {
+        Err(ProtoError::BufferTooShort {
+            needed,
+            remaining: buf.remaining(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// The entries of `new` that `old` lacks or holds another value for: what
+/// SetActorData has to send to bring a client from one to the other.
+pub fn metadata_changes(
+    old: &[EntityMetadataEntry],
+    new: &[EntityMetadataEntry],
+) -> Vec<EntityMetadataEntry> {
+    new.iter().filter(|e| !old.contains(e)).cloned().collect()
+}
+
+/// Bits of the [`key::FLAGS`] entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityFlag {
+    OnFire = 0,
+    Sneaking = 1,
+    Riding = 2,
+    Sprinting = 3,
+    UsingItem = 4,
+    Invisible = 5,
+    Tempted = 6,
+    InLove = 7,
+    Saddled = 8,
+    Powered = 9,
+    Ignited = 10,
+    Baby = 11,
+    Converting = 12,
+    Critical = 13,
+    CanShowNametag = 14,
+    AlwaysShowNametag = 15,
+    NoAi = 16,
+    Silent = 17,
+    WallClimbing = 18,
+    CanClimb = 19,
+    Swimmer = 20,
+    CanFly = 21,
+    Walker = 22,
+    Resting = 23,
+    Sitting = 24,
+    Angry = 25,
+    Interested = 26,
+    Charged = 27,
+    Tamed = 28,
+    Orphaned = 29,
+    Leashed = 30,
+    Sheared = 31,
+    Gliding = 32,
+    Elder = 33,
+    Moving = 34,
+    Breathing = 35,
+    Chested = 36,
+    Stackable = 37,
+    ShowBase = 38,
+    Rearing = 39,
+    Vibrating = 40,
+    Idling = 41,
+    EvokerSpell = 42,
+    ChargeAttack = 43,
+    WasdControlled = 44,
+    CanPowerJump = 45,
+    CanDash = 46,
+    Linger = 47,
+    HasCollision = 48,
+    AffectedByGravity = 49,
+    FireImmune = 50,
+    Dancing = 51,
+    Enchanted = 52,
+    ShowTridentRope = 53,
+    ContainerPrivate = 54,
+    Transforming = 55,
+    SpinAttack = 56,
+    Swimming = 57,
+}
+
+impl EntityFlag {
+    /// The flag's bit in the [`key::FLAGS`] value.
+    pub fn bit(self) -> i64 {
+        1 << self as u8
+    }
+}
+
+/// Flags plugins may set, by name.
+pub const ENTITY_FLAGS: [(&str, EntityFlag); 20] = [
+    ("on_fire", EntityFlag::OnFire),
+    ("sneaking", EntityFlag::Sneaking),
+    ("sprinting", EntityFlag::Sprinting),
+    ("invisible", EntityFlag::Invisible),
+    ("in_love", EntityFlag::InLove),
+    ("saddled", EntityFlag::Saddled),
+    ("powered", EntityFlag::Powered),
+    ("ignited", EntityFlag::Ignited),
+    ("baby", EntityFlag::Baby),
+    ("can_show_nametag", EntityFlag::CanShowNametag),
+    ("always_show_nametag", EntityFlag::AlwaysShowNametag),
+    ("no_ai", EntityFlag::NoAi),
+    ("silent", EntityFlag::Silent),
+    ("sitting", EntityFlag::Sitting),
+    ("angry", EntityFlag::Angry),
+    ("charged", EntityFlag::Charged),
+    ("tamed", EntityFlag::Tamed),
+    ("sheared", EntityFlag::Sheared),
+    ("gliding", EntityFlag::Gliding),
+    ("swimming", EntityFlag::Swimming),
+];
+
+/// A flag by name.
+pub fn entity_flag(name: &str) -> Option<EntityFlag> {
+    ENTITY_FLAGS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, flag)| flag)
+}
+
+/// Builds a list of metadata entries, one per key: setting a key again
+/// replaces its value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetadataBuilder {
+    entries: Vec<EntityMetadataEntry>,
+}
+
+impl MetadataBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from existing entries.
+    pub fn from_entries(entries: Vec<EntityMetadataEntry>) -> Self {
+        entries
+            .into_iter()
+            .fold(Self::new(), |builder, e| builder.set(e.key, e.value))
+    }
+
+    /// Set an entry.
+    pub fn set(mut self, key: u32, value: MetadataValue) -> Self {
+        let entry = EntityMetadataEntry::new(key, value);
+        match self.entries.iter_mut().find(|e| e.key == key) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+        self
+    }
+
+    /// The flags set so far.
+    pub fn current_flags(&self) -> i64 {
+        match self.entries.iter().find(|e| e.key == key::FLAGS) {
+            Some(EntityMetadataEntry {
+                value: MetadataValue::Long(flags),
+                ..
+            }) => *flags,
+            _ => 0,
+        }
+    }
+
+    /// Set all flags at once.
+    pub fn flags(self, flags: i64) -> Self {
+        self.set(key::FLAGS, MetadataValue::Long(flags))
+    }
+
+    /// Turn one flag on or off, keeping the others.
+    pub fn flag(self, flag: EntityFlag, on: bool) -> Self {
+        let flags = self.current_flags();
+        self.flags(if on {
+            flags | flag.bit()
+        } else {
+            flags & !flag.bit()
+        })
+    }
+
+    pub fn nametag(self, name: impl Into<String>) -> Self {
+        self.set(key::NAMETAG, MetadataValue::String(name.into()))
+    }
+
+    pub fn scale(self, scale: f32) -> Self {
+        self.set(key::SCALE, MetadataValue::Float(scale))
+    }
+
+    pub fn bounding_box(self, width: f32, height: f32) -> Self {
+        self.set(key::BOUNDING_BOX_WIDTH, MetadataValue::Float(width))
+            .set(key::BOUNDING_BOX_HEIGHT, MetadataValue::Float(height))
+    }
+
+    /// Air left, in ticks.
+    pub fn air(self, ticks: i16) -> Self {
+        self.set(key::AIR, MetadataValue::Short(ticks))
+    }
+
+    /// Mob variant, such as a cat's breed.
+    pub fn variant(self, variant: i32) -> Self {
+        self.set(key::VARIANT, MetadataValue::Int(variant))
+    }
+
+    /// Mob color, such as a sheep's wool.
+    pub fn color(self, color: u8) -> Self {
+        self.set(key::COLOR, MetadataValue::Byte(color))
+    }
+
+    pub fn build(self) -> Vec<EntityMetadataEntry> {
+        self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn builder_keeps_one_entry_per_key() {
+        let entries = MetadataBuilder::new()
+            .flag(EntityFlag::Invisible, true)
+            .scale(1.0)
+            .flag(EntityFlag::AlwaysShowNametag, true)
+            .flag(EntityFlag::Invisible, false)
+            .scale(0.5)
+            .build();
+        assert_eq!(
+            entries,
+            [
+                EntityMetadataEntry::new(key::FLAGS, MetadataValue::Long(1 << 15)),
+                EntityMetadataEntry::new(key::SCALE, MetadataValue::Float(0.5)),
+            ]
+        );
+        assert_eq!(entries[1].data_type, 3);
+    }
+
+    #[test]
+    fn from_entries_then_override() {
+        let base = MetadataBuilder::new().flags(1).nametag("Zombie").build();
+        let entries = MetadataBuilder::from_entries(base)
+            .flag(EntityFlag::Baby, true)
+            .variant(2)
+            .build();
+        assert_eq!(entries[0].value, MetadataValue::Long(1 | (1 << 11)));
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entity_flag("baby"), Some(EntityFlag::Baby));
+        assert_eq!(entity_flag("wobbly"), None);
+    }
+
+    #[test]
+    fn changes_hold_only_new_values() {
+        let old = MetadataBuilder::new()
+            .flags(0)
+            .nametag("Steve")
+            .scale(1.0)
+            .build();
+        let new = MetadataBuilder::from_entries(old.clone())
+            .flag(EntityFlag::Sneaking, true)
+            .air(200)
+            .build();
+        assert_eq!(
+            metadata_changes(&old, &new),
+            [
+                EntityMetadataEntry::new(key::FLAGS, MetadataValue::Long(1 << 1)),
+                EntityMetadataEntry::new(key::AIR, MetadataValue::Short(200)),
+            ]
+        );
+        assert!(metadata_changes(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn encode_entries() {
+        let entries = MetadataBuilder::new().nametag("Hi").air(300).build();
+        let mut buf = BytesMut::new();
+        encode_entity_metadata(&mut buf, &entries);
+        // count, key 4 type 4 "Hi", key 7 type 1 300
+        assert_eq!(&buf[..], &[2, 4, 4, 2, b'H', b'i', 7, 1, 0x2C, 0x01]);
+    }
+
+    #[test]
+    fn decode_roundtrip() {
+        let entries = MetadataBuilder::new()
+            .flag(EntityFlag::OnFire, true)
+            .nametag("Zombie")
+            .scale(0.5)
+            .variant(-3)
+            .color(14)
+            .air(120)
+            .build();
+        let mut buf = BytesMut::new();
+        encode_entity_metadata(&mut buf, &entries);
+        assert_eq!(decode_entity_metadata(&mut buf.freeze()).unwrap(), entries);
+    }
+}
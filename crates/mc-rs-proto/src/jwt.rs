@@ -67,6 +67,17 @@ pub fn decode_jwt_unverified(token: &str) -> Result<(JwtHeader, serde_json::Valu
     Ok((header, payload))
 }
 
+/// Encode claims as a JWT with no real signature, as offline-mode clients
+/// (bots, test clients) send them. Only [`decode_jwt_unverified`] accepts it.
+pub fn encode_jwt_unsigned(claims: &serde_json::Value) -> String {
+    let header = serde_json::json!({ "alg": "ES384", "x5u": "" });
+    format!(
+        "{}.{}.unsigned",
+        URL_SAFE_NO_PAD.encode(header.to_string()),
+        URL_SAFE_NO_PAD.encode(claims.to_string())
+    )
+}
+
 /// Extract player identity from the JWT chain (last JWT with `extraData`).
 pub fn extract_login_data(chain: &[String]) -> Result<LoginData, ProtoError> {
     if chain.is_empty() {
@@ -417,4 +428,14 @@ mod tests {
         assert_eq!(data.display_name, "NoXuid");
         assert_eq!(data.xuid, ""); // defaults to empty
     }
+
+    #[test]
+    fn unsigned_jwt_roundtrip() {
+        let jwt = encode_jwt_unsigned(&sample_identity_payload());
+        let (header, payload) = decode_jwt_unverified(&jwt).unwrap();
+        assert_eq!(header.alg, "ES384");
+        assert_eq!(payload, sample_identity_payload());
+        let data = extract_login_data(&[jwt]).unwrap();
+        assert_eq!(data.display_name, "TestPlayer");
+    }
 }
@@ -3,8 +3,10 @@
 pub mod batch;
 pub mod codec;
 pub mod compression;
+pub mod entity_metadata;
 pub mod error;
 pub mod item_stack;
 pub mod jwt;
 pub mod packets;
+pub mod protocol;
 pub mod types;
@@ -5,7 +5,7 @@
 use bytes::BufMut;
 
 use crate::codec::{write_string, ProtoEncode};
-use crate::packets::add_player::{encode_entity_metadata, EntityMetadataEntry};
+use crate::entity_metadata::{encode_entity_metadata, EntityMetadataEntry};
 use crate::types::{VarLong, VarUInt32, VarUInt64, Vec3};
 
 /// A single attribute sent with AddActor.
@@ -65,7 +65,7 @@ impl ProtoEncode for AddActor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::packets::add_player::MetadataValue;
+    use crate::entity_metadata::MetadataBuilder;
     use bytes::BytesMut;
 
     #[test]
@@ -129,18 +129,7 @@ mod tests {
             head_yaw: 0.0,
             body_yaw: 0.0,
             attributes: vec![],
-            metadata: vec![
-                EntityMetadataEntry {
-                    key: 0,
-                    data_type: 7,
-                    value: MetadataValue::Long(0),
-                },
-                EntityMetadataEntry {
-                    key: 23,
-                    data_type: 3,
-                    value: MetadataValue::Float(1.0),
-                },
-            ],
+            metadata: MetadataBuilder::new().flags(0).scale(1.0).build(),
         };
         let mut buf = BytesMut::new();
         pkt.proto_encode(&mut buf);
@@ -5,28 +5,9 @@
 use bytes::BufMut;
 
 use crate::codec::{write_string, ProtoEncode};
+use crate::entity_metadata::{encode_entity_metadata, EntityMetadataEntry, MetadataBuilder};
 use crate::item_stack::ItemStack;
-use crate::types::{Uuid, VarInt, VarLong, VarUInt32, VarUInt64, Vec3};
-
-/// Entity metadata value types.
-pub enum MetadataValue {
-    Byte(u8),
-    Short(i16),
-    Int(i32),
-    Float(f32),
-    String(String),
-    Long(i64),
-}
-
-/// A single entity metadata entry.
-pub struct EntityMetadataEntry {
-    /// Metadata key (e.g. 0=FLAGS, 4=NAMETAG, 23=SCALE).
-    pub key: u32,
-    /// Data type ID (0=byte, 1=short, 2=int, 3=float, 4=string, 7=long).
-    pub data_type: u32,
-    /// The value.
-    pub value: MetadataValue,
-}
+use crate::types::{Uuid, VarInt, VarUInt32, VarUInt64, Vec3};
 
 /// Spawn a remote player.
 pub struct AddPlayer {
@@ -77,23 +58,6 @@ impl ProtoEncode for AddPlayer {
     }
 }
 
-/// Encode entity metadata entries.
-pub fn encode_entity_metadata(buf: &mut impl BufMut, entries: &[EntityMetadataEntry]) {
-    VarUInt32(entries.len() as u32).proto_encode(buf);
-    for entry in entries {
-        VarUInt32(entry.key).proto_encode(buf);
-        VarUInt32(entry.data_type).proto_encode(buf);
-        match &entry.value {
-            MetadataValue::Byte(v) => buf.put_u8(*v),
-            MetadataValue::Short(v) => buf.put_i16_le(*v),
-            MetadataValue::Int(v) => VarInt(*v).proto_encode(buf),
-            MetadataValue::Float(v) => buf.put_f32_le(*v),
-            MetadataValue::String(v) => write_string(buf, v),
-            MetadataValue::Long(v) => VarLong(*v).proto_encode(buf),
-        }
-    }
-}
-
 /// Encode minimal ability data (one Base layer).
 fn encode_ability_data(buf: &mut impl BufMut, player: &AddPlayer) {
     buf.put_u8(player.command_permission_level);
@@ -120,38 +84,18 @@ fn encode_ability_data(buf: &mut impl BufMut, player: &AddPlayer) {
 
 /// Build default entity metadata for a player.
 pub fn default_player_metadata(display_name: &str) -> Vec<EntityMetadataEntry> {
-    vec![
-        EntityMetadataEntry {
-            key: 0,
-            data_type: 7,                  // i64
-            value: MetadataValue::Long(0), // FLAGS — no special flags
-        },
-        EntityMetadataEntry {
-            key: 4,
-            data_type: 4,                                           // string
-            value: MetadataValue::String(display_name.to_string()), // NAMETAG
-        },
-        EntityMetadataEntry {
-            key: 23,
-            data_type: 3,                     // f32
-            value: MetadataValue::Float(1.0), // SCALE
-        },
-        EntityMetadataEntry {
-            key: 38,
-            data_type: 3,                     // f32
-            value: MetadataValue::Float(0.6), // BOUNDING_BOX_WIDTH
-        },
-        EntityMetadataEntry {
-            key: 39,
-            data_type: 3,                     // f32
-            value: MetadataValue::Float(1.8), // BOUNDING_BOX_HEIGHT
-        },
-    ]
+    MetadataBuilder::new()
+        .flags(0)
+        .nametag(display_name)
+        .scale(1.0)
+        .bounding_box(0.6, 1.8)
+        .build()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::entity_metadata::MetadataValue;
     use bytes::BytesMut;
 
     #[test]
@@ -0,0 +1,252 @@
+//! CameraPresets (0xC6) and CameraInstruction (0x12C) — Server → Client.
+//!
+//! CameraPresets lists the camera presets a client knows; CameraInstruction
+//! moves its camera to one of them by index, eased, or fades the screen.
+//! Optional fields are a bool followed by the value when it is true.
+
+use bytes::BufMut;
+
+use crate::codec::{write_string, ProtoEncode};
+use crate::types::{VarUInt32, Vec2, Vec3};
+
+/// The vanilla presets, in the order [`CameraPresets::vanilla`] sends them.
+pub const VANILLA_CAMERA_PRESETS: [&str; 4] = [
+    "minecraft:free",
+    "minecraft:first_person",
+    "minecraft:third_person",
+    "minecraft:third_person_front",
+];
+
+/// Index of `minecraft:free`, the preset for moving the camera anywhere.
+pub const CAMERA_PRESET_FREE: u32 = 0;
+
+/// Easing functions, in protocol order.
+pub const CAMERA_EASE_TYPES: [&str; 32] = [
+    "linear",
+    "spring",
+    "in_quad",
+    "out_quad",
+    "in_out_quad",
+    "in_cubic",
+    "out_cubic",
+    "in_out_cubic",
+    "in_quart",
+    "out_quart",
+    "in_out_quart",
+    "in_quint",
+    "out_quint",
+    "in_out_quint",
+    "in_sine",
+    "out_sine",
+    "in_out_sine",
+    "in_expo",
+    "out_expo",
+    "in_out_expo",
+    "in_circ",
+    "out_circ",
+    "in_out_circ",
+    "in_bounce",
+    "out_bounce",
+    "in_out_bounce",
+    "in_back",
+    "out_back",
+    "in_out_back",
+    "in_elastic",
+    "out_elastic",
+    "in_out_elastic",
+];
+
+/// The protocol value of an easing function, by name.
+pub fn camera_ease_type(name: &str) -> Option<u8> {
+    CAMERA_EASE_TYPES
+        .iter()
+        .position(|&t| t == name)
+        .map(|i| i as u8)
+}
+
+fn write_optional<B: BufMut, T>(buf: &mut B, value: &Option<T>, write: impl FnOnce(&mut B, &T)) {
+    buf.put_u8(value.is_some() as u8);
+    if let Some(value) = value {
+        write(buf, value);
+    }
+}
+
+/// A camera preset inheriting everything from its parent.
+pub struct CameraPreset {
+    pub name: String,
+    pub parent: String,
+}
+
+/// CameraPresets packet.
+pub struct CameraPresets {
+    pub presets: Vec<CameraPreset>,
+}
+
+impl CameraPresets {
+    /// The vanilla presets, so that instructions can refer to them.
+    pub fn vanilla() -> Self {
+        Self {
+            presets: VANILLA_CAMERA_PRESETS
+                .iter()
+                .map(|name| CameraPreset {
+                    name: name.to_string(),
+                    parent: String::new(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Optional preset settings the server leaves to the parent: position x,
+/// y, z, rotation x, y, rotation speed, snap to target, horizontal and
+/// vertical rotation limits, continue targeting, tracking radius, view
+/// offset, entity offset, radius, min/max yaw limit, audio listener, player
+/// effects, align target and camera forward, aim assist and control scheme.
+const CAMERA_PRESET_OPTIONALS: usize = 21;
+
+impl ProtoEncode for CameraPresets {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt32(self.presets.len() as u32).proto_encode(buf);
+        for preset in &self.presets {
+            write_string(buf, &preset.name);
+            write_string(buf, &preset.parent);
+            for _ in 0..CAMERA_PRESET_OPTIONALS {
+                buf.put_u8(0);
+            }
+        }
+    }
+}
+
+/// How the camera moves to a new position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraEase {
+    /// Index in [`CAMERA_EASE_TYPES`].
+    pub ease_type: u8,
+    /// In seconds.
+    pub duration: f32,
+}
+
+/// Move the camera to a preset, optionally somewhere else than the preset
+/// puts it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraSet {
+    /// Index in the presets sent with [`CameraPresets`].
+    pub preset: u32,
+    pub ease: Option<CameraEase>,
+    pub position: Option<Vec3>,
+    /// Pitch and yaw.
+    pub rotation: Option<Vec2>,
+    /// A point to look at, instead of a rotation.
+    pub facing: Option<Vec3>,
+}
+
+/// Fade the screen to a color and back.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraFade {
+    /// Fade in, hold and fade out, in seconds.
+    pub times: Option<(f32, f32, f32)>,
+    /// Red, green and blue, from 0 to 1.
+    pub color: Option<(f32, f32, f32)>,
+}
+
+/// CameraInstruction packet.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CameraInstruction {
+    pub set: Option<CameraSet>,
+    /// Give the camera back to the player.
+    pub clear: bool,
+    pub fade: Option<CameraFade>,
+}
+
+impl ProtoEncode for CameraInstruction {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        write_optional(buf, &self.set, |buf, set| {
+            buf.put_u32_le(set.preset);
+            write_optional(buf, &set.ease, |buf, ease| {
+                buf.put_u8(ease.ease_type);
+                buf.put_f32_le(ease.duration);
+            });
+            write_optional(buf, &set.position, |buf, p| p.proto_encode(buf));
+            write_optional(buf, &set.rotation, |buf, r| r.proto_encode(buf));
+            write_optional(buf, &set.facing, |buf, f| f.proto_encode(buf));
+            buf.put_u8(0); // view offset
+            buf.put_u8(0); // entity offset
+            buf.put_u8(0); // default
+            buf.put_u8(0); // remove ignore starting values
+        });
+        write_optional(buf, &self.clear.then_some(true), |buf, &c| {
+            buf.put_u8(c as u8)
+        });
+        write_optional(buf, &self.fade, |buf, fade| {
+            write_optional(buf, &fade.times, |buf, &(fade_in, hold, fade_out)| {
+                buf.put_f32_le(fade_in);
+                buf.put_f32_le(hold);
+                buf.put_f32_le(fade_out);
+            });
+            write_optional(buf, &fade.color, |buf, &(r, g, b)| {
+                buf.put_f32_le(r);
+                buf.put_f32_le(g);
+                buf.put_f32_le(b);
+            });
+        });
+        buf.put_u8(0); // target
+        buf.put_u8(0); // remove target
+        buf.put_u8(0); // field of view
+        buf.put_u8(0); // spline
+        buf.put_u8(0); // attach to entity
+        buf.put_u8(0); // detach from entity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn encode_clear() {
+        let pkt = CameraInstruction {
+            clear: true,
+            ..Default::default()
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        assert_eq!(&buf[..], &[0, 1, 1, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn encode_eased_set() {
+        let pkt = CameraInstruction {
+            set: Some(CameraSet {
+                preset: CAMERA_PRESET_FREE,
+                ease: Some(CameraEase {
+                    ease_type: camera_ease_type("in_out_sine").unwrap(),
+                    duration: 2.0,
+                }),
+                position: Some(Vec3::new(1.0, 2.0, 3.0)),
+                rotation: Some(Vec2::new(30.0, 90.0)),
+                facing: None,
+            }),
+            ..Default::default()
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        // set flag, preset, ease flag + type + duration, position, rotation
+        assert_eq!(buf[0], 1);
+        assert_eq!(&buf[1..5], &0u32.to_le_bytes());
+        assert_eq!(&buf[5..7], &[1, 16]);
+        assert_eq!(&buf[7..11], &2.0f32.to_le_bytes());
+        assert_eq!(buf[11], 1);
+        assert_eq!(&buf[12..16], &1.0f32.to_le_bytes());
+        // ...then 4 flags closing the set and 8 absent instructions
+        assert_eq!(buf.len(), 1 + 4 + 1 + 5 + 1 + 12 + 1 + 8 + 1 + 4 + 8);
+    }
+
+    #[test]
+    fn vanilla_presets() {
+        let mut buf = BytesMut::new();
+        CameraPresets::vanilla().proto_encode(&mut buf);
+        assert_eq!(buf[0], 4);
+        assert_eq!(camera_ease_type("nope"), None);
+    }
+}
@@ -1,8 +1,9 @@
 //! ChunkRadiusUpdated (0x46) — Server → Client.
 
-use bytes::BufMut;
+use bytes::{Buf, BufMut};
 
-use crate::codec::ProtoEncode;
+use crate::codec::{ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
 use crate::types::VarInt;
 
 /// Tells the client the accepted chunk render distance.
@@ -17,10 +18,17 @@ impl ProtoEncode for ChunkRadiusUpdated {
     }
 }
 
+impl ProtoDecode for ChunkRadiusUpdated {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        Ok(Self {
+            chunk_radius: VarInt::proto_decode(buf)?.0,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::codec::ProtoDecode;
     use bytes::BytesMut;
 
     #[test]
@@ -0,0 +1,162 @@
+//! ClientboundMapItemData (0x43) — Server → Client.
+//!
+//! Sends the pixels and markers of a filled map.
+
+use bytes::BufMut;
+
+use crate::codec::{write_string, ProtoEncode};
+use crate::types::{BlockPos, VarInt, VarLong, VarUInt32};
+
+/// The packet creates the map on the client (includes `included_in`).
+pub const MAP_UPDATE_INITIALISATION: u32 = 0x08;
+/// The packet carries map markers.
+pub const MAP_UPDATE_DECORATION: u32 = 0x04;
+/// The packet carries pixels.
+pub const MAP_UPDATE_TEXTURE: u32 = 0x02;
+
+/// Decoration type of a player marker (white arrow).
+pub const MAP_DECORATION_PLAYER: u8 = 0;
+/// Decoration type of a white X marker.
+pub const MAP_DECORATION_X: u8 = 4;
+/// Decoration type of a plains village marker.
+pub const MAP_DECORATION_VILLAGE: u8 = 18;
+
+/// A marker drawn on the map.
+#[derive(Debug, Clone)]
+pub struct MapDecoration {
+    pub decoration_type: u8,
+    /// Rotation in 16ths of a full turn.
+    pub rotation: u8,
+    /// Position in half pixels from the map center (-128..127).
+    pub x: i8,
+    pub y: i8,
+    pub label: String,
+    /// Marker color (RGBA, little-endian).
+    pub color: u32,
+}
+
+/// Map data update.
+#[derive(Debug, Clone)]
+pub struct ClientboundMapItemData {
+    pub map_id: i64,
+    /// Combination of the `MAP_UPDATE_*` flags.
+    pub update_flags: u32,
+    pub dimension: u8,
+    pub locked: bool,
+    /// World position of the map's top-left corner.
+    pub origin: BlockPos,
+    /// Maps this update also applies to (initialisation only).
+    pub included_in: Vec<i64>,
+    pub scale: u8,
+    pub decorations: Vec<MapDecoration>,
+    pub width: i32,
+    pub height: i32,
+    pub x_offset: i32,
+    pub y_offset: i32,
+    /// `width * height` pixels (RGBA, little-endian), row by row.
+    pub pixels: Vec<u32>,
+}
+
+impl ProtoEncode for ClientboundMapItemData {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarLong(self.map_id).proto_encode(buf);
+        VarUInt32(self.update_flags).proto_encode(buf);
+        buf.put_u8(self.dimension);
+        buf.put_u8(self.locked as u8);
+        self.origin.proto_encode(buf);
+
+        if self.update_flags & MAP_UPDATE_INITIALISATION != 0 {
+            VarUInt32(self.included_in.len() as u32).proto_encode(buf);
+            for id in &self.included_in {
+                VarLong(*id).proto_encode(buf);
+            }
+        }
+        if self.update_flags
+            & (MAP_UPDATE_INITIALISATION | MAP_UPDATE_DECORATION | MAP_UPDATE_TEXTURE)
+            != 0
+        {
+            buf.put_u8(self.scale);
+        }
+        if self.update_flags & MAP_UPDATE_DECORATION != 0 {
+            // Tracked objects: markers are sent as plain decorations
+            VarUInt32(0).proto_encode(buf);
+            VarUInt32(self.decorations.len() as u32).proto_encode(buf);
+            for d in &self.decorations {
+                buf.put_u8(d.decoration_type);
+                buf.put_u8(d.rotation);
+                buf.put_u8(d.x as u8);
+                buf.put_u8(d.y as u8);
+                write_string(buf, &d.label);
+                VarUInt32(d.color).proto_encode(buf);
+            }
+        }
+        if self.update_flags & MAP_UPDATE_TEXTURE != 0 {
+            VarInt(self.width).proto_encode(buf);
+            VarInt(self.height).proto_encode(buf);
+            VarInt(self.x_offset).proto_encode(buf);
+            VarInt(self.y_offset).proto_encode(buf);
+            VarUInt32(self.pixels.len() as u32).proto_encode(buf);
+            for p in &self.pixels {
+                VarUInt32(*p).proto_encode(buf);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    fn map_data(update_flags: u32) -> ClientboundMapItemData {
+        ClientboundMapItemData {
+            map_id: -3,
+            update_flags,
+            dimension: 0,
+            locked: false,
+            origin: BlockPos::new(-64, 0, -64),
+            included_in: vec![-3],
+            scale: 1,
+            decorations: vec![MapDecoration {
+                decoration_type: MAP_DECORATION_PLAYER,
+                rotation: 8,
+                x: -2,
+                y: 4,
+                label: String::new(),
+                color: 0xFFFF_FFFF,
+            }],
+            width: 1,
+            height: 1,
+            x_offset: 0,
+            y_offset: 0,
+            pixels: vec![0xFF00_00FF],
+        }
+    }
+
+    #[test]
+    fn encode_header_only() {
+        let mut buf = BytesMut::new();
+        map_data(0).proto_encode(&mut buf);
+        // map_id(-3 → zigzag 5), flags, dimension, locked, origin (3 VarInts)
+        assert_eq!(&buf[..4], &[5, 0, 0, 0]);
+        // Origin: three one-byte VarInts
+        assert_eq!(buf.len(), 4 + 3);
+    }
+
+    #[test]
+    fn encode_full_update() {
+        let mut header = BytesMut::new();
+        map_data(0).proto_encode(&mut header);
+        let mut buf = BytesMut::new();
+        map_data(MAP_UPDATE_INITIALISATION | MAP_UPDATE_DECORATION | MAP_UPDATE_TEXTURE)
+            .proto_encode(&mut buf);
+        let rest = &buf[header.len()..];
+        // included_in [-3], scale
+        assert_eq!(&rest[..3], &[1, 5, 1]);
+        // no tracked objects, 1 decoration: type, rotation, x, y, empty label
+        assert_eq!(&rest[3..10], &[0, 1, 0, 8, 0xFE, 4, 0]);
+        // Decoration color, then 1x1 texture at offset 0 with one pixel
+        let color_len = 5;
+        assert_eq!(&rest[10 + color_len..15 + color_len], &[2, 2, 0, 0, 1]);
+    }
+}
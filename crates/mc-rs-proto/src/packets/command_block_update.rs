@@ -0,0 +1,177 @@
+//! CommandBlockUpdate (0x4E) — Client → Server.
+//!
+//! Sent when the player saves the settings of a command block or a command
+//! block minecart in its editing screen.
+
+use bytes::{Buf, BufMut};
+
+use crate::codec::{read_string, write_string, ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
+use crate::types::{BlockPos, VarUInt32, VarUInt64};
+
+/// What is being edited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandBlockTarget {
+    /// A command block in the world.
+    Block {
+        position: BlockPos,
+        /// 0 = impulse, 1 = repeat, 2 = chain.
+        mode: u32,
+        /// `false` = "Always Active".
+        needs_redstone: bool,
+        conditional: bool,
+    },
+    /// A command block minecart.
+    Minecart { runtime_id: u64 },
+}
+
+/// CommandBlockUpdate packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandBlockUpdate {
+    pub target: CommandBlockTarget,
+    pub command: String,
+    pub last_output: String,
+    /// Custom name, used as the sender name of its commands.
+    pub name: String,
+    pub filtered_name: String,
+    pub track_output: bool,
+    /// Ticks between runs of a repeating block, or before a chain block runs.
+    pub tick_delay: i32,
+    pub execute_on_first_tick: bool,
+}
+
+impl ProtoDecode for CommandBlockUpdate {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let target = if read_bool(buf)? {
+            let position = BlockPos::proto_decode(buf)?;
+            let mode = VarUInt32::proto_decode(buf)?.0;
+            let needs_redstone = read_bool(buf)?;
+            let conditional = read_bool(buf)?;
+            CommandBlockTarget::Block {
+                position,
+                mode,
+                needs_redstone,
+                conditional,
+            }
+        } else {
+            CommandBlockTarget::Minecart {
+                runtime_id: VarUInt64::proto_decode(buf)?.0,
+            }
+        };
+        let command = read_string(buf)?;
+        let last_output = read_string(buf)?;
+        let name = read_string(buf)?;
+        let filtered_name = read_string(buf)?;
+        let track_output = read_bool(buf)?;
+        if buf.remaining() < 4 {
+            return Err(ProtoError::BufferTooShort {
+                needed: 4,
+                remaining: buf.remaining(),
+            });
+        }
+        let tick_delay = buf.get_i32_le();
+        let execute_on_first_tick = read_bool(buf)?;
+
+        Ok(Self {
+            target,
+            command,
+            last_output,
+            name,
+            filtered_name,
+            track_output,
+            tick_delay,
+            execute_on_first_tick,
+        })
+    }
+}
+
+impl ProtoEncode for CommandBlockUpdate {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        match &self.target {
+            CommandBlockTarget::Block {
+                position,
+                mode,
+                needs_redstone,
+                conditional,
+            } => {
+                buf.put_u8(1);
+                position.proto_encode(buf);
+                VarUInt32(*mode).proto_encode(buf);
+                buf.put_u8(*needs_redstone as u8);
+                buf.put_u8(*conditional as u8);
+            }
+            CommandBlockTarget::Minecart { runtime_id } => {
+                buf.put_u8(0);
+                VarUInt64(*runtime_id).proto_encode(buf);
+            }
+        }
+        write_string(buf, &self.command);
+        write_string(buf, &self.last_output);
+        write_string(buf, &self.name);
+        write_string(buf, &self.filtered_name);
+        buf.put_u8(self.track_output as u8);
+        buf.put_i32_le(self.tick_delay);
+        buf.put_u8(self.execute_on_first_tick as u8);
+    }
+}
+
+fn read_bool(buf: &mut impl Buf) -> Result<bool, ProtoError> {
+    if buf.remaining() < 1 {
+        return Err(ProtoError::BufferTooShort {
+            needed: 1,
+            remaining: 0,
+        });
+    }
+    Ok(buf.get_u8() != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    fn packet(target: CommandBlockTarget) -> CommandBlockUpdate {
+        CommandBlockUpdate {
+            target,
+            command: "say hi".into(),
+            last_output: String::new(),
+            name: "Greeter".into(),
+            filtered_name: "Greeter".into(),
+            track_output: true,
+            tick_delay: 20,
+            execute_on_first_tick: false,
+        }
+    }
+
+    #[test]
+    fn roundtrip_block() {
+        let pkt = packet(CommandBlockTarget::Block {
+            position: BlockPos::new(-4, 70, 12),
+            mode: 2,
+            needs_redstone: false,
+            conditional: true,
+        });
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let decoded = CommandBlockUpdate::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded, pkt);
+    }
+
+    #[test]
+    fn roundtrip_minecart() {
+        let pkt = packet(CommandBlockTarget::Minecart { runtime_id: 300 });
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let decoded = CommandBlockUpdate::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded, pkt);
+    }
+
+    #[test]
+    fn decode_truncated() {
+        let pkt = packet(CommandBlockTarget::Minecart { runtime_id: 1 });
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let truncated = buf.split_to(buf.len() - 3);
+        assert!(CommandBlockUpdate::proto_decode(&mut truncated.freeze()).is_err());
+    }
+}
@@ -59,6 +59,13 @@ pub struct CommandRequest {
     pub origin: CommandOrigin,
 }
 
+impl ProtoEncode for CommandRequest {
+    fn proto_encode(&self, buf: &mut impl bytes::BufMut) {
+        write_string(buf, &self.command);
+        self.origin.proto_encode(buf);
+    }
+}
+
 impl ProtoDecode for CommandRequest {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         let command = read_string(buf)?;
@@ -91,6 +98,24 @@ mod tests {
         assert!(pkt.origin.player_entity_id.is_none());
     }
 
+    #[test]
+    fn encode_roundtrip() {
+        let pkt = CommandRequest {
+            command: "/gamemode creative".into(),
+            origin: CommandOrigin {
+                origin_type: 0,
+                uuid: Uuid::new(1, 2),
+                request_id: String::new(),
+                player_entity_id: None,
+            },
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let decoded = CommandRequest::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded.command, "/gamemode creative");
+        assert_eq!(decoded.origin.uuid, Uuid::new(1, 2));
+    }
+
     #[test]
     fn decode_dev_console_origin() {
         let mut buf = BytesMut::new();
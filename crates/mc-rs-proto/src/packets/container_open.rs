@@ -2,9 +2,10 @@
 //!
 //! Opens a container window (chest, furnace, etc.) on the client.
 
-use bytes::BufMut;
+use bytes::{Buf, BufMut};
 
-use crate::codec::ProtoEncode;
+use crate::codec::{ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
 use crate::types::{BlockPos, VarLong};
 
 /// Opens a container window for the player.
@@ -29,6 +30,27 @@ impl ProtoEncode for ContainerOpen {
     }
 }
 
+impl ProtoDecode for ContainerOpen {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        if buf.remaining() < 2 {
+            return Err(ProtoError::BufferTooShort {
+                needed: 2,
+                remaining: buf.remaining(),
+            });
+        }
+        let window_id = buf.get_u8();
+        let container_type = buf.get_u8();
+        let position = BlockPos::proto_decode(buf)?;
+        let entity_unique_id = VarLong::proto_decode(buf)?.0;
+        Ok(Self {
+            window_id,
+            container_type,
+            position,
+            entity_unique_id,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -48,5 +70,10 @@ mod tests {
         assert!(buf.len() >= 5);
         assert_eq!(buf[0], 1); // window_id
         assert_eq!(buf[1], 2); // container_type
+
+        let decoded = ContainerOpen::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded.window_id, 1);
+        assert_eq!(decoded.position, BlockPos::new(10, 64, -5));
+        assert_eq!(decoded.entity_unique_id, -1);
     }
 }
@@ -6,7 +6,7 @@
 use bytes::BufMut;
 
 use crate::codec::ProtoEncode;
-use crate::types::{VarInt, VarUInt32};
+use crate::types::{Uuid, VarInt, VarUInt32};
 
 /// An ingredient in a crafting recipe (for the CraftingData wire format).
 #[derive(Debug, Clone)]
@@ -118,18 +118,30 @@ pub struct FurnaceRecipeEntry {
     pub tag: String,
 }
 
+/// A recipe implemented by the client itself (type 4 = MultiRecipe), such
+/// as map cloning in the cartography table.
+#[derive(Debug, Clone)]
+pub struct MultiRecipeEntry {
+    /// UUID the client knows the recipe by.
+    pub uuid: Uuid,
+    /// Network ID.
+    pub network_id: u32,
+}
+
 /// The CraftingData packet containing all recipe definitions.
 pub struct CraftingData {
     pub shaped: Vec<ShapedRecipeEntry>,
     pub shapeless: Vec<ShapelessRecipeEntry>,
     pub furnace: Vec<FurnaceRecipeEntry>,
+    pub multi: Vec<MultiRecipeEntry>,
     /// Whether to clear existing recipes first.
     pub clear_recipes: bool,
 }
 
 impl ProtoEncode for CraftingData {
     fn proto_encode(&self, buf: &mut impl BufMut) {
-        let total = self.shaped.len() + self.shapeless.len() + self.furnace.len();
+        let total =
+            self.shaped.len() + self.shapeless.len() + self.furnace.len() + self.multi.len();
         VarUInt32(total as u32).proto_encode(buf);
 
         // Shapeless recipes (type = 0)
@@ -150,6 +162,13 @@ impl ProtoEncode for CraftingData {
             encode_furnace(buf, recipe);
         }
 
+        // Multi recipes (type = 4)
+        for recipe in &self.multi {
+            VarInt(4).proto_encode(buf); // recipe type: multi
+            recipe.uuid.proto_encode(buf);
+            VarUInt32(recipe.network_id).proto_encode(buf);
+        }
+
         // Potion mixes count = 0
         VarUInt32(0).proto_encode(buf);
         // Container mixes count = 0
@@ -237,6 +256,7 @@ mod tests {
             shaped: Vec::new(),
             shapeless: Vec::new(),
             furnace: Vec::new(),
+            multi: Vec::new(),
             clear_recipes: true,
         };
         let mut buf = BytesMut::new();
@@ -317,6 +337,7 @@ mod tests {
             shaped: vec![entry],
             shapeless: Vec::new(),
             furnace: Vec::new(),
+            multi: Vec::new(),
             clear_recipes: true,
         };
         let mut buf = BytesMut::new();
@@ -341,6 +362,7 @@ mod tests {
             shaped: Vec::new(),
             shapeless: Vec::new(),
             furnace: vec![entry],
+            multi: Vec::new(),
             clear_recipes: true,
         };
         let mut buf = BytesMut::new();
@@ -348,4 +370,24 @@ mod tests {
         // Should have: VarUInt32(1) + VarInt(3) + furnace data + trailing counts + flag
         assert!(buf.len() > 10);
     }
+
+    #[test]
+    fn encode_multi_recipe() {
+        let pkt = CraftingData {
+            shaped: Vec::new(),
+            shapeless: Vec::new(),
+            furnace: Vec::new(),
+            multi: vec![MultiRecipeEntry {
+                uuid: Uuid::parse("442d85ed-8272-4543-a6f1-418f90ded05d").unwrap(),
+                network_id: 300,
+            }],
+            clear_recipes: true,
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        // VarUInt32(1) + VarInt(4) + UUID + VarUInt32(300) + 3 × VarUInt32(0) + u8(1)
+        assert_eq!(buf.len(), 1 + 1 + 16 + 2 + 3 + 1);
+        assert_eq!(buf[1], 8); // zigzag-encoded 4
+        assert_eq!(&buf[2..10], &0x442d_85ed_8272_4543u64.to_le_bytes());
+    }
 }
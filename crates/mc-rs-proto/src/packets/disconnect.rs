@@ -1,8 +1,9 @@
 //! Disconnect (0x05) — Server → Client.
 
-use bytes::BufMut;
+use bytes::{Buf, BufMut};
 
-use crate::codec::{self, ProtoEncode};
+use crate::codec::{self, ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
 use crate::types::VarInt;
 
 /// Sent by the server to disconnect a client.
@@ -14,7 +15,7 @@ pub struct Disconnect {
     pub hide_disconnect_screen: bool,
     /// Message shown on the disconnect screen (only if `hide_disconnect_screen` is false).
     pub message: Option<String>,
-    /// Filtered variant of the message (protocol 924+).
+    /// Filtered variant of the message.
     pub filtered_message: Option<String>,
 }
 
@@ -60,6 +61,36 @@ impl ProtoEncode for Disconnect {
     }
 }
 
+impl ProtoDecode for Disconnect {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let reason = VarInt::proto_decode(buf)?.0;
+        if !buf.has_remaining() {
+            return Err(ProtoError::BufferTooShort {
+                needed: 1,
+                remaining: 0,
+            });
+        }
+        let hide_disconnect_screen = buf.get_u8() != 0;
+        let (message, filtered_message) = if hide_disconnect_screen {
+            (None, None)
+        } else {
+            let message = codec::read_string(buf)?;
+            let filtered = if buf.has_remaining() {
+                Some(codec::read_string(buf)?)
+            } else {
+                None
+            };
+            (Some(message), filtered)
+        };
+        Ok(Self {
+            reason,
+            hide_disconnect_screen,
+            message,
+            filtered_message,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,4 +150,15 @@ mod tests {
         // Should encode both strings
         assert!(buf.len() > 4);
     }
+
+    #[test]
+    fn roundtrip_with_message() {
+        let pkt = Disconnect::with_message("Server full");
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let decoded = Disconnect::proto_decode(&mut buf.freeze()).unwrap();
+        assert!(!decoded.hide_disconnect_screen);
+        assert_eq!(decoded.message.as_deref(), Some("Server full"));
+        assert_eq!(decoded.filtered_message.as_deref(), Some("Server full"));
+    }
 }
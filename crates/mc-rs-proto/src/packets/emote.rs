@@ -0,0 +1,99 @@
+//! Emote (0x8A) — Bidirectional.
+//!
+//! Sent by the client when the player plays an emote. The server relays
+//! it to nearby players with the server-side flag set.
+
+use bytes::{Buf, BufMut};
+
+use crate::codec::{read_string, write_string, ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
+use crate::types::{VarUInt32, VarUInt64};
+
+/// The emote was relayed by the server.
+pub const FLAG_SERVER_SIDE: u8 = 0x01;
+/// Don't announce the emote in chat.
+pub const FLAG_MUTE_ANNOUNCEMENT: u8 = 0x02;
+
+/// A player playing an emote.
+#[derive(Debug, Clone)]
+pub struct Emote {
+    pub entity_runtime_id: u64,
+    /// Emote piece UUID, as a string.
+    pub emote_id: String,
+    /// Length of the emote animation, in ticks.
+    pub emote_length_ticks: u32,
+    pub xuid: String,
+    pub platform_id: String,
+    pub flags: u8,
+}
+
+impl ProtoEncode for Emote {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt64(self.entity_runtime_id).proto_encode(buf);
+        write_string(buf, &self.emote_id);
+        VarUInt32(self.emote_length_ticks).proto_encode(buf);
+        write_string(buf, &self.xuid);
+        write_string(buf, &self.platform_id);
+        buf.put_u8(self.flags);
+    }
+}
+
+impl ProtoDecode for Emote {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let entity_runtime_id = VarUInt64::proto_decode(buf)?.0;
+        let emote_id = read_string(buf)?;
+        let emote_length_ticks = VarUInt32::proto_decode(buf)?.0;
+        let xuid = read_string(buf)?;
+        let platform_id = read_string(buf)?;
+        if buf.remaining() < 1 {
+            return Err(ProtoError::BufferTooShort {
+                needed: 1,
+                remaining: 0,
+            });
+        }
+        let flags = buf.get_u8();
+        Ok(Self {
+            entity_runtime_id,
+            emote_id,
+            emote_length_ticks,
+            xuid,
+            platform_id,
+            flags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn emote_roundtrip() {
+        let pkt = Emote {
+            entity_runtime_id: 7,
+            emote_id: "4c8ae710-df2e-47cd-814d-cc7bf21a3d67".into(),
+            emote_length_ticks: 60,
+            xuid: "2535400000000000".into(),
+            platform_id: String::new(),
+            flags: FLAG_SERVER_SIDE | FLAG_MUTE_ANNOUNCEMENT,
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+
+        let decoded = Emote::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded.entity_runtime_id, 7);
+        assert_eq!(decoded.emote_id, pkt.emote_id);
+        assert_eq!(decoded.emote_length_ticks, 60);
+        assert_eq!(decoded.xuid, pkt.xuid);
+        assert_eq!(decoded.flags, 0x03);
+    }
+
+    #[test]
+    fn decode_emote_truncated() {
+        let mut buf = BytesMut::new();
+        VarUInt64(7).proto_encode(&mut buf);
+        write_string(&mut buf, "emote");
+        assert!(Emote::proto_decode(&mut buf.freeze()).is_err());
+    }
+}
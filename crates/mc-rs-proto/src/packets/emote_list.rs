@@ -0,0 +1,72 @@
+//! EmoteList (0x98) — Bidirectional.
+//!
+//! Sent by the client after spawning with the emotes the player has
+//! equipped. The server forwards it to other players so their clients can
+//! load those emotes before they are played.
+
+use bytes::{Buf, BufMut};
+
+use crate::codec::{ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
+use crate::types::{Uuid, VarUInt32, VarUInt64};
+
+/// Emote pieces equipped by a player.
+#[derive(Debug, Clone)]
+pub struct EmoteList {
+    pub player_runtime_id: u64,
+    pub emote_pieces: Vec<Uuid>,
+}
+
+impl ProtoEncode for EmoteList {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt64(self.player_runtime_id).proto_encode(buf);
+        VarUInt32(self.emote_pieces.len() as u32).proto_encode(buf);
+        for piece in &self.emote_pieces {
+            piece.proto_encode(buf);
+        }
+    }
+}
+
+impl ProtoDecode for EmoteList {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let player_runtime_id = VarUInt64::proto_decode(buf)?.0;
+        let count = VarUInt32::proto_decode(buf)?.0 as usize;
+        // 16 bytes per UUID: don't trust the count for the allocation
+        let mut emote_pieces = Vec::with_capacity(count.min(buf.remaining() / 16));
+        for _ in 0..count {
+            emote_pieces.push(Uuid::proto_decode(buf)?);
+        }
+        Ok(Self {
+            player_runtime_id,
+            emote_pieces,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn emote_list_roundtrip() {
+        let pkt = EmoteList {
+            player_runtime_id: 3,
+            emote_pieces: vec![Uuid::new(1, 2), Uuid::new(3, 4)],
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+
+        let decoded = EmoteList::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded.player_runtime_id, 3);
+        assert_eq!(decoded.emote_pieces, pkt.emote_pieces);
+    }
+
+    #[test]
+    fn decode_emote_list_truncated() {
+        let mut buf = BytesMut::new();
+        VarUInt64(3).proto_encode(&mut buf);
+        VarUInt32(1000).proto_encode(&mut buf);
+        assert!(EmoteList::proto_decode(&mut buf.freeze()).is_err());
+    }
+}
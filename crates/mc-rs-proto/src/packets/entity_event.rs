@@ -13,6 +13,8 @@ pub const EVENT_HURT: u8 = 2;
 pub const EVENT_DEATH: u8 = 3;
 /// Love/breeding particles event.
 pub const EVENT_LOVE_PARTICLES: u8 = 18;
+/// Eating particles event (data = item id << 16 | metadata).
+pub const EVENT_EATING_ITEM: u8 = 57;
 
 /// EntityEvent packet.
 pub struct EntityEvent {
@@ -48,6 +50,15 @@ impl EntityEvent {
             data: 0,
         }
     }
+
+    /// Create an eating particles event for an entity eating the given item.
+    pub fn eating_item(entity_runtime_id: u64, item_id: i32, metadata: u16) -> Self {
+        Self {
+            entity_runtime_id,
+            event_id: EVENT_EATING_ITEM,
+            data: (item_id << 16) | metadata as i32,
+        }
+    }
 }
 
 impl ProtoEncode for EntityEvent {
@@ -90,4 +101,13 @@ mod tests {
         assert!(buf.len() >= 3);
         assert_eq!(buf[1], EVENT_LOVE_PARTICLES);
     }
+
+    #[test]
+    fn encode_eating_item() {
+        let pkt = EntityEvent::eating_item(7, 257, 0);
+        assert_eq!(pkt.data, 257 << 16);
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        assert_eq!(buf[1], EVENT_EATING_ITEM);
+    }
 }
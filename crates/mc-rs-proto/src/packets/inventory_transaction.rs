@@ -3,9 +3,9 @@
 //! Sent when the player interacts with blocks or items.
 //! We parse TransactionType 2 (UseItem), 3 (UseItemOnEntity), and 4 (ReleaseItem).
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
-use crate::codec::{read_string, ProtoDecode};
+use crate::codec::{read_string, ProtoDecode, ProtoEncode};
 use crate::error::ProtoError;
 use crate::types::{BlockPos, VarInt, VarUInt32, VarUInt64, Vec3};
 
@@ -348,6 +348,63 @@ fn skip_user_data(buf: &mut impl Buf) -> Result<(), ProtoError> {
     Ok(())
 }
 
+/// Write the held item of a transaction. Only its block runtime ID survives
+/// decoding, so a held block is written as one item of network ID 1 carrying
+/// it, and anything else as an empty slot.
+fn write_held_item(buf: &mut impl BufMut, block_runtime_id: i32) {
+    if block_runtime_id == 0 {
+        VarInt(0).proto_encode(buf);
+        return;
+    }
+    VarInt(1).proto_encode(buf); // Network ID
+    buf.put_u16_le(1); // Count
+    VarUInt32(0).proto_encode(buf); // Metadata
+    buf.put_u8(0); // HasStackID
+    VarInt(block_runtime_id).proto_encode(buf);
+    VarUInt32(0).proto_encode(buf); // No user data
+    VarInt(0).proto_encode(buf); // CanPlaceOn
+    VarInt(0).proto_encode(buf); // CanDestroy
+}
+
+impl ProtoEncode for InventoryTransaction {
+    /// Writes a UseItem, UseItemOnEntity or ReleaseItem transaction (the
+    /// first one set) without inventory actions, or an empty Normal one.
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarInt(0).proto_encode(buf); // LegacyRequestID
+        if let Some(data) = &self.use_item {
+            VarUInt32(2).proto_encode(buf);
+            VarUInt32(0).proto_encode(buf); // Actions
+            VarUInt32(data.action as u32).proto_encode(buf);
+            data.block_position.proto_encode(buf);
+            VarInt(data.face).proto_encode(buf);
+            VarInt(data.hotbar_slot).proto_encode(buf);
+            write_held_item(buf, data.held_item_block_runtime_id);
+            data.player_position.proto_encode(buf);
+            data.click_position.proto_encode(buf);
+            VarUInt32(data.block_runtime_id).proto_encode(buf);
+        } else if let Some(data) = &self.use_item_on_entity {
+            VarUInt32(3).proto_encode(buf);
+            VarUInt32(0).proto_encode(buf); // Actions
+            VarUInt64(data.entity_runtime_id).proto_encode(buf);
+            VarUInt32(data.action as u32).proto_encode(buf);
+            VarInt(data.hotbar_slot).proto_encode(buf);
+            write_held_item(buf, 0);
+            data.player_position.proto_encode(buf);
+            data.click_position.proto_encode(buf);
+        } else if let Some(data) = &self.release_item {
+            VarUInt32(4).proto_encode(buf);
+            VarUInt32(0).proto_encode(buf); // Actions
+            VarUInt32(data.action as u32).proto_encode(buf);
+            VarInt(data.hotbar_slot).proto_encode(buf);
+            write_held_item(buf, 0);
+            data.head_position.proto_encode(buf);
+        } else {
+            VarUInt32(0).proto_encode(buf); // Normal
+            VarUInt32(0).proto_encode(buf); // Actions
+        }
+    }
+}
+
 impl ProtoDecode for InventoryTransaction {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         // LegacyRequestID (VarInt zigzag) — should be 0
@@ -487,7 +544,6 @@ impl ProtoDecode for InventoryTransaction {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::codec::ProtoEncode;
     use bytes::BytesMut;
 
     /// Encode an empty item stack (runtime_id = 0).
@@ -560,6 +616,54 @@ mod tests {
         assert_eq!(data.held_item_block_runtime_id, 42);
     }
 
+    #[test]
+    fn encode_roundtrip() {
+        let pkt = InventoryTransaction {
+            use_item: Some(UseItemData {
+                action: UseItemAction::ClickBlock,
+                block_position: BlockPos::new(5, 3, 5),
+                face: 1,
+                hotbar_slot: 2,
+                held_item_block_runtime_id: 42,
+                player_position: Vec3::ZERO,
+                click_position: Vec3::new(0.5, 1.0, 0.5),
+                block_runtime_id: 200,
+            }),
+            use_item_on_entity: None,
+            release_item: None,
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let data = InventoryTransaction::proto_decode(&mut buf.freeze())
+            .unwrap()
+            .use_item
+            .expect("should be UseItem");
+        assert_eq!(data.action, UseItemAction::ClickBlock);
+        assert_eq!(data.hotbar_slot, 2);
+        assert_eq!(data.held_item_block_runtime_id, 42);
+        assert_eq!(data.block_runtime_id, 200);
+
+        let pkt = InventoryTransaction {
+            use_item: None,
+            use_item_on_entity: Some(UseItemOnEntityData {
+                entity_runtime_id: 42,
+                action: UseItemOnEntityAction::Attack,
+                hotbar_slot: 0,
+                player_position: Vec3::ZERO,
+                click_position: Vec3::ZERO,
+            }),
+            release_item: None,
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let data = InventoryTransaction::proto_decode(&mut buf.freeze())
+            .unwrap()
+            .use_item_on_entity
+            .expect("should be UseItemOnEntity");
+        assert_eq!(data.entity_runtime_id, 42);
+        assert_eq!(data.action, UseItemOnEntityAction::Attack);
+    }
+
     #[test]
     fn skip_non_use_item() {
         let mut buf = BytesMut::new();
@@ -3,9 +3,9 @@
 //! The client sends inventory manipulation requests. The server validates
 //! and responds with ItemStackResponse.
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
-use crate::codec::{read_string, ProtoDecode};
+use crate::codec::{read_string, write_string, ProtoDecode, ProtoEncode};
 use crate::error::ProtoError;
 use crate::types::{VarInt, VarUInt32};
 
@@ -21,6 +21,14 @@ pub struct StackSlot {
     pub stack_network_id: i32,
 }
 
+impl ProtoEncode for StackSlot {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        buf.put_u8(self.container_id);
+        buf.put_u8(self.slot);
+        VarInt(self.stack_network_id).proto_encode(buf);
+    }
+}
+
 impl ProtoDecode for StackSlot {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         if buf.remaining() < 1 {
@@ -79,6 +87,11 @@ pub enum StackAction {
     Create {
         result_slot: u8,
     },
+    /// Select beacon powers, paid for by the item in the payment slot (action type 10).
+    BeaconPayment {
+        primary_effect: i32,
+        secondary_effect: i32,
+    },
     /// Craft a recipe by network ID (action type 12).
     CraftRecipe {
         recipe_network_id: u32,
@@ -127,6 +140,118 @@ pub struct ItemStackRequest {
     pub requests: Vec<StackRequest>,
 }
 
+impl ProtoEncode for StackAction {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        match self {
+            Self::Take { count, src, dst } => {
+                buf.put_u8(0);
+                buf.put_u8(*count);
+                src.proto_encode(buf);
+                dst.proto_encode(buf);
+            }
+            Self::Place { count, src, dst } => {
+                buf.put_u8(1);
+                buf.put_u8(*count);
+                src.proto_encode(buf);
+                dst.proto_encode(buf);
+            }
+            Self::Swap { src, dst } => {
+                buf.put_u8(2);
+                src.proto_encode(buf);
+                dst.proto_encode(buf);
+            }
+            Self::Drop {
+                count,
+                src,
+                randomly,
+            } => {
+                buf.put_u8(3);
+                buf.put_u8(*count);
+                src.proto_encode(buf);
+                buf.put_u8(*randomly as u8);
+            }
+            Self::Destroy { count, src } => {
+                buf.put_u8(4);
+                buf.put_u8(*count);
+                src.proto_encode(buf);
+            }
+            Self::Consume { count, src } => {
+                buf.put_u8(5);
+                buf.put_u8(*count);
+                src.proto_encode(buf);
+            }
+            Self::Create { result_slot } => {
+                buf.put_u8(6);
+                buf.put_u8(*result_slot);
+            }
+            Self::BeaconPayment {
+                primary_effect,
+                secondary_effect,
+            } => {
+                buf.put_u8(10);
+                VarInt(*primary_effect).proto_encode(buf);
+                VarInt(*secondary_effect).proto_encode(buf);
+            }
+            Self::CraftRecipe { recipe_network_id } => {
+                buf.put_u8(12);
+                VarUInt32(*recipe_network_id).proto_encode(buf);
+            }
+            Self::CraftRecipeAuto {
+                recipe_network_id,
+                times_crafted,
+                ingredients,
+            } => {
+                buf.put_u8(13);
+                VarUInt32(*recipe_network_id).proto_encode(buf);
+                buf.put_u8(*times_crafted);
+                VarUInt32(ingredients.len() as u32).proto_encode(buf);
+                buf.put_slice(ingredients);
+            }
+            Self::CraftCreative {
+                creative_item_network_id,
+            } => {
+                buf.put_u8(14);
+                VarUInt32(*creative_item_network_id).proto_encode(buf);
+            }
+            Self::CraftRecipeOptional {
+                recipe_network_id,
+                filter_string_index,
+            } => {
+                buf.put_u8(15);
+                VarUInt32(*recipe_network_id).proto_encode(buf);
+                VarInt(*filter_string_index).proto_encode(buf);
+            }
+            Self::CraftGrindstone { recipe_network_id } => {
+                buf.put_u8(16);
+                VarUInt32(*recipe_network_id).proto_encode(buf);
+            }
+            Self::CraftLoom { pattern_id } => {
+                buf.put_u8(17);
+                write_string(buf, pattern_id);
+            }
+            Self::Unknown { action_type } => buf.put_u8(*action_type),
+        }
+    }
+}
+
+impl ProtoEncode for ItemStackRequest {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt32(self.requests.len() as u32).proto_encode(buf);
+        for request in &self.requests {
+            VarInt(request.request_id).proto_encode(buf);
+            VarUInt32(request.actions.len() as u32).proto_encode(buf);
+            for action in &request.actions {
+                action.proto_encode(buf);
+            }
+            VarUInt32(request.filter_strings.len() as u32).proto_encode(buf);
+            for filter in &request.filter_strings {
+                write_string(buf, filter);
+            }
+            VarInt(request.filter_cause).proto_encode(buf);
+        }
+    }
+}
+
 impl ProtoDecode for ItemStackRequest {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         let count = VarUInt32::proto_decode(buf)?.0;
@@ -264,6 +389,15 @@ fn decode_stack_action(buf: &mut impl Buf) -> Result<StackAction, ProtoError> {
             let result_slot = buf.get_u8();
             Ok(StackAction::Create { result_slot })
         }
+        10 => {
+            // BeaconPayment
+            let primary_effect = VarInt::proto_decode(buf)?.0;
+            let secondary_effect = VarInt::proto_decode(buf)?.0;
+            Ok(StackAction::BeaconPayment {
+                primary_effect,
+                secondary_effect,
+            })
+        }
         12 => {
             // CraftRecipe
             let recipe_network_id = VarUInt32::proto_decode(buf)?.0;
@@ -329,3 +463,56 @@ fn decode_stack_action(buf: &mut impl Buf) -> Result<StackAction, ProtoError> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn encode_roundtrip() {
+        let slot = |container_id, slot| StackSlot {
+            container_id,
+            slot,
+            stack_network_id: 0,
+        };
+        let pkt = ItemStackRequest {
+            requests: vec![StackRequest {
+                request_id: -3,
+                actions: vec![
+                    StackAction::CraftRecipe {
+                        recipe_network_id: 7,
+                    },
+                    StackAction::Consume {
+                        count: 1,
+                        src: slot(13, 28),
+                    },
+                    StackAction::Take {
+                        count: 4,
+                        src: slot(60, 50),
+                        dst: slot(12, 0),
+                    },
+                ],
+                filter_strings: vec![],
+                filter_cause: 0,
+            }],
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+
+        let decoded = ItemStackRequest::proto_decode(&mut buf.freeze()).unwrap();
+        let req = &decoded.requests[0];
+        assert_eq!(req.request_id, -3);
+        assert_eq!(req.actions.len(), 3);
+        assert!(matches!(
+            req.actions[0],
+            StackAction::CraftRecipe {
+                recipe_network_id: 7
+            }
+        ));
+        assert!(matches!(
+            &req.actions[2],
+            StackAction::Take { count: 4, src, dst } if src.container_id == 60 && dst.slot == 0
+        ));
+    }
+}
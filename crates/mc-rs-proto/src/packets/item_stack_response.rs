@@ -3,12 +3,14 @@
 //! Sent by the server in response to ItemStackRequest to confirm or reject
 //! inventory operations.
 
-use bytes::BufMut;
+use bytes::{Buf, BufMut};
 
-use crate::codec::{write_string, ProtoEncode};
-use crate::types::VarInt;
+use crate::codec::{read_string, write_string, ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
+use crate::types::{VarInt, VarUInt32};
 
 /// Response for a single slot after an inventory operation.
+#[derive(Debug, Clone)]
 pub struct StackResponseSlot {
     /// Slot index.
     pub slot: u8,
@@ -35,7 +37,27 @@ impl ProtoEncode for StackResponseSlot {
     }
 }
 
+impl ProtoDecode for StackResponseSlot {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        if buf.remaining() < 3 {
+            return Err(ProtoError::BufferTooShort {
+                needed: 3,
+                remaining: buf.remaining(),
+            });
+        }
+        Ok(Self {
+            slot: buf.get_u8(),
+            hotbar_slot: buf.get_u8(),
+            count: buf.get_u8(),
+            stack_network_id: VarInt::proto_decode(buf)?.0,
+            custom_name: read_string(buf)?,
+            durability_correction: VarInt::proto_decode(buf)?.0,
+        })
+    }
+}
+
 /// Container slot updates grouped by container.
+#[derive(Debug, Clone)]
 pub struct StackResponseContainer {
     /// Container ID (0 = inventory, 119 = armor, etc.).
     pub container_id: u8,
@@ -47,16 +69,38 @@ impl ProtoEncode for StackResponseContainer {
     fn proto_encode(&self, buf: &mut impl BufMut) {
         // FullContainerName
         buf.put_u8(self.container_id);
-        crate::types::VarUInt32(0).proto_encode(buf); // dynamic_container_id = 0
-                                                      // Slots
-        crate::types::VarUInt32(self.slots.len() as u32).proto_encode(buf);
+        VarUInt32(0).proto_encode(buf); // dynamic_container_id = 0
+                                        // Slots
+        VarUInt32(self.slots.len() as u32).proto_encode(buf);
         for slot in &self.slots {
             slot.proto_encode(buf);
         }
     }
 }
 
+impl ProtoDecode for StackResponseContainer {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        if !buf.has_remaining() {
+            return Err(ProtoError::BufferTooShort {
+                needed: 1,
+                remaining: 0,
+            });
+        }
+        let container_id = buf.get_u8();
+        let _dynamic_container_id = VarUInt32::proto_decode(buf)?;
+        let count = VarUInt32::proto_decode(buf)?.0;
+        let slots = (0..count)
+            .map(|_| StackResponseSlot::proto_decode(buf))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            container_id,
+            slots,
+        })
+    }
+}
+
 /// Response for a single request.
+#[derive(Debug, Clone)]
 pub struct StackResponseEntry {
     /// 0 = success, non-zero = error.
     pub status: u8,
@@ -71,7 +115,7 @@ impl ProtoEncode for StackResponseEntry {
         buf.put_u8(self.status);
         VarInt(self.request_id).proto_encode(buf);
         if self.status == 0 {
-            crate::types::VarUInt32(self.containers.len() as u32).proto_encode(buf);
+            VarUInt32(self.containers.len() as u32).proto_encode(buf);
             for container in &self.containers {
                 container.proto_encode(buf);
             }
@@ -79,16 +123,98 @@ impl ProtoEncode for StackResponseEntry {
     }
 }
 
+impl ProtoDecode for StackResponseEntry {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        if !buf.has_remaining() {
+            return Err(ProtoError::BufferTooShort {
+                needed: 1,
+                remaining: 0,
+            });
+        }
+        let status = buf.get_u8();
+        let request_id = VarInt::proto_decode(buf)?.0;
+        let containers = if status == 0 {
+            let count = VarUInt32::proto_decode(buf)?.0;
+            (0..count)
+                .map(|_| StackResponseContainer::proto_decode(buf))
+                .collect::<Result<_, _>>()?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            status,
+            request_id,
+            containers,
+        })
+    }
+}
+
 /// The complete ItemStackResponse packet.
+#[derive(Debug, Clone)]
 pub struct ItemStackResponse {
     pub responses: Vec<StackResponseEntry>,
 }
 
 impl ProtoEncode for ItemStackResponse {
     fn proto_encode(&self, buf: &mut impl BufMut) {
-        crate::types::VarUInt32(self.responses.len() as u32).proto_encode(buf);
+        VarUInt32(self.responses.len() as u32).proto_encode(buf);
         for response in &self.responses {
             response.proto_encode(buf);
         }
     }
 }
+
+impl ProtoDecode for ItemStackResponse {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let count = VarUInt32::proto_decode(buf)?.0;
+        let responses = (0..count)
+            .map(|_| StackResponseEntry::proto_decode(buf))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { responses })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn roundtrip_response() {
+        let pkt = ItemStackResponse {
+            responses: vec![
+                StackResponseEntry {
+                    status: 0,
+                    request_id: -3,
+                    containers: vec![StackResponseContainer {
+                        container_id: 29,
+                        slots: vec![StackResponseSlot {
+                            slot: 0,
+                            hotbar_slot: 0,
+                            count: 4,
+                            stack_network_id: 12,
+                            custom_name: String::new(),
+                            durability_correction: 0,
+                        }],
+                    }],
+                },
+                StackResponseEntry {
+                    status: 1,
+                    request_id: -5,
+                    containers: Vec::new(),
+                },
+            ],
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let decoded = ItemStackResponse::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded.responses.len(), 2);
+        let ok = &decoded.responses[0];
+        assert_eq!((ok.status, ok.request_id), (0, -3));
+        assert_eq!(ok.containers[0].container_id, 29);
+        assert_eq!(ok.containers[0].slots[0].count, 4);
+        assert_eq!(ok.containers[0].slots[0].stack_network_id, 12);
+        assert_eq!(decoded.responses[1].status, 1);
+        assert!(decoded.responses[1].containers.is_empty());
+    }
+}
@@ -5,11 +5,15 @@
 use bytes::BufMut;
 
 use crate::codec::ProtoEncode;
-use crate::types::{VarInt, Vec3};
+use crate::types::{BlockPos, VarInt, Vec3};
 
 /// Event ID for block-destroy particles.
 pub const PARTICLE_DESTROY_BLOCK: i32 = 2001;
 
+/// Block cracking event IDs (data: crack progress per tick, out of 65535).
+pub const BLOCK_START_BREAK: i32 = 3600;
+pub const BLOCK_STOP_BREAK: i32 = 3601;
+
 /// Weather event IDs.
 pub const START_RAIN: i32 = 3001;
 pub const START_THUNDER: i32 = 3002;
@@ -61,6 +65,24 @@ impl LevelEvent {
         }
     }
 
+    /// Start the crack animation on a block that breaks in `break_ticks`.
+    pub fn start_block_break(block: BlockPos, break_ticks: u32) -> Self {
+        Self {
+            event_id: BLOCK_START_BREAK,
+            position: Vec3::new(block.x as f32, block.y as f32, block.z as f32),
+            data: (65535 / break_ticks.max(1)) as i32,
+        }
+    }
+
+    /// Remove the crack animation from a block.
+    pub fn stop_block_break(block: BlockPos) -> Self {
+        Self {
+            event_id: BLOCK_STOP_BREAK,
+            position: Vec3::new(block.x as f32, block.y as f32, block.z as f32),
+            data: 0,
+        }
+    }
+
     /// Create a block-destroy particle event at the block center.
     pub fn destroy_block(block_x: i32, block_y: i32, block_z: i32, runtime_id: u32) -> Self {
         Self {
@@ -100,4 +122,18 @@ mod tests {
         assert_eq!(pkt.position.y, 64.5);
         assert_eq!(pkt.position.z, -4.5);
     }
+
+    #[test]
+    fn block_break_progress() {
+        let start = LevelEvent::start_block_break(BlockPos::new(1, 2, 3), 20);
+        assert_eq!(start.event_id, BLOCK_START_BREAK);
+        assert_eq!(start.data, 3276);
+        assert_eq!(start.position.y, 2.0);
+        // Instant breaks don't divide by zero
+        assert_eq!(
+            LevelEvent::start_block_break(BlockPos::new(0, 0, 0), 0).data,
+            65535
+        );
+        assert_eq!(LevelEvent::stop_block_break(BlockPos::new(0, 0, 0)).data, 0);
+    }
 }
@@ -0,0 +1,107 @@
+//! LevelSoundEvent (0x7B) — Bidirectional.
+//!
+//! Plays a built-in sound at a position: note block notes, jukebox records,
+//! and most block and entity sounds.
+
+use bytes::{Buf, BufMut};
+
+use crate::codec::{read_string, write_string, ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
+use crate::types::{VarInt, VarUInt32, Vec3};
+
+/// Note block note. Extra data: `instrument << 8 | pitch`.
+pub const SOUND_NOTE: u32 = 81;
+/// First jukebox record ("13"); the other original records follow it.
+pub const SOUND_RECORD_13: u32 = 101;
+/// Stops the record playing at the position.
+pub const SOUND_STOP_RECORD: u32 = 113;
+
+/// LevelSoundEvent packet.
+#[derive(Debug, Clone)]
+pub struct LevelSoundEvent {
+    pub sound_id: u32,
+    pub position: Vec3,
+    /// Sound specific data, e.g. a block runtime ID or a note.
+    pub extra_data: i32,
+    /// Entity identifier for mob sounds, `":"` otherwise.
+    pub entity_type: String,
+    pub is_baby_mob: bool,
+    /// Play at full volume wherever the listener is.
+    pub is_global: bool,
+    /// Entity the sound comes from, -1 for none.
+    pub entity_unique_id: i64,
+}
+
+impl LevelSoundEvent {
+    /// A block sound centered on the block at `(x, y, z)`.
+    pub fn at_block(sound_id: u32, x: i32, y: i32, z: i32, extra_data: i32) -> Self {
+        Self {
+            sound_id,
+            position: Vec3::new(x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5),
+            extra_data,
+            entity_type: ":".into(),
+            is_baby_mob: false,
+            is_global: false,
+            entity_unique_id: -1,
+        }
+    }
+}
+
+impl ProtoEncode for LevelSoundEvent {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt32(self.sound_id).proto_encode(buf);
+        self.position.proto_encode(buf);
+        VarInt(self.extra_data).proto_encode(buf);
+        write_string(buf, &self.entity_type);
+        buf.put_u8(self.is_baby_mob as u8);
+        buf.put_u8(self.is_global as u8);
+        buf.put_i64_le(self.entity_unique_id);
+    }
+}
+
+impl ProtoDecode for LevelSoundEvent {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let sound_id = VarUInt32::proto_decode(buf)?.0;
+        let position = Vec3::proto_decode(buf)?;
+        let extra_data = VarInt::proto_decode(buf)?.0;
+        let entity_type = read_string(buf)?;
+        if buf.remaining() < 10 {
+            return Err(ProtoError::BufferTooShort {
+                needed: 10,
+                remaining: buf.remaining(),
+            });
+        }
+        let is_baby_mob = buf.get_u8() != 0;
+        let is_global = buf.get_u8() != 0;
+        let entity_unique_id = buf.get_i64_le();
+        Ok(Self {
+            sound_id,
+            position,
+            extra_data,
+            entity_type,
+            is_baby_mob,
+            is_global,
+            entity_unique_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn level_sound_event_roundtrip() {
+        let pkt = LevelSoundEvent::at_block(SOUND_NOTE, 3, 64, -2, (4 << 8) | 12);
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+
+        let decoded = LevelSoundEvent::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded.sound_id, SOUND_NOTE);
+        assert_eq!(decoded.position, Vec3::new(3.5, 64.5, -1.5));
+        assert_eq!(decoded.extra_data, (4 << 8) | 12);
+        assert_eq!(decoded.entity_type, ":");
+        assert_eq!(decoded.entity_unique_id, -1);
+    }
+}
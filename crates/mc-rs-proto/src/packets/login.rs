@@ -1,9 +1,10 @@
 //! LoginPacket (0x01) — Client → Server.
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
-use crate::codec::ProtoDecode;
+use crate::codec::{ProtoDecode, ProtoEncode};
 use crate::error::ProtoError;
+use crate::jwt::encode_jwt_unsigned;
 use crate::types::VarUInt32;
 
 /// Login packet containing the client's protocol version and JWT chain.
@@ -27,6 +28,54 @@ pub struct LoginPacket {
     pub client_data_jwt: String,
 }
 
+impl LoginPacket {
+    /// An unsigned login, accepted by servers in offline mode: one identity
+    /// JWT naming the player, and client data with just a device.
+    pub fn offline(
+        protocol_version: i32,
+        display_name: &str,
+        xuid: &str,
+        identity: &str,
+        device_id: &str,
+    ) -> Self {
+        let identity_jwt = encode_jwt_unsigned(&serde_json::json!({
+            "extraData": {
+                "XUID": xuid,
+                "identity": identity,
+                "displayName": display_name,
+            },
+            "identityPublicKey": "",
+        }));
+        let client_data_jwt = encode_jwt_unsigned(&serde_json::json!({
+            "DeviceOS": 7,
+            "DeviceId": device_id,
+        }));
+        Self {
+            protocol_version,
+            chain_data: vec![identity_jwt],
+            client_data_jwt,
+        }
+    }
+}
+
+impl ProtoEncode for LoginPacket {
+    /// Writes the chain in the `Certificate` wrapper of protocol 924+.
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        let certificate = serde_json::json!({ "chain": self.chain_data }).to_string();
+        let chain_json =
+            serde_json::json!({ "AuthenticationType": 0, "Certificate": certificate }).to_string();
+        let chain_bytes = chain_json.as_bytes();
+        let client_bytes = self.client_data_jwt.as_bytes();
+
+        buf.put_i32(self.protocol_version); // BE
+        VarUInt32((4 + chain_bytes.len() + 4 + client_bytes.len()) as u32).proto_encode(buf);
+        buf.put_i32_le(chain_bytes.len() as i32);
+        buf.put_slice(chain_bytes);
+        buf.put_i32_le(client_bytes.len() as i32);
+        buf.put_slice(client_bytes);
+    }
+}
+
 impl ProtoDecode for LoginPacket {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         if buf.remaining() < 4 {
@@ -129,9 +178,7 @@ fn parse_chain_json(data: &[u8]) -> Result<Vec<String>, ProtoError> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use bytes::{BufMut, BytesMut};
-
-    use crate::codec::ProtoEncode;
+    use bytes::BytesMut;
 
     /// Helper: build a LoginPacket's raw bytes for testing.
     fn build_login_bytes(protocol_version: i32, chain_json: &str, client_data: &str) -> BytesMut {
@@ -188,6 +235,22 @@ mod tests {
         assert_eq!(pkt.chain_data[0], "jwt1.p.s");
     }
 
+    #[test]
+    fn encode_roundtrip() {
+        let login = LoginPacket {
+            protocol_version: 924,
+            chain_data: vec!["jwt1.p.s".into(), "jwt2.p.s".into()],
+            client_data_jwt: "cd.p.s".into(),
+        };
+        let mut buf = BytesMut::new();
+        login.proto_encode(&mut buf);
+
+        let pkt = LoginPacket::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(pkt.protocol_version, 924);
+        assert_eq!(pkt.chain_data, login.chain_data);
+        assert_eq!(pkt.client_data_jwt, "cd.p.s");
+    }
+
     #[test]
     fn decode_login_packet_truncated() {
         let buf = BytesMut::from(&[0x00, 0x00, 0x02][..]);
@@ -205,4 +268,17 @@ mod tests {
         let buf = build_login_bytes(924, r#"{"notchain":[]}"#, "cd");
         assert!(LoginPacket::proto_decode(&mut buf.freeze()).is_err());
     }
+
+    #[test]
+    fn offline_login_names_the_player() {
+        let pkt = LoginPacket::offline(924, "Steve", "2535400000000001", "uuid", "device");
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let decoded = LoginPacket::proto_decode(&mut buf.freeze()).unwrap();
+        let data = crate::jwt::extract_login_data(&decoded.chain_data).unwrap();
+        assert_eq!(data.display_name, "Steve");
+        assert_eq!(data.xuid, "2535400000000001");
+        let client = crate::jwt::extract_client_data(&decoded.client_data_jwt).unwrap();
+        assert_eq!(client.device_id, "device");
+    }
 }
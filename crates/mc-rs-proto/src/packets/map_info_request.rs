@@ -0,0 +1,40 @@
+//! MapInfoRequest (0x44) — Client → Server.
+//!
+//! Sent when the client holds a filled map it has no data for.
+
+use bytes::Buf;
+
+use crate::codec::ProtoDecode;
+use crate::error::ProtoError;
+use crate::types::VarLong;
+
+/// Request for the contents of a map.
+#[derive(Debug, Clone)]
+pub struct MapInfoRequest {
+    pub map_id: i64,
+}
+
+impl ProtoDecode for MapInfoRequest {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let map_id = VarLong::proto_decode(buf)?.0;
+        // Client pixel hints follow; the server sends the whole map anyway
+        buf.advance(buf.remaining());
+        Ok(Self { map_id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::ProtoEncode;
+    use bytes::BytesMut;
+
+    #[test]
+    fn decode_map_info_request() {
+        let mut buf = BytesMut::new();
+        VarLong(-123_456_789).proto_encode(&mut buf);
+        buf.extend_from_slice(&[0, 0, 0, 0]);
+        let pkt = MapInfoRequest::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(pkt.map_id, -123_456_789);
+    }
+}
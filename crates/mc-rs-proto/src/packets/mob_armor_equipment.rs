@@ -0,0 +1,60 @@
+//! MobArmorEquipment (0x20) — Server → Client.
+//!
+//! Shows the armor a mob or another player is wearing.
+
+use bytes::BufMut;
+
+use crate::codec::ProtoEncode;
+use crate::item_stack::ItemStack;
+use crate::types::VarUInt64;
+
+/// MobArmorEquipment packet.
+pub struct MobArmorEquipment {
+    pub entity_runtime_id: u64,
+    pub helmet: ItemStack,
+    pub chestplate: ItemStack,
+    pub leggings: ItemStack,
+    pub boots: ItemStack,
+    /// Body armor of horses and wolves.
+    pub body: ItemStack,
+}
+
+impl MobArmorEquipment {
+    /// Armor made of just a helmet (empty for none).
+    pub fn helmet(entity_runtime_id: u64, helmet: ItemStack) -> Self {
+        Self {
+            entity_runtime_id,
+            helmet,
+            chestplate: ItemStack::empty(),
+            leggings: ItemStack::empty(),
+            boots: ItemStack::empty(),
+            body: ItemStack::empty(),
+        }
+    }
+}
+
+impl ProtoEncode for MobArmorEquipment {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt64(self.entity_runtime_id).proto_encode(buf);
+        self.helmet.proto_encode(buf);
+        self.chestplate.proto_encode(buf);
+        self.leggings.proto_encode(buf);
+        self.boots.proto_encode(buf);
+        self.body.proto_encode(buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn encode_no_armor() {
+        let pkt = MobArmorEquipment::helmet(5, ItemStack::empty());
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        // VarUInt64(5) + five empty stacks (VarInt(0) each)
+        assert_eq!(&buf[..], &[5, 0, 0, 0, 0, 0]);
+    }
+}
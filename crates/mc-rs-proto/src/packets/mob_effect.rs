@@ -37,6 +37,9 @@ pub mod effect_id {
     pub const POISON: i32 = 19;
     pub const WITHER: i32 = 20;
     pub const ABSORPTION: i32 = 22;
+    pub const SLOW_FALLING: i32 = 27;
+    pub const BAD_OMEN: i32 = 28;
+    pub const HERO_OF_THE_VILLAGE: i32 = 29;
 }
 
 /// MobEffect packet.
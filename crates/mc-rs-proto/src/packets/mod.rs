@@ -9,9 +9,12 @@ pub mod available_entity_identifiers;
 pub mod biome_definition_list;
 pub mod block_actor_data;
 pub mod boss_event;
+pub mod camera;
 pub mod change_dimension;
 pub mod chunk_radius_updated;
 pub mod client_to_server_handshake;
+pub mod clientbound_map_item_data;
+pub mod command_block_update;
 pub mod command_output;
 pub mod command_request;
 pub mod container_close;
@@ -20,6 +23,8 @@ pub mod container_set_data;
 pub mod crafting_data;
 pub mod creative_content;
 pub mod disconnect;
+pub mod emote;
+pub mod emote_list;
 pub mod entity_event;
 pub mod game_rules_changed;
 pub mod inventory_content;
@@ -30,7 +35,10 @@ pub mod item_stack_request;
 pub mod item_stack_response;
 pub mod level_chunk;
 pub mod level_event;
+pub mod level_sound_event;
 pub mod login;
+pub mod map_info_request;
+pub mod mob_armor_equipment;
 pub mod mob_effect;
 pub mod mob_equipment;
 pub mod modal_form_request;
@@ -39,11 +47,13 @@ pub mod move_actor_absolute;
 pub mod move_player;
 pub mod network_chunk_publisher_update;
 pub mod network_settings;
+pub mod open_sign;
 pub mod play_sound;
 pub mod play_status;
 pub mod player_action;
 pub mod player_auth_input;
 pub mod player_enchant_options;
+pub mod player_fog;
 pub mod player_list;
 pub mod player_skin;
 pub mod remove_entity;
@@ -57,6 +67,8 @@ pub mod resource_pack_stack;
 pub mod resource_packs_info;
 pub mod respawn;
 pub mod server_to_client_handshake;
+pub mod set_actor_data;
+pub mod set_difficulty;
 pub mod set_display_objective;
 pub mod set_entity_motion;
 pub mod set_local_player_as_initialized;
@@ -72,19 +84,25 @@ pub mod transfer;
 pub mod update_abilities;
 pub mod update_attributes;
 pub mod update_block;
+pub mod update_client_input_locks;
 
 pub use add_actor::{ActorAttribute, AddActor};
 pub use add_item_entity::AddItemEntity;
-pub use add_player::{AddPlayer, EntityMetadataEntry, MetadataValue};
+pub use add_player::AddPlayer;
 pub use animate::Animate;
 pub use available_commands::AvailableCommands;
 pub use available_entity_identifiers::AvailableEntityIdentifiers;
 pub use biome_definition_list::BiomeDefinitionList;
 pub use block_actor_data::BlockActorData;
 pub use boss_event::BossEvent;
+pub use camera::{
+    CameraEase, CameraFade, CameraInstruction, CameraPreset, CameraPresets, CameraSet,
+};
 pub use change_dimension::ChangeDimension;
 pub use chunk_radius_updated::ChunkRadiusUpdated;
 pub use client_to_server_handshake::ClientToServerHandshake;
+pub use clientbound_map_item_data::{ClientboundMapItemData, MapDecoration};
+pub use command_block_update::{CommandBlockTarget, CommandBlockUpdate};
 pub use command_output::CommandOutput;
 pub use command_request::{CommandOrigin, CommandRequest};
 pub use container_close::ContainerClose;
@@ -93,6 +111,8 @@ pub use container_set_data::ContainerSetData;
 pub use crafting_data::CraftingData;
 pub use creative_content::CreativeContent;
 pub use disconnect::Disconnect;
+pub use emote::Emote;
+pub use emote_list::EmoteList;
 pub use entity_event::EntityEvent;
 pub use game_rules_changed::GameRulesChanged;
 pub use inventory_content::InventoryContent;
@@ -107,7 +127,10 @@ pub use item_stack_response::{
 };
 pub use level_chunk::LevelChunk;
 pub use level_event::LevelEvent;
+pub use level_sound_event::LevelSoundEvent;
 pub use login::LoginPacket;
+pub use map_info_request::MapInfoRequest;
+pub use mob_armor_equipment::MobArmorEquipment;
 pub use mob_effect::MobEffect;
 pub use mob_equipment::MobEquipment;
 pub use modal_form_request::ModalFormRequest;
@@ -116,11 +139,13 @@ pub use move_actor_absolute::MoveActorAbsolute;
 pub use move_player::{MoveMode, MovePlayer};
 pub use network_chunk_publisher_update::NetworkChunkPublisherUpdate;
 pub use network_settings::NetworkSettings;
+pub use open_sign::OpenSign;
 pub use play_sound::PlaySound;
 pub use play_status::{PlayStatus, PlayStatusType};
 pub use player_action::{PlayerAction, PlayerActionType};
 pub use player_auth_input::PlayerAuthInput;
 pub use player_enchant_options::PlayerEnchantOptions;
+pub use player_fog::PlayerFog;
 pub use player_list::{PlayerListAdd, PlayerListAddPacket, PlayerListRemove};
 pub use player_skin::PlayerSkin;
 pub use remove_entity::RemoveEntity;
@@ -134,6 +159,8 @@ pub use resource_pack_stack::ResourcePackStack;
 pub use resource_packs_info::ResourcePacksInfo;
 pub use respawn::Respawn;
 pub use server_to_client_handshake::ServerToClientHandshake;
+pub use set_actor_data::SetActorData;
+pub use set_difficulty::SetDifficulty;
 pub use set_display_objective::SetDisplayObjective;
 pub use set_entity_motion::SetEntityMotion;
 pub use set_local_player_as_initialized::SetLocalPlayerAsInitialized;
@@ -149,6 +176,7 @@ pub use transfer::Transfer;
 pub use update_abilities::UpdateAbilities;
 pub use update_attributes::{AttributeEntry, UpdateAttributes};
 pub use update_block::UpdateBlock;
+pub use update_client_input_locks::UpdateClientInputLocks;
 
 /// Game packet IDs.
 pub mod id {
@@ -180,21 +208,28 @@ pub mod id {
     pub const ANIMATE: u32 = 0x2C;
     pub const RESPAWN: u32 = 0x2D;
     pub const LEVEL_EVENT: u32 = 0x19;
+    pub const LEVEL_SOUND_EVENT: u32 = 0x7B;
     pub const ENTITY_EVENT: u32 = 0x1B;
+    pub const SET_ACTOR_DATA: u32 = 0x27;
     pub const MOB_EFFECT: u32 = 0x1C;
     pub const UPDATE_ATTRIBUTES: u32 = 0x1D;
     pub const INVENTORY_TRANSACTION: u32 = 0x1E;
     pub const MOB_EQUIPMENT: u32 = 0x1F;
+    pub const MOB_ARMOR_EQUIPMENT: u32 = 0x20;
     pub const PLAYER_ACTION: u32 = 0x24;
     pub const LEVEL_CHUNK: u32 = 0x3A;
+    pub const SET_DIFFICULTY: u32 = 0x3C;
     pub const CHANGE_DIMENSION: u32 = 0x3D;
     pub const SET_PLAYER_GAME_TYPE: u32 = 0x3E;
     pub const PLAYER_LIST: u32 = 0x3F;
     pub const REQUEST_CHUNK_RADIUS: u32 = 0x45;
     pub const CHUNK_RADIUS_UPDATED: u32 = 0x46;
+    pub const CLIENTBOUND_MAP_ITEM_DATA: u32 = 0x43;
+    pub const MAP_INFO_REQUEST: u32 = 0x44;
     pub const GAME_RULES_CHANGED: u32 = 0x48;
     pub const AVAILABLE_COMMANDS: u32 = 0x4C;
     pub const COMMAND_REQUEST: u32 = 0x4D;
+    pub const COMMAND_BLOCK_UPDATE: u32 = 0x4E;
     pub const COMMAND_OUTPUT: u32 = 0x4F;
     pub const SET_LOCAL_PLAYER_AS_INITIALIZED: u32 = 0x71;
     pub const AVAILABLE_ENTITY_IDENTIFIERS: u32 = 0x78;
@@ -213,6 +248,8 @@ pub mod id {
     pub const CONTAINER_CLOSE: u32 = 0x2F;
     pub const CONTAINER_SET_DATA: u32 = 0x33;
     pub const PLAYER_SKIN: u32 = 0x5D;
+    pub const EMOTE: u32 = 0x8A;
+    pub const EMOTE_LIST: u32 = 0x98;
     pub const BLOCK_ACTOR_DATA: u32 = 0x38;
     pub const BOSS_EVENT: u32 = 0x4A;
     pub const TRANSFER: u32 = 0x55;
@@ -224,6 +261,11 @@ pub mod id {
     pub const UPDATE_ABILITIES: u32 = 0xBB;
     pub const REQUEST_NETWORK_SETTINGS: u32 = 0xC1;
     pub const ITEM_REGISTRY: u32 = 0xA2;
+    pub const OPEN_SIGN: u32 = 0x12F;
+    pub const PLAYER_FOG: u32 = 0xA0;
+    pub const UPDATE_CLIENT_INPUT_LOCKS: u32 = 0xC4;
+    pub const CAMERA_PRESETS: u32 = 0xC6;
+    pub const CAMERA_INSTRUCTION: u32 = 0x12C;
     pub const SERVERBOUND_LOADING_SCREEN: u32 = 0x138;
 }
 
@@ -233,17 +275,23 @@ pub const PROTOCOL_VERSION: i32 = 924;
 /// Minimum supported protocol version (Minecraft Bedrock 1.26.2).
 pub const MIN_PROTOCOL_VERSION: i32 = 924;
 
+/// Accepted protocol versions and their game versions, newest first.
+/// Layout differences are handled by `proto_encode_versioned` (see
+/// [`crate::protocol`]).
+pub const SUPPORTED_VERSIONS: &[(i32, &str)] = &[(924, "1.26.2")];
+
 /// Check whether a client protocol version is supported.
 pub fn is_supported_version(v: i32) -> bool {
-    (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&v)
+    SUPPORTED_VERSIONS.iter().any(|&(p, _)| p == v)
 }
 
 /// Return the game version string for a supported protocol version.
 pub fn game_version_for_protocol(v: i32) -> &'static str {
-    match v {
-        924 => "1.26.2",
-        _ => "1.26.2", // fallback
-    }
+    SUPPORTED_VERSIONS
+        .iter()
+        .find(|&&(p, _)| p == v)
+        .map(|&(_, name)| name)
+        .unwrap_or(SUPPORTED_VERSIONS[0].1) // fallback
 }
 
 #[cfg(test)]
@@ -254,6 +302,7 @@ mod tests {
     fn supported_version_range() {
         assert!(is_supported_version(924));
         assert!(!is_supported_version(923));
+        assert!(!is_supported_version(898));
         assert!(!is_supported_version(925));
         assert!(!is_supported_version(0));
     }
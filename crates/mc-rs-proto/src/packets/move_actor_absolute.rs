@@ -22,6 +22,9 @@ pub struct MoveActorAbsolute {
 }
 
 impl MoveActorAbsolute {
+    pub const FLAG_ON_GROUND: u16 = 1;
+    pub const FLAG_TELEPORT: u16 = 2;
+
     /// Compress an angle (0..360) to a single byte.
     fn angle_to_byte(angle: f32) -> u8 {
         ((angle % 360.0 + 360.0) % 360.0 * (256.0 / 360.0)) as u8
@@ -38,13 +41,28 @@ impl MoveActorAbsolute {
     ) -> Self {
         Self {
             entity_runtime_id: runtime_id,
-            flags: if on_ground { 1 } else { 0 },
+            flags: if on_ground { Self::FLAG_ON_GROUND } else { 0 },
             position,
             pitch,
             yaw,
             head_yaw,
         }
     }
+
+    /// Create a move packet that makes clients teleport the entity instead
+    /// of interpolating towards it.
+    pub fn teleport(
+        runtime_id: u64,
+        position: Vec3,
+        pitch: f32,
+        yaw: f32,
+        head_yaw: f32,
+        on_ground: bool,
+    ) -> Self {
+        let mut pkt = Self::normal(runtime_id, position, pitch, yaw, head_yaw, on_ground);
+        pkt.flags |= Self::FLAG_TELEPORT;
+        pkt
+    }
 }
 
 impl ProtoEncode for MoveActorAbsolute {
@@ -90,5 +108,7 @@ mod tests {
         let airborne = MoveActorAbsolute::normal(1, Vec3::ZERO, 0.0, 0.0, 0.0, false);
         assert_eq!(grounded.flags, 1);
         assert_eq!(airborne.flags, 0);
+        let teleport = MoveActorAbsolute::teleport(1, Vec3::ZERO, 0.0, 0.0, 0.0, true);
+        assert_eq!(teleport.flags, 3);
     }
 }
@@ -105,6 +105,57 @@ impl MovePlayer {
             tick,
         }
     }
+
+    /// Create a Teleport packet, for a player who moved too far to
+    /// interpolate.
+    pub fn teleport(
+        runtime_entity_id: u64,
+        position: Vec3,
+        pitch: f32,
+        yaw: f32,
+        head_yaw: f32,
+        on_ground: bool,
+        tick: u64,
+    ) -> Self {
+        Self {
+            mode: MoveMode::Teleport,
+            teleport_cause: Some(0),
+            teleport_entity_type: Some(0),
+            ..Self::normal(
+                runtime_entity_id,
+                position,
+                pitch,
+                yaw,
+                head_yaw,
+                on_ground,
+                tick,
+            )
+        }
+    }
+
+    /// Create a Rotation packet, for a player who only turned.
+    pub fn rotation(
+        runtime_entity_id: u64,
+        position: Vec3,
+        pitch: f32,
+        yaw: f32,
+        head_yaw: f32,
+        on_ground: bool,
+        tick: u64,
+    ) -> Self {
+        Self {
+            mode: MoveMode::Rotation,
+            ..Self::normal(
+                runtime_entity_id,
+                position,
+                pitch,
+                yaw,
+                head_yaw,
+                on_ground,
+                tick,
+            )
+        }
+    }
 }
 
 impl ProtoEncode for MovePlayer {
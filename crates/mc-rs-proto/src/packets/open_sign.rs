@@ -0,0 +1,70 @@
+//! OpenSign (0x12F) — Server → Client.
+//!
+//! Opens the sign editor on the client for one face of a sign.
+
+use bytes::{Buf, BufMut};
+
+use crate::codec::{ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
+use crate::types::BlockPos;
+
+/// Opens the sign editing screen.
+#[derive(Debug, Clone)]
+pub struct OpenSign {
+    /// Position of the sign block.
+    pub position: BlockPos,
+    /// Whether to edit the front face (false = back face).
+    pub front_side: bool,
+}
+
+impl ProtoEncode for OpenSign {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        self.position.proto_encode(buf);
+        buf.put_u8(self.front_side as u8);
+    }
+}
+
+impl ProtoDecode for OpenSign {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let position = BlockPos::proto_decode(buf)?;
+        if buf.remaining() < 1 {
+            return Err(ProtoError::BufferTooShort {
+                needed: 1,
+                remaining: 0,
+            });
+        }
+        let front_side = buf.get_u8() != 0;
+        Ok(Self {
+            position,
+            front_side,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn open_sign_roundtrip() {
+        let pkt = OpenSign {
+            position: BlockPos::new(-12, 70, 300),
+            front_side: false,
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        assert_eq!(buf[buf.len() - 1], 0);
+
+        let decoded = OpenSign::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded.position, BlockPos::new(-12, 70, 300));
+        assert!(!decoded.front_side);
+    }
+
+    #[test]
+    fn decode_open_sign_truncated() {
+        let mut buf = BytesMut::new();
+        BlockPos::new(1, 2, 3).proto_encode(&mut buf);
+        assert!(OpenSign::proto_decode(&mut buf.freeze()).is_err());
+    }
+}
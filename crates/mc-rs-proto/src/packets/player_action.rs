@@ -2,9 +2,9 @@
 //!
 //! Sent when the player performs various actions: start/stop mining, etc.
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
-use crate::codec::ProtoDecode;
+use crate::codec::{ProtoDecode, ProtoEncode};
 use crate::error::ProtoError;
 use crate::types::{BlockPos, VarInt, VarUInt64};
 
@@ -31,6 +31,17 @@ impl PlayerActionType {
             other => Self::Other(other),
         }
     }
+
+    fn to_i32(self) -> i32 {
+        match self {
+            Self::StartBreak => 0,
+            Self::AbortBreak => 1,
+            Self::StopBreak => 2,
+            Self::PredictDestroyBlock => 22,
+            Self::ContinueDestroyBlock => 23,
+            Self::Other(other) => other,
+        }
+    }
 }
 
 /// PlayerAction packet fields.
@@ -43,6 +54,16 @@ pub struct PlayerAction {
     pub face: i32,
 }
 
+impl ProtoEncode for PlayerAction {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt64(self.entity_runtime_id).proto_encode(buf);
+        VarInt(self.action.to_i32()).proto_encode(buf);
+        self.block_position.proto_encode(buf);
+        self.result_position.proto_encode(buf);
+        VarInt(self.face).proto_encode(buf);
+    }
+}
+
 impl ProtoDecode for PlayerAction {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         let entity_runtime_id = VarUInt64::proto_decode(buf)?.0;
@@ -68,9 +89,6 @@ mod tests {
     use bytes::BytesMut;
     use std::io::Cursor;
 
-    use crate::codec::ProtoEncode;
-    use crate::types::VarUInt64;
-
     fn encode_action(action: i32, pos: BlockPos) -> BytesMut {
         let mut buf = BytesMut::new();
         VarUInt64(1).proto_encode(&mut buf); // entity_runtime_id
@@ -118,6 +136,24 @@ mod tests {
         assert_eq!(action.action, PlayerActionType::Other(99));
     }
 
+    #[test]
+    fn encode_roundtrip() {
+        let action = PlayerAction {
+            entity_runtime_id: 7,
+            action: PlayerActionType::ContinueDestroyBlock,
+            block_position: BlockPos::new(1, 2, 3),
+            result_position: BlockPos::new(0, 0, 0),
+            face: 4,
+        };
+        let mut buf = BytesMut::new();
+        action.proto_encode(&mut buf);
+        let decoded = PlayerAction::proto_decode(&mut Cursor::new(&buf[..])).unwrap();
+        assert_eq!(decoded.entity_runtime_id, 7);
+        assert_eq!(decoded.action, PlayerActionType::ContinueDestroyBlock);
+        assert_eq!(decoded.block_position, BlockPos::new(1, 2, 3));
+        assert_eq!(decoded.face, 4);
+    }
+
     #[test]
     fn decode_buffer_too_short() {
         let data = [0x01]; // Not enough data
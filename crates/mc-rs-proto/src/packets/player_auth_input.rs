@@ -4,9 +4,9 @@
 //! and input state. This is the primary movement packet under
 //! server-authoritative movement (auth_type = 1 or 2).
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
-use crate::codec::ProtoDecode;
+use crate::codec::{ProtoDecode, ProtoEncode};
 use crate::error::ProtoError;
 use crate::types::{VarUInt32, VarUInt64, Vec2, Vec3};
 
@@ -52,6 +52,9 @@ pub mod input_flags {
     pub const PERFORM_ITEM_INTERACTION: u64 = 1 << 35;
     pub const PERFORM_BLOCK_ACTIONS: u64 = 1 << 36;
     pub const PERFORM_ITEM_STACK_REQUEST: u64 = 1 << 37;
+
+    pub const START_GLIDING: u64 = 1 << 38;
+    pub const STOP_GLIDING: u64 = 1 << 39;
 }
 
 /// Core fields of the PlayerAuthInput packet.
@@ -82,6 +85,27 @@ impl PlayerAuthInput {
     }
 }
 
+impl ProtoEncode for PlayerAuthInput {
+    /// Writes the core fields only, without any conditional sub-packets. A
+    /// VR play mode gets a zero gaze direction.
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        buf.put_f32_le(self.pitch);
+        buf.put_f32_le(self.yaw);
+        self.position.proto_encode(buf);
+        self.move_vector.proto_encode(buf);
+        buf.put_f32_le(self.head_yaw);
+        VarUInt64(self.input_data).proto_encode(buf);
+        VarUInt32(self.input_mode).proto_encode(buf);
+        VarUInt32(self.play_mode).proto_encode(buf);
+        VarUInt32(self.interaction_model).proto_encode(buf);
+        if self.play_mode == 5 {
+            Vec3::ZERO.proto_encode(buf);
+        }
+        VarUInt64(self.tick).proto_encode(buf);
+        self.position_delta.proto_encode(buf);
+    }
+}
+
 impl ProtoDecode for PlayerAuthInput {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         if buf.remaining() < 8 {
@@ -138,23 +162,12 @@ impl ProtoDecode for PlayerAuthInput {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::codec::ProtoEncode;
-    use bytes::{BufMut, BytesMut};
+    use bytes::BytesMut;
 
     /// Encode a PlayerAuthInput into raw bytes (wire format).
     fn encode_test_input(pkt: &PlayerAuthInput) -> BytesMut {
         let mut buf = BytesMut::new();
-        buf.put_f32_le(pkt.pitch);
-        buf.put_f32_le(pkt.yaw);
-        pkt.position.proto_encode(&mut buf);
-        pkt.move_vector.proto_encode(&mut buf);
-        buf.put_f32_le(pkt.head_yaw);
-        VarUInt64(pkt.input_data).proto_encode(&mut buf);
-        VarUInt32(pkt.input_mode).proto_encode(&mut buf);
-        VarUInt32(pkt.play_mode).proto_encode(&mut buf);
-        VarUInt32(pkt.interaction_model).proto_encode(&mut buf);
-        VarUInt64(pkt.tick).proto_encode(&mut buf);
-        pkt.position_delta.proto_encode(&mut buf);
+        pkt.proto_encode(&mut buf);
         buf
     }
 
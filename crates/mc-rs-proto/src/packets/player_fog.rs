@@ -0,0 +1,42 @@
+//! PlayerFog (0xA0) — Server → Client.
+//!
+//! Replaces the client's fog stack: fog definitions from resource packs,
+//! the last one drawn on top of the others.
+
+use bytes::BufMut;
+
+use crate::codec::{write_string, ProtoEncode};
+use crate::types::VarUInt32;
+
+/// PlayerFog packet.
+pub struct PlayerFog {
+    /// Fog identifiers, such as `minecraft:fog_hell`, bottom first.
+    pub fog_stack: Vec<String>,
+}
+
+impl ProtoEncode for PlayerFog {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt32(self.fog_stack.len() as u32).proto_encode(buf);
+        for fog in &self.fog_stack {
+            write_string(buf, fog);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn encode_fog_stack() {
+        let pkt = PlayerFog {
+            fog_stack: vec!["minecraft:fog_hell".into()],
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        assert_eq!(buf[0], 1);
+        assert_eq!(buf[1] as usize, "minecraft:fog_hell".len());
+        assert_eq!(&buf[2..], b"minecraft:fog_hell");
+    }
+}
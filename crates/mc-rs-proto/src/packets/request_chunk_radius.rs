@@ -1,8 +1,8 @@
 //! RequestChunkRadius (0x45) — Client → Server.
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
-use crate::codec::ProtoDecode;
+use crate::codec::{ProtoDecode, ProtoEncode};
 use crate::error::ProtoError;
 use crate::types::VarInt;
 
@@ -13,6 +13,13 @@ pub struct RequestChunkRadius {
     pub max_chunk_radius: i32,
 }
 
+impl ProtoEncode for RequestChunkRadius {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarInt(self.chunk_radius).proto_encode(buf);
+        VarInt(self.max_chunk_radius).proto_encode(buf);
+    }
+}
+
 impl ProtoDecode for RequestChunkRadius {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         let chunk_radius = VarInt::proto_decode(buf)?.0;
@@ -27,14 +34,16 @@ impl ProtoDecode for RequestChunkRadius {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::codec::ProtoEncode;
     use bytes::BytesMut;
 
     #[test]
     fn decode_radius() {
         let mut buf = BytesMut::new();
-        VarInt(8).proto_encode(&mut buf);
-        VarInt(16).proto_encode(&mut buf);
+        RequestChunkRadius {
+            chunk_radius: 8,
+            max_chunk_radius: 16,
+        }
+        .proto_encode(&mut buf);
         let pkt = RequestChunkRadius::proto_decode(&mut buf.freeze().as_ref()).unwrap();
         assert_eq!(pkt.chunk_radius, 8);
         assert_eq!(pkt.max_chunk_radius, 16);
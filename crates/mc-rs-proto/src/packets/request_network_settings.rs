@@ -1,8 +1,8 @@
 //! RequestNetworkSettings (0xC1) — Client → Server.
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
-use crate::codec::ProtoDecode;
+use crate::codec::{ProtoDecode, ProtoEncode};
 use crate::error::ProtoError;
 
 /// The first game packet sent by the client after the RakNet handshake.
@@ -13,6 +13,12 @@ pub struct RequestNetworkSettings {
     pub protocol_version: i32,
 }
 
+impl ProtoEncode for RequestNetworkSettings {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        buf.put_i32(self.protocol_version); // BE
+    }
+}
+
 impl ProtoDecode for RequestNetworkSettings {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         if buf.remaining() < 4 {
@@ -40,6 +46,16 @@ mod tests {
         assert_eq!(pkt.protocol_version, 924);
     }
 
+    #[test]
+    fn encode_roundtrip() {
+        let mut buf = bytes::BytesMut::new();
+        RequestNetworkSettings {
+            protocol_version: 924,
+        }
+        .proto_encode(&mut buf);
+        assert_eq!(&buf[..], &[0x00, 0x00, 0x03, 0x9C]);
+    }
+
     #[test]
     fn decode_buffer_too_short() {
         let data = Bytes::from_static(&[0x00, 0x00]);
@@ -1,8 +1,8 @@
 //! ResourcePackClientResponse (0x08) — Client → Server.
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
-use crate::codec::{self, ProtoDecode};
+use crate::codec::{self, ProtoDecode, ProtoEncode};
 use crate::error::ProtoError;
 use crate::types::VarUInt32;
 
@@ -39,6 +39,16 @@ pub struct ResourcePackClientResponse {
     pub resource_pack_ids: Vec<String>,
 }
 
+impl ProtoEncode for ResourcePackClientResponse {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        buf.put_u8(self.status as u8);
+        VarUInt32(self.resource_pack_ids.len() as u32).proto_encode(buf);
+        for id in &self.resource_pack_ids {
+            codec::write_string(buf, id);
+        }
+    }
+}
+
 impl ProtoDecode for ResourcePackClientResponse {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         if !buf.has_remaining() {
@@ -100,6 +110,19 @@ mod tests {
         assert_eq!(pkt.resource_pack_ids, vec!["test"]);
     }
 
+    #[test]
+    fn encode_roundtrip() {
+        let mut buf = BytesMut::new();
+        ResourcePackClientResponse {
+            status: ResourcePackResponseStatus::SendPacks,
+            resource_pack_ids: vec!["test".into()],
+        }
+        .proto_encode(&mut buf);
+        let pkt = ResourcePackClientResponse::proto_decode(&mut buf.freeze().as_ref()).unwrap();
+        assert_eq!(pkt.status, ResourcePackResponseStatus::SendPacks);
+        assert_eq!(pkt.resource_pack_ids, vec!["test"]);
+    }
+
     #[test]
     fn decode_unknown_status() {
         let mut buf = BytesMut::new();
@@ -6,6 +6,7 @@
 use bytes::BufMut;
 
 use crate::codec::{self, ProtoEncode};
+use crate::types::VarUInt32;
 
 /// Metadata about a resource/behavior pack the server is about to transfer.
 #[derive(Debug, Clone)]
@@ -18,11 +19,11 @@ pub struct ResourcePackDataInfo {
     pub chunk_count: u32,
     /// Total compressed pack size in bytes.
     pub pack_size: u64,
-    /// SHA-256 hash of the pack file.
-    pub pack_hash: String,
+    /// SHA-256 hash of the pack file (raw bytes).
+    pub pack_hash: Vec<u8>,
     /// Whether this is a premium pack.
     pub is_premium: bool,
-    /// Pack type: 1 = resource, 2 = behavior.
+    /// Pack type: 4 = behavior, 6 = resources.
     pub pack_type: u8,
 }
 
@@ -32,7 +33,8 @@ impl ProtoEncode for ResourcePackDataInfo {
         buf.put_u32_le(self.max_chunk_size);
         buf.put_u32_le(self.chunk_count);
         buf.put_u64_le(self.pack_size);
-        codec::write_string(buf, &self.pack_hash);
+        VarUInt32(self.pack_hash.len() as u32).proto_encode(buf);
+        buf.put_slice(&self.pack_hash);
         buf.put_u8(self.is_premium as u8);
         buf.put_u8(self.pack_type);
     }
@@ -50,9 +52,9 @@ mod tests {
             max_chunk_size: 1_048_576,
             chunk_count: 2,
             pack_size: 2_000_000,
-            pack_hash: "h".into(),
+            pack_hash: vec![0xAB],
             is_premium: false,
-            pack_type: 2,
+            pack_type: 4,
         };
         let mut buf = BytesMut::new();
         pkt.proto_encode(&mut buf);
@@ -0,0 +1,66 @@
+//! SetActorData (0x27) — Server → Client.
+//!
+//! Replaces entity metadata entries, e.g. to rename an entity.
+
+use bytes::{Buf, BufMut};
+
+use crate::codec::{ProtoDecode, ProtoEncode};
+use crate::entity_metadata::{decode_entity_metadata, encode_entity_metadata, EntityMetadataEntry};
+use crate::error::ProtoError;
+use crate::types::{VarUInt32, VarUInt64};
+
+/// Update some metadata entries of an entity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetActorData {
+    pub entity_runtime_id: u64,
+    pub metadata: Vec<EntityMetadataEntry>,
+    pub tick: u64,
+}
+
+impl ProtoEncode for SetActorData {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt64(self.entity_runtime_id).proto_encode(buf);
+        encode_entity_metadata(buf, &self.metadata);
+        // Entity sync properties (int count + float count)
+        VarUInt32(0).proto_encode(buf);
+        VarUInt32(0).proto_encode(buf);
+        VarUInt64(self.tick).proto_encode(buf);
+    }
+}
+
+impl ProtoDecode for SetActorData {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let entity_runtime_id = VarUInt64::proto_decode(buf)?.0;
+        let metadata = decode_entity_metadata(buf)?;
+        // The server sends no sync properties
+        VarUInt32::proto_decode(buf)?;
+        VarUInt32::proto_decode(buf)?;
+        let tick = VarUInt64::proto_decode(buf)?.0;
+        Ok(Self {
+            entity_runtime_id,
+            metadata,
+            tick,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity_metadata::MetadataBuilder;
+    use bytes::BytesMut;
+
+    #[test]
+    fn encode_nametag_update() {
+        let pkt = SetActorData {
+            entity_runtime_id: 7,
+            metadata: MetadataBuilder::new().nametag("Hi").build(),
+            tick: 0,
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        // runtime ID, 1 entry: key 4, type 4, "Hi", 2 empty property lists, tick
+        assert_eq!(&buf[..], &[7, 1, 4, 4, 2, b'H', b'i', 0, 0, 0]);
+        assert_eq!(SetActorData::proto_decode(&mut buf.freeze()).unwrap(), pkt);
+    }
+}
@@ -0,0 +1,35 @@
+//! SetDifficulty (0x3C) — Server → Client.
+//!
+//! Changes the world difficulty shown by the client.
+
+use bytes::BufMut;
+
+use crate::codec::ProtoEncode;
+use crate::types::VarUInt32;
+
+/// Notify the client that the difficulty has changed.
+pub struct SetDifficulty {
+    /// 0 = peaceful, 1 = easy, 2 = normal, 3 = hard.
+    pub difficulty: u32,
+}
+
+impl ProtoEncode for SetDifficulty {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt32(self.difficulty).proto_encode(buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn encode_hard() {
+        let pkt = SetDifficulty { difficulty: 3 };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        // VarUInt32(3), no zigzag
+        assert_eq!(&buf[..], &[0x03]);
+    }
+}
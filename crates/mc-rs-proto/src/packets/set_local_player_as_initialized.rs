@@ -1,8 +1,8 @@
 //! SetLocalPlayerAsInitialized (0x71) — Client → Server.
 
-use bytes::Buf;
+use bytes::{Buf, BufMut};
 
-use crate::codec::ProtoDecode;
+use crate::codec::{ProtoDecode, ProtoEncode};
 use crate::error::ProtoError;
 use crate::types::VarUInt64;
 
@@ -12,6 +12,12 @@ pub struct SetLocalPlayerAsInitialized {
     pub entity_runtime_id: u64,
 }
 
+impl ProtoEncode for SetLocalPlayerAsInitialized {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt64(self.entity_runtime_id).proto_encode(buf);
+    }
+}
+
 impl ProtoDecode for SetLocalPlayerAsInitialized {
     fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
         let entity_runtime_id = VarUInt64::proto_decode(buf)?.0;
@@ -22,13 +28,15 @@ impl ProtoDecode for SetLocalPlayerAsInitialized {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::codec::ProtoEncode;
     use bytes::BytesMut;
 
     #[test]
     fn decode_runtime_id() {
         let mut buf = BytesMut::new();
-        VarUInt64(42).proto_encode(&mut buf);
+        SetLocalPlayerAsInitialized {
+            entity_runtime_id: 42,
+        }
+        .proto_encode(&mut buf);
         let pkt = SetLocalPlayerAsInitialized::proto_decode(&mut buf.freeze().as_ref()).unwrap();
         assert_eq!(pkt.entity_runtime_id, 42);
     }
@@ -8,6 +8,12 @@ use bytes::BufMut;
 use crate::codec::ProtoEncode;
 use crate::types::VarUInt32;
 
+/// Flying speed of creative players.
+const FLY_SPEED: f32 = 0.05;
+
+/// Spectators fly twice as fast as creative players.
+const SPECTATOR_FLY_SPEED: f32 = 0.1;
+
 /// Update a player's ability data.
 pub struct UpdateAbilities {
     pub command_permission_level: u8,
@@ -26,16 +32,22 @@ impl ProtoEncode for UpdateAbilities {
         VarUInt32(1).proto_encode(buf);
         // Layer type = Base (0)
         buf.put_u16_le(0);
-        // Abilities allowed bitmask
-        buf.put_u32_le(0x0001_BFFF);
+        // Abilities allowed bitmask (including no-clip)
+        buf.put_u32_le(0x0003_BFFF);
         // Abilities values bitmask
         let values = match self.gamemode {
-            1 | 3 => 0x0000_0477, // creative/spectator: fly, instabuild, mayfly
-            _ => 0x0000_0003,     // survival/adventure: basic
+            1 => 0x0000_0477, // creative: fly, instabuild, mayfly
+            3 => 0x0002_0700, // spectator: invulnerable, flying, mayfly, no-clip
+            _ => 0x0000_0003, // survival/adventure: basic
         };
         buf.put_u32_le(values);
         // Fly speed
-        buf.put_f32_le(0.05);
+        let fly_speed = if self.gamemode == 3 {
+            SPECTATOR_FLY_SPEED
+        } else {
+            FLY_SPEED
+        };
+        buf.put_f32_le(fly_speed);
         // Walk speed
         buf.put_f32_le(0.1);
     }
@@ -76,4 +88,22 @@ mod tests {
         let values = u32::from_le_bytes([buf[17], buf[18], buf[19], buf[20]]);
         assert_eq!(values, 0x0000_0003);
     }
+
+    #[test]
+    fn encode_spectator_abilities() {
+        let pkt = UpdateAbilities {
+            command_permission_level: 0,
+            permission_level: 1,
+            entity_unique_id: 1,
+            gamemode: 3,
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let values = u32::from_le_bytes([buf[17], buf[18], buf[19], buf[20]]);
+        // No-clip and flying, but no building
+        assert_eq!(values & 0x0002_0200, 0x0002_0200);
+        assert_eq!(values & 0x0000_0003, 0);
+        let fly_speed = f32::from_le_bytes([buf[21], buf[22], buf[23], buf[24]]);
+        assert_eq!(fly_speed, 0.1);
+    }
 }
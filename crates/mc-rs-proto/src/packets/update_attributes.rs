@@ -2,12 +2,14 @@
 //!
 //! Syncs entity attributes (health, movement speed, etc.) to the client.
 
-use bytes::BufMut;
+use bytes::{Buf, BufMut};
 
-use crate::codec::{write_string, ProtoEncode};
+use crate::codec::{read_string, write_string, ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
 use crate::types::{VarUInt32, VarUInt64};
 
 /// A single attribute entry.
+#[derive(Debug, Clone)]
 pub struct AttributeEntry {
     pub min: f32,
     pub max: f32,
@@ -17,6 +19,7 @@ pub struct AttributeEntry {
 }
 
 /// UpdateAttributes packet.
+#[derive(Debug, Clone)]
 pub struct UpdateAttributes {
     pub entity_runtime_id: u64,
     pub attributes: Vec<AttributeEntry>,
@@ -223,6 +226,46 @@ impl ProtoEncode for UpdateAttributes {
     }
 }
 
+impl ProtoDecode for UpdateAttributes {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        let entity_runtime_id = VarUInt64::proto_decode(buf)?.0;
+        let count = VarUInt32::proto_decode(buf)?.0;
+        let mut attributes = Vec::new();
+        for _ in 0..count {
+            if buf.remaining() < 16 {
+                return Err(ProtoError::BufferTooShort {
+                    needed: 16,
+                    remaining: buf.remaining(),
+                });
+            }
+            let min = buf.get_f32_le();
+            let max = buf.get_f32_le();
+            let current = buf.get_f32_le();
+            let default = buf.get_f32_le();
+            let name = read_string(buf)?;
+            let modifiers = VarUInt32::proto_decode(buf)?.0;
+            if modifiers != 0 {
+                return Err(ProtoError::InvalidData(format!(
+                    "attribute modifiers are not supported ({name} has {modifiers})"
+                )));
+            }
+            attributes.push(AttributeEntry {
+                min,
+                max,
+                current,
+                default,
+                name,
+            });
+        }
+        let tick = VarUInt64::proto_decode(buf)?.0;
+        Ok(Self {
+            entity_runtime_id,
+            attributes,
+            tick,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -303,4 +346,18 @@ mod tests {
         assert!((pkt.attributes[0].default - 0.0).abs() < 0.001);
         assert!((pkt.attributes[1].max - 1.0).abs() < 0.001);
     }
+
+    #[test]
+    fn roundtrip_health_and_hunger() {
+        let pkt = UpdateAttributes::health_and_hunger(7, 13.5, 18.0, 4.0, 1.5, 300);
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        let decoded = UpdateAttributes::proto_decode(&mut buf.freeze()).unwrap();
+        assert_eq!(decoded.entity_runtime_id, 7);
+        assert_eq!(decoded.tick, 300);
+        assert_eq!(decoded.attributes.len(), 4);
+        assert_eq!(decoded.attributes[0].name, "minecraft:health");
+        assert_eq!(decoded.attributes[0].current, 13.5);
+        assert_eq!(decoded.attributes[3].max, 5.0);
+    }
 }
@@ -2,9 +2,10 @@
 //!
 //! Sent when a single block changes in the world.
 
-use bytes::BufMut;
+use bytes::{Buf, BufMut};
 
-use crate::codec::ProtoEncode;
+use crate::codec::{ProtoDecode, ProtoEncode};
+use crate::error::ProtoError;
 use crate::types::{BlockPos, VarUInt32};
 
 /// UpdateBlock packet.
@@ -40,10 +41,20 @@ impl ProtoEncode for UpdateBlock {
     }
 }
 
+impl ProtoDecode for UpdateBlock {
+    fn proto_decode(buf: &mut impl Buf) -> Result<Self, ProtoError> {
+        Ok(Self {
+            position: BlockPos::proto_decode(buf)?,
+            runtime_id: VarUInt32::proto_decode(buf)?.0,
+            flags: VarUInt32::proto_decode(buf)?.0,
+            layer: VarUInt32::proto_decode(buf)?.0,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::codec::ProtoDecode;
     use bytes::BytesMut;
 
     #[test]
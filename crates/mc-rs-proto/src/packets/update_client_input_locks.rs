@@ -0,0 +1,65 @@
+//! UpdateClientInputLocks (0xC4) — Server → Client.
+//!
+//! Locks some of the player's inputs, such as turning the camera or moving.
+
+use bytes::BufMut;
+
+use crate::codec::ProtoEncode;
+use crate::types::{VarUInt32, Vec3};
+
+/// Lockable inputs, by their `/inputpermission` name.
+pub const INPUT_LOCKS: [(&str, u32); 11] = [
+    ("camera", 1 << 1),
+    ("movement", 1 << 2),
+    ("lateral_movement", 1 << 4),
+    ("sneak", 1 << 5),
+    ("jump", 1 << 6),
+    ("mount", 1 << 7),
+    ("dismount", 1 << 8),
+    ("move_forward", 1 << 9),
+    ("move_backward", 1 << 10),
+    ("move_left", 1 << 11),
+    ("move_right", 1 << 12),
+];
+
+/// The lock flag of an input, by name.
+pub fn input_lock(name: &str) -> Option<u32> {
+    INPUT_LOCKS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, flag)| flag)
+}
+
+/// UpdateClientInputLocks packet.
+pub struct UpdateClientInputLocks {
+    /// Locked inputs, flags from [`INPUT_LOCKS`].
+    pub locks: u32,
+    /// Where the server has the player.
+    pub position: Vec3,
+}
+
+impl ProtoEncode for UpdateClientInputLocks {
+    fn proto_encode(&self, buf: &mut impl BufMut) {
+        VarUInt32(self.locks).proto_encode(buf);
+        self.position.proto_encode(buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+
+    #[test]
+    fn encode_camera_and_movement_lock() {
+        let pkt = UpdateClientInputLocks {
+            locks: input_lock("camera").unwrap() | input_lock("movement").unwrap(),
+            position: Vec3::new(0.5, 64.0, 0.5),
+        };
+        let mut buf = BytesMut::new();
+        pkt.proto_encode(&mut buf);
+        assert_eq!(buf[0], 0b110);
+        assert_eq!(buf.len(), 1 + 12);
+        assert_eq!(input_lock("fly"), None);
+    }
+}
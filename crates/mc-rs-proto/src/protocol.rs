@@ -0,0 +1,86 @@
+//! Per-connection protocol adapter.
+//!
+//! The server speaks the newest protocol internally and accepts every version
+//! in [`SUPPORTED_VERSIONS`]. Each connection keeps a [`ProtocolAdapter`] for
+//! the version its client announced in RequestNetworkSettings; packets are
+//! encoded through it so that version-specific layouts (fields added or
+//! removed between releases) are handled by the packet types'
+//! `proto_encode_versioned` overrides. Only the current protocol is supported
+//! for now, so no packet overrides it: accepting older clients also needs
+//! their packet layouts and block/item palettes, which this tree does not
+//! have. Other versions are refused at login with `PlayStatus`.
+
+use bytes::{Bytes, BytesMut};
+
+use crate::codec::ProtoEncode;
+use crate::packets::{PROTOCOL_VERSION, SUPPORTED_VERSIONS};
+use crate::types::VarUInt32;
+
+/// Encoder/decoder for one client protocol version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolAdapter {
+    protocol: i32,
+    game_version: &'static str,
+}
+
+impl Default for ProtocolAdapter {
+    /// Adapter for the newest protocol.
+    fn default() -> Self {
+        Self::new(PROTOCOL_VERSION).expect("current protocol is supported")
+    }
+}
+
+impl ProtocolAdapter {
+    /// Adapter for `protocol`, or `None` if that version is not supported.
+    pub fn new(protocol: i32) -> Option<Self> {
+        SUPPORTED_VERSIONS
+            .iter()
+            .find(|&&(p, _)| p == protocol)
+            .map(|&(protocol, game_version)| Self {
+                protocol,
+                game_version,
+            })
+    }
+
+    pub fn protocol(&self) -> i32 {
+        self.protocol
+    }
+
+    /// Game version string for this protocol (e.g. `"1.26.2"`).
+    pub fn game_version(&self) -> &'static str {
+        self.game_version
+    }
+
+    /// Encode a sub-packet (VarUInt32 packet ID + body) for this client.
+    pub fn encode(&self, packet_id: u32, packet: &impl ProtoEncode) -> Bytes {
+        let mut buf = BytesMut::new();
+        VarUInt32(packet_id).proto_encode(&mut buf);
+        packet.proto_encode_versioned(&mut buf, self.protocol);
+        buf.freeze()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packets::Disconnect;
+
+    #[test]
+    fn adapter_for_supported_versions_only() {
+        assert_eq!(ProtocolAdapter::new(924).unwrap().game_version(), "1.26.2");
+        assert!(ProtocolAdapter::new(898).is_none());
+        assert!(ProtocolAdapter::new(860).is_none());
+        let current = ProtocolAdapter::default();
+        assert_eq!(current.protocol(), PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn encode_prefixes_packet_id() {
+        let packet = Disconnect::with_message("bye");
+        let encoded = ProtocolAdapter::default().encode(0x05, &packet);
+        let mut body = BytesMut::new();
+        packet.proto_encode(&mut body);
+        assert_eq!(encoded[0], 0x05);
+        assert_eq!(&encoded[1..], &body[..]);
+    }
+}
@@ -0,0 +1,253 @@
+//! Datagram capture files.
+//!
+//! When capture is enabled the server records every UDP datagram it receives
+//! or sends, so that a session can be replayed later through the codec (see
+//! the `mc-rs-replay` crate).
+//!
+//! ```text
+//! file    = "MCRSCAP\0" u16_le(version) record*
+//! record  = u64_le(unix micros) u8(direction) address u32_le(len) data[len]
+//! ```
+//!
+//! `address` uses the RakNet wire encoding ([`RakNetAddress`]).
+
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bytes::{Bytes, BytesMut};
+
+use crate::address::RakNetAddress;
+use crate::error::RakNetError;
+
+/// Magic bytes at the start of a capture file.
+pub const MAGIC: [u8; 8] = *b"MCRSCAP\0";
+/// Current capture format version.
+pub const VERSION: u16 = 1;
+/// Upper bound on a recorded datagram (anything larger is corrupt).
+const MAX_RECORD_LEN: usize = 64 * 1024;
+
+/// Direction of a captured datagram, relative to the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+impl Direction {
+    fn to_byte(self) -> u8 {
+        match self {
+            Direction::Inbound => 0,
+            Direction::Outbound => 1,
+        }
+    }
+
+    fn from_byte(b: u8) -> Result<Self, RakNetError> {
+        match b {
+            0 => Ok(Direction::Inbound),
+            1 => Ok(Direction::Outbound),
+            _ => Err(RakNetError::InvalidCapture(format!("bad direction {b}"))),
+        }
+    }
+}
+
+/// A single captured datagram.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRecord {
+    /// Capture time in microseconds since the Unix epoch.
+    pub timestamp_us: u64,
+    pub direction: Direction,
+    /// Remote address (source for inbound, destination for outbound).
+    pub addr: SocketAddr,
+    pub data: Bytes,
+}
+
+/// Appends datagrams to a capture file.
+pub struct CaptureWriter<W: Write = BufWriter<File>> {
+    out: W,
+}
+
+impl CaptureWriter {
+    /// Create (truncate) a capture file.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Wrap a writer and emit the file header.
+    pub fn new(mut out: W) -> io::Result<Self> {
+        out.write_all(&MAGIC)?;
+        out.write_all(&VERSION.to_le_bytes())?;
+        Ok(Self { out })
+    }
+
+    /// Record a datagram with the current time.
+    pub fn record(
+        &mut self,
+        direction: Direction,
+        addr: SocketAddr,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let timestamp_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        self.write_record(timestamp_us, direction, addr, data)
+    }
+
+    fn write_record(
+        &mut self,
+        timestamp_us: u64,
+        direction: Direction,
+        addr: SocketAddr,
+        data: &[u8],
+    ) -> io::Result<()> {
+        let mut header = BytesMut::with_capacity(48);
+        header.extend_from_slice(&timestamp_us.to_le_bytes());
+        header.extend_from_slice(&[direction.to_byte()]);
+        RakNetAddress(addr).encode(&mut header);
+        header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        self.out.write_all(&header)?;
+        self.out.write_all(data)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Reads records back from a capture file.
+pub struct CaptureReader<R: Read> {
+    input: R,
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// Validate the file header.
+    pub fn new(mut input: R) -> Result<Self, RakNetError> {
+        let mut header = [0u8; 10];
+        input.read_exact(&mut header)?;
+        if header[..8] != MAGIC {
+            return Err(RakNetError::InvalidCapture("bad magic".into()));
+        }
+        let version = u16::from_le_bytes([header[8], header[9]]);
+        if version != VERSION {
+            return Err(RakNetError::InvalidCapture(format!(
+                "unsupported version {version}"
+            )));
+        }
+        Ok(Self { input })
+    }
+
+    /// Read the next record, or `None` at end of file.
+    pub fn next_record(&mut self) -> Result<Option<CaptureRecord>, RakNetError> {
+        let mut ts = [0u8; 8];
+        match self.input.read_exact(&mut ts) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let mut dir = [0u8; 1];
+        self.input.read_exact(&mut dir)?;
+        let direction = Direction::from_byte(dir[0])?;
+        let addr = self.read_address()?;
+        let mut len = [0u8; 4];
+        self.input.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_RECORD_LEN {
+            return Err(RakNetError::InvalidCapture(format!(
+                "record too large ({len} bytes)"
+            )));
+        }
+        let mut data = vec![0u8; len];
+        self.input.read_exact(&mut data)?;
+        Ok(Some(CaptureRecord {
+            timestamp_us: u64::from_le_bytes(ts),
+            direction,
+            addr,
+            data: Bytes::from(data),
+        }))
+    }
+
+    fn read_address(&mut self) -> Result<SocketAddr, RakNetError> {
+        let mut version = [0u8; 1];
+        self.input.read_exact(&mut version)?;
+        // IPv4: 4 address + 2 port; IPv6: family(2) + port(2) + flow(4) + addr(16) + scope(4)
+        let rest_len = match version[0] {
+            4 => 6,
+            6 => 28,
+            v => return Err(RakNetError::InvalidAddressVersion(v)),
+        };
+        let mut buf = vec![version[0]];
+        buf.resize(1 + rest_len, 0);
+        self.input.read_exact(&mut buf[1..])?;
+        Ok(RakNetAddress::decode(&mut &buf[..])?.0)
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = Result<CaptureRecord, RakNetError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_record().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_read_roundtrip() {
+        let v4: SocketAddr = "192.168.1.20:50000".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::2]:19133".parse().unwrap();
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        writer
+            .write_record(1_000, Direction::Inbound, v4, &[0x01, 0x02, 0x03])
+            .unwrap();
+        writer
+            .write_record(2_000, Direction::Outbound, v6, &[0xFE])
+            .unwrap();
+        let bytes = writer.into_inner();
+
+        let records: Vec<CaptureRecord> = CaptureReader::new(&bytes[..])
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].timestamp_us, 1_000);
+        assert_eq!(records[0].direction, Direction::Inbound);
+        assert_eq!(records[0].addr, v4);
+        assert_eq!(&records[0].data[..], &[0x01, 0x02, 0x03]);
+        assert_eq!(records[1].direction, Direction::Outbound);
+        assert_eq!(records[1].addr, v6);
+    }
+
+    #[test]
+    fn reject_bad_header() {
+        assert!(CaptureReader::new(&b"NOTACAP\0\x01\x00"[..]).is_err());
+        assert!(CaptureReader::new(&b"MCRSCAP\0\x09\x00"[..]).is_err());
+    }
+
+    #[test]
+    fn truncated_record_is_error() {
+        let addr: SocketAddr = "10.0.0.1:1".parse().unwrap();
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        writer
+            .write_record(1, Direction::Inbound, addr, &[1, 2, 3, 4])
+            .unwrap();
+        let mut bytes = writer.into_inner();
+        bytes.truncate(bytes.len() - 2);
+        let mut reader = CaptureReader::new(&bytes[..]).unwrap();
+        assert!(reader.next_record().is_err());
+    }
+}
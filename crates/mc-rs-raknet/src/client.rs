@@ -0,0 +1,479 @@
+//! RakNet client: the client side of the connection handshake and of a
+//! connected session. Used by the load-testing bots.
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use bytes::{Bytes, BytesMut};
+use tokio::net::UdpSocket;
+
+use crate::address::RakNetAddress;
+use crate::constants::*;
+use crate::error::RakNetError;
+use crate::packet::frame::{AckNack, FrameSet, Reliability};
+use crate::packet::offline::{self, OfflinePacket};
+use crate::packet::online::{self, OnlinePacket, NUM_SYSTEM_ADDRESSES};
+use crate::reliability::{compress_ack_records, expand_ack_records};
+use crate::session::{RakNetSession, SessionState};
+
+/// Game packet wrapper byte inside a RakNet frame.
+const GAME_PACKET: u8 = 0xFE;
+
+/// How long to wait for a reply before resending a handshake packet.
+const HANDSHAKE_RETRY: Duration = Duration::from_millis(500);
+
+/// How often the client pings the server to measure latency.
+const CLIENT_PING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Counters kept over the life of a client connection.
+#[derive(Debug, Clone, Default)]
+pub struct ClientStats {
+    pub datagrams_sent: u64,
+    pub datagrams_received: u64,
+    /// FrameSets received, the datagrams sequence numbers are counted from.
+    pub framesets_received: u64,
+    /// Highest FrameSet sequence number received from the server.
+    pub highest_sequence: Option<u32>,
+    /// Our FrameSets the server reported missing.
+    pub datagrams_nacked: u64,
+    /// Round trips of the answered ConnectedPings.
+    pub ping_rtts: Vec<Duration>,
+}
+
+impl ClientStats {
+    /// FrameSets from the server that never arrived: gaps in the sequence
+    /// numbers received so far.
+    pub fn datagrams_missed(&self) -> u64 {
+        self.highest_sequence.map_or(0, |h| {
+            (h as u64 + 1).saturating_sub(self.framesets_received)
+        })
+    }
+}
+
+/// A RakNet connection to a server.
+pub struct RakNetClient {
+    socket: UdpSocket,
+    session: RakNetSession,
+    guid: i64,
+    /// Origin of the timestamps in pings and connection packets.
+    epoch: Instant,
+    /// ConnectionRequestAccepted, kept until the handshake completes.
+    accepted: Option<(RakNetAddress, i64)>,
+    pub stats: ClientStats,
+}
+
+impl RakNetClient {
+    /// Connect to a server: the offline handshake, then ConnectionRequest
+    /// and NewIncomingConnection. `mtu` is the size proposed to the server,
+    /// which may lower it.
+    pub async fn connect(
+        server: SocketAddr,
+        guid: i64,
+        mtu: u16,
+        timeout: Duration,
+    ) -> Result<Self, RakNetError> {
+        let local: SocketAddr = if server.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+        let socket = UdpSocket::bind(local).await?;
+        socket.connect(server).await?;
+        let deadline = Instant::now() + timeout;
+
+        let mtu = open_connection(&socket, server, guid, mtu, deadline).await?;
+        let mut session = RakNetSession::new(server, mtu, guid);
+        session.state = SessionState::HandshakeCompleted;
+        let mut client = Self {
+            socket,
+            session,
+            guid,
+            epoch: Instant::now(),
+            accepted: None,
+            stats: ClientStats::default(),
+        };
+
+        let request = OnlinePacket::ConnectionRequest {
+            client_guid: guid,
+            timestamp: client.timestamp(),
+            use_security: false,
+        };
+        client.queue(&request, Reliability::ReliableOrdered);
+        client.tick().await?;
+
+        let mut buf = vec![0u8; RECV_BUF_SIZE];
+        let (server_address, accept_timestamp) = loop {
+            if let Some(accepted) = client.accepted.take() {
+                break accepted;
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(RakNetError::Timeout("ConnectionRequestAccepted"));
+            }
+            match tokio::time::timeout(remaining.min(HANDSHAKE_RETRY), client.socket.recv(&mut buf))
+                .await
+            {
+                Ok(len) => {
+                    let len = len?;
+                    client.handle_datagram(&buf[..len])?;
+                }
+                Err(_) => client.tick().await?,
+            }
+        };
+
+        let mut system_addresses = [RakNetAddress::EMPTY_V4; NUM_SYSTEM_ADDRESSES];
+        system_addresses[0] = RakNetAddress::from(client.socket.local_addr()?);
+        let incoming = OnlinePacket::NewIncomingConnection {
+            server_address,
+            system_addresses,
+            request_timestamp: accept_timestamp,
+            accept_timestamp: client.timestamp(),
+        };
+        client.queue(&incoming, Reliability::ReliableOrdered);
+        client.session.state = SessionState::Connected;
+        client.tick().await?;
+        Ok(client)
+    }
+
+    /// The MTU agreed with the server.
+    pub fn mtu(&self) -> u16 {
+        self.session.mtu
+    }
+
+    /// The GUID this client connected with.
+    pub fn guid(&self) -> i64 {
+        self.guid
+    }
+
+    /// Queue a game packet batch (without the 0xFE wrapper byte). It is sent
+    /// on the next [`tick`](Self::tick).
+    pub fn send_game(&mut self, batch: &[u8]) {
+        let mut body = BytesMut::with_capacity(1 + batch.len());
+        body.extend_from_slice(&[GAME_PACKET]);
+        body.extend_from_slice(batch);
+        self.session
+            .queue_frame(body.freeze(), Reliability::ReliableOrdered, 0);
+    }
+
+    /// Wait for the next datagram and return the game batches it completed
+    /// (without the 0xFE wrapper byte). Cancel-safe: nothing is lost if the
+    /// future is dropped before a datagram arrives.
+    pub async fn recv(&mut self) -> Result<Vec<Bytes>, RakNetError> {
+        let mut buf = [0u8; RECV_BUF_SIZE];
+        let len = self.socket.recv(&mut buf).await?;
+        self.handle_datagram(&buf[..len])
+    }
+
+    /// Send ACKs, retransmissions, a ping when one is due and everything
+    /// queued. Fails once the server has been silent for too long.
+    pub async fn tick(&mut self) -> Result<(), RakNetError> {
+        let now = Instant::now();
+        if self.session.is_timed_out(now) {
+            return Err(RakNetError::Timeout("the server"));
+        }
+        if self.session.state == SessionState::Connected
+            && now.duration_since(self.session.last_ping_sent) >= CLIENT_PING_INTERVAL
+        {
+            let ping = OnlinePacket::ConnectedPing {
+                timestamp: self.timestamp(),
+            };
+            self.queue(&ping, Reliability::Unreliable);
+            self.session.last_ping_sent = now;
+        }
+
+        let mut datagrams = Vec::new();
+        if !self.session.ack_queue.is_empty() {
+            let records = compress_ack_records(&mut self.session.ack_queue);
+            let mut buf = BytesMut::with_capacity(64);
+            AckNack {
+                is_ack: true,
+                records,
+            }
+            .encode(&mut buf);
+            datagrams.push(buf.freeze());
+            self.session.ack_queue.clear();
+        }
+        self.session.check_retransmit(now);
+        datagrams.extend(self.session.flush_send_queue());
+        self.session.cleanup_fragments();
+
+        for datagram in datagrams {
+            self.socket.send(&datagram).await?;
+            self.stats.datagrams_sent += 1;
+        }
+        Ok(())
+    }
+
+    /// Tell the server we are leaving.
+    pub async fn disconnect(&mut self) -> Result<(), RakNetError> {
+        self.queue(
+            &OnlinePacket::DisconnectionNotification,
+            Reliability::ReliableOrdered,
+        );
+        self.tick().await
+    }
+
+    fn queue(&mut self, packet: &OnlinePacket, reliability: Reliability) {
+        let mut buf = BytesMut::with_capacity(256);
+        packet.encode(&mut buf);
+        self.session.queue_frame(buf.freeze(), reliability, 0);
+    }
+
+    fn timestamp(&self) -> i64 {
+        self.epoch.elapsed().as_millis() as i64
+    }
+
+    fn handle_datagram(&mut self, data: &[u8]) -> Result<Vec<Bytes>, RakNetError> {
+        self.stats.datagrams_received += 1;
+        self.session.last_activity = Instant::now();
+        let Some(&packet_id) = data.first() else {
+            return Ok(Vec::new());
+        };
+        match packet_id {
+            0x80..=0x8D => {
+                let frameset = FrameSet::decode(data)?;
+                self.stats.framesets_received += 1;
+                let seq = frameset.sequence_number;
+                if self.stats.highest_sequence.is_none_or(|h| seq > h) {
+                    self.stats.highest_sequence = Some(seq);
+                }
+                let payloads = self.session.process_incoming_frameset(frameset);
+                let mut batches = Vec::new();
+                for payload in payloads {
+                    if let Some(batch) = self.handle_payload(payload)? {
+                        batches.push(batch);
+                    }
+                }
+                Ok(batches)
+            }
+            AckNack::ACK_ID => {
+                self.session.handle_ack(&AckNack::decode(data)?);
+                Ok(Vec::new())
+            }
+            AckNack::NACK_ID => {
+                let nack = AckNack::decode(data)?;
+                self.stats.datagrams_nacked += expand_ack_records(&nack.records).len() as u64;
+                self.session.handle_nack(&nack);
+                Ok(Vec::new())
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Handle a connected payload, returning it if it is a game batch.
+    fn handle_payload(&mut self, payload: Bytes) -> Result<Option<Bytes>, RakNetError> {
+        match payload.first() {
+            Some(&GAME_PACKET) => Ok(Some(payload.slice(1..))),
+            Some(&online::id::CONNECTED_PING) => {
+                if let OnlinePacket::ConnectedPing { timestamp } = OnlinePacket::decode(&payload)? {
+                    let pong = OnlinePacket::ConnectedPong {
+                        ping_timestamp: timestamp,
+                        pong_timestamp: self.timestamp(),
+                    };
+                    self.queue(&pong, Reliability::Unreliable);
+                }
+                Ok(None)
+            }
+            Some(&online::id::CONNECTED_PONG) => {
+                if let OnlinePacket::ConnectedPong { ping_timestamp, .. } =
+                    OnlinePacket::decode(&payload)?
+                {
+                    let rtt = self.timestamp().saturating_sub(ping_timestamp);
+                    self.stats
+                        .ping_rtts
+                        .push(Duration::from_millis(rtt.max(0) as u64));
+                }
+                Ok(None)
+            }
+            Some(&online::id::CONNECTION_REQUEST_ACCEPTED) => {
+                if let OnlinePacket::ConnectionRequestAccepted {
+                    accept_timestamp, ..
+                } = OnlinePacket::decode(&payload)?
+                {
+                    let server = RakNetAddress::from(self.session.addr);
+                    self.accepted = Some((server, accept_timestamp));
+                }
+                Ok(None)
+            }
+            Some(&online::id::DISCONNECTION_NOTIFICATION) => Err(RakNetError::Disconnected),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// The offline half of the handshake: OpenConnectionRequest 1 and 2, each
+/// resent until answered. Returns the MTU the server settled on.
+async fn open_connection(
+    socket: &UdpSocket,
+    server: SocketAddr,
+    guid: i64,
+    mtu: u16,
+    deadline: Instant,
+) -> Result<u16, RakNetError> {
+    let request1 = OfflinePacket::OpenConnectionRequest1 {
+        protocol_version: RAKNET_PROTOCOL_VERSION,
+        mtu_size: mtu,
+    };
+    let reply1 = exchange(
+        socket,
+        &request1,
+        offline::id::OPEN_CONNECTION_REPLY_1,
+        deadline,
+    )
+    .await
+    .map_err(|e| match e {
+        RakNetError::Timeout(_) => RakNetError::Timeout("OpenConnectionReply1"),
+        e => e,
+    })?;
+    let OfflinePacket::OpenConnectionReply1 { mtu_size, .. } = reply1 else {
+        return Err(RakNetError::UnknownPacketId(
+            offline::id::OPEN_CONNECTION_REPLY_1,
+        ));
+    };
+
+    let request2 = OfflinePacket::OpenConnectionRequest2 {
+        server_address: RakNetAddress::from(server),
+        mtu_size,
+        client_guid: guid,
+    };
+    let reply2 = exchange(
+        socket,
+        &request2,
+        offline::id::OPEN_CONNECTION_REPLY_2,
+        deadline,
+    )
+    .await
+    .map_err(|e| match e {
+        RakNetError::Timeout(_) => RakNetError::Timeout("OpenConnectionReply2"),
+        e => e,
+    })?;
+    let OfflinePacket::OpenConnectionReply2 { mtu_size, .. } = reply2 else {
+        return Err(RakNetError::UnknownPacketId(
+            offline::id::OPEN_CONNECTION_REPLY_2,
+        ));
+    };
+    if !(MIN_MTU..=MAX_MTU).contains(&mtu_size) {
+        return Err(RakNetError::MtuOutOfRange(mtu_size));
+    }
+    Ok(mtu_size)
+}
+
+/// Send an offline packet until a reply with the given ID comes back.
+async fn exchange(
+    socket: &UdpSocket,
+    request: &OfflinePacket,
+    reply_id: u8,
+    deadline: Instant,
+) -> Result<OfflinePacket, RakNetError> {
+    let mut out = BytesMut::with_capacity(MAX_MTU as usize);
+    request.encode(&mut out);
+    let mut buf = [0u8; RECV_BUF_SIZE];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(RakNetError::Timeout("an offline reply"));
+        }
+        socket.send(&out).await?;
+        let wait_until = Instant::now() + remaining.min(HANDSHAKE_RETRY);
+        while let Ok(len) = tokio::time::timeout_at(wait_until.into(), socket.recv(&mut buf)).await
+        {
+            let len = len?;
+            if buf[..len].first() == Some(&reply_id) {
+                return OfflinePacket::decode(&buf[..len], len);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::offline::ServerMotd;
+    use crate::server::{RakNetConfig, RakNetEvent, RakNetServer};
+    use crate::throttle::ThrottleConfig;
+
+    fn test_config() -> RakNetConfig {
+        RakNetConfig {
+            address: "127.0.0.1:0".parse().unwrap(),
+            server_guid: 42,
+            motd: ServerMotd {
+                server_name: "Test".into(),
+                protocol_version: 924,
+                game_version: "1.26.2".into(),
+                online_players: 0,
+                max_players: 20,
+                server_guid: 42,
+                world_name: "world".into(),
+                gamemode: "Survival".into(),
+                gamemode_numeric: 1,
+                ipv4_port: 19132,
+                ipv6_port: 19133,
+                is_editor_mode: 0,
+            },
+            max_connections: 10,
+            proxy_protocol: false,
+            trusted_proxies: Vec::new(),
+            throttle: ThrottleConfig::default(),
+            lan_discovery: false,
+            lan_broadcast: false,
+            capture_path: None,
+        }
+    }
+
+    #[test]
+    fn missed_datagrams_are_sequence_gaps() {
+        let mut stats = ClientStats::default();
+        assert_eq!(stats.datagrams_missed(), 0);
+        stats.framesets_received = 8;
+        stats.highest_sequence = Some(9);
+        assert_eq!(stats.datagrams_missed(), 2);
+    }
+
+    #[tokio::test]
+    async fn connects_and_exchanges_game_packets() {
+        let (mut server, mut events, handle) = RakNetServer::bind(test_config()).await.unwrap();
+        let server_addr = server.local_addr().unwrap();
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        tokio::spawn(async move { server.run(shutdown_rx).await });
+
+        let mut client = RakNetClient::connect(server_addr, 7, 1400, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(client.mtu() <= 1400);
+
+        let addr = match events.recv().await.unwrap() {
+            RakNetEvent::SessionConnected { addr, guid } => {
+                assert_eq!(guid, 7);
+                addr
+            }
+            other => panic!("expected SessionConnected, got {other:?}"),
+        };
+
+        client.send_game(b"hello");
+        client.tick().await.unwrap();
+        match events.recv().await.unwrap() {
+            RakNetEvent::Packet { payload, .. } => assert_eq!(&payload[..], b"hello"),
+            other => panic!("expected Packet, got {other:?}"),
+        }
+
+        // Big enough to be split into fragments
+        let big = vec![7u8; 5000];
+        let mut wrapped = vec![GAME_PACKET];
+        wrapped.extend_from_slice(&big);
+        handle
+            .send_to(addr, Bytes::from(wrapped), Reliability::ReliableOrdered, 0)
+            .await;
+        let batch = loop {
+            let batches = tokio::time::timeout(Duration::from_secs(5), client.recv())
+                .await
+                .expect("the server's packet arrives")
+                .unwrap();
+            client.tick().await.unwrap();
+            if let Some(batch) = batches.into_iter().next() {
+                break batch;
+            }
+        };
+        assert_eq!(&batch[..], &big[..]);
+        assert_eq!(client.stats.datagrams_missed(), 0);
+    }
+}
@@ -41,5 +41,15 @@ pub const FRAGMENT_TIMEOUT: Duration = Duration::from_secs(30);
 /// Maximum buffered out-of-order frames per ordering channel.
 pub const MAX_ORDER_CHANNEL_BUFFER: usize = 256;
 
+/// Maximum proxy flows tracked at once; headers for new flows beyond this
+/// are dropped until idle ones expire.
+pub const MAX_PROXY_FLOWS: usize = 4096;
+
 /// Size of the UDP receive buffer.
 pub const RECV_BUF_SIZE: usize = 2048;
+
+/// Port Bedrock clients broadcast LAN discovery pings to.
+pub const LAN_DISCOVERY_PORT: u16 = 19132;
+
+/// How often the server announces itself on the LAN when broadcasting.
+pub const LAN_BROADCAST_INTERVAL: Duration = Duration::from_secs(2);
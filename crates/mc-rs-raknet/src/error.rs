@@ -28,4 +28,16 @@ pub enum RakNetError {
 
     #[error("invalid UTF-8 in packet")]
     InvalidUtf8,
+
+    #[error("invalid PROXY protocol header: {0}")]
+    InvalidProxyHeader(String),
+
+    #[error("invalid capture file: {0}")]
+    InvalidCapture(String),
+
+    #[error("timed out waiting for {0}")]
+    Timeout(&'static str),
+
+    #[error("disconnected by the remote peer")]
+    Disconnected,
 }
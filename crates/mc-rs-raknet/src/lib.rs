@@ -1,19 +1,25 @@
 //! RakNet transport layer for Minecraft Bedrock Edition.
 
 pub mod address;
+pub mod capture;
+pub mod client;
 pub mod codec;
 pub mod constants;
 pub mod error;
 pub mod fragmentation;
 pub mod ordering;
 pub mod packet;
+pub mod proxy_protocol;
 pub mod reliability;
 pub mod server;
 pub mod session;
+pub mod throttle;
 
 pub use address::RakNetAddress;
+pub use client::{ClientStats, RakNetClient};
 pub use error::RakNetError;
 pub use packet::frame::Reliability;
 pub use packet::offline::ServerMotd;
 pub use server::{RakNetConfig, RakNetEvent, RakNetServer, ServerCommand, ServerHandle};
 pub use session::SessionState;
+pub use throttle::ThrottleConfig;
@@ -1,7 +1,7 @@
 use bytes::{Buf, BufMut, BytesMut};
 
 use crate::address::RakNetAddress;
-use crate::codec::{read_magic, write_magic, write_string};
+use crate::codec::{read_magic, read_string, write_magic, write_string};
 use crate::error::RakNetError;
 
 /// Packet IDs for offline packets.
@@ -49,8 +49,8 @@ pub enum OfflinePacket {
 }
 
 impl OfflinePacket {
-    /// Decode an offline packet. `datagram_len` is the total UDP payload length
-    /// (needed to infer MTU from OpenConnectionRequest1).
+    /// Decode an offline packet, in either direction. `datagram_len` is the
+    /// total UDP payload length (needed to infer MTU from OpenConnectionRequest1).
     pub fn decode(data: &[u8], datagram_len: usize) -> Result<Self, RakNetError> {
         if data.is_empty() {
             return Err(RakNetError::PacketTooShort {
@@ -92,6 +92,41 @@ impl OfflinePacket {
                     client_guid,
                 })
             }
+            id::UNCONNECTED_PONG => {
+                let send_timestamp = buf.get_i64();
+                let server_guid = buf.get_i64();
+                read_magic(&mut buf)?;
+                let motd = read_string(&mut buf)?;
+                Ok(Self::UnconnectedPong {
+                    send_timestamp,
+                    server_guid,
+                    motd,
+                })
+            }
+            id::OPEN_CONNECTION_REPLY_1 => {
+                read_magic(&mut buf)?;
+                let server_guid = buf.get_i64();
+                let use_security = buf.get_u8() != 0;
+                let mtu_size = buf.get_u16();
+                Ok(Self::OpenConnectionReply1 {
+                    server_guid,
+                    use_security,
+                    mtu_size,
+                })
+            }
+            id::OPEN_CONNECTION_REPLY_2 => {
+                read_magic(&mut buf)?;
+                let server_guid = buf.get_i64();
+                let client_address = RakNetAddress::decode(&mut buf)?;
+                let mtu_size = buf.get_u16();
+                let encryption_enabled = buf.get_u8() != 0;
+                Ok(Self::OpenConnectionReply2 {
+                    server_guid,
+                    client_address,
+                    mtu_size,
+                    encryption_enabled,
+                })
+            }
             _ => Err(RakNetError::UnknownPacketId(packet_id)),
         }
     }
@@ -134,11 +169,36 @@ impl OfflinePacket {
                 buf.put_u16(*mtu_size);
                 buf.put_u8(*encryption_enabled as u8);
             }
-            // Client-side packets — we don't encode these on the server
-            Self::UnconnectedPing { .. }
-            | Self::OpenConnectionRequest1 { .. }
-            | Self::OpenConnectionRequest2 { .. } => {
-                unreachable!("server should not encode client offline packets")
+            Self::UnconnectedPing {
+                send_timestamp,
+                client_guid,
+            } => {
+                buf.put_u8(id::UNCONNECTED_PING);
+                buf.put_i64(*send_timestamp);
+                write_magic(buf);
+                buf.put_i64(*client_guid);
+            }
+            Self::OpenConnectionRequest1 {
+                protocol_version,
+                mtu_size,
+            } => {
+                // The MTU is conveyed by padding the datagram to that size
+                let start = buf.len();
+                buf.put_u8(id::OPEN_CONNECTION_REQUEST_1);
+                write_magic(buf);
+                buf.put_u8(*protocol_version);
+                buf.resize(start + *mtu_size as usize, 0);
+            }
+            Self::OpenConnectionRequest2 {
+                server_address,
+                mtu_size,
+                client_guid,
+            } => {
+                buf.put_u8(id::OPEN_CONNECTION_REQUEST_2);
+                write_magic(buf);
+                server_address.encode(buf);
+                buf.put_u16(*mtu_size);
+                buf.put_i64(*client_guid);
             }
         }
     }
@@ -244,4 +304,118 @@ mod tests {
             _ => panic!("expected OCR1"),
         }
     }
+
+    #[test]
+    fn ocr1_encode_pads_to_mtu() {
+        let packet = OfflinePacket::OpenConnectionRequest1 {
+            protocol_version: crate::constants::RAKNET_PROTOCOL_VERSION,
+            mtu_size: 1200,
+        };
+        let mut buf = BytesMut::new();
+        packet.encode(&mut buf);
+        assert_eq!(buf.len(), 1200);
+        match OfflinePacket::decode(&buf, buf.len()).unwrap() {
+            OfflinePacket::OpenConnectionRequest1 { mtu_size, .. } => assert_eq!(mtu_size, 1200),
+            _ => panic!("expected OCR1"),
+        }
+    }
+
+    #[test]
+    fn ocr2_roundtrip() {
+        let packet = OfflinePacket::OpenConnectionRequest2 {
+            server_address: RakNetAddress::from(
+                "127.0.0.1:19132".parse::<std::net::SocketAddr>().unwrap(),
+            ),
+            mtu_size: 1400,
+            client_guid: -7,
+        };
+        let mut buf = BytesMut::new();
+        packet.encode(&mut buf);
+        match OfflinePacket::decode(&buf, buf.len()).unwrap() {
+            OfflinePacket::OpenConnectionRequest2 {
+                server_address,
+                mtu_size,
+                client_guid,
+            } => {
+                assert_eq!(server_address.0.port(), 19132);
+                assert_eq!(mtu_size, 1400);
+                assert_eq!(client_guid, -7);
+            }
+            _ => panic!("expected OCR2"),
+        }
+    }
+
+    #[test]
+    fn replies_roundtrip() {
+        let reply1 = OfflinePacket::OpenConnectionReply1 {
+            server_guid: 42,
+            use_security: false,
+            mtu_size: 1400,
+        };
+        let mut buf = BytesMut::new();
+        reply1.encode(&mut buf);
+        match OfflinePacket::decode(&buf, buf.len()).unwrap() {
+            OfflinePacket::OpenConnectionReply1 {
+                server_guid,
+                use_security,
+                mtu_size,
+            } => {
+                assert_eq!(server_guid, 42);
+                assert!(!use_security);
+                assert_eq!(mtu_size, 1400);
+            }
+            _ => panic!("expected Reply1"),
+        }
+
+        let reply2 = OfflinePacket::OpenConnectionReply2 {
+            server_guid: 42,
+            client_address: RakNetAddress::from(
+                "10.0.0.2:50000".parse::<std::net::SocketAddr>().unwrap(),
+            ),
+            mtu_size: 1200,
+            encryption_enabled: false,
+        };
+        let mut buf = BytesMut::new();
+        reply2.encode(&mut buf);
+        match OfflinePacket::decode(&buf, buf.len()).unwrap() {
+            OfflinePacket::OpenConnectionReply2 {
+                client_address,
+                mtu_size,
+                ..
+            } => {
+                assert_eq!(client_address.0.port(), 50000);
+                assert_eq!(mtu_size, 1200);
+            }
+            _ => panic!("expected Reply2"),
+        }
+    }
+
+    #[test]
+    fn ping_pong_roundtrip() {
+        let ping = OfflinePacket::UnconnectedPing {
+            send_timestamp: 99,
+            client_guid: 5,
+        };
+        let mut buf = BytesMut::new();
+        ping.encode(&mut buf);
+        match OfflinePacket::decode(&buf, buf.len()).unwrap() {
+            OfflinePacket::UnconnectedPing {
+                send_timestamp,
+                client_guid,
+            } => assert_eq!((send_timestamp, client_guid), (99, 5)),
+            _ => panic!("expected UnconnectedPing"),
+        }
+
+        let pong = OfflinePacket::UnconnectedPong {
+            send_timestamp: 99,
+            server_guid: 42,
+            motd: "MCPE;Test;".into(),
+        };
+        let mut buf = BytesMut::new();
+        pong.encode(&mut buf);
+        match OfflinePacket::decode(&buf, buf.len()).unwrap() {
+            OfflinePacket::UnconnectedPong { motd, .. } => assert_eq!(motd, "MCPE;Test;"),
+            _ => panic!("expected UnconnectedPong"),
+        }
+    }
 }
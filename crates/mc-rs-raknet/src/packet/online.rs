@@ -14,7 +14,7 @@ pub mod id {
 }
 
 /// Number of system addresses in connection packets.
-const NUM_SYSTEM_ADDRESSES: usize = 20;
+pub const NUM_SYSTEM_ADDRESSES: usize = 20;
 
 #[derive(Debug)]
 pub enum OnlinePacket {
@@ -47,7 +47,7 @@ pub enum OnlinePacket {
 }
 
 impl OnlinePacket {
-    /// Decode an online packet from the raw frame body.
+    /// Decode an online packet from the raw frame body, in either direction.
     pub fn decode(data: &[u8]) -> Result<Self, RakNetError> {
         if data.is_empty() {
             return Err(RakNetError::PacketTooShort {
@@ -106,6 +106,25 @@ impl OnlinePacket {
                     accept_timestamp,
                 })
             }
+            id::CONNECTION_REQUEST_ACCEPTED => {
+                let client_address = RakNetAddress::decode(&mut buf)?;
+                let system_index = buf.get_u16();
+                let mut system_addresses = [RakNetAddress::EMPTY_V4; NUM_SYSTEM_ADDRESSES];
+                for addr in &mut system_addresses {
+                    if buf.remaining() > 16 {
+                        *addr = RakNetAddress::decode(&mut buf)?;
+                    }
+                }
+                let request_timestamp = buf.get_i64();
+                let accept_timestamp = buf.get_i64();
+                Ok(Self::ConnectionRequestAccepted {
+                    client_address,
+                    system_index,
+                    system_addresses,
+                    request_timestamp,
+                    accept_timestamp,
+                })
+            }
             id::DISCONNECTION_NOTIFICATION => Ok(Self::DisconnectionNotification),
             _ => Err(RakNetError::UnknownPacketId(packet_id)),
         }
@@ -142,13 +161,33 @@ impl OnlinePacket {
                 buf.put_i64(*request_timestamp);
                 buf.put_i64(*accept_timestamp);
             }
+            Self::ConnectionRequest {
+                client_guid,
+                timestamp,
+                use_security,
+            } => {
+                buf.put_u8(id::CONNECTION_REQUEST);
+                buf.put_i64(*client_guid);
+                buf.put_i64(*timestamp);
+                buf.put_u8(*use_security as u8);
+            }
+            Self::NewIncomingConnection {
+                server_address,
+                system_addresses,
+                request_timestamp,
+                accept_timestamp,
+            } => {
+                buf.put_u8(id::NEW_INCOMING_CONNECTION);
+                server_address.encode(buf);
+                for addr in system_addresses {
+                    addr.encode(buf);
+                }
+                buf.put_i64(*request_timestamp);
+                buf.put_i64(*accept_timestamp);
+            }
             Self::DisconnectionNotification => {
                 buf.put_u8(id::DISCONNECTION_NOTIFICATION);
             }
-            // Client-side packets
-            Self::ConnectionRequest { .. } | Self::NewIncomingConnection { .. } => {
-                unreachable!("server should not encode client online packets")
-            }
         }
     }
 }
@@ -199,4 +238,79 @@ mod tests {
         let decoded = OnlinePacket::decode(&buf).unwrap();
         assert!(matches!(decoded, OnlinePacket::DisconnectionNotification));
     }
+
+    #[test]
+    fn connection_request_roundtrip() {
+        let packet = OnlinePacket::ConnectionRequest {
+            client_guid: 77,
+            timestamp: 1000,
+            use_security: false,
+        };
+        let mut buf = BytesMut::new();
+        packet.encode(&mut buf);
+        match OnlinePacket::decode(&buf).unwrap() {
+            OnlinePacket::ConnectionRequest {
+                client_guid,
+                timestamp,
+                use_security,
+            } => {
+                assert_eq!((client_guid, timestamp), (77, 1000));
+                assert!(!use_security);
+            }
+            _ => panic!("expected ConnectionRequest"),
+        }
+    }
+
+    #[test]
+    fn connection_handshake_roundtrip() {
+        let client: std::net::SocketAddr = "192.168.1.5:50000".parse().unwrap();
+        let server: std::net::SocketAddr = "192.168.1.1:19132".parse().unwrap();
+        let mut system_addresses = [RakNetAddress::EMPTY_V4; NUM_SYSTEM_ADDRESSES];
+        system_addresses[0] = RakNetAddress::from(client);
+
+        let accepted = OnlinePacket::ConnectionRequestAccepted {
+            client_address: RakNetAddress::from(client),
+            system_index: 0,
+            system_addresses,
+            request_timestamp: 1000,
+            accept_timestamp: 1005,
+        };
+        let mut buf = BytesMut::new();
+        accepted.encode(&mut buf);
+        match OnlinePacket::decode(&buf).unwrap() {
+            OnlinePacket::ConnectionRequestAccepted {
+                client_address,
+                system_addresses,
+                request_timestamp,
+                accept_timestamp,
+                ..
+            } => {
+                assert_eq!(client_address.0, client);
+                assert_eq!(system_addresses[0].0, client);
+                assert_eq!((request_timestamp, accept_timestamp), (1000, 1005));
+            }
+            _ => panic!("expected ConnectionRequestAccepted"),
+        }
+
+        let incoming = OnlinePacket::NewIncomingConnection {
+            server_address: RakNetAddress::from(server),
+            system_addresses,
+            request_timestamp: 1005,
+            accept_timestamp: 1010,
+        };
+        let mut buf = BytesMut::new();
+        incoming.encode(&mut buf);
+        match OnlinePacket::decode(&buf).unwrap() {
+            OnlinePacket::NewIncomingConnection {
+                server_address,
+                request_timestamp,
+                accept_timestamp,
+                ..
+            } => {
+                assert_eq!(server_address.0, server);
+                assert_eq!((request_timestamp, accept_timestamp), (1005, 1010));
+            }
+            _ => panic!("expected NewIncomingConnection"),
+        }
+    }
 }
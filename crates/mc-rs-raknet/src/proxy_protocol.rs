@@ -0,0 +1,164 @@
+//! HAProxy PROXY protocol v2 header parsing.
+//!
+//! UDP load balancers and Bedrock proxies that support it prepend a binary
+//! PROXY v2 header to the first datagram of a flow, carrying the real client
+//! address. The server remembers the mapping so that every later datagram on
+//! the same flow is attributed to the real client.
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::error::RakNetError;
+
+/// The 12-byte signature starting every PROXY v2 header.
+pub const SIGNATURE: [u8; 12] = [
+    0x0D, 0x0A, 0x0D, 0x0A, 0x00, 0x0D, 0x0A, 0x51, 0x55, 0x49, 0x54, 0x0A,
+];
+
+/// Fixed part of the header: signature + version/command + family + length.
+const FIXED_LEN: usize = 16;
+
+const VERSION_2: u8 = 0x20;
+const CMD_LOCAL: u8 = 0x00;
+const CMD_PROXY: u8 = 0x01;
+const AF_INET: u8 = 0x10;
+const AF_INET6: u8 = 0x20;
+
+/// A parsed PROXY v2 header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyHeader {
+    /// Real client address, or `None` for a LOCAL command (health checks).
+    pub source: Option<SocketAddr>,
+    /// Total header length, i.e. the offset of the payload in the datagram.
+    pub header_len: usize,
+}
+
+/// Whether a datagram starts with the PROXY v2 signature.
+pub fn has_header(data: &[u8]) -> bool {
+    data.starts_with(&SIGNATURE)
+}
+
+/// Parse a PROXY v2 header at the start of `data`.
+pub fn parse(data: &[u8]) -> Result<ProxyHeader, RakNetError> {
+    if data.len() < FIXED_LEN {
+        return Err(RakNetError::PacketTooShort {
+            expected: FIXED_LEN,
+            actual: data.len(),
+        });
+    }
+    if !has_header(data) {
+        return Err(RakNetError::InvalidProxyHeader("bad signature".into()));
+    }
+    let version_cmd = data[12];
+    if version_cmd & 0xF0 != VERSION_2 {
+        return Err(RakNetError::InvalidProxyHeader(format!(
+            "unsupported version 0x{:X}",
+            version_cmd >> 4
+        )));
+    }
+    let family = data[13] & 0xF0;
+    let addr_len = u16::from_be_bytes([data[14], data[15]]) as usize;
+    let header_len = FIXED_LEN + addr_len;
+    if data.len() < header_len {
+        return Err(RakNetError::PacketTooShort {
+            expected: header_len,
+            actual: data.len(),
+        });
+    }
+    let addrs = &data[FIXED_LEN..header_len];
+
+    let source = match version_cmd & 0x0F {
+        CMD_LOCAL => None,
+        CMD_PROXY => Some(parse_source(family, addrs)?),
+        cmd => {
+            return Err(RakNetError::InvalidProxyHeader(format!(
+                "unknown command {cmd}"
+            )))
+        }
+    };
+    Ok(ProxyHeader { source, header_len })
+}
+
+/// Extract the source address from the address block (TLVs are ignored).
+fn parse_source(family: u8, addrs: &[u8]) -> Result<SocketAddr, RakNetError> {
+    match family {
+        AF_INET if addrs.len() >= 12 => {
+            let ip = Ipv4Addr::new(addrs[0], addrs[1], addrs[2], addrs[3]);
+            let port = u16::from_be_bytes([addrs[8], addrs[9]]);
+            Ok(SocketAddr::V4(SocketAddrV4::new(ip, port)))
+        }
+        AF_INET6 if addrs.len() >= 36 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&addrs[..16]);
+            let port = u16::from_be_bytes([addrs[32], addrs[33]]);
+            Ok(SocketAddr::V6(SocketAddrV6::new(
+                Ipv6Addr::from(octets),
+                port,
+                0,
+                0,
+            )))
+        }
+        _ => Err(RakNetError::InvalidProxyHeader(format!(
+            "unsupported address family 0x{family:02X}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v4_header(cmd: u8) -> Vec<u8> {
+        let mut buf = SIGNATURE.to_vec();
+        buf.push(VERSION_2 | cmd);
+        buf.push(AF_INET | 0x02); // UDP over IPv4
+        buf.extend_from_slice(&12u16.to_be_bytes());
+        buf.extend_from_slice(&[203, 0, 113, 7]); // src
+        buf.extend_from_slice(&[10, 0, 0, 1]); // dst
+        buf.extend_from_slice(&54321u16.to_be_bytes());
+        buf.extend_from_slice(&19132u16.to_be_bytes());
+        buf
+    }
+
+    #[test]
+    fn parse_ipv4_proxy() {
+        let mut data = v4_header(CMD_PROXY);
+        data.extend_from_slice(&[0x01, 0x02]);
+        let header = parse(&data).unwrap();
+        assert_eq!(header.source, Some("203.0.113.7:54321".parse().unwrap()));
+        assert_eq!(header.header_len, 28);
+        assert_eq!(&data[header.header_len..], &[0x01, 0x02]);
+    }
+
+    #[test]
+    fn parse_ipv6_proxy() {
+        let mut data = SIGNATURE.to_vec();
+        data.push(VERSION_2 | CMD_PROXY);
+        data.push(AF_INET6 | 0x02);
+        data.extend_from_slice(&36u16.to_be_bytes());
+        let src: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        data.extend_from_slice(&src.octets());
+        data.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        data.extend_from_slice(&40000u16.to_be_bytes());
+        data.extend_from_slice(&19132u16.to_be_bytes());
+        let header = parse(&data).unwrap();
+        assert_eq!(header.source, Some("[2001:db8::1]:40000".parse().unwrap()));
+    }
+
+    #[test]
+    fn parse_local_command() {
+        let header = parse(&v4_header(CMD_LOCAL)).unwrap();
+        assert_eq!(header.source, None);
+    }
+
+    #[test]
+    fn reject_malformed() {
+        assert!(!has_header(&[0x01, 0x00]));
+        assert!(parse(&SIGNATURE).is_err());
+        let mut truncated = v4_header(CMD_PROXY);
+        truncated.truncate(20);
+        assert!(parse(&truncated).is_err());
+        let mut bad_version = v4_header(CMD_PROXY);
+        bad_version[12] = 0x11;
+        assert!(parse(&bad_version).is_err());
+    }
+}
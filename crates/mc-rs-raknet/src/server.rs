@@ -1,5 +1,6 @@
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -9,13 +10,16 @@ use tokio::sync::{mpsc, watch};
 use tracing::{debug, info, trace, warn};
 
 use crate::address::RakNetAddress;
+use crate::capture::{CaptureWriter, Direction};
 use crate::constants::*;
 use crate::error::RakNetError;
 use crate::packet::frame::{AckNack, FrameSet, Reliability};
 use crate::packet::offline::{self, OfflinePacket, ServerMotd};
 use crate::packet::online::{self, OnlinePacket};
+use crate::proxy_protocol;
 use crate::reliability::compress_ack_records;
 use crate::session::{RakNetSession, SessionState};
+use crate::throttle::{Throttle, ThrottleConfig};
 
 /// Events emitted by the RakNet server to the consumer.
 #[derive(Debug)]
@@ -38,6 +42,10 @@ pub enum ServerCommand {
         reliability: Reliability,
         channel: u8,
     },
+    /// Replace the MOTD advertised in UnconnectedPong.
+    UpdateMotd(ServerMotd),
+    /// Change how many sessions may be connected at once.
+    SetMaxConnections(usize),
 }
 
 /// A cloneable handle for sending commands to the RakNet server from any task.
@@ -47,6 +55,14 @@ pub struct ServerHandle {
 }
 
 impl ServerHandle {
+    /// A handle not backed by a running server: the commands it is sent come
+    /// out of the returned receiver instead. Used to drive a consumer without
+    /// a socket, e.g. in tests.
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<ServerCommand>) {
+        let (command_tx, command_rx) = mpsc::channel(capacity);
+        (Self { command_tx }, command_rx)
+    }
+
     /// Queue a payload to be sent to a connected session.
     pub async fn send_to(
         &self,
@@ -65,6 +81,20 @@ impl ServerHandle {
             })
             .await;
     }
+
+    /// Replace the MOTD advertised to pinging clients.
+    pub async fn update_motd(&self, motd: ServerMotd) {
+        let _ = self.command_tx.send(ServerCommand::UpdateMotd(motd)).await;
+    }
+
+    /// Change how many sessions may be connected at once. Sessions over the
+    /// new limit are kept; only new connections are refused.
+    pub async fn set_max_connections(&self, max: usize) {
+        let _ = self
+            .command_tx
+            .send(ServerCommand::SetMaxConnections(max))
+            .await;
+    }
 }
 
 /// Configuration for the RakNet server.
@@ -73,6 +103,24 @@ pub struct RakNetConfig {
     pub server_guid: i64,
     pub motd: ServerMotd,
     pub max_connections: usize,
+    /// Accept HAProxy PROXY v2 headers and attribute flows to the real client.
+    pub proxy_protocol: bool,
+    /// Proxies allowed to send PROXY headers. Empty = none.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Per-IP rate limits and flood protection.
+    pub throttle: ThrottleConfig,
+    /// Answer LAN discovery pings on the standard port when listening elsewhere.
+    pub lan_discovery: bool,
+    /// Periodically broadcast an UnconnectedPong to the LAN.
+    pub lan_broadcast: bool,
+    /// Record every datagram sent or received to this file (see [`crate::capture`]).
+    pub capture_path: Option<PathBuf>,
+}
+
+/// A UDP flow from a proxy, mapped to the real client address.
+struct ProxyFlow {
+    client: SocketAddr,
+    last_seen: Instant,
 }
 
 /// The RakNet server — manages UDP socket and all sessions.
@@ -82,6 +130,15 @@ pub struct RakNetServer {
     config: RakNetConfig,
     event_tx: mpsc::Sender<RakNetEvent>,
     command_rx: mpsc::Receiver<ServerCommand>,
+    /// Proxy flow address → real client (PROXY protocol only).
+    proxy_flows: HashMap<SocketAddr, ProxyFlow>,
+    /// Real client → proxy flow address, for replies.
+    proxy_clients: HashMap<SocketAddr, SocketAddr>,
+    throttle: Throttle,
+    capture: Option<CaptureWriter>,
+    /// Extra socket on [`LAN_DISCOVERY_PORT`], if LAN discovery needs one.
+    lan_socket: Option<Arc<UdpSocket>>,
+    last_lan_broadcast: Instant,
 }
 
 impl RakNetServer {
@@ -95,6 +152,38 @@ impl RakNetServer {
         let (command_tx, command_rx) = mpsc::channel(256);
 
         info!("RakNet server bound on {}", config.address);
+        if config.proxy_protocol {
+            if config.trusted_proxies.is_empty() {
+                warn!("PROXY protocol enabled without trusted proxies: all PROXY headers will be ignored");
+            } else {
+                info!("PROXY protocol enabled for {:?}", config.trusted_proxies);
+            }
+        }
+
+        if config.lan_broadcast {
+            socket.set_broadcast(true)?;
+        }
+        let lan_socket = if config.lan_discovery {
+            bind_lan_socket(config.address).await
+        } else {
+            None
+        };
+
+        let throttle = Throttle::new(config.throttle.clone());
+        let capture =
+            config
+                .capture_path
+                .as_deref()
+                .and_then(|path| match CaptureWriter::create(path) {
+                    Ok(writer) => {
+                        info!("Capturing datagrams to {}", path.display());
+                        Some(writer)
+                    }
+                    Err(e) => {
+                        warn!("Failed to open capture file {}: {e}", path.display());
+                        None
+                    }
+                });
 
         Ok((
             Self {
@@ -103,6 +192,12 @@ impl RakNetServer {
                 config,
                 event_tx,
                 command_rx,
+                proxy_flows: HashMap::new(),
+                proxy_clients: HashMap::new(),
+                throttle,
+                capture,
+                lan_socket,
+                last_lan_broadcast: Instant::now(),
             },
             event_rx,
             ServerHandle { command_tx },
@@ -112,27 +207,29 @@ impl RakNetServer {
     /// Run the server main loop. Blocks until the shutdown signal is received.
     pub async fn run(&mut self, mut shutdown: watch::Receiver<bool>) {
         let mut recv_buf = vec![0u8; RECV_BUF_SIZE];
+        let mut lan_buf = vec![0u8; RECV_BUF_SIZE];
         let mut tick_interval = tokio::time::interval(SERVER_TICK_INTERVAL);
+        let lan_socket = self.lan_socket.clone();
 
         loop {
             tokio::select! {
+                Some((len, addr)) = recv_lan(lan_socket.as_deref(), &mut lan_buf) => {
+                    if let Err(e) = self.handle_lan_ping(&lan_buf[..len], addr).await {
+                        trace!("Error handling LAN ping from {addr}: {e}");
+                    }
+                }
                 result = self.socket.recv_from(&mut recv_buf) => {
                     match result {
                         Ok((len, addr)) => {
-                            if let Err(e) = self.handle_datagram(&recv_buf[..len], addr).await {
+                            self.capture(Direction::Inbound, addr, &recv_buf[..len]);
+                            if let Err(e) = self.handle_incoming(&recv_buf[..len], addr).await {
                                 trace!("Error handling datagram from {addr}: {e}");
                             }
                         }
                         Err(e) => warn!("UDP recv error: {e}"),
                     }
                 }
-                Some(cmd) = self.command_rx.recv() => {
-                    match cmd {
-                        ServerCommand::Send { addr, payload, reliability, channel } => {
-                            self.send_to(addr, payload, reliability, channel);
-                        }
-                    }
-                }
+                Some(cmd) = self.command_rx.recv() => self.handle_command(cmd),
                 _ = tick_interval.tick() => {
                     self.tick().await;
                 }
@@ -147,6 +244,24 @@ impl RakNetServer {
         }
     }
 
+    /// The address the server's socket is bound to.
+    pub fn local_addr(&self) -> Result<SocketAddr, RakNetError> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    fn handle_command(&mut self, cmd: ServerCommand) {
+        match cmd {
+            ServerCommand::Send {
+                addr,
+                payload,
+                reliability,
+                channel,
+            } => self.send_to(addr, payload, reliability, channel),
+            ServerCommand::UpdateMotd(motd) => self.update_motd(motd),
+            ServerCommand::SetMaxConnections(max) => self.config.max_connections = max,
+        }
+    }
+
     /// Update the MOTD (e.g. when player count changes).
     pub fn update_motd(&mut self, motd: ServerMotd) {
         self.config.motd = motd;
@@ -165,6 +280,135 @@ impl RakNetServer {
         }
     }
 
+    // -----------------------------------------------------------------------
+    // Internal: PROXY protocol
+    // -----------------------------------------------------------------------
+
+    /// Strip an optional PROXY header and resolve the datagram's client address.
+    async fn handle_incoming(&mut self, data: &[u8], from: SocketAddr) -> Result<(), RakNetError> {
+        if !self.config.proxy_protocol {
+            return self.handle_checked(data, from).await;
+        }
+
+        let mut payload = data;
+        if proxy_protocol::has_header(data) {
+            if !self.is_trusted_proxy(from.ip()) {
+                debug!("Ignoring PROXY header from untrusted source {from}");
+                return Ok(());
+            }
+            let header = proxy_protocol::parse(data)?;
+            if let Some(client) = header.source {
+                if self.proxy_flows.len() >= MAX_PROXY_FLOWS
+                    && !self.proxy_flows.contains_key(&from)
+                {
+                    debug!("Too many PROXY flows, dropping datagram from {from}");
+                    return Ok(());
+                }
+                if let Some(old) = self.proxy_flows.insert(
+                    from,
+                    ProxyFlow {
+                        client,
+                        last_seen: Instant::now(),
+                    },
+                ) {
+                    self.proxy_clients.remove(&old.client);
+                }
+                self.proxy_clients.insert(client, from);
+                debug!("PROXY flow {from} -> client {client}");
+            }
+            payload = &data[header.header_len..];
+        }
+
+        let addr = match self.proxy_flows.get_mut(&from) {
+            Some(flow) => {
+                flow.last_seen = Instant::now();
+                flow.client
+            }
+            None => from,
+        };
+        self.handle_checked(payload, addr).await
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal: sending and capture
+    // -----------------------------------------------------------------------
+
+    /// Send a raw datagram to a client (through its proxy flow, if any).
+    async fn send_datagram(&mut self, data: &[u8], addr: SocketAddr) -> std::io::Result<()> {
+        let wire_addr = self.wire_addr(addr);
+        self.capture(Direction::Outbound, wire_addr, data);
+        self.socket.send_to(data, wire_addr).await.map(|_| ())
+    }
+
+    /// Append a datagram to the capture file, if capturing. Capture stops on
+    /// the first write error.
+    fn capture(&mut self, direction: Direction, addr: SocketAddr, data: &[u8]) {
+        if let Some(writer) = &mut self.capture {
+            if let Err(e) = writer.record(direction, addr, data) {
+                warn!("Datagram capture failed, disabling: {e}");
+                self.capture = None;
+            }
+        }
+    }
+
+    fn is_trusted_proxy(&self, ip: IpAddr) -> bool {
+        self.config.trusted_proxies.contains(&ip)
+    }
+
+    /// The socket address datagrams for `addr` must be sent to (the proxy flow
+    /// for proxied clients).
+    fn wire_addr(&self, addr: SocketAddr) -> SocketAddr {
+        self.proxy_clients.get(&addr).copied().unwrap_or(addr)
+    }
+
+    // -----------------------------------------------------------------------
+    // Internal: flood protection
+    // -----------------------------------------------------------------------
+
+    /// Drop datagrams from banned IPs and count malformed ones.
+    async fn handle_checked(&mut self, data: &[u8], addr: SocketAddr) -> Result<(), RakNetError> {
+        let ip = addr.ip();
+        if self.throttle.is_banned(ip, Instant::now()) {
+            return Ok(());
+        }
+        let result = self.handle_datagram(data, addr).await;
+        if let Err(ref e) = result {
+            if !matches!(e, RakNetError::Io(_))
+                && self.throttle.record_malformed(ip, Instant::now())
+            {
+                warn!("Banned {ip} for sending malformed packets");
+                self.drop_sessions_from(ip).await;
+            }
+        }
+        result
+    }
+
+    /// Remove every session from an IP, notifying the consumer of connected ones.
+    async fn drop_sessions_from(&mut self, ip: IpAddr) {
+        let addrs: Vec<SocketAddr> = self
+            .sessions
+            .keys()
+            .filter(|a| a.ip() == ip)
+            .copied()
+            .collect();
+        for addr in addrs {
+            let was_connected = self
+                .sessions
+                .remove(&addr)
+                .is_some_and(|s| s.state == SessionState::Connected);
+            if was_connected {
+                let _ = self
+                    .event_tx
+                    .send(RakNetEvent::SessionDisconnected { addr })
+                    .await;
+            }
+        }
+    }
+
+    fn sessions_from(&self, ip: IpAddr) -> usize {
+        self.sessions.keys().filter(|a| a.ip() == ip).count()
+    }
+
     // -----------------------------------------------------------------------
     // Internal: datagram dispatch
     // -----------------------------------------------------------------------
@@ -203,30 +447,76 @@ impl RakNetServer {
     // -----------------------------------------------------------------------
 
     async fn handle_unconnected_ping(
-        &self,
+        &mut self,
         data: &[u8],
         addr: SocketAddr,
     ) -> Result<(), RakNetError> {
+        if !self.throttle.allow_ping(addr.ip(), Instant::now()) {
+            trace!("Ping rate limit exceeded for {addr}");
+            return Ok(());
+        }
         let ping = OfflinePacket::decode(data, data.len())?;
         if let OfflinePacket::UnconnectedPing {
             send_timestamp,
             client_guid: _,
         } = ping
         {
-            let motd = self.config.motd.to_motd_string();
-            let pong = OfflinePacket::UnconnectedPong {
-                send_timestamp,
-                server_guid: self.config.server_guid,
-                motd,
-            };
-            let mut buf = BytesMut::with_capacity(256);
-            pong.encode(&mut buf);
-            self.socket.send_to(&buf, addr).await?;
+            let buf = self.encode_pong(send_timestamp);
+            self.send_datagram(&buf, addr).await?;
             trace!("Sent UnconnectedPong to {addr}");
         }
         Ok(())
     }
 
+    fn encode_pong(&self, send_timestamp: i64) -> BytesMut {
+        let pong = OfflinePacket::UnconnectedPong {
+            send_timestamp,
+            server_guid: self.config.server_guid,
+            motd: self.config.motd.to_motd_string(),
+        };
+        let mut buf = BytesMut::with_capacity(256);
+        pong.encode(&mut buf);
+        buf
+    }
+
+    // -----------------------------------------------------------------------
+    // LAN discovery
+    // -----------------------------------------------------------------------
+
+    /// Answer an UnconnectedPing received on the LAN discovery socket. The
+    /// pong advertises the real game port, so the client connects there.
+    async fn handle_lan_ping(&mut self, data: &[u8], addr: SocketAddr) -> Result<(), RakNetError> {
+        if !matches!(
+            data.first(),
+            Some(&(offline::id::UNCONNECTED_PING | offline::id::UNCONNECTED_PING_OPEN))
+        ) {
+            return Ok(());
+        }
+        let now = Instant::now();
+        if self.throttle.is_banned(addr.ip(), now) || !self.throttle.allow_ping(addr.ip(), now) {
+            return Ok(());
+        }
+        if let OfflinePacket::UnconnectedPing { send_timestamp, .. } =
+            OfflinePacket::decode(data, data.len())?
+        {
+            let buf = self.encode_pong(send_timestamp);
+            if let Some(socket) = &self.lan_socket {
+                socket.send_to(&buf, addr).await?;
+                trace!("Sent LAN UnconnectedPong to {addr}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Announce the server to the local network.
+    async fn broadcast_presence(&mut self) {
+        let buf = self.encode_pong(current_timestamp());
+        let target = SocketAddr::from((Ipv4Addr::BROADCAST, LAN_DISCOVERY_PORT));
+        if let Err(e) = self.socket.send_to(&buf, target).await {
+            debug!("LAN broadcast failed: {e}");
+        }
+    }
+
     async fn handle_open_connection_request_1(
         &mut self,
         data: &[u8],
@@ -245,6 +535,20 @@ impl RakNetServer {
                 // Still respond so client gets feedback
             }
 
+            if !self
+                .throttle
+                .allow_connection_attempt(addr.ip(), Instant::now())
+            {
+                debug!("OCR1 from {addr}: connection rate limit exceeded");
+                return Ok(());
+            }
+            if !self.sessions.contains_key(&addr)
+                && !self.throttle.allow_session(self.sessions_from(addr.ip()))
+            {
+                debug!("OCR1 from {addr}: too many sessions from this IP");
+                return Ok(());
+            }
+
             let mtu = mtu_size.clamp(MIN_MTU, MAX_MTU);
             debug!("OCR1 from {addr}: MTU={mtu}");
 
@@ -260,7 +564,7 @@ impl RakNetServer {
             };
             let mut buf = BytesMut::with_capacity(64);
             reply.encode(&mut buf);
-            self.socket.send_to(&buf, addr).await?;
+            self.send_datagram(&buf, addr).await?;
         }
         Ok(())
     }
@@ -281,6 +585,12 @@ impl RakNetServer {
                 debug!("OCR2 from {addr}: max connections reached, rejecting");
                 return Ok(());
             }
+            if !self.sessions.contains_key(&addr)
+                && !self.throttle.allow_session(self.sessions_from(addr.ip()))
+            {
+                debug!("OCR2 from {addr}: too many sessions from this IP");
+                return Ok(());
+            }
 
             let session = self
                 .sessions
@@ -303,7 +613,7 @@ impl RakNetServer {
             };
             let mut buf = BytesMut::with_capacity(64);
             reply.encode(&mut buf);
-            self.socket.send_to(&buf, addr).await?;
+            self.send_datagram(&buf, addr).await?;
         }
         Ok(())
     }
@@ -534,7 +844,7 @@ impl RakNetServer {
 
         // Send all queued datagrams
         for (addr, data) in to_send {
-            let _ = self.socket.send_to(&data, addr).await;
+            let _ = self.send_datagram(&data, addr).await;
         }
 
         // Remove timed-out sessions
@@ -553,10 +863,46 @@ impl RakNetServer {
                     .await;
             }
         }
+
+        self.throttle.cleanup(now);
+
+        if self.config.lan_broadcast
+            && now.duration_since(self.last_lan_broadcast) >= LAN_BROADCAST_INTERVAL
+        {
+            self.last_lan_broadcast = now;
+            self.broadcast_presence().await;
+        }
+
+        if let Some(writer) = &mut self.capture {
+            if let Err(e) = writer.flush() {
+                warn!("Datagram capture failed, disabling: {e}");
+                self.capture = None;
+            }
+        }
+
+        // Forget idle proxy flows without a session
+        if !self.proxy_flows.is_empty() {
+            let sessions = &self.sessions;
+            let proxy_clients = &mut self.proxy_clients;
+            self.proxy_flows.retain(|_, flow| {
+                let keep = sessions.contains_key(&flow.client)
+                    || now.duration_since(flow.last_seen) < SESSION_TIMEOUT;
+                if !keep {
+                    proxy_clients.remove(&flow.client);
+                }
+                keep
+            });
+        }
     }
 
     async fn shutdown(&mut self) {
+        // Packets queued before the shutdown (e.g. kick messages) go first
+        while let Ok(cmd) = self.command_rx.try_recv() {
+            self.handle_command(cmd);
+        }
+
         // Send disconnect notification to all connected sessions
+        let mut to_send: Vec<(SocketAddr, Bytes)> = Vec::new();
         for (addr, session) in &mut self.sessions {
             if session.state == SessionState::Connected {
                 let disconnect = OnlinePacket::DisconnectionNotification;
@@ -564,13 +910,46 @@ impl RakNetServer {
                 disconnect.encode(&mut payload);
                 session.queue_frame(payload.freeze(), Reliability::ReliableOrdered, 0);
 
-                let datagrams = session.flush_send_queue();
-                for dg in datagrams {
-                    let _ = self.socket.send_to(&dg, *addr).await;
+                for dg in session.flush_send_queue() {
+                    to_send.push((*addr, dg));
                 }
             }
         }
+        for (addr, data) in to_send {
+            let _ = self.send_datagram(&data, addr).await;
+        }
         self.sessions.clear();
+
+        if let Some(writer) = &mut self.capture {
+            let _ = writer.flush();
+        }
+    }
+}
+
+/// Bind the LAN discovery socket. Not needed when the server already listens
+/// on the discovery port; a failure (port taken) only disables LAN discovery.
+async fn bind_lan_socket(server_addr: SocketAddr) -> Option<Arc<UdpSocket>> {
+    if server_addr.port() == LAN_DISCOVERY_PORT || server_addr.is_ipv6() {
+        return None;
+    }
+    let lan_addr = SocketAddr::new(server_addr.ip(), LAN_DISCOVERY_PORT);
+    match UdpSocket::bind(lan_addr).await {
+        Ok(socket) => {
+            info!("Answering LAN discovery on {lan_addr}");
+            Some(Arc::new(socket))
+        }
+        Err(e) => {
+            warn!("LAN discovery disabled, cannot bind {lan_addr}: {e}");
+            None
+        }
+    }
+}
+
+/// Receive from the LAN discovery socket, or never complete if there is none.
+async fn recv_lan(socket: Option<&UdpSocket>, buf: &mut [u8]) -> Option<(usize, SocketAddr)> {
+    match socket {
+        Some(socket) => socket.recv_from(buf).await.ok(),
+        None => std::future::pending().await,
     }
 }
 
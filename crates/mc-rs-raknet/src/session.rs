@@ -92,6 +92,18 @@ impl RakNetSession {
     pub fn queue_frame(&mut self, body: Bytes, reliability: Reliability, channel: u8) {
         let max_body = self.mtu as usize - MAX_FRAME_OVERHEAD - 4; // 4 for frameset header
 
+        // A split packet is ordered as a whole: every fragment carries the
+        // same ordered index.
+        let (ordered_index, order_channel) =
+            if reliability.is_ordered() || reliability.is_sequenced() {
+                let ch = channel as usize;
+                let idx = self.send_ordered_index[ch];
+                self.send_ordered_index[ch] += 1;
+                (Some(idx), Some(channel))
+            } else {
+                (None, None)
+            };
+
         if body.len() > max_body {
             // Fragment the payload
             let split_id = self.split_id_counter;
@@ -107,7 +119,8 @@ impl RakNetSession {
                 let frame = self.build_frame(
                     fragment,
                     reliability,
-                    channel,
+                    ordered_index,
+                    order_channel,
                     Some(SplitInfo {
                         count: split_count,
                         id: split_id,
@@ -117,7 +130,7 @@ impl RakNetSession {
                 self.send_queue.push_back(frame);
             }
         } else {
-            let frame = self.build_frame(body, reliability, channel, None);
+            let frame = self.build_frame(body, reliability, ordered_index, order_channel, None);
             self.send_queue.push_back(frame);
         }
     }
@@ -126,7 +139,8 @@ impl RakNetSession {
         &mut self,
         body: Bytes,
         reliability: Reliability,
-        channel: u8,
+        ordered_index: Option<u32>,
+        order_channel: Option<u8>,
         split: Option<SplitInfo>,
     ) -> Frame {
         let reliable_index = if reliability.is_reliable() {
@@ -137,16 +151,6 @@ impl RakNetSession {
             None
         };
 
-        let (ordered_index, order_channel) =
-            if reliability.is_ordered() || reliability.is_sequenced() {
-                let ch = channel as usize;
-                let idx = self.send_ordered_index[ch];
-                self.send_ordered_index[ch] += 1;
-                (Some(idx), Some(channel))
-            } else {
-                (None, None)
-            };
-
         Frame {
             reliability,
             reliable_index,
@@ -0,0 +1,228 @@
+//! Per-IP rate limiting and flood protection.
+//!
+//! Tracks, for each source IP, how many unconnected pings and connection
+//! attempts it sent in the current one-second window and how many malformed
+//! datagrams it sent in the current minute. IPs exceeding the malformed
+//! packet limit are banned for a while; all their datagrams are dropped.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Window for the ping and connection-attempt rate limits.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+/// Window for counting malformed datagrams.
+const MALFORMED_WINDOW: Duration = Duration::from_secs(60);
+
+/// Limits applied per source IP. A limit of 0 disables that check.
+#[derive(Debug, Clone)]
+pub struct ThrottleConfig {
+    /// Unconnected pings answered per second.
+    pub max_pings_per_second: u32,
+    /// Open Connection Request 1 packets accepted per second.
+    pub max_connection_attempts_per_second: u32,
+    /// Concurrent sessions (including ones still handshaking).
+    pub max_sessions_per_ip: usize,
+    /// Malformed datagrams tolerated per minute before the IP is banned.
+    pub max_malformed_per_minute: u32,
+    /// How long a ban for malformed packets lasts.
+    pub ban_duration: Duration,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            max_pings_per_second: 10,
+            max_connection_attempts_per_second: 5,
+            max_sessions_per_ip: 5,
+            max_malformed_per_minute: 50,
+            ban_duration: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Counters for a single IP.
+struct IpState {
+    rate_window_start: Instant,
+    pings: u32,
+    connection_attempts: u32,
+    malformed_window_start: Instant,
+    malformed: u32,
+}
+
+impl IpState {
+    fn new(now: Instant) -> Self {
+        Self {
+            rate_window_start: now,
+            pings: 0,
+            connection_attempts: 0,
+            malformed_window_start: now,
+            malformed: 0,
+        }
+    }
+
+    fn roll_windows(&mut self, now: Instant) {
+        if now.duration_since(self.rate_window_start) >= RATE_WINDOW {
+            self.rate_window_start = now;
+            self.pings = 0;
+            self.connection_attempts = 0;
+        }
+        if now.duration_since(self.malformed_window_start) >= MALFORMED_WINDOW {
+            self.malformed_window_start = now;
+            self.malformed = 0;
+        }
+    }
+}
+
+/// Per-IP throttle state.
+pub struct Throttle {
+    config: ThrottleConfig,
+    ips: HashMap<IpAddr, IpState>,
+    /// Banned IP → ban expiry.
+    bans: HashMap<IpAddr, Instant>,
+}
+
+impl Throttle {
+    pub fn new(config: ThrottleConfig) -> Self {
+        Self {
+            config,
+            ips: HashMap::new(),
+            bans: HashMap::new(),
+        }
+    }
+
+    /// Whether the IP is currently banned.
+    pub fn is_banned(&self, ip: IpAddr, now: Instant) -> bool {
+        self.bans.get(&ip).is_some_and(|&until| now < until)
+    }
+
+    fn state(&mut self, ip: IpAddr, now: Instant) -> &mut IpState {
+        let state = self.ips.entry(ip).or_insert_with(|| IpState::new(now));
+        state.roll_windows(now);
+        state
+    }
+
+    /// Count an unconnected ping. Returns `false` if it should be dropped.
+    pub fn allow_ping(&mut self, ip: IpAddr, now: Instant) -> bool {
+        let limit = self.config.max_pings_per_second;
+        let state = self.state(ip, now);
+        state.pings += 1;
+        limit == 0 || state.pings <= limit
+    }
+
+    /// Count a connection attempt. Returns `false` if it should be dropped.
+    pub fn allow_connection_attempt(&mut self, ip: IpAddr, now: Instant) -> bool {
+        let limit = self.config.max_connection_attempts_per_second;
+        let state = self.state(ip, now);
+        state.connection_attempts += 1;
+        limit == 0 || state.connection_attempts <= limit
+    }
+
+    /// Whether another session may be opened by an IP that has `current` sessions.
+    pub fn allow_session(&self, current: usize) -> bool {
+        self.config.max_sessions_per_ip == 0 || current < self.config.max_sessions_per_ip
+    }
+
+    /// Count a malformed datagram. Returns `true` if the IP just got banned.
+    pub fn record_malformed(&mut self, ip: IpAddr, now: Instant) -> bool {
+        let limit = self.config.max_malformed_per_minute;
+        if limit == 0 {
+            return false;
+        }
+        let state = self.state(ip, now);
+        state.malformed += 1;
+        if state.malformed > limit {
+            state.malformed = 0;
+            self.bans.insert(ip, now + self.config.ban_duration);
+            return true;
+        }
+        false
+    }
+
+    /// Drop expired bans and counters of IPs that have been quiet for a while.
+    pub fn cleanup(&mut self, now: Instant) {
+        self.bans.retain(|_, &mut until| now < until);
+        self.ips.retain(|_, s| {
+            now.duration_since(s.rate_window_start) < MALFORMED_WINDOW
+                || now.duration_since(s.malformed_window_start) < MALFORMED_WINDOW
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IP: IpAddr = IpAddr::V4(std::net::Ipv4Addr::new(198, 51, 100, 1));
+
+    fn config() -> ThrottleConfig {
+        ThrottleConfig {
+            max_pings_per_second: 2,
+            max_connection_attempts_per_second: 1,
+            max_sessions_per_ip: 2,
+            max_malformed_per_minute: 3,
+            ban_duration: Duration::from_secs(10),
+        }
+    }
+
+    #[test]
+    fn ping_limit_resets_each_second() {
+        let mut t = Throttle::new(config());
+        let now = Instant::now();
+        assert!(t.allow_ping(IP, now));
+        assert!(t.allow_ping(IP, now));
+        assert!(!t.allow_ping(IP, now));
+        assert!(t.allow_ping(IP, now + RATE_WINDOW));
+    }
+
+    #[test]
+    fn connection_attempts_limited() {
+        let mut t = Throttle::new(config());
+        let now = Instant::now();
+        assert!(t.allow_connection_attempt(IP, now));
+        assert!(!t.allow_connection_attempt(IP, now));
+        let other: IpAddr = "198.51.100.2".parse().unwrap();
+        assert!(t.allow_connection_attempt(other, now));
+    }
+
+    #[test]
+    fn sessions_per_ip() {
+        let t = Throttle::new(config());
+        assert!(t.allow_session(1));
+        assert!(!t.allow_session(2));
+        let unlimited = Throttle::new(ThrottleConfig {
+            max_sessions_per_ip: 0,
+            ..config()
+        });
+        assert!(unlimited.allow_session(100));
+    }
+
+    #[test]
+    fn malformed_packets_ban_then_expire() {
+        let mut t = Throttle::new(config());
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(!t.record_malformed(IP, now));
+        }
+        assert!(t.record_malformed(IP, now));
+        assert!(t.is_banned(IP, now));
+        let later = now + Duration::from_secs(11);
+        assert!(!t.is_banned(IP, later));
+        t.cleanup(later);
+        assert!(t.bans.is_empty());
+    }
+
+    #[test]
+    fn zero_limits_disable_checks() {
+        let mut t = Throttle::new(ThrottleConfig {
+            max_pings_per_second: 0,
+            max_malformed_per_minute: 0,
+            ..config()
+        });
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert!(t.allow_ping(IP, now));
+            assert!(!t.record_malformed(IP, now));
+        }
+    }
+}
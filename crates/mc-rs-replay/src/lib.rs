@@ -0,0 +1,421 @@
+//! Replay harness for RakNet datagram captures.
+//!
+//! Feeds a capture written by the server (`[capture] enabled = true`) back
+//! through the RakNet and game protocol decoders and reports every datagram
+//! or packet that no longer decodes. Used to check protocol changes against
+//! traffic recorded from real clients.
+//!
+//! Inbound datagrams go through the same decoders the server uses; outbound
+//! datagrams are decoded down to the game packet layer, which is enough to
+//! follow compression being negotiated. Batches sent after the encryption
+//! handshake cannot be decoded and are only counted.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read};
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+use mc_rs_proto::batch::{decode_batch, BatchConfig};
+use mc_rs_proto::codec::ProtoDecode;
+use mc_rs_proto::error::ProtoError;
+use mc_rs_proto::packets::{self, id};
+use mc_rs_proto::types::VarUInt32;
+use mc_rs_raknet::capture::{CaptureReader, CaptureRecord, Direction};
+use mc_rs_raknet::packet::frame::{AckNack, FrameSet};
+use mc_rs_raknet::packet::offline::OfflinePacket;
+use mc_rs_raknet::packet::online::{self, OnlinePacket};
+use mc_rs_raknet::session::RakNetSession;
+use mc_rs_raknet::{proxy_protocol, RakNetError};
+
+/// Game packet wrapper byte inside a RakNet frame.
+const GAME_PACKET: u8 = 0xFE;
+/// MTU used for the replay sessions (only affects sending, which is unused).
+const REPLAY_MTU: u16 = 1400;
+
+/// A datagram or packet that failed to decode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayError {
+    /// Index of the record in the capture.
+    pub record: usize,
+    pub direction: Direction,
+    pub addr: SocketAddr,
+    pub message: String,
+}
+
+/// Summary of a replayed capture.
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    pub inbound_datagrams: usize,
+    pub outbound_datagrams: usize,
+    pub offline_packets: usize,
+    pub framesets: usize,
+    pub acks: usize,
+    pub game_batches: usize,
+    /// Batches skipped because the connection was encrypted.
+    pub encrypted_batches: usize,
+    /// Decoded game packets from clients, by packet ID.
+    pub inbound_packets: BTreeMap<u32, u64>,
+    /// Decoded game packets from the server, by packet ID.
+    pub outbound_packets: BTreeMap<u32, u64>,
+    pub errors: Vec<ReplayError>,
+}
+
+impl ReplayReport {
+    /// Whether every datagram and packet decoded.
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Per-direction reassembly state of a connection.
+struct Flow {
+    session: RakNetSession,
+}
+
+/// Game-layer state shared by both directions of a connection.
+#[derive(Default)]
+struct Connection {
+    batch_config: BatchConfig,
+    encrypted: bool,
+}
+
+/// Replays capture records in order.
+#[derive(Default)]
+pub struct Replayer {
+    flows: HashMap<(SocketAddr, bool), Flow>,
+    connections: HashMap<SocketAddr, Connection>,
+    report: ReplayReport,
+    next_record: usize,
+}
+
+impl Replayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one captured datagram.
+    pub fn feed(&mut self, record: &CaptureRecord) {
+        let index = self.next_record;
+        self.next_record += 1;
+        if let Err(message) = self.process(record) {
+            self.report.errors.push(ReplayError {
+                record: index,
+                direction: record.direction,
+                addr: record.addr,
+                message,
+            });
+        }
+    }
+
+    /// Finish the replay and return the report.
+    pub fn finish(self) -> ReplayReport {
+        self.report
+    }
+
+    fn process(&mut self, record: &CaptureRecord) -> Result<(), String> {
+        let inbound = record.direction == Direction::Inbound;
+        let mut data = &record.data[..];
+        if inbound {
+            self.report.inbound_datagrams += 1;
+            if proxy_protocol::has_header(data) {
+                let header = proxy_protocol::parse(data).map_err(err)?;
+                data = &data[header.header_len..];
+            }
+        } else {
+            self.report.outbound_datagrams += 1;
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        match data[0] {
+            0x80..=0x8D => self.process_frameset(record.addr, inbound, data),
+            AckNack::ACK_ID | AckNack::NACK_ID => {
+                self.report.acks += 1;
+                AckNack::decode(data).map(|_| ()).map_err(err)
+            }
+            _ => {
+                self.report.offline_packets += 1;
+                OfflinePacket::decode(data, data.len())
+                    .map(|_| ())
+                    .map_err(err)
+            }
+        }
+    }
+
+    fn process_frameset(
+        &mut self,
+        addr: SocketAddr,
+        inbound: bool,
+        data: &[u8],
+    ) -> Result<(), String> {
+        self.report.framesets += 1;
+        let frameset = FrameSet::decode(data).map_err(err)?;
+        let flow = self.flows.entry((addr, inbound)).or_insert_with(|| Flow {
+            session: RakNetSession::new(addr, REPLAY_MTU, 0),
+        });
+        let payloads = flow.session.process_incoming_frameset(frameset);
+
+        for payload in payloads {
+            if payload.is_empty() {
+                continue;
+            }
+            match payload[0] {
+                GAME_PACKET => self.process_batch(addr, inbound, payload.slice(1..))?,
+                online::id::DISCONNECTION_NOTIFICATION => {
+                    self.flows.remove(&(addr, true));
+                    self.flows.remove(&(addr, false));
+                    self.connections.remove(&addr);
+                    return Ok(());
+                }
+                _ => {
+                    OnlinePacket::decode(&payload).map_err(err)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn process_batch(
+        &mut self,
+        addr: SocketAddr,
+        inbound: bool,
+        batch: Bytes,
+    ) -> Result<(), String> {
+        self.report.game_batches += 1;
+        let conn = self.connections.entry(addr).or_default();
+        if conn.encrypted {
+            self.report.encrypted_batches += 1;
+            return Ok(());
+        }
+
+        let sub_packets = decode_batch(batch, &conn.batch_config).map_err(err)?;
+        for sub_packet in sub_packets {
+            let mut cursor = Cursor::new(&sub_packet[..]);
+            let header = VarUInt32::proto_decode(&mut cursor).map_err(err)?.0;
+            let packet_id = header & 0x3FF;
+
+            let counts = if inbound {
+                &mut self.report.inbound_packets
+            } else {
+                &mut self.report.outbound_packets
+            };
+            *counts.entry(packet_id).or_default() += 1;
+
+            if inbound {
+                decode_serverbound(packet_id, &mut cursor)
+                    .map_err(|e| format!("packet 0x{packet_id:02X}: {e}"))?;
+            } else {
+                match packet_id {
+                    id::NETWORK_SETTINGS => conn.batch_config.compression_enabled = true,
+                    // Everything after the handshake is encrypted.
+                    id::SERVER_TO_CLIENT_HANDSHAKE => conn.encrypted = true,
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Decode a client → server game packet body, if the codec knows it.
+fn decode_serverbound(packet_id: u32, buf: &mut Cursor<&[u8]>) -> Result<(), ProtoError> {
+    match packet_id {
+        id::REQUEST_NETWORK_SETTINGS => {
+            packets::RequestNetworkSettings::proto_decode(buf)?;
+        }
+        id::LOGIN => {
+            packets::LoginPacket::proto_decode(buf)?;
+        }
+        id::CLIENT_TO_SERVER_HANDSHAKE => {
+            packets::ClientToServerHandshake::proto_decode(buf)?;
+        }
+        id::RESOURCE_PACK_CLIENT_RESPONSE => {
+            packets::ResourcePackClientResponse::proto_decode(buf)?;
+        }
+        id::RESOURCE_PACK_CHUNK_REQUEST => {
+            packets::ResourcePackChunkRequest::proto_decode(buf)?;
+        }
+        id::REQUEST_CHUNK_RADIUS => {
+            packets::RequestChunkRadius::proto_decode(buf)?;
+        }
+        id::SET_LOCAL_PLAYER_AS_INITIALIZED => {
+            packets::SetLocalPlayerAsInitialized::proto_decode(buf)?;
+        }
+        id::TEXT => {
+            packets::Text::proto_decode(buf)?;
+        }
+        id::MOVE_PLAYER => {
+            packets::MovePlayer::proto_decode(buf)?;
+        }
+        id::PLAYER_AUTH_INPUT => {
+            packets::PlayerAuthInput::proto_decode(buf)?;
+        }
+        id::PLAYER_ACTION => {
+            packets::PlayerAction::proto_decode(buf)?;
+        }
+        id::ANIMATE => {
+            packets::Animate::proto_decode(buf)?;
+        }
+        id::RESPAWN => {
+            packets::Respawn::proto_decode(buf)?;
+        }
+        id::INVENTORY_TRANSACTION => {
+            packets::InventoryTransaction::proto_decode(buf)?;
+        }
+        id::MOB_EQUIPMENT => {
+            packets::MobEquipment::proto_decode(buf)?;
+        }
+        id::ITEM_STACK_REQUEST => {
+            packets::ItemStackRequest::proto_decode(buf)?;
+        }
+        id::CONTAINER_CLOSE => {
+            packets::ContainerClose::proto_decode(buf)?;
+        }
+        id::COMMAND_REQUEST => {
+            packets::CommandRequest::proto_decode(buf)?;
+        }
+        id::MODAL_FORM_RESPONSE => {
+            packets::ModalFormResponse::proto_decode(buf)?;
+        }
+        id::PLAYER_SKIN => {
+            packets::PlayerSkin::proto_decode(buf)?;
+        }
+        id::BLOCK_ACTOR_DATA => {
+            packets::BlockActorData::proto_decode(buf)?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn err(e: impl std::fmt::Display) -> String {
+    e.to_string()
+}
+
+/// Replay a whole capture.
+pub fn replay<R: Read>(input: R) -> Result<ReplayReport, RakNetError> {
+    let mut replayer = Replayer::new();
+    for record in CaptureReader::new(input)? {
+        replayer.feed(&record?);
+    }
+    Ok(replayer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::BytesMut;
+    use mc_rs_proto::batch::encode_batch;
+    use mc_rs_proto::codec::ProtoEncode;
+    use mc_rs_raknet::capture::CaptureWriter;
+    use mc_rs_raknet::packet::offline;
+    use mc_rs_raknet::Reliability;
+
+    fn client() -> SocketAddr {
+        "10.0.0.2:50000".parse().unwrap()
+    }
+
+    fn sub_packet(packet_id: u32, body: &[u8]) -> Bytes {
+        let mut buf = BytesMut::new();
+        VarUInt32(packet_id).proto_encode(&mut buf);
+        buf.extend_from_slice(body);
+        buf.freeze()
+    }
+
+    /// Wrap game packets into RakNet datagrams sent by `session`.
+    fn game_datagrams(
+        session: &mut RakNetSession,
+        packets: &[Bytes],
+        config: &BatchConfig,
+    ) -> Vec<Bytes> {
+        let batch = encode_batch(packets, config).unwrap();
+        let mut payload = BytesMut::new();
+        payload.extend_from_slice(&[GAME_PACKET]);
+        payload.extend_from_slice(&batch);
+        session.queue_frame(payload.freeze(), Reliability::ReliableOrdered, 0);
+        session.flush_send_queue()
+    }
+
+    fn record(direction: Direction, data: Bytes) -> CaptureRecord {
+        CaptureRecord {
+            timestamp_us: 0,
+            direction,
+            addr: client(),
+            data,
+        }
+    }
+
+    /// A login prefix: RequestNetworkSettings, NetworkSettings, then a
+    /// compressed client batch.
+    fn handshake_capture() -> Vec<CaptureRecord> {
+        let mut client_side = RakNetSession::new(client(), REPLAY_MTU, 0);
+        let mut server_side = RakNetSession::new(client(), REPLAY_MTU, 0);
+        let mut config = BatchConfig::default();
+        let mut records = Vec::new();
+
+        let request = sub_packet(id::REQUEST_NETWORK_SETTINGS, &766i32.to_be_bytes());
+        for dg in game_datagrams(&mut client_side, &[request], &config) {
+            records.push(record(Direction::Inbound, dg));
+        }
+
+        let mut settings = BytesMut::new();
+        packets::NetworkSettings::default().proto_encode(&mut settings);
+        let settings = sub_packet(id::NETWORK_SETTINGS, &settings);
+        for dg in game_datagrams(&mut server_side, &[settings], &config) {
+            records.push(record(Direction::Outbound, dg));
+        }
+
+        config.compression_enabled = true;
+        config.compression_threshold = 0;
+        let chunk_radius = sub_packet(id::REQUEST_CHUNK_RADIUS, &[0x10, 0x00]);
+        for dg in game_datagrams(&mut client_side, &[chunk_radius], &config) {
+            records.push(record(Direction::Inbound, dg));
+        }
+        records
+    }
+
+    #[test]
+    fn replays_handshake_with_compression() {
+        let mut replayer = Replayer::new();
+        for r in handshake_capture() {
+            replayer.feed(&r);
+        }
+        let report = replayer.finish();
+        assert!(report.is_clean(), "{:?}", report.errors);
+        assert_eq!(report.game_batches, 3);
+        assert_eq!(report.inbound_packets[&id::REQUEST_NETWORK_SETTINGS], 1);
+        assert_eq!(report.inbound_packets[&id::REQUEST_CHUNK_RADIUS], 1);
+        assert_eq!(report.outbound_packets[&id::NETWORK_SETTINGS], 1);
+    }
+
+    #[test]
+    fn reports_undecodable_packets() {
+        let mut records = handshake_capture();
+        // Truncated OpenConnectionRequest2.
+        records.push(record(
+            Direction::Inbound,
+            Bytes::from_static(&[offline::id::OPEN_CONNECTION_REQUEST_2, 0x00]),
+        ));
+        let mut replayer = Replayer::new();
+        for r in &records {
+            replayer.feed(r);
+        }
+        let report = replayer.finish();
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].record, records.len() - 1);
+    }
+
+    #[test]
+    fn replay_reads_capture_file() {
+        let mut writer = CaptureWriter::new(Vec::new()).unwrap();
+        for r in handshake_capture() {
+            writer.record(r.direction, r.addr, &r.data).unwrap();
+        }
+        let bytes = writer.into_inner();
+        let report = replay(&bytes[..]).unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.inbound_datagrams, 2);
+        assert_eq!(report.outbound_datagrams, 1);
+    }
+}
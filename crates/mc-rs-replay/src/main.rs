@@ -0,0 +1,65 @@
+//! `mc-rs-replay <capture>` — replay a datagram capture through the codec.
+//!
+//! Exits with status 1 if any datagram or packet failed to decode.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("usage: mc-rs-replay <capture file>");
+        return ExitCode::from(2);
+    };
+    let file = match File::open(&path) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("cannot open {path}: {e}");
+            return ExitCode::from(2);
+        }
+    };
+    let report = match mc_rs_replay::replay(BufReader::new(file)) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("cannot read {path}: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    println!(
+        "{} datagrams in, {} out: {} offline, {} framesets, {} ACK/NACK",
+        report.inbound_datagrams,
+        report.outbound_datagrams,
+        report.offline_packets,
+        report.framesets,
+        report.acks
+    );
+    println!(
+        "{} game batches ({} encrypted, skipped)",
+        report.game_batches, report.encrypted_batches
+    );
+    for (label, counts) in [
+        ("client", &report.inbound_packets),
+        ("server", &report.outbound_packets),
+    ] {
+        let list: Vec<String> = counts
+            .iter()
+            .map(|(id, n)| format!("0x{id:02X}×{n}"))
+            .collect();
+        println!("{label} packets: {}", list.join(" "));
+    }
+
+    if report.is_clean() {
+        println!("OK: everything decoded");
+        return ExitCode::SUCCESS;
+    }
+    for e in &report.errors {
+        let dir = match e.direction {
+            mc_rs_raknet::capture::Direction::Inbound => "from",
+            mc_rs_raknet::capture::Direction::Outbound => "to",
+        };
+        println!("record #{} ({dir} {}): {}", e.record, e.addr, e.message);
+    }
+    println!("FAILED: {} decode errors", report.errors.len());
+    ExitCode::FAILURE
+}
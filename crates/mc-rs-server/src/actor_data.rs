@@ -0,0 +1,58 @@
+//! Change tracking for entity metadata: remembers what clients were last
+//! told about each entity so that only what changed goes out in
+//! SetActorData.
+
+use std::collections::HashMap;
+
+use mc_rs_proto::entity_metadata::{metadata_changes, EntityMetadataEntry};
+
+/// The metadata last sent for each entity, by runtime ID.
+#[derive(Debug, Default)]
+pub struct ActorDataTracker {
+    sent: HashMap<u64, Vec<EntityMetadataEntry>>,
+}
+
+impl ActorDataTracker {
+    /// Record an entity's current metadata. Returns the entries to send:
+    /// everything the first time the entity is seen, then only changes.
+    pub fn update(
+        &mut self,
+        runtime_id: u64,
+        metadata: Vec<EntityMetadataEntry>,
+    ) -> Vec<EntityMetadataEntry> {
+        let changes = match self.sent.get(&runtime_id) {
+            Some(sent) => metadata_changes(sent, &metadata),
+            None => metadata.clone(),
+        };
+        self.sent.insert(runtime_id, metadata);
+        changes
+    }
+
+    /// Forget an entity that is gone.
+    pub fn forget(&mut self, runtime_id: u64) {
+        self.sent.remove(&runtime_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_rs_proto::entity_metadata::{EntityFlag, MetadataBuilder};
+
+    #[test]
+    fn sends_everything_once_then_only_changes() {
+        let mut tracker = ActorDataTracker::default();
+        let standing = MetadataBuilder::new().flags(0).nametag("Alice").build();
+        let sneaking = MetadataBuilder::from_entries(standing.clone())
+            .flag(EntityFlag::Sneaking, true)
+            .build();
+
+        assert_eq!(tracker.update(1, standing.clone()), standing);
+        assert!(tracker.update(1, standing.clone()).is_empty());
+        assert_eq!(tracker.update(1, sneaking.clone()), sneaking[..1]);
+        assert!(tracker.update(1, sneaking).is_empty());
+
+        tracker.forget(1);
+        assert_eq!(tracker.update(1, standing.clone()), standing);
+    }
+}
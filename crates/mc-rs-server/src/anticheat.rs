@@ -0,0 +1,439 @@
+//! Anti-cheat checks and violation scoring.
+//!
+//! Each failed check adds one to the player's score for that check. Scores
+//! decay over time; crossing a check's thresholds warns or kicks the player,
+//! and the offending action can be undone (rubber-banding). Thresholds are
+//! set per check under `[anticheat.<check>]` in `server.toml`.
+//!
+//! Hits are checked against where the target was over the last few ticks
+//! as well as where it is now, since the attacker's client shows other
+//! entities slightly in the past.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use serde::Deserialize;
+
+/// An anti-cheat check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Moving further per tick than sprinting or gliding allows.
+    Speed,
+    /// Staying airborne without falling in survival.
+    Fly,
+    /// Moving into solid blocks.
+    NoClip,
+    /// Breaking or using blocks out of reach.
+    Reach,
+    /// Hitting entities the player is not facing.
+    KillAura,
+    /// Breaking blocks faster than the tool allows.
+    FastBreak,
+    /// Sending actions faster than the per-action intervals.
+    RateLimit,
+}
+
+impl Check {
+    pub const ALL: [Check; 7] = [
+        Check::Speed,
+        Check::Fly,
+        Check::NoClip,
+        Check::Reach,
+        Check::KillAura,
+        Check::FastBreak,
+        Check::RateLimit,
+    ];
+
+    /// Name used in `server.toml`, logs and plugin events.
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::Speed => "speed",
+            Check::Fly => "fly",
+            Check::NoClip => "noclip",
+            Check::Reach => "reach",
+            Check::KillAura => "killaura",
+            Check::FastBreak => "fastbreak",
+            Check::RateLimit => "rate_limit",
+        }
+    }
+
+    /// Message shown to players kicked by this check.
+    pub fn kick_message(self) -> &'static str {
+        match self {
+            Check::Speed => "Speed hack detected",
+            Check::Fly => "Fly hack detected",
+            Check::NoClip => "No-clip detected",
+            Check::Reach => "Reach hack detected",
+            Check::KillAura => "Kill aura detected",
+            Check::FastBreak => "Fast break detected",
+            Check::RateLimit => "Too many actions",
+        }
+    }
+
+    /// Score at which a player is kicked unless configured otherwise.
+    fn default_kick_score(self) -> u32 {
+        match self {
+            Check::Speed => 10,
+            Check::Fly => 5,
+            Check::NoClip => 5,
+            Check::Reach => 20,
+            Check::KillAura => 10,
+            Check::FastBreak => 10,
+            Check::RateLimit => 50,
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Settings of one check as written in `server.toml`. Unset fields fall
+/// back to the check's defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CheckSettings {
+    pub enabled: Option<bool>,
+    /// Score at which the player is warned. 0 = never.
+    pub warn_score: Option<u32>,
+    /// Score at which the player is kicked. 0 = never.
+    pub kick_score: Option<u32>,
+    /// Undo the offending action: move the player back, or ignore the hit,
+    /// break or packet.
+    pub rubber_band: Option<bool>,
+}
+
+impl CheckSettings {
+    /// Fill in unset fields with the check's defaults.
+    pub fn resolve(&self, check: Check) -> CheckConfig {
+        let kick_score = self.kick_score.unwrap_or(check.default_kick_score());
+        CheckConfig {
+            enabled: self.enabled.unwrap_or(true),
+            warn_score: self.warn_score.unwrap_or(kick_score / 2),
+            kick_score,
+            rubber_band: self.rubber_band.unwrap_or(true),
+        }
+    }
+}
+
+/// Effective settings of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CheckConfig {
+    pub enabled: bool,
+    pub warn_score: u32,
+    pub kick_score: u32,
+    pub rubber_band: bool,
+}
+
+/// What to do about a violation that brought a check's score to a value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ViolationResponse {
+    pub warn: bool,
+    pub rubber_band: bool,
+    pub kick: bool,
+}
+
+impl CheckConfig {
+    /// Actions for a violation that brought the score to `score`. The
+    /// warning is only given once, when the score reaches the threshold.
+    pub fn respond(&self, score: u32) -> ViolationResponse {
+        ViolationResponse {
+            warn: self.warn_score > 0 && score == self.warn_score,
+            rubber_band: self.rubber_band,
+            kick: self.kick_score > 0 && score >= self.kick_score,
+        }
+    }
+}
+
+/// Per-player violation scores, one per check.
+#[derive(Debug, Clone, Default)]
+pub struct ViolationScores {
+    scores: [u32; Check::ALL.len()],
+}
+
+impl ViolationScores {
+    pub fn get(&self, check: Check) -> u32 {
+        self.scores[check.index()]
+    }
+
+    /// Add a violation, returning the new score.
+    pub fn add(&mut self, check: Check) -> u32 {
+        let score = &mut self.scores[check.index()];
+        *score = score.saturating_add(1);
+        *score
+    }
+
+    /// Lower every score by 1.
+    pub fn decay(&mut self) {
+        for score in &mut self.scores {
+            *score = score.saturating_sub(1);
+        }
+    }
+}
+
+/// Horizontal angle in degrees between where a player at `from` looks
+/// (`yaw`, Bedrock convention) and the direction to `to`, both `(x, z)`.
+/// `None` when the target is too close for the angle to mean anything.
+pub fn attack_angle(yaw: f32, from: (f32, f32), to: (f32, f32)) -> Option<f32> {
+    let (dx, dz) = (to.0 - from.0, to.1 - from.1);
+    let len = (dx * dx + dz * dz).sqrt();
+    if len < 1.0 {
+        return None;
+    }
+    let yaw = yaw.to_radians();
+    let (look_x, look_z) = (-yaw.sin(), yaw.cos());
+    let cos = ((look_x * dx + look_z * dz) / len).clamp(-1.0, 1.0);
+    Some(cos.acos().to_degrees())
+}
+
+/// Ticks of movement kept per entity for lag compensation (2 seconds).
+pub const POSITION_HISTORY_TICKS: u64 = 40;
+
+/// Recent positions (feet) of an entity, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct PositionHistory {
+    entries: VecDeque<(u64, (f32, f32, f32))>,
+}
+
+impl PositionHistory {
+    /// Record where the entity is as of `tick`, dropping positions it left
+    /// more than [`POSITION_HISTORY_TICKS`] ago.
+    pub fn record(&mut self, tick: u64, feet: (f32, f32, f32)) {
+        self.entries.push_back((tick, feet));
+        let cutoff = tick.saturating_sub(POSITION_HISTORY_TICKS);
+        while self.entries.len() > 1 && self.entries[1].0 <= cutoff {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Tick of the latest recorded position.
+    pub fn last_tick(&self) -> Option<u64> {
+        self.entries.back().map(|&(tick, _)| tick)
+    }
+
+    /// Every position the entity held during the `window` ticks before
+    /// `now`, newest first.
+    pub fn positions_within(&self, now: u64, window: u64) -> Vec<(f32, f32, f32)> {
+        let cutoff = now.saturating_sub(window.min(POSITION_HISTORY_TICKS));
+        let mut positions = Vec::new();
+        for &(tick, feet) in self.entries.iter().rev() {
+            positions.push(feet);
+            // The newest position from before the window still held at its start
+            if tick <= cutoff {
+                break;
+            }
+        }
+        positions
+    }
+}
+
+/// An entity's hitbox, standing on `feet`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hitbox {
+    pub feet: (f32, f32, f32),
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Hitbox {
+    /// Distance from `eye` to the closest point of the hitbox.
+    pub fn distance_from(&self, eye: (f32, f32, f32)) -> f32 {
+        let half = self.width / 2.0;
+        let dx = eye.0 - eye.0.clamp(self.feet.0 - half, self.feet.0 + half);
+        let dy = eye.1 - eye.1.clamp(self.feet.1, self.feet.1 + self.height);
+        let dz = eye.2 - eye.2.clamp(self.feet.2 - half, self.feet.2 + half);
+        (dx * dx + dy * dy + dz * dz).sqrt()
+    }
+}
+
+/// Outcome of validating a hit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HitVerdict {
+    /// The hit lands. `compensated` if only a past position of the target
+    /// was in reach and in view.
+    Valid { compensated: bool },
+    /// Every position of the target was out of reach; the closest distance.
+    OutOfReach(f32),
+    /// The target was in reach but never in view; the smallest angle.
+    OutOfView(f32),
+}
+
+/// Validate a hit from a player at `eye` looking at `yaw` on a target that
+/// was at each of `hitboxes` recently, the current one first.
+pub fn validate_hit(
+    eye: (f32, f32, f32),
+    yaw: f32,
+    hitboxes: &[Hitbox],
+    reach: f32,
+    max_angle: f32,
+) -> HitVerdict {
+    let mut closest = f32::INFINITY;
+    let mut narrowest = f32::INFINITY;
+    for (i, hitbox) in hitboxes.iter().enumerate() {
+        let distance = hitbox.distance_from(eye);
+        closest = closest.min(distance);
+        if distance > reach {
+            continue;
+        }
+        let angle =
+            attack_angle(yaw, (eye.0, eye.2), (hitbox.feet.0, hitbox.feet.2)).unwrap_or(0.0);
+        narrowest = narrowest.min(angle);
+        if angle <= max_angle {
+            return HitVerdict::Valid { compensated: i > 0 };
+        }
+    }
+    if narrowest.is_finite() {
+        HitVerdict::OutOfView(narrowest)
+    } else {
+        HitVerdict::OutOfReach(closest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_thresholds() {
+        let speed = CheckSettings::default().resolve(Check::Speed);
+        assert!(speed.enabled);
+        assert_eq!(speed.kick_score, 10);
+        assert_eq!(speed.warn_score, 5);
+        assert!(speed.rubber_band);
+        assert_eq!(
+            CheckSettings::default()
+                .resolve(Check::RateLimit)
+                .kick_score,
+            50
+        );
+
+        let custom = CheckSettings {
+            kick_score: Some(0),
+            rubber_band: Some(false),
+            ..CheckSettings::default()
+        }
+        .resolve(Check::Fly);
+        assert_eq!(custom.kick_score, 0);
+        assert_eq!(custom.warn_score, 0);
+        assert!(!custom.rubber_band);
+    }
+
+    #[test]
+    fn responses() {
+        let config = CheckSettings::default().resolve(Check::Fly);
+        assert_eq!(
+            config.respond(1),
+            ViolationResponse {
+                rubber_band: true,
+                ..ViolationResponse::default()
+            }
+        );
+        assert!(config.respond(2).warn);
+        assert!(!config.respond(3).warn);
+        assert!(!config.respond(4).kick);
+        assert!(config.respond(5).kick);
+
+        let never = CheckConfig {
+            warn_score: 0,
+            kick_score: 0,
+            ..config
+        };
+        assert_eq!(never.respond(100), never.respond(1));
+    }
+
+    #[test]
+    fn scores_add_and_decay() {
+        let mut scores = ViolationScores::default();
+        assert_eq!(scores.add(Check::Speed), 1);
+        assert_eq!(scores.add(Check::Speed), 2);
+        assert_eq!(scores.add(Check::Reach), 1);
+        scores.decay();
+        assert_eq!(scores.get(Check::Speed), 1);
+        assert_eq!(scores.get(Check::Reach), 0);
+        scores.decay();
+        assert_eq!(scores.get(Check::Speed), 0);
+        assert_eq!(scores.get(Check::Fly), 0);
+    }
+
+    #[test]
+    fn attack_angles() {
+        // Yaw 0 looks towards +Z
+        let ahead = attack_angle(0.0, (0.0, 0.0), (0.0, 3.0)).unwrap();
+        assert!(ahead < 1.0);
+        let behind = attack_angle(0.0, (0.0, 0.0), (0.0, -3.0)).unwrap();
+        assert!((behind - 180.0).abs() < 1.0);
+        // Yaw 90 looks towards -X
+        let side = attack_angle(90.0, (0.0, 0.0), (-3.0, 0.0)).unwrap();
+        assert!(side < 1.0);
+        assert!(attack_angle(0.0, (0.0, 0.0), (0.2, 0.3)).is_none());
+    }
+
+    #[test]
+    fn position_history_window() {
+        let mut history = PositionHistory::default();
+        for tick in 0..100 {
+            history.record(tick, (tick as f32, 0.0, 0.0));
+        }
+        // Trimmed to the last 2 seconds
+        assert!(history.entries.len() <= POSITION_HISTORY_TICKS as usize + 1);
+        assert_eq!(history.last_tick(), Some(99));
+
+        let recent = history.positions_within(99, 3);
+        assert_eq!(recent.first(), Some(&(99.0, 0.0, 0.0)));
+        assert_eq!(recent.last(), Some(&(96.0, 0.0, 0.0)));
+        assert_eq!(recent.len(), 4);
+
+        // A target that stopped moving keeps its last position
+        let mut still = PositionHistory::default();
+        still.record(10, (5.0, 0.0, 5.0));
+        assert_eq!(still.positions_within(500, 10), vec![(5.0, 0.0, 5.0)]);
+    }
+
+    #[test]
+    fn hitbox_distance() {
+        let zombie = Hitbox {
+            feet: (0.0, 64.0, 0.0),
+            width: 0.6,
+            height: 1.9,
+        };
+        // Level with the body: distance to the side of the box
+        assert!((zombie.distance_from((3.3, 65.0, 0.0)) - 3.0).abs() < 1e-4);
+        // Straight above the head
+        assert!((zombie.distance_from((0.0, 68.9, 0.0)) - 3.0).abs() < 1e-4);
+        assert_eq!(zombie.distance_from((0.1, 65.0, 0.1)), 0.0);
+    }
+
+    #[test]
+    fn hit_validation() {
+        let at = |x: f32, z: f32| Hitbox {
+            feet: (x, 64.0, z),
+            width: 0.6,
+            height: 1.8,
+        };
+        let eye = (0.0, 65.62, 0.0);
+        // Facing +Z, target 3 blocks ahead
+        assert_eq!(
+            validate_hit(eye, 0.0, &[at(0.0, 3.0)], 4.0, 90.0),
+            HitVerdict::Valid { compensated: false }
+        );
+        // Target ran out of reach, but was in reach a moment ago
+        assert_eq!(
+            validate_hit(eye, 0.0, &[at(0.0, 6.0), at(0.0, 3.5)], 4.0, 90.0),
+            HitVerdict::Valid { compensated: true }
+        );
+        assert!(matches!(
+            validate_hit(eye, 0.0, &[at(0.0, 8.0), at(0.0, 7.0)], 4.0, 90.0),
+            HitVerdict::OutOfReach(d) if (d - 6.7).abs() < 1e-3
+        ));
+        // Target behind the attacker
+        assert!(matches!(
+            validate_hit(eye, 0.0, &[at(0.0, -3.0)], 4.0, 90.0),
+            HitVerdict::OutOfView(a) if a > 170.0
+        ));
+    }
+}
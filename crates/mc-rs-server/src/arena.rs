@@ -0,0 +1,550 @@
+//! Minigame arenas defined by plugins: players queue in a lobby, a
+//! countdown starts once enough of them joined, the match splits them
+//! over the teams, and the arena's blocks are rolled back when it ends.
+//!
+//! This module only keeps the state machine; the connection handler moves
+//! the players, shows the scoreboard and takes the block snapshots.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use mc_rs_game::block_entity::BlockEntityData;
+
+/// Largest region an arena may cover, in blocks, to bound the snapshot.
+pub const MAX_ARENA_VOLUME: u64 = 1_000_000;
+
+/// Seconds left at which the countdown is announced.
+const COUNTDOWN_ANNOUNCEMENTS: [u64; 6] = [10, 5, 4, 3, 2, 1];
+
+/// A team of an arena.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaTeam {
+    pub name: String,
+    pub spawn: (f32, f32, f32),
+}
+
+/// What a plugin defined an arena as.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaDefinition {
+    pub name: String,
+    /// Lowest corner of the region, inclusive.
+    pub min: [i32; 3],
+    /// Highest corner of the region, inclusive.
+    pub max: [i32; 3],
+    pub lobby: (f32, f32, f32),
+    pub teams: Vec<ArenaTeam>,
+    pub min_players: usize,
+    pub max_players: usize,
+    pub countdown_ticks: u64,
+    /// `None` runs the match until a plugin ends it.
+    pub duration_ticks: Option<u64>,
+}
+
+impl ArenaDefinition {
+    /// Number of blocks in the region.
+    pub fn volume(&self) -> u64 {
+        (0..3)
+            .map(|i| (self.max[i] as i64 - self.min[i] as i64 + 1) as u64)
+            .product()
+    }
+
+    /// Every position of the region, in a fixed order.
+    pub fn positions(&self) -> impl Iterator<Item = (i32, i32, i32)> {
+        let (min, max) = (self.min, self.max);
+        (min[0]..=max[0]).flat_map(move |x| {
+            (min[1]..=max[1]).flat_map(move |y| (min[2]..=max[2]).map(move |z| (x, y, z)))
+        })
+    }
+
+    pub fn contains(&self, (x, y, z): (i32, i32, i32)) -> bool {
+        (self.min[0]..=self.max[0]).contains(&x)
+            && (self.min[1]..=self.max[1]).contains(&y)
+            && (self.min[2]..=self.max[2]).contains(&z)
+    }
+}
+
+/// Where an arena is in its cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaPhase {
+    /// Waiting for enough players.
+    Waiting,
+    Countdown {
+        ticks_left: u64,
+    },
+    Running {
+        ticks_left: Option<u64>,
+    },
+}
+
+/// Why an arena request was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArenaError {
+    Unknown,
+    NoTeams,
+    BadPlayerCounts,
+    TooLarge,
+    Full,
+    /// The arena's match is running.
+    Running,
+    /// The arena's match is not running.
+    NotRunning,
+    AlreadyInArena,
+}
+
+impl fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ArenaError::Unknown => "no such arena",
+            ArenaError::NoTeams => "an arena needs at least one team",
+            ArenaError::BadPlayerCounts => "max_players must be at least min_players",
+            ArenaError::TooLarge => "the arena region is too large",
+            ArenaError::Full => "the arena is full",
+            ArenaError::Running => "the arena is in a match",
+            ArenaError::NotRunning => "the arena is not in a match",
+            ArenaError::AlreadyInArena => "already in an arena",
+        })
+    }
+}
+
+/// The blocks of an arena's region when its match started.
+#[derive(Debug, Clone, Default)]
+pub struct RegionSnapshot {
+    /// Runtime IDs in [`ArenaDefinition::positions`] order; `None` where the
+    /// chunk wasn't loaded.
+    pub blocks: Vec<Option<u32>>,
+    pub block_entities: Vec<((i32, i32, i32), BlockEntityData)>,
+}
+
+/// An arena and its players.
+#[derive(Debug)]
+pub struct Arena {
+    pub def: ArenaDefinition,
+    pub phase: ArenaPhase,
+    /// Queued and playing players, in join order.
+    pub players: Vec<String>,
+    /// Team index of each player in the running match.
+    pub teams: HashMap<String, usize>,
+    /// Sidebar lines: entry → (scoreboard entry ID, score).
+    pub scores: BTreeMap<String, (i64, i32)>,
+    pub snapshot: Option<RegionSnapshot>,
+}
+
+impl Arena {
+    fn new(def: ArenaDefinition) -> Self {
+        Self {
+            def,
+            phase: ArenaPhase::Waiting,
+            players: Vec::new(),
+            teams: HashMap::new(),
+            scores: BTreeMap::new(),
+            snapshot: None,
+        }
+    }
+
+    /// Scoreboard objective of the arena's sidebar.
+    pub fn objective_name(&self) -> String {
+        format!("arena.{}", self.def.name)
+    }
+
+    /// Whether a player is in the running match (not just queued).
+    pub fn is_playing(&self, player: &str) -> bool {
+        self.teams.contains_key(player)
+    }
+
+    /// Players of the running match, in join order.
+    pub fn playing(&self) -> Vec<String> {
+        self.players
+            .iter()
+            .filter(|p| self.is_playing(p))
+            .cloned()
+            .collect()
+    }
+
+    /// Split the first `max_players` queued players over the teams, in turn.
+    fn assign_teams(&mut self) -> Vec<(String, Vec<String>)> {
+        let mut teams: Vec<(String, Vec<String>)> = self
+            .def
+            .teams
+            .iter()
+            .map(|t| (t.name.clone(), Vec::new()))
+            .collect();
+        self.teams.clear();
+        for (i, player) in self.players.iter().take(self.def.max_players).enumerate() {
+            let team = i % teams.len();
+            self.teams.insert(player.clone(), team);
+            teams[team].1.push(player.clone());
+        }
+        teams
+    }
+
+    fn finish(&mut self, winner: Option<String>) -> ArenaUpdate {
+        let update = ArenaUpdate::Ended {
+            arena: self.def.name.clone(),
+            winner,
+            players: self.playing(),
+            snapshot: self.snapshot.take(),
+        };
+        self.phase = ArenaPhase::Waiting;
+        self.teams.clear();
+        self.scores.clear();
+        update
+    }
+}
+
+/// Something the server has to act on after an arena changed phase.
+#[derive(Debug)]
+pub enum ArenaUpdate {
+    CountdownStarted {
+        arena: String,
+        secs: u64,
+    },
+    CountdownTick {
+        arena: String,
+        secs: u64,
+    },
+    /// Players left and too few are queued.
+    CountdownCancelled {
+        arena: String,
+    },
+    Started {
+        arena: String,
+        teams: Vec<(String, Vec<String>)>,
+    },
+    /// The match ended. `players` played it; `snapshot` holds the blocks
+    /// to roll back.
+    Ended {
+        arena: String,
+        winner: Option<String>,
+        players: Vec<String>,
+        snapshot: Option<RegionSnapshot>,
+    },
+}
+
+/// All arenas of the server.
+#[derive(Debug, Default)]
+pub struct ArenaManager {
+    arenas: BTreeMap<String, Arena>,
+    /// Matches ended by a plugin, handed out by the next tick.
+    ended: Vec<ArenaUpdate>,
+}
+
+impl ArenaManager {
+    /// Define an arena, or redefine one whose match isn't running. Its
+    /// queued players stay queued.
+    pub fn create(&mut self, mut def: ArenaDefinition) -> Result<(), ArenaError> {
+        if def.teams.is_empty() {
+            return Err(ArenaError::NoTeams);
+        }
+        def.min_players = def.min_players.max(1);
+        if def.max_players < def.min_players {
+            return Err(ArenaError::BadPlayerCounts);
+        }
+        let (a, b) = (def.min, def.max);
+        def.min = [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])];
+        def.max = [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])];
+        if def.volume() > MAX_ARENA_VOLUME {
+            return Err(ArenaError::TooLarge);
+        }
+        match self.arenas.get_mut(&def.name) {
+            Some(arena) if matches!(arena.phase, ArenaPhase::Running { .. }) => {
+                Err(ArenaError::Running)
+            }
+            Some(arena) => {
+                arena.def = def;
+                arena.phase = ArenaPhase::Waiting;
+                Ok(())
+            }
+            None => {
+                self.arenas.insert(def.name.clone(), Arena::new(def));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arena> {
+        self.arenas.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Arena> {
+        self.arenas.get_mut(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arena> {
+        self.arenas.values()
+    }
+
+    /// Name of the arena a player is queued or playing in.
+    pub fn arena_of(&self, player: &str) -> Option<&str> {
+        self.arenas
+            .values()
+            .find(|a| a.players.iter().any(|p| p == player))
+            .map(|a| a.def.name.as_str())
+    }
+
+    /// Queue a player in an arena. Returns its lobby.
+    pub fn join(&mut self, player: &str, arena: &str) -> Result<(f32, f32, f32), ArenaError> {
+        if self.arena_of(player).is_some() {
+            return Err(ArenaError::AlreadyInArena);
+        }
+        let arena = self.arenas.get_mut(arena).ok_or(ArenaError::Unknown)?;
+        if arena.players.len() >= arena.def.max_players {
+            return Err(ArenaError::Full);
+        }
+        arena.players.push(player.to_string());
+        Ok(arena.def.lobby)
+    }
+
+    /// Take a player out of their arena. Returns the arena and whether they
+    /// were in its match. A match nobody plays anymore ends on the next tick.
+    pub fn leave(&mut self, player: &str) -> Option<(String, bool)> {
+        let arena = self
+            .arenas
+            .values_mut()
+            .find(|a| a.players.iter().any(|p| p == player))?;
+        arena.players.retain(|p| p != player);
+        let was_playing = arena.teams.remove(player).is_some();
+        Some((arena.def.name.clone(), was_playing))
+    }
+
+    /// Set a sidebar line. `new_id` is the scoreboard entry ID to use if the
+    /// entry is new; returns the entry's ID.
+    pub fn set_score(
+        &mut self,
+        arena: &str,
+        entry: &str,
+        score: i32,
+        new_id: i64,
+    ) -> Result<i64, ArenaError> {
+        let arena = self.arenas.get_mut(arena).ok_or(ArenaError::Unknown)?;
+        let line = arena
+            .scores
+            .entry(entry.to_string())
+            .or_insert((new_id, score));
+        line.1 = score;
+        Ok(line.0)
+    }
+
+    /// End an arena's match. The next tick reports it.
+    pub fn end(&mut self, arena: &str, winner: Option<&str>) -> Result<(), ArenaError> {
+        let arena = self.arenas.get_mut(arena).ok_or(ArenaError::Unknown)?;
+        if !matches!(arena.phase, ArenaPhase::Running { .. }) {
+            return Err(ArenaError::NotRunning);
+        }
+        self.ended.push(arena.finish(winner.map(str::to_string)));
+        Ok(())
+    }
+
+    /// Advance every arena by a tick.
+    pub fn tick(&mut self) -> Vec<ArenaUpdate> {
+        let mut updates = std::mem::take(&mut self.ended);
+        for arena in self.arenas.values_mut() {
+            let name = arena.def.name.clone();
+            let enough = arena.players.len() >= arena.def.min_players;
+            match arena.phase {
+                ArenaPhase::Waiting if enough => {
+                    arena.phase = ArenaPhase::Countdown {
+                        ticks_left: arena.def.countdown_ticks,
+                    };
+                    updates.push(ArenaUpdate::CountdownStarted {
+                        arena: name,
+                        secs: arena.def.countdown_ticks.div_ceil(20),
+                    });
+                }
+                ArenaPhase::Waiting => {}
+                ArenaPhase::Countdown { .. } if !enough => {
+                    arena.phase = ArenaPhase::Waiting;
+                    updates.push(ArenaUpdate::CountdownCancelled { arena: name });
+                }
+                ArenaPhase::Countdown { ticks_left: 0 } => {
+                    let teams = arena.assign_teams();
+                    arena.phase = ArenaPhase::Running {
+                        ticks_left: arena.def.duration_ticks,
+                    };
+                    updates.push(ArenaUpdate::Started { arena: name, teams });
+                }
+                ArenaPhase::Countdown { ticks_left } => {
+                    let ticks_left = ticks_left - 1;
+                    arena.phase = ArenaPhase::Countdown { ticks_left };
+                    let secs = ticks_left / 20;
+                    if ticks_left % 20 == 0 && COUNTDOWN_ANNOUNCEMENTS.contains(&secs) {
+                        updates.push(ArenaUpdate::CountdownTick { arena: name, secs });
+                    }
+                }
+                ArenaPhase::Running { .. } if arena.teams.is_empty() => {
+                    updates.push(arena.finish(None));
+                }
+                ArenaPhase::Running {
+                    ticks_left: Some(0),
+                } => {
+                    updates.push(arena.finish(None));
+                }
+                ArenaPhase::Running { ticks_left } => {
+                    arena.phase = ArenaPhase::Running {
+                        ticks_left: ticks_left.map(|t| t - 1),
+                    };
+                }
+            }
+        }
+        updates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn duel(countdown_ticks: u64, duration_ticks: Option<u64>) -> ArenaDefinition {
+        ArenaDefinition {
+            name: "duel".into(),
+            min: [10, 70, 10],
+            max: [0, 60, 0],
+            lobby: (0.5, 80.0, 0.5),
+            teams: vec![
+                ArenaTeam {
+                    name: "red".into(),
+                    spawn: (1.5, 61.0, 1.5),
+                },
+                ArenaTeam {
+                    name: "blue".into(),
+                    spawn: (9.5, 61.0, 9.5),
+                },
+            ],
+            min_players: 2,
+            max_players: 3,
+            countdown_ticks,
+            duration_ticks,
+        }
+    }
+
+    fn tick_n(mgr: &mut ArenaManager, n: usize) -> Vec<ArenaUpdate> {
+        (0..n).flat_map(|_| mgr.tick()).collect()
+    }
+
+    #[test]
+    fn create_normalizes_and_validates() {
+        let mut mgr = ArenaManager::default();
+        mgr.create(duel(0, None)).unwrap();
+        let def = &mgr.get("duel").unwrap().def;
+        assert_eq!((def.min, def.max), ([0, 60, 0], [10, 70, 10]));
+        assert_eq!(def.volume(), 11 * 11 * 11);
+        assert_eq!(def.positions().count(), 11 * 11 * 11);
+        assert!(def.contains((10, 60, 0)) && !def.contains((11, 60, 0)));
+
+        let mut no_teams = duel(0, None);
+        no_teams.teams.clear();
+        assert_eq!(mgr.create(no_teams), Err(ArenaError::NoTeams));
+        let mut huge = duel(0, None);
+        huge.max = [1000, 319, 1000];
+        assert_eq!(mgr.create(huge), Err(ArenaError::TooLarge));
+        let mut counts = duel(0, None);
+        counts.max_players = 1;
+        assert_eq!(mgr.create(counts), Err(ArenaError::BadPlayerCounts));
+    }
+
+    #[test]
+    fn join_and_leave() {
+        let mut mgr = ArenaManager::default();
+        mgr.create(duel(0, None)).unwrap();
+        assert_eq!(mgr.join("Alice", "duel"), Ok((0.5, 80.0, 0.5)));
+        assert_eq!(mgr.join("Alice", "duel"), Err(ArenaError::AlreadyInArena));
+        assert_eq!(mgr.join("Bob", "ffa"), Err(ArenaError::Unknown));
+        mgr.join("Bob", "duel").unwrap();
+        mgr.join("Carol", "duel").unwrap();
+        assert_eq!(mgr.join("Dave", "duel"), Err(ArenaError::Full));
+        assert_eq!(mgr.arena_of("Bob"), Some("duel"));
+        assert_eq!(mgr.leave("Bob"), Some(("duel".into(), false)));
+        assert_eq!(mgr.arena_of("Bob"), None);
+        assert_eq!(mgr.leave("Bob"), None);
+    }
+
+    #[test]
+    fn countdown_starts_and_assigns_teams() {
+        let mut mgr = ArenaManager::default();
+        mgr.create(duel(100, None)).unwrap();
+        mgr.join("Alice", "duel").unwrap();
+        assert!(mgr.tick().is_empty());
+        mgr.join("Bob", "duel").unwrap();
+        mgr.join("Carol", "duel").unwrap();
+
+        let updates = mgr.tick();
+        assert!(matches!(
+            updates[..],
+            [ArenaUpdate::CountdownStarted { secs: 5, .. }]
+        ));
+        let ticks: Vec<u64> = tick_n(&mut mgr, 100)
+            .iter()
+            .map(|u| match u {
+                ArenaUpdate::CountdownTick { secs, .. } => *secs,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+        assert_eq!(ticks, [4, 3, 2, 1]);
+
+        let Some(ArenaUpdate::Started { teams, .. }) = mgr.tick().pop() else {
+            panic!("match did not start");
+        };
+        assert_eq!(
+            teams[0],
+            ("red".into(), vec!["Alice".into(), "Carol".into()])
+        );
+        assert_eq!(teams[1], ("blue".into(), vec!["Bob".into()]));
+        assert!(mgr.get("duel").unwrap().is_playing("Carol"));
+        assert_eq!(mgr.create(duel(0, None)), Err(ArenaError::Running));
+    }
+
+    #[test]
+    fn countdown_is_cancelled_when_players_leave() {
+        let mut mgr = ArenaManager::default();
+        mgr.create(duel(100, None)).unwrap();
+        mgr.join("Alice", "duel").unwrap();
+        mgr.join("Bob", "duel").unwrap();
+        tick_n(&mut mgr, 10);
+        mgr.leave("Bob");
+        assert!(matches!(
+            mgr.tick()[..],
+            [ArenaUpdate::CountdownCancelled { .. }]
+        ));
+        assert_eq!(mgr.get("duel").unwrap().phase, ArenaPhase::Waiting);
+    }
+
+    #[test]
+    fn matches_end_on_time_when_empty_or_on_request() {
+        let mut mgr = ArenaManager::default();
+        mgr.create(duel(0, Some(40))).unwrap();
+        mgr.join("Alice", "duel").unwrap();
+        mgr.join("Bob", "duel").unwrap();
+        tick_n(&mut mgr, 2);
+        mgr.set_score("duel", "Alice", 3, 7).unwrap();
+        assert_eq!(mgr.set_score("duel", "Alice", 4, 8), Ok(7));
+
+        let updates = tick_n(&mut mgr, 41);
+        let [ArenaUpdate::Ended {
+            winner, players, ..
+        }] = &updates[..]
+        else {
+            panic!("expected the match to end, got {updates:?}");
+        };
+        assert_eq!(*winner, None);
+        assert_eq!(players, &["Alice".to_string(), "Bob".to_string()]);
+        let arena = mgr.get("duel").unwrap();
+        assert!(arena.scores.is_empty() && arena.teams.is_empty());
+        assert_eq!(arena.players.len(), 2, "players stay queued");
+
+        // The next match starts right away, and ends when both leave
+        tick_n(&mut mgr, 2);
+        assert_eq!(mgr.leave("Alice"), Some(("duel".into(), true)));
+        mgr.leave("Bob");
+        assert!(matches!(mgr.tick()[..], [ArenaUpdate::Ended { .. }]));
+
+        mgr.join("Alice", "duel").unwrap();
+        mgr.join("Bob", "duel").unwrap();
+        assert_eq!(mgr.end("duel", None).unwrap_err(), ArenaError::NotRunning);
+        tick_n(&mut mgr, 2);
+        mgr.end("duel", Some("Bob")).unwrap();
+        assert!(matches!(
+            &mgr.tick()[..],
+            [ArenaUpdate::Ended { winner: Some(w), .. }, ArenaUpdate::CountdownStarted { .. }]
+                if w == "Bob"
+        ));
+    }
+}
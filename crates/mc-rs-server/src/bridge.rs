@@ -0,0 +1,257 @@
+//! Chat bridge for Discord and the like, without a plugin.
+//!
+//! Outbound, joins, quits, chat, deaths and the server start are posted as
+//! JSON (`{"event": "chat", "player": "Steve", "message": "hi"}`) to
+//! `[bridge] webhook_url`, one at a time and in order, from a task of their
+//! own. Inbound, a small HTTP endpoint on `[bridge] listen` takes
+//! `POST /chat` with `{"author": "...", "message": "..."}` and a
+//! `Authorization: Bearer <token>` header, and relays the message into the
+//! game.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, Semaphore};
+use tracing::{debug, info, warn};
+
+use crate::config::BridgeSection;
+use crate::http::{self, HttpUrl};
+
+/// How long to wait for the webhook before giving up on an event.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Endpoint connections handled at once; more are closed on accept.
+const MAX_CONNECTIONS: usize = 32;
+
+/// A server event posted to the webhook.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BridgeEvent {
+    Join { player: String },
+    Leave { player: String },
+    Chat { player: String, message: String },
+    Death { player: String, message: String },
+    ServerStart,
+}
+
+impl BridgeEvent {
+    /// Name of the event in `[bridge] events` and the JSON.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BridgeEvent::Join { .. } => "join",
+            BridgeEvent::Leave { .. } => "leave",
+            BridgeEvent::Chat { .. } => "chat",
+            BridgeEvent::Death { .. } => "death",
+            BridgeEvent::ServerStart => "server_start",
+        }
+    }
+}
+
+/// The outbound half: the queue of events for the webhook task.
+#[derive(Debug, Default)]
+pub struct BridgeWebhook {
+    events: Vec<String>,
+    queue: Option<mpsc::UnboundedSender<BridgeEvent>>,
+}
+
+impl BridgeWebhook {
+    /// Start posting to `[bridge] webhook_url`, if set. An invalid URL is
+    /// logged and leaves the webhook off.
+    pub fn start(config: &BridgeSection) -> Self {
+        if config.webhook_url.is_empty() {
+            return Self::default();
+        }
+        let url = match HttpUrl::parse(&config.webhook_url) {
+            Ok(url) => url,
+            Err(e) => {
+                warn!("Invalid bridge webhook URL {}: {e}", config.webhook_url);
+                return Self::default();
+            }
+        };
+        let (queue, mut events) = mpsc::unbounded_channel::<BridgeEvent>();
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                let body = serde_json::to_string(&event).unwrap_or_default();
+                let post = tokio::time::timeout(WEBHOOK_TIMEOUT, http::post_json(&url, &body));
+                let result = match post.await {
+                    Ok(response) => response.and_then(|r| http::response_body(&r)),
+                    Err(_) => Err("no answer".into()),
+                };
+                if let Err(e) = result {
+                    warn!("Bridge webhook failed for a {} event: {e}", event.name());
+                }
+            }
+        });
+        Self {
+            events: config.events.clone(),
+            queue: Some(queue),
+        }
+    }
+
+    /// Queue an event for the webhook, if `[bridge] events` lists it.
+    pub fn post(&self, event: BridgeEvent) {
+        let Some(queue) = &self.queue else {
+            return;
+        };
+        if self.events.iter().any(|e| e == event.name()) {
+            let _ = queue.send(event);
+        }
+    }
+}
+
+/// Chat relayed into the game through the endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BridgeChat {
+    pub author: String,
+    pub message: String,
+}
+
+/// Start the endpoint on `listen`. Relayed chat is forwarded to the
+/// connection handler.
+pub fn start(listen: String, token: String, chat_tx: mpsc::Sender<BridgeChat>) {
+    tokio::spawn(async move {
+        let listener = match TcpListener::bind(&listen).await {
+            Ok(l) => l,
+            Err(e) => {
+                warn!("Failed to bind the bridge endpoint on {listen}: {e}");
+                return;
+            }
+        };
+        info!("Bridge endpoint listening on {listen}");
+
+        let slots = Arc::new(Semaphore::new(MAX_CONNECTIONS));
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    let Ok(permit) = Arc::clone(&slots).try_acquire_owned() else {
+                        debug!("Too many bridge connections, closing {peer}");
+                        continue;
+                    };
+                    let token = token.clone();
+                    let tx = chat_tx.clone();
+                    tokio::spawn(async move {
+                        handle_bridge_connection(stream, peer, &token, &tx).await;
+                        drop(permit);
+                    });
+                }
+                Err(e) => {
+                    warn!("Bridge accept error: {e}");
+                }
+            }
+        }
+    });
+}
+
+async fn handle_bridge_connection(
+    mut stream: TcpStream,
+    peer: SocketAddr,
+    token: &str,
+    chat_tx: &mpsc::Sender<BridgeChat>,
+) {
+    let request = match http::read_request(&mut stream).await {
+        Ok(r) => r,
+        Err(e) => {
+            debug!("Bad bridge request from {peer}: {e}");
+            http::write_response(&mut stream, 400, "bad request").await;
+            return;
+        }
+    };
+    let (status, body) = match check_request(&request, token) {
+        Ok(chat) => {
+            debug!(
+                "Bridge chat from {peer}: <{}> {}",
+                chat.author, chat.message
+            );
+            let _ = chat_tx.send(chat).await;
+            (204, "")
+        }
+        Err(answer) => answer,
+    };
+    http::write_response(&mut stream, status, body).await;
+}
+
+/// The chat in a request, or the status and message to refuse it with.
+fn check_request(
+    request: &http::HttpRequest,
+    token: &str,
+) -> Result<BridgeChat, (u16, &'static str)> {
+    if request.path != "/chat" {
+        return Err((404, "not found"));
+    }
+    if request.method != "POST" {
+        return Err((405, "use POST"));
+    }
+    let authorized = request
+        .header("authorization")
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .is_some_and(|t| mc_rs_crypto::constant_time_eq(t.as_bytes(), token.as_bytes()));
+    if !authorized {
+        return Err((401, "invalid token"));
+    }
+    match serde_json::from_str::<BridgeChat>(&request.body) {
+        Ok(chat) if !chat.author.trim().is_empty() && !chat.message.trim().is_empty() => Ok(chat),
+        _ => Err((400, "expected {\"author\": ..., \"message\": ...}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, path: &str, auth: &str, body: &str) -> http::HttpRequest {
+        http::HttpRequest {
+            method: method.into(),
+            path: path.into(),
+            headers: vec![("authorization".into(), auth.into())],
+            body: body.into(),
+        }
+    }
+
+    #[test]
+    fn events_are_tagged_json() {
+        let event = BridgeEvent::Chat {
+            player: "Steve".into(),
+            message: "hi".into(),
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"chat","player":"Steve","message":"hi"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&BridgeEvent::ServerStart).unwrap(),
+            r#"{"event":"server_start"}"#
+        );
+    }
+
+    #[test]
+    fn endpoint_checks_requests() {
+        let body = r#"{"author": "Bob", "message": "hello"}"#;
+        assert_eq!(
+            check_request(&request("POST", "/chat", "Bearer s3cret", body), "s3cret"),
+            Ok(BridgeChat {
+                author: "Bob".into(),
+                message: "hello".into(),
+            })
+        );
+        let refused = |r: http::HttpRequest| check_request(&r, "s3cret").unwrap_err().0;
+        assert_eq!(refused(request("POST", "/chat", "Bearer wrong", body)), 401);
+        assert_eq!(refused(request("POST", "/chat", "", body)), 401);
+        assert_eq!(refused(request("GET", "/chat", "Bearer s3cret", "")), 405);
+        assert_eq!(
+            refused(request("POST", "/other", "Bearer s3cret", body)),
+            404
+        );
+        assert_eq!(
+            refused(request(
+                "POST",
+                "/chat",
+                "Bearer s3cret",
+                r#"{"author": "Bob"}"#
+            )),
+            400
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Chat moderation: the `[chat]` word filter and `/mute` durations.
+
+use crate::config::ChatSection;
+
+/// What the word filter makes of a chat message.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Filtered {
+    /// The message has no filtered word.
+    Clean,
+    /// The message with its filtered words masked.
+    Replaced(String),
+    /// The message has a filtered word and mustn't be sent.
+    Blocked,
+}
+
+/// Look for the filtered words in `message`, whole and ignoring case, and
+/// mask them or block the message as `filter_mode` says.
+pub fn filter_message(config: &ChatSection, message: &str) -> Filtered {
+    if config.filter_words.is_empty() {
+        return Filtered::Clean;
+    }
+    let mut filtered = String::with_capacity(message.len());
+    let mut found = false;
+    let mut rest = message;
+    while !rest.is_empty() {
+        let word_len = rest
+            .find(|c: char| !c.is_alphanumeric())
+            .unwrap_or(rest.len());
+        let (word, tail) = rest.split_at(word_len);
+        if !word.is_empty()
+            && config
+                .filter_words
+                .iter()
+                .any(|w| w.to_lowercase() == word.to_lowercase())
+        {
+            found = true;
+            filtered.extend(std::iter::repeat_n(
+                config.filter_replacement,
+                word.chars().count(),
+            ));
+        } else {
+            filtered.push_str(word);
+        }
+        let sep_len = tail.find(char::is_alphanumeric).unwrap_or(tail.len());
+        let (sep, tail) = tail.split_at(sep_len);
+        filtered.push_str(sep);
+        rest = tail;
+    }
+    match (found, config.filter_mode.as_str()) {
+        (false, _) => Filtered::Clean,
+        (true, "block") => Filtered::Blocked,
+        (true, _) => Filtered::Replaced(filtered),
+    }
+}
+
+/// Parse a `/mute` duration: seconds, or a number followed by `s`, `m`,
+/// `h` or `d`.
+pub fn parse_duration(s: &str) -> Option<u64> {
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    };
+    number.parse::<u64>().ok()?.checked_mul(scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: &str) -> ChatSection {
+        ChatSection {
+            filter_words: vec!["darn".into(), "Heck".into()],
+            filter_mode: mode.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn replaces_whole_words() {
+        let config = config("replace");
+        assert_eq!(
+            filter_message(&config, "Darn it, heck!"),
+            Filtered::Replaced("**** it, ****!".into())
+        );
+        assert_eq!(filter_message(&config, "darned heckler"), Filtered::Clean);
+        assert_eq!(filter_message(&config, ""), Filtered::Clean);
+    }
+
+    #[test]
+    fn blocks_messages() {
+        let config = config("block");
+        assert_eq!(filter_message(&config, "oh HECK"), Filtered::Blocked);
+        assert_eq!(filter_message(&config, "hello"), Filtered::Clean);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("30"), Some(30));
+        assert_eq!(parse_duration("30s"), Some(30));
+        assert_eq!(parse_duration("10m"), Some(600));
+        assert_eq!(parse_duration("2h"), Some(7200));
+        assert_eq!(parse_duration("1d"), Some(86400));
+        assert_eq!(parse_duration("1w"), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("-5"), None);
+    }
+}
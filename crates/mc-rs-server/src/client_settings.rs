@@ -0,0 +1,79 @@
+//! Settings the client applies itself: fog, client-side game rules and
+//! input locks.
+
+/// Game rules only the client acts on, with their default values.
+pub const CLIENT_GAME_RULES: [(&str, bool); 4] = [
+    ("showcoordinates", false),
+    ("showdaysplayed", false),
+    ("showtags", true),
+    ("showbordereffect", true),
+];
+
+/// The lowercase name of a client-side game rule, whatever its case.
+pub fn client_game_rule(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    CLIENT_GAME_RULES
+        .iter()
+        .find(|(rule, _)| *rule == name)
+        .map(|&(rule, _)| rule)
+}
+
+/// A player's fog stack, as managed by `/fog`: each fog is pushed under a
+/// user ID so that whoever pushed it can take it off again.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FogStack {
+    /// (user ID, fog ID), bottom first.
+    entries: Vec<(String, String)>,
+}
+
+impl FogStack {
+    pub fn push(&mut self, fog_id: &str, user_id: &str) {
+        self.entries.push((user_id.to_string(), fog_id.to_string()));
+    }
+
+    /// Remove the top fog pushed under a user ID. Returns its fog ID.
+    pub fn pop(&mut self, user_id: &str) -> Option<String> {
+        let index = self.entries.iter().rposition(|(u, _)| u == user_id)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Remove every fog pushed under a user ID. Returns how many there were.
+    pub fn remove(&mut self, user_id: &str) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|(u, _)| u != user_id);
+        before - self.entries.len()
+    }
+
+    /// The fog IDs to send, bottom first.
+    pub fn fog_ids(&self) -> Vec<String> {
+        self.entries.iter().map(|(_, fog)| fog.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fog_is_taken_off_by_user_id() {
+        let mut stack = FogStack::default();
+        stack.push("minecraft:fog_hell", "intro");
+        stack.push("minecraft:fog_the_end", "boss");
+        stack.push("minecraft:fog_ocean", "intro");
+        assert_eq!(stack.pop("intro"), Some("minecraft:fog_ocean".into()));
+        assert_eq!(
+            stack.fog_ids(),
+            ["minecraft:fog_hell", "minecraft:fog_the_end"]
+        );
+        assert_eq!(stack.remove("boss"), 1);
+        assert_eq!(stack.remove("boss"), 0);
+        assert_eq!(stack.pop("nobody"), None);
+        assert_eq!(stack.fog_ids(), ["minecraft:fog_hell"]);
+    }
+
+    #[test]
+    fn client_game_rules_ignore_case() {
+        assert_eq!(client_game_rule("showCoordinates"), Some("showcoordinates"));
+        assert_eq!(client_game_rule("keepInventory"), None);
+    }
+}
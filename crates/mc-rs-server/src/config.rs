@@ -1,7 +1,18 @@
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
 use std::path::Path;
 
-#[derive(Debug, Deserialize)]
+use mc_rs_game::combat::KnockbackProfile;
+
+use crate::anticheat::{Check, CheckConfig, CheckSettings};
+use crate::kits::KitDefinition;
+use crate::world_flags::WorldFlags;
+
+/// The configuration file, in the working directory.
+pub const CONFIG_FILE: &str = "server.toml";
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct ServerConfig {
     pub server: ServerSection,
     pub world: WorldSection,
@@ -14,15 +25,128 @@ pub struct ServerConfig {
     pub rcon: RconSection,
     #[serde(default)]
     pub query: QuerySection,
+    #[serde(default)]
+    pub gameplay: GameplaySection,
+    #[serde(default)]
+    pub player_data: PlayerDataSection,
+    #[serde(default)]
+    pub transfer: TransferSection,
+    #[serde(default)]
+    pub proxy: ProxySection,
+    #[serde(default)]
+    pub throttle: ThrottleSection,
+    #[serde(default)]
+    pub capture: CaptureSection,
+    #[serde(default)]
+    pub lan: LanSection,
+    #[serde(default)]
+    pub storage: StorageSection,
+    #[serde(default)]
+    pub anticheat: AntiCheatSection,
+    #[serde(default)]
+    pub combat: CombatSection,
+    #[serde(default)]
+    pub world_flags: WorldFlagsSection,
+    #[serde(default)]
+    pub afk: AfkSection,
+    #[serde(default)]
+    pub teleport: TeleportSection,
+    #[serde(default)]
+    pub watchdog: WatchdogSection,
+    #[serde(default)]
+    pub dynamic_view: DynamicViewSection,
+    #[serde(default)]
+    pub geoip: GeoIpSection,
+    #[serde(default)]
+    pub prelogin: PreLoginSection,
+    #[serde(default)]
+    pub chat: ChatSection,
+    #[serde(default)]
+    pub bridge: BridgeSection,
+    #[serde(default)]
+    pub economy: EconomySection,
+    #[serde(default)]
+    pub plugins: PluginsSection,
+    /// Kit definitions: name → kit (`[kits.<name>]` tables).
+    #[serde(default)]
+    pub kits: HashMap<String, KitDefinition>,
+    /// Worlds hosted next to the main one: name → world (`[worlds.<name>]`
+    /// tables).
+    #[serde(default)]
+    pub worlds: HashMap<String, ExtraWorldSection>,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct PermissionsSection {
     #[serde(default)]
     pub whitelist_enabled: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct GameplaySection {
+    /// Initial value of the keepInventory game rule.
+    #[serde(default)]
+    pub keep_inventory: bool,
+    /// On death, store the player's items in a chest at the death location
+    /// instead of dropping them (overflow is still dropped).
+    #[serde(default)]
+    pub death_graves: bool,
+    /// Initial value of the commandBlocksEnabled game rule.
+    #[serde(default = "default_command_blocks_enabled")]
+    pub command_blocks_enabled: bool,
+    /// Most command blocks one chain may run in a tick.
+    #[serde(default = "default_max_command_chain_length")]
+    pub max_command_chain_length: usize,
+    /// Initial value of the showCoordinates game rule.
+    #[serde(default)]
+    pub show_coordinates: bool,
+    /// Keep sneaking players from walking off the edge of the block they
+    /// stand on, even if their client lets them.
+    #[serde(default)]
+    pub sneak_edge_protection: bool,
+    /// Where hostile mobs may spawn: `light` (dark spots, falling back to
+    /// `height` while chunks carry no light data) or `height` (anywhere at
+    /// night, only deep underground by day).
+    #[serde(default = "default_hostile_spawn_rule")]
+    pub hostile_spawn_rule: String,
+    /// Push overlapping mobs apart (and away from players) and let fast
+    /// minecarts and boats ram what they run into.
+    #[serde(default = "default_entity_collision")]
+    pub entity_collision: bool,
+}
+
+fn default_command_blocks_enabled() -> bool {
+    true
+}
+
+fn default_max_command_chain_length() -> usize {
+    65535
+}
+
+fn default_hostile_spawn_rule() -> String {
+    "light".into()
+}
+
+fn default_entity_collision() -> bool {
+    true
+}
+
+impl Default for GameplaySection {
+    fn default() -> Self {
+        Self {
+            keep_inventory: false,
+            death_graves: false,
+            command_blocks_enabled: default_command_blocks_enabled(),
+            max_command_chain_length: default_max_command_chain_length(),
+            show_coordinates: false,
+            sneak_edge_protection: false,
+            hostile_spawn_rule: default_hostile_spawn_rule(),
+            entity_collision: default_entity_collision(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct ServerSection {
     pub address: String,
     pub port: u16,
@@ -31,9 +155,39 @@ pub struct ServerSection {
     pub gamemode: String,
     pub difficulty: String,
     pub online_mode: bool,
+    /// Seconds between refreshes of the advertised MOTD and player count.
+    /// 0 = never refresh.
+    #[serde(default = "default_motd_update_interval")]
+    pub motd_update_interval: u64,
+    /// Largest chunk radius a client may ask for.
+    #[serde(default = "default_view_distance")]
+    pub view_distance: i32,
+    /// Chunk radius around players in which mobs move and blocks tick.
+    #[serde(default = "default_simulation_distance")]
+    pub simulation_distance: i32,
+    /// Late ticks run back to back to catch up before the ones still owed
+    /// are dropped.
+    #[serde(default = "default_max_catch_up_ticks")]
+    pub max_catch_up_ticks: u32,
+}
+
+fn default_motd_update_interval() -> u64 {
+    5
 }
 
-#[derive(Debug, Deserialize)]
+fn default_view_distance() -> i32 {
+    8
+}
+
+fn default_simulation_distance() -> i32 {
+    4
+}
+
+fn default_max_catch_up_ticks() -> u32 {
+    10
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct WorldSection {
     pub name: String,
     pub generator: String,
@@ -47,33 +201,90 @@ fn default_auto_save_interval() -> u64 {
     300
 }
 
-#[derive(Debug, Deserialize)]
+/// An additional world, reached with `/world tp <name>`. Its chunks are
+/// stored with the main world's.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtraWorldSection {
+    /// "default", "flat", "nether" or "end".
+    #[serde(default = "default_extra_world_generator")]
+    pub generator: String,
+    /// World seed. Defaults to the main world's.
+    #[serde(default)]
+    pub seed: Option<i64>,
+    /// Spawn point (feet position). Found from the terrain if unset.
+    #[serde(default)]
+    pub spawn: Option<[f32; 3]>,
+}
+
+fn default_extra_world_generator() -> String {
+    "default".into()
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct PacksSection {
     #[serde(default = "default_packs_directory")]
     pub directory: String,
+    /// Directory of resource packs (`.zip`/`.mcpack` or unpacked) sent to clients.
+    #[serde(default = "default_resource_packs_directory")]
+    pub resource_directory: String,
+    /// Make every pack required, except those listed in `optional`.
     #[serde(default)]
     pub force_packs: bool,
+    /// Packs (UUIDs or names) players must accept to join.
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// Packs (UUIDs or names) players may decline, even with `force_packs`.
+    #[serde(default)]
+    pub optional: Vec<String>,
+    /// Disconnect message for players who refuse required packs.
+    #[serde(default = "default_required_prompt")]
+    pub required_prompt: String,
+    /// Chat message for players who joined without the optional packs;
+    /// empty to send none.
+    #[serde(default)]
+    pub optional_prompt: String,
+    /// Maximum pack chunks sent to each client per tick.
+    #[serde(default = "default_chunks_per_tick")]
+    pub chunks_per_tick: usize,
 }
 
 fn default_packs_directory() -> String {
     "packs".into()
 }
 
+fn default_resource_packs_directory() -> String {
+    "resource_packs".into()
+}
+
+fn default_required_prompt() -> String {
+    "You must accept resource packs to join this server.".into()
+}
+
+fn default_chunks_per_tick() -> usize {
+    2
+}
+
 impl Default for PacksSection {
     fn default() -> Self {
         Self {
             directory: default_packs_directory(),
+            resource_directory: default_resource_packs_directory(),
             force_packs: false,
+            required: Vec::new(),
+            optional: Vec::new(),
+            required_prompt: default_required_prompt(),
+            optional_prompt: String::new(),
+            chunks_per_tick: default_chunks_per_tick(),
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct LoggingSection {
     pub level: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct RconSection {
     #[serde(default)]
     pub enabled: bool,
@@ -97,7 +308,7 @@ impl Default for RconSection {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct QuerySection {
     #[serde(default)]
     pub enabled: bool,
@@ -118,86 +329,1471 @@ impl Default for QuerySection {
     }
 }
 
-impl ServerConfig {
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
-        let contents = std::fs::read_to_string(path)?;
-        let config: Self = toml::from_str(&contents)?;
-        Ok(config)
+/// Where player data is stored (`[player_data]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlayerDataSection {
+    /// `"file"` (per-world JSON files) or `"redis"` (shared across servers).
+    #[serde(default = "default_player_data_backend")]
+    pub backend: String,
+    /// Redis `host:port`, used by the `"redis"` backend.
+    #[serde(default = "default_redis_address")]
+    pub redis_address: String,
+    #[serde(default)]
+    pub redis_password: String,
+    /// Prefix prepended to the player's XUID to form the Redis key.
+    #[serde(default = "default_player_key_prefix")]
+    pub key_prefix: String,
+}
+
+fn default_player_data_backend() -> String {
+    "file".into()
+}
+
+fn default_redis_address() -> String {
+    "127.0.0.1:6379".into()
+}
+
+fn default_player_key_prefix() -> String {
+    "mc-rs:player:".into()
+}
+
+impl Default for PlayerDataSection {
+    fn default() -> Self {
+        Self {
+            backend: default_player_data_backend(),
+            redis_address: default_redis_address(),
+            redis_password: String::new(),
+            key_prefix: default_player_key_prefix(),
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Server-to-server transfers (`[transfer]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferSection {
+    /// Secret shared by all servers of the network. Empty disables identity
+    /// forwarding (the Transfer packet is still sent).
+    #[serde(default)]
+    pub secret: String,
+    /// UDP port on which handoff tickets are sent and received.
+    #[serde(default = "default_handoff_port")]
+    pub handoff_port: u16,
+}
 
-    #[test]
-    fn parse_config() {
-        let toml_str = r#"
-            [server]
-            address = "0.0.0.0"
-            port = 19132
-            motd = "Test Server"
-            max_players = 20
-            gamemode = "survival"
-            difficulty = "normal"
-            online_mode = true
+fn default_handoff_port() -> u16 {
+    19140
+}
 
-            [world]
-            name = "world"
-            generator = "flat"
-            seed = 12345
+impl Default for TransferSection {
+    fn default() -> Self {
+        Self {
+            secret: String::new(),
+            handoff_port: default_handoff_port(),
+        }
+    }
+}
 
-            [logging]
-            level = "debug"
-        "#;
-        let config: ServerConfig = toml::from_str(toml_str).unwrap();
-        assert_eq!(config.server.port, 19132);
-        assert_eq!(config.server.motd, "Test Server");
-        assert_eq!(config.server.max_players, 20);
-        assert!(config.server.online_mode);
-        assert_eq!(config.world.name, "world");
-        assert_eq!(config.world.generator, "flat");
-        assert_eq!(config.world.seed, 12345);
-        assert_eq!(config.world.auto_save_interval, 300); // default
-        assert_eq!(config.logging.level, "debug");
-        // permissions section defaults when absent
-        assert!(!config.permissions.whitelist_enabled);
-        // packs section defaults when absent
-        assert_eq!(config.packs.directory, "packs");
-        assert!(!config.packs.force_packs);
-        // rcon section defaults when absent
-        assert!(!config.rcon.enabled);
-        assert_eq!(config.rcon.port, 25575);
-        assert!(config.rcon.password.is_empty());
-        // query section defaults when absent
-        assert!(!config.query.enabled);
-        assert_eq!(config.query.port, 19132);
+/// PROXY protocol support for servers behind UDP load balancers (`[proxy]`).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProxySection {
+    /// Accept HAProxy PROXY v2 headers carrying the real client address.
+    #[serde(default)]
+    pub proxy_protocol: bool,
+    /// IPs of proxies allowed to send PROXY headers. Required when
+    /// `proxy_protocol` is on.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl ProxySection {
+    /// Refuse PROXY headers without a list of proxies allowed to send them:
+    /// anyone could otherwise spoof client addresses.
+    fn validate(&self) -> Result<(), String> {
+        if self.proxy_protocol && self.trusted_proxies.is_empty() {
+            return Err(
+                "proxy.proxy_protocol requires at least one proxy.trusted_proxies entry".into(),
+            );
+        }
+        Ok(())
     }
+}
 
-    #[test]
-    fn parse_config_with_permissions() {
-        let toml_str = r#"
-            [server]
-            address = "0.0.0.0"
-            port = 19132
-            motd = "Test"
-            max_players = 20
-            gamemode = "survival"
-            difficulty = "normal"
-            online_mode = false
+/// Per-IP flood protection for the RakNet listener (`[throttle]`). 0 disables a limit.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThrottleSection {
+    #[serde(default = "default_max_pings_per_second")]
+    pub max_pings_per_second: u32,
+    #[serde(default = "default_max_connection_attempts_per_second")]
+    pub max_connection_attempts_per_second: u32,
+    #[serde(default = "default_max_sessions_per_ip")]
+    pub max_sessions_per_ip: usize,
+    /// Malformed datagrams tolerated per minute before the IP is banned.
+    #[serde(default = "default_max_malformed_per_minute")]
+    pub max_malformed_per_minute: u32,
+    /// Ban duration in seconds for IPs sending malformed packets.
+    #[serde(default = "default_ban_duration")]
+    pub ban_duration: u64,
+}
 
-            [world]
-            name = "world"
-            generator = "flat"
-            seed = 0
+fn default_max_pings_per_second() -> u32 {
+    10
+}
 
-            [logging]
-            level = "info"
+fn default_max_connection_attempts_per_second() -> u32 {
+    5
+}
 
-            [permissions]
-            whitelist_enabled = true
-        "#;
-        let config: ServerConfig = toml::from_str(toml_str).unwrap();
-        assert!(config.permissions.whitelist_enabled);
+fn default_max_sessions_per_ip() -> usize {
+    5
+}
+
+fn default_max_malformed_per_minute() -> u32 {
+    50
+}
+
+fn default_ban_duration() -> u64 {
+    300
+}
+
+impl Default for ThrottleSection {
+    fn default() -> Self {
+        Self {
+            max_pings_per_second: default_max_pings_per_second(),
+            max_connection_attempts_per_second: default_max_connection_attempts_per_second(),
+            max_sessions_per_ip: default_max_sessions_per_ip(),
+            max_malformed_per_minute: default_max_malformed_per_minute(),
+            ban_duration: default_ban_duration(),
+        }
+    }
+}
+
+/// Visibility in the Friends/LAN tab (`[lan]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct LanSection {
+    /// Answer LAN discovery pings on port 19132 when the server uses another port.
+    #[serde(default = "default_lan_discovery")]
+    pub discovery: bool,
+    /// Periodically broadcast the server's presence to the local network.
+    #[serde(default)]
+    pub broadcast: bool,
+}
+
+fn default_lan_discovery() -> bool {
+    true
+}
+
+impl Default for LanSection {
+    fn default() -> Self {
+        Self {
+            discovery: default_lan_discovery(),
+            broadcast: false,
+        }
+    }
+}
+
+/// Datagram capture for protocol debugging (`[capture]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct CaptureSection {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Capture file, replayable with `mc-rs-replay`.
+    #[serde(default = "default_capture_file")]
+    pub file: String,
+}
+
+fn default_capture_file() -> String {
+    "captures/raknet.mcap".into()
+}
+
+impl Default for CaptureSection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: default_capture_file(),
+        }
+    }
+}
+
+/// LevelDB tuning and integrity checks (`[storage]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct StorageSection {
+    /// Chunk storage backend: `leveldb` (`db/`), `region` (`region/`) or
+    /// `memory` (nothing saved, for tests and throwaway worlds).
+    #[serde(default = "default_storage_backend")]
+    pub backend: String,
+    /// LevelDB block read cache size in MiB.
+    #[serde(default = "default_cache_size_mb")]
+    pub cache_size_mb: usize,
+    /// Seconds between compactions of the database. 0 = never compact.
+    #[serde(default = "default_compaction_interval")]
+    pub compaction_interval: u64,
+    /// Checksum chunks and regenerate the ones that fail to verify.
+    #[serde(default = "default_verify_checksums")]
+    pub verify_checksums: bool,
+}
+
+fn default_storage_backend() -> String {
+    "leveldb".into()
+}
+
+fn default_cache_size_mb() -> usize {
+    8
+}
+
+fn default_compaction_interval() -> u64 {
+    600
+}
+
+fn default_verify_checksums() -> bool {
+    true
+}
+
+impl Default for StorageSection {
+    fn default() -> Self {
+        Self {
+            backend: default_storage_backend(),
+            cache_size_mb: default_cache_size_mb(),
+            compaction_interval: default_compaction_interval(),
+            verify_checksums: default_verify_checksums(),
+        }
+    }
+}
+
+/// Anti-cheat checks and their thresholds (`[anticheat]`, with one
+/// `[anticheat.<check>]` table per check).
+#[derive(Debug, Clone, Deserialize)]
+pub struct AntiCheatSection {
+    /// Run the checks at all. When off, nothing is scored or undone.
+    #[serde(default = "default_anticheat_enabled")]
+    pub enabled: bool,
+    /// Seconds between decays, each lowering every violation score by 1.
+    #[serde(default = "default_violation_decay_interval")]
+    pub decay_interval: u64,
+    /// Widest angle in degrees between where a player looks and an entity
+    /// they hit before the hit counts as a kill aura violation.
+    #[serde(default = "default_killaura_max_angle")]
+    pub killaura_max_angle: f32,
+    /// Ticks of past target positions a hit may be checked against, to
+    /// make up for the attacker's latency. At most 40.
+    #[serde(default = "default_lag_compensation_ticks")]
+    pub lag_compensation_ticks: u64,
+    #[serde(default)]
+    pub speed: CheckSettings,
+    #[serde(default)]
+    pub fly: CheckSettings,
+    #[serde(default)]
+    pub noclip: CheckSettings,
+    #[serde(default)]
+    pub reach: CheckSettings,
+    #[serde(default)]
+    pub killaura: CheckSettings,
+    #[serde(default)]
+    pub fastbreak: CheckSettings,
+    #[serde(default)]
+    pub rate_limit: CheckSettings,
+}
+
+fn default_anticheat_enabled() -> bool {
+    true
+}
+
+fn default_violation_decay_interval() -> u64 {
+    10
+}
+
+fn default_killaura_max_angle() -> f32 {
+    90.0
+}
+
+fn default_lag_compensation_ticks() -> u64 {
+    10
+}
+
+impl Default for AntiCheatSection {
+    fn default() -> Self {
+        Self {
+            enabled: default_anticheat_enabled(),
+            decay_interval: default_violation_decay_interval(),
+            killaura_max_angle: default_killaura_max_angle(),
+            lag_compensation_ticks: default_lag_compensation_ticks(),
+            speed: CheckSettings::default(),
+            fly: CheckSettings::default(),
+            noclip: CheckSettings::default(),
+            reach: CheckSettings::default(),
+            killaura: CheckSettings::default(),
+            fastbreak: CheckSettings::default(),
+            rate_limit: CheckSettings::default(),
+        }
+    }
+}
+
+impl AntiCheatSection {
+    /// Effective settings of a check.
+    pub fn check(&self, check: Check) -> CheckConfig {
+        let settings = match check {
+            Check::Speed => &self.speed,
+            Check::Fly => &self.fly,
+            Check::NoClip => &self.noclip,
+            Check::Reach => &self.reach,
+            Check::KillAura => &self.killaura,
+            Check::FastBreak => &self.fastbreak,
+            Check::RateLimit => &self.rate_limit,
+        };
+        settings.resolve(check)
+    }
+}
+
+/// Melee knockback tuning (`[combat]`), adjustable at runtime with `/knockback`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CombatSection {
+    /// Horizontal push of a plain hit.
+    #[serde(default = "default_knockback_horizontal")]
+    pub knockback_horizontal: f32,
+    /// Upward push of a hit.
+    #[serde(default = "default_knockback_vertical")]
+    pub knockback_vertical: f32,
+    /// Horizontal push multiplier for hits while sprinting.
+    #[serde(default = "default_knockback_sprint_multiplier")]
+    pub knockback_sprint_multiplier: f32,
+    /// Extra horizontal push per Knockback enchantment level.
+    #[serde(default = "default_knockback_enchant_bonus")]
+    pub knockback_enchant_bonus: f32,
+    /// Share (0-1) of the victim's current velocity removed before the push
+    /// is added: 1.0 replaces it, 0.5 halves it.
+    #[serde(default = "default_knockback_friction")]
+    pub knockback_friction: f32,
+    /// Ticks after taking damage during which a player can't be hurt
+    /// again by attacks and projectiles.
+    #[serde(default = "default_damage_cooldown")]
+    pub damage_cooldown: u64,
+}
+
+fn default_knockback_horizontal() -> f32 {
+    KnockbackProfile::default().horizontal
+}
+
+fn default_knockback_vertical() -> f32 {
+    KnockbackProfile::default().vertical
+}
+
+fn default_knockback_sprint_multiplier() -> f32 {
+    KnockbackProfile::default().sprint_multiplier
+}
+
+fn default_knockback_enchant_bonus() -> f32 {
+    KnockbackProfile::default().enchant_bonus
+}
+
+fn default_knockback_friction() -> f32 {
+    KnockbackProfile::default().friction
+}
+
+fn default_damage_cooldown() -> u64 {
+    10
+}
+
+impl Default for CombatSection {
+    fn default() -> Self {
+        Self {
+            knockback_horizontal: default_knockback_horizontal(),
+            knockback_vertical: default_knockback_vertical(),
+            knockback_sprint_multiplier: default_knockback_sprint_multiplier(),
+            knockback_enchant_bonus: default_knockback_enchant_bonus(),
+            knockback_friction: default_knockback_friction(),
+            damage_cooldown: default_damage_cooldown(),
+        }
+    }
+}
+
+impl CombatSection {
+    /// The configured knockback profile.
+    pub fn knockback(&self) -> KnockbackProfile {
+        KnockbackProfile {
+            horizontal: self.knockback_horizontal,
+            vertical: self.knockback_vertical,
+            sprint_multiplier: self.knockback_sprint_multiplier,
+            enchant_bonus: self.knockback_enchant_bonus,
+            friction: self.knockback_friction.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Per-dimension flags (`[world_flags.overworld]`, `[world_flags.nether]`,
+/// `[world_flags.end]`), adjustable at runtime with `/worldflag`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WorldFlagsSection {
+    #[serde(default)]
+    pub overworld: WorldFlags,
+    #[serde(default)]
+    pub nether: WorldFlags,
+    #[serde(default)]
+    pub end: WorldFlags,
+}
+
+impl WorldFlagsSection {
+    /// Flags indexed by dimension ID.
+    pub fn by_dimension(&self) -> [WorldFlags; 3] {
+        [self.overworld, self.nether, self.end]
+    }
+}
+
+/// Idle player handling (`[afk]`). Players with the `mcrs.afk.exempt`
+/// permission node are never marked AFK or kicked.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AfkSection {
+    /// Seconds without input before a player is marked AFK. 0 = never.
+    #[serde(default = "default_afk_idle_time")]
+    pub idle_time: u64,
+    /// Seconds without input before a player is kicked. 0 = never.
+    #[serde(default)]
+    pub kick_time: u64,
+    /// Announce in chat when players go AFK and come back.
+    #[serde(default = "default_afk_broadcast")]
+    pub broadcast: bool,
+}
+
+fn default_afk_idle_time() -> u64 {
+    300
+}
+
+fn default_afk_broadcast() -> bool {
+    true
+}
+
+impl Default for AfkSection {
+    fn default() -> Self {
+        Self {
+            idle_time: default_afk_idle_time(),
+            kick_time: 0,
+            broadcast: default_afk_broadcast(),
+        }
+    }
+}
+
+/// Player teleports: `/home`, `/back` and `/tpa` (`[teleport]`). Players
+/// with the `mcrs.teleport.bypass` permission node skip the warm-up and
+/// cooldown.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeleportSection {
+    /// Seconds to stand still before teleporting. Moving or taking damage
+    /// cancels it. 0 = instant.
+    #[serde(default = "default_teleport_warmup")]
+    pub warmup: u64,
+    /// Seconds between two teleports of the same player.
+    #[serde(default = "default_teleport_cooldown")]
+    pub cooldown: u64,
+    /// Seconds before an unanswered `/tpa` request expires.
+    #[serde(default = "default_teleport_request_timeout")]
+    pub request_timeout: u64,
+    /// Homes a player can set with `/sethome`.
+    #[serde(default = "default_teleport_max_homes")]
+    pub max_homes: usize,
+}
+
+fn default_teleport_warmup() -> u64 {
+    3
+}
+
+fn default_teleport_cooldown() -> u64 {
+    10
+}
+
+fn default_teleport_request_timeout() -> u64 {
+    60
+}
+
+fn default_teleport_max_homes() -> usize {
+    3
+}
+
+impl Default for TeleportSection {
+    fn default() -> Self {
+        Self {
+            warmup: default_teleport_warmup(),
+            cooldown: default_teleport_cooldown(),
+            request_timeout: default_teleport_request_timeout(),
+            max_homes: default_teleport_max_homes(),
+        }
+    }
+}
+
+/// Stalled tick detection (`[watchdog]`). When no tick finishes for
+/// `timeout` seconds, a crash report is written to `crash-reports/`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchdogSection {
+    #[serde(default = "default_watchdog_enabled")]
+    pub enabled: bool,
+    /// Seconds without a finished tick before the server counts as stalled.
+    #[serde(default = "default_watchdog_timeout")]
+    pub timeout: u64,
+    /// Abort the process after writing the crash report.
+    #[serde(default = "default_watchdog_abort")]
+    pub abort: bool,
+    /// Before aborting, give the game loop `save_timeout` seconds to come
+    /// back and save the world. A loop stuck for good can't save.
+    #[serde(default = "default_watchdog_force_save")]
+    pub force_save: bool,
+    #[serde(default = "default_watchdog_save_timeout")]
+    pub save_timeout: u64,
+}
+
+fn default_watchdog_enabled() -> bool {
+    true
+}
+
+fn default_watchdog_timeout() -> u64 {
+    60
+}
+
+fn default_watchdog_abort() -> bool {
+    true
+}
+
+fn default_watchdog_force_save() -> bool {
+    true
+}
+
+fn default_watchdog_save_timeout() -> u64 {
+    30
+}
+
+impl Default for WatchdogSection {
+    fn default() -> Self {
+        Self {
+            enabled: default_watchdog_enabled(),
+            timeout: default_watchdog_timeout(),
+            abort: default_watchdog_abort(),
+            force_save: default_watchdog_force_save(),
+            save_timeout: default_watchdog_save_timeout(),
+        }
+    }
+}
+
+/// Lowering view and simulation distances while the server is behind
+/// (`[dynamic_view]`). Every `interval` seconds, both go down a chunk if the
+/// TPS is under `low_tps`, and back up a chunk once it reaches
+/// `recover_tps`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DynamicViewSection {
+    #[serde(default = "default_dynamic_view_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_dynamic_view_interval")]
+    pub interval: u64,
+    #[serde(default = "default_dynamic_view_low_tps")]
+    pub low_tps: f64,
+    #[serde(default = "default_dynamic_view_recover_tps")]
+    pub recover_tps: f64,
+    /// View distance never lowered below.
+    #[serde(default = "default_dynamic_view_min_view_distance")]
+    pub min_view_distance: i32,
+    /// Simulation distance never lowered below.
+    #[serde(default = "default_dynamic_view_min_simulation_distance")]
+    pub min_simulation_distance: i32,
+}
+
+fn default_dynamic_view_enabled() -> bool {
+    true
+}
+
+fn default_dynamic_view_interval() -> u64 {
+    5
+}
+
+fn default_dynamic_view_low_tps() -> f64 {
+    18.0
+}
+
+fn default_dynamic_view_recover_tps() -> f64 {
+    19.5
+}
+
+fn default_dynamic_view_min_view_distance() -> i32 {
+    4
+}
+
+fn default_dynamic_view_min_simulation_distance() -> i32 {
+    2
+}
+
+impl Default for DynamicViewSection {
+    fn default() -> Self {
+        Self {
+            enabled: default_dynamic_view_enabled(),
+            interval: default_dynamic_view_interval(),
+            low_tps: default_dynamic_view_low_tps(),
+            recover_tps: default_dynamic_view_recover_tps(),
+            min_view_distance: default_dynamic_view_min_view_distance(),
+            min_simulation_distance: default_dynamic_view_min_simulation_distance(),
+        }
+    }
+}
+
+/// Country lookup of joining players in a local MaxMind database
+/// (`[geoip]`), and which countries may join. Countries are ISO codes
+/// (`"FR"`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeoIpSection {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Path of the country (or city) MMDB database.
+    #[serde(default = "default_geoip_database")]
+    pub database: String,
+    /// Countries allowed to join; empty = every country not denied.
+    #[serde(default)]
+    pub allowed_countries: Vec<String>,
+    /// Countries refused.
+    #[serde(default)]
+    pub denied_countries: Vec<String>,
+    /// Whether players whose country is unknown (LAN addresses, addresses
+    /// missing from the database) may join.
+    #[serde(default = "default_geoip_allow_unknown")]
+    pub allow_unknown: bool,
+}
+
+fn default_geoip_database() -> String {
+    "GeoLite2-Country.mmdb".into()
+}
+
+fn default_geoip_allow_unknown() -> bool {
+    true
+}
+
+impl Default for GeoIpSection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            database: default_geoip_database(),
+            allowed_countries: Vec::new(),
+            denied_countries: Vec::new(),
+            allow_unknown: default_geoip_allow_unknown(),
+        }
+    }
+}
+
+/// Checks run on joining players after the bans, whitelist and country
+/// lists (`[prelogin]`): a blocklist of IP ranges and an external HTTP
+/// webhook. Each is off while its path or URL is empty.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PreLoginSection {
+    /// File of blocked addresses and networks (`10.0.0.0/8`), one per line.
+    #[serde(default)]
+    pub blocklist: String,
+    /// `http://` URL asked about every login.
+    #[serde(default)]
+    pub webhook_url: String,
+    /// Milliseconds to wait for the webhook.
+    #[serde(default = "default_prelogin_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Whether players get in when the webhook fails or times out.
+    #[serde(default = "default_prelogin_fail_open")]
+    pub fail_open: bool,
+    /// Message refused players are disconnected with, unless the webhook
+    /// gives one.
+    #[serde(default = "default_prelogin_deny_message")]
+    pub deny_message: String,
+}
+
+fn default_prelogin_timeout_ms() -> u64 {
+    3000
+}
+
+fn default_prelogin_fail_open() -> bool {
+    true
+}
+
+fn default_prelogin_deny_message() -> String {
+    "You are not allowed to join this server.".into()
+}
+
+impl Default for PreLoginSection {
+    fn default() -> Self {
+        Self {
+            blocklist: String::new(),
+            webhook_url: String::new(),
+            timeout_ms: default_prelogin_timeout_ms(),
+            fail_open: default_prelogin_fail_open(),
+            deny_message: default_prelogin_deny_message(),
+        }
+    }
+}
+
+/// Chat moderation (`[chat]`): slow mode and the word filter. Mutes are
+/// kept with the bans, in muted-players.json.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatSection {
+    /// Seconds players must wait between chat messages, 0 for no slow mode.
+    #[serde(default)]
+    pub slow_mode: u64,
+    /// Words filtered out of chat, matched whole and ignoring case.
+    #[serde(default)]
+    pub filter_words: Vec<String>,
+    /// `"replace"` to mask filtered words, `"block"` to refuse the message.
+    #[serde(default = "default_chat_filter_mode")]
+    pub filter_mode: String,
+    /// Character filtered words are masked with.
+    #[serde(default = "default_chat_filter_replacement")]
+    pub filter_replacement: char,
+}
+
+fn default_chat_filter_mode() -> String {
+    "replace".into()
+}
+
+fn default_chat_filter_replacement() -> char {
+    '*'
+}
+
+impl Default for ChatSection {
+    fn default() -> Self {
+        Self {
+            slow_mode: 0,
+            filter_words: Vec::new(),
+            filter_mode: default_chat_filter_mode(),
+            filter_replacement: default_chat_filter_replacement(),
+        }
+    }
+}
+
+/// Chat bridge (`[bridge]`), e.g. to a Discord channel: server events
+/// posted to a webhook, and chat relayed into the game from an HTTP
+/// endpoint. Each half is off while its URL or address is empty.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BridgeSection {
+    /// `http://` URL server events are posted to as JSON.
+    #[serde(default)]
+    pub webhook_url: String,
+    /// Events posted: `join`, `leave`, `chat`, `death`, `server_start`.
+    #[serde(default = "default_bridge_events")]
+    pub events: Vec<String>,
+    /// Address of the endpoint taking chat to relay (`127.0.0.1:8095`).
+    #[serde(default)]
+    pub listen: String,
+    /// Bearer token the endpoint requires; it doesn't start without one.
+    #[serde(default)]
+    pub token: String,
+    /// How relayed chat is shown in game, with `{author}` and `{message}`.
+    #[serde(default = "default_bridge_chat_format")]
+    pub chat_format: String,
+}
+
+fn default_bridge_events() -> Vec<String> {
+    ["join", "leave", "chat", "death", "server_start"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_bridge_chat_format() -> String {
+    "[Discord] <{author}> {message}".into()
+}
+
+impl Default for BridgeSection {
+    fn default() -> Self {
+        Self {
+            webhook_url: String::new(),
+            events: default_bridge_events(),
+            listen: String::new(),
+            token: String::new(),
+            chat_format: default_bridge_chat_format(),
+        }
+    }
+}
+
+/// The economy (`[economy]`): `/balance`, `/pay`, `/eco` and the plugin
+/// money API. Balances are whole amounts of a single currency.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EconomySection {
+    /// Name the currency is shown with, e.g. `150 coins`.
+    #[serde(default = "default_economy_currency_name")]
+    pub currency_name: String,
+    /// Balance of the account a player gets when they first join.
+    #[serde(default)]
+    pub starting_balance: u64,
+}
+
+fn default_economy_currency_name() -> String {
+    "coins".into()
+}
+
+impl Default for EconomySection {
+    fn default() -> Self {
+        Self {
+            currency_name: default_economy_currency_name(),
+            starting_balance: 0,
+        }
+    }
+}
+
+impl EconomySection {
+    /// An amount with the currency name, e.g. `150 coins`.
+    pub fn format(&self, amount: u64) -> String {
+        format!("{amount} {}", self.currency_name)
+    }
+}
+
+/// Plugin loading (`[plugins]`). WASM and Lua plugins are always loaded.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PluginsSection {
+    /// Load native plugins (shared libraries). They run unsandboxed in the
+    /// server process, so only enable this for plugins you trust.
+    #[serde(default)]
+    pub native: bool,
+}
+
+impl ServerConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        let config: Self = toml::from_str(&contents)?;
+        config.proxy.validate()?;
+        Ok(config)
+    }
+
+    /// Take the settings that can change while the server runs (MOTD, max
+    /// players, view and simulation distances, whitelist, anti-cheat) from `next`, a freshly
+    /// loaded configuration. Returns the other settings that changed, which
+    /// only apply after a restart.
+    pub fn reload_from(&mut self, next: ServerConfig) -> Vec<&'static str> {
+        let mut restart_only = Vec::new();
+        let mut check = |changed: bool, key: &'static str| {
+            if changed {
+                restart_only.push(key);
+            }
+        };
+        check(next.server.address != self.server.address, "server.address");
+        check(next.server.port != self.server.port, "server.port");
+        check(
+            next.server.online_mode != self.server.online_mode,
+            "server.online_mode",
+        );
+        check(next.world.name != self.world.name, "world.name");
+        check(
+            next.world.generator != self.world.generator,
+            "world.generator",
+        );
+        check(next.world.seed != self.world.seed, "world.seed");
+        check(next.bridge.listen != self.bridge.listen, "bridge.listen");
+        check(next.bridge.token != self.bridge.token, "bridge.token");
+
+        self.server.motd = next.server.motd;
+        self.server.max_players = next.server.max_players;
+        self.server.motd_update_interval = next.server.motd_update_interval;
+        self.server.view_distance = next.server.view_distance;
+        self.server.simulation_distance = next.server.simulation_distance;
+        self.server.max_catch_up_ticks = next.server.max_catch_up_ticks;
+        self.dynamic_view = next.dynamic_view;
+        self.geoip = next.geoip;
+        self.prelogin = next.prelogin;
+        self.chat = next.chat;
+        self.bridge.webhook_url = next.bridge.webhook_url;
+        self.bridge.events = next.bridge.events;
+        self.bridge.chat_format = next.bridge.chat_format;
+        self.economy = next.economy;
+        self.plugins = next.plugins;
+        self.permissions = next.permissions;
+        self.anticheat = next.anticheat;
+        restart_only
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test Server"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = true
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 12345
+
+            [logging]
+            level = "debug"
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.server.port, 19132);
+        assert_eq!(config.server.motd, "Test Server");
+        assert_eq!(config.server.max_players, 20);
+        assert!(config.server.online_mode);
+        assert_eq!(config.server.motd_update_interval, 5); // default
+        assert_eq!(config.server.view_distance, 8); // default
+        assert_eq!(config.server.simulation_distance, 4); // default
+        assert_eq!(config.server.max_catch_up_ticks, 10); // default
+        assert!(config.dynamic_view.enabled);
+        assert!(!config.geoip.enabled);
+        assert!(config.geoip.allow_unknown);
+        assert!(config.prelogin.webhook_url.is_empty());
+        assert!(config.prelogin.fail_open);
+        assert_eq!(config.chat.slow_mode, 0);
+        assert_eq!(config.chat.filter_mode, "replace");
+        assert!(config.bridge.webhook_url.is_empty());
+        assert_eq!(config.bridge.events.len(), 5);
+        assert_eq!(config.economy.currency_name, "coins");
+        assert_eq!(config.economy.starting_balance, 0);
+        assert_eq!(config.economy.format(150), "150 coins");
+        assert!(!config.plugins.native);
+        assert_eq!(config.world.name, "world");
+        assert_eq!(config.world.generator, "flat");
+        assert_eq!(config.world.seed, 12345);
+        assert_eq!(config.world.auto_save_interval, 300); // default
+        assert_eq!(config.logging.level, "debug");
+        // permissions section defaults when absent
+        assert!(!config.permissions.whitelist_enabled);
+        // packs section defaults when absent
+        assert_eq!(config.packs.directory, "packs");
+        assert_eq!(config.packs.resource_directory, "resource_packs");
+        assert!(!config.packs.force_packs);
+        assert_eq!(config.packs.chunks_per_tick, 2);
+        // rcon section defaults when absent
+        assert!(!config.rcon.enabled);
+        assert_eq!(config.rcon.port, 25575);
+        assert!(config.rcon.password.is_empty());
+        // query section defaults when absent
+        assert!(!config.query.enabled);
+        assert_eq!(config.query.port, 19132);
+        // no kits when absent
+        assert!(config.kits.is_empty());
+        assert!(config.worlds.is_empty());
+        // gameplay section defaults when absent
+        assert!(!config.gameplay.keep_inventory);
+        assert!(!config.gameplay.death_graves);
+        assert!(config.gameplay.command_blocks_enabled);
+        assert_eq!(config.gameplay.max_command_chain_length, 65535);
+        assert!(!config.gameplay.sneak_edge_protection);
+        assert!(config.gameplay.entity_collision);
+        // player data defaults to per-world files
+        assert_eq!(config.player_data.backend, "file");
+        assert_eq!(config.player_data.redis_address, "127.0.0.1:6379");
+        assert_eq!(config.player_data.key_prefix, "mc-rs:player:");
+        // identity forwarding disabled by default
+        assert!(config.transfer.secret.is_empty());
+        assert_eq!(config.transfer.handoff_port, 19140);
+        // PROXY protocol off by default
+        assert!(!config.proxy.proxy_protocol);
+        assert!(config.proxy.trusted_proxies.is_empty());
+        // throttle section defaults when absent
+        assert_eq!(config.throttle.max_pings_per_second, 10);
+        assert_eq!(config.throttle.max_sessions_per_ip, 5);
+        assert_eq!(config.throttle.ban_duration, 300);
+        // LAN discovery on, broadcasting off by default
+        assert!(config.lan.discovery);
+        assert!(!config.lan.broadcast);
+        // capture off by default
+        assert!(!config.capture.enabled);
+        assert_eq!(config.capture.file, "captures/raknet.mcap");
+        // storage section defaults when absent
+        assert_eq!(config.storage.backend, "leveldb");
+        assert_eq!(config.storage.cache_size_mb, 8);
+        assert_eq!(config.storage.compaction_interval, 600);
+        assert!(config.storage.verify_checksums);
+        // anti-cheat on with per-check default thresholds
+        assert!(config.anticheat.enabled);
+        assert_eq!(config.anticheat.decay_interval, 10);
+        assert_eq!(config.anticheat.lag_compensation_ticks, 10);
+        assert_eq!(config.anticheat.check(Check::Speed).kick_score, 10);
+        assert_eq!(config.anticheat.check(Check::Fly).kick_score, 5);
+        // vanilla-like knockback by default
+        assert_eq!(config.combat.knockback(), KnockbackProfile::default());
+        assert_eq!(config.combat.damage_cooldown, 10);
+        assert_eq!(config.afk.idle_time, 300);
+        assert_eq!(config.afk.kick_time, 0);
+        assert!(config.afk.broadcast);
+        assert_eq!(config.teleport.warmup, 3);
+        assert_eq!(config.teleport.cooldown, 10);
+        assert_eq!(config.teleport.request_timeout, 60);
+        assert_eq!(config.teleport.max_homes, 3);
+        assert!(config.watchdog.enabled);
+        assert_eq!(config.watchdog.timeout, 60);
+        assert!(config.watchdog.abort && config.watchdog.force_save);
+        assert_eq!(
+            config.world_flags.by_dimension(),
+            [WorldFlags::default(); 3]
+        );
+    }
+
+    #[test]
+    fn parse_config_with_combat() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [combat]
+            knockback_horizontal = 0.35
+            knockback_vertical = 0.36
+            knockback_friction = 2.0
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        let kb = config.combat.knockback();
+        assert!((kb.horizontal - 0.35).abs() < f32::EPSILON);
+        assert!((kb.vertical - 0.36).abs() < f32::EPSILON);
+        assert!((kb.sprint_multiplier - 1.5).abs() < f32::EPSILON);
+        // Friction is clamped to 0-1
+        assert!((kb.friction - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn parse_config_with_world_flags() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [combat]
+            damage_cooldown = 4
+
+            [world_flags.overworld]
+            pvp = false
+
+            [world_flags.end]
+            fall_damage = false
+            hunger = false
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.combat.damage_cooldown, 4);
+        let [overworld, nether, end] = config.world_flags.by_dimension();
+        assert!(!overworld.pvp);
+        assert!(overworld.fall_damage);
+        assert_eq!(nether, WorldFlags::default());
+        assert!(end.pvp && end.drowning);
+        assert!(!end.fall_damage && !end.hunger);
+    }
+
+    #[test]
+    fn parse_config_with_afk() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [afk]
+            idle_time = 120
+            kick_time = 600
+            broadcast = false
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.afk.idle_time, 120);
+        assert_eq!(config.afk.kick_time, 600);
+        assert!(!config.afk.broadcast);
+    }
+
+    #[test]
+    fn parse_config_with_teleport() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [teleport]
+            warmup = 0
+            max_homes = 10
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.teleport.warmup, 0);
+        assert_eq!(config.teleport.max_homes, 10);
+        // unset keys keep their defaults
+        assert_eq!(config.teleport.cooldown, 10);
+        assert_eq!(config.teleport.request_timeout, 60);
+    }
+
+    #[test]
+    fn parse_config_with_watchdog() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [watchdog]
+            timeout = 20
+            abort = false
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert!(config.watchdog.enabled);
+        assert_eq!(config.watchdog.timeout, 20);
+        assert!(!config.watchdog.abort);
+        assert_eq!(config.watchdog.save_timeout, 30);
+    }
+
+    #[test]
+    fn reload_takes_runtime_settings_only() {
+        let base = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Before"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+        "#;
+        let mut config: ServerConfig = toml::from_str(base).unwrap();
+        let edited = base
+            .replace(
+                "\"Before\"",
+                "\"After\"\n            view_distance = 4\n            simulation_distance = 3",
+            )
+            .replace("max_players = 20", "max_players = 50")
+            .replace("port = 19132", "port = 19200")
+            .replace("seed = 0", "seed = 7")
+            + "\n[permissions]\nwhitelist_enabled = true\n[anticheat.speed]\nkick_score = 3\n";
+        let next: ServerConfig = toml::from_str(&edited).unwrap();
+
+        let restart_only = config.reload_from(next);
+        assert_eq!(restart_only, ["server.port", "world.seed"]);
+        assert_eq!(config.server.motd, "After");
+        assert_eq!(config.server.max_players, 50);
+        assert_eq!(config.server.view_distance, 4);
+        assert_eq!(config.server.simulation_distance, 3);
+        assert!(config.permissions.whitelist_enabled);
+        assert_eq!(config.anticheat.check(Check::Speed).kick_score, 3);
+        // Still the running values
+        assert_eq!(config.server.port, 19132);
+        assert_eq!(config.world.seed, 0);
+    }
+
+    #[test]
+    fn parse_config_with_anticheat() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [anticheat]
+            decay_interval = 5
+            killaura_max_angle = 60.0
+            lag_compensation_ticks = 4
+
+            [anticheat.speed]
+            kick_score = 20
+
+            [anticheat.fly]
+            enabled = false
+
+            [anticheat.reach]
+            warn_score = 0
+            kick_score = 0
+            rubber_band = false
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        let ac = &config.anticheat;
+        assert_eq!(ac.decay_interval, 5);
+        assert!((ac.killaura_max_angle - 60.0).abs() < f32::EPSILON);
+        assert_eq!(ac.lag_compensation_ticks, 4);
+        let speed = ac.check(Check::Speed);
+        assert_eq!(speed.kick_score, 20);
+        assert_eq!(speed.warn_score, 10);
+        assert!(!ac.check(Check::Fly).enabled);
+        let reach = ac.check(Check::Reach);
+        assert_eq!((reach.warn_score, reach.kick_score), (0, 0));
+        assert!(!reach.rubber_band);
+        assert!(ac.check(Check::KillAura).enabled);
+    }
+
+    #[test]
+    fn parse_config_with_storage() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [storage]
+            backend = "region"
+            cache_size_mb = 64
+            compaction_interval = 0
+            verify_checksums = false
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.storage.backend, "region");
+        assert_eq!(config.storage.cache_size_mb, 64);
+        assert_eq!(config.storage.compaction_interval, 0);
+        assert!(!config.storage.verify_checksums);
+    }
+
+    #[test]
+    fn parse_config_with_permissions() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [permissions]
+            whitelist_enabled = true
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert!(config.permissions.whitelist_enabled);
+    }
+
+    #[test]
+    fn parse_config_with_gameplay() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [gameplay]
+            keep_inventory = true
+            death_graves = true
+            command_blocks_enabled = false
+            max_command_chain_length = 100
+            sneak_edge_protection = true
+            entity_collision = false
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert!(config.gameplay.keep_inventory);
+        assert!(config.gameplay.death_graves);
+        assert!(!config.gameplay.command_blocks_enabled);
+        assert_eq!(config.gameplay.max_command_chain_length, 100);
+        assert!(config.gameplay.sneak_edge_protection);
+        assert!(!config.gameplay.entity_collision);
+    }
+
+    #[test]
+    fn proxy_protocol_requires_valid_trusted_proxies() {
+        let base = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+        "#;
+        let parse =
+            |proxy: &str| toml::from_str::<ServerConfig>(&format!("{base}\n[proxy]\n{proxy}"));
+
+        let config = parse("proxy_protocol = true\ntrusted_proxies = [\"10.0.0.1\"]").unwrap();
+        assert!(config.proxy.validate().is_ok());
+        assert_eq!(
+            config.proxy.trusted_proxies,
+            vec!["10.0.0.1".parse::<IpAddr>().unwrap()]
+        );
+        // Trusting nobody is refused, not read as trusting everybody
+        let config = parse("proxy_protocol = true").unwrap();
+        assert!(config.proxy.validate().is_err());
+        // A typo is an error, not a silently dropped entry
+        assert!(parse("proxy_protocol = true\ntrusted_proxies = [\"10.0.0.300\"]").is_err());
+    }
+
+    #[test]
+    fn parse_config_with_worlds() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "default"
+            seed = 42
+
+            [logging]
+            level = "info"
+
+            [worlds.lobby]
+            generator = "flat"
+            spawn = [0.5, 5.0, 0.5]
+
+            [worlds.mining]
+            seed = 7
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.worlds.len(), 2);
+        let lobby = &config.worlds["lobby"];
+        assert_eq!(lobby.generator, "flat");
+        assert_eq!(lobby.seed, None);
+        assert_eq!(lobby.spawn, Some([0.5, 5.0, 0.5]));
+        let mining = &config.worlds["mining"];
+        assert_eq!(mining.generator, "default");
+        assert_eq!(mining.seed, Some(7));
+        assert_eq!(mining.spawn, None);
+    }
+
+    #[test]
+    fn parse_config_with_kits() {
+        let toml_str = r#"
+            [server]
+            address = "0.0.0.0"
+            port = 19132
+            motd = "Test"
+            max_players = 20
+            gamemode = "survival"
+            difficulty = "normal"
+            online_mode = false
+
+            [world]
+            name = "world"
+            generator = "flat"
+            seed = 0
+
+            [logging]
+            level = "info"
+
+            [kits.starter]
+            first_join = true
+            items = [{ item = "stone_sword" }, { item = "bread", count = 8 }]
+
+            [kits.vip]
+            cooldown = 86400
+            op_only = true
+            items = [{ item = "diamond", count = 2 }]
+        "#;
+        let config: ServerConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.kits.len(), 2);
+        let starter = &config.kits["starter"];
+        assert!(starter.first_join);
+        assert_eq!(starter.cooldown, 0);
+        assert_eq!(starter.items.len(), 2);
+        assert_eq!(starter.items[0].count, 1);
+        assert_eq!(starter.items[1].count, 8);
+        let vip = &config.kits["vip"];
+        assert_eq!(vip.cooldown, 86400);
+        assert!(vip.op_only);
     }
 }
@@ -0,0 +1,86 @@
+//! Pushing entity metadata changes to clients: flags like on fire,
+//! sneaking and baby are worked out every tick and whatever changed since
+//! the last SetActorData goes out.
+
+use mc_rs_proto::entity_metadata::{EntityFlag, EntityMetadataEntry, MetadataBuilder};
+use mc_rs_proto::packets::add_player::default_player_metadata;
+use mc_rs_proto::packets::mob_effect::effect_id;
+use mc_rs_proto::packets::{self, SetActorData};
+use mc_rs_world::physics::{Pose, PLAYER_WIDTH};
+
+use super::{mob_metadata, ConnectionHandler, LoginState, PlayerConnection};
+
+impl ConnectionHandler {
+    /// A player's metadata as it stands, appearance included.
+    pub(super) fn player_metadata(
+        &self,
+        conn: &PlayerConnection,
+        name: &str,
+    ) -> Vec<EntityMetadataEntry> {
+        let effects = self.game_world.player_effects(conn.entity_unique_id);
+        let pose = conn.pose();
+        let metadata = MetadataBuilder::from_entries(default_player_metadata(name))
+            .flag(EntityFlag::OnFire, conn.fire_ticks > 0)
+            .flag(EntityFlag::Sneaking, conn.is_sneaking)
+            .flag(EntityFlag::Sprinting, conn.is_sprinting)
+            .flag(
+                EntityFlag::Invisible,
+                mc_rs_game::effects::has(effects, effect_id::INVISIBILITY),
+            )
+            .flag(EntityFlag::Gliding, pose == Pose::Gliding)
+            // Crawling shows as the swimming pose, which is the same shape
+            .flag(
+                EntityFlag::Swimming,
+                matches!(pose, Pose::Swimming | Pose::Crawling),
+            )
+            .bounding_box(PLAYER_WIDTH, pose.height())
+            .build();
+        self.with_appearance(conn.entity_runtime_id, metadata)
+    }
+
+    /// The metadata of every player and mob as it stands.
+    fn actor_metadata(&mut self) -> Vec<(u64, Vec<EntityMetadataEntry>)> {
+        let mut actors: Vec<_> = self
+            .connections
+            .values()
+            .filter(|c| c.state == LoginState::InGame)
+            .filter_map(|conn| {
+                let name = &conn.login_data.as_ref()?.display_name;
+                Some((conn.entity_runtime_id, self.player_metadata(conn, name)))
+            })
+            .collect();
+        for mob in self.game_world.all_mobs() {
+            let metadata = MetadataBuilder::from_entries(mob_metadata(
+                mob.bb_width,
+                mob.bb_height,
+                mob.is_baby,
+            ))
+            .flag(EntityFlag::OnFire, mob.on_fire)
+            .build();
+            actors.push((
+                mob.runtime_id,
+                self.with_appearance(mob.runtime_id, metadata),
+            ));
+        }
+        actors
+    }
+
+    /// Send everyone the metadata of players and mobs that changed since
+    /// it was last sent.
+    pub(super) async fn tick_actor_data(&mut self) {
+        let tick = self.game_world.current_tick();
+        for (runtime_id, metadata) in self.actor_metadata() {
+            let changes = self.actor_data.update(runtime_id, metadata);
+            if changes.is_empty() {
+                continue;
+            }
+            let pkt = SetActorData {
+                entity_runtime_id: runtime_id,
+                metadata: changes,
+                tick,
+            };
+            self.broadcast_packet(packets::id::SET_ACTOR_DATA, &pkt)
+                .await;
+        }
+    }
+}
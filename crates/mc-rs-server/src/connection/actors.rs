@@ -0,0 +1,308 @@
+//! Actor persistence: mobs, item entities, XP orbs and projectiles are saved
+//! with the chunk they occupy and respawned when that chunk is loaded.
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+
+use mc_rs_game::actor::{SavedActor, SavedActorData};
+use mc_rs_proto::packets::{self, AddItemEntity};
+use mc_rs_proto::types::Vec3;
+use mc_rs_world::storage::{
+    actor_digest_key_dim, actor_key, deserialize_actor_digest, serialize_actor_digest,
+};
+use tracing::warn;
+
+use super::drops::{xp_orb_actor, DroppedItem, ExperienceOrb};
+use super::projectile::{projectile_actor, ActiveProjectile, ProjectileKind};
+use super::ConnectionHandler;
+
+/// Actors to save, keyed by `(cx, cz, dim)`, with their storage IDs.
+type ActorsByChunk = HashMap<(i32, i32, i32), Vec<(i64, SavedActor)>>;
+
+impl ConnectionHandler {
+    /// ID an actor is stored under. The world start count in the high half
+    /// keeps IDs from clashing with those saved by earlier sessions.
+    fn actor_storage_id(&self, unique_id: i64) -> i64 {
+        (self.level_dat.world_start_count << 32) | (unique_id & 0xFFFF_FFFF)
+    }
+
+    /// Snapshot every actor standing in a loaded chunk. Mobs and projectiles
+    /// only exist in the overworld.
+    fn collect_saved_actors(&mut self) -> ActorsByChunk {
+        let mut actors: Vec<(i64, i32, SavedActor)> = Vec::new();
+        for mob in self.game_world.all_mobs() {
+            actors.push((
+                mob.unique_id,
+                0,
+                SavedActor {
+                    position: mob.position,
+                    rotation: (mob.yaw, mob.pitch),
+                    motion: (0.0, 0.0, 0.0),
+                    data: SavedActorData::Mob {
+                        identifier: mob.mob_type,
+                        health: mob.health,
+                        is_baby: mob.is_baby,
+                    },
+                },
+            ));
+        }
+        for item in &self.dropped_items {
+            actors.push((
+                item.unique_id,
+                item.dimension,
+                SavedActor {
+                    position: item.position,
+                    rotation: (0.0, 0.0),
+                    motion: (0.0, 0.0, 0.0),
+                    data: SavedActorData::Item {
+                        item: item.item.clone(),
+                        age: item.ticks_alive,
+                    },
+                },
+            ));
+        }
+        for orb in &self.xp_orbs {
+            actors.push((
+                orb.unique_id,
+                orb.dimension,
+                SavedActor {
+                    position: orb.position,
+                    rotation: (0.0, 0.0),
+                    motion: (0.0, 0.0, 0.0),
+                    data: SavedActorData::XpOrb {
+                        value: orb.value,
+                        age: orb.ticks_alive,
+                    },
+                },
+            ));
+        }
+        // Returning tridents belong to their thrower
+        for proj in self.active_projectiles.iter().filter(|p| !p.returning) {
+            actors.push((
+                proj.unique_id,
+                0,
+                SavedActor {
+                    position: proj.position,
+                    rotation: (proj.yaw, proj.pitch),
+                    motion: proj.velocity,
+                    data: SavedActorData::Projectile {
+                        loyalty: match proj.kind {
+                            ProjectileKind::Arrow => None,
+                            ProjectileKind::Trident { loyalty } => Some(loyalty),
+                        },
+                        is_stuck: proj.is_stuck,
+                        critical: proj.critical,
+                        damage: proj.damage,
+                        punch_level: proj.punch_level,
+                        flame: proj.flame,
+                        age: proj.ticks_alive,
+                    },
+                },
+            ));
+        }
+
+        let mut by_chunk = ActorsByChunk::new();
+        for (unique_id, dim, actor) in actors {
+            let (cx, cz) = actor.chunk();
+            if !self
+                .dim_chunks(dim)
+                .is_some_and(|m| m.contains_key(&(cx, cz)))
+            {
+                continue;
+            }
+            by_chunk
+                .entry((cx, cz, dim))
+                .or_default()
+                .push((self.actor_storage_id(unique_id), actor));
+        }
+        by_chunk
+    }
+
+    /// Write the actors of every loaded chunk with their digests, and drop
+    /// the records of actors that are gone. Returns the number saved.
+    pub(super) fn save_actors(&mut self) -> usize {
+        let by_chunk = self.collect_saved_actors();
+        let live: HashSet<i64> = by_chunk
+            .values()
+            .flat_map(|actors| actors.iter().map(|(id, _)| *id))
+            .collect();
+
+        // Chunks whose digest was written or read before but that are empty now
+        let emptied: Vec<(i32, i32, i32)> = self
+            .actor_digests
+            .keys()
+            .filter(|key| !by_chunk.contains_key(key))
+            .copied()
+            .collect();
+        let stale: Vec<i64> = self
+            .actor_digests
+            .values()
+            .flatten()
+            .filter(|id| !live.contains(id))
+            .copied()
+            .collect();
+
+        let mut count = 0;
+        for (&(cx, cz, dim), actors) in &by_chunk {
+            for (id, actor) in actors {
+                if let Err(e) = self
+                    .chunk_storage
+                    .put_raw(&actor_key(*id), &actor.to_le_nbt(*id))
+                {
+                    warn!("Failed to save actor {id} in chunk ({cx},{cz}): {e}");
+                }
+            }
+            let ids: Vec<i64> = actors.iter().map(|(id, _)| *id).collect();
+            if let Err(e) = self.chunk_storage.put_raw(
+                &actor_digest_key_dim(cx, cz, dim),
+                &serialize_actor_digest(&ids),
+            ) {
+                warn!("Failed to save actor digest for chunk ({cx},{cz}): {e}");
+            }
+            count += ids.len();
+            self.actor_digests.insert((cx, cz, dim), ids);
+        }
+        for (cx, cz, dim) in emptied {
+            if let Err(e) = self
+                .chunk_storage
+                .delete_raw(&actor_digest_key_dim(cx, cz, dim))
+            {
+                warn!("Failed to delete actor digest for chunk ({cx},{cz}): {e}");
+            }
+            self.actor_digests.remove(&(cx, cz, dim));
+        }
+        for id in stale {
+            if let Err(e) = self.chunk_storage.delete_raw(&actor_key(id)) {
+                warn!("Failed to delete actor {id}: {e}");
+            }
+        }
+        count
+    }
+
+    /// Respawn the actors saved in a chunk just loaded from storage. Their
+    /// records are replaced on the next save.
+    pub(super) async fn load_chunk_actors(&mut self, cx: i32, cz: i32, dim: i32) {
+        if self.actor_digests.contains_key(&(cx, cz, dim)) {
+            return;
+        }
+        let Some(digest) = self
+            .chunk_storage
+            .get_raw(&actor_digest_key_dim(cx, cz, dim))
+        else {
+            return;
+        };
+        let ids = deserialize_actor_digest(&digest);
+        for &id in &ids {
+            let Some(data) = self.chunk_storage.get_raw(&actor_key(id)) else {
+                continue;
+            };
+            match SavedActor::from_le_nbt(&data) {
+                Some(actor) => self.respawn_actor(actor, dim).await,
+                None => warn!("Skipping unreadable actor {id} in chunk ({cx},{cz})"),
+            }
+        }
+        self.actor_digests.insert((cx, cz, dim), ids);
+    }
+
+    async fn respawn_actor(&mut self, actor: SavedActor, dim: i32) {
+        let position = actor.position;
+        match actor.data {
+            SavedActorData::Mob {
+                identifier,
+                health,
+                is_baby,
+            } => {
+                if dim != 0 {
+                    return;
+                }
+                if self
+                    .game_world
+                    .restore_mob(&identifier, position, actor.rotation, health, is_baby)
+                    .is_none()
+                {
+                    warn!("Skipping saved mob of unknown type {identifier}");
+                }
+            }
+            SavedActorData::Item { item, age } => {
+                let entity_id = self.allocate_entity_id();
+                let runtime_id = entity_id as u64;
+                let pkt = AddItemEntity {
+                    entity_unique_id: entity_id,
+                    entity_runtime_id: runtime_id,
+                    item: item.clone(),
+                    position: Vec3::new(position.0, position.1, position.2),
+                    velocity: Vec3::ZERO,
+                    is_from_fishing: false,
+                };
+                self.broadcast_packet(packets::id::ADD_ITEM_ENTITY, &pkt)
+                    .await;
+                self.dropped_items.push(DroppedItem {
+                    unique_id: entity_id,
+                    runtime_id,
+                    item,
+                    position,
+                    dimension: dim,
+                    ticks_alive: age,
+                });
+            }
+            SavedActorData::XpOrb { value, age } => {
+                let entity_id = self.allocate_entity_id();
+                let runtime_id = entity_id as u64;
+                self.broadcast_packet(
+                    packets::id::ADD_ACTOR,
+                    &xp_orb_actor(entity_id, runtime_id, position),
+                )
+                .await;
+                self.xp_orbs.push(ExperienceOrb {
+                    unique_id: entity_id,
+                    runtime_id,
+                    value,
+                    position,
+                    dimension: dim,
+                    ticks_alive: age,
+                });
+            }
+            SavedActorData::Projectile {
+                loyalty,
+                is_stuck,
+                critical,
+                damage,
+                punch_level,
+                flame,
+                age,
+            } => {
+                if dim != 0 {
+                    return;
+                }
+                let entity_id = self.allocate_entity_id();
+                let proj = ActiveProjectile {
+                    unique_id: entity_id,
+                    runtime_id: entity_id as u64,
+                    position,
+                    velocity: actor.motion,
+                    pitch: actor.rotation.1,
+                    yaw: actor.rotation.0,
+                    // The shooter isn't saved: nobody picks it up or gets credit
+                    shooter_runtime_id: 0,
+                    shooter_addr: SocketAddr::from(([0, 0, 0, 0], 0)),
+                    kind: match loyalty {
+                        Some(loyalty) => ProjectileKind::Trident { loyalty },
+                        None => ProjectileKind::Arrow,
+                    },
+                    ticks_alive: age,
+                    stuck_ticks: 0,
+                    is_stuck,
+                    critical,
+                    damage,
+                    punch_level,
+                    flame,
+                    infinity: false,
+                    returning: false,
+                };
+                self.broadcast_packet(packets::id::ADD_ACTOR, &projectile_actor(&proj))
+                    .await;
+                self.active_projectiles.push(proj);
+            }
+        }
+    }
+}
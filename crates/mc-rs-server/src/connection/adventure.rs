@@ -0,0 +1,49 @@
+//! Adventure mode: players only break blocks listed in their held item's
+//! CanDestroy, and only place blocks against ones listed in its CanPlaceOn.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::adventure;
+
+use super::ConnectionHandler;
+
+/// Gamemode ID of adventure mode.
+const ADVENTURE: i32 = 2;
+
+impl ConnectionHandler {
+    /// Namespaced name of a block state, e.g. `"minecraft:stone"`.
+    fn block_name(&self, runtime_id: u32) -> Option<&str> {
+        self.block_states
+            .get(runtime_id)
+            .map(|info| info.name.as_str())
+            .or_else(|| {
+                self.block_registry
+                    .mining_info(runtime_id)
+                    .map(|(name, _, _)| name)
+            })
+    }
+
+    /// Whether a player may break a block with their held item. Only
+    /// adventure mode restricts it.
+    pub(super) fn may_break_block(&self, addr: SocketAddr, runtime_id: u32) -> bool {
+        let Some(conn) = self.connections.get(&addr) else {
+            return false;
+        };
+        conn.gamemode != ADVENTURE
+            || self
+                .block_name(runtime_id)
+                .is_some_and(|name| adventure::can_destroy(conn.inventory.held_item(), name))
+    }
+
+    /// Whether a player may place their held item against a block. Only
+    /// adventure mode restricts it.
+    pub(super) fn may_place_against(&self, addr: SocketAddr, runtime_id: u32) -> bool {
+        let Some(conn) = self.connections.get(&addr) else {
+            return false;
+        };
+        conn.gamemode != ADVENTURE
+            || self
+                .block_name(runtime_id)
+                .is_some_and(|name| adventure::can_place_on(conn.inventory.held_item(), name))
+    }
+}
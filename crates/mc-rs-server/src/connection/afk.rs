@@ -0,0 +1,90 @@
+//! AFK detection: players without input for too long are marked AFK, and
+//! kicked once idle past the configured kick time.
+
+use std::net::SocketAddr;
+
+use mc_rs_plugin_api::PluginEvent;
+use mc_rs_proto::packets::{self, Text};
+use tracing::info;
+
+use super::{ConnectionHandler, LoginState};
+use crate::permissions::AFK_EXEMPT;
+
+impl ConnectionHandler {
+    /// Note meaningful input from a player (moving, looking around,
+    /// chatting, interacting), bringing them back if they were AFK.
+    pub(super) async fn mark_active(&mut self, addr: SocketAddr) {
+        let now = self.game_world.current_tick();
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        conn.last_active_tick = now;
+        if conn.afk {
+            self.set_afk(addr, false).await;
+        }
+    }
+
+    /// Once a second, mark idle players AFK and kick the ones idle for
+    /// longer than `kick_time`.
+    pub(super) async fn tick_afk(&mut self) {
+        let now = self.game_world.current_tick();
+        if !now.is_multiple_of(20) {
+            return;
+        }
+        let idle_ticks = self.server_config.afk.idle_time * 20;
+        let kick_ticks = self.server_config.afk.kick_time * 20;
+        if idle_ticks == 0 && kick_ticks == 0 {
+            return;
+        }
+
+        let idle: Vec<(SocketAddr, u64, bool)> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame)
+            .filter(|(_, c)| {
+                c.login_data
+                    .as_ref()
+                    .is_none_or(|d| !self.permissions.has_node(&d.display_name, AFK_EXEMPT))
+            })
+            .map(|(addr, c)| (*addr, now.saturating_sub(c.last_active_tick), c.afk))
+            .collect();
+
+        for (addr, idle_for, afk) in idle {
+            if kick_ticks > 0 && idle_for >= kick_ticks {
+                info!("Kicking {addr} for being idle");
+                self.disconnect_player(addr, "You were kicked for being idle")
+                    .await;
+            } else if !afk && idle_ticks > 0 && idle_for >= idle_ticks {
+                self.set_afk(addr, true).await;
+            }
+        }
+    }
+
+    /// Mark a player AFK or back, announcing it and notifying plugins.
+    async fn set_afk(&mut self, addr: SocketAddr, afk: bool) {
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        conn.afk = afk;
+        let Some(conn) = self.connections.get(&addr) else {
+            return;
+        };
+        let player = self.make_plugin_player(conn);
+
+        if self.server_config.afk.broadcast {
+            let msg = if afk {
+                format!("§7{} is now AFK", player.name)
+            } else {
+                format!("§7{} is no longer AFK", player.name)
+            };
+            self.broadcast_packet(packets::id::TEXT, &Text::raw(msg))
+                .await;
+        }
+
+        // Plugin event: PlayerAfkChange
+        let event = PluginEvent::PlayerAfkChange { player, afk };
+        let snapshot = self.build_snapshot();
+        let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+        self.apply_plugin_actions(actions).await;
+    }
+}
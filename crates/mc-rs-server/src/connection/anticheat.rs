@@ -0,0 +1,158 @@
+//! Anti-cheat responses: scoring the violations the movement, combat and
+//! mining checks report, and warning, rubber-banding or kicking players.
+//! Also keeps the entity position history hits are validated against.
+
+use std::net::SocketAddr;
+
+use mc_rs_plugin_api::{EventResult, PluginEvent};
+use mc_rs_proto::packets::{self, Text};
+use mc_rs_world::physics::{ATTACK_REACH, PLAYER_EYE_HEIGHT, PLAYER_WIDTH};
+use tracing::{debug, warn};
+
+use super::ConnectionHandler;
+use crate::anticheat::{validate_hit, Check, HitVerdict, Hitbox, POSITION_HISTORY_TICKS};
+
+impl ConnectionHandler {
+    /// Report a failed check for a player. Returns whether the offending
+    /// action should be undone: the player moved back, or the hit, break or
+    /// packet ignored. Disabled checks and violations cancelled by a plugin
+    /// are let through.
+    pub(super) async fn flag_violation(
+        &mut self,
+        addr: SocketAddr,
+        check: Check,
+        detail: &str,
+    ) -> bool {
+        let config = self.server_config.anticheat.check(check);
+        if !self.server_config.anticheat.enabled || !config.enabled {
+            return false;
+        }
+        let Some(conn) = self.connections.get(&addr) else {
+            return false;
+        };
+        debug!("Anti-cheat {check} violation by {addr}: {detail}");
+
+        // Plugin event: PlayerViolation (cancellable)
+        let event = PluginEvent::PlayerViolation {
+            player: self.make_plugin_player(conn),
+            check: check.name().to_string(),
+            score: conn.violations.get(check) + 1,
+            detail: detail.to_string(),
+        };
+        let snapshot = self.build_snapshot();
+        let (result, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+        self.apply_plugin_actions(actions).await;
+        if result == EventResult::Cancelled {
+            return false;
+        }
+
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return false;
+        };
+        let score = conn.violations.add(check);
+        let name = conn
+            .login_data
+            .as_ref()
+            .map(|d| d.display_name.clone())
+            .unwrap_or_else(|| addr.to_string());
+        let response = config.respond(score);
+
+        if response.kick {
+            warn!("Kicking {name} for anti-cheat {check} violations (score {score}): {detail}");
+            self.disconnect_player(addr, check.kick_message()).await;
+            return true;
+        }
+        if response.warn {
+            warn!("{name} reached anti-cheat {check} score {score}: {detail}");
+            let msg = Text::raw(format!(
+                "§e[Anti-cheat] Suspicious movement or actions detected ({check})"
+            ));
+            self.send_packet(addr, packets::id::TEXT, &msg).await;
+        }
+        response.rubber_band
+    }
+
+    /// Validate a hit on an entity: it must be in reach and in view of the
+    /// attacker, either now or within the lag compensation window. Returns
+    /// false if the hit should be ignored.
+    pub(super) async fn attack_allowed(&mut self, addr: SocketAddr, target: u64) -> bool {
+        let Some((eye, yaw)) = self
+            .connections
+            .get(&addr)
+            .map(|c| ((c.position.x, c.position.y, c.position.z), c.yaw))
+        else {
+            return false;
+        };
+        let Some(current) = self.entity_hitbox(target) else {
+            return false;
+        };
+        let window = self.server_config.anticheat.lag_compensation_ticks;
+        let max_angle = self.server_config.anticheat.killaura_max_angle;
+        let now = self.game_world.current_tick();
+
+        let mut hitboxes = vec![current];
+        if let Some(history) = self.position_history.get(&target) {
+            hitboxes.extend(
+                history
+                    .positions_within(now, window)
+                    .into_iter()
+                    .map(|feet| Hitbox { feet, ..current }),
+            );
+        }
+        match validate_hit(eye, yaw, &hitboxes, ATTACK_REACH, max_angle) {
+            HitVerdict::Valid { compensated } => {
+                if compensated {
+                    debug!("Lag compensated hit on entity {target} by {addr}");
+                }
+                true
+            }
+            HitVerdict::OutOfReach(distance) => {
+                let detail = format!("hit an entity {distance:.2} blocks away");
+                !self.flag_violation(addr, Check::Reach, &detail).await
+            }
+            HitVerdict::OutOfView(angle) => {
+                let detail = format!("hit an entity {angle:.0}° away from where they look");
+                !self.flag_violation(addr, Check::KillAura, &detail).await
+            }
+        }
+    }
+
+    /// Current hitbox of a player or mob.
+    fn entity_hitbox(&mut self, runtime_id: u64) -> Option<Hitbox> {
+        if let Some(addr) = self.runtime_id_to_addr.get(&runtime_id) {
+            let c = self.connections.get(addr)?;
+            return Some(Hitbox {
+                feet: (c.position.x, c.position.y - PLAYER_EYE_HEIGHT, c.position.z),
+                width: PLAYER_WIDTH,
+                height: c.pose().height(),
+            });
+        }
+        let feet = self.game_world.mob_position(runtime_id)?;
+        let (width, height) = self.game_world.mob_bounding_box(runtime_id)?;
+        Some(Hitbox {
+            feet,
+            width,
+            height,
+        })
+    }
+
+    /// Remember where an entity is as of this tick, for lag compensated hits.
+    pub(super) fn record_entity_position(&mut self, runtime_id: u64, feet: (f32, f32, f32)) {
+        let tick = self.game_world.current_tick();
+        self.position_history
+            .entry(runtime_id)
+            .or_default()
+            .record(tick, feet);
+    }
+
+    /// Forget entities that have not moved for longer than the history
+    /// covers, including despawned mobs and players who left.
+    pub(super) fn prune_position_history(&mut self) {
+        let now = self.game_world.current_tick();
+        self.position_history.retain(|_, history| {
+            history
+                .last_tick()
+                .is_some_and(|tick| tick + POSITION_HISTORY_TICKS >= now)
+        });
+    }
+}
@@ -0,0 +1,30 @@
+//! Entity appearance set by plugins: laid over the metadata of mobs and
+//! players whenever they are sent.
+
+use mc_rs_proto::entity_metadata::EntityMetadataEntry;
+
+use super::ConnectionHandler;
+use crate::entity_appearance::EntityAppearance;
+
+impl ConnectionHandler {
+    /// An entity's metadata with its plugin-set appearance laid over.
+    pub(super) fn with_appearance(
+        &self,
+        runtime_id: u64,
+        metadata: Vec<EntityMetadataEntry>,
+    ) -> Vec<EntityMetadataEntry> {
+        match self.entity_appearances.get(&runtime_id) {
+            Some(appearance) => appearance.apply(metadata),
+            None => metadata,
+        }
+    }
+
+    /// Change an entity's appearance. It goes out with the next metadata
+    /// update.
+    pub(super) fn set_entity_appearance(&mut self, runtime_id: u64, appearance: EntityAppearance) {
+        self.entity_appearances
+            .entry(runtime_id)
+            .or_default()
+            .merge(appearance);
+    }
+}
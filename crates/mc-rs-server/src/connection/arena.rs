@@ -0,0 +1,401 @@
+//! Running plugin arenas: moving players between lobby and team spawns,
+//! the match sidebar, rolling the region back, and `/arena`.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_plugin_api::PluginEvent;
+use mc_rs_proto::packets::set_score::{ScoreEntry, IDENTITY_FAKE_PLAYER};
+use mc_rs_proto::packets::{self, BlockActorData, SetDisplayObjective, SetScore, Text};
+use mc_rs_proto::types::BlockPos;
+use tracing::{debug, warn};
+
+use super::ConnectionHandler;
+use crate::arena::{ArenaDefinition, ArenaPhase, ArenaUpdate, RegionSnapshot};
+
+impl ConnectionHandler {
+    /// Pick up arenas defined while plugins were enabled, then advance
+    /// every arena.
+    pub(super) async fn tick_arenas(&mut self) {
+        for def in std::mem::take(&mut self.plugin_manager.new_arenas) {
+            self.create_arena(def);
+        }
+        let updates = self.arenas.tick();
+        for update in updates {
+            self.apply_arena_update(update).await;
+        }
+    }
+
+    pub(super) fn create_arena(&mut self, def: ArenaDefinition) {
+        let name = def.name.clone();
+        match self.arenas.create(def) {
+            Ok(()) => debug!("Arena {name} defined"),
+            Err(e) => warn!("[plugin] Cannot define arena {name}: {e}"),
+        }
+    }
+
+    async fn arena_message(&mut self, players: &[String], message: &str) {
+        for name in players {
+            if let Some(addr) = self.find_player_addr(name) {
+                self.send_packet(addr, packets::id::TEXT, &Text::raw(message))
+                    .await;
+            }
+        }
+    }
+
+    /// Queue a player in an arena and send them to its lobby.
+    pub(super) async fn join_arena(
+        &mut self,
+        player_name: &str,
+        arena_name: &str,
+    ) -> Result<String, String> {
+        let Some(addr) = self.find_player_addr(player_name) else {
+            return Err(format!("Player not found: {player_name}"));
+        };
+        let (x, y, z) = self
+            .arenas
+            .join(player_name, arena_name)
+            .map_err(|e| format!("Cannot join {arena_name}: {e}"))?;
+        self.teleport_player(addr, player_name, x, y, z).await;
+        let arena = self.arenas.get(arena_name).map(|a| {
+            (
+                a.players.len(),
+                a.def.max_players,
+                matches!(a.phase, ArenaPhase::Running { .. }),
+            )
+        });
+        Ok(match arena {
+            Some((_, _, true)) => {
+                format!("Joined {arena_name}, you will play in the next match")
+            }
+            Some((queued, max, false)) => format!("Joined {arena_name} ({queued}/{max})"),
+            None => format!("Joined {arena_name}"),
+        })
+    }
+
+    /// Take a player out of their arena. Players in a match go back to the
+    /// lobby. Returns the arena they left.
+    pub(super) async fn leave_arena(&mut self, player_name: &str) -> Option<String> {
+        let (arena_name, was_playing) = self.arenas.leave(player_name)?;
+        if was_playing {
+            if let Some(addr) = self.find_player_addr(player_name) {
+                self.hide_arena_sidebar(addr).await;
+                if let Some((x, y, z)) = self.arenas.get(&arena_name).map(|a| a.def.lobby) {
+                    self.teleport_player(addr, player_name, x, y, z).await;
+                }
+            }
+        }
+        Some(arena_name)
+    }
+
+    /// Take a player who disconnected out of their arena.
+    pub(super) fn forget_arena_player(&mut self, player_name: &str) {
+        self.arenas.leave(player_name);
+    }
+
+    /// Set a line of an arena's sidebar, showing it to the players of a
+    /// running match.
+    pub(super) async fn set_arena_score(&mut self, arena_name: &str, entry: &str, score: i32) {
+        let new_id = self.next_score_entry_id;
+        let entry_id = match self.arenas.set_score(arena_name, entry, score, new_id) {
+            Ok(id) => id,
+            Err(e) => {
+                warn!("[plugin] Cannot set score in arena {arena_name}: {e}");
+                return;
+            }
+        };
+        if entry_id == new_id {
+            self.next_score_entry_id += 1;
+        }
+        let Some(arena) = self.arenas.get(arena_name) else {
+            return;
+        };
+        if !matches!(arena.phase, ArenaPhase::Running { .. }) {
+            return;
+        }
+        let pkt = SetScore::change(vec![ScoreEntry {
+            entry_id,
+            objective_name: arena.objective_name(),
+            score,
+            identity_type: IDENTITY_FAKE_PLAYER,
+            custom_name: entry.to_string(),
+        }]);
+        for name in arena.playing() {
+            if let Some(addr) = self.find_player_addr(&name) {
+                self.send_packet(addr, packets::id::SET_SCORE, &pkt).await;
+            }
+        }
+    }
+
+    pub(super) fn end_arena(&mut self, arena_name: &str, winner: Option<&str>) {
+        if let Err(e) = self.arenas.end(arena_name, winner) {
+            warn!("[plugin] Cannot end arena {arena_name}: {e}");
+        }
+    }
+
+    async fn apply_arena_update(&mut self, update: ArenaUpdate) {
+        match update {
+            ArenaUpdate::CountdownStarted { arena, secs }
+            | ArenaUpdate::CountdownTick { arena, secs } => {
+                let players = self.arena_players(&arena);
+                let unit = if secs == 1 { "second" } else { "seconds" };
+                self.arena_message(&players, &format!("{arena} starts in {secs} {unit}"))
+                    .await;
+            }
+            ArenaUpdate::CountdownCancelled { arena } => {
+                let players = self.arena_players(&arena);
+                self.arena_message(&players, &format!("Not enough players, {arena} is waiting"))
+                    .await;
+            }
+            ArenaUpdate::Started { arena, teams } => self.start_arena_match(arena, teams).await,
+            ArenaUpdate::Ended {
+                arena,
+                winner,
+                players,
+                snapshot,
+            } => {
+                self.finish_arena_match(arena, winner, players, snapshot)
+                    .await
+            }
+        }
+    }
+
+    fn arena_players(&self, arena: &str) -> Vec<String> {
+        self.arenas
+            .get(arena)
+            .map(|a| a.players.clone())
+            .unwrap_or_default()
+    }
+
+    /// Snapshot the region, send everyone to their team's spawn and show
+    /// the sidebar.
+    async fn start_arena_match(&mut self, arena_name: String, teams: Vec<(String, Vec<String>)>) {
+        let Some(arena) = self.arenas.get(&arena_name) else {
+            return;
+        };
+        let def = arena.def.clone();
+        let objective = arena.objective_name();
+        let scores: Vec<ScoreEntry> = arena
+            .scores
+            .iter()
+            .map(|(entry, &(entry_id, score))| ScoreEntry {
+                entry_id,
+                objective_name: objective.clone(),
+                score,
+                identity_type: IDENTITY_FAKE_PLAYER,
+                custom_name: entry.clone(),
+            })
+            .collect();
+        let snapshot = self.snapshot_region(&def);
+        if let Some(arena) = self.arenas.get_mut(&arena_name) {
+            arena.snapshot = Some(snapshot);
+        }
+        debug!("Arena {arena_name} started");
+
+        let display = SetDisplayObjective {
+            display_slot: "sidebar".into(),
+            objective_name: objective,
+            display_name: arena_name.clone(),
+            criteria: "dummy".into(),
+            sort_order: 1,
+        };
+        let scores = SetScore::change(scores);
+        for ((team, players), team_def) in teams.iter().zip(&def.teams) {
+            let (x, y, z) = team_def.spawn;
+            for name in players {
+                let Some(addr) = self.find_player_addr(name) else {
+                    continue;
+                };
+                self.teleport_player(addr, name, x, y, z).await;
+                self.send_packet(addr, packets::id::SET_DISPLAY_OBJECTIVE, &display)
+                    .await;
+                self.send_packet(addr, packets::id::SET_SCORE, &scores)
+                    .await;
+                self.send_packet(
+                    addr,
+                    packets::id::TEXT,
+                    &Text::raw(format!("{arena_name} has begun! You are on team {team}")),
+                )
+                .await;
+            }
+        }
+
+        let event = PluginEvent::ArenaStart {
+            arena: arena_name,
+            teams,
+        };
+        let snapshot = self.build_snapshot();
+        let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+        self.apply_plugin_actions(actions).await;
+    }
+
+    /// Roll the region back and send the players back to the lobby.
+    async fn finish_arena_match(
+        &mut self,
+        arena_name: String,
+        winner: Option<String>,
+        players: Vec<String>,
+        snapshot: Option<RegionSnapshot>,
+    ) {
+        let Some(def) = self.arenas.get(&arena_name).map(|a| a.def.clone()) else {
+            return;
+        };
+        if let Some(snapshot) = snapshot {
+            self.restore_region(&def, snapshot).await;
+        }
+        debug!("Arena {arena_name} ended, winner: {winner:?}");
+
+        let message = match &winner {
+            Some(winner) => format!("{arena_name} is over, {winner} won!"),
+            None => format!("{arena_name} is over"),
+        };
+        let (x, y, z) = def.lobby;
+        for name in &players {
+            let Some(addr) = self.find_player_addr(name) else {
+                continue;
+            };
+            self.hide_arena_sidebar(addr).await;
+            self.teleport_player(addr, name, x, y, z).await;
+            self.send_packet(addr, packets::id::TEXT, &Text::raw(&message))
+                .await;
+        }
+
+        let event = PluginEvent::ArenaEnd {
+            arena: arena_name,
+            winner,
+        };
+        let snapshot = self.build_snapshot();
+        let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+        self.apply_plugin_actions(actions).await;
+    }
+
+    /// Take the arena sidebar off a player's screen, putting back the
+    /// server's own sidebar if one is set.
+    async fn hide_arena_sidebar(&mut self, addr: SocketAddr) {
+        let pkt = match self
+            .scoreboard_displays
+            .get("sidebar")
+            .and_then(|name| Some((name, self.scoreboard_objectives.get(name)?)))
+        {
+            Some((name, (display_name, criteria))) => SetDisplayObjective {
+                display_slot: "sidebar".into(),
+                objective_name: name.clone(),
+                display_name: display_name.clone(),
+                criteria: criteria.clone(),
+                sort_order: 1,
+            },
+            None => SetDisplayObjective::clear("sidebar"),
+        };
+        self.send_packet(addr, packets::id::SET_DISPLAY_OBJECTIVE, &pkt)
+            .await;
+    }
+
+    /// The blocks and block entities of an arena's region.
+    fn snapshot_region(&self, def: &ArenaDefinition) -> RegionSnapshot {
+        RegionSnapshot {
+            blocks: def
+                .positions()
+                .map(|(x, y, z)| self.get_block(x, y, z))
+                .collect(),
+            block_entities: self
+                .block_entities
+                .iter()
+                .filter(|(&(x, y, z, dim), _)| dim == 0 && def.contains((x, y, z)))
+                .map(|(&(x, y, z, _), be)| ((x, y, z), be.clone()))
+                .collect(),
+        }
+    }
+
+    /// Put back the blocks of a region that changed since the snapshot.
+    async fn restore_region(&mut self, def: &ArenaDefinition, snapshot: RegionSnapshot) {
+        let mut restored = 0;
+        for ((x, y, z), saved) in def.positions().zip(snapshot.blocks) {
+            let Some(saved) = saved else {
+                continue;
+            };
+            if self.get_block(x, y, z).is_some_and(|now| now != saved) {
+                self.set_block_and_broadcast(x, y, z, saved).await;
+                restored += 1;
+            }
+        }
+
+        let added: Vec<(i32, i32, i32)> = self
+            .block_entities
+            .keys()
+            .filter(|&&(x, y, z, dim)| dim == 0 && def.contains((x, y, z)))
+            .map(|&(x, y, z, _)| (x, y, z))
+            .collect();
+        for pos in added {
+            self.remove_block_entity(pos);
+        }
+        for ((x, y, z), be) in snapshot.block_entities {
+            let pkt = BlockActorData {
+                position: BlockPos::new(x, y, z),
+                nbt_data: be.to_network_nbt(x, y, z),
+            };
+            self.insert_block_entity((x, y, z), be);
+            self.broadcast_packet(packets::id::BLOCK_ACTOR_DATA, &pkt)
+                .await;
+        }
+        debug!("Rolled back {restored} block(s) of arena {}", def.name);
+    }
+
+    /// `/arena <list|join|leave>`. The console (no `addr`) can only list.
+    pub(super) async fn cmd_arena(
+        &mut self,
+        addr: Option<SocketAddr>,
+        args: &[String],
+    ) -> CommandResult {
+        const USAGE: &str = "Usage: /arena <list|join <arena>|leave>";
+        let player_name = addr.and_then(|a| {
+            self.connections
+                .get(&a)?
+                .login_data
+                .as_ref()
+                .map(|l| l.display_name.clone())
+        });
+        match (args.first().map(String::as_str), &player_name) {
+            (Some("list") | None, _) => {
+                let lines: Vec<String> = self
+                    .arenas
+                    .iter()
+                    .map(|a| {
+                        let state = match a.phase {
+                            ArenaPhase::Waiting => "waiting",
+                            ArenaPhase::Countdown { .. } => "starting",
+                            ArenaPhase::Running { .. } => "in a match",
+                        };
+                        format!(
+                            "- {} ({state}, {}/{})",
+                            a.def.name,
+                            a.players.len(),
+                            a.def.max_players
+                        )
+                    })
+                    .collect();
+                if lines.is_empty() {
+                    CommandResult::ok("No arenas")
+                } else {
+                    CommandResult::ok(format!("{} arena(s):\n{}", lines.len(), lines.join("\n")))
+                }
+            }
+            (Some("join"), Some(player)) => {
+                let Some(arena) = args.get(1) else {
+                    return CommandResult::err(USAGE);
+                };
+                match self.join_arena(&player.clone(), arena).await {
+                    Ok(message) => CommandResult::ok(message),
+                    Err(message) => CommandResult::err(message),
+                }
+            }
+            (Some("leave"), Some(player)) => match self.leave_arena(&player.clone()).await {
+                Some(arena) => CommandResult::ok(format!("Left {arena}")),
+                None => CommandResult::err("You are not in an arena"),
+            },
+            (Some("join" | "leave"), None) => {
+                CommandResult::err("This command must be run by a player")
+            }
+            _ => CommandResult::err(USAGE),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Banners: their block entity, dropping them with their patterns, and
+//! applying patterns in the loom.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::block_entity::BlockEntityData;
+use mc_rs_game::loom;
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::packets::item_stack_request::StackRequest;
+use mc_rs_proto::packets::item_stack_response::{
+    StackResponseContainer, StackResponseEntry, StackResponseSlot,
+};
+use mc_rs_proto::packets::{self, BlockActorData, InventoryContent};
+use mc_rs_proto::types::BlockPos;
+use tracing::debug;
+
+use super::ConnectionHandler;
+
+/// Container type of the loom UI.
+const CONTAINER_TYPE_LOOM: u8 = 24;
+/// Container ID of the loom banner slot in stack responses.
+const LOOM_INPUT: u8 = 41;
+/// Container ID of the loom dye slot in stack responses.
+const LOOM_DYE: u8 = 42;
+/// Container ID of the loom pattern slot in stack responses.
+const LOOM_MATERIAL: u8 = 43;
+
+impl ConnectionHandler {
+    /// Create the block entity of a freshly placed banner from the held
+    /// item, and show it to everyone.
+    pub(super) async fn place_banner(&mut self, addr: SocketAddr, pos: BlockPos) {
+        let Some(conn) = self.connections.get(&addr) else {
+            return;
+        };
+        let data = BlockEntityData::banner_from_item(conn.inventory.held_item());
+        let nbt = data.to_network_nbt(pos.x, pos.y, pos.z);
+        self.insert_block_entity((pos.x, pos.y, pos.z), data);
+        self.broadcast_packet(
+            packets::id::BLOCK_ACTOR_DATA,
+            &BlockActorData {
+                position: pos,
+                nbt_data: nbt,
+            },
+        )
+        .await;
+    }
+
+    /// Drop the item of a broken banner, keeping its colors and patterns.
+    pub(super) async fn drop_banner(
+        &mut self,
+        data: &BlockEntityData,
+        pos: BlockPos,
+        dimension: i32,
+    ) {
+        let BlockEntityData::Banner {
+            base,
+            patterns,
+            banner_type,
+        } = data
+        else {
+            return;
+        };
+        let Some(info) = self.item_registry.get_by_name("minecraft:banner") else {
+            return;
+        };
+        let mut item = ItemStack::new(info.numeric_id as i32, 1);
+        item.metadata = *base as u16;
+        item.nbt_data = loom::banner_item_nbt(patterns, *banner_type);
+        let position = (pos.x as f32 + 0.5, pos.y as f32, pos.z as f32 + 0.5);
+        self.spawn_dropped_item(item, position, dimension).await;
+    }
+
+    /// Handle a loom craft: the patterned banner replaces the one in the
+    /// banner slot and one dye is used up. The pattern item is kept.
+    pub(super) async fn handle_loom_craft(
+        &mut self,
+        addr: SocketAddr,
+        req: &StackRequest,
+        pattern_id: &str,
+    ) -> StackResponseEntry {
+        let reject = StackResponseEntry {
+            request_id: req.request_id,
+            status: 1,
+            containers: Vec::new(),
+        };
+
+        let (window_id, pos) = match self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.open_container.as_ref())
+        {
+            Some(oc) if oc.container_type == CONTAINER_TYPE_LOOM => (oc.window_id, oc.position),
+            _ => return reject,
+        };
+        let (banner, mut dye, pattern) = match self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::Loom {
+                banner,
+                dye,
+                pattern,
+            }) => (banner.clone(), dye.clone(), pattern.clone()),
+            _ => return reject,
+        };
+
+        // Only one banner at a time: the result takes its slot
+        if banner.count != 1 {
+            return reject;
+        }
+        let name_of = |item: &ItemStack| {
+            self.item_registry
+                .get_by_id(item.runtime_id as i16)
+                .map(|i| i.name.clone())
+        };
+        if name_of(&banner).as_deref() != Some("minecraft:banner") {
+            return reject;
+        }
+        let dye_name = name_of(&dye).unwrap_or_default();
+        let pattern_item = name_of(&pattern);
+        let Some(result) =
+            loom::loom_result(&banner, &dye_name, pattern_item.as_deref(), pattern_id)
+        else {
+            return reject;
+        };
+        dye.count -= 1;
+        if dye.count == 0 {
+            dye = ItemStack::empty();
+        }
+
+        if let Some(BlockEntityData::Loom {
+            banner: be_banner,
+            dye: be_dye,
+            ..
+        }) = self.block_entities.get_mut(&(pos.x, pos.y, pos.z, 0))
+        {
+            *be_banner = result.clone();
+            *be_dye = dye.clone();
+        }
+        debug!("Loom at {pos}: applied pattern {pattern_id} for {addr}");
+
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_CONTENT,
+            &InventoryContent {
+                window_id: window_id as u32,
+                items: vec![result.clone(), dye.clone(), pattern.clone()],
+            },
+        )
+        .await;
+
+        let slot = |container_id: u8, item: &ItemStack| StackResponseContainer {
+            container_id,
+            slots: vec![StackResponseSlot {
+                slot: 0,
+                hotbar_slot: 0,
+                count: item.count as u8,
+                stack_network_id: item.stack_network_id,
+                custom_name: String::new(),
+                durability_correction: 0,
+            }],
+        };
+        StackResponseEntry {
+            request_id: req.request_id,
+            status: 0,
+            containers: vec![
+                slot(LOOM_INPUT, &result),
+                slot(LOOM_DYE, &dye),
+                slot(LOOM_MATERIAL, &pattern),
+            ],
+        }
+    }
+}
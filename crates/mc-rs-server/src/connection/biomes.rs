@@ -0,0 +1,79 @@
+//! Biomes: as stored in loaded chunks, as the world generators place them,
+//! and `/locate`.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_world::biome;
+
+use super::ConnectionHandler;
+
+/// How far `/locate biome` searches, in blocks.
+const LOCATE_RADIUS: i32 = 6400;
+
+/// Spacing of the columns `/locate biome` samples, in blocks.
+const LOCATE_STEP: i32 = 32;
+
+/// Biome of the flat world.
+const FLAT_BIOME: u8 = 1;
+
+impl ConnectionHandler {
+    /// The biome at a world position in a dimension, if its chunk is loaded.
+    pub(super) fn biome_in(&self, dim: i32, x: i32, y: i32, z: i32) -> Option<u8> {
+        let column = self.dim_chunks(dim)?.get(&(x >> 4, z >> 4))?;
+        column.biome_at((x & 15) as usize, y, (z & 15) as usize)
+    }
+
+    /// The biome the generator of a dimension places at a column, loaded or
+    /// not.
+    fn generated_biome(&self, dim: i32, x: i32, z: i32) -> u8 {
+        match dim {
+            1 => biome::NETHER_BIOME,
+            2 => biome::END_BIOME,
+            _ => self
+                .overworld_generator
+                .as_ref()
+                .map_or(FLAT_BIOME, |gen| gen.biome_at(x, z)),
+        }
+    }
+
+    /// `/locate biome|structure ...`.
+    pub(super) async fn cmd_locate(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        match args.first().map(String::as_str) {
+            Some("biome") => self.cmd_locate_biome(addr, &args[1..]),
+            Some("structure") => self.cmd_locate_structure(addr, &args[1..]).await,
+            _ => CommandResult::err("Usage: /locate <biome|structure> <name>"),
+        }
+    }
+
+    /// `/locate biome <biome>`: the nearest column of a biome to the sender,
+    /// or to the world spawn from the console.
+    fn cmd_locate_biome(&self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        let [name] = args else {
+            return CommandResult::err("Usage: /locate biome <biome>");
+        };
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        let Some(id) = biome::biome_id(name) else {
+            return CommandResult::err(format!("Unknown biome: {name}"));
+        };
+        let (dim, origin) = match self.connections.get(&addr) {
+            Some(conn) => (conn.dimension, conn.position),
+            None => (0, self.spawn_position),
+        };
+        let (x, z) = (origin.x.floor() as i32, origin.z.floor() as i32);
+        let found = biome::locate_biome(x, z, id, LOCATE_RADIUS, LOCATE_STEP, |bx, bz| {
+            self.generated_biome(dim, bx, bz)
+        });
+        match found {
+            Some((bx, bz)) => {
+                let distance = ((bx - x) as f64).hypot((bz - z) as f64).round();
+                CommandResult::ok(format!(
+                    "The nearest {name} is at {bx}, ~, {bz} ({distance} blocks away)"
+                ))
+            }
+            None => CommandResult::err(format!(
+                "Could not find a {name} biome within {LOCATE_RADIUS} blocks"
+            )),
+        }
+    }
+}
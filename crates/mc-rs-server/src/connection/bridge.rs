@@ -0,0 +1,37 @@
+//! The chat bridge: chat relayed into the game from the `[bridge]`
+//! endpoint. Events are posted to the webhook where they happen.
+
+use mc_rs_proto::packets::{self, Text};
+use tracing::info;
+
+use super::ConnectionHandler;
+use crate::bridge::{BridgeChat, BridgeWebhook};
+
+impl ConnectionHandler {
+    /// Show chat from the bridge endpoint to everyone, through the word
+    /// filter.
+    pub async fn relay_bridge_chat(&mut self, chat: BridgeChat) {
+        let message = match self.filter_chat(&chat.message) {
+            Ok(message) => message,
+            Err(_) => {
+                info!("Refused bridge chat from {}: {}", chat.author, chat.message);
+                return;
+            }
+        };
+        let line = self
+            .server_config
+            .bridge
+            .chat_format
+            .replace("{author}", &chat.author)
+            .replace("{message}", &message);
+        info!("{line}");
+        self.broadcast_packet(packets::id::TEXT, &Text::raw(line))
+            .await;
+        self.flush_packets().await;
+    }
+
+    /// Post to the webhook of `[bridge]` again after it was reloaded.
+    pub(super) fn reload_bridge(&mut self) {
+        self.bridge = BridgeWebhook::start(&self.server_config.bridge);
+    }
+}
@@ -0,0 +1,177 @@
+//! Chat moderation: mutes, slow mode and the `[chat]` word filter, checked
+//! before chat reaches plugins.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::packets::{self, Text};
+
+use super::ConnectionHandler;
+use crate::chat::{self, Filtered};
+use crate::permissions::{MuteEntry, CHAT_BYPASS};
+use crate::persistence::unix_timestamp;
+use crate::stats::format_duration;
+
+impl ConnectionHandler {
+    /// Check a chat message against mutes, slow mode and the word filter:
+    /// the message to send on, or why it's refused.
+    pub(super) fn moderate_chat(
+        &mut self,
+        addr: SocketAddr,
+        name: &str,
+        message: &str,
+    ) -> Result<String, String> {
+        if let Some(reason) = self.mute_message(name) {
+            return Err(reason);
+        }
+
+        let current_tick = self.game_world.current_tick();
+        let slow_mode_ticks = self.slow_mode * 20;
+        if slow_mode_ticks > 0
+            && !self.permissions.is_op(name)
+            && !self.permissions.has_node(name, CHAT_BYPASS)
+        {
+            let last = self.connections.get(&addr).and_then(|c| c.last_chat_tick);
+            if let Some(last) = last {
+                let elapsed = current_tick.saturating_sub(last);
+                if elapsed < slow_mode_ticks {
+                    return Err(format!(
+                        "Slow mode is on: wait {} before chatting again",
+                        format_duration((slow_mode_ticks - elapsed).div_ceil(20))
+                    ));
+                }
+            }
+        }
+
+        let message = self.filter_chat(message)?;
+        if let Some(conn) = self.connections.get_mut(&addr) {
+            conn.last_chat_tick = Some(current_tick);
+        }
+        Ok(message)
+    }
+
+    /// What a muted player is told when they try to chat, `None` if they
+    /// aren't muted.
+    pub(super) fn mute_message(&self, name: &str) -> Option<String> {
+        let now = unix_timestamp();
+        let mute = self.permissions.mute(name, now)?;
+        Some(match mute.until {
+            Some(until) => format!(
+                "You are muted for another {}",
+                format_duration((until - now).max(0) as u64)
+            ),
+            None => "You are muted".into(),
+        })
+    }
+
+    /// Run the word filter over a message: the message to send on, or why
+    /// it's blocked.
+    pub(super) fn filter_chat(&self, message: &str) -> Result<String, String> {
+        match chat::filter_message(&self.server_config.chat, message) {
+            Filtered::Clean => Ok(message.to_string()),
+            Filtered::Replaced(filtered) => Ok(filtered),
+            Filtered::Blocked => Err("Your message contains a blocked word".into()),
+        }
+    }
+
+    /// /mute <player> [duration]
+    pub(super) async fn cmd_mute(
+        &mut self,
+        sender_addr: SocketAddr,
+        args: &[String],
+    ) -> CommandResult {
+        if args.is_empty() {
+            return CommandResult::err("Usage: /mute <player> [duration]");
+        }
+        let duration = match args.get(1) {
+            Some(d) => match chat::parse_duration(d) {
+                Some(secs) if secs > 0 => Some(secs),
+                _ => return CommandResult::err(format!("Invalid duration: {d}")),
+            },
+            None => None,
+        };
+        let targets = match self.resolve_target(&args[0], sender_addr) {
+            Ok(t) => t,
+            Err(e) => return CommandResult::err(e),
+        };
+
+        let now = unix_timestamp();
+        let mut messages = Vec::new();
+        for target_name in &targets {
+            self.permissions.muted_players.insert(
+                target_name.clone(),
+                MuteEntry {
+                    until: duration.map(|secs| now + secs as i64),
+                },
+            );
+            let what = match duration {
+                Some(secs) => format!("for {}", format_duration(secs)),
+                None => "until unmuted".into(),
+            };
+            if let Some(target_addr) = self.find_player_addr(target_name) {
+                self.send_packet(
+                    target_addr,
+                    packets::id::TEXT,
+                    &Text::raw(format!("You have been muted {what}")),
+                )
+                .await;
+            }
+            messages.push(format!("Muted {target_name} {what}"));
+        }
+        self.permissions.save_muted_players(now);
+
+        CommandResult {
+            success: true,
+            messages,
+            broadcast: None,
+            should_stop: false,
+        }
+    }
+
+    /// /unmute <player>
+    pub(super) async fn cmd_unmute(&mut self, args: &[String]) -> CommandResult {
+        if args.is_empty() {
+            return CommandResult::err("Usage: /unmute <player>");
+        }
+
+        let name = &args[0];
+        if self.permissions.muted_players.remove(name).is_none() {
+            return CommandResult::err(format!("{name} is not muted"));
+        }
+        self.permissions.save_muted_players(unix_timestamp());
+        if let Some(addr) = self.find_player_addr(name) {
+            self.send_packet(
+                addr,
+                packets::id::TEXT,
+                &Text::raw("You are no longer muted"),
+            )
+            .await;
+        }
+        CommandResult::ok(format!("Unmuted {name}"))
+    }
+
+    /// /slowmode [seconds|off]
+    pub(super) fn cmd_slowmode(&mut self, args: &[String]) -> CommandResult {
+        let seconds = match args.first().map(String::as_str) {
+            None => {
+                return CommandResult::ok(match self.slow_mode {
+                    0 => "Slow mode is off".to_string(),
+                    secs => format!("Slow mode: one message every {}", format_duration(secs)),
+                })
+            }
+            Some("off") => 0,
+            Some(value) => match chat::parse_duration(value) {
+                Some(secs) => secs,
+                None => return CommandResult::err("Usage: /slowmode [seconds|off]"),
+            },
+        };
+        self.slow_mode = seconds;
+        CommandResult::ok(match seconds {
+            0 => "Turned slow mode off".to_string(),
+            secs => format!(
+                "Turned slow mode on: one message every {}",
+                format_duration(secs)
+            ),
+        })
+    }
+}
@@ -0,0 +1,82 @@
+//! Chunk load and unload plugin events.
+//!
+//! The server keeps the chunks it read or generated in memory. For plugins,
+//! a chunk is loaded while a player has it in view: it loads when the first
+//! player is sent it and unloads when the last one loses it.
+
+use std::net::SocketAddr;
+
+use mc_rs_plugin_api::PluginEvent;
+
+use super::ConnectionHandler;
+
+impl ConnectionHandler {
+    /// Whether a player other than `except` has a chunk of `dim` in view.
+    fn chunk_in_view(&self, dim: i32, chunk: (i32, i32), except: SocketAddr) -> bool {
+        self.connections
+            .iter()
+            .any(|(&a, c)| a != except && c.dimension == dim && c.sent_chunks.contains(&chunk))
+    }
+
+    /// Record chunks of `dim` as sent to a player, firing `ChunkLoad` for
+    /// the ones no player had in view.
+    pub(super) async fn mark_chunks_sent(
+        &mut self,
+        addr: SocketAddr,
+        dim: i32,
+        chunks: &[(i32, i32)],
+    ) {
+        let mut loaded = Vec::new();
+        for &chunk in chunks {
+            let Some(conn) = self.connections.get_mut(&addr) else {
+                return;
+            };
+            if conn.sent_chunks.insert(chunk) && !self.chunk_in_view(dim, chunk, addr) {
+                loaded.push(chunk);
+            }
+        }
+        self.dispatch_chunk_events(dim, &loaded, true).await;
+    }
+
+    /// Fire `ChunkUnload` for chunks of `dim` a player lost from view that
+    /// no other player has in view.
+    pub(super) async fn chunks_left_view(
+        &mut self,
+        addr: SocketAddr,
+        dim: i32,
+        chunks: &[(i32, i32)],
+    ) {
+        let unloaded: Vec<(i32, i32)> = chunks
+            .iter()
+            .copied()
+            .filter(|&chunk| !self.chunk_in_view(dim, chunk, addr))
+            .collect();
+        self.dispatch_chunk_events(dim, &unloaded, false).await;
+    }
+
+    async fn dispatch_chunk_events(&mut self, dim: i32, chunks: &[(i32, i32)], load: bool) {
+        if chunks.is_empty() {
+            return;
+        }
+        let snapshot = self.build_snapshot();
+        let mut all_actions = Vec::new();
+        for &(cx, cz) in chunks {
+            let event = if load {
+                PluginEvent::ChunkLoad {
+                    dimension: dim,
+                    cx,
+                    cz,
+                }
+            } else {
+                PluginEvent::ChunkUnload {
+                    dimension: dim,
+                    cx,
+                    cz,
+                }
+            };
+            let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+            all_actions.extend(actions);
+        }
+        self.apply_plugin_actions(all_actions).await;
+    }
+}
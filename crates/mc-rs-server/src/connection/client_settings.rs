@@ -0,0 +1,244 @@
+//! Fog, client-side game rules and input locks: `/fog`, `/inputpermission`
+//! and the client rules of `/gamerule`.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::packets::start_game::{GameRule, GameRuleValue};
+use mc_rs_proto::packets::update_client_input_locks::{input_lock, INPUT_LOCKS};
+use mc_rs_proto::packets::{self, GameRulesChanged, PlayerFog, UpdateClientInputLocks};
+use mc_rs_proto::types::Vec3;
+
+use super::ConnectionHandler;
+use crate::client_settings::{FogStack, CLIENT_GAME_RULES};
+
+/// The client rules in the casing `/gamerule` shows them.
+fn display_name(rule: &str) -> &str {
+    match rule {
+        "showcoordinates" => "showCoordinates",
+        "showdaysplayed" => "showDaysPlayed",
+        "showtags" => "showTags",
+        "showbordereffect" => "showBorderEffect",
+        other => other,
+    }
+}
+
+fn bool_rule(rule: &str, value: bool) -> GameRule {
+    GameRule {
+        name: rule.to_string(),
+        editable: false,
+        value: GameRuleValue::Bool(value),
+    }
+}
+
+impl ConnectionHandler {
+    /// The client rules of the world, for StartGame.
+    pub(super) fn client_game_rules(&self) -> Vec<GameRule> {
+        CLIENT_GAME_RULES
+            .iter()
+            .map(|&(rule, default)| {
+                bool_rule(
+                    rule,
+                    self.client_rules.get(rule).copied().unwrap_or(default),
+                )
+            })
+            .collect()
+    }
+
+    /// Query or set a client rule for everyone, from `/gamerule`.
+    pub(super) async fn cmd_client_gamerule(
+        &mut self,
+        rule: &'static str,
+        value: Option<&String>,
+    ) -> CommandResult {
+        let name = display_name(rule);
+        let Some(value_str) = value else {
+            let current = self
+                .client_game_rules()
+                .into_iter()
+                .find(|r| r.name == rule);
+            return match current.map(|r| r.value) {
+                Some(GameRuleValue::Bool(v)) => CommandResult::ok(format!("{name} = {v}")),
+                _ => CommandResult::err(format!("Unknown game rule: {name}")),
+            };
+        };
+        let value = match value_str.to_lowercase().as_str() {
+            "true" => true,
+            "false" => false,
+            _ => {
+                return CommandResult::err(format!(
+                    "Invalid value: {value_str} (expected true/false)"
+                ))
+            }
+        };
+        self.client_rules.insert(rule, value);
+        let pkt = GameRulesChanged {
+            rules: vec![bool_rule(rule, value)],
+        };
+        self.broadcast_packet(packets::id::GAME_RULES_CHANGED, &pkt)
+            .await;
+        CommandResult::ok(format!("Game rule {name} set to {value}"))
+    }
+
+    /// Change a client rule for one player only, until they rejoin.
+    pub(super) async fn set_player_client_rule(
+        &mut self,
+        addr: SocketAddr,
+        rule: &str,
+        value: bool,
+    ) {
+        let pkt = GameRulesChanged {
+            rules: vec![bool_rule(rule, value)],
+        };
+        self.send_packet(addr, packets::id::GAME_RULES_CHANGED, &pkt)
+            .await;
+    }
+
+    /// Change a player's fog stack and send it.
+    pub(super) async fn edit_fog<T>(
+        &mut self,
+        addr: SocketAddr,
+        edit: impl FnOnce(&mut FogStack) -> T,
+    ) -> Option<T> {
+        let conn = self.connections.get_mut(&addr)?;
+        let result = edit(&mut conn.fog);
+        let pkt = PlayerFog {
+            fog_stack: conn.fog.fog_ids(),
+        };
+        self.send_packet(addr, packets::id::PLAYER_FOG, &pkt).await;
+        Some(result)
+    }
+
+    /// Lock or unlock some of a player's inputs.
+    pub(super) async fn set_input_locked(&mut self, addr: SocketAddr, flag: u32, locked: bool) {
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        if locked {
+            conn.input_locks |= flag;
+        } else {
+            conn.input_locks &= !flag;
+        }
+        let pkt = UpdateClientInputLocks {
+            locks: conn.input_locks,
+            position: Vec3::new(conn.position.x, conn.position.y - 1.62, conn.position.z),
+        };
+        self.send_packet(addr, packets::id::UPDATE_CLIENT_INPUT_LOCKS, &pkt)
+            .await;
+    }
+
+    /// Online players matching a target argument.
+    fn target_addrs(
+        &self,
+        target: &str,
+        addr: SocketAddr,
+    ) -> Result<Vec<(String, SocketAddr)>, String> {
+        Ok(self
+            .resolve_target(target, addr)?
+            .into_iter()
+            .filter_map(|name| {
+                let addr = self.find_player_addr(&name)?;
+                Some((name, addr))
+            })
+            .collect())
+    }
+
+    /// `/fog <target> <push <fog_id> <user_id>|pop <user_id>|remove <user_id>>`
+    pub(super) async fn cmd_fog(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        const USAGE: &str =
+            "Usage: /fog <target> <push <fog_id> <user_id>|pop <user_id>|remove <user_id>>";
+        let (Some(target), Some(action)) = (args.first(), args.get(1)) else {
+            return CommandResult::err(USAGE);
+        };
+        let (fog_id, user_id) = match (action.as_str(), &args[2..]) {
+            ("push", [fog_id, user_id]) => (Some(fog_id.as_str()), user_id.as_str()),
+            ("pop" | "remove", [user_id]) => (None, user_id.as_str()),
+            _ => return CommandResult::err(USAGE),
+        };
+        let targets = match self.target_addrs(target, addr) {
+            Ok(t) => t,
+            Err(e) => return CommandResult::err(e),
+        };
+
+        let mut messages = Vec::new();
+        for (name, target_addr) in targets {
+            let changed = self
+                .edit_fog(target_addr, |fog| match (action.as_str(), fog_id) {
+                    ("push", Some(fog_id)) => {
+                        fog.push(fog_id, user_id);
+                        1
+                    }
+                    ("pop", _) => fog.pop(user_id).map_or(0, |_| 1),
+                    _ => fog.remove(user_id),
+                })
+                .await
+                .unwrap_or(0);
+            messages.push(match (action.as_str(), changed) {
+                ("push", _) => format!("Pushed {} onto {name}'s fog", fog_id.unwrap_or_default()),
+                (_, 0) => format!("{name} has no fog under {user_id}"),
+                (_, n) => format!("Removed {n} fog setting(s) under {user_id} from {name}"),
+            });
+        }
+        if messages.is_empty() {
+            return CommandResult::err("No player was found");
+        }
+        CommandResult::ok(messages.join("\n"))
+    }
+
+    /// `/inputpermission <query <target> <permission>|set <target> <permission> <enabled|disabled>>`
+    pub(super) async fn cmd_inputpermission(
+        &mut self,
+        addr: SocketAddr,
+        args: &[String],
+    ) -> CommandResult {
+        const USAGE: &str = "Usage: /inputpermission <query <target> <permission>|set <target> <permission> <enabled|disabled>>";
+        let (Some(action), Some(target), Some(permission)) =
+            (args.first(), args.get(1), args.get(2))
+        else {
+            return CommandResult::err(USAGE);
+        };
+        let Some(flag) = input_lock(permission) else {
+            let names: Vec<&str> = INPUT_LOCKS.iter().map(|(n, _)| *n).collect();
+            return CommandResult::err(format!(
+                "Unknown permission: {permission} (expected {})",
+                names.join(", ")
+            ));
+        };
+        let enable = match (action.as_str(), args.get(3).map(String::as_str)) {
+            ("query", None) => None,
+            ("set", Some("enabled")) => Some(true),
+            ("set", Some("disabled")) => Some(false),
+            _ => return CommandResult::err(USAGE),
+        };
+        let targets = match self.target_addrs(target, addr) {
+            Ok(t) => t,
+            Err(e) => return CommandResult::err(e),
+        };
+        if targets.is_empty() {
+            return CommandResult::err("No player was found");
+        }
+
+        let count = targets.len();
+        let mut messages = Vec::new();
+        for (name, target_addr) in targets {
+            match enable {
+                Some(enable) => self.set_input_locked(target_addr, flag, !enable).await,
+                None => {
+                    let locked = self
+                        .connections
+                        .get(&target_addr)
+                        .is_some_and(|c| c.input_locks & flag != 0);
+                    let state = if locked { "disabled" } else { "enabled" };
+                    messages.push(format!("{name}: {permission} is {state}"));
+                }
+            }
+        }
+        match enable {
+            Some(enable) => CommandResult::ok(format!(
+                "{permission} {} for {count} player(s)",
+                if enable { "enabled" } else { "disabled" }
+            )),
+            None => CommandResult::ok(messages.join("\n")),
+        }
+    }
+}
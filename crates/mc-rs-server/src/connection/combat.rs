@@ -46,7 +46,10 @@ impl ConnectionHandler {
                 .get(&attacker_addr)
                 .map(|c| c.last_attack_tick)
                 .unwrap_or(0);
-            if !self.check_rate_limit(attacker_addr, last, MIN_ATTACK_INTERVAL) {
+            if !self
+                .check_rate_limit(attacker_addr, last, MIN_ATTACK_INTERVAL)
+                .await
+            {
                 return;
             }
             if let Some(conn) = self.connections.get_mut(&attacker_addr) {
@@ -71,7 +74,7 @@ impl ConnectionHandler {
                     item.runtime_id,
                     c.is_sprinting,
                     c.on_ground,
-                    c.last_position_delta_y,
+                    c.last_position_delta.y,
                     item.nbt_data.clone(),
                 )
             }
@@ -84,9 +87,7 @@ impl ConnectionHandler {
         }
 
         // Attack exhaustion
-        if let Some(conn) = self.connections.get_mut(&attacker_addr) {
-            conn.exhaustion += 0.1;
-        }
+        self.add_exhaustion(attacker_addr, 0.1);
 
         let base_damage = base_attack_damage(&self.item_registry, held_item_rid);
         let is_critical = game_combat::is_critical_hit(on_ground, delta_y);
@@ -99,7 +100,7 @@ impl ConnectionHandler {
                 None => return,
             };
 
-            if attacker_pos.distance(&mob_pos) > 6.0 {
+            if !self.attack_allowed(attacker_addr, victim_runtime_id).await {
                 return;
             }
 
@@ -108,6 +109,7 @@ impl ConnectionHandler {
                 base_damage,
                 weapon_nbt: &weapon_nbt,
                 armor_defense: 0.0,
+                armor_toughness: 0.0,
                 armor_nbt_slots: &[],
                 is_critical,
                 strength_bonus,
@@ -133,6 +135,7 @@ impl ConnectionHandler {
             {
                 return; // invulnerable
             }
+            self.wear_held_item(attacker_addr).await;
 
             // Broadcast critical hit animation
             if is_critical {
@@ -153,18 +156,15 @@ impl ConnectionHandler {
 
             // Knockback (with enchantment bonus)
             let kb_enchant = game_combat::knockback_bonus(&weapon_nbt);
-            let dx = mob_pos.x - attacker_pos.x;
-            let dz = mob_pos.z - attacker_pos.z;
-            let horizontal_len = (dx * dx + dz * dz).sqrt();
-            if horizontal_len > 0.001 {
-                let norm_x = dx / horizontal_len;
-                let norm_z = dz / horizontal_len;
-                let kb = 0.4 + kb_enchant as f32 * 0.3;
-                let sprint_mult = if is_sprinting { 1.5 } else { 1.0 };
-                let vx = norm_x * kb * sprint_mult;
-                let vy = 0.4;
-                let vz = norm_z * kb * sprint_mult;
-
+            let current = self
+                .game_world
+                .mob_velocity(victim_runtime_id)
+                .unwrap_or_default();
+            let direction = (mob_pos.x - attacker_pos.x, mob_pos.z - attacker_pos.z);
+            if let Some((vx, vy, vz)) =
+                self.knockback
+                    .velocity(direction, current, is_sprinting, kb_enchant)
+            {
                 self.game_world
                     .apply_knockback(victim_runtime_id, vx, vy, vz);
 
@@ -187,8 +187,11 @@ impl ConnectionHandler {
             None => return,
         };
 
-        let (victim_gamemode, victim_pos) = match self.connections.get(&victim_addr) {
-            Some(c) if c.state == LoginState::InGame && !c.is_dead => (c.gamemode, c.position),
+        let (victim_gamemode, victim_pos, victim_motion) = match self.connections.get(&victim_addr)
+        {
+            Some(c) if c.state == LoginState::InGame && !c.is_dead => {
+                (c.gamemode, c.position, c.last_position_delta)
+            }
             _ => return,
         };
 
@@ -196,43 +199,56 @@ impl ConnectionHandler {
             return;
         }
 
-        let distance = attacker_pos.distance(&victim_pos);
-        if distance > 6.0 {
-            debug!("Attack rejected: distance {distance:.2} > 6.0 from {attacker_addr}");
+        if !self.damage_allowed(victim_addr, DamageCause::Attack, true) {
+            return;
+        }
+
+        if !self.attack_allowed(attacker_addr, victim_runtime_id).await {
             return;
         }
 
-        // Invulnerability check (10 ticks = 500ms)
+        // Invulnerability check
         let current_tick = self
             .connections
             .get(&attacker_addr)
             .map(|c| c.client_tick)
             .unwrap_or(0);
-        if let Some(last_tick) = self
-            .connections
-            .get(&victim_addr)
-            .and_then(|c| c.last_damage_tick)
+        let damage_cooldown = self.server_config.combat.damage_cooldown;
+        let Some(victim_entity) = self
+            .player_uid(victim_addr)
+            .and_then(|uid| self.game_world.player_entity(uid))
+        else {
+            return;
+        };
+        if self
+            .game_world
+            .invulnerable(victim_entity, current_tick, damage_cooldown)
         {
-            if current_tick.saturating_sub(last_tick) < 10 {
-                return;
-            }
+            return;
         }
 
         // Gather victim armor data for damage calculation
-        let (armor_defense, armor_nbt_slots) = {
+        let (armor_defense, armor_toughness, armor_nbt_slots) = {
             let victim_conn = match self.connections.get(&victim_addr) {
                 Some(c) => c,
                 None => return,
             };
             let defense =
-                game_combat::total_armor_defense(&self.item_registry, &victim_conn.inventory.armor);
+                game_combat::total_armor_defense(&self.item_registry, &victim_conn.inventory.armor)
+                    + self
+                        .custom_items
+                        .armor_protection(&self.item_registry, &victim_conn.inventory.armor);
+            let toughness = game_combat::total_armor_toughness(
+                &self.item_registry,
+                &victim_conn.inventory.armor,
+            );
             let nbt_slots: Vec<Vec<u8>> = victim_conn
                 .inventory
                 .armor
                 .iter()
                 .map(|item| item.nbt_data.clone())
                 .collect();
-            (defense, nbt_slots)
+            (defense, toughness, nbt_slots)
         };
         let armor_nbt_refs: Vec<&[u8]> = armor_nbt_slots.iter().map(|v| v.as_slice()).collect();
         let resistance_factor = self.get_resistance_factor(victim_addr);
@@ -242,6 +258,7 @@ impl ConnectionHandler {
             base_damage,
             weapon_nbt: &weapon_nbt,
             armor_defense,
+            armor_toughness,
             armor_nbt_slots: &armor_nbt_refs,
             is_critical,
             strength_bonus,
@@ -251,7 +268,7 @@ impl ConnectionHandler {
 
         // Plugin event: PlayerDamage (cancellable)
         if let Some(conn) = self.connections.get(&victim_addr) {
-            let player = Self::make_plugin_player(conn);
+            let player = self.make_plugin_player(conn);
             let event = PluginEvent::PlayerDamage {
                 player,
                 damage,
@@ -265,20 +282,24 @@ impl ConnectionHandler {
             }
         }
 
+        self.wear_held_item(attacker_addr).await;
+
         // Apply damage
-        let (new_health, victim_rid, victim_name) = {
-            let conn = match self.connections.get_mut(&victim_addr) {
-                Some(c) => c,
-                None => return,
-            };
-            conn.health = (conn.health - damage).max(0.0);
-            conn.last_damage_tick = Some(current_tick);
-            let name = conn
-                .login_data
-                .as_ref()
-                .map(|d| d.display_name.clone())
-                .unwrap_or_default();
-            (conn.health, conn.entity_runtime_id, name)
+        let (victim_rid, victim_name) = match self.connections.get(&victim_addr) {
+            Some(conn) => (
+                conn.entity_runtime_id,
+                conn.login_data
+                    .as_ref()
+                    .map(|d| d.display_name.clone())
+                    .unwrap_or_default(),
+            ),
+            None => return,
+        };
+        let Some(new_health) =
+            self.game_world
+                .damage_entity(victim_entity, damage, current_tick, damage_cooldown)
+        else {
+            return;
         };
 
         // Send UpdateAttributes (health) to victim
@@ -312,29 +333,18 @@ impl ConnectionHandler {
 
         // Knockback (with enchantment bonus)
         let kb_enchant = game_combat::knockback_bonus(&weapon_nbt);
-        let dx = victim_pos.x - attacker_pos.x;
-        let dz = victim_pos.z - attacker_pos.z;
-        let horizontal_len = (dx * dx + dz * dz).sqrt();
-
-        if horizontal_len > 0.001 {
-            let norm_x = dx / horizontal_len;
-            let norm_z = dz / horizontal_len;
-            let kb_horizontal = 0.4 + kb_enchant as f32 * 0.3;
-            let kb_vertical = 0.4;
-            let sprint_mult = if is_sprinting { 1.5 } else { 1.0 };
-
-            let motion = Vec3::new(
-                norm_x * kb_horizontal * sprint_mult,
-                kb_vertical,
-                norm_z * kb_horizontal * sprint_mult,
-            );
-
+        let direction = (victim_pos.x - attacker_pos.x, victim_pos.z - attacker_pos.z);
+        let current = (victim_motion.x, victim_motion.y, victim_motion.z);
+        if let Some((vx, vy, vz)) =
+            self.knockback
+                .velocity(direction, current, is_sprinting, kb_enchant)
+        {
             self.send_packet(
                 victim_addr,
                 packets::id::SET_ENTITY_MOTION,
                 &SetEntityMotion {
                     entity_runtime_id: victim_rid,
-                    motion,
+                    motion: Vec3::new(vx, vy, vz),
                 },
             )
             .await;
@@ -376,7 +386,7 @@ impl ConnectionHandler {
                 .map(|d| d.display_name.clone())
                 .unwrap_or_else(|| "???".to_string());
             if let Some(conn) = self.connections.get(&victim_addr) {
-                let player = Self::make_plugin_player(conn);
+                let player = self.make_plugin_player(conn);
                 let event = PluginEvent::PlayerDeath {
                     player,
                     message: format!("{victim_name} was slain by {killer_name}"),
@@ -385,14 +395,17 @@ impl ConnectionHandler {
                 let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
                 self.apply_plugin_actions(actions).await;
             }
+            self.bridge.post(BridgeEvent::Death {
+                player: victim_name.to_string(),
+                message: format!("{victim_name} was slain by {killer_name}"),
+            });
         }
 
-        // XP loss on death + mark as dead
+        // Drop inventory + XP (unless keepInventory) and mark as dead
+        self.drop_death_loot(victim_addr).await;
         if let Some(conn) = self.connections.get_mut(&victim_addr) {
-            let (nl, nt) = xp::after_death(conn.xp_level, conn.xp_total);
-            conn.xp_level = nl;
-            conn.xp_total = nt;
             conn.is_dead = true;
+            conn.stats.deaths += 1;
         }
 
         // Broadcast death event to all
@@ -437,7 +450,8 @@ impl ConnectionHandler {
 
         // Plugin event: PlayerDeath
         if let Some(conn) = self.connections.get(&victim_addr) {
-            let player = Self::make_plugin_player(conn);
+            let player = self.make_plugin_player(conn);
+            let name = player.name.clone();
             let event = PluginEvent::PlayerDeath {
                 player,
                 message: message.to_string(),
@@ -445,15 +459,23 @@ impl ConnectionHandler {
             let snapshot = self.build_snapshot();
             let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
             self.apply_plugin_actions(actions).await;
+            self.bridge.post(BridgeEvent::Death {
+                player: name,
+                message: message.to_string(),
+            });
         }
 
+        self.drop_death_loot(victim_addr).await;
         if let Some(conn) = self.connections.get_mut(&victim_addr) {
-            let (nl, nt) = xp::after_death(conn.xp_level, conn.xp_total);
-            conn.xp_level = nl;
-            conn.xp_total = nt;
             conn.is_dead = true;
+            conn.stats.deaths += 1;
             conn.fire_ticks = 0;
-            conn.effects.clear();
+            if let Some(entity) = self.game_world.player_entity(conn.entity_unique_id) {
+                self.game_world.clear_effects(entity);
+            }
+            conn.back_location = Some(Self::saved_location(conn));
+            conn.pending_teleport = None;
+            conn.sleeping = None;
         }
 
         self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::death(victim_rid))
@@ -499,18 +521,15 @@ impl ConnectionHandler {
 
         let runtime_id = match self.connections.get_mut(&addr) {
             Some(conn) => {
-                conn.health = 20.0;
+                self.game_world.reset_player_vitals(conn.entity_unique_id);
                 conn.is_dead = false;
-                conn.last_damage_tick = None;
                 conn.position = spawn_pos;
-                conn.effects.clear();
                 conn.fire_ticks = 0;
-                conn.food = 20;
-                conn.saturation = 5.0;
-                conn.exhaustion = 0.0;
                 conn.fall_distance = 0.0;
                 conn.air_ticks = 300;
                 conn.is_swimming = false;
+                conn.is_crawling = false;
+                conn.is_gliding = false;
                 conn.entity_runtime_id
             }
             None => return,
@@ -518,7 +537,7 @@ impl ConnectionHandler {
 
         // Plugin event: PlayerRespawn
         if let Some(conn) = self.connections.get(&addr) {
-            let player = Self::make_plugin_player(conn);
+            let player = self.make_plugin_player(conn);
             let event = PluginEvent::PlayerRespawn { player };
             let snapshot = self.build_snapshot();
             let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
@@ -538,15 +557,25 @@ impl ConnectionHandler {
         .await;
 
         // Send full health + hunger + XP
-        let (tick, xl, xt) = match self.connections.get(&addr) {
-            Some(c) => (c.client_tick, c.xp_level, c.xp_total),
+        let tick = match self.connections.get(&addr) {
+            Some(c) => c.client_tick,
             None => return,
         };
-        let xp_prog = xp::xp_progress(xl, xt);
+        let (hp, hunger, xp) = (self.health(addr), self.hunger(addr), self.experience(addr));
+        let xp_prog = xp::xp_progress(xp.level, xp.total);
         self.send_packet(
             addr,
             packets::id::UPDATE_ATTRIBUTES,
-            &UpdateAttributes::all(runtime_id, 20.0, 20.0, 5.0, 0.0, xl, xp_prog, tick),
+            &UpdateAttributes::all(
+                runtime_id,
+                hp,
+                hunger.food as f32,
+                hunger.saturation,
+                hunger.exhaustion,
+                xp.level,
+                xp_prog,
+                tick,
+            ),
         )
         .await;
 
@@ -555,4 +584,44 @@ impl ConnectionHandler {
         self.broadcast_packet(packets::id::MOVE_PLAYER, &move_pkt)
             .await;
     }
+
+    /// `/knockback [horizontal|vertical|sprint|enchant|friction <value>|reset]`:
+    /// show or tune the melee knockback profile until the next restart.
+    pub(super) fn cmd_knockback(&mut self, args: &[String]) -> CommandResult {
+        let kb = &mut self.knockback;
+        match args {
+            [] => {}
+            [reset] if reset == "reset" => {
+                *kb = self.server_config.combat.knockback();
+            }
+            [key, value] => {
+                let Ok(value) = value.parse::<f32>() else {
+                    return CommandResult::err(format!("Invalid number: {value}"));
+                };
+                if !value.is_finite() || value < 0.0 {
+                    return CommandResult::err("Value must be a positive number");
+                }
+                let field = match key.as_str() {
+                    "horizontal" => &mut kb.horizontal,
+                    "vertical" => &mut kb.vertical,
+                    "sprint" => &mut kb.sprint_multiplier,
+                    "enchant" => &mut kb.enchant_bonus,
+                    "friction" if value <= 1.0 => &mut kb.friction,
+                    "friction" => return CommandResult::err("Friction must be between 0 and 1"),
+                    _ => return CommandResult::err(format!("Unknown knockback setting: {key}")),
+                };
+                *field = value;
+            }
+            _ => {
+                return CommandResult::err(
+                    "Usage: /knockback [horizontal|vertical|sprint|enchant|friction <value>|reset]",
+                )
+            }
+        }
+        let kb = &self.knockback;
+        CommandResult::ok(format!(
+            "Knockback: horizontal {}, vertical {}, sprint x{}, enchant +{}/level, friction {}",
+            kb.horizontal, kb.vertical, kb.sprint_multiplier, kb.enchant_bonus, kb.friction
+        ))
+    }
 }
@@ -0,0 +1,555 @@
+//! Command blocks and command block minecarts: editing, placement and
+//! execution through the command dispatcher.
+
+use mc_rs_game::command_block::{CommandBlock, CommandBlockMode, MINECART_INTERVAL_TICKS};
+use mc_rs_proto::packets::{CommandBlockTarget, CommandBlockUpdate};
+use mc_rs_world::piston::{facing_delta, facing_from_look, is_piston_powered};
+
+use super::commands::required_line_op_level;
+use super::functions::{AUTOMATED_OP_LEVEL, SERVER_ADDR};
+use super::*;
+
+/// ContainerOpen type of the command block editing screen.
+const CONTAINER_TYPE_COMMAND_BLOCK: u8 = 16;
+
+/// Rails a command block minecart can be placed on.
+const RAILS: &[&str] = &[
+    "minecraft:rail",
+    "minecraft:golden_rail",
+    "minecraft:detector_rail",
+    "minecraft:activator_rail",
+];
+
+/// A command block minecart standing on a rail. It runs its command while
+/// the rail is a powered activator rail.
+pub struct CommandBlockMinecart {
+    pub unique_id: i64,
+    pub runtime_id: u64,
+    /// Feet-level position.
+    pub position: (f32, f32, f32),
+    pub dimension: i32,
+    /// The rail it stands on.
+    pub rail: (i32, i32, i32),
+    pub command_block: CommandBlock,
+}
+
+impl ConnectionHandler {
+    /// Whether the player may edit command blocks: an operator of at least
+    /// the level their commands run at, in creative.
+    pub(super) fn can_edit_command_blocks(&self, addr: SocketAddr) -> bool {
+        self.connections.get(&addr).is_some_and(|c| {
+            c.gamemode == 1
+                && c.login_data.as_ref().is_some_and(|d| {
+                    self.permissions.op_level(&d.display_name) >= AUTOMATED_OP_LEVEL
+                })
+        })
+    }
+
+    /// Turn a command block placed from the item towards where the player looks.
+    pub(super) fn command_block_placement(&self, addr: SocketAddr, rid: u32) -> Option<u32> {
+        let (mode, _, conditional) = self.tick_blocks.command_block_state(rid)?;
+        let (pitch, yaw) = self
+            .connections
+            .get(&addr)
+            .map(|c| (c.pitch, c.yaw))
+            .unwrap_or((0.0, 0.0));
+        Some(
+            self.tick_blocks
+                .command_block_with(mode, facing_from_look(pitch, yaw), conditional),
+        )
+    }
+
+    /// Open the editing screen of the command block at `pos`. Returns `false`
+    /// if the player isn't allowed to, so the click is handled normally.
+    pub(super) async fn open_command_block(&mut self, addr: SocketAddr, pos: BlockPos) -> bool {
+        if !self.can_edit_command_blocks(addr) {
+            return false;
+        }
+        if !matches!(
+            self.block_entities.get(&(pos.x, pos.y, pos.z, 0)),
+            Some(BlockEntityData::CommandBlock(_))
+        ) {
+            self.insert_block_entity((pos.x, pos.y, pos.z), BlockEntityData::new_command_block());
+        }
+        let Some(be) = self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) else {
+            return true;
+        };
+        let nbt = be.to_network_nbt(pos.x, pos.y, pos.z);
+        self.send_packet(
+            addr,
+            packets::id::BLOCK_ACTOR_DATA,
+            &BlockActorData {
+                position: pos,
+                nbt_data: nbt,
+            },
+        )
+        .await;
+        self.open_command_block_screen(addr, pos, -1).await;
+        true
+    }
+
+    /// Open the command block editing screen for a block or, with a
+    /// minecart's unique ID, a minecart.
+    async fn open_command_block_screen(
+        &mut self,
+        addr: SocketAddr,
+        position: BlockPos,
+        entity_unique_id: i64,
+    ) {
+        let window_id = match self.connections.get_mut(&addr) {
+            Some(conn) => {
+                let wid = conn.next_window_id;
+                conn.next_window_id = conn.next_window_id.wrapping_add(1);
+                if conn.next_window_id == 0 {
+                    conn.next_window_id = 1;
+                }
+                conn.open_container = Some(OpenContainer {
+                    window_id: wid,
+                    container_type: CONTAINER_TYPE_COMMAND_BLOCK,
+                    position,
+                });
+                wid
+            }
+            None => return,
+        };
+
+        self.send_packet(
+            addr,
+            packets::id::CONTAINER_OPEN,
+            &ContainerOpen {
+                window_id,
+                container_type: CONTAINER_TYPE_COMMAND_BLOCK,
+                position,
+                entity_unique_id,
+            },
+        )
+        .await;
+    }
+
+    /// Handle the settings saved in a command block editing screen.
+    pub(super) async fn handle_command_block_update(
+        &mut self,
+        addr: SocketAddr,
+        buf: &mut Cursor<&[u8]>,
+    ) {
+        let pkt = match CommandBlockUpdate::proto_decode(buf) {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("Bad CommandBlockUpdate from {addr}: {e}");
+                return;
+            }
+        };
+        if !self.can_edit_command_blocks(addr) {
+            debug!("CommandBlockUpdate from {addr} without permission, ignoring");
+            return;
+        }
+        // Nobody may store a command they could not run themselves
+        let editor_level = self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.login_data.as_ref())
+            .map(|d| self.permissions.op_level(&d.display_name))
+            .unwrap_or(0);
+        let command = pkt.command.trim().trim_start_matches('/');
+        if editor_level < required_line_op_level(command) {
+            self.send_packet(
+                addr,
+                packets::id::TEXT,
+                &Text::raw("You do not have permission to use this command"),
+            )
+            .await;
+            return;
+        }
+
+        match pkt.target {
+            CommandBlockTarget::Block {
+                position,
+                mode,
+                conditional,
+                ..
+            } => {
+                let Some(rid) = self.get_block(position.x, position.y, position.z) else {
+                    return;
+                };
+                let Some((_, facing, _)) = self.tick_blocks.command_block_state(rid) else {
+                    return;
+                };
+                if CommandBlockMode::from_index(mode).is_none() {
+                    return;
+                }
+
+                // Mode and conditional are part of the block state
+                let new_rid = self
+                    .tick_blocks
+                    .command_block_with(mode, facing, conditional);
+                if new_rid != rid {
+                    self.set_block_and_broadcast(position.x, position.y, position.z, new_rid)
+                        .await;
+                }
+
+                let pos = (position.x, position.y, position.z);
+                if !matches!(
+                    self.block_entities.get(&(pos.0, pos.1, pos.2, 0)),
+                    Some(BlockEntityData::CommandBlock(_))
+                ) {
+                    self.insert_block_entity(pos, BlockEntityData::new_command_block());
+                }
+                let Some(be) = self.block_entities.get_mut(&(pos.0, pos.1, pos.2, 0)) else {
+                    return;
+                };
+                if let BlockEntityData::CommandBlock(command_block) = be {
+                    command_block.apply_update(&pkt);
+                }
+                let nbt = be.to_network_nbt(pos.0, pos.1, pos.2);
+                self.broadcast_packet(
+                    packets::id::BLOCK_ACTOR_DATA,
+                    &BlockActorData {
+                        position,
+                        nbt_data: nbt,
+                    },
+                )
+                .await;
+                debug!("Command block at {position} set to '{}'", pkt.command);
+            }
+            CommandBlockTarget::Minecart { runtime_id } => {
+                if let Some(minecart) = self
+                    .command_block_minecarts
+                    .iter_mut()
+                    .find(|m| m.runtime_id == runtime_id)
+                {
+                    minecart.command_block.apply_update(&pkt);
+                    debug!(
+                        "Command block minecart {runtime_id} set to '{}'",
+                        pkt.command
+                    );
+                }
+            }
+        }
+    }
+
+    /// Whether the block at `(x, y, z)` receives redstone power.
+    pub(super) fn block_powered(&self, x: i32, y: i32, z: i32) -> bool {
+        let playing = self.playing_jukeboxes();
+        is_piston_powered(x, y, z, &self.tick_blocks, &|bx, by, bz| {
+            let block = self.get_block(bx, by, bz);
+            music::redstone_view(&playing, &self.tick_blocks, (bx, by, bz), block)
+        })
+    }
+
+    /// Run command blocks triggered this tick, following their chains, and
+    /// the command block minecarts on powered activator rails.
+    pub(super) async fn tick_command_blocks(&mut self) {
+        if !self.command_blocks_enabled {
+            return;
+        }
+        let tick = self.game_world.current_tick();
+
+        let positions: Vec<(i32, i32, i32)> = self
+            .block_entities
+            .iter()
+            .filter(|(&(_, _, _, dim), be)| {
+                dim == 0 && matches!(be, BlockEntityData::CommandBlock(_))
+            })
+            .map(|(&(x, y, z, _), _)| (x, y, z))
+            .collect();
+
+        for (x, y, z) in positions {
+            let Some((mode, _, _)) = self
+                .get_block(x, y, z)
+                .and_then(|rid| self.tick_blocks.command_block_state(rid))
+            else {
+                continue;
+            };
+            let Some(mode) = CommandBlockMode::from_index(mode) else {
+                continue;
+            };
+            if mode == CommandBlockMode::Chain {
+                continue;
+            }
+            let powered = self.block_powered(x, y, z);
+            let runs = match self.block_entities.get_mut(&(x, y, z, 0)) {
+                Some(BlockEntityData::CommandBlock(command_block)) => {
+                    command_block.tick_power(mode, powered, tick)
+                }
+                _ => false,
+            };
+            if runs {
+                self.run_command_block_chain((x, y, z), tick).await;
+            }
+        }
+
+        if tick.is_multiple_of(MINECART_INTERVAL_TICKS) {
+            self.tick_command_block_minecarts(tick).await;
+        }
+    }
+
+    /// Run the command block at `start`, then each active chain command
+    /// block it points into, up to the configured chain length.
+    async fn run_command_block_chain(&mut self, start: (i32, i32, i32), tick: u64) {
+        let max_length = self.server_config.gameplay.max_command_chain_length;
+        let mut pos = start;
+        let mut length = 0;
+        while let Some((_, facing, conditional)) = self
+            .get_block(pos.0, pos.1, pos.2)
+            .and_then(|rid| self.tick_blocks.command_block_state(rid))
+        {
+            self.run_command_block(pos, facing, conditional, tick).await;
+            length += 1;
+            if length >= max_length {
+                debug!("Command block chain from {start:?} stopped after {max_length} blocks");
+                break;
+            }
+
+            let (dx, dy, dz) = facing_delta(facing);
+            let next = (pos.0 + dx, pos.1 + dy, pos.2 + dz);
+            let is_chain = self
+                .get_block(next.0, next.1, next.2)
+                .and_then(|rid| self.tick_blocks.command_block_state(rid))
+                .is_some_and(|(mode, ..)| mode == CommandBlockMode::Chain.index());
+            if !is_chain {
+                break;
+            }
+            let powered = self.block_powered(next.0, next.1, next.2);
+            let active = match self.block_entities.get_mut(&(next.0, next.1, next.2, 0)) {
+                Some(BlockEntityData::CommandBlock(command_block)) => {
+                    command_block.powered = powered;
+                    command_block.is_active()
+                }
+                _ => false,
+            };
+            if !active {
+                break;
+            }
+            pos = next;
+        }
+    }
+
+    /// Run the command of one command block. A conditional block only runs
+    /// if the command block behind it succeeded.
+    async fn run_command_block(
+        &mut self,
+        (x, y, z): (i32, i32, i32),
+        facing: u8,
+        conditional: bool,
+        tick: u64,
+    ) {
+        if conditional {
+            let (dx, dy, dz) = facing_delta(facing);
+            let behind_succeeded = matches!(
+                self.block_entities.get(&(x - dx, y - dy, z - dz, 0)),
+                Some(BlockEntityData::CommandBlock(behind)) if behind.success_count > 0
+            );
+            if !behind_succeeded {
+                if let Some(BlockEntityData::CommandBlock(command_block)) =
+                    self.block_entities.get_mut(&(x, y, z, 0))
+                {
+                    command_block.record_condition_failed();
+                }
+                return;
+            }
+        }
+
+        let (sender, command) = match self.block_entities.get(&(x, y, z, 0)) {
+            Some(BlockEntityData::CommandBlock(command_block)) => (
+                command_block.sender_name().to_string(),
+                command_block.command.clone(),
+            ),
+            _ => return,
+        };
+        let origin = (x as f32 + 0.5, y as f32 + 0.5, z as f32 + 0.5);
+        let result = self
+            .run_command_block_command(&sender, &command, origin)
+            .await;
+
+        if let Some(BlockEntityData::CommandBlock(command_block)) =
+            self.block_entities.get_mut(&(x, y, z, 0))
+        {
+            command_block.record_run(result.success, result.messages.join("\n"), tick);
+        }
+    }
+
+    /// Run a command block's command as `sender`, resolving selectors from
+    /// `origin`, at [`AUTOMATED_OP_LEVEL`] whoever wrote it.
+    async fn run_command_block_command(
+        &mut self,
+        sender: &str,
+        command: &str,
+        origin: (f32, f32, f32),
+    ) -> CommandResult {
+        let command = command.trim();
+        let command = command.strip_prefix('/').unwrap_or(command);
+        if command.is_empty() {
+            return CommandResult::err("No command set");
+        }
+        self.command_origin = Some(origin);
+        let result = self
+            .dispatch_command(SERVER_ADDR, sender, command, AUTOMATED_OP_LEVEL)
+            .await;
+        self.command_origin = None;
+        self.apply_command_effects(sender, &result).await;
+        result
+    }
+
+    /// Run the command of each minecart standing on a powered activator rail.
+    async fn tick_command_block_minecarts(&mut self, tick: u64) {
+        let due: Vec<u64> = self
+            .command_block_minecarts
+            .iter()
+            .filter(|m| m.dimension == 0)
+            .filter(|m| {
+                let (x, y, z) = m.rail;
+                self.get_block(x, y, z).is_some_and(|rid| {
+                    self.tick_blocks.is_activator_rail(rid) && self.block_powered(x, y, z)
+                })
+            })
+            .map(|m| m.runtime_id)
+            .collect();
+
+        for runtime_id in due {
+            let Some((sender, command, origin)) = self
+                .command_block_minecarts
+                .iter()
+                .find(|m| m.runtime_id == runtime_id)
+                .map(|m| {
+                    (
+                        m.command_block.sender_name().to_string(),
+                        m.command_block.command.clone(),
+                        m.position,
+                    )
+                })
+            else {
+                continue;
+            };
+            let result = self
+                .run_command_block_command(&sender, &command, origin)
+                .await;
+            if let Some(minecart) = self
+                .command_block_minecarts
+                .iter_mut()
+                .find(|m| m.runtime_id == runtime_id)
+            {
+                minecart
+                    .command_block
+                    .record_run(result.success, result.messages.join("\n"), tick);
+            }
+        }
+    }
+
+    /// Place a held command block minecart on the rail at `pos`. Returns
+    /// whether the click was used.
+    pub(super) async fn place_command_block_minecart(
+        &mut self,
+        addr: SocketAddr,
+        pos: BlockPos,
+    ) -> bool {
+        let (held_rid, gamemode, dimension) = match self.connections.get(&addr) {
+            Some(c) => (c.inventory.held_item().runtime_id, c.gamemode, c.dimension),
+            None => return false,
+        };
+        let holds_minecart = held_rid != 0
+            && self
+                .item_registry
+                .get_by_id(held_rid as i16)
+                .is_some_and(|info| info.name == "minecraft:command_block_minecart");
+        if !holds_minecart {
+            return false;
+        }
+        let on_rail = self
+            .get_block(pos.x, pos.y, pos.z)
+            .and_then(|rid| self.block_registry.get(rid))
+            .is_some_and(|info| RAILS.contains(&info.name));
+        // The item is only obtainable in creative, where it isn't used up
+        if !on_rail || gamemode != 1 {
+            return true;
+        }
+
+        let unique_id = self.allocate_entity_id();
+        let minecart = CommandBlockMinecart {
+            unique_id,
+            runtime_id: unique_id as u64,
+            position: (pos.x as f32 + 0.5, pos.y as f32, pos.z as f32 + 0.5),
+            dimension,
+            rail: (pos.x, pos.y, pos.z),
+            command_block: CommandBlock::default(),
+        };
+        self.broadcast_packet(packets::id::ADD_ACTOR, &minecart_actor(&minecart))
+            .await;
+        self.command_block_minecarts.push(minecart);
+        debug!("Command block minecart placed at {pos} by {addr}");
+        true
+    }
+
+    /// Open the editing screen of a command block minecart. Returns whether
+    /// `runtime_id` is one.
+    pub(super) async fn interact_command_block_minecart(
+        &mut self,
+        addr: SocketAddr,
+        runtime_id: u64,
+    ) -> bool {
+        let Some((unique_id, rail)) = self
+            .command_block_minecarts
+            .iter()
+            .find(|m| m.runtime_id == runtime_id)
+            .map(|m| (m.unique_id, m.rail))
+        else {
+            return false;
+        };
+        if self.can_edit_command_blocks(addr) {
+            let position = BlockPos::new(rail.0, rail.1, rail.2);
+            self.open_command_block_screen(addr, position, unique_id)
+                .await;
+        }
+        true
+    }
+
+    /// Break a command block minecart hit by a player. Returns whether
+    /// `runtime_id` is one.
+    pub(super) async fn attack_command_block_minecart(&mut self, runtime_id: u64) -> bool {
+        let Some(index) = self
+            .command_block_minecarts
+            .iter()
+            .position(|m| m.runtime_id == runtime_id)
+        else {
+            return false;
+        };
+        let minecart = self.command_block_minecarts.swap_remove(index);
+        self.broadcast_packet(
+            packets::id::REMOVE_ENTITY,
+            &RemoveEntity {
+                entity_unique_id: minecart.unique_id,
+            },
+        )
+        .await;
+        true
+    }
+
+    /// Send every command block minecart to a player who just joined.
+    pub(super) async fn sync_command_block_minecarts_to_player(&mut self, addr: SocketAddr) {
+        let actors: Vec<AddActor> = self
+            .command_block_minecarts
+            .iter()
+            .map(minecart_actor)
+            .collect();
+        for pkt in &actors {
+            self.send_packet(addr, packets::id::ADD_ACTOR, pkt).await;
+        }
+    }
+}
+
+/// Build the AddActor packet for a command block minecart.
+fn minecart_actor(minecart: &CommandBlockMinecart) -> AddActor {
+    let (x, y, z) = minecart.position;
+    AddActor {
+        entity_unique_id: minecart.unique_id,
+        entity_runtime_id: minecart.runtime_id,
+        entity_type: "minecraft:command_block_minecart".to_string(),
+        position: Vec3::new(x, y, z),
+        velocity: Vec3::ZERO,
+        pitch: 0.0,
+        yaw: 0.0,
+        head_yaw: 0.0,
+        body_yaw: 0.0,
+        attributes: vec![],
+        metadata: vec![],
+    }
+}
@@ -1,4 +1,8 @@
 use super::*;
+use crate::client_settings::client_game_rule;
+use mc_rs_command::score::{ScoreOperator, ScoreRange};
+use mc_rs_game::adventure;
+use mc_rs_nbt::{NbtRoot, NbtTag};
 
 impl ConnectionHandler {
     // -----------------------------------------------------------------------
@@ -26,14 +30,24 @@ impl ConnectionHandler {
             .map(|d| d.display_name.clone())
             .unwrap_or_else(|| "unknown".to_string());
 
-        info!("<{sender_name}> {}", text.message);
+        let message = match self.moderate_chat(addr, &sender_name, &text.message) {
+            Ok(message) => message,
+            Err(reason) => {
+                info!("Refused chat from {sender_name}: {}", text.message);
+                self.send_packet(addr, packets::id::TEXT, &Text::raw(reason))
+                    .await;
+                return;
+            }
+        };
+
+        info!("<{sender_name}> {message}");
 
         // Plugin event: PlayerChat (cancellable)
         if let Some(conn) = self.connections.get(&addr) {
-            let player = Self::make_plugin_player(conn);
+            let player = self.make_plugin_player(conn);
             let event = PluginEvent::PlayerChat {
                 player,
-                message: text.message.clone(),
+                message: message.clone(),
             };
             let snapshot = self.build_snapshot();
             let (result, actions) = self.plugin_manager.dispatch(&event, &snapshot);
@@ -43,7 +57,12 @@ impl ConnectionHandler {
             }
         }
 
-        let response = Text::raw(format!("<{sender_name}> {}", text.message));
+        self.bridge.post(BridgeEvent::Chat {
+            player: sender_name.clone(),
+            message: message.clone(),
+        });
+
+        let response = Text::raw(format!("<{sender_name}> {message}"));
         self.send_packet(addr, packets::id::TEXT, &response).await;
     }
 
@@ -64,7 +83,10 @@ impl ConnectionHandler {
                 .get(&addr)
                 .map(|c| c.last_command_tick)
                 .unwrap_or(0);
-            if !self.check_rate_limit(addr, last, MIN_COMMAND_INTERVAL) {
+            if !self
+                .check_rate_limit(addr, last, MIN_COMMAND_INTERVAL)
+                .await
+            {
                 return;
             }
             if let Some(conn) = self.connections.get_mut(&addr) {
@@ -100,7 +122,7 @@ impl ConnectionHandler {
 
         // Plugin event: PlayerCommand (cancellable)
         if let Some(conn) = self.connections.get(&addr) {
-            let player = Self::make_plugin_player(conn);
+            let player = self.make_plugin_player(conn);
             let event = PluginEvent::PlayerCommand {
                 player,
                 command: cmd_name.to_string(),
@@ -114,94 +136,135 @@ impl ConnectionHandler {
             }
         }
 
-        // Permission check for operator-only commands
-        let needs_op = matches!(
-            cmd_name,
-            "gamemode"
-                | "tp"
-                | "give"
-                | "kill"
-                | "kick"
-                | "op"
-                | "deop"
-                | "ban"
-                | "ban-ip"
-                | "unban"
-                | "unban-ip"
-                | "whitelist"
-                | "summon"
-                | "enchant"
-                | "time"
-                | "weather"
-                | "gamerule"
-                | "stop"
-                | "reload"
-                | "setblock"
-                | "fill"
-                | "clone"
-                | "title"
-                | "particle"
-                | "playsound"
-                | "scoreboard"
-                | "tag"
-                | "bossbar"
-                | "execute"
-                | "transfer"
-                | "tickingarea"
-                | "import"
-                | "export"
-        );
-        if needs_op && !self.permissions.ops.contains(&sender_name) {
-            let result = CommandResult::err("You do not have permission to use this command");
-            let output = CommandOutput::failure(request.origin, result.messages.join("\n"));
-            self.send_packet(addr, packets::id::COMMAND_OUTPUT, &output)
+        let op_level = self.permissions.op_level(&sender_name);
+        let result = self
+            .dispatch_command(addr, &sender_name, command_str, op_level)
+            .await;
+
+        // Send CommandOutput
+        let output = if result.success {
+            CommandOutput::success(request.origin, result.messages.join("\n"))
+        } else {
+            CommandOutput::failure(request.origin, result.messages.join("\n"))
+        };
+        self.send_packet(addr, packets::id::COMMAND_OUTPUT, &output)
+            .await;
+
+        // Send result messages as chat text to the sender
+        for msg in &result.messages {
+            self.send_packet(addr, packets::id::TEXT, &Text::raw(msg))
                 .await;
-            for msg in &result.messages {
-                self.send_packet(addr, packets::id::TEXT, &Text::raw(msg))
-                    .await;
-            }
-            return;
         }
 
+        self.apply_command_effects(&sender_name, &result).await;
+    }
+
+    /// Run a command line (without the leading `/`) as `sender_name`, with
+    /// the permissions of `op_level`: server commands, then plugin commands,
+    /// then the command registry.
+    pub(super) async fn dispatch_command(
+        &mut self,
+        addr: SocketAddr,
+        sender_name: &str,
+        command_str: &str,
+        op_level: u8,
+    ) -> CommandResult {
+        if op_level < required_line_op_level(command_str) {
+            return CommandResult::err("You do not have permission to use this command");
+        }
+        let sender_name = sender_name.to_string();
+        let mut parts = command_str.split_whitespace();
+        let cmd_name = parts.next().unwrap_or("");
+        let raw_args: Vec<String> = parts.map(String::from).collect();
+
         // Try server commands first (need &mut self access)
         let server_result = match cmd_name {
             "gamemode" => Some(self.cmd_gamemode(addr, &sender_name, &raw_args).await),
             "tp" => Some(self.cmd_tp(addr, &sender_name, &raw_args).await),
             "give" => Some(self.cmd_give(addr, &raw_args).await),
+            "kit" => Some(self.cmd_kit(addr, &sender_name, &raw_args).await),
+            "stats" => Some(self.cmd_stats(&sender_name, &raw_args)),
+            "home" => Some(self.cmd_home(addr, &raw_args).await),
+            "sethome" => Some(self.cmd_sethome(addr, &raw_args)),
+            "delhome" => Some(self.cmd_delhome(addr, &raw_args)),
+            "back" => Some(self.cmd_back(addr).await),
+            "tpa" => Some(self.cmd_tpa(addr, &sender_name, &raw_args).await),
+            "tpaccept" => Some(self.cmd_tpaccept(addr, &sender_name, &raw_args).await),
+            "tpdeny" => Some(self.cmd_tpdeny(addr, &sender_name, &raw_args).await),
+            "warp" => Some(self.cmd_warp(addr, &sender_name, &raw_args).await),
+            "setwarp" => Some(self.cmd_setwarp(addr, &raw_args)),
+            "delwarp" => Some(self.cmd_delwarp(&raw_args)),
+            "spawn" => Some(self.cmd_spawn(addr).await),
+            "balance" => Some(self.cmd_balance(Some(addr), &raw_args)),
+            "pay" => Some(self.cmd_pay(addr, &raw_args).await),
+            "eco" => Some(self.cmd_eco(&sender_name, &raw_args)),
+            "arena" => Some(self.cmd_arena(Some(addr), &raw_args).await),
+            "hologram" => Some(self.cmd_hologram(Some(addr), &raw_args).await),
+            "npc" => Some(self.cmd_npc(Some(addr), &raw_args).await),
+            "packs" => Some(self.cmd_packs(Some(addr), &raw_args)),
             "kill" => Some(self.cmd_kill(addr, &sender_name, &raw_args).await),
             "kick" => Some(self.cmd_kick(addr, &raw_args).await),
-            "op" => Some(self.cmd_op(addr, &raw_args).await),
+            "op" => Some(self.cmd_op(addr, &sender_name, &raw_args).await),
             "deop" => Some(self.cmd_deop(addr, &raw_args).await),
             "ban" => Some(self.cmd_ban(addr, &raw_args).await),
             "ban-ip" => Some(self.cmd_ban_ip(addr, &raw_args).await),
             "unban" => Some(self.cmd_unban(&raw_args)),
             "unban-ip" => Some(self.cmd_unban_ip(&raw_args)),
             "whitelist" => Some(self.cmd_whitelist(&raw_args)),
+            "permission" => Some(self.cmd_permission(&raw_args)),
             "summon" => Some(self.cmd_summon(addr, &raw_args)),
             "effect" => Some(self.cmd_effect(addr, &sender_name, &raw_args).await),
             "enchant" => Some(self.cmd_enchant(addr, &sender_name, &raw_args).await),
             "time" => Some(self.cmd_time(addr, &raw_args).await),
             "weather" => Some(self.cmd_weather(addr, &raw_args).await),
             "gamerule" => Some(self.cmd_gamerule(addr, &raw_args).await),
-            "reload" => Some(self.cmd_reload(addr).await),
+            "difficulty" => Some(self.cmd_difficulty(&raw_args).await),
+            "knockback" => Some(self.cmd_knockback(&raw_args)),
+            "worldflag" => Some(self.cmd_worldflag(addr, &raw_args)),
+            "world" => Some(self.cmd_world(addr, &sender_name, &raw_args).await),
+            "reload" => Some(self.cmd_reload(&raw_args).await),
+            "restart" => Some(self.cmd_restart(&sender_name, &raw_args).await),
             "setblock" => Some(self.cmd_setblock(addr, &raw_args).await),
             "fill" => Some(self.cmd_fill(addr, &raw_args).await),
+            "locate" => Some(self.cmd_locate(addr, &raw_args).await),
             "clone" => Some(self.cmd_clone(addr, &raw_args).await),
             "title" => Some(self.cmd_title(addr, &sender_name, &raw_args).await),
+            "camera" => Some(self.cmd_camera(addr, &raw_args).await),
+            "fog" => Some(self.cmd_fog(addr, &raw_args).await),
+            "inputpermission" => Some(self.cmd_inputpermission(addr, &raw_args).await),
             "particle" => Some(self.cmd_particle(addr, &raw_args).await),
             "playsound" => Some(self.cmd_playsound(addr, &sender_name, &raw_args).await),
             "scoreboard" => Some(self.cmd_scoreboard(addr, &raw_args).await),
             "tag" => Some(self.cmd_tag(addr, &sender_name, &raw_args).await),
             "bossbar" => Some(self.cmd_bossbar(addr, &raw_args).await),
-            "execute" => Some(self.cmd_execute(addr, &sender_name, &raw_args).await),
+            "execute" => Some(
+                self.cmd_execute(addr, &sender_name, &raw_args, op_level)
+                    .await,
+            ),
             "transfer" => Some(self.cmd_transfer(addr, &sender_name, &raw_args).await),
             "tickingarea" => Some(self.cmd_tickingarea(&raw_args)),
             "import" => Some(self.cmd_import(&raw_args)),
             "export" => Some(self.cmd_export(&raw_args)),
+            "scriptevent" => Some(self.cmd_scriptevent(addr, command_str).await),
+            "function" => Some(
+                self.cmd_function(addr, &sender_name, &raw_args, op_level)
+                    .await,
+            ),
+            "timings" => Some(self.cmd_timings(&raw_args)),
+            "tps" => Some(self.cmd_tps()),
+            "viewdistance" => Some(self.cmd_viewdistance(addr, &sender_name, &raw_args).await),
+            "whois" => Some(self.cmd_whois(&raw_args)),
+            "mute" => Some(self.cmd_mute(addr, &raw_args).await),
+            "unmute" => Some(self.cmd_unmute(&raw_args).await),
+            "slowmode" => Some(self.cmd_slowmode(&raw_args)),
+            "msg" | "tell" => Some(self.cmd_msg(addr, &sender_name, &raw_args).await),
+            "reply" => Some(self.cmd_reply(addr, &sender_name, &raw_args).await),
+            "ignore" => Some(self.cmd_ignore(addr, &raw_args)),
+            "socialspy" => Some(self.cmd_socialspy(addr)),
             _ => None,
         };
 
-        let result = if let Some(r) = server_result {
+        if let Some(r) = server_result {
             r
         } else if self.plugin_manager.plugin_commands.contains_key(cmd_name) {
             // Plugin-registered command
@@ -234,23 +297,15 @@ impl ConnectionHandler {
                 args,
             };
             self.command_registry.execute(cmd_name, &ctx)
-        };
-
-        // Send CommandOutput
-        let output = if result.success {
-            CommandOutput::success(request.origin, result.messages.join("\n"))
-        } else {
-            CommandOutput::failure(request.origin, result.messages.join("\n"))
-        };
-        self.send_packet(addr, packets::id::COMMAND_OUTPUT, &output)
-            .await;
-
-        // Send result messages as chat text to the sender
-        for msg in &result.messages {
-            self.send_packet(addr, packets::id::TEXT, &Text::raw(msg))
-                .await;
         }
+    }
 
+    /// Broadcast and stop requests carried by a command result.
+    pub(super) async fn apply_command_effects(
+        &mut self,
+        sender_name: &str,
+        result: &CommandResult,
+    ) {
         // Broadcast if requested
         if let Some(broadcast_msg) = &result.broadcast {
             let text = Text::raw(broadcast_msg);
@@ -260,7 +315,7 @@ impl ConnectionHandler {
         // Shutdown if requested
         if result.should_stop {
             info!("Server stop requested by {sender_name}");
-            self.save_all();
+            self.save_all().await;
             let _ = self.shutdown_tx.send(true);
         }
     }
@@ -319,27 +374,8 @@ impl ConnectionHandler {
             )
             .await;
 
-            // Send UpdateAbilities to the target
-            let (entity_unique_id, perm, cmd_perm) = {
-                let conn = &self.connections[&target_addr];
-                let is_op = self.permissions.ops.contains(target_name.as_str());
-                (
-                    conn.entity_unique_id,
-                    if is_op { 2u8 } else { 1u8 },
-                    if is_op { 1u8 } else { 0u8 },
-                )
-            };
-            self.send_packet(
-                target_addr,
-                packets::id::UPDATE_ABILITIES,
-                &UpdateAbilities {
-                    command_permission_level: cmd_perm,
-                    permission_level: perm,
-                    entity_unique_id,
-                    gamemode,
-                },
-            )
-            .await;
+            self.send_abilities(target_addr).await;
+            self.apply_gamemode_state(target_addr).await;
 
             messages.push(format!("Set {target_name}'s game mode to {mode_name}"));
         }
@@ -461,7 +497,7 @@ impl ConnectionHandler {
     }
 
     /// Perform the actual teleport for a single player.
-    async fn teleport_player(
+    pub(super) async fn teleport_player(
         &mut self,
         target_addr: SocketAddr,
         target_name: &str,
@@ -513,28 +549,34 @@ impl ConnectionHandler {
     /// /give <player> <item> [amount] [metadata]
     async fn cmd_give(&mut self, sender_addr: SocketAddr, args: &[String]) -> CommandResult {
         if args.len() < 2 {
-            return CommandResult::err("Usage: /give <player> <item> [amount] [metadata]");
+            return CommandResult::err("Usage: /give <player> <item> [amount] [metadata] [nbt]");
         }
 
+        // NBT may also follow the item name directly: player_head{owner:Steve}
+        let (item_arg, inline_nbt, rest) = match skull::split_item_nbt(&args[1..]) {
+            Some((item, nbt, rest)) => (item, Some(nbt), rest),
+            None => (args[1].as_str(), None, &args[2..]),
+        };
+
         let targets = match self.resolve_target(&args[0], sender_addr) {
             Ok(t) => t,
             Err(e) => return CommandResult::err(e),
         };
 
         // Normalize item name: add "minecraft:" prefix if missing
-        let item_name = if args[1].contains(':') {
-            args[1].clone()
+        let item_name = if item_arg.contains(':') {
+            item_arg.to_string()
         } else {
-            format!("minecraft:{}", args[1])
+            format!("minecraft:{item_arg}")
         };
 
         let item_info = match self.item_registry.get_by_name(&item_name) {
             Some(info) => info.clone(),
-            None => return CommandResult::err(format!("Unknown item: {}", args[1])),
+            None => return CommandResult::err(format!("Unknown item: {item_arg}")),
         };
 
-        let amount = if args.len() >= 3 {
-            match args[2].parse::<u16>() {
+        let amount = if let Some(amount) = rest.first() {
+            match amount.parse::<u16>() {
                 Ok(a) if (1..=255).contains(&a) => a,
                 _ => return CommandResult::err("Amount must be 1-255"),
             }
@@ -542,8 +584,8 @@ impl ConnectionHandler {
             1
         };
 
-        let metadata = if args.len() >= 4 {
-            match args[3].parse::<u16>() {
+        let metadata = if let Some(metadata) = rest.get(1) {
+            match metadata.parse::<u16>() {
                 Ok(m) => m,
                 _ => return CommandResult::err("Invalid metadata value"),
             }
@@ -551,6 +593,29 @@ impl ConnectionHandler {
             0
         };
 
+        // Item NBT as SNBT, e.g. {display:{Name:"Excalibur"}}. Adventure mode
+        // CanPlaceOn/CanDestroy lists are taken out into the item itself.
+        let snbt = inline_nbt.or_else(|| (rest.len() >= 3).then(|| rest[2..].join(" ")));
+        let (nbt_data, can_place_on, can_destroy) = if let Some(snbt) = snbt {
+            match mc_rs_nbt::parse_snbt(&snbt) {
+                Ok(NbtTag::Compound(mut c)) => {
+                    let (can_place_on, can_destroy) = adventure::take_block_lists(&mut c);
+                    if item_name == "minecraft:player_head" {
+                        self.resolve_head_owner(&mut c);
+                    }
+                    let mut buf = Vec::new();
+                    if !c.is_empty() {
+                        mc_rs_nbt::write_nbt_network(&mut buf, &NbtRoot::new("", c));
+                    }
+                    (buf, can_place_on, can_destroy)
+                }
+                Ok(_) => return CommandResult::err("Item NBT must be a compound"),
+                Err(e) => return CommandResult::err(format!("Invalid item NBT: {e}")),
+            }
+        } else {
+            (Vec::new(), Vec::new(), Vec::new())
+        };
+
         let mut messages = Vec::new();
 
         for target_name in &targets {
@@ -582,12 +647,15 @@ impl ConnectionHandler {
                 None => continue,
             };
 
-            let item = mc_rs_proto::item_stack::ItemStack::new_with_meta(
+            let mut item = mc_rs_proto::item_stack::ItemStack::new_with_meta(
                 item_info.numeric_id as i32,
                 amount,
                 metadata,
                 stack_id,
             );
+            item.nbt_data = nbt_data.clone();
+            item.can_place_on = can_place_on.clone();
+            item.can_destroy = can_destroy.clone();
 
             // Set in server inventory
             if let Some(conn) = self.connections.get_mut(&target_addr) {
@@ -617,6 +685,164 @@ impl ConnectionHandler {
         }
     }
 
+    /// /kit [name] — list kits, or claim one (subject to its cooldown).
+    async fn cmd_kit(
+        &mut self,
+        sender_addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        let is_op = self.permissions.is_op(sender_name);
+
+        if args.is_empty() {
+            let mut names: Vec<String> = self
+                .kits
+                .names()
+                .into_iter()
+                .chain(self.plugin_manager.plugin_kits.keys().cloned())
+                .filter(|n| is_op || self.find_kit(n).is_some_and(|k| !k.op_only))
+                .collect();
+            names.sort();
+            names.dedup();
+            if names.is_empty() {
+                return CommandResult::ok("No kits are available");
+            }
+            return CommandResult::ok(format!("Available kits: {}", names.join(", ")));
+        }
+
+        let name = &args[0];
+        let kit = match self.find_kit(name) {
+            Some(k) => k.clone(),
+            None => return CommandResult::err(format!("Unknown kit: {name}")),
+        };
+        if kit.op_only && !is_op {
+            return CommandResult::err("You do not have permission to claim this kit");
+        }
+
+        let now = crate::persistence::unix_timestamp();
+        let last_claim = self
+            .connections
+            .get(&sender_addr)
+            .and_then(|c| c.kit_cooldowns.get(name.as_str()).copied());
+        if let Some(remaining) = kit.cooldown_remaining(last_claim, now) {
+            return CommandResult::err(format!(
+                "You must wait {remaining}s before claiming kit {name} again"
+            ));
+        }
+
+        // Nothing is given, and the cooldown doesn't start, unless it all fits
+        if self.grant_kit_items(sender_addr, &kit).is_none() {
+            return CommandResult::err("Your inventory doesn't have room for this kit");
+        }
+        if let Some(conn) = self.connections.get_mut(&sender_addr) {
+            conn.kit_cooldowns.insert(name.clone(), now);
+        }
+        self.send_inventory(sender_addr).await;
+
+        CommandResult::ok(format!("Claimed kit {name}"))
+    }
+
+    /// Look up a kit by name: server-defined kits first, then plugin-registered ones.
+    fn find_kit(&self, name: &str) -> Option<&KitDefinition> {
+        self.kits
+            .get(name)
+            .or_else(|| self.plugin_manager.plugin_kits.get(name))
+    }
+
+    /// Put a kit's items into empty main-inventory slots, split into stacks
+    /// the items allow. Unknown items are skipped. Returns the number of
+    /// stacks given, or `None` (giving nothing) if they don't all fit.
+    ///
+    /// Only updates server-side state; the caller is responsible for syncing the inventory.
+    pub(super) fn grant_kit_items(
+        &mut self,
+        addr: SocketAddr,
+        kit: &KitDefinition,
+    ) -> Option<usize> {
+        let mut stacks = Vec::new();
+        for entry in &kit.items {
+            let item_name = if entry.item.contains(':') {
+                entry.item.clone()
+            } else {
+                format!("minecraft:{}", entry.item)
+            };
+            let Some(info) = self.item_registry.get_by_name(&item_name) else {
+                warn!("Kit item {item_name} is not a known item, skipping");
+                continue;
+            };
+            for count in entry.stack_counts(info.max_stack_size as u16) {
+                stacks.push((info.numeric_id as i32, count, entry.metadata));
+            }
+        }
+
+        let conn = self.connections.get_mut(&addr)?;
+        let free: Vec<usize> = conn
+            .inventory
+            .main
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.is_empty())
+            .map(|(i, _)| i)
+            .collect();
+        if free.len() < stacks.len() {
+            return None;
+        }
+        for (&slot, &(numeric_id, count, metadata)) in free.iter().zip(&stacks) {
+            let stack_id = conn.inventory.next_stack_network_id();
+            let item = mc_rs_proto::item_stack::ItemStack::new_with_meta(
+                numeric_id, count, metadata, stack_id,
+            );
+            conn.inventory.set_slot(0, slot as u8, item);
+        }
+        Some(stacks.len())
+    }
+
+    /// /stats [player] — show a player's statistics (default = self).
+    fn cmd_stats(&self, sender_name: &str, args: &[String]) -> CommandResult {
+        let target = args.first().map(String::as_str).unwrap_or(sender_name);
+        let Some(stats) = self
+            .find_player_addr(target)
+            .and_then(|a| self.connections.get(&a))
+            .map(|c| &c.stats)
+        else {
+            return CommandResult::err(format!("Player not found: {target}"));
+        };
+
+        let describe = |counts: &HashMap<String, u64>| {
+            crate::stats::top_entries(counts, 3)
+                .into_iter()
+                .map(|(name, n)| format!("{} x{n}", name.trim_start_matches("minecraft:")))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut messages = vec![format!("Statistics for {target}:")];
+        let mut line = format!("Blocks mined: {}", stats.total_blocks_mined());
+        if !stats.blocks_mined.is_empty() {
+            line.push_str(&format!(" ({})", describe(&stats.blocks_mined)));
+        }
+        messages.push(line);
+        let mut line = format!("Mobs killed: {}", stats.total_mobs_killed());
+        if !stats.mobs_killed.is_empty() {
+            line.push_str(&format!(" ({})", describe(&stats.mobs_killed)));
+        }
+        messages.push(line);
+        messages.push(format!(
+            "Distance walked: {} blocks",
+            stats.distance_walked_blocks()
+        ));
+        messages.push(format!("Deaths: {}", stats.deaths));
+        messages.push(format!(
+            "Playtime: {}",
+            crate::stats::format_duration(stats.playtime_secs())
+        ));
+        CommandResult {
+            success: true,
+            messages,
+            broadcast: None,
+            should_stop: false,
+        }
+    }
+
     /// /kill [player] (default = self)
     async fn cmd_kill(
         &mut self,
@@ -649,10 +875,13 @@ impl ConnectionHandler {
                 None => continue,
             };
 
-            // Set health to 0 and mark as dead
+            // Drop loot, set health to 0 and mark as dead
+            self.drop_death_loot(target_addr).await;
             if let Some(conn) = self.connections.get_mut(&target_addr) {
-                conn.health = 0.0;
+                self.game_world
+                    .set_player_health(conn.entity_unique_id, 0.0);
                 conn.is_dead = true;
+                conn.stats.deaths += 1;
             }
 
             // Send health=0 to the victim
@@ -743,10 +972,34 @@ impl ConnectionHandler {
         }
     }
 
-    /// /op <player>
-    async fn cmd_op(&mut self, sender_addr: SocketAddr, args: &[String]) -> CommandResult {
-        if args.is_empty() {
-            return CommandResult::err("Usage: /op <player>");
+    /// /op <player> [level]
+    async fn cmd_op(
+        &mut self,
+        sender_addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        if args.is_empty() || args.len() > 2 {
+            return CommandResult::err("Usage: /op <player> [level]");
+        }
+
+        // Players can't grant more than their own level
+        let max_level = if sender_name == functions::SERVER_SENDER {
+            MAX_OP_LEVEL
+        } else {
+            self.permissions.op_level(sender_name)
+        };
+        let level = match args.get(1) {
+            Some(arg) => match arg.parse::<u8>() {
+                Ok(l) if (1..=MAX_OP_LEVEL).contains(&l) => l,
+                _ => return CommandResult::err(format!("Op level must be 1-{MAX_OP_LEVEL}")),
+            },
+            None => max_level,
+        };
+        if level > max_level {
+            return CommandResult::err(format!(
+                "You can't grant an op level above your own ({max_level})"
+            ));
         }
 
         let targets = match self.resolve_target(&args[0], sender_addr) {
@@ -765,26 +1018,11 @@ impl ConnectionHandler {
                 }
             };
 
-            self.permissions.ops.insert(target_name.clone());
+            self.permissions.ops.insert(target_name.clone(), level);
             self.permissions.save_ops();
+            self.send_abilities(target_addr).await;
 
-            let (entity_unique_id, gamemode) = {
-                let conn = &self.connections[&target_addr];
-                (conn.entity_unique_id, conn.gamemode)
-            };
-            self.send_packet(
-                target_addr,
-                packets::id::UPDATE_ABILITIES,
-                &UpdateAbilities {
-                    command_permission_level: 1,
-                    permission_level: 2,
-                    entity_unique_id,
-                    gamemode,
-                },
-            )
-            .await;
-
-            messages.push(format!("Opped {target_name}"));
+            messages.push(format!("Opped {target_name} (level {level})"));
         }
 
         CommandResult {
@@ -819,22 +1057,7 @@ impl ConnectionHandler {
 
             self.permissions.ops.remove(target_name.as_str());
             self.permissions.save_ops();
-
-            let (entity_unique_id, gamemode) = {
-                let conn = &self.connections[&target_addr];
-                (conn.entity_unique_id, conn.gamemode)
-            };
-            self.send_packet(
-                target_addr,
-                packets::id::UPDATE_ABILITIES,
-                &UpdateAbilities {
-                    command_permission_level: 0,
-                    permission_level: 1,
-                    entity_unique_id,
-                    gamemode,
-                },
-            )
-            .await;
+            self.send_abilities(target_addr).await;
 
             messages.push(format!("De-opped {target_name}"));
         }
@@ -1026,6 +1249,42 @@ impl ConnectionHandler {
         }
     }
 
+    /// /permission <grant|revoke|list> <player> [node]
+    pub(super) fn cmd_permission(&mut self, args: &[String]) -> CommandResult {
+        const USAGE: &str = "Usage: /permission <grant|revoke|list> <player> [node]";
+        match args {
+            [action, name, node] if action == "grant" => {
+                let nodes = self.permissions.nodes.entry(name.clone()).or_default();
+                if !nodes.insert(node.clone()) {
+                    return CommandResult::err(format!("{name} already has {node}"));
+                }
+                self.permissions.save_nodes();
+                CommandResult::ok(format!("Granted {node} to {name}"))
+            }
+            [action, name, node] if action == "revoke" => {
+                let Some(nodes) = self.permissions.nodes.get_mut(name.as_str()) else {
+                    return CommandResult::err(format!("{name} does not have {node}"));
+                };
+                if !nodes.remove(node.as_str()) {
+                    return CommandResult::err(format!("{name} does not have {node}"));
+                }
+                if nodes.is_empty() {
+                    self.permissions.nodes.remove(name.as_str());
+                }
+                self.permissions.save_nodes();
+                CommandResult::ok(format!("Revoked {node} from {name}"))
+            }
+            [action, name] if action == "list" => match self.permissions.nodes.get(name.as_str()) {
+                Some(nodes) => CommandResult::ok(format!(
+                    "Permissions of {name}: {}",
+                    nodes.iter().cloned().collect::<Vec<_>>().join(", ")
+                )),
+                None => CommandResult::ok(format!("{name} has no permissions")),
+            },
+            _ => CommandResult::err(USAGE),
+        }
+    }
+
     /// /summon <entity_type> [x y z]
     fn cmd_summon(&mut self, sender_addr: SocketAddr, args: &[String]) -> CommandResult {
         if args.is_empty() {
@@ -1277,10 +1536,13 @@ impl ConnectionHandler {
     // /time command
     // -----------------------------------------------------------------------
 
-    async fn cmd_time(&mut self, _addr: SocketAddr, args: &[String]) -> CommandResult {
+    /// `/time`, acting on the world the sender is in.
+    async fn cmd_time(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
         if args.is_empty() {
-            return CommandResult::err("Usage: /time set <value>|day|noon|night|midnight|sunrise|sunset\n/time add <value>\n/time query daytime|gametime");
+            return CommandResult::err("Usage: /time set <value>|day|noon|night|midnight|sunrise|sunset\n/time add <value>\n/time query daytime|gametime|day");
         }
+        let dim = self.connections.get(&addr).map_or(0, |c| c.dimension);
+        let world_time = self.world_time(dim);
 
         match args[0].as_str() {
             "set" => {
@@ -1310,12 +1572,8 @@ impl ConnectionHandler {
                         return CommandResult::ok("Time change cancelled by plugin".to_string());
                     }
                 }
-                self.world_time = time;
-                let pkt = SetTime {
-                    time: self.world_time as i32,
-                };
-                self.broadcast_packet(packets::id::SET_TIME, &pkt).await;
-                CommandResult::ok(format!("Set the time to {}", self.world_time))
+                self.set_world_time(dim, time).await;
+                CommandResult::ok(format!("Set the time to {time}"))
             }
             "add" => {
                 if args.len() < 2 {
@@ -1325,7 +1583,7 @@ impl ConnectionHandler {
                     Ok(v) => v,
                     Err(_) => return CommandResult::err(format!("Invalid amount: {}", args[1])),
                 };
-                let new_time = self.world_time + amount;
+                let new_time = world_time + amount;
                 // Plugin event: TimeChange (cancellable)
                 {
                     let event = PluginEvent::TimeChange { new_time };
@@ -1336,25 +1594,22 @@ impl ConnectionHandler {
                         return CommandResult::ok("Time change cancelled by plugin".to_string());
                     }
                 }
-                self.world_time = new_time;
-                let pkt = SetTime {
-                    time: self.world_time as i32,
-                };
-                self.broadcast_packet(packets::id::SET_TIME, &pkt).await;
-                CommandResult::ok(format!(
-                    "Added {} to the time (now {})",
-                    amount, self.world_time
-                ))
+                self.set_world_time(dim, new_time).await;
+                CommandResult::ok(format!("Added {amount} to the time (now {new_time})"))
             }
             "query" => {
                 if args.len() < 2 {
-                    return CommandResult::err("Usage: /time query daytime|gametime");
+                    return CommandResult::err("Usage: /time query daytime|gametime|day");
                 }
                 match args[1].as_str() {
                     "daytime" => {
-                        let daytime = self.world_time % 24000;
+                        let daytime = world_time.rem_euclid(24000);
                         CommandResult::ok(format!("The time is {daytime}"))
                     }
+                    "day" => {
+                        let day = world_time.div_euclid(24000);
+                        CommandResult::ok(format!("The day is {day}"))
+                    }
                     "gametime" => {
                         let total = self.game_world.current_tick();
                         CommandResult::ok(format!("The game time is {total}"))
@@ -1438,6 +1693,42 @@ impl ConnectionHandler {
         }
     }
 
+    // -----------------------------------------------------------------------
+    // /difficulty command
+    // -----------------------------------------------------------------------
+
+    /// Change the world difficulty: tell every client, toggle hostile
+    /// spawning, and clear hostile mobs when going to Peaceful.
+    pub(super) async fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+        self.game_world.spawn_config.spawn_hostiles = difficulty.allows_hostile_mobs();
+        if !difficulty.allows_hostile_mobs() {
+            let removed = self.game_world.remove_hostile_mobs();
+            if removed > 0 {
+                debug!("Removed {removed} hostile mob(s) for Peaceful");
+            }
+        }
+        let pkt = SetDifficulty {
+            difficulty: difficulty.id() as u32,
+        };
+        self.broadcast_packet(packets::id::SET_DIFFICULTY, &pkt)
+            .await;
+    }
+
+    async fn cmd_difficulty(&mut self, args: &[String]) -> CommandResult {
+        match args {
+            [] => CommandResult::ok(format!("The difficulty is {}", self.difficulty.name())),
+            [value] => match Difficulty::from_name(value) {
+                Some(difficulty) => {
+                    self.set_difficulty(difficulty).await;
+                    CommandResult::ok(format!("Set the difficulty to {}", difficulty.name()))
+                }
+                None => CommandResult::err(format!("Unknown difficulty: {value}")),
+            },
+            _ => CommandResult::err("Usage: /difficulty [peaceful|easy|normal|hard]"),
+        }
+    }
+
     // -----------------------------------------------------------------------
     // /gamerule command
     // -----------------------------------------------------------------------
@@ -1445,11 +1736,14 @@ impl ConnectionHandler {
     async fn cmd_gamerule(&mut self, _addr: SocketAddr, args: &[String]) -> CommandResult {
         if args.is_empty() {
             return CommandResult::err(
-                "Usage: /gamerule <rule> [value]\nAvailable: doDaylightCycle, doWeatherCycle",
+                "Usage: /gamerule <rule> [value]\nAvailable: doDaylightCycle, doWeatherCycle, keepInventory, commandBlocksEnabled, playersSleepingPercentage, showCoordinates, showDaysPlayed, showTags, showBorderEffect",
             );
         }
 
         let rule_name = &args[0];
+        if let Some(rule) = client_game_rule(rule_name) {
+            return self.cmd_client_gamerule(rule, args.get(1)).await;
+        }
         let canonical = rule_name.to_lowercase();
 
         if args.len() == 1 {
@@ -1461,8 +1755,43 @@ impl ConnectionHandler {
                 "doweathercycle" => {
                     CommandResult::ok(format!("doWeatherCycle = {}", self.do_weather_cycle))
                 }
+                "keepinventory" => {
+                    CommandResult::ok(format!("keepInventory = {}", self.keep_inventory))
+                }
+                "commandblocksenabled" => CommandResult::ok(format!(
+                    "commandBlocksEnabled = {}",
+                    self.command_blocks_enabled
+                )),
+                "playerssleepingpercentage" => CommandResult::ok(format!(
+                    "playersSleepingPercentage = {}",
+                    self.players_sleeping_percentage
+                )),
                 _ => CommandResult::err(format!("Unknown game rule: {rule_name}")),
             }
+        } else if canonical == "playerssleepingpercentage" {
+            // The only integer rule
+            let value_str = &args[1];
+            let value: i32 = match value_str.parse() {
+                Ok(v) if v >= 0 => v,
+                _ => {
+                    return CommandResult::err(format!(
+                        "Invalid value: {value_str} (expected a number from 0)"
+                    ))
+                }
+            };
+            self.players_sleeping_percentage = value;
+            let pkt = GameRulesChanged {
+                rules: vec![GameRule {
+                    name: "playerssleepingpercentage".into(),
+                    editable: false,
+                    value: GameRuleValue::Int(value),
+                }],
+            };
+            self.broadcast_packet(packets::id::GAME_RULES_CHANGED, &pkt)
+                .await;
+            CommandResult::ok(format!(
+                "Game rule playersSleepingPercentage set to {value}"
+            ))
         } else {
             // Set mode
             let value_str = &args[1];
@@ -1503,39 +1832,37 @@ impl ConnectionHandler {
                         .await;
                     CommandResult::ok(format!("Game rule doWeatherCycle set to {value}"))
                 }
+                "keepinventory" => {
+                    self.keep_inventory = value;
+                    let pkt = GameRulesChanged {
+                        rules: vec![GameRule {
+                            name: "keepinventory".into(),
+                            editable: false,
+                            value: GameRuleValue::Bool(value),
+                        }],
+                    };
+                    self.broadcast_packet(packets::id::GAME_RULES_CHANGED, &pkt)
+                        .await;
+                    CommandResult::ok(format!("Game rule keepInventory set to {value}"))
+                }
+                "commandblocksenabled" => {
+                    self.command_blocks_enabled = value;
+                    let pkt = GameRulesChanged {
+                        rules: vec![GameRule {
+                            name: "commandblocksenabled".into(),
+                            editable: false,
+                            value: GameRuleValue::Bool(value),
+                        }],
+                    };
+                    self.broadcast_packet(packets::id::GAME_RULES_CHANGED, &pkt)
+                        .await;
+                    CommandResult::ok(format!("Game rule commandBlocksEnabled set to {value}"))
+                }
                 _ => CommandResult::err(format!("Unknown game rule: {rule_name}")),
             }
         }
     }
 
-    // -----------------------------------------------------------------------
-    // Phase 4.2/4.3: /reload (hot-reload plugins)
-    // -----------------------------------------------------------------------
-
-    async fn cmd_reload(&mut self, _addr: SocketAddr) -> CommandResult {
-        self.plugin_manager.reload();
-
-        let plugins_dir = std::path::PathBuf::from("plugins");
-        std::fs::create_dir_all(&plugins_dir).ok();
-        let engine = mc_rs_plugin_wasm::create_engine();
-        let wasm_plugins = mc_rs_plugin_wasm::load_wasm_plugins(&plugins_dir, &engine);
-        for plugin in wasm_plugins {
-            self.plugin_manager.register(plugin);
-        }
-        let lua_plugins = mc_rs_plugin_lua::load_lua_plugins(&plugins_dir);
-        for plugin in lua_plugins {
-            self.plugin_manager.register(plugin);
-        }
-        let snapshot = self.build_snapshot();
-        self.plugin_manager.enable_all(&snapshot);
-        self.plugin_manager.load_configs();
-
-        let cmd_count = self.plugin_manager.plugin_commands.len();
-        CommandResult::ok(format!(
-            "Plugins reloaded. {cmd_count} plugin command(s) registered."
-        ))
-    }
-
     // -----------------------------------------------------------------------
     // Phase 5.2: /setblock
     // -----------------------------------------------------------------------
@@ -1543,7 +1870,7 @@ impl ConnectionHandler {
     async fn cmd_setblock(&mut self, _addr: SocketAddr, args: &[String]) -> CommandResult {
         if args.len() < 4 {
             return CommandResult::err(
-                "Usage: /setblock <x> <y> <z> <block> [replace|destroy|keep]",
+                "Usage: /setblock <x> <y> <z> <block>[{nbt}] [replace|destroy|keep]",
             );
         }
         let x: i32 = match args[0].parse() {
@@ -1559,17 +1886,32 @@ impl ConnectionHandler {
             Err(_) => return CommandResult::err("Invalid z coordinate"),
         };
 
-        let block_name = if args[3].contains(':') {
-            args[3].clone()
+        // The block may be followed by block entity NBT as SNBT, which can
+        // contain spaces: `chest{Items:[...]}`
+        let rest = args[3..].join(" ");
+        let name_end = rest.find(['{', ' ']).unwrap_or(rest.len());
+        let (block_arg, rest) = rest.split_at(name_end);
+        let (block_nbt, rest) = if rest.starts_with('{') {
+            match mc_rs_nbt::snbt::parse_snbt_prefix(rest) {
+                Ok((NbtTag::Compound(c), rest)) => (Some(c), rest),
+                Ok(_) => return CommandResult::err("Block NBT must be a compound"),
+                Err(e) => return CommandResult::err(format!("Invalid block NBT: {e}")),
+            }
         } else {
-            format!("minecraft:{}", args[3])
+            (None, rest)
+        };
+
+        let block_name = if block_arg.contains(':') {
+            block_arg.to_string()
+        } else {
+            format!("minecraft:{block_arg}")
         };
         let rid = hash_block_state(&block_name);
         if rid == self.tick_blocks.air && block_name != "minecraft:air" {
-            return CommandResult::err(format!("Unknown block: {}", args[3]));
+            return CommandResult::err(format!("Unknown block: {block_arg}"));
         }
 
-        let mode = args.get(4).map(|s| s.as_str()).unwrap_or("replace");
+        let mode = rest.split_whitespace().next().unwrap_or("replace");
 
         match mode {
             "keep" => {
@@ -1591,7 +1933,35 @@ impl ConnectionHandler {
             _ => return CommandResult::err("Mode must be replace, destroy, or keep"),
         }
 
+        let block_entity = match &block_nbt {
+            Some(patch) => {
+                let base = self.block_entities.get(&(x, y, z, 0));
+                match BlockEntityData::with_nbt(base, (x, y, z), patch) {
+                    Some(data) => Some(data),
+                    None => {
+                        return CommandResult::err(
+                            "Block NBT must set a known block entity id for this block",
+                        )
+                    }
+                }
+            }
+            None => None,
+        };
+
         self.set_block_and_broadcast(x, y, z, rid).await;
+        if let Some(data) = block_entity {
+            self.remove_block_entity((x, y, z));
+            let nbt = data.to_network_nbt(x, y, z);
+            self.insert_block_entity((x, y, z), data);
+            self.broadcast_packet(
+                packets::id::BLOCK_ACTOR_DATA,
+                &BlockActorData {
+                    position: BlockPos::new(x, y, z),
+                    nbt_data: nbt,
+                },
+            )
+            .await;
+        }
         self.schedule_fluid_neighbors(x, y, z);
         self.schedule_piston_neighbors(x, y, z);
         self.update_redstone_from(x, y, z).await;
@@ -2542,11 +2912,14 @@ impl ConnectionHandler {
         }
     }
 
+    /// /execute ... run <command>: the command runs as the executor, with the
+    /// permissions of the sender (`op_level`).
     async fn cmd_execute(
         &mut self,
         addr: SocketAddr,
         sender_name: &str,
         args: &[String],
+        op_level: u8,
     ) -> CommandResult {
         if args.is_empty() {
             return CommandResult::err("Usage: /execute <as|at|positioned|if|unless|run> ...");
@@ -2559,8 +2932,15 @@ impl ConnectionHandler {
             .unwrap_or((0.0, 0.0, 0.0));
 
         let mut results = Vec::new();
-        self.execute_chain(args, sender_name.to_string(), addr, pos, &mut results)
-            .await;
+        self.execute_chain(
+            args,
+            sender_name.to_string(),
+            addr,
+            pos,
+            op_level,
+            &mut results,
+        )
+        .await;
 
         if results.is_empty() {
             CommandResult::ok("Execute: no results")
@@ -2575,6 +2955,7 @@ impl ConnectionHandler {
         executor_name: String,
         executor_addr: SocketAddr,
         position: (f32, f32, f32),
+        op_level: u8,
         results: &'a mut Vec<String>,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send + 'a>> {
         Box::pin(async move {
@@ -2604,8 +2985,15 @@ impl ConnectionHandler {
                             .get(&target_addr)
                             .map(|c| (c.position.x, c.position.y, c.position.z))
                             .unwrap_or(position);
-                        self.execute_chain(rest, target_name, target_addr, target_pos, results)
-                            .await;
+                        self.execute_chain(
+                            rest,
+                            target_name,
+                            target_addr,
+                            target_pos,
+                            op_level,
+                            results,
+                        )
+                        .await;
                     }
                 }
                 "at" => {
@@ -2633,6 +3021,7 @@ impl ConnectionHandler {
                                 executor_name.clone(),
                                 executor_addr,
                                 target_pos,
+                                op_level,
                                 results,
                             )
                             .await;
@@ -2648,50 +3037,44 @@ impl ConnectionHandler {
                     let y = self.parse_relative_coord(&args[2], position.1);
                     let z = self.parse_relative_coord(&args[3], position.2);
                     let rest = &args[4..];
-                    self.execute_chain(rest, executor_name, executor_addr, (x, y, z), results)
-                        .await;
+                    self.execute_chain(
+                        rest,
+                        executor_name,
+                        executor_addr,
+                        (x, y, z),
+                        op_level,
+                        results,
+                    )
+                    .await;
                 }
                 "if" | "unless" => {
-                    // /execute if block <x> <y> <z> <block> ...
-                    if args.len() < 6 || args[1] != "block" {
-                        results
-                            .push("Error: /execute if|unless block <x> <y> <z> <block> ...".into());
-                        return;
-                    }
-                    let bx: i32 = match args[2].parse() {
-                        Ok(v) => v,
-                        Err(_) => {
-                            results.push("Error: invalid x coordinate".into());
-                            return;
-                        }
-                    };
-                    let by: i32 = match args[3].parse() {
-                        Ok(v) => v,
-                        Err(_) => {
-                            results.push("Error: invalid y coordinate".into());
-                            return;
-                        }
-                    };
-                    let bz: i32 = match args[4].parse() {
-                        Ok(v) => v,
-                        Err(_) => {
-                            results.push("Error: invalid z coordinate".into());
+                    let (matched, used) = match self.execute_condition(
+                        &args[1..],
+                        &executor_name,
+                        executor_addr,
+                        position,
+                    ) {
+                        Ok(c) => c,
+                        Err(e) => {
+                            results.push(format!("Error: {e}"));
                             return;
                         }
                     };
-                    let block_name = if args[5].contains(':') {
-                        args[5].clone()
-                    } else {
-                        format!("minecraft:{}", args[5])
-                    };
-                    let expected_rid = hash_block_state(&block_name);
-                    let actual_rid = self.get_block(bx, by, bz).unwrap_or(0);
-                    let matches = actual_rid == expected_rid;
-                    let should_continue = if sub == "if" { matches } else { !matches };
-                    if should_continue {
-                        let rest = &args[6..];
-                        self.execute_chain(rest, executor_name, executor_addr, position, results)
-                            .await;
+                    let passed = if sub == "if" { matched } else { !matched };
+                    let rest = &args[1 + used..];
+                    if rest.is_empty() {
+                        // A trailing condition reports its outcome
+                        results.push(if passed { "Test passed" } else { "Test failed" }.into());
+                    } else if passed {
+                        self.execute_chain(
+                            rest,
+                            executor_name,
+                            executor_addr,
+                            position,
+                            op_level,
+                            results,
+                        )
+                        .await;
                     }
                 }
                 "run" => {
@@ -2699,45 +3082,11 @@ impl ConnectionHandler {
                         results.push("Error: /execute run <command> ...".into());
                         return;
                     }
-                    // Build the command string and dispatch
                     let cmd_str = args[1..].join(" ");
-                    let mut parts = cmd_str.split_whitespace();
-                    let cmd_name = parts.next().unwrap_or("");
-                    let cmd_args: Vec<String> = parts.map(String::from).collect();
-
-                    let result = match cmd_name {
-                        "setblock" => self.cmd_setblock(executor_addr, &cmd_args).await,
-                        "fill" => self.cmd_fill(executor_addr, &cmd_args).await,
-                        "clone" => self.cmd_clone(executor_addr, &cmd_args).await,
-                        "title" => {
-                            self.cmd_title(executor_addr, &executor_name, &cmd_args)
-                                .await
-                        }
-                        "particle" => self.cmd_particle(executor_addr, &cmd_args).await,
-                        "playsound" => {
-                            self.cmd_playsound(executor_addr, &executor_name, &cmd_args)
-                                .await
-                        }
-                        "say" => {
-                            let msg = cmd_args.join(" ");
-                            let text = Text::raw(format!("[{executor_name}] {msg}"));
-                            self.broadcast_packet(packets::id::TEXT, &text).await;
-                            CommandResult::ok(format!("Said: {msg}"))
-                        }
-                        "kill" => {
-                            self.cmd_kill(executor_addr, &executor_name, &cmd_args)
-                                .await
-                        }
-                        "give" => self.cmd_give(executor_addr, &cmd_args).await,
-                        "tp" => self.cmd_tp(executor_addr, &executor_name, &cmd_args).await,
-                        "effect" => {
-                            self.cmd_effect(executor_addr, &executor_name, &cmd_args)
-                                .await
-                        }
-                        _ => CommandResult::err(format!(
-                            "Unknown command in execute run: {cmd_name}"
-                        )),
-                    };
+                    let result = self
+                        .dispatch_command(executor_addr, &executor_name, &cmd_str, op_level)
+                        .await;
+                    self.apply_command_effects(&executor_name, &result).await;
                     for msg in &result.messages {
                         results.push(msg.clone());
                     }
@@ -2749,6 +3098,122 @@ impl ConnectionHandler {
         })
     }
 
+    /// Evaluate an `/execute if|unless` condition. Returns whether it holds
+    /// and how many arguments it took.
+    ///
+    /// - `block <x> <y> <z> <block>`
+    /// - `entity <target>`
+    /// - `score <target> <objective> matches <range>`
+    /// - `score <target> <objective> <op> <source> <objective>`
+    fn execute_condition(
+        &self,
+        args: &[String],
+        executor_name: &str,
+        executor_addr: SocketAddr,
+        position: (f32, f32, f32),
+    ) -> Result<(bool, usize), String> {
+        const USAGE: &str = "/execute if|unless <block|entity|score> ...";
+        match args.first().map(|s| s.as_str()) {
+            Some("block") => {
+                if args.len() < 5 {
+                    return Err("/execute if|unless block <x> <y> <z> <block> ...".into());
+                }
+                let bx = self.parse_relative_coord(&args[1], position.0).floor() as i32;
+                let by = self.parse_relative_coord(&args[2], position.1).floor() as i32;
+                let bz = self.parse_relative_coord(&args[3], position.2).floor() as i32;
+                let block_name = if args[4].contains(':') {
+                    args[4].clone()
+                } else {
+                    format!("minecraft:{}", args[4])
+                };
+                let expected_rid = hash_block_state(&block_name);
+                let actual_rid = self.get_block(bx, by, bz).unwrap_or(0);
+                Ok((actual_rid == expected_rid, 5))
+            }
+            Some("entity") => {
+                if args.len() < 2 {
+                    return Err("/execute if|unless entity <target> ...".into());
+                }
+                let found = self
+                    .resolve_target(&args[1], executor_addr)
+                    .unwrap_or_default()
+                    .iter()
+                    .any(|name| self.find_player_addr(name).is_some());
+                Ok((found, 2))
+            }
+            Some("score") => {
+                if args.len() < 5 {
+                    return Err(
+                        "/execute if|unless score <target> <objective> <matches|op> ...".into(),
+                    );
+                }
+                let targets = self.score_holders(&args[1], executor_name, executor_addr)?;
+                let target_scores = self.holder_scores(&args[2], &targets)?;
+
+                if args[3] == "matches" {
+                    let range = ScoreRange::parse(&args[4])
+                        .ok_or_else(|| format!("Invalid score range: {}", args[4]))?;
+                    // Entries without a score never match
+                    let matched =
+                        target_scores.is_some_and(|v| every_score(&v, |s| range.contains(s)));
+                    return Ok((matched, 5));
+                }
+
+                let op = ScoreOperator::parse(&args[3])
+                    .ok_or_else(|| format!("Invalid score comparison: {}", args[3]))?;
+                if args.len() < 6 {
+                    return Err(
+                        "/execute if|unless score <target> <objective> <op> <source> <objective> ..."
+                            .into(),
+                    );
+                }
+                let sources = self.score_holders(&args[4], executor_name, executor_addr)?;
+                let source_scores = self.holder_scores(&args[5], &sources)?;
+                let matched = match (target_scores, source_scores) {
+                    (Some(targets), Some(sources)) => {
+                        every_score(&targets, |t| every_score(&sources, |s| op.compare(t, s)))
+                    }
+                    _ => false,
+                };
+                Ok((matched, 6))
+            }
+            _ => Err(USAGE.into()),
+        }
+    }
+
+    /// Names whose scores a score condition reads: a selector's players or a
+    /// literal name (which may be a fake player like `#timer`).
+    fn score_holders(
+        &self,
+        target: &str,
+        executor_name: &str,
+        executor_addr: SocketAddr,
+    ) -> Result<Vec<String>, String> {
+        if target == "@s" {
+            Ok(vec![executor_name.to_string()])
+        } else if mc_rs_command::selector::parse_selector(target).is_some() {
+            self.resolve_target(target, executor_addr)
+        } else {
+            Ok(vec![target.trim_matches('"').to_string()])
+        }
+    }
+
+    /// The scores of `holders` in `objective`, or `None` if one has no score.
+    fn holder_scores(
+        &self,
+        objective: &str,
+        holders: &[String],
+    ) -> Result<Option<Vec<i32>>, String> {
+        if !self.scoreboard_objectives.contains_key(objective) {
+            return Err(format!("Objective '{objective}' not found"));
+        }
+        let scores = self.scoreboard_scores.get(objective);
+        Ok(holders
+            .iter()
+            .map(|h| scores.and_then(|s| s.get(h)).copied())
+            .collect())
+    }
+
     /// Parse a coordinate that may use ~ for relative positioning.
     fn parse_relative_coord(&self, s: &str, base: f32) -> f32 {
         if let Some(rest) = s.strip_prefix('~') {
@@ -2781,12 +3246,10 @@ impl ConnectionHandler {
             Err(e) => return CommandResult::err(e),
         };
 
-        let pkt = Transfer::new(host.as_str(), port);
         let mut count = 0;
         for name in &targets {
             if let Some(player_addr) = self.find_player_addr(name) {
-                self.send_packet(player_addr, packets::id::TRANSFER, &pkt)
-                    .await;
+                self.transfer_player(player_addr, host, port).await;
                 count += 1;
             }
         }
@@ -2895,7 +3358,7 @@ impl ConnectionHandler {
 
         match mc_rs_world::bds_compat::import_bds_world(
             &bds_path,
-            &mut self.chunk_storage,
+            self.chunk_storage.as_mut(),
             self.dimension_id,
         ) {
             Ok(result) => CommandResult::ok(format!(
@@ -2918,7 +3381,6 @@ impl ConnectionHandler {
             return CommandResult::err(format!("Cannot create export directory: {e}"));
         }
 
-        let registry = mc_rs_world::block_state_registry::BlockStateRegistry::new();
         let empty = HashMap::new();
         let chunks = self.dim_chunks(self.dimension_id).unwrap_or(&empty);
 
@@ -2926,7 +3388,7 @@ impl ConnectionHandler {
             chunks,
             self.dimension_id,
             &export_path,
-            &registry,
+            &self.block_states,
         ) {
             Ok(result) => CommandResult::ok(format!(
                 "Exported {} chunks to BDS format at {}",
@@ -2935,4 +3397,102 @@ impl ConnectionHandler {
             Err(e) => CommandResult::err(format!("Export failed: {e}")),
         }
     }
+
+    /// /scriptevent <namespace:id> [message]
+    async fn cmd_scriptevent(&mut self, addr: SocketAddr, line: &str) -> CommandResult {
+        let (id, payload) = match plugins::parse_script_event(line) {
+            Some(Ok(parsed)) => parsed,
+            Some(Err(e)) => return CommandResult::err(e),
+            None => return CommandResult::err("Usage: /scriptevent <namespace:id> [message]"),
+        };
+        let source = match self.connections.get(&addr) {
+            Some(conn) => ScriptEventSource::Player(self.make_plugin_player(conn)),
+            None => ScriptEventSource::Server,
+        };
+        self.fire_script_event(id.clone(), payload, source).await;
+        CommandResult::ok(format!("Sent script event {id}"))
+    }
+}
+
+/// Op level needed to run a command from a player, 0 for everyone:
+/// 2 for gameplay and world editing, 3 for managing players, 4 for running
+/// the server. Console, functions and command blocks aren't checked.
+pub(super) fn required_op_level(command: &str) -> u8 {
+    match command {
+        "gamemode" | "tp" | "give" | "kill" | "summon" | "enchant" | "time" | "weather"
+        | "gamerule" | "difficulty" | "worldflag" | "world" | "setwarp" | "delwarp"
+        | "setblock" | "fill" | "clone" | "title" | "camera" | "fog" | "particle" | "playsound"
+        | "scoreboard" | "tag" | "bossbar" | "execute" | "tickingarea" | "scriptevent"
+        | "function" | "eco" | "hologram" | "npc" | "inputpermission" | "locate" => 2,
+        "kick" | "op" | "deop" | "ban" | "ban-ip" | "unban" | "unban-ip" | "whitelist"
+        | "permission" | "knockback" | "transfer" | "viewdistance" | "whois" | "mute"
+        | "unmute" | "slowmode" | "socialspy" => 3,
+        "stop" | "restart" | "reload" | "import" | "export" | "timings" => 4,
+        _ => 0,
+    }
+}
+
+/// Whether `pred` holds for every score of a score condition. A selector
+/// that matched nobody never passes.
+fn every_score(scores: &[i32], pred: impl Fn(i32) -> bool) -> bool {
+    !scores.is_empty() && scores.iter().all(|s| pred(*s))
+}
+
+/// Op level needed to run a command line: the level of its command and,
+/// for `/execute`, of the command it runs.
+pub(super) fn required_line_op_level(command_str: &str) -> u8 {
+    let parts: Vec<&str> = command_str.split_whitespace().collect();
+    let Some((name, args)) = parts.split_first() else {
+        return 0;
+    };
+    let level = required_op_level(name);
+    match args.iter().position(|a| *a == "run") {
+        Some(i) if *name == "execute" => {
+            level.max(required_line_op_level(&args[i + 1..].join(" ")))
+        }
+        _ => level,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn op_levels_gate_commands() {
+        assert_eq!(required_op_level("home"), 0);
+        assert_eq!(required_op_level("pay"), 0);
+        assert_eq!(required_op_level("arena"), 0);
+        assert_eq!(required_op_level("packs"), 0);
+        assert_eq!(required_op_level("eco"), 2);
+        assert_eq!(required_op_level("hologram"), 2);
+        assert_eq!(required_op_level("npc"), 2);
+        assert_eq!(required_op_level("camera"), 2);
+        assert_eq!(required_op_level("inputpermission"), 2);
+        assert_eq!(required_op_level("gamemode"), 2);
+        assert_eq!(required_op_level("ban"), 3);
+        assert_eq!(required_op_level("stop"), 4);
+    }
+
+    #[test]
+    fn execute_run_needs_the_level_of_its_command() {
+        assert_eq!(required_line_op_level("execute as @a run say hi"), 2);
+        assert_eq!(required_line_op_level("execute run stop"), 4);
+        assert_eq!(
+            required_line_op_level("execute as @a at @s run execute run op Steve"),
+            3
+        );
+        // A level 2 op may use /execute but not to stop the server
+        assert!(2 >= required_line_op_level("execute as @a run tp ~ ~1 ~"));
+        assert!(2 < required_line_op_level("execute run stop"));
+    }
+
+    #[test]
+    fn score_condition_fails_without_targets() {
+        let range = ScoreRange::parse("1..").unwrap();
+        assert!(every_score(&[3, 5], |s| range.contains(s)));
+        assert!(!every_score(&[3, 0], |s| range.contains(s)));
+        // `if score @a[tag=nobody] obj matches 1..` must not run
+        assert!(!every_score(&[], |s| range.contains(s)));
+    }
 }
@@ -0,0 +1,292 @@
+//! Camera instructions, plugin cutscenes, and `/camera`.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::packets::camera::{camera_ease_type, CAMERA_PRESET_FREE, VANILLA_CAMERA_PRESETS};
+use mc_rs_proto::packets::{
+    self, CameraEase, CameraFade, CameraInstruction, CameraPresets, CameraSet,
+};
+use mc_rs_proto::types::{Vec2, Vec3};
+
+use super::{parse_coords, ConnectionHandler};
+use crate::cutscenes::{Cutscene, CutsceneCue, CutsceneStep};
+
+impl ConnectionHandler {
+    /// Send a camera instruction, first telling the client about the presets
+    /// it refers to.
+    async fn send_camera(&mut self, addr: SocketAddr, instruction: &CameraInstruction) {
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        if !conn.camera_presets_sent {
+            conn.camera_presets_sent = true;
+            self.send_packet(addr, packets::id::CAMERA_PRESETS, &CameraPresets::vanilla())
+                .await;
+        }
+        self.send_packet(addr, packets::id::CAMERA_INSTRUCTION, instruction)
+            .await;
+    }
+
+    /// Give a player their camera back.
+    async fn clear_camera(&mut self, addr: SocketAddr) {
+        let clear = CameraInstruction {
+            clear: true,
+            ..Default::default()
+        };
+        self.send_camera(addr, &clear).await;
+    }
+
+    /// Play a cutscene to an online player, replacing the one they watch.
+    pub(super) fn play_cutscene(&mut self, player: &str, cutscene: Cutscene) -> Result<(), String> {
+        if self.find_player_addr(player).is_none() {
+            return Err(format!("{player} is not online"));
+        }
+        let now = self.game_world.current_tick();
+        self.cutscenes.start(player, cutscene, now);
+        Ok(())
+    }
+
+    /// Stop a player's cutscene and give them their camera back. Returns
+    /// whether they were watching one.
+    pub(super) async fn stop_cutscene(&mut self, player: &str) -> bool {
+        if self.cutscenes.stop(player).is_none() {
+            return false;
+        }
+        if let Some(addr) = self.find_player_addr(player) {
+            self.clear_camera(addr).await;
+        }
+        true
+    }
+
+    /// Run the steps of the cutscenes that are due.
+    pub(super) async fn tick_cutscenes(&mut self) {
+        let now = self.game_world.current_tick();
+        for (player, cue) in self.cutscenes.tick(now) {
+            let Some(addr) = self.find_player_addr(&player) else {
+                self.cutscenes.stop(&player);
+                continue;
+            };
+            let instruction = match cue {
+                CutsceneCue::Step(CutsceneStep::Camera {
+                    position: [x, y, z],
+                    rotation: [pitch, yaw],
+                    ease_type,
+                    ticks,
+                }) => CameraInstruction {
+                    set: Some(CameraSet {
+                        preset: CAMERA_PRESET_FREE,
+                        ease: (ticks > 0).then_some(CameraEase {
+                            ease_type,
+                            duration: ticks as f32 / 20.0,
+                        }),
+                        position: Some(Vec3::new(x, y, z)),
+                        rotation: Some(Vec2::new(pitch, yaw)),
+                        facing: None,
+                    }),
+                    ..Default::default()
+                },
+                CutsceneCue::Step(CutsceneStep::Fade {
+                    color: [r, g, b],
+                    times: [fade_in, hold, fade_out],
+                }) => CameraInstruction {
+                    fade: Some(CameraFade {
+                        times: Some((fade_in, hold, fade_out)),
+                        color: Some((r, g, b)),
+                    }),
+                    ..Default::default()
+                },
+                CutsceneCue::Step(CutsceneStep::Wait { .. }) => continue,
+                CutsceneCue::End => CameraInstruction {
+                    clear: true,
+                    ..Default::default()
+                },
+            };
+            self.send_camera(addr, &instruction).await;
+        }
+    }
+
+    /// `/camera <target> <clear|fade|set>`
+    pub(super) async fn cmd_camera(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        const USAGE: &str = "Usage: /camera <target> <clear|fade [time <in> <hold> <out>] [color <r> <g> <b>]|set <preset> [ease <secs> <type>] [pos <x> <y> <z>] [rot <pitch> <yaw>] [facing <x> <y> <z>]>";
+        let (Some(target), Some(action)) = (args.first(), args.get(1)) else {
+            return CommandResult::err(USAGE);
+        };
+        let instruction = match action.as_str() {
+            "clear" => CameraInstruction {
+                clear: true,
+                ..Default::default()
+            },
+            "fade" => match parse_fade(&args[2..]) {
+                Ok(fade) => CameraInstruction {
+                    fade: Some(fade),
+                    ..Default::default()
+                },
+                Err(e) => return CommandResult::err(e),
+            },
+            "set" => match parse_set(&args[2..]) {
+                Ok(set) => CameraInstruction {
+                    set: Some(set),
+                    ..Default::default()
+                },
+                Err(e) => return CommandResult::err(e),
+            },
+            _ => return CommandResult::err(USAGE),
+        };
+        let targets = match self.resolve_target(target, addr) {
+            Ok(t) => t,
+            Err(e) => return CommandResult::err(e),
+        };
+
+        let mut count = 0;
+        for name in &targets {
+            let Some(target_addr) = self.find_player_addr(name) else {
+                continue;
+            };
+            // The command takes the camera over from a cutscene
+            if action != "fade" {
+                self.cutscenes.stop(name);
+            }
+            self.send_camera(target_addr, &instruction).await;
+            count += 1;
+        }
+        CommandResult::ok(format!("Camera {action} sent to {count} player(s)"))
+    }
+}
+
+/// `[time <in> <hold> <out>] [color <r> <g> <b>]`, colors from 0 to 255.
+fn parse_fade(args: &[String]) -> Result<CameraFade, String> {
+    const USAGE: &str = "Usage: /camera <target> fade [time <in> <hold> <out>] [color <r> <g> <b>]";
+    let mut fade = CameraFade {
+        times: None,
+        color: None,
+    };
+    let mut rest = args;
+    while let Some((keyword, values)) = rest.split_first() {
+        let [a, b, c] = parse_floats::<3>(values).ok_or(USAGE)?;
+        match keyword.as_str() {
+            "time" if fade.times.is_none() => fade.times = Some((a, b, c)),
+            "color" if fade.color.is_none() => {
+                fade.color = Some((a / 255.0, b / 255.0, c / 255.0));
+            }
+            _ => return Err(USAGE.into()),
+        }
+        rest = &values[3..];
+    }
+    Ok(fade)
+}
+
+/// `<preset> [ease <secs> <type>] [pos <x> <y> <z>] [rot <pitch> <yaw>]
+/// [facing <x> <y> <z>]`
+fn parse_set(args: &[String]) -> Result<CameraSet, String> {
+    let Some((preset, mut rest)) = args.split_first() else {
+        return Err("Usage: /camera <target> set <preset> [ease <secs> <type>] [pos <x> <y> <z>] [rot <pitch> <yaw>] [facing <x> <y> <z>]".into());
+    };
+    let preset_name = if preset.contains(':') {
+        preset.clone()
+    } else {
+        format!("minecraft:{preset}")
+    };
+    let preset = VANILLA_CAMERA_PRESETS
+        .iter()
+        .position(|&p| p == preset_name)
+        .ok_or_else(|| format!("Unknown camera preset: {preset_name}"))?;
+    let mut set = CameraSet {
+        preset: preset as u32,
+        ease: None,
+        position: None,
+        rotation: None,
+        facing: None,
+    };
+    while let Some((keyword, values)) = rest.split_first() {
+        let used = match keyword.as_str() {
+            "ease" => {
+                let (Some(secs), Some(kind)) = (values.first(), values.get(1)) else {
+                    return Err("Usage: ease <secs> <type>".into());
+                };
+                let duration = secs
+                    .parse::<f32>()
+                    .map_err(|_| format!("Invalid ease time: {secs}"))?;
+                let ease_type = camera_ease_type(kind)
+                    .ok_or_else(|| format!("Unknown easing function: {kind}"))?;
+                set.ease = Some(CameraEase {
+                    ease_type,
+                    duration,
+                });
+                2
+            }
+            "pos" | "facing" => {
+                let [x, y, z] = values
+                    .get(..3)
+                    .and_then(|v| parse_coords(&v[0], &v[1], &v[2]))
+                    .map(|(x, y, z)| [x, y, z])
+                    .ok_or_else(|| format!("Usage: {keyword} <x> <y> <z>"))?;
+                if keyword == "pos" {
+                    set.position = Some(Vec3::new(x, y, z));
+                } else {
+                    set.facing = Some(Vec3::new(x, y, z));
+                }
+                3
+            }
+            "rot" => {
+                let [pitch, yaw] = parse_floats::<2>(values).ok_or("Usage: rot <pitch> <yaw>")?;
+                set.rotation = Some(Vec2::new(pitch, yaw));
+                2
+            }
+            other => return Err(format!("Unknown camera option: {other}")),
+        };
+        rest = &values[used..];
+    }
+    if (set.position.is_some() || set.facing.is_some()) && set.preset != CAMERA_PRESET_FREE {
+        return Err("Only minecraft:free can be given a position or a point to face".into());
+    }
+    Ok(set)
+}
+
+/// The first `N` arguments as numbers.
+fn parse_floats<const N: usize>(args: &[String]) -> Option<[f32; N]> {
+    let mut out = [0.0; N];
+    for (value, arg) in out.iter_mut().zip(args.get(..N)?) {
+        *value = arg.parse().ok()?;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn parses_fades() {
+        let fade = parse_fade(&args("time 1 2 0.5 color 255 0 51")).unwrap();
+        assert_eq!(fade.times, Some((1.0, 2.0, 0.5)));
+        assert_eq!(fade.color, Some((1.0, 0.0, 0.2)));
+        assert_eq!(parse_fade(&[]).unwrap().times, None);
+        assert!(parse_fade(&args("time 1 2")).is_err());
+        assert!(parse_fade(&args("time 1 2 3 time 1 2 3")).is_err());
+    }
+
+    #[test]
+    fn parses_sets() {
+        let set = parse_set(&args("free ease 2 in_out_sine pos 0 80 0 rot 30 90")).unwrap();
+        assert_eq!(set.preset, CAMERA_PRESET_FREE);
+        assert_eq!(
+            set.ease.map(|e| e.ease_type),
+            camera_ease_type("in_out_sine")
+        );
+        assert_eq!(set.position, Some(Vec3::new(0.0, 80.0, 0.0)));
+        assert_eq!(set.rotation, Some(Vec2::new(30.0, 90.0)));
+
+        assert_eq!(
+            parse_set(&args("minecraft:third_person")).unwrap().preset,
+            2
+        );
+        assert!(parse_set(&args("third_person pos 0 80 0")).is_err());
+        assert!(parse_set(&args("free ease 2 wobbly")).is_err());
+        assert!(parse_set(&args("orbit")).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! Daylight and darkness for mobs: undead standing under open sky burn
+//! while the sun is up, and natural spawning sees the overworld's terrain,
+//! keeping hostile mobs to the dark.
+
+use std::collections::HashMap;
+
+use mc_rs_game::ai::spawning::SpawnTerrain;
+use mc_rs_game::sunlight;
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::packets::MobArmorEquipment;
+use mc_rs_world::block_registry::BlockRegistry;
+use mc_rs_world::chunk::{ChunkColumn, OVERWORLD_MIN_Y};
+
+use super::ConnectionHandler;
+
+/// Ticks between sunlight checks of every mob.
+const SUNLIGHT_CHECK_INTERVAL: u64 = 10;
+
+/// The overworld as natural spawning sees it.
+pub(super) struct OverworldSpawnTerrain<'a> {
+    chunks: Option<&'a HashMap<(i32, i32), ChunkColumn>>,
+    block_registry: &'a BlockRegistry,
+    air: u32,
+    night: bool,
+}
+
+impl SpawnTerrain for OverworldSpawnTerrain<'_> {
+    fn spawn_spots(&self, x: i32, z: i32) -> Vec<i32> {
+        let Some(column) = self.chunks.and_then(|c| c.get(&(x >> 4, z >> 4))) else {
+            return Vec::new();
+        };
+        let (lx, lz) = ((x & 15) as usize, (z & 15) as usize);
+        let block = |y| column.get_block_world(lx, y, lz);
+        (OVERWORLD_MIN_Y + 1..319)
+            .filter(|&y| {
+                block(y - 1).is_some_and(|b| b != self.air && self.block_registry.is_solid(b))
+                    && block(y) == Some(self.air)
+                    && block(y + 1) == Some(self.air)
+            })
+            .collect()
+    }
+
+    fn light_level(&self, _x: i32, _y: i32, _z: i32) -> Option<u8> {
+        // Chunks carry no light data: the height rule decides
+        None
+    }
+
+    fn is_night(&self) -> bool {
+        self.night
+    }
+}
+
+impl ConnectionHandler {
+    /// Run a game world tick, spawning mobs naturally in the overworld.
+    pub(super) fn tick_game_world(&mut self) {
+        let night = !sunlight::is_daytime(self.world_time(0)) || self.is_thundering;
+        let terrain = OverworldSpawnTerrain {
+            chunks: self.world_chunks.get(&0),
+            block_registry: &self.block_registry,
+            air: self.tick_blocks.air,
+            night,
+        };
+        self.game_world.tick_with_terrain(&terrain);
+    }
+
+    /// Whether a spot of the overworld gets direct sunlight: the sun is up,
+    /// it isn't raining and no block stands above it.
+    fn in_sunlight(&self, x: f32, y: f32, z: f32) -> bool {
+        if !sunlight::is_daytime(self.world_time(0)) || self.is_raining {
+            return false;
+        }
+        let (bx, bz) = (x.floor() as i32, z.floor() as i32);
+        self.top_block_y(bx, bz)
+            .is_some_and(|top| top < y.floor() as i32)
+    }
+
+    /// Tell the game world which mobs stand in sunlight, so undead catch
+    /// fire.
+    pub(super) fn tick_mob_sunlight(&mut self) {
+        if !self
+            .game_world
+            .current_tick()
+            .is_multiple_of(SUNLIGHT_CHECK_INTERVAL)
+        {
+            return;
+        }
+        let exposure: Vec<(u64, bool)> = self
+            .game_world
+            .all_mobs()
+            .into_iter()
+            .filter(|mob| sunlight::is_undead(&mob.mob_type))
+            .map(|mob| {
+                let (x, y, z) = mob.position;
+                (mob.runtime_id, self.in_sunlight(x, y, z))
+            })
+            .collect();
+        for (runtime_id, in_sunlight) in exposure {
+            self.game_world.set_mob_in_sunlight(runtime_id, in_sunlight);
+        }
+    }
+
+    /// The armor of a mob wearing (or no longer wearing) a helmet.
+    pub(super) fn helmet_armor(&self, runtime_id: u64, helmet: bool) -> MobArmorEquipment {
+        let item = self
+            .item_registry
+            .get_by_name(sunlight::HELMET_ITEM)
+            .filter(|_| helmet)
+            .map_or_else(ItemStack::empty, |info| {
+                ItemStack::new(info.numeric_id as i32, 1)
+            });
+        MobArmorEquipment::helmet(runtime_id, item)
+    }
+}
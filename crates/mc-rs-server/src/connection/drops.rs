@@ -0,0 +1,401 @@
+//! Dropped items and experience orbs: death drops, graves, pickup, despawn.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::block_entity::BlockEntityData;
+use mc_rs_game::xp;
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::packets::{
+    self, AddActor, AddItemEntity, RemoveEntity, TakeItemEntity, Text, UpdateBlock,
+};
+use mc_rs_proto::types::{BlockPos, Vec3};
+
+use super::{ConnectionHandler, LoginState};
+
+/// Ticks before a freshly dropped item can be picked up.
+const PICKUP_DELAY_TICKS: u32 = 40;
+/// Ticks before an item or orb on the ground despawns (5 minutes).
+const DESPAWN_TICKS: u32 = 6000;
+/// Horizontal/vertical distance within which players collect drops.
+const PICKUP_RADIUS: f32 = 1.5;
+/// Offset from the player's eye position to their feet.
+const EYE_HEIGHT: f32 = 1.62;
+
+/// A living player that can collect drops: (addr, runtime_id, feet position, dimension).
+type Collector = (SocketAddr, u64, (f32, f32, f32), i32);
+
+/// An item entity lying in the world.
+pub struct DroppedItem {
+    pub unique_id: i64,
+    pub runtime_id: u64,
+    pub item: ItemStack,
+    /// Feet-level position.
+    pub position: (f32, f32, f32),
+    pub dimension: i32,
+    pub ticks_alive: u32,
+}
+
+/// An experience orb lying in the world.
+pub struct ExperienceOrb {
+    pub unique_id: i64,
+    pub runtime_id: u64,
+    pub value: i32,
+    /// Feet-level position.
+    pub position: (f32, f32, f32),
+    pub dimension: i32,
+    pub ticks_alive: u32,
+}
+
+impl ConnectionHandler {
+    /// Apply death penalties to a player: drop their inventory and XP (or put the
+    /// items in a grave chest), unless the keepInventory game rule is on.
+    pub(super) async fn drop_death_loot(&mut self, addr: SocketAddr) {
+        if self.keep_inventory {
+            return;
+        }
+
+        let xp = self.experience(addr);
+        let xp_drop = xp::xp_dropped_on_death(xp.level, xp.total);
+        let (nl, nt) = xp::after_death(xp.level, xp.total);
+        self.set_experience(addr, nl, nt);
+        let (items, xp_drop, position, dimension, name) = match self.connections.get_mut(&addr) {
+            Some(conn) => {
+                let name = conn
+                    .login_data
+                    .as_ref()
+                    .map(|d| d.display_name.clone())
+                    .unwrap_or_default();
+                (
+                    conn.inventory.drain_all(),
+                    xp_drop,
+                    (
+                        conn.position.x,
+                        conn.position.y - EYE_HEIGHT,
+                        conn.position.z,
+                    ),
+                    conn.dimension,
+                    name,
+                )
+            }
+            None => return,
+        };
+
+        let mut items = items;
+        if self.server_config.gameplay.death_graves && !items.is_empty() {
+            items = self.place_grave(&name, position, dimension, items).await;
+        }
+        for item in items {
+            self.spawn_dropped_item(item, position, dimension).await;
+        }
+        if xp_drop > 0 {
+            self.spawn_xp_orb(xp_drop, position, dimension).await;
+        }
+
+        self.send_inventory(addr).await;
+        self.send_xp_attributes(addr).await;
+    }
+
+    /// Place a chest at (or just above) the death position holding as many items as fit.
+    /// Returns the items that could not be stored. Graves are only placed in the overworld.
+    async fn place_grave(
+        &mut self,
+        owner: &str,
+        position: (f32, f32, f32),
+        dimension: i32,
+        mut items: Vec<ItemStack>,
+    ) -> Vec<ItemStack> {
+        if dimension != 0 {
+            return items;
+        }
+        let x = position.0.floor() as i32;
+        let z = position.2.floor() as i32;
+        let base_y = position.1.floor() as i32;
+        let air = self.tick_blocks.air;
+        let Some(y) = (base_y..base_y + 4).find(|&y| self.get_block(x, y, z) == Some(air)) else {
+            return items;
+        };
+
+        let chest_rid = self.block_entity_hashes.chest[0];
+        if !self.set_block(x, y, z, chest_rid) {
+            return items;
+        }
+        let pos = BlockPos::new(x, y, z);
+        self.broadcast_packet(packets::id::UPDATE_BLOCK, &UpdateBlock::new(pos, chest_rid))
+            .await;
+
+        let mut chest = BlockEntityData::new_chest();
+        if let BlockEntityData::Chest {
+            items: ref mut slots,
+        } = chest
+        {
+            let stored = items.len().min(slots.len());
+            for (slot, item) in slots.iter_mut().zip(items.drain(..stored)) {
+                *slot = item;
+            }
+        }
+        self.insert_block_entity((x, y, z), chest);
+
+        self.broadcast_packet(
+            packets::id::TEXT,
+            &Text::system(format!("{owner}'s grave is at {x}, {y}, {z}")),
+        )
+        .await;
+        items
+    }
+
+    /// Spawn an item entity at a feet-level position and broadcast it.
+    pub(super) async fn spawn_dropped_item(
+        &mut self,
+        item: ItemStack,
+        position: (f32, f32, f32),
+        dimension: i32,
+    ) {
+        let entity_id = self.allocate_entity_id();
+        let runtime_id = entity_id as u64;
+        let velocity = Vec3::new(
+            (rand::random::<f32>() - 0.5) * 0.2,
+            0.2,
+            (rand::random::<f32>() - 0.5) * 0.2,
+        );
+        let pkt = AddItemEntity {
+            entity_unique_id: entity_id,
+            entity_runtime_id: runtime_id,
+            item: item.clone(),
+            position: Vec3::new(position.0, position.1 + 0.5, position.2),
+            velocity,
+            is_from_fishing: false,
+        };
+        self.broadcast_packet(packets::id::ADD_ITEM_ENTITY, &pkt)
+            .await;
+        self.dropped_items.push(DroppedItem {
+            unique_id: entity_id,
+            runtime_id,
+            item,
+            position,
+            dimension,
+            ticks_alive: 0,
+        });
+    }
+
+    /// Drop the loot of a broken behavior pack block: its loot table if it
+    /// has one, otherwise the block's own item.
+    pub(super) async fn drop_custom_block_loot(
+        &mut self,
+        runtime_id: u32,
+        pos: BlockPos,
+        dimension: i32,
+    ) {
+        let Some(state) = self.custom_blocks.get(runtime_id) else {
+            return;
+        };
+        let drops: Vec<(String, u32)> = match &state.loot_table {
+            Some(table) => self
+                .loot_tables
+                .get(table)
+                .map(|t| {
+                    t.roll()
+                        .into_iter()
+                        .map(|d| (d.item_name, d.count))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            None => vec![(self.custom_blocks.block(state).identifier.clone(), 1)],
+        };
+
+        let position = (pos.x as f32 + 0.5, pos.y as f32, pos.z as f32 + 0.5);
+        for (name, count) in drops {
+            let Some(info) = self.item_registry.get_by_name(&name) else {
+                continue;
+            };
+            let mut item = ItemStack::new(info.numeric_id as i32, count.clamp(1, 64) as u16);
+            if let Some(block) = self.custom_blocks.default_state(&name) {
+                item.block_runtime_id = block as i32;
+            }
+            self.spawn_dropped_item(item, position, dimension).await;
+        }
+    }
+
+    /// Spawn an experience orb worth `value` XP and broadcast it.
+    pub(super) async fn spawn_xp_orb(
+        &mut self,
+        value: i32,
+        position: (f32, f32, f32),
+        dimension: i32,
+    ) {
+        let entity_id = self.allocate_entity_id();
+        let runtime_id = entity_id as u64;
+        self.broadcast_packet(
+            packets::id::ADD_ACTOR,
+            &xp_orb_actor(entity_id, runtime_id, position),
+        )
+        .await;
+        self.xp_orbs.push(ExperienceOrb {
+            unique_id: entity_id,
+            runtime_id,
+            value,
+            position,
+            dimension,
+            ticks_alive: 0,
+        });
+    }
+
+    /// Age drops, despawn old ones, and let nearby living players collect them.
+    pub(super) async fn tick_drops(&mut self) {
+        if self.dropped_items.is_empty() && self.xp_orbs.is_empty() {
+            return;
+        }
+
+        let players: Vec<Collector> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame && !c.is_dead && c.gamemode != 3)
+            .map(|(&addr, c)| {
+                (
+                    addr,
+                    c.entity_runtime_id,
+                    (c.position.x, c.position.y - EYE_HEIGHT, c.position.z),
+                    c.dimension,
+                )
+            })
+            .collect();
+        let in_reach = |pos: (f32, f32, f32), dim: i32| {
+            players.iter().find(|(_, _, p, d)| {
+                *d == dim
+                    && (p.0 - pos.0).abs() <= PICKUP_RADIUS
+                    && (p.1 - pos.1).abs() <= PICKUP_RADIUS
+                    && (p.2 - pos.2).abs() <= PICKUP_RADIUS
+            })
+        };
+
+        let mut removed: Vec<i64> = Vec::new();
+        let mut inventory_changed: Vec<SocketAddr> = Vec::new();
+        let mut i = 0;
+        while i < self.dropped_items.len() {
+            let entry = &mut self.dropped_items[i];
+            entry.ticks_alive += 1;
+            if entry.ticks_alive >= DESPAWN_TICKS {
+                removed.push(entry.unique_id);
+                self.dropped_items.swap_remove(i);
+                continue;
+            }
+            if entry.ticks_alive < PICKUP_DELAY_TICKS {
+                i += 1;
+                continue;
+            }
+            let Some(&(addr, player_rid, _, _)) = in_reach(entry.position, entry.dimension) else {
+                i += 1;
+                continue;
+            };
+            let max_stack = self
+                .item_registry
+                .max_stack_size(entry.item.runtime_id as i16) as u16;
+            let leftover = match self.connections.get_mut(&addr) {
+                Some(conn) => conn.inventory.add_item(&entry.item, max_stack),
+                None => entry.item.count,
+            };
+            if leftover == entry.item.count {
+                i += 1;
+                continue;
+            }
+            inventory_changed.push(addr);
+            self.broadcast_packet(
+                packets::id::TAKE_ITEM_ENTITY,
+                &TakeItemEntity {
+                    item_runtime_id: self.dropped_items[i].runtime_id,
+                    player_runtime_id: player_rid,
+                },
+            )
+            .await;
+            if leftover > 0 {
+                self.dropped_items[i].item.count = leftover;
+                i += 1;
+            } else {
+                removed.push(self.dropped_items[i].unique_id);
+                self.dropped_items.swap_remove(i);
+            }
+        }
+
+        let mut xp_awards: Vec<(SocketAddr, i32)> = Vec::new();
+        let mut i = 0;
+        while i < self.xp_orbs.len() {
+            let orb = &mut self.xp_orbs[i];
+            orb.ticks_alive += 1;
+            if orb.ticks_alive >= DESPAWN_TICKS {
+                removed.push(orb.unique_id);
+                self.xp_orbs.swap_remove(i);
+                continue;
+            }
+            if orb.ticks_alive >= PICKUP_DELAY_TICKS {
+                if let Some(&(addr, _, _, _)) = in_reach(orb.position, orb.dimension) {
+                    xp_awards.push((addr, orb.value));
+                    removed.push(orb.unique_id);
+                    self.xp_orbs.swap_remove(i);
+                    continue;
+                }
+            }
+            i += 1;
+        }
+
+        for uid in removed {
+            self.broadcast_packet(
+                packets::id::REMOVE_ENTITY,
+                &RemoveEntity {
+                    entity_unique_id: uid,
+                },
+            )
+            .await;
+        }
+        inventory_changed.dedup();
+        for addr in inventory_changed {
+            self.send_inventory(addr).await;
+        }
+        for (addr, value) in xp_awards {
+            self.award_xp(addr, value).await;
+        }
+    }
+
+    /// Sync dropped items and XP orbs to a newly joined player.
+    pub(super) async fn sync_drops_to_player(&mut self, addr: SocketAddr) {
+        let items: Vec<AddItemEntity> = self
+            .dropped_items
+            .iter()
+            .map(|d| AddItemEntity {
+                entity_unique_id: d.unique_id,
+                entity_runtime_id: d.runtime_id,
+                item: d.item.clone(),
+                position: Vec3::new(d.position.0, d.position.1, d.position.2),
+                velocity: Vec3::ZERO,
+                is_from_fishing: false,
+            })
+            .collect();
+        let orbs: Vec<AddActor> = self
+            .xp_orbs
+            .iter()
+            .map(|o| xp_orb_actor(o.unique_id, o.runtime_id, o.position))
+            .collect();
+        for pkt in &items {
+            self.send_packet(addr, packets::id::ADD_ITEM_ENTITY, pkt)
+                .await;
+        }
+        for pkt in &orbs {
+            self.send_packet(addr, packets::id::ADD_ACTOR, pkt).await;
+        }
+    }
+}
+
+/// Build the AddActor packet for an experience orb.
+pub(super) fn xp_orb_actor(unique_id: i64, runtime_id: u64, position: (f32, f32, f32)) -> AddActor {
+    AddActor {
+        entity_unique_id: unique_id,
+        entity_runtime_id: runtime_id,
+        entity_type: "minecraft:xp_orb".to_string(),
+        position: Vec3::new(position.0, position.1 + 0.25, position.2),
+        velocity: Vec3::ZERO,
+        pitch: 0.0,
+        yaw: 0.0,
+        head_yaw: 0.0,
+        body_yaw: 0.0,
+        attributes: vec![],
+        metadata: vec![],
+    }
+}
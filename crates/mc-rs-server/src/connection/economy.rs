@@ -0,0 +1,160 @@
+//! `/balance`, `/pay` and `/eco`, and opening the accounts of joining
+//! players.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::packets::{self, Text};
+
+use super::ConnectionHandler;
+use crate::economy::parse_amount;
+
+impl ConnectionHandler {
+    /// Account ID of a connected player: their XUID, or their identity
+    /// UUID when offline.
+    pub(super) fn economy_account_id(&self, addr: SocketAddr) -> Option<String> {
+        let login = self.connections.get(&addr)?.login_data.as_ref()?;
+        Some(if login.xuid.is_empty() {
+            login.identity.clone()
+        } else {
+            login.xuid.clone()
+        })
+    }
+
+    /// Open the account of a player who just joined.
+    pub(super) fn open_economy_account(&mut self, addr: SocketAddr) {
+        let Some(id) = self.economy_account_id(addr) else {
+            return;
+        };
+        let Some(name) = self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.login_data.as_ref())
+            .map(|l| l.display_name.clone())
+        else {
+            return;
+        };
+        let starting_balance = self.server_config.economy.starting_balance;
+        self.economy
+            .lock()
+            .open_account(&id, &name, starting_balance);
+    }
+
+    /// `/balance [player]`: show the sender's balance, or another player's.
+    /// The console (no `addr`) has to name a player.
+    pub(super) fn cmd_balance(
+        &mut self,
+        addr: Option<SocketAddr>,
+        args: &[String],
+    ) -> CommandResult {
+        let config = &self.server_config.economy;
+        let economy = self.economy.lock();
+        match args.first() {
+            Some(name) => match economy.find(name).and_then(|id| economy.account(id)) {
+                Some(account) => CommandResult::ok(format!(
+                    "{} has {}",
+                    account.name,
+                    config.format(account.balance)
+                )),
+                None => CommandResult::err(format!("No account for {name}")),
+            },
+            None => {
+                let Some(id) = addr.and_then(|a| self.economy_account_id(a)) else {
+                    return CommandResult::err("Usage: /balance <player>");
+                };
+                let balance = economy.balance(&id).unwrap_or(0);
+                CommandResult::ok(format!("Balance: {}", config.format(balance)))
+            }
+        }
+    }
+
+    /// `/pay <player> <amount>`: send money to another player, online or not.
+    pub(super) async fn cmd_pay(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        const USAGE: &str = "Usage: /pay <player> <amount>";
+        let [target, amount] = args else {
+            return CommandResult::err(USAGE);
+        };
+        let Some(amount) = parse_amount(amount) else {
+            return CommandResult::err(format!("Invalid amount: {amount}"));
+        };
+        let Some(from) = self.economy_account_id(addr) else {
+            return CommandResult::err("This command must be run by a player");
+        };
+
+        let (sender_name, target_name, balance) = {
+            let mut economy = self.economy.lock();
+            let Some(to) = economy.find(target).map(str::to_string) else {
+                return CommandResult::err(format!("No account for {target}"));
+            };
+            if to == from {
+                return CommandResult::err("You cannot pay yourself");
+            }
+            let sender_name = economy.account(&from).map(|a| a.name.clone());
+            let target_name = economy.account(&to).map(|a| a.name.clone());
+            let (Some(sender_name), Some(target_name)) = (sender_name, target_name) else {
+                return CommandResult::err("You have no account");
+            };
+            if let Err(e) = economy.transfer(&from, &to, amount, "/pay") {
+                return CommandResult::err(format!("Payment failed: {e}"));
+            }
+            (
+                sender_name,
+                target_name,
+                economy.balance(&from).unwrap_or(0),
+            )
+        };
+
+        let config = &self.server_config.economy;
+        let paid = config.format(amount);
+        let left = config.format(balance);
+        if let Some(target_addr) = self.find_player_addr(&target_name) {
+            self.send_packet(
+                target_addr,
+                packets::id::TEXT,
+                &Text::raw(format!("{sender_name} paid you {paid}")),
+            )
+            .await;
+        }
+        CommandResult::ok(format!("Paid {target_name} {paid} (balance: {left})"))
+    }
+
+    /// `/eco <give|take|set> <player> <amount>`: change a player's balance.
+    pub(super) fn cmd_eco(&mut self, sender_name: &str, args: &[String]) -> CommandResult {
+        const USAGE: &str = "Usage: /eco <give|take|set> <player> <amount>";
+        let [action, target, amount] = args else {
+            return CommandResult::err(USAGE);
+        };
+        let amount = match (action.as_str(), amount.parse::<u64>()) {
+            // Setting a balance to zero is allowed, giving or taking nothing isn't
+            ("set", Ok(amount)) => amount,
+            _ => match parse_amount(amount) {
+                Some(amount) => amount,
+                None => return CommandResult::err(format!("Invalid amount: {amount}")),
+            },
+        };
+
+        let mut economy = self.economy.lock();
+        let Some(id) = economy.find(target).map(str::to_string) else {
+            return CommandResult::err(format!("No account for {target}"));
+        };
+        let reason = format!("/eco {action} by {sender_name}");
+        let result = match action.as_str() {
+            "give" => economy.deposit(&id, amount, &reason).map(|_| ()),
+            "take" => economy.withdraw(&id, amount, &reason).map(|_| ()),
+            "set" => economy.set_balance(&id, amount, &reason),
+            _ => return CommandResult::err(USAGE),
+        };
+        if let Err(e) = result {
+            return CommandResult::err(format!("Cannot {action} {amount}: {e}"));
+        }
+        let name = economy
+            .account(&id)
+            .map(|a| a.name.clone())
+            .unwrap_or_default();
+        let balance = economy.balance(&id).unwrap_or(0);
+        CommandResult::ok(format!(
+            "{name} now has {}",
+            self.server_config.economy.format(balance)
+        ))
+    }
+}
@@ -0,0 +1,123 @@
+//! Elytra gliding: usability checks, durability loss and firework boosting.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::armor;
+use mc_rs_proto::packets::{self, InventorySlot, PlaySound, SetEntityMotion};
+use mc_rs_proto::types::Vec3;
+
+use super::{ConnectionHandler, LoginState};
+
+/// Inventory window ID of the armor container.
+const ARMOR_WINDOW_ID: u8 = 119;
+
+impl ConnectionHandler {
+    /// Whether the player wears an elytra that still has durability left.
+    pub(super) fn has_usable_elytra(&self, addr: SocketAddr) -> bool {
+        let Some(conn) = self.connections.get(&addr) else {
+            return false;
+        };
+        let chest = &conn.inventory.armor[armor::SLOT_CHEST];
+        !chest.is_empty()
+            && armor::elytra_usable(chest.metadata)
+            && self
+                .item_registry
+                .get_by_id(chest.runtime_id as i16)
+                .is_some_and(|info| armor::is_elytra(&info.name))
+    }
+
+    /// Wear down the elytra of gliding survival players, one point per second.
+    /// Players stop gliding once the elytra is down to its last point.
+    pub(super) async fn tick_gliding(&mut self) {
+        let mut worn: Vec<SocketAddr> = Vec::new();
+        for (&addr, conn) in self.connections.iter_mut() {
+            if conn.state != LoginState::InGame || conn.is_dead || !conn.is_gliding {
+                continue;
+            }
+            if conn.gamemode != 0 && conn.gamemode != 2 {
+                continue;
+            }
+            conn.glide_ticks += 1;
+            if conn.glide_ticks < armor::GLIDE_DURABILITY_INTERVAL {
+                continue;
+            }
+            conn.glide_ticks = 0;
+            let elytra = &mut conn.inventory.armor[armor::SLOT_CHEST];
+            elytra.metadata = (elytra.metadata + 1).min(armor::ELYTRA_MAX_DAMAGE - 1);
+            if !armor::elytra_usable(elytra.metadata) {
+                conn.is_gliding = false;
+            }
+            worn.push(addr);
+        }
+
+        for addr in worn {
+            let item = match self.connections.get(&addr) {
+                Some(c) => c.inventory.armor[armor::SLOT_CHEST].clone(),
+                None => continue,
+            };
+            self.send_packet(
+                addr,
+                packets::id::INVENTORY_SLOT,
+                &InventorySlot {
+                    window_id: ARMOR_WINDOW_ID as u32,
+                    slot: armor::SLOT_CHEST as u32,
+                    item,
+                },
+            )
+            .await;
+        }
+    }
+
+    /// Use the held firework rocket to boost a gliding player along their look direction.
+    /// Returns `false` (and does nothing) if the player is not gliding.
+    pub(super) async fn boost_with_firework(&mut self, addr: SocketAddr) -> bool {
+        let (rid, pos, slot, updated_item, motion) = match self.connections.get_mut(&addr) {
+            Some(conn) if conn.is_gliding => {
+                let motion = armor::firework_boost(conn.pitch, conn.yaw);
+                let slot = conn.inventory.held_slot;
+                if conn.gamemode != 1 {
+                    let held = conn.inventory.held_item_mut();
+                    if held.count > 1 {
+                        held.count -= 1;
+                    } else {
+                        *held = mc_rs_proto::item_stack::ItemStack::empty();
+                    }
+                }
+                (
+                    conn.entity_runtime_id,
+                    conn.position,
+                    slot,
+                    conn.inventory.held_item().clone(),
+                    motion,
+                )
+            }
+            _ => return false,
+        };
+
+        self.send_packet(
+            addr,
+            packets::id::SET_ENTITY_MOTION,
+            &SetEntityMotion {
+                entity_runtime_id: rid,
+                motion: Vec3::new(motion.0, motion.1, motion.2),
+            },
+        )
+        .await;
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_SLOT,
+            &InventorySlot {
+                window_id: 0,
+                slot: slot as u32,
+                item: updated_item,
+            },
+        )
+        .await;
+        self.broadcast_packet(
+            packets::id::PLAY_SOUND,
+            &PlaySound::new("firework.launch", pos.x, pos.y, pos.z, 1.0, 1.0),
+        )
+        .await;
+        true
+    }
+}
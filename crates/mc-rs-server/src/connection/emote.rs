@@ -0,0 +1,173 @@
+//! Emotes: played emotes relayed to nearby players, and the equipped emote
+//! lists players exchange so their clients can load each other's emotes.
+
+use std::io::Cursor;
+use std::net::SocketAddr;
+
+use mc_rs_plugin_api::{EventResult, PluginEvent};
+use mc_rs_proto::codec::ProtoDecode;
+use mc_rs_proto::packets::emote::FLAG_SERVER_SIDE;
+use mc_rs_proto::packets::{self, Emote, EmoteList};
+use mc_rs_proto::types::Uuid;
+use mc_rs_world::physics::MIN_EMOTE_INTERVAL;
+use tracing::debug;
+
+use super::{ConnectionHandler, LoginState};
+
+/// Distance (blocks) within which other players see an emote.
+const EMOTE_RANGE: f32 = 64.0;
+
+/// Most emote pieces accepted in one EmoteList.
+const MAX_EMOTE_PIECES: usize = 32;
+
+impl ConnectionHandler {
+    /// Handle an Emote from a player: rate limit it, let plugins cancel it
+    /// and relay it to the players around them.
+    pub(super) async fn handle_emote(&mut self, addr: SocketAddr, buf: &mut Cursor<&[u8]>) {
+        let (runtime_id, last) = match self.connections.get(&addr) {
+            Some(c) if c.state == LoginState::InGame && !c.is_dead => {
+                (c.entity_runtime_id, c.last_emote_tick)
+            }
+            _ => return,
+        };
+
+        let pkt = match Emote::proto_decode(buf) {
+            Ok(p) => p,
+            Err(e) => {
+                debug!("Bad Emote from {addr}: {e}");
+                return;
+            }
+        };
+        if pkt.entity_runtime_id != runtime_id {
+            debug!("Ignoring Emote for another entity from {addr}");
+            return;
+        }
+        // Emote IDs are piece UUIDs: anything else is not worth relaying
+        if Uuid::parse(&pkt.emote_id).is_err() {
+            debug!("Ignoring Emote with bad ID {:?} from {addr}", pkt.emote_id);
+            return;
+        }
+
+        if !self.check_rate_limit(addr, last, MIN_EMOTE_INTERVAL).await {
+            return;
+        }
+        let current_tick = self.game_world.current_tick();
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        conn.last_emote_tick = current_tick;
+
+        // Plugin event: PlayerEmote (cancellable)
+        let Some(conn) = self.connections.get(&addr) else {
+            return;
+        };
+        let event = PluginEvent::PlayerEmote {
+            player: self.make_plugin_player(conn),
+            emote_id: pkt.emote_id.clone(),
+        };
+        let snapshot = self.build_snapshot();
+        let (result, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+        self.apply_plugin_actions(actions).await;
+        if result == EventResult::Cancelled {
+            return;
+        }
+
+        let Some(conn) = self.connections.get(&addr) else {
+            return;
+        };
+        let (dim, pos) = (conn.dimension, conn.position);
+        let relay = Emote {
+            entity_runtime_id: runtime_id,
+            emote_id: pkt.emote_id,
+            emote_length_ticks: pkt.emote_length_ticks,
+            xuid: conn
+                .login_data
+                .as_ref()
+                .map(|d| d.xuid.clone())
+                .unwrap_or_default(),
+            platform_id: pkt.platform_id,
+            flags: pkt.flags | FLAG_SERVER_SIDE,
+        };
+        let targets: Vec<SocketAddr> = self
+            .connections
+            .iter()
+            .filter(|(&a, c)| {
+                a != addr
+                    && c.state == LoginState::InGame
+                    && c.dimension == dim
+                    && (c.position.x - pos.x).powi(2)
+                        + (c.position.y - pos.y).powi(2)
+                        + (c.position.z - pos.z).powi(2)
+                        <= EMOTE_RANGE * EMOTE_RANGE
+            })
+            .map(|(&a, _)| a)
+            .collect();
+        for target in targets {
+            self.send_packet(target, packets::id::EMOTE, &relay).await;
+        }
+    }
+
+    /// Handle an EmoteList: remember the player's equipped emotes and pass
+    /// them on to the other players. The client sends it while spawning, in
+    /// which case it is forwarded once the player is in-game.
+    pub(super) async fn handle_emote_list(&mut self, addr: SocketAddr, buf: &mut Cursor<&[u8]>) {
+        let pkt = match EmoteList::proto_decode(buf) {
+            Ok(p) => p,
+            Err(e) => {
+                debug!("Bad EmoteList from {addr}: {e}");
+                return;
+            }
+        };
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        if !matches!(conn.state, LoginState::Spawning | LoginState::InGame)
+            || pkt.player_runtime_id != conn.entity_runtime_id
+            || pkt.emote_pieces.len() > MAX_EMOTE_PIECES
+        {
+            debug!("Ignoring EmoteList from {addr}");
+            return;
+        }
+        conn.emote_pieces = pkt.emote_pieces;
+        if conn.state != LoginState::InGame {
+            return;
+        }
+
+        let list = EmoteList {
+            player_runtime_id: conn.entity_runtime_id,
+            emote_pieces: conn.emote_pieces.clone(),
+        };
+        self.broadcast_packet_except(addr, packets::id::EMOTE_LIST, &list)
+            .await;
+    }
+
+    /// Exchange emote lists between a player who just spawned and everyone
+    /// already in-game.
+    pub(super) async fn send_existing_emote_lists_to(&mut self, new_addr: SocketAddr) {
+        let lists: Vec<EmoteList> = self
+            .connections
+            .iter()
+            .filter(|(&a, c)| {
+                a != new_addr && c.state == LoginState::InGame && !c.emote_pieces.is_empty()
+            })
+            .map(|(_, c)| EmoteList {
+                player_runtime_id: c.entity_runtime_id,
+                emote_pieces: c.emote_pieces.clone(),
+            })
+            .collect();
+        for list in &lists {
+            self.send_packet(new_addr, packets::id::EMOTE_LIST, list)
+                .await;
+        }
+
+        let own = match self.connections.get(&new_addr) {
+            Some(c) if !c.emote_pieces.is_empty() => EmoteList {
+                player_runtime_id: c.entity_runtime_id,
+                emote_pieces: c.emote_pieces.clone(),
+            },
+            _ => return,
+        };
+        self.broadcast_packet_except(new_addr, packets::id::EMOTE_LIST, &own)
+            .await;
+    }
+}
@@ -0,0 +1,81 @@
+//! Farmland: tilling with a hoe, and trampling, which pops off the crop
+//! growing on it.
+
+use std::net::SocketAddr;
+
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::types::BlockPos;
+use mc_rs_world::farmland;
+
+use super::ConnectionHandler;
+
+impl ConnectionHandler {
+    /// Till the block at `pos` with a held hoe, clicked on `face`. Returns
+    /// whether the click was used.
+    pub(super) async fn till_with_hoe(
+        &mut self,
+        addr: SocketAddr,
+        pos: BlockPos,
+        face: i32,
+    ) -> bool {
+        let held_rid = match self.connections.get(&addr) {
+            Some(c) => c.inventory.held_item().runtime_id,
+            None => return false,
+        };
+        let holds_hoe = held_rid != 0
+            && self
+                .item_registry
+                .get_by_id(held_rid as i16)
+                .is_some_and(|info| info.name.ends_with("_hoe"));
+        // Hoes work on the top and sides of a block, not from under it
+        if !holds_hoe || face == 0 {
+            return false;
+        }
+        let Some(rid) = self.get_block(pos.x, pos.y, pos.z) else {
+            return false;
+        };
+        let above = self.get_block(pos.x, pos.y + 1, pos.z);
+        let Some(tilled) = farmland::tilled(rid, above, &self.tick_blocks) else {
+            return false;
+        };
+        self.set_block_and_broadcast(pos.x, pos.y, pos.z, tilled)
+            .await;
+        self.wear_held_item(addr).await;
+        true
+    }
+
+    /// A player landed on `pos` after falling `fall_distance` blocks: if it
+    /// is farmland, they may trample it back into dirt.
+    pub(super) async fn trample_farmland(&mut self, pos: BlockPos, fall_distance: f32) {
+        let is_farmland = self
+            .get_block(pos.x, pos.y, pos.z)
+            .is_some_and(|rid| self.tick_blocks.farmland_moisture(rid).is_some());
+        if is_farmland && farmland::tramples(fall_distance, rand::random()) {
+            self.revert_farmland(pos).await;
+        }
+    }
+
+    /// Turn farmland back into dirt, popping off the crop on it as items.
+    pub(super) async fn revert_farmland(&mut self, pos: BlockPos) {
+        let dirt = self.tick_blocks.dirt;
+        self.set_block_and_broadcast(pos.x, pos.y, pos.z, dirt)
+            .await;
+        let crop = self
+            .get_block(pos.x, pos.y + 1, pos.z)
+            .and_then(|rid| self.tick_blocks.crop_growth(rid));
+        let Some((crop, growth)) = crop else {
+            return;
+        };
+        let air = self.tick_blocks.air;
+        self.set_block_and_broadcast(pos.x, pos.y + 1, pos.z, air)
+            .await;
+        let position = (pos.x as f32 + 0.5, pos.y as f32 + 1.0, pos.z as f32 + 0.5);
+        for (name, count) in farmland::crop_drops(crop, growth) {
+            let Some(info) = self.item_registry.get_by_name(name) else {
+                continue;
+            };
+            let item = ItemStack::new(info.numeric_id as i32, count);
+            self.spawn_dropped_item(item, position, 0).await;
+        }
+    }
+}
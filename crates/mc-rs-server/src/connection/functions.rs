@@ -0,0 +1,110 @@
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr};
+use std::pin::Pin;
+
+use super::*;
+use crate::functions::{MAX_FUNCTION_COMMANDS, MAX_FUNCTION_DEPTH};
+
+/// Name commands run by the server itself (tick/load functions, the console)
+/// are executed as.
+pub(super) const SERVER_SENDER: &str = "Server";
+/// Address standing in for the server as a command sender; no player has it.
+pub(super) const SERVER_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+/// Op level of commands run by the console and plugins.
+pub(super) const SERVER_OP_LEVEL: u8 = 4;
+/// Op level of commands stored in the world or packs: tick/load functions,
+/// command blocks, NPC console actions and entity events. Like vanilla's
+/// `function-permission-level`, it stays below the levels that manage
+/// players and the server, so setting these up (level 2) grants no more.
+pub(super) const AUTOMATED_OP_LEVEL: u8 = 2;
+
+impl ConnectionHandler {
+    /// /function <name>
+    pub(super) async fn cmd_function(
+        &mut self,
+        addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+        op_level: u8,
+    ) -> CommandResult {
+        if args.is_empty() {
+            return CommandResult::err("Usage: /function <name>");
+        }
+        let name = args.join(" ");
+        self.run_function(&name, sender_name.to_string(), addr, op_level)
+            .await
+    }
+
+    /// Run every command of function `name` as `executor_name`, with the
+    /// permissions of `op_level`. Nested calls share the command budget of
+    /// the outermost one.
+    pub(super) fn run_function<'a>(
+        &'a mut self,
+        name: &'a str,
+        executor_name: String,
+        executor_addr: SocketAddr,
+        op_level: u8,
+    ) -> Pin<Box<dyn Future<Output = CommandResult> + Send + 'a>> {
+        Box::pin(async move {
+            let Some(commands) = self.functions.get(name) else {
+                return CommandResult::err(format!("Unknown function: {name}"));
+            };
+            if self.function_depth >= MAX_FUNCTION_DEPTH {
+                return CommandResult::err(format!(
+                    "Function {name} not run: functions nested more than {MAX_FUNCTION_DEPTH} deep"
+                ));
+            }
+            if self.function_depth == 0 {
+                self.function_commands = 0;
+            }
+
+            self.function_depth += 1;
+            let mut count = 0;
+            for line in commands.iter() {
+                if self.function_commands >= MAX_FUNCTION_COMMANDS {
+                    warn!("Function {name} stopped after {MAX_FUNCTION_COMMANDS} commands");
+                    break;
+                }
+                self.function_commands += 1;
+                let result = self
+                    .dispatch_command(executor_addr, &executor_name, line, op_level)
+                    .await;
+                self.apply_command_effects(&executor_name, &result).await;
+                if !result.success {
+                    debug!(
+                        "Function {name}: '{line}' failed: {}",
+                        result.messages.join(" ")
+                    );
+                }
+                count += 1;
+            }
+            self.function_depth -= 1;
+
+            CommandResult::ok(format!("Ran {count} command(s) from function {name}"))
+        })
+    }
+
+    /// Run the functions tagged in load.json (on the first tick) and tick.json.
+    pub(super) async fn tick_functions(&mut self) {
+        if !self.plugin_started {
+            for name in self.functions.load().to_vec() {
+                self.run_function(
+                    &name,
+                    SERVER_SENDER.to_string(),
+                    SERVER_ADDR,
+                    AUTOMATED_OP_LEVEL,
+                )
+                .await;
+            }
+        }
+        for name in self.functions.tick().to_vec() {
+            self.run_function(
+                &name,
+                SERVER_SENDER.to_string(),
+                SERVER_ADDR,
+                AUTOMATED_OP_LEVEL,
+            )
+            .await;
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Where players connect from: the country looked up at login, which
+//! `[geoip]` may refuse, and `/whois`.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+
+use super::ConnectionHandler;
+use crate::geoip::{self, GeoIp};
+
+impl ConnectionHandler {
+    /// Look up and remember the country of a connecting player.
+    pub(super) fn lookup_country(&mut self, addr: SocketAddr) -> Option<String> {
+        let country = self.geoip.country(addr.ip());
+        if let Some(conn) = self.connections.get_mut(&addr) {
+            conn.country = country.clone();
+        }
+        country
+    }
+
+    /// Whether `[geoip]` lets players from `country` in.
+    pub(super) fn country_allowed(&self, country: Option<&str>) -> bool {
+        geoip::country_allowed(&self.server_config.geoip, country)
+    }
+
+    /// Open the database again after `[geoip]` was reloaded, and look up
+    /// the countries of everyone connected with it.
+    pub(super) fn reload_geoip(&mut self) {
+        self.geoip = GeoIp::load(&self.server_config.geoip);
+        for (addr, conn) in self.connections.iter_mut() {
+            conn.country = self.geoip.country(addr.ip());
+        }
+    }
+
+    /// /whois <player>
+    pub(super) fn cmd_whois(&self, args: &[String]) -> CommandResult {
+        let Some(name) = args.first() else {
+            return CommandResult::err("Usage: /whois <player>");
+        };
+        let Some((addr, conn)) = self
+            .find_player_addr(name)
+            .and_then(|addr| self.connections.get(&addr).map(|c| (addr, c)))
+        else {
+            return CommandResult::err(format!("Player not found: {name}"));
+        };
+        let xuid = conn
+            .login_data
+            .as_ref()
+            .map(|d| d.xuid.as_str())
+            .filter(|x| !x.is_empty())
+            .unwrap_or("none");
+        CommandResult::ok(format!(
+            "{name}: address {addr}, country {}, XUID {xuid}",
+            conn.country.as_deref().unwrap_or("unknown")
+        ))
+    }
+}
@@ -0,0 +1,110 @@
+//! Grindstone crafts: removing enchantments and combining damaged items,
+//! with the XP given back as an orb.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::block_entity::BlockEntityData;
+use mc_rs_game::grindstone;
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::packets::item_stack_request::StackRequest;
+use mc_rs_proto::packets::item_stack_response::{
+    StackResponseContainer, StackResponseEntry, StackResponseSlot,
+};
+use mc_rs_proto::packets::{self, InventoryContent};
+use tracing::debug;
+
+use super::ConnectionHandler;
+
+/// Container type of the grindstone UI.
+const CONTAINER_TYPE_GRINDSTONE: u8 = 26;
+/// Container ID of the grindstone top input slot in stack responses.
+const GRINDSTONE_INPUT: u8 = 50;
+/// Container ID of the grindstone bottom input slot in stack responses.
+const GRINDSTONE_ADDITIONAL: u8 = 51;
+
+impl ConnectionHandler {
+    /// Handle a grindstone craft: the result replaces the top input, the
+    /// bottom one is used up, and the XP of the removed enchantments drops
+    /// as an orb on the grindstone.
+    pub(super) async fn handle_grindstone_craft(
+        &mut self,
+        addr: SocketAddr,
+        req: &StackRequest,
+    ) -> StackResponseEntry {
+        let reject = StackResponseEntry {
+            request_id: req.request_id,
+            status: 1,
+            containers: Vec::new(),
+        };
+
+        let (window_id, pos) = match self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.open_container.as_ref())
+        {
+            Some(oc) if oc.container_type == CONTAINER_TYPE_GRINDSTONE => {
+                (oc.window_id, oc.position)
+            }
+            _ => return reject,
+        };
+        let (input1, input2) = match self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::Grindstone { input1, input2 }) => {
+                (input1.clone(), input2.clone())
+            }
+            _ => return reject,
+        };
+        let Some(result) =
+            grindstone::compute_grindstone_output(&input1, &input2, &self.item_registry)
+        else {
+            return reject;
+        };
+        let output = result.output;
+        let cleared = ItemStack::empty();
+
+        if let Some(BlockEntityData::Grindstone { input1, input2 }) =
+            self.block_entities.get_mut(&(pos.x, pos.y, pos.z, 0))
+        {
+            *input1 = output.clone();
+            *input2 = cleared.clone();
+        }
+        debug!(
+            "Grindstone at {pos}: ground items for {addr} ({} XP)",
+            result.xp
+        );
+
+        if result.xp > 0 {
+            let position = (pos.x as f32 + 0.5, pos.y as f32 + 1.0, pos.z as f32 + 0.5);
+            self.spawn_xp_orb(result.xp, position, 0).await;
+        }
+
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_CONTENT,
+            &InventoryContent {
+                window_id: window_id as u32,
+                items: vec![output.clone(), cleared.clone()],
+            },
+        )
+        .await;
+
+        let slot = |container_id: u8, item: &ItemStack| StackResponseContainer {
+            container_id,
+            slots: vec![StackResponseSlot {
+                slot: 0,
+                hotbar_slot: 0,
+                count: item.count as u8,
+                stack_network_id: item.stack_network_id,
+                custom_name: String::new(),
+                durability_correction: 0,
+            }],
+        };
+        StackResponseEntry {
+            request_id: req.request_id,
+            status: 0,
+            containers: vec![
+                slot(GRINDSTONE_INPUT, &output),
+                slot(GRINDSTONE_ADDITIONAL, &cleared),
+            ],
+        }
+    }
+}
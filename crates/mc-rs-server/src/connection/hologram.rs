@@ -0,0 +1,363 @@
+//! Showing holograms to players, refreshing their placeholders, and
+//! `/hologram`.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::entity_metadata::{EntityFlag, MetadataBuilder};
+use mc_rs_proto::packets::{self, AddActor, RemoveEntity, SetActorData};
+use mc_rs_proto::types::Vec3;
+
+use super::{ConnectionHandler, LoginState};
+use crate::holograms::{render_line, split_lines, Hologram, PlaceholderValues};
+
+/// How often lines with placeholders are refreshed, in ticks.
+const HOLOGRAM_REFRESH_TICKS: u64 = 20;
+
+impl ConnectionHandler {
+    /// Players who see holograms: everyone in the overworld.
+    fn hologram_viewers(&self) -> Vec<(SocketAddr, String)> {
+        self.connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame && c.dimension == 0)
+            .filter_map(|(&addr, c)| Some((addr, c.login_data.as_ref()?.display_name.clone())))
+            .collect()
+    }
+
+    /// Fill in the placeholders of a line for one player.
+    fn render_hologram_line(&self, line: &str, viewer: &str) -> String {
+        let economy = self.economy.lock();
+        let balance_of = |name: &str| {
+            let balance = economy.balance(economy.find(name)?)?;
+            Some(self.server_config.economy.format(balance))
+        };
+        let values = PlaceholderValues {
+            online: self
+                .connections
+                .values()
+                .filter(|c| c.state == LoginState::InGame)
+                .count(),
+            max_players: self.server_config.server.max_players,
+            tps: self.tps.tps(),
+            player: viewer,
+            balance_of: &balance_of,
+        };
+        render_line(line, &values)
+    }
+
+    /// Give every line of a hologram an entity ID, keeping the ones it has.
+    fn assign_hologram_ids(&mut self, name: &str) {
+        let Some(hologram) = self.holograms.get(name) else {
+            return;
+        };
+        let (have, want) = (hologram.entity_ids.len(), hologram.lines.len());
+        let new_ids: Vec<i64> = (have..want).map(|_| self.allocate_entity_id()).collect();
+        if let Some(hologram) = self.holograms.get_mut(name) {
+            hologram.entity_ids.truncate(want);
+            hologram.entity_ids.extend(new_ids);
+        }
+    }
+
+    /// The AddActor packets of a hologram, as one player sees it.
+    fn hologram_actors(&self, hologram: &Hologram, viewer: &str) -> Vec<AddActor> {
+        hologram
+            .lines
+            .iter()
+            .zip(&hologram.entity_ids)
+            .enumerate()
+            .map(|(i, (line, &id))| {
+                let [x, y, z] = hologram.line_position(i);
+                hologram_actor(
+                    id,
+                    Vec3::new(x, y, z),
+                    self.render_hologram_line(line, viewer),
+                )
+            })
+            .collect()
+    }
+
+    /// Show a hologram to everyone who sees holograms.
+    async fn show_hologram(&mut self, name: &str) {
+        self.assign_hologram_ids(name);
+        let Some(hologram) = self.holograms.get(name) else {
+            return;
+        };
+        let sends: Vec<(SocketAddr, Vec<AddActor>)> = self
+            .hologram_viewers()
+            .into_iter()
+            .map(|(addr, viewer)| (addr, self.hologram_actors(hologram, &viewer)))
+            .collect();
+        for (addr, actors) in sends {
+            for pkt in &actors {
+                self.send_packet(addr, packets::id::ADD_ACTOR, pkt).await;
+            }
+        }
+    }
+
+    /// Remove the entities of a hologram from every client.
+    async fn hide_hologram(&mut self, name: &str) {
+        let Some(hologram) = self.holograms.get_mut(name) else {
+            return;
+        };
+        for id in std::mem::take(&mut hologram.entity_ids) {
+            let pkt = RemoveEntity {
+                entity_unique_id: id,
+            };
+            self.broadcast_packet(packets::id::REMOVE_ENTITY, &pkt)
+                .await;
+        }
+    }
+
+    /// Add or replace a hologram and show it.
+    pub(super) async fn put_hologram(&mut self, hologram: Hologram) {
+        let name = hologram.name.clone();
+        self.hide_hologram(&name).await;
+        self.holograms.insert(hologram);
+        self.show_hologram(&name).await;
+    }
+
+    /// Change a hologram, save it and show it again. Returns `None` if there
+    /// is no hologram with that name.
+    pub(super) async fn edit_hologram<T>(
+        &mut self,
+        name: &str,
+        edit: impl FnOnce(&mut Hologram) -> T,
+    ) -> Option<T> {
+        self.holograms.get(name)?;
+        self.hide_hologram(name).await;
+        let result = edit(self.holograms.get_mut(name)?);
+        self.holograms.save();
+        self.show_hologram(name).await;
+        Some(result)
+    }
+
+    /// Delete a hologram. Returns whether it existed.
+    pub(super) async fn delete_hologram(&mut self, name: &str) -> bool {
+        self.hide_hologram(name).await;
+        self.holograms.remove(name).is_some()
+    }
+
+    /// Send every hologram to a player entering the overworld.
+    pub(super) async fn sync_holograms_to_player(&mut self, addr: SocketAddr) {
+        let Some(viewer) = self
+            .connections
+            .get(&addr)
+            .filter(|c| c.dimension == 0)
+            .and_then(|c| c.login_data.as_ref())
+            .map(|l| l.display_name.clone())
+        else {
+            return;
+        };
+        let names: Vec<String> = self.holograms.iter().map(|h| h.name.clone()).collect();
+        for name in &names {
+            self.assign_hologram_ids(name);
+        }
+        let actors: Vec<AddActor> = self
+            .holograms
+            .iter()
+            .flat_map(|h| self.hologram_actors(h, &viewer))
+            .collect();
+        for pkt in &actors {
+            self.send_packet(addr, packets::id::ADD_ACTOR, pkt).await;
+        }
+    }
+
+    /// Show holograms created while plugins were enabled, and refresh the
+    /// lines with placeholders once a second.
+    pub(super) async fn tick_holograms(&mut self) {
+        for hologram in std::mem::take(&mut self.plugin_manager.new_holograms) {
+            self.put_hologram(hologram).await;
+        }
+        let tick = self.game_world.current_tick();
+        if !tick.is_multiple_of(HOLOGRAM_REFRESH_TICKS) {
+            return;
+        }
+        let mut updates = Vec::new();
+        for (addr, viewer) in self.hologram_viewers() {
+            for hologram in self.holograms.iter().filter(|h| h.has_placeholders()) {
+                for (line, &id) in hologram.lines.iter().zip(&hologram.entity_ids) {
+                    if crate::holograms::has_placeholders(line) {
+                        let text = self.render_hologram_line(line, &viewer);
+                        updates.push((addr, id, text));
+                    }
+                }
+            }
+        }
+        for (addr, id, text) in updates {
+            let pkt = SetActorData {
+                entity_runtime_id: id as u64,
+                metadata: MetadataBuilder::new().nametag(text).build(),
+                tick,
+            };
+            self.send_packet(addr, packets::id::SET_ACTOR_DATA, &pkt)
+                .await;
+        }
+    }
+
+    /// `/hologram <create|remove|list|addline|setline|removeline|movehere|spacing>`
+    pub(super) async fn cmd_hologram(
+        &mut self,
+        addr: Option<SocketAddr>,
+        args: &[String],
+    ) -> CommandResult {
+        const USAGE: &str = "Usage: /hologram <create <name> <text>|remove <name>|list|addline <name> <text>|setline <name> <line> <text>|removeline <name> <line>|movehere <name>|spacing <name> <blocks>>";
+        let Some((sub, rest)) = args.split_first() else {
+            return CommandResult::err(USAGE);
+        };
+        let name = rest.first().map(String::as_str).unwrap_or_default();
+        let text = rest.get(1..).map(|t| t.join(" ")).unwrap_or_default();
+
+        match sub.as_str() {
+            "list" => {
+                let lines: Vec<String> = self
+                    .holograms
+                    .iter()
+                    .map(|h| {
+                        let [x, y, z] = h.position;
+                        format!(
+                            "- {} ({} line(s) at {x:.1}, {y:.1}, {z:.1})",
+                            h.name,
+                            h.lines.len()
+                        )
+                    })
+                    .collect();
+                if lines.is_empty() {
+                    return CommandResult::ok("There are no holograms");
+                }
+                CommandResult::ok(format!("Holograms:\n{}", lines.join("\n")))
+            }
+            "create" => {
+                if name.is_empty() || text.is_empty() {
+                    return CommandResult::err("Usage: /hologram create <name> <text>");
+                }
+                let position = match self.hologram_position(addr) {
+                    Ok(pos) => pos,
+                    Err(e) => return CommandResult::err(e),
+                };
+                if self.holograms.get(name).is_some() {
+                    return CommandResult::err(format!("Hologram {name} already exists"));
+                }
+                self.put_hologram(Hologram::new(name, position, split_lines(&text)))
+                    .await;
+                CommandResult::ok(format!("Hologram {name} created"))
+            }
+            "remove" => {
+                if self.delete_hologram(name).await {
+                    CommandResult::ok(format!("Hologram {name} removed"))
+                } else {
+                    CommandResult::err(format!("Unknown hologram: {name}"))
+                }
+            }
+            "addline" => {
+                if text.is_empty() {
+                    return CommandResult::err("Usage: /hologram addline <name> <text>");
+                }
+                self.edit_hologram(name, |h| h.lines.extend(split_lines(&text)))
+                    .await
+                    .map(|_| CommandResult::ok(format!("Added a line to {name}")))
+                    .unwrap_or_else(|| CommandResult::err(format!("Unknown hologram: {name}")))
+            }
+            "setline" | "removeline" => {
+                let Some(index) = text
+                    .split_whitespace()
+                    .next()
+                    .and_then(|n| n.parse::<usize>().ok())
+                else {
+                    return CommandResult::err(format!(
+                        "Usage: /hologram {sub} <name> <line>{}",
+                        if sub == "setline" { " <text>" } else { "" }
+                    ));
+                };
+                let new_text = rest.get(2..).map(|t| t.join(" ")).unwrap_or_default();
+                if sub == "setline" && new_text.is_empty() {
+                    return CommandResult::err("Usage: /hologram setline <name> <line> <text>");
+                }
+                let edited = self
+                    .edit_hologram(name, |h| {
+                        if index == 0 || index > h.lines.len() {
+                            return Err(format!(
+                                "{name} has {} line(s), there is no line {index}",
+                                h.lines.len()
+                            ));
+                        }
+                        if sub == "setline" {
+                            h.lines[index - 1] = new_text;
+                        } else if h.lines.len() == 1 {
+                            return Err(format!(
+                                "Cannot remove the last line; use /hologram remove {name}"
+                            ));
+                        } else {
+                            h.lines.remove(index - 1);
+                        }
+                        Ok(())
+                    })
+                    .await;
+                match edited {
+                    Some(Ok(())) if sub == "setline" => {
+                        CommandResult::ok(format!("Line {index} of {name} changed"))
+                    }
+                    Some(Ok(())) => CommandResult::ok(format!("Line {index} of {name} removed")),
+                    Some(Err(e)) => CommandResult::err(e),
+                    None => CommandResult::err(format!("Unknown hologram: {name}")),
+                }
+            }
+            "movehere" => {
+                let position = match self.hologram_position(addr) {
+                    Ok(pos) => pos,
+                    Err(e) => return CommandResult::err(e),
+                };
+                self.edit_hologram(name, |h| h.position = position)
+                    .await
+                    .map(|_| CommandResult::ok(format!("Hologram {name} moved")))
+                    .unwrap_or_else(|| CommandResult::err(format!("Unknown hologram: {name}")))
+            }
+            "spacing" => {
+                let Some(spacing) = text.parse::<f32>().ok().filter(|s| *s > 0.0) else {
+                    return CommandResult::err("Usage: /hologram spacing <name> <blocks>");
+                };
+                self.edit_hologram(name, |h| h.spacing = spacing)
+                    .await
+                    .map(|_| CommandResult::ok(format!("Line spacing of {name} set to {spacing}")))
+                    .unwrap_or_else(|| CommandResult::err(format!("Unknown hologram: {name}")))
+            }
+            _ => CommandResult::err(USAGE),
+        }
+    }
+
+    /// Where a player stands, for placing a hologram there.
+    fn hologram_position(&self, addr: Option<SocketAddr>) -> Result<[f32; 3], String> {
+        let Some(conn) = addr.and_then(|a| self.connections.get(&a)) else {
+            return Err("This command must be run by a player".into());
+        };
+        if conn.dimension != 0 {
+            return Err("Holograms can only be placed in the overworld".into());
+        }
+        Ok([conn.position.x, conn.position.y, conn.position.z])
+    }
+}
+
+/// Build the AddActor packet for one line: an invisible armor stand with
+/// no hitbox that always shows its name.
+fn hologram_actor(unique_id: i64, position: Vec3, text: String) -> AddActor {
+    AddActor {
+        entity_unique_id: unique_id,
+        entity_runtime_id: unique_id as u64,
+        entity_type: "minecraft:armor_stand".to_string(),
+        position,
+        velocity: Vec3::ZERO,
+        pitch: 0.0,
+        yaw: 0.0,
+        head_yaw: 0.0,
+        body_yaw: 0.0,
+        attributes: vec![],
+        metadata: MetadataBuilder::new()
+            .flag(EntityFlag::Invisible, true)
+            .flag(EntityFlag::CanShowNametag, true)
+            .flag(EntityFlag::AlwaysShowNametag, true)
+            .flag(EntityFlag::NoAi, true)
+            .nametag(text)
+            .scale(0.01)
+            .bounding_box(0.0, 0.0)
+            .build(),
+    }
+}
@@ -48,6 +48,9 @@ impl ConnectionHandler {
         };
 
         if let Some(conn) = self.connections.get_mut(&addr) {
+            if conn.inventory.held_slot != equipment.hotbar_slot {
+                conn.eating_since = None;
+            }
             conn.inventory.held_slot = equipment.hotbar_slot;
 
             // Sync held item name to ECS for tempt behavior
@@ -123,6 +126,61 @@ impl ConnectionHandler {
                 continue;
             }
 
+            // Cartography table crafts use the recipes built into the client
+            let multi_recipe = req.actions.iter().find_map(|a| match a {
+                mc_rs_proto::packets::item_stack_request::StackAction::CraftRecipe {
+                    recipe_network_id,
+                } => self
+                    .recipe_registry
+                    .get_multi_by_network_id(*recipe_network_id)
+                    .map(|r| r.uuid.clone()),
+                _ => None,
+            });
+            if let Some(uuid) = multi_recipe {
+                let resp = self.handle_cartography_craft(addr, req, &uuid).await;
+                responses.push(resp);
+                continue;
+            }
+
+            let loom_pattern = req.actions.iter().find_map(|a| match a {
+                mc_rs_proto::packets::item_stack_request::StackAction::CraftLoom { pattern_id } => {
+                    Some(pattern_id.clone())
+                }
+                _ => None,
+            });
+            if let Some(pattern_id) = loom_pattern {
+                let resp = self.handle_loom_craft(addr, req, &pattern_id).await;
+                responses.push(resp);
+                continue;
+            }
+
+            let grindstone_craft = req.actions.iter().any(|a| {
+                matches!(
+                    a,
+                    mc_rs_proto::packets::item_stack_request::StackAction::CraftGrindstone { .. }
+                )
+            });
+            if grindstone_craft {
+                let resp = self.handle_grindstone_craft(addr, req).await;
+                responses.push(resp);
+                continue;
+            }
+
+            let beacon_payment = req.actions.iter().find_map(|a| match a {
+                mc_rs_proto::packets::item_stack_request::StackAction::BeaconPayment {
+                    primary_effect,
+                    secondary_effect,
+                } => Some((*primary_effect, *secondary_effect)),
+                _ => None,
+            });
+            if let Some((primary, secondary)) = beacon_payment {
+                let resp = self
+                    .handle_beacon_payment(addr, req, primary, secondary)
+                    .await;
+                responses.push(resp);
+                continue;
+            }
+
             // Check if player has an open container (chest)
             let container_info = self
                 .connections
@@ -159,6 +217,10 @@ impl ConnectionHandler {
                     Some(BlockEntityData::Anvil { input, material }) => {
                         vec![input.clone(), material.clone()]
                     }
+                    Some(BlockEntityData::CartographyTable { input, additional }) => {
+                        vec![input.clone(), additional.clone()]
+                    }
+                    Some(BlockEntityData::Beacon { payment, .. }) => vec![payment.clone()],
                     _ => vec![mc_rs_proto::item_stack::ItemStack::empty(); 3],
                 };
 
@@ -223,6 +285,17 @@ impl ConnectionHandler {
                             *material = container_items[1].clone();
                         }
                     }
+                    Some(BlockEntityData::CartographyTable { input, additional }) => {
+                        if container_items.len() >= 2 {
+                            *input = container_items[0].clone();
+                            *additional = container_items[1].clone();
+                        }
+                    }
+                    Some(BlockEntityData::Beacon { payment, .. }) => {
+                        if !container_items.is_empty() {
+                            *payment = container_items[0].clone();
+                        }
+                    }
                     _ => {}
                 }
 
@@ -427,10 +500,23 @@ impl ConnectionHandler {
             }
         }
 
+        let multi_entries = self
+            .recipe_registry
+            .multi_recipes()
+            .iter()
+            .filter_map(|recipe| {
+                Some(mc_rs_proto::packets::crafting_data::MultiRecipeEntry {
+                    uuid: mc_rs_proto::types::Uuid::parse(&recipe.uuid).ok()?,
+                    network_id: recipe.network_id,
+                })
+            })
+            .collect();
+
         mc_rs_proto::packets::crafting_data::CraftingData {
             shaped: shaped_entries,
             shapeless: shapeless_entries,
             furnace: furnace_entries,
+            multi: multi_entries,
             clear_recipes: true,
         }
     }
@@ -450,17 +536,28 @@ impl ConnectionHandler {
         };
 
         match action.action {
+            PlayerActionType::StartBreak | PlayerActionType::ContinueDestroyBlock
+                if self.is_spectator(addr) => {}
             PlayerActionType::StartBreak => {
-                if let Some(conn) = self.connections.get_mut(&addr) {
-                    conn.breaking_block = Some((action.block_position, Instant::now()));
-                }
+                self.start_breaking(addr, action.block_position).await;
+                self.punch_note_block(action.block_position).await;
+                self.sell_to_shop(addr, action.block_position).await;
                 debug!("StartBreak at {} by {addr}", action.block_position);
             }
-            PlayerActionType::AbortBreak => {
-                if let Some(conn) = self.connections.get_mut(&addr) {
-                    conn.breaking_block = None;
+            PlayerActionType::ContinueDestroyBlock => {
+                // Sent when the crosshair moves to another block while mining
+                let current = self
+                    .connections
+                    .get(&addr)
+                    .and_then(|c| c.breaking_block)
+                    .map(|b| b.pos);
+                if current != Some(action.block_position) {
+                    self.start_breaking(addr, action.block_position).await;
                 }
-                debug!("AbortBreak by {addr}");
+            }
+            PlayerActionType::AbortBreak | PlayerActionType::StopBreak => {
+                self.stop_breaking(addr).await;
+                debug!("{:?} by {addr}", action.action);
             }
             other => {
                 debug!("PlayerAction {:?} from {addr}", other);
@@ -486,23 +583,53 @@ impl ConnectionHandler {
             }
         };
 
+        // Spectators can't touch the world; clicking a player jumps to them
+        if self.is_spectator(addr) {
+            if let Some(entity_data) = transaction.use_item_on_entity {
+                self.spectate_player(addr, entity_data.entity_runtime_id)
+                    .await;
+            }
+            return;
+        }
+
         // Handle UseItemOnEntity (attack/interact) first
         if let Some(entity_data) = transaction.use_item_on_entity {
+            let runtime_id = entity_data.entity_runtime_id;
+            // NPCs act on both clicks
+            if self.interact_npc(addr, runtime_id).await {
+                return;
+            }
             if entity_data.action == UseItemOnEntityAction::Attack {
-                self.handle_attack(addr, entity_data.entity_runtime_id)
-                    .await;
-            } else if entity_data.action == UseItemOnEntityAction::Interact {
-                self.handle_feed_mob(addr, entity_data.entity_runtime_id)
-                    .await;
+                if !self.attack_command_block_minecart(runtime_id).await {
+                    self.handle_attack(addr, runtime_id).await;
+                }
+            } else if entity_data.action == UseItemOnEntityAction::Interact
+                && !self.interact_command_block_minecart(addr, runtime_id).await
+            {
+                self.handle_feed_mob(addr, runtime_id).await;
             }
             return;
         }
 
-        // Handle ReleaseItem (transaction type 4): bow release
+        // Handle ReleaseItem (transaction type 4): bow release, finish/cancel eating
         if let Some(release_data) = transaction.release_item {
             use mc_rs_proto::packets::inventory_transaction::ReleaseItemAction;
-            if release_data.action == ReleaseItemAction::Release {
-                self.handle_bow_release(addr).await;
+            match release_data.action {
+                ReleaseItemAction::Release => {
+                    let eating = self
+                        .connections
+                        .get(&addr)
+                        .is_some_and(|c| c.eating_since.is_some());
+                    if eating {
+                        // Let go of the use button mid-meal: stop eating
+                        if let Some(conn) = self.connections.get_mut(&addr) {
+                            conn.eating_since = None;
+                        }
+                    } else {
+                        self.handle_bow_release(addr).await;
+                    }
+                }
+                ReleaseItemAction::Consume => self.finish_eating(addr).await,
             }
             return;
         }
@@ -549,7 +676,7 @@ impl ConnectionHandler {
                         .get(&addr)
                         .map(|c| c.last_break_tick)
                         .unwrap_or(0);
-                    if !self.check_rate_limit(addr, last, MIN_BREAK_INTERVAL) {
+                    if !self.check_rate_limit(addr, last, MIN_BREAK_INTERVAL).await {
                         return;
                     }
                     if let Some(conn) = self.connections.get_mut(&addr) {
@@ -568,67 +695,47 @@ impl ConnectionHandler {
                         let dz = pos.z as f32 + 0.5 - conn.position.z;
                         let distance = (dx * dx + dy * dy + dz * dz).sqrt();
                         if distance > BLOCK_REACH {
-                            debug!(
-                                "Block reach violation by {addr}: {distance:.1} > {BLOCK_REACH}"
-                            );
-                            if let Some(conn) = self.connections.get_mut(&addr) {
-                                conn.violations.reach += 1;
+                            let detail =
+                                format!("broke a block {distance:.1} blocks away at {pos}");
+                            if self.flag_violation(addr, Check::Reach, &detail).await {
+                                return;
                             }
-                            return;
                         }
                     }
                 }
 
-                if gamemode == 0 {
-                    // Survival mode: validate mining time
-                    let breaking_info = self.connections.get(&addr).and_then(|c| c.breaking_block);
-
-                    if let Some(expected_secs) =
-                        self.block_registry.expected_mining_secs(old_runtime_id)
-                    {
-                        // Apply Efficiency enchantment to expected mining time
-                        let eff_level = self
-                            .connections
-                            .get(&addr)
-                            .map(|c| {
-                                game_combat::efficiency_level(&c.inventory.held_item().nbt_data)
-                            })
-                            .unwrap_or(0);
-                        let adjusted_secs = if eff_level > 0 {
-                            expected_secs / (1.0 + (eff_level * eff_level) as f32)
-                        } else {
-                            expected_secs
-                        };
-                        if adjusted_secs > 0.0 {
-                            match breaking_info {
-                                Some((break_pos, start_time)) if break_pos == pos => {
-                                    let elapsed = start_time.elapsed().as_secs_f32();
-                                    let min_allowed = adjusted_secs * 0.8;
-                                    if elapsed < min_allowed {
-                                        debug!(
-                                            "Mining too fast at {pos} by {addr}: {elapsed:.2}s < {min_allowed:.2}s (expected {adjusted_secs:.2}s)"
-                                        );
-                                        return;
-                                    }
-                                }
-                                _ => {
-                                    // No StartBreak recorded for this position
-                                    debug!("No StartBreak for {pos} from {addr}, rejecting break");
-                                    return;
-                                }
-                            }
-                        }
-                    }
+                // Adventure mode: only what the held item's CanDestroy lists
+                if !self.may_break_block(addr, old_runtime_id)
+                    || !self.may_touch_shop_block(addr, pos).await
+                {
+                    self.send_packet(
+                        addr,
+                        packets::id::UPDATE_BLOCK,
+                        &UpdateBlock::new(pos, old_runtime_id),
+                    )
+                    .await;
+                    self.resend_sign(addr, pos).await;
+                    return;
+                }
 
-                    // Clear breaking state
-                    if let Some(conn) = self.connections.get_mut(&addr) {
-                        conn.breaking_block = None;
+                if gamemode == 0 {
+                    // Survival mode: validate break time, putting the block
+                    // back on the client if it was mined too fast
+                    if !self.break_time_elapsed(addr, pos, old_runtime_id).await {
+                        self.send_packet(
+                            addr,
+                            packets::id::UPDATE_BLOCK,
+                            &UpdateBlock::new(pos, old_runtime_id),
+                        )
+                        .await;
+                        return;
                     }
                 }
+                self.stop_breaking(addr).await;
 
                 // Plugin event: BlockBreak (cancellable)
                 if let Some(conn) = self.connections.get(&addr) {
-                    let player = Self::make_plugin_player(conn);
+                    let player = self.make_plugin_player(conn);
                     let event = PluginEvent::BlockBreak {
                         player,
                         position: PluginBlockPos {
@@ -646,15 +753,44 @@ impl ConnectionHandler {
                     }
                 }
 
-                // Set to air
-                if !self.set_block(pos.x, pos.y, pos.z, air_hash) {
+                // Set to air, or to the water a waterlogged block held
+                let liquid = self
+                    .get_liquid_in(0, pos.x, pos.y, pos.z)
+                    .filter(|&rid| rid != air_hash);
+                if !self.set_block(pos.x, pos.y, pos.z, liquid.unwrap_or(air_hash)) {
                     return;
                 }
 
                 // Send UpdateBlock to all players
-                let update = UpdateBlock::new(pos, air_hash);
+                let update = UpdateBlock::new(pos, liquid.unwrap_or(air_hash));
                 self.broadcast_packet(packets::id::UPDATE_BLOCK, &update)
                     .await;
+                if liquid.is_some() {
+                    let update = UpdateBlock {
+                        layer: 1,
+                        ..UpdateBlock::new(pos, air_hash)
+                    };
+                    self.broadcast_packet(packets::id::UPDATE_BLOCK, &update)
+                        .await;
+                }
+
+                // The other half of a door or bed goes too
+                let other_half = self.block_states.get(old_runtime_id).and_then(|info| {
+                    let (x, y, z) = placement::other_half(info, (pos.x, pos.y, pos.z))?;
+                    let other = self.get_block(x, y, z)?;
+                    let same = self
+                        .block_states
+                        .get(other)
+                        .is_some_and(|other| other.name == info.name);
+                    same.then_some(BlockPos::new(x, y, z))
+                });
+                if let Some(other) = other_half {
+                    if self.set_block(other.x, other.y, other.z, air_hash) {
+                        let update = UpdateBlock::new(other, air_hash);
+                        self.broadcast_packet(packets::id::UPDATE_BLOCK, &update)
+                            .await;
+                    }
+                }
 
                 // Send LevelEvent (destroy particles) to all players
                 let event = LevelEvent::destroy_block(pos.x, pos.y, pos.z, old_runtime_id);
@@ -664,13 +800,35 @@ impl ConnectionHandler {
                 // Trigger fluid updates for neighbors (water/lava may flow into the gap)
                 self.schedule_fluid_neighbors(pos.x, pos.y, pos.z);
 
+                // Leaves left without a log decay
+                if self.tick_blocks.is_log(old_runtime_id) {
+                    self.schedule_leaf_decay(pos);
+                }
+
                 // Trigger redstone updates if broken near wire
                 self.update_redstone_from(pos.x, pos.y, pos.z).await;
 
                 // Remove block entity if any
-                self.remove_block_entity((pos.x, pos.y, pos.z));
+                let block_entity = self.remove_block_entity((pos.x, pos.y, pos.z));
                 // Close any open containers at this position
                 self.close_container_at(pos).await;
+                self.close_shops_at(pos);
+
+                if let Some(info) = self.block_registry.get(old_runtime_id) {
+                    if let Some(conn) = self.connections.get_mut(&addr) {
+                        conn.stats.record_block_mined(info.name);
+                    }
+                }
+
+                let dimension = self
+                    .connections
+                    .get(&addr)
+                    .map(|c| c.dimension)
+                    .unwrap_or(0);
+                // A jukebox drops its record whatever the game mode
+                if let Some(BlockEntityData::Jukebox { record, .. }) = &block_entity {
+                    self.drop_record(record.clone(), pos, dimension).await;
+                }
 
                 // Award XP for ore mining (survival only)
                 if gamemode == 0 {
@@ -680,7 +838,17 @@ impl ConnectionHandler {
                             self.award_xp(addr, ore_xp).await;
                         }
                     }
+                    self.drop_custom_block_loot(old_runtime_id, pos, dimension)
+                        .await;
+                    if !self.is_holding_shears(addr) {
+                        self.drop_leaf_loot(old_runtime_id, pos).await;
+                    }
+                    if let Some(be) = &block_entity {
+                        self.drop_head(be, pos, dimension).await;
+                        self.drop_banner(be, pos, dimension).await;
+                    }
                 }
+                self.wear_held_item(addr).await;
 
                 debug!("Block broken at {pos} by {addr}");
             }
@@ -696,13 +864,11 @@ impl ConnectionHandler {
                         let dz = click_pos.z as f32 + 0.5 - conn.position.z;
                         let distance = (dx * dx + dy * dy + dz * dz).sqrt();
                         if distance > BLOCK_REACH {
-                            debug!(
-                                "Click reach violation by {addr}: {distance:.1} > {BLOCK_REACH}"
-                            );
-                            if let Some(conn) = self.connections.get_mut(&addr) {
-                                conn.violations.reach += 1;
+                            let detail =
+                                format!("used a block {distance:.1} blocks away at {click_pos}");
+                            if self.flag_violation(addr, Check::Reach, &detail).await {
+                                return;
                             }
-                            return;
                         }
                     }
                 }
@@ -726,9 +892,25 @@ impl ConnectionHandler {
                             .await;
                         return;
                     }
+                    if let Some(bites) = self.tick_blocks.cake_bites(rid) {
+                        if self.eat_cake(addr, click_pos, bites).await {
+                            return;
+                        }
+                    }
+                    if self.block_entity_hashes.is_sign(rid)
+                        && self.interact_sign(addr, click_pos, rid).await
+                    {
+                        return;
+                    }
+                    if self.block_entity_hashes.is_bed(rid) {
+                        self.sleep_in_bed(addr, click_pos).await;
+                        return;
+                    }
                     // Check if clicking on a chest → open it
                     if self.block_entity_hashes.is_chest(rid) {
-                        self.open_chest(addr, click_pos).await;
+                        if self.may_touch_shop_block(addr, click_pos).await {
+                            self.open_chest(addr, click_pos).await;
+                        }
                         return;
                     }
                     if self.block_entity_hashes.is_furnace(rid) {
@@ -755,6 +937,37 @@ impl ConnectionHandler {
                         self.open_anvil(addr, click_pos).await;
                         return;
                     }
+                    if self.block_entity_hashes.is_cartography_table(rid) {
+                        self.open_cartography_table(addr, click_pos).await;
+                        return;
+                    }
+                    if self.block_entity_hashes.is_beacon(rid) {
+                        self.open_beacon(addr, click_pos).await;
+                        return;
+                    }
+                    if self.block_entity_hashes.is_noteblock(rid) {
+                        self.tune_note_block(click_pos).await;
+                        return;
+                    }
+                    if self.block_entity_hashes.is_jukebox(rid) {
+                        self.use_jukebox(addr, click_pos).await;
+                        return;
+                    }
+                    if self.tick_blocks.command_block_state(rid).is_some()
+                        && self.open_command_block(addr, click_pos).await
+                    {
+                        return;
+                    }
+                }
+
+                // --- Command block minecart: place it on the clicked rail ---
+                if self.place_command_block_minecart(addr, click_pos).await {
+                    return;
+                }
+
+                // --- Hoe: till dirt and grass into farmland ---
+                if self.till_with_hoe(addr, click_pos, use_item.face).await {
+                    return;
                 }
 
                 // --- Flint and steel: try to light a Nether portal ---
@@ -788,7 +1001,7 @@ impl ConnectionHandler {
                         .get(&addr)
                         .map(|c| c.last_place_tick)
                         .unwrap_or(0);
-                    if !self.check_rate_limit(addr, last, MIN_PLACE_INTERVAL) {
+                    if !self.check_rate_limit(addr, last, MIN_PLACE_INTERVAL).await {
                         return;
                     }
                     if let Some(conn) = self.connections.get_mut(&addr) {
@@ -803,9 +1016,10 @@ impl ConnectionHandler {
                     return;
                 }
 
-                // Get the block runtime ID from the held item
+                // Get the block runtime ID from the held item (a hash, which
+                // goes negative as an i32 for half of all block states)
                 let block_runtime_id = use_item.held_item_block_runtime_id;
-                if block_runtime_id <= 0 {
+                if block_runtime_id == 0 {
                     return;
                 }
                 let block_runtime_id = block_runtime_id as u32;
@@ -816,6 +1030,20 @@ impl ConnectionHandler {
                     return;
                 }
 
+                // Adventure mode: only against what the held item's
+                // CanPlaceOn lists
+                let against = self.get_block(click_pos.x, click_pos.y, click_pos.z);
+                if !against.is_some_and(|rid| self.may_place_against(addr, rid)) {
+                    let current = self.get_block(target.x, target.y, target.z);
+                    self.send_packet(
+                        addr,
+                        packets::id::UPDATE_BLOCK,
+                        &UpdateBlock::new(target, current.unwrap_or(air_hash)),
+                    )
+                    .await;
+                    return;
+                }
+
                 // Check that target is in a loaded chunk
                 let cx = target.x >> 4;
                 let cz = target.z >> 4;
@@ -831,9 +1059,19 @@ impl ConnectionHandler {
                     return;
                 }
 
+                // Only operators in creative may place command blocks
+                if self
+                    .tick_blocks
+                    .command_block_state(block_runtime_id)
+                    .is_some()
+                    && !self.can_edit_command_blocks(addr)
+                {
+                    return;
+                }
+
                 // Plugin event: BlockPlace (cancellable)
                 if let Some(conn) = self.connections.get(&addr) {
-                    let player = Self::make_plugin_player(conn);
+                    let player = self.make_plugin_player(conn);
                     let event = PluginEvent::BlockPlace {
                         player,
                         position: PluginBlockPos {
@@ -866,6 +1104,15 @@ impl ConnectionHandler {
                     } else {
                         block_runtime_id
                     }
+                } else if self.block_entity_hashes.is_banner(block_runtime_id) {
+                    // Banners stand like signs or hang on the clicked wall
+                    if (2..=5).contains(&use_item.face) {
+                        self.block_entity_hashes
+                            .wall_banner_face(use_item.face)
+                            .unwrap_or(block_runtime_id)
+                    } else {
+                        self.block_entity_hashes.standing_banner_direction(yaw)
+                    }
                 } else if self.block_entity_hashes.is_chest(block_runtime_id) {
                     // Chest faces the player
                     self.block_entity_hashes.chest_from_yaw(yaw)
@@ -882,36 +1129,94 @@ impl ConnectionHandler {
                 } else if self.tick_blocks.sticky_piston.contains(&block_runtime_id) {
                     let pitch = self.connections.get(&addr).map(|c| c.pitch).unwrap_or(0.0);
                     self.tick_blocks.piston_from_look(pitch, yaw, true)
+                } else if let Some(rid) = self.command_block_placement(addr, block_runtime_id) {
+                    // Command block faces the direction the player is looking
+                    rid
+                } else if let Some((kind, _)) = self.block_entity_hashes.head_kind(block_runtime_id)
+                {
+                    // Heads stand on the floor or hang on the clicked wall
+                    let face = if (2..=5).contains(&use_item.face) {
+                        use_item.face
+                    } else {
+                        1
+                    };
+                    self.block_entity_hashes.head_facing(kind, face)
+                } else if self.custom_blocks.get(block_runtime_id).is_some() {
+                    // Behavior pack block: fill in its trait states
+                    let pitch = self.connections.get(&addr).map(|c| c.pitch).unwrap_or(0.0);
+                    self.custom_blocks
+                        .placement_state(
+                            block_runtime_id,
+                            yaw,
+                            pitch,
+                            use_item.face,
+                            use_item.click_position.y,
+                        )
+                        .unwrap_or(block_runtime_id)
                 } else {
                     block_runtime_id
                 };
 
-                // Set the block
-                if !self.set_block(target.x, target.y, target.z, final_rid) {
-                    return;
+                // Placement rules for vanilla blocks: what they need to stand
+                // on, both halves of doors and beds, slabs stacking and
+                // facing the player
+                let mut blocks = vec![(target.x, target.y, target.z, final_rid)];
+                let mut waterlogged = false;
+                let placed_name = (final_rid == block_runtime_id)
+                    .then(|| self.block_registry.get(block_runtime_id))
+                    .flatten()
+                    .map(|info| info.name);
+                if let Some(name) = placed_name {
+                    let ctx = PlacementContext {
+                        clicked: (click_pos.x, click_pos.y, click_pos.z),
+                        face: use_item.face,
+                        click_y: use_item.click_position.y,
+                        yaw,
+                    };
+                    let placed = placement::place(
+                        name,
+                        block_runtime_id,
+                        &ctx,
+                        &self.block_registry,
+                        |x, y, z| self.get_block(x, y, z),
+                    );
+                    let Some(placed) = placed else {
+                        let current = self.get_block(target.x, target.y, target.z);
+                        self.send_packet(
+                            addr,
+                            packets::id::UPDATE_BLOCK,
+                            &UpdateBlock::new(target, current.unwrap_or(air_hash)),
+                        )
+                        .await;
+                        return;
+                    };
+                    blocks = placed.blocks;
+                    waterlogged = placed.waterlogged;
                 }
 
-                // Send UpdateBlock to all players
-                let update = UpdateBlock::new(target, final_rid);
-                self.broadcast_packet(packets::id::UPDATE_BLOCK, &update)
-                    .await;
+                // Set the blocks, and send UpdateBlock to all players
+                for &(x, y, z, rid) in &blocks {
+                    if !self.set_block(x, y, z, rid) {
+                        return;
+                    }
+                    let update = UpdateBlock::new(BlockPos::new(x, y, z), rid);
+                    self.broadcast_packet(packets::id::UPDATE_BLOCK, &update)
+                        .await;
+                }
+                if waterlogged {
+                    let water = self.tick_blocks.water[0];
+                    self.set_liquid_in(0, target.x, target.y, target.z, water);
+                    let update = UpdateBlock {
+                        layer: 1,
+                        ..UpdateBlock::new(target, water)
+                    };
+                    self.broadcast_packet(packets::id::UPDATE_BLOCK, &update)
+                        .await;
+                }
 
                 // Create block entity if sign or chest
                 if self.block_entity_hashes.is_sign(final_rid) {
-                    let be = BlockEntityData::new_sign();
-                    let nbt = be.to_network_nbt(target.x, target.y, target.z);
-                    self.block_entities
-                        .insert((target.x, target.y, target.z, 0), be);
-                    // Send BlockActorData to open the sign editor
-                    self.send_packet(
-                        addr,
-                        packets::id::BLOCK_ACTOR_DATA,
-                        &BlockActorData {
-                            position: target,
-                            nbt_data: nbt,
-                        },
-                    )
-                    .await;
+                    self.place_sign(addr, target).await;
                 } else if self.block_entity_hashes.is_chest(final_rid) {
                     self.insert_block_entity(
                         (target.x, target.y, target.z),
@@ -922,6 +1227,30 @@ impl ConnectionHandler {
                         (target.x, target.y, target.z),
                         BlockEntityData::new_enchanting_table(),
                     );
+                } else if self.block_entity_hashes.is_beacon(final_rid) {
+                    self.insert_block_entity(
+                        (target.x, target.y, target.z),
+                        BlockEntityData::new_beacon(),
+                    );
+                } else if self.tick_blocks.command_block_state(final_rid).is_some() {
+                    self.insert_block_entity(
+                        (target.x, target.y, target.z),
+                        BlockEntityData::new_command_block(),
+                    );
+                } else if self.block_entity_hashes.is_banner(final_rid) {
+                    self.place_banner(addr, target).await;
+                } else if self.block_entity_hashes.is_noteblock(final_rid) {
+                    self.insert_block_entity(
+                        (target.x, target.y, target.z),
+                        BlockEntityData::new_note_block(),
+                    );
+                } else if self.block_entity_hashes.is_jukebox(final_rid) {
+                    self.insert_block_entity(
+                        (target.x, target.y, target.z),
+                        BlockEntityData::new_jukebox(),
+                    );
+                } else if let Some((kind, face)) = self.block_entity_hashes.head_kind(final_rid) {
+                    self.place_head(addr, target, kind, face).await;
                 } else if let Some(variant) = self.block_entity_hashes.furnace_variant(final_rid) {
                     use mc_rs_game::smelting::FurnaceType;
                     let ft = match variant {
@@ -939,7 +1268,9 @@ impl ConnectionHandler {
 
                 // Trigger fluid updates: if placed block is fluid, schedule self;
                 // also schedule neighboring fluids that may be affected
-                self.schedule_fluid_neighbors(target.x, target.y, target.z);
+                for &(x, y, z, _) in &blocks {
+                    self.schedule_fluid_neighbors(x, y, z);
+                }
 
                 // Trigger redstone updates if placed near wire
                 self.update_redstone_from(target.x, target.y, target.z)
@@ -954,8 +1285,8 @@ impl ConnectionHandler {
             }
             UseItemAction::ClickAir => {
                 // Get held item info
-                let (item_rid, food_level) = match self.connections.get(&addr) {
-                    Some(c) => (c.inventory.held_item().runtime_id, c.food),
+                let item_rid = match self.connections.get(&addr) {
+                    Some(c) => c.inventory.held_item().runtime_id,
                     None => return,
                 };
                 if item_rid == 0 {
@@ -966,6 +1297,36 @@ impl ConnectionHandler {
                     .get_by_id(item_rid as i16)
                     .map(|info| info.name.clone());
 
+                // Custom items on cooldown can't be used
+                if let Some(name) = item_name.as_deref() {
+                    if !self.start_item_cooldown(addr, name) {
+                        return;
+                    }
+                }
+
+                // Armor: equip from the hotbar, swapping with whatever is worn
+                if let Some(slot) = item_name.as_deref().and_then(|name| {
+                    mc_rs_game::armor::armor_slot(name)
+                        .or_else(|| self.custom_items.armor_slot(name))
+                }) {
+                    self.equip_held_armor(addr, slot).await;
+                    return;
+                }
+
+                // Firework rocket: boost elytra flight
+                if item_name.as_deref() == Some("minecraft:firework_rocket")
+                    && self.boost_with_firework(addr).await
+                {
+                    return;
+                }
+
+                // Empty map: start a filled map centered on the player
+                if item_name.as_deref() == Some(mc_rs_game::map::EMPTY_MAP)
+                    && self.use_empty_map(addr).await
+                {
+                    return;
+                }
+
                 // Bow: start charging
                 if item_name.as_deref() == Some("minecraft:bow") {
                     let tick = self.game_world.current_tick();
@@ -979,61 +1340,114 @@ impl ConnectionHandler {
                     return;
                 }
 
-                // Food consumption
+                // Food and milk: first use starts eating, the next one finishes the meal
                 if let Some(name) = item_name {
-                    if let Some(fd) = mc_rs_game::food::food_data(&name) {
-                        if food_level < 20 {
-                            let conn = match self.connections.get_mut(&addr) {
-                                Some(c) => c,
-                                None => return,
-                            };
-                            conn.food = (conn.food + fd.hunger).min(20);
-                            conn.saturation =
-                                (conn.saturation + fd.saturation).min(conn.food as f32);
-
-                            // Decrement item count
-                            let slot = conn.inventory.held_slot as usize;
-                            let stack = &mut conn.inventory.main[slot];
-                            if stack.count > 1 {
-                                stack.count -= 1;
-                            } else {
-                                conn.inventory.main[slot] =
-                                    mc_rs_proto::item_stack::ItemStack::empty();
-                            }
-
-                            let rid = conn.entity_runtime_id;
-                            let food = conn.food;
-                            let sat = conn.saturation;
-                            let exh = conn.exhaustion;
-                            let tick = conn.client_tick;
-                            let updated_item = conn.inventory.main[slot].clone();
-
-                            // Send updated inventory slot
-                            self.send_packet(
-                                addr,
-                                packets::id::INVENTORY_SLOT,
-                                &InventorySlot {
-                                    window_id: 0,
-                                    slot: slot as u32,
-                                    item: updated_item,
-                                },
-                            )
-                            .await;
-
-                            // Send updated hunger attributes
-                            self.send_packet(
-                                addr,
-                                packets::id::UPDATE_ATTRIBUTES,
-                                &UpdateAttributes::hunger(rid, food as f32, sat, exh, tick),
-                            )
-                            .await;
-                        }
+                    if mc_rs_game::food::is_consumable(&name)
+                        || self.custom_items.food(&name).is_some()
+                    {
+                        self.use_food(addr, &name).await;
                     }
                 }
             }
         }
     }
 
+    /// Start the cooldown of a held custom item. Returns `false` if the item's
+    /// cooldown category is still cooling down. Finishing a meal is not a new use.
+    fn start_item_cooldown(&mut self, addr: SocketAddr, item_name: &str) -> bool {
+        let Some(cooldown) = self
+            .custom_items
+            .get(item_name)
+            .and_then(|i| i.cooldown.as_ref())
+        else {
+            return true;
+        };
+        let tick = self.game_world.current_tick();
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return false;
+        };
+        if conn.eating_since.is_some() {
+            return true;
+        }
+        if conn
+            .item_cooldowns
+            .get(&cooldown.category)
+            .is_some_and(|&ready| tick < ready)
+        {
+            return false;
+        }
+        conn.item_cooldowns
+            .insert(cooldown.category.clone(), tick + cooldown.duration_ticks);
+        true
+    }
+
+    /// Wear down the held item if it is a custom item with durability
+    /// (survival and adventure only). The item breaks once fully worn.
+    pub(super) async fn wear_held_item(&mut self, addr: SocketAddr) {
+        let (slot, pos) = {
+            let Some(conn) = self.connections.get_mut(&addr) else {
+                return;
+            };
+            if conn.gamemode != 0 && conn.gamemode != 2 {
+                return;
+            }
+            let held = conn.inventory.held_item();
+            let Some(durability) = self
+                .item_registry
+                .get_by_id(held.runtime_id as i16)
+                .and_then(|info| self.custom_items.get(&info.name))
+                .and_then(|item| item.durability)
+            else {
+                return;
+            };
+            if !crate::custom_items::rolls_durability_loss(&durability, &mut rand::thread_rng()) {
+                return;
+            }
+            let held = conn.inventory.held_item_mut();
+            held.metadata += 1;
+            let broken = held.metadata >= durability.max_durability;
+            if broken {
+                *held = mc_rs_proto::item_stack::ItemStack::empty();
+            }
+            (conn.inventory.held_slot, broken.then_some(conn.position))
+        };
+
+        let item = match self.connections.get(&addr) {
+            Some(c) => c.inventory.held_item().clone(),
+            None => return,
+        };
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_SLOT,
+            &InventorySlot {
+                window_id: 0,
+                slot: slot as u32,
+                item,
+            },
+        )
+        .await;
+        if let Some(pos) = pos {
+            self.broadcast_packet(
+                packets::id::PLAY_SOUND,
+                &PlaySound::new("random.break", pos.x, pos.y, pos.z, 1.0, 1.0),
+            )
+            .await;
+        }
+    }
+
+    /// Swap the held item into an armor slot, moving the previously worn piece to the hand.
+    async fn equip_held_armor(&mut self, addr: SocketAddr, armor_slot: usize) {
+        match self.connections.get_mut(&addr) {
+            Some(conn) => {
+                let held_slot = conn.inventory.held_slot as usize;
+                let inv = &mut conn.inventory;
+                std::mem::swap(&mut inv.main[held_slot], &mut inv.armor[armor_slot]);
+            }
+            None => return,
+        }
+        self.send_inventory(addr).await;
+    }
+
     /// Open a chest container for a player.
     async fn open_chest(&mut self, addr: SocketAddr, pos: BlockPos) {
         let window_id = match self.connections.get_mut(&addr) {
@@ -1518,6 +1932,59 @@ impl ConnectionHandler {
         debug!("Opened anvil at {pos} for {addr} (window_id={window_id})");
     }
 
+    /// Open a beacon UI for a player.
+    async fn open_beacon(&mut self, addr: SocketAddr, pos: BlockPos) {
+        self.block_entities
+            .entry((pos.x, pos.y, pos.z, 0))
+            .or_insert_with(BlockEntityData::new_beacon);
+
+        let window_id = match self.connections.get_mut(&addr) {
+            Some(conn) => {
+                let wid = conn.next_window_id;
+                conn.next_window_id = conn.next_window_id.wrapping_add(1);
+                if conn.next_window_id == 0 {
+                    conn.next_window_id = 1;
+                }
+                conn.open_container = Some(OpenContainer {
+                    window_id: wid,
+                    container_type: 13, // BEACON
+                    position: pos,
+                });
+                wid
+            }
+            None => return,
+        };
+
+        self.send_packet(
+            addr,
+            packets::id::CONTAINER_OPEN,
+            &ContainerOpen {
+                window_id,
+                container_type: 13,
+                position: pos,
+                entity_unique_id: -1,
+            },
+        )
+        .await;
+
+        let items = match self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::Beacon { payment, .. }) => vec![payment.clone()],
+            _ => vec![mc_rs_proto::item_stack::ItemStack::empty()],
+        };
+
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_CONTENT,
+            &InventoryContent {
+                window_id: window_id as u32,
+                items,
+            },
+        )
+        .await;
+
+        debug!("Opened beacon at {pos} for {addr} (window_id={window_id})");
+    }
+
     /// Send enchantment options to a player based on their enchanting table state.
     async fn send_enchant_options(&mut self, addr: SocketAddr, pos: BlockPos) {
         use mc_rs_game::enchanting;
@@ -1676,7 +2143,7 @@ impl ConnectionHandler {
         };
 
         let (has_enough_xp, has_enough_lapis) = match self.connections.get(&addr) {
-            Some(conn) => {
+            Some(_) => {
                 let lapis_count = match self.block_entities.get(&(
                     container_pos.x,
                     container_pos.y,
@@ -1686,7 +2153,7 @@ impl ConnectionHandler {
                     Some(BlockEntityData::EnchantingTable { lapis, .. }) => lapis.count as i32,
                     _ => 0,
                 };
-                (conn.xp_level >= cost, lapis_count >= cost)
+                (self.experience(addr).level >= cost, lapis_count >= cost)
             }
             None => return reject,
         };
@@ -1717,20 +2184,21 @@ impl ConnectionHandler {
         }
 
         // Deduct XP levels
+        let xp_level = self.experience(addr).level - cost;
+        self.set_experience(addr, xp_level, mc_rs_game::xp::total_xp_for_level(xp_level));
         if let Some(conn) = self.connections.get_mut(&addr) {
-            conn.xp_level -= cost;
-            conn.xp_total = mc_rs_game::xp::total_xp_for_level(conn.xp_level);
             // Re-roll enchant seed
             conn.enchant_seed = rand::thread_rng().gen();
             conn.pending_enchant_options.clear();
         }
 
         // Send updated XP attributes
-        let (rid, xp_level, xp_total, tick) = match self.connections.get(&addr) {
-            Some(c) => (c.entity_runtime_id, c.xp_level, c.xp_total, c.client_tick),
+        let (rid, tick) = match self.connections.get(&addr) {
+            Some(c) => (c.entity_runtime_id, c.client_tick),
             None => return reject,
         };
-        let xp_progress = mc_rs_game::xp::xp_progress(xp_level, xp_total);
+        let xp = self.experience(addr);
+        let (xp_level, xp_progress) = (xp.level, mc_rs_game::xp::xp_progress(xp.level, xp.total));
         self.send_packet(
             addr,
             packets::id::UPDATE_ATTRIBUTES,
@@ -1871,10 +2339,10 @@ impl ConnectionHandler {
             };
 
         // Validate XP
-        let has_enough_xp = match self.connections.get(&addr) {
-            Some(conn) => conn.xp_level >= result.xp_cost,
-            None => return reject,
-        };
+        if !self.connections.contains_key(&addr) {
+            return reject;
+        }
+        let has_enough_xp = self.experience(addr).level >= result.xp_cost;
         if !has_enough_xp {
             return reject;
         }
@@ -1892,17 +2360,16 @@ impl ConnectionHandler {
 
         // Deduct XP levels
         let cost = result.xp_cost;
-        if let Some(conn) = self.connections.get_mut(&addr) {
-            conn.xp_level -= cost;
-            conn.xp_total = mc_rs_game::xp::total_xp_for_level(conn.xp_level);
-        }
+        let xp_level = self.experience(addr).level - cost;
+        self.set_experience(addr, xp_level, mc_rs_game::xp::total_xp_for_level(xp_level));
 
         // Send updated XP attributes
-        let (rid, xp_level, xp_total, tick) = match self.connections.get(&addr) {
-            Some(c) => (c.entity_runtime_id, c.xp_level, c.xp_total, c.client_tick),
+        let (rid, tick) = match self.connections.get(&addr) {
+            Some(c) => (c.entity_runtime_id, c.client_tick),
             None => return reject,
         };
-        let xp_progress = mc_rs_game::xp::xp_progress(xp_level, xp_total);
+        let xp = self.experience(addr);
+        let (xp_level, xp_progress) = (xp.level, mc_rs_game::xp::xp_progress(xp.level, xp.total));
         self.send_packet(
             addr,
             packets::id::UPDATE_ATTRIBUTES,
@@ -1969,6 +2436,104 @@ impl ConnectionHandler {
         }
     }
 
+    /// Handle a beacon power selection paid for with the item in the payment slot.
+    async fn handle_beacon_payment(
+        &mut self,
+        addr: SocketAddr,
+        req: &mc_rs_proto::packets::item_stack_request::StackRequest,
+        primary: i32,
+        secondary: i32,
+    ) -> mc_rs_proto::packets::item_stack_response::StackResponseEntry {
+        use mc_rs_game::beacon;
+        use mc_rs_proto::packets::item_stack_response::{
+            StackResponseContainer, StackResponseEntry, StackResponseSlot,
+        };
+
+        let reject = StackResponseEntry {
+            request_id: req.request_id,
+            status: 1,
+            containers: Vec::new(),
+        };
+
+        let pos = match self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.open_container.as_ref())
+        {
+            Some(oc) if oc.container_type == 13 => oc.position,
+            _ => return reject,
+        };
+
+        // Payment must be one of the accepted mineral items
+        let payment_ok = match self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::Beacon { payment, .. }) if !payment.is_empty() => self
+                .item_registry
+                .get_by_id(payment.runtime_id as i16)
+                .is_some_and(|i| beacon::PAYMENT_ITEMS.contains(&i.name.as_str())),
+            _ => return reject,
+        };
+        if !payment_ok {
+            return reject;
+        }
+
+        // Powers must be unlocked by the current pyramid
+        let levels = self.beacon_levels(pos.x, pos.y, pos.z);
+        if !beacon::primary_allowed(primary, levels)
+            || !beacon::secondary_allowed(primary, secondary, levels)
+        {
+            return reject;
+        }
+
+        // Store the selection and consume one payment item
+        let payment_resp = match self.block_entities.get_mut(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::Beacon {
+                primary: p,
+                secondary: s,
+                payment,
+            }) => {
+                *p = primary;
+                *s = secondary;
+                payment.count -= 1;
+                if payment.count == 0 {
+                    *payment = mc_rs_proto::item_stack::ItemStack::empty();
+                }
+                payment.clone()
+            }
+            _ => return reject,
+        };
+
+        // Broadcast the new selection so clients update the beacon UI/beam
+        if let Some(be) = self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            let nbt = be.to_network_nbt(pos.x, pos.y, pos.z);
+            self.broadcast_packet(
+                packets::id::BLOCK_ACTOR_DATA,
+                &BlockActorData {
+                    position: pos,
+                    nbt_data: nbt,
+                },
+            )
+            .await;
+        }
+
+        debug!("Beacon at {pos} set to primary={primary} secondary={secondary} by {addr}");
+
+        StackResponseEntry {
+            request_id: req.request_id,
+            status: 0, // OK
+            containers: vec![StackResponseContainer {
+                container_id: 8, // BEACON_PAYMENT
+                slots: vec![StackResponseSlot {
+                    slot: 0,
+                    hotbar_slot: 0,
+                    count: payment_resp.count as u8,
+                    stack_network_id: payment_resp.stack_network_id,
+                    custom_name: String::new(),
+                    durability_correction: 0,
+                }],
+            }],
+        }
+    }
+
     /// Close any open containers at the given position for all players.
     async fn close_container_at(&mut self, pos: BlockPos) {
         let to_close: Vec<(SocketAddr, u8)> = self
@@ -2037,75 +2602,6 @@ impl ConnectionHandler {
         .await;
     }
 
-    /// Handle a block actor data update from the client (sign text editing).
-    pub(super) async fn handle_block_actor_data(
-        &mut self,
-        addr: SocketAddr,
-        buf: &mut Cursor<&[u8]>,
-    ) {
-        let pkt = match BlockActorData::proto_decode(buf) {
-            Ok(p) => p,
-            Err(e) => {
-                debug!("Bad BlockActorData from {addr}: {e}");
-                return;
-            }
-        };
-
-        let pos = pkt.position;
-
-        // Only handle sign edits for editable signs
-        let is_editable_sign = self
-            .block_entities
-            .get(&(pos.x, pos.y, pos.z, 0))
-            .map(|be| {
-                matches!(
-                    be,
-                    BlockEntityData::Sign {
-                        is_editable: true,
-                        ..
-                    }
-                )
-            })
-            .unwrap_or(false);
-
-        if !is_editable_sign {
-            return;
-        }
-
-        // Parse the sign text from the client's NBT
-        let (front, back) = match BlockEntityData::sign_from_network_nbt(&pkt.nbt_data) {
-            Some(t) => t,
-            None => return,
-        };
-
-        // Update the sign and mark as no longer editable
-        if let Some(BlockEntityData::Sign {
-            front_text,
-            back_text,
-            is_editable,
-        }) = self.block_entities.get_mut(&(pos.x, pos.y, pos.z, 0))
-        {
-            *front_text = front;
-            *back_text = back;
-            *is_editable = false;
-        }
-
-        // Broadcast updated sign to all players
-        if let Some(be) = self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
-            let nbt = be.to_network_nbt(pos.x, pos.y, pos.z);
-            self.broadcast_packet(
-                packets::id::BLOCK_ACTOR_DATA,
-                &BlockActorData {
-                    position: pos,
-                    nbt_data: nbt,
-                },
-            )
-            .await;
-        }
-
-        debug!("Sign edited at {pos} by {addr}");
-    }
-
     /// Handle right-click interact on a mob (feeding for breeding).
     async fn handle_feed_mob(&mut self, addr: SocketAddr, mob_runtime_id: u64) {
         // Get held item info
@@ -1,4 +1,5 @@
 use super::*;
+use crate::packs::{HostedPack, PackKind, PACK_CHUNK_SIZE};
 
 impl ConnectionHandler {
     pub(super) fn handle_session_connected(&mut self, addr: SocketAddr, guid: i64) {
@@ -23,41 +24,74 @@ impl ConnectionHandler {
                 client_tick: 0,
                 sent_chunks: HashSet::new(),
                 chunk_radius: 0,
+                requested_chunk_radius: 0,
+                view_distance: None,
                 dimension: self.dimension_id,
                 portal_cooldown_until: 0,
                 gamemode: gamemode_from_str(&self.server_config.server.gamemode),
                 breaking_block: None,
                 airborne_ticks: 0,
+                last_surface: Surface::Normal,
                 inventory: PlayerInventory::new(),
-                health: 20.0,
-                last_damage_tick: None,
                 is_dead: false,
                 is_sprinting: false,
-                effects: Vec::new(),
-                last_position_delta_y: 0.0,
+                is_sneaking: false,
+                is_crawling: false,
+                last_position_delta: Vec3::ZERO,
+                last_sent_move: None,
+                country: None,
                 fire_ticks: 0,
-                food: 20,
-                saturation: 5.0,
-                exhaustion: 0.0,
                 fall_distance: 0.0,
                 air_ticks: 300,
                 is_swimming: false,
-                xp_level: 0,
-                xp_total: 0,
-                pending_forms: HashMap::new(),
+                is_gliding: false,
+                glide_ticks: 0,
+                eating_since: None,
+                pending_forms: FormStack::default(),
+                kit_cooldowns: HashMap::new(),
+                item_cooldowns: HashMap::new(),
+                data_version: 0,
+                data_stale: false,
+                save_pending: false,
+                stats: PlayerStats::default(),
                 open_container: None,
+                editing_sign: None,
+                custom_skin: false,
+                emote_pieces: Vec::new(),
                 next_window_id: 1,
                 enchant_seed: rand::thread_rng().gen(),
                 pending_enchant_options: Vec::new(),
                 tags: HashSet::new(),
-                protocol_version: packets::PROTOCOL_VERSION,
-                violations: ViolationTracker::default(),
+                camera_presets_sent: false,
+                fog: FogStack::default(),
+                input_locks: 0,
+                protocol: ProtocolAdapter::default(),
+                pack_chunk_queue: VecDeque::new(),
+                offered_packs: Vec::new(),
+                accepted_packs: Vec::new(),
+                violations: ViolationScores::default(),
                 last_break_tick: 0,
                 last_place_tick: 0,
                 last_attack_tick: 0,
                 last_command_tick: 0,
+                last_emote_tick: 0,
+                last_chat_tick: None,
                 actions_this_second: 0,
                 action_second_start: 0,
+                last_active_tick: self.game_world.current_tick(),
+                afk: false,
+                homes: HashMap::new(),
+                back_location: None,
+                pending_teleport: None,
+                last_teleport_tick: None,
+                tpa_requests: Vec::new(),
+                world_positions: HashMap::new(),
+                sleeping: None,
+                ignored: HashSet::new(),
+                reply_to: None,
+                social_spy: false,
+                outbox: Vec::new(),
+                outbox_bytes: 0,
             },
         );
 
@@ -76,45 +110,51 @@ impl ConnectionHandler {
     }
 
     pub(super) async fn handle_session_disconnected(&mut self, addr: SocketAddr) {
+        self.pending_logins.remove(&addr);
         // Save player data before removing connection
-        if let Some(conn) = self.connections.get(&addr) {
-            if conn.state == LoginState::InGame {
-                if let Some(ref login) = conn.login_data {
-                    let player_data = PlayerData::from_connection(conn);
-                    if let Err(e) = player_data.save(&self.world_dir, &login.identity) {
-                        warn!("Failed to save player data for {}: {e}", login.display_name);
-                    }
-                }
-            }
+        if self
+            .connections
+            .get(&addr)
+            .is_some_and(|c| c.state == LoginState::InGame)
+        {
+            self.save_player_data(addr).await;
         }
 
         // Collect data before removing from connections
-        let (was_in_game, entity_unique_id, uuid, display_name) = match self.connections.get(&addr)
-        {
-            Some(conn) => {
-                let in_game = conn.state == LoginState::InGame;
-                let uid = conn.entity_unique_id;
-                let (uuid, name) = match &conn.login_data {
-                    Some(d) => (
-                        Uuid::parse(&d.identity).unwrap_or(Uuid::ZERO),
-                        d.display_name.clone(),
-                    ),
-                    None => (Uuid::ZERO, String::new()),
-                };
-                (in_game, uid, uuid, name)
-            }
-            None => return,
-        };
+        let (was_in_game, entity_unique_id, uuid, display_name, dim, chunks) =
+            match self.connections.get(&addr) {
+                Some(conn) => {
+                    let in_game = conn.state == LoginState::InGame;
+                    let uid = conn.entity_unique_id;
+                    let (uuid, name) = match &conn.login_data {
+                        Some(d) => (
+                            Uuid::parse(&d.identity).unwrap_or(Uuid::ZERO),
+                            d.display_name.clone(),
+                        ),
+                        None => (Uuid::ZERO, String::new()),
+                    };
+                    let chunks: Vec<(i32, i32)> = conn.sent_chunks.iter().copied().collect();
+                    (in_game, uid, uuid, name, conn.dimension, chunks)
+                }
+                None => return,
+            };
 
         // Plugin event: PlayerQuit
         if was_in_game {
             if let Some(conn) = self.connections.get(&addr) {
-                let player = Self::make_plugin_player(conn);
+                let player = self.make_plugin_player(conn);
                 let event = PluginEvent::PlayerQuit { player };
                 let snapshot = self.build_snapshot();
                 let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
                 self.apply_plugin_actions(actions).await;
             }
+            self.bridge.post(BridgeEvent::Leave {
+                player: display_name.clone(),
+            });
+            self.forget_arena_player(&display_name);
+            self.cutscenes.stop(&display_name);
+            self.entity_appearances.remove(&(entity_unique_id as u64));
+            self.actor_data.forget(entity_unique_id as u64);
         }
 
         // Clean up projectiles belonging to this player
@@ -123,6 +163,7 @@ impl ConnectionHandler {
         // Remove the connection
         self.runtime_id_to_addr.remove(&(entity_unique_id as u64));
         self.connections.remove(&addr);
+        self.chunks_left_view(addr, dim, &chunks).await;
 
         // Despawn the ECS mirror entity for this player
         self.game_world.despawn_player(entity_unique_id);
@@ -198,6 +239,23 @@ impl ConnectionHandler {
                     continue;
                 }
             };
+            self.activity
+                .record_packet(addr, packet_id, sub_packet.len());
+
+            if matches!(
+                packet_id,
+                packets::id::TEXT
+                    | packets::id::COMMAND_REQUEST
+                    | packets::id::MOB_EQUIPMENT
+                    | packets::id::INVENTORY_TRANSACTION
+                    | packets::id::PLAYER_ACTION
+                    | packets::id::ITEM_STACK_REQUEST
+                    | packets::id::ANIMATE
+                    | packets::id::MODAL_FORM_RESPONSE
+                    | packets::id::EMOTE
+            ) {
+                self.mark_active(addr).await;
+            }
 
             match packet_id {
                 packets::id::REQUEST_NETWORK_SETTINGS => {
@@ -232,6 +290,9 @@ impl ConnectionHandler {
                 packets::id::COMMAND_REQUEST => {
                     self.handle_command_request(addr, &mut cursor).await;
                 }
+                packets::id::COMMAND_BLOCK_UPDATE => {
+                    self.handle_command_block_update(addr, &mut cursor).await;
+                }
                 packets::id::MOB_EQUIPMENT => {
                     self.handle_mob_equipment(addr, &mut cursor).await;
                 }
@@ -262,9 +323,18 @@ impl ConnectionHandler {
                 packets::id::BLOCK_ACTOR_DATA => {
                     self.handle_block_actor_data(addr, &mut cursor).await;
                 }
+                packets::id::MAP_INFO_REQUEST => {
+                    self.handle_map_info_request(addr, &mut cursor).await;
+                }
                 packets::id::PLAYER_SKIN => {
                     self.handle_player_skin(addr, &mut cursor).await;
                 }
+                packets::id::EMOTE => {
+                    self.handle_emote(addr, &mut cursor).await;
+                }
+                packets::id::EMOTE_LIST => {
+                    self.handle_emote_list(addr, &mut cursor).await;
+                }
                 packets::id::SERVERBOUND_LOADING_SCREEN => {
                     // Parse the loading screen type for debugging
                     use mc_rs_proto::codec::ProtoDecode;
@@ -309,23 +379,24 @@ impl ConnectionHandler {
             request.protocol_version
         );
 
-        if !packets::is_supported_version(request.protocol_version) {
-            let status = if request.protocol_version < packets::MIN_PROTOCOL_VERSION {
+        let Some(protocol) = ProtocolAdapter::new(request.protocol_version) else {
+            let status = if request.protocol_version < packets::PROTOCOL_VERSION {
                 PlayStatusType::FailedClient
             } else {
                 PlayStatusType::FailedServer
             };
+            let supported: Vec<i32> = packets::SUPPORTED_VERSIONS
+                .iter()
+                .map(|&(p, _)| p)
+                .collect();
             info!(
-                "Protocol mismatch from {addr}: got {}, supported {}-{} -> {:?}",
+                "Protocol mismatch from {addr}: got {}, supported {supported:?} -> {status:?}",
                 request.protocol_version,
-                packets::MIN_PROTOCOL_VERSION,
-                packets::PROTOCOL_VERSION,
-                status
             );
             self.send_packet(addr, packets::id::PLAY_STATUS, &PlayStatus { status })
                 .await;
             return;
-        }
+        };
 
         let settings = NetworkSettings::default();
         self.send_packet(addr, packets::id::NETWORK_SETTINGS, &settings)
@@ -337,7 +408,7 @@ impl ConnectionHandler {
                 CompressionAlgorithm::from_u16(settings.compression_algorithm)
                     .unwrap_or(CompressionAlgorithm::Zlib);
             conn.batch_config.compression_threshold = settings.compression_threshold as usize;
-            conn.protocol_version = request.protocol_version;
+            conn.protocol = protocol;
             conn.state = LoginState::AwaitingLogin;
         }
 
@@ -374,7 +445,7 @@ impl ConnectionHandler {
             }
         };
 
-        let login_data = match jwt::extract_login_data(&login.chain_data) {
+        let mut login_data = match jwt::extract_login_data(&login.chain_data) {
             Ok(data) => data,
             Err(e) => {
                 warn!("JWT extraction failed for {addr}: {e}");
@@ -388,15 +459,68 @@ impl ConnectionHandler {
             }
         };
 
+        // Online mode: the chain must be signed by Mojang and vouch for the
+        // claimed XUID; the client data must be signed by the chain's key.
+        if self.online_mode {
+            let verified =
+                verify_login_chain(&login.chain_data, crate::persistence::unix_timestamp())
+                    .and_then(|identity| {
+                        verify_jwt(&login.client_data_jwt, &identity.identity_public_key)
+                            .map(|_| identity)
+                    });
+            let rejection = match verified {
+                Err(e) => Some(e.to_string()),
+                Ok(identity)
+                    if identity.xuid != login_data.xuid
+                        || identity.identity_public_key != login_data.identity_public_key =>
+                {
+                    Some(format!(
+                        "XUID mismatch (claimed {}, verified {})",
+                        login_data.xuid, identity.xuid
+                    ))
+                }
+                Ok(_) => None,
+            };
+            if let Some(reason) = rejection {
+                warn!("Xbox Live authentication failed for {addr}: {reason}");
+                self.send_packet(
+                    addr,
+                    packets::id::DISCONNECT,
+                    &Disconnect::with_message("Authentication failed"),
+                )
+                .await;
+                return;
+            }
+        }
+
         // Parse client data (skin, device info) from the client_data JWT
-        let client_data = jwt::extract_client_data(&login.client_data_jwt).unwrap_or_else(|e| {
-            warn!("Failed to parse client_data from {addr}: {e}, using defaults");
-            jwt::ClientData::default()
-        });
+        let mut client_data =
+            jwt::extract_client_data(&login.client_data_jwt).unwrap_or_else(|e| {
+                warn!("Failed to parse client_data from {addr}: {e}, using defaults");
+                jwt::ClientData::default()
+            });
+        // A skin that could crash other clients is never relayed
+        if let Err(reason) = mc_rs_game::skin::validate_skin(&client_data) {
+            warn!("Invalid skin from {addr}: {reason}, using the default skin");
+            client_data = jwt::ClientData {
+                device_id: client_data.device_id,
+                device_os: client_data.device_os,
+                ..jwt::ClientData::default()
+            };
+        }
 
+        // Offline mode: trust an identity forwarded by another server of the network
+        if !self.online_mode {
+            self.claim_handoff(addr, &mut login_data);
+        }
+
+        let country = self.lookup_country(addr);
         info!(
-            "Login from {addr}: {} (XUID: {}, UUID: {})",
-            login_data.display_name, login_data.xuid, login_data.identity
+            "Login from {addr} ({}): {} (XUID: {}, UUID: {})",
+            country.as_deref().unwrap_or("unknown country"),
+            login_data.display_name,
+            login_data.xuid,
+            login_data.identity
         );
 
         // Check IP ban
@@ -451,6 +575,52 @@ impl ConnectionHandler {
             return;
         }
 
+        // Check country
+        if !self.country_allowed(country.as_deref()) {
+            info!(
+                "Rejected player {} from {}",
+                login_data.display_name,
+                country.as_deref().unwrap_or("an unknown country")
+            );
+            self.send_packet(
+                addr,
+                packets::id::DISCONNECT,
+                &Disconnect::with_message("Your country is not allowed on this server."),
+            )
+            .await;
+            return;
+        }
+
+        // Check the blocklist, then ask the webhook without holding up the server
+        if let Err(message) = self.prelogin.check_blocklist(addr.ip()) {
+            info!(
+                "Rejected blocklisted player {} ({ip_str})",
+                login_data.display_name
+            );
+            self.send_packet(
+                addr,
+                packets::id::DISCONNECT,
+                &Disconnect::with_message(message),
+            )
+            .await;
+            return;
+        }
+        if self.prelogin.has_webhook() {
+            self.start_prelogin_check(addr, login_data, client_data);
+            return;
+        }
+
+        self.finish_login(addr, login_data, client_data).await;
+    }
+
+    /// Log in a player who passed every check: start encryption in online
+    /// mode, or go straight to the resource packs.
+    pub(super) async fn finish_login(
+        &mut self,
+        addr: SocketAddr,
+        login_data: jwt::LoginData,
+        client_data: jwt::ClientData,
+    ) {
         if self.online_mode {
             // Store client_data before encryption handshake
             if let Some(conn) = self.connections.get_mut(&addr) {
@@ -590,25 +760,34 @@ impl ConnectionHandler {
         use mc_rs_proto::packets::resource_packs_info::ResourcePackInfoEntry;
         use mc_rs_proto::types::Uuid;
 
-        let pack_entries: Vec<ResourcePackInfoEntry> = self
-            .behavior_packs
+        let offered = self.packs_for(addr);
+        // The client can only accept or refuse the whole list: make it
+        // mandatory only when nothing in it may be declined
+        let must_accept = !offered.is_empty()
+            && offered
+                .iter()
+                .all(|pack| pack.is_required(&self.server_config.packs));
+        let offered_uuids: Vec<String> = offered.iter().map(|p| p.uuid.clone()).collect();
+        let pack_entries: Vec<ResourcePackInfoEntry> = offered
             .iter()
             .map(|pack| ResourcePackInfoEntry {
-                pack_id: Uuid::parse(&pack.manifest.header.uuid).unwrap_or(Uuid::ZERO),
-                version: pack.manifest.version_string(),
-                size: pack.pack_size,
-                encryption_key: String::new(),
+                pack_id: Uuid::parse(&pack.uuid).unwrap_or(Uuid::ZERO),
+                version: pack.version.clone(),
+                size: pack.bytes.len() as u64,
+                encryption_key: pack.content_key.clone(),
                 sub_pack_name: String::new(),
-                content_id: String::new(),
-                has_scripts: false,
-                is_addon_pack: true,
+                content_id: pack.content_id.clone(),
+                has_scripts: pack.has_scripts,
+                is_addon_pack: pack.kind == PackKind::Behavior,
                 is_rtx_capable: false,
                 cdn_url: String::new(),
             })
             .collect();
 
         let pack_info = ResourcePacksInfo {
-            must_accept: self.server_config.packs.force_packs && !self.behavior_packs.is_empty(),
+            must_accept,
+            has_addons: pack_entries.iter().any(|p| p.is_addon_pack),
+            has_scripts: pack_entries.iter().any(|p| p.has_scripts),
             resource_packs: pack_entries,
             ..ResourcePacksInfo::default()
         };
@@ -619,6 +798,7 @@ impl ConnectionHandler {
 
         if let Some(conn) = self.connections.get_mut(&addr) {
             conn.state = LoginState::AwaitingResourcePackResponse;
+            conn.offered_packs = offered_uuids;
         }
 
         if pack_count > 0 {
@@ -653,34 +833,24 @@ impl ConnectionHandler {
 
         match (current_state, response.status) {
             (LoginState::AwaitingResourcePackResponse, ResourcePackResponseStatus::SendPacks) => {
-                // Client wants us to send pack data — collect DataInfo packets first
+                // Client wants us to send pack data — describe each pack, then
+                // serve its ResourcePackChunkRequests.
                 let data_infos: Vec<(packets::ResourcePackDataInfo, String)> = response
                     .resource_pack_ids
                     .iter()
-                    .filter_map(|pack_id_str| {
-                        let uuid = pack_id_str.split('_').next().unwrap_or(pack_id_str);
-                        let pack = self
-                            .behavior_packs
-                            .iter()
-                            .find(|p| p.manifest.header.uuid == uuid)?;
-                        let bytes = pack.pack_bytes.as_ref()?;
-                        let chunk_size: u32 = 1_048_576;
-                        let chunk_count = (bytes.len() as u64).div_ceil(chunk_size as u64) as u32;
+                    .filter_map(|pack_id| {
+                        let pack = self.packs.find(pack_id)?;
                         Some((
                             packets::ResourcePackDataInfo {
-                                pack_id: format!(
-                                    "{}_{}",
-                                    pack.manifest.header.uuid,
-                                    pack.manifest.version_string()
-                                ),
-                                max_chunk_size: chunk_size,
-                                chunk_count,
-                                pack_size: bytes.len() as u64,
-                                pack_hash: String::new(),
+                                pack_id: pack.pack_id(),
+                                max_chunk_size: PACK_CHUNK_SIZE,
+                                chunk_count: pack.chunk_count(),
+                                pack_size: pack.bytes.len() as u64,
+                                pack_hash: pack.hash.to_vec(),
                                 is_premium: false,
-                                pack_type: 2,
+                                pack_type: pack.kind.wire_type(),
                             },
-                            pack.manifest.header.name.clone(),
+                            pack.name.clone(),
                         ))
                     })
                     .collect();
@@ -700,33 +870,20 @@ impl ConnectionHandler {
             )
             | (LoginState::AwaitingResourcePackResponse, ResourcePackResponseStatus::Completed) => {
                 // Client has all packs (or none needed) — send stack
-                use mc_rs_proto::packets::resource_pack_stack::StackPackEntry;
-
-                let pack_stack: Vec<StackPackEntry> = self
-                    .behavior_packs
-                    .iter()
-                    .map(|pack| StackPackEntry {
-                        uuid: pack.manifest.header.uuid.clone(),
-                        version: pack.manifest.version_string(),
-                        sub_pack_name: String::new(),
-                    })
-                    .collect();
-
-                let stack = ResourcePackStack {
-                    must_accept: self.server_config.packs.force_packs
-                        && !self.behavior_packs.is_empty(),
-                    resource_pack_stack: pack_stack,
-                    ..ResourcePackStack::default()
-                };
-
-                self.send_packet(addr, packets::id::RESOURCE_PACK_STACK, &stack)
-                    .await;
-
+                self.send_resource_pack_stack(addr, true).await;
+            }
+            (LoginState::AwaitingResourcePackResponse, ResourcePackResponseStatus::Refused) => {
+                if self.offered_required_packs(addr) {
+                    info!("{addr} refused the required resource packs");
+                    let prompt = self.server_config.packs.required_prompt.clone();
+                    self.disconnect_player(addr, &prompt).await;
+                    return;
+                }
+                // Packs are optional: continue without them
                 if let Some(conn) = self.connections.get_mut(&addr) {
-                    conn.state = LoginState::AwaitingResourcePackComplete;
+                    conn.pack_chunk_queue.clear();
                 }
-
-                info!("Sent ResourcePackStack to {addr}");
+                self.send_resource_pack_stack(addr, false).await;
             }
             (LoginState::AwaitingResourcePackComplete, ResourcePackResponseStatus::Completed) => {
                 // Resource packs done — start game initialization
@@ -742,7 +899,57 @@ impl ConnectionHandler {
         }
     }
 
-    /// Handle a ResourcePackChunkRequest (0x54) — send the requested chunk data.
+    /// Send the ResourcePackStack; `with_packs` is false when the client refused them.
+    async fn send_resource_pack_stack(&mut self, addr: SocketAddr, with_packs: bool) {
+        use mc_rs_proto::packets::resource_pack_stack::StackPackEntry;
+
+        let accepted: Vec<&HostedPack> = match self.connections.get(&addr) {
+            Some(conn) if with_packs => conn
+                .offered_packs
+                .iter()
+                .filter_map(|uuid| self.packs.find(uuid))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let must_accept = !accepted.is_empty()
+            && accepted
+                .iter()
+                .all(|pack| pack.is_required(&self.server_config.packs));
+        let accepted_uuids: Vec<String> = accepted.iter().map(|p| p.uuid.clone()).collect();
+        let pack_stack: Vec<StackPackEntry> = accepted
+            .iter()
+            .map(|pack| StackPackEntry {
+                uuid: pack.uuid.clone(),
+                version: pack.version.clone(),
+                sub_pack_name: String::new(),
+            })
+            .collect();
+
+        let game_version = self
+            .connections
+            .get(&addr)
+            .map(|c| c.protocol.game_version())
+            .unwrap_or_default();
+        let stack = ResourcePackStack {
+            must_accept,
+            resource_pack_stack: pack_stack,
+            game_version: game_version.into(),
+            ..ResourcePackStack::default()
+        };
+
+        self.send_packet(addr, packets::id::RESOURCE_PACK_STACK, &stack)
+            .await;
+
+        if let Some(conn) = self.connections.get_mut(&addr) {
+            conn.state = LoginState::AwaitingResourcePackComplete;
+            conn.accepted_packs = accepted_uuids;
+        }
+
+        info!("Sent ResourcePackStack to {addr}");
+    }
+
+    /// Handle a ResourcePackChunkRequest (0x54) — queue the chunk; queued
+    /// chunks are sent a few per tick by [`Self::tick_pack_transfers`].
     pub(super) async fn handle_resource_pack_chunk_request(
         &mut self,
         addr: SocketAddr,
@@ -756,52 +963,59 @@ impl ConnectionHandler {
             }
         };
 
-        // Extract chunk data from the pack (collect before send to avoid borrow conflict)
-        let uuid = request
-            .pack_id
-            .split('_')
-            .next()
-            .unwrap_or(&request.pack_id);
-        let chunk_size: usize = 1_048_576;
+        let valid = self
+            .packs
+            .find(&request.pack_id)
+            .is_some_and(|pack| request.chunk_index < pack.chunk_count());
+        if !valid {
+            warn!(
+                "Invalid chunk request {} for pack {} from {addr}",
+                request.chunk_index, request.pack_id
+            );
+            return;
+        }
 
-        let chunk_data = self
-            .behavior_packs
-            .iter()
-            .find(|p| p.manifest.header.uuid == uuid)
-            .and_then(|pack| {
-                let bytes = pack.pack_bytes.as_ref()?;
-                let offset = request.chunk_index as usize * chunk_size;
-                if offset >= bytes.len() {
-                    return None;
-                }
-                let end = (offset + chunk_size).min(bytes.len());
-                Some((
-                    packets::ResourcePackChunkData {
-                        pack_id: request.pack_id.clone(),
-                        chunk_index: request.chunk_index,
-                        progress: offset as u64,
-                        data: bytes[offset..end].to_vec(),
-                    },
-                    bytes.len().div_ceil(chunk_size),
-                ))
-            });
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        if conn.state != LoginState::AwaitingResourcePackResponse {
+            return;
+        }
+        let entry = (request.pack_id, request.chunk_index);
+        if !conn.pack_chunk_queue.contains(&entry) {
+            conn.pack_chunk_queue.push_back(entry);
+        }
+    }
 
-        match chunk_data {
-            Some((data, total_chunks)) => {
-                self.send_packet(addr, packets::id::RESOURCE_PACK_CHUNK_DATA, &data)
-                    .await;
-                debug!(
-                    "Sent chunk {}/{total_chunks} for pack {} to {addr}",
-                    request.chunk_index, request.pack_id,
-                );
-            }
-            None => {
-                warn!(
-                    "Failed to find chunk {} for pack {} from {addr}",
-                    request.chunk_index, request.pack_id
-                );
+    /// Send up to `packs.chunks_per_tick` queued pack chunks to each client.
+    pub(super) async fn tick_pack_transfers(&mut self) {
+        let per_tick = self.server_config.packs.chunks_per_tick.max(1);
+        let mut to_send = Vec::new();
+        for (&addr, conn) in &mut self.connections {
+            for _ in 0..per_tick {
+                match conn.pack_chunk_queue.pop_front() {
+                    Some(entry) => to_send.push((addr, entry)),
+                    None => break,
+                }
             }
         }
+
+        for (addr, (pack_id, chunk_index)) in to_send {
+            let Some(data) = self.packs.find(&pack_id).and_then(|pack| {
+                let (progress, bytes) = pack.chunk(chunk_index)?;
+                Some(packets::ResourcePackChunkData {
+                    pack_id: pack_id.clone(),
+                    chunk_index,
+                    progress,
+                    data: bytes.to_vec(),
+                })
+            }) else {
+                continue;
+            };
+            self.send_packet(addr, packets::id::RESOURCE_PACK_CHUNK_DATA, &data)
+                .await;
+            debug!("Sent chunk {chunk_index} for pack {pack_id} to {addr}");
+        }
     }
 
     // -----------------------------------------------------------------------
@@ -809,41 +1023,89 @@ impl ConnectionHandler {
     // -----------------------------------------------------------------------
 
     async fn send_start_game(&mut self, addr: SocketAddr) {
-        let (entity_unique_id, entity_runtime_id, player_uuid, client_proto) =
+        let (entity_unique_id, entity_runtime_id, player_xuid, player_uuid, client_proto) =
             match self.connections.get(&addr) {
                 Some(c) => {
-                    let uuid = c
+                    let (xuid, uuid) = c
                         .login_data
                         .as_ref()
-                        .map(|d| d.identity.clone())
+                        .map(|d| (d.xuid.clone(), d.identity.clone()))
                         .unwrap_or_default();
                     (
                         c.entity_unique_id,
                         c.entity_runtime_id,
+                        xuid,
                         uuid,
-                        c.protocol_version,
+                        c.protocol,
                     )
                 }
                 None => return,
             };
 
-        // Try to load saved player data
-        let saved = if !player_uuid.is_empty() {
-            PlayerData::load(&self.world_dir, &player_uuid)
+        // Try to load saved player data. If it exists but cannot be read,
+        // refuse the login rather than start over and overwrite it later.
+        let (saved, data_version) = if !player_uuid.is_empty() {
+            match self.player_store.load(&player_xuid, &player_uuid).await {
+                Ok(Some((data, version))) => (Some(data), version),
+                Ok(None) => (None, 0),
+                Err(e) => {
+                    warn!("Failed to load player data for {player_uuid}: {e}");
+                    self.disconnect_player(
+                        addr,
+                        "Could not load your player data, please try again later",
+                    )
+                    .await;
+                    return;
+                }
+            }
         } else {
-            None
+            (None, 0)
         };
 
         // Apply saved data to connection (position, health, inventory, effects, etc.)
-        let player_position = if let Some(ref data) = saved {
+        let mut player_position = if let Some(ref data) = saved {
             if let Some(conn) = self.connections.get_mut(&addr) {
-                data.apply_to_connection(conn);
+                data.apply_to_connection(conn, &mut self.game_world);
+                conn.data_version = data_version;
             }
             Vec3::new(data.position[0], data.position[1], data.position[2])
         } else {
             self.spawn_position
         };
 
+        // Players saved in a world that is no longer hosted go to spawn
+        let saved_dim = self.connections.get(&addr).map_or(0, |c| c.dimension);
+        if !self.dimension_exists(saved_dim) {
+            player_position = self.spawn_position;
+            if let Some(conn) = self.connections.get_mut(&addr) {
+                conn.dimension = self.dimension_id;
+                conn.position = self.spawn_position;
+            }
+        }
+        let player_dimension = self
+            .connections
+            .get(&addr)
+            .map_or(self.dimension_id, |c| self.client_dimension(c.dimension));
+        let time = self.world_time(self.connections.get(&addr).map_or(0, |c| c.dimension));
+
+        // First join: grant starter kits (inventory is synced during spawn)
+        if saved.is_none() {
+            let now = crate::persistence::unix_timestamp();
+            for name in self.kits.first_join_kits() {
+                let kit = match self.kits.get(&name) {
+                    Some(k) => k.clone(),
+                    None => continue,
+                };
+                if self.grant_kit_items(addr, &kit).is_none() {
+                    warn!("First-join kit {name} doesn't fit in {addr}'s inventory");
+                    continue;
+                }
+                if let Some(conn) = self.connections.get_mut(&addr) {
+                    conn.kit_cooldowns.insert(name, now);
+                }
+            }
+        }
+
         let player_rotation = if let Some(ref data) = saved {
             Vec2::new(data.pitch, data.yaw)
         } else {
@@ -856,7 +1118,7 @@ impl ConnectionHandler {
         } else {
             gamemode_from_str(&config.server.gamemode)
         };
-        let difficulty = difficulty_from_str(&config.server.difficulty);
+        let difficulty = self.difficulty.id();
         let generator = generator_from_str(&config.world.generator);
         let enchant_seed = self
             .connections
@@ -871,21 +1133,21 @@ impl ConnectionHandler {
             player_position,
             rotation: player_rotation,
             seed: config.world.seed as u64,
-            dimension: self.dimension_id,
+            dimension: player_dimension,
             generator,
             world_gamemode: gamemode,
             difficulty,
             spawn_position: self.spawn_block,
             level_id: "level".into(),
             world_name: config.world.name.clone(),
-            game_version: packets::game_version_for_protocol(client_proto).into(),
+            game_version: client_proto.game_version().into(),
             rain_level: self.rain_level,
             lightning_level: self.lightning_level,
-            current_tick: self.world_time,
+            current_tick: time,
             day_cycle_stop_time: if self.do_daylight_cycle {
                 -1
             } else {
-                self.world_time as i32
+                time as i32
             },
             game_rules: vec![
                 GameRule {
@@ -903,13 +1165,27 @@ impl ConnectionHandler {
                     editable: false,
                     value: GameRuleValue::Bool(self.do_weather_cycle),
                 },
+                GameRule {
+                    name: "keepinventory".into(),
+                    editable: false,
+                    value: GameRuleValue::Bool(self.keep_inventory),
+                },
                 GameRule {
                     name: "commandblocksenabled".into(),
                     editable: false,
-                    value: GameRuleValue::Bool(false),
+                    value: GameRuleValue::Bool(self.command_blocks_enabled),
+                },
+                GameRule {
+                    name: "playerssleepingpercentage".into(),
+                    editable: false,
+                    value: GameRuleValue::Int(self.players_sleeping_percentage),
                 },
-            ],
+            ]
+            .into_iter()
+            .chain(self.client_game_rules())
+            .collect(),
             enchantment_seed: enchant_seed,
+            block_properties: self.custom_blocks.palette(),
             ..StartGame::default()
         };
 
@@ -922,12 +1198,16 @@ impl ConnectionHandler {
             .item_registry
             .item_table_entries()
             .into_iter()
-            .map(|e| packets::ItemRegistryEntry {
-                string_id: e.string_id,
-                numeric_id: e.numeric_id,
-                is_component_based: e.is_component_based,
-                version: 0,
-                component_nbt: Vec::new(),
+            .map(|e| {
+                // Custom items are data-driven (version 1) and carry their definition
+                let definition = self.custom_items.definition_nbt(&e.string_id, e.numeric_id);
+                packets::ItemRegistryEntry {
+                    version: if definition.is_some() { 1 } else { 0 },
+                    component_nbt: definition.unwrap_or_default(),
+                    string_id: e.string_id,
+                    numeric_id: e.numeric_id,
+                    is_component_based: e.is_component_based,
+                }
             })
             .collect();
         let item_registry_pkt = packets::ItemRegistry {
@@ -993,45 +1273,93 @@ impl ConnectionHandler {
                 return;
             }
         };
+        let form_id = response.form_id;
+        let response_data = response.response_data.as_deref();
 
-        // Look up the form type from pending_forms
-        let form_type = match self.connections.get_mut(&addr) {
-            Some(conn) => conn.pending_forms.remove(&response.form_id),
-            None => return,
+        let Some(form) = self
+            .connections
+            .get_mut(&addr)
+            .and_then(|c| c.pending_forms.answer(form_id))
+        else {
+            debug!("ModalFormResponse for unknown form_id {form_id} from {addr}");
+            return;
         };
 
-        let form_type = match form_type {
-            Some(ft) => ft,
-            None => {
-                debug!(
-                    "ModalFormResponse for unknown form_id {} from {addr}",
-                    response.form_id
-                );
-                return;
-            }
-        };
+        if !self
+            .handle_npc_dialogue_response(addr, &form.form_type, response_data)
+            .await
+        {
+            self.answer_plugin_form(addr, &form, response_data).await;
+        }
+        if let Some(conn) = self.connections.get_mut(&addr) {
+            conn.pending_forms.settle();
+        }
+    }
 
-        // Parse the response based on form type
+    /// Tell the plugin that showed a form the player's answer, unless it
+    /// breaks the rules of the form's inputs: then show the form again.
+    async fn answer_plugin_form(
+        &mut self,
+        addr: SocketAddr,
+        form: &PendingForm,
+        response_data: Option<&str>,
+    ) {
         let form_response =
-            mc_rs_plugin_api::parse_form_response(&form_type, response.response_data.as_deref());
+            mc_rs_plugin_api::parse_form_response(&form.form_type, &form.form_data, response_data);
+
+        let errors = mc_rs_plugin_api::validate_form_response(&form.form_data, &form_response);
+        if !errors.is_empty() {
+            if let Some(conn) = self.connections.get_mut(&addr) {
+                conn.pending_forms.reopen(form.form_id);
+            }
+            let again =
+                mc_rs_plugin_api::form_with_errors(&form.form_data, &form_response, &errors);
+            self.send_form(addr, form.form_id, &again).await;
+            return;
+        }
+
+        // Closing a chained form goes back to the one it was opened from
+        if matches!(form_response, mc_rs_plugin_api::FormResponse::Cancelled) {
+            let previous = self
+                .connections
+                .get_mut(&addr)
+                .and_then(|c| c.pending_forms.go_back(form.form_id));
+            if let Some(previous) = previous {
+                self.send_form(addr, previous.form_id, &previous.form_data)
+                    .await;
+            }
+        }
 
         // Build plugin player and dispatch event
         let player = match self.connections.get(&addr) {
-            Some(conn) => Self::make_plugin_player(conn),
+            Some(conn) => self.make_plugin_player(conn),
             None => return,
         };
 
         let event = PluginEvent::FormResponse {
             player,
-            form_id: response.form_id,
+            form_id: form.form_id,
             response: form_response,
         };
 
         let snapshot = self.build_snapshot();
-        let (_result, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+        let (_result, actions) = match &form.plugin {
+            Some(plugin) => self.plugin_manager.dispatch_to(plugin, &event, &snapshot),
+            None => self.plugin_manager.dispatch(&event, &snapshot),
+        };
         self.apply_plugin_actions(actions).await;
     }
 
+    /// Show a player a form, without the rules of its inputs.
+    pub(super) async fn send_form(&mut self, addr: SocketAddr, form_id: u32, form_data: &str) {
+        let pkt = packets::ModalFormRequest {
+            form_id,
+            form_data: mc_rs_plugin_api::client_form_json(form_data),
+        };
+        self.send_packet(addr, packets::id::MODAL_FORM_REQUEST, &pkt)
+            .await;
+    }
+
     // -----------------------------------------------------------------------
     // PlayerSkin (0x5D)
     // -----------------------------------------------------------------------
@@ -1045,7 +1373,35 @@ impl ConnectionHandler {
             }
         };
 
-        // Update the stored skin data for this player.
+        // The skin belongs to the sender, whatever UUID the packet names
+        let (uuid, current) = match self.connections.get(&addr) {
+            Some(c) if c.state == LoginState::InGame => (
+                c.login_data
+                    .as_ref()
+                    .and_then(|d| Uuid::parse(&d.identity).ok())
+                    .unwrap_or(Uuid::ZERO),
+                c.client_data.clone(),
+            ),
+            _ => return,
+        };
+
+        if let Err(reason) = mc_rs_game::skin::validate_skin(&pkt.skin_data) {
+            warn!("Rejected skin change from {addr}: {reason}");
+            // Put the current skin back on the sender's client
+            if let Some(skin_data) = current {
+                let revert = packets::PlayerSkin {
+                    uuid,
+                    skin_data,
+                    new_skin_name: pkt.old_skin_name,
+                    old_skin_name: pkt.new_skin_name,
+                };
+                self.send_packet(addr, packets::id::PLAYER_SKIN, &revert)
+                    .await;
+            }
+            return;
+        }
+
+        // Update the stored skin data for this player; it is now saved with them.
         if let Some(conn) = self.connections.get_mut(&addr) {
             if let Some(ref mut cd) = conn.client_data {
                 cd.skin_id = pkt.skin_data.skin_id.clone();
@@ -1059,11 +1415,12 @@ impl ConnectionHandler {
                 cd.persona_skin = pkt.skin_data.persona_skin;
                 cd.play_fab_id = pkt.skin_data.play_fab_id.clone();
             }
+            conn.custom_skin = true;
         }
 
         // Broadcast the PlayerSkin packet to all other players.
         let broadcast = packets::PlayerSkin {
-            uuid: pkt.uuid,
+            uuid,
             skin_data: pkt.skin_data,
             new_skin_name: pkt.new_skin_name,
             old_skin_name: pkt.old_skin_name,
@@ -0,0 +1,579 @@
+//! Filled maps: created from empty maps, rendered around the players
+//! holding them, and cloned or zoomed out in a cartography table.
+
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::net::SocketAddr;
+
+use mc_rs_game::block_entity::BlockEntityData;
+use mc_rs_game::map::{self, block_map_color, CartographyCraft, MapData, MapSample, MAP_SIZE};
+use mc_rs_proto::codec::ProtoDecode;
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::packets::clientbound_map_item_data::{
+    MAP_DECORATION_PLAYER, MAP_UPDATE_DECORATION, MAP_UPDATE_INITIALISATION, MAP_UPDATE_TEXTURE,
+};
+use mc_rs_proto::packets::item_stack_request::StackRequest;
+use mc_rs_proto::packets::item_stack_response::{
+    StackResponseContainer, StackResponseEntry, StackResponseSlot,
+};
+use mc_rs_proto::packets::{
+    self, ClientboundMapItemData, ContainerOpen, InventoryContent, MapDecoration, MapInfoRequest,
+};
+use mc_rs_proto::types::BlockPos;
+use mc_rs_world::chunk::OVERWORLD_MIN_Y;
+use mc_rs_world::storage::{
+    deserialize_actor_digest, map_key, serialize_actor_digest, MAP_INDEX_KEY,
+};
+use tracing::{debug, warn};
+
+use super::{ConnectionHandler, LoginState, OpenContainer};
+
+/// Container type of the cartography table UI.
+const CONTAINER_TYPE_CARTOGRAPHY: u8 = 30;
+/// Container ID of the cartography table map slot in stack responses.
+const CARTOGRAPHY_INPUT: u8 = 55;
+/// Container ID of the cartography table additional slot in stack responses.
+const CARTOGRAPHY_ADDITIONAL: u8 = 56;
+
+/// Height of a player's eyes above their feet.
+const EYE_HEIGHT: f32 = 1.62;
+
+/// Block colors already looked up during one map update, by runtime ID.
+type ColorCache = HashMap<u32, Option<(u8, u8, u8)>>;
+
+impl ConnectionHandler {
+    /// Allocate the ID of a new map. The world start count in the high half
+    /// keeps IDs from clashing with maps created by earlier sessions.
+    fn allocate_map_id(&mut self) -> i64 {
+        let id = (self.level_dat.world_start_count << 32) | (self.next_map_id & 0xFFFF_FFFF);
+        self.next_map_id += 1;
+        id
+    }
+
+    /// Make sure a map is in memory, loading it from storage if needed.
+    /// Returns false if no such map exists.
+    fn load_map(&mut self, id: i64) -> bool {
+        if self.maps.contains_key(&id) {
+            return true;
+        }
+        let Some(data) = self.chunk_storage.get_raw(&map_key(id)) else {
+            return false;
+        };
+        match MapData::from_le_nbt(&data) {
+            Some(map_data) => {
+                self.maps.insert(id, map_data);
+                true
+            }
+            None => {
+                warn!("Skipping unreadable map {id}");
+                false
+            }
+        }
+    }
+
+    /// Write every changed map and add it to the map index. Returns the
+    /// number saved.
+    pub(super) fn save_maps(&mut self) -> usize {
+        let mut index = self
+            .chunk_storage
+            .get_raw(MAP_INDEX_KEY)
+            .map(|data| deserialize_actor_digest(&data))
+            .unwrap_or_default();
+        let mut count = 0;
+        for map_data in self.maps.values_mut().filter(|m| m.dirty) {
+            if let Err(e) = self
+                .chunk_storage
+                .put_raw(&map_key(map_data.id), &map_data.to_le_nbt())
+            {
+                warn!("Failed to save map {}: {e}", map_data.id);
+                continue;
+            }
+            map_data.dirty = false;
+            if !index.contains(&map_data.id) {
+                index.push(map_data.id);
+            }
+            count += 1;
+        }
+        if count > 0 {
+            if let Err(e) = self
+                .chunk_storage
+                .put_raw(MAP_INDEX_KEY, &serialize_actor_digest(&index))
+            {
+                warn!("Failed to save map index: {e}");
+            }
+        }
+        count
+    }
+
+    /// ID of the filled map a player holds in their main hand.
+    fn held_map_id(&self, addr: SocketAddr) -> Option<i64> {
+        let item = self.connections.get(&addr)?.inventory.held_item();
+        let info = self.item_registry.get_by_id(item.runtime_id as i16)?;
+        if info.name != map::FILLED_MAP {
+            return None;
+        }
+        map::map_id_from_item_nbt(&item.nbt_data)
+    }
+
+    /// Top block of a world column that maps don't see through. `None` if
+    /// the chunk isn't loaded or the column is empty.
+    fn map_sample(&self, dim: i32, x: i32, z: i32, colors: &mut ColorCache) -> Option<MapSample> {
+        let column = self.dim_chunks(dim)?.get(&(x >> 4, z >> 4))?;
+        let (lx, lz) = ((x & 15) as usize, (z & 15) as usize);
+        let mut color_of = |rid: u32| {
+            *colors.entry(rid).or_insert_with(|| {
+                self.block_registry
+                    .get(rid)
+                    .and_then(|info| block_map_color(info.name))
+            })
+        };
+        for (sub_index, sub) in column.sub_chunks.iter().enumerate().rev() {
+            // Skip sub-chunks made of a single see-through block (air)
+            if sub.palette.len() == 1 && color_of(sub.palette[0]).is_none() {
+                continue;
+            }
+            for ly in (0..16).rev() {
+                if let Some(color) = color_of(sub.get_block(lx, ly, lz)) {
+                    return Some(MapSample {
+                        color,
+                        height: OVERWORLD_MIN_Y + (sub_index * 16 + ly) as i32,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Explore a map around a holder standing at `(x, z)`. Returns whether
+    /// any pixel changed.
+    fn explore_map(&self, map_data: &mut MapData, x: f32, z: f32) -> bool {
+        let dim = map_data.dimension;
+        let mut colors = ColorCache::new();
+        map_data.explore((x.floor() as i32, z.floor() as i32), |bx, bz| {
+            self.map_sample(dim, bx, bz, &mut colors)
+        })
+    }
+
+    /// The map's fixed markers, then the players in the map's dimension
+    /// holding it.
+    fn map_decorations(&self, map_data: &MapData) -> Vec<MapDecoration> {
+        let markers = map_data.markers.iter().filter_map(|m| {
+            let (x, y) = map_data.marker_position(m.x as f32 + 0.5, m.z as f32 + 0.5)?;
+            Some(MapDecoration {
+                decoration_type: m.decoration_type,
+                rotation: 8,
+                x,
+                y,
+                label: String::new(),
+                color: 0xFFFF_FFFF,
+            })
+        });
+        let players = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame && c.dimension == map_data.dimension)
+            .filter(|(&addr, _)| self.held_map_id(addr) == Some(map_data.id))
+            .filter_map(|(_, c)| {
+                let (x, y) = map_data.marker_position(c.position.x, c.position.z)?;
+                Some(MapDecoration {
+                    decoration_type: MAP_DECORATION_PLAYER,
+                    rotation: ((c.yaw.rem_euclid(360.0) / 22.5).round() as u8) & 15,
+                    x,
+                    y,
+                    label: String::new(),
+                    color: 0xFFFF_FFFF,
+                })
+            });
+        markers.chain(players).collect()
+    }
+
+    /// ClientboundMapItemData for a loaded map with the given update flags.
+    fn map_packet(&self, id: i64, update_flags: u32) -> Option<ClientboundMapItemData> {
+        let map_data = self.maps.get(&id)?;
+        let texture = update_flags & MAP_UPDATE_TEXTURE != 0;
+        let size = if texture { MAP_SIZE as i32 } else { 0 };
+        let (ox, oz) = map_data.origin();
+        Some(ClientboundMapItemData {
+            map_id: id,
+            update_flags,
+            dimension: self.client_dimension(map_data.dimension) as u8,
+            locked: map_data.locked,
+            origin: BlockPos::new(ox, 0, oz),
+            included_in: Vec::new(),
+            scale: map_data.scale,
+            decorations: if update_flags & MAP_UPDATE_DECORATION != 0 {
+                self.map_decorations(map_data)
+            } else {
+                Vec::new()
+            },
+            width: size,
+            height: size,
+            x_offset: 0,
+            y_offset: 0,
+            pixels: if texture {
+                map_data.pixels.clone()
+            } else {
+                Vec::new()
+            },
+        })
+    }
+
+    /// Send the whole map to one player.
+    async fn send_full_map(&mut self, addr: SocketAddr, id: i64) {
+        let flags = MAP_UPDATE_INITIALISATION | MAP_UPDATE_DECORATION | MAP_UPDATE_TEXTURE;
+        if let Some(pkt) = self.map_packet(id, flags) {
+            self.send_packet(addr, packets::id::CLIENTBOUND_MAP_ITEM_DATA, &pkt)
+                .await;
+        }
+    }
+
+    /// Turn one held empty map into a filled map centered on the player.
+    /// In creative the empty map is kept. Returns false if the player isn't
+    /// holding an empty map.
+    pub(super) async fn use_empty_map(&mut self, addr: SocketAddr) -> bool {
+        let Some(filled_rid) = self
+            .item_registry
+            .get_by_name(map::FILLED_MAP)
+            .map(|info| info.numeric_id as i32)
+        else {
+            return false;
+        };
+        let (position, dimension) = match self.connections.get(&addr) {
+            Some(c) => (c.position, c.dimension),
+            None => return false,
+        };
+
+        let id = self.allocate_map_id();
+        let block = (position.x.floor() as i32, position.z.floor() as i32);
+        let mut map_data = MapData::new(id, dimension, block, 0);
+        self.explore_map(&mut map_data, position.x, position.z);
+        self.maps.insert(id, map_data);
+
+        let mut filled = ItemStack::new(filled_rid, 1);
+        filled.nbt_data = map::filled_map_nbt(id);
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return true;
+        };
+        let creative = conn.gamemode == 1;
+        let held = conn.inventory.held_item_mut();
+        let leftover = if !creative && held.count <= 1 {
+            filled.stack_network_id = held.stack_network_id;
+            *held = filled.clone();
+            0
+        } else {
+            if !creative {
+                held.count -= 1;
+            }
+            let max_stack = self.item_registry.max_stack_size(filled_rid as i16) as u16;
+            conn.inventory.add_item(&filled, max_stack)
+        };
+        let unique_id = conn.entity_unique_id;
+        let held_rid = conn.inventory.held_item().runtime_id;
+
+        self.send_inventory(addr).await;
+        let held_name = self
+            .item_registry
+            .get_by_id(held_rid as i16)
+            .map(|i| i.name.clone())
+            .unwrap_or_default();
+        self.game_world
+            .update_player_held_item(unique_id, held_name);
+        if leftover > 0 {
+            let feet = (position.x, position.y - EYE_HEIGHT, position.z);
+            self.spawn_dropped_item(filled, feet, dimension).await;
+        }
+        self.send_full_map(addr, id).await;
+
+        debug!("Created map {id} for {addr}");
+        true
+    }
+
+    /// Give a player an explorer map of `scale` marking block column
+    /// `target` with `decoration_type`. Dropped at their feet if their
+    /// inventory is full. Returns false if they aren't online.
+    pub(super) async fn give_explorer_map(
+        &mut self,
+        addr: SocketAddr,
+        target: (i32, i32),
+        scale: u8,
+        decoration_type: u8,
+    ) -> bool {
+        let Some(filled_rid) = self
+            .item_registry
+            .get_by_name(map::FILLED_MAP)
+            .map(|info| info.numeric_id as i32)
+        else {
+            return false;
+        };
+        let (position, dimension) = match self.connections.get(&addr) {
+            Some(c) => (c.position, c.dimension),
+            None => return false,
+        };
+
+        let id = self.allocate_map_id();
+        self.maps.insert(
+            id,
+            MapData::explorer(id, dimension, target, scale, decoration_type),
+        );
+
+        let mut filled = ItemStack::new(filled_rid, 1);
+        filled.nbt_data = map::filled_map_nbt(id);
+        let max_stack = self.item_registry.max_stack_size(filled_rid as i16) as u16;
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return false;
+        };
+        let leftover = conn.inventory.add_item(&filled, max_stack);
+        self.send_inventory(addr).await;
+        if leftover > 0 {
+            let feet = (position.x, position.y - EYE_HEIGHT, position.z);
+            self.spawn_dropped_item(filled, feet, dimension).await;
+        }
+        self.send_full_map(addr, id).await;
+
+        debug!("Created explorer map {id} for {addr}");
+        true
+    }
+
+    /// Handle a client asking for the contents of a map it holds.
+    pub(super) async fn handle_map_info_request(
+        &mut self,
+        addr: SocketAddr,
+        buf: &mut Cursor<&[u8]>,
+    ) {
+        let pkt = match MapInfoRequest::proto_decode(buf) {
+            Ok(p) => p,
+            Err(e) => {
+                debug!("Bad MapInfoRequest from {addr}: {e}");
+                return;
+            }
+        };
+        if !self.load_map(pkt.map_id) {
+            debug!("Map {} requested by {addr} does not exist", pkt.map_id);
+            return;
+        }
+        self.send_full_map(addr, pkt.map_id).await;
+    }
+
+    /// Explore the maps players are holding, then send each holder the new
+    /// pixels and the positions of everyone holding the same map.
+    pub(super) async fn tick_maps(&mut self) {
+        if !self
+            .game_world
+            .current_tick()
+            .is_multiple_of(map::MAP_UPDATE_INTERVAL_TICKS)
+        {
+            return;
+        }
+
+        let holders: Vec<(SocketAddr, i64)> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame)
+            .filter_map(|(&addr, _)| Some((addr, self.held_map_id(addr)?)))
+            .collect();
+
+        let mut changed = HashSet::new();
+        for &(addr, id) in &holders {
+            if !self.load_map(id) {
+                continue;
+            }
+            let Some(conn) = self.connections.get(&addr) else {
+                continue;
+            };
+            let (position, dimension) = (conn.position, conn.dimension);
+            let Some(mut map_data) = self.maps.remove(&id) else {
+                continue;
+            };
+            if map_data.dimension == dimension
+                && self.explore_map(&mut map_data, position.x, position.z)
+            {
+                changed.insert(id);
+            }
+            self.maps.insert(id, map_data);
+        }
+
+        for (addr, id) in holders {
+            let flags = if changed.contains(&id) {
+                MAP_UPDATE_DECORATION | MAP_UPDATE_TEXTURE
+            } else {
+                MAP_UPDATE_DECORATION
+            };
+            if let Some(pkt) = self.map_packet(id, flags) {
+                self.send_packet(addr, packets::id::CLIENTBOUND_MAP_ITEM_DATA, &pkt)
+                    .await;
+            }
+        }
+    }
+
+    /// Open a cartography table UI for a player.
+    pub(super) async fn open_cartography_table(&mut self, addr: SocketAddr, pos: BlockPos) {
+        self.block_entities
+            .entry((pos.x, pos.y, pos.z, 0))
+            .or_insert_with(BlockEntityData::new_cartography_table);
+
+        let window_id = match self.connections.get_mut(&addr) {
+            Some(conn) => {
+                let wid = conn.next_window_id;
+                conn.next_window_id = conn.next_window_id.wrapping_add(1);
+                if conn.next_window_id == 0 {
+                    conn.next_window_id = 1;
+                }
+                conn.open_container = Some(OpenContainer {
+                    window_id: wid,
+                    container_type: CONTAINER_TYPE_CARTOGRAPHY,
+                    position: pos,
+                });
+                wid
+            }
+            None => return,
+        };
+
+        self.send_packet(
+            addr,
+            packets::id::CONTAINER_OPEN,
+            &ContainerOpen {
+                window_id,
+                container_type: CONTAINER_TYPE_CARTOGRAPHY,
+                position: pos,
+                entity_unique_id: -1,
+            },
+        )
+        .await;
+
+        let items = match self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::CartographyTable { input, additional }) => {
+                vec![input.clone(), additional.clone()]
+            }
+            _ => vec![ItemStack::empty(), ItemStack::empty()],
+        };
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_CONTENT,
+            &InventoryContent {
+                window_id: window_id as u32,
+                items,
+            },
+        )
+        .await;
+
+        debug!("Opened cartography table at {pos} for {addr} (window_id={window_id})");
+    }
+
+    /// Handle a cartography table craft: the result replaces the map in the
+    /// input slot and one additional item is used up.
+    pub(super) async fn handle_cartography_craft(
+        &mut self,
+        addr: SocketAddr,
+        req: &StackRequest,
+        recipe_uuid: &str,
+    ) -> StackResponseEntry {
+        let reject = StackResponseEntry {
+            request_id: req.request_id,
+            status: 1,
+            containers: Vec::new(),
+        };
+
+        let (window_id, pos) = match self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.open_container.as_ref())
+        {
+            Some(oc) if oc.container_type == CONTAINER_TYPE_CARTOGRAPHY => {
+                (oc.window_id, oc.position)
+            }
+            _ => return reject,
+        };
+        let (mut input, mut additional) = match self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::CartographyTable { input, additional }) => {
+                (input.clone(), additional.clone())
+            }
+            _ => return reject,
+        };
+
+        let name_of = |item: &ItemStack| {
+            self.item_registry
+                .get_by_id(item.runtime_id as i16)
+                .map(|i| i.name.clone())
+                .unwrap_or_default()
+        };
+        let craft = match map::cartography_craft(&name_of(&input), &name_of(&additional)) {
+            Some(c) => c,
+            None => return reject,
+        };
+        let expected_uuid = match craft {
+            CartographyCraft::Clone => map::MAP_CLONING_RECIPE_UUID,
+            CartographyCraft::Extend => map::MAP_EXTENDING_RECIPE_UUID,
+        };
+        if recipe_uuid != expected_uuid {
+            return reject;
+        }
+
+        match craft {
+            CartographyCraft::Clone => {
+                let max_stack = self.item_registry.max_stack_size(input.runtime_id as i16) as u16;
+                if input.count >= max_stack {
+                    return reject;
+                }
+                input.count += 1;
+            }
+            CartographyCraft::Extend => {
+                // Only a single map can be zoomed out at a time
+                let Some(id) = map::map_id_from_item_nbt(&input.nbt_data) else {
+                    return reject;
+                };
+                if input.count != 1 || !self.load_map(id) {
+                    return reject;
+                }
+                let new_id = self.allocate_map_id();
+                let Some(zoomed) = self.maps.get(&id).and_then(|m| m.zoomed_out(new_id)) else {
+                    return reject;
+                };
+                self.maps.insert(new_id, zoomed);
+                input.nbt_data = map::filled_map_nbt(new_id);
+            }
+        }
+        additional.count -= 1;
+        if additional.count == 0 {
+            additional = ItemStack::empty();
+        }
+
+        if let Some(BlockEntityData::CartographyTable {
+            input: be_input,
+            additional: be_additional,
+        }) = self.block_entities.get_mut(&(pos.x, pos.y, pos.z, 0))
+        {
+            *be_input = input.clone();
+            *be_additional = additional.clone();
+        }
+
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_CONTENT,
+            &InventoryContent {
+                window_id: window_id as u32,
+                items: vec![input.clone(), additional.clone()],
+            },
+        )
+        .await;
+
+        let slot = |container_id: u8, item: &ItemStack| StackResponseContainer {
+            container_id,
+            slots: vec![StackResponseSlot {
+                slot: 0,
+                hotbar_slot: 0,
+                count: item.count as u8,
+                stack_network_id: item.stack_network_id,
+                custom_name: String::new(),
+                durability_correction: 0,
+            }],
+        };
+        StackResponseEntry {
+            request_id: req.request_id,
+            status: 0,
+            containers: vec![
+                slot(CARTOGRAPHY_INPUT, &input),
+                slot(CARTOGRAPHY_ADDITIONAL, &additional),
+            ],
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Private messages: `/msg`, `/reply`, `/ignore`, and `/socialspy` for
+//! operators to read them.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_plugin_api::{EventResult, PluginEvent};
+use mc_rs_proto::packets::{self, Text};
+use tracing::info;
+
+use super::ConnectionHandler;
+
+impl ConnectionHandler {
+    /// /msg <player> <message>
+    pub(super) async fn cmd_msg(
+        &mut self,
+        sender_addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        if args.len() < 2 {
+            return CommandResult::err("Usage: /msg <player> <message>");
+        }
+        if !self.connections.contains_key(&sender_addr) {
+            return CommandResult::err("Only players can send private messages");
+        }
+        if let Some(reason) = self.mute_message(sender_name) {
+            return CommandResult::err(reason);
+        }
+        let target_name = &args[0];
+        let Some(target_addr) = self.find_player_addr(target_name) else {
+            return CommandResult::err(format!("Player not found: {target_name}"));
+        };
+        if target_addr == sender_addr {
+            return CommandResult::err("You can't message yourself");
+        }
+        let message = match self.filter_chat(&args[1..].join(" ")) {
+            Ok(message) => message,
+            Err(reason) => return CommandResult::err(reason),
+        };
+        let (Some(sender), Some(target)) = (
+            self.connections.get(&sender_addr),
+            self.connections.get(&target_addr),
+        ) else {
+            return CommandResult::err(format!("Player not found: {target_name}"));
+        };
+        if target.ignored.contains(sender_name) {
+            return CommandResult::err(format!("{target_name} is ignoring you"));
+        }
+
+        // Plugin event: PlayerPrivateMessage (cancellable)
+        let event = PluginEvent::PlayerPrivateMessage {
+            player: self.make_plugin_player(sender),
+            recipient: self.make_plugin_player(target),
+            message: message.clone(),
+        };
+        let snapshot = self.build_snapshot();
+        let (result, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+        self.apply_plugin_actions(actions).await;
+        if result == EventResult::Cancelled {
+            return CommandResult {
+                success: false,
+                messages: Vec::new(),
+                broadcast: None,
+                should_stop: false,
+            };
+        }
+
+        info!("[{sender_name} -> {target_name}] {message}");
+        self.send_packet(
+            target_addr,
+            packets::id::TEXT,
+            &Text::raw(format!("[{sender_name} -> you] {message}")),
+        )
+        .await;
+        if let Some(conn) = self.connections.get_mut(&sender_addr) {
+            conn.reply_to = Some(target_name.clone());
+        }
+        if let Some(conn) = self.connections.get_mut(&target_addr) {
+            conn.reply_to = Some(sender_name.to_string());
+        }
+
+        let spies: Vec<SocketAddr> = self
+            .connections
+            .iter()
+            .filter(|(&addr, c)| {
+                c.social_spy
+                    && addr != sender_addr
+                    && addr != target_addr
+                    && c.login_data
+                        .as_ref()
+                        .is_some_and(|d| self.permissions.is_op(&d.display_name))
+            })
+            .map(|(&addr, _)| addr)
+            .collect();
+        let spied = Text::raw(format!("[Spy] [{sender_name} -> {target_name}] {message}"));
+        for addr in spies {
+            self.send_packet(addr, packets::id::TEXT, &spied).await;
+        }
+
+        CommandResult::ok(format!("[you -> {target_name}] {message}"))
+    }
+
+    /// /reply <message>
+    pub(super) async fn cmd_reply(
+        &mut self,
+        sender_addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        if args.is_empty() {
+            return CommandResult::err("Usage: /reply <message>");
+        }
+        let Some(target_name) = self
+            .connections
+            .get(&sender_addr)
+            .and_then(|c| c.reply_to.clone())
+        else {
+            return CommandResult::err("You have nobody to reply to");
+        };
+        let mut msg_args = vec![target_name];
+        msg_args.extend_from_slice(args);
+        self.cmd_msg(sender_addr, sender_name, &msg_args).await
+    }
+
+    /// /ignore [player]: toggle ignoring a player, or list the ignored ones.
+    pub(super) fn cmd_ignore(&mut self, sender_addr: SocketAddr, args: &[String]) -> CommandResult {
+        let Some(conn) = self.connections.get_mut(&sender_addr) else {
+            return CommandResult::err("Only players can ignore others");
+        };
+        let Some(name) = args.first() else {
+            if conn.ignored.is_empty() {
+                return CommandResult::ok("You aren't ignoring anyone");
+            }
+            let mut names: Vec<&str> = conn.ignored.iter().map(String::as_str).collect();
+            names.sort_unstable();
+            return CommandResult::ok(format!("Ignoring: {}", names.join(", ")));
+        };
+        if conn.ignored.remove(name) {
+            CommandResult::ok(format!("No longer ignoring {name}"))
+        } else {
+            conn.ignored.insert(name.clone());
+            CommandResult::ok(format!("Ignoring private messages from {name}"))
+        }
+    }
+
+    /// /socialspy: toggle seeing other players' private messages.
+    pub(super) fn cmd_socialspy(&mut self, sender_addr: SocketAddr) -> CommandResult {
+        let Some(conn) = self.connections.get_mut(&sender_addr) else {
+            return CommandResult::err("Only players can spy on private messages");
+        };
+        conn.social_spy = !conn.social_spy;
+        CommandResult::ok(if conn.social_spy {
+            "Social spy on: you will see private messages"
+        } else {
+            "Social spy off"
+        })
+    }
+}
@@ -0,0 +1,154 @@
+//! Block breaking: server-side break times and the crack animation other
+//! players see while a block is being mined.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::combat as game_combat;
+use mc_rs_game::mining::{tool_from_item, MiningContext};
+use mc_rs_proto::packets::mob_effect::effect_id;
+use mc_rs_proto::packets::{self, LevelEvent};
+use mc_rs_proto::types::BlockPos;
+use tracing::debug;
+
+use super::{BreakingBlock, ConnectionHandler};
+use crate::anticheat::Check;
+
+impl ConnectionHandler {
+    /// Mining modifiers for a player: held tool, Efficiency, Haste/Mining
+    /// Fatigue, and whether they are under water or off the ground.
+    fn mining_context(&self, addr: SocketAddr) -> Option<MiningContext> {
+        let conn = self.connections.get(&addr)?;
+        let held = conn.inventory.held_item();
+        let tool = self
+            .item_registry
+            .get_by_id(held.runtime_id as i16)
+            .and_then(|info| tool_from_item(&info.name));
+        let effects = self.effects(addr);
+        let amplifier = |id: i32| {
+            effects
+                .iter()
+                .find(|e| e.effect_id == id)
+                .map(|e| e.amplifier)
+        };
+
+        // Position is at eye level
+        let head = (
+            conn.position.x.floor() as i32,
+            conn.position.y.floor() as i32,
+            conn.position.z.floor() as i32,
+        );
+        let head_in_water = matches!(
+            self.get_block(head.0, head.1, head.2)
+                .and_then(|hash| self.block_registry.get(hash))
+                .map(|info| info.name),
+            Some("minecraft:water") | Some("minecraft:flowing_water")
+        );
+        let aqua_affinity = game_combat::has_aqua_affinity(&conn.inventory.armor[0].nbt_data);
+
+        Some(MiningContext {
+            tool,
+            efficiency: game_combat::efficiency_level(&held.nbt_data),
+            haste: amplifier(effect_id::HASTE),
+            mining_fatigue: amplifier(effect_id::MINING_FATIGUE),
+            underwater: head_in_water && !aqua_affinity,
+            on_ground: conn.on_ground,
+        })
+    }
+
+    /// Ticks this player needs to break a block right now. `None` for
+    /// unbreakable or unknown blocks.
+    pub(super) fn block_break_ticks(&self, addr: SocketAddr, runtime_id: u32) -> Option<u32> {
+        let (name, hardness, tool_type) = self.block_registry.mining_info(runtime_id)?;
+        self.mining_context(addr)?
+            .break_ticks(name, hardness, tool_type)
+    }
+
+    /// Start tracking a block the player began breaking, and show the crack
+    /// animation to the other players in the dimension. Blocks an adventure
+    /// mode player may not break are ignored.
+    pub(super) async fn start_breaking(&mut self, addr: SocketAddr, pos: BlockPos) {
+        self.stop_breaking(addr).await;
+        let Some(break_ticks) = self
+            .get_block(pos.x, pos.y, pos.z)
+            .filter(|&rid| self.may_break_block(addr, rid))
+            .and_then(|rid| self.block_break_ticks(addr, rid))
+        else {
+            return;
+        };
+        let start_tick = self.game_world.current_tick();
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        conn.breaking_block = Some(BreakingBlock {
+            pos,
+            start_tick,
+            break_ticks,
+        });
+        let dim = conn.dimension;
+        if break_ticks > 0 {
+            self.broadcast_packet_in_dimension_except(
+                dim,
+                addr,
+                packets::id::LEVEL_EVENT,
+                &LevelEvent::start_block_break(pos, break_ticks),
+            )
+            .await;
+        }
+    }
+
+    /// Forget the block being broken and clear its crack animation.
+    pub(super) async fn stop_breaking(&mut self, addr: SocketAddr) {
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        let Some(breaking) = conn.breaking_block.take() else {
+            return;
+        };
+        let dim = conn.dimension;
+        if breaking.break_ticks > 0 {
+            self.broadcast_packet_in_dimension_except(
+                dim,
+                addr,
+                packets::id::LEVEL_EVENT,
+                &LevelEvent::stop_block_break(breaking.pos),
+            )
+            .await;
+        }
+    }
+
+    /// Whether a survival player has mined a block long enough to break it.
+    /// Conditions can change while mining (landing, drinking a potion), so
+    /// the faster of the start and current break times is used, with some
+    /// slack for latency. Breaking too fast is a fast break violation.
+    pub(super) async fn break_time_elapsed(
+        &mut self,
+        addr: SocketAddr,
+        pos: BlockPos,
+        runtime_id: u32,
+    ) -> bool {
+        // Blocks without known properties are not timed
+        let Some(now_ticks) = self.block_break_ticks(addr, runtime_id) else {
+            return true;
+        };
+        if now_ticks == 0 {
+            return true;
+        }
+        let breaking = self.connections.get(&addr).and_then(|c| c.breaking_block);
+        match breaking {
+            Some(b) if b.pos == pos => {
+                let expected = b.break_ticks.min(now_ticks) as u64;
+                let elapsed = self.game_world.current_tick().saturating_sub(b.start_tick);
+                if elapsed * 5 < expected * 4 {
+                    let detail =
+                        format!("broke {pos} in {elapsed} ticks, less than 80% of {expected}");
+                    return !self.flag_violation(addr, Check::FastBreak, &detail).await;
+                }
+                true
+            }
+            _ => {
+                debug!("No StartBreak for {pos} from {addr}, rejecting break");
+                false
+            }
+        }
+    }
+}
@@ -1,22 +1,75 @@
 //! Per-player connection state management and login flow.
 
+mod actor_data;
+mod actors;
+mod adventure;
+mod afk;
+mod anticheat;
+mod appearance;
+mod arena;
+mod banner;
+mod biomes;
+mod bridge;
+mod chat;
+mod chunk_events;
+mod client_settings;
 mod combat;
+mod command_blocks;
 mod commands;
+mod cutscene;
+mod daylight;
+mod drops;
+mod economy;
+mod elytra;
+mod emote;
+mod farmland;
+mod functions;
+mod geoip;
+mod grindstone;
+mod hologram;
 mod inventory;
 mod login;
+mod maps;
+mod messaging;
+mod mining;
 mod movement;
+mod music;
+mod npc;
+mod packs;
 mod plugins;
 mod portal;
+mod prelogin;
 mod projectile;
+mod raids;
+mod reload;
+mod shop;
+mod sign;
+mod skull;
 mod spawn;
+mod spectator;
+mod structures;
+mod surface;
 mod survival;
+mod teleport;
+mod time;
+mod timings;
+mod transfer;
+mod trees;
+mod view_distance;
+mod warps;
+mod water;
+mod weather;
+mod world_flags;
 mod world_tick;
+mod worlds;
 
-use std::collections::{HashMap, HashSet};
+pub use reload::RESTART_EXIT_CODE;
+
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::Cursor;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use bytes::{BufMut, Bytes, BytesMut};
 use tracing::{debug, info, warn};
@@ -24,70 +77,120 @@ use tracing::{debug, info, warn};
 use mc_rs_command::selector::PlayerInfo;
 use mc_rs_command::{CommandRegistry, CommandResult};
 use mc_rs_crypto::{
-    create_handshake_jwt, derive_key, parse_client_public_key, PacketEncryption, ServerKeyPair,
+    create_handshake_jwt, derive_key, parse_client_public_key, verify_jwt, verify_login_chain,
+    PacketEncryption, ServerKeyPair,
 };
-use mc_rs_game::combat as game_combat;
+use mc_rs_game::ai::spawning::HostileSpawnRule;
+use mc_rs_game::combat::{self as game_combat, KnockbackProfile};
+use mc_rs_game::components::{Experience, Hunger};
+use mc_rs_game::difficulty::Difficulty;
+pub use mc_rs_game::effects::ActiveEffect;
 use mc_rs_game::game_world::{GameEvent, GameWorld};
 use mc_rs_game::inventory::PlayerInventory;
+use mc_rs_game::move_sync::{self, MoveUpdate, SentMove};
 use mc_rs_game::recipe::RecipeRegistry;
 use mc_rs_game::xp;
-use mc_rs_proto::batch::{decode_batch, encode_single, BatchConfig};
+use mc_rs_proto::batch::{decode_batch, encode_batch, BatchConfig};
 use mc_rs_proto::codec::{ProtoDecode, ProtoEncode};
 use mc_rs_proto::compression::CompressionAlgorithm;
+use mc_rs_proto::entity_metadata::{EntityFlag, EntityMetadataEntry, MetadataBuilder};
 use mc_rs_proto::jwt;
-use mc_rs_proto::packets::add_player::default_player_metadata;
 use mc_rs_proto::packets::{
     self, ActorAttribute, AddActor, AddPlayer, Animate, AvailableCommands,
     AvailableEntityIdentifiers, BiomeDefinitionList, BlockActorData, BossEvent, ChunkRadiusUpdated,
     ClientToServerHandshake, CommandOutput, CommandRequest, ContainerClose, ContainerOpen,
-    ContainerSetData, Disconnect, EntityEvent, EntityMetadataEntry, GameRule, GameRuleValue,
-    GameRulesChanged, InventoryContent, InventorySlot, InventoryTransaction, ItemStackRequest,
-    ItemStackResponse, LevelChunk, LevelEvent, MetadataValue, MobEffect, MobEquipment,
-    MoveActorAbsolute, MoveMode, MovePlayer, NetworkChunkPublisherUpdate, NetworkSettings,
-    PlaySound, PlayStatus, PlayStatusType, PlayerAction, PlayerActionType, PlayerAuthInput,
-    PlayerListAdd, PlayerListAddPacket, PlayerListRemove, RemoveEntity, RequestChunkRadius,
-    ResourcePackClientResponse, ResourcePackResponseStatus, ResourcePackStack, ResourcePacksInfo,
-    Respawn, ScoreEntry, ServerToClientHandshake, SetDisplayObjective, SetEntityMotion,
-    SetLocalPlayerAsInitialized, SetPlayerGameType, SetScore, SetTime, SetTitle,
-    SpawnParticleEffect, StartGame, Text, Transfer, UpdateAbilities, UpdateAttributes, UpdateBlock,
-    UseItemAction, UseItemOnEntityAction,
+    ContainerSetData, Disconnect, EntityEvent, GameRule, GameRuleValue, GameRulesChanged,
+    InventoryContent, InventorySlot, InventoryTransaction, ItemStackRequest, ItemStackResponse,
+    LevelChunk, LevelEvent, MobEffect, MobEquipment, MoveActorAbsolute, MoveMode, MovePlayer,
+    NetworkChunkPublisherUpdate, NetworkSettings, PlaySound, PlayStatus, PlayStatusType,
+    PlayerAction, PlayerActionType, PlayerAuthInput, PlayerListAdd, PlayerListAddPacket,
+    PlayerListRemove, RemoveEntity, RequestChunkRadius, ResourcePackClientResponse,
+    ResourcePackResponseStatus, ResourcePackStack, ResourcePacksInfo, Respawn, ScoreEntry,
+    ServerToClientHandshake, SetDifficulty, SetDisplayObjective, SetEntityMotion,
+    SetLocalPlayerAsInitialized, SetPlayerGameType, SetScore, SetTitle, SpawnParticleEffect,
+    StartGame, Text, UpdateAttributes, UpdateBlock, UseItemAction, UseItemOnEntityAction,
 };
+use mc_rs_proto::protocol::ProtocolAdapter;
 use mc_rs_proto::types::{BlockPos, Uuid, VarUInt32, Vec2, Vec3};
-use mc_rs_raknet::{RakNetEvent, Reliability, ServerHandle};
+use mc_rs_raknet::{RakNetEvent, Reliability, ServerHandle, ServerMotd};
 use rand::prelude::*;
 
 use mc_rs_game::block_entity::{self, BlockEntityData};
 use mc_rs_game::smelting::SmeltingRegistry;
 use mc_rs_world::block_hash::{hash_block_state, BlockEntityHashes, FlatWorldBlocks, TickBlocks};
 use mc_rs_world::block_registry::BlockRegistry;
+use mc_rs_world::block_state_registry::BlockStateRegistry;
 use mc_rs_world::block_tick::{process_random_tick, process_scheduled_tick, TickScheduler};
 use mc_rs_world::chunk::{ChunkColumn, OVERWORLD_MIN_Y, OVERWORLD_SUB_CHUNK_COUNT};
 use mc_rs_world::end_generator::EndGenerator;
-use mc_rs_world::flat_generator::generate_flat_chunk;
 use mc_rs_world::fluid;
 use mc_rs_world::gravity;
 use mc_rs_world::item_registry::ItemRegistry;
+use mc_rs_world::memory::MemoryProvider;
 use mc_rs_world::nether_generator::NetherGenerator;
 use mc_rs_world::overworld_generator::OverworldGenerator;
 use mc_rs_world::physics::{
-    PlayerAabb, ViolationTracker, BLOCK_REACH, MAX_ACTIONS_PER_SECOND, MAX_AIRBORNE_KICK,
-    MAX_AIRBORNE_TICKS, MAX_FALL_PER_TICK, MIN_ATTACK_INTERVAL, MIN_BREAK_INTERVAL,
-    MIN_COMMAND_INTERVAL, MIN_PLACE_INTERVAL, VIOLATION_DECAY_INTERVAL,
+    is_climbable, max_swim_vertical, PlayerAabb, Pose, Surface, BLOCK_REACH,
+    MAX_ACTIONS_PER_SECOND, MAX_AIRBORNE_KICK, MAX_AIRBORNE_TICKS, MAX_FALL_PER_TICK,
+    MIN_ATTACK_INTERVAL, MIN_BREAK_INTERVAL, MIN_COMMAND_INTERVAL, MIN_PLACE_INTERVAL,
+    PLAYER_EYE_HEIGHT,
 };
 use mc_rs_world::piston;
+use mc_rs_world::placement::{self, PlacementContext};
 use mc_rs_world::redstone;
+use mc_rs_world::region::RegionFileProvider;
 use mc_rs_world::serializer::serialize_chunk_column_cached;
-use mc_rs_world::storage::{block_entity_key, LevelDbProvider};
+use mc_rs_world::storage::{
+    block_entity_key_dim, convert_storage, ChunkStorageProvider, LevelDbProvider, StorageOptions,
+    STRUCTURES_KEY,
+};
+use mc_rs_world::structure::StructureRegistry;
 use tokio::sync::watch;
 
-use mc_rs_behavior_pack::loader::LoadedBehaviorPack;
-use mc_rs_behavior_pack::loot_table::LootTableFile;
-use mc_rs_plugin_api::{DamageCause, EventResult, PluginBlockPos, PluginEvent, PluginPlayer};
+use mc_rs_behavior_pack::loot_table::{vanilla_loot_tables, LootTableFile};
+use mc_rs_plugin_api::{
+    is_valid_script_event_id, DamageCause, EventResult, PluginBlockPos, PluginEvent, PluginPlayer,
+    PluginPlayerStats, ScriptEventSource,
+};
 
-use crate::config::ServerConfig;
-use crate::permissions::{BanEntry, PermissionManager};
-use crate::persistence::{LevelDat, PlayerData};
+use crate::actor_data::ActorDataTracker;
+use crate::anticheat::{Check, PositionHistory, ViolationScores};
+use crate::arena::ArenaManager;
+use crate::bridge::BridgeEvent;
+use crate::client_settings::FogStack;
+use crate::config::{ServerConfig, StorageSection};
+use crate::custom_blocks::CustomBlocks;
+use crate::custom_items::CustomItems;
+use crate::cutscenes::CutsceneManager;
+use crate::economy::{Economy, SharedEconomy};
+use crate::entity_appearance::EntityAppearance;
+use crate::forms::{FormStack, PendingForm};
+use crate::functions::Functions;
+use crate::holograms::HologramManager;
+use crate::kits::{KitDefinition, KitManager};
+use crate::npcs::NpcManager;
+use crate::packs::preferences::PackPreferences;
+use crate::packs::PackStore;
+use crate::permissions::{BanEntry, PermissionManager, MAX_OP_LEVEL};
+use crate::persistence::{LevelDat, PlayerData, SavedLocation};
+use crate::player_store::{PlayerStore, StoreError};
 use crate::plugin_manager::{PendingAction, PluginManager, ServerSnapshot};
+use crate::shops::ShopManager;
+use crate::stats::PlayerStats;
+use crate::warps::WarpManager;
+use crate::world_flags::WorldFlags;
+use worlds::ExtraWorld;
+
+/// Queued bytes for one player past which their batch is sent right away,
+/// rather than at the end of the tick.
+const MAX_BATCH_BYTES: usize = 512 * 1024;
+
+/// Queued packets for one player past which their batch is sent right away.
+const MAX_BATCH_PACKETS: usize = 512;
+
+/// Longest [`ConnectionHandler::save_all`] waits for player saves; slower
+/// ones are applied on a later tick.
+const PLAYER_SAVE_DEADLINE: Duration = Duration::from_secs(5);
 
 /// Login state machine states.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -96,6 +199,8 @@ pub enum LoginState {
     AwaitingNetworkSettings,
     /// NetworkSettings sent, waiting for LoginPacket (0x01).
     AwaitingLogin,
+    /// Login received, waiting for the pre-login webhook.
+    AwaitingPreLoginCheck,
     /// ServerToClientHandshake sent, waiting for ClientToServerHandshake (0x04).
     /// Only used when online_mode = true.
     AwaitingHandshake,
@@ -117,7 +222,16 @@ struct PendingEncryption {
     iv: [u8; 16],
 }
 
-/// Per-player connection state.
+/// Outcome of a player save run on the blocking pool.
+struct PlayerSave {
+    addr: SocketAddr,
+    name: String,
+    result: Result<u64, StoreError>,
+}
+
+/// Per-player connection state. Gameplay state shared with mobs (health,
+/// hunger, status effects, experience) lives on the player's entity in the
+/// [`GameWorld`] instead.
 pub struct PlayerConnection {
     pub state: LoginState,
     pub batch_config: BatchConfig,
@@ -146,52 +260,79 @@ pub struct PlayerConnection {
     pub sent_chunks: HashSet<(i32, i32)>,
     /// Accepted chunk radius for this player.
     pub chunk_radius: i32,
+    /// Chunk radius the client asked for.
+    pub requested_chunk_radius: i32,
+    /// View distance set with `/viewdistance`, instead of the server's.
+    pub view_distance: Option<i32>,
     /// Current dimension: 0=overworld, 1=nether, 2=end.
     pub dimension: i32,
     /// Tick after which portal can be used again (cooldown).
     pub portal_cooldown_until: u64,
     /// Player gamemode: 0=survival, 1=creative, 2=adventure, 3=spectator.
     pub gamemode: i32,
-    /// Active block-breaking state: (position, start_time).
-    pub breaking_block: Option<(BlockPos, Instant)>,
+    /// Block the player is breaking (survival break time validation).
+    pub breaking_block: Option<BreakingBlock>,
     /// Consecutive ticks spent airborne (for anti-fly detection).
     pub airborne_ticks: u32,
+    /// Surface of the block the player last stood on, whose speed they
+    /// keep through jumps (for speed checks).
+    pub last_surface: Surface,
     /// Player inventory.
     pub inventory: PlayerInventory,
-    /// Player health (0.0 - 20.0).
-    pub health: f32,
-    /// Tick when player last took damage (invulnerability frames).
-    pub last_damage_tick: Option<u64>,
     /// Whether the player is dead (waiting for respawn).
     pub is_dead: bool,
     /// Whether the player is sprinting (from PlayerAuthInput flags).
     pub is_sprinting: bool,
-    /// Active status effects on this player.
-    pub effects: Vec<ActiveEffect>,
-    /// Last vertical velocity (position_delta.y) for critical hit detection.
-    pub last_position_delta_y: f32,
+    /// Whether the player is sneaking (from PlayerAuthInput flags).
+    pub is_sneaking: bool,
+    /// Whether the player is crawling (from PlayerAuthInput flags).
+    pub is_crawling: bool,
+    /// Last velocity (position_delta), for critical hit detection and
+    /// knockback friction.
+    pub last_position_delta: Vec3,
+    /// Movement last broadcast to other players, `None` until the first.
+    pub last_sent_move: Option<SentMove>,
+    /// ISO code of the country the player connects from, if known.
+    pub country: Option<String>,
     /// Remaining fire ticks (1 damage per 20 ticks). 0 = not on fire.
     pub fire_ticks: i32,
-    /// Food level (0-20). 20 = full.
-    pub food: i32,
-    /// Saturation level (0.0-20.0). Consumed before food.
-    pub saturation: f32,
-    /// Exhaustion accumulator (0.0-4.0+). Drains saturation/food at 4.0.
-    pub exhaustion: f32,
     /// Accumulated fall distance in blocks.
     pub fall_distance: f32,
     /// Air supply ticks (300 = full). Decrements when head is in water.
     pub air_ticks: i32,
     /// Whether the player is swimming (from PlayerAuthInput flags).
     pub is_swimming: bool,
-    /// XP level (0+).
-    pub xp_level: i32,
-    /// Total accumulated XP.
-    pub xp_total: i32,
-    /// Pending forms awaiting response: form_id -> form_type ("simple"/"modal"/"custom").
-    pub pending_forms: HashMap<u32, String>,
+    /// Whether the player is gliding with an elytra.
+    pub is_gliding: bool,
+    /// Ticks spent gliding since the last elytra durability loss.
+    pub glide_ticks: u32,
+    /// Game tick at which the player started eating the held item (None = not eating).
+    pub eating_since: Option<u64>,
+    /// Forms shown to the player, awaiting their answer or leading back
+    /// from a chained form.
+    pub pending_forms: FormStack,
+    /// Last claim time (unix seconds) per kit name, for `/kit` cooldowns.
+    pub kit_cooldowns: HashMap<String, i64>,
+    /// Tick at which each custom item cooldown category is usable again.
+    pub item_cooldowns: HashMap<String, u64>,
+    /// Version of the player data loaded from the store (for optimistic locking).
+    pub data_version: u64,
+    /// Another server saved newer player data: this copy is never saved.
+    pub data_stale: bool,
+    /// A save of this player's data is still running.
+    pub save_pending: bool,
+    /// Cumulative statistics (blocks mined, kills, distance, deaths, playtime).
+    pub stats: PlayerStats,
     /// Currently open container (chest, etc.).
     pub open_container: Option<OpenContainer>,
+    /// Sign whose editor is open; the only sign this player may write to.
+    pub editing_sign: Option<BlockPos>,
+    /// Skin changed in-game (or restored from player data): saved with the
+    /// player so it survives a rejoin.
+    pub custom_skin: bool,
+    /// Emote pieces the player has equipped (from EmoteList), forwarded to
+    /// players who join later.
+    pub emote_pieces: Vec<Uuid>,
     /// Next window ID to assign when opening a container.
     pub next_window_id: u8,
     /// Enchantment seed (used for deterministic option generation).
@@ -200,12 +341,26 @@ pub struct PlayerConnection {
     pub pending_enchant_options: Vec<mc_rs_game::enchanting::EnchantOption>,
     /// Entity tags assigned via /tag command.
     pub tags: HashSet<String>,
+    /// Whether the camera presets were sent, before the first camera
+    /// instruction.
+    pub camera_presets_sent: bool,
+    /// Fog pushed with `/fog` or by plugins.
+    pub fog: FogStack,
+    /// Inputs locked with `/inputpermission` or by plugins, flags of
+    /// UpdateClientInputLocks.
+    pub input_locks: u32,
     // ── Protocol ───────────────────────────────────────────────────────
-    /// Client's negotiated protocol version.
-    pub protocol_version: i32,
+    /// Encoder/decoder for the client's negotiated protocol version.
+    pub protocol: ProtocolAdapter,
+    /// Pack chunks requested by the client, sent a few per tick: (pack ID, chunk index).
+    pub pack_chunk_queue: VecDeque<(String, u32)>,
+    /// UUIDs of the packs offered in ResourcePacksInfo.
+    pub offered_packs: Vec<String>,
+    /// UUIDs of the packs the client accepted, set with the pack stack.
+    pub accepted_packs: Vec<String>,
     // ── Anti-cheat ──────────────────────────────────────────────────────
-    /// Violation tracker for anti-cheat kick thresholds.
-    pub violations: ViolationTracker,
+    /// Anti-cheat violation scores, one per check.
+    pub violations: ViolationScores,
     /// Last tick a block was broken (rate limiting).
     pub last_break_tick: u64,
     /// Last tick a block was placed (rate limiting).
@@ -214,10 +369,63 @@ pub struct PlayerConnection {
     pub last_attack_tick: u64,
     /// Last tick a command was sent (rate limiting).
     pub last_command_tick: u64,
+    /// Last tick an emote was played (rate limiting).
+    pub last_emote_tick: u64,
+    /// Last tick a chat message was sent, for slow mode.
+    pub last_chat_tick: Option<u64>,
     /// Number of actions in the current 1-second window.
     pub actions_this_second: u16,
     /// Tick at which the current 1-second action window started.
     pub action_second_start: u64,
+
+    // ── AFK ─────────────────────────────────────────────────────────────
+    /// Server tick of the player's last meaningful input.
+    pub last_active_tick: u64,
+    /// Whether the player is marked AFK.
+    pub afk: bool,
+
+    // ── Teleports ───────────────────────────────────────────────────────
+    /// Homes set with `/sethome`, by name.
+    pub homes: HashMap<String, SavedLocation>,
+    /// Where `/back` returns to: the last teleport origin or death point.
+    pub back_location: Option<SavedLocation>,
+    /// Teleport waiting for its warm-up to finish.
+    pub pending_teleport: Option<PendingTeleport>,
+    /// Server tick of the player's last teleport, for the cooldown.
+    pub last_teleport_tick: Option<u64>,
+    /// `/tpa` requests other players sent to this one, oldest first.
+    pub tpa_requests: Vec<TpaRequest>,
+    /// Where the player left each world, by world name, for `/world tp`.
+    pub world_positions: HashMap<String, SavedLocation>,
+    /// Set while the player lies in a bed.
+    pub sleeping: Option<SleepState>,
+
+    // ── Private messages ────────────────────────────────────────────────
+    /// Players whose private messages this one ignores, set with `/ignore`.
+    pub ignored: HashSet<String>,
+    /// Who `/reply` answers: the last player this one messaged or heard
+    /// from.
+    pub reply_to: Option<String>,
+    /// Whether this operator sees the private messages of others.
+    pub social_spy: bool,
+
+    // ── Outgoing packets ────────────────────────────────────────────────
+    /// Encoded sub-packets waiting for [`ConnectionHandler::flush_packets`].
+    pub outbox: Vec<Bytes>,
+    /// Total size of `outbox`, in bytes.
+    pub outbox_bytes: usize,
+}
+
+impl PlayerConnection {
+    /// The player's pose, for their hitbox and what others see.
+    pub fn pose(&self) -> Pose {
+        Pose::from_state(
+            self.is_sneaking,
+            self.is_swimming,
+            self.is_crawling,
+            self.is_gliding,
+        )
+    }
 }
 
 /// State for a currently open container window.
@@ -232,15 +440,52 @@ pub struct OpenContainer {
     pub position: BlockPos,
 }
 
-/// An active status effect on a player.
+/// Where a pending teleport takes the player.
+#[derive(Debug, Clone)]
+pub enum TeleportDestination {
+    Location(SavedLocation),
+    /// Another player, wherever they are when the warm-up ends.
+    Player(String),
+}
+
+/// A teleport waiting for its warm-up, cancelled if the player moves or
+/// takes damage.
+#[derive(Debug, Clone)]
+pub struct PendingTeleport {
+    pub destination: TeleportDestination,
+    /// Shown to the player, e.g. "home" or a player name.
+    pub label: String,
+    /// Tick at which the teleport happens.
+    pub execute_tick: u64,
+    pub start_position: Vec3,
+    pub start_health: f32,
+}
+
+/// A `/tpa` request waiting for the target to accept or deny it.
 #[derive(Debug, Clone)]
-pub struct ActiveEffect {
-    /// Effect ID (see `mc_rs_proto::packets::mob_effect::effect_id`).
-    pub effect_id: i32,
-    /// Amplifier (0 = level I, 1 = level II, etc.).
-    pub amplifier: i32,
-    /// Remaining duration in ticks.
-    pub remaining_ticks: i32,
+pub struct TpaRequest {
+    /// Display name of the player asking to teleport.
+    pub from: String,
+    pub expires_tick: u64,
+}
+
+/// A player lying in a bed.
+#[derive(Debug, Clone, Copy)]
+pub struct SleepState {
+    /// Tick the player lay down.
+    pub since_tick: u64,
+    /// Where they were, to get them up if they move away.
+    pub position: Vec3,
+}
+
+/// A block being broken, from StartBreak until it breaks or is aborted.
+#[derive(Debug, Clone, Copy)]
+pub struct BreakingBlock {
+    pub pos: BlockPos,
+    /// Tick the player started breaking it.
+    pub start_tick: u64,
+    /// Break time computed when mining started, in ticks.
+    pub break_ticks: u32,
 }
 
 /// (dimension, chunk_x, chunk_z) → list of block-entity positions in that chunk.
@@ -262,28 +507,97 @@ pub struct ConnectionHandler {
     end_generator: Option<Arc<EndGenerator>>,
     /// Dimension ID for the current world (0=overworld, 1=nether, 2=end).
     dimension_id: i32,
+    /// Worlds from `[worlds.<name>]`, hosted under dimension IDs from 3 up.
+    extra_worlds: Vec<ExtraWorld>,
     /// Pre-computed spawn position (eye position).
     spawn_position: Vec3,
     /// Pre-computed spawn block position (feet).
     spawn_block: BlockPos,
     command_registry: CommandRegistry,
     shutdown_tx: Arc<watch::Sender<bool>>,
+    /// Set by `/restart`: the process exits with [`RESTART_EXIT_CODE`].
+    restart_requested: bool,
     /// Cached world chunks — keyed by dimension → (chunk_x, chunk_z) → ChunkColumn.
     world_chunks: HashMap<i32, HashMap<(i32, i32), ChunkColumn>>,
     /// Block property registry for all vanilla blocks.
     block_registry: BlockRegistry,
+    /// Block state registry (hash ↔ name + states), including custom blocks.
+    block_states: BlockStateRegistry,
+    /// Custom blocks from behavior packs.
+    custom_blocks: CustomBlocks,
+    /// Custom items from behavior packs.
+    custom_items: CustomItems,
+    /// Functions from behavior packs.
+    functions: Functions,
+    /// How deep the function being run is nested (0 outside functions).
+    function_depth: usize,
+    /// Commands run so far by the current top-level function call.
+    function_commands: usize,
     /// Item registry for all vanilla items.
     item_registry: ItemRegistry,
     /// Recipe registry for crafting.
     recipe_registry: RecipeRegistry,
     /// Permission manager: ops, whitelist, bans.
     permissions: PermissionManager,
+    /// Country database for joining players.
+    geoip: crate::geoip::GeoIp,
+    /// Kits defined in server.toml and the world's kits.json.
+    kits: KitManager,
+    /// Warps set with `/setwarp`.
+    warps: WarpManager,
+    /// Player balances, shared with the plugin API.
+    economy: SharedEconomy,
+    /// Chest shops made with `[Shop]` signs.
+    shops: ShopManager,
+    /// Floating text made with `/hologram` or by plugins.
+    holograms: HologramManager,
+    /// NPCs made with `/npc`.
+    npcs: NpcManager,
+    /// Minigame arenas defined by plugins.
+    arenas: ArenaManager,
+    /// Cutscenes played by plugins, by player name.
+    cutscenes: CutsceneManager,
+    /// Appearance set by plugins, by entity runtime ID.
+    entity_appearances: HashMap<u64, EntityAppearance>,
+    /// Entity metadata last sent to clients, so only changes go out.
+    actor_data: ActorDataTracker,
+    /// Identity tickets forwarded by other servers, keyed by player name.
+    pending_handoffs: HashMap<String, crate::handoff::HandoffTicket>,
+    /// Where server events are posted for the chat bridge.
+    bridge: crate::bridge::BridgeWebhook,
+    /// Blocklist and webhook joining players are checked against.
+    prelogin: Arc<crate::prelogin::PreLoginChecks>,
+    /// Logins waiting for the webhook, until its verdict comes in through
+    /// `prelogin_rx`.
+    pending_logins: HashMap<SocketAddr, (jwt::LoginData, jwt::ClientData)>,
+    prelogin_tx: tokio::sync::mpsc::UnboundedSender<(SocketAddr, crate::prelogin::Verdict)>,
+    prelogin_rx: tokio::sync::mpsc::UnboundedReceiver<(SocketAddr, crate::prelogin::Verdict)>,
+    /// MOTD advertised to pinging clients; `server_name` is rendered from the
+    /// `[server] motd` template on every refresh.
+    motd: ServerMotd,
+    /// Recent tick rate, for `{tps}` in the MOTD and the view limiter.
+    tps: crate::tps::TpsMeter,
+    /// When the next tick is due, catching up on overrunning ticks.
+    tick_clock: crate::tps::TickClock,
+    /// Lowers view and simulation distances while the server is behind.
+    view_limiter: crate::view_distance::ViewLimiter,
+    /// Seconds players must wait between chat messages, from `[chat]` or
+    /// `/slowmode`.
+    slow_mode: u64,
+    /// Per-section tick timings, for `/timings`.
+    timings: crate::timings::TickProfiler,
+    /// What the game loop is doing, for the watchdog.
+    activity: Arc<crate::watchdog::Activity>,
     /// LevelDB chunk storage provider.
-    chunk_storage: LevelDbProvider,
+    chunk_storage: Box<dyn ChunkStorageProvider>,
     /// World metadata (level.dat).
     level_dat: LevelDat,
     /// Path to the world directory on disk.
     world_dir: std::path::PathBuf,
+    /// Player data backend (world files or a shared store).
+    player_store: Arc<PlayerStore>,
+    /// Player saves started by [`Self::save_all`] that have not been applied yet.
+    pending_saves: tokio::task::JoinSet<PlayerSave>,
     /// Tick counter for auto-save scheduling.
     save_tick_counter: u64,
     /// Auto-save interval in ticks (0 = disabled).
@@ -300,6 +614,23 @@ pub struct ConnectionHandler {
     do_daylight_cycle: bool,
     /// Whether the weather cycle is active.
     do_weather_cycle: bool,
+    /// keepInventory game rule: players keep items and XP on death.
+    keep_inventory: bool,
+    /// commandBlocksEnabled game rule: command blocks and minecarts run their commands.
+    command_blocks_enabled: bool,
+    /// playersSleepingPercentage game rule: share of a world's players that
+    /// must sleep to skip the night. Over 100 never skips it.
+    players_sleeping_percentage: i32,
+    /// Game rules only the client acts on, such as showCoordinates; rules
+    /// missing here have their default value.
+    client_rules: HashMap<&'static str, bool>,
+    /// World difficulty, from `server.difficulty` and `/difficulty`.
+    difficulty: Difficulty,
+    /// Melee knockback tuning, from `[combat]` and `/knockback`.
+    knockback: KnockbackProfile,
+    /// Per-dimension PvP, fall damage, drowning and hunger flags, indexed
+    /// by dimension ID. From `[world_flags]`, `/worldflag` and plugins.
+    world_flags: [WorldFlags; 3],
     /// Current rain intensity (0.0-1.0).
     rain_level: f32,
     /// Current lightning intensity (0.0-1.0).
@@ -314,12 +645,15 @@ pub struct ConnectionHandler {
     is_raining: bool,
     /// Whether it is currently thundering.
     is_thundering: bool,
+    /// Lightning bolt entities on screen: (unique ID, tick to remove them).
+    lightning_bolts: Vec<(i64, u64)>,
     /// Whether the ServerStarted plugin event has been dispatched.
     plugin_started: bool,
-    /// Loaded behavior packs (for resource pack transfer to clients).
-    behavior_packs: Vec<LoadedBehaviorPack>,
+    /// Resource and behavior packs offered to clients.
+    packs: PackStore,
+    /// Optional packs players turned off with `/packs`.
+    pack_preferences: PackPreferences,
     /// Merged loot tables from all loaded behavior packs.
-    #[allow(dead_code)]
     loot_tables: HashMap<String, LootTableFile>,
     /// Block entities (signs, chests, furnaces) keyed by (x, y, z, dimension).
     block_entities: HashMap<(i32, i32, i32, i32), BlockEntityData>,
@@ -329,6 +663,28 @@ pub struct ConnectionHandler {
     smelting_registry: SmeltingRegistry,
     /// Active projectiles (arrows, tridents) in flight.
     active_projectiles: Vec<projectile::ActiveProjectile>,
+    /// Item entities lying in the world.
+    dropped_items: Vec<drops::DroppedItem>,
+    /// Experience orbs lying in the world.
+    xp_orbs: Vec<drops::ExperienceOrb>,
+    /// Actor IDs in each chunk's stored digest, keyed by (cx, cz, dim), for
+    /// the chunks whose actors were loaded or saved this session.
+    actor_digests: HashMap<(i32, i32, i32), Vec<i64>>,
+    /// Filled maps loaded or created this session, by map ID.
+    maps: HashMap<i64, mc_rs_game::map::MapData>,
+    /// Counter for the low half of new map IDs.
+    next_map_id: i64,
+    /// Structures generated in every dimension, for `/locate structure`.
+    structures: StructureRegistry,
+    /// Raids going on in villages.
+    raids: Vec<raids::ActiveRaid>,
+    /// Runtime IDs of the captains guarding pillager outposts.
+    raid_captains: HashSet<u64>,
+    /// Command block minecarts standing on rails.
+    command_block_minecarts: Vec<command_blocks::CommandBlockMinecart>,
+    /// Position selectors resolve from while a command block or minecart
+    /// runs its command.
+    command_origin: Option<(f32, f32, f32)>,
     /// Bow charge start tick per player (for arrow velocity calculation).
     bow_charge_start: HashMap<SocketAddr, u64>,
     /// Scoreboard objectives: name → (display_name, criteria).
@@ -347,6 +703,9 @@ pub struct ConnectionHandler {
     runtime_id_to_addr: HashMap<u64, SocketAddr>,
     /// Block entity positions indexed by chunk coordinate for O(1) per-chunk lookup.
     block_entity_chunk_index: ChunkBlockEntityIndex,
+    /// Recent positions of moving players and mobs, by runtime ID, for lag
+    /// compensated hit validation.
+    position_history: HashMap<u64, PositionHistory>,
 }
 
 /// Data for a custom boss bar.
@@ -374,11 +733,31 @@ impl ConnectionHandler {
         online_mode: bool,
         server_config: Arc<ServerConfig>,
         shutdown_tx: Arc<watch::Sender<bool>>,
+        motd: ServerMotd,
     ) -> Self {
         let mut command_registry = CommandRegistry::new();
         command_registry.register_stub("gamemode", "Set a player's game mode");
         command_registry.register_stub("tp", "Teleport a player");
         command_registry.register_stub("give", "Give items to a player");
+        command_registry.register_stub("kit", "Claim a kit");
+        command_registry.register_stub("stats", "Show player statistics");
+        command_registry.register_stub("home", "Teleport to one of your homes");
+        command_registry.register_stub("sethome", "Set a home at your position");
+        command_registry.register_stub("delhome", "Delete one of your homes");
+        command_registry.register_stub("back", "Return to your previous location");
+        command_registry.register_stub("tpa", "Ask to teleport to a player");
+        command_registry.register_stub("tpaccept", "Accept a teleport request");
+        command_registry.register_stub("tpdeny", "Deny a teleport request");
+        command_registry.register_stub("warp", "Teleport to a warp");
+        command_registry.register_stub("setwarp", "Create a warp at your position");
+        command_registry.register_stub("delwarp", "Delete a warp");
+        command_registry.register_stub("spawn", "Teleport to the world spawn");
+        command_registry.register_stub("balance", "Show your balance or a player's");
+        command_registry.register_stub("pay", "Send money to a player");
+        command_registry.register_stub("eco", "Give, take or set a player's money");
+        command_registry.register_stub("arena", "List, join or leave minigame arenas");
+        command_registry.register_stub("hologram", "Create and edit floating text");
+        command_registry.register_stub("npc", "Create and edit NPCs");
         command_registry.register_stub("kill", "Kill a player");
         command_registry.register_stub("kick", "Kick a player from the server");
         command_registry.register_stub("op", "Grant operator status");
@@ -388,16 +767,29 @@ impl ConnectionHandler {
         command_registry.register_stub("unban", "Unban a player");
         command_registry.register_stub("unban-ip", "Unban an IP address");
         command_registry.register_stub("whitelist", "Manage the whitelist");
+        command_registry.register_stub("permission", "Grant or revoke permission nodes");
         command_registry.register_stub("summon", "Summon an entity");
         command_registry.register_stub("enchant", "Enchant the held item");
         command_registry.register_stub("time", "Set or query the world time");
         command_registry.register_stub("weather", "Set the weather");
         command_registry.register_stub("gamerule", "Set or query a game rule value");
-        command_registry.register_stub("reload", "Reload all plugins");
+        command_registry.register_stub("difficulty", "Set or query the world difficulty");
+        command_registry.register_stub("knockback", "Show or tune melee knockback");
+        command_registry.register_stub("worldflag", "Show or set per-dimension world flags");
+        command_registry.register_stub("world", "List worlds or move players between them");
+        command_registry
+            .register_stub("reload", "Reload server.toml, permission lists and plugins");
+        command_registry.register_stub("restart", "Save, kick everyone and restart the server");
         command_registry.register_stub("setblock", "Set a block at a position");
         command_registry.register_stub("fill", "Fill a region with blocks");
+        command_registry.register_stub("locate", "Find the nearest biome or structure");
         command_registry.register_stub("clone", "Clone a region of blocks");
         command_registry.register_stub("title", "Display a title to players");
+        command_registry.register_stub("camera", "Move or fade a player's camera");
+        command_registry.register_stub("fog", "Push or remove fog for players");
+        command_registry.register_stub("inputpermission", "Lock or unlock player inputs");
+        command_registry
+            .register_stub("packs", "List the server's packs or turn optional ones off");
         command_registry.register_stub("particle", "Spawn particle effects");
         command_registry.register_stub("playsound", "Play a sound");
         command_registry.register_stub("scoreboard", "Manage scoreboards");
@@ -406,8 +798,30 @@ impl ConnectionHandler {
         command_registry.register_stub("execute", "Execute a command with modifiers");
         command_registry.register_stub("transfer", "Transfer players to another server");
         command_registry.register_stub("tickingarea", "Manage ticking areas");
+        command_registry.register_stub("scriptevent", "Send a script event to plugins");
+        command_registry.register_stub("function", "Run a function from a behavior pack");
+        command_registry.register_stub("timings", "Report where tick time is spent");
+        command_registry.register_stub("tps", "Show the server's tick rate");
+        command_registry.register_stub("viewdistance", "Show or set a player's view distance");
+        command_registry.register_stub("whois", "Show where a player connects from");
+        command_registry.register_stub("mute", "Stop a player from chatting");
+        command_registry.register_stub("unmute", "Let a muted player chat again");
+        command_registry.register_stub("slowmode", "Show or set the chat slow mode");
+        command_registry.register_stub("msg", "Send a private message to a player");
+        command_registry.register_stub("tell", "Send a private message to a player");
+        command_registry.register_stub("reply", "Answer your last private message");
+        command_registry.register_stub("ignore", "Ignore or stop ignoring a player's messages");
+        command_registry.register_stub("socialspy", "See other players' private messages");
 
         let permissions = PermissionManager::load(server_config.permissions.whitelist_enabled);
+        let geoip = crate::geoip::GeoIp::load(&server_config.geoip);
+        let prelogin = Arc::new(crate::prelogin::PreLoginChecks::load(
+            &server_config.prelogin,
+        ));
+        let (prelogin_tx, prelogin_rx) = tokio::sync::mpsc::unbounded_channel();
+        let slow_mode = server_config.chat.slow_mode;
+        let native_plugins = server_config.plugins.native;
+        let bridge = crate::bridge::BridgeWebhook::start(&server_config.bridge);
 
         // Initialize world generator based on config
         let gen_name = server_config.world.generator.to_lowercase();
@@ -444,16 +858,23 @@ impl ConnectionHandler {
 
         // Initialize world storage
         let world_dir = std::path::PathBuf::from(format!("worlds/{}", server_config.world.name));
-        std::fs::create_dir_all(world_dir.join("db")).expect("Failed to create world db directory");
         std::fs::create_dir_all(world_dir.join("players"))
             .expect("Failed to create players directory");
 
-        let chunk_storage =
-            LevelDbProvider::open(&world_dir.join("db")).expect("Failed to open LevelDB");
+        let mut chunk_storage = open_chunk_storage(
+            &world_dir,
+            &server_config.storage,
+            &server_config.storage.backend,
+        )
+        .expect("Failed to open chunk storage");
+        let structures = chunk_storage
+            .get_raw(STRUCTURES_KEY)
+            .map(|data| StructureRegistry::deserialize(&data))
+            .unwrap_or_default();
 
         // Load or create level.dat
         let level_dat_path = world_dir.join("level.dat");
-        let level_dat = if level_dat_path.exists() {
+        let mut level_dat = if level_dat_path.exists() {
             LevelDat::load(&level_dat_path).unwrap_or_else(|e| {
                 warn!("Failed to load level.dat: {e}, creating new");
                 LevelDat::new(
@@ -464,21 +885,44 @@ impl ConnectionHandler {
                 )
             })
         } else {
-            let dat = LevelDat::new(
+            LevelDat::new(
                 &server_config.world.name,
                 server_config.world.seed,
                 &server_config.world.generator,
                 (spawn_block.x, spawn_block.y, spawn_block.z),
-            );
-            if let Err(e) = dat.save(&level_dat_path) {
-                warn!("Failed to save initial level.dat: {e}");
-            }
-            dat
+            )
         };
+        // Saved right away so a crash can't reuse this session's actor IDs
+        level_dat.world_start_count += 1;
+        if let Err(e) = level_dat.save(&level_dat_path) {
+            warn!("Failed to save level.dat: {e}");
+        }
 
         // Write levelname.txt
         std::fs::write(world_dir.join("levelname.txt"), &server_config.world.name).ok();
 
+        let kits = KitManager::load(&world_dir, &server_config.kits);
+        let warps = WarpManager::load(&world_dir);
+        let economy = SharedEconomy::new(Economy::load(&world_dir));
+        let shops = ShopManager::load(&world_dir);
+        let holograms = HologramManager::load(&world_dir);
+        let npcs = NpcManager::load(&world_dir);
+        let player_store = Arc::new(PlayerStore::from_config(
+            &server_config.player_data,
+            &world_dir,
+        ));
+        let keep_inventory = server_config.gameplay.keep_inventory;
+        let command_blocks_enabled = server_config.gameplay.command_blocks_enabled;
+        let show_coordinates = server_config.gameplay.show_coordinates;
+        let difficulty =
+            Difficulty::from_name(&server_config.server.difficulty).unwrap_or_else(|| {
+                warn!(
+                    "Unknown difficulty {:?}, using normal",
+                    server_config.server.difficulty
+                );
+                Difficulty::Normal
+            });
+
         let auto_save_interval_ticks = server_config.world.auto_save_interval * 20;
 
         // Extract weather state from level_dat before moving it
@@ -495,6 +939,15 @@ impl ConnectionHandler {
 
         // Debug: log flat world block hashes for verification
         let fb = FlatWorldBlocks::compute();
+        let world_ids = crate::worlds::assign_dimensions(&world_dir, server_config.worlds.keys());
+        let world_times = crate::worlds::load_times(&world_dir);
+        let extra_worlds = ExtraWorld::from_config(
+            &server_config.worlds,
+            &world_ids,
+            &world_times,
+            server_config.world.seed,
+            fb,
+        );
         info!(
             "Block hashes: air={:#010X}, bedrock={:#010X}, dirt={:#010X}, grass={:#010X}",
             fb.air, fb.bedrock, fb.dirt, fb.grass_block
@@ -505,12 +958,30 @@ impl ConnectionHandler {
         std::fs::create_dir_all(&packs_dir).ok();
         let behavior_packs = mc_rs_behavior_pack::load_all_packs(&packs_dir);
 
+        // Collect the packs offered to clients (resource packs + zipped behavior packs)
+        let resource_packs_dir = std::path::PathBuf::from(&server_config.packs.resource_directory);
+        std::fs::create_dir_all(&resource_packs_dir).ok();
+        let packs = PackStore::load(&resource_packs_dir, &behavior_packs);
+        let pack_preferences = PackPreferences::load(&world_dir);
+
         // Build registries and register behavior pack content
         let mut block_registry = BlockRegistry::new();
+        let mut block_states = BlockStateRegistry::new();
+        let mut custom_blocks = CustomBlocks::default();
+        let mut custom_items = CustomItems::default();
+        let mut functions = Functions::default();
         let mut item_registry = ItemRegistry::new();
         let mut recipe_registry = RecipeRegistry::new();
         let mut game_world = GameWorld::new(1);
-        let mut loot_tables: HashMap<String, LootTableFile> = HashMap::new();
+        game_world.spawn_config.spawn_hostiles = difficulty.allows_hostile_mobs();
+        let hostile_rule = &server_config.gameplay.hostile_spawn_rule;
+        game_world.spawn_config.hostile_rule = HostileSpawnRule::from_name(hostile_rule)
+            .unwrap_or_else(|| {
+                warn!("Unknown hostile spawn rule {hostile_rule:?}, using light");
+                HostileSpawnRule::Light
+            });
+        game_world.set_entity_collision(server_config.gameplay.entity_collision);
+        let mut loot_tables: HashMap<String, LootTableFile> = vanilla_loot_tables();
 
         for pack in &behavior_packs {
             // Register custom entities into mob registry
@@ -536,23 +1007,23 @@ impl ConnectionHandler {
                         bb_width: entity.bb_width,
                         bb_height: entity.bb_height,
                     });
+                game_world
+                    .mob_registry
+                    .register_behavior(&entity.identifier, entity.behavior.clone());
             }
 
             // Register custom items
             for item in &pack.items {
-                item_registry.register_item(
-                    item.identifier.clone(),
-                    item.max_stack_size,
-                    item.is_component_based,
-                );
+                custom_items.register(item.clone(), &mut item_registry);
             }
 
             // Register custom blocks
             for block in &pack.blocks {
-                block_registry.register_block(
-                    block.identifier.clone(),
-                    block.hardness,
-                    block.is_solid,
+                custom_blocks.register(
+                    block.clone(),
+                    &mut block_registry,
+                    &mut block_states,
+                    &mut item_registry,
                 );
             }
 
@@ -620,8 +1091,13 @@ impl ConnectionHandler {
             for (key, table) in &pack.loot_tables {
                 loot_tables.insert(key.clone(), table.clone());
             }
+
+            functions.register(&pack.functions);
         }
+        functions.check_tags();
 
+        let knockback = server_config.combat.knockback();
+        let world_flags = server_config.world_flags.by_dimension();
         Self {
             connections: HashMap::new(),
             server_handle,
@@ -633,18 +1109,52 @@ impl ConnectionHandler {
             nether_generator,
             end_generator,
             dimension_id,
+            extra_worlds,
             spawn_position,
             spawn_block,
             command_registry,
             shutdown_tx,
+            restart_requested: false,
             world_chunks: HashMap::new(),
             block_registry,
+            block_states,
+            custom_blocks,
+            custom_items,
+            functions,
+            function_depth: 0,
+            function_commands: 0,
             item_registry,
             recipe_registry,
             permissions,
+            geoip,
+            kits,
+            warps,
+            economy,
+            shops,
+            holograms,
+            npcs,
+            arenas: ArenaManager::default(),
+            cutscenes: CutsceneManager::default(),
+            entity_appearances: HashMap::new(),
+            actor_data: ActorDataTracker::default(),
+            pending_handoffs: HashMap::new(),
+            bridge,
+            prelogin,
+            pending_logins: HashMap::new(),
+            prelogin_tx,
+            prelogin_rx,
+            motd,
+            tps: crate::tps::TpsMeter::new(),
+            tick_clock: crate::tps::TickClock::new(Instant::now()),
+            view_limiter: crate::view_distance::ViewLimiter::new(),
+            slow_mode,
+            timings: crate::timings::TickProfiler::new(),
+            activity: Arc::new(crate::watchdog::Activity::new()),
             chunk_storage,
             level_dat,
             world_dir,
+            player_store,
+            pending_saves: tokio::task::JoinSet::new(),
             save_tick_counter: 0,
             auto_save_interval_ticks,
             tick_blocks: TickBlocks::compute(),
@@ -652,6 +1162,13 @@ impl ConnectionHandler {
             world_time: initial_world_time,
             do_daylight_cycle: true,
             do_weather_cycle: true,
+            keep_inventory,
+            command_blocks_enabled,
+            players_sleeping_percentage: 100,
+            client_rules: HashMap::from([("showcoordinates", show_coordinates)]),
+            difficulty,
+            knockback,
+            world_flags,
             rain_level: initial_rain_level,
             lightning_level: initial_lightning_level,
             rain_target: initial_rain_level,
@@ -659,6 +1176,7 @@ impl ConnectionHandler {
             weather_duration: initial_weather_duration,
             is_raining: initial_rain_level > 0.0,
             is_thundering: initial_lightning_level > 0.0,
+            lightning_bolts: Vec::new(),
             plugin_manager: {
                 let mut mgr = PluginManager::new();
                 // Load WASM plugins from plugins/ directory
@@ -674,15 +1192,32 @@ impl ConnectionHandler {
                 for plugin in lua_plugins {
                     mgr.register(plugin);
                 }
+                // Load native plugins only when enabled
+                if native_plugins {
+                    for plugin in mc_rs_plugin_native::load_native_plugins(&plugins_dir) {
+                        mgr.register(plugin);
+                    }
+                }
                 mgr
             },
             plugin_started: false,
-            behavior_packs,
+            packs,
+            pack_preferences,
             loot_tables,
             block_entities: HashMap::new(),
             block_entity_hashes: BlockEntityHashes::compute(),
             smelting_registry: SmeltingRegistry::new(),
             active_projectiles: Vec::new(),
+            dropped_items: Vec::new(),
+            xp_orbs: Vec::new(),
+            actor_digests: HashMap::new(),
+            maps: HashMap::new(),
+            next_map_id: 0,
+            structures,
+            raids: Vec::new(),
+            raid_captains: HashSet::new(),
+            command_block_minecarts: Vec::new(),
+            command_origin: None,
             bow_charge_start: HashMap::new(),
             scoreboard_objectives: HashMap::new(),
             scoreboard_scores: HashMap::new(),
@@ -692,6 +1227,7 @@ impl ConnectionHandler {
             ticking_areas: Vec::new(),
             runtime_id_to_addr: HashMap::new(),
             block_entity_chunk_index: HashMap::new(),
+            position_history: HashMap::new(),
         }
     }
 
@@ -741,6 +1277,23 @@ impl ConnectionHandler {
         result
     }
 
+    /// Load the block entities stored for a chunk just loaded from storage.
+    pub(super) fn load_chunk_block_entities(&mut self, cx: i32, cz: i32, dim: i32) {
+        let Some(data) = self
+            .chunk_storage
+            .get_raw(&block_entity_key_dim(cx, cz, dim))
+        else {
+            return;
+        };
+        for ((bx, by, bz), be) in block_entity::parse_block_entities(&data) {
+            if (bx >> 4, bz >> 4) != (cx, cz) {
+                warn!("Skipping block entity at ({bx},{by},{bz}) stored in chunk ({cx},{cz})");
+                continue;
+            }
+            self.insert_block_entity_dim((bx, by, bz), dim, be);
+        }
+    }
+
     /// Get block entities in a specific chunk (overworld, dim=0) (O(1) via index).
     #[allow(dead_code)]
     pub(super) fn block_entities_in_chunk(&self, cx: i32, cz: i32) -> Vec<(i32, i32, i32)> {
@@ -763,7 +1316,7 @@ impl ConnectionHandler {
     // ─── Plugin helpers ────────────────────────────────────────────────────
 
     /// Build a PluginPlayer from a PlayerConnection.
-    pub(super) fn make_plugin_player(conn: &PlayerConnection) -> PluginPlayer {
+    pub(super) fn make_plugin_player(&self, conn: &PlayerConnection) -> PluginPlayer {
         let name = conn
             .login_data
             .as_ref()
@@ -780,7 +1333,8 @@ impl ConnectionHandler {
             runtime_id: conn.entity_runtime_id,
             position: (conn.position.x, conn.position.y, conn.position.z),
             gamemode: conn.gamemode,
-            health: conn.health,
+            health: self.game_world.player_health(conn.entity_unique_id),
+            country: conn.country.clone(),
         }
     }
 
@@ -790,18 +1344,58 @@ impl ConnectionHandler {
             .connections
             .values()
             .filter(|c| c.state == LoginState::InGame)
-            .map(Self::make_plugin_player)
+            .map(|c| self.make_plugin_player(c))
+            .collect();
+        let player_stats: HashMap<String, PluginPlayerStats> = self
+            .connections
+            .values()
+            .filter(|c| c.state == LoginState::InGame)
+            .filter_map(|c| {
+                let name = c.login_data.as_ref()?.display_name.clone();
+                let s = &c.stats;
+                Some((
+                    name,
+                    PluginPlayerStats {
+                        blocks_mined: s.blocks_mined.clone(),
+                        mobs_killed: s.mobs_killed.clone(),
+                        distance_walked_cm: s.distance_walked_cm,
+                        deaths: s.deaths,
+                        playtime_ticks: s.playtime_ticks,
+                    },
+                ))
+            })
             .collect();
         ServerSnapshot {
             players,
+            player_stats,
             world_time: self.world_time,
             current_tick: self.game_world.current_tick(),
+            tps: self.tps.tps(),
+            mspt: self.tps.mspt(),
             is_raining: self.is_raining,
+            world_flags: self.world_flags,
+            economy: self.economy.clone(),
+            currency_name: self.server_config.economy.currency_name.clone(),
+            arena_players: self
+                .arenas
+                .iter()
+                .flat_map(|a| a.players.iter().map(|p| (p.clone(), a.def.name.clone())))
+                .collect(),
+            accepted_packs: self
+                .connections
+                .values()
+                .filter(|c| c.state == LoginState::InGame)
+                .filter_map(|c| {
+                    let name = c.login_data.as_ref()?.display_name.clone();
+                    Some((name, c.accepted_packs.clone()))
+                })
+                .collect(),
         }
     }
 
     /// Process a RakNet event.
     pub async fn handle_event(&mut self, event: RakNetEvent) {
+        self.enter_section(crate::timings::PACKETS);
         match event {
             RakNetEvent::SessionConnected { addr, guid } => {
                 self.handle_session_connected(addr, guid);
@@ -816,19 +1410,80 @@ impl ConnectionHandler {
         // Immediately process any game events generated during packet handling
         // (e.g. mob spawns, damage) so clients see results without waiting for next tick.
         self.process_game_events().await;
+        self.flush_packets().await;
+        self.exit_section();
     }
 
     /// Run one ECS game tick (called every 50ms from main loop) and process outgoing events.
     pub async fn game_tick(&mut self) {
-        self.game_world.tick();
+        use crate::timings::{
+            BLOCK_ENTITIES, BLOCK_UPDATES, ENTITIES, FUNCTIONS, MOB_AI, PLAYERS, SENDING, STORAGE,
+        };
+
+        self.tps.record(Instant::now());
+        self.start_tick_timing();
+        self.enter_section(MOB_AI);
+        let simulated = self.get_simulation_chunks();
+        self.game_world.set_simulated_chunks(simulated);
+        self.tick_bubble_columns();
+        self.tick_mob_surfaces();
+        self.tick_mob_sunlight();
+        self.tick_game_world();
         self.process_game_events().await;
+        self.exit_section();
+
+        self.enter_section(PLAYERS);
+        self.tick_prelogin_checks().await;
         self.tick_effects().await;
         self.tick_survival().await;
+        self.tick_eating().await;
+        self.tick_gliding().await;
+        self.tick_actor_data().await;
+        self.exit_section();
+
+        self.enter_section(BLOCK_UPDATES);
         self.tick_block_updates().await;
+        self.exit_section();
+
+        self.enter_section(BLOCK_ENTITIES);
         self.tick_furnaces().await;
+        self.tick_beacons().await;
+        self.tick_maps().await;
+        self.tick_command_blocks().await;
+        self.tick_note_blocks().await;
+        self.tick_jukeboxes().await;
+        self.exit_section();
+
+        self.enter_section(ENTITIES);
         self.tick_projectiles().await;
+        self.tick_drops().await;
+        self.exit_section();
+
         self.tick_time_and_weather().await;
 
+        self.enter_section(PLAYERS);
+        self.tick_pack_transfers().await;
+        self.tick_afk().await;
+        self.tick_teleports().await;
+        self.tick_arenas().await;
+        self.tick_holograms().await;
+        self.tick_cutscenes().await;
+        self.tick_sleep().await;
+        self.tick_raids().await;
+        self.tick_view_distance().await;
+        self.exit_section();
+
+        // Statistics: playtime
+        for conn in self.connections.values_mut() {
+            if conn.state == LoginState::InGame {
+                conn.stats.playtime_ticks += 1;
+            }
+        }
+
+        self.enter_section(FUNCTIONS);
+        self.tick_functions().await;
+        self.exit_section();
+
         // Plugin: dispatch ServerStarted on first tick
         if !self.plugin_started {
             self.plugin_started = true;
@@ -837,6 +1492,7 @@ impl ConnectionHandler {
                 .plugin_manager
                 .dispatch(&PluginEvent::ServerStarted, &snapshot);
             self.apply_plugin_actions(actions).await;
+            self.bridge.post(BridgeEvent::ServerStart);
         }
 
         // Plugin scheduler tick
@@ -846,39 +1502,239 @@ impl ConnectionHandler {
         };
         self.apply_plugin_actions(plugin_actions).await;
 
-        // Anti-cheat: decay violations and kick if threshold exceeded
+        // Anti-cheat: decay violation scores
         let current_tick = self.game_world.current_tick();
-        if current_tick.is_multiple_of(VIOLATION_DECAY_INTERVAL) {
-            let mut kick_list: Vec<(SocketAddr, &'static str)> = Vec::new();
-            for (&addr, conn) in &mut self.connections {
-                if conn.state != LoginState::InGame {
-                    continue;
-                }
+        let decay_interval = self.server_config.anticheat.decay_interval * 20;
+        if decay_interval > 0 && current_tick.is_multiple_of(decay_interval) {
+            for conn in self.connections.values_mut() {
                 conn.violations.decay();
-                if let Some(reason) = conn.violations.should_kick() {
-                    kick_list.push((addr, reason));
-                }
-            }
-            for (addr, reason) in kick_list {
-                warn!("Kicking player {addr} for anti-cheat violation: {reason}");
-                self.disconnect_player(addr, reason).await;
             }
         }
+        if current_tick.is_multiple_of(20) {
+            self.prune_position_history();
+        }
+
+        // Refresh the advertised MOTD
+        let motd_interval = self.server_config.server.motd_update_interval * 20;
+        if motd_interval > 0 && current_tick.is_multiple_of(motd_interval) {
+            self.refresh_motd().await;
+        }
 
-        // Auto-save
+        // Auto-save, or the save the watchdog asks for before aborting
+        self.enter_section(STORAGE);
+        while let Some(done) = self.pending_saves.try_join_next() {
+            match done {
+                Ok(save) => {
+                    self.finish_player_save(save).await;
+                }
+                Err(e) => warn!("Player save task failed: {e}"),
+            }
+        }
+        if self.activity.take_save_request() {
+            warn!("Saving the world for the watchdog");
+            self.save_all().await;
+            self.activity.confirm_saved();
+        }
         if self.auto_save_interval_ticks > 0 {
             self.save_tick_counter += 1;
             if self.save_tick_counter >= self.auto_save_interval_ticks {
                 self.save_tick_counter = 0;
-                self.save_all();
+                self.save_all().await;
+            }
+        }
+
+        // Storage maintenance
+        for (cx, cz, dim) in self.chunk_storage.take_quarantined() {
+            warn!("Chunk dim={dim} ({cx},{cz}) failed verification, quarantined and regenerated");
+        }
+        if current_tick.is_multiple_of(20) {
+            match self.chunk_storage.maybe_compact() {
+                Ok(true) => debug!("Compacted chunk storage"),
+                Ok(false) => {}
+                Err(e) => warn!("Chunk storage compaction failed: {e}"),
             }
         }
+        self.exit_section();
+
+        self.enter_section(SENDING);
+        self.flush_packets().await;
+        self.exit_section();
+        self.finish_tick_timing();
     }
 
     // -----------------------------------------------------------------------
     // Utility helpers used by sub-modules
     // -----------------------------------------------------------------------
 
+    /// Unique entity ID of the player at `addr`, which keys their entity in
+    /// the game world.
+    pub(super) fn player_uid(&self, addr: SocketAddr) -> Option<i64> {
+        self.connections.get(&addr).map(|c| c.entity_unique_id)
+    }
+
+    /// A player's health, 0 if not connected.
+    pub(super) fn health(&self, addr: SocketAddr) -> f32 {
+        self.player_uid(addr)
+            .map_or(0.0, |uid| self.game_world.player_health(uid))
+    }
+
+    /// Add `delta` to a player's health, clamped between 0 and their
+    /// maximum. Returns the new health.
+    pub(super) fn change_health(&mut self, addr: SocketAddr, delta: f32) -> f32 {
+        let Some(uid) = self.player_uid(addr) else {
+            return 0.0;
+        };
+        let health = self.game_world.player_health(uid);
+        self.game_world.set_player_health(uid, health + delta)
+    }
+
+    /// A player's hunger (full if not connected).
+    pub(super) fn hunger(&self, addr: SocketAddr) -> Hunger {
+        self.player_uid(addr)
+            .and_then(|uid| self.game_world.player::<Hunger>(uid).copied())
+            .unwrap_or_default()
+    }
+
+    pub(super) fn set_hunger(&mut self, addr: SocketAddr, hunger: Hunger) {
+        let Some(uid) = self.player_uid(addr) else {
+            return;
+        };
+        if let Some(mut h) = self.game_world.player_mut::<Hunger>(uid) {
+            *h = hunger;
+        }
+    }
+
+    pub(super) fn add_exhaustion(&mut self, addr: SocketAddr, amount: f32) {
+        let mut hunger = self.hunger(addr);
+        hunger.exhaustion += amount;
+        self.set_hunger(addr, hunger);
+    }
+
+    /// A player's experience (none if not connected).
+    pub(super) fn experience(&self, addr: SocketAddr) -> Experience {
+        self.player_uid(addr)
+            .and_then(|uid| self.game_world.player::<Experience>(uid).copied())
+            .unwrap_or_default()
+    }
+
+    pub(super) fn set_experience(&mut self, addr: SocketAddr, level: i32, total: i32) {
+        let Some(uid) = self.player_uid(addr) else {
+            return;
+        };
+        if let Some(mut xp) = self.game_world.player_mut::<Experience>(uid) {
+            *xp = Experience { level, total };
+        }
+    }
+
+    /// A player's active status effects.
+    pub(super) fn effects(&self, addr: SocketAddr) -> &[ActiveEffect] {
+        match self.player_uid(addr) {
+            Some(uid) => self.game_world.player_effects(uid),
+            None => &[],
+        }
+    }
+
+    /// Save a player's data to the player store. Returns `true` on success.
+    ///
+    /// If another server saved newer data since it was loaded, the player is
+    /// kicked and their data here is never saved, so that they pick up the
+    /// newer copy when they join again.
+    pub(super) async fn save_player_data(&mut self, addr: SocketAddr) -> bool {
+        if self.connections.get(&addr).is_some_and(|c| c.save_pending) {
+            self.collect_player_saves(Instant::now() + PLAYER_SAVE_DEADLINE)
+                .await;
+        }
+        let Some(save) = self.begin_player_save(addr) else {
+            return false;
+        };
+        let save = save.await;
+        self.finish_player_save(save).await
+    }
+
+    /// Snapshot a player's data and return the save to run, unless their
+    /// data is stale or a save is already running.
+    fn begin_player_save(
+        &mut self,
+        addr: SocketAddr,
+    ) -> Option<impl std::future::Future<Output = PlayerSave> + Send + 'static> {
+        let conn = self.connections.get_mut(&addr)?;
+        let login = conn.login_data.as_ref()?;
+        if conn.data_stale || conn.save_pending {
+            return None;
+        }
+        let (xuid, uuid, name) = (
+            login.xuid.clone(),
+            login.identity.clone(),
+            login.display_name.clone(),
+        );
+        let data = PlayerData::from_connection(conn, &self.game_world);
+        let version = conn.data_version;
+        conn.save_pending = true;
+        let store = Arc::clone(&self.player_store);
+        Some(async move {
+            let result = store.save(&xuid, &uuid, data, version).await;
+            PlayerSave { addr, name, result }
+        })
+    }
+
+    /// Apply a finished save. Returns `true` if it succeeded.
+    async fn finish_player_save(&mut self, save: PlayerSave) -> bool {
+        let PlayerSave { addr, name, result } = save;
+        if let Some(conn) = self.connections.get_mut(&addr) {
+            conn.save_pending = false;
+        }
+        match result {
+            Ok(version) => {
+                if let Some(conn) = self.connections.get_mut(&addr) {
+                    conn.data_version = version;
+                }
+                true
+            }
+            Err(e @ StoreError::Conflict { .. }) => {
+                warn!("Not saving player data for {name}: {e}");
+                if let Some(conn) = self.connections.get_mut(&addr) {
+                    conn.data_stale = true;
+                }
+                self.disconnect_player(
+                    addr,
+                    "Your player data was changed on another server, please reconnect",
+                )
+                .await;
+                false
+            }
+            Err(e) => {
+                warn!("Failed to save player data for {name}: {e}");
+                false
+            }
+        }
+    }
+
+    /// Apply player saves as they finish, until none are left or `deadline`
+    /// passes. Returns how many succeeded.
+    async fn collect_player_saves(&mut self, deadline: Instant) -> u32 {
+        let mut saved = 0;
+        loop {
+            let next = self.pending_saves.join_next();
+            match tokio::time::timeout_at(deadline.into(), next).await {
+                Ok(Some(Ok(save))) => {
+                    if self.finish_player_save(save).await {
+                        saved += 1;
+                    }
+                }
+                Ok(Some(Err(e))) => warn!("Player save task failed: {e}"),
+                Ok(None) => break,
+                Err(_) => {
+                    warn!(
+                        "{} player saves are still running, applying them later",
+                        self.pending_saves.len()
+                    );
+                    break;
+                }
+            }
+        }
+        saved
+    }
+
     /// Find a player's SocketAddr by display name.
     pub(super) fn find_player_addr(&self, name: &str) -> Option<SocketAddr> {
         self.connections.iter().find_map(|(&addr, conn)| {
@@ -916,16 +1772,25 @@ impl ConnectionHandler {
         target: &str,
         addr: SocketAddr,
     ) -> Result<Vec<String>, String> {
-        let conn = self
-            .connections
-            .get(&addr)
-            .ok_or_else(|| "Sender not found".to_string())?;
-        let sender_name = conn
-            .login_data
-            .as_ref()
-            .map(|d| d.display_name.as_str())
-            .unwrap_or("unknown");
-        let sender_pos = (conn.position.x, conn.position.y, conn.position.z);
+        let (sender_name, sender_pos) = match self.connections.get(&addr) {
+            Some(conn) => (
+                conn.login_data
+                    .as_ref()
+                    .map(|d| d.display_name.as_str())
+                    .unwrap_or("unknown"),
+                (conn.position.x, conn.position.y, conn.position.z),
+            ),
+            // Commands run by the server resolve from the command block
+            // running them, or the world spawn
+            None => (
+                functions::SERVER_SENDER,
+                self.command_origin.unwrap_or((
+                    self.spawn_block.x as f32,
+                    self.spawn_block.y as f32,
+                    self.spawn_block.z as f32,
+                )),
+            ),
+        };
         let players = self.online_player_infos();
         mc_rs_command::selector::resolve_target(target, sender_name, sender_pos, &players)
     }
@@ -939,11 +1804,12 @@ impl ConnectionHandler {
     pub(super) async fn disconnect_player(&mut self, addr: SocketAddr, reason: &str) {
         let pkt = Disconnect::with_message(reason);
         self.send_packet(addr, packets::id::DISCONNECT, &pkt).await;
+        self.flush_player(addr).await;
     }
 
     /// Check rate limit for an action. Returns true if the action is allowed.
     /// Increments the global action counter and checks the per-category interval.
-    pub(super) fn check_rate_limit(
+    pub(super) async fn check_rate_limit(
         &mut self,
         addr: SocketAddr,
         last_tick: u64,
@@ -963,18 +1829,18 @@ impl ConnectionHandler {
         conn.actions_this_second += 1;
 
         // Global action rate
-        if conn.actions_this_second > MAX_ACTIONS_PER_SECOND {
-            conn.violations.rate_limit += 1;
-            return false;
-        }
-
-        // Per-category interval
-        if current_tick.saturating_sub(last_tick) < min_interval {
-            conn.violations.rate_limit += 1;
-            return false;
-        }
-
-        true
+        let detail = if conn.actions_this_second > MAX_ACTIONS_PER_SECOND {
+            format!("{} actions this second", conn.actions_this_second)
+        } else if current_tick.saturating_sub(last_tick) < min_interval {
+            // Per-category interval
+            format!(
+                "{} ticks since last action, minimum {min_interval}",
+                current_tick.saturating_sub(last_tick)
+            )
+        } else {
+            return true;
+        };
+        !self.flag_violation(addr, Check::RateLimit, &detail).await
     }
 
     pub(super) async fn broadcast_packet(&mut self, packet_id: u32, packet: &impl ProtoEncode) {
@@ -1068,20 +1934,7 @@ impl ConnectionHandler {
 
     /// Get the block runtime ID at a world position in a specific dimension.
     pub(super) fn get_block_in(&self, dim: i32, x: i32, y: i32, z: i32) -> Option<u32> {
-        let cx = x >> 4;
-        let cz = z >> 4;
-        let dim_map = self.world_chunks.get(&dim)?;
-        let column = dim_map.get(&(cx, cz))?;
-
-        let sub_index = (y - OVERWORLD_MIN_Y) / 16;
-        if sub_index < 0 || sub_index >= OVERWORLD_SUB_CHUNK_COUNT as i32 {
-            return None;
-        }
-        let local_x = (x & 15) as usize;
-        let local_y = ((y - OVERWORLD_MIN_Y) % 16) as usize;
-        let local_z = (z & 15) as usize;
-
-        Some(column.sub_chunks[sub_index as usize].get_block(local_x, local_y, local_z))
+        block_in(self.world_chunks.get(&dim)?, x, y, z)
     }
 
     /// Set a block at a world position (overworld, dim=0). Returns false if the chunk is not loaded.
@@ -1117,7 +1970,52 @@ impl ConnectionHandler {
         let local_y = ((y - OVERWORLD_MIN_Y) % 16) as usize;
         let local_z = (z & 15) as usize;
 
-        column.sub_chunks[sub_index as usize].set_block(local_x, local_y, local_z, runtime_id);
+        // Whatever the block held (waterlogging) goes with it
+        let air = self.flat_world_blocks.air;
+        let sub = &mut column.sub_chunks[sub_index as usize];
+        sub.set_block(local_x, local_y, local_z, runtime_id);
+        sub.set_liquid(local_x, local_y, local_z, air, air);
+        column.dirty = true;
+        column.cached_payload = None;
+        true
+    }
+
+    /// Get the liquid a block holds (its second layer), if any.
+    pub(super) fn get_liquid_in(&self, dim: i32, x: i32, y: i32, z: i32) -> Option<u32> {
+        let column = self.world_chunks.get(&dim)?.get(&(x >> 4, z >> 4))?;
+        column.get_liquid_world((x & 15) as usize, y, (z & 15) as usize)
+    }
+
+    /// Set the liquid a block holds, for waterlogging. Returns false if the
+    /// chunk is not loaded.
+    pub(super) fn set_liquid_in(
+        &mut self,
+        dim: i32,
+        x: i32,
+        y: i32,
+        z: i32,
+        runtime_id: u32,
+    ) -> bool {
+        let air = self.flat_world_blocks.air;
+        let Some(column) = self
+            .world_chunks
+            .get_mut(&dim)
+            .and_then(|m| m.get_mut(&(x >> 4, z >> 4)))
+        else {
+            return false;
+        };
+        let sub_index = (y - OVERWORLD_MIN_Y) / 16;
+        if sub_index < 0 || sub_index >= OVERWORLD_SUB_CHUNK_COUNT as i32 {
+            return false;
+        }
+        let local_y = ((y - OVERWORLD_MIN_Y) % 16) as usize;
+        column.sub_chunks[sub_index as usize].set_liquid(
+            (x & 15) as usize,
+            local_y,
+            (z & 15) as usize,
+            runtime_id,
+            air,
+        );
         column.dirty = true;
         column.cached_payload = None;
         true
@@ -1140,36 +2038,75 @@ impl ConnectionHandler {
     // Packet sending
     // -----------------------------------------------------------------------
 
+    /// Send a packet to a player. In game, packets are queued and sent as one
+    /// batch per player by [`flush_packets`](Self::flush_packets); during
+    /// login, where compression and encryption are switched on between
+    /// packets, each is sent on its own right away.
     pub(super) async fn send_packet(
         &mut self,
         addr: SocketAddr,
         packet_id: u32,
         packet: &impl ProtoEncode,
     ) {
-        let (batch_config, has_encryption) = match self.connections.get(&addr) {
-            Some(c) => (c.batch_config.clone(), c.encryption.is_some()),
-            None => return,
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
         };
+        let sub_packet = conn.protocol.encode(packet_id, packet);
+        if conn.state != LoginState::InGame {
+            self.send_batch(addr, vec![sub_packet]).await;
+            return;
+        }
+        conn.outbox_bytes += sub_packet.len();
+        conn.outbox.push(sub_packet);
+        if conn.outbox_bytes >= MAX_BATCH_BYTES || conn.outbox.len() >= MAX_BATCH_PACKETS {
+            self.flush_player(addr).await;
+        }
+    }
 
-        let sub_packet = encode_sub_packet(packet_id, packet);
-        let batch = match encode_single(sub_packet, &batch_config) {
+    /// Send every player's queued packets, one batch each.
+    pub async fn flush_packets(&mut self) {
+        let addrs: Vec<SocketAddr> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| !c.outbox.is_empty())
+            .map(|(&addr, _)| addr)
+            .collect();
+        for addr in addrs {
+            self.flush_player(addr).await;
+        }
+    }
+
+    /// Send a player's queued packets as one batch.
+    pub(super) async fn flush_player(&mut self, addr: SocketAddr) {
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        if conn.outbox.is_empty() {
+            return;
+        }
+        let packets = std::mem::take(&mut conn.outbox);
+        conn.outbox_bytes = 0;
+        self.send_batch(addr, packets).await;
+    }
+
+    /// Compress, encrypt and send sub-packets as one batch.
+    async fn send_batch(&mut self, addr: SocketAddr, packets: Vec<Bytes>) {
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        let batch = match encode_batch(&packets, &conn.batch_config) {
             Ok(b) => b,
             Err(e) => {
-                tracing::error!("Failed to encode packet 0x{packet_id:02X} for {addr}: {e}");
+                tracing::error!(
+                    "Failed to encode a batch of {} packet(s) for {addr}: {e}",
+                    packets.len()
+                );
                 return;
             }
         };
-
-        let final_payload = if has_encryption {
-            match self.connections.get_mut(&addr) {
-                Some(conn) => match conn.encryption.as_mut() {
-                    Some(enc) => enc.encrypt(&batch),
-                    None => batch,
-                },
-                None => return,
-            }
-        } else {
-            batch
+        let final_payload = match conn.encryption.as_mut() {
+            Some(enc) => enc.encrypt(&batch),
+            None => batch,
         };
 
         let mut out = BytesMut::with_capacity(1 + final_payload.len());
@@ -1181,11 +2118,56 @@ impl ConnectionHandler {
             .await;
     }
 
+    /// What the game loop is doing, for the watchdog.
+    pub fn activity(&self) -> Arc<crate::watchdog::Activity> {
+        Arc::clone(&self.activity)
+    }
+
+    /// When the game loop should run the next tick.
+    pub fn next_tick_deadline(&self) -> Instant {
+        self.tick_clock.deadline()
+    }
+
     /// Get the current game tick number.
     pub fn current_tick(&mut self) -> u64 {
         self.game_world.current_tick()
     }
 
+    /// Render the MOTD template with live values.
+    fn render_motd(&self) -> String {
+        let online = self
+            .connections
+            .values()
+            .filter(|c| c.state == LoginState::InGame)
+            .count();
+        crate::motd::render(
+            &self.server_config.server.motd,
+            &crate::motd::MotdValues {
+                online,
+                max: self.server_config.server.max_players,
+                tps: self.tps.tps(),
+                world: &self.server_config.world.name,
+                version: &self.motd.game_version,
+            },
+        )
+    }
+
+    /// Push the current player count and rendered MOTD to the RakNet server.
+    pub(super) async fn refresh_motd(&mut self) {
+        let online_players = self
+            .connections
+            .values()
+            .filter(|c| c.state == LoginState::InGame)
+            .count() as u32;
+        let server_name = self.render_motd();
+        if server_name == self.motd.server_name && online_players == self.motd.online_players {
+            return;
+        }
+        self.motd.server_name = server_name;
+        self.motd.online_players = online_players;
+        self.server_handle.update_motd(self.motd.clone()).await;
+    }
+
     /// Build server stats for the Query protocol.
     pub fn build_query_stats(&self) -> crate::query::ServerStats {
         let player_names: Vec<String> = self
@@ -1195,7 +2177,7 @@ impl ConnectionHandler {
             .filter_map(|c| c.login_data.as_ref().map(|d| d.display_name.clone()))
             .collect();
         crate::query::ServerStats {
-            motd: self.server_config.server.motd.clone(),
+            motd: self.render_motd(),
             game_type: "SMP".into(),
             map_name: self.server_config.world.name.clone(),
             num_players: player_names.len() as u32,
@@ -1203,6 +2185,8 @@ impl ConnectionHandler {
             host_port: self.server_config.server.port,
             host_ip: self.server_config.server.address.clone(),
             player_names,
+            tps: self.tps.tps(),
+            mspt: self.tps.mspt(),
             version: mc_rs_proto::packets::game_version_for_protocol(
                 mc_rs_proto::packets::PROTOCOL_VERSION,
             )
@@ -1212,6 +2196,12 @@ impl ConnectionHandler {
 
     /// Handle a command from the console or RCON (no associated player connection).
     pub async fn handle_console_command(&mut self, line: &str) -> String {
+        let output = self.run_console_command(line).await;
+        self.flush_packets().await;
+        output
+    }
+
+    async fn run_console_command(&mut self, line: &str) -> String {
         let line = line.strip_prefix('/').unwrap_or(line);
         let mut parts = line.split_whitespace();
         let cmd_name = match parts.next() {
@@ -1265,12 +2255,7 @@ impl ConnectionHandler {
                         "midnight" => 18000,
                         v => v.parse().unwrap_or(0),
                     };
-                    self.world_time = new_time;
-                    let pkt = mc_rs_proto::packets::SetTime {
-                        time: self.world_time as i32,
-                    };
-                    self.broadcast_packet(mc_rs_proto::packets::id::SET_TIME, &pkt)
-                        .await;
+                    self.set_world_time(0, new_time).await;
                     format!("Set time to {new_time}")
                 } else if args[0] == "query" {
                     format!("Current time: {}", self.world_time)
@@ -1278,14 +2263,85 @@ impl ConnectionHandler {
                     "Usage: time <set|query> [value]".into()
                 }
             }
+            "difficulty" => match args.first() {
+                None => format!("Difficulty: {}", self.difficulty.name()),
+                Some(value) => match Difficulty::from_name(value) {
+                    Some(difficulty) => {
+                        self.set_difficulty(difficulty).await;
+                        format!("Set the difficulty to {}", difficulty.name())
+                    }
+                    None => format!("Unknown difficulty: {value}"),
+                },
+            },
             "save-all" => {
-                self.save_all();
+                self.save_all().await;
                 "World saved.".into()
             }
-            "reload" => {
-                self.plugin_manager.reload();
-                info!("Plugins reloaded from console");
-                "Plugins reloaded.".into()
+            "world" => match args.first().map(String::as_str) {
+                Some("repair") => match self.chunk_storage.repair() {
+                    Ok(report) => {
+                        for (cx, cz, dim) in &report.quarantined {
+                            warn!("Quarantined corrupt chunk dim={dim} ({cx},{cz})");
+                        }
+                        format!(
+                            "Checked {} chunks, quarantined {}.",
+                            report.checked,
+                            report.quarantined.len()
+                        )
+                    }
+                    Err(e) => format!("World repair failed: {e}"),
+                },
+                Some("convert") if args.len() >= 2 => {
+                    let target = args[1].as_str();
+                    if target == self.server_config.storage.backend {
+                        return format!("Storage backend is already {target}");
+                    }
+                    if let Err(e) = self.chunk_storage.flush() {
+                        return format!("World convert failed: {e}");
+                    }
+                    let result =
+                        open_chunk_storage(&self.world_dir, &self.server_config.storage, target)
+                            .and_then(|mut to| {
+                                convert_storage(self.chunk_storage.as_mut(), to.as_mut())
+                            });
+                    match result {
+                        Ok(count) => format!(
+                            "Converted {count} chunks to {target}. Set backend = \"{target}\" in [storage] and restart to use it."
+                        ),
+                        Err(e) => format!("World convert failed: {e}"),
+                    }
+                }
+                _ => "Usage: world <repair|convert <leveldb|region>>".into(),
+            },
+            "reload" => self.cmd_reload(&args).await.messages.join("\n"),
+            "restart" => self
+                .cmd_restart(functions::SERVER_SENDER, &args)
+                .await
+                .messages
+                .join("\n"),
+            "scriptevent" => match plugins::parse_script_event(line) {
+                Some(Ok((id, payload))) => {
+                    self.fire_script_event(id.clone(), payload, ScriptEventSource::Server)
+                        .await;
+                    format!("Sent script event {id}")
+                }
+                Some(Err(e)) => e,
+                None => "Usage: scriptevent <namespace:id> [message]".into(),
+            },
+            "function" => {
+                if args.is_empty() {
+                    return "Usage: function <name>".into();
+                }
+                let name = args.join(" ");
+                let result = self
+                    .run_function(
+                        &name,
+                        functions::SERVER_SENDER.to_string(),
+                        functions::SERVER_ADDR,
+                        functions::SERVER_OP_LEVEL,
+                    )
+                    .await;
+                result.messages.join("\n")
             }
             "kick" => {
                 if args.is_empty() {
@@ -1308,11 +2364,19 @@ impl ConnectionHandler {
             }
             "op" => {
                 if args.is_empty() {
-                    return "Usage: op <player>".into();
+                    return "Usage: op <player> [level]".into();
                 }
-                self.permissions.ops.insert(args[0].clone());
+                let level = match args.get(1).map(|l| l.parse::<u8>()) {
+                    None => MAX_OP_LEVEL,
+                    Some(Ok(l)) if (1..=MAX_OP_LEVEL).contains(&l) => l,
+                    Some(_) => return format!("Op level must be 1-{MAX_OP_LEVEL}"),
+                };
+                self.permissions.ops.insert(args[0].clone(), level);
                 self.permissions.save_ops();
-                format!("Opped {}", args[0])
+                if let Some(addr) = self.find_player_addr(&args[0]) {
+                    self.send_abilities(addr).await;
+                }
+                format!("Opped {} (level {level})", args[0])
             }
             "deop" => {
                 if args.is_empty() {
@@ -1320,6 +2384,9 @@ impl ConnectionHandler {
                 }
                 self.permissions.ops.remove(&args[0]);
                 self.permissions.save_ops();
+                if let Some(addr) = self.find_player_addr(&args[0]) {
+                    self.send_abilities(addr).await;
+                }
                 format!("De-opped {}", args[0])
             }
             "whitelist" => {
@@ -1359,12 +2426,50 @@ impl ConnectionHandler {
                     _ => "Usage: whitelist <on|off|add|remove|list>".into(),
                 }
             }
+            "permission" => self.cmd_permission(&args).messages.join("\n"),
+            "timings" => self.cmd_timings(&args).messages.join("\n"),
+            "tps" => self.cmd_tps().messages.join("\n"),
+            "whois" => self.cmd_whois(&args).messages.join("\n"),
+            "mute" => self
+                .cmd_mute(functions::SERVER_ADDR, &args)
+                .await
+                .messages
+                .join("\n"),
+            "unmute" => self.cmd_unmute(&args).await.messages.join("\n"),
+            "slowmode" => self.cmd_slowmode(&args).messages.join("\n"),
+            "balance" => self.cmd_balance(None, &args).messages.join("\n"),
+            "eco" => self.cmd_eco("console", &args).messages.join("\n"),
+            "arena" => self.cmd_arena(None, &args).await.messages.join("\n"),
+            "hologram" => self.cmd_hologram(None, &args).await.messages.join("\n"),
+            "npc" => self.cmd_npc(None, &args).await.messages.join("\n"),
+            "packs" => self.cmd_packs(None, &args).messages.join("\n"),
+            "camera" => self
+                .cmd_camera(functions::SERVER_ADDR, &args)
+                .await
+                .messages
+                .join("\n"),
+            "fog" => self
+                .cmd_fog(functions::SERVER_ADDR, &args)
+                .await
+                .messages
+                .join("\n"),
+            "inputpermission" => self
+                .cmd_inputpermission(functions::SERVER_ADDR, &args)
+                .await
+                .messages
+                .join("\n"),
+            "locate" => self
+                .cmd_locate(functions::SERVER_ADDR, &args)
+                .await
+                .messages
+                .join("\n"),
             _ => format!("Unknown console command: {cmd_name}"),
         }
     }
 
-    /// Save all dirty chunks, online player data, and level.dat to disk.
-    pub fn save_all(&mut self) {
+    /// Save the world one last time as the server stops, disabling plugins
+    /// once it is saved.
+    pub async fn shutdown(&mut self) {
         // Plugin event: ServerStopping (synchronous, actions not applied)
         {
             let snapshot = self.build_snapshot();
@@ -1372,10 +2477,23 @@ impl ConnectionHandler {
                 .plugin_manager
                 .dispatch(&PluginEvent::ServerStopping, &snapshot);
         }
+        self.save_all().await;
         self.plugin_manager.disable_all();
+    }
+
+    /// Save all dirty chunks, online player data, and level.dat to disk.
+    pub async fn save_all(&mut self) {
+        // Plugin event: WorldSave (synchronous, actions not applied)
+        {
+            let snapshot = self.build_snapshot();
+            let (_, _actions) = self
+                .plugin_manager
+                .dispatch(&PluginEvent::WorldSave, &snapshot);
+        }
 
         // Save dirty chunks across all dimensions
         let mut chunk_count = 0usize;
+        let mut saved_chunks: Vec<(i32, i32, i32)> = Vec::new();
         let dim_keys: Vec<i32> = self.world_chunks.keys().copied().collect();
         for dim in &dim_keys {
             let dirty_keys: Vec<(i32, i32)> = self
@@ -1392,7 +2510,7 @@ impl ConnectionHandler {
             chunk_count += dirty_keys.len();
             for key in &dirty_keys {
                 if let Some(col) = self.world_chunks.get(dim).and_then(|m| m.get(key)) {
-                    if let Err(e) = self.chunk_storage.save_chunk(col) {
+                    if let Err(e) = self.chunk_storage.save_chunk_dim(col, *dim) {
                         warn!("Failed to save chunk dim={dim} ({},{}): {e}", key.0, key.1);
                     }
                 }
@@ -1403,54 +2521,71 @@ impl ConnectionHandler {
                     col.dirty = false;
                 }
             }
+            saved_chunks.extend(dirty_keys.iter().map(|&(cx, cz)| (cx, cz, *dim)));
         }
 
         // Save block entities grouped by chunk
-        let mut be_by_chunk: HashMap<(i32, i32), Vec<u8>> = HashMap::new();
-        for (&(bx, by, bz, _dim), be) in &self.block_entities {
-            let cx = bx >> 4;
-            let cz = bz >> 4;
+        let mut be_by_chunk: HashMap<(i32, i32, i32), Vec<u8>> = HashMap::new();
+        for (&(bx, by, bz, dim), be) in &self.block_entities {
             be_by_chunk
-                .entry((cx, cz))
+                .entry((bx >> 4, bz >> 4, dim))
                 .or_default()
                 .extend_from_slice(&be.to_le_nbt(bx, by, bz));
         }
-        for ((cx, cz), data) in &be_by_chunk {
-            let key = block_entity_key(*cx, *cz);
+        for ((cx, cz, dim), data) in &be_by_chunk {
+            let key = block_entity_key_dim(*cx, *cz, *dim);
             if let Err(e) = self.chunk_storage.put_raw(&key, data) {
                 warn!(
-                    "Failed to save block entities for chunk ({},{}): {e}",
+                    "Failed to save block entities for chunk dim={dim} ({},{}): {e}",
                     cx, cz
                 );
             }
         }
+        // Changed chunks whose last block entity was removed
+        for (cx, cz, dim) in saved_chunks {
+            if !be_by_chunk.contains_key(&(cx, cz, dim)) {
+                let key = block_entity_key_dim(cx, cz, dim);
+                if let Err(e) = self.chunk_storage.delete_raw(&key) {
+                    warn!("Failed to delete block entities for chunk dim={dim} ({cx},{cz}): {e}");
+                }
+            }
+        }
+
+        let actor_count = self.save_actors();
+        let map_count = self.save_maps();
+        self.save_structures();
 
         if let Err(e) = self.chunk_storage.flush() {
-            warn!("Failed to flush LevelDB: {e}");
+            warn!("Failed to flush chunk storage: {e}");
         }
 
-        // Save all online players
-        let mut player_count = 0u32;
-        let player_entries: Vec<(SocketAddr, String)> = self
+        // Save all online players at once
+        let player_addrs: Vec<SocketAddr> = self
             .connections
             .iter()
             .filter(|(_, c)| c.state == LoginState::InGame)
-            .filter_map(|(&addr, c)| c.login_data.as_ref().map(|d| (addr, d.identity.clone())))
+            .map(|(&addr, _)| addr)
             .collect();
-
-        for (addr, uuid) in &player_entries {
-            if let Some(conn) = self.connections.get(addr) {
-                let data = PlayerData::from_connection(conn);
-                if let Err(e) = data.save(&self.world_dir, uuid) {
-                    warn!("Failed to save player {uuid}: {e}");
-                } else {
-                    player_count += 1;
-                }
+        for addr in player_addrs {
+            if let Some(save) = self.begin_player_save(addr) {
+                self.pending_saves.spawn(save);
             }
         }
+        let player_count = self
+            .collect_player_saves(Instant::now() + PLAYER_SAVE_DEADLINE)
+            .await;
 
         // Persist weather + time state
         self.level_dat.time = self.world_time;
+        self.level_dat.difficulty = self.difficulty.id();
+        if !self.extra_worlds.is_empty() {
+            let times = self
+                .extra_worlds
+                .iter()
+                .map(|w| (w.name.clone(), w.time))
+                .collect();
+            crate::worlds::save_times(&self.world_dir, &times);
+        }
         self.level_dat.rain_level = self.rain_level;
         self.level_dat.lightning_level = self.lightning_level;
         self.level_dat.rain_time = self.weather_duration;
@@ -1463,7 +2598,37 @@ impl ConnectionHandler {
             warn!("Failed to save level.dat: {e}");
         }
 
-        info!("World saved: {chunk_count} chunks, {player_count} players");
+        info!(
+            "World saved: {chunk_count} chunks, {actor_count} entities, {map_count} maps, {player_count} players"
+        );
+    }
+}
+
+/// Open a world's chunk storage with the given backend (`leveldb`, `region`
+/// or `memory`).
+fn open_chunk_storage(
+    world_dir: &std::path::Path,
+    config: &StorageSection,
+    backend: &str,
+) -> Result<Box<dyn ChunkStorageProvider>, String> {
+    let options = StorageOptions {
+        cache_bytes: config.cache_size_mb * 1024 * 1024,
+        compaction_interval: std::time::Duration::from_secs(config.compaction_interval),
+        verify_checksums: config.verify_checksums,
+    };
+    match backend {
+        "leveldb" => {
+            let path = world_dir.join("db");
+            std::fs::create_dir_all(&path)
+                .map_err(|e| format!("Failed to create world db directory: {e}"))?;
+            Ok(Box::new(LevelDbProvider::open_with(&path, &options)?))
+        }
+        "region" => Ok(Box::new(RegionFileProvider::open_with(
+            &world_dir.join("region"),
+            &options,
+        )?)),
+        "memory" => Ok(Box::new(MemoryProvider::new())),
+        other => Err(format!("Unknown storage backend: {other}")),
     }
 }
 
@@ -1492,18 +2657,13 @@ pub(super) fn effect_name_to_id(name: &str) -> Option<i32> {
         "poison" => Some(effect_id::POISON),
         "wither" => Some(effect_id::WITHER),
         "absorption" => Some(effect_id::ABSORPTION),
+        "slow_falling" => Some(effect_id::SLOW_FALLING),
+        "bad_omen" => Some(effect_id::BAD_OMEN),
+        "hero_of_the_village" => Some(effect_id::HERO_OF_THE_VILLAGE),
         _ => None,
     }
 }
 
-/// Encode a packet struct into a sub-packet: `VarUInt32(id) + proto_encoded fields`.
-pub(super) fn encode_sub_packet(packet_id: u32, packet: &impl ProtoEncode) -> Bytes {
-    let mut buf = BytesMut::new();
-    VarUInt32(packet_id).proto_encode(&mut buf);
-    packet.proto_encode(&mut buf);
-    buf.freeze()
-}
-
 pub(super) fn gamemode_from_str(s: &str) -> i32 {
     match s.to_lowercase().as_str() {
         "survival" => 0,
@@ -1514,16 +2674,6 @@ pub(super) fn gamemode_from_str(s: &str) -> i32 {
     }
 }
 
-pub(super) fn difficulty_from_str(s: &str) -> i32 {
-    match s.to_lowercase().as_str() {
-        "peaceful" => 0,
-        "easy" => 1,
-        "normal" => 2,
-        "hard" => 3,
-        _ => 2,
-    }
-}
-
 pub(super) fn generator_from_str(s: &str) -> i32 {
     match s.to_lowercase().as_str() {
         "legacy" => 0,
@@ -1608,54 +2758,36 @@ pub(super) fn base_attack_damage(registry: &ItemRegistry, runtime_id: i32) -> f3
     }
 }
 
-/// Build default entity metadata for a mob (FLAGS, SCALE, bounding box).
-pub(super) fn default_mob_metadata(bb_width: f32, bb_height: f32) -> Vec<EntityMetadataEntry> {
-    vec![
-        EntityMetadataEntry {
-            key: 0,
-            data_type: 7,
-            value: MetadataValue::Long(0), // FLAGS
-        },
-        EntityMetadataEntry {
-            key: 23,
-            data_type: 3,
-            value: MetadataValue::Float(1.0), // SCALE
-        },
-        EntityMetadataEntry {
-            key: 38,
-            data_type: 3,
-            value: MetadataValue::Float(bb_width), // BB_WIDTH
-        },
-        EntityMetadataEntry {
-            key: 39,
-            data_type: 3,
-            value: MetadataValue::Float(bb_height), // BB_HEIGHT
-        },
-    ]
+/// Build entity metadata for a mob: babies are half size.
+pub(super) fn mob_metadata(
+    bb_width: f32,
+    bb_height: f32,
+    is_baby: bool,
+) -> Vec<EntityMetadataEntry> {
+    MetadataBuilder::new()
+        .flag(EntityFlag::Baby, is_baby)
+        .scale(if is_baby { 0.5 } else { 1.0 })
+        .bounding_box(bb_width, bb_height)
+        .build()
 }
 
-/// Build metadata for a baby mob (half scale, BABY flag bit).
-pub(super) fn baby_mob_metadata(bb_width: f32, bb_height: f32) -> Vec<EntityMetadataEntry> {
-    vec![
-        EntityMetadataEntry {
-            key: 0,
-            data_type: 7,
-            value: MetadataValue::Long(1 << 8), // FLAGS: BABY bit
-        },
-        EntityMetadataEntry {
-            key: 23,
-            data_type: 3,
-            value: MetadataValue::Float(0.5), // SCALE: half size
-        },
-        EntityMetadataEntry {
-            key: 38,
-            data_type: 3,
-            value: MetadataValue::Float(bb_width), // BB_WIDTH
-        },
-        EntityMetadataEntry {
-            key: 39,
-            data_type: 3,
-            value: MetadataValue::Float(bb_height), // BB_HEIGHT
-        },
-    ]
+/// Get the block runtime ID at a world position in one dimension's chunks.
+/// Needs no handler, so block ticks can read the world from other threads.
+pub(super) fn block_in(
+    chunks: &HashMap<(i32, i32), ChunkColumn>,
+    x: i32,
+    y: i32,
+    z: i32,
+) -> Option<u32> {
+    let column = chunks.get(&(x >> 4, z >> 4))?;
+
+    let sub_index = (y - OVERWORLD_MIN_Y) / 16;
+    if sub_index < 0 || sub_index >= OVERWORLD_SUB_CHUNK_COUNT as i32 {
+        return None;
+    }
+    let local_x = (x & 15) as usize;
+    let local_y = ((y - OVERWORLD_MIN_Y) % 16) as usize;
+    let local_z = (z & 15) as usize;
+
+    Some(column.sub_chunks[sub_index as usize].get_block(local_x, local_y, local_z))
 }
@@ -9,6 +9,14 @@ impl ConnectionHandler {
     /// Sprint = ~0.28 b/t; 1.0 gives generous margin for latency.
     const MAX_MOVE_DISTANCE_PER_TICK: f32 = 1.0;
 
+    /// Maximum horizontal distance (blocks) a gliding player can move per tick.
+    /// Firework-boosted elytra flight reaches ~1.7 b/t.
+    const MAX_GLIDE_DISTANCE_PER_TICK: f32 = 4.0;
+
+    /// Maximum horizontal distance (blocks) a spectator can fly per tick,
+    /// at twice the creative flying speed.
+    const MAX_SPECTATOR_DISTANCE_PER_TICK: f32 = 2.0;
+
     /// Minimum allowed Y position (world bottom).
     const MIN_Y_POSITION: f32 = -64.0;
 
@@ -30,10 +38,63 @@ impl ConnectionHandler {
             }
         };
 
-        let (prev_position, entity_runtime_id, gamemode) = match self.connections.get(&addr) {
-            Some(c) => (c.position, c.entity_runtime_id, c.gamemode),
+        // Walking, jumping or looking around is activity, being pushed by
+        // water or mobs isn't
+        let looked_around = self.connections.get(&addr).is_some_and(|c| {
+            (input.yaw - c.yaw).abs() > 1.0 || (input.pitch - c.pitch).abs() > 1.0
+        });
+        if looked_around
+            || input.move_vector.x != 0.0
+            || input.move_vector.z != 0.0
+            || input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::JUMPING)
+        {
+            self.mark_active(addr).await;
+        }
+
+        let (prev_position, entity_runtime_id, gamemode, was_gliding, prev_delta_y) =
+            match self.connections.get(&addr) {
+                Some(c) => (
+                    c.position,
+                    c.entity_runtime_id,
+                    c.gamemode,
+                    c.is_gliding,
+                    c.last_position_delta.y,
+                ),
+                None => return,
+            };
+        let pose = match self.connections.get(&addr) {
+            Some(c) => incoming_pose(c, &input),
             None => return,
         };
+        let player_dim = self
+            .connections
+            .get(&addr)
+            .map(|c| c.dimension)
+            .unwrap_or(0);
+
+        // Water slows down survival and adventure players who were in it and
+        // still are
+        let prev_water = self.player_submersion(player_dim, prev_position, pose);
+        let water = self.player_submersion(player_dim, input.position, pose);
+        let swim_checked =
+            gamemode != 1 && gamemode != 3 && !was_gliding && prev_water.in_water && water.in_water;
+        // Ladders, vines and scaffolding hold up players inside them
+        let climbing =
+            PlayerAabb::with_pose(input.position.x, input.position.y, input.position.z, pose)
+                .intersecting_blocks()
+                .any(|(bx, by, bz)| {
+                    self.get_block_in(player_dim, bx, by, bz)
+                        .and_then(|hash| self.block_registry.get(hash))
+                        .is_some_and(|info| is_climbable(info.name))
+                });
+        // Soul sand and honey hold players back, ice and slime let them
+        // build up speed
+        let surface = self.surface_under(
+            player_dim,
+            input.position.x,
+            input.position.y - PLAYER_EYE_HEIGHT,
+            input.position.z,
+        );
 
         // --- Validation ---
         let mut needs_correction = false;
@@ -53,12 +114,18 @@ impl ConnectionHandler {
             let dz = input.position.z - prev_position.z;
             let horizontal_distance = (dx * dx + dz * dz).sqrt();
 
-            if horizontal_distance > Self::MAX_MOVE_DISTANCE_PER_TICK {
-                debug!("Movement too fast from {addr}: {horizontal_distance:.2} blocks/tick");
-                needs_correction = true;
-                if let Some(conn) = self.connections.get_mut(&addr) {
-                    conn.violations.speed += 1;
-                }
+            let max_distance = if was_gliding {
+                Self::MAX_GLIDE_DISTANCE_PER_TICK
+            } else if gamemode == 3 {
+                Self::MAX_SPECTATOR_DISTANCE_PER_TICK
+            } else if swim_checked {
+                self.max_swim_distance(addr, input.position, horizontal_distance)
+            } else {
+                self.max_move_distance(addr, Self::MAX_MOVE_DISTANCE_PER_TICK, surface)
+            };
+            if horizontal_distance > max_distance {
+                let detail = format!("moved {horizontal_distance:.2} blocks in a tick");
+                needs_correction = self.flag_violation(addr, Check::Speed, &detail).await;
             }
         }
 
@@ -72,39 +139,60 @@ impl ConnectionHandler {
             needs_correction = true;
         }
 
-        // 4. Vertical speed check (terminal velocity)
+        // 4. Vertical speed check (terminal velocity, or water drag outside
+        // bubble columns)
         if !needs_correction {
             let dy = (input.position.y - prev_position.y).abs();
             if dy > MAX_FALL_PER_TICK {
                 debug!("Vertical speed too fast from {addr}: {dy:.2} blocks/tick");
                 needs_correction = true;
+            } else if swim_checked
+                && prev_water.bubble_column.is_none()
+                && water.bubble_column.is_none()
+                && dy > max_swim_vertical(prev_delta_y)
+            {
+                let detail = format!("swam {dy:.2} blocks up or down in a tick");
+                needs_correction = self.flag_violation(addr, Check::Speed, &detail).await;
             }
         }
 
         // 5. No-clip detection (survival/adventure only)
         // Check that the player's AABB does not overlap any solid block.
-        let player_dim = self
-            .connections
-            .get(&addr)
-            .map(|c| c.dimension)
-            .unwrap_or(0);
         if !needs_correction && gamemode != 1 && gamemode != 3 {
             let aabb =
-                PlayerAabb::from_eye_position(input.position.x, input.position.y, input.position.z);
+                PlayerAabb::with_pose(input.position.x, input.position.y, input.position.z, pose);
             for (bx, by, bz) in aabb.intersecting_blocks() {
                 if let Some(hash) = self.get_block_in(player_dim, bx, by, bz) {
                     if self.block_registry.is_solid(hash) {
-                        debug!("No-clip detected at ({bx},{by},{bz}) from {addr}");
-                        needs_correction = true;
-                        if let Some(conn) = self.connections.get_mut(&addr) {
-                            conn.violations.noclip += 1;
-                        }
+                        let detail = format!("inside a solid block at ({bx},{by},{bz})");
+                        needs_correction = self.flag_violation(addr, Check::NoClip, &detail).await;
                         break;
                     }
                 }
             }
         }
 
+        // 6. Sneaking players stay on the block they stand on, if configured
+        if !needs_correction
+            && self.server_config.gameplay.sneak_edge_protection
+            && pose == Pose::Sneaking
+            && gamemode != 3
+            && input.position.y <= prev_position.y
+        {
+            // Unloaded chunks count as ground
+            let stands_on_something = |position: Vec3| {
+                PlayerAabb::from_eye_position(position.x, position.y, position.z)
+                    .blocks_below()
+                    .any(|(bx, by, bz)| {
+                        self.get_block_in(player_dim, bx, by, bz)
+                            .is_none_or(|hash| self.block_registry.is_solid(hash))
+                    })
+            };
+            if stands_on_something(prev_position) && !stands_on_something(input.position) {
+                needs_correction = true;
+            }
+        }
+
         if needs_correction {
             let conn = match self.connections.get(&addr) {
                 Some(c) => c,
@@ -126,7 +214,7 @@ impl ConnectionHandler {
 
         // Plugin event: PlayerMove (cancellable)
         if let Some(conn) = self.connections.get(&addr) {
-            let player = Self::make_plugin_player(conn);
+            let player = self.make_plugin_player(conn);
             let from = (prev_position.x, prev_position.y, prev_position.z);
             let to = (input.position.x, input.position.y, input.position.z);
             let event = PluginEvent::PlayerMove { player, from, to };
@@ -157,40 +245,68 @@ impl ConnectionHandler {
 
         // On-ground detection: check block below player's feet.
         // In Bedrock, position.y is the eye position (1.62 above feet).
-        const PLAYER_EYE_HEIGHT: f32 = 1.62;
         let feet_y = input.position.y - PLAYER_EYE_HEIGHT;
         let check_y = (feet_y - 0.01).floor() as i32;
         let check_x = input.position.x.floor() as i32;
         let check_z = input.position.z.floor() as i32;
+        // Scaffolding can be stood on, unless sneaking to climb down it
+        let sneaking =
+            input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::SNEAKING);
         let on_ground = self
             .get_block_in(player_dim, check_x, check_y, check_z)
-            .map(|hash| self.block_registry.is_solid(hash))
+            .map(|hash| {
+                self.block_registry.is_solid(hash)
+                    || (!sneaking
+                        && self
+                            .block_registry
+                            .get(hash)
+                            .is_some_and(|info| info.name == "minecraft:scaffolding"))
+            })
             .unwrap_or(true); // Default true for unloaded chunks
 
-        // Anti-fly: track consecutive airborne ticks (survival only)
-        let anti_fly_correction = if gamemode == 0 && !on_ground {
-            let ticks = self
-                .connections
-                .get(&addr)
-                .map(|c| c.airborne_ticks)
-                .unwrap_or(0);
-            let dy = input.position.y - prev_position.y;
-            // If airborne too long AND not falling → fly hack
-            ticks + 1 > MAX_AIRBORNE_TICKS && dy >= 0.0
-        } else {
-            false
-        };
+        // Anti-fly: track consecutive airborne ticks (survival only). Water
+        // and climbable blocks hold players up, so swimming or climbing isn't
+        // flying.
+        let anti_fly_correction =
+            if gamemode == 0 && !on_ground && !was_gliding && !water.in_water && !climbing {
+                let ticks = self
+                    .connections
+                    .get(&addr)
+                    .map(|c| c.airborne_ticks)
+                    .unwrap_or(0);
+                let dy = input.position.y - prev_position.y;
+                // If airborne too long AND not falling → fly hack
+                ticks + 1 > MAX_AIRBORNE_TICKS && dy >= 0.0
+            } else {
+                false
+            };
+
+        // Elytra: gliding needs a usable elytra in the chest slot
+        let can_glide = gamemode != 3 && self.has_usable_elytra(addr);
+        let food_level = self.hunger(addr).food;
 
         if let Some(conn) = self.connections.get_mut(&addr) {
+            if on_ground && !conn.is_gliding && gamemode != 3 {
+                conn.stats.record_walk(
+                    input.position.x - conn.position.x,
+                    input.position.z - conn.position.z,
+                );
+            }
             conn.position = input.position;
             conn.pitch = input.pitch;
             conn.yaw = input.yaw;
             conn.head_yaw = input.head_yaw;
             conn.client_tick = input.tick;
             conn.on_ground = on_ground;
-            conn.last_position_delta_y = input.position_delta.y;
-            conn.is_sprinting =
-                input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::SPRINTING);
+            if on_ground {
+                conn.last_surface = surface;
+            }
+            conn.last_position_delta = input.position_delta;
+            // Survival players cannot sprint on a low hunger bar
+            conn.is_sprinting = input
+                .has_flag(mc_rs_proto::packets::player_auth_input::input_flags::SPRINTING)
+                && (gamemode != 0 || mc_rs_game::food::can_sprint(food_level));
+            conn.is_sneaking = sneaking;
             // Swimming tracking
             if input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::START_SWIMMING)
             {
@@ -199,14 +315,31 @@ impl ConnectionHandler {
             if input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::STOP_SWIMMING) {
                 conn.is_swimming = false;
             }
-            if on_ground {
+            // Crawling tracking
+            if input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::START_CRAWLING)
+            {
+                conn.is_crawling = true;
+            }
+            if input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::STOP_CRAWLING) {
+                conn.is_crawling = false;
+            }
+            if input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::START_GLIDING)
+                && can_glide
+                && !on_ground
+            {
+                conn.is_gliding = true;
+            }
+            if input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::STOP_GLIDING)
+                || on_ground
+                || conn.is_swimming
+                || !can_glide
+            {
+                conn.is_gliding = false;
+            }
+            if on_ground || water.in_water || climbing {
                 conn.airborne_ticks = 0;
             } else {
                 conn.airborne_ticks = conn.airborne_ticks.saturating_add(1);
-                // Force fly violation if airborne far too long (10s)
-                if conn.airborne_ticks > MAX_AIRBORNE_KICK && gamemode == 0 {
-                    conn.violations.fly += 1;
-                }
             }
 
             // Sync position to ECS mirror entity
@@ -218,12 +351,31 @@ impl ConnectionHandler {
                 input.position.z,
             );
         }
+        self.record_entity_position(
+            entity_runtime_id,
+            (input.position.x, feet_y, input.position.z),
+        );
 
-        if anti_fly_correction {
-            debug!("Anti-fly: {addr} airborne too long without falling (gamemode=survival)");
-            if let Some(conn) = self.connections.get_mut(&addr) {
-                conn.violations.fly += 1;
-            }
+        // Force a fly violation if airborne far too long (10s), even while falling
+        let airborne_ticks = self
+            .connections
+            .get(&addr)
+            .map(|c| c.airborne_ticks)
+            .unwrap_or(0);
+        if airborne_ticks > MAX_AIRBORNE_KICK && gamemode == 0 && !anti_fly_correction {
+            let detail = format!("airborne for {airborne_ticks} ticks");
+            self.flag_violation(addr, Check::Fly, &detail).await;
+        }
+
+        if anti_fly_correction
+            && self
+                .flag_violation(
+                    addr,
+                    Check::Fly,
+                    "airborne too long without falling (survival)",
+                )
+                .await
+        {
             let conn = match self.connections.get(&addr) {
                 Some(c) => c,
                 None => return,
@@ -242,35 +394,73 @@ impl ConnectionHandler {
             return;
         }
 
-        // --- Broadcast position to other players in same dimension ---
-        let move_pkt = MovePlayer::normal(
-            entity_runtime_id,
-            input.position,
-            input.pitch,
-            input.yaw,
-            input.head_yaw,
-            on_ground,
-            input.tick,
-        );
-        let player_dim = self
-            .connections
-            .get(&addr)
-            .map(|c| c.dimension)
-            .unwrap_or(0);
-        self.broadcast_packet_in_dimension_except(
-            player_dim,
-            addr,
-            packets::id::MOVE_PLAYER,
-            &move_pkt,
-        )
-        .await;
+        // --- Broadcast position to other players in same dimension, if
+        // it changed enough to be seen ---
+        if let Some((player_dim, move_pkt)) =
+            self.player_move_update(addr, entity_runtime_id, &input, on_ground)
+        {
+            self.broadcast_packet_in_dimension_except(
+                player_dim,
+                addr,
+                packets::id::MOVE_PLAYER,
+                &move_pkt,
+            )
+            .await;
+        }
 
         // --- Fall distance tracking + fall damage (survival only) ---
         if gamemode == 0 {
-            if !on_ground && input.position_delta.y < 0.0 {
-                if let Some(conn) = self.connections.get_mut(&addr) {
+            use mc_rs_game::fall::{self, FallModifiers, LandingSurface};
+            use mc_rs_proto::packets::mob_effect::effect_id;
+
+            let effects = self.effects(addr);
+            let modifiers = match self.connections.get(&addr) {
+                Some(c) => FallModifiers {
+                    jump_boost: effects
+                        .iter()
+                        .find(|e| e.effect_id == effect_id::JUMP_BOOST)
+                        .map(|e| e.amplifier),
+                    slow_falling: mc_rs_game::effects::has(effects, effect_id::SLOW_FALLING),
+                    // Feather Falling reduction from boots (armor slot 3)
+                    feather_falling: game_combat::feather_falling_reduction(
+                        &c.inventory.armor[3].nbt_data,
+                    ),
+                    sneaking,
+                },
+                None => return,
+            };
+
+            // Water and cobwebs at the feet cancel any fall in progress
+            let feet_block = self.get_block_in(player_dim, check_x, feet_y.floor() as i32, check_z);
+            let surface = match feet_block {
+                Some(rid) if self.tick_blocks.is_water(rid) => LandingSurface::Water,
+                Some(rid) if self.tick_blocks.is_cobweb(rid) => LandingSurface::Cobweb,
+                _ if climbing => LandingSurface::Climbable,
+                _ => match self.get_block_in(player_dim, check_x, check_y, check_z) {
+                    Some(rid) if self.tick_blocks.is_hay_block(rid) => LandingSurface::HayBale,
+                    Some(rid) if self.tick_blocks.is_slime(rid) => LandingSurface::Slime,
+                    _ => LandingSurface::Solid,
+                },
+            };
+
+            if let Some(conn) = self.connections.get_mut(&addr) {
+                if !on_ground && input.position_delta.y < 0.0 {
                     conn.fall_distance += (-input.position_delta.y).abs();
                 }
+                if was_gliding {
+                    conn.fall_distance = mc_rs_game::armor::glide_fall_distance(
+                        conn.fall_distance,
+                        input.position_delta.y,
+                    );
+                }
+                if modifiers.slow_falling
+                    || matches!(
+                        surface,
+                        LandingSurface::Water | LandingSurface::Cobweb | LandingSurface::Climbable
+                    )
+                {
+                    conn.fall_distance = 0.0;
+                }
             }
             if on_ground {
                 let fall_dist = self
@@ -278,29 +468,44 @@ impl ConnectionHandler {
                     .get(&addr)
                     .map(|c| c.fall_distance)
                     .unwrap_or(0.0);
-                if fall_dist > 3.0 {
-                    let mut damage = (fall_dist - 3.0).ceil();
-                    // Feather Falling reduction from boots (armor slot 3)
-                    let ff_reduction = self
-                        .connections
-                        .get(&addr)
-                        .map(|c| {
-                            game_combat::feather_falling_reduction(&c.inventory.armor[3].nbt_data)
-                        })
-                        .unwrap_or(0.0);
-                    if ff_reduction > 0.0 {
-                        damage *= 1.0 - ff_reduction;
+
+                // Jumping or falling onto farmland tramples it
+                if fall_dist > 0.0 {
+                    self.trample_farmland(BlockPos::new(check_x, check_y, check_z), fall_dist)
+                        .await;
+                }
+
+                // Slime blocks bounce the player back up with their impact speed
+                if surface == LandingSurface::Slime && fall_dist > 0.0 {
+                    let impact = (-input.position_delta.y).max(-prev_delta_y);
+                    if let Some(bounce) = fall::slime_bounce(impact, sneaking) {
+                        self.send_packet(
+                            addr,
+                            packets::id::SET_ENTITY_MOTION,
+                            &SetEntityMotion {
+                                entity_runtime_id,
+                                motion: Vec3::new(0.0, bounce, 0.0),
+                            },
+                        )
+                        .await;
                     }
+                }
+
+                let damage = if self.damage_allowed(addr, DamageCause::Fall, false) {
+                    fall::fall_damage(fall_dist, surface, &modifiers)
+                } else {
+                    0.0
+                };
+                if damage > 0.0 {
                     let conn = match self.connections.get_mut(&addr) {
                         Some(c) => c,
                         None => return,
                     };
-                    conn.health = (conn.health - damage).max(0.0);
-                    conn.exhaustion += 0.0; // fall damage doesn't cause exhaustion
+                    // Fall damage doesn't cause exhaustion
                     conn.fall_distance = 0.0;
                     let rid = conn.entity_runtime_id;
-                    let hp = conn.health;
                     let tick = conn.client_tick;
+                    let hp = self.change_health(addr, -damage);
 
                     self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
                         .await;
@@ -331,24 +536,26 @@ impl ConnectionHandler {
             }
 
             // --- Exhaustion accumulation ---
-            if let Some(conn) = self.connections.get_mut(&addr) {
+            if let Some(conn) = self.connections.get(&addr) {
                 let hdist = (input.position_delta.x * input.position_delta.x
                     + input.position_delta.z * input.position_delta.z)
                     .sqrt();
 
+                let mut exhaustion = 0.0;
                 if conn.is_sprinting && hdist > 0.0 {
-                    conn.exhaustion += hdist * 0.1;
+                    exhaustion += hdist * 0.1;
                 } else if conn.is_swimming && hdist > 0.0 {
-                    conn.exhaustion += hdist * 0.01;
+                    exhaustion += hdist * 0.01;
                 }
 
                 if input.has_flag(mc_rs_proto::packets::player_auth_input::input_flags::JUMPING) {
                     if conn.is_sprinting {
-                        conn.exhaustion += 0.2;
+                        exhaustion += 0.2;
                     } else {
-                        conn.exhaustion += 0.05;
+                        exhaustion += 0.05;
                     }
                 }
+                self.add_exhaustion(addr, exhaustion);
             }
         }
 
@@ -364,10 +571,103 @@ impl ConnectionHandler {
 
         if prev_chunk != curr_chunk {
             self.send_new_chunks(addr).await;
-            self.cleanup_sent_chunks(addr);
+            self.cleanup_sent_chunks(addr).await;
         }
 
         // --- Portal detection ---
         self.check_portal_at_player(addr).await;
     }
+
+    /// The MovePlayer to broadcast for a player's new input and the
+    /// dimension to broadcast it in, or `None` if the player didn't move or
+    /// turn enough since the last one for anyone to see.
+    fn player_move_update(
+        &mut self,
+        addr: SocketAddr,
+        runtime_id: u64,
+        input: &PlayerAuthInput,
+        on_ground: bool,
+    ) -> Option<(i32, MovePlayer)> {
+        let conn = self.connections.get_mut(&addr)?;
+        let next = SentMove {
+            position: (input.position.x, input.position.y, input.position.z),
+            pitch: input.pitch,
+            yaw: input.yaw,
+            head_yaw: input.head_yaw,
+            on_ground,
+        };
+        let pkt = match move_sync::classify(conn.last_sent_move.as_ref(), &next) {
+            MoveUpdate::Skip => return None,
+            MoveUpdate::Rotation => {
+                // Keep the position the others last saw
+                let last = conn.last_sent_move.as_mut()?;
+                let (x, y, z) = last.position;
+                last.pitch = next.pitch;
+                last.yaw = next.yaw;
+                last.head_yaw = next.head_yaw;
+                MovePlayer::rotation(
+                    runtime_id,
+                    Vec3::new(x, y, z),
+                    next.pitch,
+                    next.yaw,
+                    next.head_yaw,
+                    last.on_ground,
+                    input.tick,
+                )
+            }
+            MoveUpdate::Interpolate => {
+                conn.last_sent_move = Some(next);
+                MovePlayer::normal(
+                    runtime_id,
+                    input.position,
+                    input.pitch,
+                    input.yaw,
+                    input.head_yaw,
+                    on_ground,
+                    input.tick,
+                )
+            }
+            MoveUpdate::Teleport => {
+                conn.last_sent_move = Some(next);
+                MovePlayer::teleport(
+                    runtime_id,
+                    input.position,
+                    input.pitch,
+                    input.yaw,
+                    input.head_yaw,
+                    on_ground,
+                    input.tick,
+                )
+            }
+        };
+        Some((conn.dimension, pkt))
+    }
+}
+
+/// The pose a PlayerAuthInput puts a player in, before the move is accepted.
+fn incoming_pose(conn: &PlayerConnection, input: &PlayerAuthInput) -> Pose {
+    use mc_rs_proto::packets::player_auth_input::input_flags;
+    let toggle = |start, stop, current| {
+        if input.has_flag(start) {
+            true
+        } else if input.has_flag(stop) {
+            false
+        } else {
+            current
+        }
+    };
+    Pose::from_state(
+        input.has_flag(input_flags::SNEAKING),
+        toggle(
+            input_flags::START_SWIMMING,
+            input_flags::STOP_SWIMMING,
+            conn.is_swimming,
+        ),
+        toggle(
+            input_flags::START_CRAWLING,
+            input_flags::STOP_CRAWLING,
+            conn.is_crawling,
+        ),
+        conn.is_gliding,
+    )
 }
@@ -0,0 +1,236 @@
+//! Note blocks and jukeboxes: tuning and playing notes, playing records,
+//! and the redstone power of a playing jukebox.
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+
+use mc_rs_game::block_entity::BlockEntityData;
+use mc_rs_game::music::{self, Instrument, NOTE_PITCHES};
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::packets::level_sound_event::{SOUND_NOTE, SOUND_RECORD_13, SOUND_STOP_RECORD};
+use mc_rs_proto::packets::{self, InventorySlot, LevelSoundEvent};
+use mc_rs_proto::types::BlockPos;
+use mc_rs_world::block_hash::TickBlocks;
+use tracing::debug;
+
+use super::ConnectionHandler;
+
+impl ConnectionHandler {
+    /// Right click on a note block: raise its pitch by a semitone and play it.
+    pub(super) async fn tune_note_block(&mut self, pos: BlockPos) {
+        let key = (pos.x, pos.y, pos.z, 0);
+        let entry = self
+            .block_entities
+            .entry(key)
+            .or_insert_with(BlockEntityData::new_note_block);
+        if let BlockEntityData::Music { note, .. } = entry {
+            *note = (*note + 1) % NOTE_PITCHES;
+        }
+        self.play_note_block(pos).await;
+    }
+
+    /// Left click on a block: a note block plays its note.
+    pub(super) async fn punch_note_block(&mut self, pos: BlockPos) {
+        if self
+            .get_block(pos.x, pos.y, pos.z)
+            .is_some_and(|rid| self.block_entity_hashes.is_noteblock(rid))
+        {
+            self.play_note_block(pos).await;
+        }
+    }
+
+    /// Play a note block's note, with the instrument of the block below it.
+    /// Nothing plays if the block above isn't air.
+    async fn play_note_block(&mut self, pos: BlockPos) {
+        if self.get_block(pos.x, pos.y + 1, pos.z) != Some(self.flat_world_blocks.air) {
+            return;
+        }
+        let note = match self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::Music { note, .. }) => *note,
+            _ => 0,
+        };
+        let instrument = self
+            .get_block(pos.x, pos.y - 1, pos.z)
+            .and_then(|rid| self.block_registry.get(rid))
+            .map(|info| Instrument::for_block_below(info.name))
+            .unwrap_or(Instrument::Harp);
+        self.broadcast_packet(
+            packets::id::LEVEL_SOUND_EVENT,
+            &LevelSoundEvent::at_block(
+                SOUND_NOTE,
+                pos.x,
+                pos.y,
+                pos.z,
+                music::note_sound_data(instrument, note),
+            ),
+        )
+        .await;
+    }
+
+    /// Play the note blocks that just got powered.
+    pub(super) async fn tick_note_blocks(&mut self) {
+        let positions: Vec<(i32, i32, i32)> = self
+            .block_entities
+            .iter()
+            .filter(|(&(_, _, _, dim), be)| dim == 0 && matches!(be, BlockEntityData::Music { .. }))
+            .map(|(&(x, y, z, _), _)| (x, y, z))
+            .collect();
+        for (x, y, z) in positions {
+            let now_powered = self.block_powered(x, y, z);
+            let rising = match self.block_entities.get_mut(&(x, y, z, 0)) {
+                Some(BlockEntityData::Music { powered, .. }) => {
+                    let rising = now_powered && !*powered;
+                    *powered = now_powered;
+                    rising
+                }
+                _ => false,
+            };
+            if rising {
+                self.play_note_block(BlockPos::new(x, y, z)).await;
+            }
+        }
+    }
+
+    /// Right click on a jukebox: eject its record, or put in the held disc.
+    pub(super) async fn use_jukebox(&mut self, addr: SocketAddr, pos: BlockPos) {
+        let key = (pos.x, pos.y, pos.z, 0);
+        let has_record = matches!(
+            self.block_entities.get(&key),
+            Some(BlockEntityData::Jukebox { record, .. }) if !record.is_empty()
+        );
+        if has_record {
+            self.eject_record(pos, 0).await;
+            return;
+        }
+
+        let Some(conn) = self.connections.get(&addr) else {
+            return;
+        };
+        let held = conn.inventory.held_item().clone();
+        let Some(disc) = self
+            .item_registry
+            .get_by_id(held.runtime_id as i16)
+            .and_then(|info| music::music_disc(&info.name))
+        else {
+            return;
+        };
+        let creative = conn.gamemode == 1;
+
+        let mut record = held.clone();
+        record.count = 1;
+        self.block_entities.insert(
+            key,
+            BlockEntityData::Jukebox {
+                record,
+                ticks_left: disc.ticks(),
+            },
+        );
+        debug!("Jukebox at {pos} playing {}", disc.item);
+        self.broadcast_packet(
+            packets::id::LEVEL_SOUND_EVENT,
+            &LevelSoundEvent::at_block(SOUND_RECORD_13 + disc.record, pos.x, pos.y, pos.z, -1),
+        )
+        .await;
+        self.update_redstone_from(pos.x, pos.y, pos.z).await;
+
+        if creative {
+            return;
+        }
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        let item = conn.inventory.held_item_mut();
+        item.count = item.count.saturating_sub(1);
+        if item.count == 0 {
+            *item = ItemStack::empty();
+        }
+        let updated_item = conn.inventory.held_item().clone();
+        let slot = conn.inventory.held_slot;
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_SLOT,
+            &InventorySlot {
+                window_id: 0,
+                slot: slot as u32,
+                item: updated_item,
+            },
+        )
+        .await;
+    }
+
+    /// Stop a jukebox's record and drop it on top of the jukebox.
+    pub(super) async fn eject_record(&mut self, pos: BlockPos, dimension: i32) {
+        let record = match self
+            .block_entities
+            .get_mut(&(pos.x, pos.y, pos.z, dimension))
+        {
+            Some(BlockEntityData::Jukebox { record, ticks_left }) if !record.is_empty() => {
+                *ticks_left = 0;
+                std::mem::replace(record, ItemStack::empty())
+            }
+            _ => return,
+        };
+        self.drop_record(record, pos, dimension).await;
+    }
+
+    /// Stop the record of a broken jukebox and drop it.
+    pub(super) async fn drop_record(&mut self, record: ItemStack, pos: BlockPos, dimension: i32) {
+        if record.is_empty() {
+            return;
+        }
+        self.broadcast_packet(
+            packets::id::LEVEL_SOUND_EVENT,
+            &LevelSoundEvent::at_block(SOUND_STOP_RECORD, pos.x, pos.y, pos.z, -1),
+        )
+        .await;
+        let position = (pos.x as f32 + 0.5, pos.y as f32 + 1.0, pos.z as f32 + 0.5);
+        self.spawn_dropped_item(record, position, dimension).await;
+        self.update_redstone_from(pos.x, pos.y, pos.z).await;
+    }
+
+    /// Count down the records playing; a jukebox stops powering redstone
+    /// when its record ends.
+    pub(super) async fn tick_jukeboxes(&mut self) {
+        let mut ended = Vec::new();
+        for (&(x, y, z, dim), be) in self.block_entities.iter_mut() {
+            if let BlockEntityData::Jukebox { ticks_left, .. } = be {
+                if *ticks_left > 0 {
+                    *ticks_left -= 1;
+                    if *ticks_left == 0 && dim == 0 {
+                        ended.push((x, y, z));
+                    }
+                }
+            }
+        }
+        for (x, y, z) in ended {
+            self.update_redstone_from(x, y, z).await;
+        }
+    }
+
+    /// Positions of the jukeboxes playing a record in the overworld.
+    pub(super) fn playing_jukeboxes(&self) -> HashSet<(i32, i32, i32)> {
+        self.block_entities
+            .iter()
+            .filter(|(&(_, _, _, dim), be)| {
+                dim == 0
+                    && matches!(be, BlockEntityData::Jukebox { ticks_left, .. } if *ticks_left > 0)
+            })
+            .map(|(&(x, y, z, _), _)| (x, y, z))
+            .collect()
+    }
+}
+
+/// A block as redstone sees it: a playing jukebox powers what's around it
+/// like a block of redstone.
+pub(super) fn redstone_view(
+    playing: &HashSet<(i32, i32, i32)>,
+    tick_blocks: &TickBlocks,
+    pos: (i32, i32, i32),
+    block: Option<u32>,
+) -> Option<u32> {
+    if playing.contains(&pos) {
+        Some(tick_blocks.redstone_block)
+    } else {
+        block
+    }
+}
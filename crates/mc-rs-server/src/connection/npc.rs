@@ -0,0 +1,596 @@
+//! Showing NPCs to the players who have their chunk loaded, running their
+//! actions when clicked, and `/npc`.
+
+use mc_rs_plugin_api::{parse_form_response, FormResponse, SimpleFormBuilder};
+
+use super::*;
+use crate::npcs::{fill_player, DialogueButton, Npc, NpcAction, NpcSkin, PLAYER_NPC_TYPE};
+
+/// Form ID of NPC dialogues; plugins should not use it for their forms.
+const NPC_DIALOGUE_FORM_ID: u32 = u32::MAX;
+
+/// `pending_forms` type of an NPC dialogue, followed by the NPC's name.
+const NPC_DIALOGUE_FORM_TYPE: &str = "npc:";
+
+/// Players' positions are at eye level, NPCs stand on their feet.
+const EYE_HEIGHT: f32 = 1.62;
+
+/// Upper half of the UUIDs of player-shaped NPCs; the lower half is the
+/// entity ID.
+const NPC_UUID_HIGH: u64 = 0x6e70_6300_0000_0000;
+
+impl ConnectionHandler {
+    /// The entity ID of an NPC, given one the first time it is shown.
+    fn npc_entity_id(&mut self, name: &str) -> Option<i64> {
+        if let Some(id) = self.npcs.get(name)?.entity_id {
+            return Some(id);
+        }
+        let id = self.allocate_entity_id();
+        self.npcs.get_mut(name)?.entity_id = Some(id);
+        Some(id)
+    }
+
+    /// Send an NPC to one player. Player-shaped NPCs need a player list
+    /// entry for their skin, taken out again right away so they stay off
+    /// the tab list.
+    async fn send_npc(&mut self, addr: SocketAddr, name: &str) {
+        let Some(id) = self.npc_entity_id(name) else {
+            return;
+        };
+        let Some(npc) = self.npcs.get(name) else {
+            return;
+        };
+        let [x, y, z] = npc.position;
+        let metadata = MetadataBuilder::new()
+            .flag(EntityFlag::CanShowNametag, true)
+            .flag(EntityFlag::AlwaysShowNametag, true)
+            .flag(EntityFlag::NoAi, true)
+            .scale(1.0)
+            .bounding_box(0.6, 1.8)
+            .nametag(npc.display_name.clone())
+            .build();
+
+        if !npc.is_player_shaped() {
+            let pkt = AddActor {
+                entity_unique_id: id,
+                entity_runtime_id: id as u64,
+                entity_type: npc.entity_type.clone(),
+                position: Vec3::new(x, y, z),
+                velocity: Vec3::ZERO,
+                pitch: npc.pitch,
+                yaw: npc.yaw,
+                head_yaw: npc.yaw,
+                body_yaw: npc.yaw,
+                attributes: vec![],
+                metadata,
+            };
+            self.send_packet(addr, packets::id::ADD_ACTOR, &pkt).await;
+            return;
+        }
+
+        let uuid = Uuid::new(NPC_UUID_HIGH, id as u64);
+        let skin_data = npc
+            .skin
+            .as_ref()
+            .map(NpcSkin::to_client_data)
+            .unwrap_or_default();
+        let list_add = PlayerListAddPacket {
+            entries: vec![PlayerListAdd {
+                uuid,
+                entity_unique_id: id,
+                username: npc.display_name.clone(),
+                xuid: String::new(),
+                platform_chat_id: String::new(),
+                device_os: 0,
+                skin_data,
+                is_teacher: false,
+                is_host: false,
+                is_sub_client: false,
+            }],
+        };
+        let add_player = AddPlayer {
+            uuid,
+            username: npc.display_name.clone(),
+            entity_runtime_id: id as u64,
+            platform_chat_id: String::new(),
+            position: Vec3::new(x, y + EYE_HEIGHT, z),
+            velocity: Vec3::ZERO,
+            pitch: npc.pitch,
+            yaw: npc.yaw,
+            head_yaw: npc.yaw,
+            held_item: mc_rs_proto::item_stack::ItemStack::empty(),
+            gamemode: 0,
+            metadata,
+            entity_unique_id: id,
+            permission_level: 1,
+            command_permission_level: 0,
+            device_id: String::new(),
+            device_os: 0,
+        };
+        self.send_packet(addr, packets::id::PLAYER_LIST, &list_add)
+            .await;
+        self.send_packet(addr, packets::id::ADD_PLAYER, &add_player)
+            .await;
+        self.send_packet(
+            addr,
+            packets::id::PLAYER_LIST,
+            &PlayerListRemove { uuids: vec![uuid] },
+        )
+        .await;
+    }
+
+    /// Players who have an NPC's chunk loaded.
+    fn npc_viewers(&self, npc: &Npc) -> Vec<SocketAddr> {
+        let chunk = npc.chunk();
+        self.connections
+            .iter()
+            .filter(|(_, c)| c.dimension == npc.dimension && c.sent_chunks.contains(&chunk))
+            .map(|(&addr, _)| addr)
+            .collect()
+    }
+
+    /// Send an NPC to everyone who has its chunk loaded.
+    async fn show_npc(&mut self, name: &str) {
+        let Some(npc) = self.npcs.get(name) else {
+            return;
+        };
+        for addr in self.npc_viewers(npc) {
+            self.send_npc(addr, name).await;
+        }
+    }
+
+    /// Remove an NPC's entity from every client.
+    async fn hide_npc(&mut self, name: &str) {
+        let Some(id) = self.npcs.get(name).and_then(|n| n.entity_id) else {
+            return;
+        };
+        let pkt = RemoveEntity {
+            entity_unique_id: id,
+        };
+        self.broadcast_packet(packets::id::REMOVE_ENTITY, &pkt)
+            .await;
+    }
+
+    /// Send a player the NPCs of a chunk that was just sent to them.
+    pub(super) async fn spawn_npcs_in_chunk(
+        &mut self,
+        addr: SocketAddr,
+        dimension: i32,
+        chunk: (i32, i32),
+    ) {
+        for name in self.npcs.in_chunk(dimension, chunk) {
+            self.send_npc(addr, &name).await;
+        }
+    }
+
+    /// Remove the NPCs of chunks a player no longer has loaded.
+    pub(super) async fn despawn_npcs_in_chunks(
+        &mut self,
+        addr: SocketAddr,
+        dimension: i32,
+        chunks: &[(i32, i32)],
+    ) {
+        let ids: Vec<i64> = chunks
+            .iter()
+            .flat_map(|&chunk| self.npcs.in_chunk(dimension, chunk))
+            .filter_map(|name| self.npcs.get(&name)?.entity_id)
+            .collect();
+        for id in ids {
+            let pkt = RemoveEntity {
+                entity_unique_id: id,
+            };
+            self.send_packet(addr, packets::id::REMOVE_ENTITY, &pkt)
+                .await;
+        }
+    }
+
+    /// Add or replace an NPC and show it.
+    async fn put_npc(&mut self, npc: Npc) {
+        let name = npc.name.clone();
+        self.hide_npc(&name).await;
+        self.npcs.insert(npc);
+        self.show_npc(&name).await;
+    }
+
+    /// Change an NPC, save it and show it again. Returns `None` if there is
+    /// no NPC with that name.
+    async fn edit_npc<T>(&mut self, name: &str, edit: impl FnOnce(&mut Npc) -> T) -> Option<T> {
+        self.npcs.get(name)?;
+        self.hide_npc(name).await;
+        let result = edit(self.npcs.get_mut(name)?);
+        self.npcs.save();
+        self.show_npc(name).await;
+        Some(result)
+    }
+
+    /// Run the actions of an NPC clicked by a player. Returns whether
+    /// `runtime_id` is an NPC.
+    pub(super) async fn interact_npc(&mut self, addr: SocketAddr, runtime_id: u64) -> bool {
+        let Some(npc) = self.npcs.by_entity(runtime_id).cloned() else {
+            return false;
+        };
+        let now = self.game_world.current_tick();
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return true;
+        };
+        // A click often arrives as several packets: act on the first one
+        if now.saturating_sub(conn.last_command_tick) < MIN_COMMAND_INTERVAL {
+            return true;
+        }
+        conn.last_command_tick = now;
+        let Some(player) = conn.login_data.as_ref().map(|l| l.display_name.clone()) else {
+            return true;
+        };
+
+        for action in &npc.actions {
+            match action {
+                NpcAction::Command { command } => {
+                    self.run_npc_command(addr, &player, command).await;
+                }
+                NpcAction::Console { command } => {
+                    let line = fill_player(command, &player);
+                    let line = line.trim().trim_start_matches('/');
+                    let result = self
+                        .dispatch_command(
+                            functions::SERVER_ADDR,
+                            functions::SERVER_SENDER,
+                            line,
+                            functions::AUTOMATED_OP_LEVEL,
+                        )
+                        .await;
+                    self.apply_command_effects(functions::SERVER_SENDER, &result)
+                        .await;
+                    if !result.success {
+                        debug!(
+                            "Command of NPC {} failed: {line}: {}",
+                            npc.name,
+                            result.messages.join(" ")
+                        );
+                    }
+                }
+                NpcAction::Message { text } => {
+                    let text = Text::raw(fill_player(text, &player));
+                    self.send_packet(addr, packets::id::TEXT, &text).await;
+                }
+                NpcAction::Dialogue { text, buttons } => {
+                    self.open_npc_dialogue(addr, &player, &npc, text, buttons)
+                        .await;
+                }
+                NpcAction::Event { id } => {
+                    let Some(conn) = self.connections.get(&addr) else {
+                        continue;
+                    };
+                    let event = PluginEvent::NpcInteract {
+                        player: self.make_plugin_player(conn),
+                        npc: npc.name.clone(),
+                        id: id.clone(),
+                    };
+                    let snapshot = self.build_snapshot();
+                    let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+                    self.apply_plugin_actions(actions).await;
+                }
+            }
+        }
+        true
+    }
+
+    /// Run a command of an NPC as the player who clicked it, with their
+    /// permissions.
+    async fn run_npc_command(&mut self, addr: SocketAddr, player: &str, command: &str) {
+        let line = fill_player(command, player);
+        let line = line.trim().trim_start_matches('/');
+        let op_level = self.permissions.op_level(player);
+        let result = self.dispatch_command(addr, player, line, op_level).await;
+        for msg in &result.messages {
+            self.send_packet(addr, packets::id::TEXT, &Text::raw(msg))
+                .await;
+        }
+        self.apply_command_effects(player, &result).await;
+    }
+
+    async fn open_npc_dialogue(
+        &mut self,
+        addr: SocketAddr,
+        player: &str,
+        npc: &Npc,
+        text: &str,
+        buttons: &[DialogueButton],
+    ) {
+        let form = buttons.iter().fold(
+            SimpleFormBuilder::new(&npc.display_name, &fill_player(text, player)),
+            |form, b| form.button(&fill_player(&b.text, player)),
+        );
+        let form_data = form.to_json();
+        self.send_form(addr, NPC_DIALOGUE_FORM_ID, &form_data).await;
+        if let Some(conn) = self.connections.get_mut(&addr) {
+            conn.pending_forms.push(PendingForm::server(
+                NPC_DIALOGUE_FORM_ID,
+                format!("{NPC_DIALOGUE_FORM_TYPE}{}", npc.name),
+                form_data,
+            ));
+        }
+    }
+
+    /// Handle the answer to a form if it is an NPC dialogue. Returns whether
+    /// it was one.
+    pub(super) async fn handle_npc_dialogue_response(
+        &mut self,
+        addr: SocketAddr,
+        form_type: &str,
+        response_data: Option<&str>,
+    ) -> bool {
+        let Some(npc_name) = form_type.strip_prefix(NPC_DIALOGUE_FORM_TYPE) else {
+            return false;
+        };
+        let FormResponse::Simple { button_index } =
+            parse_form_response("simple", "", response_data)
+        else {
+            return true;
+        };
+        let command = self
+            .npcs
+            .get(npc_name)
+            .and_then(|n| n.dialogue())
+            .and_then(|(_, buttons)| buttons.get(button_index as usize)?.command.clone());
+        let player = self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.login_data.as_ref())
+            .map(|l| l.display_name.clone());
+        if let (Some(command), Some(player)) = (command, player) {
+            self.run_npc_command(addr, &player, &command).await;
+        }
+        true
+    }
+
+    /// `/npc <create|remove|list|rename|movehere|skin|type|action|dialogue|button> ...`
+    pub(super) async fn cmd_npc(
+        &mut self,
+        addr: Option<SocketAddr>,
+        args: &[String],
+    ) -> CommandResult {
+        const USAGE: &str =
+            "Usage: /npc <create|remove|list|rename|movehere|skin|type|action|dialogue|button> ...";
+        let Some((sub, rest)) = args.split_first() else {
+            return CommandResult::err(USAGE);
+        };
+        let name = rest.first().map(String::as_str).unwrap_or_default();
+        let text = rest.get(1..).map(|t| t.join(" ")).unwrap_or_default();
+        let unknown = || CommandResult::err(format!("Unknown NPC: {name}"));
+
+        match sub.as_str() {
+            "list" => {
+                let lines: Vec<String> = self
+                    .npcs
+                    .iter()
+                    .map(|n| {
+                        let [x, y, z] = n.position;
+                        format!(
+                            "- {} \"{}\" ({}, {} action(s) at {x:.1}, {y:.1}, {z:.1} in dimension {})",
+                            n.name,
+                            n.display_name,
+                            n.entity_type,
+                            n.actions.len(),
+                            n.dimension
+                        )
+                    })
+                    .collect();
+                if lines.is_empty() {
+                    return CommandResult::ok("There are no NPCs");
+                }
+                CommandResult::ok(format!("NPCs:\n{}", lines.join("\n")))
+            }
+            "create" => {
+                if name.is_empty() {
+                    return CommandResult::err("Usage: /npc create <name> [display name]");
+                }
+                let Some(conn) = addr.and_then(|a| self.connections.get(&a)) else {
+                    return CommandResult::err("This command must be run by a player");
+                };
+                if self.npcs.get(name).is_some() {
+                    return CommandResult::err(format!("NPC {name} already exists"));
+                }
+                let p = conn.position;
+                let mut npc = Npc::new(
+                    name,
+                    conn.dimension,
+                    [p.x, p.y - EYE_HEIGHT, p.z],
+                    conn.yaw,
+                    conn.pitch,
+                );
+                if !text.is_empty() {
+                    npc.display_name = text;
+                }
+                npc.skin = conn.client_data.as_ref().map(NpcSkin::from_client_data);
+                self.put_npc(npc).await;
+                CommandResult::ok(format!("NPC {name} created"))
+            }
+            "remove" => {
+                self.hide_npc(name).await;
+                match self.npcs.remove(name) {
+                    Some(_) => CommandResult::ok(format!("NPC {name} removed")),
+                    None => unknown(),
+                }
+            }
+            "rename" => {
+                if text.is_empty() {
+                    return CommandResult::err("Usage: /npc rename <name> <display name>");
+                }
+                self.edit_npc(name, |n| n.display_name = text)
+                    .await
+                    .map_or_else(unknown, |_| {
+                        CommandResult::ok(format!("NPC {name} renamed"))
+                    })
+            }
+            "movehere" => {
+                let Some(conn) = addr.and_then(|a| self.connections.get(&a)) else {
+                    return CommandResult::err("This command must be run by a player");
+                };
+                let (p, dimension, yaw, pitch) =
+                    (conn.position, conn.dimension, conn.yaw, conn.pitch);
+                self.edit_npc(name, |n| {
+                    n.position = [p.x, p.y - EYE_HEIGHT, p.z];
+                    n.dimension = dimension;
+                    n.yaw = yaw;
+                    n.pitch = pitch;
+                })
+                .await
+                .map_or_else(unknown, |_| CommandResult::ok(format!("NPC {name} moved")))
+            }
+            "skin" => {
+                let Some(skin) = self.find_player_addr(&text).and_then(|a| {
+                    let data = self.connections.get(&a)?.client_data.as_ref()?;
+                    Some(NpcSkin::from_client_data(data))
+                }) else {
+                    return CommandResult::err("Usage: /npc skin <name> <online player>");
+                };
+                self.edit_npc(name, |n| {
+                    n.entity_type = PLAYER_NPC_TYPE.to_string();
+                    n.skin = Some(skin);
+                })
+                .await
+                .map_or_else(unknown, |_| {
+                    CommandResult::ok(format!("NPC {name} now wears the skin of {text}"))
+                })
+            }
+            "type" => {
+                if text.is_empty() {
+                    return CommandResult::err("Usage: /npc type <name> <player|entity type>");
+                }
+                let entity_type = match text.as_str() {
+                    "player" => PLAYER_NPC_TYPE.to_string(),
+                    t if t.contains(':') => t.to_string(),
+                    t => format!("minecraft:{t}"),
+                };
+                self.edit_npc(name, |n| n.entity_type = entity_type.clone())
+                    .await
+                    .map_or_else(unknown, |_| {
+                        CommandResult::ok(format!("NPC {name} is now a {entity_type}"))
+                    })
+            }
+            "action" => {
+                self.cmd_npc_action(name, rest.get(1..).unwrap_or_default())
+                    .await
+            }
+            "dialogue" => {
+                if text.is_empty() {
+                    return CommandResult::err("Usage: /npc dialogue <name> <text>");
+                }
+                self.edit_npc(name, |n| {
+                    match n
+                        .actions
+                        .iter_mut()
+                        .find(|a| matches!(a, NpcAction::Dialogue { .. }))
+                    {
+                        Some(NpcAction::Dialogue { text: t, .. }) => *t = text,
+                        _ => n.actions.push(NpcAction::Dialogue {
+                            text,
+                            buttons: Vec::new(),
+                        }),
+                    }
+                })
+                .await
+                .map_or_else(unknown, |_| {
+                    CommandResult::ok(format!("Dialogue of {name} set"))
+                })
+            }
+            "button" => {
+                let (label, command) = match text.split_once('|') {
+                    Some((label, command)) => (label.trim(), Some(command.trim().to_string())),
+                    None => (text.trim(), None),
+                };
+                if label.is_empty() {
+                    return CommandResult::err("Usage: /npc button <name> <label> [| <command>]");
+                }
+                let button = DialogueButton {
+                    text: label.to_string(),
+                    command: command.filter(|c| !c.is_empty()),
+                };
+                let added = self
+                    .edit_npc(name, |n| {
+                        let dialogue = n
+                            .actions
+                            .iter_mut()
+                            .find(|a| matches!(a, NpcAction::Dialogue { .. }));
+                        if let Some(NpcAction::Dialogue { buttons, .. }) = dialogue {
+                            buttons.push(button);
+                            true
+                        } else {
+                            false
+                        }
+                    })
+                    .await;
+                match added {
+                    Some(true) => {
+                        CommandResult::ok(format!("Button added to the dialogue of {name}"))
+                    }
+                    Some(false) => CommandResult::err(format!(
+                        "{name} has no dialogue; set one with /npc dialogue {name} <text>"
+                    )),
+                    None => unknown(),
+                }
+            }
+            _ => CommandResult::err(USAGE),
+        }
+    }
+
+    /// `/npc action <name> <add <command|console|message|event> <value>|remove <n>|clear|list>`
+    async fn cmd_npc_action(&mut self, name: &str, args: &[String]) -> CommandResult {
+        const USAGE: &str = "Usage: /npc action <name> <add <command|console|message|event> <value>|remove <n>|clear|list>";
+        let Some(actions) = self.npcs.get(name).map(|n| n.actions.clone()) else {
+            return CommandResult::err(format!("Unknown NPC: {name}"));
+        };
+        let value = args.get(2..).map(|v| v.join(" ")).unwrap_or_default();
+        match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+            ["list"] | [] => {
+                if actions.is_empty() {
+                    return CommandResult::ok(format!("{name} has no actions"));
+                }
+                let lines: Vec<String> = actions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, a)| {
+                        let desc = match a {
+                            NpcAction::Command { command } => format!("command: /{command}"),
+                            NpcAction::Console { command } => format!("console: /{command}"),
+                            NpcAction::Message { text } => format!("message: {text}"),
+                            NpcAction::Dialogue { text, buttons } => {
+                                format!("dialogue: {text} ({} button(s))", buttons.len())
+                            }
+                            NpcAction::Event { id } => format!("event: {id}"),
+                        };
+                        format!("{}. {desc}", i + 1)
+                    })
+                    .collect();
+                CommandResult::ok(format!("Actions of {name}:\n{}", lines.join("\n")))
+            }
+            ["add", kind, ..] if !value.is_empty() => {
+                let value = value.trim_start_matches('/').to_string();
+                let action = match kind {
+                    "command" => NpcAction::Command { command: value },
+                    "console" => NpcAction::Console { command: value },
+                    "message" => NpcAction::Message { text: value },
+                    "event" => NpcAction::Event { id: value },
+                    _ => return CommandResult::err(USAGE),
+                };
+                self.edit_npc(name, |n| n.actions.push(action)).await;
+                CommandResult::ok(format!("Action added to {name}"))
+            }
+            ["remove", n] => match n.parse::<usize>() {
+                Ok(i) if i >= 1 && i <= actions.len() => {
+                    self.edit_npc(name, |n| n.actions.remove(i - 1)).await;
+                    CommandResult::ok(format!("Action {i} of {name} removed"))
+                }
+                _ => CommandResult::err(format!(
+                    "{name} has {} action(s), there is no action {n}",
+                    actions.len()
+                )),
+            },
+            ["clear"] => {
+                self.edit_npc(name, |n| n.actions.clear()).await;
+                CommandResult::ok(format!("Actions of {name} cleared"))
+            }
+            _ => CommandResult::err(USAGE),
+        }
+    }
+}
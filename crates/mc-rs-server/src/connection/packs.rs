@@ -0,0 +1,99 @@
+//! Which packs each player is offered and accepted, and `/packs`.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::packets::{self, Text};
+
+use super::ConnectionHandler;
+use crate::packs::HostedPack;
+
+impl ConnectionHandler {
+    /// The packs to offer a player: every pack but the optional ones they
+    /// turned off.
+    pub(super) fn packs_for(&self, addr: SocketAddr) -> Vec<&HostedPack> {
+        let account = self.economy_account_id(addr);
+        self.packs
+            .packs()
+            .iter()
+            .filter(|pack| {
+                pack.is_required(&self.server_config.packs)
+                    || !account
+                        .as_deref()
+                        .is_some_and(|id| self.pack_preferences.is_disabled(id, &pack.uuid))
+            })
+            .collect()
+    }
+
+    /// Whether any of the packs offered to a player is required.
+    pub(super) fn offered_required_packs(&self, addr: SocketAddr) -> bool {
+        self.connections.get(&addr).is_some_and(|conn| {
+            conn.offered_packs
+                .iter()
+                .filter_map(|uuid| self.packs.find(uuid))
+                .any(|pack| pack.is_required(&self.server_config.packs))
+        })
+    }
+
+    /// Tell a player who declined the optional packs how to get them.
+    pub(super) async fn send_optional_pack_prompt(&mut self, addr: SocketAddr) {
+        let prompt = &self.server_config.packs.optional_prompt;
+        let declined = self
+            .connections
+            .get(&addr)
+            .is_some_and(|c| !c.offered_packs.is_empty() && c.accepted_packs.is_empty());
+        if declined && !prompt.is_empty() {
+            let msg = Text::system(prompt.clone());
+            self.send_packet(addr, packets::id::TEXT, &msg).await;
+        }
+    }
+
+    /// `/packs [list|toggle <pack>]`
+    pub(super) fn cmd_packs(&mut self, addr: Option<SocketAddr>, args: &[String]) -> CommandResult {
+        let account = addr.and_then(|a| self.economy_account_id(a));
+        match (args.first().map(String::as_str), args.get(1)) {
+            (None | Some("list"), None) => {
+                if self.packs.is_empty() {
+                    return CommandResult::ok("This server has no packs");
+                }
+                let lines: Vec<String> = self
+                    .packs
+                    .packs()
+                    .iter()
+                    .map(|pack| {
+                        let status = if pack.is_required(&self.server_config.packs) {
+                            "required"
+                        } else if account
+                            .as_deref()
+                            .is_some_and(|id| self.pack_preferences.is_disabled(id, &pack.uuid))
+                        {
+                            "optional, off"
+                        } else {
+                            "optional"
+                        };
+                        format!("{} v{} ({}): {status}", pack.name, pack.version, pack.uuid)
+                    })
+                    .collect();
+                CommandResult::ok(lines.join("\n"))
+            }
+            (Some("toggle"), Some(entry)) => {
+                let Some(account) = account else {
+                    return CommandResult::err("Only players can turn packs off");
+                };
+                let Some(pack) = self.packs.packs().iter().find(|p| p.is_named(entry)) else {
+                    return CommandResult::err(format!("Unknown pack: {entry}"));
+                };
+                if pack.is_required(&self.server_config.packs) {
+                    return CommandResult::err(format!("{} is required", pack.name));
+                }
+                let name = pack.name.clone();
+                if self.pack_preferences.toggle(&account, &pack.uuid) {
+                    CommandResult::ok(format!("{name} turned on; rejoin to download it"))
+                } else {
+                    CommandResult::ok(format!("{name} turned off from your next join"))
+                }
+            }
+            _ => CommandResult::err("Usage: /packs [list|toggle <pack>]"),
+        }
+    }
+}
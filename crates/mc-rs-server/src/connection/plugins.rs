@@ -36,13 +36,11 @@ impl ConnectionHandler {
                     health,
                 } => {
                     if let Some(addr) = self.find_player_addr(&player_name) {
-                        let pkt = if let Some(conn) = self.connections.get_mut(&addr) {
-                            conn.health = health.clamp(0.0, 20.0);
-                            Some(UpdateAttributes::health(
-                                conn.entity_runtime_id,
-                                conn.health,
-                                0,
-                            ))
+                        let pkt = if let Some(conn) = self.connections.get(&addr) {
+                            let health = self
+                                .game_world
+                                .set_player_health(conn.entity_unique_id, health);
+                            Some(UpdateAttributes::health(conn.entity_runtime_id, health, 0))
                         } else {
                             None
                         };
@@ -54,9 +52,9 @@ impl ConnectionHandler {
                 }
                 PendingAction::SetPlayerFood { player_name, food } => {
                     if let Some(addr) = self.find_player_addr(&player_name) {
-                        if let Some(conn) = self.connections.get_mut(&addr) {
-                            conn.food = food.clamp(0, 20);
-                        }
+                        let mut hunger = self.hunger(addr);
+                        hunger.food = food.clamp(0, 20);
+                        self.set_hunger(addr, hunger);
                     }
                 }
                 PendingAction::TeleportPlayer {
@@ -89,12 +87,24 @@ impl ConnectionHandler {
                         }
                     }
                 }
+                PendingAction::TransferPlayer {
+                    player_name,
+                    host,
+                    port,
+                } => {
+                    if let Some(addr) = self.find_player_addr(&player_name) {
+                        self.transfer_player(addr, &host, port).await;
+                    }
+                }
                 PendingAction::SetTime { time } => {
-                    self.world_time = time;
-                    let pkt = SetTime {
-                        time: self.world_time as i32,
-                    };
-                    self.broadcast_packet(packets::id::SET_TIME, &pkt).await;
+                    self.set_world_time(0, time).await;
+                }
+                PendingAction::SetWorldFlag {
+                    dimension,
+                    flag,
+                    value,
+                } => {
+                    self.set_world_flag(dimension, flag, value);
                 }
                 PendingAction::SpawnMob { mob_type, x, y, z } => {
                     self.game_world.spawn_mob(&mob_type, x, y, z);
@@ -102,6 +112,15 @@ impl ConnectionHandler {
                 PendingAction::RemoveMob { runtime_id } => {
                     self.game_world.remove_mob(runtime_id);
                 }
+                PendingAction::TriggerEntityEvent { runtime_id, event } => {
+                    self.game_world.trigger_entity_event(runtime_id, &event);
+                }
+                PendingAction::SetEntityAppearance {
+                    runtime_id,
+                    appearance,
+                } => {
+                    self.set_entity_appearance(runtime_id, appearance);
+                }
                 PendingAction::RegisterCommand {
                     name, plugin_name, ..
                 } => {
@@ -109,25 +128,115 @@ impl ConnectionHandler {
                         .plugin_commands
                         .insert(name, plugin_name);
                 }
+                PendingAction::RegisterKit { name, kit } => {
+                    self.plugin_manager.plugin_kits.insert(name, kit);
+                }
+                PendingAction::CreateArena { def } => self.create_arena(def),
+                PendingAction::JoinArena {
+                    player_name,
+                    arena_name,
+                } => {
+                    if let Err(e) = self.join_arena(&player_name, &arena_name).await {
+                        warn!("[plugin] {e}");
+                    }
+                }
+                PendingAction::LeaveArena { player_name } => {
+                    self.leave_arena(&player_name).await;
+                }
+                PendingAction::SetArenaScore {
+                    arena_name,
+                    entry,
+                    score,
+                } => {
+                    self.set_arena_score(&arena_name, &entry, score).await;
+                }
+                PendingAction::EndArena { arena_name, winner } => {
+                    self.end_arena(&arena_name, winner.as_deref());
+                }
+                PendingAction::CreateHologram { hologram } => self.put_hologram(hologram).await,
+                PendingAction::SetHologramLines { name, lines } => {
+                    if self
+                        .edit_hologram(&name, |h| h.lines = lines)
+                        .await
+                        .is_none()
+                    {
+                        warn!("[plugin] Unknown hologram: {name}");
+                    }
+                }
+                PendingAction::RemoveHologram { name } => {
+                    self.delete_hologram(&name).await;
+                }
+                PendingAction::PlayCutscene {
+                    player_name,
+                    cutscene,
+                } => {
+                    if let Err(e) = self.play_cutscene(&player_name, cutscene) {
+                        warn!("[plugin] {e}");
+                    }
+                }
+                PendingAction::StopCutscene { player_name } => {
+                    self.stop_cutscene(&player_name).await;
+                }
+                PendingAction::PushFog {
+                    player_name,
+                    fog_id,
+                    user_id,
+                } => {
+                    if let Some(addr) = self.find_player_addr(&player_name) {
+                        self.edit_fog(addr, |fog| fog.push(&fog_id, &user_id)).await;
+                    }
+                }
+                PendingAction::RemoveFog {
+                    player_name,
+                    user_id,
+                } => {
+                    if let Some(addr) = self.find_player_addr(&player_name) {
+                        self.edit_fog(addr, |fog| fog.remove(&user_id)).await;
+                    }
+                }
+                PendingAction::SetClientGameRule {
+                    player_name,
+                    rule,
+                    value,
+                } => {
+                    if let Some(addr) = self.find_player_addr(&player_name) {
+                        self.set_player_client_rule(addr, rule, value).await;
+                    }
+                }
+                PendingAction::SetInputPermission {
+                    player_name,
+                    flag,
+                    enabled,
+                } => {
+                    if let Some(addr) = self.find_player_addr(&player_name) {
+                        self.set_input_locked(addr, flag, !enabled).await;
+                    }
+                }
                 PendingAction::ShowForm {
                     player_name,
                     form_id,
                     form_data,
                     form_type,
+                    plugin_name,
+                    chained,
                 } => {
                     if let Some(addr) = self.find_player_addr(&player_name) {
+                        self.send_form(addr, form_id, &form_data).await;
                         if let Some(conn) = self.connections.get_mut(&addr) {
-                            conn.pending_forms.insert(form_id, form_type);
+                            conn.pending_forms.push(PendingForm {
+                                form_id,
+                                form_type,
+                                form_data,
+                                plugin: Some(plugin_name),
+                                chained,
+                                answered: false,
+                            });
                         }
-                        self.send_packet(
-                            addr,
-                            packets::id::MODAL_FORM_REQUEST,
-                            &packets::ModalFormRequest { form_id, form_data },
-                        )
-                        .await;
                     }
                 }
-                PendingAction::ScheduleTask { .. } | PendingAction::CancelTask { .. } => {
+                PendingAction::ScheduleTask { .. }
+                | PendingAction::CancelTask { .. }
+                | PendingAction::SendScriptEvent { .. } => {
                     // These are handled internally by PluginManager
                 }
                 PendingAction::Log { level, message } => {
@@ -143,6 +252,23 @@ impl ConnectionHandler {
         }
     }
 
+    /// Fire a script event to all plugins and apply their actions.
+    pub(super) async fn fire_script_event(
+        &mut self,
+        id: String,
+        payload: String,
+        source: ScriptEventSource,
+    ) {
+        let event = PluginEvent::ScriptEvent {
+            id,
+            payload,
+            source,
+        };
+        let snapshot = self.build_snapshot();
+        let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+        self.apply_plugin_actions(actions).await;
+    }
+
     /// Drain ECS game events and send the corresponding packets.
     pub(super) async fn process_game_events(&mut self) {
         let events = self.game_world.drain_events();
@@ -174,11 +300,8 @@ impl ConnectionHandler {
                             continue;
                         }
                     }
-                    let metadata = if is_baby {
-                        baby_mob_metadata(bb_width, bb_height)
-                    } else {
-                        default_mob_metadata(bb_width, bb_height)
-                    };
+                    let metadata = self
+                        .with_appearance(runtime_id, mob_metadata(bb_width, bb_height, is_baby));
                     let pkt = AddActor {
                         entity_unique_id: unique_id,
                         entity_runtime_id: runtime_id,
@@ -199,6 +322,11 @@ impl ConnectionHandler {
                         metadata,
                     };
                     self.broadcast_packet(packets::id::ADD_ACTOR, &pkt).await;
+                    if self.game_world.mob_has_helmet(runtime_id) {
+                        let armor = self.helmet_armor(runtime_id, true);
+                        self.broadcast_packet(packets::id::MOB_ARMOR_EQUIPMENT, &armor)
+                            .await;
+                    }
                 }
                 GameEvent::MobMoved {
                     runtime_id,
@@ -207,15 +335,19 @@ impl ConnectionHandler {
                     yaw,
                     head_yaw,
                     on_ground,
+                    teleport,
                 } => {
-                    let pkt = MoveActorAbsolute::normal(
-                        runtime_id,
-                        Vec3::new(position.0, position.1, position.2),
-                        pitch,
-                        yaw,
-                        head_yaw,
-                        on_ground,
-                    );
+                    self.record_entity_position(runtime_id, position);
+                    let position = Vec3::new(position.0, position.1, position.2);
+                    let pkt = if teleport {
+                        MoveActorAbsolute::teleport(
+                            runtime_id, position, pitch, yaw, head_yaw, on_ground,
+                        )
+                    } else {
+                        MoveActorAbsolute::normal(
+                            runtime_id, position, pitch, yaw, head_yaw, on_ground,
+                        )
+                    };
                     self.broadcast_packet(packets::id::MOVE_ACTOR_ABSOLUTE, &pkt)
                         .await;
                 }
@@ -241,6 +373,9 @@ impl ConnectionHandler {
                     ref mob_type,
                     killed_by,
                 } => {
+                    self.entity_appearances.remove(&runtime_id);
+                    self.actor_data.forget(runtime_id);
+                    self.on_raider_died(runtime_id, killed_by).await;
                     // Plugin event: MobDeath (non-cancellable)
                     {
                         let mob_event = PluginEvent::MobDeath {
@@ -275,6 +410,9 @@ impl ConnectionHandler {
                                         as i32
                                 })
                                 .unwrap_or(0);
+                            if let Some(conn) = self.connections.get_mut(&killer_addr) {
+                                conn.stats.record_mob_killed(mob_type);
+                            }
                             let base_xp = xp::mob_xp(mob_type);
                             self.award_xp(killer_addr, base_xp + looting_bonus).await;
                         }
@@ -289,6 +427,21 @@ impl ConnectionHandler {
                     )
                     .await;
                 }
+                GameEvent::PlayerRammed { runtime_id, motion } => {
+                    self.broadcast_packet(
+                        packets::id::SET_ENTITY_MOTION,
+                        &SetEntityMotion {
+                            entity_runtime_id: runtime_id,
+                            motion: Vec3::new(motion.0, motion.1, motion.2),
+                        },
+                    )
+                    .await;
+                }
+                GameEvent::MobHelmetBroken { runtime_id } => {
+                    let armor = self.helmet_armor(runtime_id, false);
+                    self.broadcast_packet(packets::id::MOB_ARMOR_EQUIPMENT, &armor)
+                        .await;
+                }
                 GameEvent::MobLoveParticles { runtime_id } => {
                     self.broadcast_packet(
                         packets::id::ENTITY_EVENT,
@@ -296,6 +449,45 @@ impl ConnectionHandler {
                     )
                     .await;
                 }
+                GameEvent::EntityCommands {
+                    runtime_id,
+                    mob_type,
+                    commands,
+                } => {
+                    // Queued commands run as the server at the automated op
+                    // level; `scriptevent` keeps the entity as its source
+                    for command in commands {
+                        match parse_script_event(&command) {
+                            Some(Ok((id, payload))) => {
+                                let source = ScriptEventSource::Entity {
+                                    runtime_id,
+                                    entity_type: mob_type.clone(),
+                                };
+                                self.fire_script_event(id, payload, source).await;
+                            }
+                            Some(Err(e)) => warn!("{mob_type} queued a bad scriptevent: {e}"),
+                            None => {
+                                let line = command.strip_prefix('/').unwrap_or(&command);
+                                let result = self
+                                    .dispatch_command(
+                                        functions::SERVER_ADDR,
+                                        functions::SERVER_SENDER,
+                                        line,
+                                        functions::AUTOMATED_OP_LEVEL,
+                                    )
+                                    .await;
+                                self.apply_command_effects(functions::SERVER_SENDER, &result)
+                                    .await;
+                                if !result.success {
+                                    debug!(
+                                        "Command queued by {mob_type} failed: {command}: {}",
+                                        result.messages.join(" ")
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
                 GameEvent::MobAttackPlayer {
                     mob_runtime_id,
                     target_runtime_id,
@@ -310,20 +502,29 @@ impl ConnectionHandler {
                         .map(|(a, _)| *a);
                     if let Some(addr) = target_addr {
                         // Invulnerability check
-                        let tick = match self.connections.get(&addr) {
-                            Some(c) => c.client_tick,
+                        let (tick, entity) = match self.connections.get(&addr) {
+                            Some(c) => (
+                                c.client_tick,
+                                self.game_world.player_entity(c.entity_unique_id),
+                            ),
                             None => continue,
                         };
-                        if let Some(last) =
-                            self.connections.get(&addr).and_then(|c| c.last_damage_tick)
-                        {
-                            if tick.saturating_sub(last) < 10 {
-                                continue;
-                            }
+                        let Some(entity) = entity else {
+                            continue;
+                        };
+                        let damage_cooldown = self.server_config.combat.damage_cooldown;
+                        if self.game_world.invulnerable(entity, tick, damage_cooldown) {
+                            continue;
+                        }
+
+                        // Scale by difficulty (Peaceful mobs can't hurt)
+                        let base_damage = self.difficulty.mob_damage(raw_damage);
+                        if base_damage <= 0.0 {
+                            continue;
                         }
 
                         // Apply armor + protection + resistance reduction
-                        let (armor_defense, armor_nbt_slots) = {
+                        let (armor_defense, armor_toughness, armor_nbt_slots) = {
                             let conn = match self.connections.get(&addr) {
                                 Some(c) => c,
                                 None => continue,
@@ -331,6 +532,12 @@ impl ConnectionHandler {
                             let defense = game_combat::total_armor_defense(
                                 &self.item_registry,
                                 &conn.inventory.armor,
+                            ) + self
+                                .custom_items
+                                .armor_protection(&self.item_registry, &conn.inventory.armor);
+                            let toughness = game_combat::total_armor_toughness(
+                                &self.item_registry,
+                                &conn.inventory.armor,
                             );
                             let nbt: Vec<Vec<u8>> = conn
                                 .inventory
@@ -338,7 +545,7 @@ impl ConnectionHandler {
                                 .iter()
                                 .map(|i| i.nbt_data.clone())
                                 .collect();
-                            (defense, nbt)
+                            (defense, toughness, nbt)
                         };
                         let armor_refs: Vec<&[u8]> =
                             armor_nbt_slots.iter().map(|v| v.as_slice()).collect();
@@ -346,9 +553,10 @@ impl ConnectionHandler {
 
                         // Mob attacks have no weapon enchantments or criticals
                         let damage = game_combat::calculate_damage(&game_combat::DamageInput {
-                            base_damage: raw_damage,
+                            base_damage,
                             weapon_nbt: &[],
                             armor_defense,
+                            armor_toughness,
                             armor_nbt_slots: &armor_refs,
                             is_critical: false,
                             strength_bonus: 0.0,
@@ -357,14 +565,16 @@ impl ConnectionHandler {
                         });
 
                         // Apply damage
-                        let conn = match self.connections.get_mut(&addr) {
-                            Some(c) => c,
+                        let runtime_id = match self.connections.get(&addr) {
+                            Some(c) => c.entity_runtime_id,
                             None => continue,
                         };
-                        conn.health = (conn.health - damage).max(0.0);
-                        conn.last_damage_tick = Some(tick);
-                        let new_health = conn.health;
-                        let runtime_id = conn.entity_runtime_id;
+                        let Some(new_health) =
+                            self.game_world
+                                .damage_entity(entity, damage, tick, damage_cooldown)
+                        else {
+                            continue;
+                        };
                         let is_dead = new_health <= 0.0;
 
                         // Send hurt animation
@@ -401,13 +611,11 @@ impl ConnectionHandler {
                         }
 
                         if is_dead {
-                            // Death flow — XP loss + mark dead
+                            // Death flow — drop loot + mark dead
+                            self.drop_death_loot(addr).await;
                             let conn = self.connections.get_mut(&addr).unwrap();
-                            let (nl, nt) = xp::after_death(conn.xp_level, conn.xp_total);
-                            conn.xp_level = nl;
-                            conn.xp_total = nt;
                             conn.is_dead = true;
-                            conn.health = 0.0;
+                            conn.stats.deaths += 1;
 
                             self.broadcast_packet(
                                 packets::id::ENTITY_EVENT,
@@ -432,3 +640,46 @@ impl ConnectionHandler {
         }
     }
 }
+
+/// Parse a `scriptevent <id> [payload]` command line into `(id, payload)`.
+///
+/// Returns `None` if the line isn't a `scriptevent` command.
+pub(super) fn parse_script_event(line: &str) -> Option<Result<(String, String), String>> {
+    let line = line.trim().strip_prefix('/').unwrap_or(line.trim());
+    let rest = line.strip_prefix("scriptevent")?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = rest.trim_start();
+    let (id, payload) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if !is_valid_script_event_id(id) {
+        return Some(Err(format!(
+            "Invalid script event ID '{id}': expected namespace:name outside the minecraft namespace"
+        )));
+    }
+    Some(Ok((id.to_string(), payload.trim_start().to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_script_event;
+
+    #[test]
+    fn parse_script_event_lines() {
+        assert_eq!(
+            parse_script_event("scriptevent my_pack:open door 3"),
+            Some(Ok(("my_pack:open".into(), "door 3".into())))
+        );
+        assert_eq!(
+            parse_script_event("/scriptevent a:b"),
+            Some(Ok(("a:b".into(), String::new())))
+        );
+        assert!(matches!(
+            parse_script_event("scriptevent minecraft:x"),
+            Some(Err(_))
+        ));
+        assert!(matches!(parse_script_event("scriptevent"), Some(Err(_))));
+        assert_eq!(parse_script_event("say hi"), None);
+        assert_eq!(parse_script_event("scripteventx a:b"), None);
+    }
+}
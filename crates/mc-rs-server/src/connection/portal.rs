@@ -444,7 +444,7 @@ impl ConnectionHandler {
     }
 
     /// Execute the full dimension change flow.
-    async fn execute_dimension_change(
+    pub(super) async fn execute_dimension_change(
         &mut self,
         addr: SocketAddr,
         src_dim: i32,
@@ -469,9 +469,21 @@ impl ConnectionHandler {
         )
         .await;
 
-        // 2. Send ChangeDimension to the traveling player
+        // 2. Send ChangeDimension to the traveling player. Clients ignore a
+        // change to the dimension they are in, so moving between two worlds
+        // of the same kind goes through another dimension first.
+        let client_dim = self.client_dimension(target_dim);
+        if self.client_dimension(src_dim) == client_dim {
+            let detour = packets::ChangeDimension {
+                dimension: if client_dim == 0 { 1 } else { 0 },
+                position: target_pos,
+                respawn: false,
+            };
+            self.send_packet(addr, packets::id::CHANGE_DIMENSION, &detour)
+                .await;
+        }
         let change_dim = packets::ChangeDimension {
-            dimension: target_dim,
+            dimension: client_dim,
             position: target_pos,
             respawn: false,
         };
@@ -479,13 +491,16 @@ impl ConnectionHandler {
             .await;
 
         // 3. Update player state
+        let mut left = Vec::new();
         if let Some(conn) = self.connections.get_mut(&addr) {
             conn.dimension = target_dim;
             conn.position = target_pos;
-            conn.sent_chunks.clear();
+            conn.last_sent_move = None;
+            left.extend(conn.sent_chunks.drain());
             conn.fall_distance = 0.0;
             conn.airborne_ticks = 0;
         }
+        self.chunks_left_view(addr, src_dim, &left).await;
 
         // 4. Send chunks in the new dimension
         self.send_new_chunks(addr).await;
@@ -503,6 +518,7 @@ impl ConnectionHandler {
         // 6. Broadcast AddPlayer to players in the target dimension
         self.broadcast_add_player_to_dimension(addr, target_dim)
             .await;
+        self.sync_holograms_to_player(addr).await;
 
         // 7. Sync ECS mirror entity position
         self.game_world.update_player_position(
@@ -536,7 +552,8 @@ impl ConnectionHandler {
                     .get_slot(0, conn.inventory.held_slot)
                     .cloned()
                     .unwrap_or_else(mc_rs_proto::item_stack::ItemStack::empty);
-                let is_op = self.permissions.ops.contains(&login.display_name);
+                let (permission_level, command_permission_level) =
+                    self.permissions.ability_levels(&login.display_name);
 
                 let ap = AddPlayer {
                     uuid,
@@ -550,10 +567,10 @@ impl ConnectionHandler {
                     head_yaw: conn.head_yaw,
                     held_item,
                     gamemode: conn.gamemode,
-                    metadata: default_player_metadata(&login.display_name),
+                    metadata: self.player_metadata(conn, &login.display_name),
                     entity_unique_id: conn.entity_unique_id,
-                    permission_level: if is_op { 2 } else { 1 },
-                    command_permission_level: if is_op { 1 } else { 0 },
+                    permission_level,
+                    command_permission_level,
                     device_id: client_data.device_id.clone(),
                     device_os: client_data.device_os,
                 };
@@ -748,36 +765,19 @@ impl ConnectionHandler {
                 if let Some(loaded) = self.chunk_storage.load_chunk_dim(target_cx, target_cz, dim) {
                     self.dim_chunks_mut(dim)
                         .insert((target_cx, target_cz), loaded);
+                    self.load_chunk_block_entities(target_cx, target_cz, dim);
+                    self.load_chunk_actors(target_cx, target_cz, dim).await;
                     continue;
                 }
 
                 // Generate
-                let gen_ow = self.overworld_generator.clone();
-                let gen_neth = self.nether_generator.clone();
-                let gen_end = self.end_generator.clone();
-                let fb = self.flat_world_blocks;
-                let d = dim;
-                let tcx = target_cx;
-                let tcz = target_cz;
-
-                if let Ok(mut col) = tokio::task::spawn_blocking(move || match d {
-                    1 => gen_neth
-                        .as_ref()
-                        .map(|g| g.generate_chunk(tcx, tcz))
-                        .unwrap_or_else(|| generate_flat_chunk(tcx, tcz, &fb)),
-                    2 => gen_end
-                        .as_ref()
-                        .map(|g| g.generate_chunk(tcx, tcz))
-                        .unwrap_or_else(|| generate_flat_chunk(tcx, tcz, &fb)),
-                    _ => gen_ow
-                        .as_ref()
-                        .map(|g| g.generate_chunk(tcx, tcz))
-                        .unwrap_or_else(|| generate_flat_chunk(tcx, tcz, &fb)),
-                })
-                .await
+                let generator = self.chunk_generator(dim);
+                if let Ok((mut col, structures)) =
+                    tokio::task::spawn_blocking(move || generator.generate(target_cx, target_cz))
+                        .await
                 {
                     col.dirty = true;
-                    self.dim_chunks_mut(dim).insert((target_cx, target_cz), col);
+                    self.insert_generated_chunk(dim, target_cx, target_cz, col, &structures);
                 }
             }
         }
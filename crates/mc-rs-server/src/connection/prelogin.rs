@@ -0,0 +1,77 @@
+//! Asking the pre-login webhook about joining players: the request runs on
+//! a task of its own, and its verdict is picked up by the next tick.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use mc_rs_proto::jwt;
+use mc_rs_proto::packets::{self, Disconnect};
+use tracing::info;
+
+use super::{ConnectionHandler, LoginState};
+use crate::prelogin::{PreLoginChecks, PreLoginRequest};
+
+impl ConnectionHandler {
+    /// Hold the login while the webhook is asked about the player.
+    pub(super) fn start_prelogin_check(
+        &mut self,
+        addr: SocketAddr,
+        login_data: jwt::LoginData,
+        client_data: jwt::ClientData,
+    ) {
+        let request = PreLoginRequest {
+            name: login_data.display_name.clone(),
+            xuid: login_data.xuid.clone(),
+            uuid: login_data.identity.clone(),
+            ip: addr.ip().to_string(),
+        };
+        if let Some(conn) = self.connections.get_mut(&addr) {
+            conn.state = LoginState::AwaitingPreLoginCheck;
+        }
+        self.pending_logins.insert(addr, (login_data, client_data));
+
+        let checks = Arc::clone(&self.prelogin);
+        let verdicts = self.prelogin_tx.clone();
+        tokio::spawn(async move {
+            let verdict = checks.check_webhook(&request).await;
+            let _ = verdicts.send((addr, verdict));
+        });
+    }
+
+    /// Let in or refuse the players whose webhook verdict came in.
+    pub(super) async fn tick_prelogin_checks(&mut self) {
+        while let Ok((addr, verdict)) = self.prelogin_rx.try_recv() {
+            let Some((login_data, client_data)) = self.pending_logins.remove(&addr) else {
+                continue;
+            };
+            if !self
+                .connections
+                .get(&addr)
+                .is_some_and(|c| c.state == LoginState::AwaitingPreLoginCheck)
+            {
+                continue;
+            }
+            match verdict {
+                Ok(()) => self.finish_login(addr, login_data, client_data).await,
+                Err(message) => {
+                    info!(
+                        "Pre-login webhook refused {} ({addr}): {message}",
+                        login_data.display_name
+                    );
+                    self.send_packet(
+                        addr,
+                        packets::id::DISCONNECT,
+                        &Disconnect::with_message(message),
+                    )
+                    .await;
+                }
+            }
+        }
+    }
+
+    /// Read the blocklist and webhook URL again after `[prelogin]` was
+    /// reloaded.
+    pub(super) fn reload_prelogin(&mut self) {
+        self.prelogin = Arc::new(PreLoginChecks::load(&self.server_config.prelogin));
+    }
+}
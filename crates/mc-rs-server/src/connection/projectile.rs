@@ -6,9 +6,11 @@ use mc_rs_game::combat::{self as game_combat, enchantment_id, parse_enchantments
 use mc_rs_game::projectile::{
     self, arrow_config, check_entity_collision, launch_velocity, step_projectile, trident_config,
 };
+use mc_rs_plugin_api::DamageCause;
+use mc_rs_proto::entity_metadata::{EntityFlag, EntityMetadataEntry, MetadataBuilder};
 use mc_rs_proto::packets::{
-    self, AddActor, EntityEvent, EntityMetadataEntry, InventoryContent, MetadataValue,
-    MoveActorAbsolute, RemoveEntity, SetEntityMotion, UpdateAttributes,
+    self, AddActor, EntityEvent, InventoryContent, MoveActorAbsolute, RemoveEntity,
+    SetEntityMotion, UpdateAttributes,
 };
 use mc_rs_proto::types::Vec3;
 
@@ -72,7 +74,7 @@ impl ConnectionHandler {
                     feet_y,
                     conn.position.z,
                     0.6,
-                    1.8,
+                    conn.pose().height(),
                 ));
             }
         }
@@ -404,8 +406,12 @@ impl ConnectionHandler {
         shooter_addr: SocketAddr,
         tick: u64,
     ) {
+        if !self.damage_allowed(target_addr, DamageCause::Attack, true) {
+            return;
+        }
         // Extract everything we need from the target before any mutable borrow
-        let (rid, armor_defense, armor_nbt_slots, target_pos) = {
+        let damage_cooldown = self.server_config.combat.damage_cooldown;
+        let (entity, rid, armor_defense, armor_toughness, armor_nbt_slots, target_pos) = {
             let conn = match self.connections.get(&target_addr) {
                 Some(c) => c,
                 None => return,
@@ -413,12 +419,18 @@ impl ConnectionHandler {
             if conn.is_dead || conn.gamemode == 1 || conn.gamemode == 3 {
                 return;
             }
-            if let Some(last) = conn.last_damage_tick {
-                if tick.saturating_sub(last) < 10 {
-                    return;
-                }
+            let Some(entity) = self.game_world.player_entity(conn.entity_unique_id) else {
+                return;
+            };
+            if self.game_world.invulnerable(entity, tick, damage_cooldown) {
+                return;
             }
-            let def = game_combat::total_armor_defense(&self.item_registry, &conn.inventory.armor);
+            let def = game_combat::total_armor_defense(&self.item_registry, &conn.inventory.armor)
+                + self
+                    .custom_items
+                    .armor_protection(&self.item_registry, &conn.inventory.armor);
+            let toughness =
+                game_combat::total_armor_toughness(&self.item_registry, &conn.inventory.armor);
             let nbt_slots: Vec<Vec<u8>> = conn
                 .inventory
                 .armor
@@ -426,8 +438,10 @@ impl ConnectionHandler {
                 .map(|item| item.nbt_data.clone())
                 .collect();
             (
+                entity,
                 conn.entity_runtime_id,
                 def,
+                toughness,
                 nbt_slots,
                 (conn.position.x, conn.position.z),
             )
@@ -439,6 +453,7 @@ impl ConnectionHandler {
             base_damage: damage,
             weapon_nbt: &[],
             armor_defense,
+            armor_toughness,
             armor_nbt_slots: &armor_nbt_refs,
             is_critical: false,
             strength_bonus: 0.0,
@@ -448,13 +463,17 @@ impl ConnectionHandler {
         let final_damage = game_combat::calculate_damage(&input);
 
         // Apply damage
+        let Some(health) =
+            self.game_world
+                .damage_entity(entity, final_damage, tick, damage_cooldown)
+        else {
+            return;
+        };
         {
             let conn = match self.connections.get_mut(&target_addr) {
                 Some(c) => c,
                 None => return,
             };
-            conn.health = (conn.health - final_damage).max(0.0);
-            conn.last_damage_tick = Some(tick);
             if flame && conn.fire_ticks <= 0 {
                 conn.fire_ticks = 100; // 5 seconds
             }
@@ -484,12 +503,6 @@ impl ConnectionHandler {
             }
         }
 
-        let health = self
-            .connections
-            .get(&target_addr)
-            .map(|c| c.health)
-            .unwrap_or(0.0);
-
         // Send hurt effect
         self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
             .await;
@@ -764,26 +777,7 @@ impl ConnectionHandler {
         let packets: Vec<AddActor> = self
             .active_projectiles
             .iter()
-            .map(|proj| {
-                let entity_type = match &proj.kind {
-                    ProjectileKind::Arrow => "minecraft:arrow".to_string(),
-                    ProjectileKind::Trident { .. } => "minecraft:trident".to_string(),
-                };
-                let metadata = projectile_metadata(proj.critical);
-                AddActor {
-                    entity_unique_id: proj.unique_id,
-                    entity_runtime_id: proj.runtime_id,
-                    entity_type,
-                    position: Vec3::new(proj.position.0, proj.position.1, proj.position.2),
-                    velocity: Vec3::new(proj.velocity.0, proj.velocity.1, proj.velocity.2),
-                    pitch: proj.pitch,
-                    yaw: proj.yaw,
-                    head_yaw: proj.yaw,
-                    body_yaw: proj.yaw,
-                    attributes: vec![],
-                    metadata,
-                }
-            })
+            .map(projectile_actor)
             .collect();
         for pkt in &packets {
             self.send_packet(addr, packets::id::ADD_ACTOR, pkt).await;
@@ -831,18 +825,30 @@ enum ProjectileHitEvent {
 }
 
 /// Build entity metadata for a projectile.
+/// Build the AddActor packet for a projectile.
+pub(super) fn projectile_actor(proj: &ActiveProjectile) -> AddActor {
+    let entity_type = match &proj.kind {
+        ProjectileKind::Arrow => "minecraft:arrow".to_string(),
+        ProjectileKind::Trident { .. } => "minecraft:trident".to_string(),
+    };
+    AddActor {
+        entity_unique_id: proj.unique_id,
+        entity_runtime_id: proj.runtime_id,
+        entity_type,
+        position: Vec3::new(proj.position.0, proj.position.1, proj.position.2),
+        velocity: Vec3::new(proj.velocity.0, proj.velocity.1, proj.velocity.2),
+        pitch: proj.pitch,
+        yaw: proj.yaw,
+        head_yaw: proj.yaw,
+        body_yaw: proj.yaw,
+        attributes: vec![],
+        metadata: projectile_metadata(proj.critical),
+    }
+}
+
 fn projectile_metadata(critical: bool) -> Vec<EntityMetadataEntry> {
-    let flags: i64 = if critical { 1 } else { 0 };
-    vec![
-        EntityMetadataEntry {
-            key: 0,
-            data_type: 7, // Long
-            value: MetadataValue::Long(flags),
-        },
-        EntityMetadataEntry {
-            key: 23,
-            data_type: 3,                     // Float
-            value: MetadataValue::Float(1.0), // SCALE
-        },
-    ]
+    MetadataBuilder::new()
+        .flag(EntityFlag::Critical, critical)
+        .scale(1.0)
+        .build()
 }
@@ -0,0 +1,237 @@
+//! Raids: the captains guarding pillager outposts, the Bad Omen they leave
+//! on their killer, and the raids it sets off on entering a village, with
+//! their boss bar and Hero of the Village reward.
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+
+use mc_rs_game::raid::{self, Raid, RaidAction};
+use mc_rs_proto::packets::mob_effect::effect_id;
+use mc_rs_proto::packets::{self, BossEvent};
+use mc_rs_world::structure::{StructureBounds, StructureKind};
+use rand::Rng;
+
+use super::{ConnectionHandler, LoginState};
+
+/// Boss bar color of raids (red).
+const RAID_BAR_COLOR: u32 = 2;
+/// Pillagers guarding an outpost along with its captain.
+const OUTPOST_GUARDS: usize = 2;
+/// How far outside a village's bounds a player with Bad Omen still sets
+/// off a raid.
+const VILLAGE_MARGIN: i32 = 16;
+/// Distance from the village center at which raiders gather.
+const RAIDER_SPAWN_DISTANCE: f32 = 40.0;
+
+/// A raid in progress, with the players shown its boss bar.
+pub(super) struct ActiveRaid {
+    raid: Raid,
+    boss_id: i64,
+    players: HashSet<SocketAddr>,
+    /// Title and fill last sent to the players.
+    shown: (String, f32),
+}
+
+impl ConnectionHandler {
+    /// Spawn the captain and guards of a newly generated pillager outpost on
+    /// its lookout.
+    pub(super) fn populate_outpost(&mut self, bounds: &StructureBounds) {
+        if !self.difficulty.allows_hostile_mobs() {
+            return;
+        }
+        let (x, z) = bounds.center();
+        let (x, y, z) = (x as f32 + 0.5, bounds.max.1 as f32 + 1.0, z as f32 + 0.5);
+        if let Some((_, captain)) = self.game_world.spawn_mob(raid::PILLAGER, x, y, z) {
+            self.raid_captains.insert(captain);
+        }
+        for i in 0..OUTPOST_GUARDS {
+            let dx = if i % 2 == 0 { -1.5 } else { 1.5 };
+            self.game_world.spawn_mob(raid::PILLAGER, x + dx, y, z);
+        }
+    }
+
+    /// A mob died: killing a captain outside of a raid gives its killer Bad
+    /// Omen, one level more than they had.
+    pub(super) async fn on_raider_died(&mut self, runtime_id: u64, killed_by: Option<u64>) {
+        if !self.raid_captains.remove(&runtime_id) {
+            return;
+        }
+        let Some(killer) = killed_by.and_then(|rid| self.find_addr_by_runtime_id(rid)) else {
+            return;
+        };
+        let current = self
+            .effects(killer)
+            .iter()
+            .find(|e| e.effect_id == effect_id::BAD_OMEN)
+            .map(|e| e.amplifier);
+        let amplifier = raid::bad_omen_after_captain_kill(current);
+        // Replace rather than stack, so the new level always lasts the full time
+        if current.is_some() {
+            self.remove_effect(killer, effect_id::BAD_OMEN).await;
+        }
+        self.apply_effect(killer, effect_id::BAD_OMEN, amplifier, raid::BAD_OMEN_TICKS)
+            .await;
+    }
+
+    /// Start raids for players carrying Bad Omen into villages, then run
+    /// every raid: spawn its waves, keep its boss bar up to date and reward
+    /// the heroes once it is won.
+    pub(super) async fn tick_raids(&mut self) {
+        if self.game_world.current_tick().is_multiple_of(20) {
+            self.start_raids().await;
+        }
+        if self.raids.is_empty() {
+            return;
+        }
+
+        let mut raids = std::mem::take(&mut self.raids);
+        for active in &mut raids {
+            match active.raid.tick(|rid| self.game_world.mob_health(rid)) {
+                RaidAction::None => {}
+                RaidAction::SpawnWave(raiders) => self.spawn_wave(&mut active.raid, &raiders),
+                RaidAction::Won => {
+                    let amplifier = raid::hero_amplifier(active.raid.omen_level);
+                    for &addr in &active.players {
+                        self.apply_effect(
+                            addr,
+                            effect_id::HERO_OF_THE_VILLAGE,
+                            amplifier,
+                            raid::HERO_TICKS,
+                        )
+                        .await;
+                    }
+                }
+                RaidAction::Lost => {}
+            }
+            self.update_raid_bar(active).await;
+        }
+        for active in raids.iter().filter(|a| a.raid.finished()) {
+            for &addr in &active.players {
+                self.send_packet(
+                    addr,
+                    packets::id::BOSS_EVENT,
+                    &BossEvent::hide(active.boss_id),
+                )
+                .await;
+            }
+        }
+        raids.retain(|a| !a.raid.finished());
+        self.raids = raids;
+    }
+
+    /// Turn the Bad Omen of every overworld player standing in a village
+    /// that isn't raided yet into a raid of its level.
+    async fn start_raids(&mut self) {
+        if !self.difficulty.allows_hostile_mobs() {
+            return;
+        }
+        let omens: Vec<(SocketAddr, i32, i32, i32)> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame && !c.is_dead && c.dimension == 0)
+            .filter_map(|(addr, c)| {
+                let omen = self
+                    .effects(*addr)
+                    .iter()
+                    .find(|e| e.effect_id == effect_id::BAD_OMEN)?;
+                let (x, z) = (c.position.x.floor() as i32, c.position.z.floor() as i32);
+                Some((*addr, omen.amplifier, x, z))
+            })
+            .collect();
+
+        for (addr, amplifier, x, z) in omens {
+            let Some(village) = self.structures.nearest(0, StructureKind::Village, x, z) else {
+                continue;
+            };
+            let in_village = (village.min.0 - VILLAGE_MARGIN..=village.max.0 + VILLAGE_MARGIN)
+                .contains(&x)
+                && (village.min.2 - VILLAGE_MARGIN..=village.max.2 + VILLAGE_MARGIN).contains(&z);
+            let (cx, cz) = village.center();
+            if !in_village
+                || self
+                    .raids
+                    .iter()
+                    .any(|a| (a.raid.center.0, a.raid.center.2) == (cx, cz))
+            {
+                continue;
+            }
+
+            self.remove_effect(addr, effect_id::BAD_OMEN).await;
+            let cy = self.top_block_y(cx, cz).map_or(village.min.1, |y| y + 1);
+            let boss_id = self.next_score_entry_id;
+            self.next_score_entry_id += 1;
+            self.raids.push(ActiveRaid {
+                raid: Raid::new((cx, cy, cz), amplifier + 1, self.difficulty),
+                boss_id,
+                players: HashSet::new(),
+                shown: (String::new(), 0.0),
+            });
+        }
+    }
+
+    /// Spawn the raiders of a new wave around the village.
+    fn spawn_wave(&mut self, raid: &mut Raid, raiders: &[&'static str]) {
+        let mut rng = rand::thread_rng();
+        let angle: f32 = rng.gen_range(0.0..std::f32::consts::TAU);
+        let (cx, cz) = (raid.center.0 as f32 + 0.5, raid.center.2 as f32 + 0.5);
+        let (x, z) = (
+            cx + angle.cos() * RAIDER_SPAWN_DISTANCE,
+            cz + angle.sin() * RAIDER_SPAWN_DISTANCE,
+        );
+        for type_id in raiders {
+            let (x, z) = (x + rng.gen_range(-2.0..2.0), z + rng.gen_range(-2.0..2.0));
+            let y = self
+                .top_block_y(x.floor() as i32, z.floor() as i32)
+                .map_or(raid.center.1 as f32, |y| y as f32 + 1.0);
+            let Some((_, rid)) = self.game_world.spawn_mob(type_id, x, y, z) else {
+                continue;
+            };
+            let max_health = self
+                .game_world
+                .mob_registry
+                .get(type_id)
+                .map_or(0.0, |def| def.max_health);
+            raid.add_raider(rid, max_health);
+        }
+    }
+
+    /// Show a raid's boss bar to the players near its village, hide it from
+    /// the ones who left and send the others what changed.
+    async fn update_raid_bar(&mut self, active: &mut ActiveRaid) {
+        let near: HashSet<SocketAddr> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame && c.dimension == 0)
+            .filter(|(_, c)| active.raid.in_range(c.position.x, c.position.z))
+            .map(|(addr, _)| *addr)
+            .collect();
+        let title = active.raid.title();
+        let progress = active.raid.progress();
+
+        for &addr in active.players.difference(&near) {
+            self.send_packet(
+                addr,
+                packets::id::BOSS_EVENT,
+                &BossEvent::hide(active.boss_id),
+            )
+            .await;
+        }
+        for &addr in &near {
+            if !active.players.contains(&addr) {
+                let pkt = BossEvent::show(active.boss_id, &title, progress, RAID_BAR_COLOR);
+                self.send_packet(addr, packets::id::BOSS_EVENT, &pkt).await;
+                continue;
+            }
+            if title != active.shown.0 {
+                let pkt = BossEvent::update_title(active.boss_id, &title);
+                self.send_packet(addr, packets::id::BOSS_EVENT, &pkt).await;
+            }
+            if progress != active.shown.1 {
+                let pkt = BossEvent::update_health(active.boss_id, progress);
+                self.send_packet(addr, packets::id::BOSS_EVENT, &pkt).await;
+            }
+        }
+        active.players = near;
+        active.shown = (title, progress);
+    }
+}
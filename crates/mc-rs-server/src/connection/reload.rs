@@ -0,0 +1,187 @@
+//! `/reload` (server.toml, permission lists and plugins, without
+//! disconnecting anyone) and `/restart`.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use mc_rs_command::CommandResult;
+use tracing::{info, warn};
+
+use super::{ConnectionHandler, LoginState};
+use crate::config::{ServerConfig, CONFIG_FILE};
+use crate::permissions::PermissionManager;
+
+/// Exit code of the server after `/restart`, telling a supervisor or start
+/// script to start it again (EX_TEMPFAIL).
+pub const RESTART_EXIT_CODE: i32 = 75;
+
+impl ConnectionHandler {
+    /// /reload [config|plugins]
+    pub(super) async fn cmd_reload(&mut self, args: &[String]) -> CommandResult {
+        let (config, plugins) = match args.first().map(String::as_str) {
+            None => (true, true),
+            Some("config") => (true, false),
+            Some("plugins") => (false, true),
+            Some(_) => return CommandResult::err("Usage: /reload [config|plugins]"),
+        };
+        let mut messages = Vec::new();
+        if config {
+            match self.reload_config().await {
+                Ok(m) => messages.extend(m),
+                Err(e) => return CommandResult::err(e),
+            }
+        }
+        if plugins {
+            messages.push(self.reload_plugins());
+        }
+        CommandResult {
+            success: true,
+            messages,
+            broadcast: None,
+            should_stop: false,
+        }
+    }
+
+    /// Re-read server.toml and the permission files, and apply what can
+    /// change while players are online.
+    async fn reload_config(&mut self) -> Result<Vec<String>, String> {
+        let next = ServerConfig::load(CONFIG_FILE)
+            .map_err(|e| format!("Failed to reload {CONFIG_FILE}: {e}"))?;
+        let restart_only = Arc::make_mut(&mut self.server_config).reload_from(next);
+        let mut messages = vec![format!("Reloaded {CONFIG_FILE}")];
+        if !restart_only.is_empty() {
+            warn!(
+                "Changed settings that apply after a restart: {}",
+                restart_only.join(", ")
+            );
+            messages.push(format!(
+                "Changed settings that apply after a restart: {}",
+                restart_only.join(", ")
+            ));
+        }
+
+        self.permissions =
+            PermissionManager::load(self.server_config.permissions.whitelist_enabled);
+        self.reload_geoip();
+        self.reload_prelogin();
+        self.slow_mode = self.server_config.chat.slow_mode;
+        self.reload_bridge();
+        let kicked = self.enforce_access_lists().await;
+        messages.push(format!(
+            "Reloaded ops, whitelist, bans, mutes, country lists and blocklist ({kicked} player(s) removed)"
+        ));
+
+        let addrs: Vec<SocketAddr> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame)
+            .map(|(&addr, _)| addr)
+            .collect();
+        for addr in addrs {
+            // Op levels may have changed
+            self.send_abilities(addr).await;
+        }
+        self.apply_all_chunk_radii().await;
+
+        let max_players = self.server_config.server.max_players;
+        self.server_handle
+            .set_max_connections(max_players as usize)
+            .await;
+        self.motd.max_players = max_players;
+        self.motd.server_name = self.render_motd();
+        self.server_handle.update_motd(self.motd.clone()).await;
+
+        info!("Configuration reloaded from {CONFIG_FILE}");
+        Ok(messages)
+    }
+
+    /// Disconnect the players the reloaded ban lists and whitelist no longer
+    /// let in, as they would be refused at login. Returns how many.
+    async fn enforce_access_lists(&mut self) -> usize {
+        let refused: Vec<(SocketAddr, String)> = self
+            .connections
+            .iter()
+            .filter_map(|(&addr, c)| {
+                let name = &c.login_data.as_ref()?.display_name;
+                let reason =
+                    if let Some(ban) = self.permissions.banned_ips.get(&addr.ip().to_string()) {
+                        format!("You are banned: {}", ban.reason)
+                    } else if let Some(ban) = self.permissions.banned_players.get(name) {
+                        format!("You are banned: {}", ban.reason)
+                    } else if self.permissions.whitelist_enabled
+                        && !self.permissions.whitelist.contains(name)
+                    {
+                        "You are not whitelisted on this server.".to_string()
+                    } else if !self.country_allowed(c.country.as_deref()) {
+                        "Your country is not allowed on this server.".to_string()
+                    } else if let Err(message) = self.prelogin.check_blocklist(addr.ip()) {
+                        message
+                    } else {
+                        return None;
+                    };
+                info!("Removing {name} after reload: {reason}");
+                Some((addr, reason))
+            })
+            .collect();
+        for (addr, reason) in &refused {
+            self.disconnect_player(*addr, reason).await;
+        }
+        refused.len()
+    }
+
+    /// Unload every plugin and load the plugins directory again.
+    fn reload_plugins(&mut self) -> String {
+        self.plugin_manager.reload();
+
+        let plugins_dir = std::path::PathBuf::from("plugins");
+        std::fs::create_dir_all(&plugins_dir).ok();
+        let engine = mc_rs_plugin_wasm::create_engine();
+        let wasm_plugins = mc_rs_plugin_wasm::load_wasm_plugins(&plugins_dir, &engine);
+        for plugin in wasm_plugins {
+            self.plugin_manager.register(plugin);
+        }
+        let lua_plugins = mc_rs_plugin_lua::load_lua_plugins(&plugins_dir);
+        for plugin in lua_plugins {
+            self.plugin_manager.register(plugin);
+        }
+        if self.server_config.plugins.native {
+            for plugin in mc_rs_plugin_native::load_native_plugins(&plugins_dir) {
+                self.plugin_manager.register(plugin);
+            }
+        }
+        let snapshot = self.build_snapshot();
+        self.plugin_manager.enable_all(&snapshot);
+        self.plugin_manager.load_configs();
+
+        let cmd_count = self.plugin_manager.plugin_commands.len();
+        format!("Plugins reloaded. {cmd_count} plugin command(s) registered.")
+    }
+
+    /// /restart [message]: save, kick everyone with the message and stop,
+    /// exiting with [`RESTART_EXIT_CODE`].
+    pub(super) async fn cmd_restart(
+        &mut self,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        let message = if args.is_empty() {
+            "Server is restarting".to_string()
+        } else {
+            args.join(" ")
+        };
+        info!("Server restart requested by {sender_name}");
+        self.save_all().await;
+        let addrs: Vec<SocketAddr> = self.connections.keys().copied().collect();
+        for addr in addrs {
+            self.disconnect_player(addr, &message).await;
+        }
+        self.restart_requested = true;
+        let _ = self.shutdown_tx.send(true);
+        CommandResult::ok("Restarting the server...")
+    }
+
+    /// Whether the server is stopping for `/restart`.
+    pub fn restart_requested(&self) -> bool {
+        self.restart_requested
+    }
+}
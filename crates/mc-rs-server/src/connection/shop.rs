@@ -0,0 +1,304 @@
+//! Chest shops: making one with a `[Shop]` sign, buying with a right click
+//! on the sign, selling with a left click, and keeping others out of the
+//! shop's chest.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::block_entity::BlockEntityData;
+use mc_rs_game::shop::{self, parse_shop_sign};
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::packets::{self, InventoryContent, Text};
+use mc_rs_proto::types::BlockPos;
+use tracing::debug;
+
+use super::ConnectionHandler;
+use crate::economy::EconomyError;
+use crate::shops::Shop;
+
+impl ConnectionHandler {
+    async fn shop_message(&mut self, addr: SocketAddr, message: impl Into<String>) {
+        self.send_packet(addr, packets::id::TEXT, &Text::raw(message))
+            .await;
+    }
+
+    /// The chest a shop sign stocks from: the one under it, or the one a
+    /// wall sign hangs on.
+    fn shop_chest_for(&self, pos: BlockPos) -> Option<BlockPos> {
+        let is_chest = |p: BlockPos| {
+            self.get_block(p.x, p.y, p.z)
+                .is_some_and(|rid| self.block_entity_hashes.is_chest(rid))
+        };
+        let below = BlockPos::new(pos.x, pos.y - 1, pos.z);
+        if is_chest(below) {
+            return Some(below);
+        }
+        let rid = self.get_block(pos.x, pos.y, pos.z)?;
+        let (nx, nz) = self.block_entity_hashes.sign_front_normal(rid)?;
+        let (dx, dz) = (nx.round() as i32, nz.round() as i32);
+        let behind = BlockPos::new(pos.x - dx, pos.y, pos.z - dz);
+        (dx.abs() + dz.abs() == 1 && is_chest(behind)).then_some(behind)
+    }
+
+    /// Turn a sign whose new text starts with `[Shop]` into a shop. Returns
+    /// the text to show instead, or `None` to keep the player's text.
+    pub(super) async fn create_shop(
+        &mut self,
+        addr: SocketAddr,
+        pos: BlockPos,
+        text: &str,
+    ) -> Option<String> {
+        let terms = match parse_shop_sign(text)? {
+            Ok(terms) => terms,
+            Err(e) => {
+                self.shop_message(addr, format!("Not a valid shop sign: {e}"))
+                    .await;
+                return None;
+            }
+        };
+        if self.item_registry.get_by_name(&terms.item).is_none() {
+            self.shop_message(addr, format!("Unknown item: {}", terms.item))
+                .await;
+            return None;
+        }
+        let Some(chest) = self.shop_chest_for(pos) else {
+            self.shop_message(addr, "Shop signs go on top of or on the side of a chest")
+                .await;
+            return None;
+        };
+        let owner = self.economy_account_id(addr)?;
+        let owner_name = self
+            .connections
+            .get(&addr)?
+            .login_data
+            .as_ref()?
+            .display_name
+            .clone();
+        let chest_key = [chest.x, chest.y, chest.z];
+        let other_owner = self
+            .shops
+            .using_chest(chest_key)
+            .find(|s| s.owner != owner)
+            .map(|s| s.owner_name.clone());
+        if let Some(other) = other_owner {
+            self.shop_message(addr, format!("This chest belongs to {other}'s shop"))
+                .await;
+            return None;
+        }
+
+        self.block_entities
+            .entry((chest.x, chest.y, chest.z, 0))
+            .or_insert_with(BlockEntityData::new_chest);
+        let text = terms.sign_text(&owner_name);
+        debug!("{owner_name} opened a shop at {pos} for {}", terms.item);
+        self.shops.add(Shop::new(
+            [pos.x, pos.y, pos.z],
+            chest_key,
+            &owner,
+            &owner_name,
+            terms,
+        ));
+        self.shop_message(addr, "Shop created").await;
+        Some(text)
+    }
+
+    /// Whether a player may open or break a block: anyone but the owner of
+    /// a shop on it is kept out, unless they are an operator.
+    pub(super) async fn may_touch_shop_block(&mut self, addr: SocketAddr, pos: BlockPos) -> bool {
+        let key = [pos.x, pos.y, pos.z];
+        let Some(owner) = self
+            .shops
+            .at_sign(key)
+            .or_else(|| self.shops.using_chest(key).next())
+            .map(|s| (s.owner.clone(), s.owner_name.clone()))
+        else {
+            return true;
+        };
+        let is_op = self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.login_data.as_ref())
+            .is_some_and(|l| self.permissions.is_op(&l.display_name));
+        if is_op || self.economy_account_id(addr).as_deref() == Some(owner.0.as_str()) {
+            return true;
+        }
+        // Punching a sign already answers with the trade
+        if self.shops.at_sign(key).is_none() {
+            self.shop_message(addr, format!("This shop belongs to {}", owner.1))
+                .await;
+        }
+        false
+    }
+
+    /// Close the shops on a block that was just broken.
+    pub(super) fn close_shops_at(&mut self, pos: BlockPos) {
+        let closed = self.shops.remove_at([pos.x, pos.y, pos.z]);
+        if closed > 0 {
+            debug!("Closed {closed} shop(s) at {pos}");
+        }
+    }
+
+    /// Right click on a shop sign: buy from the shop. Returns false if the
+    /// sign is not a shop.
+    pub(super) async fn buy_from_shop(&mut self, addr: SocketAddr, pos: BlockPos) -> bool {
+        let Some(shop) = self.shops.at_sign([pos.x, pos.y, pos.z]).cloned() else {
+            return false;
+        };
+        if let Err(message) = self.trade(addr, &shop, true) {
+            self.shop_message(addr, message).await;
+            return true;
+        }
+        self.finish_trade(addr, &shop, true).await;
+        true
+    }
+
+    /// Left click on a shop sign: sell to the shop.
+    pub(super) async fn sell_to_shop(&mut self, addr: SocketAddr, pos: BlockPos) {
+        let Some(shop) = self.shops.at_sign([pos.x, pos.y, pos.z]).cloned() else {
+            return;
+        };
+        // The owner punches their sign to break it
+        if self.economy_account_id(addr).as_deref() == Some(shop.owner.as_str()) {
+            return;
+        }
+        if let Err(message) = self.trade(addr, &shop, false) {
+            self.shop_message(addr, message).await;
+            return;
+        }
+        self.finish_trade(addr, &shop, false).await;
+    }
+
+    /// Check stock, room and money, then move the items and the money.
+    /// Nothing changes if the trade is refused.
+    fn trade(&mut self, addr: SocketAddr, shop: &Shop, buying: bool) -> Result<(), String> {
+        let player = self
+            .economy_account_id(addr)
+            .ok_or_else(|| "You have no account".to_string())?;
+        if player == shop.owner {
+            return Err("This is your shop".into());
+        }
+        let item_name = shop.item.strip_prefix("minecraft:").unwrap_or(&shop.item);
+        let price = match (buying, shop.buy, shop.sell) {
+            (true, Some(price), _) | (false, _, Some(price)) => price,
+            (true, None, _) => return Err(format!("This shop only buys {item_name}")),
+            (false, _, None) => return Err(format!("This shop only sells {item_name}")),
+        };
+        let Some(info) = self.item_registry.get_by_name(&shop.item) else {
+            return Err(format!("Unknown item: {}", shop.item));
+        };
+        let runtime_id = info.numeric_id as i32;
+        let max_stack = (info.max_stack_size as u16).max(1);
+        let [cx, cy, cz] = shop.chest;
+        let Some(BlockEntityData::Chest { items: chest }) =
+            self.block_entities.get_mut(&(cx, cy, cz, 0))
+        else {
+            return Err("This shop's chest is gone".into());
+        };
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return Err("You are not in game".into());
+        };
+        let amount = shop.amount;
+
+        let (payer, payee) = if buying {
+            if shop::count_stock(chest, runtime_id) < amount as u32 {
+                return Err("This shop is out of stock".into());
+            }
+            if shop::stock_room(&conn.inventory.main, runtime_id, max_stack) < amount as u32 {
+                return Err("Your inventory is full".into());
+            }
+            (player.as_str(), shop.owner.as_str())
+        } else {
+            if shop::count_stock(&conn.inventory.main, runtime_id) < amount as u32 {
+                return Err(format!("You need {amount} {item_name} to sell"));
+            }
+            if shop::stock_room(chest, runtime_id, max_stack) < amount as u32 {
+                return Err("This shop's chest is full".into());
+            }
+            (shop.owner.as_str(), player.as_str())
+        };
+        let reason = format!("shop at {cx} {cy} {cz}: {amount} {item_name}");
+        match self.economy.lock().transfer(payer, payee, price, &reason) {
+            Ok(()) => {}
+            Err(EconomyError::InsufficientFunds) if buying => {
+                return Err("You cannot afford this".into())
+            }
+            Err(EconomyError::InsufficientFunds) => {
+                return Err(format!("{} cannot afford to buy", shop.owner_name))
+            }
+            Err(e) => return Err(format!("Trade failed: {e}")),
+        }
+
+        if buying {
+            shop::take_stock(chest, runtime_id, amount);
+            conn.inventory
+                .add_item(&ItemStack::new(runtime_id, amount), max_stack);
+        } else {
+            shop::take_stock(&mut conn.inventory.main, runtime_id, amount);
+            let inventory = &conn.inventory;
+            shop::add_stock(chest, runtime_id, amount, max_stack, || {
+                inventory.next_stack_network_id()
+            });
+        }
+        Ok(())
+    }
+
+    /// Tell both sides about a trade and refresh the inventories involved.
+    async fn finish_trade(&mut self, addr: SocketAddr, shop: &Shop, buying: bool) {
+        let config = &self.server_config.economy;
+        let item_name = shop.item.strip_prefix("minecraft:").unwrap_or(&shop.item);
+        let goods = format!("{} {item_name}", shop.amount);
+        let price = config.format(if buying { shop.buy } else { shop.sell }.unwrap_or(0));
+        let player_name = self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.login_data.as_ref())
+            .map(|l| l.display_name.clone())
+            .unwrap_or_default();
+        let (to_player, to_owner) = if buying {
+            (
+                format!("Bought {goods} for {price}"),
+                format!("{player_name} bought {goods} from your shop for {price}"),
+            )
+        } else {
+            (
+                format!("Sold {goods} for {price}"),
+                format!("{player_name} sold {goods} to your shop for {price}"),
+            )
+        };
+        debug!("{player_name}: {to_player} at shop {:?}", shop.sign);
+
+        self.send_inventory(addr).await;
+        self.shop_message(addr, to_player).await;
+        if let Some(owner_addr) = self.find_player_addr(&shop.owner_name) {
+            self.shop_message(owner_addr, to_owner).await;
+        }
+        self.refresh_chest_viewers(shop.chest).await;
+    }
+
+    /// Resend a chest's items to the players looking into it.
+    async fn refresh_chest_viewers(&mut self, [x, y, z]: [i32; 3]) {
+        let Some(BlockEntityData::Chest { items }) = self.block_entities.get(&(x, y, z, 0)) else {
+            return;
+        };
+        let items = items.clone();
+        let viewers: Vec<(SocketAddr, u8)> = self
+            .connections
+            .iter()
+            .filter_map(|(&addr, conn)| {
+                let oc = conn.open_container.as_ref()?;
+                (oc.container_type == 0 && oc.position == BlockPos::new(x, y, z))
+                    .then_some((addr, oc.window_id))
+            })
+            .collect();
+        for (addr, window_id) in viewers {
+            self.send_packet(
+                addr,
+                packets::id::INVENTORY_CONTENT,
+                &InventoryContent {
+                    window_id: window_id as u32,
+                    items: items.clone(),
+                },
+            )
+            .await;
+        }
+    }
+}
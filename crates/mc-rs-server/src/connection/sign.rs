@@ -0,0 +1,252 @@
+//! Signs: the editor opened after placement or on interaction, text edits
+//! sent back by the client, dyes and glow ink.
+
+use std::io::Cursor;
+use std::net::SocketAddr;
+
+use mc_rs_game::block_entity::BlockEntityData;
+use mc_rs_game::sign::{is_front_side, sign_ink, SignInk};
+use mc_rs_plugin_api::{EventResult, PluginBlockPos, PluginEvent};
+use mc_rs_proto::codec::ProtoDecode;
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::packets::{self, BlockActorData, InventorySlot, OpenSign};
+use mc_rs_proto::types::BlockPos;
+use tracing::debug;
+
+use super::ConnectionHandler;
+
+/// Longest text accepted on one sign face, in bytes.
+const MAX_SIGN_TEXT_LEN: usize = 512;
+
+impl ConnectionHandler {
+    /// Create the block entity of a freshly placed sign and open its editor
+    /// on the front face.
+    pub(super) async fn place_sign(&mut self, addr: SocketAddr, pos: BlockPos) {
+        self.insert_block_entity((pos.x, pos.y, pos.z), BlockEntityData::new_sign());
+        // The client needs the block entity before it can open the editor
+        if let Some(pkt) = self.sign_packet(pos) {
+            self.send_packet(addr, packets::id::BLOCK_ACTOR_DATA, &pkt)
+                .await;
+        }
+        self.open_sign_editor(addr, pos, true).await;
+    }
+
+    /// Handle a right click on a sign. A shop sign sells to the player, a
+    /// dye or (glow) ink sac restyles the face the player is looking at,
+    /// anything else opens its editor.
+    /// Returns false if the block has no sign entity.
+    pub(super) async fn interact_sign(
+        &mut self,
+        addr: SocketAddr,
+        pos: BlockPos,
+        rid: u32,
+    ) -> bool {
+        if self.buy_from_shop(addr, pos).await {
+            return true;
+        }
+        match self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            // Waxed signs swallow the click without changing
+            Some(BlockEntityData::Sign { is_waxed: true, .. }) => return true,
+            Some(BlockEntityData::Sign { .. }) => {}
+            _ => return false,
+        }
+        let Some(normal) = self.block_entity_hashes.sign_front_normal(rid) else {
+            return false;
+        };
+        let Some(conn) = self.connections.get(&addr) else {
+            return true;
+        };
+        let front = is_front_side(normal, (pos.x, pos.z), (conn.position.x, conn.position.z));
+        let held = conn.inventory.held_item();
+        let ink = self
+            .item_registry
+            .get_by_id(held.runtime_id as i16)
+            .and_then(|info| sign_ink(&info.name));
+
+        match ink {
+            Some(ink) => self.apply_sign_ink(addr, pos, front, ink).await,
+            None => self.open_sign_editor(addr, pos, front).await,
+        }
+        true
+    }
+
+    /// Open the sign editor on one face. Only this sign accepts the text the
+    /// player sends back.
+    async fn open_sign_editor(&mut self, addr: SocketAddr, pos: BlockPos, front_side: bool) {
+        match self.connections.get_mut(&addr) {
+            Some(conn) => conn.editing_sign = Some(pos),
+            None => return,
+        }
+        self.send_packet(
+            addr,
+            packets::id::OPEN_SIGN,
+            &OpenSign {
+                position: pos,
+                front_side,
+            },
+        )
+        .await;
+    }
+
+    /// Apply a dye or ink sac to one face of a sign, using up one item
+    /// outside creative. Blank faces and no-op changes keep the item.
+    async fn apply_sign_ink(&mut self, addr: SocketAddr, pos: BlockPos, front: bool, ink: SignInk) {
+        let changed = match self.block_entities.get_mut(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::Sign {
+                front: front_face,
+                back: back_face,
+                ..
+            }) => {
+                let face = if front { front_face } else { back_face };
+                !face.text.is_empty() && face.apply_ink(ink)
+            }
+            _ => false,
+        };
+        if !changed {
+            return;
+        }
+        if let Some(pkt) = self.sign_packet(pos) {
+            self.broadcast_packet(packets::id::BLOCK_ACTOR_DATA, &pkt)
+                .await;
+        }
+
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        if conn.gamemode == 1 {
+            return;
+        }
+        let item = conn.inventory.held_item_mut();
+        item.count = item.count.saturating_sub(1);
+        if item.count == 0 {
+            *item = ItemStack::empty();
+        }
+        let updated_item = conn.inventory.held_item().clone();
+        let slot = conn.inventory.held_slot;
+        let unique_id = conn.entity_unique_id;
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_SLOT,
+            &InventorySlot {
+                window_id: 0,
+                slot: slot as u32,
+                item: updated_item.clone(),
+            },
+        )
+        .await;
+        let updated_name = self
+            .item_registry
+            .get_by_id(updated_item.runtime_id as i16)
+            .map(|i| i.name.clone())
+            .unwrap_or_default();
+        self.game_world
+            .update_player_held_item(unique_id, updated_name);
+    }
+
+    /// Handle a block actor data update from the client (sign text editing).
+    pub(super) async fn handle_block_actor_data(
+        &mut self,
+        addr: SocketAddr,
+        buf: &mut Cursor<&[u8]>,
+    ) {
+        let pkt = match BlockActorData::proto_decode(buf) {
+            Ok(p) => p,
+            Err(e) => {
+                debug!("Bad BlockActorData from {addr}: {e}");
+                return;
+            }
+        };
+
+        let pos = pkt.position;
+
+        // Only accept text for the sign whose editor this player opened
+        let editing = self
+            .connections
+            .get_mut(&addr)
+            .and_then(|c| c.editing_sign.take());
+        if editing != Some(pos) {
+            debug!("Ignoring unrequested sign edit at {pos} from {addr}");
+            return;
+        }
+
+        let (front_text, back_text) = match BlockEntityData::sign_from_network_nbt(&pkt.nbt_data) {
+            Some(t) => t,
+            None => return,
+        };
+        if front_text.len() > MAX_SIGN_TEXT_LEN || back_text.len() > MAX_SIGN_TEXT_LEN {
+            self.resend_sign(addr, pos).await;
+            return;
+        }
+
+        // Colors and glow stay server-side: only the text is taken
+        let unchanged = match self.block_entities.get(&(pos.x, pos.y, pos.z, 0)) {
+            Some(BlockEntityData::Sign {
+                front,
+                back,
+                is_waxed: false,
+            }) => front.text == front_text && back.text == back_text,
+            _ => return,
+        };
+        if unchanged {
+            return;
+        }
+
+        // Plugin event: SignChange (cancellable)
+        if let Some(conn) = self.connections.get(&addr) {
+            let event = PluginEvent::SignChange {
+                player: self.make_plugin_player(conn),
+                position: PluginBlockPos {
+                    x: pos.x,
+                    y: pos.y,
+                    z: pos.z,
+                },
+                front_text: front_text.clone(),
+                back_text: back_text.clone(),
+            };
+            let snapshot = self.build_snapshot();
+            let (result, actions) = self.plugin_manager.dispatch(&event, &snapshot);
+            self.apply_plugin_actions(actions).await;
+            if result == EventResult::Cancelled {
+                // The editor already shows the new text: put the old one back
+                self.resend_sign(addr, pos).await;
+                return;
+            }
+        }
+
+        let front_text = match self.create_shop(addr, pos, &front_text).await {
+            Some(shop_text) => shop_text,
+            None => front_text,
+        };
+        if let Some(BlockEntityData::Sign { front, back, .. }) =
+            self.block_entities.get_mut(&(pos.x, pos.y, pos.z, 0))
+        {
+            front.text = front_text;
+            back.text = back_text;
+        }
+        if let Some(pkt) = self.sign_packet(pos) {
+            self.broadcast_packet(packets::id::BLOCK_ACTOR_DATA, &pkt)
+                .await;
+        }
+
+        debug!("Sign edited at {pos} by {addr}");
+    }
+
+    /// Send a sign's stored text back to one player, undoing a rejected edit.
+    pub(super) async fn resend_sign(&mut self, addr: SocketAddr, pos: BlockPos) {
+        if let Some(pkt) = self.sign_packet(pos) {
+            self.send_packet(addr, packets::id::BLOCK_ACTOR_DATA, &pkt)
+                .await;
+        }
+    }
+
+    /// BlockActorData for the sign at a position, if there is one.
+    fn sign_packet(&self, pos: BlockPos) -> Option<BlockActorData> {
+        match self.block_entities.get(&(pos.x, pos.y, pos.z, 0))? {
+            be @ BlockEntityData::Sign { .. } => Some(BlockActorData {
+                position: pos,
+                nbt_data: be.to_network_nbt(pos.x, pos.y, pos.z),
+            }),
+            _ => None,
+        }
+    }
+}
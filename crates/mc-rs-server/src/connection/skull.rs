@@ -0,0 +1,147 @@
+//! Mob and player heads: placing them as block entities, dropping them
+//! with their owner, and `/give ... player_head{owner:...}`.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::block_entity::BlockEntityData;
+use mc_rs_game::skull::{self, SkullOwner};
+use mc_rs_nbt::tag::{NbtCompound, NbtTag};
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::packets::{self, BlockActorData};
+use mc_rs_proto::types::BlockPos;
+
+use super::ConnectionHandler;
+
+impl ConnectionHandler {
+    /// Create the block entity of a freshly placed head, taking the owner
+    /// from the held item, and show it to everyone.
+    pub(super) async fn place_head(
+        &mut self,
+        addr: SocketAddr,
+        pos: BlockPos,
+        kind: usize,
+        face: i32,
+    ) {
+        let Some(conn) = self.connections.get(&addr) else {
+            return;
+        };
+        // Only heads on the floor turn; wall heads follow their block state
+        let rotation = if face == 1 {
+            skull::rotation_from_yaw(conn.yaw)
+        } else {
+            0.0
+        };
+        let owner = SkullOwner::from_item_nbt(&conn.inventory.held_item().nbt_data);
+        let data = BlockEntityData::new_skull(kind as i8, rotation, owner);
+        let nbt = data.to_network_nbt(pos.x, pos.y, pos.z);
+        self.insert_block_entity((pos.x, pos.y, pos.z), data);
+        self.broadcast_packet(
+            packets::id::BLOCK_ACTOR_DATA,
+            &BlockActorData {
+                position: pos,
+                nbt_data: nbt,
+            },
+        )
+        .await;
+    }
+
+    /// Drop the item of a broken head, keeping a player head's owner.
+    pub(super) async fn drop_head(
+        &mut self,
+        data: &BlockEntityData,
+        pos: BlockPos,
+        dimension: i32,
+    ) {
+        let BlockEntityData::Skull {
+            skull_type, owner, ..
+        } = data
+        else {
+            return;
+        };
+        let Some(info) =
+            skull::head_item(*skull_type).and_then(|n| self.item_registry.get_by_name(n))
+        else {
+            return;
+        };
+        let mut item = ItemStack::new(info.numeric_id as i32, 1);
+        if let Some(owner) = owner {
+            item.nbt_data = owner.item_nbt();
+        }
+        let position = (pos.x as f32 + 0.5, pos.y as f32, pos.z as f32 + 0.5);
+        self.spawn_dropped_item(item, position, dimension).await;
+    }
+
+    /// Turn the `owner:"<name>"` shorthand of player head item NBT into a
+    /// `SkullOwner`, with the skin the player wears if they're online.
+    pub(super) fn resolve_head_owner(&self, c: &mut NbtCompound) {
+        let Some(NbtTag::String(name)) = c.remove("owner") else {
+            return;
+        };
+        let skin_id = self
+            .find_player_addr(&name)
+            .and_then(|addr| self.connections.get(&addr)?.client_data.as_ref())
+            .map(|cd| cd.skin_id.clone())
+            .unwrap_or_default();
+        SkullOwner { name, skin_id }.write_item_nbt(c);
+    }
+}
+
+/// Split `<item>{nbt}` off the start of `/give` arguments: the item name,
+/// the SNBT (which may run over several arguments) and what follows it.
+/// `None` if the item has no NBT attached.
+pub(super) fn split_item_nbt(args: &[String]) -> Option<(&str, String, &[String])> {
+    let first = args.first()?;
+    let brace = first.find('{')?;
+    let mut snbt = String::new();
+    let mut depth = 0i32;
+    let mut in_quotes = false;
+    for (i, arg) in args.iter().enumerate() {
+        let part = if i == 0 {
+            &first[brace..]
+        } else {
+            arg.as_str()
+        };
+        if i > 0 {
+            snbt.push(' ');
+        }
+        snbt.push_str(part);
+        for ch in part.chars() {
+            match ch {
+                '"' => in_quotes = !in_quotes,
+                '{' | '[' if !in_quotes => depth += 1,
+                '}' | ']' if !in_quotes => depth -= 1,
+                _ => {}
+            }
+        }
+        if depth <= 0 && !in_quotes {
+            return Some((&first[..brace], snbt, &args[i + 1..]));
+        }
+    }
+    Some((&first[..brace], snbt, &[]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn item_nbt_is_split_off() {
+        let a = args("player_head{owner:Steve} 2");
+        let (item, snbt, rest) = split_item_nbt(&a).unwrap();
+        assert_eq!((item, snbt.as_str()), ("player_head", "{owner:Steve}"));
+        assert_eq!(rest, &args("2")[..]);
+
+        // NBT with spaces spans arguments
+        let a = args(r#"diamond_sword{display:{Name:"Big Sword"}} 1 0"#);
+        let (item, snbt, rest) = split_item_nbt(&a).unwrap();
+        assert_eq!(item, "diamond_sword");
+        assert_eq!(snbt, r#"{display:{Name:"Big Sword"}}"#);
+        assert_eq!(rest.len(), 2);
+
+        assert!(split_item_nbt(&args("stone 5")).is_none());
+    }
+}
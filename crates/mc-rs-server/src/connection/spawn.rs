@@ -22,8 +22,7 @@ impl ConnectionHandler {
             }
         };
 
-        // Clamp to a reasonable server max (8 chunks)
-        let accepted_radius = request.chunk_radius.clamp(1, 8);
+        let accepted_radius = request.chunk_radius.clamp(1, self.max_view_distance(addr));
 
         self.send_packet(
             addr,
@@ -36,6 +35,7 @@ impl ConnectionHandler {
 
         if let Some(conn) = self.connections.get_mut(&addr) {
             conn.chunk_radius = accepted_radius;
+            conn.requested_chunk_radius = request.chunk_radius;
         }
 
         if state == LoginState::Spawning {
@@ -51,7 +51,9 @@ impl ConnectionHandler {
             )
             .await;
 
+            self.enter_section(crate::timings::CHUNKS);
             self.send_spawn_chunks(addr, accepted_radius).await;
+            self.exit_section();
 
             // Send initial inventory contents
             self.send_inventory(addr).await;
@@ -88,6 +90,7 @@ impl ConnectionHandler {
             .get(&addr)
             .map(|c| c.dimension)
             .unwrap_or(0);
+        let client_dim = self.client_dimension(dim);
 
         // Phase 1: Identify missing chunks, load from LevelDB, collect those needing generation
         let mut to_generate: Vec<(i32, i32)> = Vec::new();
@@ -101,13 +104,8 @@ impl ConnectionHandler {
                 }
                 if let Some(loaded) = self.chunk_storage.load_chunk_dim(cx, cz, dim) {
                     self.dim_chunks_mut(dim).insert((cx, cz), loaded);
-                    let be_key = block_entity_key(cx, cz);
-                    if let Some(be_data) = self.chunk_storage.get_raw(&be_key) {
-                        let entries = block_entity::parse_block_entities(&be_data);
-                        for ((bx, by, bz), data) in entries {
-                            self.insert_block_entity_dim((bx, by, bz), dim, data);
-                        }
-                    }
+                    self.load_chunk_block_entities(cx, cz, dim);
+                    self.load_chunk_actors(cx, cz, dim).await;
                 } else {
                     to_generate.push((cx, cz));
                 }
@@ -116,58 +114,30 @@ impl ConnectionHandler {
 
         // Phase 2: Generate missing chunks in parallel via spawn_blocking
         if !to_generate.is_empty() {
-            let gen_overworld = self.overworld_generator.clone();
-            let gen_nether = self.nether_generator.clone();
-            let gen_end = self.end_generator.clone();
-            let flat_blocks = self.flat_world_blocks;
+            let generator = self.chunk_generator(dim);
 
             let handles: Vec<_> = to_generate
                 .iter()
                 .map(|&(cx, cz)| {
-                    let ow = gen_overworld.clone();
-                    let neth = gen_nether.clone();
-                    let end = gen_end.clone();
-                    let fb = flat_blocks;
-                    let d = dim;
+                    let generator = generator.clone();
                     tokio::task::spawn_blocking(move || {
-                        let mut col = match d {
-                            1 => {
-                                if let Some(ref g) = neth {
-                                    g.generate_chunk(cx, cz)
-                                } else {
-                                    generate_flat_chunk(cx, cz, &fb)
-                                }
-                            }
-                            2 => {
-                                if let Some(ref g) = end {
-                                    g.generate_chunk(cx, cz)
-                                } else {
-                                    generate_flat_chunk(cx, cz, &fb)
-                                }
-                            }
-                            _ => {
-                                if let Some(ref g) = ow {
-                                    g.generate_chunk(cx, cz)
-                                } else {
-                                    generate_flat_chunk(cx, cz, &fb)
-                                }
-                            }
-                        };
+                        let (mut col, structures) = generator.generate(cx, cz);
                         col.dirty = true;
-                        (cx, cz, col)
+                        (cx, cz, col, structures)
                     })
                 })
                 .collect();
 
             for handle in handles {
-                if let Ok((cx, cz, column)) = handle.await {
-                    self.dim_chunks_mut(dim).insert((cx, cz), column);
+                if let Ok((cx, cz, column, structures)) = handle.await {
+                    self.insert_generated_chunk(dim, cx, cz, column, &structures);
                 }
             }
         }
 
         // Phase 3: Serialize and send all chunks
         let mut count = 0u32;
+        let mut sent = Vec::new();
         for cx in -radius..=radius {
             for cz in -radius..=radius {
                 let column = self.dim_chunks_mut(dim).get_mut(&(cx, cz)).unwrap();
@@ -176,7 +146,7 @@ impl ConnectionHandler {
                 let level_chunk = LevelChunk {
                     chunk_x: cx,
                     chunk_z: cz,
-                    dimension_id: dim,
+                    dimension_id: client_dim,
                     sub_chunk_count,
                     cache_enabled: false,
                     payload: Bytes::from(payload),
@@ -206,12 +176,10 @@ impl ConnectionHandler {
                     }
                 }
 
-                // Track sent chunk
-                if let Some(conn) = self.connections.get_mut(&addr) {
-                    conn.sent_chunks.insert((cx, cz));
-                }
+                sent.push((cx, cz));
             }
         }
+        self.mark_chunks_sent(addr, dim, &sent).await;
         debug!("Sent {count} LevelChunk packets to {addr}");
     }
 
@@ -222,6 +190,12 @@ impl ConnectionHandler {
 
     /// Send new chunks around the player's current position that haven't been sent yet.
     pub(super) async fn send_new_chunks(&mut self, addr: SocketAddr) {
+        self.enter_section(crate::timings::CHUNKS);
+        self.stream_new_chunks(addr).await;
+        self.exit_section();
+    }
+
+    async fn stream_new_chunks(&mut self, addr: SocketAddr) {
         let (center_x, center_z, radius, dim) = match self.connections.get(&addr) {
             Some(c) => {
                 let cx = Self::chunk_coord(c.position.x);
@@ -230,6 +204,7 @@ impl ConnectionHandler {
             }
             None => return,
         };
+        let client_dim = self.client_dimension(dim);
 
         // Send NetworkChunkPublisherUpdate with the player's block position
         let player_block_pos = match self.connections.get(&addr) {
@@ -281,13 +256,8 @@ impl ConnectionHandler {
             }
             if let Some(loaded) = self.chunk_storage.load_chunk_dim(cx, cz, dim) {
                 self.dim_chunks_mut(dim).insert((cx, cz), loaded);
-                let be_key = block_entity_key(cx, cz);
-                if let Some(be_data) = self.chunk_storage.get_raw(&be_key) {
-                    let entries = block_entity::parse_block_entities(&be_data);
-                    for ((bx, by, bz), data) in entries {
-                        self.insert_block_entity_dim((bx, by, bz), dim, data);
-                    }
-                }
+                self.load_chunk_block_entities(cx, cz, dim);
+                self.load_chunk_actors(cx, cz, dim).await;
             } else {
                 to_generate.push((cx, cz));
             }
@@ -295,10 +265,7 @@ impl ConnectionHandler {
 
         // Phase 2: Generate missing chunks in parallel (limited to 4 concurrent)
         if !to_generate.is_empty() {
-            let gen_overworld = self.overworld_generator.clone();
-            let gen_nether = self.nether_generator.clone();
-            let gen_end = self.end_generator.clone();
-            let flat_blocks = self.flat_world_blocks;
+            let generator = self.chunk_generator(dim);
 
             // Limit to 4 parallel generations per tick
             let batch_size = to_generate.len().min(4);
@@ -307,44 +274,18 @@ impl ConnectionHandler {
             let handles: Vec<_> = batch
                 .iter()
                 .map(|&(cx, cz)| {
-                    let ow = gen_overworld.clone();
-                    let neth = gen_nether.clone();
-                    let end = gen_end.clone();
-                    let fb = flat_blocks;
-                    let d = dim;
+                    let generator = generator.clone();
                     tokio::task::spawn_blocking(move || {
-                        let mut col = match d {
-                            1 => {
-                                if let Some(ref g) = neth {
-                                    g.generate_chunk(cx, cz)
-                                } else {
-                                    generate_flat_chunk(cx, cz, &fb)
-                                }
-                            }
-                            2 => {
-                                if let Some(ref g) = end {
-                                    g.generate_chunk(cx, cz)
-                                } else {
-                                    generate_flat_chunk(cx, cz, &fb)
-                                }
-                            }
-                            _ => {
-                                if let Some(ref g) = ow {
-                                    g.generate_chunk(cx, cz)
-                                } else {
-                                    generate_flat_chunk(cx, cz, &fb)
-                                }
-                            }
-                        };
+                        let (mut col, structures) = generator.generate(cx, cz);
                         col.dirty = true;
-                        (cx, cz, col)
+                        (cx, cz, col, structures)
                     })
                 })
                 .collect();
 
             for handle in handles {
-                if let Ok((cx, cz, column)) = handle.await {
-                    self.dim_chunks_mut(dim).insert((cx, cz), column);
+                if let Ok((cx, cz, column, structures)) = handle.await {
+                    self.insert_generated_chunk(dim, cx, cz, column, &structures);
                 }
             }
         }
@@ -357,7 +298,7 @@ impl ConnectionHandler {
                 let level_chunk = LevelChunk {
                     chunk_x: cx,
                     chunk_z: cz,
-                    dimension_id: dim,
+                    dimension_id: client_dim,
                     sub_chunk_count,
                     cache_enabled: false,
                     payload: Bytes::from(payload),
@@ -382,6 +323,8 @@ impl ConnectionHandler {
                         .await;
                     }
                 }
+
+                self.spawn_npcs_in_chunk(addr, dim, (cx, cz)).await;
             }
         }
 
@@ -391,11 +334,7 @@ impl ConnectionHandler {
             .filter(|key| self.dim_chunks(dim).is_some_and(|m| m.contains_key(key)))
             .copied()
             .collect();
-        if let Some(conn) = self.connections.get_mut(&addr) {
-            for key in loaded_keys {
-                conn.sent_chunks.insert(key);
-            }
-        }
+        self.mark_chunks_sent(addr, dim, &loaded_keys).await;
 
         debug!("Sent {} new LevelChunk packets to {addr}", to_send.len());
     }
@@ -436,27 +375,37 @@ impl ConnectionHandler {
         self.broadcast_add_player(addr).await;
         // 5. Send AddActor for all existing mobs to the new player
         self.send_existing_mobs_to(addr).await;
-        // 6. Sync active projectiles (arrows, tridents) to the new player
+        // 6. Sync active projectiles (arrows, tridents), dropped items and XP orbs to the new player
         self.sync_projectiles_to_player(addr).await;
+        self.sync_drops_to_player(addr).await;
+        self.sync_command_block_minecarts_to_player(addr).await;
+        self.sync_holograms_to_player(addr).await;
+        self.send_existing_emote_lists_to(addr).await;
+        // StartGame carries neither spectator flags nor op levels: send the
+        // player's abilities, and hide spectators from mobs
+        self.send_abilities(addr).await;
+        self.apply_gamemode_state(addr).await;
 
         // 7. Send initial health + hunger + XP attributes so the client HUD shows correctly
-        let (rid, hp, food, sat, exh, xl, xt) = match self.connections.get(&addr) {
-            Some(c) => (
-                c.entity_runtime_id,
-                c.health,
-                c.food as f32,
-                c.saturation,
-                c.exhaustion,
-                c.xp_level,
-                c.xp_total,
-            ),
+        let rid = match self.connections.get(&addr) {
+            Some(c) => c.entity_runtime_id,
             None => return,
         };
-        let xp_progress = xp::xp_progress(xl, xt);
+        let (hp, hunger, xp) = (self.health(addr), self.hunger(addr), self.experience(addr));
+        let xp_progress = xp::xp_progress(xp.level, xp.total);
         self.send_packet(
             addr,
             packets::id::UPDATE_ATTRIBUTES,
-            &UpdateAttributes::all(rid, hp, food, sat, exh, xl, xp_progress, 0),
+            &UpdateAttributes::all(
+                rid,
+                hp,
+                hunger.food as f32,
+                hunger.saturation,
+                hunger.exhaustion,
+                xp.level,
+                xp_progress,
+                0,
+            ),
         )
         .await;
 
@@ -468,14 +417,7 @@ impl ConnectionHandler {
             .unwrap_or_default();
 
         // 7. Send current time
-        self.send_packet(
-            addr,
-            packets::id::SET_TIME,
-            &SetTime {
-                time: self.world_time as i32,
-            },
-        )
-        .await;
+        self.send_world_time(addr).await;
 
         // 8. Sync current weather state
         if self.is_raining {
@@ -490,15 +432,21 @@ impl ConnectionHandler {
         // 9. Broadcast join message
         let join_msg = Text::system(format!("{name} joined the game"));
         self.broadcast_packet(packets::id::TEXT, &join_msg).await;
+        self.send_optional_pack_prompt(addr).await;
+
+        self.open_economy_account(addr);
 
         // 10. Plugin event: PlayerJoin
         if let Some(conn) = self.connections.get(&addr) {
-            let player = Self::make_plugin_player(conn);
+            let player = self.make_plugin_player(conn);
             let event = PluginEvent::PlayerJoin { player };
             let snapshot = self.build_snapshot();
             let (_, actions) = self.plugin_manager.dispatch(&event, &snapshot);
             self.apply_plugin_actions(actions).await;
         }
+        self.bridge.post(BridgeEvent::Join {
+            player: name.clone(),
+        });
 
         info!(
             "Player {name} is now in-game ({addr}, runtime_id={})",
@@ -509,23 +457,33 @@ impl ConnectionHandler {
     /// Remove chunks from `sent_chunks` that are outside the player's view radius.
     /// The client handles visual unloading via `NetworkChunkPublisherUpdate.radius`,
     /// this just prevents the tracking `HashSet` from growing indefinitely.
-    pub(super) fn cleanup_sent_chunks(&mut self, addr: SocketAddr) {
-        let (center_x, center_z, radius) = match self.connections.get(&addr) {
+    /// NPCs standing in the dropped chunks are removed.
+    pub(super) async fn cleanup_sent_chunks(&mut self, addr: SocketAddr) {
+        let (center_x, center_z, radius, dim) = match self.connections.get(&addr) {
             Some(c) => (
                 Self::chunk_coord(c.position.x),
                 Self::chunk_coord(c.position.z),
                 c.chunk_radius,
+                c.dimension,
             ),
             None => return,
         };
 
         // Keep a margin of 2 chunks beyond the render radius
         let unload_radius = radius + 2;
+        let mut dropped = Vec::new();
         if let Some(conn) = self.connections.get_mut(&addr) {
             conn.sent_chunks.retain(|&(cx, cz)| {
-                (cx - center_x).abs() <= unload_radius && (cz - center_z).abs() <= unload_radius
+                let keep = (cx - center_x).abs() <= unload_radius
+                    && (cz - center_z).abs() <= unload_radius;
+                if !keep {
+                    dropped.push((cx, cz));
+                }
+                keep
             });
         }
+        self.despawn_npcs_in_chunks(addr, dim, &dropped).await;
+        self.chunks_left_view(addr, dim, &dropped).await;
     }
 
     /// Send PlayerList(Add) with all existing InGame players to a newly joined player.
@@ -598,7 +556,7 @@ impl ConnectionHandler {
 
     /// Send AddPlayer for each existing InGame player to a newly joined player.
     async fn send_existing_add_players_to(&mut self, new_addr: SocketAddr) {
-        let ops = &self.permissions.ops;
+        let permissions = &self.permissions;
         let players: Vec<AddPlayer> = self
             .connections
             .iter()
@@ -612,7 +570,8 @@ impl ConnectionHandler {
                     .get_slot(0, conn.inventory.held_slot)
                     .cloned()
                     .unwrap_or_else(mc_rs_proto::item_stack::ItemStack::empty);
-                let is_op = ops.contains(&login.display_name);
+                let (permission_level, command_permission_level) =
+                    permissions.ability_levels(&login.display_name);
                 Some(AddPlayer {
                     uuid,
                     username: login.display_name.clone(),
@@ -625,10 +584,10 @@ impl ConnectionHandler {
                     head_yaw: conn.head_yaw,
                     held_item,
                     gamemode: conn.gamemode,
-                    metadata: default_player_metadata(&login.display_name),
+                    metadata: self.player_metadata(conn, &login.display_name),
                     entity_unique_id: conn.entity_unique_id,
-                    permission_level: if is_op { 2 } else { 1 },
-                    command_permission_level: if is_op { 1 } else { 0 },
+                    permission_level,
+                    command_permission_level,
                     device_id: client_data.device_id,
                     device_os: client_data.device_os,
                 })
@@ -645,11 +604,10 @@ impl ConnectionHandler {
     async fn send_existing_mobs_to(&mut self, addr: SocketAddr) {
         let mobs = self.game_world.all_mobs();
         for mob in mobs {
-            let metadata = if mob.is_baby {
-                baby_mob_metadata(mob.bb_width, mob.bb_height)
-            } else {
-                default_mob_metadata(mob.bb_width, mob.bb_height)
-            };
+            let metadata = self.with_appearance(
+                mob.runtime_id,
+                mob_metadata(mob.bb_width, mob.bb_height, mob.is_baby),
+            );
             let pkt = AddActor {
                 entity_unique_id: mob.unique_id,
                 entity_runtime_id: mob.runtime_id,
@@ -670,6 +628,11 @@ impl ConnectionHandler {
                 metadata,
             };
             self.send_packet(addr, packets::id::ADD_ACTOR, &pkt).await;
+            if mob.has_helmet {
+                let armor = self.helmet_armor(mob.runtime_id, true);
+                self.send_packet(addr, packets::id::MOB_ARMOR_EQUIPMENT, &armor)
+                    .await;
+            }
         }
     }
 
@@ -691,7 +654,8 @@ impl ConnectionHandler {
                 .get_slot(0, conn.inventory.held_slot)
                 .cloned()
                 .unwrap_or_else(mc_rs_proto::item_stack::ItemStack::empty);
-            let is_op = self.permissions.ops.contains(&login.display_name);
+            let (permission_level, command_permission_level) =
+                self.permissions.ability_levels(&login.display_name);
             AddPlayer {
                 uuid,
                 username: login.display_name.clone(),
@@ -704,10 +668,10 @@ impl ConnectionHandler {
                 head_yaw: conn.head_yaw,
                 held_item,
                 gamemode: conn.gamemode,
-                metadata: default_player_metadata(&login.display_name),
+                metadata: self.player_metadata(conn, &login.display_name),
                 entity_unique_id: conn.entity_unique_id,
-                permission_level: if is_op { 2 } else { 1 },
-                command_permission_level: if is_op { 1 } else { 0 },
+                permission_level,
+                command_permission_level,
                 device_id: client_data.device_id,
                 device_os: client_data.device_os,
             }
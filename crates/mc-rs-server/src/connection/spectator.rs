@@ -0,0 +1,105 @@
+//! Spectator mode: flying through blocks, unseen by mobs, unable to touch
+//! the world, and clicking another player to jump to them.
+
+use std::net::SocketAddr;
+
+use mc_rs_proto::packets::{self, Text, UpdateAbilities};
+use mc_rs_proto::types::Vec3;
+
+use super::{ConnectionHandler, LoginState};
+
+/// Gamemode ID of spectator mode.
+pub(super) const SPECTATOR: i32 = 3;
+
+impl ConnectionHandler {
+    /// Whether a player is in spectator mode.
+    pub(super) fn is_spectator(&self, addr: SocketAddr) -> bool {
+        self.connections
+            .get(&addr)
+            .is_some_and(|c| c.gamemode == SPECTATOR)
+    }
+
+    /// Send a player the abilities of their gamemode and permission level.
+    pub(super) async fn send_abilities(&mut self, addr: SocketAddr) {
+        let Some(conn) = self.connections.get(&addr) else {
+            return;
+        };
+        let (permission_level, command_permission_level) = conn
+            .login_data
+            .as_ref()
+            .map_or((1, 0), |d| self.permissions.ability_levels(&d.display_name));
+        let pkt = UpdateAbilities {
+            command_permission_level,
+            permission_level,
+            entity_unique_id: conn.entity_unique_id,
+            gamemode: conn.gamemode,
+        };
+        self.send_packet(addr, packets::id::UPDATE_ABILITIES, &pkt)
+            .await;
+    }
+
+    /// Bring server-side state in line with a player's gamemode: mobs ignore
+    /// spectators, and whatever a spectator was doing with the world stops.
+    pub(super) async fn apply_gamemode_state(&mut self, addr: SocketAddr) {
+        if self.is_spectator(addr) {
+            self.stop_breaking(addr).await;
+        }
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        let spectator = conn.gamemode == SPECTATOR;
+        if spectator {
+            conn.eating_since = None;
+            conn.sleeping = None;
+            conn.fire_ticks = 0;
+        }
+        let unique_id = conn.entity_unique_id;
+        self.game_world
+            .set_player_ignored_by_mobs(unique_id, spectator);
+    }
+
+    /// A spectator clicked an entity: if it is a player, teleport to them.
+    pub(super) async fn spectate_player(&mut self, addr: SocketAddr, target_runtime_id: u64) {
+        let Some(target_addr) = self.runtime_id_to_addr.get(&target_runtime_id).copied() else {
+            return;
+        };
+        let Some(target) = self
+            .connections
+            .get(&target_addr)
+            .filter(|c| c.state == LoginState::InGame && target_addr != addr)
+        else {
+            return;
+        };
+        let (target_dim, pos) = (target.dimension, target.position);
+        let target_name = target
+            .login_data
+            .as_ref()
+            .map(|d| d.display_name.clone())
+            .unwrap_or_default();
+        let Some(conn) = self.connections.get(&addr) else {
+            return;
+        };
+        let (src_dim, entity_unique_id) = (conn.dimension, conn.entity_unique_id);
+        let name = conn
+            .login_data
+            .as_ref()
+            .map(|d| d.display_name.clone())
+            .unwrap_or_default();
+
+        if target_dim == src_dim {
+            self.teleport_player(addr, &name, pos.x, pos.y, pos.z).await;
+        } else {
+            self.execute_dimension_change(
+                addr,
+                src_dim,
+                target_dim,
+                Vec3::new(pos.x, pos.y, pos.z),
+                entity_unique_id,
+            )
+            .await;
+        }
+        let msg = format!("Spectating {target_name}");
+        self.send_packet(addr, packets::id::TEXT, &Text::raw(msg))
+            .await;
+    }
+}
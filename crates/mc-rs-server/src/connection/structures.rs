@@ -0,0 +1,107 @@
+//! Generated structures: recording the ones new chunks bring in,
+//! `/locate structure` and explorer maps.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::packets::clientbound_map_item_data::{MAP_DECORATION_VILLAGE, MAP_DECORATION_X};
+use mc_rs_world::chunk::ChunkColumn;
+use mc_rs_world::storage::STRUCTURES_KEY;
+use mc_rs_world::structure::{StructureBounds, StructureKind};
+use tracing::warn;
+
+use super::ConnectionHandler;
+
+/// Scale of explorer maps (one pixel = 4×4 blocks).
+const EXPLORER_MAP_SCALE: u8 = 2;
+
+/// Map decoration marking a kind of structure on explorer maps.
+fn structure_decoration(kind: StructureKind) -> u8 {
+    match kind {
+        StructureKind::Village => MAP_DECORATION_VILLAGE,
+        StructureKind::Dungeon | StructureKind::PillagerOutpost => MAP_DECORATION_X,
+    }
+}
+
+impl ConnectionHandler {
+    /// Add a chunk that was just generated, recording the structures placed
+    /// in it.
+    pub(super) fn insert_generated_chunk(
+        &mut self,
+        dim: i32,
+        cx: i32,
+        cz: i32,
+        column: ChunkColumn,
+        structures: &[StructureBounds],
+    ) {
+        self.structures.add(dim, structures);
+        for bounds in structures {
+            if bounds.kind == StructureKind::PillagerOutpost {
+                self.populate_outpost(bounds);
+            }
+        }
+        self.dim_chunks_mut(dim).insert((cx, cz), column);
+    }
+
+    /// Write the structure registry if it changed.
+    pub(super) fn save_structures(&mut self) {
+        if !self.structures.dirty {
+            return;
+        }
+        match self
+            .chunk_storage
+            .put_raw(STRUCTURES_KEY, &self.structures.serialize())
+        {
+            Ok(()) => self.structures.dirty = false,
+            Err(e) => warn!("Failed to save structures: {e}"),
+        }
+    }
+
+    /// `/locate structure <structure> [map]`: the nearest structure of a
+    /// kind generated so far, from the sender or from the world spawn on the
+    /// console. With `map`, the sender also gets an explorer map marking it.
+    pub(super) async fn cmd_locate_structure(
+        &mut self,
+        addr: SocketAddr,
+        args: &[String],
+    ) -> CommandResult {
+        const USAGE: &str = "Usage: /locate structure <structure> [map]";
+        let (name, with_map) = match args {
+            [name] => (name, false),
+            [name, map] if map == "map" => (name, true),
+            _ => return CommandResult::err(USAGE),
+        };
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        let Some(kind) = StructureKind::from_name(name) else {
+            return CommandResult::err(format!("Unknown structure: {name}"));
+        };
+        let (dim, origin) = match self.connections.get(&addr) {
+            Some(conn) => (conn.dimension, conn.position),
+            None if with_map => return CommandResult::err("Only players can get an explorer map"),
+            None => (0, self.spawn_position),
+        };
+        let (x, z) = (origin.x.floor() as i32, origin.z.floor() as i32);
+        let Some(bounds) = self.structures.nearest(dim, kind, x, z) else {
+            return CommandResult::err(format!(
+                "Could not find a {name} in the chunks generated so far"
+            ));
+        };
+        let (bx, bz) = bounds.center();
+        let distance = ((bx - x) as f64).hypot((bz - z) as f64).round();
+        let mut result = CommandResult::ok(format!(
+            "The nearest {name} is at {bx}, ~, {bz} ({distance} blocks away)"
+        ));
+        if with_map {
+            let decoration = structure_decoration(kind);
+            if self
+                .give_explorer_map(addr, (bx, bz), EXPLORER_MAP_SCALE, decoration)
+                .await
+            {
+                result
+                    .messages
+                    .push(format!("Gave an explorer map to the {name}"));
+            }
+        }
+        result
+    }
+}
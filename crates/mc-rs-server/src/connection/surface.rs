@@ -0,0 +1,61 @@
+//! Block surfaces: soul sand and honey slowing players and mobs down, ice
+//! and slime letting them slide, for movement validation and mob physics.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::armor;
+use mc_rs_game::combat as game_combat;
+use mc_rs_world::physics::{self, Surface, MAX_FALL_PER_TICK};
+
+use super::ConnectionHandler;
+
+impl ConnectionHandler {
+    /// Surface of the block under feet at this height. Air and unloaded
+    /// blocks are plain ground.
+    pub(super) fn surface_under(&self, dim: i32, x: f32, feet_y: f32, z: f32) -> Surface {
+        self.get_block_in(
+            dim,
+            x.floor() as i32,
+            (feet_y - 0.01).floor() as i32,
+            z.floor() as i32,
+        )
+        .and_then(|hash| self.block_registry.get(hash))
+        .map_or(Surface::Normal, |info| Surface::of(info.name))
+    }
+
+    /// How far a player may move on land in a tick, over the surface under
+    /// them now and the one they last stood on, with their Soul Speed boots.
+    pub(super) fn max_move_distance(&self, addr: SocketAddr, base: f32, now: Surface) -> f32 {
+        let Some(conn) = self.connections.get(&addr) else {
+            return base;
+        };
+        let soul_speed =
+            game_combat::has_soul_speed(&conn.inventory.armor[armor::SLOT_FEET].nbt_data);
+        physics::max_move_distance(base, now, conn.last_surface, soul_speed)
+    }
+
+    /// Tell the game world what each mob is over: the first solid block
+    /// under its feet, within the distance it can fall in a tick, so that it
+    /// lands on slime with a bounce.
+    pub(super) fn tick_mob_surfaces(&mut self) {
+        let surfaces: Vec<(u64, Surface)> = self
+            .game_world
+            .all_mobs()
+            .into_iter()
+            .map(|mob| {
+                let (x, y, z) = mob.position;
+                let (bx, bz) = (x.floor() as i32, z.floor() as i32);
+                let top = (y - 0.01).floor() as i32;
+                let surface = (0..=MAX_FALL_PER_TICK as i32)
+                    .filter_map(|down| self.get_block_in(0, bx, top - down, bz))
+                    .find(|&hash| self.block_registry.is_solid(hash))
+                    .and_then(|hash| self.block_registry.get(hash))
+                    .map_or(Surface::Normal, |info| Surface::of(info.name));
+                (mob.runtime_id, surface)
+            })
+            .collect();
+        for (runtime_id, surface) in surfaces {
+            self.game_world.set_mob_surface(runtime_id, surface);
+        }
+    }
+}
@@ -1,11 +1,24 @@
 use super::*;
 
+use mc_rs_game::{effects, food};
+
+use crate::world_flags::WorldFlag;
+
+/// Leeway (ticks) allowed for network jitter when validating eating duration.
+const EAT_TOLERANCE_TICKS: u64 = 4;
+
+/// Ticks between eating particle/sound broadcasts.
+const EAT_EFFECT_INTERVAL: u64 = 4;
+
 impl ConnectionHandler {
     // ------------------------------------------------------------------
     // Status effect management
     // ------------------------------------------------------------------
 
     /// Apply a status effect to a player, sending the MobEffect packet.
+    ///
+    /// An active effect of the same type is only replaced by a higher amplifier
+    /// or, at the same amplifier, a longer duration.
     pub(super) async fn apply_effect(
         &mut self,
         addr: SocketAddr,
@@ -13,19 +26,19 @@ impl ConnectionHandler {
         amplifier: i32,
         duration_ticks: i32,
     ) {
-        let runtime_id = match self.connections.get_mut(&addr) {
-            Some(conn) => {
-                // Remove existing effect of same type
-                conn.effects.retain(|e| e.effect_id != effect_id);
-                conn.effects.push(ActiveEffect {
-                    effect_id,
-                    amplifier,
-                    remaining_ticks: duration_ticks,
-                });
-                conn.entity_runtime_id
-            }
+        let (uid, runtime_id) = match self.connections.get(&addr) {
+            Some(conn) => (conn.entity_unique_id, conn.entity_runtime_id),
             None => return,
         };
+        let Some(entity) = self.game_world.player_entity(uid) else {
+            return;
+        };
+        if !self
+            .game_world
+            .add_effect(entity, effect_id, amplifier, duration_ticks)
+        {
+            return;
+        }
 
         self.send_packet(
             addr,
@@ -36,15 +49,14 @@ impl ConnectionHandler {
     }
 
     /// Remove a status effect from a player, sending the MobEffect(remove) packet.
-    #[allow(dead_code)]
     pub(super) async fn remove_effect(&mut self, addr: SocketAddr, effect_id: i32) {
-        let runtime_id = match self.connections.get_mut(&addr) {
-            Some(conn) => {
-                conn.effects.retain(|e| e.effect_id != effect_id);
-                conn.entity_runtime_id
-            }
+        let (uid, runtime_id) = match self.connections.get(&addr) {
+            Some(conn) => (conn.entity_unique_id, conn.entity_runtime_id),
             None => return,
         };
+        if let Some(entity) = self.game_world.player_entity(uid) {
+            self.game_world.remove_effect(entity, effect_id);
+        }
 
         self.send_packet(
             addr,
@@ -56,12 +68,12 @@ impl ConnectionHandler {
 
     /// Remove all status effects from a player.
     pub(super) async fn clear_effects(&mut self, addr: SocketAddr) {
-        let (runtime_id, effect_ids) = match self.connections.get_mut(&addr) {
-            Some(conn) => {
-                let ids: Vec<i32> = conn.effects.iter().map(|e| e.effect_id).collect();
-                conn.effects.clear();
-                (conn.entity_runtime_id, ids)
-            }
+        let (uid, runtime_id) = match self.connections.get(&addr) {
+            Some(conn) => (conn.entity_unique_id, conn.entity_runtime_id),
+            None => return,
+        };
+        let effect_ids = match self.game_world.player_entity(uid) {
+            Some(entity) => self.game_world.clear_effects(entity),
             None => return,
         };
 
@@ -75,7 +87,8 @@ impl ConnectionHandler {
         }
     }
 
-    /// Tick all active effects for all players. Called once per game tick (50ms).
+    /// Tick fire and all active effects for all players, through the status
+    /// effect system mobs use. Called once per game tick (50ms).
     pub(super) async fn tick_effects(&mut self) {
         // Collect addresses of all in-game players
         let addrs: Vec<SocketAddr> = self
@@ -86,15 +99,15 @@ impl ConnectionHandler {
             .collect();
 
         for addr in addrs {
+            // Tick fire damage (skip if Fire Resistance is active)
+            let has_fire_res = effects::has(
+                self.effects(addr),
+                mc_rs_proto::packets::mob_effect::effect_id::FIRE_RESISTANCE,
+            );
             let conn = match self.connections.get_mut(&addr) {
                 Some(c) => c,
                 None => continue,
             };
-
-            // Tick fire damage (skip if Fire Resistance is active)
-            let has_fire_res = conn.effects.iter().any(|e| {
-                e.effect_id == mc_rs_proto::packets::mob_effect::effect_id::FIRE_RESISTANCE
-            });
             if conn.fire_ticks > 0 {
                 conn.fire_ticks -= 1;
                 if !has_fire_res && conn.fire_ticks % 20 == 0 && conn.fire_ticks >= 0 {
@@ -107,10 +120,9 @@ impl ConnectionHandler {
                         .collect();
                     let fp_reduction = game_combat::fire_protection_reduction(&nbt);
                     let fire_dmg = 1.0 * (1.0 - fp_reduction);
-                    conn.health = (conn.health - fire_dmg).max(0.0);
                     let rid = conn.entity_runtime_id;
-                    let hp = conn.health;
                     let tick = conn.client_tick;
+                    let hp = self.change_health(addr, -fire_dmg);
                     self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
                         .await;
                     self.send_packet(
@@ -136,43 +148,296 @@ impl ConnectionHandler {
                 }
             }
 
-            // Tick effect durations
-            let mut expired = Vec::new();
-            if let Some(conn) = self.connections.get_mut(&addr) {
-                for effect in &mut conn.effects {
-                    effect.remaining_ticks -= 1;
-                    if effect.remaining_ticks <= 0 {
-                        expired.push(effect.effect_id);
-                    }
+            // Periodic effects (poison, regeneration, hunger) and durations
+            let (rid, tick, vulnerable, entity) = match self.connections.get(&addr) {
+                Some(c) => (
+                    c.entity_runtime_id,
+                    c.client_tick,
+                    c.gamemode == 0 || c.gamemode == 2,
+                    self.game_world.player_entity(c.entity_unique_id),
+                ),
+                None => continue,
+            };
+            let Some(entity) = entity else {
+                continue;
+            };
+            let result = self.game_world.tick_entity_effects(entity, vulnerable);
+            if result.health_changed {
+                let hp = self.health(addr);
+                if result.hurt {
+                    self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
+                        .await;
                 }
-                conn.effects.retain(|e| e.remaining_ticks > 0);
+                self.send_packet(
+                    addr,
+                    packets::id::UPDATE_ATTRIBUTES,
+                    &UpdateAttributes::health(rid, hp, tick),
+                )
+                .await;
             }
 
             // Send remove packets for expired effects
-            if let Some(conn) = self.connections.get(&addr) {
-                let rid = conn.entity_runtime_id;
-                for eid in expired {
-                    self.send_packet(addr, packets::id::MOB_EFFECT, &MobEffect::remove(rid, eid))
-                        .await;
-                }
+            for eid in result.expired {
+                self.send_packet(addr, packets::id::MOB_EFFECT, &MobEffect::remove(rid, eid))
+                    .await;
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // Food & eating
+    // ------------------------------------------------------------------
+
+    /// Handle a use (ClickAir) of a held food item. The first use starts eating;
+    /// the next use finishes the meal once the eat duration has elapsed.
+    pub(super) async fn use_food(&mut self, addr: SocketAddr, item_name: &str) {
+        let food_level = self.hunger(addr).food;
+        let (eating, gamemode) = match self.connections.get(&addr) {
+            Some(c) => (c.eating_since, c.gamemode),
+            None => return,
+        };
+        if eating.is_some() {
+            self.finish_eating(addr).await;
+            return;
+        }
+        let can_always_eat = food::can_always_eat(item_name)
+            || self
+                .custom_items
+                .food(item_name)
+                .is_some_and(|f| f.can_always_eat);
+        if food_level >= 20 && gamemode != 1 && !can_always_eat {
+            return;
+        }
+        let tick = self.game_world.current_tick();
+        if let Some(conn) = self.connections.get_mut(&addr) {
+            conn.eating_since = Some(tick);
+        }
+    }
+
+    /// Finish eating the held food item: restore hunger, consume the item and
+    /// roll its status effects. Rejected (and resynced) if the player ate too fast.
+    pub(super) async fn finish_eating(&mut self, addr: SocketAddr) {
+        let tick = self.game_world.current_tick();
+        let (since, slot, item) = match self.connections.get_mut(&addr) {
+            Some(c) => (
+                c.eating_since.take(),
+                c.inventory.held_slot as usize,
+                c.inventory.held_item().clone(),
+            ),
+            None => return,
+        };
+        let Some(since) = since else {
+            return;
+        };
+        let Some(name) = self
+            .item_registry
+            .get_by_id(item.runtime_id as i16)
+            .map(|info| info.name.clone())
+        else {
+            return;
+        };
+        // Behavior pack foods override the vanilla tables
+        let custom = self.custom_items.get(&name);
+        let fd = self
+            .custom_items
+            .food_data(&name)
+            .or_else(|| food::food_data(&name));
+        let is_milk = food::is_milk(&name);
+        if fd.is_none() && !is_milk {
+            return;
+        }
+        let duration = custom
+            .and_then(|i| i.use_duration_ticks)
+            .unwrap_or_else(|| food::eat_duration_ticks(&name));
+        let leftover_name = match custom.and_then(|i| i.food.as_ref()) {
+            Some(f) => f.using_converts_to.as_deref(),
+            None => food::leftover_item(&name),
+        };
+
+        let too_fast = tick.saturating_sub(since) + EAT_TOLERANCE_TICKS < duration;
+        if !too_fast {
+            let leftover = leftover_name
+                .and_then(|n| self.item_registry.get_by_name(n))
+                .map(|info| info.numeric_id as i32);
+            if let Some(fd) = fd {
+                let mut hunger = self.hunger(addr);
+                hunger.food = (hunger.food + fd.hunger).min(20);
+                hunger.saturation = (hunger.saturation + fd.saturation).min(hunger.food as f32);
+                self.set_hunger(addr, hunger);
+            }
+            let conn = match self.connections.get_mut(&addr) {
+                Some(c) => c,
+                None => return,
+            };
+
+            let stack = &mut conn.inventory.main[slot];
+            if stack.count > 1 {
+                stack.count -= 1;
+            } else {
+                let replacement = match leftover {
+                    Some(id) => {
+                        let stack_id = conn.inventory.next_stack_network_id();
+                        mc_rs_proto::item_stack::ItemStack::new_with_meta(id, 1, 0, stack_id)
+                    }
+                    None => mc_rs_proto::item_stack::ItemStack::empty(),
+                };
+                conn.inventory.main[slot] = replacement;
+            }
+        }
+
+        let hunger = self.hunger(addr);
+        let (rid, pos, client_tick, updated_item) = match self.connections.get(&addr) {
+            Some(c) => (
+                c.entity_runtime_id,
+                c.position,
+                c.client_tick,
+                c.inventory.main[slot].clone(),
+            ),
+            None => return,
+        };
+        self.send_packet(
+            addr,
+            packets::id::INVENTORY_SLOT,
+            &InventorySlot {
+                window_id: 0,
+                slot: slot as u32,
+                item: updated_item,
+            },
+        )
+        .await;
+        self.send_packet(
+            addr,
+            packets::id::UPDATE_ATTRIBUTES,
+            &UpdateAttributes::hunger(
+                rid,
+                hunger.food as f32,
+                hunger.saturation,
+                hunger.exhaustion,
+                client_tick,
+            ),
+        )
+        .await;
+        if too_fast {
+            debug!("Rejected eating by {addr}: finished too quickly");
+            return;
+        }
+
+        if is_milk {
+            self.clear_effects(addr).await;
+            return;
+        }
+
+        self.broadcast_packet(
+            packets::id::PLAY_SOUND,
+            &PlaySound::new("random.burp", pos.x, pos.y, pos.z, 0.5, 1.0),
+        )
+        .await;
+        for effect in food::food_effects(&name) {
+            if rand::random::<f32>() < effect.chance {
+                self.apply_effect(
+                    addr,
+                    effect.effect_id,
+                    effect.amplifier,
+                    effect.duration_ticks,
+                )
+                .await;
             }
         }
     }
 
+    /// Broadcast eating particles and sounds for players mid-meal.
+    pub(super) async fn tick_eating(&mut self) {
+        let tick = self.game_world.current_tick();
+        if !tick.is_multiple_of(EAT_EFFECT_INTERVAL) {
+            return;
+        }
+        let eaters: Vec<(u64, Vec3, i32, u16)> = self
+            .connections
+            .values()
+            .filter(|c| c.state == LoginState::InGame && !c.is_dead && c.eating_since.is_some())
+            .map(|c| {
+                let item = c.inventory.held_item();
+                (
+                    c.entity_runtime_id,
+                    c.position,
+                    item.runtime_id,
+                    item.metadata,
+                )
+            })
+            .collect();
+        for (rid, pos, item_id, meta) in eaters {
+            self.broadcast_packet(
+                packets::id::ENTITY_EVENT,
+                &EntityEvent::eating_item(rid, item_id, meta),
+            )
+            .await;
+            self.broadcast_packet(
+                packets::id::PLAY_SOUND,
+                &PlaySound::new("random.eat", pos.x, pos.y, pos.z, 0.5, 1.0),
+            )
+            .await;
+        }
+    }
+
+    /// Eat one slice of the cake at `pos` (already `bites` slices eaten).
+    /// Returns `false` if the player is not hungry enough to eat.
+    pub(super) async fn eat_cake(&mut self, addr: SocketAddr, pos: BlockPos, bites: usize) -> bool {
+        let (rid, tick, gamemode) = match self.connections.get(&addr) {
+            Some(c) => (c.entity_runtime_id, c.client_tick, c.gamemode),
+            None => return false,
+        };
+        let mut hunger = self.hunger(addr);
+        if hunger.food >= 20 && gamemode != 1 {
+            return false;
+        }
+        hunger.food = (hunger.food + food::CAKE_SLICE.hunger).min(20);
+        hunger.saturation =
+            (hunger.saturation + food::CAKE_SLICE.saturation).min(hunger.food as f32);
+        self.set_hunger(addr, hunger);
+
+        let next = if bites + 1 >= food::CAKE_SLICES {
+            self.tick_blocks.air
+        } else {
+            self.tick_blocks.cake[bites + 1]
+        };
+        self.set_block_and_broadcast(pos.x, pos.y, pos.z, next)
+            .await;
+        self.send_packet(
+            addr,
+            packets::id::UPDATE_ATTRIBUTES,
+            &UpdateAttributes::hunger(
+                rid,
+                hunger.food as f32,
+                hunger.saturation,
+                hunger.exhaustion,
+                tick,
+            ),
+        )
+        .await;
+        self.broadcast_packet(
+            packets::id::PLAY_SOUND,
+            &PlaySound::new(
+                "random.burp",
+                pos.x as f32 + 0.5,
+                pos.y as f32,
+                pos.z as f32 + 0.5,
+                0.5,
+                1.0,
+            ),
+        )
+        .await;
+        true
+    }
+
     /// Get combat bonuses from active effects for an attacker.
     /// Returns (strength_bonus, weakness_penalty).
     pub(super) fn get_attacker_bonuses(&self, addr: SocketAddr) -> (f32, f32) {
         use mc_rs_proto::packets::mob_effect::effect_id as eid;
 
-        let conn = match self.connections.get(&addr) {
-            Some(c) => c,
-            None => return (0.0, 0.0),
-        };
-
         let mut strength = 0.0_f32;
         let mut weakness = 0.0_f32;
 
-        for effect in &conn.effects {
+        for effect in self.effects(addr) {
             match effect.effect_id {
                 eid::STRENGTH => strength += 3.0 * (effect.amplifier as f32 + 1.0),
                 eid::WEAKNESS => weakness += 4.0,
@@ -188,13 +453,8 @@ impl ConnectionHandler {
     pub(super) fn get_resistance_factor(&self, addr: SocketAddr) -> f32 {
         use mc_rs_proto::packets::mob_effect::effect_id as eid;
 
-        let conn = match self.connections.get(&addr) {
-            Some(c) => c,
-            None => return 0.0,
-        };
-
         let mut factor = 0.0_f32;
-        for effect in &conn.effects {
+        for effect in self.effects(addr) {
             if effect.effect_id == eid::RESISTANCE {
                 factor += 0.2 * (effect.amplifier as f32 + 1.0);
             }
@@ -204,7 +464,7 @@ impl ConnectionHandler {
     }
 
     /// Tick survival mechanics: hunger drain, natural regen, starvation,
-    /// drowning, lava damage, and suffocation. Called once per game tick (50ms).
+    /// drowning, lava and magma damage, and suffocation. Called once per game tick (50ms).
     pub(super) async fn tick_survival(&mut self) {
         let addrs: Vec<SocketAddr> = self
             .connections
@@ -214,61 +474,68 @@ impl ConnectionHandler {
             .collect();
 
         for addr in addrs {
-            let (pos, tick, rid) = match self.connections.get(&addr) {
-                Some(c) => (c.position, c.client_tick, c.entity_runtime_id),
+            let (pos, tick, rid, dim) = match self.connections.get(&addr) {
+                Some(c) => (c.position, c.client_tick, c.entity_runtime_id, c.dimension),
                 None => continue,
             };
+            let hunger = self.world_flag(dim, WorldFlag::Hunger);
+            let peaceful = self.difficulty == Difficulty::Peaceful;
 
             // --- Exhaustion drain ---
-            let hunger_changed = {
-                let conn = match self.connections.get_mut(&addr) {
-                    Some(c) => c,
-                    None => continue,
-                };
-                if conn.exhaustion >= 4.0 {
-                    conn.exhaustion -= 4.0;
-                    if conn.saturation > 0.0 {
-                        conn.saturation = (conn.saturation - 1.0).max(0.0);
-                    } else {
-                        conn.food = (conn.food - 1).max(0);
-                    }
+            let mut food = self.hunger(addr);
+            let hunger_changed = if !hunger {
+                // Food stays put while hunger is off
+                food.exhaustion = 0.0;
+                false
+            } else if peaceful {
+                // Food refills on its own on Peaceful
+                food.exhaustion = 0.0;
+                if food.food < 20 && tick % 20 == 0 {
+                    food.food += 1;
                     true
                 } else {
                     false
                 }
+            } else if food.exhaustion >= 4.0 {
+                food.exhaustion -= 4.0;
+                if food.saturation > 0.0 {
+                    food.saturation = (food.saturation - 1.0).max(0.0);
+                } else {
+                    food.food = (food.food - 1).max(0);
+                }
+                true
+            } else {
+                false
             };
+            self.set_hunger(addr, food);
             if hunger_changed {
-                let conn = match self.connections.get(&addr) {
-                    Some(c) => c,
-                    None => continue,
-                };
                 self.send_packet(
                     addr,
                     packets::id::UPDATE_ATTRIBUTES,
                     &UpdateAttributes::hunger(
                         rid,
-                        conn.food as f32,
-                        conn.saturation,
-                        conn.exhaustion,
+                        food.food as f32,
+                        food.saturation,
+                        food.exhaustion,
                         tick,
                     ),
                 )
                 .await;
             }
 
-            // --- Natural regeneration (every 80 ticks = 4 seconds) ---
-            let (food, health) = match self.connections.get(&addr) {
-                Some(c) => (c.food, c.health),
-                None => continue,
+            // --- Natural regeneration (every 80 ticks = 4 seconds, or every
+            // second without food cost on Peaceful) ---
+            let (food, health) = (self.hunger(addr).food, self.health(addr));
+            let regen_due = if peaceful {
+                tick % 20 == 0
+            } else {
+                food >= 18 && tick % 80 == 0
             };
-            if food >= 18 && health < 20.0 && tick % 80 == 0 {
-                let conn = match self.connections.get_mut(&addr) {
-                    Some(c) => c,
-                    None => continue,
-                };
-                conn.health = (conn.health + 1.0).min(20.0);
-                conn.exhaustion += 6.0;
-                let hp = conn.health;
+            if regen_due && health < 20.0 {
+                let hp = self.change_health(addr, 1.0);
+                if !peaceful {
+                    self.add_exhaustion(addr, 6.0);
+                }
                 self.send_packet(
                     addr,
                     packets::id::UPDATE_ATTRIBUTES,
@@ -277,26 +544,39 @@ impl ConnectionHandler {
                 .await;
             }
 
-            // --- Starvation (every 80 ticks, food == 0) ---
-            let (food, health) = match self.connections.get(&addr) {
-                Some(c) => (c.food, c.health),
-                None => continue,
-            };
-            if food == 0 && tick % 80 == 0 && health > 1.0 {
-                let conn = match self.connections.get_mut(&addr) {
-                    Some(c) => c,
-                    None => continue,
-                };
-                conn.health = (conn.health - 1.0).max(1.0);
-                let hp = conn.health;
-                self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
+            // --- Starvation (every 80 ticks, food == 0), down to the
+            // difficulty's floor ---
+            let (food, health) = (self.hunger(addr).food, self.health(addr));
+            if let Some(floor) = self.difficulty.starvation_floor() {
+                if food == 0
+                    && tick % 80 == 0
+                    && health > floor
+                    && self.damage_allowed(addr, DamageCause::Starvation, false)
+                {
+                    let hp = self.change_health(addr, -(health - floor).min(1.0));
+                    self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
+                        .await;
+                    self.send_packet(
+                        addr,
+                        packets::id::UPDATE_ATTRIBUTES,
+                        &UpdateAttributes::health(rid, hp, tick),
+                    )
                     .await;
-                self.send_packet(
-                    addr,
-                    packets::id::UPDATE_ATTRIBUTES,
-                    &UpdateAttributes::health(rid, hp, tick),
-                )
-                .await;
+                    if hp <= 0.0 {
+                        let name = self
+                            .connections
+                            .get(&addr)
+                            .and_then(|c| c.login_data.as_ref())
+                            .map(|d| d.display_name.clone())
+                            .unwrap_or_default();
+                        self.handle_player_death_with_message(
+                            addr,
+                            &format!("{name} starved to death"),
+                        )
+                        .await;
+                        continue;
+                    }
+                }
             }
 
             // --- Drowning ---
@@ -308,26 +588,17 @@ impl ConnectionHandler {
             let hy = head_y.floor() as i32;
             let hz = pos.z.floor() as i32;
 
-            let head_block_name = self
-                .get_block(hx, hy, hz)
-                .and_then(|hash| self.block_registry.get(hash))
-                .map(|info| info.name);
+            // Lifting bubble columns bring air down to the players in them
+            let in_water = self.get_block(hx, hy, hz).is_some_and(|rid| {
+                self.tick_blocks.holds_water(rid)
+                    && self.tick_blocks.bubble_column_drag_down(rid) != Some(false)
+            });
 
-            let in_water = matches!(
-                head_block_name,
-                Some("minecraft:water") | Some("minecraft:flowing_water")
+            let has_water_breathing = effects::has(
+                self.effects(addr),
+                mc_rs_proto::packets::mob_effect::effect_id::WATER_BREATHING,
             );
 
-            let has_water_breathing = self
-                .connections
-                .get(&addr)
-                .map(|c| {
-                    c.effects.iter().any(|e| {
-                        e.effect_id == mc_rs_proto::packets::mob_effect::effect_id::WATER_BREATHING
-                    })
-                })
-                .unwrap_or(false);
-
             if in_water {
                 if has_water_breathing {
                     if let Some(conn) = self.connections.get_mut(&addr) {
@@ -351,14 +622,12 @@ impl ConnectionHandler {
                         .get(&addr)
                         .map(|c| c.air_ticks)
                         .unwrap_or(0);
-                    if air <= 0 && tick % 20 == 0 {
+                    if air <= 0
+                        && tick % 20 == 0
+                        && self.damage_allowed(addr, DamageCause::Drowning, false)
+                    {
                         // Drowning: 2 damage per second
-                        let conn = match self.connections.get_mut(&addr) {
-                            Some(c) => c,
-                            None => continue,
-                        };
-                        conn.health = (conn.health - 2.0).max(0.0);
-                        let hp = conn.health;
+                        let hp = self.change_health(addr, -2.0);
                         self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
                             .await;
                         self.send_packet(
@@ -409,16 +678,10 @@ impl ConnectionHandler {
                     conn.fire_ticks = conn.fire_ticks.max(300);
                 }
 
-                let has_fire_res = self
-                    .connections
-                    .get(&addr)
-                    .map(|c| {
-                        c.effects.iter().any(|e| {
-                            e.effect_id
-                                == mc_rs_proto::packets::mob_effect::effect_id::FIRE_RESISTANCE
-                        })
-                    })
-                    .unwrap_or(false);
+                let has_fire_res = effects::has(
+                    self.effects(addr),
+                    mc_rs_proto::packets::mob_effect::effect_id::FIRE_RESISTANCE,
+                );
 
                 if !has_fire_res && tick % 10 == 0 {
                     // 4 damage every 0.5 seconds, reduced by Fire Protection
@@ -436,12 +699,7 @@ impl ConnectionHandler {
                         })
                         .unwrap_or(0.0);
                     let lava_dmg = 4.0 * (1.0 - fp_reduction);
-                    let conn = match self.connections.get_mut(&addr) {
-                        Some(c) => c,
-                        None => continue,
-                    };
-                    conn.health = (conn.health - lava_dmg).max(0.0);
-                    let hp = conn.health;
+                    let hp = self.change_health(addr, -lava_dmg);
                     self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
                         .await;
                     self.send_packet(
@@ -467,6 +725,50 @@ impl ConnectionHandler {
                 }
             }
 
+            // --- Magma blocks (standing on one, unless sneaking, resisting
+            // fire or wearing Frost Walker boots) ---
+            let below_y = (feet_y - 0.01).floor() as i32;
+            let on_magma = self
+                .get_block(fx, below_y, fz)
+                .is_some_and(|rid| self.tick_blocks.is_magma(rid));
+            let protected = self.connections.get(&addr).is_none_or(|c| {
+                !c.on_ground
+                    || c.is_sneaking
+                    || game_combat::has_frost_walker(&c.inventory.armor[3].nbt_data)
+            }) || effects::has(
+                self.effects(addr),
+                mc_rs_proto::packets::mob_effect::effect_id::FIRE_RESISTANCE,
+            );
+            if on_magma
+                && !protected
+                && tick % 10 == 0
+                && self.damage_allowed(addr, DamageCause::Fire, false)
+            {
+                let hp = self.change_health(addr, -1.0);
+                self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
+                    .await;
+                self.send_packet(
+                    addr,
+                    packets::id::UPDATE_ATTRIBUTES,
+                    &UpdateAttributes::health(rid, hp, tick),
+                )
+                .await;
+                if hp <= 0.0 {
+                    let name = self
+                        .connections
+                        .get(&addr)
+                        .and_then(|c| c.login_data.as_ref())
+                        .map(|d| d.display_name.clone())
+                        .unwrap_or_default();
+                    self.handle_player_death_with_message(
+                        addr,
+                        &format!("{name} discovered the floor was lava"),
+                    )
+                    .await;
+                    continue;
+                }
+            }
+
             // --- Suffocation (head inside solid block) ---
             // Check block at head position
             let head_solid = self
@@ -475,12 +777,7 @@ impl ConnectionHandler {
                 .unwrap_or(false);
 
             if head_solid && tick % 10 == 0 {
-                let conn = match self.connections.get_mut(&addr) {
-                    Some(c) => c,
-                    None => continue,
-                };
-                conn.health = (conn.health - 1.0).max(0.0);
-                let hp = conn.health;
+                let hp = self.change_health(addr, -1.0);
                 self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
                     .await;
                 self.send_packet(
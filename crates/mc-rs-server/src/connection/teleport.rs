@@ -0,0 +1,355 @@
+//! Player teleports: `/home`, `/sethome`, `/delhome`, `/back` and the `/tpa`
+//! request flow, with warm-ups cancelled by moving or taking damage, and a
+//! cooldown between teleports.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::packets::{self, Text};
+use mc_rs_proto::types::Vec3;
+
+use super::{
+    ConnectionHandler, PendingTeleport, PlayerConnection, TeleportDestination, TpaRequest,
+};
+use crate::permissions::TELEPORT_BYPASS;
+use crate::persistence::SavedLocation;
+
+/// Home used by `/home` and `/sethome` without a name.
+const DEFAULT_HOME: &str = "home";
+
+/// Distance a player can drift during a warm-up before it is cancelled.
+const WARMUP_MOVE_TOLERANCE: f32 = 0.5;
+
+impl ConnectionHandler {
+    /// Where a player currently stands.
+    pub(super) fn saved_location(conn: &PlayerConnection) -> SavedLocation {
+        SavedLocation {
+            dimension: conn.dimension,
+            position: [conn.position.x, conn.position.y, conn.position.z],
+        }
+    }
+
+    fn teleport_bypass(&self, addr: SocketAddr) -> bool {
+        self.connections
+            .get(&addr)
+            .and_then(|c| c.login_data.as_ref())
+            .is_some_and(|d| self.permissions.has_node(&d.display_name, TELEPORT_BYPASS))
+    }
+
+    /// Seconds left before a player may teleport again, if any.
+    fn teleport_cooldown_remaining(&mut self, addr: SocketAddr) -> Option<u64> {
+        let last = self.connections.get(&addr)?.last_teleport_tick?;
+        let ready_at = last + self.server_config.teleport.cooldown * 20;
+        let now = self.game_world.current_tick();
+        (now < ready_at).then(|| (ready_at - now).div_ceil(20))
+    }
+
+    /// Teleport a player after a warm-up of `warmup` seconds, or right away
+    /// if it is 0 or they hold the bypass node.
+    pub(super) async fn start_teleport(
+        &mut self,
+        addr: SocketAddr,
+        destination: TeleportDestination,
+        label: &str,
+        warmup: u64,
+    ) -> CommandResult {
+        let bypass = self.teleport_bypass(addr);
+        if !bypass {
+            if let Some(remaining) = self.teleport_cooldown_remaining(addr) {
+                return CommandResult::err(format!(
+                    "You must wait {remaining}s before teleporting again"
+                ));
+            }
+        }
+
+        if bypass || warmup == 0 {
+            return match self.execute_teleport(addr, &destination).await {
+                Ok(()) => CommandResult::ok(format!("Teleported to {label}")),
+                Err(e) => CommandResult::err(e),
+            };
+        }
+
+        let execute_tick = self.game_world.current_tick() + warmup * 20;
+        let start_health = self.health(addr);
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return CommandResult::err("Player not found");
+        };
+        conn.pending_teleport = Some(PendingTeleport {
+            destination,
+            label: label.to_string(),
+            execute_tick,
+            start_position: conn.position,
+            start_health,
+        });
+        CommandResult::ok(format!(
+            "Teleporting to {label} in {warmup}s, don't move..."
+        ))
+    }
+
+    /// Finish warm-ups that are due, and cancel those of players who moved
+    /// or took damage since they started.
+    pub(super) async fn tick_teleports(&mut self) {
+        let now = self.game_world.current_tick();
+        let mut due = Vec::new();
+        let mut cancelled = Vec::new();
+        for (&addr, conn) in self.connections.iter_mut() {
+            let Some(pending) = &conn.pending_teleport else {
+                continue;
+            };
+            if self.game_world.player_health(conn.entity_unique_id) < pending.start_health {
+                conn.pending_teleport = None;
+                cancelled.push((addr, "you took damage"));
+            } else if conn.position.distance(&pending.start_position) > WARMUP_MOVE_TOLERANCE {
+                conn.pending_teleport = None;
+                cancelled.push((addr, "you moved"));
+            } else if now >= pending.execute_tick {
+                if let Some(pending) = conn.pending_teleport.take() {
+                    due.push((addr, pending));
+                }
+            }
+        }
+
+        for (addr, reason) in cancelled {
+            let msg = format!("§cTeleport cancelled: {reason}");
+            self.send_packet(addr, packets::id::TEXT, &Text::raw(msg))
+                .await;
+        }
+        for (addr, pending) in due {
+            let msg = match self.execute_teleport(addr, &pending.destination).await {
+                Ok(()) => format!("Teleported to {}", pending.label),
+                Err(e) => format!("§c{e}"),
+            };
+            self.send_packet(addr, packets::id::TEXT, &Text::raw(msg))
+                .await;
+        }
+    }
+
+    /// Move a player to a destination, across dimensions if needed, and
+    /// remember where they came from for `/back`.
+    async fn execute_teleport(
+        &mut self,
+        addr: SocketAddr,
+        destination: &TeleportDestination,
+    ) -> Result<(), String> {
+        let target = match destination {
+            TeleportDestination::Location(loc) => *loc,
+            TeleportDestination::Player(name) => {
+                let conn = self
+                    .find_player_addr(name)
+                    .and_then(|a| self.connections.get(&a))
+                    .ok_or_else(|| format!("{name} is no longer online"))?;
+                Self::saved_location(conn)
+            }
+        };
+
+        let now = self.game_world.current_tick();
+        let (src_dim, name, entity_unique_id) = match self.connections.get_mut(&addr) {
+            Some(conn) => {
+                conn.back_location = Some(Self::saved_location(conn));
+                conn.last_teleport_tick = Some(now);
+                let name = conn
+                    .login_data
+                    .as_ref()
+                    .map(|d| d.display_name.clone())
+                    .unwrap_or_default();
+                (conn.dimension, name, conn.entity_unique_id)
+            }
+            None => return Err("Player not found".into()),
+        };
+
+        let [x, y, z] = target.position;
+        if target.dimension == src_dim {
+            self.teleport_player(addr, &name, x, y, z).await;
+        } else {
+            self.execute_dimension_change(
+                addr,
+                src_dim,
+                target.dimension,
+                Vec3::new(x, y, z),
+                entity_unique_id,
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    /// `/sethome [name]`
+    pub(super) fn cmd_sethome(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        let name = args.first().map_or(DEFAULT_HOME, String::as_str);
+        let max_homes = self.server_config.teleport.max_homes;
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return CommandResult::err("Player not found");
+        };
+        if !conn.homes.contains_key(name) && conn.homes.len() >= max_homes {
+            return CommandResult::err(format!(
+                "You can't have more than {max_homes} homes, use /delhome first"
+            ));
+        }
+        let loc = Self::saved_location(conn);
+        conn.homes.insert(name.to_string(), loc);
+        CommandResult::ok(format!("Home {name} set"))
+    }
+
+    /// `/delhome [name]`
+    pub(super) fn cmd_delhome(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        let name = args.first().map_or(DEFAULT_HOME, String::as_str);
+        let removed = self
+            .connections
+            .get_mut(&addr)
+            .is_some_and(|c| c.homes.remove(name).is_some());
+        if removed {
+            CommandResult::ok(format!("Home {name} deleted"))
+        } else {
+            CommandResult::err(format!("Unknown home: {name}"))
+        }
+    }
+
+    /// `/home [name]`: teleport to a home. Without a name, goes to the only
+    /// home or the default one, or lists them.
+    pub(super) async fn cmd_home(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        let Some(conn) = self.connections.get(&addr) else {
+            return CommandResult::err("Player not found");
+        };
+        let home = match args.first() {
+            Some(name) => conn.homes.get_key_value(name.as_str()),
+            None if conn.homes.len() == 1 => conn.homes.iter().next(),
+            None => conn.homes.get_key_value(DEFAULT_HOME),
+        };
+        let Some((name, loc)) = home.map(|(n, l)| (n.clone(), *l)) else {
+            if conn.homes.is_empty() {
+                return CommandResult::err("You have no homes, set one with /sethome");
+            }
+            let mut names: Vec<&str> = conn.homes.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            return match args.first() {
+                Some(name) => CommandResult::err(format!(
+                    "Unknown home: {name}. Your homes: {}",
+                    names.join(", ")
+                )),
+                None => CommandResult::ok(format!("Your homes: {}", names.join(", "))),
+            };
+        };
+        let warmup = self.server_config.teleport.warmup;
+        self.start_teleport(addr, TeleportDestination::Location(loc), &name, warmup)
+            .await
+    }
+
+    /// `/back`: return to where the player last teleported from or died.
+    pub(super) async fn cmd_back(&mut self, addr: SocketAddr) -> CommandResult {
+        match self.connections.get(&addr).and_then(|c| c.back_location) {
+            Some(loc) => {
+                let warmup = self.server_config.teleport.warmup;
+                self.start_teleport(
+                    addr,
+                    TeleportDestination::Location(loc),
+                    "your previous location",
+                    warmup,
+                )
+                .await
+            }
+            None => CommandResult::err("You have no previous location to go back to"),
+        }
+    }
+
+    /// `/tpa <player>`: ask to teleport to another player.
+    pub(super) async fn cmd_tpa(
+        &mut self,
+        addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        let [target_name] = args else {
+            return CommandResult::err("Usage: /tpa <player>");
+        };
+        let target_addr = match self.find_player_addr(target_name) {
+            Some(a) if a != addr => a,
+            Some(_) => return CommandResult::err("You can't teleport to yourself"),
+            None => return CommandResult::err(format!("Player not found: {target_name}")),
+        };
+
+        let expires_tick =
+            self.game_world.current_tick() + self.server_config.teleport.request_timeout * 20;
+        let Some(target) = self.connections.get_mut(&target_addr) else {
+            return CommandResult::err(format!("Player not found: {target_name}"));
+        };
+        target.tpa_requests.retain(|r| r.from != sender_name);
+        target.tpa_requests.push(TpaRequest {
+            from: sender_name.to_string(),
+            expires_tick,
+        });
+
+        let msg = format!(
+            "§e{sender_name} wants to teleport to you. Type /tpaccept or /tpdeny to answer."
+        );
+        self.send_packet(target_addr, packets::id::TEXT, &Text::raw(msg))
+            .await;
+        CommandResult::ok(format!("Teleport request sent to {target_name}"))
+    }
+
+    /// Take the request from `from`, or the latest one, dropping expired ones.
+    fn take_tpa_request(&mut self, addr: SocketAddr, from: Option<&String>) -> Option<TpaRequest> {
+        let now = self.game_world.current_tick();
+        let conn = self.connections.get_mut(&addr)?;
+        conn.tpa_requests.retain(|r| r.expires_tick > now);
+        let index = match from {
+            Some(name) => conn.tpa_requests.iter().position(|r| &r.from == name)?,
+            None => conn.tpa_requests.len().checked_sub(1)?,
+        };
+        Some(conn.tpa_requests.remove(index))
+    }
+
+    /// `/tpaccept [player]`: accept a teleport request, the latest by default.
+    pub(super) async fn cmd_tpaccept(
+        &mut self,
+        addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        let Some(request) = self.take_tpa_request(addr, args.first()) else {
+            return CommandResult::err("You have no pending teleport requests");
+        };
+        let Some(requester_addr) = self.find_player_addr(&request.from) else {
+            return CommandResult::err(format!("{} is no longer online", request.from));
+        };
+
+        let warmup = self.server_config.teleport.warmup;
+        let result = self
+            .start_teleport(
+                requester_addr,
+                TeleportDestination::Player(sender_name.to_string()),
+                sender_name,
+                warmup,
+            )
+            .await;
+        let mut messages = vec![format!("§a{sender_name} accepted your teleport request")];
+        messages.extend(result.messages);
+        for msg in messages {
+            self.send_packet(requester_addr, packets::id::TEXT, &Text::raw(msg))
+                .await;
+        }
+
+        if result.success {
+            CommandResult::ok(format!("Accepted {}'s teleport request", request.from))
+        } else {
+            CommandResult::err(format!("{} can't teleport yet", request.from))
+        }
+    }
+
+    /// `/tpdeny [player]`: deny a teleport request, the latest by default.
+    pub(super) async fn cmd_tpdeny(
+        &mut self,
+        addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        let Some(request) = self.take_tpa_request(addr, args.first()) else {
+            return CommandResult::err("You have no pending teleport requests");
+        };
+        if let Some(requester_addr) = self.find_player_addr(&request.from) {
+            let msg = format!("§c{sender_name} denied your teleport request");
+            self.send_packet(requester_addr, packets::id::TEXT, &Text::raw(msg))
+                .await;
+        }
+        CommandResult::ok(format!("Denied {}'s teleport request", request.from))
+    }
+}
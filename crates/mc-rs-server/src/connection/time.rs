@@ -0,0 +1,262 @@
+//! Time of day per world, and sleeping through the night.
+//!
+//! The main world's time is `world_time`; each extra world keeps its own in
+//! `ExtraWorld::time`. Players only receive the time of the world they are
+//! in. Lying in a bed at night (or in a thunderstorm) counts the player as
+//! sleeping, and once `playersSleepingPercentage` of a world's players have
+//! slept for five seconds, that world skips to the next morning.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
+use mc_rs_proto::packets::{self, LevelEvent, SetTime, Text};
+use mc_rs_proto::types::{BlockPos, Vec3};
+use rand::Rng;
+
+use super::{ConnectionHandler, LoginState, SleepState};
+use crate::worlds::world_dimension;
+
+/// Ticks a player must lie in bed before they count towards skipping the night.
+const SLEEP_TICKS: u64 = 100;
+
+/// Distance a sleeping player can drift before they get out of bed.
+const SLEEP_MOVE_TOLERANCE: f32 = 0.5;
+
+/// Time of day from which players can sleep in clear weather.
+const NIGHT_START: i64 = 12542;
+
+/// Time of day at which sleeping players wake up on their own.
+const NIGHT_END: i64 = 23460;
+
+/// Sleepers needed to skip the night with `players` in the world, or `None`
+/// if the percentage is over 100 and the night can't be skipped.
+fn sleepers_needed(percentage: i32, players: usize) -> Option<usize> {
+    if percentage > 100 {
+        return None;
+    }
+    let needed = (players * percentage.max(0) as usize).div_ceil(100);
+    Some(needed.max(1))
+}
+
+impl ConnectionHandler {
+    /// Time of day of the world a dimension belongs to.
+    pub(super) fn world_time(&self, dim: i32) -> i64 {
+        let world = world_dimension(dim);
+        self.extra_worlds
+            .iter()
+            .find(|w| w.dimension == world)
+            .map_or(self.world_time, |w| w.time)
+    }
+
+    /// Set the time of the world a dimension belongs to and send it to the
+    /// players in that world.
+    pub(super) async fn set_world_time(&mut self, dim: i32, time: i64) {
+        let world = world_dimension(dim);
+        match self.extra_worlds.iter_mut().find(|w| w.dimension == world) {
+            Some(w) => w.time = time,
+            None => self.world_time = time,
+        }
+        self.broadcast_world_time(world).await;
+    }
+
+    /// Send a world's time to the players in it.
+    async fn broadcast_world_time(&mut self, world: i32) {
+        let pkt = SetTime {
+            time: self.world_time(world) as i32,
+        };
+        let addrs: Vec<SocketAddr> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame && world_dimension(c.dimension) == world)
+            .map(|(&a, _)| a)
+            .collect();
+        for addr in addrs {
+            self.send_packet(addr, packets::id::SET_TIME, &pkt).await;
+        }
+    }
+
+    /// Send a player the time of the world they are in.
+    pub(super) async fn send_world_time(&mut self, addr: SocketAddr) {
+        let Some(dim) = self.connections.get(&addr).map(|c| c.dimension) else {
+            return;
+        };
+        let pkt = SetTime {
+            time: self.world_time(dim) as i32,
+        };
+        self.send_packet(addr, packets::id::SET_TIME, &pkt).await;
+    }
+
+    /// Advance the time of every world, resyncing clients every 10 seconds.
+    pub(super) async fn tick_world_times(&mut self, tick: u64) {
+        self.world_time += 1;
+        for world in &mut self.extra_worlds {
+            world.time += 1;
+        }
+        if tick.is_multiple_of(200) {
+            let worlds: Vec<i32> = std::iter::once(0)
+                .chain(self.extra_worlds.iter().map(|w| w.dimension))
+                .collect();
+            for world in worlds {
+                self.broadcast_world_time(world).await;
+            }
+        }
+    }
+
+    /// Whether players in a dimension can sleep right now.
+    fn can_sleep_now(&self, dim: i32) -> bool {
+        let daytime = self.world_time(dim).rem_euclid(24000);
+        let thunder = world_dimension(dim) == 0 && self.is_thundering;
+        thunder || (NIGHT_START..NIGHT_END).contains(&daytime)
+    }
+
+    /// A player used a bed: lie down if it is night in an overworld-like
+    /// dimension.
+    pub(super) async fn sleep_in_bed(&mut self, addr: SocketAddr, bed: BlockPos) {
+        let Some(conn) = self.connections.get(&addr) else {
+            return;
+        };
+        let dim = conn.dimension;
+        let position = conn.position;
+        let msg = if conn.sleeping.is_some() {
+            return;
+        } else if self.client_dimension(dim) != 0 {
+            "You can't sleep here"
+        } else if !self.can_sleep_now(dim) {
+            "You can only sleep at night or during thunderstorms"
+        } else {
+            let center = Vec3::new(bed.x as f32 + 0.5, bed.y as f32 + 0.5, bed.z as f32 + 0.5);
+            if position.distance(&center) > 3.0 {
+                "You may not rest now; the bed is too far away"
+            } else {
+                let since_tick = self.game_world.current_tick();
+                let name = match self.connections.get_mut(&addr) {
+                    Some(conn) => {
+                        conn.sleeping = Some(SleepState {
+                            since_tick,
+                            position,
+                        });
+                        conn.login_data
+                            .as_ref()
+                            .map(|d| d.display_name.clone())
+                            .unwrap_or_default()
+                    }
+                    None => return,
+                };
+                self.announce_sleepers(dim, &name).await;
+                return;
+            }
+        };
+        self.send_packet(addr, packets::id::TEXT, &Text::raw(msg))
+            .await;
+    }
+
+    /// Tell a world's players how many of them are sleeping.
+    async fn announce_sleepers(&mut self, dim: i32, name: &str) {
+        let world = world_dimension(dim);
+        let in_world: Vec<(SocketAddr, bool)> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame && world_dimension(c.dimension) == world)
+            .map(|(&a, c)| (a, c.sleeping.is_some()))
+            .collect();
+        let sleeping = in_world.iter().filter(|(_, s)| *s).count();
+        let msg = match sleepers_needed(self.players_sleeping_percentage, in_world.len()) {
+            Some(needed) => format!("{name} is sleeping ({sleeping}/{needed})"),
+            None => format!("{name} is sleeping"),
+        };
+        for (addr, _) in in_world {
+            self.send_packet(addr, packets::id::TEXT, &Text::raw(msg.clone()))
+                .await;
+        }
+    }
+
+    /// Get players out of bed when they move away or morning comes, and skip
+    /// the night in worlds where enough players have slept.
+    pub(super) async fn tick_sleep(&mut self) {
+        let now = self.game_world.current_tick();
+        let mut woken = Vec::new();
+        // World → (players, players asleep long enough)
+        let mut worlds: BTreeMap<i32, (usize, usize)> = BTreeMap::new();
+        for (&addr, conn) in &self.connections {
+            if conn.state != LoginState::InGame {
+                continue;
+            }
+            let entry = worlds.entry(world_dimension(conn.dimension)).or_default();
+            entry.0 += 1;
+            if let Some(sleep) = &conn.sleeping {
+                if conn.position.distance(&sleep.position) > SLEEP_MOVE_TOLERANCE
+                    || !self.can_sleep_now(conn.dimension)
+                {
+                    woken.push(addr);
+                } else if now >= sleep.since_tick + SLEEP_TICKS {
+                    entry.1 += 1;
+                }
+            }
+        }
+        for addr in woken {
+            if let Some(conn) = self.connections.get_mut(&addr) {
+                conn.sleeping = None;
+            }
+        }
+
+        for (world, (players, rested)) in worlds {
+            let Some(needed) = sleepers_needed(self.players_sleeping_percentage, players) else {
+                continue;
+            };
+            if rested > 0 && rested >= needed {
+                self.skip_night(world).await;
+            }
+        }
+    }
+
+    /// Move a world to the next morning, clear the weather if it is the main
+    /// world, and wake its sleepers.
+    async fn skip_night(&mut self, world: i32) {
+        let time = self.world_time(world);
+        let morning = time - time.rem_euclid(24000) + 24000;
+        self.set_world_time(world, morning).await;
+
+        if world == 0 && (self.is_raining || self.is_thundering) {
+            if self.is_raining {
+                self.broadcast_packet(packets::id::LEVEL_EVENT, &LevelEvent::stop_rain())
+                    .await;
+            }
+            if self.is_thundering {
+                self.broadcast_packet(packets::id::LEVEL_EVENT, &LevelEvent::stop_thunder())
+                    .await;
+            }
+            self.rain_target = 0.0;
+            self.lightning_target = 0.0;
+            self.is_raining = false;
+            self.is_thundering = false;
+            self.weather_duration = rand::thread_rng().gen_range(12000..24000);
+        }
+
+        let sleepers: Vec<SocketAddr> = self
+            .connections
+            .iter_mut()
+            .filter(|(_, c)| world_dimension(c.dimension) == world)
+            .filter_map(|(&a, c)| c.sleeping.take().map(|_| a))
+            .collect();
+        for addr in sleepers {
+            self.send_packet(addr, packets::id::TEXT, &Text::raw("Good morning!"))
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sleepers_needed_rounds_up() {
+        assert_eq!(sleepers_needed(100, 3), Some(3));
+        assert_eq!(sleepers_needed(50, 3), Some(2));
+        assert_eq!(sleepers_needed(50, 4), Some(2));
+        assert_eq!(sleepers_needed(1, 10), Some(1));
+        // At least one player always has to sleep
+        assert_eq!(sleepers_needed(0, 5), Some(1));
+        assert_eq!(sleepers_needed(101, 5), None);
+    }
+}
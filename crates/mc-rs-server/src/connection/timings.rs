@@ -0,0 +1,136 @@
+//! Tick profiling: sections of the server are timed into the handler's
+//! [`TickProfiler`](crate::timings::TickProfiler), reported by `/timings`
+//! and logged when a tick runs over budget. `/tps` reports the tick rate.
+
+use std::fmt::Write;
+use std::time::Instant;
+
+use mc_rs_command::CommandResult;
+use tracing::warn;
+
+use super::ConnectionHandler;
+use crate::timings::{self, ms, TICK_BUDGET};
+use crate::tps::TARGET_TPS;
+
+/// Sections listed by `/timings report`.
+const REPORT_SECTIONS: usize = 12;
+
+/// Sections named in the slow tick warning.
+const SLOW_TICK_SECTIONS: usize = 3;
+
+impl ConnectionHandler {
+    /// Start timing a section, until the matching [`exit_section`](Self::exit_section).
+    pub(super) fn enter_section(&mut self, section: &str) {
+        self.record_plugin_time();
+        self.timings.enter(section, Instant::now());
+        self.activity.enter(section);
+    }
+
+    /// Stop timing the innermost section.
+    pub(super) fn exit_section(&mut self) {
+        self.record_plugin_time();
+        self.timings.exit(Instant::now());
+        self.activity.exit();
+    }
+
+    /// Move the time plugins spent in callbacks out of the open section and
+    /// into sections of their own.
+    fn record_plugin_time(&mut self) {
+        for (plugin, elapsed) in self.plugin_manager.take_timings() {
+            self.timings
+                .record_within(&timings::plugin_section(&plugin), elapsed);
+        }
+    }
+
+    pub(super) fn start_tick_timing(&mut self) {
+        self.record_plugin_time();
+        self.timings.start_tick(Instant::now());
+        self.activity.enter(timings::TICK);
+    }
+
+    /// Finish timing the tick, warning if it went over budget, tell the
+    /// watchdog the loop is alive and schedule the next tick.
+    pub(super) fn finish_tick_timing(&mut self) {
+        self.record_plugin_time();
+        let now = Instant::now();
+        let (duration, hot) = self.timings.finish_tick(now);
+        self.tps.record_duration(duration);
+        self.activity.exit();
+        let tick = self.game_world.current_tick();
+        let skipped = self
+            .tick_clock
+            .advance(now, self.server_config.server.max_catch_up_ticks);
+        if skipped > 0 {
+            warn!("Can't keep up: skipped {skipped} tick(s)");
+        }
+        self.activity.tick_finished(tick);
+        if tick.is_multiple_of(20) {
+            self.activity.set_timings(self.timings_report());
+            self.activity
+                .set_plugins(self.plugin_manager.plugin_names());
+        }
+        if duration > TICK_BUDGET {
+            let hot: Vec<String> = hot
+                .iter()
+                .take(SLOW_TICK_SECTIONS)
+                .map(|(section, elapsed)| format!("{section} {:.1} ms", ms(*elapsed)))
+                .collect();
+            warn!(
+                "Tick {tick} took {:.1} ms (budget {} ms): {}",
+                ms(duration),
+                TICK_BUDGET.as_millis(),
+                hot.join(", ")
+            );
+        }
+    }
+
+    /// /timings <report|reset>
+    pub(super) fn cmd_timings(&mut self, args: &[String]) -> CommandResult {
+        match args.first().map(String::as_str) {
+            Some("report") => CommandResult::ok(self.timings_report()),
+            Some("reset") => {
+                self.timings.reset();
+                CommandResult::ok("Timings reset")
+            }
+            _ => CommandResult::err("Usage: /timings <report|reset>"),
+        }
+    }
+
+    /// /tps
+    pub(super) fn cmd_tps(&self) -> CommandResult {
+        CommandResult::ok(format!(
+            "TPS: {:.1} (target {TARGET_TPS}), MSPT: {:.2} ms, {} tick(s) skipped since start",
+            self.tps.tps(),
+            self.tps.mspt(),
+            self.tick_clock.skipped()
+        ))
+    }
+
+    /// Tick durations and the sections taking the most time over the window.
+    fn timings_report(&self) -> String {
+        let ticks = self.timings.tick_count();
+        if ticks == 0 {
+            return "No ticks recorded yet".into();
+        }
+        let (mean, max, slow) = self.timings.tick_summary();
+        let mut report = format!(
+            "Timings over the last {ticks} ticks ({:.1} TPS): tick mean {:.2} ms, max {:.2} ms, {slow} over {} ms",
+            self.tps.tps(),
+            ms(mean),
+            ms(max),
+            TICK_BUDGET.as_millis()
+        );
+        for section in self.timings.sections().iter().take(REPORT_SECTIONS) {
+            let per_tick = section.total / ticks as u32;
+            let _ = write!(
+                report,
+                "\n  {}: {:.2} ms/tick ({:.1}% of budget), max {:.2} ms",
+                section.name,
+                ms(per_tick),
+                ms(per_tick) / ms(TICK_BUDGET) * 100.0,
+                ms(section.max)
+            );
+        }
+        report
+    }
+}
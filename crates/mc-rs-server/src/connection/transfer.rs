@@ -0,0 +1,73 @@
+//! Server-to-server transfers with optional identity forwarding.
+
+use std::net::SocketAddr;
+
+use mc_rs_proto::jwt::LoginData;
+use mc_rs_proto::packets::{self, Transfer};
+use tracing::info;
+
+use super::ConnectionHandler;
+use crate::handoff::{self, HandoffTicket, TICKET_TTL_SECS};
+
+impl ConnectionHandler {
+    /// Send a player to another server. When a transfer secret is configured,
+    /// a handoff ticket carrying their identity is posted to the target first.
+    pub(super) async fn transfer_player(&mut self, addr: SocketAddr, host: &str, port: u16) {
+        let secret = self.server_config.transfer.secret.clone();
+        if !secret.is_empty() {
+            let ticket = self.connections.get(&addr).and_then(|c| {
+                let login = c.login_data.as_ref()?;
+                Some(HandoffTicket {
+                    name: login.display_name.clone(),
+                    xuid: login.xuid.clone(),
+                    uuid: login.identity.clone(),
+                    client_ip: addr.ip().to_string(),
+                    target_port: port,
+                    expires_at: crate::persistence::unix_timestamp() + TICKET_TTL_SECS,
+                })
+            });
+            if let Some(ticket) = ticket {
+                let handoff_port = self.server_config.transfer.handoff_port;
+                handoff::send(&ticket, &secret, host, handoff_port).await;
+            }
+        }
+
+        // Persist before the client leaves so the target server sees fresh data.
+        self.save_player_data(addr).await;
+        self.send_packet(addr, packets::id::TRANSFER, &Transfer::new(host, port))
+            .await;
+    }
+
+    /// Store a verified handoff ticket until the player connects.
+    pub fn accept_handoff(&mut self, ticket: HandoffTicket) {
+        if ticket.target_port != self.server_config.server.port {
+            info!(
+                "Ignoring handoff ticket for {} (addressed to port {})",
+                ticket.name, ticket.target_port
+            );
+            return;
+        }
+        let now = crate::persistence::unix_timestamp();
+        self.pending_handoffs.retain(|_, t| t.expires_at >= now);
+        self.pending_handoffs.insert(ticket.name.clone(), ticket);
+    }
+
+    /// In offline mode, replace the client-claimed identity with a forwarded
+    /// one if a matching handoff ticket is pending. Returns `true` if applied.
+    pub(super) fn claim_handoff(&mut self, addr: SocketAddr, login_data: &mut LoginData) -> bool {
+        let Some(ticket) = self.pending_handoffs.remove(&login_data.display_name) else {
+            return false;
+        };
+        let now = crate::persistence::unix_timestamp();
+        if !ticket.matches(&login_data.display_name, &addr.ip().to_string(), now) {
+            return false;
+        }
+        info!(
+            "Accepted forwarded identity for {} (XUID: {})",
+            ticket.name, ticket.xuid
+        );
+        login_data.xuid = ticket.xuid;
+        login_data.identity = ticket.uuid;
+        true
+    }
+}
@@ -0,0 +1,79 @@
+//! Trees: leaves decaying once their logs are gone, and the saplings and
+//! apples they drop.
+
+use std::net::SocketAddr;
+
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::types::BlockPos;
+use mc_rs_world::tree::{self, LEAF_DECAY_DELAY};
+use rand::Rng;
+
+use super::ConnectionHandler;
+
+impl ConnectionHandler {
+    /// A log was removed at `pos`: schedule a decay check on the leaves it
+    /// may have held, each after a random delay.
+    pub(super) fn schedule_leaf_decay(&mut self, pos: BlockPos) {
+        let leaves = tree::leaves_around(pos.x, pos.y, pos.z, &self.tick_blocks, |x, y, z| {
+            self.get_block(x, y, z)
+        });
+        let current_tick = self.game_world.current_tick();
+        let mut rng = rand::thread_rng();
+        for (x, y, z) in leaves {
+            let delay = rng.gen_range(LEAF_DECAY_DELAY);
+            self.tick_scheduler
+                .schedule(x, y, z, delay, current_tick, 0);
+        }
+    }
+
+    /// Of block changes about to be applied, the leaves that decay: turned
+    /// into air.
+    pub(super) fn decaying_leaves(&self, changes: &[(i32, i32, i32, u32)]) -> Vec<(BlockPos, u32)> {
+        changes
+            .iter()
+            .filter(|&&(_, _, _, rid)| rid == self.tick_blocks.air)
+            .filter_map(|&(x, y, z, _)| {
+                let old = self.get_block(x, y, z)?;
+                self.tick_blocks
+                    .is_leaf(old)
+                    .then_some((BlockPos::new(x, y, z), old))
+            })
+            .collect()
+    }
+
+    /// Whether a player holds shears, which cut leaves without their loot.
+    pub(super) fn is_holding_shears(&self, addr: SocketAddr) -> bool {
+        let Some(conn) = self.connections.get(&addr) else {
+            return false;
+        };
+        let held = conn.inventory.held_item().runtime_id;
+        held != 0
+            && self
+                .item_registry
+                .get_by_id(held as i16)
+                .is_some_and(|info| info.name == "minecraft:shears")
+    }
+
+    /// Drop the loot of leaves gone at `pos`, from the loot table named
+    /// after them: saplings now and then, and apples from oak.
+    pub(super) async fn drop_leaf_loot(&mut self, leaves: u32, pos: BlockPos) {
+        let Some(name) = self.block_registry.get(leaves).map(|info| info.name) else {
+            return;
+        };
+        let stem = name.strip_prefix("minecraft:").unwrap_or(name);
+        if !stem.ends_with("_leaves") {
+            return;
+        }
+        let Some(drops) = self.loot_tables.get(stem).map(|table| table.roll()) else {
+            return;
+        };
+        let position = (pos.x as f32 + 0.5, pos.y as f32, pos.z as f32 + 0.5);
+        for drop in drops {
+            let Some(info) = self.item_registry.get_by_name(&drop.item_name) else {
+                continue;
+            };
+            let item = ItemStack::new(info.numeric_id as i32, drop.count.clamp(1, 64) as u16);
+            self.spawn_dropped_item(item, position, 0).await;
+        }
+    }
+}
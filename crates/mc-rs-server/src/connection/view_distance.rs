@@ -0,0 +1,180 @@
+//! View and simulation distances: per-player `/viewdistance` overrides and
+//! the dynamic limiter lowering both while the server is behind.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::packets::{self, ChunkRadiusUpdated};
+use tracing::{info, warn};
+
+use super::{ConnectionHandler, LoginState};
+
+/// Largest view distance `/viewdistance` accepts.
+const MAX_VIEW_DISTANCE: i32 = 32;
+
+impl ConnectionHandler {
+    /// Largest chunk radius the player may have now: their override or the
+    /// server's view distance, lowered by the limiter.
+    pub(super) fn max_view_distance(&self, addr: SocketAddr) -> i32 {
+        let base = self
+            .connections
+            .get(&addr)
+            .and_then(|c| c.view_distance)
+            .unwrap_or(self.server_config.server.view_distance)
+            .max(1);
+        self.view_limiter
+            .limit(base, self.server_config.dynamic_view.min_view_distance)
+    }
+
+    /// Chunk radius around players in which mobs move and blocks tick,
+    /// lowered by the limiter.
+    pub(super) fn simulation_distance(&self) -> i32 {
+        self.view_limiter.limit(
+            self.server_config.server.simulation_distance.max(1),
+            self.server_config.dynamic_view.min_simulation_distance,
+        )
+    }
+
+    /// Give the player the radius they asked for, as far as they may have
+    /// now, sending the chunks they gain and forgetting the ones they lose.
+    pub(super) async fn apply_chunk_radius(&mut self, addr: SocketAddr) {
+        let max = self.max_view_distance(addr);
+        let Some(conn) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        if conn.state != LoginState::InGame {
+            return;
+        }
+        let radius = conn.requested_chunk_radius.clamp(1, max);
+        if radius == conn.chunk_radius {
+            return;
+        }
+        let grew = radius > conn.chunk_radius;
+        conn.chunk_radius = radius;
+        self.send_packet(
+            addr,
+            packets::id::CHUNK_RADIUS_UPDATED,
+            &ChunkRadiusUpdated {
+                chunk_radius: radius,
+            },
+        )
+        .await;
+        if grew {
+            self.send_new_chunks(addr).await;
+        } else {
+            self.cleanup_sent_chunks(addr).await;
+        }
+    }
+
+    /// Re-apply every player's chunk radius, after the view distances
+    /// changed.
+    pub(super) async fn apply_all_chunk_radii(&mut self) {
+        let addrs: Vec<SocketAddr> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| c.state == LoginState::InGame)
+            .map(|(&addr, _)| addr)
+            .collect();
+        for addr in addrs {
+            self.apply_chunk_radius(addr).await;
+        }
+    }
+
+    /// Every `[dynamic_view] interval`, step the limiter by the TPS.
+    pub(super) async fn tick_view_distance(&mut self) {
+        let config = &self.server_config.dynamic_view;
+        let interval = config.interval.max(1) * 20;
+        if !self.game_world.current_tick().is_multiple_of(interval) {
+            return;
+        }
+        let max_distance = self
+            .connections
+            .values()
+            .filter_map(|c| c.view_distance)
+            .chain([
+                self.server_config.server.view_distance,
+                self.server_config.server.simulation_distance,
+            ])
+            .max()
+            .unwrap_or(1);
+        let tps = self.tps.tps();
+        if !self.view_limiter.update(tps, config, max_distance) {
+            return;
+        }
+        let reduction = self.view_limiter.reduction();
+        if reduction > 0 {
+            warn!(
+                "{tps:.1} TPS: view distance lowered to {} and simulation distance to {}",
+                self.view_limiter.limit(
+                    self.server_config.server.view_distance,
+                    self.server_config.dynamic_view.min_view_distance
+                ),
+                self.simulation_distance()
+            );
+        } else {
+            info!("{tps:.1} TPS: view and simulation distances restored");
+        }
+        self.apply_all_chunk_radii().await;
+    }
+
+    /// /viewdistance [chunks|reset] [player]
+    pub(super) async fn cmd_viewdistance(
+        &mut self,
+        sender_addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        const USAGE: &str = "Usage: /viewdistance [chunks|reset] [player]";
+        let view_distance = match args.first().map(String::as_str) {
+            None => {
+                let Some(conn) = self.connections.get(&sender_addr) else {
+                    return CommandResult::err(USAGE);
+                };
+                return CommandResult::ok(format!(
+                    "View distance: {} chunks (up to {}), simulation distance: {} chunks",
+                    conn.chunk_radius,
+                    self.max_view_distance(sender_addr),
+                    self.simulation_distance()
+                ));
+            }
+            Some("reset") => None,
+            Some(chunks) => match chunks.parse::<i32>() {
+                Ok(n) if (1..=MAX_VIEW_DISTANCE).contains(&n) => Some(n),
+                _ => {
+                    return CommandResult::err(format!(
+                        "View distance must be between 1 and {MAX_VIEW_DISTANCE}"
+                    ))
+                }
+            },
+        };
+        let targets = match args.get(1) {
+            Some(target) => match self.resolve_target(target, sender_addr) {
+                Ok(t) => t,
+                Err(e) => return CommandResult::err(e),
+            },
+            None => vec![sender_name.to_string()],
+        };
+
+        let mut messages = Vec::new();
+        for target_name in &targets {
+            let Some(addr) = self.find_player_addr(target_name) else {
+                messages.push(format!("Player not found: {target_name}"));
+                continue;
+            };
+            if let Some(conn) = self.connections.get_mut(&addr) {
+                conn.view_distance = view_distance;
+            }
+            self.apply_chunk_radius(addr).await;
+            messages.push(match view_distance {
+                Some(n) => format!("Set the view distance of {target_name} to {n} chunks"),
+                None => format!("Reset the view distance of {target_name}"),
+            });
+        }
+        CommandResult {
+            success: true,
+            messages,
+            broadcast: None,
+            should_stop: false,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! `/warp`, `/setwarp`, `/delwarp` and `/spawn`, going through the same
+//! warm-up and cooldown as the other player teleports.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+
+use super::{ConnectionHandler, TeleportDestination};
+use crate::persistence::SavedLocation;
+use crate::warps::Warp;
+
+impl ConnectionHandler {
+    /// Whether a player may use a warp: operators and holders of its
+    /// permission node, or everyone if it has none.
+    fn can_use_warp(&self, player: &str, warp: &Warp) -> bool {
+        match &warp.permission {
+            Some(node) => self.permissions.is_op(player) || self.permissions.has_node(player, node),
+            None => true,
+        }
+    }
+
+    /// `/warp [name]`: teleport to a warp, or list the ones the player can use.
+    pub(super) async fn cmd_warp(
+        &mut self,
+        addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        let Some(name) = args.first() else {
+            let names: Vec<&str> = self
+                .warps
+                .iter()
+                .filter(|(_, w)| self.can_use_warp(sender_name, w))
+                .map(|(n, _)| n.as_str())
+                .collect();
+            if names.is_empty() {
+                return CommandResult::ok("No warps are available");
+            }
+            return CommandResult::ok(format!("Warps: {}", names.join(", ")));
+        };
+
+        let warp = match self.warps.get(name) {
+            Some(w) if self.can_use_warp(sender_name, w) => w.clone(),
+            Some(_) => return CommandResult::err("You do not have permission to use this warp"),
+            None => return CommandResult::err(format!("Unknown warp: {name}")),
+        };
+        let warmup = warp.warmup.unwrap_or(self.server_config.teleport.warmup);
+        self.start_teleport(
+            addr,
+            TeleportDestination::Location(warp.location),
+            name,
+            warmup,
+        )
+        .await
+    }
+
+    /// `/setwarp <name> [warmup=<seconds>] [permission=<node>]`: create or
+    /// move a warp to the sender's position.
+    pub(super) fn cmd_setwarp(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        const USAGE: &str = "Usage: /setwarp <name> [warmup=<seconds>] [permission=<node>]";
+        let Some((name, options)) = args.split_first() else {
+            return CommandResult::err(USAGE);
+        };
+        let Some(conn) = self.connections.get(&addr) else {
+            return CommandResult::err("This command must be run by a player");
+        };
+
+        let mut warp = Warp {
+            location: Self::saved_location(conn),
+            permission: None,
+            warmup: None,
+        };
+        for option in options {
+            match option.split_once('=') {
+                Some(("warmup", secs)) => match secs.parse() {
+                    Ok(secs) => warp.warmup = Some(secs),
+                    Err(_) => return CommandResult::err(format!("Invalid warm-up: {secs}")),
+                },
+                Some(("permission", node)) if !node.is_empty() => {
+                    warp.permission = Some(node.to_string())
+                }
+                _ => return CommandResult::err(format!("Invalid option: {option}\n{USAGE}")),
+            }
+        }
+
+        let [x, y, z] = warp.location.position;
+        self.warps.set(name, warp);
+        CommandResult::ok(format!("Warp {name} set at {x:.1}, {y:.1}, {z:.1}"))
+    }
+
+    /// `/delwarp <name>`
+    pub(super) fn cmd_delwarp(&mut self, args: &[String]) -> CommandResult {
+        let [name] = args else {
+            return CommandResult::err("Usage: /delwarp <name>");
+        };
+        if self.warps.remove(name) {
+            CommandResult::ok(format!("Warp {name} deleted"))
+        } else {
+            CommandResult::err(format!("Unknown warp: {name}"))
+        }
+    }
+
+    /// `/spawn`: teleport to the world spawn, leaving the Nether or the End
+    /// if needed.
+    pub(super) async fn cmd_spawn(&mut self, addr: SocketAddr) -> CommandResult {
+        let spawn = SavedLocation {
+            dimension: 0,
+            position: [
+                self.spawn_position.x,
+                self.spawn_position.y,
+                self.spawn_position.z,
+            ],
+        };
+        let warmup = self.server_config.teleport.warmup;
+        self.start_teleport(addr, TeleportDestination::Location(spawn), "spawn", warmup)
+            .await
+    }
+}
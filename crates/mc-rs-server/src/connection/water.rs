@@ -0,0 +1,97 @@
+//! Water: what players and mobs are swimming in, the swim limits of movement
+//! validation and the push of bubble columns on mobs.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::armor;
+use mc_rs_game::combat as game_combat;
+use mc_rs_proto::types::Vec3;
+use mc_rs_world::physics::{self, PlayerAabb, Pose, DOLPHINS_GRACE_RANGE};
+
+use super::ConnectionHandler;
+
+/// Mob type whose company speeds up swimming players.
+const DOLPHIN: &str = "minecraft:dolphin";
+
+/// What an entity's body is in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct Submersion {
+    /// Some part of the body is in water.
+    pub in_water: bool,
+    /// The bubble column the body is in, if any: whether it drags down.
+    pub bubble_column: Option<bool>,
+}
+
+impl ConnectionHandler {
+    /// What the blocks at these positions hold. Unloaded blocks are dry.
+    fn submersion(&self, dim: i32, blocks: impl Iterator<Item = (i32, i32, i32)>) -> Submersion {
+        let mut result = Submersion::default();
+        for (x, y, z) in blocks {
+            let Some(rid) = self.get_block_in(dim, x, y, z) else {
+                continue;
+            };
+            if self.tick_blocks.holds_water(rid) {
+                result.in_water = true;
+                result.bubble_column = result
+                    .bubble_column
+                    .or(self.tick_blocks.bubble_column_drag_down(rid));
+            }
+        }
+        result
+    }
+
+    /// What a player's body is in at a position.
+    pub(super) fn player_submersion(&self, dim: i32, position: Vec3, pose: Pose) -> Submersion {
+        let aabb = PlayerAabb::with_pose(position.x, position.y, position.z, pose);
+        self.submersion(dim, aabb.intersecting_blocks())
+    }
+
+    /// How far a player may swim in a tick, with their Depth Strider boots.
+    /// Dolphins are only looked for when the player swam further than they
+    /// could without one.
+    pub(super) fn max_swim_distance(
+        &mut self,
+        addr: SocketAddr,
+        position: Vec3,
+        distance: f32,
+    ) -> f32 {
+        let depth_strider = self
+            .connections
+            .get(&addr)
+            .map(|c| {
+                game_combat::depth_strider_level(&c.inventory.armor[armor::SLOT_FEET].nbt_data)
+            })
+            .unwrap_or(0);
+        let limit = physics::max_swim_distance(depth_strider, false);
+        if distance <= limit {
+            return limit;
+        }
+        let near_dolphin = self.game_world.all_mobs().iter().any(|mob| {
+            let (x, y, z) = mob.position;
+            let (dx, dy, dz) = (x - position.x, y - position.y, z - position.z);
+            mob.mob_type == DOLPHIN
+                && dx * dx + dy * dy + dz * dz <= DOLPHINS_GRACE_RANGE * DOLPHINS_GRACE_RANGE
+        });
+        physics::max_swim_distance(depth_strider, near_dolphin)
+    }
+
+    /// Tell the game world which mobs are in a bubble column, for its
+    /// physics to lift or drag them.
+    pub(super) fn tick_bubble_columns(&mut self) {
+        let columns: Vec<(u64, Option<bool>)> = self
+            .game_world
+            .all_mobs()
+            .into_iter()
+            .map(|mob| {
+                let (x, y, z) = mob.position;
+                let (bx, bz) = (x.floor() as i32, z.floor() as i32);
+                let body = (y.floor() as i32)..=((y + mob.bb_height).floor() as i32);
+                let submersion = self.submersion(0, body.map(|by| (bx, by, bz)));
+                (mob.runtime_id, submersion.bubble_column)
+            })
+            .collect();
+        for (runtime_id, column) in columns {
+            self.game_world.set_mob_bubble_column(runtime_id, column);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! What the weather does to the main overworld: lightning strikes during
+//! thunderstorms, snow piling up in snowy biomes and cauldrons filling while
+//! it rains. Only chunks within simulation distance of a player are affected.
+
+use std::net::SocketAddr;
+
+use mc_rs_game::lightning::{LIGHTNING_DAMAGE, STRIKE_FIRE_TICKS, STRIKE_RADIUS};
+use mc_rs_proto::packets::{
+    self, AddActor, EntityEvent, PlaySound, RemoveEntity, UpdateAttributes,
+};
+use mc_rs_proto::types::Vec3;
+use mc_rs_world::biome::is_snowy_biome;
+use mc_rs_world::block_tick::process_precipitation;
+use mc_rs_world::chunk::OVERWORLD_MIN_Y;
+use rand::Rng;
+
+use super::{ConnectionHandler, LoginState};
+
+/// Chance per simulated chunk per tick of a lightning strike in a thunderstorm.
+const LIGHTNING_CHANCE: u32 = 100_000;
+
+/// Chance per simulated chunk per tick of rain reaching a random column.
+const PRECIPITATION_CHANCE: u32 = 16;
+
+/// Ticks a lightning bolt entity is shown before it is removed.
+const BOLT_LIFETIME: u64 = 20;
+
+impl ConnectionHandler {
+    /// Y of the highest non-air block of an overworld column, if its chunk
+    /// is loaded.
+    pub(super) fn top_block_y(&self, x: i32, z: i32) -> Option<i32> {
+        let col = self.dim_chunks(0)?.get(&(x >> 4, z >> 4))?;
+        let (lx, lz) = ((x & 15) as usize, (z & 15) as usize);
+        (OVERWORLD_MIN_Y..=319).rev().find(|&y| {
+            col.get_block_world(lx, y, lz)
+                .is_some_and(|rid| rid != self.tick_blocks.air)
+        })
+    }
+
+    /// A random column `(x, z)` from each simulated chunk picked with a
+    /// 1 in `chance` chance.
+    fn random_simulated_columns(&self, chance: u32) -> Vec<(i32, i32)> {
+        let mut rng = rand::thread_rng();
+        let mut columns = Vec::new();
+        for (cx, cz) in self.get_simulation_chunks() {
+            if rng.gen_range(0..chance) == 0 {
+                columns.push((
+                    cx * 16 + rng.gen_range(0..16),
+                    cz * 16 + rng.gen_range(0..16),
+                ));
+            }
+        }
+        columns
+    }
+
+    /// Random strikes in simulated chunks while thundering, and removal of
+    /// spent bolts.
+    pub(super) async fn tick_lightning(&mut self, tick: u64) {
+        let expired: Vec<i64> = self
+            .lightning_bolts
+            .iter()
+            .filter(|(_, expires)| tick >= *expires)
+            .map(|(id, _)| *id)
+            .collect();
+        if !expired.is_empty() {
+            self.lightning_bolts.retain(|(_, expires)| tick < *expires);
+            for entity_unique_id in expired {
+                self.broadcast_packet_in_dimension(
+                    0,
+                    packets::id::REMOVE_ENTITY,
+                    &RemoveEntity { entity_unique_id },
+                )
+                .await;
+            }
+        }
+
+        if !self.is_thundering || self.lightning_level <= 0.5 {
+            return;
+        }
+        for (x, z) in self.random_simulated_columns(LIGHTNING_CHANCE) {
+            if let Some(y) = self.top_block_y(x, z) {
+                self.strike_lightning(Vec3::new(x as f32 + 0.5, (y + 1) as f32, z as f32 + 0.5))
+                    .await;
+            }
+        }
+    }
+
+    /// Strike lightning at a point of the overworld: show the bolt, set the
+    /// ground on fire, and hit the players and mobs around it.
+    pub(super) async fn strike_lightning(&mut self, pos: Vec3) {
+        let tick = self.game_world.current_tick();
+        let entity_id = self.allocate_entity_id();
+        let bolt = AddActor {
+            entity_unique_id: entity_id,
+            entity_runtime_id: entity_id as u64,
+            entity_type: "minecraft:lightning_bolt".to_string(),
+            position: pos,
+            velocity: Vec3::ZERO,
+            pitch: 0.0,
+            yaw: 0.0,
+            head_yaw: 0.0,
+            body_yaw: 0.0,
+            attributes: vec![],
+            metadata: vec![],
+        };
+        self.broadcast_packet_in_dimension(0, packets::id::ADD_ACTOR, &bolt)
+            .await;
+        self.lightning_bolts.push((entity_id, tick + BOLT_LIFETIME));
+        for sound in [
+            "ambient.weather.thunder",
+            "ambient.weather.lightning.impact",
+        ] {
+            let pkt = PlaySound::new(sound, pos.x, pos.y, pos.z, 1.0, 1.0);
+            self.broadcast_packet_in_dimension(0, packets::id::PLAY_SOUND, &pkt)
+                .await;
+        }
+
+        // Fire where the bolt lands
+        let (bx, by, bz) = (
+            pos.x.floor() as i32,
+            pos.y.floor() as i32,
+            pos.z.floor() as i32,
+        );
+        let ground_solid = self
+            .get_block(bx, by - 1, bz)
+            .is_some_and(|rid| self.block_registry.is_solid(rid));
+        if ground_solid && self.get_block(bx, by, bz) == Some(self.tick_blocks.air) {
+            let fire = self.tick_blocks.fire;
+            self.set_block_and_broadcast(bx, by, bz, fire).await;
+        }
+
+        let hit_players: Vec<SocketAddr> = self
+            .connections
+            .iter()
+            .filter(|(_, c)| {
+                c.state == LoginState::InGame
+                    && c.dimension == 0
+                    && !c.is_dead
+                    && c.gamemode != 1
+                    && c.gamemode != 3
+                    && c.position.distance(&pos) <= STRIKE_RADIUS + 1.62
+            })
+            .map(|(&a, _)| a)
+            .collect();
+        for addr in hit_players {
+            let damage = LIGHTNING_DAMAGE * (1.0 - self.get_resistance_factor(addr));
+            let hp = self.change_health(addr, -damage);
+            let Some(conn) = self.connections.get_mut(&addr) else {
+                continue;
+            };
+            conn.fire_ticks = conn.fire_ticks.max(STRIKE_FIRE_TICKS);
+            let rid = conn.entity_runtime_id;
+            let name = conn
+                .login_data
+                .as_ref()
+                .map(|d| d.display_name.clone())
+                .unwrap_or_default();
+            self.broadcast_packet(packets::id::ENTITY_EVENT, &EntityEvent::hurt(rid))
+                .await;
+            self.send_packet(
+                addr,
+                packets::id::UPDATE_ATTRIBUTES,
+                &UpdateAttributes::health(rid, hp, tick),
+            )
+            .await;
+            if hp <= 0.0 {
+                self.handle_player_death_with_message(
+                    addr,
+                    &format!("{name} was struck by lightning"),
+                )
+                .await;
+            }
+        }
+
+        self.game_world.strike_lightning(pos.x, pos.y, pos.z, tick);
+    }
+
+    /// While it rains, pile snow in snowy biomes and fill cauldrons in the
+    /// others, on a random column of some simulated chunks each tick.
+    pub(super) async fn tick_precipitation(&mut self) {
+        if !self.is_raining || self.rain_level <= 0.2 {
+            return;
+        }
+        let mut changes = Vec::new();
+        for (x, z) in self.random_simulated_columns(PRECIPITATION_CHANCE) {
+            let Some(y) = self.top_block_y(x, z) else {
+                continue;
+            };
+            let Some(biome) = self.biome_in(0, x, y + 1, z) else {
+                continue;
+            };
+            let snowy = is_snowy_biome(biome);
+            let Some(top) = self.get_block(x, y, z) else {
+                continue;
+            };
+            changes.extend(process_precipitation(
+                top,
+                x,
+                y,
+                z,
+                snowy,
+                &self.tick_blocks,
+                |rid| self.block_registry.is_solid(rid),
+            ));
+        }
+        for (x, y, z, rid) in changes {
+            self.set_block_and_broadcast(x, y, z, rid).await;
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Per-dimension world flags: the damage gate every player damage source
+//! goes through, and `/worldflag`.
+
+use std::net::SocketAddr;
+
+use mc_rs_command::CommandResult;
+use mc_rs_plugin_api::DamageCause;
+use tracing::info;
+
+use super::ConnectionHandler;
+use crate::world_flags::{self, WorldFlag};
+
+impl ConnectionHandler {
+    /// Whether a player may take damage from `cause` in their current
+    /// dimension. `by_player` marks attacks and projectiles from another
+    /// player, which need PvP on.
+    pub(super) fn damage_allowed(
+        &self,
+        addr: SocketAddr,
+        cause: DamageCause,
+        by_player: bool,
+    ) -> bool {
+        let Some(conn) = self.connections.get(&addr) else {
+            return false;
+        };
+        self.world_flags
+            .get(conn.dimension as usize)
+            .is_none_or(|flags| flags.allows_damage(cause, by_player))
+    }
+
+    /// Whether a world flag is on in a dimension.
+    pub(super) fn world_flag(&self, dim: i32, flag: WorldFlag) -> bool {
+        self.world_flags
+            .get(dim as usize)
+            .is_none_or(|flags| flags.get(flag))
+    }
+
+    pub(super) fn set_world_flag(&mut self, dim: i32, flag: WorldFlag, value: bool) {
+        if let Some(flags) = self.world_flags.get_mut(dim as usize) {
+            flags.set(flag, value);
+            info!(
+                "World flag {} set to {value} in {}",
+                flag.name(),
+                world_flags::dimension_name(dim)
+            );
+        }
+    }
+
+    /// `/worldflag [<flag> [true|false]] [dimension]`: show or set the flags
+    /// of a dimension, the sender's by default.
+    pub(super) fn cmd_worldflag(&mut self, addr: SocketAddr, args: &[String]) -> CommandResult {
+        const USAGE: &str = "Usage: /worldflag [<pvp|fall_damage|drowning|hunger> [true|false]] [overworld|nether|end]";
+        let mut args = args;
+        let mut dim = self
+            .connections
+            .get(&addr)
+            .map(|c| c.dimension)
+            .unwrap_or(0);
+        if let Some((last, rest)) = args.split_last() {
+            if let Some(d) = world_flags::dimension_from_name(last) {
+                dim = d;
+                args = rest;
+            }
+        }
+        let dim_name = world_flags::dimension_name(dim);
+
+        match args {
+            [] => {
+                let flags: Vec<String> = WorldFlag::ALL
+                    .iter()
+                    .map(|&f| format!("{} = {}", f.name(), self.world_flag(dim, f)))
+                    .collect();
+                CommandResult::ok(format!("World flags in {dim_name}: {}", flags.join(", ")))
+            }
+            [name] => match WorldFlag::from_name(name) {
+                Some(flag) => CommandResult::ok(format!(
+                    "{name} = {} in {dim_name}",
+                    self.world_flag(dim, flag)
+                )),
+                None => CommandResult::err(format!("Unknown world flag: {name}\n{USAGE}")),
+            },
+            [name, value] => {
+                let Some(flag) = WorldFlag::from_name(name) else {
+                    return CommandResult::err(format!("Unknown world flag: {name}\n{USAGE}"));
+                };
+                let value = match value.to_lowercase().as_str() {
+                    "true" => true,
+                    "false" => false,
+                    _ => {
+                        return CommandResult::err(format!(
+                            "Invalid value: {value} (expected true/false)"
+                        ))
+                    }
+                };
+                self.set_world_flag(dim, flag, value);
+                CommandResult::ok(format!("World flag {name} set to {value} in {dim_name}"))
+            }
+            _ => CommandResult::err(USAGE),
+        }
+    }
+}
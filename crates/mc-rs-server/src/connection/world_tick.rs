@@ -1,21 +1,18 @@
 use super::*;
+use mc_rs_world::bubble_column;
+use mc_rs_world::parallel_tick::{
+    group_by_region, region_of_block, region_of_chunk, run_regions, BlockChange, Settled,
+    UpdateQueue,
+};
 
 impl ConnectionHandler {
-    /// Process random ticks and scheduled ticks for loaded chunks.
     /// Advance world time and weather each tick.
     pub(super) async fn tick_time_and_weather(&mut self) {
         let tick = self.game_world.current_tick();
 
         // --- Day/night cycle ---
         if self.do_daylight_cycle {
-            self.world_time += 1;
-            // Broadcast SetTime every 200 ticks (10 seconds)
-            if tick.is_multiple_of(200) {
-                let pkt = SetTime {
-                    time: self.world_time as i32,
-                };
-                self.broadcast_packet(packets::id::SET_TIME, &pkt).await;
-            }
+            self.tick_world_times(tick).await;
         }
 
         // --- Weather cycle ---
@@ -44,57 +41,9 @@ impl ConnectionHandler {
             }
         }
 
-        // --- Lightning strikes (rare, during thunderstorms) ---
-        if self.is_thundering && self.lightning_level > 0.5 && tick.is_multiple_of(100) {
-            // Scope RNG before any .await
-            let strike_pos = {
-                let mut rng = rand::thread_rng();
-                // ~1% chance per check (every 5 seconds) = roughly 1 strike per 500 seconds
-                if rng.gen_range(0..100) < 1 {
-                    let chunk_keys: Vec<(i32, i32)> = self
-                        .dim_chunks(0)
-                        .map(|m| m.keys().copied().collect())
-                        .unwrap_or_default();
-                    if !chunk_keys.is_empty() {
-                        let &(cx, cz) = chunk_keys.choose(&mut rng).unwrap();
-                        let lx = rng.gen_range(0..16);
-                        let lz = rng.gen_range(0..16);
-                        let world_x = cx * 16 + lx;
-                        let world_z = cz * 16 + lz;
-                        let world_y =
-                            if let Some(col) = self.dim_chunks(0).and_then(|m| m.get(&(cx, cz))) {
-                                let mut y = 319;
-                                while y > -64 {
-                                    if col
-                                        .get_block_world(lx as usize, y, lz as usize)
-                                        .unwrap_or(0)
-                                        != 0
-                                    {
-                                        break;
-                                    }
-                                    y -= 1;
-                                }
-                                y + 1
-                            } else {
-                                64
-                            };
-                        Some((world_x, world_y, world_z))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            };
-            if let Some((wx, wy, wz)) = strike_pos {
-                let pkt = LevelEvent {
-                    event_id: mc_rs_proto::packets::level_event::START_THUNDER,
-                    position: Vec3::new(wx as f32 + 0.5, wy as f32, wz as f32 + 0.5),
-                    data: 0,
-                };
-                self.broadcast_packet(packets::id::LEVEL_EVENT, &pkt).await;
-            }
-        }
+        // --- Lightning, snow and cauldrons ---
+        self.tick_lightning(tick).await;
+        self.tick_precipitation().await;
     }
 
     /// Pick the next weather state randomly.
@@ -162,97 +111,179 @@ impl ConnectionHandler {
         self.is_thundering = new_thundering;
     }
 
+    /// Process random ticks and scheduled ticks for loaded chunks, each
+    /// region of chunks in parallel (see [`mc_rs_world::parallel_tick`]).
     pub(super) async fn tick_block_updates(&mut self) {
         let current_tick = self.game_world.current_tick();
+        let no_chunks = HashMap::new();
 
         // 1. Random ticks: 1 random block per non-empty sub-chunk for chunks near players
-        // Collect all changes first (RNG must be scoped before any .await)
-        let all_changes = {
-            let sim_chunks = self.get_simulation_chunks();
-            let mut rng = thread_rng();
-            let mut changes: Vec<(i32, i32, i32, u32)> = Vec::new();
-
-            for (cx, cz) in &sim_chunks {
-                if let Some(column) = self.dim_chunks(0).and_then(|m| m.get(&(*cx, *cz))) {
-                    for sub_idx in 0..OVERWORLD_SUB_CHUNK_COUNT {
-                        // Skip empty sub-chunks (palette = [air] only)
-                        if column.sub_chunks[sub_idx].palette.len() <= 1 {
-                            continue;
-                        }
-
-                        let bx = rng.gen_range(0..16usize);
-                        let by = rng.gen_range(0..16usize);
-                        let bz = rng.gen_range(0..16usize);
-                        let rid = column.sub_chunks[sub_idx].get_block(bx, by, bz);
-
-                        if rid == self.tick_blocks.air {
-                            continue;
-                        }
-
-                        let wx = cx * 16 + bx as i32;
-                        let wy = OVERWORLD_MIN_Y + sub_idx as i32 * 16 + by as i32;
-                        let wz = cz * 16 + bz as i32;
-
-                        let result = process_random_tick(
-                            rid,
-                            wx,
-                            wy,
-                            wz,
-                            &self.tick_blocks,
-                            |x, y, z| self.get_block(x, y, z),
-                            |rid| self.block_registry.is_solid(rid),
-                        );
-                        changes.extend(result);
-                    }
+        let random_changes = {
+            let chunks = self.dim_chunks(0).unwrap_or(&no_chunks);
+            let regions = group_by_region(self.get_simulation_chunks(), |&(cx, cz)| {
+                region_of_chunk(cx, cz)
+            });
+            let (tick_blocks, block_registry) = (&self.tick_blocks, &self.block_registry);
+            let results = run_regions(&regions, |region_chunks| {
+                random_tick_chunks(region_chunks, chunks, tick_blocks, block_registry)
+            });
+            let mut queue = UpdateQueue::new();
+            for (region, ticks) in results {
+                for changes in ticks {
+                    queue.push(region, (), changes, ());
                 }
             }
-            changes
-        }; // rng dropped here, before any .await
-
-        // Apply all random tick changes
-        for (x, y, z, new_rid) in all_changes {
+            // A random tick refused at a region border is just skipped
+            queue.settle().changes
+        };
+        let decayed = self.decaying_leaves(&random_changes);
+        for (x, y, z, new_rid) in random_changes {
             self.set_block_and_broadcast(x, y, z, new_rid).await;
         }
+        for (pos, leaves) in decayed {
+            self.drop_leaf_loot(leaves, pos).await;
+        }
 
         // 2. Scheduled ticks (fluid flow, gravity, redstone)
         let ready = self.tick_scheduler.drain_ready(current_tick);
-        let scheduled_results: Vec<_> = ready
-            .iter()
-            .map(|tick| {
-                process_scheduled_tick(
-                    tick.x,
-                    tick.y,
-                    tick.z,
-                    &self.tick_blocks,
-                    |x, y, z| self.get_block(x, y, z),
-                    |rid| self.block_registry.is_solid(rid),
-                )
+        let results = {
+            let chunks = self.dim_chunks(0).unwrap_or(&no_chunks);
+            let regions = group_by_region(ready, |tick| region_of_block(tick.x, tick.z));
+            let (tick_blocks, block_registry) = (&self.tick_blocks, &self.block_registry);
+            run_regions(&regions, |ticks| {
+                ticks
+                    .iter()
+                    .map(|tick| {
+                        let result = process_scheduled_tick(
+                            tick.x,
+                            tick.y,
+                            tick.z,
+                            tick_blocks,
+                            |x, y, z| block_in(chunks, x, y, z),
+                            |rid| block_registry.is_solid(rid),
+                        );
+                        ((tick.x, tick.y, tick.z), result)
+                    })
+                    .collect::<Vec<_>>()
             })
-            .collect();
-        for result in scheduled_results {
-            for (x, y, z, rid) in result.changes {
-                self.set_block_and_broadcast(x, y, z, rid).await;
+        };
+        let mut queue = UpdateQueue::new();
+        for (region, ticks) in results {
+            for (origin, result) in ticks {
+                queue.push(
+                    region,
+                    origin,
+                    result.changes,
+                    (result.schedule, result.neighbor_updates),
+                );
             }
-            for (x, y, z, delay, prio) in result.schedule {
+        }
+        // Only ticks whose changes are applied schedule their follow-ups
+        let Settled {
+            changes,
+            follow_ups,
+            refused,
+        } = queue.settle();
+        let mut neighbor_updates = Vec::new();
+        for (schedule, neighbors) in follow_ups {
+            for (x, y, z, delay, prio) in schedule {
                 self.tick_scheduler
                     .schedule(x, y, z, delay, current_tick, prio);
             }
-            // Trigger neighbor updates for piston-moved blocks
-            for &(nx, ny, nz) in &result.neighbor_updates {
-                self.schedule_fluid_neighbors(nx, ny, nz);
-                self.schedule_piston_neighbors(nx, ny, nz);
-            }
-            for (nx, ny, nz) in result.neighbor_updates {
-                self.update_redstone_from(nx, ny, nz).await;
+            neighbor_updates.extend(neighbors);
+        }
+        let decayed = self.decaying_leaves(&changes);
+        for (x, y, z, rid) in changes {
+            self.set_block_and_broadcast(x, y, z, rid).await;
+        }
+        for (pos, leaves) in decayed {
+            self.drop_leaf_loot(leaves, pos).await;
+        }
+        // Ticks refused at a region border run again next tick
+        for (x, y, z) in refused {
+            self.tick_scheduler.schedule(x, y, z, 1, current_tick, 0);
+        }
+        // Trigger neighbor updates for piston-moved blocks
+        for &(nx, ny, nz) in &neighbor_updates {
+            self.schedule_fluid_neighbors(nx, ny, nz);
+            self.schedule_piston_neighbors(nx, ny, nz);
+        }
+        self.update_redstone_in_regions(neighbor_updates).await;
+    }
+
+    /// Recalculate redstone wire near each of `positions`, like
+    /// [`update_redstone_from`](Self::update_redstone_from) but each region
+    /// in parallel. Recalculations refused at a region border run again on
+    /// their own once the others are applied.
+    async fn update_redstone_in_regions(&mut self, mut positions: Vec<(i32, i32, i32)>) {
+        let mut seen = HashSet::new();
+        positions.retain(|p| seen.insert(*p));
+        if positions.is_empty() {
+            return;
+        }
+        self.enter_section(crate::timings::REDSTONE);
+        let current_tick = self.game_world.current_tick();
+        let no_chunks = HashMap::new();
+        let playing = self.playing_jukeboxes();
+        let results = {
+            let chunks = self.dim_chunks(0).unwrap_or(&no_chunks);
+            let regions = group_by_region(positions.iter().copied(), |&(x, _, z)| {
+                region_of_block(x, z)
+            });
+            let (tick_blocks, block_registry) = (&self.tick_blocks, &self.block_registry);
+            run_regions(&regions, |origins| {
+                origins
+                    .iter()
+                    .map(|&(x, y, z)| {
+                        let result = redstone::recalculate_wire_from(
+                            x,
+                            y,
+                            z,
+                            tick_blocks,
+                            |bx, by, bz| {
+                                let block = block_in(chunks, bx, by, bz);
+                                music::redstone_view(&playing, tick_blocks, (bx, by, bz), block)
+                            },
+                            |rid| block_registry.is_solid(rid),
+                        );
+                        ((x, y, z), result)
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+        let mut queue = UpdateQueue::new();
+        for (region, origins) in results {
+            for (origin, result) in origins {
+                queue.push(region, origin, result.changes, result.schedule);
             }
         }
+        let Settled {
+            changes,
+            follow_ups,
+            refused,
+        } = queue.settle();
+        for (sx, sy, sz, delay, prio) in follow_ups.into_iter().flatten() {
+            self.tick_scheduler
+                .schedule(sx, sy, sz, delay, current_tick, prio);
+        }
+        for (x, y, z, rid) in changes {
+            self.set_block_and_broadcast(x, y, z, rid).await;
+        }
+        // Check if any adjacent pistons need to update
+        for (x, y, z) in positions {
+            self.schedule_piston_neighbors(x, y, z);
+        }
+        self.exit_section();
+        for (x, y, z) in refused {
+            self.update_redstone_from(x, y, z).await;
+        }
     }
 
-    /// Get the set of chunk coordinates within simulation distance (4 chunks) of any player.
+    /// Get the set of chunk coordinates within simulation distance of any player.
     /// Only considers overworld (dim=0) chunks for tick processing.
-    fn get_simulation_chunks(&self) -> HashSet<(i32, i32)> {
+    pub(super) fn get_simulation_chunks(&self) -> HashSet<(i32, i32)> {
         let mut chunks = HashSet::new();
-        let sim_radius = 4i32;
+        let sim_radius = self.simulation_distance();
         let ow_chunks = self.dim_chunks(0);
 
         for conn in self.connections.values() {
@@ -332,6 +363,16 @@ impl ConnectionHandler {
                         current_tick,
                         0,
                     );
+                } else if self.tick_blocks.holds_water(rid) {
+                    // Bubble columns, and generated water that may become one
+                    self.tick_scheduler.schedule(
+                        nx,
+                        ny,
+                        nz,
+                        bubble_column::BUBBLE_COLUMN_TICK_DELAY,
+                        current_tick,
+                        0,
+                    );
                 }
             }
         }
@@ -341,13 +382,18 @@ impl ConnectionHandler {
     ///
     /// Called after block break/place or lever toggle to propagate signal changes.
     pub(super) async fn update_redstone_from(&mut self, x: i32, y: i32, z: i32) {
+        self.enter_section(crate::timings::REDSTONE);
         let current_tick = self.game_world.current_tick();
+        let playing = self.playing_jukeboxes();
         let result = redstone::recalculate_wire_from(
             x,
             y,
             z,
             &self.tick_blocks,
-            |bx, by, bz| self.get_block(bx, by, bz),
+            |bx, by, bz| {
+                let block = self.get_block(bx, by, bz);
+                music::redstone_view(&playing, &self.tick_blocks, (bx, by, bz), block)
+            },
             |rid| self.block_registry.is_solid(rid),
         );
         for (cx, cy, cz, rid) in result.changes {
@@ -359,6 +405,7 @@ impl ConnectionHandler {
         }
         // Check if any adjacent pistons need to update
         self.schedule_piston_neighbors(x, y, z);
+        self.exit_section();
     }
 
     /// Schedule piston ticks for pistons adjacent to a changed position.
@@ -391,15 +438,16 @@ impl ConnectionHandler {
 
     /// Send XP attributes to a player.
     pub(super) async fn send_xp_attributes(&mut self, addr: SocketAddr) {
-        let (rid, level, total, tick) = match self.connections.get(&addr) {
-            Some(c) => (c.entity_runtime_id, c.xp_level, c.xp_total, c.client_tick),
+        let (rid, tick) = match self.connections.get(&addr) {
+            Some(c) => (c.entity_runtime_id, c.client_tick),
             None => return,
         };
-        let progress = xp::xp_progress(level, total);
+        let xp = self.experience(addr);
+        let progress = xp::xp_progress(xp.level, xp.total);
         self.send_packet(
             addr,
             packets::id::UPDATE_ATTRIBUTES,
-            &UpdateAttributes::xp(rid, level, progress, tick),
+            &UpdateAttributes::xp(rid, xp.level, progress, tick),
         )
         .await;
     }
@@ -416,11 +464,9 @@ impl ConnectionHandler {
         if gamemode != 0 {
             return; // survival only
         }
-        if let Some(conn) = self.connections.get_mut(&addr) {
-            let (nl, nt) = xp::add_xp(conn.xp_level, conn.xp_total, amount);
-            conn.xp_level = nl;
-            conn.xp_total = nt;
-        }
+        let xp = self.experience(addr);
+        let (nl, nt) = xp::add_xp(xp.level, xp.total, amount);
+        self.set_experience(addr, nl, nt);
         self.send_xp_attributes(addr).await;
     }
 
@@ -475,6 +521,72 @@ impl ConnectionHandler {
         }
     }
 
+    /// Count the complete pyramid layers under an overworld beacon.
+    pub(super) fn beacon_levels(&self, x: i32, y: i32, z: i32) -> u8 {
+        mc_rs_game::beacon::pyramid_levels(x, y, z, |bx, by, bz| {
+            self.get_block(bx, by, bz)
+                .is_some_and(|rid| self.block_entity_hashes.is_beacon_base(rid))
+        })
+    }
+
+    /// Pulse every active beacon, applying its powers to players in range.
+    pub(super) async fn tick_beacons(&mut self) {
+        use mc_rs_game::beacon;
+
+        if !self
+            .game_world
+            .current_tick()
+            .is_multiple_of(beacon::PULSE_INTERVAL_TICKS)
+        {
+            return;
+        }
+
+        let beacons: Vec<((i32, i32, i32), i32, i32)> = self
+            .block_entities
+            .iter()
+            .filter_map(|(&(x, y, z, dim), be)| match be {
+                BlockEntityData::Beacon {
+                    primary, secondary, ..
+                } if dim == 0 && *primary != 0 => Some(((x, y, z), *primary, *secondary)),
+                _ => None,
+            })
+            .collect();
+
+        for ((x, y, z), primary, secondary) in beacons {
+            let levels = self.beacon_levels(x, y, z);
+            let effects = beacon::active_effects(primary, secondary, levels);
+            if effects.is_empty() {
+                continue;
+            }
+            let range = beacon::effect_range(levels);
+            let duration = beacon::effect_duration_ticks(levels);
+            let (cx, cz) = (x as f32 + 0.5, z as f32 + 0.5);
+
+            // The area extends `range` blocks horizontally and from `range`
+            // below the beacon all the way up.
+            let targets: Vec<SocketAddr> = self
+                .connections
+                .iter()
+                .filter(|(_, c)| {
+                    c.state == LoginState::InGame
+                        && !c.is_dead
+                        && c.dimension == 0
+                        && (c.position.x - cx).abs() <= range
+                        && (c.position.z - cz).abs() <= range
+                        && c.position.y >= y as f32 - range
+                })
+                .map(|(&a, _)| a)
+                .collect();
+
+            for addr in targets {
+                for &(effect_id, amplifier) in &effects {
+                    self.apply_effect(addr, effect_id, amplifier, duration)
+                        .await;
+                }
+            }
+        }
+    }
+
     /// Tick a single furnace at the given position (x, y, z, dim).
     async fn tick_single_furnace(&mut self, pos: (i32, i32, i32, i32)) {
         // Extract current furnace state
@@ -731,3 +843,51 @@ impl ConnectionHandler {
         }
     }
 }
+
+/// Random ticks of some chunks: one random block per non-empty sub-chunk.
+/// Returns the changes of each block ticked.
+fn random_tick_chunks(
+    region_chunks: &[(i32, i32)],
+    chunks: &HashMap<(i32, i32), ChunkColumn>,
+    tick_blocks: &TickBlocks,
+    block_registry: &BlockRegistry,
+) -> Vec<Vec<BlockChange>> {
+    let mut rng = thread_rng();
+    let mut ticks = Vec::new();
+    for &(cx, cz) in region_chunks {
+        let Some(column) = chunks.get(&(cx, cz)) else {
+            continue;
+        };
+        for sub_idx in 0..OVERWORLD_SUB_CHUNK_COUNT {
+            // Skip empty sub-chunks (palette = [air] only)
+            if column.sub_chunks[sub_idx].palette.len() <= 1 {
+                continue;
+            }
+
+            let bx = rng.gen_range(0..16usize);
+            let by = rng.gen_range(0..16usize);
+            let bz = rng.gen_range(0..16usize);
+            let rid = column.sub_chunks[sub_idx].get_block(bx, by, bz);
+
+            if rid == tick_blocks.air {
+                continue;
+            }
+
+            let wx = cx * 16 + bx as i32;
+            let wy = OVERWORLD_MIN_Y + sub_idx as i32 * 16 + by as i32;
+            let wz = cz * 16 + bz as i32;
+
+            let changes = process_random_tick(
+                rid,
+                wx,
+                wy,
+                wz,
+                tick_blocks,
+                |x, y, z| block_in(chunks, x, y, z),
+                |rid| block_registry.is_solid(rid),
+            );
+            ticks.push(changes);
+        }
+    }
+    ticks
+}
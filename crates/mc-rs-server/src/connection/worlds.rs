@@ -0,0 +1,270 @@
+//! Multi-world hosting: the extra worlds from `[worlds.<name>]`, the chunk
+//! generator of each dimension, and `/world`.
+//!
+//! Extra worlds live next to the main world's dimensions under their own
+//! dimension IDs (see `crate::worlds`), so chunk maps, block entities and
+//! dimension-scoped broadcasts keep them apart. Clients only know the three
+//! vanilla dimensions, so packets carry the world's base dimension instead.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use mc_rs_command::CommandResult;
+use mc_rs_proto::packets::{self, Text};
+use mc_rs_proto::types::Vec3;
+use mc_rs_world::block_hash::FlatWorldBlocks;
+use mc_rs_world::chunk::ChunkColumn;
+use mc_rs_world::end_generator::EndGenerator;
+use mc_rs_world::flat_generator::generate_flat_chunk;
+use mc_rs_world::nether_generator::NetherGenerator;
+use mc_rs_world::overworld_generator::OverworldGenerator;
+use mc_rs_world::structure::StructureBounds;
+use tracing::info;
+
+use super::{ConnectionHandler, LoginState};
+use crate::config::ExtraWorldSection;
+use crate::persistence::SavedLocation;
+
+/// Terrain generator of one dimension, cheap to clone into blocking tasks.
+#[derive(Clone)]
+pub enum DimensionGenerator {
+    Overworld(Arc<OverworldGenerator>),
+    Nether(Arc<NetherGenerator>),
+    End(Arc<EndGenerator>),
+    Flat(FlatWorldBlocks),
+}
+
+impl DimensionGenerator {
+    fn new(generator: &str, seed: u64, flat_blocks: FlatWorldBlocks) -> Self {
+        match generator.to_lowercase().as_str() {
+            "flat" => Self::Flat(flat_blocks),
+            "nether" => Self::Nether(Arc::new(NetherGenerator::new(seed))),
+            "end" => Self::End(Arc::new(EndGenerator::new(seed))),
+            _ => Self::Overworld(Arc::new(OverworldGenerator::new(seed))),
+        }
+    }
+
+    /// Generate a chunk, with the bounds of the structures placed in it.
+    pub fn generate(&self, cx: i32, cz: i32) -> (ChunkColumn, Vec<StructureBounds>) {
+        match self {
+            Self::Overworld(g) => g.generate_chunk_with_structures(cx, cz),
+            Self::Nether(g) => (g.generate_chunk(cx, cz), Vec::new()),
+            Self::End(g) => (g.generate_chunk(cx, cz), Vec::new()),
+            Self::Flat(fb) => (generate_flat_chunk(cx, cz, fb), Vec::new()),
+        }
+    }
+
+    /// Spawn point (eye position) near the origin.
+    fn spawn_position(&self) -> Vec3 {
+        let feet_y = match self {
+            Self::Overworld(g) => g.find_spawn_y(),
+            Self::Nether(g) => g.find_spawn_y(),
+            Self::End(g) => g.find_spawn_y(),
+            Self::Flat(_) => return Vec3::new(0.5, 5.62, 0.5),
+        };
+        Vec3::new(8.5, feet_y as f32 + 1.62, 8.5)
+    }
+}
+
+/// A world hosted next to the main one.
+pub struct ExtraWorld {
+    pub name: String,
+    /// Dimension ID the world is stored and tracked under.
+    pub dimension: i32,
+    /// Vanilla dimension clients render it as.
+    pub base_dimension: i32,
+    pub generator: DimensionGenerator,
+    /// Spawn point (eye position).
+    pub spawn_position: Vec3,
+    /// Time of day in ticks, advancing like the main world's.
+    pub time: i64,
+}
+
+impl ExtraWorld {
+    /// Build the worlds from the config, with their dimension IDs and saved
+    /// times of day.
+    pub fn from_config(
+        worlds: &std::collections::HashMap<String, ExtraWorldSection>,
+        ids: &BTreeMap<String, i32>,
+        times: &BTreeMap<String, i64>,
+        default_seed: i64,
+        flat_blocks: FlatWorldBlocks,
+    ) -> Vec<Self> {
+        ids.iter()
+            .filter_map(|(name, &dimension)| {
+                let section = worlds.get(name)?;
+                let seed = section.seed.unwrap_or(default_seed) as u64;
+                let generator = DimensionGenerator::new(&section.generator, seed, flat_blocks);
+                let spawn_position = match section.spawn {
+                    Some([x, y, z]) => Vec3::new(x, y + 1.62, z),
+                    None => generator.spawn_position(),
+                };
+                info!(
+                    "Hosting world {name} ({} generator, dimension {dimension})",
+                    section.generator
+                );
+                Some(Self {
+                    name: name.clone(),
+                    dimension,
+                    base_dimension: crate::worlds::base_dimension(&section.generator),
+                    generator,
+                    spawn_position,
+                    time: times.get(name).copied().unwrap_or(0),
+                })
+            })
+            .collect()
+    }
+}
+
+impl ConnectionHandler {
+    fn extra_world(&self, dim: i32) -> Option<&ExtraWorld> {
+        self.extra_worlds.iter().find(|w| w.dimension == dim)
+    }
+
+    /// Whether a dimension ID belongs to the main world or a hosted one.
+    pub(super) fn dimension_exists(&self, dim: i32) -> bool {
+        (0..3).contains(&dim) || self.extra_world(dim).is_some()
+    }
+
+    /// Vanilla dimension sent to clients for a dimension ID.
+    pub(super) fn client_dimension(&self, dim: i32) -> i32 {
+        self.extra_world(dim).map_or(dim, |w| w.base_dimension)
+    }
+
+    /// Name of the world a dimension belongs to.
+    pub(super) fn world_name(&self, dim: i32) -> &str {
+        self.extra_world(dim)
+            .map_or(self.server_config.world.name.as_str(), |w| &w.name)
+    }
+
+    /// Generator for the chunks of a dimension.
+    pub(super) fn chunk_generator(&self, dim: i32) -> DimensionGenerator {
+        let flat = DimensionGenerator::Flat(self.flat_world_blocks);
+        if let Some(world) = self.extra_world(dim) {
+            return world.generator.clone();
+        }
+        match dim {
+            1 => self
+                .nether_generator
+                .clone()
+                .map_or(flat, DimensionGenerator::Nether),
+            2 => self
+                .end_generator
+                .clone()
+                .map_or(flat, DimensionGenerator::End),
+            _ => self
+                .overworld_generator
+                .clone()
+                .map_or(flat, DimensionGenerator::Overworld),
+        }
+    }
+
+    /// Move a player to another world: where they last were in it, or its
+    /// spawn. Their position in the world they leave is kept for their return.
+    async fn move_to_world(&mut self, addr: SocketAddr, world: &str) -> Result<(), String> {
+        let (target_dim, spawn) = if world == self.server_config.world.name {
+            (self.dimension_id, self.spawn_position)
+        } else {
+            match self.extra_worlds.iter().find(|w| w.name == world) {
+                Some(w) => (w.dimension, w.spawn_position),
+                None => return Err(format!("Unknown world: {world}")),
+            }
+        };
+
+        let Some(conn) = self.connections.get(&addr) else {
+            return Err("Player not found".into());
+        };
+        let src_dim = conn.dimension;
+        let current = self.world_name(src_dim).to_string();
+        if current == world {
+            return Err(format!("Already in world {world}"));
+        }
+        let destination = conn
+            .world_positions
+            .get(world)
+            .copied()
+            .filter(|loc| self.dimension_exists(loc.dimension))
+            .unwrap_or(SavedLocation {
+                dimension: target_dim,
+                position: [spawn.x, spawn.y, spawn.z],
+            });
+
+        let entity_unique_id = match self.connections.get_mut(&addr) {
+            Some(conn) => {
+                let here = Self::saved_location(conn);
+                conn.world_positions.insert(current, here);
+                conn.entity_unique_id
+            }
+            None => return Err("Player not found".into()),
+        };
+        let [x, y, z] = destination.position;
+        self.execute_dimension_change(
+            addr,
+            src_dim,
+            destination.dimension,
+            Vec3::new(x, y, z),
+            entity_unique_id,
+        )
+        .await;
+        self.send_world_time(addr).await;
+        Ok(())
+    }
+
+    /// `/world [list | tp <world> [player]]`
+    pub(super) async fn cmd_world(
+        &mut self,
+        addr: SocketAddr,
+        sender_name: &str,
+        args: &[String],
+    ) -> CommandResult {
+        const USAGE: &str = "Usage: /world [list | tp <world> [player]]";
+        match args {
+            [] => match self.connections.get(&addr) {
+                Some(conn) => CommandResult::ok(format!(
+                    "You are in world {}",
+                    self.world_name(conn.dimension)
+                )),
+                None => CommandResult::err(USAGE),
+            },
+            [sub] if sub == "list" => {
+                let count = |dims: &[i32]| {
+                    self.connections
+                        .values()
+                        .filter(|c| c.state == LoginState::InGame && dims.contains(&c.dimension))
+                        .count()
+                };
+                let mut lines = vec![format!(
+                    "{} (main): {} player(s)",
+                    self.server_config.world.name,
+                    count(&[0, 1, 2])
+                )];
+                lines.extend(
+                    self.extra_worlds
+                        .iter()
+                        .map(|w| format!("{}: {} player(s)", w.name, count(&[w.dimension]))),
+                );
+                CommandResult::ok(format!("Worlds:\n{}", lines.join("\n")))
+            }
+            [sub, world, rest @ ..] if sub == "tp" && rest.len() <= 1 => {
+                let (target_addr, target_name) = match rest.first() {
+                    Some(name) => match self.find_player_addr(name) {
+                        Some(a) => (a, name.clone()),
+                        None => return CommandResult::err(format!("Player not found: {name}")),
+                    },
+                    None => (addr, sender_name.to_string()),
+                };
+                if let Err(e) = self.move_to_world(target_addr, world).await {
+                    return CommandResult::err(e);
+                }
+                if target_addr != addr {
+                    let msg = format!("Moved to world {world}");
+                    self.send_packet(target_addr, packets::id::TEXT, &Text::raw(msg))
+                        .await;
+                }
+                CommandResult::ok(format!("Moved {target_name} to world {world}"))
+            }
+            _ => CommandResult::err(USAGE),
+        }
+    }
+}
@@ -0,0 +1,507 @@
+//! Behavior pack custom blocks: state permutations, runtime IDs and the
+//! block palette sent in StartGame.
+//!
+//! Every combination of a block's states (custom and trait-provided) gets a
+//! runtime ID — the same FNV-1a state hash the client computes, since block
+//! network IDs are hashes — registered in the block state registry (for
+//! import/export) and the block registry (hardness, solidity) with the
+//! components of the permutations matching that state.
+
+use std::collections::HashMap;
+
+use bytes::BytesMut;
+use mc_rs_behavior_pack::block::{
+    BlockStateValue, BlockTrait, ParsedBlock, BLOCK_FACES, CARDINAL_DIRECTIONS,
+};
+use mc_rs_nbt::{NbtCompound, NbtRoot, NbtTag};
+use mc_rs_proto::packets::start_game::BlockProperty;
+use mc_rs_world::block_registry::BlockRegistry;
+use mc_rs_world::block_state_registry::{hash_owned_state, BlockStateRegistry, StateValueOwned};
+use mc_rs_world::item_registry::ItemRegistry;
+use serde_json::Value;
+use tracing::warn;
+
+/// Most state combinations a single block may have (the client's limit).
+pub const MAX_BLOCK_PERMUTATIONS: usize = 65_536;
+/// Legacy block IDs for custom blocks start here; their item ID is `255 - id`.
+const FIRST_CUSTOM_BLOCK_ID: i32 = 10_000;
+/// Molang version the permutation conditions are written for.
+const MOLANG_VERSION: i32 = 12;
+
+/// One registered state of a custom block.
+#[derive(Debug, Clone)]
+pub struct CustomBlockState {
+    /// Index of the block definition.
+    block: usize,
+    pub properties: Vec<(String, BlockStateValue)>,
+    /// Loot table rolled when the block is broken; `None` drops the block itself.
+    pub loot_table: Option<String>,
+}
+
+/// All custom blocks loaded from behavior packs.
+#[derive(Debug, Default)]
+pub struct CustomBlocks {
+    blocks: Vec<ParsedBlock>,
+    states: HashMap<u32, CustomBlockState>,
+    default_states: HashMap<String, u32>,
+}
+
+impl CustomBlocks {
+    /// Register every state of `block` and its block item. Returns `false`
+    /// if the block was rejected (duplicate or too many permutations).
+    pub fn register(
+        &mut self,
+        block: ParsedBlock,
+        block_registry: &mut BlockRegistry,
+        block_states: &mut BlockStateRegistry,
+        item_registry: &mut ItemRegistry,
+    ) -> bool {
+        if self.default_states.contains_key(&block.identifier) {
+            warn!("Duplicate custom block {}, ignoring", block.identifier);
+            return false;
+        }
+        let count = block.permutation_count();
+        if count > MAX_BLOCK_PERMUTATIONS {
+            warn!(
+                "Custom block {} has {count} permutations (max {MAX_BLOCK_PERMUTATIONS}), ignoring",
+                block.identifier
+            );
+            return false;
+        }
+
+        let index = self.blocks.len();
+        for state in block.state_permutations() {
+            let props = block.properties_for(&state);
+            let hash = block_states.register_custom(&block.identifier, owned_state(&state));
+            block_registry.register_block_state(
+                hash,
+                block.identifier.clone(),
+                props.hardness,
+                props.is_solid,
+            );
+            self.states.insert(
+                hash,
+                CustomBlockState {
+                    block: index,
+                    properties: state,
+                    loot_table: props.loot_table,
+                },
+            );
+        }
+
+        let default_hash =
+            hash_owned_state(&block.identifier, &owned_state(&block.default_state()));
+        self.default_states
+            .insert(block.identifier.clone(), default_hash);
+        item_registry.register_item_with_id(
+            block.identifier.clone(),
+            (255 - legacy_block_id(index)) as i16,
+            64,
+            false,
+        );
+        self.blocks.push(block);
+        true
+    }
+
+    /// Look up a custom block state by runtime ID.
+    pub fn get(&self, runtime_id: u32) -> Option<&CustomBlockState> {
+        self.states.get(&runtime_id)
+    }
+
+    /// The definition a state belongs to.
+    pub fn block(&self, state: &CustomBlockState) -> &ParsedBlock {
+        &self.blocks[state.block]
+    }
+
+    /// Runtime ID of a custom block's default state.
+    pub fn default_state(&self, identifier: &str) -> Option<u32> {
+        self.default_states.get(identifier).copied()
+    }
+
+    /// The state to place when a player uses `runtime_id` on a block face:
+    /// trait states are filled in from the placer's look direction and the
+    /// clicked face. `None` if `runtime_id` isn't a custom block.
+    pub fn placement_state(
+        &self,
+        runtime_id: u32,
+        yaw: f32,
+        pitch: f32,
+        face: i32,
+        click_y: f32,
+    ) -> Option<u32> {
+        let state = self.states.get(&runtime_id)?;
+        let block = &self.blocks[state.block];
+        if block.traits.is_empty() {
+            return Some(runtime_id);
+        }
+
+        let mut properties = state.properties.clone();
+        let mut set = |name: &str, value: &str| {
+            if let Some(slot) = properties.iter_mut().find(|(n, _)| n == name) {
+                slot.1 = BlockStateValue::Str(value.to_string());
+            }
+        };
+        for t in &block.traits {
+            match *t {
+                BlockTrait::PlacementDirection {
+                    y_rotation_offset, ..
+                } => {
+                    let cardinal = cardinal_from_yaw(yaw + y_rotation_offset);
+                    set("minecraft:cardinal_direction", cardinal);
+                    let facing = if pitch > 45.0 {
+                        "down"
+                    } else if pitch < -45.0 {
+                        "up"
+                    } else {
+                        cardinal
+                    };
+                    set("minecraft:facing_direction", facing);
+                }
+                BlockTrait::PlacementPosition { .. } => {
+                    if let Some(name) = BLOCK_FACES.get(face as usize) {
+                        set("minecraft:block_face", name);
+                    }
+                    let top = match face {
+                        0 => true,
+                        1 => false,
+                        _ => click_y > 0.5,
+                    };
+                    set(
+                        "minecraft:vertical_half",
+                        if top { "top" } else { "bottom" },
+                    );
+                }
+            }
+        }
+        Some(hash_owned_state(
+            &block.identifier,
+            &owned_state(&properties),
+        ))
+    }
+
+    /// Block palette entries for StartGame, sorted by identifier.
+    pub fn palette(&self) -> Vec<BlockProperty> {
+        let mut entries: Vec<(usize, &ParsedBlock)> = self.blocks.iter().enumerate().collect();
+        entries.sort_by(|a, b| a.1.identifier.cmp(&b.1.identifier));
+        entries
+            .into_iter()
+            .map(|(index, block)| {
+                let root = NbtRoot::new("", block_definition_nbt(index, block));
+                let mut buf = BytesMut::new();
+                mc_rs_nbt::write_nbt_network(&mut buf, &root);
+                BlockProperty {
+                    name: block.identifier.clone(),
+                    nbt: buf.freeze(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn legacy_block_id(index: usize) -> i32 {
+    FIRST_CUSTOM_BLOCK_ID + index as i32
+}
+
+/// Bedrock yaw: 0 = south, 90 = west, 180 = north, 270 = east.
+fn cardinal_from_yaw(yaw: f32) -> &'static str {
+    let quadrant = ((yaw.rem_euclid(360.0) + 45.0) / 90.0) as usize % 4;
+    match quadrant {
+        0 => CARDINAL_DIRECTIONS[1], // south
+        1 => CARDINAL_DIRECTIONS[3], // west
+        2 => CARDINAL_DIRECTIONS[0], // north
+        _ => CARDINAL_DIRECTIONS[2], // east
+    }
+}
+
+fn owned_state(state: &[(String, BlockStateValue)]) -> Vec<(String, StateValueOwned)> {
+    state
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                BlockStateValue::Int(i) => StateValueOwned::Int(*i),
+                BlockStateValue::Bool(b) => StateValueOwned::Byte(*b as i8),
+                BlockStateValue::Str(s) => StateValueOwned::Str(s.clone()),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+fn state_value_nbt(value: &BlockStateValue) -> NbtTag {
+    match value {
+        BlockStateValue::Int(i) => NbtTag::Int(*i),
+        BlockStateValue::Bool(b) => NbtTag::Byte(*b as i8),
+        BlockStateValue::Str(s) => NbtTag::String(s.clone()),
+    }
+}
+
+/// The block definition compound the client builds the block from.
+fn block_definition_nbt(index: usize, block: &ParsedBlock) -> NbtCompound {
+    let properties = block
+        .states
+        .iter()
+        .map(|def| {
+            NbtTag::Compound(NbtCompound::from([
+                ("name".to_string(), NbtTag::String(def.name.clone())),
+                (
+                    "enum".to_string(),
+                    NbtTag::List(def.values.iter().map(state_value_nbt).collect()),
+                ),
+            ]))
+        })
+        .collect();
+
+    let traits = block
+        .traits
+        .iter()
+        .map(|t| {
+            let (name, enabled, extra) = match *t {
+                BlockTrait::PlacementDirection {
+                    cardinal_direction,
+                    facing_direction,
+                    y_rotation_offset,
+                } => (
+                    "minecraft:placement_direction",
+                    [
+                        ("cardinal_direction", cardinal_direction),
+                        ("facing_direction", facing_direction),
+                    ],
+                    Some(("y_rotation_offset", NbtTag::Float(y_rotation_offset))),
+                ),
+                BlockTrait::PlacementPosition {
+                    block_face,
+                    vertical_half,
+                } => (
+                    "minecraft:placement_position",
+                    [("block_face", block_face), ("vertical_half", vertical_half)],
+                    None,
+                ),
+            };
+            let mut compound = NbtCompound::from([
+                ("name".to_string(), NbtTag::String(name.into())),
+                (
+                    "enabled_states".to_string(),
+                    NbtTag::Compound(
+                        enabled
+                            .iter()
+                            .map(|(k, v)| (k.to_string(), NbtTag::Byte(*v as i8)))
+                            .collect(),
+                    ),
+                ),
+            ]);
+            if let Some((k, v)) = extra {
+                compound.insert(k.to_string(), v);
+            }
+            NbtTag::Compound(compound)
+        })
+        .collect();
+
+    let permutations = block
+        .permutations
+        .iter()
+        .map(|p| {
+            NbtTag::Compound(NbtCompound::from([
+                ("condition".to_string(), NbtTag::String(p.condition.clone())),
+                (
+                    "components".to_string(),
+                    NbtTag::Compound(components_nbt(&p.components)),
+                ),
+            ]))
+        })
+        .collect();
+
+    let category = &block.menu_category;
+    NbtCompound::from([
+        (
+            "components".to_string(),
+            NbtTag::Compound(components_nbt(&block.components)),
+        ),
+        (
+            "menu_category".to_string(),
+            NbtTag::Compound(NbtCompound::from([
+                (
+                    "category".to_string(),
+                    NbtTag::String(if category.category.is_empty() {
+                        "none".into()
+                    } else {
+                        category.category.clone()
+                    }),
+                ),
+                ("group".to_string(), NbtTag::String(category.group.clone())),
+            ])),
+        ),
+        ("molangVersion".to_string(), NbtTag::Int(MOLANG_VERSION)),
+        ("properties".to_string(), NbtTag::List(properties)),
+        ("permutations".to_string(), NbtTag::List(permutations)),
+        ("traits".to_string(), NbtTag::List(traits)),
+        (
+            "vanilla_block_data".to_string(),
+            NbtTag::Compound(NbtCompound::from([(
+                "block_id".to_string(),
+                NbtTag::Int(legacy_block_id(index)),
+            )])),
+        ),
+    ])
+}
+
+/// Convert JSON components to NBT. Shorthand forms the client only accepts
+/// expanded (`"minecraft:geometry": "id"`, boolean boxes) are expanded;
+/// everything else is converted structurally.
+fn components_nbt(components: &HashMap<String, Value>) -> NbtCompound {
+    components
+        .iter()
+        .map(|(name, value)| {
+            let tag = match (name.as_str(), value) {
+                ("minecraft:geometry", Value::String(id)) => {
+                    NbtTag::Compound(NbtCompound::from([(
+                        "identifier".to_string(),
+                        NbtTag::String(id.clone()),
+                    )]))
+                }
+                ("minecraft:collision_box" | "minecraft:selection_box", Value::Bool(enabled)) => {
+                    let size = if *enabled { 16.0 } else { 0.0 };
+                    let floats = |v: [f32; 3]| NbtTag::List(v.map(NbtTag::Float).to_vec());
+                    NbtTag::Compound(NbtCompound::from([
+                        ("enabled".to_string(), NbtTag::Byte(*enabled as i8)),
+                        ("origin".to_string(), floats([-8.0, 0.0, -8.0])),
+                        ("size".to_string(), floats([size; 3])),
+                    ]))
+                }
+                _ => json_to_nbt(value),
+            };
+            (name.clone(), tag)
+        })
+        .collect()
+}
+
+pub(crate) fn json_to_nbt(value: &Value) -> NbtTag {
+    match value {
+        Value::Null => NbtTag::Byte(0),
+        Value::Bool(b) => NbtTag::Byte(*b as i8),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => NbtTag::Int(i as i32),
+            None => NbtTag::Float(n.as_f64().unwrap_or(0.0) as f32),
+        },
+        Value::String(s) => NbtTag::String(s.clone()),
+        Value::Array(list) => NbtTag::List(list.iter().map(json_to_nbt).collect()),
+        Value::Object(map) => NbtTag::Compound(
+            map.iter()
+                .map(|(k, v)| (k.clone(), json_to_nbt(v)))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_rs_behavior_pack::block::BlockFile;
+
+    fn lamp() -> ParsedBlock {
+        BlockFile::parse_json(
+            r#"{
+                "format_version": "1.20.80",
+                "minecraft:block": {
+                    "description": {
+                        "identifier": "custom:lamp",
+                        "states": { "custom:lit": [false, true] },
+                        "traits": {
+                            "minecraft:placement_direction": {
+                                "enabled_states": ["minecraft:cardinal_direction"]
+                            }
+                        }
+                    },
+                    "components": {
+                        "minecraft:destructible_by_mining": { "seconds_to_destroy": 1.0 },
+                        "minecraft:geometry": "geometry.lamp"
+                    },
+                    "permutations": [{
+                        "condition": "q.block_state('custom:lit') == true",
+                        "components": {
+                            "minecraft:destructible_by_mining": { "seconds_to_destroy": 3.0 },
+                            "minecraft:loot": "loot_tables/blocks/lit_lamp.json"
+                        }
+                    }]
+                }
+            }"#,
+        )
+        .unwrap()
+        .extract()
+    }
+
+    fn registered() -> (CustomBlocks, BlockRegistry, ItemRegistry) {
+        let mut blocks = CustomBlocks::default();
+        let mut block_registry = BlockRegistry::new();
+        let mut block_states = BlockStateRegistry::new();
+        let mut items = ItemRegistry::new();
+        assert!(blocks.register(lamp(), &mut block_registry, &mut block_states, &mut items));
+        assert!(!blocks.register(lamp(), &mut block_registry, &mut block_states, &mut items));
+        (blocks, block_registry, items)
+    }
+
+    #[test]
+    fn every_permutation_gets_a_runtime_id() {
+        let (blocks, block_registry, items) = registered();
+        assert_eq!(blocks.states.len(), 2 * 4);
+
+        let default = blocks.default_state("custom:lamp").unwrap();
+        let state = blocks.get(default).unwrap();
+        assert_eq!(blocks.block(state).identifier, "custom:lamp");
+        assert_eq!(state.loot_table, None);
+        assert_eq!(block_registry.hardness(default), Some(1.0));
+
+        let lit = hash_owned_state(
+            "custom:lamp",
+            &[
+                ("custom:lit".into(), StateValueOwned::Byte(1)),
+                (
+                    "minecraft:cardinal_direction".into(),
+                    StateValueOwned::Str("west".into()),
+                ),
+            ],
+        );
+        assert_eq!(block_registry.hardness(lit), Some(3.0));
+        assert_eq!(
+            blocks.get(lit).unwrap().loot_table.as_deref(),
+            Some("lit_lamp")
+        );
+
+        let item = items.get_by_name("custom:lamp").unwrap();
+        assert_eq!(item.numeric_id, (255 - FIRST_CUSTOM_BLOCK_ID) as i16);
+    }
+
+    #[test]
+    fn placement_sets_cardinal_direction() {
+        let (blocks, _, _) = registered();
+        let default = blocks.default_state("custom:lamp").unwrap();
+        let placed = blocks.placement_state(default, 90.0, 0.0, 1, 0.0).unwrap();
+        let state = blocks.get(placed).unwrap();
+        assert!(state.properties.contains(&(
+            "minecraft:cardinal_direction".into(),
+            BlockStateValue::Str("west".into())
+        )));
+        assert!(blocks.placement_state(12345, 0.0, 0.0, 1, 0.0).is_none());
+        assert_eq!(cardinal_from_yaw(0.0), "south");
+        assert_eq!(cardinal_from_yaw(-90.0), "east");
+        assert_eq!(cardinal_from_yaw(180.0), "north");
+    }
+
+    #[test]
+    fn palette_entry() {
+        let (blocks, _, _) = registered();
+        let palette = blocks.palette();
+        assert_eq!(palette.len(), 1);
+        assert_eq!(palette[0].name, "custom:lamp");
+
+        let root = mc_rs_nbt::read_nbt_network(&mut palette[0].nbt.clone()).unwrap();
+        let c = &root.compound;
+        let properties = c["properties"].as_list().unwrap();
+        assert_eq!(properties.len(), 1);
+        assert_eq!(c["permutations"].as_list().unwrap().len(), 1);
+        assert_eq!(c["traits"].as_list().unwrap().len(), 1);
+        let geometry = c["components"].as_compound().unwrap()["minecraft:geometry"]
+            .as_compound()
+            .unwrap();
+        assert_eq!(geometry["identifier"].as_string(), Some("geometry.lamp"));
+    }
+}
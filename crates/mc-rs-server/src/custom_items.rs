@@ -0,0 +1,267 @@
+//! Behavior pack custom items: the data-driven components the server honors
+//! (food, durability, wearable, cooldown) and the item definitions sent in
+//! ItemRegistryPacket so the client knows how the items behave.
+
+use std::collections::HashMap;
+
+use bytes::BytesMut;
+use mc_rs_behavior_pack::item::{ItemDurability, ItemFood, ParsedItem};
+use mc_rs_game::food::FoodData;
+use mc_rs_nbt::{NbtCompound, NbtRoot, NbtTag};
+use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_world::item_registry::ItemRegistry;
+use rand::Rng;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::custom_blocks::json_to_nbt;
+
+/// `use_animation` value for eating.
+const USE_ANIMATION_EAT: i32 = 1;
+
+/// All custom items loaded from behavior packs.
+#[derive(Debug, Default)]
+pub struct CustomItems {
+    items: HashMap<String, ParsedItem>,
+}
+
+impl CustomItems {
+    /// Register `item` and its entry in the item registry. Returns `false`
+    /// if an item with the same identifier already exists.
+    pub fn register(&mut self, item: ParsedItem, item_registry: &mut ItemRegistry) -> bool {
+        if self.items.contains_key(&item.identifier)
+            || item_registry.get_by_name(&item.identifier).is_some()
+        {
+            warn!("Duplicate custom item {}, ignoring", item.identifier);
+            return false;
+        }
+        item_registry.register_item(
+            item.identifier.clone(),
+            item.max_stack_size,
+            item.is_component_based,
+        );
+        self.items.insert(item.identifier.clone(), item);
+        true
+    }
+
+    /// Look up a custom item by identifier.
+    pub fn get(&self, name: &str) -> Option<&ParsedItem> {
+        self.items.get(name)
+    }
+
+    /// The `minecraft:food` component of a custom item.
+    pub fn food(&self, name: &str) -> Option<&ItemFood> {
+        self.get(name)?.food.as_ref()
+    }
+
+    /// Nutrition of a custom food item.
+    pub fn food_data(&self, name: &str) -> Option<FoodData> {
+        self.food(name).map(|f| FoodData {
+            hunger: f.nutrition,
+            saturation: f.saturation(),
+        })
+    }
+
+    /// Armor slot a custom wearable is worn in.
+    pub fn armor_slot(&self, name: &str) -> Option<usize> {
+        self.get(name)?.wearable.as_ref()?.armor_slot()
+    }
+
+    /// Armor points granted by the custom wearables in `armor_slots`.
+    pub fn armor_protection(&self, registry: &ItemRegistry, armor_slots: &[ItemStack]) -> f32 {
+        armor_slots
+            .iter()
+            .filter(|item| !item.is_empty())
+            .filter_map(|item| registry.get_by_id(item.runtime_id as i16))
+            .filter_map(|info| self.get(&info.name)?.wearable.as_ref())
+            .map(|w| w.protection as f32)
+            .sum()
+    }
+
+    /// The item definition NBT sent in ItemRegistryPacket, or `None` if
+    /// `name` isn't a custom item.
+    pub fn definition_nbt(&self, name: &str, numeric_id: i16) -> Option<Vec<u8>> {
+        let item = self.get(name)?;
+        let root = NbtRoot::new("", item_definition_nbt(item, numeric_id));
+        let mut buf = BytesMut::new();
+        mc_rs_nbt::write_nbt_network(&mut buf, &root);
+        Some(buf.to_vec())
+    }
+}
+
+/// Whether one use wears down an item, rolling against its `damage_chance`.
+pub fn rolls_durability_loss(durability: &ItemDurability, rng: &mut impl Rng) -> bool {
+    let (min, max) = durability.damage_chance;
+    let chance = rng.gen_range(min.min(max)..=max.max(min));
+    rng.gen_range(0..100) < chance
+}
+
+/// The definition compound the client builds the item from: the pack's
+/// components plus the `item_properties` it reads stack size, icon and use
+/// duration from.
+fn item_definition_nbt(item: &ParsedItem, numeric_id: i16) -> NbtCompound {
+    let mut properties = NbtCompound::from([(
+        "max_stack_size".to_string(),
+        NbtTag::Int(item.max_stack_size as i32),
+    )]);
+    if let Some(icon) = item.components.get("minecraft:icon").and_then(icon_texture) {
+        properties.insert(
+            "minecraft:icon".to_string(),
+            NbtTag::Compound(NbtCompound::from([(
+                "textures".to_string(),
+                NbtTag::Compound(NbtCompound::from([(
+                    "default".to_string(),
+                    NbtTag::String(icon),
+                )])),
+            )])),
+        );
+    }
+    if let Some(ticks) = item.use_duration_ticks {
+        properties.insert("use_duration".to_string(), NbtTag::Int(ticks as i32));
+    }
+    if item.food.is_some() {
+        properties.insert("use_animation".to_string(), NbtTag::Int(USE_ANIMATION_EAT));
+    }
+
+    let mut components: NbtCompound = item
+        .components
+        .iter()
+        .filter(|(name, _)| name.as_str() != "minecraft:icon")
+        .map(|(name, value)| (name.clone(), json_to_nbt(value)))
+        .collect();
+    components.insert("item_properties".to_string(), NbtTag::Compound(properties));
+
+    NbtCompound::from([
+        ("name".to_string(), NbtTag::String(item.identifier.clone())),
+        ("id".to_string(), NbtTag::Int(numeric_id as i32)),
+        ("components".to_string(), NbtTag::Compound(components)),
+    ])
+}
+
+/// `"texture"`, `{"texture": "..."}` or `{"textures": {"default": "..."}}`.
+fn icon_texture(v: &Value) -> Option<String> {
+    v.as_str()
+        .or_else(|| v.get("texture").and_then(|t| t.as_str()))
+        .or_else(|| {
+            v.get("textures")
+                .and_then(|t| t.get("default"))
+                .and_then(|t| t.as_str())
+        })
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_rs_behavior_pack::item::ItemFile;
+
+    fn helmet() -> ParsedItem {
+        ItemFile::parse_json(
+            r#"{
+                "format_version": "1.21.0",
+                "minecraft:item": {
+                    "description": { "identifier": "custom:ruby_helmet" },
+                    "components": {
+                        "minecraft:icon": "ruby_helmet",
+                        "minecraft:durability": { "max_durability": 200 },
+                        "minecraft:wearable": { "slot": "slot.armor.head", "protection": 4 }
+                    }
+                }
+            }"#,
+        )
+        .unwrap()
+        .extract()
+    }
+
+    fn pie() -> ParsedItem {
+        ItemFile::parse_json(
+            r#"{
+                "format_version": "1.21.0",
+                "minecraft:item": {
+                    "description": { "identifier": "custom:ruby_pie" },
+                    "components": {
+                        "minecraft:food": { "nutrition": 5, "saturation_modifier": 0.5 },
+                        "minecraft:use_modifiers": { "use_duration": 1.0 }
+                    }
+                }
+            }"#,
+        )
+        .unwrap()
+        .extract()
+    }
+
+    #[test]
+    fn register_and_look_up() {
+        let mut registry = ItemRegistry::new();
+        let mut items = CustomItems::default();
+        assert!(items.register(helmet(), &mut registry));
+        assert!(items.register(pie(), &mut registry));
+        assert!(!items.register(pie(), &mut registry));
+
+        let info = registry.get_by_name("custom:ruby_helmet").unwrap();
+        assert_eq!(info.max_stack_size, 1);
+        assert_eq!(items.armor_slot("custom:ruby_helmet"), Some(0));
+        assert_eq!(items.armor_slot("custom:ruby_pie"), None);
+
+        let food = items.food_data("custom:ruby_pie").unwrap();
+        assert_eq!(food.hunger, 5);
+        assert!((food.saturation - 5.0).abs() < 1e-4);
+        assert!(items.food_data("minecraft:bread").is_none());
+    }
+
+    #[test]
+    fn wearable_protection() {
+        let mut registry = ItemRegistry::new();
+        let mut items = CustomItems::default();
+        items.register(helmet(), &mut registry);
+        let id = registry
+            .get_by_name("custom:ruby_helmet")
+            .unwrap()
+            .numeric_id;
+        let armor = [
+            ItemStack::new(id as i32, 1),
+            ItemStack::empty(),
+            ItemStack::empty(),
+            ItemStack::empty(),
+        ];
+        assert_eq!(items.armor_protection(&registry, &armor), 4.0);
+    }
+
+    #[test]
+    fn durability_loss_chance() {
+        let mut rng = rand::thread_rng();
+        let always = ItemDurability {
+            max_durability: 10,
+            damage_chance: (100, 100),
+        };
+        let never = ItemDurability {
+            max_durability: 10,
+            damage_chance: (0, 0),
+        };
+        assert!((0..50).all(|_| rolls_durability_loss(&always, &mut rng)));
+        assert!((0..50).all(|_| !rolls_durability_loss(&never, &mut rng)));
+    }
+
+    #[test]
+    fn definition_nbt_has_properties() {
+        let mut registry = ItemRegistry::new();
+        let mut items = CustomItems::default();
+        items.register(pie(), &mut registry);
+        let compound = item_definition_nbt(items.get("custom:ruby_pie").unwrap(), 1234);
+        assert_eq!(compound.get("id"), Some(&NbtTag::Int(1234)));
+        let Some(NbtTag::Compound(components)) = compound.get("components") else {
+            panic!("missing components");
+        };
+        assert!(components.contains_key("minecraft:food"));
+        let Some(NbtTag::Compound(props)) = components.get("item_properties") else {
+            panic!("missing item_properties");
+        };
+        assert_eq!(props.get("use_duration"), Some(&NbtTag::Int(20)));
+        assert_eq!(
+            props.get("use_animation"),
+            Some(&NbtTag::Int(USE_ANIMATION_EAT))
+        );
+        assert!(items.definition_nbt("custom:ruby_pie", 1234).is_some());
+        assert!(items.definition_nbt("minecraft:stone", 1).is_none());
+    }
+}
@@ -0,0 +1,244 @@
+//! Cutscenes: camera sequences played to one player at a time.
+//!
+//! A cutscene is a list of steps run in order, one tick after another: camera
+//! moves wait until the camera gets where it is going, fades run alongside
+//! the next steps, and waits just pass time. When the last step is done the
+//! player gets their camera back.
+
+use std::collections::HashMap;
+
+use mc_rs_plugin_api::{PluginCutscene, PluginCutsceneStep};
+use mc_rs_proto::packets::camera::camera_ease_type;
+
+/// A step of a cutscene, with its times in ticks for the sequencer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CutsceneStep {
+    Camera {
+        position: [f32; 3],
+        /// Pitch and yaw.
+        rotation: [f32; 2],
+        /// Protocol value of the easing function.
+        ease_type: u8,
+        /// Easing time, and how long the step lasts.
+        ticks: u64,
+    },
+    Fade {
+        color: [f32; 3],
+        /// Fade in, hold and fade out, in seconds, as sent to the client.
+        times: [f32; 3],
+    },
+    Wait {
+        ticks: u64,
+    },
+}
+
+impl CutsceneStep {
+    /// How long the sequencer waits after running this step.
+    fn ticks(&self) -> u64 {
+        match self {
+            CutsceneStep::Camera { ticks, .. } | CutsceneStep::Wait { ticks } => *ticks,
+            CutsceneStep::Fade { .. } => 0,
+        }
+    }
+}
+
+fn secs_to_ticks(secs: f32) -> u64 {
+    (secs.max(0.0) * 20.0).round() as u64
+}
+
+/// A named list of steps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cutscene {
+    pub name: String,
+    pub steps: Vec<CutsceneStep>,
+}
+
+impl Cutscene {
+    /// Convert a cutscene passed by a plugin; fails on an unknown easing
+    /// function.
+    pub fn from_plugin(cutscene: PluginCutscene) -> Result<Self, String> {
+        let steps = cutscene
+            .steps
+            .into_iter()
+            .map(|step| {
+                Ok(match step {
+                    PluginCutsceneStep::Camera {
+                        position: (x, y, z),
+                        rotation: (pitch, yaw),
+                        ease,
+                        duration_secs,
+                    } => CutsceneStep::Camera {
+                        position: [x, y, z],
+                        rotation: [pitch, yaw],
+                        ease_type: camera_ease_type(&ease)
+                            .ok_or_else(|| format!("Unknown easing function: {ease}"))?,
+                        ticks: secs_to_ticks(duration_secs),
+                    },
+                    PluginCutsceneStep::Fade {
+                        color: (r, g, b),
+                        fade_in_secs,
+                        hold_secs,
+                        fade_out_secs,
+                    } => CutsceneStep::Fade {
+                        color: [r, g, b],
+                        times: [fade_in_secs, hold_secs, fade_out_secs],
+                    },
+                    PluginCutsceneStep::Wait { secs } => CutsceneStep::Wait {
+                        ticks: secs_to_ticks(secs),
+                    },
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self {
+            name: cutscene.name,
+            steps,
+        })
+    }
+}
+
+/// What a player's camera should do this tick.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CutsceneCue {
+    Step(CutsceneStep),
+    /// The cutscene is over: give the camera back.
+    End,
+}
+
+struct Playing {
+    cutscene: Cutscene,
+    next_step: usize,
+    resume_at: u64,
+}
+
+/// The cutscenes being played, by player name.
+#[derive(Default)]
+pub struct CutsceneManager {
+    playing: HashMap<String, Playing>,
+}
+
+impl CutsceneManager {
+    /// Start playing a cutscene to a player from the next tick on. Returns
+    /// the name of the one it replaces.
+    pub fn start(&mut self, player: &str, cutscene: Cutscene, now: u64) -> Option<String> {
+        let playing = Playing {
+            cutscene,
+            next_step: 0,
+            resume_at: now,
+        };
+        self.playing
+            .insert(player.to_string(), playing)
+            .map(|p| p.cutscene.name)
+    }
+
+    /// Stop a player's cutscene. Returns its name.
+    pub fn stop(&mut self, player: &str) -> Option<String> {
+        self.playing.remove(player).map(|p| p.cutscene.name)
+    }
+
+    /// Advance every cutscene to `now`, returning the steps to send to each
+    /// player in order. Finished cutscenes end with [`CutsceneCue::End`] and
+    /// are forgotten.
+    pub fn tick(&mut self, now: u64) -> Vec<(String, CutsceneCue)> {
+        let mut cues = Vec::new();
+        self.playing.retain(|player, playing| {
+            while playing.resume_at <= now {
+                let Some(step) = playing.cutscene.steps.get(playing.next_step) else {
+                    cues.push((player.clone(), CutsceneCue::End));
+                    return false;
+                };
+                playing.next_step += 1;
+                playing.resume_at = now + step.ticks();
+                cues.push((player.clone(), CutsceneCue::Step(step.clone())));
+                if step.ticks() > 0 {
+                    break;
+                }
+            }
+            true
+        });
+        cues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intro() -> Cutscene {
+        Cutscene::from_plugin(PluginCutscene {
+            name: "intro".into(),
+            steps: vec![
+                PluginCutsceneStep::Fade {
+                    color: (0.0, 0.0, 0.0),
+                    fade_in_secs: 0.5,
+                    hold_secs: 0.0,
+                    fade_out_secs: 0.5,
+                },
+                PluginCutsceneStep::Camera {
+                    position: (0.0, 80.0, 0.0),
+                    rotation: (45.0, 90.0),
+                    ease: "in_out_sine".into(),
+                    duration_secs: 1.0,
+                },
+                PluginCutsceneStep::Wait { secs: 0.5 },
+            ],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn converts_plugin_steps_to_ticks() {
+        let cutscene = intro();
+        assert_eq!(
+            cutscene.steps[1],
+            CutsceneStep::Camera {
+                position: [0.0, 80.0, 0.0],
+                rotation: [45.0, 90.0],
+                ease_type: 16,
+                ticks: 20,
+            }
+        );
+        assert_eq!(cutscene.steps[2], CutsceneStep::Wait { ticks: 10 });
+
+        let bad = Cutscene::from_plugin(PluginCutscene {
+            name: "bad".into(),
+            steps: vec![PluginCutsceneStep::Camera {
+                position: (0.0, 0.0, 0.0),
+                rotation: (0.0, 0.0),
+                ease: "wobbly".into(),
+                duration_secs: 1.0,
+            }],
+        });
+        assert_eq!(bad, Err("Unknown easing function: wobbly".into()));
+    }
+
+    #[test]
+    fn steps_wait_for_camera_moves() {
+        let mut mgr = CutsceneManager::default();
+        assert_eq!(mgr.start("Alice", intro(), 100), None);
+
+        // The fade and the camera move go out together
+        let cues = mgr.tick(100);
+        assert_eq!(cues.len(), 2);
+        assert!(matches!(
+            cues[1].1,
+            CutsceneCue::Step(CutsceneStep::Camera { .. })
+        ));
+        assert!(mgr.tick(119).is_empty());
+        assert!(matches!(
+            mgr.tick(120)[0].1,
+            CutsceneCue::Step(CutsceneStep::Wait { ticks: 10 })
+        ));
+        assert!(mgr.tick(129).is_empty());
+        assert_eq!(mgr.tick(130), [("Alice".to_string(), CutsceneCue::End)]);
+        assert_eq!(mgr.stop("Alice"), None, "finished cutscenes are forgotten");
+    }
+
+    #[test]
+    fn start_replaces_and_stop_forgets() {
+        let mut mgr = CutsceneManager::default();
+        mgr.start("Alice", intro(), 0);
+        assert_eq!(mgr.start("Alice", intro(), 5), Some("intro".into()));
+        assert_eq!(mgr.stop("Alice"), Some("intro".into()));
+        assert!(mgr.tick(5).is_empty());
+    }
+}
@@ -0,0 +1,398 @@
+//! Economy: one ledger of player balances, shared by `/balance`, `/pay`,
+//! `/eco` and plugins. Accounts are kept per XUID (the identity UUID in
+//! offline mode) in `economy.json` inside the world directory, and every
+//! change is appended to `economy-log.jsonl` next to it.
+
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::persistence::unix_timestamp;
+
+const ECONOMY_FILE: &str = "economy.json";
+const LOG_FILE: &str = "economy-log.jsonl";
+
+/// A player's account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    /// Name the player last joined with.
+    pub name: String,
+    pub balance: u64,
+}
+
+/// What a logged transaction did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    /// Money created into `to` (starting balances, grants, plugin deposits).
+    Deposit,
+    /// Money taken out of `from`.
+    Withdraw,
+    /// Money moved from `from` to `to`.
+    Transfer,
+    /// The balance of `to` set to `amount`.
+    Set,
+}
+
+/// A line of the transaction log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    /// Unix time, in seconds.
+    pub time: i64,
+    pub kind: TransactionKind,
+    /// Account ID money came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    /// Account ID money went to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    pub amount: u64,
+    pub reason: String,
+}
+
+/// Why a transaction was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EconomyError {
+    UnknownAccount,
+    InsufficientFunds,
+    /// The balance would go past `u64::MAX`.
+    Overflow,
+}
+
+impl std::fmt::Display for EconomyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            EconomyError::UnknownAccount => "no such account",
+            EconomyError::InsufficientFunds => "not enough money",
+            EconomyError::Overflow => "the balance would be too large",
+        })
+    }
+}
+
+/// The ledger. A default one is empty and kept in memory only.
+#[derive(Debug, Default)]
+pub struct Economy {
+    /// Accounts by account ID (XUID, or identity UUID in offline mode).
+    accounts: BTreeMap<String, Account>,
+    /// World directory the ledger and log are saved in.
+    dir: Option<PathBuf>,
+}
+
+impl Economy {
+    /// Load `economy.json` from the world directory. A missing file means no
+    /// accounts.
+    pub fn load(world_dir: &Path) -> Self {
+        let path = world_dir.join(ECONOMY_FILE);
+        let accounts: BTreeMap<String, Account> = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Failed to parse {}: {e}", path.display());
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        if !accounts.is_empty() {
+            info!("Loaded {} economy account(s)", accounts.len());
+        }
+        Self {
+            accounts,
+            dir: Some(world_dir.to_path_buf()),
+        }
+    }
+
+    fn save(&self) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let path = dir.join(ECONOMY_FILE);
+        match serde_json::to_string_pretty(&self.accounts) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&path, json) {
+                    warn!("Failed to save {}: {e}", path.display());
+                }
+            }
+            Err(e) => warn!("Failed to serialize economy accounts: {e}"),
+        }
+    }
+
+    /// Save the accounts and append the transaction to the log.
+    fn commit(
+        &self,
+        kind: TransactionKind,
+        from: Option<&str>,
+        to: Option<&str>,
+        amount: u64,
+        reason: &str,
+    ) {
+        self.save();
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let transaction = Transaction {
+            time: unix_timestamp(),
+            kind,
+            from: from.map(String::from),
+            to: to.map(String::from),
+            amount,
+            reason: reason.to_string(),
+        };
+        let path = dir.join(LOG_FILE);
+        let written = serde_json::to_string(&transaction)
+            .map_err(std::io::Error::other)
+            .and_then(|line| {
+                let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+                writeln!(file, "{line}")
+            });
+        if let Err(e) = written {
+            warn!("Failed to append to {}: {e}", path.display());
+        }
+    }
+
+    /// Create the account of a joining player with `starting_balance`, or
+    /// note the name they joined with.
+    pub fn open_account(&mut self, id: &str, name: &str, starting_balance: u64) {
+        match self.accounts.get_mut(id) {
+            Some(account) if account.name == name => {}
+            Some(account) => {
+                account.name = name.to_string();
+                self.save();
+            }
+            None => {
+                self.accounts.insert(
+                    id.to_string(),
+                    Account {
+                        name: name.to_string(),
+                        balance: starting_balance,
+                    },
+                );
+                if starting_balance > 0 {
+                    self.commit(
+                        TransactionKind::Deposit,
+                        None,
+                        Some(id),
+                        starting_balance,
+                        "starting balance",
+                    );
+                } else {
+                    self.save();
+                }
+            }
+        }
+    }
+
+    pub fn account(&self, id: &str) -> Option<&Account> {
+        self.accounts.get(id)
+    }
+
+    /// ID of the account of the player last seen as `name` (ignoring case).
+    pub fn find(&self, name: &str) -> Option<&str> {
+        self.accounts
+            .iter()
+            .find(|(_, a)| a.name.eq_ignore_ascii_case(name))
+            .map(|(id, _)| id.as_str())
+    }
+
+    pub fn balance(&self, id: &str) -> Option<u64> {
+        self.accounts.get(id).map(|a| a.balance)
+    }
+
+    /// Add money to an account. Returns the new balance.
+    pub fn deposit(&mut self, id: &str, amount: u64, reason: &str) -> Result<u64, EconomyError> {
+        let account = self
+            .accounts
+            .get_mut(id)
+            .ok_or(EconomyError::UnknownAccount)?;
+        account.balance = account
+            .balance
+            .checked_add(amount)
+            .ok_or(EconomyError::Overflow)?;
+        let balance = account.balance;
+        self.commit(TransactionKind::Deposit, None, Some(id), amount, reason);
+        Ok(balance)
+    }
+
+    /// Take money out of an account. Returns the new balance.
+    pub fn withdraw(&mut self, id: &str, amount: u64, reason: &str) -> Result<u64, EconomyError> {
+        let account = self
+            .accounts
+            .get_mut(id)
+            .ok_or(EconomyError::UnknownAccount)?;
+        account.balance = account
+            .balance
+            .checked_sub(amount)
+            .ok_or(EconomyError::InsufficientFunds)?;
+        let balance = account.balance;
+        self.commit(TransactionKind::Withdraw, Some(id), None, amount, reason);
+        Ok(balance)
+    }
+
+    /// Move money between two accounts, all or nothing.
+    pub fn transfer(
+        &mut self,
+        from: &str,
+        to: &str,
+        amount: u64,
+        reason: &str,
+    ) -> Result<(), EconomyError> {
+        let (Some(source), Some(target)) = (self.accounts.get(from), self.accounts.get(to)) else {
+            return Err(EconomyError::UnknownAccount);
+        };
+        let source_balance = source
+            .balance
+            .checked_sub(amount)
+            .ok_or(EconomyError::InsufficientFunds)?;
+        if from != to {
+            let target_balance = target
+                .balance
+                .checked_add(amount)
+                .ok_or(EconomyError::Overflow)?;
+            self.accounts.get_mut(from).unwrap().balance = source_balance;
+            self.accounts.get_mut(to).unwrap().balance = target_balance;
+        }
+        self.commit(
+            TransactionKind::Transfer,
+            Some(from),
+            Some(to),
+            amount,
+            reason,
+        );
+        Ok(())
+    }
+
+    /// Set the balance of an account.
+    pub fn set_balance(&mut self, id: &str, amount: u64, reason: &str) -> Result<(), EconomyError> {
+        let account = self
+            .accounts
+            .get_mut(id)
+            .ok_or(EconomyError::UnknownAccount)?;
+        account.balance = amount;
+        self.commit(TransactionKind::Set, None, Some(id), amount, reason);
+        Ok(())
+    }
+}
+
+/// The economy, shared between the server and plugin callbacks so that
+/// every change lands in the same ledger straight away.
+#[derive(Debug, Clone, Default)]
+pub struct SharedEconomy(Arc<Mutex<Economy>>);
+
+impl SharedEconomy {
+    pub fn new(economy: Economy) -> Self {
+        Self(Arc::new(Mutex::new(economy)))
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, Economy> {
+        // Every change is made whole under the lock: a panic elsewhere
+        // doesn't leave the ledger half-written
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Parse an amount of money: a whole number above zero.
+pub fn parse_amount(s: &str) -> Option<u64> {
+    s.parse().ok().filter(|&n| n > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mc_rs_economy_{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn accounts_open_with_the_starting_balance() {
+        let mut eco = Economy::default();
+        eco.open_account("2535400000000001", "Steve", 100);
+        assert_eq!(eco.balance("2535400000000001"), Some(100));
+        assert_eq!(eco.find("steve"), Some("2535400000000001"));
+
+        // Joining again keeps the balance and follows a name change
+        eco.deposit("2535400000000001", 5, "test").unwrap();
+        eco.open_account("2535400000000001", "Steve2", 100);
+        assert_eq!(eco.balance("2535400000000001"), Some(105));
+        assert_eq!(eco.find("Steve"), None);
+        assert_eq!(eco.find("STEVE2"), Some("2535400000000001"));
+    }
+
+    #[test]
+    fn deposits_withdrawals_and_transfers() {
+        let mut eco = Economy::default();
+        eco.open_account("a", "Alice", 50);
+        eco.open_account("b", "Bob", 0);
+
+        assert_eq!(eco.deposit("a", 25, "test"), Ok(75));
+        assert_eq!(
+            eco.withdraw("a", 100, "test"),
+            Err(EconomyError::InsufficientFunds)
+        );
+        assert_eq!(eco.withdraw("a", 70, "test"), Ok(5));
+        assert_eq!(
+            eco.deposit("nobody", 1, "test"),
+            Err(EconomyError::UnknownAccount)
+        );
+
+        assert_eq!(
+            eco.transfer("a", "b", 10, "test"),
+            Err(EconomyError::InsufficientFunds)
+        );
+        eco.transfer("a", "b", 5, "test").unwrap();
+        assert_eq!((eco.balance("a"), eco.balance("b")), (Some(0), Some(5)));
+
+        eco.set_balance("b", u64::MAX, "test").unwrap();
+        eco.deposit("a", 1, "test").unwrap();
+        assert_eq!(
+            eco.transfer("a", "b", 1, "test"),
+            Err(EconomyError::Overflow)
+        );
+        assert_eq!(
+            eco.balance("a"),
+            Some(1),
+            "a refused transfer changes nothing"
+        );
+    }
+
+    #[test]
+    fn ledger_and_log_are_saved() {
+        let dir = temp_dir();
+        let mut eco = Economy::load(&dir);
+        eco.open_account("a", "Alice", 10);
+        eco.open_account("b", "Bob", 0);
+        eco.transfer("a", "b", 4, "pay").unwrap();
+
+        let reloaded = Economy::load(&dir);
+        assert_eq!(reloaded.balance("a"), Some(6));
+        assert_eq!(reloaded.account("b").unwrap().name, "Bob");
+
+        let log: Vec<Transaction> = fs::read_to_string(dir.join(LOG_FILE))
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        // Bob's empty starting balance isn't logged
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].kind, TransactionKind::Deposit);
+        assert_eq!(log[0].to.as_deref(), Some("a"));
+        assert_eq!(log[1].kind, TransactionKind::Transfer);
+        assert_eq!(log[1].from.as_deref(), Some("a"));
+        assert_eq!(log[1].to.as_deref(), Some("b"));
+        assert_eq!((log[1].amount, log[1].reason.as_str()), (4, "pay"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn amounts() {
+        assert_eq!(parse_amount("25"), Some(25));
+        assert_eq!(parse_amount("0"), None);
+        assert_eq!(parse_amount("-5"), None);
+        assert_eq!(parse_amount("1.5"), None);
+    }
+}
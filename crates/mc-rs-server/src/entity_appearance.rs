@@ -0,0 +1,146 @@
+//! Entity appearance set by plugins: metadata laid over what the server
+//! sends for a mob or player, kept so that players who see the entity later
+//! get it too.
+
+use mc_rs_plugin_api::PluginEntityAppearance;
+use mc_rs_proto::entity_metadata::{entity_flag, EntityMetadataEntry, MetadataBuilder};
+
+/// The metadata a plugin changed for one entity.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntityAppearance {
+    flags_on: i64,
+    flags_off: i64,
+    /// Entries other than the flags.
+    entries: Vec<EntityMetadataEntry>,
+}
+
+impl EntityAppearance {
+    /// Convert an appearance passed by a plugin; fails on an unknown flag.
+    pub fn from_plugin(appearance: PluginEntityAppearance) -> Result<Self, String> {
+        let mut builder = MetadataBuilder::new();
+        if let Some(nametag) = appearance.nametag {
+            builder = builder.nametag(nametag);
+        }
+        if let Some(scale) = appearance.scale {
+            builder = builder.scale(scale);
+        }
+        if let Some(variant) = appearance.variant {
+            builder = builder.variant(variant);
+        }
+        if let Some(color) = appearance.color {
+            builder = builder.color(color);
+        }
+        let mut result = Self {
+            entries: builder.build(),
+            ..Self::default()
+        };
+        for (name, on) in appearance.flags {
+            let bit = entity_flag(&name)
+                .ok_or_else(|| format!("Unknown entity flag: {name}"))?
+                .bit();
+            result.set_flag(bit, on);
+        }
+        Ok(result)
+    }
+
+    fn set_flag(&mut self, bit: i64, on: bool) {
+        if on {
+            self.flags_on |= bit;
+            self.flags_off &= !bit;
+        } else {
+            self.flags_off |= bit;
+            self.flags_on &= !bit;
+        }
+    }
+
+    /// Lay a later change over this one.
+    pub fn merge(&mut self, other: EntityAppearance) {
+        self.flags_on = (self.flags_on & !other.flags_off) | other.flags_on;
+        self.flags_off = (self.flags_off & !other.flags_on) | other.flags_off;
+        self.entries = other
+            .entries
+            .into_iter()
+            .fold(
+                MetadataBuilder::from_entries(std::mem::take(&mut self.entries)),
+                |builder, e| builder.set(e.key, e.value),
+            )
+            .build();
+    }
+
+    /// The server's metadata for the entity with this appearance laid over.
+    pub fn apply(&self, metadata: Vec<EntityMetadataEntry>) -> Vec<EntityMetadataEntry> {
+        let mut builder = MetadataBuilder::from_entries(metadata);
+        if self.flags_on | self.flags_off != 0 {
+            let flags = (builder.current_flags() | self.flags_on) & !self.flags_off;
+            builder = builder.flags(flags);
+        }
+        self.entries
+            .iter()
+            .fold(builder, |builder, e| builder.set(e.key, e.value.clone()))
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_rs_proto::entity_metadata::{key, EntityFlag, MetadataValue};
+
+    fn value(metadata: &[EntityMetadataEntry], key: u32) -> Option<&MetadataValue> {
+        metadata.iter().find(|e| e.key == key).map(|e| &e.value)
+    }
+
+    fn appearance(nametag: Option<&str>, flags: &[(&str, bool)]) -> EntityAppearance {
+        EntityAppearance::from_plugin(PluginEntityAppearance {
+            nametag: nametag.map(str::to_string),
+            flags: flags.iter().map(|&(f, on)| (f.to_string(), on)).collect(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn applies_over_server_metadata() {
+        let base = MetadataBuilder::new()
+            .flag(EntityFlag::Baby, true)
+            .scale(0.5)
+            .build();
+        let metadata = appearance(Some("Boss"), &[("on_fire", true), ("baby", false)]).apply(base);
+        assert_eq!(
+            value(&metadata, key::FLAGS),
+            Some(&MetadataValue::Long(EntityFlag::OnFire.bit()))
+        );
+        assert_eq!(
+            value(&metadata, key::NAMETAG),
+            Some(&MetadataValue::String("Boss".into()))
+        );
+        assert_eq!(
+            value(&metadata, key::SCALE),
+            Some(&MetadataValue::Float(0.5))
+        );
+    }
+
+    #[test]
+    fn later_changes_win() {
+        let mut first = appearance(Some("Boss"), &[("on_fire", true), ("invisible", true)]);
+        first.merge(appearance(Some("Minion"), &[("on_fire", false)]));
+        let metadata = first.apply(Vec::new());
+        assert_eq!(
+            value(&metadata, key::FLAGS),
+            Some(&MetadataValue::Long(EntityFlag::Invisible.bit()))
+        );
+        assert_eq!(
+            value(&metadata, key::NAMETAG),
+            Some(&MetadataValue::String("Minion".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_flags() {
+        let bad = EntityAppearance::from_plugin(PluginEntityAppearance {
+            flags: vec![("wobbly".into(), true)],
+            ..Default::default()
+        });
+        assert_eq!(bad, Err("Unknown entity flag: wobbly".into()));
+    }
+}
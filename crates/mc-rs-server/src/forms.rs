@@ -0,0 +1,180 @@
+//! Forms awaiting a player's answer.
+//!
+//! Each player has a stack of them. A form a plugin opens from the answer
+//! to another one is chained to it: the answered form stays below it in the
+//! stack, so closing the new form opens the previous one again.
+
+/// A form shown to a player, awaiting their answer.
+#[derive(Debug, Clone)]
+pub struct PendingForm {
+    pub form_id: u32,
+    /// `"simple"`, `"modal"`, `"custom"`, or a server form's own type.
+    pub form_type: String,
+    /// The form JSON, with the rules of its inputs, to show it again.
+    pub form_data: String,
+    /// Plugin that showed the form, answered alone; `None` for server forms.
+    pub plugin: Option<String>,
+    /// Opened from the answer to the form below it.
+    pub chained: bool,
+    /// Answered, kept as the form a chained form goes back to.
+    pub answered: bool,
+}
+
+impl PendingForm {
+    /// A form of the server's own, e.g. an NPC dialogue.
+    pub fn server(form_id: u32, form_type: String, form_data: String) -> Self {
+        Self {
+            form_id,
+            form_type,
+            form_data,
+            plugin: None,
+            chained: false,
+            answered: false,
+        }
+    }
+}
+
+/// The forms a player was shown, oldest first.
+#[derive(Debug, Default)]
+pub struct FormStack {
+    forms: Vec<PendingForm>,
+}
+
+impl FormStack {
+    /// Push a newly shown form. It is only chained if the form on top was
+    /// just answered.
+    pub fn push(&mut self, mut form: PendingForm) {
+        form.chained &= self.forms.last().is_some_and(|f| f.answered);
+        // Showing a form again under the same ID replaces it
+        self.forms
+            .retain(|f| f.answered || f.form_id != form.form_id);
+        self.forms.push(form);
+    }
+
+    /// The awaiting form answered by `form_id`, marked answered.
+    pub fn answer(&mut self, form_id: u32) -> Option<PendingForm> {
+        let form = self
+            .forms
+            .iter_mut()
+            .rev()
+            .find(|f| !f.answered && f.form_id == form_id)?;
+        form.answered = true;
+        Some(form.clone())
+    }
+
+    /// Ask the answered `form_id` again, e.g. after invalid input.
+    pub fn reopen(&mut self, form_id: u32) {
+        if let Some(form) = self
+            .forms
+            .iter_mut()
+            .rev()
+            .find(|f| f.answered && f.form_id == form_id)
+        {
+            form.answered = false;
+        }
+    }
+
+    /// A chained form was closed: drop it and reopen the one it came from,
+    /// returned to be shown again.
+    pub fn go_back(&mut self, form_id: u32) -> Option<PendingForm> {
+        let i = self
+            .forms
+            .iter()
+            .rposition(|f| f.answered && f.form_id == form_id)?;
+        if !self.forms[i].chained || i == 0 {
+            return None;
+        }
+        self.forms.remove(i);
+        let parent = &mut self.forms[i - 1];
+        parent.answered = false;
+        Some(parent.clone())
+    }
+
+    /// Drop the answered forms no chained form leads back to.
+    pub fn settle(&mut self) {
+        let mut keep = vec![false; self.forms.len()];
+        for i in (0..self.forms.len()).rev() {
+            let leads_back = self
+                .forms
+                .get(i + 1)
+                .is_some_and(|next| next.chained && keep[i + 1]);
+            keep[i] = !self.forms[i].answered || leads_back;
+        }
+        let mut keep = keep.into_iter();
+        self.forms.retain(|_| keep.next().unwrap_or(false));
+    }
+
+    #[cfg(test)]
+    fn ids(&self) -> Vec<(u32, bool)> {
+        self.forms.iter().map(|f| (f.form_id, f.answered)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(form_id: u32, chained: bool) -> PendingForm {
+        PendingForm {
+            plugin: Some("shop".into()),
+            chained,
+            ..PendingForm::server(form_id, "simple".into(), String::new())
+        }
+    }
+
+    #[test]
+    fn answered_forms_are_dropped() {
+        let mut stack = FormStack::default();
+        stack.push(form(1, false));
+        assert_eq!(stack.answer(1).unwrap().plugin.as_deref(), Some("shop"));
+        assert!(stack.answer(1).is_none(), "answered once");
+        stack.settle();
+        assert!(stack.ids().is_empty());
+    }
+
+    #[test]
+    fn chained_forms_go_back() {
+        let mut stack = FormStack::default();
+        stack.push(form(1, false));
+        stack.answer(1);
+        stack.push(form(2, true));
+        stack.settle();
+        assert_eq!(stack.ids(), vec![(1, true), (2, false)]);
+
+        // Closing the second form opens the first one again
+        stack.answer(2);
+        assert_eq!(stack.go_back(2).unwrap().form_id, 1);
+        assert_eq!(stack.ids(), vec![(1, false)]);
+
+        // Answering the chain's last form ends it
+        stack.answer(1);
+        stack.push(form(2, true));
+        stack.answer(2);
+        assert!(stack.go_back(2).is_some());
+        stack.answer(1);
+        stack.settle();
+        assert!(stack.ids().is_empty());
+    }
+
+    #[test]
+    fn forms_only_chain_onto_an_answer() {
+        let mut stack = FormStack::default();
+        stack.push(form(1, false));
+        stack.push(form(2, true));
+        stack.answer(2);
+        assert!(stack.go_back(2).is_none());
+        stack.settle();
+        assert_eq!(stack.ids(), vec![(1, false)]);
+    }
+
+    #[test]
+    fn invalid_answers_reopen_the_form() {
+        let mut stack = FormStack::default();
+        stack.push(form(1, false));
+        stack.answer(1);
+        stack.reopen(1);
+        stack.settle();
+        assert_eq!(stack.ids(), vec![(1, false)]);
+        assert!(stack.answer(1).is_some());
+    }
+}
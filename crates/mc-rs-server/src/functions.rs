@@ -0,0 +1,121 @@
+//! Behavior pack functions (.mcfunction) and the functions tagged to run on
+//! load and every tick.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use mc_rs_behavior_pack::function::{normalize_function_name, PackFunctions};
+use tracing::warn;
+
+/// Most commands one top-level function call may run, nested calls included
+/// (the vanilla `functioncommandlimit` default).
+pub const MAX_FUNCTION_COMMANDS: usize = 10_000;
+/// How deep functions may call each other.
+pub const MAX_FUNCTION_DEPTH: usize = 64;
+
+/// All functions loaded from behavior packs.
+#[derive(Debug, Default)]
+pub struct Functions {
+    functions: HashMap<String, Arc<[String]>>,
+    tick: Vec<String>,
+    load: Vec<String>,
+}
+
+impl Functions {
+    /// Add the functions and tags of a pack. A function with the same name
+    /// as one from an earlier pack replaces it.
+    pub fn register(&mut self, pack: &PackFunctions) {
+        for (name, commands) in &pack.functions {
+            if self
+                .functions
+                .insert(name.clone(), commands.as_slice().into())
+                .is_some()
+            {
+                warn!("Function {name} is defined by several packs, using the last one");
+            }
+        }
+        for name in &pack.tick {
+            if !self.tick.contains(name) {
+                self.tick.push(name.clone());
+            }
+        }
+        for name in &pack.load {
+            if !self.load.contains(name) {
+                self.load.push(name.clone());
+            }
+        }
+    }
+
+    /// Drop tagged functions that don't exist, once every pack is registered.
+    pub fn check_tags(&mut self) {
+        let functions = &self.functions;
+        for (tag, names) in [("tick", &mut self.tick), ("load", &mut self.load)] {
+            names.retain(|name| {
+                let exists = functions.contains_key(name);
+                if !exists {
+                    warn!("Function {name} in {tag}.json doesn't exist");
+                }
+                exists
+            });
+        }
+    }
+
+    /// Look up a function by name, as written in `/function`.
+    pub fn get(&self, name: &str) -> Option<Arc<[String]>> {
+        self.functions.get(&normalize_function_name(name)).cloned()
+    }
+
+    /// Functions run every tick.
+    pub fn tick(&self) -> &[String] {
+        &self.tick
+    }
+
+    /// Functions run once when the server starts.
+    pub fn load(&self) -> &[String] {
+        &self.load
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(functions: &[(&str, &[&str])], tick: &[&str], load: &[&str]) -> PackFunctions {
+        PackFunctions {
+            functions: functions
+                .iter()
+                .map(|(name, lines)| {
+                    (
+                        name.to_string(),
+                        lines.iter().map(|l| l.to_string()).collect(),
+                    )
+                })
+                .collect(),
+            tick: tick.iter().map(|n| n.to_string()).collect(),
+            load: load.iter().map(|n| n.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn register_and_look_up() {
+        let mut functions = Functions::default();
+        functions.register(&pack(
+            &[("maps/start", &["say one"]), ("loop", &["time add 1"])],
+            &["loop"],
+            &["maps/start"],
+        ));
+        functions.register(&pack(
+            &[("maps/start", &["say two"])],
+            &["loop", "missing"],
+            &[],
+        ));
+        functions.check_tags();
+
+        assert_eq!(&*functions.get("loop").unwrap(), ["time add 1"]);
+        assert_eq!(&*functions.get("maps/start").unwrap(), ["say two"]);
+        assert_eq!(&*functions.get("\"/maps/start\"").unwrap(), ["say two"]);
+        assert!(functions.get("nope").is_none());
+        assert_eq!(functions.tick(), ["loop"]);
+        assert_eq!(functions.load(), ["maps/start"]);
+    }
+}
@@ -0,0 +1,100 @@
+//! Country lookup of joining players in a local MaxMind (MMDB) database,
+//! and the `[geoip]` country allow and deny lists.
+
+use std::net::IpAddr;
+
+use maxminddb::{geoip2, Reader};
+use tracing::{info, warn};
+
+use crate::config::GeoIpSection;
+
+/// The opened country database, if `[geoip]` is enabled.
+#[derive(Default)]
+pub struct GeoIp {
+    reader: Option<Reader<Vec<u8>>>,
+}
+
+impl GeoIp {
+    /// Open the configured database. A missing or broken database is logged
+    /// and leaves every country unknown.
+    pub fn load(config: &GeoIpSection) -> Self {
+        if !config.enabled {
+            return Self::default();
+        }
+        match Reader::open_readfile(&config.database) {
+            Ok(reader) => {
+                info!("Loaded GeoIP database {}", config.database);
+                Self {
+                    reader: Some(reader),
+                }
+            }
+            Err(e) => {
+                warn!("Failed to open GeoIP database {}: {e}", config.database);
+                Self::default()
+            }
+        }
+    }
+
+    /// ISO code of the country of `ip`, `None` if it isn't in the database
+    /// (e.g. a LAN address) or there is no database.
+    pub fn country(&self, ip: IpAddr) -> Option<String> {
+        let country: geoip2::Country = self.reader.as_ref()?.lookup(ip).ok()?;
+        country
+            .country
+            .or(country.registered_country)?
+            .iso_code
+            .map(str::to_uppercase)
+    }
+}
+
+/// Whether a player from `country` may join: not in `denied_countries`,
+/// in `allowed_countries` unless it's empty, and known unless
+/// `allow_unknown` is set. Everyone may join while `[geoip]` is disabled.
+pub fn country_allowed(config: &GeoIpSection, country: Option<&str>) -> bool {
+    if !config.enabled {
+        return true;
+    }
+    let Some(country) = country else {
+        return config.allow_unknown;
+    };
+    let listed = |list: &[String]| list.iter().any(|c| c.eq_ignore_ascii_case(country));
+    !listed(&config.denied_countries)
+        && (config.allowed_countries.is_empty() || listed(&config.allowed_countries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allow_and_deny_lists() {
+        let mut config = GeoIpSection {
+            enabled: true,
+            denied_countries: vec!["xx".into()],
+            ..Default::default()
+        };
+        assert!(country_allowed(&config, Some("FR")));
+        assert!(!country_allowed(&config, Some("XX")));
+        assert!(country_allowed(&config, None));
+
+        config.allowed_countries = vec!["FR".into(), "BE".into()];
+        assert!(country_allowed(&config, Some("be")));
+        assert!(!country_allowed(&config, Some("DE")));
+        config.allow_unknown = false;
+        assert!(!country_allowed(&config, None));
+
+        config.enabled = false;
+        assert!(country_allowed(&config, Some("DE")));
+    }
+
+    #[test]
+    fn no_database_knows_no_country() {
+        let config = GeoIpSection {
+            enabled: true,
+            database: "does-not-exist.mmdb".into(),
+            ..Default::default()
+        };
+        let geoip = GeoIp::load(&config);
+        assert_eq!(geoip.country("8.8.8.8".parse().unwrap()), None);
+    }
+}
@@ -0,0 +1,202 @@
+//! Server-to-server player handoff for `/transfer`.
+//!
+//! The Transfer packet only tells the client where to reconnect; in offline
+//! mode the receiving server has no way to know who the player really is.
+//! When a `[transfer] secret` is configured, the sending server also posts a
+//! short-lived ticket (player name, XUID, UUID, client IP) to the target's
+//! handoff port over UDP, authenticated with HMAC-SHA256. The target keeps the
+//! ticket and, when that player logs in from the same IP, trusts the forwarded
+//! identity instead of the self-signed one from the client.
+
+use std::net::SocketAddr;
+
+use mc_rs_crypto::{hmac_sha256, hmac_sha256_verify};
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+/// How long a ticket stays valid after being issued.
+pub const TICKET_TTL_SECS: i64 = 30;
+/// Length of the MAC prefix of a ticket datagram.
+const MAC_LEN: usize = 32;
+/// Largest datagram accepted by the listener.
+const MAX_DATAGRAM: usize = 2048;
+
+/// Identity forwarded from one server to another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HandoffTicket {
+    pub name: String,
+    pub xuid: String,
+    pub uuid: String,
+    /// IP the player was connected from on the sending server.
+    pub client_ip: String,
+    /// Game port of the server the player is being sent to.
+    pub target_port: u16,
+    /// Unix timestamp (seconds) after which the ticket is rejected.
+    pub expires_at: i64,
+}
+
+/// Why a received ticket was rejected.
+#[derive(Debug, PartialEq, Eq)]
+pub enum HandoffError {
+    TooShort,
+    BadMac,
+    Malformed,
+    Expired,
+}
+
+impl std::fmt::Display for HandoffError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            HandoffError::TooShort => "datagram too short",
+            HandoffError::BadMac => "invalid signature",
+            HandoffError::Malformed => "malformed ticket",
+            HandoffError::Expired => "ticket expired",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl HandoffTicket {
+    /// Serialize and sign: `HMAC-SHA256(secret, json) || json`.
+    pub fn encode(&self, secret: &str) -> Vec<u8> {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        let mut out = hmac_sha256(secret.as_bytes(), &json).to_vec();
+        out.extend_from_slice(&json);
+        out
+    }
+
+    /// Verify and parse a ticket datagram.
+    pub fn decode(datagram: &[u8], secret: &str, now: i64) -> Result<Self, HandoffError> {
+        if datagram.len() <= MAC_LEN {
+            return Err(HandoffError::TooShort);
+        }
+        let (mac, json) = datagram.split_at(MAC_LEN);
+        if !hmac_sha256_verify(secret.as_bytes(), json, mac) {
+            return Err(HandoffError::BadMac);
+        }
+        let ticket: Self = serde_json::from_slice(json).map_err(|_| HandoffError::Malformed)?;
+        if ticket.expires_at < now {
+            return Err(HandoffError::Expired);
+        }
+        Ok(ticket)
+    }
+
+    /// Whether this ticket may be used for a login of `name` from `ip`.
+    pub fn matches(&self, name: &str, ip: &str, now: i64) -> bool {
+        self.name == name && self.client_ip == ip && self.expires_at >= now
+    }
+}
+
+/// Start the handoff listener on the given UDP port. Verified tickets are
+/// forwarded to the connection handler.
+pub fn start(port: u16, secret: String, ticket_tx: mpsc::Sender<HandoffTicket>) {
+    tokio::spawn(async move {
+        let addr: SocketAddr = format!("0.0.0.0:{port}").parse().unwrap();
+        let socket = match UdpSocket::bind(addr).await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Failed to bind handoff listener on port {port}: {e}");
+                return;
+            }
+        };
+        info!("Transfer handoff listener on UDP port {port}");
+
+        let mut buf = vec![0u8; MAX_DATAGRAM];
+        loop {
+            let (len, peer) = match socket.recv_from(&mut buf).await {
+                Ok(r) => r,
+                Err(e) => {
+                    debug!("Handoff recv error: {e}");
+                    continue;
+                }
+            };
+            let now = crate::persistence::unix_timestamp();
+            match HandoffTicket::decode(&buf[..len], &secret, now) {
+                Ok(ticket) => {
+                    debug!("Handoff ticket for {} from {peer}", ticket.name);
+                    if ticket_tx.send(ticket).await.is_err() {
+                        return;
+                    }
+                }
+                Err(e) => warn!("Rejected handoff ticket from {peer}: {e}"),
+            }
+        }
+    });
+}
+
+/// Send a signed ticket to `host:port`.
+pub async fn send(ticket: &HandoffTicket, secret: &str, host: &str, port: u16) {
+    let datagram = ticket.encode(secret);
+    let result = async {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.send_to(&datagram, (host, port)).await
+    }
+    .await;
+    if let Err(e) = result {
+        warn!(
+            "Failed to send handoff ticket for {} to {host}:{port}: {e}",
+            ticket.name
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket() -> HandoffTicket {
+        HandoffTicket {
+            name: "Steve".into(),
+            xuid: "2535400000000000".into(),
+            uuid: "uuid-1".into(),
+            client_ip: "10.0.0.5".into(),
+            target_port: 19133,
+            expires_at: 1_000,
+        }
+    }
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        let bytes = ticket().encode("s3cret");
+        assert_eq!(HandoffTicket::decode(&bytes, "s3cret", 990), Ok(ticket()));
+    }
+
+    #[test]
+    fn decode_rejects_wrong_secret_and_tampering() {
+        let mut bytes = ticket().encode("s3cret");
+        assert_eq!(
+            HandoffTicket::decode(&bytes, "other", 990),
+            Err(HandoffError::BadMac)
+        );
+        let last = bytes.len() - 2;
+        bytes[last] ^= 1;
+        assert_eq!(
+            HandoffTicket::decode(&bytes, "s3cret", 990),
+            Err(HandoffError::BadMac)
+        );
+        assert_eq!(
+            HandoffTicket::decode(&[0u8; 8], "s3cret", 990),
+            Err(HandoffError::TooShort)
+        );
+    }
+
+    #[test]
+    fn decode_rejects_expired() {
+        let bytes = ticket().encode("s3cret");
+        assert_eq!(
+            HandoffTicket::decode(&bytes, "s3cret", 1_001),
+            Err(HandoffError::Expired)
+        );
+    }
+
+    #[test]
+    fn matches_name_and_ip() {
+        let t = ticket();
+        assert!(t.matches("Steve", "10.0.0.5", 1_000));
+        assert!(!t.matches("Alex", "10.0.0.5", 1_000));
+        assert!(!t.matches("Steve", "10.0.0.6", 1_000));
+        assert!(!t.matches("Steve", "10.0.0.5", 1_001));
+    }
+}
@@ -0,0 +1,244 @@
+//! Holograms: floating text made of invisible entities, one per line,
+//! persisted in `holograms.json` inside the world directory.
+//!
+//! Lines may hold placeholders, filled in for each player who sees them:
+//! `{online}`, `{max_players}`, `{tps}`, `{player}`, `{balance}` (of the
+//! player looking) and `{balance:<name>}`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+const HOLOGRAMS_FILE: &str = "holograms.json";
+
+/// Default distance between two lines, in blocks.
+pub const DEFAULT_LINE_SPACING: f32 = 0.3;
+
+/// A hologram in the overworld.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hologram {
+    pub name: String,
+    /// Position of the top line.
+    pub position: [f32; 3],
+    pub lines: Vec<String>,
+    /// Distance between two lines, in blocks.
+    #[serde(default = "default_spacing")]
+    pub spacing: f32,
+    /// Entity ID of each line while it is shown.
+    #[serde(skip)]
+    pub entity_ids: Vec<i64>,
+}
+
+fn default_spacing() -> f32 {
+    DEFAULT_LINE_SPACING
+}
+
+impl Hologram {
+    pub fn new(name: &str, position: [f32; 3], lines: Vec<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            position,
+            lines,
+            spacing: DEFAULT_LINE_SPACING,
+            entity_ids: Vec::new(),
+        }
+    }
+
+    /// Position of a line; lines go down from the top one.
+    pub fn line_position(&self, index: usize) -> [f32; 3] {
+        let [x, y, z] = self.position;
+        [x, y - self.spacing * index as f32, z]
+    }
+
+    /// Whether any line needs refreshing while shown.
+    pub fn has_placeholders(&self) -> bool {
+        self.lines.iter().any(|l| has_placeholders(l))
+    }
+}
+
+/// Split hologram text into lines at `\n` (typed as a backslash and an `n`).
+pub fn split_lines(text: &str) -> Vec<String> {
+    text.split("\\n").map(str::to_string).collect()
+}
+
+/// What placeholders are filled in with, for one player looking.
+pub struct PlaceholderValues<'a> {
+    pub online: usize,
+    pub max_players: u32,
+    pub tps: f64,
+    pub player: &'a str,
+    /// Formatted balance of a player, by name.
+    pub balance_of: &'a dyn Fn(&str) -> Option<String>,
+}
+
+pub fn has_placeholders(text: &str) -> bool {
+    text.contains('{') && text.contains('}')
+}
+
+/// Fill in the placeholders of a line. Unknown placeholders are left as
+/// they are.
+pub fn render_line(text: &str, values: &PlaceholderValues<'_>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let key = &rest[start + 1..start + len];
+        let value = match key {
+            "online" => Some(values.online.to_string()),
+            "max_players" => Some(values.max_players.to_string()),
+            "tps" => Some(format!("{:.1}", values.tps)),
+            "player" => Some(values.player.to_string()),
+            "balance" => Some((values.balance_of)(values.player).unwrap_or_default()),
+            _ => key
+                .strip_prefix("balance:")
+                .map(|name| (values.balance_of)(name).unwrap_or_default()),
+        };
+        match value {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The holograms of a world.
+pub struct HologramManager {
+    holograms: Vec<Hologram>,
+    path: PathBuf,
+}
+
+impl HologramManager {
+    /// Load `holograms.json` from the world directory. A missing file means
+    /// no holograms.
+    pub fn load(world_dir: &Path) -> Self {
+        let path = world_dir.join(HOLOGRAMS_FILE);
+        let holograms: Vec<Hologram> = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Failed to parse {}: {e}", path.display());
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        if !holograms.is_empty() {
+            info!("Loaded {} hologram(s)", holograms.len());
+        }
+        Self { holograms, path }
+    }
+
+    /// Save the file after changing a hologram.
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(&self.holograms) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&self.path, json) {
+                    warn!("Failed to save {}: {e}", self.path.display());
+                }
+            }
+            Err(e) => warn!("Failed to serialize holograms: {e}"),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Hologram> {
+        self.holograms.iter().find(|h| h.name == name)
+    }
+
+    /// A hologram to change; call [`save`](Self::save) afterwards.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Hologram> {
+        self.holograms.iter_mut().find(|h| h.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Hologram> {
+        self.holograms.iter()
+    }
+
+    /// Add a hologram, replacing one with the same name, and save the file.
+    /// Returns the replaced one.
+    pub fn insert(&mut self, hologram: Hologram) -> Option<Hologram> {
+        let old = self.remove_unsaved(&hologram.name);
+        self.holograms.push(hologram);
+        self.save();
+        old
+    }
+
+    /// Delete a hologram and save the file.
+    pub fn remove(&mut self, name: &str) -> Option<Hologram> {
+        let old = self.remove_unsaved(name);
+        if old.is_some() {
+            self.save();
+        }
+        old
+    }
+
+    fn remove_unsaved(&mut self, name: &str) -> Option<Hologram> {
+        let index = self.holograms.iter().position(|h| h.name == name)?;
+        Some(self.holograms.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mc_rs_holograms_{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let balance_of = |name: &str| (name == "Alice").then(|| "50 coins".to_string());
+        let values = PlaceholderValues {
+            online: 3,
+            max_players: 20,
+            tps: 19.96,
+            player: "Alice",
+            balance_of: &balance_of,
+        };
+        assert_eq!(
+            render_line("{online}/{max_players} at {tps} TPS", &values),
+            "3/20 at 20.0 TPS"
+        );
+        assert_eq!(
+            render_line("{player}: {balance}, Bob: {balance:Bob}", &values),
+            "Alice: 50 coins, Bob: "
+        );
+        assert_eq!(render_line("{nope} {online", &values), "{nope} {online");
+        assert!(!has_placeholders("Welcome!"));
+    }
+
+    #[test]
+    fn lines_go_down_from_the_top() {
+        let mut hologram = Hologram::new("spawn", [0.5, 70.0, 0.5], split_lines("A\\nB\\nC"));
+        assert_eq!(hologram.lines, ["A", "B", "C"]);
+        hologram.spacing = 0.5;
+        assert_eq!(hologram.line_position(2), [0.5, 69.0, 0.5]);
+    }
+
+    #[test]
+    fn save_reload_and_remove() {
+        let dir = temp_dir();
+        let mut mgr = HologramManager::load(&dir);
+        mgr.insert(Hologram::new("spawn", [0.5, 70.0, 0.5], vec!["Hi".into()]));
+        let mut replaced = Hologram::new("spawn", [1.5, 70.0, 0.5], vec!["{online}".into()]);
+        replaced.entity_ids = vec![5];
+        assert!(mgr.insert(replaced).is_some());
+
+        let reloaded = HologramManager::load(&dir);
+        let spawn = reloaded.get("spawn").unwrap();
+        assert_eq!(spawn.position, [1.5, 70.0, 0.5]);
+        assert!(spawn.entity_ids.is_empty(), "entity IDs are not saved");
+        assert!(spawn.has_placeholders());
+
+        assert!(mgr.remove("spawn").is_some());
+        assert!(mgr.remove("spawn").is_none());
+        assert!(HologramManager::load(&dir).get("spawn").is_none());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,261 @@
+//! Just enough HTTP/1.0 for the webhooks: JSON `POST`s to `http://` URLs,
+//! and reading requests and writing responses for the bridge endpoint. No
+//! TLS, keep-alive or chunked bodies.
+
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Largest response read from a webhook.
+const MAX_RESPONSE: u64 = 64 * 1024;
+/// Largest request head accepted by the endpoint.
+const MAX_HEAD: usize = 8 * 1024;
+/// Largest request body accepted by the endpoint.
+const MAX_BODY: usize = 64 * 1024;
+/// Longest a client may take to send its whole request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An `http://host[:port][/path]` URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpUrl {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl HttpUrl {
+    /// Parse `http://host[:port][/path]`.
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or("only http:// URLs are supported")?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| "invalid port")?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err("missing host".into());
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+/// POST a JSON body and read the whole response.
+pub async fn post_json(url: &HttpUrl, body: &str) -> Result<Vec<u8>, String> {
+    let mut stream = TcpStream::connect((url.host.as_str(), url.port))
+        .await
+        .map_err(|e| e.to_string())?;
+    let head = format!(
+        "POST {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        url.path,
+        url.host,
+        body.len()
+    );
+    stream
+        .write_all(head.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    stream
+        .write_all(body.as_bytes())
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut response = Vec::new();
+    stream
+        .take(MAX_RESPONSE)
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(response)
+}
+
+/// Body of a successful (2xx) HTTP response.
+pub fn response_body(response: &[u8]) -> Result<String, String> {
+    let text = String::from_utf8_lossy(response);
+    let (head, body) = text
+        .split_once("\r\n\r\n")
+        .ok_or("incomplete HTTP response")?;
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or("invalid HTTP status line")?;
+    if !(200..300).contains(&status) {
+        return Err(format!("HTTP status {status}"));
+    }
+    Ok(body.to_string())
+}
+
+/// A request received by the endpoint.
+#[derive(Debug, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    /// Headers with lowercased names.
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpRequest {
+    /// Value of a header, by lowercase name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Parse a request line and headers, without the blank line ending them.
+fn parse_head(head: &str) -> Result<HttpRequest, String> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err("invalid request line".into());
+    };
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    Ok(HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        headers,
+        body: String::new(),
+    })
+}
+
+/// Read a request, its body sized by `Content-Length`, within [`REQUEST_TIMEOUT`].
+pub async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest, String> {
+    read_request_within(stream, REQUEST_TIMEOUT).await
+}
+
+async fn read_request_within(
+    stream: &mut TcpStream,
+    limit: Duration,
+) -> Result<HttpRequest, String> {
+    tokio::time::timeout(limit, read_request_untimed(stream))
+        .await
+        .map_err(|_| "request timed out".to_string())?
+}
+
+async fn read_request_untimed(stream: &mut TcpStream) -> Result<HttpRequest, String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let head_end = loop {
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i;
+        }
+        if buf.len() > MAX_HEAD {
+            return Err("request head too large".into());
+        }
+        let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Err("connection closed".into());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let mut request = parse_head(&String::from_utf8_lossy(&buf[..head_end]))?;
+    let length = match request.header("content-length") {
+        Some(len) => len.parse::<usize>().map_err(|_| "invalid Content-Length")?,
+        None => 0,
+    };
+    if length > MAX_BODY {
+        return Err("request body too large".into());
+    }
+    let mut body = buf[head_end + 4..].to_vec();
+    body.truncate(length);
+    if body.len() < length {
+        let mut rest = vec![0u8; length - body.len()];
+        stream
+            .read_exact(&mut rest)
+            .await
+            .map_err(|e| e.to_string())?;
+        body.extend_from_slice(&rest);
+    }
+    request.body = String::from_utf8(body).map_err(|_| "request body is not UTF-8")?;
+    Ok(request)
+}
+
+/// Write a response and close the connection.
+pub async fn write_response(stream: &mut TcpStream, status: u16, body: &str) {
+    let reason = match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Error",
+    };
+    let response = format!(
+        "HTTP/1.0 {status} {reason}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls() {
+        let url = HttpUrl::parse("http://auth.local:8080/check?v=1").unwrap();
+        assert_eq!(
+            (url.host.as_str(), url.port, url.path.as_str()),
+            ("auth.local", 8080, "/check?v=1")
+        );
+        let url = HttpUrl::parse("http://auth.local").unwrap();
+        assert_eq!((url.port, url.path.as_str()), (80, "/"));
+        assert!(HttpUrl::parse("https://auth.local/").is_err());
+        assert!(HttpUrl::parse("http://:80/").is_err());
+    }
+
+    #[test]
+    fn response_bodies() {
+        assert_eq!(
+            response_body(b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\nhello"),
+            Ok("hello".to_string())
+        );
+        assert!(response_body(b"HTTP/1.1 204 No Content\r\n\r\n").is_ok());
+        assert!(response_body(b"HTTP/1.1 500 Oops\r\n\r\n").is_err());
+        assert!(response_body(b"garbage").is_err());
+    }
+
+    #[test]
+    fn request_heads() {
+        let request =
+            parse_head("POST /chat HTTP/1.1\r\nHost: x\r\nAuthorization: Bearer abc").unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/chat");
+        assert_eq!(request.header("authorization"), Some("Bearer abc"));
+        assert_eq!(request.header("content-length"), None);
+        assert!(parse_head("").is_err());
+    }
+
+    #[tokio::test]
+    async fn slow_requests_time_out() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        client.write_all(b"POST /chat HTTP/1.1\r\n").await.unwrap();
+        let (mut server, _) = listener.accept().await.unwrap();
+        assert_eq!(
+            read_request_within(&mut server, Duration::from_millis(50))
+                .await
+                .unwrap_err(),
+            "request timed out"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Kits: named item bundles granted on first join or claimed via `/kit`.
+//!
+//! Kits are defined in `server.toml` under `[kits.<name>]` and/or in
+//! `kits.json` inside the world directory (entries in the file win on name
+//! clashes). Plugins can register additional kits at runtime.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+const KITS_FILE: &str = "kits.json";
+
+/// A single item entry in a kit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KitItem {
+    /// Item identifier; the `minecraft:` prefix is optional.
+    pub item: String,
+    #[serde(default = "default_kit_item_count")]
+    pub count: u16,
+    #[serde(default)]
+    pub metadata: u16,
+}
+
+impl KitItem {
+    /// Sizes of the stacks the entry is given as, none larger than
+    /// `max_stack` (e.g. 70 arrows with a limit of 64 give 64 and 6).
+    pub fn stack_counts(&self, max_stack: u16) -> Vec<u16> {
+        let max_stack = max_stack.max(1);
+        let mut left = self.count.max(1);
+        let mut counts = Vec::new();
+        while left > 0 {
+            let n = left.min(max_stack);
+            counts.push(n);
+            left -= n;
+        }
+        counts
+    }
+}
+
+fn default_kit_item_count() -> u16 {
+    1
+}
+
+/// A named bundle of items.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KitDefinition {
+    pub items: Vec<KitItem>,
+    /// Seconds a player must wait between claims. 0 = no cooldown.
+    #[serde(default)]
+    pub cooldown: u64,
+    /// Grant this kit automatically the first time a player joins the world.
+    #[serde(default)]
+    pub first_join: bool,
+    /// Only operators may claim this kit with `/kit`.
+    #[serde(default)]
+    pub op_only: bool,
+}
+
+impl KitDefinition {
+    /// Seconds left before the kit can be claimed again, or `None` if it is available.
+    ///
+    /// `last_claim` and `now` are unix timestamps in seconds.
+    pub fn cooldown_remaining(&self, last_claim: Option<i64>, now: i64) -> Option<u64> {
+        let last = last_claim?;
+        let ready_at = last.saturating_add(self.cooldown as i64);
+        if now < ready_at {
+            Some((ready_at - now) as u64)
+        } else {
+            None
+        }
+    }
+}
+
+/// Server-defined kits loaded from config and the world directory.
+pub struct KitManager {
+    kits: HashMap<String, KitDefinition>,
+}
+
+impl KitManager {
+    /// Build the kit set from `server.toml` entries, then overlay `kits.json` from the world dir.
+    pub fn load(world_dir: &Path, configured: &HashMap<String, KitDefinition>) -> Self {
+        let mut kits = configured.clone();
+        kits.extend(load_kits_file(&world_dir.join(KITS_FILE)));
+        if !kits.is_empty() {
+            info!("Loaded {} kit(s)", kits.len());
+        }
+        Self { kits }
+    }
+
+    /// Look up a kit by name.
+    pub fn get(&self, name: &str) -> Option<&KitDefinition> {
+        self.kits.get(name)
+    }
+
+    /// Names of all kits, sorted alphabetically.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.kits.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Names of kits granted on first join, sorted alphabetically.
+    pub fn first_join_kits(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .kits
+            .iter()
+            .filter(|(_, k)| k.first_join)
+            .map(|(n, _)| n.clone())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+/// Load a name → kit map from a JSON object file. Returns an empty map if the file is absent.
+fn load_kits_file(path: &Path) -> HashMap<String, KitDefinition> {
+    if !path.exists() {
+        return HashMap::new();
+    }
+    match fs::read_to_string(path) {
+        Ok(contents) => match serde_json::from_str(&contents) {
+            Ok(map) => map,
+            Err(e) => {
+                warn!("Failed to parse {}: {e}", path.display());
+                HashMap::new()
+            }
+        },
+        Err(e) => {
+            warn!("Failed to read {}: {e}", path.display());
+            HashMap::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mc_rs_kits_{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn kit(cooldown: u64, first_join: bool) -> KitDefinition {
+        KitDefinition {
+            items: vec![KitItem {
+                item: "minecraft:bread".into(),
+                count: 4,
+                metadata: 0,
+            }],
+            cooldown,
+            first_join,
+            op_only: false,
+        }
+    }
+
+    #[test]
+    fn stacks_never_exceed_the_item_limit() {
+        let item = |count| KitItem {
+            item: "minecraft:arrow".into(),
+            count,
+            metadata: 0,
+        };
+        assert_eq!(item(128).stack_counts(64), vec![64, 64]);
+        assert_eq!(item(70).stack_counts(64), vec![64, 6]);
+        assert_eq!(item(3).stack_counts(1), vec![1, 1, 1]);
+        assert_eq!(item(0).stack_counts(16), vec![1]);
+    }
+
+    #[test]
+    fn cooldown_remaining() {
+        let k = kit(60, false);
+        assert_eq!(k.cooldown_remaining(None, 1000), None);
+        assert_eq!(k.cooldown_remaining(Some(1000), 1010), Some(50));
+        assert_eq!(k.cooldown_remaining(Some(1000), 1060), None);
+        // No cooldown: always available
+        assert_eq!(kit(0, false).cooldown_remaining(Some(1000), 1000), None);
+    }
+
+    #[test]
+    fn file_overrides_config() {
+        let dir = temp_dir();
+        fs::write(
+            dir.join(KITS_FILE),
+            r#"{
+                "starter": { "items": [{ "item": "stone_sword" }], "first_join": true },
+                "food": { "items": [{ "item": "bread", "count": 16 }], "cooldown": 3600 }
+            }"#,
+        )
+        .unwrap();
+
+        let mut configured = HashMap::new();
+        configured.insert("starter".to_string(), kit(0, false));
+        configured.insert("tools".to_string(), kit(0, false));
+
+        let mgr = KitManager::load(&dir, &configured);
+        assert_eq!(mgr.names(), vec!["food", "starter", "tools"]);
+        assert_eq!(mgr.first_join_kits(), vec!["starter"]);
+        let starter = mgr.get("starter").unwrap();
+        assert_eq!(starter.items[0].item, "stone_sword");
+        assert_eq!(starter.items[0].count, 1);
+        assert_eq!(mgr.get("food").unwrap().cooldown, 3600);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn missing_file_uses_config_only() {
+        let dir = temp_dir();
+        let mut configured = HashMap::new();
+        configured.insert("starter".to_string(), kit(0, true));
+        let mgr = KitManager::load(&dir, &configured);
+        assert_eq!(mgr.names(), vec!["starter"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,42 @@
+//! MC-RS server: the connection handler and the game logic behind it, run
+//! by the `mc-rs-server` binary and driven directly by `mc-rs-testing`.
+
+mod actor_data;
+mod anticheat;
+mod arena;
+pub mod bridge;
+mod chat;
+mod client_settings;
+pub mod config;
+pub mod connection;
+mod custom_blocks;
+mod custom_items;
+mod cutscenes;
+mod economy;
+mod entity_appearance;
+mod forms;
+mod functions;
+mod geoip;
+pub mod handoff;
+mod holograms;
+mod http;
+mod kits;
+pub mod motd;
+mod npcs;
+mod packs;
+mod permissions;
+mod persistence;
+mod player_store;
+mod plugin_manager;
+mod prelogin;
+pub mod query;
+pub mod rcon;
+mod shops;
+mod stats;
+mod timings;
+pub mod tps;
+mod view_distance;
+mod warps;
+pub mod watchdog;
+mod world_flags;
+mod worlds;
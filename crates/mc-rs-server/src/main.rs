@@ -1,20 +1,14 @@
-mod config;
-mod connection;
-mod permissions;
-mod persistence;
-mod plugin_manager;
-mod query;
-mod rcon;
-
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use config::ServerConfig;
-use connection::ConnectionHandler;
-use mc_rs_raknet::{RakNetConfig, RakNetServer, ServerMotd};
+use mc_rs_raknet::{RakNetConfig, RakNetServer, ServerMotd, ThrottleConfig};
+use mc_rs_server::config::{ServerConfig, CONFIG_FILE};
+use mc_rs_server::connection::{ConnectionHandler, RESTART_EXIT_CODE};
+use mc_rs_server::{bridge, handoff, motd, query, rcon, tps, watchdog};
 use tokio::io::AsyncBufReadExt;
-use tracing::info;
+use tracing::{info, warn};
 
 /// Return the numeric gamemode for the MOTD (1-indexed to match BDS).
 fn gamemode_to_numeric(gamemode: &str) -> u8 {
@@ -50,10 +44,10 @@ fn main() {
 }
 
 async fn async_main() {
-    let config = Arc::new(match ServerConfig::load("server.toml") {
+    let config = Arc::new(match ServerConfig::load(CONFIG_FILE) {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("Failed to load server.toml: {e}");
+            eprintln!("Failed to load {CONFIG_FILE}: {e}");
             std::process::exit(1);
         }
     });
@@ -90,13 +84,21 @@ async fn async_main() {
 
     let server_guid: i64 = rand::random();
 
+    let game_version =
+        mc_rs_proto::packets::game_version_for_protocol(mc_rs_proto::packets::PROTOCOL_VERSION);
     let motd = ServerMotd {
-        server_name: config.server.motd.clone(),
+        server_name: motd::render(
+            &config.server.motd,
+            &motd::MotdValues {
+                online: 0,
+                max: config.server.max_players,
+                tps: tps::TARGET_TPS,
+                world: &config.world.name,
+                version: game_version,
+            },
+        ),
         protocol_version: mc_rs_proto::packets::PROTOCOL_VERSION as u32,
-        game_version: mc_rs_proto::packets::game_version_for_protocol(
-            mc_rs_proto::packets::PROTOCOL_VERSION,
-        )
-        .into(),
+        game_version: game_version.into(),
         online_players: 0,
         max_players: config.server.max_players,
         server_guid,
@@ -111,8 +113,23 @@ async fn async_main() {
     let raknet_config = RakNetConfig {
         address: addr,
         server_guid,
-        motd,
+        motd: motd.clone(),
         max_connections: config.server.max_players as usize,
+        proxy_protocol: config.proxy.proxy_protocol,
+        trusted_proxies: config.proxy.trusted_proxies.clone(),
+        throttle: ThrottleConfig {
+            max_pings_per_second: config.throttle.max_pings_per_second,
+            max_connection_attempts_per_second: config.throttle.max_connection_attempts_per_second,
+            max_sessions_per_ip: config.throttle.max_sessions_per_ip,
+            max_malformed_per_minute: config.throttle.max_malformed_per_minute,
+            ban_duration: Duration::from_secs(config.throttle.ban_duration),
+        },
+        lan_discovery: config.lan.discovery,
+        lan_broadcast: config.lan.broadcast,
+        capture_path: config
+            .capture
+            .enabled
+            .then(|| PathBuf::from(&config.capture.file)),
     };
 
     let (mut server, mut events, server_handle) = RakNetServer::bind(raknet_config)
@@ -149,6 +166,30 @@ async fn async_main() {
         rcon::start(config.rcon.port, config.rcon.password.clone(), rcon_tx);
     }
 
+    // Transfer handoff listener (if a shared secret is configured)
+    let (handoff_tx, mut handoff_rx) = tokio::sync::mpsc::channel::<handoff::HandoffTicket>(32);
+    if !config.transfer.secret.is_empty() {
+        handoff::start(
+            config.transfer.handoff_port,
+            config.transfer.secret.clone(),
+            handoff_tx,
+        );
+    }
+
+    // Chat bridge endpoint (if an address and token are configured)
+    let (bridge_tx, mut bridge_rx) = tokio::sync::mpsc::channel::<bridge::BridgeChat>(32);
+    if !config.bridge.listen.is_empty() {
+        if config.bridge.token.is_empty() {
+            warn!("Not starting the bridge endpoint: [bridge] token is empty");
+        } else {
+            bridge::start(
+                config.bridge.listen.clone(),
+                config.bridge.token.clone(),
+                bridge_tx,
+            );
+        }
+    }
+
     // Query server (if enabled)
     let (query_stats_tx, query_stats_rx) =
         tokio::sync::watch::channel(query::ServerStats::default());
@@ -162,14 +203,21 @@ async fn async_main() {
     let shutdown_tx_handler = shutdown_tx.clone();
     let mut shutdown_rx_handler = shutdown_rx.clone();
     let query_enabled = server_config.query.enabled;
-    tokio::spawn(async move {
+    let watchdog_config = server_config.watchdog.clone();
+    let handler_task = tokio::spawn(async move {
         let mut handler = ConnectionHandler::new(
             server_handle,
             online_mode,
             server_config,
             shutdown_tx_handler,
+            motd,
         );
-        let mut tick_interval = tokio::time::interval(Duration::from_millis(50));
+        let activity = handler.activity();
+        if watchdog_config.enabled {
+            if let Err(e) = watchdog::spawn(Arc::clone(&activity), watchdog_config) {
+                warn!("Failed to start the watchdog: {e}");
+            }
+        }
         loop {
             tokio::select! {
                 event = events.recv() => {
@@ -178,7 +226,7 @@ async fn async_main() {
                         None => break, // channel closed
                     }
                 }
-                _ = tick_interval.tick() => {
+                _ = tokio::time::sleep_until(handler.next_tick_deadline().into()) => {
                     handler.game_tick().await;
 
                     // Update query stats periodically (every 100 ticks / 5 seconds)
@@ -194,17 +242,31 @@ async fn async_main() {
                     let response = handler.handle_console_command(&rcon_cmd.command).await;
                     let _ = rcon_cmd.response_tx.send(response);
                 }
+                Some(ticket) = handoff_rx.recv() => {
+                    handler.accept_handoff(ticket);
+                }
+                Some(chat) = bridge_rx.recv() => {
+                    handler.relay_bridge_chat(chat).await;
+                }
                 _ = shutdown_rx_handler.changed() => {
                     if *shutdown_rx_handler.borrow() {
+                        activity.stop();
                         info!("Saving world before shutdown...");
-                        handler.save_all();
+                        handler.shutdown().await;
                         break;
                     }
                 }
             }
         }
+        handler.restart_requested()
     });
 
     server.run(shutdown_rx).await;
+    // Let the handler finish saving
+    let restart = handler_task.await.unwrap_or(false);
     info!("Server shut down.");
+    if restart {
+        info!("Exiting with code {RESTART_EXIT_CODE} to be restarted");
+        std::process::exit(RESTART_EXIT_CODE);
+    }
 }
@@ -0,0 +1,88 @@
+//! MOTD templating.
+//!
+//! The `[server] motd` string may contain placeholders that are filled in
+//! each time the advertised MOTD is refreshed:
+//!
+//! | Placeholder | Value |
+//! |-------------|-------|
+//! | `{online}`  | players in game |
+//! | `{max}`     | `max_players` |
+//! | `{tps}`     | recent ticks per second, one decimal |
+//! | `{world}`   | world name |
+//! | `{version}` | game version |
+//!
+//! Unknown placeholders are left as is.
+
+/// Values substituted into the MOTD template.
+pub struct MotdValues<'a> {
+    pub online: usize,
+    pub max: u32,
+    pub tps: f64,
+    pub world: &'a str,
+    pub version: &'a str,
+}
+
+/// Replace the placeholders in `template`.
+pub fn render(template: &str, values: &MotdValues) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start..];
+        let Some(end) = after.find('}') else {
+            rest = after;
+            break;
+        };
+        let key = &after[1..end];
+        match key {
+            "online" => out.push_str(&values.online.to_string()),
+            "max" => out.push_str(&values.max.to_string()),
+            "tps" => out.push_str(&format!("{:.1}", values.tps)),
+            "world" => out.push_str(values.world),
+            "version" => out.push_str(values.version),
+            _ => out.push_str(&after[..=end]),
+        }
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    // ';' separates MOTD fields in UnconnectedPong
+    out.replace(';', ",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> MotdValues<'static> {
+        MotdValues {
+            online: 3,
+            max: 20,
+            tps: 19.96,
+            world: "world",
+            version: "1.21.130",
+        }
+    }
+
+    #[test]
+    fn render_placeholders() {
+        assert_eq!(
+            render("MC-RS {online}/{max} | TPS {tps}", &values()),
+            "MC-RS 3/20 | TPS 20.0"
+        );
+        assert_eq!(
+            render("{world} on {version}", &values()),
+            "world on 1.21.130"
+        );
+    }
+
+    #[test]
+    fn render_leaves_plain_and_unknown_text() {
+        assert_eq!(render("Plain MOTD", &values()), "Plain MOTD");
+        assert_eq!(render("{unknown} {online", &values()), "{unknown} {online");
+    }
+
+    #[test]
+    fn render_strips_field_separator() {
+        assert_eq!(render("a;b", &values()), "a,b");
+    }
+}
@@ -0,0 +1,337 @@
+//! NPCs: named entities that stand still and do something when a player
+//! clicks them, persisted in `npcs.json` inside the world directory.
+//!
+//! An NPC is a player-shaped entity wearing a saved skin, or any other
+//! entity type. Its actions run in order on every click; `{player}` in a
+//! command, message or dialogue is replaced by the name of the player who
+//! clicked.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use mc_rs_proto::jwt::{ClientData, SkinImage};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+const NPCS_FILE: &str = "npcs.json";
+
+/// Entity type of player-shaped NPCs.
+pub const PLAYER_NPC_TYPE: &str = "minecraft:player";
+
+/// What happens when a player clicks an NPC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NpcAction {
+    /// Run a command as the player, with their permissions.
+    Command { command: String },
+    /// Run a command as the server.
+    Console { command: String },
+    /// Send the player a chat message.
+    Message { text: String },
+    /// Open a form titled with the NPC's name; each button runs a command
+    /// as the player.
+    Dialogue {
+        text: String,
+        #[serde(default)]
+        buttons: Vec<DialogueButton>,
+    },
+    /// Fire the `NpcInteract` plugin event with this ID.
+    Event { id: String },
+}
+
+/// A button of an NPC dialogue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueButton {
+    pub text: String,
+    /// Run as the player when the button is clicked; none just closes the
+    /// dialogue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+/// A skin saved with an NPC, copied from a player.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NpcSkin {
+    pub skin_id: String,
+    pub width: u32,
+    pub height: u32,
+    /// RGBA pixels, base64-encoded.
+    pub data: String,
+    #[serde(default)]
+    pub resource_patch: String,
+    #[serde(default)]
+    pub geometry: String,
+    #[serde(default)]
+    pub arm_size: String,
+}
+
+impl NpcSkin {
+    pub fn from_client_data(data: &ClientData) -> Self {
+        Self {
+            skin_id: data.skin_id.clone(),
+            width: data.skin_image.width,
+            height: data.skin_image.height,
+            data: STANDARD.encode(&data.skin_image.data),
+            resource_patch: data.skin_resource_patch.clone(),
+            geometry: data.skin_geometry_data.clone(),
+            arm_size: data.arm_size.clone(),
+        }
+    }
+
+    /// The skin as sent in a PlayerList entry.
+    pub fn to_client_data(&self) -> ClientData {
+        ClientData {
+            skin_id: self.skin_id.clone(),
+            skin_image: SkinImage {
+                width: self.width,
+                height: self.height,
+                data: STANDARD.decode(&self.data).unwrap_or_default(),
+            },
+            skin_resource_patch: self.resource_patch.clone(),
+            skin_geometry_data: self.geometry.clone(),
+            arm_size: if self.arm_size.is_empty() {
+                "wide".to_string()
+            } else {
+                self.arm_size.clone()
+            },
+            ..ClientData::default()
+        }
+    }
+}
+
+/// An NPC in one of the worlds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Npc {
+    pub name: String,
+    /// Name shown above its head.
+    pub display_name: String,
+    #[serde(default)]
+    pub dimension: i32,
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub yaw: f32,
+    #[serde(default)]
+    pub pitch: f32,
+    #[serde(default = "default_entity_type")]
+    pub entity_type: String,
+    /// Skin of a player-shaped NPC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skin: Option<NpcSkin>,
+    #[serde(default)]
+    pub actions: Vec<NpcAction>,
+    /// Entity ID while it is shown.
+    #[serde(skip)]
+    pub entity_id: Option<i64>,
+}
+
+fn default_entity_type() -> String {
+    PLAYER_NPC_TYPE.to_string()
+}
+
+impl Npc {
+    pub fn new(name: &str, dimension: i32, position: [f32; 3], yaw: f32, pitch: f32) -> Self {
+        Self {
+            name: name.to_string(),
+            display_name: name.to_string(),
+            dimension,
+            position,
+            yaw,
+            pitch,
+            entity_type: default_entity_type(),
+            skin: None,
+            actions: Vec::new(),
+            entity_id: None,
+        }
+    }
+
+    pub fn is_player_shaped(&self) -> bool {
+        self.entity_type == PLAYER_NPC_TYPE
+    }
+
+    /// The chunk it stands in.
+    pub fn chunk(&self) -> (i32, i32) {
+        let [x, _, z] = self.position;
+        ((x.floor() as i32) >> 4, (z.floor() as i32) >> 4)
+    }
+
+    /// The dialogue it opens, if any.
+    pub fn dialogue(&self) -> Option<(&str, &[DialogueButton])> {
+        self.actions.iter().find_map(|a| match a {
+            NpcAction::Dialogue { text, buttons } => Some((text.as_str(), buttons.as_slice())),
+            _ => None,
+        })
+    }
+}
+
+/// Fill in `{player}`.
+pub fn fill_player(text: &str, player: &str) -> String {
+    text.replace("{player}", player)
+}
+
+/// The NPCs of a world.
+pub struct NpcManager {
+    npcs: Vec<Npc>,
+    path: PathBuf,
+}
+
+impl NpcManager {
+    /// Load `npcs.json` from the world directory. A missing file means no
+    /// NPCs.
+    pub fn load(world_dir: &Path) -> Self {
+        let path = world_dir.join(NPCS_FILE);
+        let npcs: Vec<Npc> = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Failed to parse {}: {e}", path.display());
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        if !npcs.is_empty() {
+            info!("Loaded {} NPC(s)", npcs.len());
+        }
+        Self { npcs, path }
+    }
+
+    /// Save the file after changing an NPC.
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(&self.npcs) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&self.path, json) {
+                    warn!("Failed to save {}: {e}", self.path.display());
+                }
+            }
+            Err(e) => warn!("Failed to serialize NPCs: {e}"),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Npc> {
+        self.npcs.iter().find(|n| n.name == name)
+    }
+
+    /// An NPC to change; call [`save`](Self::save) afterwards.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Npc> {
+        self.npcs.iter_mut().find(|n| n.name == name)
+    }
+
+    /// The NPC shown with an entity runtime ID.
+    pub fn by_entity(&self, runtime_id: u64) -> Option<&Npc> {
+        self.npcs
+            .iter()
+            .find(|n| n.entity_id.is_some_and(|id| id as u64 == runtime_id))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Npc> {
+        self.npcs.iter()
+    }
+
+    /// Names of the NPCs standing in a chunk.
+    pub fn in_chunk(&self, dimension: i32, chunk: (i32, i32)) -> Vec<String> {
+        self.npcs
+            .iter()
+            .filter(|n| n.dimension == dimension && n.chunk() == chunk)
+            .map(|n| n.name.clone())
+            .collect()
+    }
+
+    /// Add an NPC, replacing one with the same name, and save the file.
+    /// Returns the replaced one.
+    pub fn insert(&mut self, npc: Npc) -> Option<Npc> {
+        let old = self.remove_unsaved(&npc.name);
+        self.npcs.push(npc);
+        self.save();
+        old
+    }
+
+    /// Delete an NPC and save the file.
+    pub fn remove(&mut self, name: &str) -> Option<Npc> {
+        let old = self.remove_unsaved(name);
+        if old.is_some() {
+            self.save();
+        }
+        old
+    }
+
+    fn remove_unsaved(&mut self, name: &str) -> Option<Npc> {
+        let index = self.npcs.iter().position(|n| n.name == name)?;
+        Some(self.npcs.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mc_rs_npcs_{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn actions_are_tagged_by_type() {
+        let json = r#"[
+            {"type": "message", "text": "Hello {player}"},
+            {"type": "console", "command": "give {player} bread"},
+            {"type": "dialogue", "text": "Need a hand?", "buttons": [
+                {"text": "Go to spawn", "command": "spawn"},
+                {"text": "Bye"}
+            ]},
+            {"type": "event", "id": "quest:start"}
+        ]"#;
+        let actions: Vec<NpcAction> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            actions[1],
+            NpcAction::Console {
+                command: "give {player} bread".into()
+            }
+        );
+        let mut npc = Npc::new("guide", 0, [0.5, 65.0, 0.5], 0.0, 0.0);
+        npc.actions = actions;
+        let (text, buttons) = npc.dialogue().unwrap();
+        assert_eq!(text, "Need a hand?");
+        assert_eq!(buttons[1].command, None);
+        assert_eq!(fill_player("Hello {player}", "Alice"), "Hello Alice");
+    }
+
+    #[test]
+    fn skin_survives_a_round_trip() {
+        let data = ClientData {
+            skin_id: "custom".into(),
+            skin_image: SkinImage {
+                width: 2,
+                height: 1,
+                data: vec![1, 2, 3, 4, 5, 6, 7, 8],
+            },
+            arm_size: "slim".into(),
+            ..ClientData::default()
+        };
+        let back = NpcSkin::from_client_data(&data).to_client_data();
+        assert_eq!(back.skin_id, "custom");
+        assert_eq!(back.skin_image.data, data.skin_image.data);
+        assert_eq!(back.arm_size, "slim");
+    }
+
+    #[test]
+    fn save_reload_and_find_by_chunk() {
+        let dir = temp_dir();
+        let mut mgr = NpcManager::load(&dir);
+        let mut npc = Npc::new("guide", 0, [-0.5, 65.0, 17.5], 90.0, 0.0);
+        npc.entity_id = Some(42);
+        mgr.insert(npc);
+        assert_eq!(mgr.in_chunk(0, (-1, 1)), ["guide"]);
+        assert!(mgr.in_chunk(1, (-1, 1)).is_empty());
+        assert_eq!(mgr.by_entity(42).map(|n| n.name.as_str()), Some("guide"));
+
+        let reloaded = NpcManager::load(&dir);
+        let guide = reloaded.get("guide").unwrap();
+        assert!(guide.is_player_shaped());
+        assert_eq!(guide.yaw, 90.0);
+        assert_eq!(guide.entity_id, None, "entity IDs are not saved");
+
+        assert!(mgr.remove("guide").is_some());
+        assert!(NpcManager::load(&dir).get("guide").is_none());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,131 @@
+//! Encrypted pack support (marketplace-style content keys).
+//!
+//! An encrypted pack's `contents.json` starts with a 256-byte header
+//! (version, magic, content ID) followed by the file index encrypted with
+//! AES-256-CFB8, keyed by the pack's 32-character content key (IV = its first
+//! 16 bytes). The server never decrypts pack files — it only checks that the
+//! configured key opens `contents.json` and forwards the key to clients.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use tracing::warn;
+
+/// Magic following the version field in an encrypted `contents.json`.
+const CONTENTS_MAGIC: u32 = 0x9BCF_B9FC;
+/// Offset of the length-prefixed content ID in the header.
+const CONTENT_ID_OFFSET: usize = 0x10;
+/// Size of the plaintext header before the encrypted index.
+const CONTENTS_HEADER_LEN: usize = 0x100;
+/// Content keys are 32 ASCII characters (the AES-256 key).
+pub const CONTENT_KEY_LEN: usize = 32;
+
+/// The plaintext header and encrypted body of a `contents.json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedContents<'a> {
+    pub content_id: String,
+    pub ciphertext: &'a [u8],
+}
+
+/// Parse an encrypted `contents.json`; `None` if it is plain JSON.
+pub fn parse_contents(data: &[u8]) -> Option<EncryptedContents<'_>> {
+    if data.len() < CONTENTS_HEADER_LEN {
+        return None;
+    }
+    let magic = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    if magic != CONTENTS_MAGIC {
+        return None;
+    }
+    let id_len = data[CONTENT_ID_OFFSET] as usize;
+    let id = data.get(CONTENT_ID_OFFSET + 1..CONTENT_ID_OFFSET + 1 + id_len)?;
+    Some(EncryptedContents {
+        content_id: String::from_utf8_lossy(id).into_owned(),
+        ciphertext: &data[CONTENTS_HEADER_LEN..],
+    })
+}
+
+/// Whether `key` decrypts `contents` to a valid file index.
+pub fn key_matches(key: &str, contents: &EncryptedContents<'_>) -> bool {
+    let Some((aes_key, iv)) = split_key(key) else {
+        return false;
+    };
+    let plain = mc_rs_crypto::cfb8_decrypt(&aes_key, &iv, contents.ciphertext);
+    serde_json::from_slice::<serde_json::Value>(&plain)
+        .is_ok_and(|json| json.get("content").is_some_and(|c| c.is_array()))
+}
+
+/// Build an encrypted `contents.json` (used to produce test packs).
+#[cfg(test)]
+pub fn encrypt_contents(content_id: &str, key: &str, index: &serde_json::Value) -> Vec<u8> {
+    let (aes_key, iv) = split_key(key).expect("valid content key");
+    let mut out = vec![0u8; CONTENTS_HEADER_LEN];
+    out[4..8].copy_from_slice(&CONTENTS_MAGIC.to_le_bytes());
+    out[CONTENT_ID_OFFSET] = content_id.len() as u8;
+    out[CONTENT_ID_OFFSET + 1..CONTENT_ID_OFFSET + 1 + content_id.len()]
+        .copy_from_slice(content_id.as_bytes());
+    out.extend(mc_rs_crypto::cfb8_encrypt(
+        &aes_key,
+        &iv,
+        &serde_json::to_vec(index).unwrap(),
+    ));
+    out
+}
+
+fn split_key(key: &str) -> Option<([u8; 32], [u8; 16])> {
+    let aes_key: [u8; CONTENT_KEY_LEN] = key.as_bytes().try_into().ok()?;
+    let mut iv = [0u8; 16];
+    iv.copy_from_slice(&aes_key[..16]);
+    Some((aes_key, iv))
+}
+
+/// Load `pack_keys.json`: a map of pack UUID → content key.
+pub fn load_pack_keys(path: &Path) -> HashMap<String, String> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(_) => return HashMap::new(),
+    };
+    match serde_json::from_str::<HashMap<String, String>>(&json) {
+        Ok(keys) => keys
+            .into_iter()
+            .map(|(uuid, key)| (uuid.to_lowercase(), key.trim().to_string()))
+            .collect(),
+        Err(e) => {
+            warn!("Failed to parse {}: {e}", path.display());
+            HashMap::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &str = "abcdefghijklmnopqrstuvwxyz012345";
+
+    #[test]
+    fn encrypted_contents_roundtrip() {
+        let index = serde_json::json!({"content": [{"path": "manifest.json", "key": null}]});
+        let data = encrypt_contents("my-content-id", KEY, &index);
+        let contents = parse_contents(&data).unwrap();
+        assert_eq!(contents.content_id, "my-content-id");
+        assert!(key_matches(KEY, &contents));
+        assert!(!key_matches("0123456789abcdefghijklmnopqrstuv", &contents));
+        assert!(!key_matches("short", &contents));
+    }
+
+    #[test]
+    fn plain_contents_not_encrypted() {
+        assert!(parse_contents(br#"{"content": []}"#).is_none());
+        assert!(parse_contents(&[0u8; 512]).is_none());
+    }
+
+    #[test]
+    fn pack_keys_file() {
+        let path = std::env::temp_dir().join("mc_rs_pack_keys_test.json");
+        std::fs::write(&path, r#"{"AAAA-BBBB": " key1 "}"#).unwrap();
+        let keys = load_pack_keys(&path);
+        assert_eq!(keys.get("aaaa-bbbb").map(String::as_str), Some("key1"));
+        let _ = std::fs::remove_file(&path);
+        assert!(load_pack_keys(&path).is_empty());
+    }
+}
@@ -0,0 +1,389 @@
+//! Resource and behavior pack hosting.
+//!
+//! Collects every pack the server offers to clients — resource packs from the
+//! `resource_packs/` directory (`.zip`/`.mcpack` archives or unpacked
+//! directories) and behavior packs that ship a pre-zipped `.mcpack` — and
+//! serves their bytes in fixed-size chunks during the login pack exchange.
+//!
+//! Encrypted archives need their content key, either from a sibling
+//! `<archive>.key` file or from `pack_keys.json` (pack UUID → key) in the
+//! resource pack directory.
+
+pub mod encryption;
+pub mod preferences;
+pub mod zip;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use mc_rs_behavior_pack::loader::LoadedBehaviorPack;
+use mc_rs_behavior_pack::manifest::BehaviorPackManifest;
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::config::PacksSection;
+
+/// Size of each ResourcePackChunkData payload.
+pub const PACK_CHUNK_SIZE: u32 = 1_048_576;
+
+/// Pack type as sent in ResourcePackDataInfo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackKind {
+    Resources,
+    Behavior,
+}
+
+impl PackKind {
+    /// Protocol `PackType` value.
+    pub fn wire_type(self) -> u8 {
+        match self {
+            PackKind::Behavior => 4,
+            PackKind::Resources => 6,
+        }
+    }
+}
+
+/// A pack archive ready to be transferred to clients.
+#[derive(Debug, Clone)]
+pub struct HostedPack {
+    pub uuid: String,
+    pub version: String,
+    pub name: String,
+    pub kind: PackKind,
+    /// Content key the client needs to decrypt the pack (empty if unencrypted).
+    pub content_key: String,
+    /// Content ID from an encrypted pack's `contents.json` header.
+    pub content_id: String,
+    pub has_scripts: bool,
+    pub bytes: Vec<u8>,
+    /// SHA-256 of `bytes`.
+    pub hash: [u8; 32],
+}
+
+impl HostedPack {
+    fn new(manifest: &BehaviorPackManifest, kind: PackKind, bytes: Vec<u8>) -> Self {
+        let hash = Sha256::digest(&bytes).into();
+        Self {
+            uuid: manifest.header.uuid.clone(),
+            version: manifest.version_string(),
+            name: manifest.header.name.clone(),
+            kind,
+            content_key: String::new(),
+            content_id: String::new(),
+            has_scripts: manifest.modules.iter().any(|m| m.module_type == "script"),
+            bytes,
+            hash,
+        }
+    }
+
+    /// Pack ID used in the chunk transfer packets: `"<uuid>_<version>"`.
+    pub fn pack_id(&self) -> String {
+        format!("{}_{}", self.uuid, self.version)
+    }
+
+    /// Whether an entry of a pack list in the config names this pack: its
+    /// UUID, or its name in any case.
+    pub fn is_named(&self, entry: &str) -> bool {
+        self.uuid.eq_ignore_ascii_case(entry) || self.name.eq_ignore_ascii_case(entry)
+    }
+
+    /// Whether players must accept this pack to join: it is listed as
+    /// required, or packs are forced and it is not listed as optional.
+    pub fn is_required(&self, config: &PacksSection) -> bool {
+        if config.required.iter().any(|e| self.is_named(e)) {
+            return true;
+        }
+        config.force_packs && !config.optional.iter().any(|e| self.is_named(e))
+    }
+
+    pub fn chunk_count(&self) -> u32 {
+        (self.bytes.len() as u64).div_ceil(PACK_CHUNK_SIZE as u64) as u32
+    }
+
+    /// The bytes of chunk `index` and its byte offset, if it exists.
+    pub fn chunk(&self, index: u32) -> Option<(u64, &[u8])> {
+        let offset = index as usize * PACK_CHUNK_SIZE as usize;
+        if offset >= self.bytes.len() {
+            return None;
+        }
+        let end = (offset + PACK_CHUNK_SIZE as usize).min(self.bytes.len());
+        Some((offset as u64, &self.bytes[offset..end]))
+    }
+}
+
+/// All packs offered to clients, in stack order.
+#[derive(Debug, Default)]
+pub struct PackStore {
+    packs: Vec<HostedPack>,
+}
+
+impl PackStore {
+    /// Load resource packs from `resource_dir` and add every behavior pack
+    /// that has transferable bytes.
+    pub fn load(resource_dir: &Path, behavior_packs: &[LoadedBehaviorPack]) -> Self {
+        let mut packs = load_resource_packs(resource_dir);
+        for pack in behavior_packs {
+            if let Some(bytes) = &pack.pack_bytes {
+                packs.push(HostedPack::new(
+                    &pack.manifest,
+                    PackKind::Behavior,
+                    bytes.clone(),
+                ));
+            }
+        }
+        Self { packs }
+    }
+
+    pub fn packs(&self) -> &[HostedPack] {
+        &self.packs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packs.is_empty()
+    }
+
+    /// Look up a pack by `"<uuid>"` or `"<uuid>_<version>"`.
+    pub fn find(&self, pack_id: &str) -> Option<&HostedPack> {
+        let uuid = pack_id.split('_').next().unwrap_or(pack_id);
+        self.packs.iter().find(|p| p.uuid == uuid)
+    }
+}
+
+/// Scan `dir` for resource packs.
+fn load_resource_packs(dir: &Path) -> Vec<HostedPack> {
+    let keys = encryption::load_pack_keys(&dir.join("pack_keys.json"));
+    let mut paths: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries.flatten().map(|e| e.path()).collect(),
+        Err(_) => return Vec::new(),
+    };
+    paths.sort();
+
+    let mut packs = Vec::new();
+    for path in paths {
+        let is_archive = path
+            .extension()
+            .is_some_and(|ext| ext == "zip" || ext == "mcpack");
+        let result = if is_archive {
+            load_archive(&path, &keys)
+        } else if path.is_dir() && path.join("manifest.json").exists() {
+            load_directory(&path)
+        } else {
+            continue;
+        };
+        match result {
+            Ok(pack) => {
+                info!(
+                    "Loaded resource pack '{}' v{} ({} bytes{})",
+                    pack.name,
+                    pack.version,
+                    pack.bytes.len(),
+                    if pack.content_key.is_empty() {
+                        ""
+                    } else {
+                        ", encrypted"
+                    }
+                );
+                packs.push(pack);
+            }
+            Err(e) => warn!("Failed to load resource pack {}: {e}", path.display()),
+        }
+    }
+    packs
+}
+
+/// Load a `.zip`/`.mcpack` archive. Encrypted archives are rejected unless a
+/// content key that opens their `contents.json` is configured.
+fn load_archive(path: &Path, keys: &HashMap<String, String>) -> Result<HostedPack, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("read: {e}"))?;
+    let (manifest, contents) = {
+        let archive = zip::ZipArchive::new(&bytes)?;
+        let root = archive
+            .find_root("manifest.json")
+            .ok_or("no manifest.json in archive")?;
+        let json = archive.read(&format!("{root}manifest.json"))?;
+        let manifest = BehaviorPackManifest::parse(&String::from_utf8_lossy(&json))?;
+        (manifest, archive.read(&format!("{root}contents.json")).ok())
+    };
+
+    let mut key_path = path.as_os_str().to_owned();
+    key_path.push(".key");
+    let key = std::fs::read_to_string(&key_path)
+        .ok()
+        .map(|k| k.trim().to_string())
+        .or_else(|| keys.get(&manifest.header.uuid.to_lowercase()).cloned());
+
+    let encrypted = contents.as_deref().and_then(encryption::parse_contents);
+    let mut pack = HostedPack::new(&manifest, PackKind::Resources, bytes);
+    match (encrypted, key) {
+        (Some(contents), Some(key)) => {
+            if !encryption::key_matches(&key, &contents) {
+                return Err("content key does not decrypt contents.json".into());
+            }
+            pack.content_key = key;
+            pack.content_id = contents.content_id;
+        }
+        (Some(_), None) => return Err("pack is encrypted but has no content key".into()),
+        (None, Some(_)) => warn!(
+            "Ignoring content key for unencrypted pack '{}'",
+            manifest.header.name
+        ),
+        (None, None) => {}
+    }
+    Ok(pack)
+}
+
+/// Zip an unpacked pack directory for transfer.
+fn load_directory(path: &Path) -> Result<HostedPack, String> {
+    let json = std::fs::read_to_string(path.join("manifest.json"))
+        .map_err(|e| format!("read manifest.json: {e}"))?;
+    let manifest = BehaviorPackManifest::parse(&json)?;
+    let bytes = zip::zip_directory(path).map_err(|e| format!("zip directory: {e}"))?;
+    Ok(HostedPack::new(&manifest, PackKind::Resources, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const MANIFEST: &str = r#"{
+        "format_version": 2,
+        "header": {
+            "name": "Textures",
+            "uuid": "11111111-2222-3333-4444-555555555555",
+            "version": [1, 0, 2]
+        },
+        "modules": [{"type": "resources", "uuid": "11111111-2222-3333-4444-666666666666", "version": [1, 0, 2]}]
+    }"#;
+
+    #[test]
+    fn load_directory_and_archive() {
+        let dir = std::env::temp_dir().join("mc_rs_resource_packs_test");
+        let _ = fs::remove_dir_all(&dir);
+        let unpacked = dir.join("textures");
+        fs::create_dir_all(unpacked.join("textures")).unwrap();
+        fs::write(unpacked.join("manifest.json"), MANIFEST).unwrap();
+        fs::write(unpacked.join("textures/stone.png"), [0u8; 16]).unwrap();
+
+        let mut writer = zip::ZipWriter::default();
+        writer.add(
+            "Sounds/manifest.json",
+            MANIFEST
+                .replace("11111111-2222", "99999999-2222")
+                .as_bytes(),
+        );
+        fs::write(dir.join("sounds.zip"), writer.finish()).unwrap();
+
+        let store = PackStore::load(&dir, &[]);
+        assert_eq!(store.packs().len(), 2);
+
+        let sounds = store.find("99999999-2222-3333-4444-555555555555").unwrap();
+        assert!(sounds.content_key.is_empty());
+        assert_eq!(sounds.kind, PackKind::Resources);
+
+        let textures = store
+            .find("11111111-2222-3333-4444-555555555555_1.0.2")
+            .unwrap();
+        assert_eq!(
+            textures.pack_id(),
+            "11111111-2222-3333-4444-555555555555_1.0.2"
+        );
+        assert!(textures.content_key.is_empty());
+        assert_eq!(
+            textures.hash,
+            <[u8; 32]>::from(Sha256::digest(&textures.bytes))
+        );
+        // Zipping the same directory again yields the same hash.
+        assert_eq!(zip::zip_directory(&unpacked).unwrap(), textures.bytes,);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn encrypted_archive(uuid: &str, key: &str) -> Vec<u8> {
+        let index = serde_json::json!({"content": [{"path": "manifest.json"}]});
+        let mut writer = zip::ZipWriter::default();
+        writer.add(
+            "manifest.json",
+            MANIFEST
+                .replace("11111111-2222-3333-4444-555555555555", uuid)
+                .as_bytes(),
+        );
+        writer.add(
+            "contents.json",
+            &encryption::encrypt_contents("content-id", key, &index),
+        );
+        writer.finish()
+    }
+
+    #[test]
+    fn load_encrypted_archives() {
+        const KEY_A: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        const KEY_B: &str = "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+        let dir = std::env::temp_dir().join("mc_rs_encrypted_packs_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // Key from a sidecar file
+        fs::write(dir.join("a.zip"), encrypted_archive("aaaa", KEY_A)).unwrap();
+        fs::write(dir.join("a.zip.key"), format!("{KEY_A}\n")).unwrap();
+        // Key from pack_keys.json
+        fs::write(dir.join("b.mcpack"), encrypted_archive("bbbb", KEY_B)).unwrap();
+        // Wrong key and missing key are both skipped
+        fs::write(dir.join("c.zip"), encrypted_archive("cccc", KEY_A)).unwrap();
+        fs::write(dir.join("d.zip"), encrypted_archive("dddd", KEY_A)).unwrap();
+        fs::write(
+            dir.join("pack_keys.json"),
+            format!(r#"{{"BBBB": "{KEY_B}", "cccc": "{KEY_B}"}}"#),
+        )
+        .unwrap();
+
+        let store = PackStore::load(&dir, &[]);
+        assert_eq!(store.packs().len(), 2);
+        let a = store.find("aaaa").unwrap();
+        assert_eq!(a.content_key, KEY_A);
+        assert_eq!(a.content_id, "content-id");
+        assert_eq!(store.find("bbbb").unwrap().content_key, KEY_B);
+        assert!(store.find("cccc").is_none());
+        assert!(store.find("dddd").is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn chunking() {
+        let manifest = BehaviorPackManifest::parse(MANIFEST).unwrap();
+        let pack = HostedPack::new(
+            &manifest,
+            PackKind::Resources,
+            vec![7; PACK_CHUNK_SIZE as usize + 10],
+        );
+        assert_eq!(pack.chunk_count(), 2);
+        assert_eq!(pack.chunk(0).unwrap().1.len(), PACK_CHUNK_SIZE as usize);
+        let (offset, last) = pack.chunk(1).unwrap();
+        assert_eq!(offset, PACK_CHUNK_SIZE as u64);
+        assert_eq!(last.len(), 10);
+        assert!(pack.chunk(2).is_none());
+    }
+
+    #[test]
+    fn required_and_optional_packs() {
+        let manifest = BehaviorPackManifest::parse(MANIFEST).unwrap();
+        let pack = HostedPack::new(&manifest, PackKind::Resources, Vec::new());
+        let mut config = PacksSection::default();
+        assert!(!pack.is_required(&config));
+        config.required = vec!["textures".into()];
+        assert!(pack.is_required(&config));
+
+        config.required.clear();
+        config.force_packs = true;
+        assert!(pack.is_required(&config));
+        config.optional = vec!["11111111-2222-3333-4444-555555555555".into()];
+        assert!(!pack.is_required(&config));
+    }
+
+    #[test]
+    fn missing_directory_is_empty() {
+        let store = PackStore::load(Path::new("/nonexistent/mc_rs_packs"), &[]);
+        assert!(store.is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+//! Optional packs players turned off with `/packs toggle`, kept per XUID
+//! (the identity UUID in offline mode) in `pack_preferences.json` inside the
+//! world directory.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tracing::warn;
+
+const PREFERENCES_FILE: &str = "pack_preferences.json";
+
+/// The optional packs each player turned off. A default one is kept in
+/// memory only.
+#[derive(Debug, Default)]
+pub struct PackPreferences {
+    /// Pack UUIDs turned off, by account ID.
+    disabled: BTreeMap<String, BTreeSet<String>>,
+    path: Option<PathBuf>,
+}
+
+impl PackPreferences {
+    /// Load `pack_preferences.json` from the world directory. A missing file
+    /// means every pack is on.
+    pub fn load(world_dir: &Path) -> Self {
+        let path = world_dir.join(PREFERENCES_FILE);
+        let disabled = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Failed to parse {}: {e}", path.display());
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        Self {
+            disabled,
+            path: Some(path),
+        }
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        match serde_json::to_string_pretty(&self.disabled) {
+            Ok(json) => {
+                if let Err(e) = fs::write(path, json) {
+                    warn!("Failed to save {}: {e}", path.display());
+                }
+            }
+            Err(e) => warn!("Failed to serialize pack preferences: {e}"),
+        }
+    }
+
+    /// Whether a player turned a pack off.
+    pub fn is_disabled(&self, account: &str, uuid: &str) -> bool {
+        self.disabled
+            .get(account)
+            .is_some_and(|packs| packs.contains(uuid))
+    }
+
+    /// Turn a pack off for a player, or back on, and save the file. Returns
+    /// whether it is now on.
+    pub fn toggle(&mut self, account: &str, uuid: &str) -> bool {
+        let packs = self.disabled.entry(account.to_string()).or_default();
+        let enabled = !packs.insert(uuid.to_string());
+        if enabled {
+            packs.remove(uuid);
+            if packs.is_empty() {
+                self.disabled.remove(account);
+            }
+        }
+        self.save();
+        enabled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggles_survive_a_reload() {
+        let dir = std::env::temp_dir().join("mc_rs_pack_preferences_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut prefs = PackPreferences::load(&dir);
+        assert!(!prefs.toggle("2535", "aaaa"));
+        assert!(!prefs.toggle("2535", "bbbb"));
+        assert!(prefs.toggle("2535", "bbbb"));
+
+        let prefs = PackPreferences::load(&dir);
+        assert!(prefs.is_disabled("2535", "aaaa"));
+        assert!(!prefs.is_disabled("2535", "bbbb"));
+        assert!(!prefs.is_disabled("other", "aaaa"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
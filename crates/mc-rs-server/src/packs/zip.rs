@@ -0,0 +1,287 @@
+//! Minimal ZIP archive support for pack hosting.
+//!
+//! Reads stored and deflated entries (enough to locate `manifest.json` and
+//! `contents.json` in a `.zip`/`.mcpack`), and writes uncompressed archives
+//! from pack directories so they can be transferred to clients.
+
+use std::io::Read;
+use std::path::Path;
+
+const LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const END_OF_CENTRAL_DIR_SIG: u32 = 0x0605_4b50;
+/// Size of the end-of-central-directory record without its comment.
+const END_OF_CENTRAL_DIR_LEN: usize = 22;
+/// 1980-01-01, the earliest DOS date; keeps written archives deterministic.
+const DOS_DATE_EPOCH: u16 = 0x21;
+
+/// An entry in the archive's central directory.
+#[derive(Debug, Clone)]
+pub struct ZipEntry {
+    pub name: String,
+    method: u16,
+    compressed_size: usize,
+    local_header_offset: usize,
+}
+
+/// Read-only view over an in-memory ZIP archive.
+pub struct ZipArchive<'a> {
+    data: &'a [u8],
+    entries: Vec<ZipEntry>,
+}
+
+impl<'a> ZipArchive<'a> {
+    /// Parse the central directory of `data`.
+    pub fn new(data: &'a [u8]) -> Result<Self, String> {
+        let eocd = find_end_of_central_dir(data).ok_or("not a zip archive")?;
+        let count = read_u16(data, eocd + 10)? as usize;
+        let mut offset = read_u32(data, eocd + 16)? as usize;
+
+        let mut entries = Vec::with_capacity(count);
+        for _ in 0..count {
+            if read_u32(data, offset)? != CENTRAL_HEADER_SIG {
+                return Err("corrupt central directory".into());
+            }
+            let method = read_u16(data, offset + 10)?;
+            let compressed_size = read_u32(data, offset + 20)? as usize;
+            let name_len = read_u16(data, offset + 28)? as usize;
+            let extra_len = read_u16(data, offset + 30)? as usize;
+            let comment_len = read_u16(data, offset + 32)? as usize;
+            let local_header_offset = read_u32(data, offset + 42)? as usize;
+            let name = data
+                .get(offset + 46..offset + 46 + name_len)
+                .ok_or("truncated entry name")?;
+            entries.push(ZipEntry {
+                name: String::from_utf8_lossy(name).replace('\\', "/"),
+                method,
+                compressed_size,
+                local_header_offset,
+            });
+            offset += 46 + name_len + extra_len + comment_len;
+        }
+        Ok(Self { data, entries })
+    }
+
+    /// Read and decompress the entry called `name`.
+    pub fn read(&self, name: &str) -> Result<Vec<u8>, String> {
+        let entry = self
+            .entries
+            .iter()
+            .find(|e| e.name == name)
+            .ok_or_else(|| format!("{name} not found in archive"))?;
+        let offset = entry.local_header_offset;
+        if read_u32(self.data, offset)? != LOCAL_HEADER_SIG {
+            return Err(format!("corrupt local header for {name}"));
+        }
+        let name_len = read_u16(self.data, offset + 26)? as usize;
+        let extra_len = read_u16(self.data, offset + 28)? as usize;
+        let start = offset + 30 + name_len + extra_len;
+        let raw = self
+            .data
+            .get(start..start + entry.compressed_size)
+            .ok_or_else(|| format!("truncated data for {name}"))?;
+        match entry.method {
+            0 => Ok(raw.to_vec()),
+            8 => {
+                let mut out = Vec::new();
+                flate2::read::DeflateDecoder::new(raw)
+                    .read_to_end(&mut out)
+                    .map_err(|e| format!("inflate {name}: {e}"))?;
+                Ok(out)
+            }
+            other => Err(format!("unsupported compression method {other} for {name}")),
+        }
+    }
+
+    /// Find the shallowest entry whose file name is `file_name` and return
+    /// the directory prefix it lives in (e.g. `""` or `"MyPack/"`).
+    pub fn find_root(&self, file_name: &str) -> Option<String> {
+        self.entries
+            .iter()
+            .filter_map(|e| {
+                let prefix = e.name.strip_suffix(file_name)?;
+                (prefix.is_empty() || prefix.ends_with('/')).then(|| prefix.to_string())
+            })
+            .min_by_key(|prefix| prefix.matches('/').count())
+    }
+}
+
+/// Build an uncompressed archive from every file under `dir`.
+///
+/// Entries are sorted and carry a fixed timestamp so the same directory
+/// always produces the same bytes (and therefore the same pack hash).
+pub fn zip_directory(dir: &Path) -> std::io::Result<Vec<u8>> {
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files)?;
+    files.sort();
+
+    let mut writer = ZipWriter::default();
+    for name in files {
+        let data = std::fs::read(dir.join(&name))?;
+        writer.add(&name, &data);
+    }
+    Ok(writer.finish())
+}
+
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            out.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+/// Writes stored (uncompressed) ZIP entries.
+#[derive(Default)]
+pub struct ZipWriter {
+    data: Vec<u8>,
+    central: Vec<u8>,
+    count: u16,
+}
+
+impl ZipWriter {
+    pub fn add(&mut self, name: &str, contents: &[u8]) {
+        let mut crc = flate2::Crc::new();
+        crc.update(contents);
+        let crc = crc.sum();
+        let offset = self.data.len() as u32;
+
+        // Local file header
+        self.data.extend_from_slice(&LOCAL_HEADER_SIG.to_le_bytes());
+        self.write_common_header(true, name, crc, contents.len() as u32);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(contents);
+
+        // Central directory header
+        self.central
+            .extend_from_slice(&CENTRAL_HEADER_SIG.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        self.write_common_header(false, name, crc, contents.len() as u32);
+        self.central.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        self.central.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        self.central.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name.as_bytes());
+        self.count += 1;
+    }
+
+    /// Fields shared by the local and central headers, from "version needed"
+    /// through "extra field length".
+    fn write_common_header(&mut self, local: bool, name: &str, crc: u32, size: u32) {
+        let out = if local {
+            &mut self.data
+        } else {
+            &mut self.central
+        };
+        out.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        out.extend_from_slice(&0u16.to_le_bytes()); // flags
+        out.extend_from_slice(&0u16.to_le_bytes()); // method: stored
+        out.extend_from_slice(&0u16.to_le_bytes()); // time
+        out.extend_from_slice(&DOS_DATE_EPOCH.to_le_bytes());
+        out.extend_from_slice(&crc.to_le_bytes());
+        out.extend_from_slice(&size.to_le_bytes()); // compressed
+        out.extend_from_slice(&size.to_le_bytes()); // uncompressed
+        out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        out.extend_from_slice(&0u16.to_le_bytes()); // extra length
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.data.len() as u32;
+        let central_len = self.central.len() as u32;
+        self.data.append(&mut self.central);
+        self.data
+            .extend_from_slice(&END_OF_CENTRAL_DIR_SIG.to_le_bytes());
+        self.data.extend_from_slice(&[0; 4]); // disk numbers
+        self.data.extend_from_slice(&self.count.to_le_bytes());
+        self.data.extend_from_slice(&self.count.to_le_bytes());
+        self.data.extend_from_slice(&central_len.to_le_bytes());
+        self.data.extend_from_slice(&central_offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.data
+    }
+}
+
+fn find_end_of_central_dir(data: &[u8]) -> Option<usize> {
+    if data.len() < END_OF_CENTRAL_DIR_LEN {
+        return None;
+    }
+    // The record is followed by a comment of at most 64 KiB.
+    let earliest = data
+        .len()
+        .saturating_sub(END_OF_CENTRAL_DIR_LEN + u16::MAX as usize);
+    (earliest..=data.len() - END_OF_CENTRAL_DIR_LEN)
+        .rev()
+        .find(|&i| data[i..i + 4] == END_OF_CENTRAL_DIR_SIG.to_le_bytes())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| "unexpected end of archive".into())
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| "unexpected end of archive".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn stored_roundtrip() {
+        let mut writer = ZipWriter::default();
+        writer.add("manifest.json", b"{}");
+        writer.add("textures/a.png", &[1, 2, 3]);
+        let bytes = writer.finish();
+
+        let archive = ZipArchive::new(&bytes).unwrap();
+        assert_eq!(archive.entries.len(), 2);
+        assert_eq!(archive.read("manifest.json").unwrap(), b"{}");
+        assert_eq!(archive.read("textures/a.png").unwrap(), vec![1, 2, 3]);
+        assert!(archive.read("missing").is_err());
+    }
+
+    #[test]
+    fn reads_deflated_entry() {
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"hello hello hello").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        // Build a stored archive, then patch the entry to method 8.
+        let mut writer = ZipWriter::default();
+        writer.add("a.txt", &compressed);
+        let mut bytes = writer.finish();
+        bytes[8] = 8;
+        let central = bytes.len() - END_OF_CENTRAL_DIR_LEN - 46 - "a.txt".len();
+        bytes[central + 10] = 8;
+
+        let archive = ZipArchive::new(&bytes).unwrap();
+        assert_eq!(archive.read("a.txt").unwrap(), b"hello hello hello");
+    }
+
+    #[test]
+    fn find_root_prefers_shallowest() {
+        let mut writer = ZipWriter::default();
+        writer.add("Pack/sub/manifest.json", b"");
+        writer.add("Pack/manifest.json", b"");
+        let bytes = writer.finish();
+        let archive = ZipArchive::new(&bytes).unwrap();
+        assert_eq!(archive.find_root("manifest.json").as_deref(), Some("Pack/"));
+        assert_eq!(archive.find_root("contents.json"), None);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(ZipArchive::new(b"definitely not a zip file").is_err());
+    }
+}
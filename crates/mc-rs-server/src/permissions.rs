@@ -1,11 +1,12 @@
-//! Permission management: ops, whitelist, bans.
+//! Permission management: ops, whitelist, bans, mutes and permission nodes.
 //!
 //! Persists data as JSON files in the current working directory.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
@@ -15,16 +16,26 @@ pub struct BanEntry {
     pub reason: String,
 }
 
+/// A mute, until a Unix time in seconds or for good.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MuteEntry {
+    pub until: Option<i64>,
+}
+
 /// Manages operators, whitelist, and ban lists with JSON persistence.
 pub struct PermissionManager {
-    /// Display names of operators.
-    pub ops: HashSet<String>,
+    /// Operators: display_name → op level (1-4).
+    pub ops: HashMap<String, u8>,
     /// Display names of whitelisted players.
     pub whitelist: HashSet<String>,
     /// Banned players: display_name → ban entry.
     pub banned_players: HashMap<String, BanEntry>,
     /// Banned IPs: IP string → ban entry.
     pub banned_ips: HashMap<String, BanEntry>,
+    /// Muted players: display_name → mute entry.
+    pub muted_players: HashMap<String, MuteEntry>,
+    /// Permission nodes granted to players: display_name → nodes.
+    pub nodes: HashMap<String, BTreeSet<String>>,
     /// Whether the whitelist is currently enforced (runtime toggle).
     pub whitelist_enabled: bool,
 }
@@ -33,22 +44,58 @@ const OPS_FILE: &str = "ops.json";
 const WHITELIST_FILE: &str = "whitelist.json";
 const BANNED_PLAYERS_FILE: &str = "banned-players.json";
 const BANNED_IPS_FILE: &str = "banned-ips.json";
+const MUTED_PLAYERS_FILE: &str = "muted-players.json";
+const PERMISSIONS_FILE: &str = "permissions.json";
+
+/// Highest op level, and the one `/op` grants by default.
+pub const MAX_OP_LEVEL: u8 = 4;
+
+/// Permission node exempting a player from the AFK kick.
+pub const AFK_EXEMPT: &str = "mcrs.afk.exempt";
+
+/// Permission node skipping the teleport warm-up and cooldown.
+pub const TELEPORT_BYPASS: &str = "mcrs.teleport.bypass";
+
+/// Permission node exempting a player from chat slow mode.
+pub const CHAT_BYPASS: &str = "mcrs.chat.bypass";
 
 impl PermissionManager {
     /// Load all permission data from JSON files. Creates empty defaults if files don't exist.
     pub fn load(whitelist_enabled: bool) -> Self {
         Self {
-            ops: load_set(OPS_FILE),
+            ops: load_ops(OPS_FILE),
             whitelist: load_set(WHITELIST_FILE),
             banned_players: load_map(BANNED_PLAYERS_FILE),
             banned_ips: load_map(BANNED_IPS_FILE),
+            muted_players: load_map(MUTED_PLAYERS_FILE),
+            nodes: load_map(PERMISSIONS_FILE),
             whitelist_enabled,
         }
     }
 
     /// Save the ops list to disk.
     pub fn save_ops(&self) {
-        save_set(OPS_FILE, &self.ops);
+        save_map(OPS_FILE, &self.ops);
+    }
+
+    /// Op level of a player, 0 if they aren't an operator.
+    pub fn op_level(&self, name: &str) -> u8 {
+        self.ops.get(name).copied().unwrap_or(0)
+    }
+
+    /// Whether a player is an operator of any level.
+    pub fn is_op(&self, name: &str) -> bool {
+        self.ops.contains_key(name)
+    }
+
+    /// `(permission_level, command_permission_level)` to send a player in
+    /// `UpdateAbilities` and `AddPlayer`: operators get the operator player
+    /// permission and a command permission matching their op level.
+    pub fn ability_levels(&self, name: &str) -> (u8, u8) {
+        match self.op_level(name) {
+            0 => (1, 0),
+            level => (2, level),
+        }
     }
 
     /// Save the whitelist to disk.
@@ -65,6 +112,33 @@ impl PermissionManager {
     pub fn save_banned_ips(&self) {
         save_map(BANNED_IPS_FILE, &self.banned_ips);
     }
+
+    /// Save the muted players list to disk, dropping the expired mutes.
+    pub fn save_muted_players(&mut self, now: i64) {
+        self.muted_players
+            .retain(|_, m| m.until.is_none_or(|until| until > now));
+        save_map(MUTED_PLAYERS_FILE, &self.muted_players);
+    }
+
+    /// The mute of a player still running at Unix time `now`, if any.
+    pub fn mute(&self, name: &str, now: i64) -> Option<&MuteEntry> {
+        self.muted_players
+            .get(name)
+            .filter(|m| m.until.is_none_or(|until| until > now))
+    }
+
+    /// Save the granted permission nodes to disk.
+    pub fn save_nodes(&self) {
+        save_map(PERMISSIONS_FILE, &self.nodes);
+    }
+
+    /// Whether a player was granted a permission node, directly or through
+    /// the `*` node.
+    pub fn has_node(&self, name: &str, node: &str) -> bool {
+        self.nodes
+            .get(name)
+            .is_some_and(|nodes| nodes.contains(node) || nodes.contains("*"))
+    }
 }
 
 /// Load a HashSet<String> from a JSON array file.
@@ -90,15 +164,33 @@ fn load_set(path: &str) -> HashSet<String> {
     }
 }
 
-/// Load a HashMap<String, BanEntry> from a JSON object file.
-fn load_map(path: &str) -> HashMap<String, BanEntry> {
+/// Load the ops file: an object of name → op level, or a plain array of
+/// names from before op levels, whose ops get the highest level.
+fn load_ops(path: &str) -> HashMap<String, u8> {
+    let is_array =
+        fs::read_to_string(path).is_ok_and(|contents| contents.trim_start().starts_with('['));
+    let ops: HashMap<String, u8> = if is_array {
+        load_set(path)
+            .into_iter()
+            .map(|name| (name, MAX_OP_LEVEL))
+            .collect()
+    } else {
+        load_map(path)
+    };
+    ops.into_iter()
+        .map(|(name, level)| (name, level.clamp(1, MAX_OP_LEVEL)))
+        .collect()
+}
+
+/// Load a HashMap<String, T> from a JSON object file.
+fn load_map<T: DeserializeOwned>(path: &str) -> HashMap<String, T> {
     if !Path::new(path).exists() {
         return HashMap::new();
     }
     match fs::read_to_string(path) {
         Ok(contents) => match serde_json::from_str(&contents) {
             Ok(map) => {
-                let map: HashMap<String, BanEntry> = map;
+                let map: HashMap<String, T> = map;
                 info!("Loaded {} entries from {path}", map.len());
                 map
             }
@@ -128,8 +220,8 @@ fn save_set(path: &str, set: &HashSet<String>) {
     }
 }
 
-/// Save a HashMap<String, BanEntry> as a JSON object.
-fn save_map(path: &str, map: &HashMap<String, BanEntry>) {
+/// Save a HashMap<String, T> as a JSON object.
+fn save_map<T: Serialize>(path: &str, map: &HashMap<String, T>) {
     match serde_json::to_string_pretty(map) {
         Ok(json) => {
             if let Err(e) = fs::write(path, json) {
@@ -177,14 +269,27 @@ mod tests {
     fn save_and_reload_ops() {
         in_temp_dir(|| {
             let mut pm = PermissionManager::load(false);
-            pm.ops.insert("Steve".into());
-            pm.ops.insert("Alex".into());
+            pm.ops.insert("Steve".into(), 4);
+            pm.ops.insert("Alex".into(), 2);
             pm.save_ops();
 
             let pm2 = PermissionManager::load(false);
             assert_eq!(pm2.ops.len(), 2);
-            assert!(pm2.ops.contains("Steve"));
-            assert!(pm2.ops.contains("Alex"));
+            assert_eq!(pm2.op_level("Steve"), 4);
+            assert_eq!(pm2.op_level("Alex"), 2);
+            assert_eq!(pm2.op_level("Bob"), 0);
+            assert_eq!(pm2.ability_levels("Alex"), (2, 2));
+            assert_eq!(pm2.ability_levels("Bob"), (1, 0));
+        });
+    }
+
+    #[test]
+    fn legacy_ops_list_gets_max_level() {
+        in_temp_dir(|| {
+            fs::write(OPS_FILE, r#"["Steve"]"#).unwrap();
+            let pm = PermissionManager::load(false);
+            assert!(pm.is_op("Steve"));
+            assert_eq!(pm.op_level("Steve"), MAX_OP_LEVEL);
         });
     }
 
@@ -227,4 +332,47 @@ mod tests {
             assert_eq!(pm2.banned_ips["10.0.0.1"].reason, "Spam");
         });
     }
+
+    #[test]
+    fn mutes_expire() {
+        in_temp_dir(|| {
+            let mut pm = PermissionManager::load(false);
+            pm.muted_players
+                .insert("Steve".into(), MuteEntry { until: None });
+            pm.muted_players
+                .insert("Alex".into(), MuteEntry { until: Some(100) });
+            pm.muted_players
+                .insert("Bob".into(), MuteEntry { until: Some(10) });
+            pm.save_muted_players(50);
+
+            let pm2 = PermissionManager::load(false);
+            assert_eq!(pm2.muted_players.len(), 2);
+            assert!(pm2.mute("Steve", 1000).is_some());
+            assert!(pm2.mute("Alex", 99).is_some());
+            assert!(pm2.mute("Alex", 100).is_none());
+            assert!(pm2.mute("Bob", 0).is_none());
+        });
+    }
+
+    #[test]
+    fn save_and_reload_nodes() {
+        in_temp_dir(|| {
+            let mut pm = PermissionManager::load(false);
+            pm.nodes
+                .entry("Steve".into())
+                .or_default()
+                .insert(AFK_EXEMPT.into());
+            pm.nodes
+                .entry("Alex".into())
+                .or_default()
+                .insert("*".into());
+            pm.save_nodes();
+
+            let pm2 = PermissionManager::load(false);
+            assert!(pm2.has_node("Steve", AFK_EXEMPT));
+            assert!(!pm2.has_node("Steve", "mcrs.other"));
+            assert!(pm2.has_node("Alex", "mcrs.other"));
+            assert!(!pm2.has_node("Bob", AFK_EXEMPT));
+        });
+    }
 }
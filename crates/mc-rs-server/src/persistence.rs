@@ -1,17 +1,24 @@
 //! World metadata (level.dat) and player data persistence.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::Cursor;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use bytes::BufMut;
 use mc_rs_nbt::{NbtRoot, NbtTag};
 use mc_rs_proto::item_stack::ItemStack;
+use mc_rs_proto::jwt::{ClientData, SkinImage};
 use serde::{Deserialize, Serialize};
 use tracing::warn;
 
+use mc_rs_game::components::{Experience, Hunger, StatusEffects};
+use mc_rs_game::game_world::GameWorld;
+
 use crate::connection::{ActiveEffect, PlayerConnection};
+use crate::stats::PlayerStats;
 
 // ─── level.dat ──────────────────────────────────────────────────────────────
 
@@ -37,6 +44,8 @@ pub struct LevelDat {
     pub rain_time: i32,
     /// Remaining thunder duration in ticks.
     pub lightning_time: i32,
+    /// Times the world has been opened; keeps saved actor IDs unique.
+    pub world_start_count: i64,
 }
 
 impl LevelDat {
@@ -63,6 +72,7 @@ impl LevelDat {
             lightning_level: 0.0,
             rain_time: 0,
             lightning_time: 0,
+            world_start_count: 0,
         }
     }
 
@@ -111,6 +121,10 @@ impl LevelDat {
                 .unwrap_or(0.0),
             rain_time: c.get("rainTime").and_then(|t| t.as_int()).unwrap_or(0),
             lightning_time: c.get("LightningTime").and_then(|t| t.as_int()).unwrap_or(0),
+            world_start_count: c
+                .get("worldStartCount")
+                .and_then(|t| t.as_long())
+                .unwrap_or(0),
         })
     }
 
@@ -140,6 +154,10 @@ impl LevelDat {
         compound.insert("lightningLevel".into(), NbtTag::Float(self.lightning_level));
         compound.insert("rainTime".into(), NbtTag::Int(self.rain_time));
         compound.insert("LightningTime".into(), NbtTag::Int(self.lightning_time));
+        compound.insert(
+            "worldStartCount".into(),
+            NbtTag::Long(self.world_start_count),
+        );
 
         let root = NbtRoot::new("", compound);
 
@@ -164,7 +182,8 @@ impl LevelDat {
     }
 }
 
-fn unix_timestamp() -> i64 {
+/// Current unix time in seconds.
+pub fn unix_timestamp() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -198,6 +217,36 @@ pub struct PlayerData {
     pub tags: Vec<String>,
     #[serde(default)]
     pub dimension: i32,
+    /// Last claim time (unix seconds) per kit name.
+    #[serde(default)]
+    pub kit_cooldowns: HashMap<String, i64>,
+    #[serde(default)]
+    pub stats: PlayerStats,
+    /// Skin the player changed to in-game, restored when they rejoin.
+    #[serde(default)]
+    pub skin: Option<SerializedSkin>,
+    /// Homes set with `/sethome`, by name.
+    #[serde(default)]
+    pub homes: HashMap<String, SavedLocation>,
+    /// Where `/back` returns to: the last teleport origin or death point.
+    #[serde(default)]
+    pub back_location: Option<SavedLocation>,
+    /// Where the player left each other world, by world name.
+    #[serde(default)]
+    pub world_positions: HashMap<String, SavedLocation>,
+    /// View distance set with `/viewdistance`.
+    #[serde(default)]
+    pub view_distance: Option<i32>,
+    /// Players whose private messages are ignored, set with `/ignore`.
+    #[serde(default)]
+    pub ignored: HashSet<String>,
+}
+
+/// A position in a given dimension, used for homes and `/back`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedLocation {
+    pub dimension: i32,
+    pub position: [f32; 3],
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -226,6 +275,82 @@ pub struct SerializedEffect {
     pub remaining_ticks: i32,
 }
 
+/// A skin, with its images base64-encoded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedSkin {
+    pub skin_id: String,
+    pub play_fab_id: String,
+    pub resource_patch: String,
+    pub geometry_data: String,
+    pub skin_color: String,
+    pub arm_size: String,
+    pub persona: bool,
+    pub image: SerializedSkinImage,
+    pub cape_id: String,
+    pub cape_image: SerializedSkinImage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializedSkinImage {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA pixels, base64-encoded.
+    pub data: String,
+}
+
+impl SerializedSkinImage {
+    fn from_image(image: &SkinImage) -> Self {
+        Self {
+            width: image.width,
+            height: image.height,
+            data: STANDARD.encode(&image.data),
+        }
+    }
+
+    fn to_image(&self) -> Option<SkinImage> {
+        Some(SkinImage {
+            width: self.width,
+            height: self.height,
+            data: STANDARD.decode(&self.data).ok()?,
+        })
+    }
+}
+
+impl SerializedSkin {
+    pub fn from_client_data(cd: &ClientData) -> Self {
+        Self {
+            skin_id: cd.skin_id.clone(),
+            play_fab_id: cd.play_fab_id.clone(),
+            resource_patch: cd.skin_resource_patch.clone(),
+            geometry_data: cd.skin_geometry_data.clone(),
+            skin_color: cd.skin_color.clone(),
+            arm_size: cd.arm_size.clone(),
+            persona: cd.persona_skin,
+            image: SerializedSkinImage::from_image(&cd.skin_image),
+            cape_id: cd.cape_id.clone(),
+            cape_image: SerializedSkinImage::from_image(&cd.cape_image),
+        }
+    }
+
+    /// The client data with this skin in place of its own. Device info is
+    /// kept. `None` if an image is not valid base64.
+    pub fn apply_to(&self, cd: &ClientData) -> Option<ClientData> {
+        Some(ClientData {
+            skin_id: self.skin_id.clone(),
+            skin_image: self.image.to_image()?,
+            cape_id: self.cape_id.clone(),
+            cape_image: self.cape_image.to_image()?,
+            skin_resource_patch: self.resource_patch.clone(),
+            skin_geometry_data: self.geometry_data.clone(),
+            skin_color: self.skin_color.clone(),
+            arm_size: self.arm_size.clone(),
+            persona_skin: self.persona,
+            play_fab_id: self.play_fab_id.clone(),
+            ..cd.clone()
+        })
+    }
+}
+
 impl SerializedItem {
     fn from_item_stack(item: &ItemStack) -> Self {
         Self {
@@ -267,18 +392,22 @@ impl SerializedItem {
 }
 
 impl PlayerData {
-    /// Extract persistent state from a PlayerConnection.
-    pub fn from_connection(conn: &PlayerConnection) -> Self {
+    /// Extract persistent state from a PlayerConnection and the player's
+    /// entity in `world`.
+    pub fn from_connection(conn: &PlayerConnection, world: &GameWorld) -> Self {
+        let uid = conn.entity_unique_id;
+        let hunger = world.player::<Hunger>(uid).copied().unwrap_or_default();
+        let xp = world.player::<Experience>(uid).copied().unwrap_or_default();
         Self {
             position: [conn.position.x, conn.position.y, conn.position.z],
             pitch: conn.pitch,
             yaw: conn.yaw,
             head_yaw: conn.head_yaw,
             gamemode: conn.gamemode,
-            health: conn.health,
-            food: conn.food,
-            saturation: conn.saturation,
-            exhaustion: conn.exhaustion,
+            health: world.player_health(uid),
+            food: hunger.food,
+            saturation: hunger.saturation,
+            exhaustion: hunger.exhaustion,
             fire_ticks: conn.fire_ticks,
             air_ticks: conn.air_ticks,
             fall_distance: conn.fall_distance,
@@ -298,8 +427,8 @@ impl PlayerData {
                 offhand: SerializedItem::from_item_stack(&conn.inventory.offhand),
                 held_slot: conn.inventory.held_slot,
             },
-            effects: conn
-                .effects
+            effects: world
+                .player_effects(uid)
                 .iter()
                 .map(|e| SerializedEffect {
                     effect_id: e.effect_id,
@@ -307,15 +436,29 @@ impl PlayerData {
                     remaining_ticks: e.remaining_ticks,
                 })
                 .collect(),
-            xp_level: conn.xp_level,
-            xp_total: conn.xp_total,
+            xp_level: xp.level,
+            xp_total: xp.total,
             tags: conn.tags.iter().cloned().collect(),
             dimension: conn.dimension,
+            kit_cooldowns: conn.kit_cooldowns.clone(),
+            stats: conn.stats.clone(),
+            skin: conn
+                .client_data
+                .as_ref()
+                .filter(|_| conn.custom_skin)
+                .map(SerializedSkin::from_client_data),
+            homes: conn.homes.clone(),
+            back_location: conn.back_location,
+            world_positions: conn.world_positions.clone(),
+            view_distance: conn.view_distance,
+            ignored: conn.ignored.clone(),
         }
     }
 
-    /// Apply loaded data to a PlayerConnection, overwriting defaults.
-    pub fn apply_to_connection(&self, conn: &mut PlayerConnection) {
+    /// Apply loaded data to a PlayerConnection and the player's entity in
+    /// `world`, overwriting defaults.
+    pub fn apply_to_connection(&self, conn: &mut PlayerConnection, world: &mut GameWorld) {
+        let uid = conn.entity_unique_id;
         conn.position.x = self.position[0];
         conn.position.y = self.position[1];
         conn.position.z = self.position[2];
@@ -323,10 +466,14 @@ impl PlayerData {
         conn.yaw = self.yaw;
         conn.head_yaw = self.head_yaw;
         conn.gamemode = self.gamemode;
-        conn.health = self.health;
-        conn.food = self.food;
-        conn.saturation = self.saturation;
-        conn.exhaustion = self.exhaustion;
+        world.set_player_health(uid, self.health);
+        if let Some(mut hunger) = world.player_mut::<Hunger>(uid) {
+            *hunger = Hunger {
+                food: self.food,
+                saturation: self.saturation,
+                exhaustion: self.exhaustion,
+            };
+        }
         conn.fire_ticks = self.fire_ticks;
         conn.air_ticks = self.air_ticks;
         conn.fall_distance = self.fall_distance;
@@ -346,11 +493,15 @@ impl PlayerData {
         conn.inventory.held_slot = self.inventory.held_slot;
 
         // Restore XP
-        conn.xp_level = self.xp_level;
-        conn.xp_total = self.xp_total;
+        if let Some(mut xp) = world.player_mut::<Experience>(uid) {
+            *xp = Experience {
+                level: self.xp_level,
+                total: self.xp_total,
+            };
+        }
 
         // Restore effects
-        conn.effects = self
+        let effects = self
             .effects
             .iter()
             .map(|e| ActiveEffect {
@@ -359,27 +510,57 @@ impl PlayerData {
                 remaining_ticks: e.remaining_ticks,
             })
             .collect();
+        if let Some(mut status) = world.player_mut::<StatusEffects>(uid) {
+            status.0 = effects;
+        }
 
         // Restore tags
         conn.tags = self.tags.iter().cloned().collect();
 
         // Restore dimension
         conn.dimension = self.dimension;
-    }
 
-    /// Load player data from a JSON file.
-    pub fn load(world_dir: &Path, uuid: &str) -> Option<Self> {
-        let path = world_dir.join("players").join(format!("{uuid}.json"));
-        let data = std::fs::read_to_string(&path).ok()?;
-        match serde_json::from_str(&data) {
-            Ok(player) => Some(player),
-            Err(e) => {
-                warn!("Failed to parse player data for {uuid}: {e}");
-                None
+        // Restore kit cooldowns
+        conn.kit_cooldowns = self.kit_cooldowns.clone();
+
+        // Restore statistics
+        conn.stats = self.stats.clone();
+
+        // Restore homes and /back
+        conn.homes = self.homes.clone();
+        conn.back_location = self.back_location;
+        conn.world_positions = self.world_positions.clone();
+        conn.view_distance = self.view_distance;
+        conn.ignored = self.ignored.clone();
+
+        // Restore a skin changed in-game, unless it no longer passes validation
+        let restored = match (&self.skin, &conn.client_data) {
+            (Some(skin), Some(cd)) => skin.apply_to(cd),
+            _ => None,
+        };
+        match restored {
+            Some(cd) if mc_rs_game::skin::validate_skin(&cd).is_ok() => {
+                conn.client_data = Some(cd);
+                conn.custom_skin = true;
             }
+            Some(_) => warn!("Ignoring saved skin that fails validation"),
+            None => {}
         }
     }
 
+    /// Load player data from a JSON file, `None` if the player has none.
+    pub fn load(world_dir: &Path, uuid: &str) -> std::io::Result<Option<Self>> {
+        let path = world_dir.join("players").join(format!("{uuid}.json"));
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_str(&data)
+            .map(Some)
+            .map_err(std::io::Error::other)
+    }
+
     /// Save player data to a JSON file.
     pub fn save(&self, world_dir: &Path, uuid: &str) -> std::io::Result<()> {
         let dir = world_dir.join("players");
@@ -421,6 +602,7 @@ mod tests {
         assert_eq!(loaded.lightning_level, 0.0);
         assert_eq!(loaded.rain_time, 0);
         assert_eq!(loaded.lightning_time, 0);
+        assert_eq!(loaded.world_start_count, 0);
 
         std::fs::remove_dir_all(&dir).ok();
     }
@@ -435,6 +617,7 @@ mod tests {
         dat.lightning_level = 0.5;
         dat.rain_time = 6000;
         dat.lightning_time = 3000;
+        dat.world_start_count = 7;
         dat.save(&path).unwrap();
 
         let loaded = LevelDat::load(&path).unwrap();
@@ -442,6 +625,7 @@ mod tests {
         assert!((loaded.lightning_level - 0.5).abs() < 0.001);
         assert_eq!(loaded.rain_time, 6000);
         assert_eq!(loaded.lightning_time, 3000);
+        assert_eq!(loaded.world_start_count, 7);
 
         std::fs::remove_dir_all(&dir).ok();
     }
@@ -519,11 +703,47 @@ mod tests {
             xp_total: 160,
             tags: vec!["vip".into(), "builder".into()],
             dimension: 0,
+            kit_cooldowns: HashMap::from([("food".to_string(), 1_700_000_000)]),
+            stats: PlayerStats {
+                blocks_mined: HashMap::from([("minecraft:stone".to_string(), 12)]),
+                deaths: 1,
+                playtime_ticks: 7200,
+                ..Default::default()
+            },
+            skin: Some(SerializedSkin::from_client_data(&ClientData {
+                skin_id: "custom".into(),
+                skin_image: SkinImage {
+                    width: 64,
+                    height: 32,
+                    data: vec![7; 64 * 32 * 4],
+                },
+                ..ClientData::default()
+            })),
+            homes: HashMap::from([(
+                "base".to_string(),
+                SavedLocation {
+                    dimension: 1,
+                    position: [100.5, 70.0, -8.5],
+                },
+            )]),
+            back_location: Some(SavedLocation {
+                dimension: 0,
+                position: [1.0, 64.0, 2.0],
+            }),
+            world_positions: HashMap::from([(
+                "lobby".to_string(),
+                SavedLocation {
+                    dimension: 3,
+                    position: [0.5, 6.62, 0.5],
+                },
+            )]),
+            view_distance: Some(12),
+            ignored: HashSet::from(["Griefer".to_string()]),
         };
 
         data.save(&dir, "test-uuid-1234").unwrap();
 
-        let loaded = PlayerData::load(&dir, "test-uuid-1234").unwrap();
+        let loaded = PlayerData::load(&dir, "test-uuid-1234").unwrap().unwrap();
         assert_eq!(loaded.position, [10.5, 65.62, -20.3]);
         assert_eq!(loaded.health, 18.5);
         assert_eq!(loaded.food, 15);
@@ -536,6 +756,27 @@ mod tests {
         assert_eq!(loaded.effects[0].remaining_ticks, 600);
         assert_eq!(loaded.xp_level, 5);
         assert_eq!(loaded.xp_total, 160);
+        assert_eq!(loaded.kit_cooldowns.get("food"), Some(&1_700_000_000));
+        assert_eq!(loaded.stats.blocks_mined.get("minecraft:stone"), Some(&12));
+        assert_eq!(loaded.stats.deaths, 1);
+        assert_eq!(loaded.stats.playtime_ticks, 7200);
+        assert_eq!(loaded.homes["base"].dimension, 1);
+        assert_eq!(loaded.homes["base"].position, [100.5, 70.0, -8.5]);
+        assert_eq!(
+            loaded.back_location.map(|l| l.position),
+            Some([1.0, 64.0, 2.0])
+        );
+        assert_eq!(loaded.world_positions["lobby"].dimension, 3);
+        assert_eq!(loaded.view_distance, Some(12));
+        assert!(loaded.ignored.contains("Griefer"));
+        let skin = loaded
+            .skin
+            .unwrap()
+            .apply_to(&ClientData::default())
+            .unwrap();
+        assert_eq!(skin.skin_id, "custom");
+        assert_eq!(skin.skin_image.width, 64);
+        assert_eq!(skin.skin_image.data, vec![7; 64 * 32 * 4]);
 
         std::fs::remove_dir_all(&dir).ok();
     }
@@ -543,7 +784,19 @@ mod tests {
     #[test]
     fn player_data_missing_returns_none() {
         let dir = temp_dir();
-        assert!(PlayerData::load(&dir, "nonexistent-uuid").is_none());
+        assert!(PlayerData::load(&dir, "nonexistent-uuid")
+            .unwrap()
+            .is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn player_data_corrupt_is_an_error() {
+        let dir = temp_dir();
+        std::fs::create_dir_all(dir.join("players")).unwrap();
+        std::fs::write(dir.join("players").join("broken.json"), "{\"position\":").unwrap();
+        // Not "no data": the player must not start over and overwrite it
+        assert!(PlayerData::load(&dir, "broken").is_err());
         std::fs::remove_dir_all(&dir).ok();
     }
 
@@ -0,0 +1,452 @@
+//! Player data storage backends.
+//!
+//! By default player data is kept as JSON files in the world's `players/`
+//! directory. For multi-server networks (players moving between servers with
+//! the Transfer packet) a shared Redis store can be used instead, so that
+//! inventories, XP and effects follow the player.
+//!
+//! Redis entries are keyed by XUID (falling back to the identity UUID in
+//! offline mode) and carry a version number. Saves use optimistic locking
+//! (`WATCH`/`MULTI`/`EXEC`): if another server wrote the entry after we loaded
+//! it, our save is rejected instead of clobbering the newer data.
+//!
+//! Both backends block, so [`PlayerStore::load`] and [`PlayerStore::save`]
+//! run them on Tokio's blocking thread pool. Redis connections are kept open
+//! and reused between commands.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::PlayerDataSection;
+use crate::persistence::PlayerData;
+
+/// Connect/read/write timeout for the Redis connection.
+const REDIS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Idle Redis connections kept open for reuse.
+const MAX_IDLE_CONNECTIONS: usize = 8;
+
+/// Error returned when loading or saving player data.
+#[derive(Debug)]
+pub enum StoreError {
+    Io(io::Error),
+    /// The stored entry was modified by another server since it was loaded.
+    Conflict {
+        expected: u64,
+    },
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreError::Io(e) => write!(f, "{e}"),
+            StoreError::Conflict { expected } => write!(
+                f,
+                "stored data changed since version {expected} was loaded (saved by another server)"
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for StoreError {
+    fn from(e: io::Error) -> Self {
+        StoreError::Io(e)
+    }
+}
+
+/// Versioned wrapper stored in the shared backend.
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    version: u64,
+    data: PlayerData,
+}
+
+/// Where player data is loaded from and saved to.
+pub enum PlayerStore {
+    /// JSON files in `<world>/players/<uuid>.json` (unversioned).
+    File { world_dir: PathBuf },
+    /// Shared Redis instance.
+    Redis(RedisStore),
+}
+
+impl PlayerStore {
+    /// Build the store selected in `server.toml`. Unknown backends fall back to files.
+    pub fn from_config(config: &PlayerDataSection, world_dir: &Path) -> Self {
+        match config.backend.as_str() {
+            "redis" => {
+                info!(
+                    "Player data stored in Redis at {} (prefix \"{}\")",
+                    config.redis_address, config.key_prefix
+                );
+                PlayerStore::Redis(RedisStore::new(
+                    &config.redis_address,
+                    &config.redis_password,
+                    &config.key_prefix,
+                ))
+            }
+            other => {
+                if other != "file" {
+                    warn!("Unknown player data backend \"{other}\", using files");
+                }
+                PlayerStore::File {
+                    world_dir: world_dir.to_path_buf(),
+                }
+            }
+        }
+    }
+
+    /// Load a player's data and its version. `xuid` may be empty (offline mode).
+    /// `Ok(None)` means the player has no data yet; an error means it exists
+    /// but could not be read.
+    pub async fn load(
+        self: &Arc<Self>,
+        xuid: &str,
+        uuid: &str,
+    ) -> Result<Option<(PlayerData, u64)>, StoreError> {
+        let (store, xuid, uuid) = (Arc::clone(self), xuid.to_string(), uuid.to_string());
+        blocking(move || store.load_blocking(&xuid, &uuid)).await
+    }
+
+    /// Save a player's data if the stored version still equals `expected_version`.
+    /// Returns the new version.
+    pub async fn save(
+        self: &Arc<Self>,
+        xuid: &str,
+        uuid: &str,
+        data: PlayerData,
+        expected_version: u64,
+    ) -> Result<u64, StoreError> {
+        let (store, xuid, uuid) = (Arc::clone(self), xuid.to_string(), uuid.to_string());
+        blocking(move || store.save_blocking(&xuid, &uuid, data, expected_version)).await
+    }
+
+    fn load_blocking(
+        &self,
+        xuid: &str,
+        uuid: &str,
+    ) -> Result<Option<(PlayerData, u64)>, StoreError> {
+        match self {
+            PlayerStore::File { world_dir } => {
+                Ok(PlayerData::load(world_dir, uuid)?.map(|d| (d, 0)))
+            }
+            PlayerStore::Redis(redis) => Ok(redis
+                .load(&redis.key(xuid, uuid))?
+                .map(|e| (e.data, e.version))),
+        }
+    }
+
+    fn save_blocking(
+        &self,
+        xuid: &str,
+        uuid: &str,
+        data: PlayerData,
+        expected_version: u64,
+    ) -> Result<u64, StoreError> {
+        match self {
+            PlayerStore::File { world_dir } => {
+                data.save(world_dir, uuid)?;
+                Ok(expected_version)
+            }
+            PlayerStore::Redis(redis) => redis.save(&redis.key(xuid, uuid), data, expected_version),
+        }
+    }
+}
+
+/// Run blocking store I/O off the async worker threads.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, StoreError> + Send + 'static,
+) -> Result<T, StoreError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| StoreError::Io(io::Error::other(e)))?
+}
+
+/// Player data kept in a shared Redis instance.
+pub struct RedisStore {
+    address: String,
+    password: String,
+    key_prefix: String,
+    /// Open connections waiting for the next command.
+    idle: Mutex<Vec<RespConnection>>,
+}
+
+impl RedisStore {
+    fn new(address: &str, password: &str, key_prefix: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            password: password.to_string(),
+            key_prefix: key_prefix.to_string(),
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn key(&self, xuid: &str, uuid: &str) -> String {
+        let id = if xuid.is_empty() { uuid } else { xuid };
+        format!("{}{id}", self.key_prefix)
+    }
+
+    fn connect(&self) -> io::Result<RespConnection> {
+        let addr = self
+            .address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::other(format!("cannot resolve {}", self.address)))?;
+        let stream = TcpStream::connect_timeout(&addr, REDIS_TIMEOUT)?;
+        stream.set_read_timeout(Some(REDIS_TIMEOUT))?;
+        stream.set_write_timeout(Some(REDIS_TIMEOUT))?;
+        let mut conn = RespConnection {
+            writer: stream.try_clone()?,
+            reader: BufReader::new(stream),
+        };
+        if !self.password.is_empty() {
+            conn.command(&[b"AUTH", self.password.as_bytes()])?;
+        }
+        Ok(conn)
+    }
+
+    /// Run `f` on an idle connection that still answers, or a new one. The
+    /// connection is kept for reuse unless `f` failed with an I/O error.
+    fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&mut RespConnection) -> Result<T, StoreError>,
+    ) -> Result<T, StoreError> {
+        let idle = || self.idle.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut conn = loop {
+            let Some(mut conn) = idle().pop() else {
+                break self.connect()?;
+            };
+            if conn.command(&[b"PING"]).is_ok() {
+                break conn;
+            }
+        };
+        let result = f(&mut conn);
+        if !matches!(result, Err(StoreError::Io(_))) {
+            let mut idle = idle();
+            if idle.len() < MAX_IDLE_CONNECTIONS {
+                idle.push(conn);
+            }
+        }
+        result
+    }
+
+    fn load(&self, key: &str) -> Result<Option<Envelope>, StoreError> {
+        self.with_connection(|conn| {
+            let value = conn.command(&[b"GET", key.as_bytes()])?;
+            Ok(decode_envelope(value)?)
+        })
+    }
+
+    fn save(&self, key: &str, data: PlayerData, expected: u64) -> Result<u64, StoreError> {
+        self.with_connection(|conn| Self::save_on(conn, key, data, expected))
+    }
+
+    fn save_on(
+        conn: &mut RespConnection,
+        key: &str,
+        data: PlayerData,
+        expected: u64,
+    ) -> Result<u64, StoreError> {
+        conn.command(&[b"WATCH", key.as_bytes()])?;
+        let found = decode_envelope(conn.command(&[b"GET", key.as_bytes()])?)?
+            .map(|e| e.version)
+            .unwrap_or(0);
+        if found != expected {
+            conn.command(&[b"UNWATCH"])?;
+            return Err(StoreError::Conflict { expected });
+        }
+
+        let version = expected + 1;
+        let json = serde_json::to_vec(&Envelope { version, data }).map_err(io::Error::other)?;
+        conn.command(&[b"MULTI"])?;
+        conn.command(&[b"SET", key.as_bytes(), &json])?;
+        match conn.command(&[b"EXEC"])? {
+            // A nil reply means the watched key changed between GET and EXEC.
+            RespValue::Array(None) => Err(StoreError::Conflict { expected }),
+            _ => Ok(version),
+        }
+    }
+}
+
+/// Decode a GET reply into a versioned entry.
+fn decode_envelope(value: RespValue) -> io::Result<Option<Envelope>> {
+    match value {
+        RespValue::Bulk(None) => Ok(None),
+        RespValue::Bulk(Some(bytes)) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(io::Error::other),
+        other => Err(io::Error::other(format!("unexpected GET reply: {other:?}"))),
+    }
+}
+
+// ─── Minimal RESP client ─────────────────────────────────────────────────────
+
+/// A Redis protocol (RESP2) reply.
+#[derive(Debug, PartialEq)]
+enum RespValue {
+    Simple(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<RespValue>>),
+}
+
+struct RespConnection {
+    writer: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl RespConnection {
+    /// Send a command and read its reply. Error replies become `io::Error`s.
+    fn command(&mut self, args: &[&[u8]]) -> io::Result<RespValue> {
+        self.writer.write_all(&encode_command(args))?;
+        read_value(&mut self.reader)
+    }
+}
+
+/// Encode a command as a RESP array of bulk strings.
+fn encode_command(args: &[&[u8]]) -> Vec<u8> {
+    let mut buf = format!("*{}\r\n", args.len()).into_bytes();
+    for arg in args {
+        buf.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buf.extend_from_slice(arg);
+        buf.extend_from_slice(b"\r\n");
+    }
+    buf
+}
+
+fn read_line(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(line.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn parse_len(s: &str) -> io::Result<i64> {
+    s.parse()
+        .map_err(|_| io::Error::other(format!("bad RESP length: {s}")))
+}
+
+/// Read one RESP reply.
+fn read_value(reader: &mut impl BufRead) -> io::Result<RespValue> {
+    let line = read_line(reader)?;
+    let (kind, rest) = line.split_at(line.len().min(1));
+    match kind {
+        "+" => Ok(RespValue::Simple(rest.to_string())),
+        "-" => Err(io::Error::other(format!("Redis error: {rest}"))),
+        ":" => Ok(RespValue::Integer(parse_len(rest)?)),
+        "$" => {
+            let len = parse_len(rest)?;
+            if len < 0 {
+                return Ok(RespValue::Bulk(None));
+            }
+            let mut buf = vec![0u8; len as usize + 2];
+            reader.read_exact(&mut buf)?;
+            buf.truncate(len as usize);
+            Ok(RespValue::Bulk(Some(buf)))
+        }
+        "*" => {
+            let len = parse_len(rest)?;
+            if len < 0 {
+                return Ok(RespValue::Array(None));
+            }
+            let items = (0..len)
+                .map(|_| read_value(reader))
+                .collect::<io::Result<Vec<_>>>()?;
+            Ok(RespValue::Array(Some(items)))
+        }
+        _ => Err(io::Error::other(format!("bad RESP reply: {line}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn encode_command_format() {
+        let buf = encode_command(&[b"GET", b"key"]);
+        assert_eq!(buf, b"*2\r\n$3\r\nGET\r\n$3\r\nkey\r\n");
+    }
+
+    #[test]
+    fn read_value_types() {
+        let mut r =
+            Cursor::new(&b"+OK\r\n:42\r\n$5\r\nhello\r\n$-1\r\n*-1\r\n*2\r\n+QUEUED\r\n:1\r\n"[..]);
+        assert_eq!(read_value(&mut r).unwrap(), RespValue::Simple("OK".into()));
+        assert_eq!(read_value(&mut r).unwrap(), RespValue::Integer(42));
+        assert_eq!(
+            read_value(&mut r).unwrap(),
+            RespValue::Bulk(Some(b"hello".to_vec()))
+        );
+        assert_eq!(read_value(&mut r).unwrap(), RespValue::Bulk(None));
+        assert_eq!(read_value(&mut r).unwrap(), RespValue::Array(None));
+        assert_eq!(
+            read_value(&mut r).unwrap(),
+            RespValue::Array(Some(vec![
+                RespValue::Simple("QUEUED".into()),
+                RespValue::Integer(1)
+            ]))
+        );
+    }
+
+    #[test]
+    fn read_value_error_reply() {
+        let mut r = Cursor::new(&b"-ERR wrong type\r\n"[..]);
+        let err = read_value(&mut r).unwrap_err();
+        assert!(err.to_string().contains("wrong type"));
+    }
+
+    #[test]
+    fn redis_key_prefers_xuid() {
+        let store = RedisStore::new("", "", "mc-rs:player:");
+        assert_eq!(store.key("2535", "uuid-1"), "mc-rs:player:2535");
+        assert_eq!(store.key("", "uuid-1"), "mc-rs:player:uuid-1");
+    }
+
+    #[test]
+    fn redis_connections_are_reused() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let store = RedisStore::new(&listener.local_addr().unwrap().to_string(), "", "p:");
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut commands = Vec::new();
+            while let Ok(RespValue::Array(Some(args))) = read_value(&mut reader) {
+                let RespValue::Bulk(Some(name)) = &args[0] else {
+                    break;
+                };
+                commands.push(String::from_utf8_lossy(name).into_owned());
+                let reply: &[u8] = if name == b"PING" {
+                    b"+PONG\r\n"
+                } else {
+                    b"$-1\r\n"
+                };
+                writer.write_all(reply).unwrap();
+            }
+            commands
+        });
+
+        for _ in 0..3 {
+            assert!(store.load("p:1").unwrap().is_none());
+        }
+        drop(store);
+        assert_eq!(
+            server.join().unwrap(),
+            ["GET", "PING", "GET", "PING", "GET"]
+        );
+    }
+
+    #[test]
+    fn missing_entry_decodes_as_none() {
+        assert!(decode_envelope(RespValue::Bulk(None)).unwrap().is_none());
+        assert!(decode_envelope(RespValue::Integer(1)).is_err());
+    }
+}
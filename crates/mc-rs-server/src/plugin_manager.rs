@@ -1,12 +1,31 @@
 //! Plugin manager: loads, enables, and dispatches events to plugins.
 
-use std::collections::HashMap;
-
-use mc_rs_plugin_api::{EventResult, LogLevel, Plugin, PluginEvent, PluginPlayer, ServerApi};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use mc_rs_plugin_api::{
+    form_type_of, is_valid_script_event_id, EventResult, LogLevel, Plugin, PluginArena,
+    PluginCutscene, PluginEntityAppearance, PluginEvent, PluginKitItem, PluginPlayer,
+    PluginPlayerStats, ScriptEventSource, ServerApi,
+};
+use mc_rs_proto::packets::update_client_input_locks::input_lock;
 use tracing::{debug, error, info, warn};
 
+use crate::arena::{ArenaDefinition, ArenaTeam};
+use crate::client_settings::client_game_rule;
+use crate::cutscenes::Cutscene;
+use crate::economy::SharedEconomy;
+use crate::entity_appearance::EntityAppearance;
+use crate::holograms::Hologram;
+use crate::kits::{KitDefinition, KitItem};
+use crate::world_flags::{self, WorldFlag, WorldFlags};
+
 // ─── Types ───────────────────────────────────────────────────────────────────
 
+/// Maximum number of script events plugins can send in reaction to one callback,
+/// to stop plugins that answer each other's events forever.
+const MAX_SCRIPT_EVENT_CHAIN: usize = 32;
+
 /// A scheduled task owned by a plugin.
 pub struct ScheduledTask {
     pub plugin_name: String,
@@ -19,9 +38,25 @@ pub struct ScheduledTask {
 /// Server state snapshot for plugin API reads (built before dispatch).
 pub struct ServerSnapshot {
     pub players: Vec<PluginPlayer>,
+    /// Statistics of online players, keyed by player name.
+    pub player_stats: HashMap<String, PluginPlayerStats>,
     pub world_time: i64,
     pub current_tick: u64,
+    /// Recent ticks per second.
+    pub tps: f64,
+    /// Recent mean milliseconds per tick.
+    pub mspt: f64,
     pub is_raining: bool,
+    /// World flags of each dimension, indexed by dimension ID.
+    pub world_flags: [WorldFlags; 3],
+    /// The economy ledger itself, not a copy: plugins move money in it
+    /// straight away.
+    pub economy: SharedEconomy,
+    pub currency_name: String,
+    /// Arena of each player queued or playing in one, keyed by player name.
+    pub arena_players: HashMap<String, String>,
+    /// UUIDs of the packs each in-game player accepted, keyed by player name.
+    pub accepted_packs: HashMap<String, Vec<String>>,
 }
 
 /// Deferred side-effect requested by a plugin during a callback.
@@ -52,9 +87,19 @@ pub enum PendingAction {
         y: f32,
         z: f32,
     },
+    TransferPlayer {
+        player_name: String,
+        host: String,
+        port: u16,
+    },
     SetTime {
         time: i64,
     },
+    SetWorldFlag {
+        dimension: i32,
+        flag: WorldFlag,
+        value: bool,
+    },
     SpawnMob {
         mob_type: String,
         x: f32,
@@ -64,6 +109,19 @@ pub enum PendingAction {
     RemoveMob {
         runtime_id: u64,
     },
+    TriggerEntityEvent {
+        runtime_id: u64,
+        event: String,
+    },
+    SetEntityAppearance {
+        runtime_id: u64,
+        appearance: EntityAppearance,
+    },
+    /// Delivered to plugins by the `PluginManager` itself, never reaches the server.
+    SendScriptEvent {
+        id: String,
+        payload: String,
+    },
     RegisterCommand {
         name: String,
         description: String,
@@ -85,6 +143,71 @@ pub enum PendingAction {
         form_id: u32,
         form_data: String,
         form_type: String,
+        /// The plugin showing it, the only one told the answer.
+        plugin_name: String,
+        /// Opened from the answer to another form, going back to it when
+        /// closed.
+        chained: bool,
+    },
+    RegisterKit {
+        name: String,
+        kit: KitDefinition,
+    },
+    CreateArena {
+        def: ArenaDefinition,
+    },
+    JoinArena {
+        player_name: String,
+        arena_name: String,
+    },
+    LeaveArena {
+        player_name: String,
+    },
+    SetArenaScore {
+        arena_name: String,
+        entry: String,
+        score: i32,
+    },
+    EndArena {
+        arena_name: String,
+        winner: Option<String>,
+    },
+    CreateHologram {
+        hologram: Hologram,
+    },
+    SetHologramLines {
+        name: String,
+        lines: Vec<String>,
+    },
+    RemoveHologram {
+        name: String,
+    },
+    PlayCutscene {
+        player_name: String,
+        cutscene: Cutscene,
+    },
+    StopCutscene {
+        player_name: String,
+    },
+    PushFog {
+        player_name: String,
+        fog_id: String,
+        user_id: String,
+    },
+    RemoveFog {
+        player_name: String,
+        user_id: String,
+    },
+    SetClientGameRule {
+        player_name: String,
+        rule: &'static str,
+        value: bool,
+    },
+    SetInputPermission {
+        player_name: String,
+        /// Lock flag of UpdateClientInputLocks.
+        flag: u32,
+        enabled: bool,
     },
 }
 
@@ -93,17 +216,42 @@ pub enum PendingAction {
 /// Implements `ServerApi` using a snapshot for reads and accumulating PendingActions for writes.
 struct ServerApiImpl<'a> {
     snapshot: &'a ServerSnapshot,
+    /// The plugin calling the API.
+    plugin_name: String,
     actions: Vec<PendingAction>,
 }
 
 impl<'a> ServerApiImpl<'a> {
-    fn new(snapshot: &'a ServerSnapshot) -> Self {
+    fn new(snapshot: &'a ServerSnapshot, plugin_name: &str) -> Self {
         Self {
             snapshot,
+            plugin_name: plugin_name.to_string(),
             actions: Vec::new(),
         }
     }
 
+    fn show_form(&mut self, player_name: &str, form_id: u32, json: &str, form_type: &str) {
+        self.show_form_chained(player_name, form_id, json, form_type, false);
+    }
+
+    fn show_form_chained(
+        &mut self,
+        player_name: &str,
+        form_id: u32,
+        json: &str,
+        form_type: &str,
+        chained: bool,
+    ) {
+        self.actions.push(PendingAction::ShowForm {
+            player_name: player_name.to_string(),
+            form_id,
+            form_data: json.to_string(),
+            form_type: form_type.to_string(),
+            plugin_name: self.plugin_name.clone(),
+            chained,
+        });
+    }
+
     fn take_actions(self) -> Vec<PendingAction> {
         self.actions
     }
@@ -122,6 +270,10 @@ impl ServerApi for ServerApiImpl<'_> {
             .cloned()
     }
 
+    fn get_player_stats(&self, name: &str) -> Option<PluginPlayerStats> {
+        self.snapshot.player_stats.get(name).cloned()
+    }
+
     fn send_message(&mut self, player_name: &str, message: &str) {
         self.actions.push(PendingAction::SendMessage {
             player_name: player_name.to_string(),
@@ -165,6 +317,14 @@ impl ServerApi for ServerApiImpl<'_> {
         });
     }
 
+    fn transfer_player(&mut self, player_name: &str, host: &str, port: u16) {
+        self.actions.push(PendingAction::TransferPlayer {
+            player_name: player_name.to_string(),
+            host: host.to_string(),
+            port,
+        });
+    }
+
     fn get_time(&self) -> i64 {
         self.snapshot.world_time
     }
@@ -177,6 +337,26 @@ impl ServerApi for ServerApiImpl<'_> {
         self.snapshot.is_raining
     }
 
+    fn get_world_flag(&self, dimension: &str, flag: &str) -> Option<bool> {
+        let dim = world_flags::dimension_from_name(dimension)?;
+        let flag = WorldFlag::from_name(flag)?;
+        Some(self.snapshot.world_flags[dim as usize].get(flag))
+    }
+
+    fn set_world_flag(&mut self, dimension: &str, flag: &str, value: bool) {
+        match (
+            world_flags::dimension_from_name(dimension),
+            WorldFlag::from_name(flag),
+        ) {
+            (Some(dimension), Some(flag)) => self.actions.push(PendingAction::SetWorldFlag {
+                dimension,
+                flag,
+                value,
+            }),
+            _ => warn!("[plugin] Unknown world flag {dimension}.{flag}"),
+        }
+    }
+
     fn spawn_mob(&mut self, mob_type: &str, x: f32, y: f32, z: f32) {
         self.actions.push(PendingAction::SpawnMob {
             mob_type: mob_type.to_string(),
@@ -190,10 +370,42 @@ impl ServerApi for ServerApiImpl<'_> {
         self.actions.push(PendingAction::RemoveMob { runtime_id });
     }
 
+    fn trigger_entity_event(&mut self, runtime_id: u64, event: &str) {
+        self.actions.push(PendingAction::TriggerEntityEvent {
+            runtime_id,
+            event: event.to_string(),
+        });
+    }
+
+    fn set_entity_appearance(&mut self, runtime_id: u64, appearance: PluginEntityAppearance) {
+        match EntityAppearance::from_plugin(appearance) {
+            Ok(appearance) => self.actions.push(PendingAction::SetEntityAppearance {
+                runtime_id,
+                appearance,
+            }),
+            Err(e) => warn!("[plugin] Entity {runtime_id} appearance: {e}"),
+        }
+    }
+
+    fn send_script_event(&mut self, id: &str, payload: &str) {
+        self.actions.push(PendingAction::SendScriptEvent {
+            id: id.to_string(),
+            payload: payload.to_string(),
+        });
+    }
+
     fn get_tick(&self) -> u64 {
         self.snapshot.current_tick
     }
 
+    fn get_tps(&self) -> f64 {
+        self.snapshot.tps
+    }
+
+    fn get_mspt(&self) -> f64 {
+        self.snapshot.mspt
+    }
+
     fn log(&self, level: LogLevel, message: &str) {
         match level {
             LogLevel::Info => info!("[plugin] {message}"),
@@ -247,31 +459,215 @@ impl ServerApi for ServerApiImpl<'_> {
     }
 
     fn show_simple_form(&mut self, player_name: &str, form_id: u32, json: &str) {
-        self.actions.push(PendingAction::ShowForm {
+        self.show_form(player_name, form_id, json, "simple");
+    }
+
+    fn show_modal_form(&mut self, player_name: &str, form_id: u32, json: &str) {
+        self.show_form(player_name, form_id, json, "modal");
+    }
+
+    fn show_custom_form(&mut self, player_name: &str, form_id: u32, json: &str) {
+        self.show_form(player_name, form_id, json, "custom");
+    }
+
+    fn show_next_form(&mut self, player_name: &str, form_id: u32, json: &str) {
+        let Some(form_type) = form_type_of(json) else {
+            warn!(
+                "[plugin] {} showed a form of unknown type",
+                self.plugin_name
+            );
+            return;
+        };
+        self.show_form_chained(player_name, form_id, json, form_type, true);
+    }
+
+    fn register_kit(&mut self, name: &str, items: Vec<PluginKitItem>, cooldown_secs: u64) {
+        self.actions.push(PendingAction::RegisterKit {
+            name: name.to_string(),
+            kit: KitDefinition {
+                items: items
+                    .into_iter()
+                    .map(|i| KitItem {
+                        item: i.item,
+                        count: i.count,
+                        metadata: i.metadata,
+                    })
+                    .collect(),
+                cooldown: cooldown_secs,
+                first_join: false,
+                op_only: false,
+            },
+        });
+    }
+
+    fn currency_name(&self) -> String {
+        self.snapshot.currency_name.clone()
+    }
+
+    fn get_balance(&self, player_name: &str) -> Option<u64> {
+        let economy = self.snapshot.economy.lock();
+        economy.balance(economy.find(player_name)?)
+    }
+
+    fn deposit(&mut self, player_name: &str, amount: u64, reason: &str) -> bool {
+        let mut economy = self.snapshot.economy.lock();
+        let Some(id) = economy.find(player_name).map(String::from) else {
+            return false;
+        };
+        economy.deposit(&id, amount, reason).is_ok()
+    }
+
+    fn withdraw(&mut self, player_name: &str, amount: u64, reason: &str) -> bool {
+        let mut economy = self.snapshot.economy.lock();
+        let Some(id) = economy.find(player_name).map(String::from) else {
+            return false;
+        };
+        economy.withdraw(&id, amount, reason).is_ok()
+    }
+
+    fn transfer_money(&mut self, from: &str, to: &str, amount: u64, reason: &str) -> bool {
+        let mut economy = self.snapshot.economy.lock();
+        let (Some(from), Some(to)) = (
+            economy.find(from).map(String::from),
+            economy.find(to).map(String::from),
+        ) else {
+            return false;
+        };
+        economy.transfer(&from, &to, amount, reason).is_ok()
+    }
+
+    fn create_arena(&mut self, arena: PluginArena) {
+        let (a, b) = arena.region;
+        self.actions.push(PendingAction::CreateArena {
+            def: ArenaDefinition {
+                name: arena.name,
+                min: [a.x, a.y, a.z],
+                max: [b.x, b.y, b.z],
+                lobby: arena.lobby,
+                teams: arena
+                    .teams
+                    .into_iter()
+                    .map(|t| ArenaTeam {
+                        name: t.name,
+                        spawn: t.spawn,
+                    })
+                    .collect(),
+                min_players: arena.min_players as usize,
+                max_players: arena.max_players as usize,
+                countdown_ticks: arena.countdown_secs as u64 * 20,
+                duration_ticks: (arena.duration_secs > 0).then(|| arena.duration_secs as u64 * 20),
+            },
+        });
+    }
+
+    fn join_arena(&mut self, player_name: &str, arena_name: &str) {
+        self.actions.push(PendingAction::JoinArena {
             player_name: player_name.to_string(),
-            form_id,
-            form_data: json.to_string(),
-            form_type: "simple".to_string(),
+            arena_name: arena_name.to_string(),
         });
     }
 
-    fn show_modal_form(&mut self, player_name: &str, form_id: u32, json: &str) {
-        self.actions.push(PendingAction::ShowForm {
+    fn leave_arena(&mut self, player_name: &str) {
+        self.actions.push(PendingAction::LeaveArena {
             player_name: player_name.to_string(),
-            form_id,
-            form_data: json.to_string(),
-            form_type: "modal".to_string(),
         });
     }
 
-    fn show_custom_form(&mut self, player_name: &str, form_id: u32, json: &str) {
-        self.actions.push(PendingAction::ShowForm {
+    fn set_arena_score(&mut self, arena_name: &str, entry: &str, score: i32) {
+        self.actions.push(PendingAction::SetArenaScore {
+            arena_name: arena_name.to_string(),
+            entry: entry.to_string(),
+            score,
+        });
+    }
+
+    fn end_arena(&mut self, arena_name: &str, winner: Option<&str>) {
+        self.actions.push(PendingAction::EndArena {
+            arena_name: arena_name.to_string(),
+            winner: winner.map(str::to_string),
+        });
+    }
+
+    fn player_arena(&self, player_name: &str) -> Option<String> {
+        self.snapshot.arena_players.get(player_name).cloned()
+    }
+
+    fn create_hologram(&mut self, name: &str, x: f32, y: f32, z: f32, lines: Vec<String>) {
+        self.actions.push(PendingAction::CreateHologram {
+            hologram: Hologram::new(name, [x, y, z], lines),
+        });
+    }
+
+    fn set_hologram_lines(&mut self, name: &str, lines: Vec<String>) {
+        self.actions.push(PendingAction::SetHologramLines {
+            name: name.to_string(),
+            lines,
+        });
+    }
+
+    fn remove_hologram(&mut self, name: &str) {
+        self.actions.push(PendingAction::RemoveHologram {
+            name: name.to_string(),
+        });
+    }
+
+    fn play_cutscene(&mut self, player_name: &str, cutscene: PluginCutscene) {
+        let name = cutscene.name.clone();
+        match Cutscene::from_plugin(cutscene) {
+            Ok(cutscene) => self.actions.push(PendingAction::PlayCutscene {
+                player_name: player_name.to_string(),
+                cutscene,
+            }),
+            Err(e) => warn!("[plugin] Cutscene {name}: {e}"),
+        }
+    }
+
+    fn stop_cutscene(&mut self, player_name: &str) {
+        self.actions.push(PendingAction::StopCutscene {
             player_name: player_name.to_string(),
-            form_id,
-            form_data: json.to_string(),
-            form_type: "custom".to_string(),
         });
     }
+
+    fn push_fog(&mut self, player_name: &str, fog_id: &str, user_id: &str) {
+        self.actions.push(PendingAction::PushFog {
+            player_name: player_name.to_string(),
+            fog_id: fog_id.to_string(),
+            user_id: user_id.to_string(),
+        });
+    }
+
+    fn remove_fog(&mut self, player_name: &str, user_id: &str) {
+        self.actions.push(PendingAction::RemoveFog {
+            player_name: player_name.to_string(),
+            user_id: user_id.to_string(),
+        });
+    }
+
+    fn set_client_game_rule(&mut self, player_name: &str, rule: &str, value: bool) {
+        match client_game_rule(rule) {
+            Some(rule) => self.actions.push(PendingAction::SetClientGameRule {
+                player_name: player_name.to_string(),
+                rule,
+                value,
+            }),
+            None => warn!("[plugin] Unknown client game rule {rule}"),
+        }
+    }
+
+    fn set_input_permission(&mut self, player_name: &str, permission: &str, enabled: bool) {
+        match input_lock(permission) {
+            Some(flag) => self.actions.push(PendingAction::SetInputPermission {
+                player_name: player_name.to_string(),
+                flag,
+                enabled,
+            }),
+            None => warn!("[plugin] Unknown input permission {permission}"),
+        }
+    }
+
+    fn accepted_packs(&self, player_name: &str) -> Option<Vec<String>> {
+        self.snapshot.accepted_packs.get(player_name).cloned()
+    }
 }
 
 // ─── PluginManager ───────────────────────────────────────────────────────────
@@ -279,17 +675,32 @@ impl ServerApi for ServerApiImpl<'_> {
 /// Manages all loaded plugins, their scheduled tasks, and command registrations.
 pub struct PluginManager {
     plugins: Vec<Box<dyn Plugin>>,
+    /// Time spent in each plugin's callbacks since the last
+    /// [`take_timings`](Self::take_timings), indexed like `plugins`.
+    timings: Vec<Duration>,
     tasks: Vec<ScheduledTask>,
     /// Commands registered by plugins: command_name → plugin_name.
     pub plugin_commands: HashMap<String, String>,
+    /// Kits registered by plugins: kit_name → kit.
+    pub plugin_kits: HashMap<String, KitDefinition>,
+    /// Arenas defined while plugins were being enabled, for the server to
+    /// pick up on its next tick.
+    pub new_arenas: Vec<ArenaDefinition>,
+    /// Holograms created while plugins were being enabled, shown on the
+    /// server's next tick.
+    pub new_holograms: Vec<Hologram>,
 }
 
 impl PluginManager {
     pub fn new() -> Self {
         Self {
             plugins: Vec::new(),
+            timings: Vec::new(),
             tasks: Vec::new(),
             plugin_commands: HashMap::new(),
+            plugin_kits: HashMap::new(),
+            new_arenas: Vec::new(),
+            new_holograms: Vec::new(),
         }
     }
 
@@ -298,6 +709,7 @@ impl PluginManager {
     pub fn register(&mut self, plugin: Box<dyn Plugin>) {
         info!("Registered plugin: {}", plugin.info().name);
         self.plugins.push(plugin);
+        self.timings.push(Duration::ZERO);
     }
 
     /// Enable all registered plugins.
@@ -306,7 +718,7 @@ impl PluginManager {
         // First pass: enable all plugins, collect actions
         let mut all_actions = Vec::new();
         for plugin in &mut self.plugins {
-            let mut api = ServerApiImpl::new(snapshot);
+            let mut api = ServerApiImpl::new(snapshot, &plugin.info().name);
             plugin.on_enable(&mut api);
             all_actions.extend(api.take_actions());
         }
@@ -325,7 +737,9 @@ impl PluginManager {
     pub fn reload(&mut self) {
         self.disable_all();
         self.plugins.clear();
+        self.timings.clear();
         self.plugin_commands.clear();
+        self.plugin_kits.clear();
         self.tasks.clear();
         info!("All plugins unloaded for reload");
     }
@@ -335,14 +749,49 @@ impl PluginManager {
         &mut self,
         event: &PluginEvent,
         snapshot: &ServerSnapshot,
+    ) -> (EventResult, Vec<PendingAction>) {
+        let (result, actions) = self.dispatch_to_plugins(event, snapshot);
+        (result, self.deliver_script_events(actions, snapshot))
+    }
+
+    /// Dispatch an event to one plugin only, e.g. the answer to its form.
+    /// Nothing happens if it isn't loaded anymore.
+    pub fn dispatch_to(
+        &mut self,
+        plugin_name: &str,
+        event: &PluginEvent,
+        snapshot: &ServerSnapshot,
+    ) -> (EventResult, Vec<PendingAction>) {
+        let Some((plugin, time)) = self
+            .plugins
+            .iter_mut()
+            .zip(&mut self.timings)
+            .find(|(p, _)| p.info().name == plugin_name)
+        else {
+            return (EventResult::Continue, Vec::new());
+        };
+        let mut api = ServerApiImpl::new(snapshot, plugin_name);
+        let started = Instant::now();
+        let result = plugin.on_event(event, &mut api);
+        *time += started.elapsed();
+        let actions = api.take_actions();
+        (result, self.deliver_script_events(actions, snapshot))
+    }
+
+    fn dispatch_to_plugins(
+        &mut self,
+        event: &PluginEvent,
+        snapshot: &ServerSnapshot,
     ) -> (EventResult, Vec<PendingAction>) {
         let cancellable = event.is_cancellable();
         let mut all_actions = Vec::new();
         let mut final_result = EventResult::Continue;
 
-        for plugin in &mut self.plugins {
-            let mut api = ServerApiImpl::new(snapshot);
+        for (plugin, time) in self.plugins.iter_mut().zip(&mut self.timings) {
+            let mut api = ServerApiImpl::new(snapshot, &plugin.info().name);
+            let started = Instant::now();
             let result = plugin.on_event(event, &mut api);
+            *time += started.elapsed();
             all_actions.extend(api.take_actions());
 
             if cancellable && result == EventResult::Cancelled {
@@ -378,18 +827,21 @@ impl PluginManager {
 
         // Call on_task for each fired task
         for (plugin_name, task_id) in fired {
-            if let Some(plugin) = self
+            if let Some((plugin, time)) = self
                 .plugins
                 .iter_mut()
-                .find(|p| p.info().name == plugin_name)
+                .zip(&mut self.timings)
+                .find(|(p, _)| p.info().name == plugin_name)
             {
-                let mut api = ServerApiImpl::new(snapshot);
+                let mut api = ServerApiImpl::new(snapshot, &plugin_name);
+                let started = Instant::now();
                 plugin.on_task(task_id, &mut api);
+                *time += started.elapsed();
                 all_actions.extend(api.take_actions());
             }
         }
 
-        all_actions
+        self.deliver_script_events(all_actions, snapshot)
     }
 
     /// Handle a plugin-registered command. Returns (response_message, pending_actions).
@@ -405,19 +857,83 @@ impl PluginManager {
             None => return (None, Vec::new()),
         };
 
-        if let Some(plugin) = self
+        if let Some((plugin, time)) = self
             .plugins
             .iter_mut()
-            .find(|p| p.info().name == plugin_name)
+            .zip(&mut self.timings)
+            .find(|(p, _)| p.info().name == plugin_name)
         {
-            let mut api = ServerApiImpl::new(snapshot);
+            let mut api = ServerApiImpl::new(snapshot, &plugin_name);
+            let started = Instant::now();
             let response = plugin.on_command(command, args, sender, &mut api);
-            (response, api.take_actions())
+            *time += started.elapsed();
+            let actions = api.take_actions();
+            (response, self.deliver_script_events(actions, snapshot))
         } else {
             (None, Vec::new())
         }
     }
 
+    /// Deliver the script events plugins sent in `actions` to every plugin,
+    /// returning the remaining actions (including those produced by the
+    /// script event handlers).
+    fn deliver_script_events(
+        &mut self,
+        actions: Vec<PendingAction>,
+        snapshot: &ServerSnapshot,
+    ) -> Vec<PendingAction> {
+        let mut queue = VecDeque::from(actions);
+        let mut remaining = Vec::new();
+        let mut delivered = 0;
+
+        while let Some(action) = queue.pop_front() {
+            let PendingAction::SendScriptEvent { id, payload } = action else {
+                remaining.push(action);
+                continue;
+            };
+            if !is_valid_script_event_id(&id) {
+                warn!("[plugin] Ignoring script event with invalid ID: {id}");
+                continue;
+            }
+            if delivered == MAX_SCRIPT_EVENT_CHAIN {
+                warn!("[plugin] Script event chain too long, dropping {id}");
+                continue;
+            }
+            delivered += 1;
+            let event = PluginEvent::ScriptEvent {
+                id,
+                payload,
+                source: ScriptEventSource::Server,
+            };
+            let (_, actions) = self.dispatch_to_plugins(&event, snapshot);
+            queue.extend(actions);
+        }
+
+        remaining
+    }
+
+    /// Name and version of every loaded plugin.
+    pub fn plugin_names(&self) -> Vec<String> {
+        self.plugins
+            .iter()
+            .map(|p| {
+                let info = p.info();
+                format!("{} {}", info.name, info.version)
+            })
+            .collect()
+    }
+
+    /// Time each plugin spent in its callbacks since the last call, for
+    /// plugins that ran at all.
+    pub fn take_timings(&mut self) -> Vec<(String, Duration)> {
+        self.plugins
+            .iter()
+            .zip(&mut self.timings)
+            .filter(|(_, time)| !time.is_zero())
+            .map(|(plugin, time)| (plugin.info().name, std::mem::take(time)))
+            .collect()
+    }
+
     /// Load configs for all plugins from disk.
     #[allow(dead_code)]
     pub fn load_configs(&mut self) {
@@ -465,6 +981,15 @@ impl PluginManager {
                 } => {
                     self.plugin_commands.insert(name, plugin_name);
                 }
+                PendingAction::RegisterKit { name, kit } => {
+                    self.plugin_kits.insert(name, kit);
+                }
+                PendingAction::CreateArena { def } => {
+                    self.new_arenas.push(def);
+                }
+                PendingAction::CreateHologram { hologram } => {
+                    self.new_holograms.push(hologram);
+                }
                 PendingAction::ScheduleTask { task } => {
                     self.tasks.push(task);
                 }
@@ -492,14 +1017,24 @@ impl PluginManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mc_rs_plugin_api::{DamageCause, PluginInfo};
+    use mc_rs_plugin_api::{
+        DamageCause, FormResponse, PluginArenaTeam, PluginBlockPos, PluginInfo, SimpleFormBuilder,
+    };
 
     fn empty_snapshot() -> ServerSnapshot {
         ServerSnapshot {
             players: Vec::new(),
+            player_stats: HashMap::new(),
             world_time: 6000,
             current_tick: 100,
+            tps: 20.0,
+            mspt: 4.5,
             is_raining: false,
+            world_flags: Default::default(),
+            economy: SharedEconomy::default(),
+            currency_name: "coins".into(),
+            arena_players: HashMap::new(),
+            accepted_packs: HashMap::new(),
         }
     }
 
@@ -512,10 +1047,26 @@ mod tests {
                 position: (0.5, 65.62, 0.5),
                 gamemode: 0,
                 health: 20.0,
+                country: None,
             }],
+            player_stats: HashMap::from([(
+                "Alice".to_string(),
+                PluginPlayerStats {
+                    deaths: 3,
+                    playtime_ticks: 1200,
+                    ..Default::default()
+                },
+            )]),
             world_time: 6000,
             current_tick: 100,
+            tps: 20.0,
+            mspt: 4.5,
             is_raining: false,
+            world_flags: Default::default(),
+            economy: SharedEconomy::default(),
+            currency_name: "coins".into(),
+            arena_players: HashMap::from([("Alice".to_string(), "duel".to_string())]),
+            accepted_packs: HashMap::from([("Alice".to_string(), vec!["aaaa".to_string()])]),
         }
     }
 
@@ -543,6 +1094,35 @@ mod tests {
         fn on_enable(&mut self, api: &mut dyn ServerApi) {
             self.enabled = true;
             api.register_command("test", "A test command", "TestPlugin");
+            api.register_kit(
+                "test_kit",
+                vec![PluginKitItem {
+                    item: "minecraft:apple".into(),
+                    count: 3,
+                    metadata: 0,
+                }],
+                30,
+            );
+            api.create_arena(PluginArena {
+                name: "duel".into(),
+                region: (
+                    PluginBlockPos { x: 0, y: 60, z: 0 },
+                    PluginBlockPos {
+                        x: 15,
+                        y: 70,
+                        z: 15,
+                    },
+                ),
+                lobby: (8.5, 72.0, 8.5),
+                teams: vec![PluginArenaTeam {
+                    name: "red".into(),
+                    spawn: (1.5, 61.0, 1.5),
+                }],
+                min_players: 2,
+                max_players: 4,
+                countdown_secs: 10,
+                duration_secs: 0,
+            });
         }
 
         fn on_disable(&mut self) {
@@ -556,6 +1136,25 @@ mod tests {
                     api.send_message(&player.name, "Welcome!");
                     EventResult::Continue
                 }
+                PluginEvent::FormResponse {
+                    player,
+                    form_id: 1,
+                    response: FormResponse::Simple { .. },
+                } => {
+                    let next = SimpleFormBuilder::new("Next", "").button("Back").to_json();
+                    api.show_next_form(&player.name, 2, &next);
+                    EventResult::Continue
+                }
+                PluginEvent::ScriptEvent { id, payload, .. } => {
+                    match id.as_str() {
+                        "test:ping" => api.send_script_event("test:pong", payload),
+                        "test:pong" => api.broadcast_message(&format!("pong {payload}")),
+                        // Answers itself forever
+                        "test:loop" => api.send_script_event("test:loop", payload),
+                        _ => {}
+                    }
+                    EventResult::Continue
+                }
                 _ => EventResult::Continue,
             }
         }
@@ -583,6 +1182,171 @@ mod tests {
         assert!(mgr.plugin_commands.contains_key("test"));
     }
 
+    #[test]
+    fn enable_all_registers_kits() {
+        let mut mgr = PluginManager::new();
+        mgr.register(Box::new(TestPlugin::new()));
+        mgr.enable_all(&empty_snapshot());
+        let kit = mgr.plugin_kits.get("test_kit").unwrap();
+        assert_eq!(kit.items[0].item, "minecraft:apple");
+        assert_eq!(kit.items[0].count, 3);
+        assert_eq!(kit.cooldown, 30);
+        assert!(!kit.first_join);
+    }
+
+    #[test]
+    fn enable_all_queues_arenas() {
+        let mut mgr = PluginManager::new();
+        mgr.register(Box::new(TestPlugin::new()));
+        mgr.enable_all(&empty_snapshot());
+        let [def] = &mgr.new_arenas[..] else {
+            panic!("expected one arena");
+        };
+        assert_eq!(def.name, "duel");
+        assert_eq!((def.min, def.max), ([0, 60, 0], [15, 70, 15]));
+        assert_eq!(def.countdown_ticks, 200);
+        assert_eq!(def.duration_ticks, None);
+    }
+
+    #[test]
+    fn api_queues_hologram_actions() {
+        let snapshot = empty_snapshot();
+        let mut api = ServerApiImpl::new(&snapshot, "test");
+        api.create_hologram("top", 0.5, 70.0, 0.5, vec!["{balance:Alice}".into()]);
+        api.set_hologram_lines("top", vec!["Hi".into()]);
+        api.remove_hologram("top");
+        let [PendingAction::CreateHologram { hologram }, PendingAction::SetHologramLines { lines, .. }, PendingAction::RemoveHologram { name }] =
+            &api.take_actions()[..]
+        else {
+            panic!("expected create, set lines and remove");
+        };
+        assert_eq!(hologram.position, [0.5, 70.0, 0.5]);
+        assert!(hologram.has_placeholders());
+        assert_eq!(lines, &["Hi"]);
+        assert_eq!(name, "top");
+    }
+
+    #[test]
+    fn api_queues_valid_cutscenes_only() {
+        use mc_rs_plugin_api::PluginCutsceneStep;
+
+        let snapshot = empty_snapshot();
+        let mut api = ServerApiImpl::new(&snapshot, "test");
+        let pan = |ease: &str| PluginCutscene {
+            name: "pan".into(),
+            steps: vec![PluginCutsceneStep::Camera {
+                position: (0.0, 80.0, 0.0),
+                rotation: (20.0, 0.0),
+                ease: ease.into(),
+                duration_secs: 2.0,
+            }],
+        };
+        api.play_cutscene("Alice", pan("linear"));
+        api.play_cutscene("Alice", pan("wobbly"));
+        api.stop_cutscene("Alice");
+        let [PendingAction::PlayCutscene { cutscene, .. }, PendingAction::StopCutscene { player_name }] =
+            &api.take_actions()[..]
+        else {
+            panic!("expected the valid cutscene and a stop");
+        };
+        assert_eq!(cutscene.name, "pan");
+        assert_eq!(player_name, "Alice");
+    }
+
+    #[test]
+    fn api_checks_client_rules_and_input_permissions() {
+        let snapshot = empty_snapshot();
+        let mut api = ServerApiImpl::new(&snapshot, "test");
+        api.set_client_game_rule("Alice", "showCoordinates", true);
+        api.set_client_game_rule("Alice", "keepInventory", true);
+        api.set_input_permission("Alice", "jump", false);
+        api.set_input_permission("Alice", "fly", false);
+        api.push_fog("Alice", "minecraft:fog_hell", "intro");
+        let [PendingAction::SetClientGameRule { rule, .. }, PendingAction::SetInputPermission { flag, enabled, .. }, PendingAction::PushFog { user_id, .. }] =
+            &api.take_actions()[..]
+        else {
+            panic!("expected the known rule, the known input and the fog");
+        };
+        assert_eq!(*rule, "showcoordinates");
+        assert_eq!((*flag, *enabled), (1 << 6, false));
+        assert_eq!(user_id, "intro");
+    }
+
+    #[test]
+    fn api_drops_appearances_with_unknown_flags() {
+        let snapshot = empty_snapshot();
+        let mut api = ServerApiImpl::new(&snapshot, "test");
+        let flags = vec![("wobbly".to_string(), true)];
+        api.set_entity_appearance(
+            7,
+            PluginEntityAppearance {
+                flags,
+                ..Default::default()
+            },
+        );
+        api.set_entity_appearance(
+            7,
+            PluginEntityAppearance {
+                scale: Some(2.0),
+                ..Default::default()
+            },
+        );
+        assert!(matches!(
+            &api.take_actions()[..],
+            [PendingAction::SetEntityAppearance { runtime_id: 7, .. }]
+        ));
+    }
+
+    #[test]
+    fn api_reads_accepted_packs_from_snapshot() {
+        let snapshot = snapshot_with_player();
+        let api = ServerApiImpl::new(&snapshot, "test");
+        assert_eq!(api.accepted_packs("Alice"), Some(vec!["aaaa".into()]));
+        assert_eq!(api.accepted_packs("Bob"), None);
+    }
+
+    #[test]
+    fn api_reads_player_arena_from_snapshot() {
+        let snapshot = snapshot_with_player();
+        let mut api = ServerApiImpl::new(&snapshot, "test");
+        assert_eq!(api.player_arena("Alice"), Some("duel".into()));
+        assert_eq!(api.player_arena("Bob"), None);
+        api.end_arena("duel", Some("Alice"));
+        assert!(matches!(
+            &api.take_actions()[..],
+            [PendingAction::EndArena { winner: Some(w), .. }] if w == "Alice"
+        ));
+    }
+
+    #[test]
+    fn api_reads_player_stats_from_snapshot() {
+        let snapshot = snapshot_with_player();
+        let api = ServerApiImpl::new(&snapshot, "test");
+        let stats = api.get_player_stats("Alice").unwrap();
+        assert_eq!(stats.deaths, 3);
+        assert_eq!(stats.playtime_ticks, 1200);
+        assert!(api.get_player_stats("Bob").is_none());
+    }
+
+    #[test]
+    fn api_queues_transfer() {
+        let snapshot = snapshot_with_player();
+        let mut api = ServerApiImpl::new(&snapshot, "test");
+        api.transfer_player("Alice", "lobby.example.net", 19133);
+        match api.take_actions().as_slice() {
+            [PendingAction::TransferPlayer {
+                player_name,
+                host,
+                port,
+            }] => {
+                assert_eq!(player_name, "Alice");
+                assert_eq!(host, "lobby.example.net");
+                assert_eq!(*port, 19133);
+            }
+            _ => panic!("expected a single TransferPlayer action"),
+        }
+    }
+
     #[test]
     fn dispatch_cancels_damage() {
         let mut mgr = PluginManager::new();
@@ -597,6 +1361,7 @@ mod tests {
                 position: (0.0, 0.0, 0.0),
                 gamemode: 0,
                 health: 20.0,
+                country: None,
             },
             damage: 5.0,
             cause: DamageCause::Attack,
@@ -605,6 +1370,33 @@ mod tests {
         assert_eq!(result, EventResult::Cancelled);
     }
 
+    #[test]
+    fn form_answers_reach_their_plugin_only() {
+        let mut mgr = PluginManager::new();
+        mgr.register(Box::new(TestPlugin::new()));
+        mgr.enable_all(&empty_snapshot());
+
+        let event = PluginEvent::FormResponse {
+            player: snapshot_with_player().players[0].clone(),
+            form_id: 1,
+            response: FormResponse::Simple { button_index: 0 },
+        };
+        let (_, actions) = mgr.dispatch_to("Other", &event, &snapshot_with_player());
+        assert!(actions.is_empty());
+
+        let (_, actions) = mgr.dispatch_to("TestPlugin", &event, &snapshot_with_player());
+        assert!(matches!(
+            &actions[..],
+            [PendingAction::ShowForm {
+                form_id: 2,
+                form_type,
+                plugin_name,
+                chained: true,
+                ..
+            }] if form_type == "simple" && plugin_name == "TestPlugin"
+        ));
+    }
+
     #[test]
     fn dispatch_continues_for_join() {
         let mut mgr = PluginManager::new();
@@ -619,6 +1411,7 @@ mod tests {
                 position: (0.0, 0.0, 0.0),
                 gamemode: 0,
                 health: 20.0,
+                country: None,
             },
         };
         let (result, actions) = mgr.dispatch(&event, &snapshot_with_player());
@@ -627,6 +1420,17 @@ mod tests {
         assert!(actions.iter().any(|a| matches!(a, PendingAction::SendMessage { player_name, .. } if player_name == "Alice")));
     }
 
+    #[test]
+    fn plugin_time_is_taken_once() {
+        let mut mgr = PluginManager::new();
+        mgr.register(Box::new(TestPlugin::new()));
+        mgr.enable_all(&empty_snapshot());
+        mgr.dispatch(&PluginEvent::ServerStarted, &empty_snapshot());
+        let timings = mgr.take_timings();
+        assert!(timings.iter().all(|(name, _)| name == "TestPlugin"));
+        assert!(mgr.take_timings().is_empty());
+    }
+
     #[test]
     fn dispatch_no_plugins_continues() {
         let mut mgr = PluginManager::new();
@@ -636,6 +1440,39 @@ mod tests {
         assert!(actions.is_empty());
     }
 
+    #[test]
+    fn dispatch_delivers_script_events_from_plugins() {
+        let mut mgr = PluginManager::new();
+        mgr.register(Box::new(TestPlugin::new()));
+        mgr.enable_all(&empty_snapshot());
+
+        let event = PluginEvent::ScriptEvent {
+            id: "test:ping".into(),
+            payload: "42".into(),
+            source: ScriptEventSource::Server,
+        };
+        let (_, actions) = mgr.dispatch(&event, &empty_snapshot());
+        match actions.as_slice() {
+            [PendingAction::BroadcastMessage { message }] => assert_eq!(message, "pong 42"),
+            _ => panic!("expected the pong broadcast only"),
+        }
+    }
+
+    #[test]
+    fn script_event_chains_are_bounded() {
+        let mut mgr = PluginManager::new();
+        mgr.register(Box::new(TestPlugin::new()));
+        mgr.enable_all(&empty_snapshot());
+
+        let event = PluginEvent::ScriptEvent {
+            id: "test:loop".into(),
+            payload: String::new(),
+            source: ScriptEventSource::Server,
+        };
+        let (_, actions) = mgr.dispatch(&event, &empty_snapshot());
+        assert!(actions.is_empty());
+    }
+
     #[test]
     fn scheduler_fires_delayed_task() {
         let mut mgr = PluginManager::new();
@@ -720,6 +1557,7 @@ mod tests {
 
         mgr.reload();
         assert!(mgr.plugin_commands.is_empty());
+        assert!(mgr.plugin_kits.is_empty());
         assert!(mgr.tasks.is_empty());
     }
 
@@ -739,19 +1577,66 @@ mod tests {
     #[test]
     fn server_api_impl_reads_snapshot() {
         let snapshot = snapshot_with_player();
-        let api = ServerApiImpl::new(&snapshot);
+        let api = ServerApiImpl::new(&snapshot, "test");
         assert_eq!(api.online_players().len(), 1);
         assert!(api.get_player("Alice").is_some());
         assert!(api.get_player("Bob").is_none());
         assert_eq!(api.get_time(), 6000);
         assert_eq!(api.get_tick(), 100);
+        assert_eq!(api.get_tps(), 20.0);
+        assert_eq!(api.get_mspt(), 4.5);
         assert!(!api.is_raining());
+        assert_eq!(api.get_world_flag("nether", "pvp"), Some(true));
+        assert_eq!(api.get_world_flag("nether", "keep_inventory"), None);
+        assert_eq!(api.get_world_flag("moon", "pvp"), None);
+    }
+
+    #[test]
+    fn server_api_impl_set_world_flag() {
+        let snapshot = empty_snapshot();
+        let mut api = ServerApiImpl::new(&snapshot, "test");
+        api.set_world_flag("end", "fall_damage", false);
+        api.set_world_flag("end", "keep_inventory", false);
+        let actions = api.take_actions();
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(
+            actions[0],
+            PendingAction::SetWorldFlag {
+                dimension: 2,
+                flag: WorldFlag::FallDamage,
+                value: false,
+            }
+        ));
+    }
+
+    #[test]
+    fn server_api_impl_moves_money_in_the_shared_ledger() {
+        let snapshot = empty_snapshot();
+        {
+            let mut economy = snapshot.economy.lock();
+            economy.open_account("xuid-alice", "Alice", 30);
+            economy.open_account("xuid-bob", "Bob", 0);
+        }
+        let mut api = ServerApiImpl::new(&snapshot, "test");
+        assert_eq!(api.currency_name(), "coins");
+        assert_eq!(api.get_balance("alice"), Some(30));
+        assert_eq!(api.get_balance("Carol"), None);
+
+        assert!(api.withdraw("Alice", 10, "shop"));
+        assert!(!api.withdraw("Alice", 100, "shop"));
+        assert!(api.transfer_money("Alice", "Bob", 5, "trade"));
+        assert!(!api.deposit("Carol", 5, "gift"));
+        // Straight in the ledger, nothing left for later
+        assert!(api.take_actions().is_empty());
+        let economy = snapshot.economy.lock();
+        assert_eq!(economy.balance("xuid-alice"), Some(15));
+        assert_eq!(economy.balance("xuid-bob"), Some(5));
     }
 
     #[test]
     fn server_api_impl_accumulates_actions() {
         let snapshot = empty_snapshot();
-        let mut api = ServerApiImpl::new(&snapshot);
+        let mut api = ServerApiImpl::new(&snapshot, "test");
         api.send_message("Alice", "Hello");
         api.broadcast_message("Hi all");
         api.set_time(12000);
@@ -0,0 +1,292 @@
+//! Pre-login checks: after the built-in bans, whitelist and country lists,
+//! a joining player's IP and identity can be run through a blocklist of IP
+//! ranges (VPNs, proxies, hosting providers) and an external HTTP webhook,
+//! either of which may refuse them with a message of its own.
+//!
+//! The webhook is sent a JSON `POST` (`name`, `xuid`, `uuid`, `ip`) and
+//! answers with `{"allow": bool, "message": "..."}`, `message` being
+//! optional. A webhook that fails or doesn't answer within the timeout lets
+//! the player in or refuses them depending on `fail_open`.
+
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::PreLoginSection;
+use crate::http::{self, HttpUrl};
+
+/// What the checks are told about a joining player.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreLoginRequest {
+    pub name: String,
+    pub xuid: String,
+    pub uuid: String,
+    pub ip: String,
+}
+
+/// Outcome of the checks: `Err` holds the message the player is refused
+/// with.
+pub type Verdict = Result<(), String>;
+
+/// Answer of the webhook.
+#[derive(Debug, Deserialize)]
+struct WebhookResponse {
+    allow: bool,
+    message: Option<String>,
+}
+
+/// A network of IP addresses, `address/prefix`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    /// Parse `1.2.3.4`, `10.0.0.0/8` or `2001:db8::/32`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (address, prefix) = match s.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix.parse::<u8>().ok()?)),
+            None => (s, None),
+        };
+        let network: IpAddr = address.trim().parse().ok()?;
+        let bits = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(bits);
+        (prefix <= bits).then_some(Self { network, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parse a blocklist: one address or network per line, `#` starting a
+/// comment. Returns the ranges and the lines that couldn't be parsed.
+pub fn parse_blocklist(contents: &str) -> (Vec<IpRange>, Vec<String>) {
+    let mut ranges = Vec::new();
+    let mut invalid = Vec::new();
+    for line in contents.lines() {
+        let entry = line.split('#').next().unwrap_or("").trim();
+        if entry.is_empty() {
+            continue;
+        }
+        match IpRange::parse(entry) {
+            Some(range) => ranges.push(range),
+            None => invalid.push(entry.to_string()),
+        }
+    }
+    (ranges, invalid)
+}
+
+/// The configured checks.
+#[derive(Debug, Default)]
+pub struct PreLoginChecks {
+    blocklist: Vec<IpRange>,
+    webhook: Option<Webhook>,
+    deny_message: String,
+}
+
+impl PreLoginChecks {
+    /// Read the blocklist and parse the webhook URL of `[prelogin]`. Errors
+    /// are logged and leave the faulty check out.
+    pub fn load(config: &PreLoginSection) -> Self {
+        let mut blocklist = Vec::new();
+        if !config.blocklist.is_empty() {
+            match std::fs::read_to_string(Path::new(&config.blocklist)) {
+                Ok(contents) => {
+                    let (ranges, invalid) = parse_blocklist(&contents);
+                    for entry in invalid {
+                        warn!("Ignoring invalid entry in {}: {entry}", config.blocklist);
+                    }
+                    info!(
+                        "Loaded {} blocked IP range(s) from {}",
+                        ranges.len(),
+                        config.blocklist
+                    );
+                    blocklist = ranges;
+                }
+                Err(e) => warn!("Failed to read blocklist {}: {e}", config.blocklist),
+            }
+        }
+        let webhook = if config.webhook_url.is_empty() {
+            None
+        } else {
+            match HttpUrl::parse(&config.webhook_url) {
+                Ok(url) => Some(Webhook {
+                    url,
+                    timeout: Duration::from_millis(config.timeout_ms),
+                    fail_open: config.fail_open,
+                }),
+                Err(e) => {
+                    warn!("Invalid pre-login webhook URL {}: {e}", config.webhook_url);
+                    None
+                }
+            }
+        };
+        Self {
+            blocklist,
+            webhook,
+            deny_message: config.deny_message.clone(),
+        }
+    }
+
+    /// Check the blocklist, which needs no waiting.
+    pub fn check_blocklist(&self, ip: IpAddr) -> Verdict {
+        if self.blocklist.iter().any(|range| range.contains(ip)) {
+            Err(self.deny_message.clone())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Whether there is a webhook to ask, which needs waiting for.
+    pub fn has_webhook(&self) -> bool {
+        self.webhook.is_some()
+    }
+
+    /// Ask the webhook, if any, applying the failure policy when it can't
+    /// answer.
+    pub async fn check_webhook(&self, request: &PreLoginRequest) -> Verdict {
+        let Some(webhook) = &self.webhook else {
+            return Ok(());
+        };
+        let answer = match tokio::time::timeout(webhook.timeout, webhook.ask(request)).await {
+            Ok(answer) => answer,
+            Err(_) => Err(format!(
+                "no answer within {} ms",
+                webhook.timeout.as_millis()
+            )),
+        };
+        match answer {
+            Ok(response) if response.allow => Ok(()),
+            Ok(response) => Err(response
+                .message
+                .filter(|m| !m.is_empty())
+                .unwrap_or_else(|| self.deny_message.clone())),
+            Err(e) if webhook.fail_open => {
+                warn!(
+                    "Pre-login webhook failed for {}, letting them in: {e}",
+                    request.name
+                );
+                Ok(())
+            }
+            Err(e) => {
+                warn!(
+                    "Pre-login webhook failed for {}, refusing them: {e}",
+                    request.name
+                );
+                Err(self.deny_message.clone())
+            }
+        }
+    }
+}
+
+/// An `http://` endpoint asked about every login.
+#[derive(Debug, Clone, PartialEq)]
+struct Webhook {
+    url: HttpUrl,
+    timeout: Duration,
+    fail_open: bool,
+}
+
+impl Webhook {
+    /// POST the request and read the answer.
+    async fn ask(&self, request: &PreLoginRequest) -> Result<WebhookResponse, String> {
+        let body = serde_json::to_string(request).map_err(|e| e.to_string())?;
+        parse_response(&http::post_json(&self.url, &body).await?)
+    }
+}
+
+/// The answer in an HTTP response.
+fn parse_response(response: &[u8]) -> Result<WebhookResponse, String> {
+    let body = http::response_body(response)?;
+    serde_json::from_str(&body).map_err(|e| format!("invalid answer: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ip_ranges() {
+        let range = IpRange::parse("10.0.0.0/8").unwrap();
+        assert!(range.contains("10.20.30.40".parse().unwrap()));
+        assert!(!range.contains("11.0.0.1".parse().unwrap()));
+        assert!(!range.contains("::1".parse().unwrap()));
+
+        let single = IpRange::parse("192.168.1.5").unwrap();
+        assert!(single.contains("192.168.1.5".parse().unwrap()));
+        assert!(!single.contains("192.168.1.6".parse().unwrap()));
+
+        let v6 = IpRange::parse("2001:db8::/32").unwrap();
+        assert!(v6.contains("2001:db8:1::1".parse().unwrap()));
+        assert!(IpRange::parse("0.0.0.0/0")
+            .unwrap()
+            .contains("8.8.8.8".parse().unwrap()));
+        assert_eq!(IpRange::parse("10.0.0.0/33"), None);
+        assert_eq!(IpRange::parse("not an ip"), None);
+    }
+
+    #[test]
+    fn blocklist_skips_comments_and_reports_bad_lines() {
+        let (ranges, invalid) = parse_blocklist("# VPN ranges\n10.0.0.0/8 # test\n\nbogus\n");
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(invalid, vec!["bogus".to_string()]);
+    }
+
+    #[test]
+    fn webhook_responses() {
+        let allowed = parse_response(
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n{\"allow\":true}",
+        )
+        .unwrap();
+        assert!(allowed.allow);
+        let denied =
+            parse_response(b"HTTP/1.1 200 OK\r\n\r\n{\"allow\":false,\"message\":\"VPN\"}")
+                .unwrap();
+        assert_eq!(denied.message.as_deref(), Some("VPN"));
+        assert!(parse_response(b"HTTP/1.1 500 Oops\r\n\r\n").is_err());
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\n\r\nnot json").is_err());
+    }
+
+    #[test]
+    fn unreachable_webhook_follows_the_failure_policy() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let request = PreLoginRequest {
+            name: "Steve".into(),
+            xuid: String::new(),
+            uuid: String::new(),
+            ip: "127.0.0.1".into(),
+        };
+        let mut config = PreLoginSection {
+            // Nothing listens on port 1
+            webhook_url: "http://127.0.0.1:1/".into(),
+            ..Default::default()
+        };
+        let checks = PreLoginChecks::load(&config);
+        assert_eq!(runtime.block_on(checks.check_webhook(&request)), Ok(()));
+
+        config.fail_open = false;
+        let checks = PreLoginChecks::load(&config);
+        assert_eq!(
+            runtime.block_on(checks.check_webhook(&request)),
+            Err(config.deny_message.clone())
+        );
+    }
+}
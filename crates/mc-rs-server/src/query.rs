@@ -20,6 +20,10 @@ pub struct ServerStats {
     pub host_port: u16,
     pub host_ip: String,
     pub player_names: Vec<String>,
+    /// Recent ticks per second.
+    pub tps: f64,
+    /// Recent mean milliseconds per tick.
+    pub mspt: f64,
     pub version: String,
 }
 
@@ -34,6 +38,8 @@ impl Default for ServerStats {
             host_port: 19132,
             host_ip: "0.0.0.0".into(),
             player_names: Vec::new(),
+            tps: crate::tps::TARGET_TPS,
+            mspt: 0.0,
             version: mc_rs_proto::packets::game_version_for_protocol(
                 mc_rs_proto::packets::PROTOCOL_VERSION,
             )
@@ -171,6 +177,8 @@ fn build_full_stat(session_id: i32, stats: &ServerStats) -> Vec<u8> {
         ("maxplayers", &stats.max_players.to_string()),
         ("hostport", &stats.host_port.to_string()),
         ("hostip", stats.host_ip.as_str()),
+        ("tps", &format!("{:.1}", stats.tps)),
+        ("mspt", &format!("{:.2}", stats.mspt)),
     ];
     for (key, val) in &kvs {
         push_cstring(&mut resp, key);
@@ -210,6 +218,8 @@ mod tests {
             host_port: 19132,
             host_ip: "127.0.0.1".into(),
             player_names: vec![],
+            tps: 20.0,
+            mspt: 3.5,
             version: mc_rs_proto::packets::game_version_for_protocol(
                 mc_rs_proto::packets::PROTOCOL_VERSION,
             )
@@ -231,6 +241,8 @@ mod tests {
             host_port: 19132,
             host_ip: "127.0.0.1".into(),
             player_names: vec!["Alice".into()],
+            tps: 18.3,
+            mspt: 61.0,
             version: mc_rs_proto::packets::game_version_for_protocol(
                 mc_rs_proto::packets::PROTOCOL_VERSION,
             )
@@ -242,6 +254,7 @@ mod tests {
         // Should contain "Alice" somewhere
         let resp_str = String::from_utf8_lossy(&resp);
         assert!(resp_str.contains("Alice"));
+        assert!(resp_str.contains("tps\x0018.3\x00mspt\x0061.00\x00"));
     }
 
     #[test]
@@ -0,0 +1,163 @@
+//! Chest shops: a `[Shop]` sign on a chest, persisted in `shops.json`
+//! inside the world directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use mc_rs_game::shop::ShopSign;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+const SHOPS_FILE: &str = "shops.json";
+
+/// A player's shop in the overworld.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Shop {
+    /// Position of the shop sign.
+    pub sign: [i32; 3],
+    /// Position of the chest holding the stock.
+    pub chest: [i32; 3],
+    /// Economy account ID of the owner.
+    pub owner: String,
+    /// Owner's name when the shop was made.
+    pub owner_name: String,
+    pub item: String,
+    /// Items per trade.
+    pub amount: u16,
+    /// What a buyer pays, if the shop sells.
+    #[serde(default)]
+    pub buy: Option<u64>,
+    /// What a seller gets, if the shop buys.
+    #[serde(default)]
+    pub sell: Option<u64>,
+}
+
+impl Shop {
+    pub fn new(
+        sign: [i32; 3],
+        chest: [i32; 3],
+        owner: &str,
+        owner_name: &str,
+        terms: ShopSign,
+    ) -> Self {
+        Self {
+            sign,
+            chest,
+            owner: owner.to_string(),
+            owner_name: owner_name.to_string(),
+            item: terms.item,
+            amount: terms.amount,
+            buy: terms.buy,
+            sell: terms.sell,
+        }
+    }
+}
+
+/// The shops of a world.
+pub struct ShopManager {
+    shops: Vec<Shop>,
+    path: PathBuf,
+}
+
+impl ShopManager {
+    /// Load `shops.json` from the world directory. A missing file means no shops.
+    pub fn load(world_dir: &Path) -> Self {
+        let path = world_dir.join(SHOPS_FILE);
+        let shops: Vec<Shop> = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Failed to parse {}: {e}", path.display());
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        if !shops.is_empty() {
+            info!("Loaded {} shop(s)", shops.len());
+        }
+        Self { shops, path }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string_pretty(&self.shops) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&self.path, json) {
+                    warn!("Failed to save {}: {e}", self.path.display());
+                }
+            }
+            Err(e) => warn!("Failed to serialize shops: {e}"),
+        }
+    }
+
+    /// The shop whose sign is at `pos`.
+    pub fn at_sign(&self, pos: [i32; 3]) -> Option<&Shop> {
+        self.shops.iter().find(|s| s.sign == pos)
+    }
+
+    /// The shops selling out of the chest at `pos`.
+    pub fn using_chest(&self, pos: [i32; 3]) -> impl Iterator<Item = &Shop> {
+        self.shops.iter().filter(move |s| s.chest == pos)
+    }
+
+    /// Add a shop and save the file.
+    pub fn add(&mut self, shop: Shop) {
+        self.shops.retain(|s| s.sign != shop.sign);
+        self.shops.push(shop);
+        self.save();
+    }
+
+    /// Close the shops whose sign or chest is at `pos`, saving the file.
+    /// Returns how many were closed.
+    pub fn remove_at(&mut self, pos: [i32; 3]) -> usize {
+        let before = self.shops.len();
+        self.shops.retain(|s| s.sign != pos && s.chest != pos);
+        let removed = before - self.shops.len();
+        if removed > 0 {
+            self.save();
+        }
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mc_rs_shops_{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn shop(sign: [i32; 3], chest: [i32; 3]) -> Shop {
+        let terms = ShopSign {
+            amount: 16,
+            item: "minecraft:diamond".into(),
+            buy: Some(10),
+            sell: None,
+        };
+        Shop::new(sign, chest, "xuid-1", "Alice", terms)
+    }
+
+    #[test]
+    fn save_reload_and_close() {
+        let dir = temp_dir();
+        let mut mgr = ShopManager::load(&dir);
+        mgr.add(shop([0, 5, 0], [0, 4, 0]));
+        mgr.add(shop([1, 4, 0], [0, 4, 0]));
+        mgr.add(shop([9, 5, 9], [9, 4, 9]));
+
+        let reloaded = ShopManager::load(&dir);
+        assert_eq!(
+            reloaded.at_sign([0, 5, 0]),
+            Some(&shop([0, 5, 0], [0, 4, 0]))
+        );
+        assert_eq!(reloaded.using_chest([0, 4, 0]).count(), 2);
+
+        // Breaking the chest closes both of its shops
+        assert_eq!(mgr.remove_at([0, 4, 0]), 2);
+        assert_eq!(mgr.remove_at([9, 5, 9]), 1);
+        assert_eq!(mgr.remove_at([9, 5, 9]), 0);
+        assert!(ShopManager::load(&dir).at_sign([9, 5, 9]).is_none());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,131 @@
+//! Per-player statistics: blocks mined, mobs killed, distance walked, deaths
+//! and playtime. Persisted with the player data and shown by `/stats`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Largest per-tick horizontal move counted as walking (filters teleports).
+const MAX_WALK_STEP: f32 = 1.0;
+
+/// Cumulative statistics for a single player.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    /// Blocks broken, keyed by block name (e.g. `minecraft:stone`).
+    #[serde(default)]
+    pub blocks_mined: HashMap<String, u64>,
+    /// Mobs killed, keyed by entity type (e.g. `minecraft:zombie`).
+    #[serde(default)]
+    pub mobs_killed: HashMap<String, u64>,
+    /// Horizontal distance walked on the ground, in centimetres.
+    #[serde(default)]
+    pub distance_walked_cm: u64,
+    #[serde(default)]
+    pub deaths: u64,
+    /// Game ticks spent online.
+    #[serde(default)]
+    pub playtime_ticks: u64,
+}
+
+impl PlayerStats {
+    pub fn record_block_mined(&mut self, block_name: &str) {
+        *self.blocks_mined.entry(block_name.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn record_mob_killed(&mut self, mob_type: &str) {
+        *self.mobs_killed.entry(mob_type.to_string()).or_insert(0) += 1;
+    }
+
+    /// Add a horizontal movement step. Steps larger than a block are ignored.
+    pub fn record_walk(&mut self, dx: f32, dz: f32) {
+        let step = (dx * dx + dz * dz).sqrt();
+        if step.is_finite() && step <= MAX_WALK_STEP {
+            self.distance_walked_cm += (step * 100.0).round() as u64;
+        }
+    }
+
+    pub fn total_blocks_mined(&self) -> u64 {
+        self.blocks_mined.values().sum()
+    }
+
+    pub fn total_mobs_killed(&self) -> u64 {
+        self.mobs_killed.values().sum()
+    }
+
+    /// Distance walked in whole blocks.
+    pub fn distance_walked_blocks(&self) -> u64 {
+        self.distance_walked_cm / 100
+    }
+
+    /// Playtime in whole seconds (20 ticks per second).
+    pub fn playtime_secs(&self) -> u64 {
+        self.playtime_ticks / 20
+    }
+}
+
+/// Format seconds as `1h 02m 03s`, dropping leading zero units.
+pub fn format_duration(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, (secs / 60) % 60, secs % 60);
+    if h > 0 {
+        format!("{h}h {m:02}m {s:02}s")
+    } else if m > 0 {
+        format!("{m}m {s:02}s")
+    } else {
+        format!("{s}s")
+    }
+}
+
+/// Return the `n` entries with the highest counts, ties broken by name.
+pub fn top_entries(counts: &HashMap<String, u64>, n: usize) -> Vec<(&str, u64)> {
+    let mut entries: Vec<(&str, u64)> = counts.iter().map(|(k, &v)| (k.as_str(), v)).collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    entries.truncate(n);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_counts() {
+        let mut stats = PlayerStats::default();
+        stats.record_block_mined("minecraft:stone");
+        stats.record_block_mined("minecraft:stone");
+        stats.record_block_mined("minecraft:dirt");
+        stats.record_mob_killed("minecraft:zombie");
+        assert_eq!(stats.blocks_mined["minecraft:stone"], 2);
+        assert_eq!(stats.total_blocks_mined(), 3);
+        assert_eq!(stats.total_mobs_killed(), 1);
+    }
+
+    #[test]
+    fn walk_ignores_teleports() {
+        let mut stats = PlayerStats::default();
+        stats.record_walk(0.3, 0.4);
+        assert_eq!(stats.distance_walked_cm, 50);
+        stats.record_walk(50.0, 0.0);
+        assert_eq!(stats.distance_walked_cm, 50);
+    }
+
+    #[test]
+    fn duration_formatting() {
+        assert_eq!(format_duration(5), "5s");
+        assert_eq!(format_duration(125), "2m 05s");
+        assert_eq!(format_duration(3723), "1h 02m 03s");
+    }
+
+    #[test]
+    fn top_entries_sorted() {
+        let counts = HashMap::from([
+            ("minecraft:dirt".to_string(), 3),
+            ("minecraft:stone".to_string(), 10),
+            ("minecraft:cobblestone".to_string(), 3),
+        ]);
+        let top = top_entries(&counts, 2);
+        assert_eq!(
+            top,
+            vec![("minecraft:stone", 10), ("minecraft:cobblestone", 3)]
+        );
+    }
+}
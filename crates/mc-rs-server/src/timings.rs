@@ -0,0 +1,245 @@
+//! Tick profiling: time spent in each part of the server over a sliding
+//! window of recent ticks, for `/timings report`.
+//!
+//! Sections nest: time spent in an inner section (e.g. redstone updates
+//! during packet handling) is charged to it alone, not to the outer one.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Time one tick may take at 20 TPS.
+pub const TICK_BUDGET: Duration = Duration::from_millis(50);
+/// Number of ticks kept (30 seconds at full speed).
+const WINDOW: usize = 600;
+
+// Section names.
+pub const PACKETS: &str = "packets";
+pub const CHUNKS: &str = "chunks";
+pub const MOB_AI: &str = "mob AI";
+pub const REDSTONE: &str = "redstone";
+pub const BLOCK_UPDATES: &str = "block updates";
+pub const BLOCK_ENTITIES: &str = "block entities";
+pub const ENTITIES: &str = "projectiles and drops";
+pub const PLAYERS: &str = "players";
+pub const FUNCTIONS: &str = "functions";
+pub const STORAGE: &str = "storage";
+pub const SENDING: &str = "packet sending";
+/// The rest of the tick, outside any other section.
+pub const TICK: &str = "tick";
+
+/// Section name of a plugin's callbacks.
+pub fn plugin_section(name: &str) -> String {
+    format!("plugin {name}")
+}
+
+/// Sections timed since the previous tick.
+#[derive(Default)]
+struct TickSample {
+    /// Duration of the tick itself.
+    duration: Duration,
+    sections: HashMap<String, Duration>,
+}
+
+/// Time spent in one section over the window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionTimings {
+    pub name: String,
+    pub total: Duration,
+    /// Most spent in the section in one tick.
+    pub max: Duration,
+}
+
+/// Records section timings and keeps the last [`WINDOW`] ticks of them.
+#[derive(Default)]
+pub struct TickProfiler {
+    ticks: VecDeque<TickSample>,
+    /// Sections timed since the last tick finished.
+    current: HashMap<String, Duration>,
+    /// Open sections, innermost last, with when each last resumed.
+    open: Vec<(String, Instant)>,
+    /// Start of the running tick, and what was timed before it began.
+    tick: Option<(Instant, HashMap<String, Duration>)>,
+}
+
+impl TickProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start timing `section` at `now`, pausing the enclosing one.
+    pub fn enter(&mut self, section: &str, now: Instant) {
+        if let Some((outer, resumed)) = self.open.last() {
+            *self.current.entry(outer.clone()).or_default() += now.duration_since(*resumed);
+        }
+        self.open.push((section.to_string(), now));
+    }
+
+    /// Stop timing the innermost section at `now`, resuming the enclosing one.
+    pub fn exit(&mut self, now: Instant) {
+        let Some((section, resumed)) = self.open.pop() else {
+            return;
+        };
+        *self.current.entry(section).or_default() += now.duration_since(resumed);
+        if let Some((_, resumed)) = self.open.last_mut() {
+            *resumed = now;
+        }
+    }
+
+    /// Charge `elapsed`, spent inside the innermost open section, to
+    /// `section` instead.
+    pub fn record_within(&mut self, section: &str, elapsed: Duration) {
+        *self.current.entry(section.to_string()).or_default() += elapsed;
+        if let Some((_, resumed)) = self.open.last_mut() {
+            *resumed += elapsed;
+        }
+    }
+
+    /// Start a tick at `now`, in the [`TICK`] section.
+    pub fn start_tick(&mut self, now: Instant) {
+        self.tick = Some((now, std::mem::take(&mut self.current)));
+        self.enter(TICK, now);
+    }
+
+    /// Finish the running tick at `now`. Returns how long it took and the
+    /// sections timed during it, slowest first.
+    pub fn finish_tick(&mut self, now: Instant) -> (Duration, Vec<(String, Duration)>) {
+        self.exit(now);
+        let Some((started, before)) = self.tick.take() else {
+            return (Duration::ZERO, Vec::new());
+        };
+        let during = std::mem::take(&mut self.current);
+        let mut hot: Vec<(String, Duration)> =
+            during.iter().map(|(s, d)| (s.clone(), *d)).collect();
+        hot.sort_by_key(|&(_, elapsed)| std::cmp::Reverse(elapsed));
+
+        let mut sections = before;
+        for (section, elapsed) in during {
+            *sections.entry(section).or_default() += elapsed;
+        }
+        let duration = now.duration_since(started);
+        if self.ticks.len() == WINDOW {
+            self.ticks.pop_front();
+        }
+        self.ticks.push_back(TickSample { duration, sections });
+        (duration, hot)
+    }
+
+    /// Forget every recorded tick.
+    pub fn reset(&mut self) {
+        self.ticks.clear();
+    }
+
+    /// Number of ticks in the window.
+    pub fn tick_count(&self) -> usize {
+        self.ticks.len()
+    }
+
+    /// Mean and longest tick duration over the window, and how many ticks
+    /// went over [`TICK_BUDGET`].
+    pub fn tick_summary(&self) -> (Duration, Duration, usize) {
+        if self.ticks.is_empty() {
+            return (Duration::ZERO, Duration::ZERO, 0);
+        }
+        let total: Duration = self.ticks.iter().map(|t| t.duration).sum();
+        let max = self
+            .ticks
+            .iter()
+            .map(|t| t.duration)
+            .max()
+            .unwrap_or_default();
+        let slow = self
+            .ticks
+            .iter()
+            .filter(|t| t.duration > TICK_BUDGET)
+            .count();
+        (total / self.ticks.len() as u32, max, slow)
+    }
+
+    /// Every section timed over the window, most time first.
+    pub fn sections(&self) -> Vec<SectionTimings> {
+        let mut by_name: HashMap<&str, SectionTimings> = HashMap::new();
+        for tick in &self.ticks {
+            for (name, &elapsed) in &tick.sections {
+                let entry = by_name
+                    .entry(name.as_str())
+                    .or_insert_with(|| SectionTimings {
+                        name: name.clone(),
+                        total: Duration::ZERO,
+                        max: Duration::ZERO,
+                    });
+                entry.total += elapsed;
+                entry.max = entry.max.max(elapsed);
+            }
+        }
+        let mut sections: Vec<SectionTimings> = by_name.into_values().collect();
+        sections.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+        sections
+    }
+}
+
+/// Milliseconds, for reports and logs.
+pub fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn nested_sections_are_exclusive() {
+        let mut p = TickProfiler::new();
+        let t0 = Instant::now();
+        p.enter(PACKETS, t0);
+        p.enter(REDSTONE, t0 + millis(2));
+        p.exit(t0 + millis(5));
+        // 1 ms of plugin work inside the packet handling
+        p.record_within(&plugin_section("Hello"), millis(1));
+        p.exit(t0 + millis(7));
+
+        p.start_tick(t0 + millis(10));
+        p.enter(MOB_AI, t0 + millis(11));
+        p.exit(t0 + millis(14));
+        let (duration, hot) = p.finish_tick(t0 + millis(15));
+        assert_eq!(duration, millis(5));
+        assert_eq!(hot[0], (MOB_AI.to_string(), millis(3)));
+        assert_eq!(hot[1], (TICK.to_string(), millis(2)));
+
+        let sections = p.sections();
+        let get = |name: &str| sections.iter().find(|s| s.name == name).unwrap().total;
+        assert_eq!(get(PACKETS), millis(3));
+        assert_eq!(get(REDSTONE), millis(3));
+        assert_eq!(get("plugin Hello"), millis(1));
+        assert_eq!(get(MOB_AI), millis(3));
+        assert_eq!(get(TICK), millis(2));
+    }
+
+    #[test]
+    fn summary_counts_slow_ticks_and_keeps_window() {
+        let mut p = TickProfiler::new();
+        let t0 = Instant::now();
+        for i in 0..(WINDOW as u64 + 10) {
+            let start = t0 + millis(100 * i);
+            p.start_tick(start);
+            let length = if i % 100 == 0 { 60 } else { 10 };
+            p.finish_tick(start + millis(length));
+        }
+        assert_eq!(p.tick_count(), WINDOW);
+        let (mean, max, slow) = p.tick_summary();
+        assert_eq!(max, millis(60));
+        assert_eq!(slow, 6);
+        assert!(mean > millis(10) && mean < millis(11));
+
+        let sections = p.sections();
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].max, millis(60));
+
+        p.reset();
+        assert_eq!(p.tick_count(), 0);
+        assert!(p.sections().is_empty());
+    }
+}
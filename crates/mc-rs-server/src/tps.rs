@@ -0,0 +1,187 @@
+//! Ticks-per-second measurement over a sliding window of recent ticks, and
+//! the clock deciding when the next tick is due.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Nominal game tick rate.
+pub const TARGET_TPS: f64 = 20.0;
+/// Time between two ticks at the nominal rate.
+pub const TICK_INTERVAL: Duration = Duration::from_millis(50);
+/// Number of tick timestamps kept (5 seconds at full speed).
+const WINDOW: usize = 100;
+
+/// Records tick times and reports the recent tick rate.
+#[derive(Default)]
+pub struct TpsMeter {
+    ticks: VecDeque<Instant>,
+    /// How long each of the recent ticks took to run.
+    durations: VecDeque<Duration>,
+}
+
+impl TpsMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a tick at `now`.
+    pub fn record(&mut self, now: Instant) {
+        if self.ticks.len() == WINDOW {
+            self.ticks.pop_front();
+        }
+        self.ticks.push_back(now);
+    }
+
+    /// Average ticks per second over the window, capped at [`TARGET_TPS`].
+    /// Reports the target until enough ticks were recorded.
+    pub fn tps(&self) -> f64 {
+        let (Some(first), Some(last)) = (self.ticks.front(), self.ticks.back()) else {
+            return TARGET_TPS;
+        };
+        let elapsed = last.duration_since(*first).as_secs_f64();
+        if self.ticks.len() < 2 || elapsed <= 0.0 {
+            return TARGET_TPS;
+        }
+        ((self.ticks.len() - 1) as f64 / elapsed).min(TARGET_TPS)
+    }
+
+    /// Record how long a tick took to run.
+    pub fn record_duration(&mut self, duration: Duration) {
+        if self.durations.len() == WINDOW {
+            self.durations.pop_front();
+        }
+        self.durations.push_back(duration);
+    }
+
+    /// Mean milliseconds per tick over the window, 0 before the first tick.
+    pub fn mspt(&self) -> f64 {
+        if self.durations.is_empty() {
+            return 0.0;
+        }
+        let total: Duration = self.durations.iter().sum();
+        total.as_secs_f64() * 1000.0 / self.durations.len() as f64
+    }
+}
+
+/// When the next tick is due. Ticks that overran are caught up by running
+/// the following ones back to back, up to a limit; past it, the ticks still
+/// owed are dropped and the schedule restarts from now, so the game slows
+/// down instead of bursting forever.
+pub struct TickClock {
+    next: Instant,
+    /// Ticks run back to back so far to catch up.
+    catching_up: u32,
+    /// Ticks dropped since the start.
+    skipped: u64,
+}
+
+impl TickClock {
+    /// A clock whose first tick is due at `start`.
+    pub fn new(start: Instant) -> Self {
+        Self {
+            next: start,
+            catching_up: 0,
+            skipped: 0,
+        }
+    }
+
+    /// When the next tick should run.
+    pub fn deadline(&self) -> Instant {
+        self.next
+    }
+
+    /// Ticks dropped since the start.
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Schedule the next tick, after one finished at `now`. Once more than
+    /// `max_catch_up` ticks in a row were already late, drop the ticks still
+    /// owed and return how many.
+    pub fn advance(&mut self, now: Instant, max_catch_up: u32) -> u64 {
+        self.next += TICK_INTERVAL;
+        if self.next > now {
+            self.catching_up = 0;
+            return 0;
+        }
+        if self.catching_up < max_catch_up {
+            self.catching_up += 1;
+            return 0;
+        }
+        let behind = now.duration_since(self.next);
+        let dropped = (behind.as_nanos() / TICK_INTERVAL.as_nanos()) as u64;
+        self.next = now;
+        self.catching_up = 0;
+        self.skipped += dropped;
+        dropped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn empty_meter_reports_target() {
+        assert_eq!(TpsMeter::new().tps(), TARGET_TPS);
+    }
+
+    #[test]
+    fn measures_slow_ticks() {
+        let mut meter = TpsMeter::new();
+        let start = Instant::now();
+        for i in 0..11 {
+            meter.record(start + Duration::from_millis(100 * i));
+        }
+        assert!((meter.tps() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn caps_at_target_and_keeps_window() {
+        let mut meter = TpsMeter::new();
+        let start = Instant::now();
+        for i in 0..500 {
+            meter.record(start + Duration::from_millis(10 * i));
+        }
+        assert_eq!(meter.ticks.len(), WINDOW);
+        assert_eq!(meter.tps(), TARGET_TPS);
+    }
+
+    #[test]
+    fn mspt_is_the_mean_tick_duration() {
+        let mut meter = TpsMeter::new();
+        assert_eq!(meter.mspt(), 0.0);
+        meter.record_duration(Duration::from_millis(10));
+        meter.record_duration(Duration::from_millis(30));
+        assert!((meter.mspt() - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clock_keeps_the_schedule_when_on_time() {
+        let start = Instant::now();
+        let mut clock = TickClock::new(start);
+        assert_eq!(clock.advance(start + Duration::from_millis(5), 3), 0);
+        assert_eq!(clock.deadline(), start + TICK_INTERVAL);
+    }
+
+    #[test]
+    fn clock_catches_up_then_drops_ticks() {
+        let start = Instant::now();
+        let mut clock = TickClock::new(start);
+        // One 260 ms tick: the next ticks are due right away
+        let mut now = start + Duration::from_millis(260);
+        for _ in 0..2 {
+            assert_eq!(clock.advance(now, 2), 0);
+            assert!(clock.deadline() <= now);
+            now += Duration::from_millis(1);
+        }
+        // Still behind after 2 catch-up ticks: the rest is dropped
+        assert_eq!(clock.advance(now, 2), 2);
+        assert_eq!(clock.deadline(), now);
+        assert_eq!(clock.skipped(), 2);
+        // And the schedule restarts from there
+        assert_eq!(clock.advance(now + Duration::from_millis(1), 2), 0);
+        assert_eq!(clock.deadline(), now + TICK_INTERVAL);
+    }
+}
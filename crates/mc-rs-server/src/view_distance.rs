@@ -0,0 +1,84 @@
+//! Dynamic view distance: lowering view and simulation distances while the
+//! server can't keep up, and raising them back once it recovers.
+
+use crate::config::DynamicViewSection;
+
+/// How far below their configured values view and simulation distances
+/// currently are.
+#[derive(Debug, Default)]
+pub struct ViewLimiter {
+    reduction: i32,
+}
+
+impl ViewLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Chunks currently taken off view and simulation distances.
+    pub fn reduction(&self) -> i32 {
+        self.reduction
+    }
+
+    /// Step the reduction by one chunk after measuring `tps`: up while under
+    /// `low_tps`, down once at `recover_tps`, never taking `max_distance`
+    /// (the largest distance in use) below `min_view_distance`. Returns
+    /// whether it changed.
+    pub fn update(&mut self, tps: f64, config: &DynamicViewSection, max_distance: i32) -> bool {
+        let before = self.reduction;
+        if !config.enabled {
+            self.reduction = 0;
+        } else if tps < config.low_tps {
+            let most = (max_distance - config.min_view_distance).max(0);
+            self.reduction = (self.reduction + 1).min(most);
+        } else if tps >= config.recover_tps {
+            self.reduction = (self.reduction - 1).max(0);
+        }
+        self.reduction != before
+    }
+
+    /// `distance` with the reduction applied, but not below `min` (or
+    /// `distance` itself, if it's already lower).
+    pub fn limit(&self, distance: i32, min: i32) -> i32 {
+        (distance - self.reduction).max(min.min(distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowers_while_behind_and_recovers() {
+        let config = DynamicViewSection::default();
+        let mut limiter = ViewLimiter::new();
+        assert!(!limiter.update(20.0, &config, 8));
+
+        assert!(limiter.update(15.0, &config, 8));
+        assert!(limiter.update(15.0, &config, 8));
+        assert_eq!(limiter.limit(8, config.min_view_distance), 6);
+        // Between the thresholds, it holds
+        assert!(!limiter.update(19.0, &config, 8));
+        // Never below the minimum
+        for _ in 0..10 {
+            limiter.update(5.0, &config, 8);
+        }
+        assert_eq!(limiter.reduction(), 4);
+        assert_eq!(limiter.limit(8, config.min_view_distance), 4);
+        assert_eq!(limiter.limit(3, config.min_view_distance), 3);
+        assert_eq!(limiter.limit(4, config.min_simulation_distance), 2);
+
+        assert!(limiter.update(20.0, &config, 8));
+        assert_eq!(limiter.reduction(), 3);
+    }
+
+    #[test]
+    fn disabled_limiter_restores_everything() {
+        let mut config = DynamicViewSection::default();
+        let mut limiter = ViewLimiter::new();
+        limiter.update(10.0, &config, 8);
+        config.enabled = false;
+        assert!(limiter.update(10.0, &config, 8));
+        assert_eq!(limiter.limit(8, 4), 8);
+    }
+}
@@ -0,0 +1,139 @@
+//! Warps: named locations set by operators with `/setwarp`, persisted in
+//! `warps.json` inside the world directory.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::persistence::SavedLocation;
+
+const WARPS_FILE: &str = "warps.json";
+
+/// A named teleport destination.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Warp {
+    pub location: SavedLocation,
+    /// Permission node needed to use the warp. Operators can always use it.
+    #[serde(default)]
+    pub permission: Option<String>,
+    /// Warm-up in seconds, overriding `[teleport] warmup`.
+    #[serde(default)]
+    pub warmup: Option<u64>,
+}
+
+/// The warps of a world.
+pub struct WarpManager {
+    warps: BTreeMap<String, Warp>,
+    path: PathBuf,
+}
+
+impl WarpManager {
+    /// Load `warps.json` from the world directory. A missing file means no warps.
+    pub fn load(world_dir: &Path) -> Self {
+        let path = world_dir.join(WARPS_FILE);
+        let warps: BTreeMap<String, Warp> = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Failed to parse {}: {e}", path.display());
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        if !warps.is_empty() {
+            info!("Loaded {} warp(s)", warps.len());
+        }
+        Self { warps, path }
+    }
+
+    fn save(&self) {
+        match serde_json::to_string_pretty(&self.warps) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&self.path, json) {
+                    warn!("Failed to save {}: {e}", self.path.display());
+                }
+            }
+            Err(e) => warn!("Failed to serialize warps: {e}"),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Warp> {
+        self.warps.get(name)
+    }
+
+    /// All warps, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Warp)> {
+        self.warps.iter()
+    }
+
+    /// Create or replace a warp and save the file.
+    pub fn set(&mut self, name: &str, warp: Warp) {
+        self.warps.insert(name.to_string(), warp);
+        self.save();
+    }
+
+    /// Delete a warp and save the file. Returns false if it didn't exist.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let removed = self.warps.remove(name).is_some();
+        if removed {
+            self.save();
+        }
+        removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mc_rs_warps_{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn save_and_reload() {
+        let dir = temp_dir();
+        let mut mgr = WarpManager::load(&dir);
+        assert_eq!(mgr.iter().count(), 0);
+
+        let arena = Warp {
+            location: SavedLocation {
+                dimension: 1,
+                position: [10.5, 64.0, -3.5],
+            },
+            permission: Some("mcrs.warp.arena".into()),
+            warmup: Some(0),
+        };
+        mgr.set("arena", arena.clone());
+        mgr.set(
+            "shop",
+            Warp {
+                location: SavedLocation {
+                    dimension: 0,
+                    position: [0.0, 70.0, 0.0],
+                },
+                permission: None,
+                warmup: None,
+            },
+        );
+        assert!(mgr.remove("shop"));
+        assert!(!mgr.remove("shop"));
+
+        let reloaded = WarpManager::load(&dir);
+        assert_eq!(reloaded.get("arena"), Some(&arena));
+        assert!(reloaded.get("shop").is_none());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn bad_file_loads_empty() {
+        let dir = temp_dir();
+        fs::write(dir.join(WARPS_FILE), "not json").unwrap();
+        assert_eq!(WarpManager::load(&dir).iter().count(), 0);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,372 @@
+//! Watchdog: a thread of its own that notices when the game loop stops
+//! finishing ticks, writes a crash report and optionally aborts.
+//!
+//! The game loop keeps an [`Activity`] up to date (last tick, the sections
+//! it is in, recent packets, timings, plugins); the watchdog only reads it,
+//! since the loop itself is what's stuck.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tracing::{error, info, warn};
+
+use crate::config::WatchdogSection;
+
+/// Directory crash reports are written under.
+pub const CRASH_REPORT_DIR: &str = "crash-reports";
+
+/// Packets kept for the crash report.
+const RECENT_PACKETS: usize = 64;
+
+/// How often the watchdog looks at the game loop.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A game packet received from a player.
+#[derive(Debug, Clone)]
+struct PacketRecord {
+    at: Instant,
+    addr: SocketAddr,
+    id: u32,
+    len: usize,
+}
+
+#[derive(Debug)]
+struct ActivityState {
+    last_tick: Instant,
+    tick: u64,
+    /// Tick profiler sections the loop is in, outermost first.
+    sections: Vec<String>,
+    packets: VecDeque<PacketRecord>,
+    timings: String,
+    plugins: Vec<String>,
+    stopped: bool,
+}
+
+/// What the game loop is doing, shared with the watchdog.
+#[derive(Debug)]
+pub struct Activity {
+    state: Mutex<ActivityState>,
+    save_requested: AtomicBool,
+    saved: AtomicBool,
+}
+
+impl Default for Activity {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Activity {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(ActivityState {
+                last_tick: Instant::now(),
+                tick: 0,
+                sections: Vec::new(),
+                packets: VecDeque::new(),
+                timings: String::new(),
+                plugins: Vec::new(),
+                stopped: false,
+            }),
+            save_requested: AtomicBool::new(false),
+            saved: AtomicBool::new(false),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ActivityState> {
+        // A panic while holding the lock leaves nothing half-written worth
+        // refusing to read
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Note that tick `tick` finished.
+    pub fn tick_finished(&self, tick: u64) {
+        let mut state = self.state();
+        state.last_tick = Instant::now();
+        state.tick = tick;
+    }
+
+    pub fn enter(&self, section: &str) {
+        self.state().sections.push(section.to_string());
+    }
+
+    pub fn exit(&self) {
+        self.state().sections.pop();
+    }
+
+    /// Note a game packet about to be handled.
+    pub fn record_packet(&self, addr: SocketAddr, id: u32, len: usize) {
+        let mut state = self.state();
+        if state.packets.len() == RECENT_PACKETS {
+            state.packets.pop_front();
+        }
+        state.packets.push_back(PacketRecord {
+            at: Instant::now(),
+            addr,
+            id,
+            len,
+        });
+    }
+
+    pub fn set_timings(&self, report: String) {
+        self.state().timings = report;
+    }
+
+    pub fn set_plugins(&self, plugins: Vec<String>) {
+        self.state().plugins = plugins;
+    }
+
+    /// The loop is shutting down: stalls from here on aren't reported.
+    pub fn stop(&self) {
+        self.state().stopped = true;
+    }
+
+    /// Whether the watchdog asked for the world to be saved. Asks once.
+    pub fn take_save_request(&self) -> bool {
+        self.save_requested.swap(false, Ordering::AcqRel)
+    }
+
+    pub fn confirm_saved(&self) {
+        self.saved.store(true, Ordering::Release);
+    }
+
+    /// How long since the last tick finished, as of `now`.
+    fn stalled_for(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.state().last_tick)
+    }
+}
+
+/// Start the watchdog thread.
+pub fn spawn(activity: Arc<Activity>, config: WatchdogSection) -> std::io::Result<JoinHandle<()>> {
+    std::thread::Builder::new()
+        .name("watchdog".into())
+        .spawn(move || run(&activity, &config))
+}
+
+fn run(activity: &Activity, config: &WatchdogSection) {
+    let timeout = Duration::from_secs(config.timeout.max(1));
+    let mut reported_tick = None;
+    loop {
+        std::thread::sleep(CHECK_INTERVAL);
+        if activity.state().stopped {
+            return;
+        }
+        let stalled = activity.stalled_for(Instant::now());
+        let tick = activity.state().tick;
+        if stalled < timeout || reported_tick == Some(tick) {
+            continue;
+        }
+        reported_tick = Some(tick);
+        error!(
+            "Watchdog: no tick finished for {}s (last tick {tick})",
+            stalled.as_secs()
+        );
+        match write_report(Path::new(CRASH_REPORT_DIR), activity, stalled, config) {
+            Ok(dir) => error!("Watchdog: crash report written to {}", dir.display()),
+            Err(e) => error!("Watchdog: failed to write the crash report: {e}"),
+        }
+        if !config.abort {
+            continue;
+        }
+        if config.force_save {
+            save_before_abort(activity, Duration::from_secs(config.save_timeout));
+        }
+        error!("Watchdog: aborting");
+        std::process::abort();
+    }
+}
+
+/// Ask the game loop to save the world, and wait for it to, or for `wait`.
+fn save_before_abort(activity: &Activity, wait: Duration) {
+    activity.save_requested.store(true, Ordering::Release);
+    warn!(
+        "Watchdog: waiting up to {}s for the game loop to save the world",
+        wait.as_secs()
+    );
+    let deadline = Instant::now() + wait;
+    while Instant::now() < deadline {
+        if activity.saved.load(Ordering::Acquire) {
+            info!("Watchdog: world saved");
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    error!("Watchdog: the game loop never came back, the world was not saved");
+}
+
+/// Write a crash report directory under `root`, returning its path.
+fn write_report(
+    root: &Path,
+    activity: &Activity,
+    stalled: Duration,
+    config: &WatchdogSection,
+) -> std::io::Result<PathBuf> {
+    let unix_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (summary, packets, timings, plugins) = {
+        let state = activity.state();
+        let now = Instant::now();
+        let mut summary = format!(
+            "MC-RS crash report: stalled tick\n\
+             time: {unix_time} (unix)\n\
+             last finished tick: {}\n\
+             stalled for: {:.1}s (timeout {}s)\n\
+             game loop in: {}\n",
+            state.tick,
+            stalled.as_secs_f64(),
+            config.timeout,
+            if state.sections.is_empty() {
+                "(no timed section)".to_string()
+            } else {
+                state.sections.join(" > ")
+            }
+        );
+        let next = match (config.abort, config.force_save) {
+            (false, _) => "keep running",
+            (true, false) => "abort",
+            (true, true) => "ask the game loop to save, then abort",
+        };
+        let _ = writeln!(summary, "action: {next}");
+
+        let mut packets = String::from("# newest last: seconds ago, player, packet ID, bytes\n");
+        for p in &state.packets {
+            let _ = writeln!(
+                packets,
+                "{:.3}s\t{}\t0x{:02X}\t{}",
+                now.saturating_duration_since(p.at).as_secs_f64(),
+                p.addr,
+                p.id,
+                p.len
+            );
+        }
+        let timings = if state.timings.is_empty() {
+            "No timings recorded yet\n".to_string()
+        } else {
+            format!("{}\n", state.timings)
+        };
+        let mut plugins = String::new();
+        for plugin in &state.plugins {
+            let _ = writeln!(plugins, "{plugin}");
+        }
+        (summary, packets, timings, plugins)
+    };
+
+    let dir = root.join(format!("stall-{unix_time}"));
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("report.txt"), summary)?;
+    std::fs::write(dir.join("threads.txt"), thread_dump())?;
+    std::fs::write(dir.join("packets.txt"), packets)?;
+    std::fs::write(dir.join("timings.txt"), timings)?;
+    std::fs::write(dir.join("plugins.txt"), plugins)?;
+    Ok(dir)
+}
+
+/// Every thread of the process with its state, from `/proc`. A thread's
+/// stack can't be unwound from another thread, so where the game loop is
+/// stuck is given by its timed sections in `report.txt`; the kernel stacks
+/// are included when readable (usually as root only).
+fn thread_dump() -> String {
+    let Ok(tasks) = std::fs::read_dir("/proc/self/task") else {
+        return "Thread listing is only available on Linux\n".into();
+    };
+    let mut tids: Vec<u32> = tasks
+        .filter_map(|e| e.ok()?.file_name().to_str()?.parse().ok())
+        .collect();
+    tids.sort_unstable();
+
+    let mut dump = String::new();
+    for tid in tids {
+        let read = |file: &str| {
+            std::fs::read_to_string(format!("/proc/self/task/{tid}/{file}"))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        let stat = read("stat");
+        // The state follows the parenthesized name
+        let state = stat
+            .rsplit_once(')')
+            .and_then(|(_, rest)| rest.split_whitespace().next())
+            .unwrap_or("?");
+        let _ = writeln!(
+            dump,
+            "thread {tid} \"{}\" state {state} wchan {}",
+            read("comm"),
+            read("wchan")
+        );
+        let stack = read("stack");
+        for line in stack.lines() {
+            let _ = writeln!(dump, "    {line}");
+        }
+    }
+    dump
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stall_is_time_since_last_tick() {
+        let activity = Activity::new();
+        activity.tick_finished(7);
+        let now = Instant::now();
+        assert!(activity.stalled_for(now) < Duration::from_secs(1));
+        let later = now + Duration::from_secs(90);
+        assert!(activity.stalled_for(later) >= Duration::from_secs(90));
+    }
+
+    #[test]
+    fn save_request_is_taken_once() {
+        let activity = Activity::new();
+        assert!(!activity.take_save_request());
+        activity.save_requested.store(true, Ordering::Release);
+        assert!(activity.take_save_request());
+        assert!(!activity.take_save_request());
+    }
+
+    #[test]
+    fn crash_report_has_every_file() {
+        let activity = Activity::new();
+        activity.tick_finished(1234);
+        activity.enter("tick");
+        activity.enter("redstone");
+        let addr: SocketAddr = "127.0.0.1:19133".parse().unwrap();
+        for id in 0..(RECENT_PACKETS as u32 + 6) {
+            activity.record_packet(addr, id, 10);
+        }
+        activity.set_timings("Timings over the last 20 ticks".into());
+        activity.set_plugins(vec!["Hello 1.0.0".into()]);
+
+        let root = std::env::temp_dir().join(format!("mc-rs-watchdog-{}", std::process::id()));
+        let dir = write_report(
+            &root,
+            &activity,
+            Duration::from_secs(61),
+            &WatchdogSection::default(),
+        )
+        .unwrap();
+
+        let read = |file: &str| std::fs::read_to_string(dir.join(file)).unwrap();
+        let report = read("report.txt");
+        assert!(report.contains("last finished tick: 1234"), "{report}");
+        assert!(report.contains("game loop in: tick > redstone"), "{report}");
+        let packets = read("packets.txt");
+        assert_eq!(packets.lines().count(), RECENT_PACKETS + 1);
+        assert!(packets.contains("\t0x45\t"), "{packets}");
+        assert!(!packets.contains("\t0x05\t"), "{packets}");
+        assert!(read("timings.txt").starts_with("Timings over"));
+        assert_eq!(read("plugins.txt"), "Hello 1.0.0\n");
+        if cfg!(target_os = "linux") {
+            assert!(read("threads.txt").contains("thread "));
+        }
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
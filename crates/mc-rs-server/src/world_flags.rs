@@ -0,0 +1,136 @@
+//! Per-dimension world flags: PvP, fall damage, drowning and hunger.
+
+use mc_rs_plugin_api::{DamageCause, WORLD_FLAGS, WORLD_FLAG_DIMENSIONS};
+use serde::Deserialize;
+
+/// A rule that can be turned off per dimension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldFlag {
+    Pvp,
+    FallDamage,
+    Drowning,
+    Hunger,
+}
+
+impl WorldFlag {
+    pub const ALL: [WorldFlag; 4] = [
+        WorldFlag::Pvp,
+        WorldFlag::FallDamage,
+        WorldFlag::Drowning,
+        WorldFlag::Hunger,
+    ];
+
+    /// Name used in `server.toml`, `/worldflag` and the plugin API.
+    pub fn name(self) -> &'static str {
+        WORLD_FLAGS[self as usize]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.name() == name)
+    }
+}
+
+/// Dimension ID (0=overworld, 1=nether, 2=end) from its flag name.
+pub fn dimension_from_name(name: &str) -> Option<i32> {
+    WORLD_FLAG_DIMENSIONS
+        .iter()
+        .position(|d| *d == name)
+        .map(|i| i as i32)
+}
+
+/// Flag name of a dimension ID.
+pub fn dimension_name(dim: i32) -> &'static str {
+    WORLD_FLAG_DIMENSIONS
+        .get(dim as usize)
+        .copied()
+        .unwrap_or("overworld")
+}
+
+/// Flags of one dimension. Everything is on by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct WorldFlags {
+    pub pvp: bool,
+    pub fall_damage: bool,
+    pub drowning: bool,
+    /// Hunger drains food and starving players take damage.
+    pub hunger: bool,
+}
+
+impl Default for WorldFlags {
+    fn default() -> Self {
+        Self {
+            pvp: true,
+            fall_damage: true,
+            drowning: true,
+            hunger: true,
+        }
+    }
+}
+
+impl WorldFlags {
+    pub fn get(&self, flag: WorldFlag) -> bool {
+        match flag {
+            WorldFlag::Pvp => self.pvp,
+            WorldFlag::FallDamage => self.fall_damage,
+            WorldFlag::Drowning => self.drowning,
+            WorldFlag::Hunger => self.hunger,
+        }
+    }
+
+    pub fn set(&mut self, flag: WorldFlag, value: bool) {
+        match flag {
+            WorldFlag::Pvp => self.pvp = value,
+            WorldFlag::FallDamage => self.fall_damage = value,
+            WorldFlag::Drowning => self.drowning = value,
+            WorldFlag::Hunger => self.hunger = value,
+        }
+    }
+
+    /// Whether a player may take damage from `cause`. Attacks count as PvP
+    /// when `by_player` is set.
+    pub fn allows_damage(&self, cause: DamageCause, by_player: bool) -> bool {
+        match cause {
+            DamageCause::Attack if by_player => self.pvp,
+            DamageCause::Fall => self.fall_damage,
+            DamageCause::Drowning => self.drowning,
+            DamageCause::Starvation => self.hunger,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flag_names_round_trip() {
+        for flag in WorldFlag::ALL {
+            assert_eq!(WorldFlag::from_name(flag.name()), Some(flag));
+        }
+        assert_eq!(
+            WorldFlag::from_name("fall_damage"),
+            Some(WorldFlag::FallDamage)
+        );
+        assert_eq!(WorldFlag::from_name("keep_inventory"), None);
+        assert_eq!(dimension_from_name("nether"), Some(1));
+        assert_eq!(dimension_from_name("the_end"), None);
+        assert_eq!(dimension_name(2), "end");
+    }
+
+    #[test]
+    fn damage_gated_by_flags() {
+        let mut flags = WorldFlags::default();
+        assert!(flags.allows_damage(DamageCause::Attack, true));
+        flags.set(WorldFlag::Pvp, false);
+        flags.set(WorldFlag::Hunger, false);
+        assert!(!flags.get(WorldFlag::Pvp));
+        assert!(!flags.allows_damage(DamageCause::Attack, true));
+        // Mobs can still hit
+        assert!(flags.allows_damage(DamageCause::Attack, false));
+        assert!(!flags.allows_damage(DamageCause::Starvation, false));
+        assert!(flags.allows_damage(DamageCause::Fall, false));
+        assert!(flags.allows_damage(DamageCause::Lava, false));
+    }
+}
@@ -0,0 +1,166 @@
+//! Extra worlds: the dimension IDs they are stored under.
+//!
+//! The main world uses dimensions 0-2 (overworld, nether, end). Each world
+//! from `[worlds.<name>]` gets its own ID from 3 up, recorded in
+//! `worlds.json` in the main world directory so its chunks and players stay
+//! in place when worlds are added or removed from the config. Their time of
+//! day is kept in `world_times.json`, the main world's being in `level.dat`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use tracing::warn;
+
+const WORLDS_FILE: &str = "worlds.json";
+const TIMES_FILE: &str = "world_times.json";
+
+/// First dimension ID given to an extra world.
+pub const FIRST_EXTRA_DIMENSION: i32 = 3;
+
+/// Bedrock dimension a generator produces, which clients render the world as.
+pub fn base_dimension(generator: &str) -> i32 {
+    match generator.to_lowercase().as_str() {
+        "nether" => 1,
+        "end" => 2,
+        _ => 0,
+    }
+}
+
+/// Dimension identifying the world a dimension belongs to: 0 for the main
+/// world's three dimensions, the dimension itself for an extra world.
+pub fn world_dimension(dim: i32) -> i32 {
+    if dim < FIRST_EXTRA_DIMENSION {
+        0
+    } else {
+        dim
+    }
+}
+
+/// Dimension IDs of the given worlds, reusing the ones recorded in
+/// `worlds.json` and recording new ones.
+pub fn assign_dimensions<'a>(
+    world_dir: &Path,
+    names: impl IntoIterator<Item = &'a String>,
+) -> BTreeMap<String, i32> {
+    let path = world_dir.join(WORLDS_FILE);
+    let mut ids: BTreeMap<String, i32> = match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Failed to parse {}: {e}", path.display());
+            BTreeMap::new()
+        }),
+        Err(_) => BTreeMap::new(),
+    };
+
+    let mut names: Vec<&String> = names.into_iter().collect();
+    names.sort();
+    let mut changed = false;
+    for name in &names {
+        if !ids.contains_key(*name) {
+            let next = ids
+                .values()
+                .max()
+                .map_or(FIRST_EXTRA_DIMENSION, |max| max + 1);
+            ids.insert((*name).clone(), next);
+            changed = true;
+        }
+    }
+    if changed {
+        match serde_json::to_string_pretty(&ids) {
+            Ok(json) => {
+                if let Err(e) = fs::write(&path, json) {
+                    warn!("Failed to save {}: {e}", path.display());
+                }
+            }
+            Err(e) => warn!("Failed to serialize world IDs: {e}"),
+        }
+    }
+
+    // Worlds removed from the config keep their ID in the file
+    ids.retain(|name, _| names.contains(&name));
+    ids
+}
+
+/// Saved time of day of each extra world. A missing file means none.
+pub fn load_times(world_dir: &Path) -> BTreeMap<String, i64> {
+    let path = world_dir.join(TIMES_FILE);
+    match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            warn!("Failed to parse {}: {e}", path.display());
+            BTreeMap::new()
+        }),
+        Err(_) => BTreeMap::new(),
+    }
+}
+
+/// Save the time of day of each extra world.
+pub fn save_times(world_dir: &Path, times: &BTreeMap<String, i64>) {
+    let path = world_dir.join(TIMES_FILE);
+    match serde_json::to_string_pretty(times) {
+        Ok(json) => {
+            if let Err(e) = fs::write(&path, json) {
+                warn!("Failed to save {}: {e}", path.display());
+            }
+        }
+        Err(e) => warn!("Failed to serialize world times: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mc_rs_worlds_{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn ids_are_stable() {
+        let dir = temp_dir();
+        let names = ["mining".to_string(), "lobby".to_string()];
+        let ids = assign_dimensions(&dir, &names);
+        assert_eq!(ids["lobby"], 3);
+        assert_eq!(ids["mining"], 4);
+
+        // Removing a world and adding another doesn't reuse or shift IDs
+        let names = ["mining".to_string(), "arena".to_string()];
+        let ids = assign_dimensions(&dir, &names);
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids["mining"], 4);
+        assert_eq!(ids["arena"], 5);
+
+        let names = ["lobby".to_string()];
+        assert_eq!(assign_dimensions(&dir, &names)["lobby"], 3);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn times_round_trip() {
+        let dir = temp_dir();
+        assert!(load_times(&dir).is_empty());
+        let times = BTreeMap::from([("lobby".to_string(), 6000), ("mining".to_string(), 13500)]);
+        save_times(&dir, &times);
+        assert_eq!(load_times(&dir), times);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn base_dimensions() {
+        assert_eq!(base_dimension("default"), 0);
+        assert_eq!(base_dimension("flat"), 0);
+        assert_eq!(base_dimension("Nether"), 1);
+        assert_eq!(base_dimension("end"), 2);
+    }
+
+    #[test]
+    fn world_dimensions() {
+        assert_eq!(world_dimension(0), 0);
+        assert_eq!(world_dimension(1), 0);
+        assert_eq!(world_dimension(2), 0);
+        assert_eq!(world_dimension(4), 4);
+    }
+}
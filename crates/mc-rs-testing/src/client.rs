@@ -0,0 +1,457 @@
+//! A scripted client speaking the game protocol to a [`TestServer`].
+
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+use mc_rs_proto::batch::{decode_batch, encode_single, BatchConfig};
+use mc_rs_proto::codec::{ProtoDecode, ProtoEncode};
+use mc_rs_proto::compression::CompressionAlgorithm;
+use mc_rs_proto::error::ProtoError;
+use mc_rs_proto::packets::inventory_transaction::{
+    InventoryTransaction, UseItemAction, UseItemData, UseItemOnEntityAction, UseItemOnEntityData,
+};
+use mc_rs_proto::packets::item_stack_request::{StackAction, StackRequest};
+use mc_rs_proto::packets::player_auth_input::input_flags;
+use mc_rs_proto::packets::{
+    self, id, CommandOrigin, CommandRequest, ItemStackRequest, LoginPacket, NetworkSettings,
+    PlayStatus, PlayStatusType, PlayerAction, PlayerActionType, PlayerAuthInput,
+    RequestChunkRadius, RequestNetworkSettings, ResourcePackClientResponse,
+    ResourcePackResponseStatus, SetLocalPlayerAsInitialized, Text, TextType,
+};
+use mc_rs_proto::protocol::ProtocolAdapter;
+use mc_rs_proto::types::{BlockPos, Uuid, VarInt, VarLong, VarUInt32, VarUInt64, Vec2, Vec3};
+
+use crate::server::TestServer;
+
+/// Chunk radius clients ask for: enough to stand and walk around spawn
+/// while keeping login fast.
+pub const CHUNK_RADIUS: i32 = 2;
+
+/// Furthest a client moves in one PlayerAuthInput, under the server's
+/// per-tick speed limit.
+const MAX_STEP: f32 = 0.5;
+
+/// A game packet received from the server.
+#[derive(Debug, Clone)]
+pub struct ReceivedPacket {
+    pub id: u32,
+    /// The packet body, after its ID.
+    pub body: Bytes,
+}
+
+impl ReceivedPacket {
+    /// Decode the body as `T`.
+    pub fn decode<T: ProtoDecode>(&self) -> Result<T, ProtoError> {
+        T::proto_decode(&mut self.body.clone())
+    }
+}
+
+/// A fake Bedrock client. Every packet it sends goes through mc-rs-proto's
+/// encoders and batching, and every packet the server sends it is kept, in
+/// order, until [`clear`](Self::clear)ed.
+pub struct FakeClient {
+    pub addr: SocketAddr,
+    pub name: String,
+    /// Runtime ID of the player, from StartGame.
+    pub runtime_id: u64,
+    /// Eye position the client last reported (or was spawned at).
+    pub position: Vec3,
+    tick: u64,
+    /// ID of the next ItemStackRequest; like the real client's, these are
+    /// negative.
+    next_request_id: i32,
+    batch_config: BatchConfig,
+    protocol: ProtocolAdapter,
+    received: Vec<ReceivedPacket>,
+    /// Batches the received packets came in.
+    batches: usize,
+}
+
+impl FakeClient {
+    pub(crate) fn new(name: &str, addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            name: name.to_string(),
+            runtime_id: 0,
+            position: Vec3::ZERO,
+            tick: 0,
+            next_request_id: -1,
+            batch_config: BatchConfig::default(),
+            protocol: ProtocolAdapter::default(),
+            received: Vec::new(),
+            batches: 0,
+        }
+    }
+
+    /// Send one packet and collect whatever the server sent back.
+    pub async fn send(
+        &mut self,
+        server: &mut TestServer,
+        packet_id: u32,
+        packet: &impl ProtoEncode,
+    ) {
+        let sub_packet = self.protocol.encode(packet_id, packet);
+        let batch = encode_single(sub_packet, &self.batch_config).expect("batch encodes");
+        server.deliver(self.addr, batch).await;
+        self.receive(server);
+    }
+
+    /// Collect the packets the server sent since the last call.
+    pub fn receive(&mut self, server: &mut TestServer) {
+        for payload in server.take_inbox(self.addr) {
+            // Strip the 0xFE game packet marker
+            let batch = payload.slice(1..);
+            self.batches += 1;
+            let sub_packets = decode_batch(batch, &self.batch_config)
+                .unwrap_or_else(|e| panic!("{}: undecodable batch from server: {e}", self.name));
+            for mut body in sub_packets {
+                let packet_id = VarUInt32::proto_decode(&mut body).expect("packet ID").0;
+                if packet_id == id::NETWORK_SETTINGS {
+                    let settings = NetworkSettings::proto_decode(&mut body.clone())
+                        .expect("NetworkSettings decodes");
+                    self.batch_config.compression_enabled = true;
+                    self.batch_config.compression =
+                        CompressionAlgorithm::from_u16(settings.compression_algorithm)
+                            .unwrap_or(CompressionAlgorithm::Zlib);
+                    self.batch_config.compression_threshold =
+                        settings.compression_threshold as usize;
+                }
+                self.received.push(ReceivedPacket {
+                    id: packet_id,
+                    body,
+                });
+            }
+        }
+    }
+
+    /// Every packet received, oldest first.
+    pub fn received(&self) -> &[ReceivedPacket] {
+        &self.received
+    }
+
+    /// The received packets with the given ID.
+    pub fn packets(&self, packet_id: u32) -> impl Iterator<Item = &ReceivedPacket> {
+        self.received.iter().filter(move |p| p.id == packet_id)
+    }
+
+    /// Decode every received packet with the given ID as `T`.
+    pub fn decoded<T: ProtoDecode>(&self, packet_id: u32) -> Vec<T> {
+        self.packets(packet_id)
+            .map(|p| {
+                p.decode().unwrap_or_else(|e| {
+                    panic!(
+                        "{}: packet 0x{packet_id:02X} does not decode: {e}",
+                        self.name
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Messages of the received Text packets.
+    pub fn messages(&self) -> Vec<String> {
+        self.decoded::<Text>(id::TEXT)
+            .into_iter()
+            .map(|t| t.message)
+            .collect()
+    }
+
+    /// Number of batches the received packets came in.
+    pub fn batches(&self) -> usize {
+        self.batches
+    }
+
+    /// Positions of the chunks received in LevelChunk packets.
+    pub fn chunk_positions(&self) -> Vec<(i32, i32)> {
+        self.packets(id::LEVEL_CHUNK)
+            .map(|p| {
+                let mut body = p.body.clone();
+                let x = VarInt::proto_decode(&mut body).expect("chunk X");
+                let z = VarInt::proto_decode(&mut body).expect("chunk Z");
+                (x.0, z.0)
+            })
+            .collect()
+    }
+
+    /// Forget the packets received so far.
+    pub fn clear(&mut self) {
+        self.received.clear();
+        self.batches = 0;
+    }
+
+    // -----------------------------------------------------------------------
+    // Login
+    // -----------------------------------------------------------------------
+
+    /// Ask for network settings and send the Login packet, which is as far
+    /// as a player the server refuses gets.
+    pub async fn send_login(&mut self, server: &mut TestServer) {
+        self.send(
+            server,
+            id::REQUEST_NETWORK_SETTINGS,
+            &RequestNetworkSettings {
+                protocol_version: packets::PROTOCOL_VERSION,
+            },
+        )
+        .await;
+
+        let port = self.addr.port();
+        let login = LoginPacket::offline(
+            packets::PROTOCOL_VERSION,
+            &self.name,
+            &format!("{}", 2_535_400_000_000_000u64 + port as u64),
+            &format!("00000000-0000-4000-8000-{port:012x}"),
+            &format!("mc-rs-testing-{port}"),
+        );
+        self.send(server, id::LOGIN, &login).await;
+    }
+
+    /// Go through the offline-mode login sequence up to being in game.
+    /// Panics if the server refuses the player.
+    pub(crate) async fn login(&mut self, server: &mut TestServer) {
+        self.send_login(server).await;
+
+        for status in [
+            ResourcePackResponseStatus::HaveAllPacks,
+            ResourcePackResponseStatus::Completed,
+        ] {
+            self.send(
+                server,
+                id::RESOURCE_PACK_CLIENT_RESPONSE,
+                &ResourcePackClientResponse {
+                    status,
+                    resource_pack_ids: Vec::new(),
+                },
+            )
+            .await;
+        }
+
+        let start_game = self
+            .packets(id::START_GAME)
+            .next()
+            .cloned()
+            .unwrap_or_else(|| panic!("{} was not sent StartGame: {:?}", self.name, self.ids()));
+        let mut body = start_game.body;
+        let _entity_unique_id = VarLong::proto_decode(&mut body).expect("unique ID");
+        self.runtime_id = VarUInt64::proto_decode(&mut body).expect("runtime ID").0;
+        let _gamemode = VarInt::proto_decode(&mut body).expect("gamemode");
+        self.position = Vec3::proto_decode(&mut body).expect("position");
+
+        self.send(
+            server,
+            id::REQUEST_CHUNK_RADIUS,
+            &RequestChunkRadius {
+                chunk_radius: CHUNK_RADIUS,
+                max_chunk_radius: CHUNK_RADIUS,
+            },
+        )
+        .await;
+        let spawned = self
+            .decoded::<PlayStatus>(id::PLAY_STATUS)
+            .iter()
+            .any(|s| s.status == PlayStatusType::PlayerSpawn);
+        assert!(spawned, "{} was not allowed to spawn", self.name);
+
+        self.send(
+            server,
+            id::SET_LOCAL_PLAYER_AS_INITIALIZED,
+            &SetLocalPlayerAsInitialized {
+                entity_runtime_id: self.runtime_id,
+            },
+        )
+        .await;
+    }
+
+    /// IDs of the received packets, for failure messages.
+    fn ids(&self) -> Vec<String> {
+        self.received
+            .iter()
+            .map(|p| format!("0x{:02X}", p.id))
+            .collect()
+    }
+
+    // -----------------------------------------------------------------------
+    // Gameplay
+    // -----------------------------------------------------------------------
+
+    /// Send a chat message.
+    pub async fn chat(&mut self, server: &mut TestServer, message: &str) {
+        let text = Text {
+            text_type: TextType::Chat,
+            source_name: self.name.clone(),
+            ..Text::raw(message)
+        };
+        self.send(server, id::TEXT, &text).await;
+    }
+
+    /// Run a command, e.g. `"/gamemode creative"`.
+    pub async fn command(&mut self, server: &mut TestServer, command: &str) {
+        let request = CommandRequest {
+            command: command.to_string(),
+            origin: CommandOrigin {
+                origin_type: 0,
+                uuid: Uuid::ZERO,
+                request_id: String::new(),
+                player_entity_id: None,
+            },
+        };
+        self.send(server, id::COMMAND_REQUEST, &request).await;
+    }
+
+    /// Walk to an eye position in a straight line, one PlayerAuthInput of at
+    /// most half a block per tick.
+    pub async fn move_to(&mut self, server: &mut TestServer, target: Vec3) {
+        self.walk(server, target, 0).await;
+    }
+
+    /// Sneak to an eye position, as [`move_to`](Self::move_to) does.
+    pub async fn sneak_to(&mut self, server: &mut TestServer, target: Vec3) {
+        self.walk(server, target, input_flags::SNEAKING).await;
+    }
+
+    async fn walk(&mut self, server: &mut TestServer, target: Vec3, input_data: u64) {
+        loop {
+            let (dx, dy, dz) = (
+                target.x - self.position.x,
+                target.y - self.position.y,
+                target.z - self.position.z,
+            );
+            let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+            if distance < f32::EPSILON {
+                return;
+            }
+            let step = (MAX_STEP / distance).min(1.0);
+            let delta = Vec3::new(dx * step, dy * step, dz * step);
+            self.position = Vec3::new(
+                self.position.x + delta.x,
+                self.position.y + delta.y,
+                self.position.z + delta.z,
+            );
+            self.tick += 1;
+            let input = PlayerAuthInput {
+                pitch: 0.0,
+                yaw: 0.0,
+                position: self.position,
+                move_vector: Vec2::ZERO,
+                head_yaw: 0.0,
+                input_data,
+                input_mode: 1,
+                play_mode: 0,
+                interaction_model: 0,
+                tick: self.tick,
+                position_delta: delta,
+            };
+            self.send(server, id::PLAYER_AUTH_INPUT, &input).await;
+            server.tick(1).await;
+            self.receive(server);
+        }
+    }
+
+    /// Send a PlayerAction about a block, e.g. to start or stop breaking it.
+    pub async fn player_action(
+        &mut self,
+        server: &mut TestServer,
+        action: PlayerActionType,
+        block_position: BlockPos,
+        face: i32,
+    ) {
+        let packet = PlayerAction {
+            entity_runtime_id: self.runtime_id,
+            action,
+            block_position,
+            result_position: BlockPos::new(0, 0, 0),
+            face,
+        };
+        self.send(server, id::PLAYER_ACTION, &packet).await;
+    }
+
+    /// Report a block as broken (instant in creative; in survival the server
+    /// checks it was mined for long enough).
+    pub async fn break_block(&mut self, server: &mut TestServer, position: BlockPos) {
+        self.use_item(server, UseItemAction::BreakBlock, position, 1, 0)
+            .await;
+    }
+
+    /// Place a block against a face of `against`, as if holding it in
+    /// hotbar slot 0.
+    pub async fn place_block(
+        &mut self,
+        server: &mut TestServer,
+        against: BlockPos,
+        face: i32,
+        block_runtime_id: u32,
+    ) {
+        self.use_item(
+            server,
+            UseItemAction::ClickBlock,
+            against,
+            face,
+            block_runtime_id as i32,
+        )
+        .await;
+    }
+
+    async fn use_item(
+        &mut self,
+        server: &mut TestServer,
+        action: UseItemAction,
+        block_position: BlockPos,
+        face: i32,
+        held_block_runtime_id: i32,
+    ) {
+        let transaction = InventoryTransaction {
+            use_item: Some(UseItemData {
+                action,
+                block_position,
+                face,
+                hotbar_slot: 0,
+                held_item_block_runtime_id: held_block_runtime_id,
+                player_position: self.position,
+                click_position: Vec3::new(0.5, 0.5, 0.5),
+                block_runtime_id: 0,
+            }),
+            use_item_on_entity: None,
+            release_item: None,
+        };
+        self.send(server, id::INVENTORY_TRANSACTION, &transaction)
+            .await;
+    }
+
+    /// Hit an entity (another player or a mob) by runtime ID.
+    pub async fn attack(&mut self, server: &mut TestServer, entity_runtime_id: u64) {
+        let transaction = InventoryTransaction {
+            use_item: None,
+            use_item_on_entity: Some(UseItemOnEntityData {
+                entity_runtime_id,
+                action: UseItemOnEntityAction::Attack,
+                hotbar_slot: 0,
+                player_position: self.position,
+                click_position: Vec3::ZERO,
+            }),
+            release_item: None,
+        };
+        self.send(server, id::INVENTORY_TRANSACTION, &transaction)
+            .await;
+    }
+
+    /// Send one ItemStackRequest made of `actions` and return its request ID,
+    /// which the server's ItemStackResponse answers.
+    pub async fn stack_request(
+        &mut self,
+        server: &mut TestServer,
+        actions: Vec<StackAction>,
+    ) -> i32 {
+        let request_id = self.next_request_id;
+        self.next_request_id -= 2;
+        let packet = ItemStackRequest {
+            requests: vec![StackRequest {
+                request_id,
+                actions,
+                filter_strings: Vec::new(),
+                filter_cause: 0,
+            }],
+        };
+        self.send(server, id::ITEM_STACK_REQUEST, &packet).await;
+        request_id
+    }
+}
@@ -0,0 +1,1702 @@
+//! End-to-end test harness: a headless server and scripted fake clients.
+//!
+//! [`TestServer`] runs a [`ConnectionHandler`](mc_rs_server::connection::ConnectionHandler)
+//! without a socket, on a flat world kept in memory. [`FakeClient`] speaks
+//! the game protocol to it through mc-rs-proto (login, movement, block and
+//! entity interactions) and keeps every packet the server sends it.
+//!
+//! All servers of a test process share one scratch working directory, so
+//! server-wide files such as `ops.json` are shared too: give the players of
+//! each test names of their own.
+
+mod client;
+mod server;
+
+pub use client::{FakeClient, ReceivedPacket, CHUNK_RADIUS};
+pub use server::TestServer;
+
+use std::future::Future;
+
+/// Stack size of the thread tests run on, as for the server's own workers:
+/// the handler's futures are far too deep for the default test thread.
+const STACK_SIZE: usize = 32 * 1024 * 1024;
+
+/// Run an async test on a thread and runtime of its own, with a stack big
+/// enough for the server.
+pub fn run<F, Fut>(test: F) -> Fut::Output
+where
+    F: FnOnce() -> Fut + Send,
+    Fut: Future,
+    Fut::Output: Send,
+{
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, || {
+                tokio::runtime::Builder::new_current_thread()
+                    .thread_stack_size(STACK_SIZE)
+                    .enable_all()
+                    .build()
+                    .expect("failed to build the test runtime")
+                    .block_on(test())
+            })
+            .expect("failed to spawn the test thread")
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_rs_game::block_entity::BlockEntityData;
+    use mc_rs_game::inventory::{
+        CONTAINER_CRAFTING_INPUT, CONTAINER_CRAFTING_OUTPUT, CONTAINER_INVENTORY,
+    };
+    use mc_rs_game::loom;
+    use mc_rs_game::recipe::RecipeRegistry;
+    use mc_rs_game::sign::SignText;
+    use mc_rs_game::skull::SkullOwner;
+    use mc_rs_nbt::read_nbt_network;
+    use mc_rs_proto::codec::{read_string, ProtoDecode};
+    use mc_rs_proto::entity_metadata::{key, EntityFlag, EntityMetadataEntry, MetadataValue};
+    use mc_rs_proto::packets::item_stack_request::{StackAction, StackSlot};
+    use mc_rs_proto::packets::item_stack_response::ItemStackResponse;
+    use mc_rs_proto::packets::level_sound_event::{SOUND_NOTE, SOUND_RECORD_13};
+    use mc_rs_proto::packets::player_action::PlayerActionType;
+    use mc_rs_proto::packets::{
+        id, BlockActorData, ChunkRadiusUpdated, ContainerOpen, Disconnect, LevelSoundEvent,
+        MoveMode, MovePlayer, SetActorData, UpdateAttributes, UpdateBlock,
+    };
+    use mc_rs_proto::types::{BlockPos, Uuid, VarUInt64, Vec3};
+    use mc_rs_world::block_hash::{
+        hash_block_state, BlockEntityHashes, FlatWorldBlocks, TickBlocks,
+    };
+    use mc_rs_world::block_state_registry::BlockStateRegistry;
+
+    #[test]
+    fn login_reaches_the_game() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let alice = server.join("LoginAlice").await;
+            assert_ne!(alice.runtime_id, 0);
+            assert!(alice.packets(id::UPDATE_ABILITIES).next().is_some());
+            assert!(alice
+                .messages()
+                .iter()
+                .any(|m| m.contains("LoginAlice joined")));
+        });
+    }
+
+    #[test]
+    fn spawn_chunks_cover_the_requested_radius() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let alice = server.join("ChunkAlice").await;
+            let chunks = alice.chunk_positions();
+            for x in -CHUNK_RADIUS..=CHUNK_RADIUS {
+                for z in -CHUNK_RADIUS..=CHUNK_RADIUS {
+                    assert!(chunks.contains(&(x, z)), "chunk ({x}, {z}) not sent");
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn walking_sends_new_chunks() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("WalkAlice").await;
+            alice.clear();
+            let spawn = alice.position;
+            alice
+                .move_to(&mut server, Vec3::new(48.5, spawn.y, spawn.z))
+                .await;
+            let chunks = alice.chunk_positions();
+            assert!(chunks.contains(&(3 + CHUNK_RADIUS - 1, 0)), "{chunks:?}");
+            assert!(!chunks.contains(&(0, 0)), "chunk (0, 0) sent twice");
+        });
+    }
+
+    #[test]
+    fn other_players_see_movement() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("MoveAlice").await;
+            let mut bob = server.join("MoveBob").await;
+            alice.receive(&mut server);
+            assert!(alice.packets(id::ADD_PLAYER).next().is_some());
+
+            bob.clear();
+            let spawn = alice.position;
+            alice
+                .move_to(&mut server, Vec3::new(spawn.x + 2.0, spawn.y, spawn.z))
+                .await;
+            bob.receive(&mut server);
+            let moves = bob.decoded::<MovePlayer>(id::MOVE_PLAYER);
+            assert!(moves
+                .iter()
+                .any(|m| m.runtime_entity_id == alice.runtime_id && m.position.x > spawn.x + 1.5));
+        });
+    }
+
+    #[test]
+    fn unseen_movement_is_not_broadcast() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("NudgeAlice").await;
+            let mut bob = server.join("NudgeBob").await;
+            let spawn = alice.position;
+            alice
+                .move_to(&mut server, Vec3::new(spawn.x + 1.0, spawn.y, spawn.z))
+                .await;
+            bob.receive(&mut server);
+            bob.clear();
+
+            let here = alice.position;
+            alice
+                .move_to(&mut server, Vec3::new(here.x + 0.004, here.y, here.z))
+                .await;
+            bob.receive(&mut server);
+            assert!(bob.packets(id::MOVE_PLAYER).next().is_none());
+        });
+    }
+
+    #[test]
+    fn creative_player_breaks_and_places_blocks() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.gamemode = "creative".into();
+            });
+            let mut alice = server.join("BlockAlice").await;
+            let mut bob = server.join("BlockBob").await;
+            server.tick(20).await;
+
+            // The flat world's grass is at y = 3
+            let grass = BlockPos::new(1, 3, 0);
+            alice.break_block(&mut server, grass).await;
+            bob.receive(&mut server);
+            let air = FlatWorldBlocks::compute().air;
+            assert!(bob
+                .decoded::<UpdateBlock>(id::UPDATE_BLOCK)
+                .iter()
+                .any(|u| u.position == grass && u.runtime_id == air));
+
+            server.tick(20).await;
+            let dirt = FlatWorldBlocks::compute().dirt;
+            alice
+                .place_block(&mut server, BlockPos::new(2, 3, 0), 1, dirt)
+                .await;
+            bob.receive(&mut server);
+            assert!(bob
+                .decoded::<UpdateBlock>(id::UPDATE_BLOCK)
+                .iter()
+                .any(|u| u.position == BlockPos::new(2, 4, 0) && u.runtime_id == dirt));
+        });
+    }
+
+    #[test]
+    fn doors_are_placed_and_broken_whole() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.gamemode = "creative".into();
+            });
+            let mut alice = server.join("DoorAlice").await;
+            let mut bob = server.join("DoorBob").await;
+            server.tick(20).await;
+
+            // On the flat world's grass, at y = 3
+            let door = hash_block_state("minecraft:oak_door");
+            alice
+                .place_block(&mut server, BlockPos::new(2, 3, 0), 1, door)
+                .await;
+            bob.receive(&mut server);
+            let states = BlockStateRegistry::new();
+            let halves: Vec<(BlockPos, String)> = bob
+                .decoded::<UpdateBlock>(id::UPDATE_BLOCK)
+                .iter()
+                .filter_map(|u| {
+                    let info = states.get(u.runtime_id)?;
+                    let upper = info.property("upper_block_bit")?;
+                    Some((u.position, format!("{} {upper:?}", info.name)))
+                })
+                .collect();
+            assert_eq!(
+                halves,
+                [
+                    (BlockPos::new(2, 4, 0), "minecraft:oak_door Byte(0)".into()),
+                    (BlockPos::new(2, 5, 0), "minecraft:oak_door Byte(1)".into()),
+                ]
+            );
+
+            // Breaking the upper half takes the lower one too
+            server.tick(20).await;
+            bob.clear();
+            alice.break_block(&mut server, BlockPos::new(2, 5, 0)).await;
+            bob.receive(&mut server);
+            let air = FlatWorldBlocks::compute().air;
+            assert!(bob
+                .decoded::<UpdateBlock>(id::UPDATE_BLOCK)
+                .iter()
+                .any(|u| u.position == BlockPos::new(2, 4, 0) && u.runtime_id == air));
+        });
+    }
+
+    #[test]
+    fn hoes_till_grass_into_farmland() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.gamemode = "creative".into();
+            });
+            let mut alice = server.join("HoeAlice").await;
+            let mut bob = server.join("HoeBob").await;
+            server.console("op HoeAlice").await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/give HoeAlice wooden_hoe")
+                .await;
+            server.tick(20).await;
+            bob.clear();
+
+            // The flat world's grass is at y = 3
+            let grass = BlockPos::new(2, 3, 0);
+            alice.place_block(&mut server, grass, 1, 0).await;
+            bob.receive(&mut server);
+            let farmland = TickBlocks::compute().farmland[0];
+            assert!(bob
+                .decoded::<UpdateBlock>(id::UPDATE_BLOCK)
+                .iter()
+                .any(|u| u.position == grass && u.runtime_id == farmland));
+        });
+    }
+
+    #[test]
+    fn leaves_decay_once_their_log_is_broken() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.gamemode = "creative".into();
+            });
+            let mut alice = server.join("LeafAlice").await;
+            let mut bob = server.join("LeafBob").await;
+            server.console("op LeafAlice").await;
+            server.tick(20).await;
+
+            // A placed log on the flat world's grass, leaves on top of it
+            let log = hash_block_state("minecraft:oak_log");
+            alice
+                .place_block(&mut server, BlockPos::new(2, 3, 0), 1, log)
+                .await;
+            alice
+                .command(&mut server, "/setblock 2 5 0 oak_leaves")
+                .await;
+            server.tick(20).await;
+            let leaves = BlockPos::new(2, 5, 0);
+            let air = FlatWorldBlocks::compute().air;
+            let decayed = |updates: Vec<UpdateBlock>| {
+                updates
+                    .iter()
+                    .any(|u| u.position == leaves && u.runtime_id == air)
+            };
+            server.tick(220).await;
+            bob.receive(&mut server);
+            assert!(!decayed(bob.decoded(id::UPDATE_BLOCK)));
+
+            bob.clear();
+            alice.break_block(&mut server, BlockPos::new(2, 4, 0)).await;
+            server.tick(220).await;
+            bob.receive(&mut server);
+            assert!(decayed(bob.decoded(id::UPDATE_BLOCK)));
+        });
+    }
+
+    #[test]
+    fn melee_attack_hurts_the_target() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("CombatAlice").await;
+            let mut bob = server.join("CombatBob").await;
+            // Two blocks straight ahead of Alice, who faces +Z
+            let spawn = alice.position;
+            bob.move_to(&mut server, Vec3::new(spawn.x, spawn.y, spawn.z + 2.0))
+                .await;
+            bob.clear();
+
+            alice.attack(&mut server, bob.runtime_id).await;
+            bob.receive(&mut server);
+            let health: Vec<f32> = bob
+                .decoded::<UpdateAttributes>(id::UPDATE_ATTRIBUTES)
+                .iter()
+                .filter(|u| u.entity_runtime_id == bob.runtime_id)
+                .flat_map(|u| u.attributes.iter())
+                .filter(|a| a.name == "minecraft:health")
+                .map(|a| a.current)
+                .collect();
+            assert!(health.iter().any(|&hp| hp < 20.0), "{health:?}");
+        });
+    }
+
+    #[test]
+    fn packets_of_one_tick_come_in_one_batch() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("BatchAlice").await;
+            let mut bob = server.join("BatchBob").await;
+            let spawn = alice.position;
+            bob.move_to(&mut server, Vec3::new(spawn.x, spawn.y, spawn.z + 2.0))
+                .await;
+            bob.clear();
+
+            // Hurting Bob sends him his health, the hurt animation and the
+            // knockback
+            alice.attack(&mut server, bob.runtime_id).await;
+            bob.receive(&mut server);
+            assert!(bob.received().len() > 1, "{}", bob.received().len());
+            assert_eq!(bob.batches(), 1);
+        });
+    }
+
+    #[test]
+    fn timings_report_lists_tick_sections() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("TimingsAlice").await;
+            let spawn = alice.position;
+            alice
+                .move_to(&mut server, Vec3::new(spawn.x + 2.0, spawn.y, spawn.z))
+                .await;
+
+            let report = server.console("timings report").await;
+            assert!(report.starts_with("Timings over the last"), "{report}");
+            for section in ["packets", "mob AI", "tick"] {
+                assert!(report.contains(&format!("\n  {section}: ")), "{report}");
+            }
+            assert_eq!(server.console("timings reset").await, "Timings reset");
+            assert_eq!(
+                server.console("timings report").await,
+                "No ticks recorded yet"
+            );
+        });
+    }
+
+    #[test]
+    fn reload_applies_bans_and_view_distance() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("ReloadAlice").await;
+            let mut bob = server.join("ReloadBob").await;
+            alice.clear();
+            bob.clear();
+
+            std::fs::write(
+                "server.toml",
+                r#"
+                [server]
+                address = "127.0.0.1"
+                port = 19132
+                motd = "Reloaded"
+                max_players = 20
+                gamemode = "survival"
+                difficulty = "normal"
+                online_mode = false
+                view_distance = 1
+
+                [world]
+                name = "world"
+                generator = "flat"
+                seed = 0
+
+                [logging]
+                level = "warn"
+                "#,
+            )
+            .unwrap();
+            std::fs::write(
+                "banned-players.json",
+                r#"{"ReloadBob": {"reason": "Griefing"}}"#,
+            )
+            .unwrap();
+            let output = server.console("reload config").await;
+            std::fs::remove_file("server.toml").unwrap();
+            std::fs::remove_file("banned-players.json").unwrap();
+            assert!(output.starts_with("Reloaded server.toml"), "{output}");
+            assert!(output.contains("(1 player(s) removed)"), "{output}");
+
+            bob.receive(&mut server);
+            let kicks = bob.decoded::<Disconnect>(id::DISCONNECT);
+            assert_eq!(
+                kicks[0].message.as_deref(),
+                Some("You are banned: Griefing")
+            );
+            alice.receive(&mut server);
+            assert!(alice.packets(id::DISCONNECT).next().is_none());
+            assert!(alice.packets(id::CHUNK_RADIUS_UPDATED).next().is_some());
+        });
+    }
+
+    #[test]
+    fn view_distance_override_widens_the_radius() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.view_distance = 1;
+            });
+            let mut alice = server.join("ViewAlice").await;
+            server.console("op ViewAlice").await;
+            server.tick(20).await;
+            alice.clear();
+
+            alice.command(&mut server, "/viewdistance 5").await;
+            let radii = alice.decoded::<ChunkRadiusUpdated>(id::CHUNK_RADIUS_UPDATED);
+            // Never more than the client asked for
+            assert_eq!(radii[0].chunk_radius, CHUNK_RADIUS);
+            let chunks = alice.chunk_positions();
+            assert!(!chunks.is_empty());
+            assert!(chunks.iter().all(|&(x, z)| x.abs() == 2 || z.abs() == 2));
+
+            server.tick(20).await;
+            alice.clear();
+            alice.command(&mut server, "/viewdistance reset").await;
+            let radii = alice.decoded::<ChunkRadiusUpdated>(id::CHUNK_RADIUS_UPDATED);
+            assert_eq!(radii[0].chunk_radius, 1);
+        });
+    }
+
+    #[test]
+    fn tps_is_open_to_everyone() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("TpsAlice").await;
+            server.tick(20).await;
+            alice.clear();
+
+            alice.command(&mut server, "/tps").await;
+            let messages = alice.messages();
+            assert!(
+                messages.iter().any(|m| m.starts_with("TPS: ")),
+                "{messages:?}"
+            );
+            assert!(server.console("tps").await.contains("MSPT: "));
+        });
+    }
+
+    #[test]
+    fn locate_finds_the_flat_worlds_biome() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("LocateAlice").await;
+            server.console("op LocateAlice").await;
+            server.tick(20).await;
+            alice.clear();
+
+            // The flat world is all plains, as its chunks say
+            alice
+                .command(&mut server, "/locate biome minecraft:plains")
+                .await;
+            let messages = alice.messages();
+            assert!(
+                messages
+                    .iter()
+                    .any(|m| m.starts_with("The nearest plains is at")
+                        && m.ends_with("(0 blocks away)")),
+                "{messages:?}"
+            );
+            assert!(server
+                .console("locate biome desert")
+                .await
+                .contains("Could not find a desert biome"));
+            assert!(server
+                .console("locate biome moon")
+                .await
+                .contains("Unknown biome"));
+        });
+    }
+
+    #[test]
+    fn locate_finds_generated_structures() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.world.generator = "default".into();
+            });
+            let mut alice = server.join("StructureAlice").await;
+            server.console("op StructureAlice").await;
+            server.tick(20).await;
+            alice.clear();
+
+            // Dungeons are common enough to be in the chunks around spawn
+            alice
+                .command(&mut server, "/locate structure dungeon map")
+                .await;
+            let messages = alice.messages();
+            assert!(
+                messages
+                    .iter()
+                    .any(|m| m.starts_with("The nearest dungeon is at")),
+                "{messages:?}"
+            );
+            assert!(
+                messages
+                    .iter()
+                    .any(|m| m == "Gave an explorer map to the dungeon"),
+                "{messages:?}"
+            );
+            assert!(alice
+                .packets(id::CLIENTBOUND_MAP_ITEM_DATA)
+                .next()
+                .is_some());
+
+            assert!(server
+                .console("locate structure dungeon map")
+                .await
+                .contains("Only players can get an explorer map"));
+            assert!(server
+                .console("locate structure stronghold")
+                .await
+                .contains("Unknown structure"));
+        });
+    }
+
+    #[test]
+    fn unknown_countries_can_be_refused() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.geoip.enabled = true;
+                config.geoip.allow_unknown = false;
+            });
+            let mut alice = server.connect("GeoAlice").await;
+            alice.send_login(&mut server).await;
+
+            let kicks = alice.decoded::<Disconnect>(id::DISCONNECT);
+            assert_eq!(
+                kicks[0].message.as_deref(),
+                Some("Your country is not allowed on this server.")
+            );
+            assert!(alice.packets(id::START_GAME).next().is_none());
+        });
+    }
+
+    #[test]
+    fn prelogin_webhook_can_refuse_a_player() {
+        run(|| async {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let webhook = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = webhook.local_addr().unwrap().port();
+            tokio::spawn(async move {
+                let (mut socket, _) = webhook.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.ends_with(b"}") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8(request).unwrap();
+                assert!(request.starts_with("POST /check HTTP/1.0"), "{request}");
+                assert!(request.contains("\"name\":\"HookAlice\""), "{request}");
+                socket
+                    .write_all(b"HTTP/1.0 200 OK\r\n\r\n{\"allow\":false,\"message\":\"No VPNs\"}")
+                    .await
+                    .unwrap();
+            });
+
+            let mut server = TestServer::start_with(|config| {
+                config.prelogin.webhook_url = format!("http://127.0.0.1:{port}/check");
+            });
+            let mut alice = server.connect("HookAlice").await;
+            alice.send_login(&mut server).await;
+            // Held until the webhook answers, without holding up the server
+            assert!(alice.packets(id::DISCONNECT).next().is_none());
+            for _ in 0..100 {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                server.tick(1).await;
+                alice.receive(&mut server);
+                if alice.packets(id::DISCONNECT).next().is_some() {
+                    break;
+                }
+            }
+            let kicks = alice.decoded::<Disconnect>(id::DISCONNECT);
+            assert_eq!(kicks[0].message.as_deref(), Some("No VPNs"));
+            assert!(alice.packets(id::PLAY_STATUS).next().is_none());
+        });
+    }
+
+    #[test]
+    fn bridge_posts_events_and_relays_chat() {
+        run(|| async {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let webhook = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = webhook.local_addr().unwrap().port();
+            let (posted_tx, mut posted) = tokio::sync::mpsc::unbounded_channel::<String>();
+            tokio::spawn(async move {
+                loop {
+                    let (mut socket, _) = webhook.accept().await.unwrap();
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.ends_with(b"}") {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        request.extend_from_slice(&buf[..n]);
+                    }
+                    let request = String::from_utf8(request).unwrap();
+                    let body = request.split("\r\n\r\n").nth(1).unwrap().to_string();
+                    socket
+                        .write_all(b"HTTP/1.0 204 No Content\r\n\r\n")
+                        .await
+                        .unwrap();
+                    let _ = posted_tx.send(body);
+                }
+            });
+
+            let mut server = TestServer::start_with(|config| {
+                config.bridge.webhook_url = format!("http://127.0.0.1:{port}/events");
+                config.bridge.events = vec!["join".into(), "chat".into()];
+            });
+            let mut alice = server.join("BridgeAlice").await;
+            alice.chat(&mut server, "hello discord").await;
+            let mut bodies = Vec::new();
+            for _ in 0..100 {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                while let Ok(body) = posted.try_recv() {
+                    bodies.push(body);
+                }
+                if bodies.len() == 2 {
+                    break;
+                }
+            }
+            assert_eq!(
+                bodies,
+                [
+                    r#"{"event":"join","player":"BridgeAlice"}"#,
+                    r#"{"event":"chat","player":"BridgeAlice","message":"hello discord"}"#,
+                ]
+            );
+
+            alice.receive(&mut server);
+            alice.clear();
+            server
+                .handler()
+                .relay_bridge_chat(mc_rs_server::bridge::BridgeChat {
+                    author: "Bob".into(),
+                    message: "hi from discord".into(),
+                })
+                .await;
+            server.tick(1).await;
+            alice.receive(&mut server);
+            assert_eq!(alice.messages(), ["[Discord] <Bob> hi from discord"]);
+        });
+    }
+
+    #[test]
+    fn whois_shows_where_players_connect_from() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let alice = server.join("WhoisAlice").await;
+            let output = server.console("whois WhoisAlice").await;
+            assert_eq!(
+                output,
+                format!(
+                    "WhoisAlice: address {}, country unknown, XUID {}",
+                    alice.addr,
+                    2_535_400_000_000_000u64 + alice.addr.port() as u64
+                )
+            );
+        });
+    }
+
+    #[test]
+    fn chat_is_moderated_before_it_is_sent() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.chat.slow_mode = 5;
+                config.chat.filter_words = vec!["darn".into()];
+            });
+            let mut alice = server.join("ChatAlice").await;
+            alice.receive(&mut server);
+            alice.clear();
+
+            alice.chat(&mut server, "darn it").await;
+            alice.receive(&mut server);
+            assert_eq!(alice.messages(), ["<ChatAlice> **** it"]);
+
+            alice.clear();
+            alice.chat(&mut server, "hello again").await;
+            alice.receive(&mut server);
+            assert_eq!(
+                alice.messages(),
+                ["Slow mode is on: wait 5s before chatting again"]
+            );
+
+            assert_eq!(
+                server.console("mute ChatAlice 10m").await,
+                "Muted ChatAlice for 10m 00s"
+            );
+            server.tick(100).await;
+            alice.clear();
+            alice.chat(&mut server, "hello?").await;
+            alice.receive(&mut server);
+            assert!(alice.messages()[1].starts_with("You are muted for another"));
+
+            assert_eq!(
+                server.console("unmute ChatAlice").await,
+                "Unmuted ChatAlice"
+            );
+            alice.clear();
+            alice.chat(&mut server, "thanks").await;
+            alice.receive(&mut server);
+            assert_eq!(
+                alice.messages(),
+                ["You are no longer muted", "<ChatAlice> thanks"]
+            );
+        });
+    }
+
+    #[test]
+    fn private_messages_reach_the_recipient_and_spies() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("PmAlice").await;
+            let mut bob = server.join("PmBob").await;
+            let mut carol = server.join("PmCarol").await;
+            server.console("op PmCarol").await;
+            server.tick(20).await;
+            carol.command(&mut server, "/socialspy").await;
+            for client in [&mut alice, &mut bob, &mut carol] {
+                client.receive(&mut server);
+                client.clear();
+            }
+
+            alice.command(&mut server, "/msg PmBob hi there").await;
+            assert!(alice
+                .messages()
+                .contains(&"[you -> PmBob] hi there".to_string()));
+            bob.receive(&mut server);
+            assert_eq!(bob.messages(), ["[PmAlice -> you] hi there"]);
+            carol.receive(&mut server);
+            assert_eq!(carol.messages(), ["[Spy] [PmAlice -> PmBob] hi there"]);
+
+            server.tick(20).await;
+            bob.clear();
+            bob.command(&mut server, "/reply hello").await;
+            alice.receive(&mut server);
+            assert!(alice
+                .messages()
+                .contains(&"[PmBob -> you] hello".to_string()));
+
+            server.tick(20).await;
+            bob.command(&mut server, "/ignore PmAlice").await;
+            server.tick(20).await;
+            alice.clear();
+            bob.clear();
+            alice.command(&mut server, "/msg PmBob still there?").await;
+            assert!(alice
+                .messages()
+                .contains(&"PmBob is ignoring you".to_string()));
+            bob.receive(&mut server);
+            assert!(bob.messages().is_empty());
+        });
+    }
+
+    #[test]
+    fn money_moves_between_players() {
+        run(|| async {
+            let mut server = TestServer::start_with(|c| c.economy.starting_balance = 10);
+            let mut alice = server.join("EcoAlice").await;
+            let mut bob = server.join("EcoBob").await;
+            server.tick(20).await;
+
+            assert_eq!(
+                server.console("eco give EcoAlice 40").await,
+                "EcoAlice now has 50 coins"
+            );
+            alice.clear();
+            alice.command(&mut server, "/pay EcoBob 30").await;
+            assert!(alice
+                .messages()
+                .contains(&"Paid EcoBob 30 coins (balance: 20 coins)".to_string()));
+            bob.receive(&mut server);
+            assert!(bob
+                .messages()
+                .contains(&"EcoAlice paid you 30 coins".to_string()));
+
+            server.tick(20).await;
+            alice.clear();
+            alice.command(&mut server, "/pay EcoBob 100").await;
+            assert!(alice
+                .messages()
+                .contains(&"Payment failed: not enough money".to_string()));
+            assert_eq!(
+                server.console("balance EcoBob").await,
+                "EcoBob has 40 coins"
+            );
+        });
+    }
+
+    #[test]
+    fn arena_command_needs_a_plugin_arena() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("ArenaAlice").await;
+            server.tick(20).await;
+            assert_eq!(server.console("arena list").await, "No arenas");
+            assert_eq!(
+                server.console("arena join duel").await,
+                "This command must be run by a player"
+            );
+
+            alice.clear();
+            alice.command(&mut server, "/arena join duel").await;
+            assert!(alice
+                .messages()
+                .contains(&"Cannot join duel: no such arena".to_string()));
+            server.tick(20).await;
+            alice.clear();
+            alice.command(&mut server, "/arena leave").await;
+            assert!(alice
+                .messages()
+                .contains(&"You are not in an arena".to_string()));
+        });
+    }
+
+    #[test]
+    fn holograms_show_placeholders_per_player() {
+        run(|| async {
+            let shows = |client: &FakeClient, text: &str| {
+                client
+                    .packets(id::ADD_ACTOR)
+                    .any(|p| p.body.windows(text.len()).any(|w| w == text.as_bytes()))
+            };
+            let mut server = TestServer::start();
+            let mut alice = server.join("HoloAlice").await;
+            server.console("op HoloAlice").await;
+            server.tick(20).await;
+            alice.clear();
+            alice
+                .command(&mut server, "/hologram create lobby Welcome\\n{player}")
+                .await;
+            assert!(alice
+                .messages()
+                .contains(&"Hologram lobby created".to_string()));
+            assert!(shows(&alice, "Welcome"));
+            assert!(shows(&alice, "HoloAlice"));
+
+            let bob = server.join("HoloBob").await;
+            assert!(shows(&bob, "HoloBob"));
+            assert!(server
+                .console("hologram list")
+                .await
+                .contains("- lobby (2 line(s)"));
+
+            alice.clear();
+            assert_eq!(
+                server.console("hologram remove lobby").await,
+                "Hologram lobby removed"
+            );
+            alice.receive(&mut server);
+            assert_eq!(alice.packets(id::REMOVE_ENTITY).count(), 2);
+        });
+    }
+
+    #[test]
+    fn camera_command_sends_presets_once() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("CamAlice").await;
+            server.tick(20).await;
+            alice.clear();
+            assert_eq!(
+                server
+                    .console("camera CamAlice fade time 0.5 1 0.5 color 0 0 0")
+                    .await,
+                "Camera fade sent to 1 player(s)"
+            );
+            assert_eq!(
+                server
+                    .console("camera @a set free ease 2 in_out_sine pos 0 80 0 rot 30 0")
+                    .await,
+                "Camera set sent to 1 player(s)"
+            );
+            assert_eq!(
+                server.console("camera @a set orbit").await,
+                "Unknown camera preset: minecraft:orbit"
+            );
+            server.console("camera CamAlice clear").await;
+            alice.receive(&mut server);
+            assert_eq!(alice.packets(id::CAMERA_PRESETS).count(), 1);
+            let instructions: Vec<_> = alice.packets(id::CAMERA_INSTRUCTION).collect();
+            assert_eq!(instructions.len(), 3);
+            // A fade only: no set, no clear, then the fade flag
+            assert_eq!(&instructions[0].body[..3], &[0, 0, 1]);
+            assert_eq!(instructions[1].body[0], 1);
+        });
+    }
+
+    #[test]
+    fn fog_game_rules_and_input_locks_reach_the_client() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("FogAlice").await;
+            server.console("op FogAlice").await;
+            server.tick(20).await;
+            alice.clear();
+            alice
+                .command(&mut server, "/gamerule showCoordinates true")
+                .await;
+            assert!(alice
+                .messages()
+                .contains(&"Game rule showCoordinates set to true".to_string()));
+            assert_eq!(
+                server.console("fog @a push minecraft:fog_hell intro").await,
+                "Pushed minecraft:fog_hell onto FogAlice's fog"
+            );
+            assert_eq!(
+                server.console("fog FogAlice pop outro").await,
+                "FogAlice has no fog under outro"
+            );
+            assert_eq!(
+                server
+                    .console("inputpermission set FogAlice movement disabled")
+                    .await,
+                "movement disabled for 1 player(s)"
+            );
+            assert_eq!(
+                server
+                    .console("inputpermission query FogAlice movement")
+                    .await,
+                "FogAlice: movement is disabled"
+            );
+            alice.receive(&mut server);
+
+            let rules = alice.packets(id::GAME_RULES_CHANGED).last().unwrap();
+            assert!(rules.body.windows(15).any(|w| w == b"showcoordinates"));
+            let fog: Vec<_> = alice.packets(id::PLAYER_FOG).collect();
+            assert_eq!(fog.len(), 2);
+            assert_eq!(&fog[0].body[2..], b"minecraft:fog_hell");
+            let locks = alice.packets(id::UPDATE_CLIENT_INPUT_LOCKS).last().unwrap();
+            assert_eq!(locks.body[0], 1 << 2);
+        });
+    }
+
+    #[test]
+    fn optional_packs_can_be_turned_off() {
+        run(|| async {
+            let packs_dir = std::env::temp_dir().join("mc-rs-testing-optional-packs");
+            let _ = std::fs::remove_dir_all(&packs_dir);
+            for (name, uuid) in [
+                ("Textures", "11111111-2222-3333-4444-555555555555"),
+                ("Sounds", "99999999-2222-3333-4444-555555555555"),
+            ] {
+                let dir = packs_dir.join(name);
+                std::fs::create_dir_all(&dir).unwrap();
+                let manifest = format!(
+                    r#"{{"format_version": 2, "header": {{"name": "{name}", "uuid": "{uuid}", "version": [1, 0, 0]}}, "modules": []}}"#
+                );
+                std::fs::write(dir.join("manifest.json"), manifest).unwrap();
+            }
+            let mut server = TestServer::start_with(|config| {
+                config.packs.resource_directory = packs_dir.display().to_string();
+                config.packs.required = vec!["Textures".into()];
+            });
+            let mut alice = server.join("PackAlice").await;
+            server.tick(20).await;
+
+            // Sounds may be declined, so the client is not made to accept
+            let info = alice.packets(id::RESOURCE_PACKS_INFO).next().unwrap();
+            assert_eq!(info.body[0], 0);
+            assert_eq!(&info.body[21..23], &2u16.to_le_bytes());
+
+            for line in ["/packs toggle textures", "/packs toggle Sounds", "/packs"] {
+                alice.command(&mut server, line).await;
+                server.tick(20).await;
+            }
+            let messages = alice.messages();
+            assert!(messages.contains(&"Textures is required".to_string()));
+            assert!(messages.contains(&"Sounds turned off from your next join".to_string()));
+            assert!(messages.iter().any(|m| m
+                .contains("Sounds v1.0.0 (99999999-2222-3333-4444-555555555555): optional, off")));
+
+            let _ = std::fs::remove_dir_all(&packs_dir);
+        });
+    }
+
+    #[test]
+    fn clicking_an_npc_runs_its_actions() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("NpcAlice").await;
+            server.console("op NpcAlice").await;
+            server.tick(20).await;
+            alice.clear();
+            alice
+                .command(&mut server, "/npc create guide Town Guide")
+                .await;
+            assert!(alice.messages().contains(&"NPC guide created".to_string()));
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/npc action guide add message Hello {player}")
+                .await;
+            server.tick(20).await;
+
+            // AddPlayer starts with the UUID, the name and the runtime ID
+            let mut body = alice
+                .packets(id::ADD_PLAYER)
+                .map(|p| p.body.clone())
+                .last()
+                .expect("the NPC is shown");
+            Uuid::proto_decode(&mut body).unwrap();
+            assert_eq!(read_string(&mut body).unwrap(), "Town Guide");
+            let runtime_id = VarUInt64::proto_decode(&mut body).unwrap().0;
+
+            alice.clear();
+            alice.attack(&mut server, runtime_id).await;
+            assert!(alice.messages().contains(&"Hello NpcAlice".to_string()));
+            assert!(
+                alice.packets(id::ENTITY_EVENT).next().is_none(),
+                "NPCs take no damage"
+            );
+            assert!(server
+                .console("npc list")
+                .await
+                .contains("- guide \"Town Guide\""));
+        });
+    }
+
+    #[test]
+    fn restart_kicks_everyone_with_the_message() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("RestartAlice").await;
+            let output = server.console("restart Back in a minute").await;
+            assert_eq!(output, "Restarting the server...");
+            assert!(server.handler().restart_requested());
+
+            alice.receive(&mut server);
+            let kicks = alice.decoded::<Disconnect>(id::DISCONNECT);
+            assert_eq!(kicks[0].message.as_deref(), Some("Back in a minute"));
+        });
+    }
+
+    #[test]
+    fn crafting_planks_from_a_log() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("CraftAlice").await;
+            server.console("op CraftAlice").await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/give CraftAlice oak_log 1")
+                .await;
+
+            let recipes = RecipeRegistry::new();
+            let planks = recipes
+                .shapeless_recipes()
+                .iter()
+                .find(|r| {
+                    r.inputs[0].item_name == "minecraft:oak_log"
+                        && r.output[0].item_name == "minecraft:oak_planks"
+                })
+                .expect("planks recipe");
+            let slot = |container_id, slot| StackSlot {
+                container_id,
+                slot,
+                stack_network_id: 0,
+            };
+            let request_id = alice
+                .stack_request(
+                    &mut server,
+                    vec![
+                        StackAction::Place {
+                            count: 1,
+                            src: slot(CONTAINER_INVENTORY, 0),
+                            dst: slot(CONTAINER_CRAFTING_INPUT, 0),
+                        },
+                        StackAction::CraftRecipe {
+                            recipe_network_id: planks.network_id,
+                        },
+                        StackAction::Take {
+                            count: 4,
+                            src: slot(CONTAINER_CRAFTING_OUTPUT, 0),
+                            dst: slot(CONTAINER_INVENTORY, 1),
+                        },
+                    ],
+                )
+                .await;
+
+            let responses = alice.decoded::<ItemStackResponse>(id::ITEM_STACK_RESPONSE);
+            let response = responses
+                .iter()
+                .flat_map(|r| r.responses.iter())
+                .find(|r| r.request_id == request_id)
+                .expect("the request was answered");
+            assert_eq!(response.status, 0);
+            let crafted = response
+                .containers
+                .iter()
+                .filter(|c| c.container_id == CONTAINER_INVENTORY)
+                .flat_map(|c| c.slots.iter())
+                .find(|s| s.slot == 1)
+                .expect("the planks landed in slot 1");
+            assert_eq!(crafted.count, 4);
+        });
+    }
+
+    #[test]
+    fn placed_player_head_keeps_its_owner() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.gamemode = "creative".into();
+            });
+            let mut alice = server.join("HeadAlice").await;
+            let mut bob = server.join("HeadBob").await;
+            server.console("op HeadAlice").await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/give HeadAlice player_head{owner:HeadBob}")
+                .await;
+            assert!(alice
+                .messages()
+                .iter()
+                .any(|m| m.contains("Gave 1 minecraft:player_head")));
+
+            server.tick(20).await;
+            let head = BlockEntityHashes::compute().head_facing(3, 0);
+            bob.clear();
+            alice
+                .place_block(&mut server, BlockPos::new(2, 3, 0), 1, head)
+                .await;
+            bob.receive(&mut server);
+            let placed = BlockPos::new(2, 4, 0);
+            let data = bob.decoded::<BlockActorData>(id::BLOCK_ACTOR_DATA);
+            let skull = data
+                .iter()
+                .find(|d| d.position == placed)
+                .expect("the head's block entity was sent");
+            let owner = SkullOwner::from_item_nbt(&skull.nbt_data).expect("an owner");
+            assert_eq!(owner.name, "HeadBob");
+        });
+    }
+
+    #[test]
+    fn placed_banner_keeps_its_patterns() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.gamemode = "creative".into();
+            });
+            let mut alice = server.join("BannerAlice").await;
+            let mut bob = server.join("BannerBob").await;
+            server.console("op BannerAlice").await;
+            server.tick(20).await;
+            alice
+                .command(
+                    &mut server,
+                    r#"/give BannerAlice banner{Patterns:[{Pattern:"bo",Color:1}]} 1 4"#,
+                )
+                .await;
+            server.tick(20).await;
+
+            let standing = BlockEntityHashes::compute().standing_banner[0];
+            bob.clear();
+            alice
+                .place_block(&mut server, BlockPos::new(2, 3, 0), 1, standing)
+                .await;
+            bob.receive(&mut server);
+            let placed = BlockPos::new(2, 4, 0);
+            let data = bob.decoded::<BlockActorData>(id::BLOCK_ACTOR_DATA);
+            let banner = data
+                .iter()
+                .find(|d| d.position == placed)
+                .expect("the banner's block entity was sent");
+            let root = read_nbt_network(&mut &banner.nbt_data[..]).unwrap();
+            assert_eq!(root.compound.get("Base").and_then(|t| t.as_int()), Some(4));
+            let patterns = loom::patterns_from_nbt(root.compound.get("Patterns"));
+            assert_eq!(patterns.len(), 1);
+            assert_eq!((patterns[0].pattern.as_str(), patterns[0].color), ("bo", 1));
+        });
+    }
+
+    #[test]
+    fn note_block_and_jukebox_play_sounds() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.gamemode = "creative".into();
+            });
+            let mut alice = server.join("MusicAlice").await;
+            let mut bob = server.join("MusicBob").await;
+            server.console("op MusicAlice").await;
+            server.tick(20).await;
+
+            let hashes = BlockEntityHashes::compute();
+            let note_block = BlockPos::new(2, 4, 0);
+            alice
+                .place_block(&mut server, BlockPos::new(2, 3, 0), 1, hashes.noteblock)
+                .await;
+            server.tick(1).await;
+            bob.clear();
+            // Right click tunes it up a semitone and plays it
+            alice.place_block(&mut server, note_block, 1, 0).await;
+            bob.receive(&mut server);
+            let sounds = bob.decoded::<LevelSoundEvent>(id::LEVEL_SOUND_EVENT);
+            let note = sounds
+                .iter()
+                .find(|s| s.sound_id == SOUND_NOTE)
+                .expect("the note was played");
+            assert_eq!(note.extra_data, 1);
+
+            let jukebox = BlockPos::new(3, 4, 0);
+            server.tick(20).await;
+            alice
+                .place_block(&mut server, BlockPos::new(3, 3, 0), 1, hashes.jukebox)
+                .await;
+            alice
+                .command(&mut server, "/give MusicAlice music_disc_cat")
+                .await;
+            server.tick(20).await;
+            bob.clear();
+            alice.place_block(&mut server, jukebox, 1, 0).await;
+            bob.receive(&mut server);
+            let sounds = bob.decoded::<LevelSoundEvent>(id::LEVEL_SOUND_EVENT);
+            assert!(sounds.iter().any(|s| s.sound_id == SOUND_RECORD_13 + 1));
+        });
+    }
+
+    #[test]
+    fn chest_shop_buys_and_sells() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.gamemode = "creative".into();
+            });
+            let mut alice = server.join("ShopAlice").await;
+            let mut bob = server.join("ShopBob").await;
+            server.tick(20).await;
+
+            // A chest with a wall sign above it, hung on a dirt pillar
+            let hashes = BlockEntityHashes::compute();
+            let dirt = FlatWorldBlocks::compute().dirt;
+            let chest = BlockPos::new(2, 4, 0);
+            let sign = BlockPos::new(2, 5, 0);
+            alice
+                .place_block(&mut server, BlockPos::new(2, 3, 0), 1, hashes.chest[0])
+                .await;
+            alice
+                .place_block(&mut server, BlockPos::new(3, 3, 0), 1, dirt)
+                .await;
+            alice
+                .place_block(&mut server, BlockPos::new(3, 4, 0), 1, dirt)
+                .await;
+            server.tick(20).await;
+            alice
+                .place_block(
+                    &mut server,
+                    BlockPos::new(3, 5, 0),
+                    4,
+                    hashes.standing_sign[0],
+                )
+                .await;
+            server.tick(20).await;
+            let text = BlockEntityData::Sign {
+                front: SignText::plain("[Shop]\n16 diamond\nB 10 : S 5"),
+                back: SignText::default(),
+                is_waxed: false,
+            };
+            alice.clear();
+            alice
+                .send(
+                    &mut server,
+                    id::BLOCK_ACTOR_DATA,
+                    &BlockActorData {
+                        position: sign,
+                        nbt_data: text.to_network_nbt(sign.x, sign.y, sign.z),
+                    },
+                )
+                .await;
+            server.tick(1).await;
+            alice.receive(&mut server);
+            assert!(alice.messages().contains(&"Shop created".to_string()));
+
+            server.console("eco give ShopAlice 100").await;
+            server.console("eco give ShopBob 20").await;
+            server.console("op ShopBob").await;
+            server.tick(20).await;
+            bob.command(&mut server, "/give ShopBob diamond 16").await;
+            server.console("deop ShopBob").await;
+            server.tick(20).await;
+
+            // Left click sells, right click buys back
+            bob.clear();
+            bob.player_action(&mut server, PlayerActionType::StartBreak, sign, 4)
+                .await;
+            server.tick(1).await;
+            bob.receive(&mut server);
+            assert!(bob
+                .messages()
+                .contains(&"Sold 16 diamond for 5 coins".to_string()));
+            bob.place_block(&mut server, sign, 4, 0).await;
+            server.tick(1).await;
+            bob.receive(&mut server);
+            assert!(bob
+                .messages()
+                .contains(&"Bought 16 diamond for 10 coins".to_string()));
+            bob.clear();
+            bob.place_block(&mut server, sign, 4, 0).await;
+            server.tick(1).await;
+            bob.receive(&mut server);
+            assert!(bob
+                .messages()
+                .contains(&"This shop is out of stock".to_string()));
+
+            assert_eq!(
+                server.console("balance ShopBob").await,
+                "ShopBob has 15 coins"
+            );
+            assert_eq!(
+                server.console("balance ShopAlice").await,
+                "ShopAlice has 105 coins"
+            );
+
+            // Only the owner can open or break the shop's chest
+            bob.clear();
+            bob.break_block(&mut server, chest).await;
+            server.tick(1).await;
+            bob.receive(&mut server);
+            assert!(bob
+                .messages()
+                .contains(&"This shop belongs to ShopAlice".to_string()));
+        });
+    }
+
+    #[test]
+    fn grindstone_strips_enchantments_for_xp() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.server.gamemode = "creative".into();
+            });
+            let mut alice = server.join("GrindAlice").await;
+            server.console("op GrindAlice").await;
+            server.tick(20).await;
+            alice
+                .command(
+                    &mut server,
+                    "/give GrindAlice diamond_sword{ench:[{id:9s,lvl:3s},{id:28s,lvl:1s}]}",
+                )
+                .await;
+            server.tick(20).await;
+
+            let grindstone = BlockPos::new(2, 4, 0);
+            let hashes = BlockEntityHashes::compute();
+            alice
+                .place_block(&mut server, BlockPos::new(2, 3, 0), 1, hashes.grindstone[0])
+                .await;
+            server.tick(20).await;
+            alice.clear();
+            alice.place_block(&mut server, grindstone, 1, 0).await;
+            let window_id = alice.decoded::<ContainerOpen>(id::CONTAINER_OPEN)[0].window_id;
+
+            let slot = |container_id, slot| StackSlot {
+                container_id,
+                slot,
+                stack_network_id: 0,
+            };
+            alice
+                .stack_request(
+                    &mut server,
+                    vec![StackAction::Place {
+                        count: 1,
+                        src: slot(CONTAINER_INVENTORY, 0),
+                        dst: slot(window_id, 0),
+                    }],
+                )
+                .await;
+            let request_id = alice
+                .stack_request(
+                    &mut server,
+                    vec![StackAction::CraftGrindstone {
+                        recipe_network_id: 0,
+                    }],
+                )
+                .await;
+
+            let responses = alice.decoded::<ItemStackResponse>(id::ITEM_STACK_RESPONSE);
+            let response = responses
+                .iter()
+                .flat_map(|r| r.responses.iter())
+                .find(|r| r.request_id == request_id)
+                .expect("the request was answered");
+            assert_eq!(response.status, 0);
+
+            // The XP orb waits on the grindstone: fly above it, then pick it
+            // up in survival, where XP counts
+            alice.move_to(&mut server, Vec3::new(2.5, 9.62, 0.5)).await;
+            alice
+                .command(&mut server, "/gamemode survival GrindAlice")
+                .await;
+            alice.clear();
+            alice.move_to(&mut server, Vec3::new(2.5, 6.62, 0.5)).await;
+            server.tick(40).await;
+            alice.receive(&mut server);
+            let experience: Vec<f32> = alice
+                .decoded::<UpdateAttributes>(id::UPDATE_ATTRIBUTES)
+                .iter()
+                .flat_map(|u| u.attributes.iter())
+                .filter(|a| a.name == "minecraft:player.experience")
+                .map(|a| a.current)
+                .collect();
+            assert!(experience.iter().any(|&xp| xp > 0.0), "{experience:?}");
+        });
+    }
+
+    #[test]
+    fn metadata_changes_reach_other_players() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("DataAlice").await;
+            let mut bob = server.join("DataBob").await;
+            server.console("op DataAlice").await;
+            server.tick(20).await;
+            bob.receive(&mut server);
+            bob.clear();
+
+            alice
+                .command(&mut server, "/effect DataAlice invisibility")
+                .await;
+            server.tick(1).await;
+            bob.receive(&mut server);
+            let updates: Vec<SetActorData> = bob
+                .decoded::<SetActorData>(id::SET_ACTOR_DATA)
+                .into_iter()
+                .filter(|u| u.entity_runtime_id == alice.runtime_id)
+                .collect();
+            // Only the flags changed
+            assert_eq!(updates.len(), 1);
+            assert_eq!(
+                updates[0].metadata,
+                [EntityMetadataEntry::new(
+                    key::FLAGS,
+                    MetadataValue::Long(EntityFlag::Invisible.bit())
+                )]
+            );
+
+            bob.clear();
+            server.tick(20).await;
+            bob.receive(&mut server);
+            assert!(bob.packets(id::SET_ACTOR_DATA).all(|p| p
+                .decode::<SetActorData>()
+                .unwrap()
+                .entity_runtime_id
+                != alice.runtime_id));
+        });
+    }
+
+    #[test]
+    fn sneaking_players_stay_on_edges() {
+        run(|| async {
+            let mut server = TestServer::start_with(|config| {
+                config.gameplay.sneak_edge_protection = true;
+            });
+            let mut alice = server.join("EdgeAlice").await;
+            let mut bob = server.join("EdgeBob").await;
+            server.console("op EdgeAlice").await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/gamemode creative EdgeAlice")
+                .await;
+            server.tick(20).await;
+
+            // Come down onto the grass at Y=3 and dig it out just east of Alice
+            let spawn = Vec3::new(alice.position.x, 5.62, alice.position.z);
+            alice.move_to(&mut server, spawn).await;
+            let (x, z) = (spawn.x.floor() as i32, spawn.z.floor() as i32);
+            alice
+                .command(
+                    &mut server,
+                    &format!("/fill {} 3 {} {} 3 {} air", x + 1, z - 1, x + 3, z + 1),
+                )
+                .await;
+            server.tick(20).await;
+            bob.receive(&mut server);
+            alice.clear();
+            bob.clear();
+
+            alice
+                .sneak_to(&mut server, Vec3::new(spawn.x + 2.0, spawn.y, spawn.z))
+                .await;
+            let resets = alice.decoded::<MovePlayer>(id::MOVE_PLAYER);
+            assert!(resets
+                .iter()
+                .any(|m| m.runtime_entity_id == alice.runtime_id && m.mode == MoveMode::Reset));
+
+            // Bob sees Alice crouch, with a shorter hitbox
+            bob.receive(&mut server);
+            let metadata: Vec<EntityMetadataEntry> = bob
+                .decoded::<SetActorData>(id::SET_ACTOR_DATA)
+                .into_iter()
+                .filter(|u| u.entity_runtime_id == alice.runtime_id)
+                .flat_map(|u| u.metadata)
+                .collect();
+            assert!(metadata.contains(&EntityMetadataEntry::new(
+                key::FLAGS,
+                MetadataValue::Long(EntityFlag::Sneaking.bit())
+            )));
+            assert!(metadata.contains(&EntityMetadataEntry::new(
+                key::BOUNDING_BOX_HEIGHT,
+                MetadataValue::Float(1.5)
+            )));
+        });
+    }
+
+    #[test]
+    fn climbing_ladders_is_not_flying() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("ClimbAlice").await;
+            let mut bob = server.join("ClimbBob").await;
+            server.console("op ClimbAlice").await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/gamemode creative ClimbAlice")
+                .await;
+            server.tick(20).await;
+            let spawn = Vec3::new(alice.position.x, 5.62, alice.position.z);
+            alice.move_to(&mut server, spawn).await;
+            let (x, z) = (spawn.x.floor() as i32, spawn.z.floor() as i32);
+            alice
+                .command(&mut server, &format!("/fill {x} 4 {z} {x} 60 {z} ladder"))
+                .await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/gamemode survival ClimbAlice")
+                .await;
+            server.tick(20).await;
+            alice.clear();
+
+            // Well past the airborne limit on the ladder...
+            alice
+                .move_to(&mut server, Vec3::new(spawn.x, spawn.y + 45.0, spawn.z))
+                .await;
+            let reset = |client: &FakeClient| {
+                client
+                    .decoded::<MovePlayer>(id::MOVE_PLAYER)
+                    .iter()
+                    .any(|m| m.runtime_entity_id == client.runtime_id && m.mode == MoveMode::Reset)
+            };
+            assert!(!reset(&alice));
+
+            // ...which rising through the air next to it doesn't get away with
+            let side = Vec3::new(spawn.x + 3.0, bob.position.y, spawn.z);
+            bob.move_to(&mut server, side).await;
+            bob.clear();
+            bob.move_to(&mut server, Vec3::new(side.x, side.y + 45.0, side.z))
+                .await;
+            assert!(reset(&bob));
+        });
+    }
+
+    #[test]
+    fn soul_sand_slows_players_down() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("SandAlice").await;
+            server.console("op SandAlice").await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/gamemode creative SandAlice")
+                .await;
+            server.tick(20).await;
+            let spawn = Vec3::new(alice.position.x, 5.62, alice.position.z);
+            alice.move_to(&mut server, spawn).await;
+            let (x, z) = (spawn.x.floor() as i32, spawn.z.floor() as i32);
+            alice
+                .command(
+                    &mut server,
+                    &format!("/fill {x} 3 {z} {} 3 {z} soul_sand", x + 10),
+                )
+                .await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/gamemode survival SandAlice")
+                .await;
+            server.tick(20).await;
+            let reset = |client: &FakeClient| {
+                client
+                    .decoded::<MovePlayer>(id::MOVE_PLAYER)
+                    .iter()
+                    .any(|m| m.runtime_entity_id == client.runtime_id && m.mode == MoveMode::Reset)
+            };
+
+            // Half a block a tick is fine on grass...
+            alice.clear();
+            alice
+                .move_to(&mut server, Vec3::new(spawn.x, spawn.y, spawn.z - 4.0))
+                .await;
+            assert!(!reset(&alice));
+
+            // ...but too fast on soul sand
+            alice.move_to(&mut server, spawn).await;
+            alice.clear();
+            alice
+                .move_to(&mut server, Vec3::new(spawn.x + 4.0, spawn.y, spawn.z))
+                .await;
+            assert!(reset(&alice));
+        });
+    }
+
+    #[test]
+    fn bubble_columns_form_and_magma_burns() {
+        run(|| async {
+            let mut server = TestServer::start();
+            let mut alice = server.join("WaterAlice").await;
+            server.console("op WaterAlice").await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/gamemode creative WaterAlice")
+                .await;
+            server.tick(20).await;
+            let spawn = Vec3::new(alice.position.x, 5.62, alice.position.z);
+            alice.move_to(&mut server, spawn).await;
+            let (x, z) = (spawn.x.floor() as i32, spawn.z.floor() as i32);
+
+            // Water stacked on soul sand turns into a lifting column
+            for (y, block) in [(3, "soul_sand"), (4, "water"), (5, "water")] {
+                alice
+                    .command(&mut server, &format!("/setblock {} {y} {z} {block}", x + 2))
+                    .await;
+                server.tick(20).await;
+            }
+            server.tick(20).await;
+            alice.receive(&mut server);
+            let column = TickBlocks::compute().bubble_column[0];
+            let updates = alice.decoded::<UpdateBlock>(id::UPDATE_BLOCK);
+            for y in [4, 5] {
+                assert!(updates
+                    .iter()
+                    .any(|u| u.position == BlockPos::new(x + 2, y, z) && u.runtime_id == column));
+            }
+
+            // Standing on magma burns, until Alice sneaks
+            alice
+                .command(&mut server, &format!("/setblock {x} 3 {z} magma"))
+                .await;
+            server.tick(20).await;
+            alice
+                .command(&mut server, "/gamemode survival WaterAlice")
+                .await;
+            alice.clear();
+            server.tick(40).await;
+            alice.receive(&mut server);
+            let health: Vec<f32> = alice
+                .decoded::<UpdateAttributes>(id::UPDATE_ATTRIBUTES)
+                .iter()
+                .flat_map(|u| u.attributes.iter())
+                .filter(|a| a.name == "minecraft:health")
+                .map(|a| a.current)
+                .collect();
+            assert!(health.iter().any(|&hp| hp < 20.0), "{health:?}");
+
+            alice.sneak_to(&mut server, spawn).await;
+            alice.clear();
+            server.tick(40).await;
+            alice.receive(&mut server);
+            assert!(alice
+                .decoded::<UpdateAttributes>(id::UPDATE_ATTRIBUTES)
+                .iter()
+                .flat_map(|u| u.attributes.iter())
+                .all(|a| a.name != "minecraft:health" || a.current >= *health.last().unwrap()));
+        });
+    }
+}
@@ -0,0 +1,198 @@
+//! A connection handler running without a socket.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Once};
+
+use bytes::Bytes;
+use mc_rs_raknet::{RakNetEvent, ServerCommand, ServerHandle, ServerMotd};
+use mc_rs_server::config::ServerConfig;
+use mc_rs_server::connection::ConnectionHandler;
+use tokio::sync::{mpsc, watch};
+
+use crate::client::FakeClient;
+
+/// Payloads the server may queue before they are routed to clients. The
+/// queue is drained after every event and tick, so it only has to hold what
+/// one of them sends (a client's spawn chunks, at most).
+const OUTBOX_CAPACITY: usize = 16_384;
+
+/// Configuration every test server starts from: an offline-mode flat world
+/// kept in memory.
+const BASE_CONFIG: &str = r#"
+[server]
+address = "127.0.0.1"
+port = 19132
+motd = "MC-RS Test Server"
+max_players = 20
+gamemode = "survival"
+difficulty = "normal"
+online_mode = false
+
+[world]
+name = "world"
+generator = "flat"
+seed = 0
+
+[logging]
+level = "warn"
+
+[storage]
+backend = "memory"
+"#;
+
+/// Source of unique world names and client ports within the test process.
+static NEXT_ID: AtomicU16 = AtomicU16::new(1);
+
+pub(crate) fn next_id() -> u16 {
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Move the process into a scratch directory, since the server keeps its
+/// worlds, plugins and permission files relative to the working directory.
+fn enter_scratch_dir() {
+    static ENTER: Once = Once::new();
+    ENTER.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("mc-rs-testing-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("failed to create the scratch directory");
+        std::env::set_current_dir(&dir).expect("failed to enter the scratch directory");
+    });
+}
+
+/// A headless server: a [`ConnectionHandler`] fed events directly, whose
+/// outgoing packets are held for the [`FakeClient`]s they are addressed to.
+pub struct TestServer {
+    handler: ConnectionHandler,
+    outbox: mpsc::Receiver<ServerCommand>,
+    inboxes: HashMap<SocketAddr, Vec<Bytes>>,
+    world_dir: PathBuf,
+}
+
+impl TestServer {
+    /// Start a server with the default test configuration.
+    pub fn start() -> Self {
+        Self::start_with(|_| {})
+    }
+
+    /// Start a server after adjusting the default test configuration. Each
+    /// server gets a world of its own.
+    pub fn start_with(configure: impl FnOnce(&mut ServerConfig)) -> Self {
+        enter_scratch_dir();
+        let mut config: ServerConfig =
+            toml::from_str(BASE_CONFIG).expect("the base test config is valid");
+        config.world.name = format!("test-{}", next_id());
+        configure(&mut config);
+
+        let motd = ServerMotd {
+            server_name: config.server.motd.clone(),
+            protocol_version: mc_rs_proto::packets::PROTOCOL_VERSION as u32,
+            game_version: mc_rs_proto::packets::game_version_for_protocol(
+                mc_rs_proto::packets::PROTOCOL_VERSION,
+            )
+            .into(),
+            online_players: 0,
+            max_players: config.server.max_players,
+            server_guid: 0,
+            world_name: config.world.name.clone(),
+            gamemode: config.server.gamemode.clone(),
+            gamemode_numeric: 1,
+            ipv4_port: config.server.port,
+            ipv6_port: config.server.port + 1,
+            is_editor_mode: 0,
+        };
+        let world_dir = PathBuf::from("worlds").join(&config.world.name);
+        let (server_handle, outbox) = ServerHandle::channel(OUTBOX_CAPACITY);
+        let (shutdown_tx, _) = watch::channel(false);
+        let handler = ConnectionHandler::new(
+            server_handle,
+            config.server.online_mode,
+            Arc::new(config),
+            Arc::new(shutdown_tx),
+            motd,
+        );
+        Self {
+            handler,
+            outbox,
+            inboxes: HashMap::new(),
+            world_dir,
+        }
+    }
+
+    /// Open a RakNet session for a new client, without logging it in.
+    pub async fn connect(&mut self, name: &str) -> FakeClient {
+        let id = next_id();
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, id));
+        self.handle(RakNetEvent::SessionConnected {
+            addr,
+            guid: id as i64,
+        })
+        .await;
+        FakeClient::new(name, addr)
+    }
+
+    /// Connect a client and take it through login and spawning.
+    pub async fn join(&mut self, name: &str) -> FakeClient {
+        let mut client = self.connect(name).await;
+        client.login(self).await;
+        client
+    }
+
+    /// Close a client's session, as if it had quit.
+    pub async fn disconnect(&mut self, client: &FakeClient) {
+        self.handle(RakNetEvent::SessionDisconnected { addr: client.addr })
+            .await;
+        self.inboxes.remove(&client.addr);
+    }
+
+    /// Run `ticks` game ticks. Clients pick up what was sent to them the
+    /// next time they send or [`receive`](FakeClient::receive).
+    pub async fn tick(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.handler.game_tick().await;
+            self.route_outbox();
+        }
+    }
+
+    /// Run a console command and return its output.
+    pub async fn console(&mut self, line: &str) -> String {
+        let output = self.handler.handle_console_command(line).await;
+        self.route_outbox();
+        output
+    }
+
+    /// The handler itself, for whatever the helpers don't cover.
+    pub fn handler(&mut self) -> &mut ConnectionHandler {
+        &mut self.handler
+    }
+
+    /// Hand the server a batch sent by a client.
+    pub(crate) async fn deliver(&mut self, addr: SocketAddr, payload: Bytes) {
+        self.handle(RakNetEvent::Packet { addr, payload }).await;
+    }
+
+    /// Payloads sent to `addr` since it last collected them.
+    pub(crate) fn take_inbox(&mut self, addr: SocketAddr) -> Vec<Bytes> {
+        self.inboxes.remove(&addr).unwrap_or_default()
+    }
+
+    async fn handle(&mut self, event: RakNetEvent) {
+        self.handler.handle_event(event).await;
+        self.route_outbox();
+    }
+
+    fn route_outbox(&mut self) {
+        while let Ok(command) = self.outbox.try_recv() {
+            if let ServerCommand::Send { addr, payload, .. } = command {
+                self.inboxes.entry(addr).or_default().push(payload);
+            }
+        }
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.world_dir);
+    }
+}
@@ -16,11 +16,16 @@ use rusty_leveldb::{LdbIterator, DB};
 
 use crate::block_hash::hash_block_state;
 use crate::block_state_registry::BlockStateRegistry;
+use crate::chunk::biomes_from_2d;
 use crate::chunk::{ChunkColumn, SubChunk, OVERWORLD_SUB_CHUNK_COUNT};
+use crate::storage::{
+    deserialize_data_2d, deserialize_data_3d, serialize_data_3d, ChunkStorageProvider,
+};
 
 // ─── LevelDB key tags (same as storage.rs) ──────────────────────────────────
 
 const TAG_CHUNK_VERSION: u8 = 0x2C;
+const TAG_DATA_3D: u8 = 0x2B;
 const TAG_DATA_2D: u8 = 0x2D;
 const TAG_SUB_CHUNK_PREFIX: u8 = 0x2F;
 const TAG_BLOCK_ENTITY: u8 = 0x31;
@@ -91,9 +96,13 @@ pub fn parse_bds_sub_chunk(data: &[u8]) -> Option<SubChunk> {
         return None;
     }
 
-    // Parse only the first layer (we only support 1 layer)
     let mut pos = 2;
-    parse_bds_layer(data, &mut pos)
+    let mut sub = parse_bds_layer(data, &mut pos)?;
+    // The second layer holds the water of waterlogged blocks
+    if num_layers > 1 {
+        sub.liquid = parse_bds_layer(data, &mut pos).map(Box::new);
+    }
+    Some(sub)
 }
 
 /// Parse one BDS layer starting at `pos`, returning a SubChunk with FNV-1a palette.
@@ -181,7 +190,11 @@ fn parse_bds_layer(data: &[u8], pos: &mut usize) -> Option<SubChunk> {
             }
         }
 
-        Some(SubChunk { blocks, palette })
+        Some(SubChunk {
+            blocks,
+            palette,
+            liquid: None,
+        })
     }
 }
 
@@ -321,12 +334,21 @@ fn read_u32_le(data: &[u8], pos: &mut usize) -> Option<u32> {
 
 /// Serialize a sub-chunk to BDS format (NBT compound palette).
 ///
-/// Format: `[version=9][num_layers=1][header][packed_blocks][palette_count:i32_le][NBT_LE_compound[]]`
+/// Format: `[version=9][num_layers][per layer: header, packed_blocks, palette_count:i32_le, NBT_LE_compound[]]`,
+/// with a second layer for the water of waterlogged blocks.
 pub fn serialize_bds_sub_chunk(sub: &SubChunk, registry: &BlockStateRegistry) -> Vec<u8> {
     let mut buf = Vec::new();
     buf.push(9); // version
-    buf.push(1); // num_layers
+    buf.push(1 + sub.liquid.is_some() as u8); // num_layers
+    write_bds_layer(&mut buf, sub, registry);
+    if let Some(liquid) = &sub.liquid {
+        write_bds_layer(&mut buf, liquid, registry);
+    }
+    buf
+}
 
+/// Write one layer of a sub-chunk in BDS format.
+fn write_bds_layer(buf: &mut Vec<u8>, sub: &SubChunk, registry: &BlockStateRegistry) {
     let palette_size = sub.palette.len();
 
     if palette_size <= 1 {
@@ -373,8 +395,6 @@ pub fn serialize_bds_sub_chunk(sub: &SubChunk, registry: &BlockStateRegistry) ->
             }
         }
     }
-
-    buf
 }
 
 /// Generate NBT LE for minecraft:air (fallback).
@@ -413,13 +433,13 @@ pub struct ImportResult {
     pub block_entities: usize,
 }
 
-/// Import a BDS world into our LevelDB format.
+/// Import a BDS world into any of our storage backends.
 ///
 /// Opens the BDS LevelDB at `bds_path/db`, converts sub-chunks from NBT palette
 /// to FNV-1a hash palette, and saves them to `target`.
 pub fn import_bds_world(
     bds_db_path: &Path,
-    target: &mut crate::storage::LevelDbProvider,
+    target: &mut dyn ChunkStorageProvider,
     dim: i32,
 ) -> Result<ImportResult, String> {
     let opts = rusty_leveldb::Options {
@@ -458,13 +478,15 @@ pub fn import_bds_world(
             .try_into()
             .unwrap_or_else(|_| panic!("expected {OVERWORLD_SUB_CHUNK_COUNT} sub-chunks"));
 
-        // Load biomes (Data2D)
-        let data_2d_key = chunk_key_dim(*cx, *cz, dim, TAG_DATA_2D);
-        let biomes = if let Some(data) = bds_db.get(&data_2d_key) {
-            deserialize_data_2d(&data).unwrap_or([0u8; 256])
-        } else {
-            [0u8; 256]
-        };
+        // Load biomes (Data3D, or Data2D from worlds older than 1.18)
+        let data_3d = bds_db.get(&chunk_key_dim(*cx, *cz, dim, TAG_DATA_3D));
+        let data_2d = bds_db.get(&chunk_key_dim(*cx, *cz, dim, TAG_DATA_2D));
+        let biomes = match (data_3d, data_2d) {
+            (Some(data), _) => deserialize_data_3d(&data),
+            (None, Some(data)) => deserialize_data_2d(&data).map(|b| biomes_from_2d(&b)),
+            (None, None) => None,
+        }
+        .unwrap_or([[0u8; 64]; OVERWORLD_SUB_CHUNK_COUNT]);
 
         let column = ChunkColumn {
             x: *cx,
@@ -567,16 +589,11 @@ pub fn export_bds_world(
         db.put(&version_key, &[40])
             .map_err(|e| format!("put version: {e}"))?;
 
-        // Write Data2D (heightmap + biomes)
-        let data_2d_key = chunk_key_dim(cx, cz, dim, TAG_DATA_2D);
+        // Write Data3D (heightmap + biomes)
+        let data_3d_key = chunk_key_dim(cx, cz, dim, TAG_DATA_3D);
         let heightmap = compute_heightmap(column, air_hash);
-        let mut data_2d = Vec::with_capacity(768);
-        for &h in &heightmap {
-            data_2d.extend_from_slice(&h.to_le_bytes());
-        }
-        data_2d.extend_from_slice(&column.biomes);
-        db.put(&data_2d_key, &data_2d)
-            .map_err(|e| format!("put data2d: {e}"))?;
+        db.put(&data_3d_key, &serialize_data_3d(&heightmap, &column.biomes))
+            .map_err(|e| format!("put data3d: {e}"))?;
 
         // Write sub-chunks in BDS format
         for (i, sub_chunk) in column.sub_chunks.iter().enumerate() {
@@ -599,16 +616,6 @@ pub fn export_bds_world(
     Ok(result)
 }
 
-/// Deserialize Data2D: skip heightmap, read biomes.
-fn deserialize_data_2d(data: &[u8]) -> Option<[u8; 256]> {
-    if data.len() < 768 {
-        return None;
-    }
-    let mut biomes = [0u8; 256];
-    biomes.copy_from_slice(&data[512..768]);
-    Some(biomes)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
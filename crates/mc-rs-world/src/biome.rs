@@ -233,6 +233,60 @@ impl BiomeSelector {
     }
 }
 
+/// Biome of the whole Nether (nether wastes).
+pub const NETHER_BIOME: u8 = 8;
+
+/// Biome of the whole End.
+pub const END_BIOME: u8 = 9;
+
+/// Biomes of the other dimensions, which have no definition.
+const DIMENSION_BIOMES: &[(u8, &str)] = &[(NETHER_BIOME, "hell"), (END_BIOME, "the_end")];
+
+/// Look up a biome ID by name, with or without the `minecraft:` prefix.
+pub fn biome_id(name: &str) -> Option<u8> {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    BIOME_DEFS
+        .iter()
+        .map(|b| (b.id, b.name))
+        .chain(DIMENSION_BIOMES.iter().copied())
+        .find(|&(_, n)| n == name)
+        .map(|(id, _)| id)
+}
+
+/// Where the biome `id` is nearest to `(x, z)`, as `biome_at` reports
+/// biomes, within `radius` blocks: sampled every `step` blocks in square
+/// rings growing from `(x, z)`, the closest match in the first ring that
+/// has one.
+pub fn locate_biome(
+    x: i32,
+    z: i32,
+    id: u8,
+    radius: i32,
+    step: i32,
+    biome_at: impl Fn(i32, i32) -> u8,
+) -> Option<(i32, i32)> {
+    let distance = |(bx, bz): (i32, i32)| (bx - x).pow(2) as i64 + (bz - z).pow(2) as i64;
+    for ring in 0..=radius / step {
+        let r = ring * step;
+        let found = (-ring..=ring)
+            .flat_map(|i| {
+                let i = i * step;
+                [
+                    (x + i, z - r),
+                    (x + i, z + r),
+                    (x - r, z + i),
+                    (x + r, z + i),
+                ]
+            })
+            .filter(|&(bx, bz)| biome_at(bx, bz) == id)
+            .min_by_key(|&pos| distance(pos));
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
 /// Look up a biome definition by its protocol ID.
 fn biome_by_id(id: u8) -> &'static BiomeDef {
     BIOME_DEFS
@@ -241,6 +295,16 @@ fn biome_by_id(id: u8) -> &'static BiomeDef {
         .unwrap_or(&BIOME_DEFS[1]) // fallback: plains
 }
 
+/// Vanilla Bedrock biomes cold enough to snow in, for imported worlds: frozen
+/// ocean and river, ice plains and mountains, cold beach, the cold taigas
+/// and ice spikes.
+const VANILLA_SNOWY_BIOMES: &[u8] = &[10, 11, 12, 13, 26, 30, 31, 140, 158];
+
+/// Whether rain falls as snow in a biome.
+pub fn is_snowy_biome(id: u8) -> bool {
+    VANILLA_SNOWY_BIOMES.contains(&id) || BIOME_DEFS.iter().any(|b| b.id == id && b.has_snow)
+}
+
 /// Get the static biome definitions table.
 pub fn biome_defs() -> &'static [BiomeDef] {
     BIOME_DEFS
@@ -250,6 +314,14 @@ pub fn biome_defs() -> &'static [BiomeDef] {
 mod tests {
     use super::*;
 
+    #[test]
+    fn snowy_biomes() {
+        assert!(is_snowy_biome(12)); // ice plains
+        assert!(is_snowy_biome(30)); // cold taiga
+        assert!(!is_snowy_biome(1)); // plains
+        assert!(!is_snowy_biome(2)); // desert
+    }
+
     #[test]
     fn biome_ids_match_protocol() {
         assert_eq!(biome_by_id(0).name, "ocean");
@@ -315,6 +387,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn biome_ids_by_name() {
+        assert_eq!(biome_id("desert"), Some(2));
+        assert_eq!(biome_id("minecraft:birch_forest"), Some(27));
+        assert_eq!(biome_id("the_end"), Some(END_BIOME));
+        assert_eq!(biome_id("moon"), None);
+    }
+
+    #[test]
+    fn locate_nearest_biome() {
+        // Desert east of x = 100, ocean north of z = -300
+        let biome_at = |x: i32, z: i32| match (x, z) {
+            (_, ..=-300) => 0,
+            (100.., _) => 2,
+            _ => 1,
+        };
+        assert_eq!(locate_biome(0, 0, 1, 1000, 32, biome_at), Some((0, 0)));
+        assert_eq!(locate_biome(0, 0, 2, 1000, 32, biome_at), Some((128, 0)));
+        assert_eq!(locate_biome(0, 0, 0, 1000, 32, biome_at), Some((0, -320)));
+        assert_eq!(locate_biome(0, 0, 0, 200, 32, biome_at), None);
+        assert_eq!(locate_biome(0, 0, 35, 1000, 32, biome_at), None);
+    }
+
     #[test]
     fn fallback_biome_is_plains() {
         let biome = biome_by_id(255); // Non-existent ID
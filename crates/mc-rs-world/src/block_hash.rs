@@ -6,6 +6,8 @@
 
 use bytes::{BufMut, BytesMut};
 
+use crate::biome::TreeType;
+
 /// FNV-1a 32-bit offset basis.
 const FNV1_32_INIT: u32 = 0x811c_9dc5;
 /// FNV-1a 32-bit prime.
@@ -428,6 +430,9 @@ pub struct TickBlocks {
     pub potatoes: [u32; 8],
     pub beetroot: [u32; 4],
     pub farmland: [u32; 8],
+    // What else a hoe tills
+    pub coarse_dirt: u32,
+    pub grass_path: u32,
     // Fluids (liquid_depth 0..15)
     pub water: [u32; 16],
     pub lava: [u32; 16],
@@ -466,6 +471,11 @@ pub struct TickBlocks {
     pub sticky_piston: [u32; 6],
     pub piston_arm: [u32; 6],
     pub sticky_piston_arm: [u32; 6],
+    // Command blocks: [mode 0-2][facing_direction 0-5][conditional_bit]
+    // (0 = impulse, 1 = repeating, 2 = chain)
+    pub command_block: [[[u32; 2]; 6]; 3],
+    // Activator rail: [rail_direction 0-5][rail_data_bit]
+    pub activator_rail: [[u32; 2]; 6],
     // Immovable blocks (for piston push rejection)
     pub bedrock: u32,
     pub enchanting_table_tick: u32,
@@ -475,6 +485,27 @@ pub struct TickBlocks {
     pub end_portal: u32,                 // minecraft:end_portal
     pub end_portal_frame: [[u32; 2]; 4], // [direction 0-3][end_portal_eye_bit 0/1]
     pub fire: u32,                       // minecraft:fire
+    // Cake (bite_counter 0..6)
+    pub cake: [u32; 7],
+    // Fall-damage modifying blocks
+    pub hay_block: [u32; 3], // pillar_axis x/y/z
+    pub slime: u32,
+    pub cobweb: u32,
+    // Snow layer (height 0..7, uncovered), plus the stateless hash generators place
+    pub snow_layer: [u32; 8],
+    pub snow_layer_plain: u32,
+    // Water cauldron (fill_level 0..6)
+    pub cauldron: [u32; 7],
+    // Water as generators place it, without a liquid_depth
+    pub water_plain: u32,
+    // Bubble column (drag_down 0/1) and the blocks that feed one
+    pub bubble_column: [u32; 2],
+    pub soul_sand: u32,
+    pub magma: u32,
+    // Saplings (age_bit 0/1), by tree type: oak, birch, spruce, acacia
+    pub sapling: [[u32; 2]; 4],
+    // Logs as players place them (pillar_axis x/y/z), in the same order
+    pub log_axes: [[u32; 3]; 4],
 }
 
 impl TickBlocks {
@@ -592,6 +623,41 @@ impl TickBlocks {
             );
         }
 
+        // Command blocks: facing_direction (Int 0-5) + conditional_bit (Byte)
+        let mut command_block = [[[0u32; 2]; 6]; 3];
+        let names = [
+            "minecraft:command_block",
+            "minecraft:repeating_command_block",
+            "minecraft:chain_command_block",
+        ];
+        for (facings, name) in command_block.iter_mut().zip(names) {
+            for (dir, entry) in facings.iter_mut().enumerate() {
+                for conditional in 0..2i8 {
+                    entry[conditional as usize] = hash_block_state_with_props(
+                        name,
+                        &[
+                            ("conditional_bit", StateValue::Byte(conditional)),
+                            ("facing_direction", StateValue::Int(dir as i32)),
+                        ],
+                    );
+                }
+            }
+        }
+
+        // Activator rail: rail_data_bit (Byte) + rail_direction (Int 0-5)
+        let mut activator_rail = [[0u32; 2]; 6];
+        for (dir, entry) in activator_rail.iter_mut().enumerate() {
+            for data in 0..2i8 {
+                entry[data as usize] = hash_block_state_with_props(
+                    "minecraft:activator_rail",
+                    &[
+                        ("rail_data_bit", StateValue::Byte(data)),
+                        ("rail_direction", StateValue::Int(dir as i32)),
+                    ],
+                );
+            }
+        }
+
         Self {
             air: hash_block_state("minecraft:air"),
             dirt: hash_block_state("minecraft:dirt"),
@@ -601,6 +667,8 @@ impl TickBlocks {
             potatoes,
             beetroot,
             farmland,
+            coarse_dirt: hash_block_state("minecraft:coarse_dirt"),
+            grass_path: hash_block_state("minecraft:grass_path"),
             water,
             lava,
             sand: hash_block_state("minecraft:sand"),
@@ -628,6 +696,8 @@ impl TickBlocks {
             sticky_piston,
             piston_arm,
             sticky_piston_arm,
+            command_block,
+            activator_rail,
             bedrock: hash_block_state("minecraft:bedrock"),
             enchanting_table_tick: hash_block_state("minecraft:enchanting_table"),
             nether_portal_x: hash_block_state_with_props(
@@ -655,9 +725,103 @@ impl TickBlocks {
                 epf
             },
             fire: hash_block_state("minecraft:fire"),
+            cake: {
+                let mut cake = [0u32; 7];
+                for (i, slot) in cake.iter_mut().enumerate() {
+                    *slot = hash_block_state_with_int("minecraft:cake", "bite_counter", i as i32);
+                }
+                cake
+            },
+            hay_block: ["x", "y", "z"].map(|axis| {
+                hash_block_state_with_props(
+                    "minecraft:hay_block",
+                    &[
+                        ("deprecated", StateValue::Int(0)),
+                        ("pillar_axis", StateValue::Str(axis)),
+                    ],
+                )
+            }),
+            slime: hash_block_state("minecraft:slime"),
+            cobweb: hash_block_state("minecraft:web"),
+            snow_layer: std::array::from_fn(|height| {
+                hash_block_state_with_props(
+                    "minecraft:snow_layer",
+                    &[
+                        ("covered_bit", StateValue::Byte(0)),
+                        ("height", StateValue::Int(height as i32)),
+                    ],
+                )
+            }),
+            snow_layer_plain: hash_block_state("minecraft:snow_layer"),
+            cauldron: std::array::from_fn(|level| {
+                hash_block_state_with_props(
+                    "minecraft:cauldron",
+                    &[
+                        ("cauldron_liquid", StateValue::Str("water")),
+                        ("fill_level", StateValue::Int(level as i32)),
+                    ],
+                )
+            }),
+            water_plain: hash_block_state("minecraft:water"),
+            bubble_column: [0, 1].map(|drag_down| {
+                hash_block_state_with_props(
+                    "minecraft:bubble_column",
+                    &[("drag_down", StateValue::Byte(drag_down))],
+                )
+            }),
+            soul_sand: hash_block_state("minecraft:soul_sand"),
+            magma: hash_block_state("minecraft:magma"),
+            log_axes: ["oak", "birch", "spruce", "acacia"].map(|wood| {
+                ["x", "y", "z"].map(|axis| {
+                    hash_block_state_with_props(
+                        &format!("minecraft:{wood}_log"),
+                        &[("pillar_axis", StateValue::Str(axis))],
+                    )
+                })
+            }),
+            sapling: ["oak", "birch", "spruce", "acacia"].map(|wood| {
+                [0, 1].map(|age| {
+                    hash_block_state_with_props(
+                        &format!("minecraft:{wood}_sapling"),
+                        &[("age_bit", StateValue::Byte(age))],
+                    )
+                })
+            }),
+        }
+    }
+
+    /// The tree a sapling grows into, and whether it has aged enough to
+    /// grow. None if not a sapling.
+    pub fn sapling_growth(&self, rid: u32) -> Option<(TreeType, bool)> {
+        const TREES: [TreeType; 4] = [
+            TreeType::Oak,
+            TreeType::Birch,
+            TreeType::Spruce,
+            TreeType::Acacia,
+        ];
+        self.sapling.iter().zip(TREES).find_map(|(ages, tree)| {
+            ages.iter()
+                .position(|&h| h == rid)
+                .map(|age| (tree, age == 1))
+        })
+    }
+
+    /// Log and leaves of a kind of tree.
+    pub fn tree_blocks(&self, tree_type: TreeType) -> Option<(u32, u32)> {
+        match tree_type {
+            TreeType::Oak => Some((self.oak_log, self.oak_leaves)),
+            TreeType::Birch => Some((self.birch_log, self.birch_leaves)),
+            TreeType::Spruce => Some((self.spruce_log, self.spruce_leaves)),
+            TreeType::Acacia => Some((self.acacia_log, self.acacia_leaves)),
+            TreeType::None => None,
         }
     }
 
+    /// Moisture of a farmland block, or None if not farmland.
+    pub fn farmland_moisture(&self, rid: u32) -> Option<usize> {
+        self.farmland.iter().position(|&h| h == rid)
+    }
+
     /// Check if a runtime ID is any leaf type.
     pub fn is_leaf(&self, rid: u32) -> bool {
         rid == self.oak_leaves
@@ -672,6 +836,7 @@ impl TickBlocks {
             || rid == self.birch_log
             || rid == self.spruce_log
             || rid == self.acacia_log
+            || self.log_axes.iter().flatten().any(|&h| h == rid)
     }
 
     /// Find the growth stage of a crop block. Returns None if not a crop.
@@ -902,6 +1067,83 @@ impl TickBlocks {
         None
     }
 
+    /// Get the number of slices eaten (0-6) from a cake. Returns None if not a cake.
+    pub fn cake_bites(&self, rid: u32) -> Option<usize> {
+        self.cake.iter().position(|&h| h == rid)
+    }
+
+    /// Get the height (0-7, one less than the layer count) of a snow layer.
+    /// Returns None if not a snow layer.
+    pub fn snow_layer_height(&self, rid: u32) -> Option<usize> {
+        if rid == self.snow_layer_plain {
+            return Some(0);
+        }
+        self.snow_layer.iter().position(|&h| h == rid)
+    }
+
+    /// Get the water level (0-6) of a cauldron. Returns None if not a water cauldron.
+    pub fn cauldron_fill_level(&self, rid: u32) -> Option<usize> {
+        self.cauldron.iter().position(|&h| h == rid)
+    }
+
+    /// Check if a runtime ID is a hay bale (any axis).
+    pub fn is_hay_block(&self, rid: u32) -> bool {
+        self.hay_block.contains(&rid)
+    }
+
+    /// Check if a runtime ID is a slime block.
+    pub fn is_slime(&self, rid: u32) -> bool {
+        rid == self.slime
+    }
+
+    /// Check if a runtime ID is a cobweb.
+    pub fn is_cobweb(&self, rid: u32) -> bool {
+        rid == self.cobweb
+    }
+
+    /// Check if a runtime ID is a magma block.
+    pub fn is_magma(&self, rid: u32) -> bool {
+        rid == self.magma
+    }
+
+    /// Check if a runtime ID is a bubble column (either direction).
+    pub fn is_bubble_column(&self, rid: u32) -> bool {
+        self.bubble_column.contains(&rid)
+    }
+
+    /// Whether a bubble column drags down (true) or lifts up (false).
+    /// Returns None if not a bubble column.
+    pub fn bubble_column_drag_down(&self, rid: u32) -> Option<bool> {
+        self.bubble_column
+            .iter()
+            .position(|&h| h == rid)
+            .map(|i| i == 1)
+    }
+
+    /// The bubble column a block feeds into the water above it: soul sand
+    /// lifts, magma drags down, and a column carries on its own direction.
+    pub fn bubble_column_base(&self, rid: u32) -> Option<bool> {
+        if rid == self.soul_sand {
+            Some(false)
+        } else if rid == self.magma {
+            Some(true)
+        } else {
+            self.bubble_column_drag_down(rid)
+        }
+    }
+
+    /// Check if a runtime ID is a still water block: a source, with or
+    /// without its liquid_depth.
+    pub fn is_water_source(&self, rid: u32) -> bool {
+        rid == self.water[0] || rid == self.water_plain
+    }
+
+    /// Whether an entity inside this block is in water: any water block or
+    /// a bubble column.
+    pub fn holds_water(&self, rid: u32) -> bool {
+        self.is_water(rid) || rid == self.water_plain || self.is_bubble_column(rid)
+    }
+
     /// Get the delay setting (0-3) of a repeater.
     pub fn repeater_delay(&self, rid: u32) -> Option<u8> {
         for dir in 0..4 {
@@ -995,6 +1237,36 @@ impl TickBlocks {
             || rid == self.bedrock
             || rid == self.enchanting_table_tick
             || self.is_piston_arm(rid)
+            || self.command_block_state(rid).is_some()
+    }
+
+    // -----------------------------------------------------------------------
+    // Command block helpers
+    // -----------------------------------------------------------------------
+
+    /// Get the (mode, facing_direction, conditional) of a command block.
+    /// Mode is 0 = impulse, 1 = repeating, 2 = chain.
+    pub fn command_block_state(&self, rid: u32) -> Option<(u32, u8, bool)> {
+        for (mode, facings) in self.command_block.iter().enumerate() {
+            for (facing, states) in facings.iter().enumerate() {
+                if let Some(conditional) = states.iter().position(|&h| h == rid) {
+                    return Some((mode as u32, facing as u8, conditional == 1));
+                }
+            }
+        }
+        None
+    }
+
+    /// Get the command block hash for a mode, facing_direction and conditional flag.
+    pub fn command_block_with(&self, mode: u32, facing: u8, conditional: bool) -> u32 {
+        self.command_block[(mode as usize).min(2)][(facing as usize).min(5)][conditional as usize]
+    }
+
+    /// Check if a runtime ID is an activator rail (powered or not).
+    pub fn is_activator_rail(&self, rid: u32) -> bool {
+        self.activator_rail
+            .iter()
+            .any(|pair| rid == pair[0] || rid == pair[1])
     }
 
     /// Check if a runtime ID is a nether portal block (any axis).
@@ -1059,6 +1331,17 @@ pub enum FluidType {
 /// Cardinal direction strings for furnace/blast_furnace/smoker (sorted alphabetically for hash).
 const CARDINAL_DIRS: [&str; 4] = ["east", "north", "south", "west"];
 
+/// Mob head blocks, indexed by their `SkullType` in the block entity.
+pub const HEAD_BLOCKS: [&str; 7] = [
+    "minecraft:skeleton_skull",
+    "minecraft:wither_skeleton_skull",
+    "minecraft:zombie_head",
+    "minecraft:player_head",
+    "minecraft:creeper_head",
+    "minecraft:dragon_head",
+    "minecraft:piglin_head",
+];
+
 /// Furnace variant type (mirrors mc_rs_game::smelting::FurnaceType without dep).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FurnaceVariant {
@@ -1095,6 +1378,25 @@ pub struct BlockEntityHashes {
     pub loom: [u32; 4],
     /// Anvil: `minecraft:cardinal_direction` (4 dirs) × `damage` (3 states) = 12 hashes.
     pub anvil: Vec<u32>,
+    /// Cartography table (no directional state).
+    pub cartography_table: u32,
+    /// Beacon (no directional state).
+    pub beacon: u32,
+    /// Beacon pyramid base blocks: iron, gold, diamond, emerald, netherite.
+    pub beacon_base: [u32; 5],
+    /// Bed: `direction` (Int 0-3) × `head_piece_bit` × `occupied_bit` = 16 hashes.
+    pub bed: Vec<u32>,
+    /// Mob heads: `facing_direction` 0-5 for each of [`HEAD_BLOCKS`] =
+    /// 42 hashes.
+    pub head: Vec<u32>,
+    /// Note block (no state; the pitch is in its block entity).
+    pub noteblock: u32,
+    /// Jukebox (no state).
+    pub jukebox: u32,
+    /// Standing banner: `ground_sign_direction` 0-15.
+    pub standing_banner: [u32; 16],
+    /// Wall banner: `facing_direction` 2-5 (index 0-3 maps to face 2-5).
+    pub wall_banner: [u32; 4],
 }
 
 impl BlockEntityHashes {
@@ -1199,6 +1501,54 @@ impl BlockEntityHashes {
             }
         }
 
+        let mut bed = Vec::with_capacity(16);
+        for dir in 0..4 {
+            for head in 0..2 {
+                for occupied in 0..2 {
+                    bed.push(hash_block_state_with_props(
+                        "minecraft:bed",
+                        &[
+                            ("direction", StateValue::Int(dir)),
+                            ("head_piece_bit", StateValue::Byte(head)),
+                            ("occupied_bit", StateValue::Byte(occupied)),
+                        ],
+                    ));
+                }
+            }
+        }
+
+        let mut head = Vec::with_capacity(HEAD_BLOCKS.len() * 6);
+        for name in &HEAD_BLOCKS {
+            for face in 0..6 {
+                head.push(hash_block_state_with_int(name, "facing_direction", face));
+            }
+        }
+
+        let cartography_table = hash_block_state("minecraft:cartography_table");
+
+        let mut standing_banner = [0u32; 16];
+        for (i, hash) in standing_banner.iter_mut().enumerate() {
+            *hash = hash_block_state_with_int(
+                "minecraft:standing_banner",
+                "ground_sign_direction",
+                i as i32,
+            );
+        }
+        let mut wall_banner = [0u32; 4];
+        for (idx, face) in (2..=5).enumerate() {
+            wall_banner[idx] =
+                hash_block_state_with_int("minecraft:wall_banner", "facing_direction", face);
+        }
+
+        let beacon = hash_block_state("minecraft:beacon");
+        let beacon_base = [
+            hash_block_state("minecraft:iron_block"),
+            hash_block_state("minecraft:gold_block"),
+            hash_block_state("minecraft:diamond_block"),
+            hash_block_state("minecraft:emerald_block"),
+            hash_block_state("minecraft:netherite_block"),
+        ];
+
         Self {
             standing_sign,
             wall_sign,
@@ -1215,6 +1565,15 @@ impl BlockEntityHashes {
             grindstone,
             loom,
             anvil,
+            cartography_table,
+            beacon,
+            beacon_base,
+            bed,
+            head,
+            noteblock: hash_block_state("minecraft:noteblock"),
+            jukebox: hash_block_state("minecraft:jukebox"),
+            standing_banner,
+            wall_banner,
         }
     }
 
@@ -1223,6 +1582,22 @@ impl BlockEntityHashes {
         self.standing_sign.contains(&rid) || self.wall_sign.contains(&rid)
     }
 
+    /// Horizontal unit vector the front face of a sign points along, as
+    /// `(x, z)`. Returns `None` if the block is not a sign.
+    pub fn sign_front_normal(&self, rid: u32) -> Option<(f32, f32)> {
+        if let Some(dir) = self.standing_sign.iter().position(|&h| h == rid) {
+            // Direction 0 faces south, each step turns 22.5° clockwise
+            let angle = (dir as f32 * 22.5).to_radians();
+            return Some((-angle.sin(), angle.cos()));
+        }
+        match self.wall_sign.iter().position(|&h| h == rid)? {
+            0 => Some((0.0, -1.0)),
+            1 => Some((0.0, 1.0)),
+            2 => Some((-1.0, 0.0)),
+            _ => Some((1.0, 0.0)),
+        }
+    }
+
     /// Check if a block runtime ID is a chest.
     pub fn is_chest(&self, rid: u32) -> bool {
         self.chest.contains(&rid)
@@ -1268,6 +1643,67 @@ impl BlockEntityHashes {
         self.anvil.contains(&rid)
     }
 
+    /// Check if a block runtime ID is a cartography table.
+    pub fn is_cartography_table(&self, rid: u32) -> bool {
+        rid == self.cartography_table
+    }
+
+    /// Check if a block runtime ID is a beacon.
+    pub fn is_beacon(&self, rid: u32) -> bool {
+        rid == self.beacon
+    }
+
+    /// Check if a block runtime ID can form part of a beacon pyramid.
+    pub fn is_beacon_base(&self, rid: u32) -> bool {
+        self.beacon_base.contains(&rid)
+    }
+
+    /// Check if a block runtime ID is either half of a bed.
+    pub fn is_bed(&self, rid: u32) -> bool {
+        self.bed.contains(&rid)
+    }
+
+    /// Check if a block runtime ID is a note block.
+    pub fn is_noteblock(&self, rid: u32) -> bool {
+        rid == self.noteblock
+    }
+
+    /// Check if a block runtime ID is a jukebox.
+    pub fn is_jukebox(&self, rid: u32) -> bool {
+        rid == self.jukebox
+    }
+
+    /// Check if a block runtime ID is a standing or wall banner.
+    pub fn is_banner(&self, rid: u32) -> bool {
+        self.standing_banner.contains(&rid) || self.wall_banner.contains(&rid)
+    }
+
+    /// Get the standing banner hash for a player yaw, like standing signs.
+    pub fn standing_banner_direction(&self, yaw: f32) -> u32 {
+        let dir = (((yaw + 180.0) * 16.0 / 360.0).floor() as i32).rem_euclid(16);
+        self.standing_banner[dir as usize]
+    }
+
+    /// Get the wall banner hash for a given face (2-5), or `None` for
+    /// top/bottom.
+    pub fn wall_banner_face(&self, face: i32) -> Option<u32> {
+        (2..=5)
+            .contains(&face)
+            .then(|| self.wall_banner[(face - 2) as usize])
+    }
+
+    /// Index into [`HEAD_BLOCKS`] of a mob head and its `facing_direction`
+    /// (1 = on the floor, 2-5 = on a wall).
+    pub fn head_kind(&self, rid: u32) -> Option<(usize, i32)> {
+        let i = self.head.iter().position(|&h| h == rid)?;
+        Some((i / 6, (i % 6) as i32))
+    }
+
+    /// Get the hash of a mob head with the given `facing_direction`.
+    pub fn head_facing(&self, kind: usize, face: i32) -> u32 {
+        self.head[kind * 6 + face.clamp(0, 5) as usize]
+    }
+
     /// Check if a block runtime ID is a lit furnace variant.
     pub fn is_lit_furnace(&self, rid: u32) -> bool {
         self.lit_furnace.contains(&rid)
@@ -1552,6 +1988,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn tick_blocks_bubble_columns() {
+        let tb = TickBlocks::compute();
+        assert_ne!(tb.bubble_column[0], tb.bubble_column[1]);
+        assert_eq!(tb.bubble_column_drag_down(tb.bubble_column[1]), Some(true));
+        assert_eq!(tb.bubble_column_base(tb.soul_sand), Some(false));
+        assert_eq!(tb.bubble_column_base(tb.magma), Some(true));
+        assert_eq!(tb.bubble_column_base(tb.bubble_column[0]), Some(false));
+        assert_eq!(tb.bubble_column_base(tb.stone), None);
+        assert!(tb.holds_water(tb.bubble_column[1]));
+        assert!(tb.holds_water(tb.water_plain));
+        assert!(tb.holds_water(tb.water[3]));
+        assert!(!tb.holds_water(tb.lava[0]));
+        assert!(tb.is_water_source(tb.water_plain));
+        assert!(!tb.is_water_source(tb.water[1]));
+    }
+
     #[test]
     fn tick_blocks_water_depths_all_distinct() {
         let tb = TickBlocks::compute();
@@ -1587,6 +2040,23 @@ mod tests {
         assert!(!tb.is_log(tb.oak_leaves));
     }
 
+    #[test]
+    fn tick_blocks_command_block_state() {
+        let tb = TickBlocks::compute();
+        let rid = tb.command_block_with(2, 4, true);
+        assert_eq!(tb.command_block_state(rid), Some((2, 4, true)));
+        // The item places the default state: impulse, facing down, unconditional
+        assert_eq!(
+            tb.command_block_state(tb.command_block[0][0][0]),
+            Some((0, 0, false))
+        );
+        assert_ne!(tb.command_block[0][0][0], tb.command_block[1][0][0]);
+        assert_eq!(tb.command_block_state(tb.stone), None);
+        assert!(tb.is_immovable(rid));
+        assert!(tb.is_activator_rail(tb.activator_rail[3][1]));
+        assert!(!tb.is_activator_rail(tb.stone));
+    }
+
     #[test]
     fn tick_blocks_compatible_with_world_blocks() {
         let tb = TickBlocks::compute();
@@ -1641,6 +2111,18 @@ mod tests {
         assert_eq!(hash, beh.standing_sign[8]);
     }
 
+    #[test]
+    fn sign_front_normals() {
+        let beh = BlockEntityHashes::compute();
+        let (x, z) = beh.sign_front_normal(beh.standing_sign[0]).unwrap();
+        assert!(x.abs() < 1e-6 && (z - 1.0).abs() < 1e-6);
+        let (x, z) = beh.sign_front_normal(beh.standing_sign[4]).unwrap();
+        assert!((x + 1.0).abs() < 1e-6 && z.abs() < 1e-6);
+        assert_eq!(beh.sign_front_normal(beh.wall_sign[0]), Some((0.0, -1.0)));
+        assert_eq!(beh.sign_front_normal(beh.wall_sign[3]), Some((1.0, 0.0)));
+        assert_eq!(beh.sign_front_normal(beh.chest[0]), None);
+    }
+
     #[test]
     fn wall_sign_face_valid() {
         let beh = BlockEntityHashes::compute();
@@ -1773,6 +2255,70 @@ mod tests {
         assert!(!beh.is_anvil(beh.enchanting_table));
     }
 
+    #[test]
+    fn bed_detection() {
+        let beh = BlockEntityHashes::compute();
+        assert_eq!(beh.bed.len(), 16); // 4 dirs × head/foot × occupied
+        for &h in &beh.bed {
+            assert!(beh.is_bed(h));
+            assert!(!beh.is_anvil(h));
+        }
+        let unique: std::collections::HashSet<_> = beh.bed.iter().collect();
+        assert_eq!(unique.len(), 16);
+        assert!(!beh.is_bed(beh.beacon));
+    }
+
+    #[test]
+    fn head_detection() {
+        let beh = BlockEntityHashes::compute();
+        assert_eq!(beh.head.len(), 42); // 7 heads × 6 faces
+        let player_wall = beh.head_facing(3, 4);
+        assert_eq!(
+            player_wall,
+            hash_block_state_with_int("minecraft:player_head", "facing_direction", 4)
+        );
+        assert_eq!(beh.head_kind(player_wall), Some((3, 4)));
+        assert_eq!(beh.head_kind(beh.head_facing(0, 1)), Some((0, 1)));
+        assert_eq!(beh.head_kind(beh.beacon), None);
+    }
+
+    #[test]
+    fn banner_detection() {
+        let beh = BlockEntityHashes::compute();
+        assert_eq!(beh.standing_banner_direction(0.0), beh.standing_banner[8]);
+        assert!(beh.is_banner(beh.standing_banner[8]));
+        let wall = beh.wall_banner_face(3).unwrap();
+        assert_eq!(
+            wall,
+            hash_block_state_with_int("minecraft:wall_banner", "facing_direction", 3)
+        );
+        assert!(beh.is_banner(wall));
+        assert_eq!(beh.wall_banner_face(1), None);
+        assert!(!beh.is_banner(beh.standing_sign[0]));
+    }
+
+    #[test]
+    fn cartography_table_detection() {
+        let beh = BlockEntityHashes::compute();
+        assert_ne!(beh.cartography_table, 0);
+        assert!(beh.is_cartography_table(beh.cartography_table));
+        assert!(!beh.is_cartography_table(beh.enchanting_table));
+        assert!(!beh.is_anvil(beh.cartography_table));
+    }
+
+    #[test]
+    fn beacon_detection() {
+        let beh = BlockEntityHashes::compute();
+        assert_ne!(beh.beacon, 0);
+        assert!(beh.is_beacon(beh.beacon));
+        assert!(!beh.is_beacon(beh.enchanting_table));
+        for &h in &beh.beacon_base {
+            assert!(beh.is_beacon_base(h));
+            assert!(!beh.is_beacon(h));
+        }
+        assert!(!beh.is_beacon_base(beh.beacon));
+    }
+
     #[test]
     fn portal_hashes_nonzero_and_distinct() {
         let tb = TickBlocks::compute();
@@ -1813,4 +2359,40 @@ mod tests {
         assert!(tb.is_end_portal_frame(tb.end_portal_frame[2][1]));
         assert!(!tb.is_end_portal_frame(tb.air));
     }
+
+    #[test]
+    fn cake_bites_detection() {
+        let tb = TickBlocks::compute();
+        for (i, &h) in tb.cake.iter().enumerate() {
+            assert_ne!(h, 0);
+            assert_eq!(tb.cake_bites(h), Some(i));
+        }
+        assert_ne!(tb.cake[0], tb.cake[6]);
+        assert_eq!(tb.cake_bites(tb.air), None);
+    }
+
+    #[test]
+    fn snow_and_cauldron_states() {
+        let tb = TickBlocks::compute();
+        assert_eq!(tb.snow_layer_height(tb.snow_layer_plain), Some(0));
+        assert_eq!(tb.snow_layer_height(tb.snow_layer[3]), Some(3));
+        assert_eq!(tb.snow_layer_height(tb.air), None);
+        assert_eq!(tb.cauldron_fill_level(tb.cauldron[0]), Some(0));
+        assert_eq!(tb.cauldron_fill_level(tb.cauldron[6]), Some(6));
+        assert_eq!(tb.cauldron_fill_level(tb.snow_layer[0]), None);
+    }
+
+    #[test]
+    fn fall_modifier_blocks() {
+        let tb = TickBlocks::compute();
+        for &h in &tb.hay_block {
+            assert!(tb.is_hay_block(h));
+            assert!(!tb.is_slime(h));
+        }
+        assert_ne!(tb.hay_block[0], tb.hay_block[1]);
+        assert!(tb.is_slime(tb.slime));
+        assert!(tb.is_cobweb(tb.cobweb));
+        assert!(!tb.is_cobweb(tb.air));
+        assert!(!tb.is_hay_block(tb.stone));
+    }
 }
@@ -5,7 +5,10 @@
 
 use std::collections::HashMap;
 
-use crate::block_hash::hash_block_state;
+use crate::block_hash::{
+    hash_block_state, hash_block_state_with_int, hash_block_state_with_props, StateValue,
+};
+use crate::placement;
 
 /// Tool types relevant for mining speed calculation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -60,6 +63,9 @@ impl BlockRegistry {
         for info in BLOCK_DATA {
             let hash = hash_block_state(info.name);
             blocks.insert(hash, info);
+            for hash in state_hashes(info.name) {
+                blocks.insert(hash, info);
+            }
         }
         Self {
             blocks,
@@ -72,6 +78,11 @@ impl BlockRegistry {
         self.blocks.get(&hash)
     }
 
+    /// Look up a custom block state by runtime ID hash.
+    pub fn get_custom(&self, hash: u32) -> Option<&CustomBlockInfo> {
+        self.custom.get(&hash)
+    }
+
     /// Check if a block is solid. Defaults to `true` for unknown blocks.
     pub fn is_solid(&self, hash: u32) -> bool {
         if let Some(info) = self.blocks.get(&hash) {
@@ -101,9 +112,24 @@ impl BlockRegistry {
             .map(|info| mining_secs(info.hardness, info.tool_type))
     }
 
+    /// Name, hardness and preferred tool of a block, for break times.
+    pub fn mining_info(&self, hash: u32) -> Option<(&str, f32, ToolType)> {
+        if let Some(info) = self.blocks.get(&hash) {
+            return Some((info.name, info.hardness, info.tool_type));
+        }
+        self.custom
+            .get(&hash)
+            .map(|info| (info.name.as_str(), info.hardness, info.tool_type))
+    }
+
     /// Register a custom block (e.g. from a behavior pack).
     pub fn register_block(&mut self, name: String, hardness: f32, is_solid: bool) {
         let hash = hash_block_state(&name);
+        self.register_block_state(hash, name, hardness, is_solid);
+    }
+
+    /// Register one state of a custom block under its runtime ID hash.
+    pub fn register_block_state(&mut self, hash: u32, name: String, hardness: f32, is_solid: bool) {
         self.custom.insert(
             hash,
             CustomBlockInfo {
@@ -116,6 +142,52 @@ impl BlockRegistry {
     }
 }
 
+/// Names of all known vanilla blocks.
+pub fn block_names() -> impl Iterator<Item = &'static str> {
+    BLOCK_DATA.iter().map(|info| info.name)
+}
+
+/// Hashes of the states a block is stored with in the world, for the blocks
+/// whose states share one entry: fluids at every depth, bubble columns,
+/// climbable blocks and the states blocks are placed in.
+fn state_hashes(name: &str) -> Vec<u32> {
+    let int_states = |prop: &str, count: i32| {
+        (0..count)
+            .map(|value| hash_block_state_with_int(name, prop, value))
+            .collect()
+    };
+    match name {
+        "minecraft:water" | "minecraft:lava" => int_states("liquid_depth", 16),
+        "minecraft:ladder" => int_states("facing_direction", 6),
+        "minecraft:farmland" => int_states("moisturized_amount", 8),
+        "minecraft:vine" => int_states("vine_direction_bits", 16),
+        "minecraft:twisting_vines" => int_states("twisting_vines_age", 26),
+        "minecraft:weeping_vines" => int_states("weeping_vines_age", 26),
+        "minecraft:scaffolding" => (0..8)
+            .flat_map(|stability| {
+                (0..2).map(move |check| {
+                    hash_block_state_with_props(
+                        name,
+                        &[
+                            ("stability", StateValue::Int(stability)),
+                            ("stability_check", StateValue::Byte(check)),
+                        ],
+                    )
+                })
+            })
+            .collect(),
+        "minecraft:bubble_column" => (0..2)
+            .map(|drag_down| {
+                hash_block_state_with_props(name, &[("drag_down", StateValue::Byte(drag_down))])
+            })
+            .collect(),
+        _ => placement::block_states(name)
+            .iter()
+            .map(|(name, props)| hash_block_state_with_props(name, props))
+            .collect(),
+    }
+}
+
 fn mining_secs(hardness: f32, tool_type: ToolType) -> f32 {
     if hardness <= 0.0 {
         return 0.0;
@@ -629,9 +701,17 @@ static BLOCK_DATA: &[BlockInfo] = &[
     block!("minecraft:flowing_water", 100.0, non_solid, None),
     block!("minecraft:lava", 100.0, non_solid, None),
     block!("minecraft:flowing_lava", 100.0, non_solid, None),
+    block!("minecraft:bubble_column", 100.0, non_solid, None),
     // ===== Plants =====
     block!("minecraft:short_grass", 0.0, non_solid, None),
     block!("minecraft:tallgrass", 0.0, non_solid, None),
+    block!("minecraft:oak_sapling", 0.0, non_solid, None),
+    block!("minecraft:spruce_sapling", 0.0, non_solid, None),
+    block!("minecraft:birch_sapling", 0.0, non_solid, None),
+    block!("minecraft:jungle_sapling", 0.0, non_solid, None),
+    block!("minecraft:acacia_sapling", 0.0, non_solid, None),
+    block!("minecraft:dark_oak_sapling", 0.0, non_solid, None),
+    block!("minecraft:cherry_sapling", 0.0, non_solid, None),
     block!("minecraft:tall_grass", 0.0, non_solid, None),
     block!("minecraft:fern", 0.0, non_solid, None),
     block!("minecraft:large_fern", 0.0, non_solid, None),
@@ -738,7 +818,7 @@ static BLOCK_DATA: &[BlockInfo] = &[
     block!("minecraft:chiseled_bookshelf", 1.5, solid, Axe),
     block!("minecraft:decorated_pot", 0.0, solid, None),
     block!("minecraft:jukebox", 2.0, solid, Axe),
-    block!("minecraft:note_block", 0.8, solid, Axe),
+    block!("minecraft:noteblock", 0.8, solid, Axe),
     block!("minecraft:bell", 5.0, solid, Pickaxe),
     block!("minecraft:beacon", 3.0, solid, None),
     block!("minecraft:conduit", 3.0, solid, None),
@@ -854,6 +934,13 @@ static BLOCK_DATA: &[BlockInfo] = &[
     block!("minecraft:wall_banner", 1.0, non_solid, Axe),
     // ===== Skulls =====
     block!("minecraft:skull", 1.0, non_solid, None),
+    block!("minecraft:skeleton_skull", 1.0, non_solid, None),
+    block!("minecraft:wither_skeleton_skull", 1.0, non_solid, None),
+    block!("minecraft:zombie_head", 1.0, non_solid, None),
+    block!("minecraft:player_head", 1.0, non_solid, None),
+    block!("minecraft:creeper_head", 1.0, non_solid, None),
+    block!("minecraft:dragon_head", 1.0, non_solid, None),
+    block!("minecraft:piglin_head", 1.0, non_solid, None),
     // ===== Amethyst clusters =====
     block!("minecraft:small_amethyst_bud", 1.5, solid, None),
     block!("minecraft:medium_amethyst_bud", 1.5, solid, None),
@@ -878,7 +965,6 @@ static BLOCK_DATA: &[BlockInfo] = &[
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::block_hash::hash_block_state;
 
     #[test]
     fn known_block_hardness() {
@@ -910,6 +996,30 @@ mod tests {
         assert!(!registry.is_solid(water));
     }
 
+    #[test]
+    fn fluid_states_share_their_block() {
+        let registry = BlockRegistry::new();
+        let flowing = hash_block_state_with_int("minecraft:water", "liquid_depth", 3);
+        assert!(!registry.is_solid(flowing));
+        assert_eq!(registry.get(flowing).unwrap().name, "minecraft:water");
+        let column = hash_block_state_with_props(
+            "minecraft:bubble_column",
+            &[("drag_down", StateValue::Byte(1))],
+        );
+        assert!(!registry.is_solid(column));
+
+        let ladder = hash_block_state_with_int("minecraft:ladder", "facing_direction", 2);
+        assert_eq!(registry.get(ladder).unwrap().name, "minecraft:ladder");
+        let scaffolding = hash_block_state_with_props(
+            "minecraft:scaffolding",
+            &[
+                ("stability", StateValue::Int(3)),
+                ("stability_check", StateValue::Byte(1)),
+            ],
+        );
+        assert!(!registry.is_solid(scaffolding));
+    }
+
     #[test]
     fn stone_is_solid() {
         let registry = BlockRegistry::new();
@@ -974,6 +1084,23 @@ mod tests {
         assert_eq!(registry.expected_mining_secs(air), Some(0.0));
     }
 
+    #[test]
+    fn mining_info_lookup() {
+        let mut registry = BlockRegistry::new();
+        let stone = hash_block_state("minecraft:stone");
+        assert_eq!(
+            registry.mining_info(stone),
+            Some(("minecraft:stone", 1.5, ToolType::Pickaxe))
+        );
+        registry.register_block("custom:marble".to_string(), 2.5, true);
+        let marble = hash_block_state("custom:marble");
+        assert_eq!(
+            registry.mining_info(marble),
+            Some(("custom:marble", 2.5, ToolType::None))
+        );
+        assert_eq!(registry.mining_info(12345), None);
+    }
+
     #[test]
     fn block_count() {
         // Verify we have a substantial number of blocks registered
@@ -992,5 +1119,10 @@ mod tests {
         let hash = hash_block_state("custom:marble");
         assert_eq!(registry.hardness(hash), Some(2.5));
         assert!(registry.is_solid(hash));
+        assert_eq!(registry.get_custom(hash).unwrap().name, "custom:marble");
+
+        registry.register_block_state(42, "custom:slab".to_string(), 0.0, false);
+        assert_eq!(registry.hardness(42), Some(0.0));
+        assert!(!registry.is_solid(42));
     }
 }
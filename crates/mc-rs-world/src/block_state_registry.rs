@@ -9,6 +9,8 @@ use crate::block_hash::{
     hash_block_state, hash_block_state_with_int, hash_block_state_with_props, StateValue,
     LEVER_DIRS, TORCH_DIRS,
 };
+use crate::block_registry;
+use crate::placement;
 
 /// Block state version for 1.26.0 protocol (same as block_hash.rs).
 const BLOCK_STATE_VERSION: i32 = 18_168_865;
@@ -31,6 +33,16 @@ pub struct BlockStateInfo {
     pub properties: Vec<(String, StateValueOwned)>,
 }
 
+impl BlockStateInfo {
+    /// The value of one of the state's properties.
+    pub fn property(&self, name: &str) -> Option<&StateValueOwned> {
+        self.properties
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value)
+    }
+}
+
 /// Bidirectional registry mapping FNV-1a hashes to block state info.
 #[derive(Debug, Clone)]
 pub struct BlockStateRegistry {
@@ -145,6 +157,14 @@ impl BlockStateRegistry {
             reg.register_int("minecraft:lava", "liquid_depth", d);
         }
 
+        // --- Bubble column: drag_down ---
+        for drag_down in 0..2i8 {
+            reg.register_props(
+                "minecraft:bubble_column",
+                &[("drag_down", StateValue::Byte(drag_down))],
+            );
+        }
+
         // --- Redstone wire: redstone_signal 0..15 ---
         for s in 0..16 {
             reg.register_int("minecraft:redstone_wire", "redstone_signal", s);
@@ -297,6 +317,13 @@ impl BlockStateRegistry {
             }
         }
 
+        // --- Placed blocks: torches, buttons, doors, beds, slabs, stairs, logs ---
+        for name in block_registry::block_names() {
+            for (state_name, props) in placement::block_states(name) {
+                reg.register_props(&state_name, &props);
+            }
+        }
+
         reg
     }
 
@@ -330,22 +357,25 @@ impl BlockStateRegistry {
     /// to network NBT format and runs FNV-1a to produce the hash our server uses.
     pub fn nbt_le_to_hash(nbt_data: &[u8]) -> Option<u32> {
         let (name, props) = parse_bds_nbt_le(nbt_data)?;
-        if props.is_empty() {
-            Some(hash_block_state(&name))
-        } else {
-            let prop_refs: Vec<(&str, StateValue)> = props
-                .iter()
-                .map(|(k, v)| {
-                    let sv = match v {
-                        StateValueOwned::Int(i) => StateValue::Int(*i),
-                        StateValueOwned::Byte(b) => StateValue::Byte(*b),
-                        StateValueOwned::Str(s) => StateValue::Str(s.as_str()),
-                    };
-                    (k.as_str(), sv)
-                })
-                .collect();
-            Some(hash_block_state_with_props(&name, &prop_refs))
-        }
+        Some(hash_owned_state(&name, &props))
+    }
+
+    /// Register a data-driven block state (e.g. a behavior pack block
+    /// permutation) and return its runtime ID hash.
+    pub fn register_custom(
+        &mut self,
+        name: &str,
+        properties: Vec<(String, StateValueOwned)>,
+    ) -> u32 {
+        let hash = hash_owned_state(name, &properties);
+        self.entries.insert(
+            hash,
+            BlockStateInfo {
+                name: name.to_string(),
+                properties,
+            },
+        );
+        hash
     }
 
     // --- Internal helpers ---
@@ -395,6 +425,25 @@ impl BlockStateRegistry {
     }
 }
 
+/// Hash a block state whose properties are owned values.
+pub fn hash_owned_state(name: &str, props: &[(String, StateValueOwned)]) -> u32 {
+    if props.is_empty() {
+        return hash_block_state(name);
+    }
+    let prop_refs: Vec<(&str, StateValue)> = props
+        .iter()
+        .map(|(k, v)| {
+            let sv = match v {
+                StateValueOwned::Int(i) => StateValue::Int(*i),
+                StateValueOwned::Byte(b) => StateValue::Byte(*b),
+                StateValueOwned::Str(s) => StateValue::Str(s.as_str()),
+            };
+            (k.as_str(), sv)
+        })
+        .collect();
+    hash_block_state_with_props(name, &prop_refs)
+}
+
 impl Default for BlockStateRegistry {
     fn default() -> Self {
         Self::new()
@@ -681,6 +730,33 @@ mod tests {
         hash_block_state, hash_block_state_with_int, hash_block_state_with_props, StateValue,
     };
 
+    #[test]
+    fn register_custom_state() {
+        let mut reg = BlockStateRegistry::new();
+        let props = vec![
+            ("custom:lit".to_string(), StateValueOwned::Byte(1)),
+            ("custom:level".to_string(), StateValueOwned::Int(2)),
+        ];
+        let hash = reg.register_custom("custom:lamp", props.clone());
+        assert_eq!(
+            hash,
+            hash_block_state_with_props(
+                "custom:lamp",
+                &[
+                    ("custom:level", StateValue::Int(2)),
+                    ("custom:lit", StateValue::Byte(1))
+                ]
+            )
+        );
+        assert_eq!(reg.get(hash).unwrap().properties, props);
+        let nbt = reg.hash_to_nbt_le(hash).unwrap();
+        assert_eq!(BlockStateRegistry::nbt_le_to_hash(&nbt), Some(hash));
+        assert_eq!(
+            reg.register_custom("custom:plain", Vec::new()),
+            hash_block_state("custom:plain")
+        );
+    }
+
     #[test]
     fn registry_not_empty() {
         let reg = BlockStateRegistry::new();
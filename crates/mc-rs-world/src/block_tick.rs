@@ -6,10 +6,13 @@ use std::collections::{BinaryHeap, HashSet};
 use rand::prelude::*;
 
 use crate::block_hash::TickBlocks;
+use crate::bubble_column;
+use crate::farmland;
 use crate::fluid;
 use crate::gravity;
 use crate::piston;
 use crate::redstone;
+use crate::tree;
 
 // ---------------------------------------------------------------------------
 // Scheduled tick queue
@@ -132,6 +135,15 @@ pub fn process_scheduled_tick(
     get_block: impl Fn(i32, i32, i32) -> Option<u32>,
     is_solid: impl Fn(u32) -> bool,
 ) -> ScheduledTickResult {
+    // Bubble columns, and water sources that should become one
+    if let Some(bu) = bubble_column::process_bubble_column_tick(x, y, z, tb, &get_block) {
+        return ScheduledTickResult {
+            changes: bu.changes,
+            schedule: bu.schedule,
+            ..Default::default()
+        };
+    }
+
     // Check if the block at this position is a fluid
     if let Some(rid) = get_block(x, y, z) {
         if tb.is_fluid(rid) {
@@ -180,6 +192,16 @@ pub fn process_scheduled_tick(
         }
     }
 
+    // Leaves whose log was removed
+    if let Some(rid) = get_block(x, y, z) {
+        if tb.is_leaf(rid) {
+            return ScheduledTickResult {
+                changes: tree::process_leaf_decay(x, y, z, tb, &get_block),
+                ..Default::default()
+            };
+        }
+    }
+
     ScheduledTickResult::default()
 }
 
@@ -226,14 +248,67 @@ pub fn process_random_tick(
         return Vec::new();
     }
 
+    // Farmland: moistened by water nearby, or drying out
+    if let Some(moisture) = tb.farmland_moisture(runtime_id) {
+        return farmland::process_farmland_tick(moisture, wx, wy, wz, tb, &get_block, &is_solid);
+    }
+
+    // Saplings: age, then grow into a tree
+    if let Some((tree_type, aged)) = tb.sapling_growth(runtime_id) {
+        let trunk_height = tree::trunk_height(tree_type, &mut thread_rng());
+        return tree::process_sapling_tick(
+            tree_type,
+            aged,
+            trunk_height,
+            (wx, wy, wz),
+            tb,
+            &get_block,
+        );
+    }
+
     // Leaf decay: check for nearby logs within Manhattan distance 4
     if tb.is_leaf(runtime_id) {
-        if !has_log_nearby(wx, wy, wz, 4, tb, &get_block) {
-            return vec![(wx, wy, wz, tb.air)];
+        return tree::process_leaf_decay(wx, wy, wz, tb, &get_block);
+    }
+
+    Vec::new()
+}
+
+/// Snow layers rain can pile up on a block, as a height (layers - 1).
+pub const MAX_SNOW_HEIGHT: usize = 2;
+
+/// Process precipitation on the top block of a column during rain: snow
+/// settles and piles up in snowy biomes, and water cauldrons fill elsewhere.
+/// Returns a list of block changes (x, y, z, new_rid).
+///
+/// `top` is the highest non-air block, at `(wx, wy, wz)`.
+pub fn process_precipitation(
+    top: u32,
+    wx: i32,
+    wy: i32,
+    wz: i32,
+    snowy: bool,
+    tb: &TickBlocks,
+    is_solid: impl Fn(u32) -> bool,
+) -> Vec<(i32, i32, i32, u32)> {
+    if snowy {
+        if let Some(height) = tb.snow_layer_height(top) {
+            if height < MAX_SNOW_HEIGHT {
+                return vec![(wx, wy, wz, tb.snow_layer[height + 1])];
+            }
+            return Vec::new();
+        }
+        if is_solid(top) {
+            return vec![(wx, wy + 1, wz, tb.snow_layer[0])];
         }
         return Vec::new();
     }
 
+    if let Some(level) = tb.cauldron_fill_level(top) {
+        if level < 6 {
+            return vec![(wx, wy, wz, tb.cauldron[level + 1])];
+        }
+    }
     Vec::new()
 }
 
@@ -282,38 +357,39 @@ fn try_grass_spread(
     Vec::new()
 }
 
-/// Check if there is a log block within Manhattan distance `radius` of (wx, wy, wz).
-fn has_log_nearby(
-    wx: i32,
-    wy: i32,
-    wz: i32,
-    radius: i32,
-    tb: &TickBlocks,
-    get_block: &impl Fn(i32, i32, i32) -> Option<u32>,
-) -> bool {
-    for dx in -radius..=radius {
-        let rem = radius - dx.abs();
-        for dy in -rem..=rem {
-            let rem2 = rem - dy.abs();
-            for dz in -rem2..=rem2 {
-                if dx == 0 && dy == 0 && dz == 0 {
-                    continue;
-                }
-                if let Some(rid) = get_block(wx + dx, wy + dy, wz + dz) {
-                    if tb.is_log(rid) {
-                        return true;
-                    }
-                }
-            }
-        }
-    }
-    false
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn precipitation_piles_snow() {
+        let tb = TickBlocks::compute();
+        let solid = |rid: u32| rid != tb.air;
+        assert_eq!(
+            process_precipitation(tb.dirt, 1, 64, 2, true, &tb, solid),
+            vec![(1, 65, 2, tb.snow_layer[0])]
+        );
+        assert_eq!(
+            process_precipitation(tb.snow_layer_plain, 1, 65, 2, true, &tb, solid),
+            vec![(1, 65, 2, tb.snow_layer[1])]
+        );
+        let full = tb.snow_layer[MAX_SNOW_HEIGHT];
+        assert!(process_precipitation(full, 1, 65, 2, true, &tb, solid).is_empty());
+        // No snow outside snowy biomes
+        assert!(process_precipitation(tb.dirt, 1, 64, 2, false, &tb, solid).is_empty());
+    }
+
+    #[test]
+    fn precipitation_fills_cauldrons() {
+        let tb = TickBlocks::compute();
+        let solid = |_| true;
+        assert_eq!(
+            process_precipitation(tb.cauldron[0], 0, 70, 0, false, &tb, solid),
+            vec![(0, 70, 0, tb.cauldron[1])]
+        );
+        assert!(process_precipitation(tb.cauldron[6], 0, 70, 0, false, &tb, solid).is_empty());
+    }
+
     #[test]
     fn scheduler_basic() {
         let mut s = TickScheduler::new();
@@ -0,0 +1,113 @@
+//! Bubble columns: water sources over soul sand lift entities, over magma
+//! they drag them down.
+//!
+//! A column grows up through water sources one block per tick, and turns
+//! back into water from the bottom up when what it stands on goes away.
+
+use crate::block_hash::{FluidType, TickBlocks};
+use crate::fluid;
+
+/// Tick delay for a bubble column to grow or shrink by one block.
+pub const BUBBLE_COLUMN_TICK_DELAY: u64 = 5;
+
+/// Result of processing a bubble column tick.
+#[derive(Debug, Default)]
+pub struct BubbleColumnUpdate {
+    /// Block changes to apply: (x, y, z, new_runtime_id).
+    pub changes: Vec<(i32, i32, i32, u32)>,
+    /// New ticks to schedule: (x, y, z, delay, priority).
+    pub schedule: Vec<(i32, i32, i32, u64, i32)>,
+}
+
+/// Process a scheduled tick at `(x, y, z)` for a bubble column, or a water
+/// source that should become one.
+///
+/// Returns `None` for any other block, water sources with nothing to feed a
+/// column included, so that the caller can run its fluid tick instead.
+pub fn process_bubble_column_tick(
+    x: i32,
+    y: i32,
+    z: i32,
+    tb: &TickBlocks,
+    get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+) -> Option<BubbleColumnUpdate> {
+    let rid = get_block(x, y, z)?;
+    let current = tb.bubble_column_drag_down(rid);
+    let wanted = get_block(x, y - 1, z).and_then(|below| tb.bubble_column_base(below));
+    if current.is_none() && (wanted.is_none() || !tb.is_water_source(rid)) {
+        return None;
+    }
+
+    let mut update = BubbleColumnUpdate::default();
+    if current != wanted {
+        let new_rid = match wanted {
+            Some(drag_down) => tb.bubble_column[drag_down as usize],
+            None => tb.water[0],
+        };
+        update.changes.push((x, y, z, new_rid));
+        // The block above follows on the next step
+        update
+            .schedule
+            .push((x, y + 1, z, BUBBLE_COLUMN_TICK_DELAY, 0));
+        if wanted.is_none() {
+            // Plain water again: let it flow where it can
+            update
+                .schedule
+                .push((x, y, z, fluid::tick_delay(FluidType::Water), 0));
+        }
+    }
+    Some(update)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick(tb: &TickBlocks, at: u32, below: u32) -> Option<BubbleColumnUpdate> {
+        process_bubble_column_tick(0, 10, 0, tb, |_, y, _| match y {
+            10 => Some(at),
+            9 => Some(below),
+            _ => Some(tb.air),
+        })
+    }
+
+    #[test]
+    fn water_over_soul_sand_lifts() {
+        let tb = TickBlocks::compute();
+        let update = tick(&tb, tb.water_plain, tb.soul_sand).unwrap();
+        assert_eq!(update.changes, [(0, 10, 0, tb.bubble_column[0])]);
+        assert_eq!(update.schedule, [(0, 11, 0, BUBBLE_COLUMN_TICK_DELAY, 0)]);
+    }
+
+    #[test]
+    fn columns_carry_on_and_switch() {
+        let tb = TickBlocks::compute();
+        // Over a dragging column, a water source drags too
+        let update = tick(&tb, tb.water[0], tb.bubble_column[1]).unwrap();
+        assert_eq!(update.changes, [(0, 10, 0, tb.bubble_column[1])]);
+        // A lifting column over magma turns around
+        let update = tick(&tb, tb.bubble_column[0], tb.magma).unwrap();
+        assert_eq!(update.changes, [(0, 10, 0, tb.bubble_column[1])]);
+        // Nothing to do when the column is already right
+        assert!(tick(&tb, tb.bubble_column[1], tb.magma)
+            .unwrap()
+            .changes
+            .is_empty());
+    }
+
+    #[test]
+    fn column_without_base_turns_back_into_water() {
+        let tb = TickBlocks::compute();
+        let update = tick(&tb, tb.bubble_column[0], tb.stone).unwrap();
+        assert_eq!(update.changes, [(0, 10, 0, tb.water[0])]);
+        assert_eq!(update.schedule.len(), 2);
+    }
+
+    #[test]
+    fn other_blocks_are_left_to_fluids() {
+        let tb = TickBlocks::compute();
+        assert!(tick(&tb, tb.water[0], tb.stone).is_none());
+        assert!(tick(&tb, tb.water[3], tb.soul_sand).is_none());
+        assert!(tick(&tb, tb.air, tb.soul_sand).is_none());
+    }
+}
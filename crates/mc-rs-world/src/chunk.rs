@@ -6,12 +6,19 @@ pub const OVERWORLD_SUB_CHUNK_COUNT: usize = 24;
 /// Minimum Y coordinate in the Overworld.
 pub const OVERWORLD_MIN_Y: i32 = -64;
 
-/// A 16x16x16 sub-chunk with a single block storage layer.
+/// Biomes of one sub-chunk, one per 4x4x4 cell, indexed `(x * 4 + z) * 4 + y`
+/// in cells.
+pub type SubChunkBiomes = [u8; 64];
+
+/// A 16x16x16 sub-chunk: a block storage layer, and a second one for the
+/// water of waterlogged blocks once there is any.
 pub struct SubChunk {
     /// Palette indices for each block, stored in XZY order: `(x*16 + z)*16 + y`.
     pub blocks: [u16; 4096],
     /// Palette of block runtime IDs (FNV-1a hashes).
     pub palette: Vec<u32>,
+    /// Layer 1: water sharing a position with the block in `blocks`.
+    pub liquid: Option<Box<SubChunk>>,
 }
 
 /// A full chunk column (16x384x16 for Overworld).
@@ -19,8 +26,8 @@ pub struct ChunkColumn {
     pub x: i32,
     pub z: i32,
     pub sub_chunks: [SubChunk; OVERWORLD_SUB_CHUNK_COUNT],
-    /// 2D biome map: one biome ID per XZ column, indexed `[x * 16 + z]`.
-    pub biomes: [u8; 256],
+    /// 3D biome map: a grid of 4x4x4 cells per sub-chunk.
+    pub biomes: [SubChunkBiomes; OVERWORLD_SUB_CHUNK_COUNT],
     /// Whether this chunk has unsaved modifications.
     pub dirty: bool,
     /// Cached serialized payload: `(sub_chunk_count, payload_bytes)`.
@@ -35,7 +42,7 @@ impl ChunkColumn {
             x,
             z,
             sub_chunks: std::array::from_fn(|_| SubChunk::new_single(air_id)),
-            biomes: [0; 256],
+            biomes: [[0; 64]; OVERWORLD_SUB_CHUNK_COUNT],
             dirty: false,
             cached_payload: None,
         }
@@ -72,6 +79,125 @@ impl ChunkColumn {
         let local_y = shifted as usize % 16;
         Some(self.sub_chunks[sub_index].get_block(local_x, local_y, local_z))
     }
+
+    /// Get the runtime ID in the liquid layer, as [`get_block_world`](Self::get_block_world)
+    /// does. `None` if y is out of range or the sub-chunk has no liquid layer.
+    pub fn get_liquid_world(&self, local_x: usize, world_y: i32, local_z: usize) -> Option<u32> {
+        let shifted = world_y - OVERWORLD_MIN_Y;
+        if shifted < 0 || shifted >= (OVERWORLD_SUB_CHUNK_COUNT as i32 * 16) {
+            return None;
+        }
+        let sub_index = shifted as usize / 16;
+        let local_y = shifted as usize % 16;
+        self.sub_chunks[sub_index].get_liquid(local_x, local_y, local_z)
+    }
+
+    /// Get the biome ID using local x (0..16), world y, local z (0..16).
+    /// Returns `None` if y is out of range.
+    pub fn biome_at(&self, local_x: usize, world_y: i32, local_z: usize) -> Option<u8> {
+        let shifted = world_y - OVERWORLD_MIN_Y;
+        if shifted < 0 || shifted >= (OVERWORLD_SUB_CHUNK_COUNT as i32 * 16) {
+            return None;
+        }
+        let cell = biome_cell(local_x, shifted as usize % 16, local_z);
+        Some(self.biomes[shifted as usize / 16][cell])
+    }
+
+    /// Set the biome of the 4x4x4 cell holding local x (0..16), world y,
+    /// local z (0..16). Returns false if y is out of range.
+    pub fn set_biome(&mut self, local_x: usize, world_y: i32, local_z: usize, id: u8) -> bool {
+        let shifted = world_y - OVERWORLD_MIN_Y;
+        if shifted < 0 || shifted >= (OVERWORLD_SUB_CHUNK_COUNT as i32 * 16) {
+            return false;
+        }
+        let cell = biome_cell(local_x, shifted as usize % 16, local_z);
+        self.biomes[shifted as usize / 16][cell] = id;
+        self.cached_payload = None;
+        true
+    }
+
+    /// Set the biome of a whole column of cells, at every height.
+    pub fn set_biome_column(&mut self, local_x: usize, local_z: usize, id: u8) {
+        for sub in &mut self.biomes {
+            for y in 0..4 {
+                sub[biome_cell(local_x, y * 4, local_z)] = id;
+            }
+        }
+        self.cached_payload = None;
+    }
+}
+
+/// Index of the 4x4x4 biome cell holding local block coordinates.
+fn biome_cell(x: usize, y: usize, z: usize) -> usize {
+    ((x / 4) * 4 + z / 4) * 4 + y / 4
+}
+
+/// 3D biomes from a 2D map of one biome per XZ column, indexed
+/// `[x * 16 + z]`, as older chunk formats store them. Each cell takes the
+/// biome of the column at its center.
+pub fn biomes_from_2d(biomes: &[u8; 256]) -> [SubChunkBiomes; OVERWORLD_SUB_CHUNK_COUNT] {
+    let cells: SubChunkBiomes =
+        std::array::from_fn(|cell| biomes[((cell / 16) * 4 + 2) * 16 + ((cell / 4) % 4) * 4 + 2]);
+    [cells; OVERWORLD_SUB_CHUNK_COUNT]
+}
+
+/// Biome cells as a palette and the bits per entry and words of their
+/// storage at block resolution, 4096 entries in XZY order, as both the
+/// network and disk formats lay out biomes. No words for a single biome.
+pub(crate) fn biome_storage(cells: &SubChunkBiomes) -> (Vec<u8>, u8, Vec<u32>) {
+    let mut palette: Vec<u8> = Vec::new();
+    let mut indices = [0u32; 64];
+    for (cell, &id) in cells.iter().enumerate() {
+        indices[cell] = match palette.iter().position(|&p| p == id) {
+            Some(index) => index as u32,
+            None => {
+                palette.push(id);
+                palette.len() as u32 - 1
+            }
+        };
+    }
+    let bits = match palette.len() {
+        0..=1 => return (palette, 0, Vec::new()),
+        2 => 1,
+        3..=4 => 2,
+        5..=8 => 3,
+        9..=16 => 4,
+        17..=32 => 5,
+        _ => 6,
+    };
+    let per_word = 32 / bits as usize;
+    let words = (0..4096_usize.div_ceil(per_word))
+        .map(|word| {
+            (0..per_word)
+                .map(|slot| (word * per_word + slot, slot))
+                .filter(|&(entry, _)| entry < 4096)
+                .fold(0u32, |acc, (entry, slot)| {
+                    let cell = biome_cell(entry >> 8, entry & 15, (entry >> 4) & 15);
+                    acc | (indices[cell] << (bits as usize * slot))
+                })
+        })
+        .collect();
+    (palette, bits, words)
+}
+
+/// Biome cells from a storage at block resolution, the inverse of
+/// [`biome_storage`]: each cell takes the entry at its lowest corner.
+/// `None` if an entry is out of the palette.
+pub(crate) fn biome_cells(palette: &[u8], bits: u8, words: &[u32]) -> Option<SubChunkBiomes> {
+    if bits == 0 {
+        return palette.first().map(|&id| [id; 64]);
+    }
+    let per_word = 32 / bits as usize;
+    let mask = (1u32 << bits) - 1;
+    let mut cells = [0u8; 64];
+    for (cell, biome) in cells.iter_mut().enumerate() {
+        let (x, z, y) = (cell / 16 * 4, (cell / 4) % 4 * 4, cell % 4 * 4);
+        let entry = (x << 8) | (z << 4) | y;
+        let word = words.get(entry / per_word)?;
+        let index = (word >> ((entry % per_word) * bits as usize)) & mask;
+        *biome = *palette.get(index as usize)?;
+    }
+    Some(cells)
 }
 
 impl SubChunk {
@@ -80,6 +206,7 @@ impl SubChunk {
         Self {
             blocks: [0; 4096],
             palette: vec![runtime_id],
+            liquid: None,
         }
     }
 
@@ -104,6 +231,23 @@ impl SubChunk {
         let palette_index = self.blocks[block_index] as usize;
         self.palette[palette_index]
     }
+
+    /// Get the runtime ID in the liquid layer at local coordinates, if the
+    /// sub-chunk has one.
+    pub fn get_liquid(&self, x: usize, y: usize, z: usize) -> Option<u32> {
+        self.liquid.as_ref().map(|layer| layer.get_block(x, y, z))
+    }
+
+    /// Set the liquid layer at local coordinates, adding the layer (filled
+    /// with `air_id`) if the sub-chunk has none yet.
+    pub fn set_liquid(&mut self, x: usize, y: usize, z: usize, runtime_id: u32, air_id: u32) {
+        if self.liquid.is_none() && runtime_id == air_id {
+            return;
+        }
+        self.liquid
+            .get_or_insert_with(|| Box::new(SubChunk::new_single(air_id)))
+            .set_block(x, y, z, runtime_id);
+    }
 }
 
 #[cfg(test)]
@@ -126,6 +270,19 @@ mod tests {
         assert_eq!(sub.get_block(0, 0, 0), 100);
     }
 
+    #[test]
+    fn liquid_layer_added_on_demand() {
+        let mut sub = SubChunk::new_single(1);
+        assert_eq!(sub.get_liquid(2, 3, 4), None);
+        // Clearing water that was never there adds nothing
+        sub.set_liquid(2, 3, 4, 1, 1);
+        assert!(sub.liquid.is_none());
+        sub.set_liquid(2, 3, 4, 9, 1);
+        assert_eq!(sub.get_liquid(2, 3, 4), Some(9));
+        assert_eq!(sub.get_liquid(0, 0, 0), Some(1));
+        assert_eq!(sub.get_block(2, 3, 4), 1);
+    }
+
     #[test]
     fn palette_growth() {
         let mut sub = SubChunk::new_single(1);
@@ -153,7 +310,7 @@ mod tests {
         let col = ChunkColumn::new_air(3, -5, 999);
         assert_eq!(col.x, 3);
         assert_eq!(col.z, -5);
-        assert_eq!(col.biomes, [0u8; 256]);
+        assert_eq!(col.biome_at(0, 0, 0), Some(0));
         // All blocks should be the air ID
         assert_eq!(col.get_block_world(0, 0, 0), Some(999));
         assert_eq!(col.get_block_world(8, 100, 8), Some(999));
@@ -178,10 +335,42 @@ mod tests {
     }
 
     #[test]
-    fn biome_storage() {
+    fn biome_map_3d() {
         let mut col = ChunkColumn::new_air(0, 0, 1);
-        col.biomes[3 * 16 + 5] = 42;
-        assert_eq!(col.biomes[3 * 16 + 5], 42);
-        assert_eq!(col.biomes[0], 0); // others still default
+        // The whole 4x4x4 cell takes the biome
+        assert!(col.set_biome(5, 10, 3, 42));
+        assert_eq!(col.biome_at(4, 8, 0), Some(42));
+        assert_eq!(col.biome_at(7, 11, 3), Some(42));
+        assert_eq!(col.biome_at(5, 12, 3), Some(0));
+        assert_eq!(col.biome_at(0, 0, 0), Some(0)); // others still default
+        assert!(!col.set_biome(0, 320, 0, 1));
+        assert_eq!(col.biome_at(0, -65, 0), None);
+
+        col.set_biome_column(15, 15, 7);
+        assert_eq!(col.biome_at(12, -64, 12), Some(7));
+        assert_eq!(col.biome_at(15, 319, 15), Some(7));
+    }
+
+    #[test]
+    fn biomes_from_a_2d_map() {
+        let mut map = [1u8; 256];
+        map[6 * 16 + 10] = 2; // center of cell x = 1, z = 2
+        let biomes = biomes_from_2d(&map);
+        let col_cell = (4 + 2) * 4;
+        assert!(biomes.iter().all(|sub| sub[col_cell] == 2 && sub[0] == 1));
+    }
+
+    #[test]
+    fn biome_storage_roundtrip() {
+        let mut cells = [3u8; 64];
+        assert_eq!(biome_storage(&cells), (vec![3], 0, vec![]));
+        cells[5] = 8;
+        cells[63] = 9;
+        let (palette, bits, words) = biome_storage(&cells);
+        assert_eq!((palette.as_slice(), bits), ([3, 8, 9].as_slice(), 2));
+        assert_eq!(words.len(), 4096 / 16);
+        assert_eq!(biome_cells(&palette, bits, &words), Some(cells));
+        // Entries out of the palette don't decode
+        assert_eq!(biome_cells(&palette[..2], bits, &words), None);
     }
 }
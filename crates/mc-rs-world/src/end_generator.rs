@@ -9,8 +9,9 @@
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
+use crate::biome::END_BIOME;
 use crate::block_hash::EndBlocks;
-use crate::chunk::ChunkColumn;
+use crate::chunk::{ChunkColumn, OVERWORLD_SUB_CHUNK_COUNT};
 use crate::noise::OctaveNoise;
 
 /// Center Y for the main island platform.
@@ -63,13 +64,16 @@ impl EndGenerator {
         // Between main and outer islands: void (all air)
 
         // Biome data: the_end = biome ID 9
-        for i in 0..256 {
-            column.biomes[i] = 9;
-        }
+        column.biomes = [[END_BIOME; 64]; OVERWORLD_SUB_CHUNK_COUNT];
 
         column
     }
 
+    /// The biome at world block coordinates: always the End.
+    pub fn biome_at(&self, _block_x: i32, _block_z: i32) -> u8 {
+        END_BIOME
+    }
+
     /// Find a safe spawn Y on the main island.
     pub fn find_spawn_y(&self) -> i32 {
         let chunk = self.generate_chunk(0, 0);
@@ -300,7 +304,7 @@ mod tests {
     fn end_biome_is_the_end() {
         let gen = test_gen();
         let col = gen.generate_chunk(0, 0);
-        for &biome in col.biomes.iter() {
+        for &biome in col.biomes.iter().flatten() {
             assert_eq!(biome, 9, "All End biomes should be the_end (9)");
         }
     }
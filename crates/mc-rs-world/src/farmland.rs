@@ -0,0 +1,198 @@
+//! Farmland: tilled with a hoe, kept moist by water nearby, and turned back
+//! into dirt when it dries out or is trampled.
+
+use crate::block_hash::{CropType, TickBlocks};
+
+/// How far water keeps farmland moist, horizontally.
+pub const HYDRATION_RANGE: i32 = 4;
+
+/// Moisture of farmland next to water.
+pub const MAX_MOISTURE: usize = 7;
+
+/// Fall distance above which landing on farmland may trample it.
+pub const TRAMPLE_FALL_DISTANCE: f32 = 0.5;
+
+/// What a hoe turns a block into, when the block above it is `above`:
+/// grass, dirt and paths become farmland, coarse dirt becomes dirt. Only
+/// with air above.
+pub fn tilled(rid: u32, above: Option<u32>, tb: &TickBlocks) -> Option<u32> {
+    if above != Some(tb.air) {
+        return None;
+    }
+    if rid == tb.grass_block || rid == tb.dirt || rid == tb.grass_path {
+        Some(tb.farmland[0])
+    } else if rid == tb.coarse_dirt {
+        Some(tb.dirt)
+    } else {
+        None
+    }
+}
+
+/// Whether there is water within [`HYDRATION_RANGE`] of the farmland at
+/// `(x, y, z)`, at its level or one block up.
+pub fn is_hydrated(
+    x: i32,
+    y: i32,
+    z: i32,
+    tb: &TickBlocks,
+    get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+) -> bool {
+    let range = -HYDRATION_RANGE..=HYDRATION_RANGE;
+    range.clone().any(|dx| {
+        range.clone().any(|dz| {
+            (0..=1)
+                .any(|dy| get_block(x + dx, y + dy, z + dz).is_some_and(|rid| tb.holds_water(rid)))
+        })
+    })
+}
+
+/// Random tick of a farmland block with moisture `moisture`: water nearby
+/// wets it, otherwise it dries out one step at a time, and dry farmland
+/// with no crop on it turns back into dirt. So does farmland under a solid
+/// block.
+pub fn process_farmland_tick(
+    moisture: usize,
+    x: i32,
+    y: i32,
+    z: i32,
+    tb: &TickBlocks,
+    get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+    is_solid: impl Fn(u32) -> bool,
+) -> Vec<(i32, i32, i32, u32)> {
+    let above = get_block(x, y + 1, z);
+    if above.is_some_and(&is_solid) {
+        return vec![(x, y, z, tb.dirt)];
+    }
+    if is_hydrated(x, y, z, tb, &get_block) {
+        if moisture < MAX_MOISTURE {
+            return vec![(x, y, z, tb.farmland[MAX_MOISTURE])];
+        }
+        return Vec::new();
+    }
+    if moisture > 0 {
+        return vec![(x, y, z, tb.farmland[moisture - 1])];
+    }
+    if above.is_some_and(|rid| tb.crop_growth(rid).is_some()) {
+        return Vec::new();
+    }
+    vec![(x, y, z, tb.dirt)]
+}
+
+/// Whether landing on farmland after falling `fall_distance` blocks
+/// tramples it, for a random `roll` in `[0, 1)`: the further the fall, the
+/// likelier.
+pub fn tramples(fall_distance: f32, roll: f32) -> bool {
+    fall_distance > TRAMPLE_FALL_DISTANCE && roll < fall_distance - TRAMPLE_FALL_DISTANCE
+}
+
+/// Items a crop pops off as when the farmland under it goes: its seed, and
+/// its produce too once fully grown.
+pub fn crop_drops(crop: CropType, growth: usize) -> Vec<(&'static str, u16)> {
+    let grown = growth >= TickBlocks::crop_max_growth(crop);
+    match crop {
+        CropType::Wheat if grown => vec![("minecraft:wheat", 1), ("minecraft:wheat_seeds", 1)],
+        CropType::Wheat => vec![("minecraft:wheat_seeds", 1)],
+        CropType::Carrots => vec![("minecraft:carrot", if grown { 2 } else { 1 })],
+        CropType::Potatoes => vec![("minecraft:potato", if grown { 2 } else { 1 })],
+        CropType::Beetroot if grown => {
+            vec![("minecraft:beetroot", 1), ("minecraft:beetroot_seeds", 1)]
+        }
+        CropType::Beetroot => vec![("minecraft:beetroot_seeds", 1)],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Farmland at (0, 64, 0) with `above` on it, and water at `water`.
+    fn world(
+        tb: &TickBlocks,
+        above: u32,
+        water: Option<(i32, i32, i32)>,
+    ) -> impl Fn(i32, i32, i32) -> Option<u32> + '_ {
+        move |x, y, z| {
+            Some(match (x, y, z) {
+                (0, 65, 0) => above,
+                pos if Some(pos) == water => tb.water[0],
+                (_, ..=64, _) => tb.dirt,
+                _ => tb.air,
+            })
+        }
+    }
+
+    fn tick(
+        tb: &TickBlocks,
+        moisture: usize,
+        get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+    ) -> Vec<(i32, i32, i32, u32)> {
+        process_farmland_tick(moisture, 0, 64, 0, tb, get_block, |rid| rid == tb.dirt)
+    }
+
+    #[test]
+    fn hoes_till_dirt_and_grass() {
+        let tb = TickBlocks::compute();
+        assert_eq!(
+            tilled(tb.grass_block, Some(tb.air), &tb),
+            Some(tb.farmland[0])
+        );
+        assert_eq!(tilled(tb.dirt, Some(tb.air), &tb), Some(tb.farmland[0]));
+        assert_eq!(tilled(tb.coarse_dirt, Some(tb.air), &tb), Some(tb.dirt));
+        assert_eq!(tilled(tb.dirt, Some(tb.dirt), &tb), None);
+        assert_eq!(tilled(tb.sand, Some(tb.air), &tb), None);
+    }
+
+    #[test]
+    fn water_nearby_keeps_farmland_moist() {
+        let tb = TickBlocks::compute();
+        let near = world(&tb, tb.air, Some((4, 64, -4)));
+        assert_eq!(tick(&tb, 2, &near), [(0, 64, 0, tb.farmland[MAX_MOISTURE])]);
+        assert!(tick(&tb, MAX_MOISTURE, &near).is_empty());
+        // Too far, or under the farmland
+        assert!(!is_hydrated(
+            0,
+            64,
+            0,
+            &tb,
+            world(&tb, tb.air, Some((5, 64, 0)))
+        ));
+        assert!(!is_hydrated(
+            0,
+            64,
+            0,
+            &tb,
+            world(&tb, tb.air, Some((1, 63, 0)))
+        ));
+    }
+
+    #[test]
+    fn dry_farmland_turns_back_into_dirt() {
+        let tb = TickBlocks::compute();
+        let dry = world(&tb, tb.air, None);
+        assert_eq!(tick(&tb, 3, &dry), [(0, 64, 0, tb.farmland[2])]);
+        assert_eq!(tick(&tb, 0, &dry), [(0, 64, 0, tb.dirt)]);
+        // Unless a crop grows on it
+        assert!(tick(&tb, 0, world(&tb, tb.wheat[2], None)).is_empty());
+        // A solid block on top always turns it back
+        let covered = world(&tb, tb.dirt, Some((1, 64, 0)));
+        assert_eq!(tick(&tb, MAX_MOISTURE, covered), [(0, 64, 0, tb.dirt)]);
+    }
+
+    #[test]
+    fn falls_trample_and_crops_pop_off() {
+        assert!(!tramples(0.4, 0.0));
+        assert!(tramples(1.0, 0.3));
+        assert!(!tramples(1.0, 0.6));
+        assert!(tramples(2.0, 0.99));
+
+        assert_eq!(
+            crop_drops(CropType::Wheat, 3),
+            [("minecraft:wheat_seeds", 1)]
+        );
+        assert_eq!(
+            crop_drops(CropType::Beetroot, 3),
+            [("minecraft:beetroot", 1), ("minecraft:beetroot_seeds", 1)]
+        );
+        assert_eq!(crop_drops(CropType::Potatoes, 7), [("minecraft:potato", 2)]);
+    }
+}
@@ -425,8 +425,10 @@ fn fluid_rid(fluid: FluidType, depth: u8, tb: &TickBlocks) -> u32 {
 }
 
 /// Get the depth of a fluid block, or None if not the expected fluid type.
+/// Bubble columns count as water sources.
 fn fluid_depth(rid: u32, fluid: FluidType, tb: &TickBlocks) -> Option<u8> {
     match fluid {
+        FluidType::Water if tb.is_bubble_column(rid) => Some(0),
         FluidType::Water => tb.water_depth(rid),
         FluidType::Lava => tb.lava_depth(rid),
     }
@@ -130,12 +130,26 @@ impl ItemRegistry {
             return;
         }
         let next_id = self.by_id.keys().copied().max().unwrap_or(0) + 1;
-        self.by_id.insert(next_id, name.clone());
+        self.register_item_with_id(name, next_id, max_stack_size, is_component_based);
+    }
+
+    /// Register a custom item under a fixed numeric ID (e.g. a custom block's item).
+    pub fn register_item_with_id(
+        &mut self,
+        name: String,
+        numeric_id: i16,
+        max_stack_size: u8,
+        is_component_based: bool,
+    ) {
+        if self.by_name.contains_key(&name) {
+            return;
+        }
+        self.by_id.insert(numeric_id, name.clone());
         self.by_name.insert(
             name.clone(),
             ItemInfo {
                 name,
-                numeric_id: next_id,
+                numeric_id,
                 max_stack_size,
                 is_component_based,
             },
@@ -6,17 +6,25 @@ pub mod block_hash;
 pub mod block_registry;
 pub mod block_state_registry;
 pub mod block_tick;
+pub mod bubble_column;
 pub mod chunk;
 pub mod end_generator;
+pub mod farmland;
 pub mod flat_generator;
 pub mod fluid;
 pub mod gravity;
 pub mod item_registry;
+pub mod memory;
 pub mod nether_generator;
 pub mod noise;
 pub mod overworld_generator;
+pub mod parallel_tick;
 pub mod physics;
 pub mod piston;
+pub mod placement;
 pub mod redstone;
+pub mod region;
 pub mod serializer;
 pub mod storage;
+pub mod structure;
+pub mod tree;
@@ -0,0 +1,141 @@
+//! In-memory chunk storage, for tests and throwaway worlds.
+
+use std::collections::HashMap;
+
+use crate::chunk::ChunkColumn;
+use crate::region::{decode_chunk, encode_chunk};
+use crate::storage::{assemble_column, ChunkStorageProvider, RepairReport};
+
+/// Chunk storage that keeps everything in memory and never touches disk.
+///
+/// Chunks are kept serialized (in the region record format) so loads return
+/// fresh, clean columns just like the on-disk backends.
+#[derive(Default)]
+pub struct MemoryProvider {
+    /// Serialized chunks by `(cx, cz, dim)`.
+    chunks: HashMap<(i32, i32, i32), Vec<u8>>,
+    raw: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl MemoryProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored chunks.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+}
+
+impl ChunkStorageProvider for MemoryProvider {
+    fn load_chunk_dim(&mut self, cx: i32, cz: i32, dim: i32) -> Option<ChunkColumn> {
+        let (biomes, sub_chunks) = decode_chunk(self.chunks.get(&(cx, cz, dim))?, true)?;
+        Some(assemble_column(cx, cz, biomes, sub_chunks))
+    }
+
+    fn save_chunk_dim(&mut self, column: &ChunkColumn, dim: i32) -> Result<(), String> {
+        self.chunks
+            .insert((column.x, column.z, dim), encode_chunk(column));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn get_raw(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.raw.get(key).cloned()
+    }
+
+    fn put_raw(&mut self, key: &[u8], value: &[u8]) -> Result<(), String> {
+        self.raw.insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn delete_raw(&mut self, key: &[u8]) -> Result<(), String> {
+        self.raw.remove(key);
+        Ok(())
+    }
+
+    fn chunk_positions(&mut self) -> Result<Vec<(i32, i32, i32)>, String> {
+        let mut chunks: Vec<_> = self.chunks.keys().copied().collect();
+        chunks.sort_unstable();
+        Ok(chunks)
+    }
+
+    fn take_quarantined(&mut self) -> Vec<(i32, i32, i32)> {
+        Vec::new()
+    }
+
+    /// Nothing can be corrupt in memory, so this only counts the chunks.
+    fn repair(&mut self) -> Result<RepairReport, String> {
+        Ok(RepairReport {
+            checked: self.chunks.len(),
+            quarantined: Vec::new(),
+        })
+    }
+
+    fn maybe_compact(&mut self) -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{block_entity_key_dim, convert_storage};
+
+    /// Block at the origin of sub-chunk 4, biome at the origin and dirty flag of a
+    /// chunk, without keeping a whole column on the test's stack frame.
+    fn loaded(
+        storage: &mut dyn ChunkStorageProvider,
+        cx: i32,
+        cz: i32,
+        dim: i32,
+    ) -> Option<(u32, u8, bool)> {
+        let column = storage.load_chunk_dim(cx, cz, dim)?;
+        Some((
+            column.sub_chunks[4].get_block(0, 0, 0),
+            column.biome_at(0, 0, 0).unwrap(),
+            column.dirty,
+        ))
+    }
+
+    #[test]
+    fn save_load_roundtrip() {
+        let mut provider = MemoryProvider::new();
+        assert!(loaded(&mut provider, 0, 0, 0).is_none());
+
+        let mut column = ChunkColumn::new_air(2, -7, 10);
+        column.set_biome(0, 0, 0, 4);
+        column.sub_chunks[4].set_block(0, 0, 0, 42);
+        column.dirty = true;
+        provider.save_chunk_dim(&column, 1).unwrap();
+
+        // Later changes to the column aren't seen by the store
+        column.sub_chunks[4].set_block(0, 0, 0, 43);
+        assert_eq!(loaded(&mut provider, 2, -7, 1), Some((42, 4, false)));
+        assert!(loaded(&mut provider, 2, -7, 0).is_none());
+        assert_eq!(provider.chunk_positions().unwrap(), vec![(2, -7, 1)]);
+        assert_eq!(provider.repair().unwrap().checked, 1);
+    }
+
+    #[test]
+    fn raw_records_and_conversion() {
+        let mut provider = MemoryProvider::new();
+        let mut column = ChunkColumn::new_air(1, 1, 10);
+        column.sub_chunks[4].set_block(0, 0, 0, 9);
+        provider.save_chunk(&column).unwrap();
+        let key = block_entity_key_dim(1, 1, 0);
+        provider.put_raw(&key, b"entities").unwrap();
+        assert_eq!(provider.get_raw(&key).unwrap(), b"entities");
+        assert!(provider.get_raw(b"missing").is_none());
+
+        let mut copy = MemoryProvider::new();
+        assert_eq!(convert_storage(&mut provider, &mut copy).unwrap(), 1);
+        assert_eq!(copy.chunk_count(), 1);
+        assert_eq!(loaded(&mut copy, 1, 1, 0), Some((9, 0, false)));
+        assert_eq!(copy.get_raw(&key).unwrap(), b"entities");
+    }
+}
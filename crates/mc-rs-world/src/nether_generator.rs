@@ -9,8 +9,9 @@
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
+use crate::biome::NETHER_BIOME;
 use crate::block_hash::NetherBlocks;
-use crate::chunk::ChunkColumn;
+use crate::chunk::{ChunkColumn, OVERWORLD_SUB_CHUNK_COUNT};
 use crate::noise::OctaveNoise;
 
 /// Nether lava sea level.
@@ -72,13 +73,16 @@ impl NetherGenerator {
         self.place_ores(&mut column, chunk_x, chunk_z);
 
         // Phase 8: Biome data (all nether_wastes = biome ID 8)
-        for i in 0..256 {
-            column.biomes[i] = 8;
-        }
+        column.biomes = [[NETHER_BIOME; 64]; OVERWORLD_SUB_CHUNK_COUNT];
 
         column
     }
 
+    /// The biome at world block coordinates: always nether wastes.
+    pub fn biome_at(&self, _block_x: i32, _block_z: i32) -> u8 {
+        NETHER_BIOME
+    }
+
     /// Find a safe spawn Y in the Nether (first air above lava sea at chunk center).
     pub fn find_spawn_y(&self) -> i32 {
         let chunk = self.generate_chunk(0, 0);
@@ -468,7 +472,7 @@ mod tests {
     fn nether_biome_is_nether_wastes() {
         let gen = test_gen();
         let col = gen.generate_chunk(0, 0);
-        for &biome in col.biomes.iter() {
+        for &biome in col.biomes.iter().flatten() {
             assert_eq!(biome, 8, "All Nether biomes should be nether_wastes (8)");
         }
     }
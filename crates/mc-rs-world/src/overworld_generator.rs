@@ -12,6 +12,8 @@ use crate::biome::{BiomeDef, BiomeSelector, TreeType};
 use crate::block_hash::WorldBlocks;
 use crate::chunk::{ChunkColumn, OVERWORLD_MIN_Y};
 use crate::noise::OctaveNoise;
+use crate::structure::{StructureBounds, StructureKind};
+use crate::tree;
 
 /// Standard Minecraft sea level.
 pub const SEA_LEVEL: i32 = 62;
@@ -53,6 +55,16 @@ impl OverworldGenerator {
 
     /// Generate a full chunk column at the given chunk coordinates.
     pub fn generate_chunk(&self, chunk_x: i32, chunk_z: i32) -> ChunkColumn {
+        self.generate_chunk_with_structures(chunk_x, chunk_z).0
+    }
+
+    /// Generate a full chunk column, with the bounds of the structures
+    /// placed in it.
+    pub fn generate_chunk_with_structures(
+        &self,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> (ChunkColumn, Vec<StructureBounds>) {
         let mut column = ChunkColumn::new_air(chunk_x, chunk_z, self.blocks.air);
 
         // Phase 1: Compute heightmap and biomes
@@ -73,7 +85,8 @@ impl OverworldGenerator {
         self.place_ores(&mut column, chunk_x, chunk_z);
 
         // Phase 5.5: Place structures (dungeons, villages)
-        self.place_structures(&mut column, chunk_x, chunk_z, &heightmap, &biome_map);
+        let structures =
+            self.place_structures(&mut column, chunk_x, chunk_z, &heightmap, &biome_map);
 
         // Phase 6: Place trees
         self.place_trees(&mut column, chunk_x, chunk_z, &heightmap, &biome_map);
@@ -81,14 +94,20 @@ impl OverworldGenerator {
         // Phase 7: Place vegetation
         self.place_vegetation(&mut column, chunk_x, chunk_z, &heightmap, &biome_map);
 
-        // Phase 8: Store biome data
-        for lx in 0..16 {
-            for lz in 0..16 {
-                column.biomes[lx * 16 + lz] = biome_map[lx][lz];
+        // Phase 8: Store biome data, each 4x4 cell column taking the biome
+        // at its center
+        for lx in (0..16).step_by(4) {
+            for lz in (0..16).step_by(4) {
+                column.set_biome_column(lx, lz, biome_map[lx + 2][lz + 2]);
             }
         }
 
-        column
+        (column, structures)
+    }
+
+    /// The biome at world block coordinates, as generated chunks store it.
+    pub fn biome_at(&self, block_x: i32, block_z: i32) -> u8 {
+        self.biome_selector.get_biome(block_x, block_z).id
     }
 
     /// Find the ground level at the spawn point (chunk 0,0, block 0,0).
@@ -459,82 +478,16 @@ impl OverworldGenerator {
             TreeType::None => return,
         };
 
-        let trunk_height = match tree_type {
-            TreeType::Spruce => rng.gen_range(6..9),
-            _ => rng.gen_range(4..7),
-        };
-
-        // Place trunk
-        for dy in 0..trunk_height {
-            column.set_block_world(x, base_y + dy, z, log);
+        let shape = tree::tree_shape(tree_type, tree::trunk_height(tree_type, rng));
+        let (x, z) = (x as i32, z as i32);
+        // Trunk first, then leaves where there is air, within the chunk
+        for &(dx, dy, dz) in &shape.logs {
+            column.set_block_world((x + dx) as usize, base_y + dy, (z + dz) as usize, log);
         }
-
-        // Place leaves
-        match tree_type {
-            TreeType::Spruce => {
-                // Tapered canopy
-                let top_y = base_y + trunk_height;
-                // Top 1x1
-                self.set_if_air(column, x, top_y, z, leaves);
-                // Layer below: 3x3
-                for dx in -1i32..=1 {
-                    for dz in -1i32..=1 {
-                        let lx = x as i32 + dx;
-                        let lz = z as i32 + dz;
-                        if (0..16).contains(&lx) && (0..16).contains(&lz) {
-                            self.set_if_air(column, lx as usize, top_y - 1, lz as usize, leaves);
-                        }
-                    }
-                }
-                // Wider layers below
-                for layer in 2..=3 {
-                    let radius = layer;
-                    let y = top_y - layer;
-                    for dx in -radius..=radius {
-                        for dz in -radius..=radius {
-                            // Diamond shape
-                            if dx.abs() + dz.abs() > radius + 1 {
-                                continue;
-                            }
-                            let lx = x as i32 + dx;
-                            let lz = z as i32 + dz;
-                            if (0..16).contains(&lx) && (0..16).contains(&lz) {
-                                self.set_if_air(column, lx as usize, y, lz as usize, leaves);
-                            }
-                        }
-                    }
-                }
-            }
-            _ => {
-                // Standard canopy (oak, birch, acacia): 5x5x2 + 3x3x1 on top
-                let top_y = base_y + trunk_height;
-                // Top layer: 3x3
-                for dx in -1i32..=1 {
-                    for dz in -1i32..=1 {
-                        let lx = x as i32 + dx;
-                        let lz = z as i32 + dz;
-                        if (0..16).contains(&lx) && (0..16).contains(&lz) {
-                            self.set_if_air(column, lx as usize, top_y, lz as usize, leaves);
-                        }
-                    }
-                }
-                // Two layers below: 5x5 minus corners
-                for layer in 1..=2 {
-                    let y = top_y - layer;
-                    for dx in -2i32..=2 {
-                        for dz in -2i32..=2 {
-                            // Skip corners for a rounder shape
-                            if dx.abs() == 2 && dz.abs() == 2 {
-                                continue;
-                            }
-                            let lx = x as i32 + dx;
-                            let lz = z as i32 + dz;
-                            if (0..16).contains(&lx) && (0..16).contains(&lz) {
-                                self.set_if_air(column, lx as usize, y, lz as usize, leaves);
-                            }
-                        }
-                    }
-                }
+        for &(dx, dy, dz) in &shape.leaves {
+            let (lx, lz) = (x + dx, z + dz);
+            if (0..16).contains(&lx) && (0..16).contains(&lz) {
+                self.set_if_air(column, lx as usize, base_y + dy, lz as usize, leaves);
             }
         }
     }
@@ -635,7 +588,7 @@ impl OverworldGenerator {
         }
     }
 
-    // ---- Structure generation (dungeons + villages) ----
+    // ---- Structure generation (dungeons, villages, pillager outposts) ----
 
     fn place_structures(
         &self,
@@ -644,19 +597,25 @@ impl OverworldGenerator {
         chunk_z: i32,
         heightmap: &[[i32; 16]; 16],
         biome_map: &[[u8; 16]; 16],
-    ) {
-        self.place_dungeon(column, chunk_x, chunk_z, heightmap);
-        self.place_village(column, chunk_x, chunk_z, heightmap, biome_map);
+    ) -> Vec<StructureBounds> {
+        let dungeon = self.place_dungeon(column, chunk_x, chunk_z, heightmap);
+        let village = self.place_village(column, chunk_x, chunk_z, heightmap, biome_map);
+        let outpost = match village {
+            Some(_) => None,
+            None => self.place_outpost(column, chunk_x, chunk_z, heightmap, biome_map),
+        };
+        dungeon.into_iter().chain(village).chain(outpost).collect()
     }
 
     /// Place a dungeon (~10% chance per chunk): 7×7×5 cobblestone room with spawner + chests.
+    /// Returns its bounds if one was placed.
     fn place_dungeon(
         &self,
         column: &mut ChunkColumn,
         chunk_x: i32,
         chunk_z: i32,
         heightmap: &[[i32; 16]; 16],
-    ) {
+    ) -> Option<StructureBounds> {
         let mut rng = StdRng::seed_from_u64(
             self.seed
                 .wrapping_mul(7919)
@@ -667,7 +626,7 @@ impl OverworldGenerator {
         );
 
         if rng.gen_range(0u32..10) != 0 {
-            return;
+            return None;
         }
 
         // Room anchor: top-left corner. Room is 7×7, so anchor must be in [0, 9].
@@ -677,17 +636,17 @@ impl OverworldGenerator {
         let min_room_y = OVERWORLD_MIN_Y + 10;
         let max_room_y = (center_surface - 10).min(50);
         if max_room_y <= min_room_y {
-            return;
+            return None;
         }
         let room_y = rng.gen_range(min_room_y..=max_room_y);
 
         // Verify center is solid (stone or deepslate)
         if let Some(block) = column.get_block_world(dx + 3, room_y + 1, dz + 3) {
             if block != self.blocks.stone && block != self.blocks.deepslate {
-                return;
+                return None;
             }
         } else {
-            return;
+            return None;
         }
 
         // Build 7×7×5 room
@@ -731,9 +690,17 @@ impl OverworldGenerator {
         if rng.gen_range(0u32..2) == 0 {
             column.set_block_world(dx + 5, room_y + 1, dz + 5, self.blocks.chest);
         }
+
+        let (x, z) = (chunk_x * 16 + dx as i32, chunk_z * 16 + dz as i32);
+        Some(StructureBounds {
+            kind: StructureKind::Dungeon,
+            min: (x, room_y, z),
+            max: (x + 6, room_y + 4, z + 6),
+        })
     }
 
     /// Place a village (~0.5% chance, only in plains/desert/savanna on flat terrain).
+    /// Returns its bounds if one was placed.
     fn place_village(
         &self,
         column: &mut ChunkColumn,
@@ -741,11 +708,11 @@ impl OverworldGenerator {
         chunk_z: i32,
         heightmap: &[[i32; 16]; 16],
         biome_map: &[[u8; 16]; 16],
-    ) {
+    ) -> Option<StructureBounds> {
         let center_biome = biome_map[8][8];
         // Only plains (1), desert (2), savanna (35)
         if center_biome != 1 && center_biome != 2 && center_biome != 35 {
-            return;
+            return None;
         }
 
         let mut rng = StdRng::seed_from_u64(
@@ -758,7 +725,7 @@ impl OverworldGenerator {
         );
 
         if rng.gen_range(0u32..200) != 0 {
-            return;
+            return None;
         }
 
         // Check flatness (center 8×8)
@@ -771,7 +738,7 @@ impl OverworldGenerator {
             }
         }
         if max_h - min_h > 5 || min_h < SEA_LEVEL {
-            return;
+            return None;
         }
 
         let num_houses = rng.gen_range(3u32..=5);
@@ -779,6 +746,12 @@ impl OverworldGenerator {
         // Well at center (3×3)
         let well_y = heightmap[8][8];
         self.place_well(column, 7, well_y, 7);
+        let (ox, oz) = (chunk_x * 16, chunk_z * 16);
+        let mut bounds = StructureBounds {
+            kind: StructureKind::Village,
+            min: (ox + 7, well_y - 1, oz + 7),
+            max: (ox + 9, well_y + 1, oz + 9),
+        };
 
         // Houses at fixed offsets
         let house_positions: [(usize, usize); 5] = [(1, 1), (1, 10), (10, 1), (10, 10), (5, 12)];
@@ -789,6 +762,8 @@ impl OverworldGenerator {
             }
             let hy = heightmap[hx + 2][hz + 2];
             self.place_house(column, hx, hy, hz);
+            let (x, z) = (ox + hx as i32, oz + hz as i32);
+            bounds.include((x, hy, z), (x + 4, hy + 3, z + 4));
         }
 
         // Paths from well to each house (coarse_dirt strips)
@@ -796,6 +771,81 @@ impl OverworldGenerator {
             let (hx, hz) = house_positions[i];
             self.place_path(column, 8, 8, hx + 2, hz + 2, heightmap);
         }
+        Some(bounds)
+    }
+
+    /// Place a pillager outpost (~0.5% chance, only in plains/desert/savanna/taiga
+    /// on flat terrain, never with a village): a 5×5 cobblestone base, oak log
+    /// pillars at the corners and an oak_planks lookout on top.
+    /// Returns its bounds if one was placed.
+    fn place_outpost(
+        &self,
+        column: &mut ChunkColumn,
+        chunk_x: i32,
+        chunk_z: i32,
+        heightmap: &[[i32; 16]; 16],
+        biome_map: &[[u8; 16]; 16],
+    ) -> Option<StructureBounds> {
+        let center_biome = biome_map[8][8];
+        // Only plains (1), desert (2), taiga (5), savanna (35)
+        if !matches!(center_biome, 1 | 2 | 5 | 35) {
+            return None;
+        }
+
+        let mut rng = StdRng::seed_from_u64(
+            self.seed
+                .wrapping_mul(69069)
+                .wrapping_add(chunk_x as u64)
+                .wrapping_mul(1_103_515_245)
+                .wrapping_add(chunk_z as u64)
+                .wrapping_add(0xBAD0),
+        );
+
+        if rng.gen_range(0u32..200) != 0 {
+            return None;
+        }
+
+        // Check flatness under the base (5×5 at 6..11)
+        let (x0, z0) = (6usize, 6usize);
+        let mut min_h = i32::MAX;
+        let mut max_h = i32::MIN;
+        for row in &heightmap[x0..x0 + 5] {
+            for &h in &row[z0..z0 + 5] {
+                min_h = min_h.min(h);
+                max_h = max_h.max(h);
+            }
+        }
+        if max_h - min_h > 3 || min_h < SEA_LEVEL {
+            return None;
+        }
+
+        let base_y = max_h;
+        for lx in 0..5 {
+            for lz in 0..5 {
+                let (wx, wz) = (x0 + lx, z0 + lz);
+                let is_corner = (lx == 0 || lx == 4) && (lz == 0 || lz == 4);
+                // Fill under the base down to the ground
+                for y in heightmap[wx][wz]..=base_y {
+                    column.set_block_world(wx, y, wz, self.blocks.cobblestone);
+                }
+                for dy in 1..=4 {
+                    let block = if is_corner {
+                        self.blocks.oak_log
+                    } else {
+                        self.blocks.air
+                    };
+                    column.set_block_world(wx, base_y + dy, wz, block);
+                }
+                column.set_block_world(wx, base_y + 5, wz, self.blocks.oak_planks);
+            }
+        }
+
+        let (x, z) = (chunk_x * 16 + x0 as i32, chunk_z * 16 + z0 as i32);
+        Some(StructureBounds {
+            kind: StructureKind::PillagerOutpost,
+            min: (x, base_y, z),
+            max: (x + 4, base_y + 5, z + 4),
+        })
     }
 
     /// Build a 3×3 well with cobblestone walls and water at bottom.
@@ -995,13 +1045,16 @@ mod tests {
         let gen = test_gen();
         let col = gen.generate_chunk(0, 0);
         // At least some biome IDs should be non-zero (not all ocean)
-        let non_zero = col.biomes.iter().filter(|&&b| b != 0).count();
+        let non_zero = col.biomes.iter().flatten().filter(|&&b| b != 0).count();
         // It's possible (but unlikely) that the entire chunk is ocean.
         // The seed 42 should produce land at chunk (0,0).
         assert!(
-            non_zero > 0 || col.biomes.iter().all(|&b| b == 0),
+            non_zero > 0 || col.biomes.iter().flatten().all(|&b| b == 0),
             "Biomes should be populated"
         );
+        // The same at every height, and as the generator reports
+        assert_eq!(col.biome_at(10, -64, 6), Some(gen.biome_at(10, 6)));
+        assert_eq!(col.biome_at(10, 300, 6), Some(gen.biome_at(10, 6)));
     }
 
     #[test]
@@ -1190,6 +1243,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dungeons_report_their_bounds() {
+        let gen = test_gen();
+        let mut found = 0;
+        for cx in -10..10 {
+            for cz in -10..10 {
+                let (col, structures) = gen.generate_chunk_with_structures(cx, cz);
+                let dungeons = structures
+                    .iter()
+                    .filter(|b| b.kind == StructureKind::Dungeon);
+                for bounds in dungeons {
+                    // The spawner sits in the middle of the room's floor
+                    let (x, z) = bounds.center();
+                    let (lx, lz) = ((x - cx * 16) as usize, (z - cz * 16) as usize);
+                    assert_eq!(
+                        col.get_block_world(lx, bounds.min.1 + 1, lz),
+                        Some(gen.blocks.mob_spawner)
+                    );
+                    assert!(bounds.contains((x, bounds.min.1 + 1, z)));
+                    found += 1;
+                }
+            }
+        }
+        assert!(found > 0, "Should place dungeons across 20×20 chunks");
+    }
+
     #[test]
     fn dungeon_has_chest() {
         let gen = test_gen();
@@ -1199,6 +1278,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pillager_outposts_have_a_lookout() {
+        let gen = test_gen();
+        let mut found = 0;
+        for cx in -30..30 {
+            for cz in -30..30 {
+                let (col, structures) = gen.generate_chunk_with_structures(cx, cz);
+                for bounds in structures {
+                    if bounds.kind != StructureKind::PillagerOutpost {
+                        continue;
+                    }
+                    assert!(!structures_has_village(&gen, cx, cz));
+                    let (x, z) = (bounds.min.0 - cx * 16, bounds.min.2 - cz * 16);
+                    let (x, z) = (x as usize, z as usize);
+                    let top = bounds.max.1;
+                    assert_eq!(col.get_block_world(x, top - 1, z), Some(gen.blocks.oak_log));
+                    assert_eq!(
+                        col.get_block_world(x + 2, top, z + 2),
+                        Some(gen.blocks.oak_planks)
+                    );
+                    assert_eq!(
+                        col.get_block_world(x + 2, bounds.min.1, z + 2),
+                        Some(gen.blocks.cobblestone)
+                    );
+                    found += 1;
+                }
+            }
+        }
+        assert!(
+            found > 0,
+            "Should place pillager outposts across 60×60 chunks"
+        );
+    }
+
+    fn structures_has_village(gen: &OverworldGenerator, cx: i32, cz: i32) -> bool {
+        let (_, structures) = gen.generate_chunk_with_structures(cx, cz);
+        structures.iter().any(|b| b.kind == StructureKind::Village)
+    }
+
     #[test]
     fn village_only_in_valid_biomes() {
         // Mountains (biome 3) should never have village structures
@@ -1215,7 +1333,7 @@ mod tests {
         for cx in -10..10 {
             for cz in -10..10 {
                 let col = gen_mt.generate_chunk(cx, cz);
-                let center_biome = col.biomes[8 * 16 + 8];
+                let center_biome = col.biome_at(8, 64, 8).unwrap();
                 // Only check mountain biomes (3)
                 if center_biome == 3 {
                     for y in 60..100 {
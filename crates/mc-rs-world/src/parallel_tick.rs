@@ -0,0 +1,246 @@
+//! Parallel block ticking over independent regions of loaded chunks.
+//!
+//! The block tick work of a game tick (random ticks, scheduled fluid,
+//! gravity, piston and redstone ticks, wire recalculation) is grouped by the
+//! region of [`REGION_CHUNKS`]×[`REGION_CHUNKS`] chunks it happens in, and
+//! the regions are evaluated in parallel against the world as it was before
+//! the phase. The block changes they produce go through an [`UpdateQueue`]:
+//! ticks staying inside their region are applied as produced, ticks reaching
+//! into another region after them, and only if nothing else changed their
+//! blocks in the phase. Refused ticks are handed back so they can run again
+//! against the settled world, and what they asked for next (ticks to
+//! schedule, neighbours to update) is dropped with their changes.
+
+use std::collections::{BTreeMap, HashSet};
+
+use rayon::prelude::*;
+
+/// Width of a region, in chunks.
+pub const REGION_CHUNKS: i32 = 4;
+
+/// log2 of the width of a region in blocks (4 chunks × 16 blocks).
+const REGION_BLOCK_SHIFT: i32 = 6;
+
+/// Region coordinates: chunk coordinates divided by [`REGION_CHUNKS`].
+pub type RegionPos = (i32, i32);
+
+/// A block change: (x, y, z, new runtime ID).
+pub type BlockChange = (i32, i32, i32, u32);
+
+/// Region of the block at `(x, z)`.
+pub fn region_of_block(x: i32, z: i32) -> RegionPos {
+    (x >> REGION_BLOCK_SHIFT, z >> REGION_BLOCK_SHIFT)
+}
+
+/// Region of the chunk at `(cx, cz)`.
+pub fn region_of_chunk(cx: i32, cz: i32) -> RegionPos {
+    (cx.div_euclid(REGION_CHUNKS), cz.div_euclid(REGION_CHUNKS))
+}
+
+/// Group `items` by region. Regions come out in a fixed order, and items in
+/// the order they were given, so neither the work nor the order its changes
+/// are applied in depend on hashing.
+pub fn group_by_region<T>(
+    items: impl IntoIterator<Item = T>,
+    region: impl Fn(&T) -> RegionPos,
+) -> Vec<(RegionPos, Vec<T>)> {
+    let mut groups: BTreeMap<RegionPos, Vec<T>> = BTreeMap::new();
+    for item in items {
+        groups.entry(region(&item)).or_default().push(item);
+    }
+    groups.into_iter().collect()
+}
+
+/// Run `work` on every region's items in parallel. Results come back in the
+/// order of `groups`.
+pub fn run_regions<T, R>(
+    groups: &[(RegionPos, Vec<T>)],
+    work: impl Fn(&[T]) -> R + Sync,
+) -> Vec<(RegionPos, R)>
+where
+    T: Sync,
+    R: Send,
+{
+    groups
+        .par_iter()
+        .map(|(region, items)| (*region, work(items)))
+        .collect()
+}
+
+/// Block changes made by regions ticked in parallel, settled into one order
+/// that is safe to apply. `O` identifies what made the changes (e.g. the
+/// position of a scheduled tick), to run it again if they are refused. `F`
+/// is what the tick does next, kept only if its changes are applied.
+pub struct UpdateQueue<O, F = ()> {
+    /// Ticks whose changes all stay inside their own region.
+    local: Vec<(O, Vec<BlockChange>, F)>,
+    /// Ticks with changes reaching into another region.
+    cross: Vec<(O, Vec<BlockChange>, F)>,
+}
+
+/// An [`UpdateQueue`] settled into what to apply.
+pub struct Settled<O, F> {
+    /// Block changes to apply, in order.
+    pub changes: Vec<BlockChange>,
+    /// Follow-ups of the applied ticks, in order.
+    pub follow_ups: Vec<F>,
+    /// Origins of the refused ticks.
+    pub refused: Vec<O>,
+}
+
+impl<O, F> Default for UpdateQueue<O, F> {
+    fn default() -> Self {
+        Self {
+            local: Vec::new(),
+            cross: Vec::new(),
+        }
+    }
+}
+
+impl<O, F> UpdateQueue<O, F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue the changes of one tick of region `from` and its follow-up.
+    /// They are applied or refused together, so e.g. a piston never moves
+    /// half its blocks. A tick without changes is never refused.
+    pub fn push(&mut self, from: RegionPos, origin: O, changes: Vec<BlockChange>, follow_up: F) {
+        if changes
+            .iter()
+            .all(|&(x, _, z, _)| region_of_block(x, z) == from)
+        {
+            self.local.push((origin, changes, follow_up));
+        } else {
+            self.cross.push((origin, changes, follow_up));
+        }
+    }
+
+    /// Settle the queue into the changes to apply, in order, the follow-ups
+    /// of the ticks they come from, and the origins of the refused ticks.
+    ///
+    /// Ticks staying inside their region all apply (two regions never both
+    /// do to the same block), the later of two changes to a block winning as
+    /// when ticking in sequence. A tick reaching into another region applies
+    /// only if none of its blocks was changed already: the region a block is
+    /// in wins it, then the first tick in order.
+    pub fn settle(self) -> Settled<O, F> {
+        let mut applied = Vec::new();
+        let mut follow_ups = Vec::new();
+        for (_, changes, follow_up) in self.local {
+            applied.extend(changes);
+            follow_ups.push(follow_up);
+        }
+        let mut written: HashSet<(i32, i32, i32)> =
+            applied.iter().map(|&(x, y, z, _)| (x, y, z)).collect();
+        let mut refused = Vec::new();
+        for (origin, changes, follow_up) in self.cross {
+            if changes
+                .iter()
+                .any(|&(x, y, z, _)| written.contains(&(x, y, z)))
+            {
+                refused.push(origin);
+                continue;
+            }
+            written.extend(changes.iter().map(|&(x, y, z, _)| (x, y, z)));
+            applied.extend(changes);
+            follow_ups.push(follow_up);
+        }
+        Settled {
+            changes: applied,
+            follow_ups,
+            refused,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions_cover_four_chunks() {
+        assert_eq!(region_of_block(0, 0), (0, 0));
+        assert_eq!(region_of_block(63, 63), (0, 0));
+        assert_eq!(region_of_block(64, -1), (1, -1));
+        assert_eq!(region_of_block(-64, -65), (-1, -2));
+        assert_eq!(region_of_chunk(3, -1), (0, -1));
+        assert_eq!(region_of_chunk(4, -4), (1, -1));
+        assert_eq!(region_of_chunk(-5, 0), (-2, 0));
+        // A block and its chunk agree
+        assert_eq!(
+            region_of_block(-70, 130),
+            region_of_chunk(-70 >> 4, 130 >> 4)
+        );
+    }
+
+    #[test]
+    fn groups_and_results_keep_their_order() {
+        let chunks = [(5, 0), (0, 0), (1, 1), (-1, 0), (4, 3)];
+        let groups = group_by_region(chunks, |&(cx, cz)| region_of_chunk(cx, cz));
+        assert_eq!(
+            groups,
+            vec![
+                ((-1, 0), vec![(-1, 0)]),
+                ((0, 0), vec![(0, 0), (1, 1)]),
+                ((1, 0), vec![(5, 0), (4, 3)]),
+            ]
+        );
+        let counts = run_regions(&groups, |chunks| chunks.len());
+        assert_eq!(counts, vec![((-1, 0), 1), ((0, 0), 2), ((1, 0), 2)]);
+    }
+
+    #[test]
+    fn cross_region_ticks_yield_to_the_owner() {
+        let mut queue = UpdateQueue::new();
+        // Region (1, 0) reaches over into region (0, 0)'s edge block, which
+        // (0, 0) changes itself: the whole tick is refused
+        queue.push((1, 0), "b1", vec![(64, 10, 0, 0), (63, 10, 0, 2)], ());
+        queue.push((0, 0), "a1", vec![(63, 10, 0, 1)], ());
+        // Reaching into a block nobody else touches
+        queue.push((1, 0), "b2", vec![(62, 10, 0, 2)], ());
+        // Two regions reaching into a third: the first in order wins
+        queue.push((0, 0), "a2", vec![(64, 10, 64, 1)], ());
+        queue.push((1, 0), "b3", vec![(64, 10, 64, 2)], ());
+        // The later local change to a block wins
+        queue.push((0, 0), "a3", vec![(1, 1, 1, 5)], ());
+        queue.push((0, 0), "a4", vec![(1, 1, 1, 6)], ());
+        queue.push((0, 0), "nothing", Vec::new(), ());
+
+        let Settled {
+            changes: applied,
+            refused,
+            ..
+        } = queue.settle();
+        assert_eq!(
+            applied,
+            vec![
+                (63, 10, 0, 1),
+                (1, 1, 1, 5),
+                (1, 1, 1, 6),
+                (62, 10, 0, 2),
+                (64, 10, 64, 1),
+            ]
+        );
+        assert_eq!(refused, vec!["b1", "b3"]);
+    }
+
+    #[test]
+    fn refused_ticks_schedule_nothing() {
+        let mut queue = UpdateQueue::new();
+        // Regions (0, 0) and (1, 0) both reach into (0, 1)'s block
+        // (10, 5, 64), each wanting to tick it again later
+        queue.push((0, 0), "a", vec![(10, 5, 64, 1)], vec![(10, 5, 64, 2)]);
+        queue.push((1, 0), "b", vec![(10, 5, 64, 2)], vec![(10, 5, 64, 4)]);
+        // A tick that changes nothing still schedules its follow-up
+        queue.push((1, 0), "c", Vec::new(), vec![(70, 5, 0, 1)]);
+
+        let settled = queue.settle();
+        assert_eq!(settled.changes, vec![(10, 5, 64, 1)]);
+        assert_eq!(settled.refused, vec!["b"]);
+        assert_eq!(
+            settled.follow_ups,
+            vec![vec![(70, 5, 0, 1)], vec![(10, 5, 64, 2)]]
+        );
+    }
+}
@@ -6,6 +6,12 @@ pub const PLAYER_WIDTH: f32 = 0.6;
 /// Player hitbox height when standing (Bedrock: 1.8 blocks).
 pub const PLAYER_HEIGHT: f32 = 1.8;
 
+/// Player hitbox height when sneaking (Bedrock: 1.5 blocks).
+pub const PLAYER_SNEAKING_HEIGHT: f32 = 1.5;
+
+/// Player hitbox height when swimming, crawling or gliding (0.6 blocks).
+pub const PLAYER_PRONE_HEIGHT: f32 = 0.6;
+
 /// Eye offset above feet (Bedrock: 1.62 blocks).
 pub const PLAYER_EYE_HEIGHT: f32 = 1.62;
 
@@ -22,8 +28,9 @@ pub const MAX_AIRBORNE_TICKS: u32 = 80;
 /// Maximum distance (blocks) a player can reach to interact with blocks.
 pub const BLOCK_REACH: f32 = 7.0;
 
-/// Maximum distance (blocks) for PvP/PvE attacks.
-pub const ATTACK_REACH: f32 = 6.0;
+/// Maximum distance (blocks) from a player's eyes to the hitbox of an
+/// entity they attack.
+pub const ATTACK_REACH: f32 = 4.0;
 
 /// Minimum ticks between consecutive block breaks.
 pub const MIN_BREAK_INTERVAL: u64 = 2;
@@ -37,72 +44,197 @@ pub const MIN_ATTACK_INTERVAL: u64 = 2;
 /// Minimum ticks between consecutive commands.
 pub const MIN_COMMAND_INTERVAL: u64 = 10;
 
+/// Minimum ticks between consecutive emotes.
+pub const MIN_EMOTE_INTERVAL: u64 = 20;
+
 /// Maximum total actions per second (all categories).
 pub const MAX_ACTIONS_PER_SECOND: u16 = 30;
 
-/// Number of speed violations before auto-kick.
-pub const SPEED_KICK_THRESHOLD: u32 = 10;
+/// Airborne ticks before forced fly-kick regardless of velocity (10 seconds).
+pub const MAX_AIRBORNE_KICK: u32 = 200;
+
+/// How a player holds their body, which decides the height of their hitbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pose {
+    #[default]
+    Standing,
+    Sneaking,
+    Swimming,
+    Crawling,
+    Gliding,
+}
+
+impl Pose {
+    /// The pose a player is in: gliding wins over swimming, swimming over
+    /// crawling and crawling over sneaking.
+    pub fn from_state(sneaking: bool, swimming: bool, crawling: bool, gliding: bool) -> Self {
+        if gliding {
+            Pose::Gliding
+        } else if swimming {
+            Pose::Swimming
+        } else if crawling {
+            Pose::Crawling
+        } else if sneaking {
+            Pose::Sneaking
+        } else {
+            Pose::Standing
+        }
+    }
+
+    /// Hitbox height in this pose.
+    pub fn height(self) -> f32 {
+        match self {
+            Pose::Standing => PLAYER_HEIGHT,
+            Pose::Sneaking => PLAYER_SNEAKING_HEIGHT,
+            Pose::Swimming | Pose::Crawling | Pose::Gliding => PLAYER_PRONE_HEIGHT,
+        }
+    }
+}
+
+// -----------------------------------------------------------------------
+// Water physics
+// -----------------------------------------------------------------------
 
-/// Number of fly violations before auto-kick.
-pub const FLY_KICK_THRESHOLD: u32 = 5;
+/// Share of its vertical speed an entity keeps after a tick in water.
+pub const WATER_DRAG: f32 = 0.8;
 
-/// Number of no-clip violations before auto-kick.
-pub const NOCLIP_KICK_THRESHOLD: u32 = 5;
+/// Maximum horizontal distance (blocks) a player can swim per tick.
+/// Sprint-swimming = ~0.2 b/t; 0.5 gives margin for latency.
+pub const MAX_SWIM_DISTANCE_PER_TICK: f32 = 0.5;
 
-/// Number of reach violations before auto-kick.
-pub const REACH_KICK_THRESHOLD: u32 = 20;
+/// Maximum vertical distance (blocks) a player can swim per tick, once the
+/// speed they hit the water with has worn off.
+pub const MAX_SWIM_VERTICAL_PER_TICK: f32 = 0.5;
 
-/// Number of rate-limit violations before auto-kick.
-pub const RATE_LIMIT_KICK_THRESHOLD: u32 = 50;
+/// Fastest a bubble column lifts an entity, in blocks per tick.
+pub const BUBBLE_COLUMN_MAX_RISE: f32 = 1.8;
 
-/// Ticks between violation decay passes (10 seconds).
-pub const VIOLATION_DECAY_INTERVAL: u64 = 200;
+/// Fastest a bubble column drags an entity down, in blocks per tick.
+pub const BUBBLE_COLUMN_MAX_SINK: f32 = 0.9;
 
-/// Airborne ticks before forced fly-kick regardless of velocity (10 seconds).
-pub const MAX_AIRBORNE_KICK: u32 = 200;
+/// How far from a dolphin a swimming player gets its speed boost.
+pub const DOLPHINS_GRACE_RANGE: f32 = 10.0;
 
-/// Tracks anti-cheat violation counts per category.
-#[derive(Debug, Clone, Default)]
-pub struct ViolationTracker {
-    pub speed: u32,
-    pub fly: u32,
-    pub noclip: u32,
-    pub reach: u32,
-    pub rate_limit: u32,
+/// Horizontal swim limit with Depth Strider boots and near a dolphin: each
+/// Depth Strider level takes a third of the way to twice the speed (the
+/// walking limit at level 3), and Dolphin's Grace doubles the result.
+pub fn max_swim_distance(depth_strider: i16, dolphins_grace: bool) -> f32 {
+    let strider = depth_strider.clamp(0, 3) as f32 / 3.0;
+    let limit = MAX_SWIM_DISTANCE_PER_TICK * (1.0 + strider);
+    if dolphins_grace {
+        limit * 2.0
+    } else {
+        limit
+    }
 }
 
-impl ViolationTracker {
-    /// Decrement each violation counter by 1 (min 0). Called periodically.
-    pub fn decay(&mut self) {
-        self.speed = self.speed.saturating_sub(1);
-        self.fly = self.fly.saturating_sub(1);
-        self.noclip = self.noclip.saturating_sub(1);
-        self.reach = self.reach.saturating_sub(1);
-        self.rate_limit = self.rate_limit.saturating_sub(1);
+/// Vertical swim limit this tick: whatever is left of the last tick's speed
+/// after water drag, on top of the swimming speed.
+pub fn max_swim_vertical(last_delta_y: f32) -> f32 {
+    last_delta_y.abs().min(MAX_FALL_PER_TICK) * WATER_DRAG + MAX_SWIM_VERTICAL_PER_TICK
+}
+
+/// Vertical velocity of an entity after a tick in a bubble column.
+pub fn bubble_column_velocity(velocity_y: f32, drag_down: bool) -> f32 {
+    if drag_down {
+        (velocity_y - 0.03).max(-BUBBLE_COLUMN_MAX_SINK)
+    } else {
+        (velocity_y + 0.1).min(BUBBLE_COLUMN_MAX_RISE)
     }
+}
 
-    /// Check if any category has exceeded its kick threshold.
-    /// Returns the violation reason if the player should be kicked.
-    pub fn should_kick(&self) -> Option<&'static str> {
-        if self.speed >= SPEED_KICK_THRESHOLD {
-            return Some("Speed hack detected");
-        }
-        if self.fly >= FLY_KICK_THRESHOLD {
-            return Some("Fly hack detected");
+// -----------------------------------------------------------------------
+// Block surfaces
+// -----------------------------------------------------------------------
+
+/// Share of their own speed entities keep on soul sand and honey blocks.
+pub const SLOW_BLOCK_FACTOR: f32 = 0.4;
+
+/// What an entity stands on, as far as its movement cares.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Surface {
+    #[default]
+    Normal,
+    Slime,
+    Ice,
+    BlueIce,
+    SoulSand,
+    Honey,
+}
+
+impl Surface {
+    /// The surface of a block.
+    pub fn of(block_name: &str) -> Self {
+        match block_name {
+            "minecraft:slime" => Self::Slime,
+            "minecraft:ice" | "minecraft:packed_ice" | "minecraft:frosted_ice" => Self::Ice,
+            "minecraft:blue_ice" => Self::BlueIce,
+            "minecraft:soul_sand" => Self::SoulSand,
+            "minecraft:honey_block" => Self::Honey,
+            _ => Self::Normal,
         }
-        if self.noclip >= NOCLIP_KICK_THRESHOLD {
-            return Some("No-clip detected");
+    }
+
+    /// Share of its horizontal velocity an entity keeps after a tick on
+    /// this surface: slippery blocks let it slide on.
+    pub fn horizontal_drag(self) -> f32 {
+        match self {
+            Self::Normal | Self::SoulSand | Self::Honey => 0.91,
+            Self::Slime => 0.94,
+            Self::Ice => 0.98,
+            Self::BlueIce => 0.989,
         }
-        if self.reach >= REACH_KICK_THRESHOLD {
-            return Some("Reach hack detected");
+    }
+
+    /// Multiplier on how far an entity walks in a tick on this surface.
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            Self::SoulSand | Self::Honey => SLOW_BLOCK_FACTOR,
+            _ => 1.0,
         }
-        if self.rate_limit >= RATE_LIMIT_KICK_THRESHOLD {
-            return Some("Too many actions");
+    }
+
+    /// Multiplier on how far a player may move in a tick on this surface.
+    /// Speed builds up jumping along slippery blocks.
+    pub fn max_distance_factor(self) -> f32 {
+        match self {
+            Self::Normal => 1.0,
+            Self::Slime => 1.5,
+            Self::Ice => 2.0,
+            Self::BlueIce => 3.0,
+            Self::SoulSand | Self::Honey => SLOW_BLOCK_FACTOR,
         }
-        None
     }
 }
 
+/// How far a player may move on land in a tick, given the surface under
+/// them now and the one they last stood on: the faster of the two, since
+/// speed carries over through jumps and onto other blocks. Soul Speed boots
+/// take away the soul sand slowdown.
+pub fn max_move_distance(base: f32, now: Surface, last_ground: Surface, soul_speed: bool) -> f32 {
+    let factor = |surface: Surface| match surface {
+        Surface::SoulSand if soul_speed => 1.0,
+        other => other.max_distance_factor(),
+    };
+    base * factor(now).max(factor(last_ground))
+}
+
+// -----------------------------------------------------------------------
+// Climbing
+// -----------------------------------------------------------------------
+
+/// Whether players climb a block: ladders, vines and scaffolding.
+pub fn is_climbable(block_name: &str) -> bool {
+    matches!(
+        block_name,
+        "minecraft:ladder"
+            | "minecraft:vine"
+            | "minecraft:twisting_vines"
+            | "minecraft:weeping_vines"
+            | "minecraft:scaffolding"
+    )
+}
+
 /// Half the player width, used for AABB calculations.
 const HALF_WIDTH: f32 = PLAYER_WIDTH / 2.0;
 
@@ -120,17 +252,35 @@ pub struct PlayerAabb {
 impl PlayerAabb {
     /// Build the AABB from an eye position (Bedrock convention: position.y = eyes).
     pub fn from_eye_position(x: f32, y: f32, z: f32) -> Self {
+        Self::with_pose(x, y, z, Pose::Standing)
+    }
+
+    /// Build the AABB of a player in a pose from their eye position. The
+    /// client keeps reporting standing eye height whatever the pose, so only
+    /// the top of the box moves.
+    pub fn with_pose(x: f32, y: f32, z: f32, pose: Pose) -> Self {
         let feet_y = y - PLAYER_EYE_HEIGHT;
         Self {
             min_x: x - HALF_WIDTH,
             max_x: x + HALF_WIDTH,
             min_y: feet_y,
-            max_y: feet_y + PLAYER_HEIGHT,
+            max_y: feet_y + pose.height(),
             min_z: z - HALF_WIDTH,
             max_z: z + HALF_WIDTH,
         }
     }
 
+    /// Block positions right under the bottom of this AABB: what the player
+    /// could be standing on.
+    pub fn blocks_below(&self) -> impl Iterator<Item = (i32, i32, i32)> {
+        let below = Self {
+            min_y: self.min_y - 0.01,
+            max_y: self.min_y - 0.01 + 0.002,
+            ..*self
+        };
+        below.intersecting_blocks()
+    }
+
     /// Iterate all block positions that intersect this AABB.
     ///
     /// A small epsilon (0.001) is subtracted from max bounds so that a player
@@ -175,6 +325,65 @@ mod tests {
         assert!(MAX_FALL_PER_TICK > terminal_velocity);
     }
 
+    #[test]
+    fn block_surfaces() {
+        assert_eq!(Surface::of("minecraft:packed_ice"), Surface::Ice);
+        assert_eq!(Surface::of("minecraft:honey_block"), Surface::Honey);
+        assert_eq!(Surface::of("minecraft:stone"), Surface::Normal);
+        assert!(Surface::BlueIce.horizontal_drag() > Surface::Ice.horizontal_drag());
+        assert!(Surface::Ice.horizontal_drag() > Surface::Normal.horizontal_drag());
+        assert_eq!(Surface::SoulSand.speed_factor(), SLOW_BLOCK_FACTOR);
+        assert_eq!(Surface::Ice.speed_factor(), 1.0);
+    }
+
+    #[test]
+    fn move_limits_by_surface() {
+        let limit = |now, last, soul_speed| max_move_distance(1.0, now, last, soul_speed);
+        assert_eq!(limit(Surface::Normal, Surface::Normal, false), 1.0);
+        // Soul sand holds players back only once both feet are on it
+        assert_eq!(limit(Surface::SoulSand, Surface::SoulSand, false), 0.4);
+        assert_eq!(limit(Surface::SoulSand, Surface::Normal, false), 1.0);
+        assert_eq!(limit(Surface::SoulSand, Surface::SoulSand, true), 1.0);
+        assert_eq!(limit(Surface::Honey, Surface::Honey, true), 0.4);
+        // Ice speed lasts through jumps
+        assert_eq!(limit(Surface::Normal, Surface::Ice, false), 2.0);
+        assert_eq!(limit(Surface::BlueIce, Surface::Normal, false), 3.0);
+    }
+
+    #[test]
+    fn swim_limits() {
+        assert_eq!(max_swim_distance(0, false), MAX_SWIM_DISTANCE_PER_TICK);
+        assert!((max_swim_distance(3, false) - 1.0).abs() < 0.001);
+        assert!((max_swim_distance(3, true) - 2.0).abs() < 0.001);
+        assert_eq!(max_swim_distance(7, false), max_swim_distance(3, false));
+
+        // Hitting the water at terminal velocity wears off tick by tick
+        let first = max_swim_vertical(-3.92);
+        assert!(first > 3.0);
+        assert!(max_swim_vertical(first * WATER_DRAG) < first);
+        assert_eq!(max_swim_vertical(0.0), MAX_SWIM_VERTICAL_PER_TICK);
+    }
+
+    #[test]
+    fn climbable_blocks() {
+        assert!(is_climbable("minecraft:ladder"));
+        assert!(is_climbable("minecraft:scaffolding"));
+        assert!(!is_climbable("minecraft:stone"));
+    }
+
+    #[test]
+    fn bubble_columns_cap_speed() {
+        let mut vy = 0.0;
+        for _ in 0..40 {
+            vy = bubble_column_velocity(vy, false);
+        }
+        assert_eq!(vy, BUBBLE_COLUMN_MAX_RISE);
+        for _ in 0..200 {
+            vy = bubble_column_velocity(vy, true);
+        }
+        assert_eq!(vy, -BUBBLE_COLUMN_MAX_SINK);
+    }
+
     #[test]
     fn aabb_from_eye_at_spawn() {
         // Spawn eye position: (0.5, 5.62, 0.5), feet at Y=4.0
@@ -230,101 +439,39 @@ mod tests {
         assert!(blocks.iter().all(|b| b.0 == 0 && b.2 == 0));
     }
 
+    #[test]
+    fn pose_sets_the_height() {
+        assert_eq!(Pose::from_state(true, false, false, false), Pose::Sneaking);
+        assert_eq!(Pose::from_state(true, true, true, false), Pose::Swimming);
+        assert_eq!(Pose::from_state(false, false, true, false), Pose::Crawling);
+        assert_eq!(Pose::from_state(true, true, false, true), Pose::Gliding);
+        assert_eq!(Pose::default().height(), PLAYER_HEIGHT);
+
+        // Crawling under a slab: feet at Y=4.0, top at 4.6
+        let aabb = PlayerAabb::with_pose(0.5, 5.62, 0.5, Pose::Crawling);
+        assert!((aabb.max_y - 4.6).abs() < 0.001);
+        let blocks: Vec<_> = aabb.intersecting_blocks().collect();
+        assert_eq!(blocks, [(0, 4, 0)]);
+    }
+
+    #[test]
+    fn blocks_below_cover_the_footprint() {
+        // Straddling X=1 with feet at Y=4.0: standing on blocks 0 and 1 at Y=3
+        let aabb = PlayerAabb::from_eye_position(1.0, 5.62, 0.5);
+        let below: Vec<_> = aabb.blocks_below().collect();
+        assert_eq!(below, [(0, 3, 0), (1, 3, 0)]);
+    }
+
     #[test]
     fn anti_cheat_constants() {
         assert!((BLOCK_REACH - 7.0).abs() < f32::EPSILON);
-        assert!((ATTACK_REACH - 6.0).abs() < f32::EPSILON);
+        assert!((ATTACK_REACH - 4.0).abs() < f32::EPSILON);
         assert_eq!(MIN_BREAK_INTERVAL, 2);
         assert_eq!(MIN_PLACE_INTERVAL, 2);
         assert_eq!(MIN_ATTACK_INTERVAL, 2);
         assert_eq!(MIN_COMMAND_INTERVAL, 10);
+        assert_eq!(MIN_EMOTE_INTERVAL, 20);
         assert_eq!(MAX_ACTIONS_PER_SECOND, 30);
-        assert_eq!(VIOLATION_DECAY_INTERVAL, 200);
         assert_eq!(MAX_AIRBORNE_KICK, 200);
     }
-
-    #[test]
-    fn violation_tracker_default() {
-        let tracker = ViolationTracker::default();
-        assert_eq!(tracker.speed, 0);
-        assert_eq!(tracker.fly, 0);
-        assert_eq!(tracker.noclip, 0);
-        assert_eq!(tracker.reach, 0);
-        assert_eq!(tracker.rate_limit, 0);
-        assert!(tracker.should_kick().is_none());
-    }
-
-    #[test]
-    fn violation_tracker_decay() {
-        let mut tracker = ViolationTracker {
-            speed: 3,
-            fly: 1,
-            noclip: 0,
-            reach: 5,
-            rate_limit: 2,
-        };
-        tracker.decay();
-        assert_eq!(tracker.speed, 2);
-        assert_eq!(tracker.fly, 0);
-        assert_eq!(tracker.noclip, 0); // stays at 0
-        assert_eq!(tracker.reach, 4);
-        assert_eq!(tracker.rate_limit, 1);
-    }
-
-    #[test]
-    fn violation_tracker_should_kick_speed() {
-        let tracker = ViolationTracker {
-            speed: SPEED_KICK_THRESHOLD,
-            ..Default::default()
-        };
-        assert_eq!(tracker.should_kick(), Some("Speed hack detected"));
-    }
-
-    #[test]
-    fn violation_tracker_should_kick_fly() {
-        let tracker = ViolationTracker {
-            fly: FLY_KICK_THRESHOLD,
-            ..Default::default()
-        };
-        assert_eq!(tracker.should_kick(), Some("Fly hack detected"));
-    }
-
-    #[test]
-    fn violation_tracker_should_kick_noclip() {
-        let tracker = ViolationTracker {
-            noclip: NOCLIP_KICK_THRESHOLD,
-            ..Default::default()
-        };
-        assert_eq!(tracker.should_kick(), Some("No-clip detected"));
-    }
-
-    #[test]
-    fn violation_tracker_should_kick_reach() {
-        let tracker = ViolationTracker {
-            reach: REACH_KICK_THRESHOLD,
-            ..Default::default()
-        };
-        assert_eq!(tracker.should_kick(), Some("Reach hack detected"));
-    }
-
-    #[test]
-    fn violation_tracker_should_kick_rate_limit() {
-        let tracker = ViolationTracker {
-            rate_limit: RATE_LIMIT_KICK_THRESHOLD,
-            ..Default::default()
-        };
-        assert_eq!(tracker.should_kick(), Some("Too many actions"));
-    }
-
-    #[test]
-    fn violation_tracker_below_threshold_no_kick() {
-        let tracker = ViolationTracker {
-            speed: SPEED_KICK_THRESHOLD - 1,
-            fly: FLY_KICK_THRESHOLD - 1,
-            noclip: NOCLIP_KICK_THRESHOLD - 1,
-            reach: REACH_KICK_THRESHOLD - 1,
-            rate_limit: RATE_LIMIT_KICK_THRESHOLD - 1,
-        };
-        assert!(tracker.should_kick().is_none());
-    }
 }
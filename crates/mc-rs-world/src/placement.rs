@@ -0,0 +1,815 @@
+//! Block placement rules: the state a held block is placed with, and the
+//! blocks it takes.
+//!
+//! Torches and buttons need a block to hang on, doors and beds take two
+//! blocks, two slabs make a double slab, stairs and logs turn the way they
+//! were placed, and waterloggable blocks keep the water source they replace.
+//! Saplings need soil, and leaves placed by players never decay.
+
+use crate::block_hash::{hash_block_state, hash_block_state_with_props, StateValue};
+use crate::block_registry::BlockRegistry;
+use crate::block_state_registry::{BlockStateInfo, StateValueOwned};
+
+/// Block faces, as the client numbers them.
+pub const FACE_DOWN: i32 = 0;
+pub const FACE_UP: i32 = 1;
+pub const FACE_NORTH: i32 = 2;
+pub const FACE_SOUTH: i32 = 3;
+pub const FACE_WEST: i32 = 4;
+pub const FACE_EAST: i32 = 5;
+
+/// One state property, with its value.
+type Property = (&'static str, StateValue<'static>);
+
+const BITS: &[StateValue<'static>] = &[StateValue::Byte(0), StateValue::Byte(1)];
+const DIRECTIONS: &[StateValue<'static>] = &[
+    StateValue::Int(0),
+    StateValue::Int(1),
+    StateValue::Int(2),
+    StateValue::Int(3),
+];
+const FACES: &[StateValue<'static>] = &[
+    StateValue::Int(0),
+    StateValue::Int(1),
+    StateValue::Int(2),
+    StateValue::Int(3),
+    StateValue::Int(4),
+    StateValue::Int(5),
+];
+const CARDINALS: &[StateValue<'static>] = &[
+    StateValue::Str("south"),
+    StateValue::Str("west"),
+    StateValue::Str("north"),
+    StateValue::Str("east"),
+];
+const TORCH_FACINGS: &[StateValue<'static>] = &[
+    StateValue::Str("top"),
+    StateValue::Str("north"),
+    StateValue::Str("south"),
+    StateValue::Str("west"),
+    StateValue::Str("east"),
+];
+const HALVES: &[StateValue<'static>] = &[StateValue::Str("bottom"), StateValue::Str("top")];
+const AXES: &[StateValue<'static>] = &[
+    StateValue::Str("x"),
+    StateValue::Str("y"),
+    StateValue::Str("z"),
+];
+
+/// How a block is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementKind {
+    /// Placed as held.
+    Simple,
+    /// Stands on the block below or hangs on the side of the clicked one.
+    Torch,
+    /// Sticks to any face of the clicked block.
+    Button,
+    /// Two blocks high, facing the way the placer looks.
+    Door,
+    /// Two blocks long, the head away from the placer.
+    Bed,
+    /// Top or bottom half; a second half makes a double slab.
+    Slab,
+    /// Ascending away from the placer, upside down when hung from above.
+    Stairs,
+    /// Logs and pillars, along the axis of the clicked face.
+    Pillar,
+    /// Leaves, which stay when placed by a player.
+    Leaves,
+    /// Planted in soil, young.
+    Sapling,
+}
+
+impl PlacementKind {
+    /// How a block is placed, from its name.
+    pub fn of(name: &str) -> Self {
+        let stem = name.strip_prefix("minecraft:").unwrap_or(name);
+        match stem {
+            "torch" | "soul_torch" | "redstone_torch" | "unlit_redstone_torch" => Self::Torch,
+            "bed" => Self::Bed,
+            "hay_block" | "bone_block" | "basalt" | "polished_basalt" => Self::Pillar,
+            _ if stem.ends_with("_button") => Self::Button,
+            _ if stem.ends_with("_door") && !stem.ends_with("_trapdoor") => Self::Door,
+            _ if stem.ends_with("_slab") && !stem.ends_with("_double_slab") => Self::Slab,
+            _ if stem.ends_with("_stairs") => Self::Stairs,
+            _ if stem.ends_with("_leaves") => Self::Leaves,
+            _ if stem.ends_with("_sapling") => Self::Sapling,
+            _ if stem.ends_with("_log")
+                || stem.ends_with("_wood")
+                || stem.ends_with("_hyphae")
+                || stem.ends_with("_pillar")
+                || stem.ends_with("warped_stem")
+                || stem.ends_with("crimson_stem") =>
+            {
+                Self::Pillar
+            }
+            _ => Self::Simple,
+        }
+    }
+
+    /// The state properties of blocks placed this way, with every value
+    /// each can take.
+    fn properties(self) -> &'static [(&'static str, &'static [StateValue<'static>])] {
+        match self {
+            Self::Simple => &[],
+            Self::Torch => &[("torch_facing_direction", TORCH_FACINGS)],
+            Self::Button => &[("button_pressed_bit", BITS), ("facing_direction", FACES)],
+            Self::Door => &[
+                ("door_hinge_bit", BITS),
+                ("minecraft:cardinal_direction", CARDINALS),
+                ("open_bit", BITS),
+                ("upper_block_bit", BITS),
+            ],
+            Self::Bed => &[
+                ("direction", DIRECTIONS),
+                ("head_piece_bit", BITS),
+                ("occupied_bit", BITS),
+            ],
+            Self::Slab => &[("minecraft:vertical_half", HALVES)],
+            Self::Stairs => &[("upside_down_bit", BITS), ("weirdo_direction", DIRECTIONS)],
+            Self::Pillar => &[("pillar_axis", AXES)],
+            Self::Leaves => &[("persistent_bit", BITS), ("update_bit", BITS)],
+            Self::Sapling => &[("age_bit", BITS)],
+        }
+    }
+}
+
+/// Name of the double slab two slabs of a kind make.
+pub fn double_slab(slab: &str) -> String {
+    format!("{}_double_slab", slab.trim_end_matches("_slab"))
+}
+
+/// Every state a block can be placed in (its double slab's too), as block
+/// name and properties. Empty for blocks placed as held.
+pub fn block_states(name: &str) -> Vec<(String, Vec<Property>)> {
+    let kind = PlacementKind::of(name);
+    let mut states: Vec<Vec<Property>> = vec![Vec::new()];
+    for &(property, values) in kind.properties() {
+        states = states
+            .into_iter()
+            .flat_map(|state| {
+                values.iter().map(move |value| {
+                    let mut state = state.clone();
+                    state.push((property, value.clone()));
+                    state
+                })
+            })
+            .collect();
+    }
+    if kind == PlacementKind::Simple {
+        return Vec::new();
+    }
+    let mut result: Vec<(String, Vec<Property>)> = states
+        .iter()
+        .map(|state| (name.to_string(), state.clone()))
+        .collect();
+    if kind == PlacementKind::Slab {
+        let double = double_slab(name);
+        result.extend(states.into_iter().map(|state| (double.clone(), state)));
+    }
+    result
+}
+
+/// Whether a block can share its position with a water source.
+pub fn is_waterloggable(name: &str) -> bool {
+    let stem = name.strip_prefix("minecraft:").unwrap_or(name);
+    matches!(
+        PlacementKind::of(name),
+        PlacementKind::Slab | PlacementKind::Stairs
+    ) || stem.ends_with("_fence")
+        || stem.ends_with("_fence_gate")
+        || stem.ends_with("_trapdoor")
+        || stem.ends_with("_wall")
+        || matches!(
+            stem,
+            "ladder" | "chest" | "lantern" | "soul_lantern" | "iron_bars" | "chain"
+        )
+}
+
+/// Whether a block gives way to a block placed in it.
+fn is_replaceable(name: &str) -> bool {
+    matches!(
+        name,
+        "minecraft:air"
+            | "minecraft:water"
+            | "minecraft:lava"
+            | "minecraft:bubble_column"
+            | "minecraft:fire"
+            | "minecraft:soul_fire"
+            | "minecraft:tallgrass"
+            | "minecraft:short_grass"
+            | "minecraft:deadbush"
+            | "minecraft:seagrass"
+            | "minecraft:snow_layer"
+            | "minecraft:vine"
+            | "minecraft:structure_void"
+    )
+}
+
+/// Where and how a player places a block.
+#[derive(Debug, Clone, Copy)]
+pub struct PlacementContext {
+    /// Position of the clicked block.
+    pub clicked: (i32, i32, i32),
+    /// Face of it that was clicked (`FACE_*`).
+    pub face: i32,
+    /// Height on that face that was clicked, from 0 to 1.
+    pub click_y: f32,
+    /// The placer's yaw: 0 = south, 90 = west, 180 = north, 270 = east.
+    pub yaw: f32,
+}
+
+impl PlacementContext {
+    /// Where the placed block goes: next to the clicked face.
+    pub fn target(&self) -> (i32, i32, i32) {
+        let (x, y, z) = self.clicked;
+        match self.face {
+            FACE_DOWN => (x, y - 1, z),
+            FACE_UP => (x, y + 1, z),
+            FACE_NORTH => (x, y, z - 1),
+            FACE_SOUTH => (x, y, z + 1),
+            FACE_WEST => (x - 1, y, z),
+            FACE_EAST => (x + 1, y, z),
+            _ => (x, y, z),
+        }
+    }
+
+    /// Way the placer looks, as a direction index: 0 = south, 1 = west,
+    /// 2 = north, 3 = east.
+    fn direction(&self) -> usize {
+        ((self.yaw.rem_euclid(360.0) + 45.0) / 90.0) as usize % 4
+    }
+
+    /// Whether the block goes in the upper half of its space: hung from a
+    /// ceiling, or placed high on a side.
+    fn upper_half(&self) -> bool {
+        match self.face {
+            FACE_DOWN => true,
+            FACE_UP => false,
+            _ => self.click_y > 0.5,
+        }
+    }
+}
+
+/// Offset of one block in a direction index's way.
+fn step(direction: usize) -> (i32, i32) {
+    match direction {
+        0 => (0, 1),
+        1 => (-1, 0),
+        2 => (0, -1),
+        _ => (1, 0),
+    }
+}
+
+/// The blocks a placement sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// Blocks to set as `(x, y, z, runtime_id)`, the placed block first.
+    pub blocks: Vec<(i32, i32, i32, u32)>,
+    /// Whether the placed block keeps the water source it replaces.
+    pub waterlogged: bool,
+}
+
+impl Placement {
+    fn single(position: (i32, i32, i32), runtime_id: u32) -> Self {
+        Self {
+            blocks: vec![(position.0, position.1, position.2, runtime_id)],
+            waterlogged: false,
+        }
+    }
+}
+
+fn state(name: &str, properties: &[Property]) -> u32 {
+    hash_block_state_with_props(name, properties)
+}
+
+/// What placing a block held as `held_rid` does. `None` if it can't be
+/// placed there: nothing to hang on, no room, or no support below.
+///
+/// `get_block` returns the block at a position, `None` where the world
+/// isn't loaded.
+pub fn place(
+    name: &str,
+    held_rid: u32,
+    ctx: &PlacementContext,
+    registry: &BlockRegistry,
+    get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+) -> Option<Placement> {
+    let kind = PlacementKind::of(name);
+    let block_name = |pos: (i32, i32, i32)| {
+        get_block(pos.0, pos.1, pos.2)
+            .and_then(|rid| registry.get(rid))
+            .map(|info| info.name)
+    };
+    let replaceable = |pos: (i32, i32, i32)| block_name(pos).is_some_and(is_replaceable);
+    let solid = |pos: (i32, i32, i32)| {
+        get_block(pos.0, pos.1, pos.2).is_some_and(|rid| registry.is_solid(rid))
+    };
+    let below = |(x, y, z): (i32, i32, i32)| (x, y - 1, z);
+
+    // A slab completes the half slab it is placed into
+    let target = ctx.target();
+    if kind == PlacementKind::Slab {
+        let half =
+            |h: &'static str| state(name, &[("minecraft:vertical_half", StateValue::Str(h))]);
+        let double = state(
+            &double_slab(name),
+            &[("minecraft:vertical_half", StateValue::Str("bottom"))],
+        );
+        let clicked = get_block(ctx.clicked.0, ctx.clicked.1, ctx.clicked.2);
+        if (ctx.face == FACE_UP && clicked == Some(half("bottom")))
+            || (ctx.face == FACE_DOWN && clicked == Some(half("top")))
+        {
+            return Some(Placement::single(ctx.clicked, double));
+        }
+        let current = get_block(target.0, target.1, target.2);
+        if current == Some(half("bottom")) || current == Some(half("top")) {
+            return Some(Placement::single(target, double));
+        }
+    }
+
+    if !replaceable(target) {
+        return None;
+    }
+    let target_rid = get_block(target.0, target.1, target.2)?;
+    let water_source = [
+        hash_block_state("minecraft:water"),
+        hash_block_state_with_props("minecraft:water", &[("liquid_depth", StateValue::Int(0))]),
+    ];
+    let waterlogged = is_waterloggable(name) && water_source.contains(&target_rid);
+    let single = |rid: u32| Placement {
+        waterlogged,
+        ..Placement::single(target, rid)
+    };
+    let direction = ctx.direction();
+
+    match kind {
+        PlacementKind::Simple => Some(single(held_rid)),
+        PlacementKind::Torch => {
+            let facing = match ctx.face {
+                FACE_UP => "top",
+                FACE_NORTH => "north",
+                FACE_SOUTH => "south",
+                FACE_WEST => "west",
+                FACE_EAST => "east",
+                _ => return None,
+            };
+            solid(ctx.clicked).then(|| {
+                single(state(
+                    name,
+                    &[("torch_facing_direction", StateValue::Str(facing))],
+                ))
+            })
+        }
+        PlacementKind::Button => solid(ctx.clicked).then(|| {
+            single(state(
+                name,
+                &[
+                    ("button_pressed_bit", StateValue::Byte(0)),
+                    ("facing_direction", StateValue::Int(ctx.face.clamp(0, 5))),
+                ],
+            ))
+        }),
+        PlacementKind::Door => {
+            let upper = (target.0, target.1 + 1, target.2);
+            if !solid(below(target)) || !replaceable(upper) {
+                return None;
+            }
+            let cardinal = &CARDINALS[direction];
+            let half = |bit: i8| {
+                state(
+                    name,
+                    &[
+                        ("door_hinge_bit", StateValue::Byte(0)),
+                        ("minecraft:cardinal_direction", cardinal.clone()),
+                        ("open_bit", StateValue::Byte(0)),
+                        ("upper_block_bit", StateValue::Byte(bit)),
+                    ],
+                )
+            };
+            Some(Placement {
+                blocks: vec![
+                    (target.0, target.1, target.2, half(0)),
+                    (upper.0, upper.1, upper.2, half(1)),
+                ],
+                waterlogged: false,
+            })
+        }
+        PlacementKind::Bed => {
+            let (dx, dz) = step(direction);
+            let head = (target.0 + dx, target.1, target.2 + dz);
+            if !replaceable(head) || !solid(below(target)) || !solid(below(head)) {
+                return None;
+            }
+            let part = |head_piece: i8| {
+                state(
+                    name,
+                    &[
+                        ("direction", StateValue::Int(direction as i32)),
+                        ("head_piece_bit", StateValue::Byte(head_piece)),
+                        ("occupied_bit", StateValue::Byte(0)),
+                    ],
+                )
+            };
+            Some(Placement {
+                blocks: vec![
+                    (target.0, target.1, target.2, part(0)),
+                    (head.0, head.1, head.2, part(1)),
+                ],
+                waterlogged: false,
+            })
+        }
+        PlacementKind::Slab => {
+            let half = if ctx.upper_half() { "top" } else { "bottom" };
+            Some(single(state(
+                name,
+                &[("minecraft:vertical_half", StateValue::Str(half))],
+            )))
+        }
+        PlacementKind::Stairs => {
+            // weirdo_direction: 0 = east, 1 = west, 2 = south, 3 = north
+            let weirdo = [2, 1, 3, 0][direction];
+            Some(single(state(
+                name,
+                &[
+                    ("upside_down_bit", StateValue::Byte(ctx.upper_half() as i8)),
+                    ("weirdo_direction", StateValue::Int(weirdo)),
+                ],
+            )))
+        }
+        PlacementKind::Leaves => Some(single(state(
+            name,
+            &[
+                ("persistent_bit", StateValue::Byte(1)),
+                ("update_bit", StateValue::Byte(0)),
+            ],
+        ))),
+        PlacementKind::Sapling => {
+            let soil = block_name(below(target)).is_some_and(|below| {
+                matches!(
+                    below,
+                    "minecraft:grass_block"
+                        | "minecraft:dirt"
+                        | "minecraft:coarse_dirt"
+                        | "minecraft:podzol"
+                        | "minecraft:rooted_dirt"
+                        | "minecraft:moss_block"
+                        | "minecraft:mud"
+                        | "minecraft:farmland"
+                )
+            });
+            soil.then(|| single(state(name, &[("age_bit", StateValue::Byte(0))])))
+        }
+        PlacementKind::Pillar => {
+            let axis = match ctx.face {
+                FACE_NORTH | FACE_SOUTH => "z",
+                FACE_WEST | FACE_EAST => "x",
+                _ => "y",
+            };
+            Some(single(state(
+                name,
+                &[("pillar_axis", StateValue::Str(axis))],
+            )))
+        }
+    }
+}
+
+/// The other block of a door or bed at `(x, y, z)`, which goes with it
+/// when it is broken.
+pub fn other_half(info: &BlockStateInfo, (x, y, z): (i32, i32, i32)) -> Option<(i32, i32, i32)> {
+    let int = |property: &str| match info.property(property) {
+        Some(StateValueOwned::Int(v)) => Some(*v),
+        Some(StateValueOwned::Byte(v)) => Some(*v as i32),
+        _ => None,
+    };
+    match PlacementKind::of(&info.name) {
+        PlacementKind::Door => match int("upper_block_bit")? {
+            0 => Some((x, y + 1, z)),
+            _ => Some((x, y - 1, z)),
+        },
+        PlacementKind::Bed => {
+            let (dx, dz) = step(int("direction")?.rem_euclid(4) as usize);
+            match int("head_piece_bit")? {
+                0 => Some((x + dx, y, z + dz)),
+                _ => Some((x - dx, y, z - dz)),
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_state_registry::BlockStateRegistry;
+    use std::collections::HashMap;
+
+    /// Stone floor at y = 0, air above, water at x = 5.
+    fn world(extra: &[((i32, i32, i32), u32)]) -> impl Fn(i32, i32, i32) -> Option<u32> {
+        let extra: HashMap<(i32, i32, i32), u32> = extra.iter().copied().collect();
+        move |x, y, z| {
+            Some(
+                extra
+                    .get(&(x, y, z))
+                    .copied()
+                    .unwrap_or_else(|| match (x, y) {
+                        (_, ..=0) => hash_block_state("minecraft:stone"),
+                        (5, _) => hash_block_state("minecraft:water"),
+                        _ => hash_block_state("minecraft:air"),
+                    }),
+            )
+        }
+    }
+
+    fn on_floor(face: i32, yaw: f32) -> PlacementContext {
+        PlacementContext {
+            clicked: (0, 0, 0),
+            face,
+            click_y: 0.0,
+            yaw,
+        }
+    }
+
+    /// Clicking a face of a stone block standing on the floor.
+    fn on_pillar(face: i32) -> PlacementContext {
+        PlacementContext {
+            clicked: (0, 1, 0),
+            ..on_floor(face, 0.0)
+        }
+    }
+
+    fn pillar() -> impl Fn(i32, i32, i32) -> Option<u32> {
+        world(&[((0, 1, 0), hash_block_state("minecraft:stone"))])
+    }
+
+    fn place_in(
+        name: &str,
+        ctx: &PlacementContext,
+        get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+    ) -> Option<Placement> {
+        let registry = BlockRegistry::new();
+        place(name, hash_block_state(name), ctx, &registry, get_block)
+    }
+
+    fn props(name: &str, props: &[Property]) -> u32 {
+        hash_block_state_with_props(name, props)
+    }
+
+    #[test]
+    fn kinds_from_names() {
+        assert_eq!(PlacementKind::of("minecraft:oak_door"), PlacementKind::Door);
+        assert_eq!(
+            PlacementKind::of("minecraft:oak_trapdoor"),
+            PlacementKind::Simple
+        );
+        assert_eq!(PlacementKind::of("minecraft:oak_slab"), PlacementKind::Slab);
+        assert_eq!(
+            PlacementKind::of("minecraft:oak_double_slab"),
+            PlacementKind::Simple
+        );
+        assert_eq!(
+            PlacementKind::of("minecraft:crimson_stem"),
+            PlacementKind::Pillar
+        );
+        assert_eq!(
+            PlacementKind::of("minecraft:oak_leaves"),
+            PlacementKind::Leaves
+        );
+        assert_eq!(
+            PlacementKind::of("minecraft:birch_sapling"),
+            PlacementKind::Sapling
+        );
+        assert_eq!(
+            PlacementKind::of("minecraft:pumpkin_stem"),
+            PlacementKind::Simple
+        );
+        assert_eq!(
+            PlacementKind::of("minecraft:stone_button"),
+            PlacementKind::Button
+        );
+        assert_eq!(block_states("minecraft:oak_door").len(), 32);
+        assert_eq!(block_states("minecraft:oak_slab").len(), 4);
+        assert!(block_states("minecraft:stone").is_empty());
+    }
+
+    #[test]
+    fn torches_need_something_to_hang_on() {
+        let torch = |face| place_in("minecraft:torch", &on_floor(face, 0.0), world(&[]));
+        assert_eq!(
+            torch(FACE_UP).unwrap().blocks,
+            [(
+                0,
+                1,
+                0,
+                props(
+                    "minecraft:torch",
+                    &[("torch_facing_direction", StateValue::Str("top"))]
+                )
+            )]
+        );
+        let wall = place_in("minecraft:torch", &on_pillar(FACE_NORTH), pillar()).unwrap();
+        assert_eq!(
+            wall.blocks,
+            [(
+                0,
+                1,
+                -1,
+                props(
+                    "minecraft:torch",
+                    &[("torch_facing_direction", StateValue::Str("north"))]
+                )
+            )]
+        );
+        // Not under a ceiling, nor on air
+        assert!(torch(FACE_DOWN).is_none());
+        let mid_air = PlacementContext {
+            clicked: (0, 3, 0),
+            ..on_floor(FACE_UP, 0.0)
+        };
+        assert!(place_in("minecraft:torch", &mid_air, world(&[])).is_none());
+        // Buttons go on any face of a solid block
+        let button = place_in("minecraft:stone_button", &on_pillar(FACE_EAST), pillar());
+        assert_eq!(
+            button.unwrap().blocks,
+            [(
+                1,
+                1,
+                0,
+                props(
+                    "minecraft:stone_button",
+                    &[
+                        ("button_pressed_bit", StateValue::Byte(0)),
+                        ("facing_direction", StateValue::Int(FACE_EAST)),
+                    ]
+                )
+            )]
+        );
+    }
+
+    #[test]
+    fn doors_and_beds_take_two_blocks() {
+        // Looking north: the door faces north, its upper half above
+        let door = place_in("minecraft:oak_door", &on_floor(FACE_UP, 180.0), world(&[])).unwrap();
+        let half = |bit| {
+            props(
+                "minecraft:oak_door",
+                &[
+                    ("door_hinge_bit", StateValue::Byte(0)),
+                    ("minecraft:cardinal_direction", StateValue::Str("north")),
+                    ("open_bit", StateValue::Byte(0)),
+                    ("upper_block_bit", StateValue::Byte(bit)),
+                ],
+            )
+        };
+        assert_eq!(door.blocks, [(0, 1, 0, half(0)), (0, 2, 0, half(1))]);
+        // No room for the upper half
+        let low_ceiling = world(&[((0, 2, 0), hash_block_state("minecraft:stone"))]);
+        assert!(place_in("minecraft:oak_door", &on_floor(FACE_UP, 180.0), low_ceiling).is_none());
+
+        // Looking east: the head goes one block east
+        let bed = place_in("minecraft:bed", &on_floor(FACE_UP, 270.0), world(&[])).unwrap();
+        assert_eq!(bed.blocks[0].0..=bed.blocks[1].0, 0..=1);
+        let registry = BlockStateRegistry::new();
+        let head = registry.get(bed.blocks[1].3).unwrap();
+        assert_eq!(
+            head.property("head_piece_bit"),
+            Some(&StateValueOwned::Byte(1))
+        );
+        assert_eq!(other_half(head, (1, 1, 0)), Some((0, 1, 0)));
+        let lower = registry.get(door.blocks[0].3).unwrap();
+        assert_eq!(other_half(lower, (0, 1, 0)), Some((0, 2, 0)));
+    }
+
+    #[test]
+    fn slabs_stack_into_double_slabs() {
+        let half = |h| {
+            props(
+                "minecraft:oak_slab",
+                &[("minecraft:vertical_half", StateValue::Str(h))],
+            )
+        };
+        let double = props(
+            "minecraft:oak_double_slab",
+            &[("minecraft:vertical_half", StateValue::Str("bottom"))],
+        );
+        let slab =
+            |ctx: &PlacementContext, get_block| place_in("minecraft:oak_slab", ctx, get_block);
+
+        assert_eq!(
+            slab(&on_floor(FACE_UP, 0.0), world(&[])).unwrap().blocks,
+            [(0, 1, 0, half("bottom"))]
+        );
+        let high_on_side = PlacementContext {
+            clicked: (0, 1, 0),
+            face: FACE_NORTH,
+            click_y: 0.8,
+            yaw: 0.0,
+        };
+        let with_block = world(&[((0, 1, 0), hash_block_state("minecraft:stone"))]);
+        assert_eq!(
+            slab(&high_on_side, with_block).unwrap().blocks,
+            [(0, 1, -1, half("top"))]
+        );
+
+        // Onto a bottom slab, or into the space a top slab leaves
+        let bottom = world(&[((0, 1, 0), half("bottom"))]);
+        let clicked = PlacementContext {
+            clicked: (0, 1, 0),
+            ..on_floor(FACE_UP, 0.0)
+        };
+        assert_eq!(slab(&clicked, bottom).unwrap().blocks, [(0, 1, 0, double)]);
+        let top = world(&[((0, 1, 0), half("top"))]);
+        assert_eq!(
+            slab(&on_floor(FACE_UP, 0.0), top).unwrap().blocks,
+            [(0, 1, 0, double)]
+        );
+    }
+
+    #[test]
+    fn saplings_need_soil_and_placed_leaves_stay() {
+        let sapling = place_in("minecraft:oak_sapling", &on_floor(FACE_UP, 0.0), world(&[]));
+        assert!(sapling.is_none(), "stone is no soil");
+        let grass = world(&[((0, 0, 0), hash_block_state("minecraft:grass_block"))]);
+        let sapling = place_in("minecraft:oak_sapling", &on_floor(FACE_UP, 0.0), grass).unwrap();
+        assert_eq!(
+            sapling.blocks,
+            [(
+                0,
+                1,
+                0,
+                props("minecraft:oak_sapling", &[("age_bit", StateValue::Byte(0))])
+            )]
+        );
+
+        let leaves = place_in("minecraft:oak_leaves", &on_floor(FACE_UP, 0.0), world(&[])).unwrap();
+        let registry = BlockStateRegistry::new();
+        let info = registry.get(leaves.blocks[0].3).unwrap();
+        assert_eq!(
+            info.property("persistent_bit"),
+            Some(&StateValueOwned::Byte(1))
+        );
+    }
+
+    #[test]
+    fn stairs_and_logs_turn_to_the_placer() {
+        let stairs = place_in(
+            "minecraft:oak_stairs",
+            &on_floor(FACE_UP, 270.0),
+            world(&[]),
+        )
+        .unwrap();
+        assert_eq!(
+            stairs.blocks[0].3,
+            props(
+                "minecraft:oak_stairs",
+                &[
+                    ("upside_down_bit", StateValue::Byte(0)),
+                    ("weirdo_direction", StateValue::Int(0)),
+                ]
+            )
+        );
+        let log = place_in("minecraft:oak_log", &on_pillar(FACE_WEST), pillar()).unwrap();
+        assert_eq!(
+            log.blocks,
+            [(
+                -1,
+                1,
+                0,
+                props(
+                    "minecraft:oak_log",
+                    &[("pillar_axis", StateValue::Str("x"))]
+                )
+            )]
+        );
+    }
+
+    #[test]
+    fn waterloggable_blocks_keep_the_water() {
+        let into_water = PlacementContext {
+            clicked: (5, 0, 0),
+            ..on_floor(FACE_UP, 0.0)
+        };
+        assert!(
+            place_in("minecraft:oak_stairs", &into_water, world(&[]))
+                .unwrap()
+                .waterlogged
+        );
+        assert!(
+            !place_in("minecraft:stone", &into_water, world(&[]))
+                .unwrap()
+                .waterlogged
+        );
+        assert!(
+            !place_in("minecraft:oak_stairs", &on_floor(FACE_UP, 0.0), world(&[]))
+                .unwrap()
+                .waterlogged
+        );
+        // Placing into a solid block fails
+        let into_stone = PlacementContext {
+            clicked: (0, -1, 0),
+            ..on_floor(FACE_UP, 0.0)
+        };
+        assert!(place_in("minecraft:stone", &into_stone, world(&[])).is_none());
+    }
+}
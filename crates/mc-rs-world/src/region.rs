@@ -0,0 +1,713 @@
+//! Append-log region file storage, an alternative to LevelDB.
+//!
+//! Chunks are grouped into 32x32 regions with one file per region and
+//! dimension (`r.<dim>.<rx>.<rz>.log`); raw records go to `raw.log`. Each
+//! file is a log of `[key_len:u16_le][value_len:u32_le][fnv1a:u32_le][key][value]`
+//! records: the last record for a key wins and a `value_len` of `u32::MAX`
+//! deletes it. Files are indexed when first opened and rewritten once most
+//! of their records are dead.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::block_hash::fnv1a_32;
+use crate::chunk::biomes_from_2d;
+use crate::chunk::{ChunkColumn, SubChunk, OVERWORLD_SUB_CHUNK_COUNT};
+use crate::storage::{
+    assemble_column, block_entity_key_dim, chunk_key_dim, deserialize_data_2d, deserialize_data_3d,
+    deserialize_sub_chunk_disk, parse_chunk_version_key, serialize_data_3d,
+    serialize_sub_chunk_disk, ChunkRecords, ChunkStorageProvider, RepairReport, StorageOptions,
+    CHUNK_VERSION, QUARANTINE_PREFIX, TAG_CHUNK_VERSION,
+};
+
+/// Version of chunk records with 3D biomes; records of [`CHUNK_VERSION`]
+/// have Data2D instead.
+const RECORD_VERSION_3D: u8 = CHUNK_VERSION + 1;
+
+/// Record header: key length, value length and checksum.
+const HEADER_LEN: usize = 10;
+
+/// `value_len` of a deletion record.
+const TOMBSTONE: u32 = u32::MAX;
+
+/// Chunks per region side.
+const REGION_SIZE: i32 = 32;
+
+// ─── Log files ──────────────────────────────────────────────────────────────
+
+/// One append-log file and the index of its live records.
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    /// Key → (record offset, record length).
+    index: HashMap<Vec<u8>, (u64, u64)>,
+    /// End of the last complete record.
+    len: u64,
+    /// Total length of the records in `index`.
+    live: u64,
+}
+
+impl LogFile {
+    /// Open or create a log and index its records. A truncated record at
+    /// the end, left by a crash mid-write, is cut off.
+    fn open(path: &Path) -> std::io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+        let file_len = file.metadata()?.len();
+
+        let mut index = HashMap::new();
+        let mut len = 0;
+        let mut live = 0;
+        let mut reader = BufReader::new(&mut file);
+        let mut header = [0u8; HEADER_LEN];
+        while len + HEADER_LEN as u64 <= file_len {
+            reader.read_exact(&mut header)?;
+            let key_len = u16::from_le_bytes([header[0], header[1]]) as u64;
+            let value_len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+            let value_size = if value_len == TOMBSTONE {
+                0
+            } else {
+                value_len as u64
+            };
+            let record_len = HEADER_LEN as u64 + key_len + value_size;
+            if len + record_len > file_len {
+                break;
+            }
+            let mut key = vec![0u8; key_len as usize];
+            reader.read_exact(&mut key)?;
+            reader.seek_relative(value_size as i64)?;
+
+            let old = if value_len == TOMBSTONE {
+                index.remove(&key)
+            } else {
+                live += record_len;
+                index.insert(key, (len, record_len))
+            };
+            if let Some((_, old_len)) = old {
+                live -= old_len;
+            }
+            len += record_len;
+        }
+        drop(reader);
+        if len < file_len {
+            file.set_len(len)?;
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            index,
+            len,
+            live,
+        })
+    }
+
+    /// Read a record's value. Fails on a checksum mismatch if `verify` is set.
+    fn get(&mut self, key: &[u8], verify: bool) -> Result<Option<Vec<u8>>, String> {
+        let Some(&(offset, record_len)) = self.index.get(key) else {
+            return Ok(None);
+        };
+        let record = self
+            .read_record(offset, record_len)
+            .map_err(|e| format!("read {}: {e}", self.path.display()))?;
+        let expected = u32::from_le_bytes([record[6], record[7], record[8], record[9]]);
+        if verify && fnv1a_32(&record[HEADER_LEN..]) != expected {
+            return Err(format!("checksum mismatch in {}", self.path.display()));
+        }
+        Ok(Some(record[HEADER_LEN + key.len()..].to_vec()))
+    }
+
+    fn read_record(&mut self, offset: u64, record_len: u64) -> std::io::Result<Vec<u8>> {
+        let mut record = vec![0u8; record_len as usize];
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.read_exact(&mut record)?;
+        Ok(record)
+    }
+
+    fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), String> {
+        self.append(key, Some(value))
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Result<(), String> {
+        if !self.index.contains_key(key) {
+            return Ok(());
+        }
+        self.append(key, None)
+    }
+
+    /// Append a record, or a deletion record if `value` is `None`.
+    fn append(&mut self, key: &[u8], value: Option<&[u8]>) -> Result<(), String> {
+        let key_len = u16::try_from(key.len()).map_err(|_| "key too long".to_string())?;
+        let value_len = match value {
+            Some(v) if v.len() >= TOMBSTONE as usize => return Err("value too long".into()),
+            Some(v) => v.len() as u32,
+            None => TOMBSTONE,
+        };
+        let value = value.unwrap_or_default();
+
+        let mut record = Vec::with_capacity(HEADER_LEN + key.len() + value.len());
+        record.extend_from_slice(&key_len.to_le_bytes());
+        record.extend_from_slice(&value_len.to_le_bytes());
+        record.extend_from_slice(&[0; 4]); // checksum, filled in below
+        record.extend_from_slice(key);
+        record.extend_from_slice(value);
+        let hash = fnv1a_32(&record[HEADER_LEN..]);
+        record[6..10].copy_from_slice(&hash.to_le_bytes());
+
+        self.file
+            .write_all(&record)
+            .map_err(|e| format!("write {}: {e}", self.path.display()))?;
+
+        let record_len = record.len() as u64;
+        let old = if value_len == TOMBSTONE {
+            self.index.remove(key)
+        } else {
+            self.live += record_len;
+            self.index.insert(key.to_vec(), (self.len, record_len))
+        };
+        if let Some((_, old_len)) = old {
+            self.live -= old_len;
+        }
+        self.len += record_len;
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<(), String> {
+        self.file
+            .sync_data()
+            .map_err(|e| format!("sync {}: {e}", self.path.display()))
+    }
+
+    /// Bytes taken by overwritten and deleted records.
+    fn garbage(&self) -> u64 {
+        self.len - self.live
+    }
+
+    /// Rewrite the log with only its live records.
+    fn compact(&mut self) -> Result<(), String> {
+        let path = self.path.clone();
+        let err = |e: std::io::Error| format!("compact {}: {e}", path.display());
+        let tmp_path = path.with_extension("log.tmp");
+        let mut records: Vec<(u64, u64)> = self.index.values().copied().collect();
+        records.sort_unstable();
+
+        let mut out = BufWriter::new(File::create(&tmp_path).map_err(err)?);
+        for (offset, record_len) in records {
+            let record = self.read_record(offset, record_len).map_err(err)?;
+            out.write_all(&record).map_err(err)?;
+        }
+        let out = out.into_inner().map_err(|e| err(e.into_error()))?;
+        out.sync_all().map_err(err)?;
+        std::fs::rename(&tmp_path, &path).map_err(err)?;
+
+        *self = Self::open(&path).map_err(err)?;
+        Ok(())
+    }
+}
+
+// ─── Chunk records ──────────────────────────────────────────────────────────
+
+/// Serialize a chunk into one record:
+/// `[version:u8][len:u32_le][Data3D][24 × ([len:u32_le][sub-chunk])]`.
+pub(crate) fn encode_chunk(column: &ChunkColumn) -> Vec<u8> {
+    let mut buf = Vec::new();
+    buf.push(RECORD_VERSION_3D);
+    let data_3d = serialize_data_3d(&[0; 256], &column.biomes);
+    buf.extend_from_slice(&(data_3d.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&data_3d);
+    for sub_chunk in &column.sub_chunks {
+        let data = serialize_sub_chunk_disk(sub_chunk);
+        buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        buf.extend_from_slice(&data);
+    }
+    buf
+}
+
+/// Deserialize a chunk record. Undecodable sub-chunks are air unless
+/// `strict` is set, in which case they fail the whole chunk.
+pub(crate) fn decode_chunk(data: &[u8], strict: bool) -> Option<ChunkRecords> {
+    let (biomes, mut pos) = if *data.first()? == RECORD_VERSION_3D {
+        let len = u32::from_le_bytes(data.get(1..5)?.try_into().ok()?) as usize;
+        let data_3d = data.get(5..5usize.checked_add(len)?)?;
+        (deserialize_data_3d(data_3d)?, 5 + len)
+    } else {
+        (
+            biomes_from_2d(&deserialize_data_2d(data.get(1..769)?)?),
+            769,
+        )
+    };
+    let mut sub_chunks = Vec::with_capacity(OVERWORLD_SUB_CHUNK_COUNT);
+    for _ in 0..OVERWORLD_SUB_CHUNK_COUNT {
+        let len = u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as usize;
+        pos += 4;
+        let sub = deserialize_sub_chunk_disk(data.get(pos..pos.checked_add(len)?)?);
+        pos += len;
+        sub_chunks.push(match sub {
+            Some(sub) => sub,
+            None if strict => return None,
+            None => SubChunk::new_single(0), // air
+        });
+    }
+    Some((biomes, sub_chunks))
+}
+
+// ─── Region file provider ───────────────────────────────────────────────────
+
+/// Chunk storage in append-log region files, for platforms where LevelDB
+/// is a problem. The `cache_bytes` option is unused.
+pub struct RegionFileProvider {
+    dir: PathBuf,
+    options: StorageOptions,
+    /// Opened region logs by `(dim, rx, rz)`.
+    regions: HashMap<(i32, i32, i32), LogFile>,
+    raw: LogFile,
+    last_compaction: Instant,
+    /// Chunks quarantined by loads since the last [`ChunkStorageProvider::take_quarantined`].
+    quarantined: Vec<(i32, i32, i32)>,
+}
+
+impl RegionFileProvider {
+    /// Open or create region storage in the given directory with default options.
+    pub fn open(dir: &Path) -> Result<Self, String> {
+        Self::open_with(dir, &StorageOptions::default())
+    }
+
+    /// Open or create region storage in the given directory.
+    pub fn open_with(dir: &Path, options: &StorageOptions) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        let raw_path = dir.join("raw.log");
+        let raw = LogFile::open(&raw_path)
+            .map_err(|e| format!("Failed to open {}: {e}", raw_path.display()))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            options: options.clone(),
+            regions: HashMap::new(),
+            raw,
+            last_compaction: Instant::now(),
+            quarantined: Vec::new(),
+        })
+    }
+
+    /// The log of the region containing a chunk. Without `create`, a region
+    /// that has no file yet is `None`.
+    fn region(
+        &mut self,
+        cx: i32,
+        cz: i32,
+        dim: i32,
+        create: bool,
+    ) -> Result<Option<&mut LogFile>, String> {
+        let pos = (dim, cx.div_euclid(REGION_SIZE), cz.div_euclid(REGION_SIZE));
+        if !self.regions.contains_key(&pos) {
+            let path = self
+                .dir
+                .join(format!("r.{}.{}.{}.log", pos.0, pos.1, pos.2));
+            if !create && !path.exists() {
+                return Ok(None);
+            }
+            let log = LogFile::open(&path)
+                .map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+            self.regions.insert(pos, log);
+        }
+        Ok(self.regions.get_mut(&pos))
+    }
+
+    /// Read and decode a chunk record, `Ok(None)` if there is none.
+    fn read_chunk(
+        &mut self,
+        cx: i32,
+        cz: i32,
+        dim: i32,
+        verify: bool,
+    ) -> Result<Option<ChunkRecords>, String> {
+        let key = chunk_key_dim(cx, cz, dim, TAG_CHUNK_VERSION);
+        let Some(region) = self.region(cx, cz, dim, false)? else {
+            return Ok(None);
+        };
+        match region.get(&key, verify)? {
+            Some(data) => decode_chunk(&data, verify)
+                .map(Some)
+                .ok_or_else(|| "undecodable chunk".into()),
+            None => Ok(None),
+        }
+    }
+
+    /// Move a chunk's record and block entities into `raw.log` under
+    /// [`QUARANTINE_PREFIX`], keeping them for inspection while the chunk
+    /// itself is regenerated.
+    fn quarantine(&mut self, cx: i32, cz: i32, dim: i32) -> Result<(), String> {
+        let key = chunk_key_dim(cx, cz, dim, TAG_CHUNK_VERSION);
+        let data = match self.region(cx, cz, dim, false)? {
+            Some(region) => {
+                let data = region.get(&key, false)?;
+                region.delete(&key)?;
+                data
+            }
+            None => None,
+        };
+        if let Some(data) = data {
+            self.raw.put(&[QUARANTINE_PREFIX, &key].concat(), &data)?;
+        }
+        let be_key = block_entity_key_dim(cx, cz, dim);
+        if let Some(data) = self.raw.get(&be_key, false)? {
+            self.raw.delete(&be_key)?;
+            self.raw
+                .put(&[QUARANTINE_PREFIX, &be_key].concat(), &data)?;
+        }
+        Ok(())
+    }
+
+    /// Rewrite every log that has dead records, or only those where they
+    /// outweigh the live ones. Returns whether any log was rewritten.
+    fn compact(&mut self, all: bool) -> Result<bool, String> {
+        let mut compacted = false;
+        for log in self
+            .regions
+            .values_mut()
+            .chain(std::iter::once(&mut self.raw))
+        {
+            let garbage = log.garbage();
+            if garbage > 0 && (all || garbage > log.live) {
+                log.compact()?;
+                compacted = true;
+            }
+        }
+        self.last_compaction = Instant::now();
+        Ok(compacted)
+    }
+}
+
+impl ChunkStorageProvider for RegionFileProvider {
+    /// A chunk that fails verification is quarantined and reported as
+    /// missing, so the caller regenerates it.
+    fn load_chunk_dim(&mut self, cx: i32, cz: i32, dim: i32) -> Option<ChunkColumn> {
+        let (biomes, sub_chunks) = match self.read_chunk(cx, cz, dim, self.options.verify_checksums)
+        {
+            Ok(Some(records)) => records,
+            Ok(None) => return None,
+            Err(_) => {
+                // The chunk regenerates even if moving its records fails
+                let _ = self.quarantine(cx, cz, dim);
+                self.quarantined.push((cx, cz, dim));
+                return None;
+            }
+        };
+        Some(assemble_column(cx, cz, biomes, sub_chunks))
+    }
+
+    fn save_chunk_dim(&mut self, column: &ChunkColumn, dim: i32) -> Result<(), String> {
+        let key = chunk_key_dim(column.x, column.z, dim, TAG_CHUNK_VERSION);
+        let data = encode_chunk(column);
+        match self.region(column.x, column.z, dim, true)? {
+            Some(region) => region.put(&key, &data),
+            None => unreachable!("region created on demand"),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        for region in self.regions.values_mut() {
+            region.sync()?;
+        }
+        self.raw.sync()
+    }
+
+    fn get_raw(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+        self.raw
+            .get(key, self.options.verify_checksums)
+            .ok()
+            .flatten()
+    }
+
+    fn put_raw(&mut self, key: &[u8], value: &[u8]) -> Result<(), String> {
+        self.raw.put(key, value)
+    }
+
+    fn delete_raw(&mut self, key: &[u8]) -> Result<(), String> {
+        self.raw.delete(key)
+    }
+
+    fn chunk_positions(&mut self) -> Result<Vec<(i32, i32, i32)>, String> {
+        let entries = std::fs::read_dir(&self.dir)
+            .map_err(|e| format!("Failed to list {}: {e}", self.dir.display()))?;
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let Some(pos) = name
+                .to_str()
+                .and_then(|n| n.strip_prefix("r."))
+                .and_then(|n| n.strip_suffix(".log"))
+            else {
+                continue;
+            };
+            let parts: Vec<i32> = pos.split('.').filter_map(|p| p.parse().ok()).collect();
+            if let [dim, rx, rz] = parts[..] {
+                self.region(rx * REGION_SIZE, rz * REGION_SIZE, dim, false)?;
+            }
+        }
+
+        let mut chunks: Vec<(i32, i32, i32)> = self
+            .regions
+            .values()
+            .flat_map(|region| region.index.keys())
+            .filter_map(|key| parse_chunk_version_key(key))
+            .collect();
+        chunks.sort_unstable();
+        Ok(chunks)
+    }
+
+    fn take_quarantined(&mut self) -> Vec<(i32, i32, i32)> {
+        std::mem::take(&mut self.quarantined)
+    }
+
+    /// Also rewrites every log with dead records afterwards. Checksums are
+    /// verified even if disabled for loads.
+    fn repair(&mut self) -> Result<RepairReport, String> {
+        let chunks = self.chunk_positions()?;
+        let mut report = RepairReport::default();
+        for (cx, cz, dim) in chunks {
+            report.checked += 1;
+            if self.read_chunk(cx, cz, dim, true).is_err() {
+                self.quarantine(cx, cz, dim)?;
+                report.quarantined.push((cx, cz, dim));
+            }
+        }
+        self.compact(true)?;
+        self.flush()?;
+        Ok(report)
+    }
+
+    /// Once the compaction interval has passed, rewrites the logs that are
+    /// mostly dead records.
+    fn maybe_compact(&mut self) -> Result<bool, String> {
+        let interval = self.options.compaction_interval;
+        if interval.is_zero() || self.last_compaction.elapsed() < interval {
+            return Ok(false);
+        }
+        self.compact(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{
+        actor_digest_key_dim, actor_key, convert_storage, map_key, serialize_actor_digest,
+        LevelDbProvider, MAP_INDEX_KEY,
+    };
+    use std::time::Duration;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mc_rs_region_{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Block at the origin of a sub-chunk, without keeping a whole column
+    /// on the test's stack frame.
+    fn block_at(
+        storage: &mut dyn ChunkStorageProvider,
+        cx: i32,
+        cz: i32,
+        dim: i32,
+        sub: usize,
+    ) -> u32 {
+        let column = storage.load_chunk_dim(cx, cz, dim).expect("chunk");
+        column.sub_chunks[sub].get_block(0, 0, 0)
+    }
+
+    fn save_with_block(
+        storage: &mut dyn ChunkStorageProvider,
+        cx: i32,
+        cz: i32,
+        dim: i32,
+        block: u32,
+    ) {
+        let mut column = ChunkColumn::new_air(cx, cz, 10);
+        column.set_biome(0, 100, 0, 3);
+        column.sub_chunks[4].set_block(0, 0, 0, block);
+        storage.save_chunk_dim(&column, dim).unwrap();
+    }
+
+    #[test]
+    fn save_load_roundtrip() {
+        let dir = temp_dir();
+        let mut provider = RegionFileProvider::open(&dir).unwrap();
+        assert!(provider.load_chunk(0, 0).is_none());
+
+        save_with_block(&mut provider, 5, -3, 0, 42);
+        save_with_block(&mut provider, -40, 70, 1, 43);
+        assert_eq!(block_at(&mut provider, 5, -3, 0, 4), 42);
+        assert_eq!(block_at(&mut provider, -40, 70, 1, 4), 43);
+        assert!(provider.load_chunk_dim(5, -3, 1).is_none());
+
+        let loaded = provider.load_chunk(5, -3).unwrap();
+        assert_eq!(loaded.biome_at(0, 100, 0), Some(3));
+        assert_eq!(loaded.biome_at(0, 0, 0), Some(0));
+        assert!(!loaded.dirty);
+
+        assert!(dir.join("r.0.0.-1.log").exists());
+        assert!(dir.join("r.1.-2.2.log").exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn reopen_keeps_latest_records() {
+        let dir = temp_dir();
+        {
+            let mut provider = RegionFileProvider::open(&dir).unwrap();
+            save_with_block(&mut provider, 1, 1, 0, 1);
+            save_with_block(&mut provider, 1, 1, 0, 2);
+            provider.put_raw(b"key", b"old").unwrap();
+            provider.put_raw(b"key", b"new").unwrap();
+            provider.flush().unwrap();
+        }
+        let mut provider = RegionFileProvider::open(&dir).unwrap();
+        assert_eq!(block_at(&mut provider, 1, 1, 0, 4), 2);
+        assert_eq!(provider.get_raw(b"key").unwrap(), b"new");
+        assert_eq!(provider.chunk_positions().unwrap(), vec![(1, 1, 0)]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn truncated_tail_is_dropped() {
+        let dir = temp_dir();
+        let path = dir.join("test.log");
+        {
+            let mut log = LogFile::open(&path).unwrap();
+            log.put(b"a", b"first").unwrap();
+            log.put(b"b", b"second").unwrap();
+        }
+        let full_len = std::fs::metadata(&path).unwrap().len();
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(full_len - 3).unwrap();
+
+        let mut log = LogFile::open(&path).unwrap();
+        assert_eq!(log.get(b"a", true).unwrap().unwrap(), b"first");
+        assert!(log.get(b"b", true).unwrap().is_none());
+        // Writes continue after the last complete record
+        log.put(b"c", b"third").unwrap();
+        let mut log = LogFile::open(&path).unwrap();
+        assert_eq!(log.get(b"c", true).unwrap().unwrap(), b"third");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn corrupt_chunk_is_quarantined() {
+        let dir = temp_dir();
+        {
+            let mut provider = RegionFileProvider::open(&dir).unwrap();
+            save_with_block(&mut provider, 0, 0, 0, 42);
+            provider.flush().unwrap();
+        }
+        // Flip a byte in the middle of the chunk record
+        let path = dir.join("r.0.0.0.log");
+        let mut data = std::fs::read(&path).unwrap();
+        let mid = data.len() / 2;
+        data[mid] ^= 0xFF;
+        std::fs::write(&path, data).unwrap();
+
+        let mut provider = RegionFileProvider::open(&dir).unwrap();
+        assert!(provider.load_chunk(0, 0).is_none());
+        assert_eq!(provider.take_quarantined(), vec![(0, 0, 0)]);
+        let key = chunk_key_dim(0, 0, 0, TAG_CHUNK_VERSION);
+        assert!(provider
+            .get_raw(&[QUARANTINE_PREFIX, &key].concat())
+            .is_some());
+        assert!(provider.chunk_positions().unwrap().is_empty());
+
+        // The slot is free for the regenerated chunk
+        save_with_block(&mut provider, 0, 0, 0, 7);
+        assert_eq!(block_at(&mut provider, 0, 0, 0, 4), 7);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn repair_and_compaction() {
+        let dir = temp_dir();
+        let options = StorageOptions {
+            compaction_interval: Duration::from_millis(1),
+            ..StorageOptions::default()
+        };
+        let mut provider = RegionFileProvider::open_with(&dir, &options).unwrap();
+        for block in 1..=4 {
+            save_with_block(&mut provider, 2, 2, 0, block);
+        }
+        let path = dir.join("r.0.0.0.log");
+        let before = std::fs::metadata(&path).unwrap().len();
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(provider.maybe_compact().unwrap());
+        let after = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(after, before / 4);
+        assert_eq!(block_at(&mut provider, 2, 2, 0, 4), 4);
+
+        // Nothing dead left
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(!provider.maybe_compact().unwrap());
+
+        let report = provider.repair().unwrap();
+        assert_eq!(report.checked, 1);
+        assert!(report.quarantined.is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn convert_between_backends() {
+        let dir = temp_dir();
+        let mut leveldb = LevelDbProvider::open(&dir.join("db")).unwrap();
+        save_with_block(&mut leveldb, 3, 4, 0, 11);
+        save_with_block(&mut leveldb, -1, 2, 2, 12);
+        let be_key = block_entity_key_dim(3, 4, 0);
+        leveldb.put_raw(&be_key, b"block entities").unwrap();
+        let digest_key = actor_digest_key_dim(-1, 2, 2);
+        leveldb
+            .put_raw(&digest_key, &serialize_actor_digest(&[42]))
+            .unwrap();
+        leveldb.put_raw(&actor_key(42), b"actor").unwrap();
+        leveldb
+            .put_raw(MAP_INDEX_KEY, &serialize_actor_digest(&[7]))
+            .unwrap();
+        leveldb.put_raw(&map_key(7), b"map").unwrap();
+
+        let mut region = RegionFileProvider::open(&dir.join("region")).unwrap();
+        assert_eq!(convert_storage(&mut leveldb, &mut region).unwrap(), 2);
+        assert_eq!(block_at(&mut region, 3, 4, 0, 4), 11);
+        assert_eq!(block_at(&mut region, -1, 2, 2, 4), 12);
+        assert_eq!(region.get_raw(&be_key).unwrap(), b"block entities");
+        assert_eq!(region.get_raw(&actor_key(42)).unwrap(), b"actor");
+
+        // And back again
+        let mut back = LevelDbProvider::open(&dir.join("db2")).unwrap();
+        assert_eq!(convert_storage(&mut region, &mut back).unwrap(), 2);
+        assert_eq!(block_at(&mut back, -1, 2, 2, 4), 12);
+        assert_eq!(back.get_raw(&be_key).unwrap(), b"block entities");
+        assert_eq!(
+            back.get_raw(&digest_key).unwrap(),
+            region.get_raw(&digest_key).unwrap()
+        );
+        assert_eq!(back.get_raw(&actor_key(42)).unwrap(), b"actor");
+        assert_eq!(back.get_raw(&map_key(7)).unwrap(), b"map");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn decodes_records_with_2d_biomes() {
+        let record = encode_chunk(&ChunkColumn::new_air(0, 0, 10));
+        let data_3d_len = u32::from_le_bytes(record[1..5].try_into().unwrap()) as usize;
+        // Heightmap and biomes, then the same sub-chunks
+        let mut legacy = vec![CHUNK_VERSION];
+        legacy.extend([0; 512]);
+        legacy.extend([4; 256]); // forest
+        legacy.extend_from_slice(&record[5 + data_3d_len..]);
+
+        let (biomes, sub_chunks) = decode_chunk(&legacy, true).unwrap();
+        assert_eq!(biomes, [[4; 64]; OVERWORLD_SUB_CHUNK_COUNT]);
+        assert_eq!(sub_chunks.len(), OVERWORLD_SUB_CHUNK_COUNT);
+    }
+}
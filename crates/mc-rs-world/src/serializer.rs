@@ -2,7 +2,9 @@
 
 use bytes::{BufMut, BytesMut};
 
-use crate::chunk::{ChunkColumn, SubChunk, OVERWORLD_SUB_CHUNK_COUNT};
+use crate::chunk::{
+    biome_storage, ChunkColumn, SubChunk, SubChunkBiomes, OVERWORLD_SUB_CHUNK_COUNT,
+};
 
 /// Serialize a full chunk column to the LevelChunk packet payload.
 ///
@@ -17,7 +19,7 @@ pub fn serialize_chunk_column(column: &ChunkColumn) -> (u32, Vec<u8>) {
         serialize_sub_chunk(&mut buf, sub_chunk, y_index);
     }
 
-    // Biome data from the chunk's biome array
+    // Biome data, one storage per sub-chunk
     serialize_biome_data(&mut buf, &column.biomes);
 
     // Border blocks: empty (not Education Edition)
@@ -39,12 +41,20 @@ pub fn serialize_chunk_column_cached(column: &mut ChunkColumn) -> (u32, Vec<u8>)
     result
 }
 
-/// Serialize a single sub-chunk to network format (Version 9).
+/// Serialize a single sub-chunk to network format (Version 9), with a
+/// second layer for the water of waterlogged blocks.
 fn serialize_sub_chunk(buf: &mut BytesMut, sub_chunk: &SubChunk, y_index: i8) {
     buf.put_u8(9); // version
-    buf.put_u8(1); // num_layers (no waterlogging)
+    buf.put_u8(1 + sub_chunk.liquid.is_some() as u8); // num_layers
     buf.put_u8(y_index as u8); // y_index (i8 -> u8 for two's complement)
+    serialize_layer(buf, sub_chunk);
+    if let Some(liquid) = &sub_chunk.liquid {
+        serialize_layer(buf, liquid);
+    }
+}
 
+/// Serialize one block storage layer of a sub-chunk.
+fn serialize_layer(buf: &mut BytesMut, sub_chunk: &SubChunk) {
     let palette_size = sub_chunk.palette.len();
 
     if palette_size <= 1 {
@@ -102,95 +112,28 @@ fn bits_per_block_for_palette(palette_size: usize) -> u8 {
     }
 }
 
-/// Serialize biome data from the chunk's 2D biome array.
-///
-/// Each of 24 sections encodes biomes at 4x4x4 resolution (64 entries).
-/// Since our biome data is 2D, all 4 Y levels in a section share the same biome.
-/// All 24 sections are identical, so we compute the section data once and repeat it.
-fn serialize_biome_data(buf: &mut BytesMut, biomes: &[u8; 256]) {
-    // Build a 4x4 biome grid (downsampled from 16x16 to 4x4 by taking every 4th column).
-    // Indexed as [section_x * 4 + section_z] where section_x/z are in 0..4.
-    let mut biome_4x4 = [0u8; 16];
-    for sx in 0..4 {
-        for sz in 0..4 {
-            // Sample the center of each 4-block span
-            let bx = sx * 4 + 2;
-            let bz = sz * 4 + 2;
-            biome_4x4[sx * 4 + sz] = biomes[bx * 16 + bz];
-        }
-    }
-
-    // Check if all 16 entries are the same biome (common case)
-    let all_same = biome_4x4.iter().all(|&b| b == biome_4x4[0]);
-
-    // Build section data once (all 24 sections are identical since biomes are 2D)
-    let mut section_buf = BytesMut::new();
-
-    if all_same {
-        // Single-biome section: header = (0 << 1) | 1 = 0x01 (0 bits, runtime flag)
-        section_buf.put_u8(0x01);
-        // NO count for bits=0, just the single palette value
-        write_signed_varint32(&mut section_buf, biome_4x4[0] as i32);
-    } else {
-        // Multi-biome section: palette-based encoding for 64 entries.
-        // Build palette using O(1) lookup array instead of Vec::contains().
-        let mut biome_to_palette = [0xFFu8; 256];
-        let mut palette: Vec<u8> = Vec::new();
-        for &b in &biome_4x4 {
-            if biome_to_palette[b as usize] == 0xFF {
-                biome_to_palette[b as usize] = palette.len() as u8;
-                palette.push(b);
-            }
+/// Serialize the biomes of each sub-chunk as a paletted storage at block
+/// resolution. A sub-chunk with the same biomes as the one below it is
+/// written as the single byte 0xFF, which copies the previous storage.
+fn serialize_biome_data(buf: &mut BytesMut, biomes: &[SubChunkBiomes]) {
+    for (index, cells) in biomes.iter().enumerate() {
+        if index > 0 && biomes[index - 1] == *cells {
+            buf.put_u8(0xFF);
+            continue;
         }
-
-        let bpe = bits_per_entry_for_biome_palette(palette.len());
+        let (palette, bits, words) = biome_storage(cells);
         // Biome storage header: (bpe << 1) | 1 — runtime flag for network encoding
-        section_buf.put_u8((bpe << 1) | 1);
-
-        // Pack 64 entries into u32 words (4x4x4, all 4 Y levels have same biome as XZ)
-        let entries_per_word = 32 / bpe as usize;
-        let word_count = 64_usize.div_ceil(entries_per_word);
-
-        for word_idx in 0..word_count {
-            let mut word: u32 = 0;
-            for slot in 0..entries_per_word {
-                let entry_idx = word_idx * entries_per_word + slot;
-                if entry_idx < 64 {
-                    let sx = entry_idx % 4;
-                    let sz = (entry_idx / 4) % 4;
-                    let biome_id = biome_4x4[sx * 4 + sz];
-                    let palette_idx = biome_to_palette[biome_id as usize] as u32;
-                    word |= palette_idx << (bpe as u32 * slot as u32);
-                }
-            }
-            section_buf.put_u32_le(word);
+        buf.put_u8((bits << 1) | 1);
+        for word in words {
+            buf.put_u32_le(word);
         }
-
-        // Palette
-        write_signed_varint32(&mut section_buf, palette.len() as i32);
-        for &biome_id in &palette {
-            write_signed_varint32(&mut section_buf, biome_id as i32);
+        // NO count for bits=0, just the single palette value
+        if bits > 0 {
+            write_signed_varint32(buf, palette.len() as i32);
+        }
+        for biome_id in palette {
+            write_signed_varint32(buf, biome_id as i32);
         }
-    }
-
-    // Write the same section data 24 times
-    let section_bytes = section_buf.freeze();
-    for _ in 0..OVERWORLD_SUB_CHUNK_COUNT {
-        buf.extend_from_slice(&section_bytes);
-    }
-}
-
-/// Determine minimum bits-per-entry for a biome palette.
-/// Valid values: 1, 2, 3, 4, 5, 6.
-fn bits_per_entry_for_biome_palette(palette_size: usize) -> u8 {
-    match palette_size {
-        0..=1 => 0,
-        2 => 1,
-        3..=4 => 2,
-        5..=8 => 3,
-        9..=16 => 4,
-        17..=32 => 5,
-        _ => 6,
     }
 }
 
@@ -245,6 +188,18 @@ mod tests {
         assert_eq!(buf.len(), 5, "total bytes for single-block sub-chunk");
     }
 
+    #[test]
+    fn liquid_layer_is_a_second_storage() {
+        let mut sub = SubChunk::new_single(10);
+        sub.set_liquid(0, 0, 0, 50, 10);
+        let mut buf = BytesMut::new();
+        serialize_sub_chunk(&mut buf, &sub, 0);
+        assert_eq!(buf[1], 2, "num_layers");
+        // Layer 0 is a single block, then layer 1 holds air and water
+        assert_eq!(buf[3], 0x01);
+        assert_eq!(buf[5], 0x03, "layer 1 storage_header (bpb=1, runtime=1)");
+    }
+
     #[test]
     fn mixed_subchunk_has_block_data() {
         let mut sub = SubChunk::new_single(10); // air
@@ -305,62 +260,50 @@ mod tests {
 
     #[test]
     fn single_biome_section_encoding() {
-        let biomes = [1u8; 256]; // All plains
+        let biomes = [[1u8; 64]; 24]; // All plains
         let mut buf = BytesMut::new();
         serialize_biome_data(&mut buf, &biomes);
-        // 24 sections, each: 0x01 (header: bpe=0, runtime=1) + zigzag(1) = 0x02
-        assert_eq!(buf.len(), 24 * 2);
-        for i in 0..24 {
-            assert_eq!(buf[i * 2], 0x01, "section {i} header (bpe=0, runtime=1)");
-            assert_eq!(buf[i * 2 + 1], 0x02, "section {i} biome plains=zigzag(1)=2");
-        }
+        // First section: 0x01 (header: bpe=0, runtime=1) + zigzag(1) = 0x02,
+        // then 23 copies of it
+        assert_eq!(buf.len(), 2 + 23);
+        assert_eq!(buf[0], 0x01, "header (bpe=0, runtime=1)");
+        assert_eq!(buf[1], 0x02, "biome plains=zigzag(1)=2");
+        assert!(buf[2..].iter().all(|&b| b == 0xFF));
     }
 
     #[test]
     fn multi_biome_section_encoding() {
-        let mut biomes = [1u8; 256]; // Plains
-                                     // Set some columns to desert (2)
-        for z in 0..16 {
-            for x in 8..16 {
-                biomes[x * 16 + z] = 2;
-            }
-        }
+        let mut biomes = [[1u8; 64]; 24]; // Plains
+        biomes[0][63] = 2; // a desert cell in the lowest sub-chunk
         let mut buf = BytesMut::new();
         serialize_biome_data(&mut buf, &biomes);
-        // Should have palette-based encoding (not single-biome)
-        // First section header should NOT be 0x01 (single-biome) since we have 2 biomes
-        // With bpe=1: header = (1 << 1) | 1 = 3
+        // With bpe=1: header = (1 << 1) | 1 = 3, then 128 words and the
+        // palette (count 2, plains, desert)
         assert_eq!(buf[0], 3, "multi-biome header (bpe=1, runtime=1)");
-        assert!(buf.len() > 24 * 2, "multi-biome data should be larger");
+        let section = 1 + 128 * 4 + 3;
+        assert_eq!(&buf[section - 3..section], [4, 2, 4]);
+        // The last word ends with x = 15, z = 15, y = 8..15: the top four
+        // are in the desert cell
+        assert_eq!(buf[section - 4], 0xF0);
+        // The next sub-chunk differs, the ones above it are copies
+        assert_eq!(&buf[section..section + 2], [0x01, 0x02]);
+        assert_eq!(buf.len(), section + 2 + 22);
     }
 
     #[test]
-    fn multi_biome_all_sections_identical() {
-        // All 24 sections should be byte-identical since biomes are 2D
-        let mut biomes = [1u8; 256];
-        for z in 0..16 {
-            for x in 8..16 {
-                biomes[x * 16 + z] = 2;
-            }
+    fn biome_sections_follow_height() {
+        // Biomes changing with height are written per sub-chunk
+        let mut biomes = [[1u8; 64]; 24];
+        for sub in &mut biomes[12..] {
+            *sub = [5; 64];
         }
         let mut buf = BytesMut::new();
         serialize_biome_data(&mut buf, &biomes);
-        // Find section size (total / 24)
-        assert_eq!(buf.len() % 24, 0, "biome data should be 24 equal sections");
-        let section_size = buf.len() / 24;
-        let first_section = &buf[..section_size];
-        for i in 1..24 {
-            let section = &buf[i * section_size..(i + 1) * section_size];
-            assert_eq!(first_section, section, "section {i} should match section 0");
-        }
-    }
-
-    #[test]
-    fn biome_palette_bits() {
-        assert_eq!(bits_per_entry_for_biome_palette(1), 0);
-        assert_eq!(bits_per_entry_for_biome_palette(2), 1);
-        assert_eq!(bits_per_entry_for_biome_palette(4), 2);
-        assert_eq!(bits_per_entry_for_biome_palette(8), 3);
+        let mut expected = vec![0x01, 0x02];
+        expected.extend([0xFF; 11]);
+        expected.extend([0x01, 0x0A]);
+        expected.extend([0xFF; 11]);
+        assert_eq!(buf.to_vec(), expected);
     }
 
     #[test]
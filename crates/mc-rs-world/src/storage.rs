@@ -5,20 +5,30 @@
 //! is deferred to Phase 5.5.
 
 use std::path::Path;
+use std::time::{Duration, Instant};
 
-use rusty_leveldb::DB;
+use rusty_leveldb::{LdbIterator, DB};
 
-use crate::chunk::{ChunkColumn, SubChunk, OVERWORLD_SUB_CHUNK_COUNT};
+use crate::block_hash::fnv1a_32;
+use crate::chunk::{
+    biome_cells, biome_storage, biomes_from_2d, ChunkColumn, SubChunk, SubChunkBiomes,
+    OVERWORLD_SUB_CHUNK_COUNT,
+};
 
 // ─── LevelDB key tags ───────────────────────────────────────────────────────
 
-const TAG_CHUNK_VERSION: u8 = 0x2C;
+pub(crate) const TAG_CHUNK_VERSION: u8 = 0x2C;
+const TAG_DATA_3D: u8 = 0x2B;
 const TAG_DATA_2D: u8 = 0x2D;
 const TAG_SUB_CHUNK_PREFIX: u8 = 0x2F;
 const TAG_FINALIZED_STATE: u8 = 0x36;
+const TAG_CHECKSUMS: u8 = 0x3B;
+
+/// Prefix for the copies of chunk records that failed verification.
+pub(crate) const QUARANTINE_PREFIX: &[u8] = b"quarantine:";
 
 /// Current chunk format version.
-const CHUNK_VERSION: u8 = 40;
+pub(crate) const CHUNK_VERSION: u8 = 40;
 
 // ─── Key builders ───────────────────────────────────────────────────────────
 
@@ -32,7 +42,7 @@ fn chunk_key(cx: i32, cz: i32, tag: u8) -> Vec<u8> {
 /// Overworld (dim=0): `[X:i32_le][Z:i32_le][tag]`
 /// Nether (dim=1): `[X:i32_le][Z:i32_le][01 00 00 00][tag]`
 /// End (dim=2): `[X:i32_le][Z:i32_le][02 00 00 00][tag]`
-fn chunk_key_dim(cx: i32, cz: i32, dim: i32, tag: u8) -> Vec<u8> {
+pub(crate) fn chunk_key_dim(cx: i32, cz: i32, dim: i32, tag: u8) -> Vec<u8> {
     let cap = if dim == 0 { 9 } else { 13 };
     let mut key = Vec::with_capacity(cap);
     key.extend_from_slice(&cx.to_le_bytes());
@@ -84,13 +94,22 @@ fn bits_per_block_for_palette(palette_size: usize) -> u8 {
 
 /// Serialize a sub-chunk to disk format.
 ///
-/// Format: `[version=9][num_layers=1][palette_header][block_data...][palette_size:i32_le][palette:u32_le[]]`
+/// Format: `[version=9][num_layers][per layer: palette_header, block_data..., palette_size:i32_le, palette:u32_le[]]`,
+/// with a second layer for the water of waterlogged blocks.
 /// Palette header bit 0 = 0 (persistence mode).
-fn serialize_sub_chunk_disk(sub: &SubChunk) -> Vec<u8> {
+pub(crate) fn serialize_sub_chunk_disk(sub: &SubChunk) -> Vec<u8> {
     let mut buf = Vec::new();
     buf.push(9); // version
-    buf.push(1); // num_layers
+    buf.push(1 + sub.liquid.is_some() as u8); // num_layers
+    write_layer_disk(&mut buf, sub);
+    if let Some(liquid) = &sub.liquid {
+        write_layer_disk(&mut buf, liquid);
+    }
+    buf
+}
 
+/// Write one layer of a sub-chunk in disk format.
+fn write_layer_disk(buf: &mut Vec<u8>, sub: &SubChunk) {
     let palette_size = sub.palette.len();
 
     if palette_size <= 1 {
@@ -128,12 +147,10 @@ fn serialize_sub_chunk_disk(sub: &SubChunk) -> Vec<u8> {
             buf.extend_from_slice(&runtime_id.to_le_bytes());
         }
     }
-
-    buf
 }
 
 /// Deserialize a sub-chunk from disk format.
-fn deserialize_sub_chunk_disk(data: &[u8]) -> Option<SubChunk> {
+pub(crate) fn deserialize_sub_chunk_disk(data: &[u8]) -> Option<SubChunk> {
     if data.len() < 3 {
         return None;
     }
@@ -143,11 +160,21 @@ fn deserialize_sub_chunk_disk(data: &[u8]) -> Option<SubChunk> {
         return None;
     }
 
-    let _num_layers = data[1];
-    let palette_header = data[2];
-    let bpb = palette_header >> 1;
+    let num_layers = data[1];
+    let mut pos = 2;
+    let mut sub = read_layer_disk(data, &mut pos)?;
+    if num_layers > 1 {
+        sub.liquid = Some(Box::new(read_layer_disk(data, &mut pos)?));
+    }
+    Some(sub)
+}
 
-    let mut pos = 3;
+/// Read one layer of a sub-chunk in disk format starting at `cursor`, and
+/// move `cursor` past it.
+fn read_layer_disk(data: &[u8], cursor: &mut usize) -> Option<SubChunk> {
+    let palette_header = *data.get(*cursor)?;
+    let bpb = palette_header >> 1;
+    let mut pos = *cursor + 1;
 
     if bpb == 0 {
         // Single-block sub-chunk
@@ -159,6 +186,7 @@ fn deserialize_sub_chunk_disk(data: &[u8]) -> Option<SubChunk> {
         pos += 4;
 
         if palette_size == 0 {
+            *cursor = pos;
             return Some(SubChunk::new_single(0));
         }
 
@@ -167,6 +195,7 @@ fn deserialize_sub_chunk_disk(data: &[u8]) -> Option<SubChunk> {
         }
         let runtime_id =
             u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+        *cursor = pos + 4;
         Some(SubChunk::new_single(runtime_id))
     } else {
         // Multi-block sub-chunk
@@ -222,24 +251,17 @@ fn deserialize_sub_chunk_disk(data: &[u8]) -> Option<SubChunk> {
             palette.push(runtime_id);
         }
 
-        Some(SubChunk { blocks, palette })
-    }
-}
-
-/// Serialize Data2D: heightmap (i16_le[256]) + biomes (u8[256]).
-fn serialize_data_2d(biomes: &[u8; 256]) -> Vec<u8> {
-    let mut buf = Vec::with_capacity(768); // 512 + 256
-                                           // Heightmap: all zeros (we don't track heightmap separately)
-    for _ in 0..256 {
-        buf.extend_from_slice(&0i16.to_le_bytes());
+        *cursor = pos + palette_size * 4;
+        Some(SubChunk {
+            blocks,
+            palette,
+            liquid: None,
+        })
     }
-    // Biomes
-    buf.extend_from_slice(biomes);
-    buf
 }
 
 /// Deserialize Data2D: skip heightmap, read biomes.
-fn deserialize_data_2d(data: &[u8]) -> Option<[u8; 256]> {
+pub(crate) fn deserialize_data_2d(data: &[u8]) -> Option<[u8; 256]> {
     // 256 * 2 (heightmap) + 256 (biomes) = 768 bytes
     if data.len() < 768 {
         return None;
@@ -249,80 +271,473 @@ fn deserialize_data_2d(data: &[u8]) -> Option<[u8; 256]> {
     Some(biomes)
 }
 
+/// Serialize Data3D: heightmap (i16_le[256]), then the biomes of each
+/// sub-chunk from the bottom as a paletted storage: `[bpe << 1]`, the
+/// words, `[palette_len:i32_le]` and the biome IDs as i32_le. A single
+/// biome has no words or length, and 0xFF copies the storage below.
+pub(crate) fn serialize_data_3d(
+    heightmap: &[i16; 256],
+    biomes: &[SubChunkBiomes; OVERWORLD_SUB_CHUNK_COUNT],
+) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(512 + OVERWORLD_SUB_CHUNK_COUNT * 5);
+    for h in heightmap {
+        buf.extend_from_slice(&h.to_le_bytes());
+    }
+    for (index, cells) in biomes.iter().enumerate() {
+        if index > 0 && biomes[index - 1] == *cells {
+            buf.push(0xFF);
+            continue;
+        }
+        let (palette, bits, words) = biome_storage(cells);
+        buf.push(bits << 1);
+        for word in words {
+            buf.extend_from_slice(&word.to_le_bytes());
+        }
+        if bits > 0 {
+            buf.extend_from_slice(&(palette.len() as i32).to_le_bytes());
+        }
+        for id in palette {
+            buf.extend_from_slice(&(id as i32).to_le_bytes());
+        }
+    }
+    buf
+}
+
+/// Deserialize Data3D: skip heightmap, read the biomes of each sub-chunk.
+/// Sub-chunks past the last storage keep the biomes of the one below.
+pub(crate) fn deserialize_data_3d(
+    data: &[u8],
+) -> Option<[SubChunkBiomes; OVERWORLD_SUB_CHUNK_COUNT]> {
+    let read_i32 = |pos: usize| -> Option<i32> {
+        Some(i32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+    };
+    let mut biomes = [[0u8; 64]; OVERWORLD_SUB_CHUNK_COUNT];
+    let mut pos = 512;
+    for index in 0..OVERWORLD_SUB_CHUNK_COUNT {
+        let previous = biomes[index.saturating_sub(1)];
+        let header = match data.get(pos) {
+            Some(&header) => header,
+            None if index > 0 => {
+                biomes[index] = previous;
+                continue;
+            }
+            None => return None,
+        };
+        pos += 1;
+        if header == 0xFF {
+            biomes[index] = previous;
+            continue;
+        }
+        let bits = header >> 1;
+        if !matches!(bits, 0..=6 | 8 | 16) {
+            return None;
+        }
+        let mut words = Vec::new();
+        if bits > 0 {
+            let per_word = 32 / bits as usize;
+            for _ in 0..4096_usize.div_ceil(per_word) {
+                words.push(read_i32(pos)? as u32);
+                pos += 4;
+            }
+        }
+        let palette_len = if bits > 0 {
+            let len = read_i32(pos)?;
+            pos += 4;
+            len.clamp(0, 4096) as usize
+        } else {
+            1
+        };
+        let mut palette = Vec::with_capacity(palette_len);
+        for _ in 0..palette_len {
+            palette.push(read_i32(pos)? as u8);
+            pos += 4;
+        }
+        biomes[index] = biome_cells(&palette, bits, &words)?;
+    }
+    Some(biomes)
+}
+
+// ─── Checksums ──────────────────────────────────────────────────────────────
+
+/// A checksum entry: record tag, sub-chunk index (0 for other records) and
+/// the FNV-1a hash of the record value.
+type ChecksumEntry = (u8, i8, u32);
+
+/// Serialize checksums: `[count:u32_le]` then `[tag:u8][y_index:u8][fnv1a:u32_le]` per record.
+fn serialize_checksums(entries: &[ChecksumEntry]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(4 + entries.len() * 6);
+    buf.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+    for &(tag, y_index, hash) in entries {
+        buf.push(tag);
+        buf.push(y_index as u8);
+        buf.extend_from_slice(&hash.to_le_bytes());
+    }
+    buf
+}
+
+/// Deserialize checksums, rejecting a count that doesn't match the length.
+fn deserialize_checksums(data: &[u8]) -> Option<Vec<ChecksumEntry>> {
+    let count = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?) as usize;
+    let body = &data[4..];
+    if body.len() != count * 6 {
+        return None;
+    }
+    Some(
+        body.chunks_exact(6)
+            .map(|e| {
+                (
+                    e[0],
+                    e[1] as i8,
+                    u32::from_le_bytes([e[2], e[3], e[4], e[5]]),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Check a record against its checksum entry. Records without an entry
+/// (or chunks saved before checksums were written) always pass.
+fn verify_record(
+    checksums: Option<&[ChecksumEntry]>,
+    tag: u8,
+    y_index: i8,
+    data: Option<&[u8]>,
+) -> Result<(), String> {
+    let Some(&(_, _, expected)) =
+        checksums.and_then(|c| c.iter().find(|e| e.0 == tag && e.1 == y_index))
+    else {
+        return Ok(());
+    };
+    match data {
+        Some(data) if fnv1a_32(data) == expected => Ok(()),
+        Some(_) => Err(format!("checksum mismatch in record {tag:#04x}/{y_index}")),
+        None => Err(format!("missing record {tag:#04x}/{y_index}")),
+    }
+}
+
+/// Parse a chunk version key back into `(cx, cz, dim)`.
+pub(crate) fn parse_chunk_version_key(key: &[u8]) -> Option<(i32, i32, i32)> {
+    let coord = |i: usize| i32::from_le_bytes([key[i], key[i + 1], key[i + 2], key[i + 3]]);
+    match key.len() {
+        9 if key[8] == TAG_CHUNK_VERSION => Some((coord(0), coord(4), 0)),
+        13 if key[12] == TAG_CHUNK_VERSION && matches!(coord(8), 1 | 2) => {
+            Some((coord(0), coord(4), coord(8)))
+        }
+        _ => None,
+    }
+}
+
+/// A chunk's decoded biomes and sub-chunks.
+pub(crate) type ChunkRecords = ([SubChunkBiomes; OVERWORLD_SUB_CHUNK_COUNT], Vec<SubChunk>);
+
+/// Build a loaded (clean) column from its decoded records.
+pub(crate) fn assemble_column(
+    cx: i32,
+    cz: i32,
+    biomes: [SubChunkBiomes; OVERWORLD_SUB_CHUNK_COUNT],
+    sub_chunks: Vec<SubChunk>,
+) -> ChunkColumn {
+    let sub_chunks: [SubChunk; OVERWORLD_SUB_CHUNK_COUNT] = sub_chunks
+        .try_into()
+        .unwrap_or_else(|_| panic!("expected {OVERWORLD_SUB_CHUNK_COUNT} sub-chunks"));
+
+    ChunkColumn {
+        x: cx,
+        z: cz,
+        sub_chunks,
+        biomes,
+        dirty: false,
+        cached_payload: None,
+    }
+}
+
 // ─── LevelDB provider ──────────────────────────────────────────────────────
 
+/// Cache, compaction and integrity settings for [`LevelDbProvider`].
+#[derive(Debug, Clone)]
+pub struct StorageOptions {
+    /// Size of the LevelDB block read cache in bytes.
+    pub cache_bytes: usize,
+    /// Minimum time between compactions, see [`LevelDbProvider::maybe_compact`].
+    /// Zero disables automatic compaction.
+    pub compaction_interval: Duration,
+    /// Verify chunk checksums on load and quarantine chunks that fail.
+    pub verify_checksums: bool,
+}
+
+impl Default for StorageOptions {
+    fn default() -> Self {
+        Self {
+            cache_bytes: 8 * 1024 * 1024,
+            compaction_interval: Duration::from_secs(600),
+            verify_checksums: true,
+        }
+    }
+}
+
+/// Outcome of [`LevelDbProvider::repair`].
+#[derive(Debug, Default)]
+pub struct RepairReport {
+    /// Number of chunks verified.
+    pub checked: usize,
+    /// Chunks moved to quarantine, as `(cx, cz, dim)`.
+    pub quarantined: Vec<(i32, i32, i32)>,
+}
+
+/// A chunk persistence backend, selected by `backend` in `[storage]`.
+///
+/// Raw records use the LevelDB key layout (see [`block_entity_key_dim`])
+/// whatever the backend, so they carry over when converting.
+pub trait ChunkStorageProvider: Send {
+    /// Load a chunk for a specific dimension. Returns `None` if the chunk
+    /// doesn't exist or failed verification (see [`Self::take_quarantined`]).
+    fn load_chunk_dim(&mut self, cx: i32, cz: i32, dim: i32) -> Option<ChunkColumn>;
+
+    /// Save a chunk for a specific dimension.
+    fn save_chunk_dim(&mut self, column: &ChunkColumn, dim: i32) -> Result<(), String>;
+
+    /// Flush pending writes to disk.
+    fn flush(&mut self) -> Result<(), String>;
+
+    /// Raw get (for block entity data, etc.).
+    fn get_raw(&mut self, key: &[u8]) -> Option<Vec<u8>>;
+
+    /// Raw put (for block entity data, etc.).
+    fn put_raw(&mut self, key: &[u8], value: &[u8]) -> Result<(), String>;
+
+    /// Raw delete. Deleting a missing key is not an error.
+    fn delete_raw(&mut self, key: &[u8]) -> Result<(), String>;
+
+    /// Every stored chunk, as `(cx, cz, dim)`.
+    fn chunk_positions(&mut self) -> Result<Vec<(i32, i32, i32)>, String>;
+
+    /// Chunks quarantined while loading since the last call.
+    fn take_quarantined(&mut self) -> Vec<(i32, i32, i32)>;
+
+    /// Verify every stored chunk and quarantine the ones that fail.
+    fn repair(&mut self) -> Result<RepairReport, String>;
+
+    /// Run periodic maintenance such as compaction if it's due. Returns
+    /// whether it ran.
+    fn maybe_compact(&mut self) -> Result<bool, String>;
+
+    /// Load a chunk (overworld).
+    fn load_chunk(&mut self, cx: i32, cz: i32) -> Option<ChunkColumn> {
+        self.load_chunk_dim(cx, cz, 0)
+    }
+
+    /// Save a chunk (overworld).
+    fn save_chunk(&mut self, column: &ChunkColumn) -> Result<(), String> {
+        self.save_chunk_dim(column, 0)
+    }
+}
+
+/// Copy every readable chunk with its block entities and actors, and every
+/// saved map, from one backend to another. Returns the number of chunks
+/// copied; chunks `from` can't load are skipped.
+pub fn convert_storage(
+    from: &mut dyn ChunkStorageProvider,
+    to: &mut dyn ChunkStorageProvider,
+) -> Result<usize, String> {
+    let mut count = 0;
+    for (cx, cz, dim) in from.chunk_positions()? {
+        let Some(column) = from.load_chunk_dim(cx, cz, dim) else {
+            continue;
+        };
+        to.save_chunk_dim(&column, dim)?;
+        let key = block_entity_key_dim(cx, cz, dim);
+        if let Some(data) = from.get_raw(&key) {
+            to.put_raw(&key, &data)?;
+        }
+        let digest_key = actor_digest_key_dim(cx, cz, dim);
+        if let Some(digest) = from.get_raw(&digest_key) {
+            for id in deserialize_actor_digest(&digest) {
+                let key = actor_key(id);
+                if let Some(data) = from.get_raw(&key) {
+                    to.put_raw(&key, &data)?;
+                }
+            }
+            to.put_raw(&digest_key, &digest)?;
+        }
+        count += 1;
+    }
+    if let Some(index) = from.get_raw(MAP_INDEX_KEY) {
+        for id in deserialize_actor_digest(&index) {
+            let key = map_key(id);
+            if let Some(data) = from.get_raw(&key) {
+                to.put_raw(&key, &data)?;
+            }
+        }
+        to.put_raw(MAP_INDEX_KEY, &index)?;
+    }
+    if let Some(structures) = from.get_raw(STRUCTURES_KEY) {
+        to.put_raw(STRUCTURES_KEY, &structures)?;
+    }
+    to.flush()?;
+    Ok(count)
+}
+
 /// Wraps a `rusty_leveldb::DB` for chunk persistence.
 pub struct LevelDbProvider {
     db: DB,
+    options: StorageOptions,
+    last_compaction: Instant,
+    writes_since_compaction: usize,
+    /// Chunks quarantined by loads since the last [`Self::take_quarantined`].
+    quarantined: Vec<(i32, i32, i32)>,
 }
 
 impl LevelDbProvider {
-    /// Open or create a LevelDB database at the given path.
+    /// Open or create a LevelDB database at the given path with default options.
     pub fn open(path: &Path) -> Result<Self, String> {
+        Self::open_with(path, &StorageOptions::default())
+    }
+
+    /// Open or create a LevelDB database at the given path.
+    pub fn open_with(path: &Path, options: &StorageOptions) -> Result<Self, String> {
         let opts = rusty_leveldb::Options {
             create_if_missing: true,
+            block_cache_capacity_bytes: options.cache_bytes,
             ..rusty_leveldb::Options::default()
         };
 
         let db = DB::open(path, opts).map_err(|e| format!("Failed to open LevelDB: {e}"))?;
-        Ok(Self { db })
+        Ok(Self {
+            db,
+            options: options.clone(),
+            last_compaction: Instant::now(),
+            writes_since_compaction: 0,
+            quarantined: Vec::new(),
+        })
     }
 
-    /// Load a chunk from LevelDB (overworld). Returns `None` if the chunk doesn't exist.
-    pub fn load_chunk(&mut self, cx: i32, cz: i32) -> Option<ChunkColumn> {
-        self.load_chunk_dim(cx, cz, 0)
+    /// Read a chunk's biomes and sub-chunks. Without verification, undecodable
+    /// records fall back to defaults; with it, they (and checksum mismatches)
+    /// are errors.
+    fn read_chunk(
+        &mut self,
+        cx: i32,
+        cz: i32,
+        dim: i32,
+        verify: bool,
+    ) -> Result<ChunkRecords, String> {
+        let checksums = if verify {
+            match self.db.get(&chunk_key_dim(cx, cz, dim, TAG_CHECKSUMS)) {
+                Some(data) => Some(deserialize_checksums(&data).ok_or("unreadable checksums")?),
+                None => None,
+            }
+        } else {
+            None
+        };
+        let checksums = checksums.as_deref();
+
+        // Load biomes from Data3D, or Data2D for chunks saved before it
+        let data_3d = self.db.get(&chunk_key_dim(cx, cz, dim, TAG_DATA_3D));
+        verify_record(checksums, TAG_DATA_3D, 0, data_3d.as_deref())?;
+        let biomes = match data_3d.map(|data| deserialize_data_3d(&data)) {
+            Some(Some(biomes)) => biomes,
+            Some(None) if verify => return Err("undecodable Data3D".into()),
+            Some(None) => [[0u8; 64]; OVERWORLD_SUB_CHUNK_COUNT],
+            None => {
+                let data_2d = self.db.get(&chunk_key_dim(cx, cz, dim, TAG_DATA_2D));
+                verify_record(checksums, TAG_DATA_2D, 0, data_2d.as_deref())?;
+                match data_2d.map(|data| deserialize_data_2d(&data)) {
+                    Some(Some(biomes)) => biomes_from_2d(&biomes),
+                    Some(None) if verify => return Err("undecodable Data2D".into()),
+                    _ => [[0u8; 64]; OVERWORLD_SUB_CHUNK_COUNT],
+                }
+            }
+        };
+
+        // Load 24 sub-chunks
+        let mut sub_chunks = Vec::with_capacity(OVERWORLD_SUB_CHUNK_COUNT);
+        for i in 0..OVERWORLD_SUB_CHUNK_COUNT {
+            let y_index = i as i8 - 4; // 0 -> -4, 23 -> 19
+            let data = self.db.get(&sub_chunk_key_dim(cx, cz, dim, y_index));
+            verify_record(checksums, TAG_SUB_CHUNK_PREFIX, y_index, data.as_deref())?;
+            sub_chunks.push(match data.map(|data| deserialize_sub_chunk_disk(&data)) {
+                Some(Some(sub)) => sub,
+                Some(None) if verify => {
+                    return Err(format!("undecodable sub-chunk {y_index}"));
+                }
+                _ => SubChunk::new_single(0), // air
+            });
+        }
+
+        Ok((biomes, sub_chunks))
     }
 
-    /// Load a chunk from LevelDB for a specific dimension.
-    pub fn load_chunk_dim(&mut self, cx: i32, cz: i32, dim: i32) -> Option<ChunkColumn> {
+    /// Move every record of a chunk under [`QUARANTINE_PREFIX`], keeping
+    /// the data for inspection while the chunk itself is regenerated.
+    fn quarantine(&mut self, cx: i32, cz: i32, dim: i32) -> Result<(), String> {
+        let mut keys: Vec<Vec<u8>> = [
+            TAG_CHUNK_VERSION,
+            TAG_DATA_3D,
+            TAG_DATA_2D,
+            TAG_FINALIZED_STATE,
+            TAG_CHECKSUMS,
+            TAG_BLOCK_ENTITY,
+        ]
+        .iter()
+        .map(|&tag| chunk_key_dim(cx, cz, dim, tag))
+        .collect();
+        keys.extend(
+            (0..OVERWORLD_SUB_CHUNK_COUNT).map(|i| sub_chunk_key_dim(cx, cz, dim, i as i8 - 4)),
+        );
+
+        for key in keys {
+            if let Some(value) = self.db.get(&key) {
+                let mut quarantine_key = QUARANTINE_PREFIX.to_vec();
+                quarantine_key.extend_from_slice(&key);
+                self.db
+                    .put(&quarantine_key, &value)
+                    .map_err(|e| format!("put quarantine: {e}"))?;
+                self.db
+                    .delete(&key)
+                    .map_err(|e| format!("delete quarantined: {e}"))?;
+            }
+        }
+        self.writes_since_compaction += 1;
+        Ok(())
+    }
+
+    /// Compact the whole key range.
+    pub fn compact(&mut self) -> Result<(), String> {
+        self.db
+            .compact_range(&[], &[0xFF; 32])
+            .map_err(|e| format!("compact: {e}"))?;
+        self.last_compaction = Instant::now();
+        self.writes_since_compaction = 0;
+        Ok(())
+    }
+}
+
+impl ChunkStorageProvider for LevelDbProvider {
+    /// A chunk that fails verification is quarantined and reported as
+    /// missing, so the caller regenerates it.
+    fn load_chunk_dim(&mut self, cx: i32, cz: i32, dim: i32) -> Option<ChunkColumn> {
         // Check if chunk version key exists
         let version_key = chunk_key_dim(cx, cz, dim, TAG_CHUNK_VERSION);
         self.db.get(&version_key)?;
 
-        // Load biomes from Data2D
-        let data_2d_key = chunk_key_dim(cx, cz, dim, TAG_DATA_2D);
-        let biomes = if let Some(data) = self.db.get(&data_2d_key) {
-            deserialize_data_2d(&data).unwrap_or([0u8; 256])
-        } else {
-            [0u8; 256]
+        let (biomes, sub_chunks) = match self.read_chunk(cx, cz, dim, self.options.verify_checksums)
+        {
+            Ok(records) => records,
+            Err(_) => {
+                // The chunk regenerates even if moving its records fails
+                let _ = self.quarantine(cx, cz, dim);
+                self.quarantined.push((cx, cz, dim));
+                return None;
+            }
         };
 
-        // Load 24 sub-chunks
-        let sub_chunks: Vec<SubChunk> = (0..OVERWORLD_SUB_CHUNK_COUNT)
-            .map(|i| {
-                let y_index = i as i8 - 4; // 0 -> -4, 23 -> 19
-                let key = sub_chunk_key_dim(cx, cz, dim, y_index);
-                if let Some(data) = self.db.get(&key) {
-                    deserialize_sub_chunk_disk(&data).unwrap_or_else(|| SubChunk::new_single(0))
-                } else {
-                    SubChunk::new_single(0) // air
-                }
-            })
-            .collect();
-
-        let sub_chunks: [SubChunk; OVERWORLD_SUB_CHUNK_COUNT] = sub_chunks
-            .try_into()
-            .unwrap_or_else(|_| panic!("expected {OVERWORLD_SUB_CHUNK_COUNT} sub-chunks"));
-
-        Some(ChunkColumn {
-            x: cx,
-            z: cz,
-            sub_chunks,
-            biomes,
-            dirty: false,
-            cached_payload: None,
-        })
-    }
-
-    /// Save a chunk to LevelDB (overworld).
-    pub fn save_chunk(&mut self, column: &ChunkColumn) -> Result<(), String> {
-        self.save_chunk_dim(column, 0)
+        Some(assemble_column(cx, cz, biomes, sub_chunks))
     }
 
-    /// Save a chunk to LevelDB for a specific dimension.
-    pub fn save_chunk_dim(&mut self, column: &ChunkColumn, dim: i32) -> Result<(), String> {
+    fn save_chunk_dim(&mut self, column: &ChunkColumn, dim: i32) -> Result<(), String> {
         let cx = column.x;
         let cz = column.z;
+        let mut checksums = Vec::with_capacity(1 + OVERWORLD_SUB_CHUNK_COUNT);
 
         // Write chunk version
         let version_key = chunk_key_dim(cx, cz, dim, TAG_CHUNK_VERSION);
@@ -330,46 +745,107 @@ impl LevelDbProvider {
             .put(&version_key, &[CHUNK_VERSION])
             .map_err(|e| format!("put version: {e}"))?;
 
-        // Write Data2D (heightmap + biomes)
-        let data_2d_key = chunk_key_dim(cx, cz, dim, TAG_DATA_2D);
-        let data_2d = serialize_data_2d(&column.biomes);
+        // Write Data3D (heightmap + biomes), dropping the Data2D it replaces
+        let data_3d_key = chunk_key_dim(cx, cz, dim, TAG_DATA_3D);
+        let data_3d = serialize_data_3d(&[0; 256], &column.biomes);
+        checksums.push((TAG_DATA_3D, 0, fnv1a_32(&data_3d)));
         self.db
-            .put(&data_2d_key, &data_2d)
-            .map_err(|e| format!("put data2d: {e}"))?;
+            .put(&data_3d_key, &data_3d)
+            .map_err(|e| format!("put data3d: {e}"))?;
+        self.db
+            .delete(&chunk_key_dim(cx, cz, dim, TAG_DATA_2D))
+            .map_err(|e| format!("delete data2d: {e}"))?;
 
         // Write 24 sub-chunks
         for (i, sub_chunk) in column.sub_chunks.iter().enumerate() {
             let y_index = i as i8 - 4;
             let key = sub_chunk_key_dim(cx, cz, dim, y_index);
             let data = serialize_sub_chunk_disk(sub_chunk);
+            checksums.push((TAG_SUB_CHUNK_PREFIX, y_index, fnv1a_32(&data)));
             self.db
                 .put(&key, &data)
                 .map_err(|e| format!("put sub-chunk {y_index}: {e}"))?;
         }
 
+        // Write checksums of the records above
+        let checksums_key = chunk_key_dim(cx, cz, dim, TAG_CHECKSUMS);
+        self.db
+            .put(&checksums_key, &serialize_checksums(&checksums))
+            .map_err(|e| format!("put checksums: {e}"))?;
+
         // Write finalized state = 2 (done)
         let finalized_key = chunk_key_dim(cx, cz, dim, TAG_FINALIZED_STATE);
         self.db
             .put(&finalized_key, &2i32.to_le_bytes())
             .map_err(|e| format!("put finalized: {e}"))?;
 
+        self.writes_since_compaction += 1;
         Ok(())
     }
 
-    /// Flush pending writes to disk.
-    pub fn flush(&mut self) -> Result<(), String> {
+    fn flush(&mut self) -> Result<(), String> {
         self.db.flush().map_err(|e| format!("flush: {e}"))
     }
 
-    /// Raw get from LevelDB (for block entity data, etc.).
-    pub fn get_raw(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+    fn get_raw(&mut self, key: &[u8]) -> Option<Vec<u8>> {
         self.db.get(key)
     }
 
-    /// Raw put to LevelDB (for block entity data, etc.).
-    pub fn put_raw(&mut self, key: &[u8], value: &[u8]) -> Result<(), String> {
+    fn put_raw(&mut self, key: &[u8], value: &[u8]) -> Result<(), String> {
+        self.writes_since_compaction += 1;
         self.db.put(key, value).map_err(|e| format!("put_raw: {e}"))
     }
+
+    fn delete_raw(&mut self, key: &[u8]) -> Result<(), String> {
+        self.writes_since_compaction += 1;
+        self.db.delete(key).map_err(|e| format!("delete_raw: {e}"))
+    }
+
+    fn chunk_positions(&mut self) -> Result<Vec<(i32, i32, i32)>, String> {
+        let mut chunks = Vec::new();
+        let mut iter = self.db.new_iter().map_err(|e| format!("iterate: {e}"))?;
+        while let Some((key, _)) = iter.next() {
+            chunks.extend(parse_chunk_version_key(&key));
+        }
+        Ok(chunks)
+    }
+
+    fn take_quarantined(&mut self) -> Vec<(i32, i32, i32)> {
+        std::mem::take(&mut self.quarantined)
+    }
+
+    /// Also flushes and compacts afterwards. Checksums are verified even if disabled for loads.
+    fn repair(&mut self) -> Result<RepairReport, String> {
+        let chunks = self.chunk_positions()?;
+        let mut report = RepairReport::default();
+        for (cx, cz, dim) in chunks {
+            report.checked += 1;
+            if self.read_chunk(cx, cz, dim, true).is_err() {
+                self.quarantine(cx, cz, dim)?;
+                report.quarantined.push((cx, cz, dim));
+            }
+        }
+        self.flush()?;
+        self.compact()?;
+        Ok(report)
+    }
+
+    /// Compacts the database if the compaction interval has passed and
+    /// there were writes since the last compaction.
+    ///
+    /// `DB` can't be shared between threads, so compaction runs on the
+    /// thread that owns the provider; call this periodically.
+    fn maybe_compact(&mut self) -> Result<bool, String> {
+        let interval = self.options.compaction_interval;
+        if interval.is_zero()
+            || self.writes_since_compaction == 0
+            || self.last_compaction.elapsed() < interval
+        {
+            return Ok(false);
+        }
+        self.compact()?;
+        Ok(true)
+    }
 }
 
 /// Block entity LevelDB tag.
@@ -385,6 +861,62 @@ pub fn block_entity_key_dim(cx: i32, cz: i32, dim: i32) -> Vec<u8> {
     chunk_key_dim(cx, cz, dim, TAG_BLOCK_ENTITY)
 }
 
+// ─── Actors ─────────────────────────────────────────────────────────────────
+
+const ACTOR_PREFIX: &[u8] = b"actorprefix";
+const DIGEST_PREFIX: &[u8] = b"digp";
+
+/// Build the key of one saved actor: `actorprefix[unique_id:i64_le]`.
+pub fn actor_key(unique_id: i64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(ACTOR_PREFIX.len() + 8);
+    key.extend_from_slice(ACTOR_PREFIX);
+    key.extend_from_slice(&unique_id.to_le_bytes());
+    key
+}
+
+/// Build the key of a chunk's actor digest: `digp[X:i32_le][Z:i32_le]`,
+/// followed by the dimension outside the overworld.
+pub fn actor_digest_key_dim(cx: i32, cz: i32, dim: i32) -> Vec<u8> {
+    let mut key = DIGEST_PREFIX.to_vec();
+    key.extend_from_slice(&cx.to_le_bytes());
+    key.extend_from_slice(&cz.to_le_bytes());
+    if dim != 0 {
+        key.extend_from_slice(&dim.to_le_bytes());
+    }
+    key
+}
+
+/// Serialize an actor digest: the unique IDs of the actors in a chunk.
+pub fn serialize_actor_digest(ids: &[i64]) -> Vec<u8> {
+    ids.iter().flat_map(|id| id.to_le_bytes()).collect()
+}
+
+/// Parse an actor digest. A trailing partial ID is ignored.
+pub fn deserialize_actor_digest(data: &[u8]) -> Vec<i64> {
+    data.chunks_exact(8)
+        .map(|b| i64::from_le_bytes(b.try_into().unwrap()))
+        .collect()
+}
+
+// ─── Maps ───────────────────────────────────────────────────────────────────
+
+const MAP_PREFIX: &[u8] = b"map_";
+
+/// Key listing the IDs of every saved map, encoded like an actor digest.
+pub const MAP_INDEX_KEY: &[u8] = b"mc-rs:map_index";
+
+/// Build the key of one saved map: `map_<id>`, the ID written in decimal.
+pub fn map_key(id: i64) -> Vec<u8> {
+    let mut key = MAP_PREFIX.to_vec();
+    key.extend_from_slice(id.to_string().as_bytes());
+    key
+}
+
+// ─── Structures ─────────────────────────────────────────────────────────────
+
+/// Key of the structure registry (see [`crate::structure::StructureRegistry`]).
+pub const STRUCTURES_KEY: &[u8] = b"mc-rs:structures";
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -453,6 +985,27 @@ mod tests {
         assert_eq!(key[9], 3); // y_index
     }
 
+    #[test]
+    fn actor_keys_and_digest() {
+        assert_eq!(&actor_key(1)[..], b"actorprefix\x01\0\0\0\0\0\0\0");
+        assert_eq!(actor_digest_key_dim(1, -1, 0).len(), 12);
+        let nether = actor_digest_key_dim(1, -1, 1);
+        assert_eq!(&nether[..4], b"digp");
+        assert_eq!(&nether[12..], &1i32.to_le_bytes());
+
+        let ids = [5, -7, i64::MAX];
+        let digest = serialize_actor_digest(&ids);
+        assert_eq!(digest.len(), 24);
+        assert_eq!(deserialize_actor_digest(&digest), ids);
+        assert_eq!(deserialize_actor_digest(&digest[..20]), &ids[..2]);
+    }
+
+    #[test]
+    fn map_keys() {
+        assert_eq!(&map_key(42)[..], b"map_42");
+        assert_eq!(&map_key(-4294967295)[..], b"map_-4294967295");
+    }
+
     #[test]
     fn sub_chunk_key_negative_y() {
         let key = sub_chunk_key(0, 0, -4);
@@ -468,6 +1021,19 @@ mod tests {
 
     // ─── Disk serialization tests ───────────────────────────────────────
 
+    #[test]
+    fn roundtrip_liquid_layer() {
+        let mut sub = SubChunk::new_single(1);
+        sub.set_block(3, 4, 5, 2);
+        sub.set_liquid(3, 4, 5, 7, 1);
+        let data = serialize_sub_chunk_disk(&sub);
+        assert_eq!(data[1], 2, "num_layers");
+        let restored = deserialize_sub_chunk_disk(&data).unwrap();
+        assert_eq!(restored.get_block(3, 4, 5), 2);
+        assert_eq!(restored.get_liquid(3, 4, 5), Some(7));
+        assert_eq!(restored.get_liquid(0, 0, 0), Some(1));
+    }
+
     #[test]
     fn roundtrip_single_block_subchunk() {
         let sub = SubChunk::new_single(42);
@@ -513,19 +1079,43 @@ mod tests {
     }
 
     #[test]
-    fn roundtrip_data_2d() {
+    fn read_data_2d() {
         let mut biomes = [0u8; 256];
         biomes[0] = 1; // plains
         biomes[100] = 2; // desert
         biomes[255] = 12; // ice plains
 
-        let data = serialize_data_2d(&biomes);
-        assert_eq!(data.len(), 768);
+        // An all-zero heightmap, then the biomes
+        let mut data = vec![0u8; 512];
+        data.extend_from_slice(&biomes);
 
         let restored = deserialize_data_2d(&data).unwrap();
         assert_eq!(restored, biomes);
     }
 
+    #[test]
+    fn roundtrip_data_3d() {
+        let mut biomes = [[1u8; 64]; OVERWORLD_SUB_CHUNK_COUNT];
+        biomes[3][10] = 2; // desert
+        biomes[20] = [12; 64]; // ice plains
+        let mut heightmap = [0i16; 256];
+        heightmap[7] = 70;
+
+        let data = serialize_data_3d(&heightmap, &biomes);
+        assert_eq!(&data[14..16], 70i16.to_le_bytes());
+        assert_eq!(deserialize_data_3d(&data), Some(biomes));
+
+        // Storages left out copy the last one
+        let short = serialize_data_3d(&heightmap, &[[5; 64]; OVERWORLD_SUB_CHUNK_COUNT]);
+        assert_eq!(short.len(), 512 + 5 + 23);
+        assert_eq!(
+            deserialize_data_3d(&short[..517]),
+            Some([[5; 64]; OVERWORLD_SUB_CHUNK_COUNT])
+        );
+        assert!(deserialize_data_3d(&data[..600]).is_none());
+        assert!(deserialize_data_3d(&data[..512]).is_none());
+    }
+
     #[test]
     fn data_2d_too_short() {
         let data = vec![0u8; 100];
@@ -544,6 +1134,31 @@ mod tests {
         assert!(deserialize_sub_chunk_disk(&data).is_none());
     }
 
+    #[test]
+    fn roundtrip_checksums() {
+        let entries = vec![(TAG_DATA_2D, 0, 0xDEAD_BEEF), (TAG_SUB_CHUNK_PREFIX, -4, 7)];
+        let data = serialize_checksums(&entries);
+        assert_eq!(data.len(), 4 + 2 * 6);
+        assert_eq!(deserialize_checksums(&data).unwrap(), entries);
+        // Count doesn't match the entries
+        assert!(deserialize_checksums(&data[..data.len() - 1]).is_none());
+        assert!(deserialize_checksums(&[1, 0]).is_none());
+    }
+
+    #[test]
+    fn parse_version_keys() {
+        assert_eq!(
+            parse_chunk_version_key(&chunk_key_dim(3, -2, 0, TAG_CHUNK_VERSION)),
+            Some((3, -2, 0))
+        );
+        assert_eq!(
+            parse_chunk_version_key(&chunk_key_dim(3, -2, 2, TAG_CHUNK_VERSION)),
+            Some((3, -2, 2))
+        );
+        assert!(parse_chunk_version_key(&chunk_key_dim(3, -2, 0, TAG_DATA_2D)).is_none());
+        assert!(parse_chunk_version_key(b"~local_player").is_none());
+    }
+
     // ─── LevelDB integration tests ─────────────────────────────────────
 
     fn temp_db_path() -> PathBuf {
@@ -574,8 +1189,8 @@ mod tests {
         let mut provider = LevelDbProvider::open(&path).unwrap();
 
         let mut column = ChunkColumn::new_air(5, -3, 10);
-        column.biomes[0] = 1;
-        column.biomes[100] = 2;
+        column.set_biome(0, 0, 0, 1);
+        column.set_biome(6, 200, 4, 2);
         column.sub_chunks[4].set_block(0, 0, 0, 42);
         column.dirty = true;
 
@@ -585,14 +1200,40 @@ mod tests {
         let loaded = provider.load_chunk(5, -3).unwrap();
         assert_eq!(loaded.x, 5);
         assert_eq!(loaded.z, -3);
-        assert_eq!(loaded.biomes[0], 1);
-        assert_eq!(loaded.biomes[100], 2);
+        assert_eq!(loaded.biomes, column.biomes);
+        assert_eq!(loaded.biome_at(6, 200, 4), Some(2));
         assert_eq!(loaded.sub_chunks[4].get_block(0, 0, 0), 42);
         assert!(!loaded.dirty, "loaded chunks should not be dirty");
 
         std::fs::remove_dir_all(&path).ok();
     }
 
+    #[test]
+    fn loads_chunks_saved_with_2d_biomes() {
+        let path = temp_db_path();
+        let mut provider = LevelDbProvider::open(&path).unwrap();
+        provider
+            .save_chunk(&ChunkColumn::new_air(1, 2, 10))
+            .unwrap();
+
+        // As saved before Data3D (and checksums)
+        let mut data_2d = vec![0u8; 512];
+        data_2d.extend([27u8; 256]); // birch forest
+        provider.delete_raw(&chunk_key(1, 2, TAG_DATA_3D)).unwrap();
+        provider
+            .delete_raw(&chunk_key(1, 2, TAG_CHECKSUMS))
+            .unwrap();
+        provider
+            .put_raw(&chunk_key(1, 2, TAG_DATA_2D), &data_2d)
+            .unwrap();
+
+        let loaded = provider.load_chunk(1, 2).unwrap();
+        assert_eq!(loaded.biome_at(3, -64, 9), Some(27));
+        assert_eq!(loaded.biome_at(15, 319, 0), Some(27));
+
+        std::fs::remove_dir_all(&path).ok();
+    }
+
     #[test]
     fn multiple_chunks() {
         let path = temp_db_path();
@@ -600,7 +1241,7 @@ mod tests {
 
         for i in 0..3 {
             let mut col = ChunkColumn::new_air(i, i * 2, 10);
-            col.biomes[0] = i as u8;
+            col.set_biome_column(0, 0, i as u8);
             provider.save_chunk(&col).unwrap();
         }
         provider.flush().unwrap();
@@ -609,7 +1250,7 @@ mod tests {
             let loaded = provider.load_chunk(i, i * 2).unwrap();
             assert_eq!(loaded.x, i);
             assert_eq!(loaded.z, i * 2);
-            assert_eq!(loaded.biomes[0], i as u8);
+            assert_eq!(loaded.biome_at(0, 64, 0), Some(i as u8));
         }
 
         // Non-existent chunk
@@ -624,7 +1265,7 @@ mod tests {
         let mut provider = LevelDbProvider::open(&path).unwrap();
 
         let mut column = ChunkColumn::new_air(3, 7, 10);
-        column.biomes[0] = 8; // nether wastes
+        column.set_biome_column(0, 0, 8); // nether wastes
         column.sub_chunks[5].set_block(1, 1, 1, 99);
         column.dirty = true;
 
@@ -638,7 +1279,7 @@ mod tests {
         let loaded = provider.load_chunk_dim(3, 7, 1).unwrap();
         assert_eq!(loaded.x, 3);
         assert_eq!(loaded.z, 7);
-        assert_eq!(loaded.biomes[0], 8);
+        assert_eq!(loaded.biome_at(0, 0, 0), Some(8));
         assert_eq!(loaded.sub_chunks[5].get_block(1, 1, 1), 99);
 
         std::fs::remove_dir_all(&path).ok();
@@ -666,4 +1307,148 @@ mod tests {
 
         std::fs::remove_dir_all(&path).ok();
     }
+
+    /// Save a chunk at (1, 1) and overwrite one of its sub-chunks with
+    /// different, still decodable data.
+    fn save_and_corrupt(provider: &mut LevelDbProvider) {
+        let mut column = ChunkColumn::new_air(1, 1, 10);
+        column.sub_chunks[4].set_block(0, 0, 0, 42);
+        provider.save_chunk(&column).unwrap();
+        let bad = serialize_sub_chunk_disk(&SubChunk::new_single(7));
+        provider.put_raw(&sub_chunk_key(1, 1, 0), &bad).unwrap();
+        provider.flush().unwrap();
+    }
+
+    /// Whether a chunk loads, without keeping a whole column on this stack frame.
+    fn loads(provider: &mut LevelDbProvider, cx: i32, cz: i32, dim: i32) -> bool {
+        provider.load_chunk_dim(cx, cz, dim).is_some()
+    }
+
+    #[test]
+    fn checksum_mismatch_quarantines_chunk() {
+        let path = temp_db_path();
+        let mut provider = LevelDbProvider::open(&path).unwrap();
+        save_and_corrupt(&mut provider);
+
+        assert!(provider.load_chunk(1, 1).is_none());
+        assert_eq!(provider.take_quarantined(), vec![(1, 1, 0)]);
+        assert!(provider.take_quarantined().is_empty());
+
+        // Records moved under the quarantine prefix
+        let version_key = chunk_key(1, 1, TAG_CHUNK_VERSION);
+        assert!(provider.get_raw(&version_key).is_none());
+        let mut quarantined = QUARANTINE_PREFIX.to_vec();
+        quarantined.extend_from_slice(&version_key);
+        assert!(provider.get_raw(&quarantined).is_some());
+
+        std::fs::remove_dir_all(&path).ok();
+    }
+
+    #[test]
+    fn undecodable_record_quarantines_chunk() {
+        let path = temp_db_path();
+        let mut provider = LevelDbProvider::open(&path).unwrap();
+        provider
+            .save_chunk(&ChunkColumn::new_air(0, 0, 10))
+            .unwrap();
+        // Corrupt without checksums, like a world from an older version
+        provider.db.delete(&chunk_key(0, 0, TAG_CHECKSUMS)).unwrap();
+        provider.put_raw(&sub_chunk_key(0, 0, 2), &[9, 1]).unwrap();
+
+        assert!(provider.load_chunk(0, 0).is_none());
+        assert_eq!(provider.take_quarantined(), vec![(0, 0, 0)]);
+
+        std::fs::remove_dir_all(&path).ok();
+    }
+
+    #[test]
+    fn chunk_without_checksums_loads() {
+        let path = temp_db_path();
+        let mut provider = LevelDbProvider::open(&path).unwrap();
+        let mut column = ChunkColumn::new_air(2, 2, 10);
+        column.sub_chunks[4].set_block(0, 0, 0, 42);
+        provider.save_chunk(&column).unwrap();
+        provider.db.delete(&chunk_key(2, 2, TAG_CHECKSUMS)).unwrap();
+
+        let loaded = provider.load_chunk(2, 2).unwrap();
+        assert_eq!(loaded.sub_chunks[4].get_block(0, 0, 0), 42);
+
+        std::fs::remove_dir_all(&path).ok();
+    }
+
+    #[test]
+    fn verification_disabled_loads_corrupt_chunk() {
+        let path = temp_db_path();
+        let options = StorageOptions {
+            verify_checksums: false,
+            ..StorageOptions::default()
+        };
+        let mut provider = LevelDbProvider::open_with(&path, &options).unwrap();
+        save_and_corrupt(&mut provider);
+
+        let loaded = provider.load_chunk(1, 1).unwrap();
+        assert_eq!(loaded.sub_chunks[4].get_block(0, 0, 0), 7);
+        assert!(provider.take_quarantined().is_empty());
+
+        std::fs::remove_dir_all(&path).ok();
+    }
+
+    #[test]
+    fn repair_quarantines_only_corrupt_chunks() {
+        let path = temp_db_path();
+        let mut provider = LevelDbProvider::open(&path).unwrap();
+        save_and_corrupt(&mut provider);
+        let column = ChunkColumn::new_air(5, 5, 10);
+        provider.save_chunk(&column).unwrap();
+        provider.save_chunk_dim(&column, 1).unwrap();
+
+        let report = provider.repair().unwrap();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.quarantined, vec![(1, 1, 0)]);
+        assert!(!loads(&mut provider, 1, 1, 0));
+        assert!(loads(&mut provider, 5, 5, 0));
+        assert!(loads(&mut provider, 5, 5, 1));
+
+        // Nothing left to repair
+        let report = provider.repair().unwrap();
+        assert_eq!(report.checked, 2);
+        assert!(report.quarantined.is_empty());
+
+        std::fs::remove_dir_all(&path).ok();
+    }
+
+    #[test]
+    fn maybe_compact_respects_interval() {
+        let path = temp_db_path();
+        let options = StorageOptions {
+            compaction_interval: Duration::from_millis(1),
+            ..StorageOptions::default()
+        };
+        let mut provider = LevelDbProvider::open_with(&path, &options).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        // No writes yet
+        assert!(!provider.maybe_compact().unwrap());
+
+        provider
+            .save_chunk(&ChunkColumn::new_air(0, 0, 10))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(provider.maybe_compact().unwrap());
+        assert!(!provider.maybe_compact().unwrap());
+        assert!(provider.load_chunk(0, 0).is_some());
+
+        // Zero interval never compacts
+        let options = StorageOptions {
+            compaction_interval: Duration::ZERO,
+            ..StorageOptions::default()
+        };
+        drop(provider);
+        let mut provider = LevelDbProvider::open_with(&path, &options).unwrap();
+        provider
+            .save_chunk(&ChunkColumn::new_air(1, 0, 10))
+            .unwrap();
+        assert!(!provider.maybe_compact().unwrap());
+
+        std::fs::remove_dir_all(&path).ok();
+    }
 }
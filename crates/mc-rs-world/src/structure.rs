@@ -0,0 +1,247 @@
+//! Generated structures: the bounding box of each one the world generators
+//! place, kept in a registry saved to the world database so `/locate
+//! structure` can find them again.
+
+/// Kinds of structures the generators place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StructureKind {
+    Dungeon,
+    Village,
+    PillagerOutpost,
+}
+
+impl StructureKind {
+    /// Every kind, in ID order.
+    pub const ALL: [StructureKind; 3] = [
+        StructureKind::Dungeon,
+        StructureKind::Village,
+        StructureKind::PillagerOutpost,
+    ];
+
+    /// Name used by commands.
+    pub fn name(self) -> &'static str {
+        match self {
+            StructureKind::Dungeon => "dungeon",
+            StructureKind::Village => "village",
+            StructureKind::PillagerOutpost => "pillager_outpost",
+        }
+    }
+
+    /// Look up a kind by its command name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    fn id(self) -> u8 {
+        self as u8
+    }
+
+    fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+}
+
+/// The blocks a structure takes up, corners included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructureBounds {
+    pub kind: StructureKind,
+    pub min: (i32, i32, i32),
+    pub max: (i32, i32, i32),
+}
+
+impl StructureBounds {
+    /// Bounds of a single block.
+    pub fn at(kind: StructureKind, pos: (i32, i32, i32)) -> Self {
+        Self {
+            kind,
+            min: pos,
+            max: pos,
+        }
+    }
+
+    /// Grow the bounds to take in the box from `min` to `max`.
+    pub fn include(&mut self, min: (i32, i32, i32), max: (i32, i32, i32)) {
+        self.min = (
+            self.min.0.min(min.0),
+            self.min.1.min(min.1),
+            self.min.2.min(min.2),
+        );
+        self.max = (
+            self.max.0.max(max.0),
+            self.max.1.max(max.1),
+            self.max.2.max(max.2),
+        );
+    }
+
+    /// Block column at the middle of the bounds.
+    pub fn center(&self) -> (i32, i32) {
+        (
+            (self.min.0 + self.max.0).div_euclid(2),
+            (self.min.2 + self.max.2).div_euclid(2),
+        )
+    }
+
+    /// Whether a block is inside the bounds.
+    pub fn contains(&self, (x, y, z): (i32, i32, i32)) -> bool {
+        (self.min.0..=self.max.0).contains(&x)
+            && (self.min.1..=self.max.1).contains(&y)
+            && (self.min.2..=self.max.2).contains(&z)
+    }
+}
+
+/// Size of one registry entry: dimension, kind and both corners.
+const ENTRY_SIZE: usize = 4 + 1 + 6 * 4;
+
+/// The structures generated so far in every dimension.
+#[derive(Debug, Clone, Default)]
+pub struct StructureRegistry {
+    entries: Vec<(i32, StructureBounds)>,
+    /// Changed since it was last saved.
+    pub dirty: bool,
+}
+
+impl StructureRegistry {
+    /// Record structures generated in a dimension. The generators are
+    /// deterministic, so a chunk generated again (because it was never
+    /// saved) doesn't add its structures twice.
+    pub fn add(&mut self, dim: i32, structures: &[StructureBounds]) {
+        for &bounds in structures {
+            if !self.entries.contains(&(dim, bounds)) {
+                self.entries.push((dim, bounds));
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Number of structures recorded.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no structure was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The structure of a kind in a dimension whose center is closest to
+    /// block column `(x, z)`.
+    pub fn nearest(
+        &self,
+        dim: i32,
+        kind: StructureKind,
+        x: i32,
+        z: i32,
+    ) -> Option<StructureBounds> {
+        self.entries
+            .iter()
+            .filter(|(d, bounds)| *d == dim && bounds.kind == kind)
+            .map(|(_, bounds)| *bounds)
+            .min_by_key(|bounds| {
+                let (cx, cz) = bounds.center();
+                (cx - x) as i64 * (cx - x) as i64 + (cz - z) as i64 * (cz - z) as i64
+            })
+    }
+
+    /// Serialize for the world database: per structure, the dimension
+    /// (`i32_le`), the kind (`u8`) and the corners (six `i32_le`).
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.entries.len() * ENTRY_SIZE);
+        for (dim, bounds) in &self.entries {
+            data.extend_from_slice(&dim.to_le_bytes());
+            data.push(bounds.kind.id());
+            let (min, max) = (bounds.min, bounds.max);
+            for v in [min.0, min.1, min.2, max.0, max.1, max.2] {
+                data.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        data
+    }
+
+    /// Parse a saved registry. Entries of unknown kinds and a trailing
+    /// partial entry are skipped.
+    pub fn deserialize(data: &[u8]) -> Self {
+        let int = |b: &[u8], i: usize| i32::from_le_bytes(b[i..i + 4].try_into().unwrap());
+        let entries = data
+            .chunks_exact(ENTRY_SIZE)
+            .filter_map(|b| {
+                let kind = StructureKind::from_id(b[4])?;
+                Some((
+                    int(b, 0),
+                    StructureBounds {
+                        kind,
+                        min: (int(b, 5), int(b, 9), int(b, 13)),
+                        max: (int(b, 17), int(b, 21), int(b, 25)),
+                    },
+                ))
+            })
+            .collect();
+        Self {
+            entries,
+            dirty: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_by_name() {
+        assert_eq!(
+            StructureKind::from_name("village"),
+            Some(StructureKind::Village)
+        );
+        assert_eq!(
+            StructureKind::from_name("dungeon"),
+            Some(StructureKind::Dungeon)
+        );
+        assert_eq!(
+            StructureKind::from_name("pillager_outpost"),
+            Some(StructureKind::PillagerOutpost)
+        );
+        assert_eq!(StructureKind::from_name("stronghold"), None);
+    }
+
+    #[test]
+    fn bounds_grow_and_contain() {
+        let mut bounds = StructureBounds::at(StructureKind::Village, (10, 64, 10));
+        bounds.include((4, 62, 12), (6, 66, 20));
+        assert_eq!(bounds.min, (4, 62, 10));
+        assert_eq!(bounds.max, (10, 66, 20));
+        assert_eq!(bounds.center(), (7, 15));
+        assert!(bounds.contains((4, 66, 20)));
+        assert!(!bounds.contains((4, 67, 20)));
+    }
+
+    #[test]
+    fn registry_finds_nearest_and_roundtrips() {
+        let dungeon = |x, z| StructureBounds {
+            kind: StructureKind::Dungeon,
+            min: (x, 20, z),
+            max: (x + 6, 24, z + 6),
+        };
+        let mut registry = StructureRegistry::default();
+        registry.add(0, &[dungeon(100, 0), dungeon(-40, -40)]);
+        registry.add(1, &[dungeon(0, 0)]);
+        // Generating the same chunk again adds nothing
+        registry.add(0, &[dungeon(100, 0)]);
+        assert_eq!(registry.len(), 3);
+        assert!(registry.dirty);
+
+        assert_eq!(
+            registry.nearest(0, StructureKind::Dungeon, 0, 0),
+            Some(dungeon(-40, -40))
+        );
+        assert_eq!(
+            registry.nearest(0, StructureKind::Dungeon, 80, 0),
+            Some(dungeon(100, 0))
+        );
+        assert_eq!(registry.nearest(0, StructureKind::Village, 0, 0), None);
+        assert_eq!(registry.nearest(2, StructureKind::Dungeon, 0, 0), None);
+
+        let loaded = StructureRegistry::deserialize(&registry.serialize());
+        assert_eq!(loaded.entries, registry.entries);
+        assert!(!loaded.dirty);
+    }
+}
@@ -0,0 +1,267 @@
+//! Tree features: the shape of each kind of tree, shared by world
+//! generation and saplings growing, and leaves decaying away from logs.
+
+use rand::Rng;
+
+use crate::biome::TreeType;
+use crate::block_hash::TickBlocks;
+
+/// How far from a log leaves stay on the tree (Manhattan distance).
+pub const LEAF_LOG_DISTANCE: i32 = 4;
+
+/// Ticks before leaves left without a log decay, at random.
+pub const LEAF_DECAY_DELAY: std::ops::RangeInclusive<u64> = 20..=200;
+
+/// Block offsets of a tree from its base, the block its trunk starts at.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeShape {
+    pub logs: Vec<(i32, i32, i32)>,
+    pub leaves: Vec<(i32, i32, i32)>,
+}
+
+/// A random trunk height for a kind of tree.
+pub fn trunk_height(tree_type: TreeType, rng: &mut impl Rng) -> i32 {
+    match tree_type {
+        TreeType::Spruce => rng.gen_range(6..9),
+        _ => rng.gen_range(4..7),
+    }
+}
+
+/// The shape of a tree with a trunk `trunk_height` blocks high: a tapered
+/// canopy for spruce, a rounded one for the others.
+pub fn tree_shape(tree_type: TreeType, trunk_height: i32) -> TreeShape {
+    if tree_type == TreeType::None {
+        return TreeShape::default();
+    }
+    let logs = (0..trunk_height).map(|dy| (0, dy, 0)).collect();
+    let mut leaves = Vec::new();
+    let top_y = trunk_height;
+    match tree_type {
+        TreeType::Spruce => {
+            // Top 1x1, then 3x3, then wider diamond layers below
+            leaves.push((0, top_y, 0));
+            for dx in -1..=1 {
+                for dz in -1..=1 {
+                    leaves.push((dx, top_y - 1, dz));
+                }
+            }
+            for radius in 2i32..=3 {
+                for dx in -radius..=radius {
+                    for dz in -radius..=radius {
+                        if dx.abs() + dz.abs() <= radius + 1 {
+                            leaves.push((dx, top_y - radius, dz));
+                        }
+                    }
+                }
+            }
+        }
+        _ => {
+            // 3x3 on top, then two 5x5 layers without their corners
+            for dx in -1..=1 {
+                for dz in -1..=1 {
+                    leaves.push((dx, top_y, dz));
+                }
+            }
+            for layer in 1..=2 {
+                for dx in -2i32..=2 {
+                    for dz in -2i32..=2 {
+                        if dx.abs() != 2 || dz.abs() != 2 {
+                            leaves.push((dx, top_y - layer, dz));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    leaves.retain(|&(dx, dy, dz)| (dx, dz) != (0, 0) || dy >= trunk_height);
+    TreeShape { logs, leaves }
+}
+
+/// Random tick of a sapling at `(x, y, z)`: a young sapling ages, an aged
+/// one grows into a tree of `trunk_height` if there is room for its trunk.
+/// The soil under it becomes dirt.
+pub fn process_sapling_tick(
+    tree_type: TreeType,
+    aged: bool,
+    trunk_height: i32,
+    (x, y, z): (i32, i32, i32),
+    tb: &TickBlocks,
+    get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+) -> Vec<(i32, i32, i32, u32)> {
+    if !aged {
+        return vec![(x, y, z, tb.sapling[tree_type as usize][1])];
+    }
+    let Some((log, leaves)) = tb.tree_blocks(tree_type) else {
+        return Vec::new();
+    };
+    let shape = tree_shape(tree_type, trunk_height);
+    let room = shape.logs.iter().skip(1).all(|&(dx, dy, dz)| {
+        get_block(x + dx, y + dy, z + dz).is_some_and(|rid| rid == tb.air || tb.is_leaf(rid))
+    });
+    if !room {
+        return Vec::new();
+    }
+    let mut changes = vec![(x, y - 1, z, tb.dirt)];
+    changes.extend(
+        shape
+            .logs
+            .iter()
+            .map(|&(dx, dy, dz)| (x + dx, y + dy, z + dz, log)),
+    );
+    changes.extend(
+        shape
+            .leaves
+            .iter()
+            .map(|&(dx, dy, dz)| (x + dx, y + dy, z + dz))
+            .filter(|&(lx, ly, lz)| get_block(lx, ly, lz) == Some(tb.air))
+            .map(|(lx, ly, lz)| (lx, ly, lz, leaves)),
+    );
+    changes
+}
+
+/// Whether there is a log within [`LEAF_LOG_DISTANCE`] of `(x, y, z)`.
+pub fn has_log_nearby(
+    x: i32,
+    y: i32,
+    z: i32,
+    tb: &TickBlocks,
+    get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+) -> bool {
+    let radius = LEAF_LOG_DISTANCE;
+    for dx in -radius..=radius {
+        let rem = radius - dx.abs();
+        for dy in -rem..=rem {
+            let rem2 = rem - dy.abs();
+            for dz in -rem2..=rem2 {
+                if (dx, dy, dz) == (0, 0, 0) {
+                    continue;
+                }
+                if get_block(x + dx, y + dy, z + dz).is_some_and(|rid| tb.is_log(rid)) {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Leaves a removed log at `(x, y, z)` may have held on the tree: those
+/// within [`LEAF_LOG_DISTANCE`] of it, to check for decay.
+pub fn leaves_around(
+    x: i32,
+    y: i32,
+    z: i32,
+    tb: &TickBlocks,
+    get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+) -> Vec<(i32, i32, i32)> {
+    let radius = LEAF_LOG_DISTANCE;
+    let mut leaves = Vec::new();
+    for dx in -radius..=radius {
+        for dy in -radius..=radius {
+            for dz in -radius..=radius {
+                let pos = (x + dx, y + dy, z + dz);
+                if get_block(pos.0, pos.1, pos.2).is_some_and(|rid| tb.is_leaf(rid)) {
+                    leaves.push(pos);
+                }
+            }
+        }
+    }
+    leaves
+}
+
+/// Decay tick of leaves at `(x, y, z)`: they fall away once no log is near.
+pub fn process_leaf_decay(
+    x: i32,
+    y: i32,
+    z: i32,
+    tb: &TickBlocks,
+    get_block: impl Fn(i32, i32, i32) -> Option<u32>,
+) -> Vec<(i32, i32, i32, u32)> {
+    if has_log_nearby(x, y, z, tb, &get_block) {
+        return Vec::new();
+    }
+    vec![(x, y, z, tb.air)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Dirt below y = 64, air above, with some blocks set.
+    fn world<'a>(
+        tb: &'a TickBlocks,
+        blocks: &'a HashMap<(i32, i32, i32), u32>,
+    ) -> impl Fn(i32, i32, i32) -> Option<u32> + 'a {
+        move |x, y, z| {
+            Some(
+                blocks
+                    .get(&(x, y, z))
+                    .copied()
+                    .unwrap_or(if y < 64 { tb.dirt } else { tb.air }),
+            )
+        }
+    }
+
+    #[test]
+    fn tree_shapes() {
+        let oak = tree_shape(TreeType::Oak, 5);
+        assert_eq!(oak.logs.len(), 5);
+        // Not where the trunk goes
+        assert_eq!(oak.leaves.len(), 9 + 2 * 20);
+        assert!(oak.leaves.contains(&(0, 5, 0)));
+        let spruce = tree_shape(TreeType::Spruce, 7);
+        assert!(spruce.leaves.contains(&(0, 7, 0)));
+        assert!(spruce.leaves.contains(&(3, 4, 1)));
+        assert!(!spruce.leaves.contains(&(3, 4, 2)));
+        assert_eq!(tree_shape(TreeType::None, 5), TreeShape::default());
+    }
+
+    #[test]
+    fn saplings_age_then_grow() {
+        let tb = TickBlocks::compute();
+        let mut blocks = HashMap::new();
+        blocks.insert((0, 64, 0), tb.sapling[0][0]);
+        blocks.insert((0, 63, 0), tb.grass_block);
+        let at = (0, 64, 0);
+        let young = process_sapling_tick(TreeType::Oak, false, 5, at, &tb, world(&tb, &blocks));
+        assert_eq!(young, [(0, 64, 0, tb.sapling[0][1])]);
+
+        let grown = process_sapling_tick(TreeType::Oak, true, 5, at, &tb, world(&tb, &blocks));
+        assert!(grown.contains(&(0, 63, 0, tb.dirt)));
+        assert!(grown.contains(&(0, 64, 0, tb.oak_log)));
+        assert!(grown.contains(&(0, 68, 0, tb.oak_log)));
+        assert!(grown.contains(&(1, 69, 1, tb.oak_leaves)));
+        // Leaves don't replace what is in the way
+        blocks.insert((1, 69, 1), tb.stone);
+        let grown = process_sapling_tick(TreeType::Oak, true, 5, at, &tb, world(&tb, &blocks));
+        assert!(!grown.iter().any(|&(x, y, z, _)| (x, y, z) == (1, 69, 1)));
+        // The trunk needs room
+        blocks.insert((0, 66, 0), tb.stone);
+        assert!(
+            process_sapling_tick(TreeType::Oak, true, 5, at, &tb, world(&tb, &blocks)).is_empty()
+        );
+    }
+
+    #[test]
+    fn leaves_decay_away_from_logs() {
+        let tb = TickBlocks::compute();
+        let mut blocks = HashMap::new();
+        blocks.insert((0, 70, 0), tb.oak_leaves);
+        blocks.insert((2, 70, 1), tb.birch_leaves);
+        blocks.insert((0, 66, 0), tb.oak_log);
+        assert!(process_leaf_decay(0, 70, 0, &tb, world(&tb, &blocks)).is_empty());
+        blocks.remove(&(0, 66, 0));
+        assert_eq!(
+            process_leaf_decay(0, 70, 0, &tb, world(&tb, &blocks)),
+            [(0, 70, 0, tb.air)]
+        );
+        // Logs as players place them hold leaves too
+        blocks.insert((0, 67, 0), tb.log_axes[0][1]);
+        assert!(process_leaf_decay(0, 70, 0, &tb, world(&tb, &blocks)).is_empty());
+        blocks.remove(&(0, 67, 0));
+        let mut around = leaves_around(0, 66, 0, &tb, world(&tb, &blocks));
+        around.sort();
+        assert_eq!(around, [(0, 70, 0), (2, 70, 1)]);
+    }
+}
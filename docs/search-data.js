@@ -21,7 +21,7 @@ window.SEARCH_DATA = [
     "title": "Protocol",
     "url": "pages/protocol.html",
     "section": "Core Systems",
-    "content": "Minecraft Bedrock Edition protocol version 766, multi-version support 748-766. Login flow: RequestNetworkSettings, NetworkSettings compression, Login JWT chain client data, ServerToClientHandshake ECDH, ClientToServerHandshake, PlayStatus LoginSuccess, resource pack exchange, StartGame 60+ fields, chunk loading, PlayStatus PlayerSpawn. Packet structure: batch framing, compression header, zlib, sub-packets with VarUInt32 length and packet ID. Key packet IDs: Login 0x01, PlayStatus 0x02, StartGame 0x0B, AddPlayer 0x0C, AddActor 0x0D, MovePlayer 0x12, MobEffect 0x1C, InventoryTransaction 0x1E, PlayerAuthInput 0x27, LevelChunk 0x3A, CraftingData 0x34, ItemStackRequest 0x93. Block network IDs are hashes: FNV-1a of canonical NBT block states. Data types: VarInt zigzag, VarLong, VarUInt32, VarUInt64, Vec3, Vec2, BlockPos, Uuid, String."
+    "content": "Minecraft Bedrock Edition protocol version 766, multi-version support 748-766. Login flow: RequestNetworkSettings, NetworkSettings compression, Login JWT chain client data, ServerToClientHandshake ECDH, ClientToServerHandshake, PlayStatus LoginSuccess, resource pack exchange, StartGame 60+ fields, chunk loading, PlayStatus PlayerSpawn. Packet structure: batch framing, compression header, zlib, sub-packets with VarUInt32 length and packet ID. Key packet IDs: Login 0x01, PlayStatus 0x02, StartGame 0x0B, AddPlayer 0x0C, AddActor 0x0D, MovePlayer 0x12, SetActorData 0x27 entity metadata changes, MobEffect 0x1C, InventoryTransaction 0x1E, PlayerAuthInput 0x27, LevelChunk 0x3A, CraftingData 0x34, ItemStackRequest 0x93. Block network IDs are hashes: FNV-1a of canonical NBT block states. Data types: VarInt zigzag, VarLong, VarUInt32, VarUInt64, Vec3, Vec2, BlockPos, Uuid, String."
   },
   {
     "title": "Networking",
@@ -33,49 +33,49 @@ window.SEARCH_DATA = [
     "title": "World",
     "url": "pages/world.html",
     "section": "Core Systems",
-    "content": "Chunk-based world with procedural terrain generation, caves, biomes, LevelDB persistence. Chunks: 16x16 columns, sub-chunks 16x16x16, Y range -64 to 319, 24 sub-chunks. Block states: FNV-1a hash u32, BlockStateRegistry 350+ blocks. Terrain generation: OverworldGenerator, seed-based Perlin noise, OctaveNoise fBm, 8-phase pipeline: base terrain, biome assignment, surface blocks, caves spaghetti noise, ores 8 types deepslate, trees oak birch spruce acacia, vegetation, structures. 10 biomes: Ocean Plains Desert Mountains Forest Taiga River Ice Plains Birch Forest Savanna. Sea level Y=62. Dimensions: Overworld, Nether lava ceiling netherrack, End main island. Portal mechanics: coordinate scaling 8:1, portal frame detection, ChangeDimension packet. Persistence: LevelDB rusty-leveldb, chunk keys, sub-chunk format version 9 FNV-1a hash palette, level.dat 8-byte header LE NBT, player JSON per UUID, auto-save 300s, dirty tracking. Block ticks: TickScheduler BinaryHeap, random ticks, crop growth, fluid flow, gravity, redstone."
+    "content": "Chunk-based world with procedural terrain generation, caves, biomes, LevelDB persistence. Chunks: 16x16 columns, sub-chunks 16x16x16, Y range -64 to 319, 24 sub-chunks. Block states: FNV-1a hash u32, BlockStateRegistry 350+ blocks. Placement rules: torch and button supports, doors and beds two blocks, slabs stack into double slabs, stairs and logs orientation, waterlogging second layer. Terrain generation: OverworldGenerator, seed-based Perlin noise, OctaveNoise fBm, 8-phase pipeline: base terrain, biome assignment, surface blocks, caves spaghetti noise, ores 8 types deepslate, trees oak birch spruce acacia, vegetation, structures. 10 biomes: Ocean Plains Desert Mountains Forest Taiga River Ice Plains Birch Forest Savanna. Sea level Y=62. Dimensions: Overworld, Nether lava ceiling netherrack, End main island. Portal mechanics: coordinate scaling 8:1, portal frame detection, ChangeDimension packet. Persistence: LevelDB rusty-leveldb, chunk keys, sub-chunk format version 9 FNV-1a hash palette, level.dat 8-byte header LE NBT, player JSON per UUID, auto-save 300s, dirty tracking, Data3D 3D biomes 4x4x4 cells per sub-chunk, Data2D fallback, biome palette per sub-chunk in LevelChunk. Block ticks: TickScheduler BinaryHeap, random ticks, crop growth, farmland hydration and drying, fluid flow, gravity, redstone."
   },
   {
     "title": "Entities",
     "url": "pages/entities.html",
     "section": "Core Systems",
-    "content": "ECS-based entity management with bevy_ecs 0.15, mob AI, spawn systems. GameWorld wraps bevy_ecs World, components Position Velocity Health MobType BehaviorList, systems gravity movement AI tick death spawn despawn, 20 TPS. 5 mob types: Zombie hostile 20hp melee, Skeleton hostile 20hp ranged, Cow passive 10hp breedable, Pig passive 10hp breedable, Chicken passive 4hp breedable. AI behaviors: priority-based, BehaviorList Vec<Box<dyn Behavior>>, BehaviorType Movement Passive TargetSelector. 9 behaviors: Float, NearestAttackableTarget, HurtByTarget, MeleeAttack, TemptGoal, BreedGoal, Panic, RandomStroll, LookAtPlayer. Spawn config: hostile_cap 20, passive_cap 10, spawn_interval 100 ticks, despawn_distance 128. Breeding: TemptGoal, BreedGoal, baby mobs, love particles, 5-minute cooldown. Player mirror entities: spawned on connect, position sync, despawned on disconnect. Network packets: AddActor 0x0D, MoveActorAbsolute 0x10, RemoveActor 0x0E."
+    "content": "ECS-based entity management with bevy_ecs 0.15, mob AI, spawn systems. GameWorld wraps bevy_ecs World, components Position Velocity Health StatusEffects Hunger Experience MobType BehaviorList, systems gravity movement AI tick death spawn despawn, 20 TPS. 5 mob types: Zombie hostile 20hp melee, Skeleton hostile 20hp ranged, Cow passive 10hp breedable, Pig passive 10hp breedable, Chicken passive 4hp breedable. AI behaviors: priority-based, BehaviorList Vec<Box<dyn Behavior>>, BehaviorType Movement Passive TargetSelector. 9 behaviors: Float, NearestAttackableTarget, HurtByTarget, MeleeAttack, TemptGoal, BreedGoal, Panic, RandomStroll, LookAtPlayer. Spawn config: hostile_cap 20, passive_cap 10, spawn_interval 100 ticks, despawn_distance 128. Hostile spawn rule light height, night, caves. Daylight: undead burn in sunlight, rain, helmet durability, MobArmorEquipment. Entity collision: soft pushing, minecart boat ramming, spatial grid, entity_collision. Breeding: TemptGoal, BreedGoal, baby mobs, love particles, 5-minute cooldown. Player player entities: health, status effects, hunger and XP shared with mob damage and effect systems, spawned on connect, position sync, despawned on disconnect. Poses: sneaking swimming crawling gliding hitbox height, sneak_edge_protection. Raids: pillager vindicator raiders, outpost captain, Bad Omen, waves, raid boss bar, Hero of the Village. Network packets: AddActor 0x0D, MoveActorAbsolute 0x10, RemoveActor 0x0E."
   },
   {
     "title": "Gameplay",
     "url": "pages/gameplay.html",
     "section": "Gameplay",
-    "content": "Complete survival experience with combat, hunger, crafting, enchantments, weather. Combat: DamageInput pipeline, armor reduction Bedrock formula damage*(1-min(20,defense)/25), enchantment protection, weapon damage lookup, critical hits 1.5x, PvP 10-tick invulnerability, knockback, projectiles arrows tridents. Armor and enchantments: 37 enchantment IDs, Protection Sharpness Fire Aspect Knockback, enchanting table seeded RNG bookshelf counting 3 slots lapis XP cost, anvil rename repair combine, grindstone strip. Hunger: food 0-20, saturation 0-20, exhaustion 0-5, sprint jump attack regen rates, natural regen at food>=18, starvation at food==0. Environmental damage: fall damage, drowning air_ticks 300, lava 4 dmg, suffocation. Crafting: RecipeRegistry ~50 recipes, shaped shapeless, CraftingData 0x34, 9-slot crafting grid, stonecutter grindstone loom furnace anvil. Weather: day/night 24000 ticks, SetTime, rain thunderstorm lightning, /time /weather commands. XP system: orbs, levels, enchanting anvil costs."
+    "content": "Complete survival experience with combat, hunger, crafting, enchantments, weather. Combat: DamageInput pipeline, armor reduction Bedrock formula damage*(1-min(20,defense)/25), enchantment protection, weapon damage lookup, critical hits 1.5x, PvP 10-tick invulnerability (damage_cooldown), per-dimension world flags pvp fall_damage drowning hunger /worldflag, knockback, projectiles arrows tridents. Armor and enchantments: 37 enchantment IDs, Protection Sharpness Fire Aspect Knockback, enchanting table seeded RNG bookshelf counting 3 slots lapis XP cost, anvil rename repair combine, grindstone strip keeping curses, combine damaged items, XP orb. Hunger: food 0-20, saturation 0-20, exhaustion 0-5, sprint jump attack regen rates, natural regen at food>=18, starvation at food==0. Difficulty: /difficulty SetDifficulty peaceful easy normal hard, mob damage scaling, starvation floor, no hostile mobs on Peaceful. Environmental damage: fall damage, drowning air_ticks 300, lava 4 dmg, magma blocks, suffocation. Swimming: water drag, Depth Strider and dolphin swim limits, bubble columns soul sand magma. Block surfaces: soul sand honey slowdown, Soul Speed, ice blue ice sliding, slime bounce, surface-aware speed checks. Crafting: RecipeRegistry ~50 recipes, shaped shapeless, CraftingData 0x34, 9-slot crafting grid, stonecutter grindstone loom furnace anvil. Banners: loom patterns, banner pattern items, 6 layers, Banner block entity, base color, ominous, shield decoration with a banner. Weather: day/night 24000 ticks, SetTime, rain thunderstorm lightning strikes fire charged creeper witch conversion, snow accumulation, cauldrons fill in rain, /time /weather commands, per-world time, sleeping in beds playersSleepingPercentage gamerule. Chest shops: [Shop] sign on a chest, right click to buy, left click to sell, stock checks, economy payments, owner-only chest, shops.json. NPCs: player skin, entity type, click actions command console message dialogue form event, npcs.json, spawned with their chunk. Note blocks: tuning 25 pitches, instrument from the block below, redstone triggered, LevelSoundEvent. Jukeboxes: music discs, records, redstone output while playing. XP system: orbs, levels, enchanting anvil costs. Farming: hoe tilling dirt grass into farmland, trampling by falls, crops pop off as seeds. Trees: saplings grow into trees, leaf decay away from logs, sapling and apple drops from leaves, shears."
   },
   {
     "title": "Commands",
     "url": "pages/commands.html",
     "section": "Gameplay",
-    "content": "~40 commands with entity selectors, tab completion, permission levels. Entity selectors: @a all players, @p nearest, @r random, @e all entities, @s self, arguments [type= r= name=]. Permission levels: 0 all players, 1 operators, 2 full operator. Basic commands: /help /list /say /msg /stop /op /deop /kick /ban /ban-ip /unban /whitelist. Player commands: /gamemode /tp /give /kill /effect /xp /enchant. World commands: /time set /time add /weather /gamerule /setblock /fill /clone /summon. UI commands: /title /particle /playsound. Advanced commands: /scoreboard /tag /bossbar /execute /tickingarea /transfer /reload /import /export. Server management: Console REPL stdin, RCON TCP Source protocol, Query UDP GameSpy4."
+    "content": "~40 commands with entity selectors, tab completion, permission levels. Entity selectors: @a all players, @p nearest, @r random, @e all entities, @s self, arguments [type= r= name=]. Permission levels: 0 all players, 1 operators, 2 full operator. Basic commands: /help /list /say /msg /tell /reply /ignore private messages, /socialspy /home /sethome /delhome /back /tpa /tpaccept /tpdeny /warp /spawn with teleport warm-up and cooldown, /setwarp /delwarp warps. Economy: /balance, /pay, /eco give take set, /arena list join leave minigame arenas, /hologram create remove list addline setline removeline movehere spacing floating text, /npc create remove list rename movehere skin type action dialogue button NPCs, /packs list toggle optional resource packs, per-XUID accounts.json per-warp permission, /world list /world tp multi-world /stop /op /deop /kick /ban /ban-ip /unban /whitelist. Player commands: /gamemode spectator no-clip click a player to spectate /tp /give CanPlaceOn CanDestroy adventure mode player_head owner player heads /kill /effect /xp /enchant. World commands: /time set /time add /time query /weather /gamerule showCoordinates showDaysPlayed /setblock /fill /clone /locate biome /locate structure village dungeon pillager_outpost explorer map /summon. UI commands: /title /camera clear fade set camera presets /fog push pop remove /inputpermission query set camera movement locks /particle /playsound. Advanced commands: /scoreboard /tag /bossbar /execute /tickingarea /transfer /whois /mute /unmute /slowmode /reload /import /export. Server management: Console REPL stdin, RCON TCP Source protocol, Query UDP GameSpy4."
   },
   {
     "title": "Plugins",
     "url": "pages/plugins.html",
     "section": "Gameplay",
-    "content": "Extensible plugin architecture with Rust API, WASM runtime, Lua scripting, Forms UI. PluginManager orchestrates 3 runtimes, PendingAction queue, ServerApiImpl. 15 event types: PlayerJoin PlayerLeave PlayerChat PlayerCommand PlayerMove PlayerBreakBlock PlayerPlaceBlock PlayerAttack PlayerDeath MobSpawn MobDeath BlockUpdate WeatherChange ServerTick FormResponse. Rust Plugin API: mc-rs-plugin-api, Plugin trait on_enable on_disable on_event, ServerApi. WASM plugins: mc-rs-plugin-wasm wasmtime, manifest.json, host functions, sandboxed. Lua scripts: mc-rs-plugin-lua mlua, simple scripting, host function bindings. Forms UI: ModalFormRequest 0x64, ModalFormResponse 0x65, SimpleForm buttons, ModalForm yes/no, CustomForm inputs dropdowns sliders toggles, builder pattern. Behavior packs: mc-rs-behavior-pack, JSON entity item block recipe loot_table definitions, [packs] config. Hot reload: /reload command unloads and reloads all plugins."
+    "content": "Extensible plugin architecture with Rust API, WASM runtime, Lua scripting, Forms UI. PluginManager orchestrates 3 runtimes, PendingAction queue, ServerApiImpl. 18 event types: PlayerJoin PlayerLeave PlayerChat PlayerCommand PlayerMove PlayerBreakBlock PlayerPlaceBlock PlayerAttack PlayerDeath MobSpawn MobDeath BlockUpdate WeatherChange ServerTick FormResponse ArenaStart ArenaEnd NpcInteract. Rust Plugin API: mc-rs-plugin-api, Plugin trait on_enable on_disable on_event, ServerApi. Arenas: create_arena minigame region lobby teams countdown sidebar scoreboard rollback, join_arena leave_arena set_arena_score end_arena, /arena list join leave. Holograms: create_hologram set_hologram_lines remove_hologram floating text placeholders {online} {balance}. Cutscenes: play_cutscene stop_cutscene camera steps fades waits easing, CameraPresets CameraInstruction. Client settings: push_fog remove_fog PlayerFog, set_client_game_rule showcoordinates showdaysplayed, set_input_permission UpdateClientInputLocks camera movement lock. Resource packs: accepted_packs required optional packs. Entity appearance: set_entity_appearance nametag scale variant flags SetActorData. WASM plugins: mc-rs-plugin-wasm wasmtime, manifest.json, host functions, sandboxed. Lua scripts: mc-rs-plugin-lua mlua, simple scripting, host function bindings. Forms UI: ModalFormRequest 0x64, ModalFormResponse 0x65, SimpleForm buttons, ModalForm yes/no, CustomForm inputs dropdowns sliders toggles, builder pattern. Behavior packs: mc-rs-behavior-pack, JSON entity item block recipe loot_table definitions, [packs] config. Hot reload: /reload command unloads and reloads all plugins, re-reads server.toml, ops, whitelist and bans."
   },
   {
     "title": "Configuration",
     "url": "pages/configuration.html",
     "section": "Operations",
-    "content": "Server configuration via server.toml with sensible defaults. [server] section: address 0.0.0.0, port 19132, motd, max_players 20, online_mode true, gamemode survival, difficulty normal, view_distance 10, tick_rate 20, operator_permission_level 2. [world] section: name world, generator default flat void, seed random, spawn position, auto_save_interval 300, max_build_height 319, enable_nether true, enable_end true. [logging] section: level info, file stdout, format pretty json compact. [permissions] section: enforce_whitelist false, ops.json, whitelist.json, banned-players.json, banned-ips.json. [rcon] section: enabled false, port 25575, password. [query] section: enabled false, port 19132. [packs] section: behavior_packs list. File locations: server.toml, worlds directory, LevelDB, player data JSON."
+    "content": "Server configuration via server.toml with sensible defaults. [server] section: address 0.0.0.0, port 19132, motd, max_players 20, online_mode true, gamemode survival, difficulty normal, view_distance 10, simulation_distance 4, max_catch_up_ticks 10, tick_rate 20, operator_permission_level 2. [world] section: name world, generator default flat void, seed random, spawn position, auto_save_interval 300, max_build_height 319, enable_nether true, enable_end true. [logging] section: level info, file stdout, format pretty json compact. [permissions] section: enforce_whitelist false, ops.json, whitelist.json, banned-players.json, banned-ips.json. [rcon] section: enabled false, port 25575, password. [query] section: enabled false, port 19132. [packs] section: behavior_packs list, force_packs, required optional packs, required_prompt optional_prompt, pack_preferences.json. [teleport] section: warmup 3, cooldown 10, request_timeout 60, max_homes 3. [economy] section: currency_name coins, starting_balance 0. [geoip] section: enabled false, database GeoLite2-Country.mmdb, allowed_countries, denied_countries, allow_unknown true, country lookup MaxMind. [prelogin] section: blocklist IP ranges VPN proxy, webhook_url HTTP POST external auth, timeout_ms 3000, fail_open true, deny_message. [gameplay] section: keep_inventory, death_graves, hostile_spawn_rule light height, entity_collision pushing ramming. [chat] section: slow_mode, filter_words profanity filter, filter_mode replace block, filter_replacement, muted-players.json. [bridge] section: Discord bridge, webhook_url events join leave chat death server_start JSON, listen token Bearer endpoint POST /chat relay, chat_format. [worlds.<name>] multi-world: generator, seed, spawn, worlds.json dimension IDs. File locations: server.toml, worlds directory, LevelDB, player data JSON."
   },
   {
     "title": "Security & Anti-Cheat",
     "url": "pages/security.html",
     "section": "Operations",
-    "content": "Multi-layered protection with violation tracking, rate limiting, permission management. Authentication: Xbox Live JWT verification, chain data validation, online_mode. Encryption: ECDH P-384, AES-256-CFB8, prevents packet sniffing injection. Anti-cheat ViolationTracker: tracks violations per player per category, configurable decay, auto-kick threshold. Categories: reach 7.0 blocks, speed, fly, noclip. Rate limiting: per-action limits block break place item use chat command, global rate limit, prevents DoS packet spam. Permission system: PermissionManager JSON persistence, ops.json operators, whitelist.json, banned-players.json, banned-ips.json. Permission levels: 0 normal player, 1 operator standard, 2 full operator /stop /op /deop. Security best practices: online_mode true, RCON password, whitelist, monitor violations, view distance."
+    "content": "Multi-layered protection with violation tracking, rate limiting, permission management. Authentication: Xbox Live JWT verification, chain data validation, online_mode. Encryption: ECDH P-384, AES-256-CFB8, prevents packet sniffing injection. Anti-cheat: violation score per player per check, configurable decay, warn, rubber-band and kick thresholds in [anticheat], PlayerViolation plugin event. Checks: speed, fly (swimming and climbing ladders vines scaffolding exempt), noclip, reach 7.0 blocks, killaura angle, fastbreak, rate_limit. Rate limiting: per-action limits block break place item use chat command, global rate limit, prevents DoS packet spam. Permission system: PermissionManager JSON persistence, ops.json operators, whitelist.json, banned-players.json, banned-ips.json. Permission levels: op levels 0-4, 0 normal player, 1 operator, 2 gameplay world editing /gamemode /tp /give, 3 player management /kick /ban /op /deop, 4 server /stop /restart /reload, UpdateAbilities command permission level sync. Security best practices: online_mode true, RCON password, whitelist, monitor violations, view distance."
   },
   {
     "title": "Performance",
     "url": "pages/performance.html",
     "section": "Operations",
-    "content": "Optimized for throughput with spatial indexing, caching, batch processing, parallel generation. SpatialGrid: 2D spatial index for entity queries, O(1) cell lookup, mob AI nearest targets, mc-rs-game/src/ai/spatial.rs. Chunk caching: cached_payload on ChunkColumn, invalidated on block change dirty flag, avoids re-serialization. Batch noise generation: sample_2d_batch, entire chunk column in one pass, reduces function call overhead. Parallel chunk generation: Arc generators + tokio::spawn_blocking, concurrent on thread pool, non-blocking. ECS indexing: MobIndex PlayerIndex O(1) lookup, runtime_id_to_addr HashMap, block_entity_chunk_index. Memory optimizations: biome palette [u8;256], block states u32 FNV-1a hashes, distance squared no sqrt, pre-computed hash tables WorldBlocks TickBlocks. Network optimizations: batch compression zlib, block_network_ids_are_hashes, chunk radius negotiation, broadcast filtering. 20 TPS tick rate, ~50ms tick budget."
+    "content": "Optimized for throughput with spatial indexing, caching, batch processing, parallel generation. SpatialGrid: 2D spatial index for entity queries, O(1) cell lookup, mob AI nearest targets, mc-rs-game/src/ai/spatial.rs. Tick profiling: /timings report, per-section tick timings, slow tick warning over 50 ms. Tick catch-up: max_catch_up_ticks, skipped ticks, /tps, TPS and MSPT. Chunk caching: cached_payload on ChunkColumn, invalidated on block change dirty flag, avoids re-serialization. Batch noise generation: sample_2d_batch, entire chunk column in one pass, reduces function call overhead. Parallel chunk generation: Arc generators + tokio::spawn_blocking, concurrent on thread pool, non-blocking. Parallel region ticking: block ticks fluid gravity piston redstone per 4x4 chunk region on rayon, cross-region update queue, refused ticks run again. ECS indexing: MobIndex PlayerIndex O(1) lookup, runtime_id_to_addr HashMap, block_entity_chunk_index. Memory optimizations: biome palette [u8;256], block states u32 FNV-1a hashes, distance squared no sqrt, pre-computed hash tables WorldBlocks TickBlocks. Network optimizations: batch compression zlib, block_network_ids_are_hashes, chunk radius negotiation, broadcast filtering. 20 TPS tick rate, ~50ms tick budget."
   }
 ]
 ;